@@ -14,4 +14,12 @@
 #define IFTODT(x) ((x)>>12 & 017)
 #define DTTOIF(x) ((x)<<12)
 
+#if defined(_LARGEFILE64_SOURCE) || defined(_GNU_SOURCE)
+#define dirent64 dirent
+#define alphasort64 alphasort
+#define readdir64 readdir
+#define readdir64_r readdir_r
+#define scandir64 scandir
+#endif
+
 #endif /* _BITS_DIRENT_H */
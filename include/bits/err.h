@@ -0,0 +1,19 @@
+#ifndef _BITS_ERR_H
+#define _BITS_ERR_H
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+void warn(const char *fmt, ...);
+void warnc(int code, const char *fmt, ...);
+void warnx(const char *fmt, ...);
+__attribute__((__noreturn__)) void err(int eval, const char *fmt, ...);
+__attribute__((__noreturn__)) void errc(int eval, int code, const char *fmt, ...);
+__attribute__((__noreturn__)) void errx(int eval, const char *fmt, ...);
+
+#ifdef __cplusplus
+} // extern "C"
+#endif
+
+#endif /* _BITS_ERR_H */
@@ -12,4 +12,9 @@ int fcntl(int fildes, int cmd, ...);
 } // extern "C"
 #endif
 
+#if defined(_LARGEFILE64_SOURCE) || defined(_GNU_SOURCE)
+#define open64 open
+#define creat64 creat
+#endif
+
 #endif
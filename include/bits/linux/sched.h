@@ -0,0 +1,14 @@
+#ifndef _BITS_LINUX_SCHED_H
+#define _BITS_LINUX_SCHED_H
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+int clone(int (*fn)(void *), void *stack, int flags, void *arg, ...);
+
+#ifdef __cplusplus
+} // extern "C"
+#endif
+
+#endif
@@ -0,0 +1,14 @@
+#ifndef _BITS_MQUEUE_H
+#define _BITS_MQUEUE_H
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+mqd_t mq_open(const char *name, int oflag, ...);
+
+#ifdef __cplusplus
+} // extern "C"
+#endif
+
+#endif
@@ -5,4 +5,13 @@
 #define SIG_IGN ((void (*)(int))1)
 #define SIG_ERR ((void (*)(int))-1)
 
+typedef struct siginfo_t siginfo_t;
+
+#define sa_handler __sa_handler.sa_handler
+#define sa_sigaction __sa_handler.sa_sigaction
+
+#define si_pid __si_fields.__rt.si_pid
+#define si_uid __si_fields.__rt.si_uid
+#define si_value __si_fields.__rt.si_value
+
 #endif // _BITS_SIGNAL_H
@@ -27,4 +27,15 @@ int sscanf(const char * input, const char * fmt, ...);
 } // extern "C"
 #endif
 
+#if defined(_LARGEFILE64_SOURCE) || defined(_GNU_SOURCE)
+#define fpos64_t fpos_t
+#define fgetpos64 fgetpos
+#define fopen64 fopen
+#define freopen64 freopen
+#define fseeko64 fseeko
+#define fsetpos64 fsetpos
+#define ftello64 ftello
+#define tmpfile64 tmpfile
+#endif
+
 #endif /* _BITS_STDIO_H */
@@ -11,4 +11,11 @@ long double strtold(const char *nptr, char **endptr);
 } // extern "C"
 #endif
 
+#if defined(_LARGEFILE64_SOURCE) || defined(_GNU_SOURCE)
+#define mkostemp64 mkostemp
+#define mkostemps64 mkostemps
+#define mkstemp64 mkstemp
+#define mkstemps64 mkstemps
+#endif
+
 #endif /* _BITS_STDLIB_H */
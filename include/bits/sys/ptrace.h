@@ -1,6 +1,6 @@
 #ifndef _BITS_SYS_PTRACE_H
 #define _BITS_SYS_PTRACE_H
 
-int ptrace(int request, ...);
+long ptrace(int request, ...);
 
 #endif
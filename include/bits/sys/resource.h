@@ -6,4 +6,11 @@
 #define RUSAGE_BOTH (-2)
 #define	RUSAGE_THREAD 1
 
+#if defined(_LARGEFILE64_SOURCE) || defined(_GNU_SOURCE)
+#define rlim64_t rlim_t
+#define rlimit64 rlimit
+#define getrlimit64 getrlimit
+#define setrlimit64 setrlimit
+#endif
+
 #endif /* _BITS_SYS_RESOURCE_H */
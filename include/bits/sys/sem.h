@@ -0,0 +1,14 @@
+#ifndef _BITS_SYS_SEM_H
+#define _BITS_SYS_SEM_H
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+int semctl(int semid, int semnum, int cmd, ...);
+
+#ifdef __cplusplus
+} // extern "C"
+#endif
+
+#endif
@@ -7,4 +7,24 @@ struct sockaddr_storage {
 	unsigned long __ss_align;
 };
 
+#define CMSG_ALIGN(len) (((len) + sizeof(size_t) - 1) & (size_t) ~(sizeof(size_t) - 1))
+#define CMSG_SPACE(len) (CMSG_ALIGN(len) + CMSG_ALIGN(sizeof(struct cmsghdr)))
+#define CMSG_LEN(len) (CMSG_ALIGN(sizeof(struct cmsghdr)) + (len))
+#define CMSG_DATA(cmsg) ((unsigned char *) (cmsg) + CMSG_ALIGN(sizeof(struct cmsghdr)))
+
+#define CMSG_FIRSTHDR(mhdr) \
+	((size_t) (mhdr)->msg_controllen >= sizeof(struct cmsghdr) \
+		? (struct cmsghdr *) (mhdr)->msg_control \
+		: (struct cmsghdr *) 0)
+
+#define __CMSG_END(mhdr) ((unsigned char *) (mhdr)->msg_control + (mhdr)->msg_controllen)
+#define __CMSG_NEXT(cmsg) ((unsigned char *) (cmsg) + CMSG_ALIGN((cmsg)->cmsg_len))
+
+#define CMSG_NXTHDR(mhdr, cmsg) \
+	((cmsg)->cmsg_len < sizeof(struct cmsghdr) \
+		|| CMSG_ALIGN((cmsg)->cmsg_len) + sizeof(struct cmsghdr) \
+			> (size_t) (__CMSG_END(mhdr) - (unsigned char *) (cmsg)) \
+		? (struct cmsghdr *) 0 \
+		: (struct cmsghdr *) __CMSG_NEXT(cmsg))
+
 #endif // _BITS_SYS_SOCKET_H
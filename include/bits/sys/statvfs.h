@@ -0,0 +1,9 @@
+#ifndef _BITS_SYS_STATVFS_H
+#define _BITS_SYS_STATVFS_H
+
+#if defined(_LARGEFILE64_SOURCE) || defined(_GNU_SOURCE)
+#define statvfs64 statvfs
+#define fstatvfs64 fstatvfs
+#endif
+
+#endif /* _BITS_SYS_STATVFS_H */
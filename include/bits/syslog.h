@@ -0,0 +1,20 @@
+#ifndef _BITS_SYSLOG_H
+#define _BITS_SYSLOG_H
+
+#define LOG_PRI(p) ((p) & LOG_PRIMASK)
+#define LOG_MAKEPRI(fac, pri) ((fac) | (pri))
+#define LOG_FAC(p) (((p) & LOG_FACMASK) >> 3)
+#define LOG_MASK(pri) (1 << (pri))
+#define LOG_UPTO(pri) ((1 << ((pri) + 1)) - 1)
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+void syslog(int priority, const char *fmt, ...);
+
+#ifdef __cplusplus
+} // extern "C"
+#endif
+
+#endif /* _BITS_SYSLOG_H */
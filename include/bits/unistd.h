@@ -7,6 +7,8 @@
 extern "C" {
 #endif
 
+char *crypt(const char *key, const char *setting);
+
 int execl(const char *path, const char* argv0, ...);
 int execle(const char *path, const char* argv0, ...);
 int execlp(const char *file, const char* argv0, ...);
@@ -15,4 +17,13 @@ int execlp(const char *file, const char* argv0, ...);
 } // extern "C"
 #endif
 
+#if defined(_LARGEFILE64_SOURCE) || defined(_GNU_SOURCE)
+#define ftruncate64 ftruncate
+#define lockf64 lockf
+#define lseek64 lseek
+#define pread64 pread
+#define pwrite64 pwrite
+#define truncate64 truncate
+#endif
+
 #endif
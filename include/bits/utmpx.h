@@ -0,0 +1,9 @@
+#ifndef _BITS_UTMPX_H
+#define _BITS_UTMPX_H
+
+#define _PATH_UTMPX "/var/run/utmp"
+#define _PATH_WTMPX "/var/log/wtmp"
+#define UTMPX_FILE _PATH_UTMPX
+#define WTMPX_FILE _PATH_WTMPX
+
+#endif /* _BITS_UTMPX_H */
@@ -1,5 +1,9 @@
 #include <openlibm_math.h>
 
+// openlibm only raises the exceptions, src/c/math_errno.c sets errno as well
+#undef math_errhandling
+#define math_errhandling (MATH_ERRNO | MATH_ERREXCEPT)
+
 // Missing typedefs
 typedef float float_t;
 typedef double double_t;
@@ -16,3 +20,45 @@ typedef double double_t;
 #ifndef M_PI_4
 #define	M_PI_4		0.78539816339744830962	/* pi/4 */
 #endif
+
+// Accuracy of the special functions, which come from openlibm, in ulp of the larger of |f(x)| and
+// 1, which bounds the absolute error next to the zeros of the Bessel functions and of lgamma:
+//   j0, j1, y0, y1: 2          j0f, j1f, y0f, y1f: 4      for |x| <= 200
+//   jn, yn: 8                  jnf, ynf: 8                for |x| <= 200 and n <= 5
+//   lgamma, lgamma_r: 4        lgammaf, lgammaf_r: 4
+// and in ulp of f(x):
+//   tgamma: 8                  tgammaf: 8                 until the result overflows
+//   erf: 1                     erff: 1
+//   erfc: 4                    erfcf: 4                   while the result is normal
+// tests/math_special_accuracy.c checks them against values computed with 200-bit precision.
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+// GNU and BSD extensions, from src/c/math.c where openlibm doesn't have them
+void sincos(double x, double *s, double *c);
+void sincosf(float x, float *s, float *c);
+void sincosl(long double x, long double *s, long double *c);
+double exp10(double x);
+float exp10f(float x);
+long double exp10l(long double x);
+double significand(double x);
+float significandf(float x);
+long double significandl(long double x);
+int finite(double x);
+int finitef(float x);
+int finitel(long double x);
+int __finite(double x);
+int __finitef(float x);
+int __finitel(long double x);
+int __isinf(double x);
+int __isinff(float x);
+int __isinfl(long double x);
+int __isnan(double x);
+int __isnanf(float x);
+int __isnanl(long double x);
+
+#ifdef __cplusplus
+} // extern "C"
+#endif
@@ -12,6 +12,7 @@ typedef unsigned long nlink_t;
 typedef long long off_t;
 typedef int pid_t;
 typedef unsigned id_t;
+typedef int key_t;
 typedef long ssize_t;
 typedef long long time_t;
 typedef unsigned int useconds_t;
@@ -31,4 +32,14 @@ typedef unsigned long u_long, ulong;
 typedef long long quad_t;
 typedef unsigned long long u_quad_t;
 typedef char *caddr_t;
+
+// These are all 64 bits already, so large file support names them the same
+#if defined(_LARGEFILE64_SOURCE) || defined(_GNU_SOURCE)
+#define blkcnt64_t blkcnt_t
+#define fsblkcnt64_t fsblkcnt_t
+#define fsfilcnt64_t fsfilcnt_t
+#define ino64_t ino_t
+#define off64_t off_t
+#endif
+
 #endif /* _SYS_TYPES_INTERNAL_H */
@@ -0,0 +1,86 @@
+// openlibm has the float and double functions of complex.h but only some of the long double
+// ones, so the rest are here, from the real long double functions. They're weak so that
+// openlibm's win wherever it has them.
+
+long double atan2l(long double y, long double x);
+long double copysignl(long double x, long double y);
+long double cosl(long double x);
+long double coshl(long double x);
+long double expl(long double x);
+long double fabsl(long double x);
+long double hypotl(long double x, long double y);
+long double logl(long double x);
+long double sinl(long double x);
+long double sinhl(long double x);
+
+static long double _Complex make(long double re, long double im) {
+    long double _Complex z;
+    __real__ z = re;
+    __imag__ z = im;
+    return z;
+}
+
+__attribute__((weak))
+long double _Complex cexpl(long double _Complex z) {
+    long double x = __real__ z, y = __imag__ z;
+    long double e = expl(x);
+    if (y == 0) {
+        return make(e, y);
+    }
+    return make(e * cosl(y), e * sinl(y));
+}
+
+__attribute__((weak))
+long double _Complex clogl(long double _Complex z) {
+    long double x = __real__ z, y = __imag__ z;
+    return make(logl(hypotl(x, y)), atan2l(y, x));
+}
+
+__attribute__((weak))
+long double _Complex ccoshl(long double _Complex z) {
+    long double x = __real__ z, y = __imag__ z;
+    if (y == 0) {
+        return make(coshl(x), x * y);
+    }
+    return make(coshl(x) * cosl(y), sinhl(x) * sinl(y));
+}
+
+__attribute__((weak))
+long double _Complex csinhl(long double _Complex z) {
+    long double x = __real__ z, y = __imag__ z;
+    if (y == 0) {
+        return make(sinhl(x), y);
+    }
+    return make(sinhl(x) * cosl(y), coshl(x) * sinl(y));
+}
+
+__attribute__((weak))
+long double _Complex ctanhl(long double _Complex z) {
+    long double x = __real__ z, y = __imag__ z;
+    // Past this, tanh(x) is 1 in every precision and cosh(2x) would overflow
+    if (fabsl(x) > 40) {
+        return make(copysignl(1, x), copysignl(0, sinl(2 * y)));
+    }
+    long double d = coshl(2 * x) + cosl(2 * y);
+    return make(sinhl(2 * x) / d, sinl(2 * y) / d);
+}
+
+// The circular functions are the hyperbolic ones turned a quarter: cos(z) = cosh(iz),
+// sin(z) = -i sinh(iz) and tan(z) = -i tanh(iz)
+
+__attribute__((weak))
+long double _Complex ccosl(long double _Complex z) {
+    return ccoshl(make(-__imag__ z, __real__ z));
+}
+
+__attribute__((weak))
+long double _Complex csinl(long double _Complex z) {
+    long double _Complex w = csinhl(make(-__imag__ z, __real__ z));
+    return make(__imag__ w, -__real__ w);
+}
+
+__attribute__((weak))
+long double _Complex ctanl(long double _Complex z) {
+    long double _Complex w = ctanhl(make(-__imag__ z, __real__ z));
+    return make(__imag__ w, -__real__ w);
+}
@@ -527,10 +527,18 @@ MAX_RELEASE_CHECK_RATE   default: 4095 unless not HAVE_MMAP
 #define ONLY_MSPACES 1
 #define HAVE_MREMAP 0
 #define NO_MALLOC_STATS 1
+#define MALLOC_INSPECT_ALL 1
 #define USE_DL_PREFIX 1
 #define USE_LOCKS 1
 #define USE_SPIN_LOCKS 1
 #define malloc_getpagesize ((size_t)4096U)
+/* Mappings big enough to hold a huge page ask for transparent huge pages */
+#define MMAP(s) huge_mmap(s)
+#define DIRECT_MMAP(s) huge_mmap(s)
+#define HUGE_PAGE_SIZE ((size_t)2U * (size_t)1024U * (size_t)1024U)
+/* Free runs this big in the middle of the heap give their pages back */
+#define M_DECOMMIT_THRESHOLD (-9)
+#define DEFAULT_DECOMMIT_THRESHOLD ((size_t)1U * (size_t)1024U * (size_t)1024U)
 /* } Customizations */
 
 /* Version identifier to allow people to support multiple versions */
@@ -1708,6 +1716,16 @@ static FORCEINLINE int win32munmap(void* ptr, size_t size) {
 #define MUNMAP_DEFAULT(a, s)        win32munmap((a), (s))
 #define DIRECT_MMAP_DEFAULT(s)      win32direct_mmap(s)
 #endif /* WIN32 */
+
+/* Customizations { */
+static FORCEINLINE void* huge_mmap(size_t size) {
+  void* ptr = MMAP_DEFAULT(size);
+  /* The advice is only a hint, the kernel may not have huge pages to give */
+  if (ptr != MFAIL && size >= HUGE_PAGE_SIZE)
+    madvise(ptr, size, MADV_HUGEPAGE);
+  return ptr;
+}
+/* } Customizations */
 #endif /* HAVE_MMAP */
 
 #if HAVE_MREMAP
@@ -2609,6 +2627,7 @@ struct malloc_state {
   msegment   seg;
   void*      extp;      /* Unused but available for extensions */
   size_t     exts;
+  size_t     decommit_pending; /* bytes freed since pages were last given back */
 };
 
 typedef struct malloc_state*    mstate;
@@ -2628,6 +2647,7 @@ struct malloc_params {
   size_t granularity;
   size_t mmap_threshold;
   size_t trim_threshold;
+  size_t decommit_threshold;
   flag_t default_mflags;
 };
 
@@ -3150,6 +3170,7 @@ static int init_mparams(void) {
     mparams.page_size = psize;
     mparams.mmap_threshold = DEFAULT_MMAP_THRESHOLD;
     mparams.trim_threshold = DEFAULT_TRIM_THRESHOLD;
+    mparams.decommit_threshold = DEFAULT_DECOMMIT_THRESHOLD;
 #if MORECORE_CONTIGUOUS
     mparams.default_mflags = USE_LOCK_BIT|USE_MMAP_BIT;
 #else  /* MORECORE_CONTIGUOUS */
@@ -3214,6 +3235,9 @@ static int change_mparam(int param_number, int value) {
   case M_MMAP_THRESHOLD:
     mparams.mmap_threshold = val;
     return 1;
+  case M_DECOMMIT_THRESHOLD:
+    mparams.decommit_threshold = val;
+    return 1;
   default:
     return 0;
   }
@@ -4364,11 +4388,32 @@ static int sys_trim(mstate m, size_t pad) {
   return (released != 0)? 1 : 0;
 }
 
+/* Customizations { */
+/*
+  Gives the pages of free chunk p back to the system, when it is at
+  least decommit_threshold bytes long. To not give back pages only to
+  touch them again, this waits for another decommit_threshold bytes to
+  be freed each time. The links at the start of the chunk and its
+  footer, in the next chunk, stay.
+*/
+static void decommit_chunk(mstate m, mchunkptr p, size_t psize) {
+  if (psize >= mparams.decommit_threshold &&
+      m->decommit_pending >= mparams.decommit_threshold) {
+    size_t start = page_align((size_t)p + sizeof(struct malloc_tree_chunk));
+    size_t end = ((size_t)p + psize) & ~(mparams.page_size - SIZE_T_ONE);
+    m->decommit_pending = 0;
+    if (end > start)
+      madvise((void*)start, end - start, MADV_DONTNEED);
+  }
+}
+/* } Customizations */
+
 /* Consolidate and bin a chunk. Differs from exported versions
    of free mainly in that the chunk need not be marked as inuse.
 */
 static void dispose_chunk(mstate m, mchunkptr p, size_t psize) {
   mchunkptr next = chunk_plus_offset(p, psize);
+  m->decommit_pending += psize;
   if (!pinuse(p)) {
     mchunkptr prev;
     size_t prevsize = p->prev_foot;
@@ -4388,6 +4433,7 @@ static void dispose_chunk(mstate m, mchunkptr p, size_t psize) {
       else if ((next->head & INUSE_BITS) == INUSE_BITS) {
         m->dvsize = psize;
         set_free_with_pinuse(p, psize, next);
+        decommit_chunk(m, p, psize);
         return;
       }
     }
@@ -4412,6 +4458,7 @@ static void dispose_chunk(mstate m, mchunkptr p, size_t psize) {
         size_t dsize = m->dvsize += psize;
         m->dv = p;
         set_size_and_pinuse_of_free_chunk(p, dsize);
+        decommit_chunk(m, p, dsize);
         return;
       }
       else {
@@ -4421,6 +4468,7 @@ static void dispose_chunk(mstate m, mchunkptr p, size_t psize) {
         set_size_and_pinuse_of_free_chunk(p, psize);
         if (p == m->dv) {
           m->dvsize = psize;
+          decommit_chunk(m, p, psize);
           return;
         }
       }
@@ -4429,6 +4477,7 @@ static void dispose_chunk(mstate m, mchunkptr p, size_t psize) {
       set_free_with_pinuse(p, psize, next);
     }
     insert_chunk(m, p, psize);
+    decommit_chunk(m, p, psize);
   }
   else {
     CORRUPTION_ERROR_ACTION(m);
@@ -4709,6 +4758,7 @@ void dlfree(void* mem) {
       if (RTCHECK(ok_address(fm, p) && ok_inuse(p))) {
         size_t psize = chunksize(p);
         mchunkptr next = chunk_plus_offset(p, psize);
+        fm->decommit_pending += psize;
         if (!pinuse(p)) {
           size_t prevsize = p->prev_foot;
           if (is_mmapped(p)) {
@@ -4728,6 +4778,7 @@ void dlfree(void* mem) {
               else if ((next->head & INUSE_BITS) == INUSE_BITS) {
                 fm->dvsize = psize;
                 set_free_with_pinuse(p, psize, next);
+                decommit_chunk(fm, p, psize);
                 goto postaction;
               }
             }
@@ -4754,6 +4805,7 @@ void dlfree(void* mem) {
               size_t dsize = fm->dvsize += psize;
               fm->dv = p;
               set_size_and_pinuse_of_free_chunk(p, dsize);
+              decommit_chunk(fm, p, dsize);
               goto postaction;
             }
             else {
@@ -4763,6 +4815,7 @@ void dlfree(void* mem) {
               set_size_and_pinuse_of_free_chunk(p, psize);
               if (p == fm->dv) {
                 fm->dvsize = psize;
+                decommit_chunk(fm, p, psize);
                 goto postaction;
               }
             }
@@ -4778,6 +4831,7 @@ void dlfree(void* mem) {
             tchunkptr tp = (tchunkptr)p;
             insert_large_chunk(fm, tp, psize);
             check_free_chunk(fm, p);
+            decommit_chunk(fm, p, psize);
             if (--fm->release_checks == 0)
               release_unused_segments(fm);
           }
@@ -5636,6 +5690,7 @@ void mspace_free(mspace msp, void* mem) {
       if (RTCHECK(ok_address(fm, p) && ok_inuse(p))) {
         size_t psize = chunksize(p);
         mchunkptr next = chunk_plus_offset(p, psize);
+        fm->decommit_pending += psize;
         if (!pinuse(p)) {
           size_t prevsize = p->prev_foot;
           if (is_mmapped(p)) {
@@ -5655,6 +5710,7 @@ void mspace_free(mspace msp, void* mem) {
               else if ((next->head & INUSE_BITS) == INUSE_BITS) {
                 fm->dvsize = psize;
                 set_free_with_pinuse(p, psize, next);
+                decommit_chunk(fm, p, psize);
                 goto postaction;
               }
             }
@@ -5681,6 +5737,7 @@ void mspace_free(mspace msp, void* mem) {
               size_t dsize = fm->dvsize += psize;
               fm->dv = p;
               set_size_and_pinuse_of_free_chunk(p, dsize);
+              decommit_chunk(fm, p, dsize);
               goto postaction;
             }
             else {
@@ -5690,6 +5747,7 @@ void mspace_free(mspace msp, void* mem) {
               set_size_and_pinuse_of_free_chunk(p, psize);
               if (p == fm->dv) {
                 fm->dvsize = psize;
+                decommit_chunk(fm, p, psize);
                 goto postaction;
               }
             }
@@ -5705,6 +5763,7 @@ void mspace_free(mspace msp, void* mem) {
             tchunkptr tp = (tchunkptr)p;
             insert_large_chunk(fm, tp, psize);
             check_free_chunk(fm, p);
+            decommit_chunk(fm, p, psize);
             if (--fm->release_checks == 0)
               release_unused_segments(fm);
           }
@@ -5852,6 +5911,37 @@ size_t mspace_bulk_free(mspace msp, void* array[], size_t nelem) {
   return internal_bulk_free((mstate)msp, array, nelem);
 }
 
+/* Customizations { */
+/*
+  For fork, which copies the heap: mspace_fork_prepare takes the
+  global lock and that of msp, so that no other thread is in the
+  middle of changing either. The parent releases them after the fork
+  with mspace_fork_parent, and the child, where the thread that might
+  have been waiting for them doesn't exist, initializes them again
+  with mspace_fork_child.
+*/
+void mspace_fork_prepare(mspace msp) {
+  mstate ms = (mstate)msp;
+  ACQUIRE_MALLOC_GLOBAL_LOCK();
+  if (use_lock(ms))
+    ACQUIRE_LOCK(&ms->mutex);
+}
+
+void mspace_fork_parent(mspace msp) {
+  mstate ms = (mstate)msp;
+  if (use_lock(ms))
+    RELEASE_LOCK(&ms->mutex);
+  RELEASE_MALLOC_GLOBAL_LOCK();
+}
+
+void mspace_fork_child(mspace msp) {
+  mstate ms = (mstate)msp;
+  if (use_lock(ms))
+    INITIAL_LOCK(&ms->mutex);
+  INITIAL_LOCK(&malloc_global_mutex);
+}
+/* } Customizations */
+
 #if MALLOC_INSPECT_ALL
 void mspace_inspect_all(mspace msp,
                         void(*handler)(void *start,
@@ -0,0 +1,49 @@
+#include <stdarg.h>
+
+// TODO: Can be implemented in rust when cbindgen supports "..." syntax
+
+void vwarn(const char *fmt, va_list ap);
+void vwarnc(int code, const char *fmt, va_list ap);
+void vwarnx(const char *fmt, va_list ap);
+void verr(int eval, const char *fmt, va_list ap);
+void verrc(int eval, int code, const char *fmt, va_list ap);
+void verrx(int eval, const char *fmt, va_list ap);
+
+void warn(const char *fmt, ...) {
+    va_list ap;
+    va_start(ap, fmt);
+    vwarn(fmt, ap);
+    va_end(ap);
+}
+
+void warnc(int code, const char *fmt, ...) {
+    va_list ap;
+    va_start(ap, fmt);
+    vwarnc(code, fmt, ap);
+    va_end(ap);
+}
+
+void warnx(const char *fmt, ...) {
+    va_list ap;
+    va_start(ap, fmt);
+    vwarnx(fmt, ap);
+    va_end(ap);
+}
+
+void err(int eval, const char *fmt, ...) {
+    va_list ap;
+    va_start(ap, fmt);
+    verr(eval, fmt, ap);
+}
+
+void errc(int eval, int code, const char *fmt, ...) {
+    va_list ap;
+    va_start(ap, fmt);
+    verrc(eval, code, fmt, ap);
+}
+
+void errx(int eval, const char *fmt, ...) {
+    va_list ap;
+    va_start(ap, fmt);
+    verrx(eval, fmt, ap);
+}
@@ -0,0 +1,7 @@
+// Implemented in C, so the frame the walk starts from is backtrace's own
+
+int __backtrace_from(void **frame, void **buffer, int size);
+
+int backtrace(void **buffer, int size) {
+    return __backtrace_from(__builtin_frame_address(0), buffer, size);
+}
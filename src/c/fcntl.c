@@ -14,6 +14,16 @@ int open(const char* filename, int flags, ...) {
     return sys_open(filename, flags, mode);
 }
 
+// Large file support's name for open, which glibc's binaries use
+int open64(const char* filename, int flags, ...) {
+    mode_t mode = 0;
+    va_list ap;
+    va_start(ap, flags);
+    mode = va_arg(ap, mode_t);
+    va_end(ap);
+    return sys_open(filename, flags, mode);
+}
+
 int sys_fcntl(int fildes, int cmd, int args);
 
 int fcntl(int fildes, int cmd, ...) {
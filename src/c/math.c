@@ -0,0 +1,141 @@
+// GNU and BSD extensions of math.h that openlibm doesn't all have. They're weak so that
+// openlibm's win wherever it has them.
+
+double cos(double x);
+float cosf(float x);
+long double cosl(long double x);
+int ilogb(double x);
+int ilogbf(float x);
+int ilogbl(long double x);
+double pow(double x, double y);
+float powf(float x, float y);
+long double powl(long double x, long double y);
+double scalbn(double x, int n);
+float scalbnf(float x, int n);
+long double scalbnl(long double x, int n);
+double sin(double x);
+float sinf(float x);
+long double sinl(long double x);
+
+__attribute__((weak))
+void sincos(double x, double *s, double *c) {
+    *s = sin(x);
+    *c = cos(x);
+}
+
+__attribute__((weak))
+void sincosf(float x, float *s, float *c) {
+    *s = sinf(x);
+    *c = cosf(x);
+}
+
+__attribute__((weak))
+void sincosl(long double x, long double *s, long double *c) {
+    *s = sinl(x);
+    *c = cosl(x);
+}
+
+// pow is exact for the integers, which exp(x * M_LN10) isn't
+__attribute__((weak))
+double exp10(double x) {
+    return pow(10, x);
+}
+
+__attribute__((weak))
+float exp10f(float x) {
+    return powf(10, x);
+}
+
+__attribute__((weak))
+long double exp10l(long double x) {
+    return powl(10, x);
+}
+
+// The mantissa in [1, 2), leaving zeros, infinities and NaNs as they are
+__attribute__((weak))
+double significand(double x) {
+    if (x == 0 || !__builtin_isfinite(x)) {
+        return x;
+    }
+    return scalbn(x, -ilogb(x));
+}
+
+__attribute__((weak))
+float significandf(float x) {
+    if (x == 0 || !__builtin_isfinite(x)) {
+        return x;
+    }
+    return scalbnf(x, -ilogbf(x));
+}
+
+__attribute__((weak))
+long double significandl(long double x) {
+    if (x == 0 || !__builtin_isfinite(x)) {
+        return x;
+    }
+    return scalbnl(x, -ilogbl(x));
+}
+
+// The functions behind the classification macros of other C libraries, which programs built
+// against them and configure scripts call by name
+
+__attribute__((weak))
+int __finite(double x) {
+    return __builtin_isfinite(x);
+}
+
+__attribute__((weak))
+int __finitef(float x) {
+    return __builtin_isfinite(x);
+}
+
+__attribute__((weak))
+int __finitel(long double x) {
+    return __builtin_isfinite(x);
+}
+
+__attribute__((weak))
+int finite(double x) {
+    return __builtin_isfinite(x);
+}
+
+__attribute__((weak))
+int finitef(float x) {
+    return __builtin_isfinite(x);
+}
+
+__attribute__((weak))
+int finitel(long double x) {
+    return __builtin_isfinite(x);
+}
+
+// -1 for the negative infinity and 1 for the positive one
+__attribute__((weak))
+int __isinf(double x) {
+    return __builtin_isinf_sign(x);
+}
+
+__attribute__((weak))
+int __isinff(float x) {
+    return __builtin_isinf_sign(x);
+}
+
+__attribute__((weak))
+int __isinfl(long double x) {
+    return __builtin_isinf_sign(x);
+}
+
+__attribute__((weak))
+int __isnan(double x) {
+    return __builtin_isnan(x);
+}
+
+__attribute__((weak))
+int __isnanf(float x) {
+    return __builtin_isnan(x);
+}
+
+__attribute__((weak))
+int __isnanl(long double x) {
+    return __builtin_isnan(x);
+}
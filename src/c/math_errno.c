@@ -0,0 +1,204 @@
+// errno for the math functions that can fail, which openlibm only reports with the floating point
+// exceptions. The Makefile renames openlibm's versions of the functions wrapped here to
+// __openlibm_<name>, and each wrapper calls that and then, as glibc does, checks the arguments
+// for domain errors and poles and the result for overflow and underflow: an infinity or a zero
+// that the arguments, being finite, couldn't give exactly. The exceptions are left as openlibm
+// raised them. expf, logf, sinf, cosf and powf are in src/c/mathf.c, which sets errno itself.
+
+#include <errno.h>
+#include <limits.h>
+
+double floor(double x);
+float floorf(float x);
+long double floorl(long double x);
+double rint(double x);
+float rintf(float x);
+long double rintl(long double x);
+double round(double x);
+float roundf(float x);
+long double roundl(long double x);
+
+#define isfinite(x) __builtin_isfinite(x)
+#define isinf(x) __builtin_isinf(x)
+#define isnan(x) __builtin_isnan(x)
+
+// An infinity or zero result from arguments for which they can't be exact
+#define OVERFLOW(finite) (isinf(result) && (finite))
+#define UNDERFLOW(nonzero) (result == 0 && (nonzero))
+// A zero or subnormal result
+#define TINY (__builtin_fpclassify(0, 0, 0, 1, 1, result))
+
+// An integer rounded from x that doesn't fit in the result
+#define UNREPRESENTABLE(r, type, min) (!((r) >= (type)(min) && (r) < -(type)(min)))
+
+// The Makefile finds the names to rename in the lines starting with WRAP. `domain` and `range`
+// are checked once the function returned, so `range` may look at the result as well.
+#define WRAP(name, type, params, args, domain, range) \
+    type __openlibm_##name params; \
+    type name params { \
+        type result = __openlibm_##name args; \
+        if (domain) { \
+            errno = EDOM; \
+        } else if (range) { \
+            errno = ERANGE; \
+        } \
+        return result; \
+    }
+
+#define WRAP1(name, type, domain, range) WRAP(name, type, (type x), (x), domain, range)
+#define WRAP2(name, type, domain, range) WRAP(name, type, (type x, type y), (x, y), domain, range)
+
+WRAP1(acos, double, x < -1 || x > 1, 0)
+WRAP1(acosf, float, x < -1 || x > 1, 0)
+WRAP1(acosl, long double, x < -1 || x > 1, 0)
+WRAP1(acosh, double, x < 1, 0)
+WRAP1(acoshf, float, x < 1, 0)
+WRAP1(acoshl, long double, x < 1, 0)
+WRAP1(asin, double, x < -1 || x > 1, 0)
+WRAP1(asinf, float, x < -1 || x > 1, 0)
+WRAP1(asinl, long double, x < -1 || x > 1, 0)
+WRAP1(atanh, double, x < -1 || x > 1, x == -1 || x == 1)
+WRAP1(atanhf, float, x < -1 || x > 1, x == -1 || x == 1)
+WRAP1(atanhl, long double, x < -1 || x > 1, x == -1 || x == 1)
+WRAP1(cos, double, isinf(x), 0)
+WRAP1(cosl, long double, isinf(x), 0)
+WRAP1(cosh, double, 0, OVERFLOW(isfinite(x)))
+WRAP1(coshf, float, 0, OVERFLOW(isfinite(x)))
+WRAP1(coshl, long double, 0, OVERFLOW(isfinite(x)))
+WRAP1(erfc, double, 0, UNDERFLOW(isfinite(x)))
+WRAP1(erfcf, float, 0, UNDERFLOW(isfinite(x)))
+WRAP1(erfcl, long double, 0, UNDERFLOW(isfinite(x)))
+WRAP1(exp, double, 0, OVERFLOW(isfinite(x)) || UNDERFLOW(isfinite(x)))
+WRAP1(expl, long double, 0, OVERFLOW(isfinite(x)) || UNDERFLOW(isfinite(x)))
+WRAP1(exp2, double, 0, OVERFLOW(isfinite(x)) || UNDERFLOW(isfinite(x)))
+WRAP1(exp2f, float, 0, OVERFLOW(isfinite(x)) || UNDERFLOW(isfinite(x)))
+WRAP1(exp2l, long double, 0, OVERFLOW(isfinite(x)) || UNDERFLOW(isfinite(x)))
+WRAP1(expm1, double, 0, OVERFLOW(isfinite(x)))
+WRAP1(expm1f, float, 0, OVERFLOW(isfinite(x)))
+WRAP1(expm1l, long double, 0, OVERFLOW(isfinite(x)))
+WRAP1(lgamma, double, 0, (isfinite(x) && x <= 0 && floor(x) == x) || OVERFLOW(isfinite(x)))
+WRAP1(lgammaf, float, 0, (isfinite(x) && x <= 0 && floorf(x) == x) || OVERFLOW(isfinite(x)))
+WRAP1(lgammal, long double, 0,
+      (isfinite(x) && x <= 0 && floorl(x) == x) || OVERFLOW(isfinite(x)))
+WRAP1(log, double, x < 0, x == 0)
+WRAP1(logl, long double, x < 0, x == 0)
+WRAP1(log10, double, x < 0, x == 0)
+WRAP1(log10f, float, x < 0, x == 0)
+WRAP1(log10l, long double, x < 0, x == 0)
+WRAP1(log1p, double, x < -1, x == -1)
+WRAP1(log1pf, float, x < -1, x == -1)
+WRAP1(log1pl, long double, x < -1, x == -1)
+WRAP1(log2, double, x < 0, x == 0)
+WRAP1(log2f, float, x < 0, x == 0)
+WRAP1(log2l, long double, x < 0, x == 0)
+WRAP1(logb, double, 0, x == 0)
+WRAP1(logbf, float, 0, x == 0)
+WRAP1(logbl, long double, 0, x == 0)
+WRAP1(sin, double, isinf(x), 0)
+WRAP1(sinl, long double, isinf(x), 0)
+WRAP1(sinh, double, 0, OVERFLOW(isfinite(x)))
+WRAP1(sinhf, float, 0, OVERFLOW(isfinite(x)))
+WRAP1(sinhl, long double, 0, OVERFLOW(isfinite(x)))
+WRAP1(sqrt, double, x < 0, 0)
+WRAP1(sqrtf, float, x < 0, 0)
+WRAP1(sqrtl, long double, x < 0, 0)
+WRAP1(tan, double, isinf(x), 0)
+WRAP1(tanf, float, isinf(x), 0)
+WRAP1(tanl, long double, isinf(x), 0)
+WRAP1(tgamma, double, x < 0 && floor(x) == x,
+      x == 0 || OVERFLOW(isfinite(x)) || UNDERFLOW(isfinite(x)))
+WRAP1(tgammaf, float, x < 0 && floorf(x) == x,
+      x == 0 || OVERFLOW(isfinite(x)) || UNDERFLOW(isfinite(x)))
+WRAP1(y0, double, x < 0, x == 0 || OVERFLOW(isfinite(x)))
+WRAP1(y0f, float, x < 0, x == 0 || OVERFLOW(isfinite(x)))
+WRAP1(y1, double, x < 0, x == 0 || OVERFLOW(isfinite(x)))
+WRAP1(y1f, float, x < 0, x == 0 || OVERFLOW(isfinite(x)))
+
+WRAP2(fdim, double, 0, OVERFLOW(isfinite(x) && isfinite(y)))
+WRAP2(fdimf, float, 0, OVERFLOW(isfinite(x) && isfinite(y)))
+WRAP2(fdiml, long double, 0, OVERFLOW(isfinite(x) && isfinite(y)))
+WRAP2(fmod, double, (isinf(x) || y == 0) && !isnan(x) && !isnan(y), 0)
+WRAP2(fmodf, float, (isinf(x) || y == 0) && !isnan(x) && !isnan(y), 0)
+WRAP2(fmodl, long double, (isinf(x) || y == 0) && !isnan(x) && !isnan(y), 0)
+WRAP2(hypot, double, 0, OVERFLOW(isfinite(x) && isfinite(y)))
+WRAP2(hypotf, float, 0, OVERFLOW(isfinite(x) && isfinite(y)))
+WRAP2(hypotl, long double, 0, OVERFLOW(isfinite(x) && isfinite(y)))
+// Stepping to a subnormal number or zero underflows, unless to y itself
+WRAP2(nextafter, double, 0, OVERFLOW(isfinite(x)) || (TINY && x != y))
+WRAP2(nextafterf, float, 0, OVERFLOW(isfinite(x)) || (TINY && x != y))
+WRAP2(nextafterl, long double, 0, OVERFLOW(isfinite(x)) || (TINY && x != y))
+// A negative number to a finite power that isn't an integer has no real result. 0 to a finite
+// negative power is a pole, which the infinity tells apart like an overflow.
+WRAP2(pow, double, x < 0 && isfinite(x) && isfinite(y) && floor(y) != y,
+      OVERFLOW(isfinite(x) && isfinite(y)) || UNDERFLOW(x != 0 && isfinite(x) && isfinite(y)))
+WRAP2(powl, long double, x < 0 && isfinite(x) && isfinite(y) && floorl(y) != y,
+      OVERFLOW(isfinite(x) && isfinite(y)) || UNDERFLOW(x != 0 && isfinite(x) && isfinite(y)))
+WRAP2(remainder, double, (isinf(x) || y == 0) && !isnan(x) && !isnan(y), 0)
+WRAP2(remainderf, float, (isinf(x) || y == 0) && !isnan(x) && !isnan(y), 0)
+WRAP2(remainderl, long double, (isinf(x) || y == 0) && !isnan(x) && !isnan(y), 0)
+
+// An infinity times zero, or infinities of opposite signs added, give a NaN
+WRAP(fma, double, (double x, double y, double z), (x, y, z),
+     isnan(result) && !isnan(x) && !isnan(y) && !isnan(z),
+     OVERFLOW(isfinite(x) && isfinite(y) && isfinite(z)))
+WRAP(fmaf, float, (float x, float y, float z), (x, y, z),
+     isnan(result) && !isnan(x) && !isnan(y) && !isnan(z),
+     OVERFLOW(isfinite(x) && isfinite(y) && isfinite(z)))
+WRAP(fmal, long double, (long double x, long double y, long double z), (x, y, z),
+     isnan(result) && !isnan(x) && !isnan(y) && !isnan(z),
+     OVERFLOW(isfinite(x) && isfinite(y) && isfinite(z)))
+WRAP(ilogb, int, (double x), (x), x == 0 || !isfinite(x), 0)
+WRAP(ilogbf, int, (float x), (x), x == 0 || !isfinite(x), 0)
+WRAP(ilogbl, int, (long double x), (x), x == 0 || !isfinite(x), 0)
+WRAP(ldexp, double, (double x, int n), (x, n), 0,
+     OVERFLOW(isfinite(x)) || UNDERFLOW(x != 0 && isfinite(x)))
+WRAP(ldexpf, float, (float x, int n), (x, n), 0,
+     OVERFLOW(isfinite(x)) || UNDERFLOW(x != 0 && isfinite(x)))
+WRAP(ldexpl, long double, (long double x, int n), (x, n), 0,
+     OVERFLOW(isfinite(x)) || UNDERFLOW(x != 0 && isfinite(x)))
+WRAP(lgamma_r, double, (double x, int *sign), (x, sign), 0,
+     (isfinite(x) && x <= 0 && floor(x) == x) || OVERFLOW(isfinite(x)))
+WRAP(lgammaf_r, float, (float x, int *sign), (x, sign), 0,
+     (isfinite(x) && x <= 0 && floorf(x) == x) || OVERFLOW(isfinite(x)))
+WRAP(lgammal_r, long double, (long double x, int *sign), (x, sign), 0,
+     (isfinite(x) && x <= 0 && floorl(x) == x) || OVERFLOW(isfinite(x)))
+WRAP(llrint, long long, (double x), (x), UNREPRESENTABLE(rint(x), double, LLONG_MIN), 0)
+WRAP(llrintf, long long, (float x), (x), UNREPRESENTABLE(rintf(x), float, LLONG_MIN), 0)
+WRAP(llrintl, long long, (long double x), (x),
+     UNREPRESENTABLE(rintl(x), long double, LLONG_MIN), 0)
+WRAP(llround, long long, (double x), (x), UNREPRESENTABLE(round(x), double, LLONG_MIN), 0)
+WRAP(llroundf, long long, (float x), (x), UNREPRESENTABLE(roundf(x), float, LLONG_MIN), 0)
+WRAP(llroundl, long long, (long double x), (x),
+     UNREPRESENTABLE(roundl(x), long double, LLONG_MIN), 0)
+WRAP(lrint, long, (double x), (x), UNREPRESENTABLE(rint(x), double, LONG_MIN), 0)
+WRAP(lrintf, long, (float x), (x), UNREPRESENTABLE(rintf(x), float, LONG_MIN), 0)
+WRAP(lrintl, long, (long double x), (x), UNREPRESENTABLE(rintl(x), long double, LONG_MIN), 0)
+WRAP(lround, long, (double x), (x), UNREPRESENTABLE(round(x), double, LONG_MIN), 0)
+WRAP(lroundf, long, (float x), (x), UNREPRESENTABLE(roundf(x), float, LONG_MIN), 0)
+WRAP(lroundl, long, (long double x), (x), UNREPRESENTABLE(roundl(x), long double, LONG_MIN), 0)
+WRAP(nexttoward, double, (double x, long double y), (x, y), 0,
+     OVERFLOW(isfinite(x)) || (TINY && x != y))
+WRAP(nexttowardf, float, (float x, long double y), (x, y), 0,
+     OVERFLOW(isfinite(x)) || (TINY && x != y))
+WRAP(nexttowardl, long double, (long double x, long double y), (x, y), 0,
+     OVERFLOW(isfinite(x)) || (TINY && x != y))
+WRAP(remquo, double, (double x, double y, int *quo), (x, y, quo),
+     (isinf(x) || y == 0) && !isnan(x) && !isnan(y), 0)
+WRAP(remquof, float, (float x, float y, int *quo), (x, y, quo),
+     (isinf(x) || y == 0) && !isnan(x) && !isnan(y), 0)
+WRAP(remquol, long double, (long double x, long double y, int *quo), (x, y, quo),
+     (isinf(x) || y == 0) && !isnan(x) && !isnan(y), 0)
+WRAP(scalbln, double, (double x, long n), (x, n), 0,
+     OVERFLOW(isfinite(x)) || UNDERFLOW(x != 0 && isfinite(x)))
+WRAP(scalblnf, float, (float x, long n), (x, n), 0,
+     OVERFLOW(isfinite(x)) || UNDERFLOW(x != 0 && isfinite(x)))
+WRAP(scalblnl, long double, (long double x, long n), (x, n), 0,
+     OVERFLOW(isfinite(x)) || UNDERFLOW(x != 0 && isfinite(x)))
+WRAP(scalbn, double, (double x, int n), (x, n), 0,
+     OVERFLOW(isfinite(x)) || UNDERFLOW(x != 0 && isfinite(x)))
+WRAP(scalbnf, float, (float x, int n), (x, n), 0,
+     OVERFLOW(isfinite(x)) || UNDERFLOW(x != 0 && isfinite(x)))
+WRAP(scalbnl, long double, (long double x, int n), (x, n), 0,
+     OVERFLOW(isfinite(x)) || UNDERFLOW(x != 0 && isfinite(x)))
+WRAP(yn, double, (int n, double x), (n, x), x < 0, x == 0 || OVERFLOW(isfinite(x)))
+WRAP(ynf, float, (int n, float x), (n, x), x < 0, x == 0 || OVERFLOW(isfinite(x)))
@@ -0,0 +1,505 @@
+// Correctly rounded expf, logf, sinf and cosf, and powf, in place of openlibm's, which can be an
+// ulp off. Each is evaluated in double to within a relative error of 2^-50, which settles how the
+// result rounds to float for all but about one argument in 2^24. Those are left to the long
+// double function, which is precise enough for every float argument of the first four, as an
+// exhaustive search found. The result is the correctly rounded one in the default rounding mode,
+// and within an ulp in the others.
+//
+// Errors set errno as well as raising their exceptions: EDOM for domain errors, and ERANGE for
+// poles and results out of range.
+
+#include <errno.h>
+#include <stdint.h>
+
+long double cosl(long double x);
+long double expl(long double x);
+long double logl(long double x);
+long double powl(long double x, long double y);
+long double sinl(long double x);
+
+// The relative error the fast paths are within, with a margin
+#define ERROR 0x1p-49
+
+static inline uint32_t asuint(float x) {
+    union { float f; uint32_t i; } u = { x };
+    return u.i;
+}
+
+static inline float asfloat(uint32_t i) {
+    union { uint32_t i; float f; } u = { i };
+    return u.f;
+}
+
+static inline double asdouble(uint64_t i) {
+    union { uint64_t i; double f; } u = { i };
+    return u.f;
+}
+
+// Whether all of [y - err, y + err] rounds to the same float, which is then stored in `out`
+static inline int settled(double y, double err, float *out) {
+    float lo = (float) (y - err), hi = (float) (y + err);
+    *out = lo;
+    return lo == hi;
+}
+
+static inline double fabs_(double x) {
+    return x < 0 ? -x : x;
+}
+
+// Results that raise the exceptions they should, which constant folding would lose, and set errno
+static float overflow(uint32_t sign) {
+    volatile float huge = asfloat(sign | 0x7f000000);
+    errno = ERANGE;
+    return huge * 0x1p127f;
+}
+
+static float underflow(uint32_t sign) {
+    volatile float tiny = asfloat(sign | 0x10000000);
+    errno = ERANGE;
+    return tiny * 0x1p-100f;
+}
+
+static float invalid(float x) {
+    volatile float zero = 0;
+    errno = EDOM;
+    return (x - x) / zero;
+}
+
+static float pole(uint32_t sign) {
+    volatile float zero = 0;
+    errno = ERANGE;
+    return asfloat(sign | 0x3f800000) / zero;
+}
+
+// Sets errno for the results that only overflow or underflow once they're rounded to float
+static float ranged(float result) {
+    uint32_t ar = asuint(result) & 0x7fffffff;
+    if (ar == 0 || ar == 0x7f800000) {
+        errno = ERANGE;
+    }
+    return result;
+}
+
+// 2^(i/64), rounded to nearest
+static const double EXP2_64[64] = {
+    0x1.0000000000000p+0, 0x1.02c9a3e778061p+0, 0x1.059b0d3158574p+0, 0x1.0874518759bc8p+0,
+    0x1.0b5586cf9890fp+0, 0x1.0e3ec32d3d1a2p+0, 0x1.11301d0125b51p+0, 0x1.1429aaea92de0p+0,
+    0x1.172b83c7d517bp+0, 0x1.1a35beb6fcb75p+0, 0x1.1d4873168b9aap+0, 0x1.2063b88628cd6p+0,
+    0x1.2387a6e756238p+0, 0x1.26b4565e27cddp+0, 0x1.29e9df51fdee1p+0, 0x1.2d285a6e4030bp+0,
+    0x1.306fe0a31b715p+0, 0x1.33c08b26416ffp+0, 0x1.371a7373aa9cbp+0, 0x1.3a7db34e59ff7p+0,
+    0x1.3dea64c123422p+0, 0x1.4160a21f72e2ap+0, 0x1.44e086061892dp+0, 0x1.486a2b5c13cd0p+0,
+    0x1.4bfdad5362a27p+0, 0x1.4f9b2769d2ca7p+0, 0x1.5342b569d4f82p+0, 0x1.56f4736b527dap+0,
+    0x1.5ab07dd485429p+0, 0x1.5e76f15ad2148p+0, 0x1.6247eb03a5585p+0, 0x1.6623882552225p+0,
+    0x1.6a09e667f3bcdp+0, 0x1.6dfb23c651a2fp+0, 0x1.71f75e8ec5f74p+0, 0x1.75feb564267c9p+0,
+    0x1.7a11473eb0187p+0, 0x1.7e2f336cf4e62p+0, 0x1.82589994cce13p+0, 0x1.868d99b4492edp+0,
+    0x1.8ace5422aa0dbp+0, 0x1.8f1ae99157736p+0, 0x1.93737b0cdc5e5p+0, 0x1.97d829fde4e50p+0,
+    0x1.9c49182a3f090p+0, 0x1.a0c667b5de565p+0, 0x1.a5503b23e255dp+0, 0x1.a9e6b5579fdbfp+0,
+    0x1.ae89f995ad3adp+0, 0x1.b33a2b84f15fbp+0, 0x1.b7f76f2fb5e47p+0, 0x1.bcc1e904bc1d2p+0,
+    0x1.c199bdd85529cp+0, 0x1.c67f12e57d14bp+0, 0x1.cb720dcef9069p+0, 0x1.d072d4a07897cp+0,
+    0x1.d5818dcfba487p+0, 0x1.da9e603db3285p+0, 0x1.dfc97337b9b5fp+0, 0x1.e502ee78b3ff6p+0,
+    0x1.ea4afa2a490dap+0, 0x1.efa1bee615a27p+0, 0x1.f50765b6e4540p+0, 0x1.fa7c1819e90d8p+0,
+};
+
+// e^(hi + lo), for |hi| <= 104 and |lo| <= 2^-40, to within 2^-51
+static double exp_dd(double hi, double lo) {
+    // hi + lo = k*log(2)/64 + r, with |r| <= log(2)/128. kd*LN2_64_HI is exact, and so is what
+    // it's subtracted from.
+    const double LN2_64_HI = 0x1.62e42fefa0000p-7, LN2_64_LO = 0x1.cf79abc9e3b3ap-46;
+    double kd = hi * 0x1.71547652b82fep+6 + 0x1.8p52;
+    kd -= 0x1.8p52;
+    int k = (int) kd;
+    double r = ((hi - kd * LN2_64_HI) - kd * LN2_64_LO) + lo;
+
+    // e^r to within 2^-58
+    double p = 1 + r * (1 + r * (0x1p-1 + r * (0x1.5555555555555p-3 + r * (0x1.5555555555555p-5
+        + r * (0x1.1111111111111p-7 + r * 0x1.6c16c16c16c17p-10)))));
+    double scale = asdouble((uint64_t) ((k >> 6) + 1023) << 52);
+    return EXP2_64[k & 63] * p * scale;
+}
+
+float expf(float x) {
+    uint32_t ix = asuint(x);
+    if ((ix & 0x7fffffff) >= 0x42b00000) {
+        // |x| >= 88, past which only overflow and underflow are left beyond the checks below
+        if ((ix & 0x7fffffff) > 0x7f800000) {
+            return x + x;
+        }
+        if (ix == 0xff800000) {
+            return 0;
+        }
+        if (ix == 0x7f800000) {
+            return x;
+        }
+        if (x > 89) {
+            return overflow(0);
+        }
+        if (x < -104) {
+            return underflow(0);
+        }
+    }
+
+    double y = exp_dd(x, 0);
+
+    float result;
+    if (settled(y, y * ERROR, &result)) {
+        return ranged(result);
+    }
+    return ranged((float) expl(x));
+}
+
+float logf(float x) {
+    uint32_t ix = asuint(x);
+    int e = 0;
+    if (ix - 0x00800000 >= 0x7f800000 - 0x00800000) {
+        // Zero, subnormal, negative, infinite or NaN
+        if (ix << 1 == 0) {
+            return pole(0x80000000);
+        }
+        if (ix == 0x7f800000) {
+            return x;
+        }
+        if (ix << 1 > 0xff000000) {
+            return x + x;
+        }
+        if (ix & 0x80000000) {
+            return invalid(x);
+        }
+        ix = asuint(x * 0x1p23f);
+        e = -23;
+    }
+
+    // x = 2^e * m, with m in [sqrt(2)/2, sqrt(2))
+    uint32_t tmp = ix - 0x3f3504f3;
+    e += (int32_t) tmp >> 23;
+    double m = asfloat(ix - (tmp & 0xff800000));
+
+    // log(m) = 2*atanh(s), whose Taylor series converges quickly for |s| <= 0.172. m - 1 and
+    // m + 1 are exact.
+    double s = (m - 1) / (m + 1), w = s * s;
+    double q = 0x1.5555555555555p-1 + w * (0x1.999999999999ap-2 + w * (0x1.2492492492492p-2
+        + w * (0x1.c71c71c71c71cp-3 + w * (0x1.745d1745d1746p-3 + w * (0x1.3b13b13b13b14p-3
+        + w * (0x1.1111111111111p-3 + w * (0x1.e1e1e1e1e1e1ep-4 + w * (0x1.af286bca1af28p-4
+        + w * 0x1.8618618618618p-4))))))));
+    double log_m = 2 * s + s * w * q;
+
+    // e*LN2_HI is exact
+    const double LN2_HI = 0x1.62e42fefa3800p-1, LN2_LO = 0x1.ef35793c76730p-45;
+    double y = e * LN2_HI + (e * LN2_LO + log_m);
+
+    float result;
+    if (settled(y, fabs_(y) * ERROR, &result)) {
+        return result;
+    }
+    return (float) logl(x);
+}
+
+// sin(r) and cos(r) for |r| <= pi/4, to within 2^-54
+static double sin_pi4(double r) {
+    double w = r * r;
+    return r + r * w * (-0x1.5555555555555p-3 + w * (0x1.1111111111111p-7
+        + w * (-0x1.a01a01a01a01ap-13 + w * (0x1.71de3a556c734p-19 + w * (-0x1.ae64567f544e4p-26
+        + w * (0x1.6124613a86d09p-33 + w * (-0x1.ae7f3e733b81fp-41
+        + w * 0x1.952c77030ad4ap-49)))))));
+}
+
+static double cos_pi4(double r) {
+    double w = r * r;
+    return 1 + w * (-0x1p-1 + w * (0x1.5555555555555p-5 + w * (-0x1.6c16c16c16c17p-10
+        + w * (0x1.a01a01a01a01ap-16 + w * (-0x1.27e4fb7789f5cp-22 + w * (0x1.1eed8eff8d898p-29
+        + w * (-0x1.93974a8c07c9dp-37 + w * (0x1.ae7f3e733b81fp-45
+        + w * -0x1.6827863b97d97p-53))))))));
+}
+
+// x = k*pi/2 + r, with |r| <= pi/4, for |x| < 2^20. Returns k mod 4. The parts of pi/2 have
+// 33 bits, so that kd*PIO2_1 and kd*PIO2_2 are exact, and the subtractions lose nothing to
+// cancellation.
+static int reduce(float x, double *r) {
+    const double PIO2_1 = 0x1.921fb54400000p+0, PIO2_2 = 0x1.0b4611a600000p-34,
+        PIO2_3 = 0x1.3198a2e037073p-69;
+    double kd = (double) x * 0x1.45f306dc9c883p-1 + 0x1.8p52;
+    kd -= 0x1.8p52;
+    *r = (((double) x - kd * PIO2_1) - kd * PIO2_2) - kd * PIO2_3;
+    return (int) kd & 3;
+}
+
+float sinf(float x) {
+    uint32_t ax = asuint(x) & 0x7fffffff;
+    if (ax >= 0x49800000) {
+        if (ax >= 0x7f800000) {
+            return ax == 0x7f800000 ? invalid(x) : x + x;
+        }
+        // The argument reduction is long double's
+        return (float) sinl(x);
+    }
+    if (ax < 0x39800000) {
+        // Below 2^-12, x^3/6 is less than half an ulp of x, and -0 keeps its sign
+        return x;
+    }
+
+    double r;
+    double y;
+    switch (reduce(x, &r)) {
+    case 0: y = sin_pi4(r); break;
+    case 1: y = cos_pi4(r); break;
+    case 2: y = -sin_pi4(r); break;
+    default: y = -cos_pi4(r); break;
+    }
+
+    float result;
+    if (settled(y, fabs_(y) * ERROR, &result)) {
+        return result;
+    }
+    return (float) sinl(x);
+}
+
+float cosf(float x) {
+    uint32_t ax = asuint(x) & 0x7fffffff;
+    if (ax >= 0x49800000) {
+        if (ax >= 0x7f800000) {
+            return ax == 0x7f800000 ? invalid(x) : x + x;
+        }
+        return (float) cosl(x);
+    }
+
+    double r;
+    double y;
+    switch (reduce(x, &r)) {
+    case 0: y = cos_pi4(r); break;
+    case 1: y = -sin_pi4(r); break;
+    case 2: y = -cos_pi4(r); break;
+    default: y = sin_pi4(r); break;
+    }
+
+    float result;
+    if (settled(y, fabs_(y) * ERROR, &result)) {
+        return result;
+    }
+    return (float) cosl(x);
+}
+
+// For m in each 64th of [sqrt(2)/2, sqrt(2)) by its bits, from sqrt(2)/2: 1/c for c the middle
+// of the interval, rounded to 20 bits, and -log of that in two parts. It's 1 for the interval
+// containing 1, so that log(m) keeps its relative precision there.
+static const double LOG_INV[64][3] = {
+    {0x1.680cc00000000p+0, -0x1.5d401f956015ap-2, -0x1.7043c8933bbbap-60},
+    {0x1.6423000000000p+0, -0x1.520f8015dc121p-2, -0x1.47218ef610e66p-58},
+    {0x1.604ec00000000p+0, -0x1.46fdb9cb14910p-2, -0x1.ae5b3ca4895b8p-57},
+    {0x1.5c8f600000000p+0, -0x1.3c0a51192c291p-2, -0x1.6fe1c2833a010p-58},
+    {0x1.58e4200000000p+0, -0x1.31345cc3bb8f2p-2, 0x1.b1be7b1fceebap-60},
+    {0x1.554c800000000p+0, -0x1.267ba0bb84357p-2, 0x1.ac10c851f8ee4p-57},
+    {0x1.51c7e00000000p+0, -0x1.1bdf74e04e3d8p-2, -0x1.7345e52dd2665p-57},
+    {0x1.4e55a00000000p+0, -0x1.115f21cf743e9p-2, 0x1.9376a50fa948fp-56},
+    {0x1.4af5200000000p+0, -0x1.06f9e0b29f9bap-2, -0x1.97cfe88357c11p-56},
+    {0x1.47a6000000000p+0, -0x1.f95f462bd498cp-3, -0x1.a7150efeddedep-57},
+    {0x1.4467a00000000p+0, -0x1.e4ff116302b2fp-3, -0x1.d171c024c72bbp-57},
+    {0x1.4139800000000p+0, -0x1.d0d20e8d92626p-3, 0x1.a654d25eeffd8p-57},
+    {0x1.3e1b200000000p+0, -0x1.bcd710eb507f5p-3, 0x1.44c036ce3cf43p-57},
+    {0x1.3b0c400000000p+0, -0x1.a90e733669109p-3, -0x1.6e97da98e0fcap-59},
+    {0x1.380c200000000p+0, -0x1.95753f3cd6d41p-3, 0x1.f1ffb75c7fdb5p-58},
+    {0x1.351aa00000000p+0, -0x1.820c79c76d990p-3, -0x1.2cae0d27168adp-57},
+    {0x1.3237200000000p+0, -0x1.6ed1ce2b31c87p-3, -0x1.f4e323b364c1ap-57},
+    {0x1.2f61600000000p+0, -0x1.5bc54e2cd7840p-3, 0x1.c0367b9f232f7p-59},
+    {0x1.2c98e00000000p+0, -0x1.48e54dabdb22cp-3, -0x1.d3fbcf7034b58p-57},
+    {0x1.29dd600000000p+0, -0x1.3631be7cd1fbdp-3, -0x1.fa93654de7cd8p-57},
+    {0x1.272e800000000p+0, -0x1.23a9aa23be948p-3, 0x1.2039f11113954p-58},
+    {0x1.248be00000000p+0, -0x1.114c07a4a0311p-3, 0x1.0a1675d42ed9cp-57},
+    {0x1.21f5200000000p+0, -0x1.fe2f76a2caf4ep-4, -0x1.1d08eefd24fbbp-62},
+    {0x1.1f6a000000000p+0, -0x1.da18f53deec4ap-4, 0x1.b057d4f08d547p-58},
+    {0x1.1cea400000000p+0, -0x1.b653dfe532b46p-4, 0x1.83c36dbd5d887p-58},
+    {0x1.1a75800000000p+0, -0x1.92dda4e230999p-4, -0x1.94e5f93f86925p-60},
+    {0x1.180ba00000000p+0, -0x1.6fb733c79e51cp-4, -0x1.8f29760807cc9p-59},
+    {0x1.15ac200000000p+0, -0x1.4cdbecef6814bp-4, 0x1.800c69527cd6fp-58},
+    {0x1.1356c00000000p+0, -0x1.2a4ab15f7bf7bp-4, -0x1.edf67819fcf91p-58},
+    {0x1.110b600000000p+0, -0x1.08042a7619561p-4, -0x1.8c6225c985882p-58},
+    {0x1.0ec9c00000000p+0, -0x1.cc0e2b1f3c83ap-5, -0x1.e4415d34fcc32p-59},
+    {0x1.0c91a00000000p+0, -0x1.88a42f991f934p-5, -0x1.ce7b868274bd2p-59},
+    {0x1.0a62c00000000p+0, -0x1.45c77ba1796d6p-5, -0x1.12594cb952cd3p-60},
+    {0x1.083cc00000000p+0, -0x1.037116f654549p-5, 0x1.dba4845743090p-60},
+    {0x1.061fa00000000p+0, -0x1.834adf46e2e0dp-6, 0x1.ea9de9786e010p-60},
+    {0x1.040b200000000p+0, -0x1.00c242597742cp-6, 0x1.4da813028fc80p-61},
+    {0x1.01ff000000000p+0, -0x1.fd04a23af054dp-8, 0x1.35da53be2030ep-62},
+    {0x1.0000000000000p+0, 0, 0},
+    {0x1.f80c600000000p-1, 0x1.00732154e2211p-6, 0x1.711f1a1abdc95p-61},
+    {0x1.f069800000000p-1, 0x1.fa903c6de175bp-6, -0x1.069c2e52a047bp-61},
+    {0x1.e901000000000p-1, 0x1.7874d50033869p-5, 0x1.fbce7c877d94cp-63},
+    {0x1.e1d0600000000p-1, 0x1.f1ccb1694a415p-5, -0x1.e6bb0e218060dp-59},
+    {0x1.dad5000000000p-1, 0x1.34b05c03ef5b0p-4, 0x1.9305f07a7bd99p-58},
+    {0x1.d40cc00000000p-1, 0x1.6f9d9b3a5790bp-4, -0x1.719f5d9a505ddp-59},
+    {0x1.cd75600000000p-1, 0x1.a9b5221d7f32cp-4, 0x1.07cb0795c3513p-58},
+    {0x1.c70cc00000000p-1, 0x1.e2fdb9523a043p-4, 0x1.e6a3d9e035545p-58},
+    {0x1.c0d1400000000p-1, 0x1.0dbd34f93093fp-3, -0x1.c6c27b1de954bp-58},
+    {0x1.bac0c00000000p-1, 0x1.2999b8a78e62cp-3, -0x1.e29c4029cc323p-57},
+    {0x1.b4d9c00000000p-1, 0x1.451603e7cbee7p-3, 0x1.4a40c97d7bc73p-59},
+    {0x1.af1a600000000p-1, 0x1.6035c020b58c3p-3, 0x1.ad0d22b83f565p-57},
+    {0x1.a981400000000p-1, 0x1.7afa8d31326a0p-3, 0x1.6df86d3142076p-57},
+    {0x1.a40ce00000000p-1, 0x1.9566e6d943724p-3, 0x1.f5b251574d5dap-57},
+    {0x1.9ebbe00000000p-1, 0x1.af7cf3eb03948p-3, 0x1.8d64f583dcea9p-57},
+    {0x1.998ce00000000p-1, 0x1.c93f1d76b11fbp-3, 0x1.a41405df48b04p-58},
+    {0x1.947ec00000000p-1, 0x1.e2aecbd580d49p-3, 0x1.84a2f1ff2b803p-57},
+    {0x1.8f90000000000p-1, 0x1.fbcf87c628bc1p-3, -0x1.4140b4734da14p-61},
+    {0x1.8abfc00000000p-1, 0x1.0a50f49ca5da9p-2, -0x1.f2835dde9ebd0p-56},
+    {0x1.860ce00000000p-1, 0x1.1693ff2cc3f0fp-2, -0x1.1cea141a780cbp-57},
+    {0x1.8176400000000p-1, 0x1.22b2062cb717bp-2, 0x1.31fc79d7ed7c6p-56},
+    {0x1.7cfb000000000p-1, 0x1.2eab9c1a66069p-2, -0x1.f6a5a8205664fp-56},
+    {0x1.789a200000000p-1, 0x1.3a81bebf839f7p-2, 0x1.238734e6e6ab2p-57},
+    {0x1.7452a00000000p-1, 0x1.463584895364ep-2, 0x1.ccdc6c93a9ae9p-57},
+    {0x1.7023c00000000p-1, 0x1.51c76ad8e9d13p-2, 0x1.3445149b3c70cp-56},
+    {0x1.6c0cc00000000p-1, 0x1.5d3800587f0efp-2, 0x1.9f92d00810e87p-59},
+};
+
+// a + b as hi + *lo exactly
+static inline double two_sum(double a, double b, double *lo) {
+    double s = a + b, bb = s - a;
+    *lo = (a - (s - bb)) + (b - bb);
+    return s;
+}
+
+// log(2^e * x) for a positive, finite, normal x, as hi + *lo to within 2^-58 of it
+static double log_dd(uint32_t ix, int e, double *lo) {
+    uint32_t tmp = ix - 0x3f3504f3;
+    e += (int32_t) tmp >> 23;
+    const double *t = LOG_INV[(tmp >> 17) & 63];
+    double m = asfloat(ix - (tmp & 0xff800000));
+
+    // m*t[0] has at most 44 bits, close to 1, so r is exact, and |r| < 2^-6.9
+    double r = m * t[0] - 1;
+    double p = r * r * (-0x1p-1 + r * (0x1.5555555555555p-2 + r * (-0x1p-2
+        + r * (0x1.999999999999ap-3 + r * (-0x1.5555555555555p-3 + r * (0x1.2492492492492p-3
+        + r * (-0x1p-3 + r * (0x1.c71c71c71c71cp-4 + r * -0x1.999999999999ap-4))))))));
+
+    const double LN2_HI = 0x1.62e42fefa3800p-1, LN2_LO = 0x1.ef35793c76730p-45;
+    double lo1, lo2;
+    double hi = two_sum(e * LN2_HI, t[1], &lo1);
+    hi = two_sum(hi, r, &lo2);
+    return two_sum(hi, lo1 + lo2 + e * LN2_LO + t[2] + p, lo);
+}
+
+// 1 if y is an odd integer, 2 if it's an even one, and 0 if it isn't one
+static int integer_kind(uint32_t iy) {
+    int e = (iy >> 23 & 0xff) - 0x7f;
+    if (e < 0) {
+        return iy << 1 == 0 ? 2 : 0;
+    }
+    if (e > 23) {
+        return 2;
+    }
+    if (iy & ((1u << (23 - e)) - 1)) {
+        return 0;
+    }
+    return iy & (1u << (23 - e)) ? 1 : 2;
+}
+
+// The x^y with |x| = a*2^e for an odd a, and y > 0 a multiple of 1/8, that have at most 53 bits,
+// exactly, and 0 for those that don't. These include the ones right between two floats.
+static double exact_pow(uint32_t ix, float y) {
+    int e = (ix >> 23 & 0xff) - 150;
+    uint64_t a = (ix & 0x7fffff) | 0x800000;
+    if (!(ix >> 23 & 0xff)) {
+        a = ix & 0x7fffff;
+        e = -149;
+    }
+    while (!(a & 1)) {
+        a >>= 1;
+        e++;
+    }
+
+    // y = n/2^q, for n odd unless q is 0
+    int n = (int) (y * 8), q = 3;
+    while (q > 0 && !(n & 1)) {
+        n >>= 1;
+        q--;
+    }
+    for (int i = 0; i < q; i++) {
+        uint64_t c = (uint64_t) __builtin_sqrt((double) a);
+        if (c * c != a || e % 2 != 0) {
+            return 0;
+        }
+        a = c;
+        e /= 2;
+    }
+
+    uint64_t b = 1;
+    for (int i = 0; i < n; i++) {
+        if (a > 1 && b >= (1ull << 53) / a) {
+            return 0;
+        }
+        b *= a;
+    }
+    // Far past what a float can hold otherwise
+    long scale = (long) e * n;
+    if (scale < -1022 || scale > 900) {
+        return 0;
+    }
+    return (double) b * asdouble((uint64_t) (scale + 1023) << 52);
+}
+
+float powf(float x, float y) {
+    uint32_t ix = asuint(x), iy = asuint(y);
+    uint32_t ax = ix & 0x7fffffff, ay = iy & 0x7fffffff;
+    if (ay == 0 || ix == 0x3f800000) {
+        return 1;
+    }
+    if (ax > 0x7f800000 || ay > 0x7f800000) {
+        return x + y;
+    }
+
+    int kind = integer_kind(iy);
+    // The sign of the result if x is negative
+    uint32_t sign = 0;
+    if (ix & 0x80000000) {
+        if (kind == 0 && ax != 0x7f800000 && ax != 0) {
+            return invalid(x);
+        }
+        sign = kind == 1 ? 0x80000000 : 0;
+    }
+
+    if (ay == 0x7f800000) {
+        if (ax == 0x3f800000) {
+            return 1;
+        }
+        return (ax > 0x3f800000) == !(iy & 0x80000000) ? y * y : 0;
+    }
+    if (ax == 0 || ax == 0x7f800000) {
+        // 0 or infinity to a power, which is the other if the power is negative
+        if ((ax == 0) == !!(iy & 0x80000000)) {
+            return ax == 0 ? pole(sign) : asfloat(sign | 0x7f800000);
+        }
+        return asfloat(sign);
+    }
+
+    int e = 0;
+    if (ax < 0x00800000) {
+        ax = asuint(asfloat(ax) * 0x1p23f);
+        e = -23;
+    }
+    // y*log|x| as t_hi + t_lo. y has 24 bits, and lhi's halves at most 26 and 27, so their products
+    // are exact.
+    double llo, lhi = log_dd(ax, e, &llo);
+    double split = lhi * 0x1.0000002p27, lhi_hi = split - (split - lhi), lhi_lo = lhi - lhi_hi;
+    double t_hi = (double) y * lhi;
+    double t_lo = (((double) y * lhi_hi - t_hi) + (double) y * lhi_lo) + (double) y * llo;
+
+    if (t_hi > 89) {
+        return overflow(sign);
+    }
+    if (t_hi < -104) {
+        return underflow(sign);
+    }
+    double z = exp_dd(t_hi, t_lo);
+    if (sign) {
+        z = -z;
+    }
+
+    float result;
+    if (settled(z, fabs_(z) * ERROR, &result)) {
+        return ranged(result);
+    }
+    // Only exact powers can be right between two floats
+    if (!(iy & 0x80000000) && y < 32 && y * 8 == (float) (int) (y * 8)) {
+        double exact = exact_pow(ix & 0x7fffffff, y);
+        if (exact != 0) {
+            return ranged((float) (sign ? -exact : exact));
+        }
+    }
+    return ranged((float) powl(x, y));
+}
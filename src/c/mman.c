@@ -0,0 +1,20 @@
+#include <stdarg.h>
+#include <stddef.h>
+
+// TODO: Can be implemented in rust when cbindgen supports "..." syntax
+
+// From sys/mman.h
+#define MREMAP_FIXED 2
+
+void *sys_mremap(void *old_address, size_t old_size, size_t new_size, int flags, void *new_address);
+
+void *mremap(void *old_address, size_t old_size, size_t new_size, int flags, ...) {
+    void *new_address = NULL;
+    if (flags & MREMAP_FIXED) {
+        va_list ap;
+        va_start(ap, flags);
+        new_address = va_arg(ap, void *);
+        va_end(ap);
+    }
+    return sys_mremap(old_address, old_size, new_size, flags, new_address);
+}
@@ -0,0 +1,27 @@
+#include <stdarg.h>
+#include <sys/types_internal.h>
+#include <bits/locale_t.h>
+
+// TODO: Can be implemented in rust when cbindgen supports "..." syntax
+
+ssize_t vstrfmon(char * s, size_t max, const char * fmt, va_list ap);
+
+ssize_t strfmon(char * s, size_t max, const char * fmt, ...) {
+    ssize_t ret;
+    va_list ap;
+    va_start(ap, fmt);
+    ret = vstrfmon(s, max, fmt, ap);
+    va_end(ap);
+    return ret;
+}
+
+ssize_t vstrfmon_l(char * s, size_t max, locale_t loc, const char * fmt, va_list ap);
+
+ssize_t strfmon_l(char * s, size_t max, locale_t loc, const char * fmt, ...) {
+    ssize_t ret;
+    va_list ap;
+    va_start(ap, fmt);
+    ret = vstrfmon_l(s, max, loc, fmt, ap);
+    va_end(ap);
+    return ret;
+}
@@ -0,0 +1,18 @@
+#include <stdarg.h>
+#include <sys/types_internal.h>
+
+// TODO: Can be implemented in rust when cbindgen supports "..." syntax
+
+struct mq_attr;
+
+int sys_mq_open(const char *name, int oflag, mode_t mode, const struct mq_attr *attr);
+
+// The mode and the attributes are only passed with O_CREAT, and only used then
+int mq_open(const char *name, int oflag, ...) {
+    va_list ap;
+    va_start(ap, oflag);
+    mode_t mode = va_arg(ap, mode_t);
+    const struct mq_attr *attr = va_arg(ap, const struct mq_attr *);
+    va_end(ap);
+    return sys_mq_open(name, oflag, mode, attr);
+}
@@ -2,12 +2,12 @@
 
 #include <stdarg.h>
 
-int sys_ptrace(int request, va_list ap);
+long sys_ptrace(int request, va_list ap);
 
-int ptrace(int request, ...) {
+long ptrace(int request, ...) {
     va_list ap;
     va_start(ap, request);
-    int ret = sys_ptrace(request, ap);
+    long ret = sys_ptrace(request, ap);
     va_end(ap);
     return ret;
 }
@@ -0,0 +1,18 @@
+#include <stdarg.h>
+#include <sys/types_internal.h>
+
+// TODO: Can be implemented in rust when cbindgen supports "..." syntax
+
+int sys_clone(int (*fn)(void *), void *stack, int flags, void *arg,
+        pid_t *parent_tid, void *tls, pid_t *child_tid);
+
+// The thread IDs and the TLS are only passed with the flags that use them
+int clone(int (*fn)(void *), void *stack, int flags, void *arg, ...) {
+    va_list ap;
+    va_start(ap, arg);
+    pid_t *parent_tid = va_arg(ap, pid_t *);
+    void *tls = va_arg(ap, void *);
+    pid_t *child_tid = va_arg(ap, pid_t *);
+    va_end(ap);
+    return sys_clone(fn, stack, flags, arg, parent_tid, tls, child_tid);
+}
@@ -0,0 +1,49 @@
+#include <stdarg.h>
+
+// TODO: Can be implemented in rust when cbindgen supports "..." syntax
+
+// From sys/sem.h
+#define IPC_SET 1
+#define IPC_STAT 2
+#define IPC_INFO 3
+#define GETALL 13
+#define SETVAL 16
+#define SETALL 17
+#define SEM_STAT 18
+#define SEM_INFO 19
+
+// The caller defines its own union semun, this one has the same layout
+union semun {
+    int val;
+    void *buf;
+    unsigned short *array;
+};
+
+int sys_semctl(int semid, int semnum, int cmd, unsigned long arg);
+
+int semctl(int semid, int semnum, int cmd, ...) {
+    unsigned long arg = 0;
+    switch (cmd) {
+        case IPC_SET:
+        case IPC_STAT:
+        case IPC_INFO:
+        case GETALL:
+        case SETALL:
+        case SEM_STAT:
+        case SEM_INFO: {
+            va_list ap;
+            va_start(ap, cmd);
+            arg = (unsigned long) va_arg(ap, union semun).buf;
+            va_end(ap);
+            break;
+        }
+        case SETVAL: {
+            va_list ap;
+            va_start(ap, cmd);
+            arg = (unsigned long) va_arg(ap, union semun).val;
+            va_end(ap);
+            break;
+        }
+    }
+    return sys_semctl(semid, semnum, cmd, arg);
+}
@@ -1,10 +1,22 @@
 #include <stdint.h>
+#include <sys/types_internal.h>
 
 void abort();
+ssize_t write(int fildes, const void *buf, size_t nbyte);
 
+// Made random at startup, this is only used before then
 uintptr_t __stack_chk_guard = 0xd048c37519fcadfe;
 
 __attribute__((noreturn))
 void __stack_chk_fail(void) {
+	// Nothing that might have been overwritten is used to report it
+	static const char msg[] = "*** stack smashing detected ***: terminated\n";
+	write(2, msg, sizeof(msg) - 1);
 	abort();
 }
+
+// What position independent code calls on i386 and some other architectures
+__attribute__((noreturn))
+void __stack_chk_fail_local(void) {
+	__stack_chk_fail();
+}
@@ -92,3 +92,8 @@ int sscanf(const char * input, const char * fmt, ...) {
     va_end(ap);
     return ret;
 }
+
+// Rust's VaList can't take a long double
+void relibc_va_arg_long_double(va_list *ap, long double *out) {
+    *out = va_arg(*ap, long double);
+}
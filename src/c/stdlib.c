@@ -1,5 +1,7 @@
-double strtod(const char *nptr, char **endptr);
+void relibc_strtold(long double *out, const char *nptr, char **endptr);
 
 long double strtold(const char *nptr, char **endptr) {
-    return (long double)strtod(nptr, endptr);
+    long double ret;
+    relibc_strtold(&ret, nptr, endptr);
+    return ret;
 }
@@ -0,0 +1,12 @@
+#include <stdarg.h>
+
+// TODO: Can be implemented in rust when cbindgen supports "..." syntax
+
+void vsyslog(int priority, const char *fmt, va_list ap);
+
+void syslog(int priority, const char *fmt, ...) {
+    va_list ap;
+    va_start(ap, fmt);
+    vsyslog(priority, fmt, ap);
+    va_end(ap);
+}
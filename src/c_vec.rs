@@ -1,6 +1,7 @@
 use crate::{
+    header::stdlib,
     io::{self, Write},
-    platform::{self, types::*, WriteByte},
+    platform::{types::*, WriteByte},
 };
 use core::{
     cmp, fmt,
@@ -51,7 +52,7 @@ impl<T> CVec<T> {
             return Ok(Self::new());
         }
         let size = Self::check_mul(cap, mem::size_of::<T>())?;
-        let ptr = NonNull::new(unsafe { platform::alloc(size) as *mut T }).ok_or(AllocError)?;
+        let ptr = NonNull::new(unsafe { stdlib::malloc(size) as *mut T }).ok_or(AllocError)?;
         Ok(Self { ptr, len: 0, cap })
     }
     unsafe fn resize(&mut self, cap: usize) -> Result<(), AllocError> {
@@ -59,10 +60,10 @@ impl<T> CVec<T> {
         let ptr = if cap == 0 {
             NonNull::dangling()
         } else if self.cap > 0 {
-            NonNull::new(platform::realloc(self.ptr.as_ptr() as *mut c_void, size) as *mut T)
+            NonNull::new(stdlib::realloc(self.ptr.as_ptr() as *mut c_void, size) as *mut T)
                 .ok_or(AllocError)?
         } else {
-            NonNull::new((platform::alloc(size)) as *mut T).ok_or(AllocError)?
+            NonNull::new(stdlib::malloc(size) as *mut T).ok_or(AllocError)?
         };
         self.ptr = ptr;
         self.cap = cap;
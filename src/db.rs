@@ -45,7 +45,7 @@ pub type FileDb = Db<BufReader<File>>;
 
 impl FileDb {
     pub fn open(path: &CStr, separator: Separator) -> io::Result<Self> {
-        let file = File::open(path, fcntl::O_RDONLY | fcntl::O_CLOEXEC)?;
+        let file = File::open(path, fcntl::O_RDONLY)?;
         Ok(Db::new(BufReader::new(file), separator))
     }
 }
@@ -0,0 +1,56 @@
+//! The errors of relibc's internal calls, as the errno they set.
+
+use core::fmt;
+
+use crate::{
+    header::errno::STR_ERROR,
+    io,
+    platform::{self, types::*},
+};
+
+/// An errno value, which a C function sets before returning its error.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Errno(pub c_int);
+
+pub type Result<T, E = Errno> = core::result::Result<T, E>;
+
+impl Errno {
+    /// The errno the last failed call set.
+    pub fn last() -> Self {
+        Self(unsafe { platform::errno })
+    }
+
+    /// Sets errno, for a C function that's about to fail.
+    pub fn set(self) {
+        unsafe { platform::errno = self.0 };
+    }
+}
+
+impl fmt::Display for Errno {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match STR_ERROR.get(self.0 as usize) {
+            Some(desc) if self.0 >= 0 => f.write_str(desc),
+            _ => write!(f, "Unknown error {}", self.0),
+        }
+    }
+}
+
+impl From<Errno> for io::Error {
+    fn from(err: Errno) -> Self {
+        io::Error::from_raw_os_error(err.0)
+    }
+}
+
+#[cfg(target_os = "redox")]
+impl From<syscall::Error> for Errno {
+    fn from(err: syscall::Error) -> Self {
+        Self(err.errno)
+    }
+}
+
+#[cfg(target_os = "redox")]
+impl From<Errno> for syscall::Error {
+    fn from(err: Errno) -> Self {
+        syscall::Error::new(err.0)
+    }
+}
@@ -0,0 +1,686 @@
+//! Exact conversions between decimal strings and binary floating point numbers of any
+//! precision, for long double, which Rust has no type for, and for printf.
+
+use alloc::{string::String, vec::Vec};
+use core::{
+    cmp::Ordering,
+    ffi::{VaList, VaListImpl},
+    num::FpCategory,
+};
+
+use crate::{
+    c_str::CStr,
+    header::{_fenv, errno::ERANGE, locale},
+    platform::{self, types::*},
+};
+
+/// An unsigned integer of any size, as its 32-bit digits, the least significant first and
+/// without zeros at the end
+#[derive(Clone)]
+struct Big(Vec<u32>);
+
+impl Big {
+    fn new(mut n: u128) -> Self {
+        let mut digits = Vec::new();
+        while n != 0 {
+            digits.push(n as u32);
+            n >>= 32;
+        }
+        Big(digits)
+    }
+
+    fn is_zero(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    fn bits(&self) -> u32 {
+        match self.0.last() {
+            Some(top) => 32 * self.0.len() as u32 - top.leading_zeros(),
+            None => 0,
+        }
+    }
+
+    fn trim(&mut self) {
+        while self.0.last() == Some(&0) {
+            self.0.pop();
+        }
+    }
+
+    fn mul_add(&mut self, factor: u32, add: u32) {
+        let mut carry = u64::from(add);
+        for digit in &mut self.0 {
+            let value = u64::from(*digit) * u64::from(factor) + carry;
+            *digit = value as u32;
+            carry = value >> 32;
+        }
+        if carry != 0 {
+            self.0.push(carry as u32);
+        }
+        self.trim();
+    }
+
+    fn mul_pow(&mut self, base: u32, mut exp: u32) {
+        // The biggest powers of 5 and 10 that fit in a digit
+        let (chunk, chunk_exp) = if base == 5 {
+            (1_220_703_125, 13)
+        } else {
+            (1_000_000_000, 9)
+        };
+        while exp >= chunk_exp {
+            self.mul_add(chunk, 0);
+            exp -= chunk_exp;
+        }
+        self.mul_add(base.pow(exp), 0);
+    }
+
+    fn shl(&self, bits: u32) -> Self {
+        if self.is_zero() {
+            return Big(Vec::new());
+        }
+        let (words, bits) = ((bits / 32) as usize, bits % 32);
+        let mut digits = vec![0; words];
+        let mut carry = 0;
+        for &digit in &self.0 {
+            digits.push(digit << bits | carry);
+            carry = if bits == 0 { 0 } else { digit >> (32 - bits) };
+        }
+        if carry != 0 {
+            digits.push(carry);
+        }
+        Big(digits)
+    }
+
+    fn cmp(&self, other: &Big) -> Ordering {
+        self.0
+            .len()
+            .cmp(&other.0.len())
+            .then_with(|| self.0.iter().rev().cmp(other.0.iter().rev()))
+    }
+
+    /// Subtracts `other`, which isn't bigger.
+    fn sub(&mut self, other: &Big) {
+        let mut borrow = 0;
+        for (i, digit) in self.0.iter_mut().enumerate() {
+            let value =
+                i64::from(*digit) - i64::from(other.0.get(i).copied().unwrap_or(0)) - borrow;
+            *digit = value as u32;
+            borrow = if value < 0 { 1 } else { 0 };
+        }
+        self.trim();
+    }
+
+    /// Divides by `divisor`, returning the remainder.
+    fn div_small(&mut self, divisor: u32) -> u32 {
+        let mut remainder = 0;
+        for digit in self.0.iter_mut().rev() {
+            let value = u64::from(remainder) << 32 | u64::from(*digit);
+            *digit = (value / u64::from(divisor)) as u32;
+            remainder = (value % u64::from(divisor)) as u32;
+        }
+        self.trim();
+        remainder
+    }
+
+    /// Divides by `divisor`, whose quotient must fit in a u128, returning it and the
+    /// remainder.
+    fn div(mut self, divisor: &Big) -> (u128, Big) {
+        let mut quotient = 0;
+        let shift = self.bits().saturating_sub(divisor.bits());
+        for i in (0..=shift).rev() {
+            let shifted = divisor.shl(i);
+            if self.cmp(&shifted) != Ordering::Less {
+                self.sub(&shifted);
+                quotient |= 1 << i;
+            }
+        }
+        (quotient, self)
+    }
+
+    /// The decimal digits, without leading zeros.
+    fn decimal(mut self) -> Vec<u8> {
+        let mut chunks = Vec::new();
+        while !self.is_zero() {
+            chunks.push(self.div_small(1_000_000_000));
+        }
+        let mut digits = Vec::new();
+        for (i, chunk) in chunks.iter().rev().enumerate() {
+            let chunk = if i == 0 {
+                format!("{}", chunk)
+            } else {
+                format!("{:09}", chunk)
+            };
+            digits.extend_from_slice(chunk.as_bytes());
+        }
+        digits
+    }
+}
+
+/// A binary floating point format: its numbers are a mantissa of `precision` bits, or fewer
+/// for the subnormal ones, times two to the power of an exponent in the range.
+pub struct Format {
+    precision: u32,
+    min_exponent: i32,
+    max_exponent: i32,
+}
+
+pub const DOUBLE: Format = Format {
+    precision: 53,
+    min_exponent: -1074,
+    max_exponent: 971,
+};
+
+/// The 80-bit extended precision of x87
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+pub const LONG_DOUBLE: Format = Format {
+    precision: 64,
+    min_exponent: -16445,
+    max_exponent: 16320,
+};
+
+/// The quadruple precision of IEEE 754
+#[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+pub const LONG_DOUBLE: Format = Format {
+    precision: 113,
+    min_exponent: -16494,
+    max_exponent: 16271,
+};
+
+/// A floating point number, with the value mantissa * 2^exponent if it's finite
+#[derive(Clone, Copy, Debug)]
+pub struct Float {
+    pub negative: bool,
+    pub class: FpCategory,
+    pub mantissa: u128,
+    pub exponent: i32,
+}
+
+/// The bytes of a long double
+#[repr(C, align(16))]
+#[derive(Clone, Copy, Debug)]
+pub struct LongDouble(pub [u8; 16]);
+
+impl Float {
+    pub fn from_f64(float: f64) -> Self {
+        let bits = float.to_bits();
+        let biased = ((bits >> 52) & 0x7ff) as i32;
+        let fraction = u128::from(bits & ((1 << 52) - 1));
+        let (mantissa, exponent) = if biased == 0 {
+            (fraction, DOUBLE.min_exponent)
+        } else {
+            (fraction | 1 << 52, biased - 1075)
+        };
+        Float {
+            negative: float.is_sign_negative(),
+            class: float.classify(),
+            mantissa,
+            exponent,
+        }
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        let bits = match self.class {
+            FpCategory::Nan => 0x7ff8 << 48,
+            FpCategory::Infinite => 0x7ff << 52,
+            FpCategory::Zero | FpCategory::Subnormal => self.mantissa as u64,
+            FpCategory::Normal => {
+                ((self.exponent + 1075) as u64) << 52 | (self.mantissa as u64 & ((1 << 52) - 1))
+            }
+        };
+        f64::from_bits(bits | (self.negative as u64) << 63)
+    }
+
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    pub fn from_long_double(float: LongDouble) -> Self {
+        let bytes = float.0;
+        let mut mantissa_bytes = [0; 8];
+        mantissa_bytes.copy_from_slice(&bytes[..8]);
+        let mantissa = u128::from(u64::from_le_bytes(mantissa_bytes));
+        let top = u16::from_le_bytes([bytes[8], bytes[9]]);
+        let biased = i32::from(top & 0x7fff);
+        let class = match biased {
+            0x7fff if mantissa << 65 == 0 => FpCategory::Infinite,
+            0x7fff => FpCategory::Nan,
+            _ if mantissa == 0 => FpCategory::Zero,
+            _ if mantissa >> 63 == 0 => FpCategory::Subnormal,
+            _ => FpCategory::Normal,
+        };
+        Float {
+            negative: top >> 15 != 0,
+            class,
+            mantissa,
+            exponent: biased.max(1) - 16446,
+        }
+    }
+
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    pub fn to_long_double(&self) -> LongDouble {
+        let (mantissa, biased) = match self.class {
+            FpCategory::Nan => (0xc000_0000_0000_0000, 0x7fff),
+            FpCategory::Infinite => (0x8000_0000_0000_0000, 0x7fff),
+            FpCategory::Zero | FpCategory::Subnormal => (self.mantissa as u64, 0),
+            FpCategory::Normal => (self.mantissa as u64, (self.exponent + 16446) as u16),
+        };
+        let mut bytes = [0; 16];
+        bytes[..8].copy_from_slice(&mantissa.to_le_bytes());
+        bytes[8..10].copy_from_slice(&(biased | (self.negative as u16) << 15).to_le_bytes());
+        LongDouble(bytes)
+    }
+
+    #[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+    pub fn from_long_double(float: LongDouble) -> Self {
+        let bits = u128::from_le_bytes(float.0);
+        let biased = ((bits >> 112) & 0x7fff) as i32;
+        let fraction = bits & ((1 << 112) - 1);
+        let class = match biased {
+            0x7fff if fraction == 0 => FpCategory::Infinite,
+            0x7fff => FpCategory::Nan,
+            0 if fraction == 0 => FpCategory::Zero,
+            0 => FpCategory::Subnormal,
+            _ => FpCategory::Normal,
+        };
+        Float {
+            negative: bits >> 127 != 0,
+            class,
+            mantissa: if biased == 0 {
+                fraction
+            } else {
+                fraction | 1 << 112
+            },
+            exponent: biased.max(1) - 16495,
+        }
+    }
+
+    #[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+    pub fn to_long_double(&self) -> LongDouble {
+        let bits = match self.class {
+            FpCategory::Nan => 0x7fff_8 << 108,
+            FpCategory::Infinite => 0x7fff << 112,
+            FpCategory::Zero | FpCategory::Subnormal => self.mantissa,
+            FpCategory::Normal => {
+                ((self.exponent + 16495) as u128) << 112 | (self.mantissa & ((1 << 112) - 1))
+            }
+        };
+        LongDouble((bits | (self.negative as u128) << 127).to_le_bytes())
+    }
+
+    pub fn is_finite(&self) -> bool {
+        self.class != FpCategory::Infinite && self.class != FpCategory::Nan
+    }
+
+    fn zero(negative: bool) -> Self {
+        Float {
+            negative,
+            class: FpCategory::Zero,
+            mantissa: 0,
+            exponent: 0,
+        }
+    }
+
+    fn special(negative: bool, class: FpCategory) -> Self {
+        Float {
+            class,
+            ..Float::zero(negative)
+        }
+    }
+
+    /// The number of `format` nearest to numerator / denominator * 2^scale, and whether it is
+    /// out of the range of the normal numbers.
+    fn nearest(
+        negative: bool,
+        numerator: Big,
+        denominator: Big,
+        scale: i32,
+        format: &Format,
+    ) -> (Self, bool) {
+        if numerator.is_zero() {
+            return (Float::zero(negative), false);
+        }
+
+        // The exponent that leaves a quotient of the precision, or one more bit
+        let mut exponent =
+            scale - (format.precision as i32 + denominator.bits() as i32 - numerator.bits() as i32);
+        let (mut mantissa, remainder, denominator) = loop {
+            exponent = exponent.max(format.min_exponent);
+            let shift = scale - exponent;
+            let (numerator, denominator) = if shift >= 0 {
+                (numerator.shl(shift as u32), denominator.clone())
+            } else {
+                (numerator.clone(), denominator.shl(-shift as u32))
+            };
+            let (quotient, remainder) = numerator.div(&denominator);
+            if quotient >> format.precision == 0 {
+                break (quotient, remainder, denominator);
+            }
+            exponent += 1;
+        };
+        // Rounding to nearest, and to even between two
+        let exact = remainder.is_zero();
+        match remainder.shl(1).cmp(&denominator) {
+            Ordering::Greater => mantissa += 1,
+            Ordering::Equal if mantissa & 1 == 1 => mantissa += 1,
+            _ => (),
+        }
+        if mantissa >> format.precision != 0 {
+            mantissa >>= 1;
+            exponent += 1;
+        }
+
+        if exponent > format.max_exponent {
+            return (Float::special(negative, FpCategory::Infinite), true);
+        }
+        let class = if mantissa == 0 {
+            FpCategory::Zero
+        } else if mantissa >> (format.precision - 1) == 0 {
+            FpCategory::Subnormal
+        } else {
+            FpCategory::Normal
+        };
+        let float = Float {
+            negative,
+            class,
+            mantissa,
+            exponent,
+        };
+        (float, !exact && class != FpCategory::Normal)
+    }
+
+    /// Reads a number the way strtod does, in `format`, returning it, the number of bytes it
+    /// took and whether it was out of range, or None if `s` doesn't start with one.
+    pub fn parse(s: &[u8], decimal_point: &[u8], format: &Format) -> Option<(Self, usize, bool)> {
+        let at = |i: usize| s.get(i).copied().unwrap_or(0);
+        let starts_with = |i: usize, word: &[u8]| {
+            s.len() >= i + word.len() && s[i..i + word.len()].eq_ignore_ascii_case(word)
+        };
+
+        let mut i = 0;
+        while at(i) == b' ' || (b'\t'..=b'\r').contains(&at(i)) {
+            i += 1;
+        }
+        let negative = at(i) == b'-';
+        if at(i) == b'-' || at(i) == b'+' {
+            i += 1;
+        }
+
+        if starts_with(i, b"inf") {
+            let len = if starts_with(i, b"infinity") { 8 } else { 3 };
+            return Some((
+                Float::special(negative, FpCategory::Infinite),
+                i + len,
+                false,
+            ));
+        }
+        if starts_with(i, b"nan") {
+            i += 3;
+            if at(i) == b'(' {
+                let end = s[i + 1..]
+                    .iter()
+                    .position(|b| !(b.is_ascii_alphanumeric() || *b == b'_'));
+                if let Some(end) = end.filter(|&end| at(i + 1 + end) == b')') {
+                    i += end + 2;
+                }
+            }
+            return Some((Float::special(negative, FpCategory::Nan), i, false));
+        }
+
+        let is_point = |i: usize| {
+            !decimal_point.is_empty() && s.get(i..).map_or(false, |s| s.starts_with(decimal_point))
+        };
+        let hex = at(i) == b'0'
+            && (at(i + 1) | 32) == b'x'
+            && (at(i + 2).is_ascii_hexdigit()
+                || is_point(i + 2) && at(i + 2 + decimal_point.len()).is_ascii_hexdigit());
+        let radix = if hex {
+            i += 2;
+            16
+        } else {
+            10
+        };
+
+        // The digits, as an integer, and the power of the radix it's to be multiplied by
+        let mut digits = Big::new(0);
+        let mut count = 0;
+        let mut scale: i64 = 0;
+        let mut any = false;
+        let mut rest = false;
+        let mut point = false;
+        loop {
+            if let Some(digit) = (at(i) as char).to_digit(radix) {
+                any = true;
+                // Far past the precision of any format, only whether the rest is zero matters
+                if count < 20000 {
+                    digits.mul_add(radix, digit);
+                    if !digits.is_zero() {
+                        count += 1;
+                    }
+                    if point {
+                        scale -= 1;
+                    }
+                } else {
+                    rest |= digit != 0;
+                    if !point {
+                        scale += 1;
+                    }
+                }
+                i += 1;
+            } else if !point && is_point(i) {
+                point = true;
+                i += decimal_point.len();
+            } else {
+                break;
+            }
+        }
+        if !any {
+            return None;
+        }
+        if rest {
+            digits.mul_add(radix, 1);
+            scale -= 1;
+        }
+
+        let mut exponent: i64 = 0;
+        if (at(i) | 32) == if hex { b'p' } else { b'e' } {
+            let mut j = i + 1;
+            let exponent_negative = at(j) == b'-';
+            if at(j) == b'-' || at(j) == b'+' {
+                j += 1;
+            }
+            if at(j).is_ascii_digit() {
+                while at(j).is_ascii_digit() {
+                    exponent = (exponent * 10 + i64::from(at(j) - b'0')).min(1 << 40);
+                    j += 1;
+                }
+                if exponent_negative {
+                    exponent = -exponent;
+                }
+                i = j;
+            }
+        }
+
+        if digits.is_zero() {
+            return Some((Float::zero(negative), i, false));
+        }
+        let (float, range) = if hex {
+            let exponent = exponent + 4 * scale;
+            // Far enough out of range that the digits can't bring it back
+            if exponent > 1 << 20 {
+                (Float::special(negative, FpCategory::Infinite), true)
+            } else if exponent < -(1 << 20) {
+                (Float::zero(negative), true)
+            } else {
+                Float::nearest(negative, digits, Big::new(1), exponent as i32, format)
+            }
+        } else {
+            let exponent = exponent + scale;
+            let magnitude = exponent + count as i64;
+            if magnitude > 5000 {
+                (Float::special(negative, FpCategory::Infinite), true)
+            } else if magnitude < -5000 {
+                (Float::zero(negative), true)
+            } else if exponent >= 0 {
+                digits.mul_pow(10, exponent as u32);
+                Float::nearest(negative, digits, Big::new(1), 0, format)
+            } else {
+                let mut denominator = Big::new(1);
+                denominator.mul_pow(10, -exponent as u32);
+                Float::nearest(negative, digits, denominator, 0, format)
+            }
+        };
+        Some((float, i, range))
+    }
+
+    /// The exact decimal digits of the magnitude, without leading zeros, and how many of them
+    /// come before the point, which can be more than there are or less than zero.
+    fn decimal(&self) -> (Vec<u8>, isize) {
+        let mut mantissa = Big::new(self.mantissa);
+        if self.exponent >= 0 {
+            let digits = mantissa.shl(self.exponent as u32).decimal();
+            let point = digits.len() as isize;
+            (digits, point)
+        } else {
+            // m / 2^n is m * 5^n / 10^n
+            let shift = -self.exponent as u32;
+            mantissa.mul_pow(5, shift);
+            let digits = mantissa.decimal();
+            let point = digits.len() as isize - shift as isize;
+            (digits, point)
+        }
+    }
+
+    /// The decimal digits of the magnitude rounded to `keep` of them in the rounding mode of
+    /// fenv, and where the point is.
+    fn rounded(&self, keep: impl Fn(isize) -> isize) -> (Vec<u8>, isize) {
+        let (mut digits, mut point) = if self.class == FpCategory::Zero {
+            (Vec::new(), 1)
+        } else {
+            self.decimal()
+        };
+        let keep = keep(point);
+        if keep >= digits.len() as isize {
+            return (digits, point);
+        }
+
+        let rest_zero = digits[keep.max(0) as usize..].iter().all(|&d| d == b'0');
+        let up = match unsafe { _fenv::fegetround() } {
+            _fenv::FE_UPWARD => !self.negative && !rest_zero,
+            _fenv::FE_DOWNWARD => self.negative && !rest_zero,
+            _fenv::FE_TOWARDZERO => false,
+            // To nearest, and to even between two
+            _ if keep < 0 => false,
+            _ => {
+                let keep = keep as usize;
+                let odd = keep > 0 && (digits[keep - 1] - b'0') % 2 == 1;
+                match digits[keep] {
+                    b'6'..=b'9' => true,
+                    b'5' => odd || digits[keep + 1..].iter().any(|&d| d != b'0'),
+                    _ => false,
+                }
+            }
+        };
+        if keep < 0 {
+            // Every digit is past the last one kept, which is a one if rounding up
+            return if up {
+                (vec![b'1'], point - keep + 1)
+            } else {
+                (Vec::new(), point)
+            };
+        }
+
+        digits.truncate(keep as usize);
+        if up {
+            let mut i = keep as usize;
+            loop {
+                if i == 0 {
+                    digits.insert(0, b'1');
+                    point += 1;
+                    break;
+                }
+                i -= 1;
+                if digits[i] == b'9' {
+                    digits[i] = b'0';
+                } else {
+                    digits[i] += 1;
+                    break;
+                }
+            }
+        }
+        (digits, point)
+    }
+
+    /// The magnitude with `precision` digits after the point, as %f writes it.
+    pub fn fixed(&self, precision: usize) -> String {
+        let (digits, point) = self.rounded(|point| point + precision as isize);
+        let digit = |i: isize| {
+            if i >= 0 && (i as usize) < digits.len() {
+                digits[i as usize] as char
+            } else {
+                '0'
+            }
+        };
+        let mut string = String::new();
+        if point <= 0 {
+            string.push('0');
+        }
+        for i in 0..point {
+            string.push(digit(i));
+        }
+        if precision > 0 {
+            string.push('.');
+            for i in point..point + precision as isize {
+                string.push(digit(i));
+            }
+        }
+        string
+    }
+
+    /// The magnitude with one digit before the point and `precision` after it, and the
+    /// exponent of ten it goes with, as %e writes them.
+    pub fn scientific(&self, precision: usize) -> (String, isize) {
+        let (digits, point) = self.rounded(|_| precision as isize + 1);
+        let digit = |i: usize| digits.get(i).map_or('0', |&d| d as char);
+        let mut string = String::new();
+        string.push(digit(0));
+        if precision > 0 {
+            string.push('.');
+            for i in 1..=precision {
+                string.push(digit(i));
+            }
+        }
+        let exponent = if digits.is_empty() { 0 } else { point - 1 };
+        (string, exponent)
+    }
+}
+
+extern "C" {
+    fn relibc_va_arg_long_double(ap: *mut VaListImpl, out: *mut LongDouble);
+}
+
+/// Takes the next argument of `ap` as a long double, which VaList::arg can't.
+pub unsafe fn va_arg_long_double(ap: &mut VaList) -> LongDouble {
+    let mut float = LongDouble([0; 16]);
+    relibc_va_arg_long_double(&mut **ap, &mut float);
+    float
+}
+
+/// strtold, for the wrapper in C that returns the long double.
+#[no_mangle]
+pub unsafe extern "C" fn relibc_strtold(
+    out: *mut LongDouble,
+    s: *const c_char,
+    endptr: *mut *mut c_char,
+) {
+    let bytes = CStr::from_ptr(s).to_bytes();
+    let decimal_point = locale::decimal_point().as_bytes();
+    let (float, len) = match Float::parse(bytes, decimal_point, &LONG_DOUBLE) {
+        Some((float, len, range)) => {
+            if range {
+                platform::errno = ERANGE;
+            }
+            (float, len)
+        }
+        None => (Float::zero(false), 0),
+    };
+    if !endptr.is_null() {
+        *endptr = s.add(len) as *mut c_char;
+    }
+    *out = float.to_long_double();
+}
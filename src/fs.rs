@@ -1,19 +1,33 @@
+//! The descriptors relibc opens for itself, which are closed when dropped and on exec.
+//!
+//! Errors are the errno the call set, which the io traits give as io::Error's raw OS error.
+
 use crate::{
     c_str::CStr,
+    error::{Errno, Result},
     header::{
-        fcntl::O_CREAT,
-        unistd::{SEEK_CUR, SEEK_END, SEEK_SET},
+        fcntl::{F_DUPFD_CLOEXEC, O_CLOEXEC, O_CREAT},
+        sys_stat::stat,
+        unistd::{self, SEEK_CUR, SEEK_END, SEEK_SET},
     },
     io,
     platform::{types::*, Pal, Sys},
 };
-use core::ops::Deref;
+use core::{mem, ops::Deref};
+
+#[cfg(target_os = "redox")]
+use redox_exec::FdGuard;
 
 pub struct File {
     pub fd: c_int,
     /// To avoid self referential FILE struct that needs both a reader and a writer,
     /// make "reference" files that share fd but don't close on drop.
     pub reference: bool,
+    /// Whether it's closed with the bare system call, leaving alone what relibc keeps for the
+    /// descriptor number. The platform layer's own scheme descriptors are, as posix_spawn's
+    /// helper shares the parent's memory but not its file table.
+    #[cfg(target_os = "redox")]
+    bare: bool,
 }
 
 impl File {
@@ -21,40 +35,77 @@ impl File {
         Self {
             fd,
             reference: false,
+            #[cfg(target_os = "redox")]
+            bare: false,
         }
     }
 
-    pub fn open(path: &CStr, oflag: c_int) -> io::Result<Self> {
-        match Sys::open(path, oflag, 0) {
-            -1 => Err(io::last_os_error()),
+    /// Opens `path`, with O_CLOEXEC whether or not `oflag` has it. One that's to be inherited
+    /// is opened with Sys::open.
+    pub fn open(path: &CStr, oflag: c_int) -> Result<Self> {
+        match Sys::open(path, oflag | O_CLOEXEC, 0) {
+            -1 => Err(Errno::last()),
             ok => Ok(Self::new(ok)),
         }
     }
 
-    pub fn create(path: &CStr, oflag: c_int, mode: mode_t) -> io::Result<Self> {
-        match Sys::open(path, oflag | O_CREAT, mode) {
-            -1 => Err(io::last_os_error()),
+    pub fn create(path: &CStr, oflag: c_int, mode: mode_t) -> Result<Self> {
+        match Sys::open(path, oflag | O_CREAT | O_CLOEXEC, mode) {
+            -1 => Err(Errno::last()),
             ok => Ok(Self::new(ok)),
         }
     }
 
-    pub fn sync_all(&self) -> io::Result<()> {
+    pub fn sync_all(&self) -> Result<()> {
         match Sys::fsync(self.fd) {
-            -1 => Err(io::last_os_error()),
+            -1 => Err(Errno::last()),
             _ok => Ok(()),
         }
     }
 
-    pub fn set_len(&self, size: u64) -> io::Result<()> {
+    pub fn set_len(&self, size: u64) -> Result<()> {
         match Sys::ftruncate(self.fd, size as off_t) {
-            -1 => Err(io::last_os_error()),
+            -1 => Err(Errno::last()),
             _ok => Ok(()),
         }
     }
 
-    pub fn try_clone(&self) -> io::Result<Self> {
-        match Sys::dup(self.fd) {
-            -1 => Err(io::last_os_error()),
+    /// Reads what's at `offset`, leaving the file's offset where it was.
+    pub fn pread(&self, buf: &mut [u8], offset: u64) -> Result<usize> {
+        let ptr = buf.as_mut_ptr() as *mut c_void;
+        match unistd::pread(self.fd, ptr, buf.len(), offset as off_t) {
+            -1 => Err(Errno::last()),
+            ok => Ok(ok as usize),
+        }
+    }
+
+    /// Writes at `offset`, leaving the file's offset where it was.
+    pub fn pwrite(&self, buf: &[u8], offset: u64) -> Result<usize> {
+        let ptr = buf.as_ptr() as *const c_void;
+        match unistd::pwrite(self.fd, ptr, buf.len(), offset as off_t) {
+            -1 => Err(Errno::last()),
+            ok => Ok(ok as usize),
+        }
+    }
+
+    pub fn fstat(&self) -> Result<stat> {
+        let mut buf = stat::default();
+        match Sys::fstat(self.fd, &mut buf) {
+            -1 => Err(Errno::last()),
+            _ok => Ok(buf),
+        }
+    }
+
+    /// Duplicates the descriptor, with O_CLOEXEC like the ones opened here.
+    pub fn dup(&self) -> Result<Self> {
+        #[cfg(target_os = "redox")]
+        {
+            if self.bare {
+                return self.dup_with(&[]);
+            }
+        }
+        match Sys::fcntl(self.fd, F_DUPFD_CLOEXEC, 0) {
+            -1 => Err(Errno::last()),
             ok => Ok(Self::new(ok)),
         }
     }
@@ -66,8 +117,89 @@ impl File {
         Self {
             fd: self.fd,
             reference: true,
+            #[cfg(target_os = "redox")]
+            bare: self.bare,
+        }
+    }
+
+    /// Closes the descriptor, giving the error that dropping it ignores. A reference is left
+    /// open.
+    pub fn close(self) -> Result<()> {
+        let res = if self.reference {
+            Ok(())
+        } else {
+            self.close_fd()
+        };
+        mem::forget(self);
+        res
+    }
+
+    fn close_fd(&self) -> Result<()> {
+        #[cfg(target_os = "redox")]
+        {
+            if self.bare {
+                return syscall::close(self.fd as usize)
+                    .map(|_| ())
+                    .map_err(Errno::from);
+            }
+        }
+        match Sys::close(self.fd) {
+            -1 => Err(Errno::last()),
+            _ok => Ok(()),
+        }
+    }
+
+    /// Gives up the descriptor, which is then left open.
+    pub fn into_raw_fd(self) -> c_int {
+        let fd = self.fd;
+        mem::forget(self);
+        fd
+    }
+}
+
+#[cfg(target_os = "redox")]
+impl File {
+    /// Takes one of the platform layer's own scheme descriptors, which is closed with the bare
+    /// system call.
+    pub fn new_bare(fd: usize) -> Self {
+        Self {
+            fd: fd as c_int,
+            reference: false,
+            bare: true,
         }
     }
+
+    /// Opens a scheme path with the bare system call, with the scheme's `flags` as they are.
+    pub fn open_bare(path: &str, flags: usize) -> Result<Self> {
+        Ok(Self::new_bare(syscall::open(path, flags)?))
+    }
+
+    /// Opens what `buf` names relative to this file, the way the scheme it's from gives for dup,
+    /// like the parts of a process `thisproc:current/open_via_dup` has.
+    pub fn dup_with(&self, buf: &[u8]) -> Result<Self> {
+        Ok(Self::new_bare(syscall::dup(self.fd as usize, buf)?))
+    }
+
+    /// The descriptor as the system calls take it.
+    pub fn raw(&self) -> usize {
+        self.fd as usize
+    }
+}
+
+/// Takes a descriptor from redox-exec, which can't use relibc's own types.
+#[cfg(target_os = "redox")]
+impl From<FdGuard> for File {
+    fn from(mut guard: FdGuard) -> Self {
+        Self::new_bare(guard.take())
+    }
+}
+
+/// Gives a descriptor to redox-exec, which closes it when done.
+#[cfg(target_os = "redox")]
+impl From<File> for FdGuard {
+    fn from(file: File) -> Self {
+        FdGuard::new(file.into_raw_fd() as usize)
+    }
 }
 
 impl io::Read for &File {
@@ -140,7 +272,7 @@ impl Deref for File {
 impl Drop for File {
     fn drop(&mut self) {
         if !self.reference {
-            let _ = Sys::close(self.fd);
+            let _ = self.close_fd();
         }
     }
 }
@@ -0,0 +1,83 @@
+//! The exceptions are the low bits of FPSR and their traps the same bits of FPCR shifted by 8.
+//! The rounding mode is bits 22 and 23 of FPCR.
+
+use core::arch::asm;
+
+use crate::platform::types::*;
+
+pub const FE_INVALID: c_int = 0x01;
+pub const FE_DIVBYZERO: c_int = 0x02;
+pub const FE_OVERFLOW: c_int = 0x04;
+pub const FE_UNDERFLOW: c_int = 0x08;
+pub const FE_INEXACT: c_int = 0x10;
+pub const FE_ALL_EXCEPT: c_int =
+    FE_INVALID | FE_DIVBYZERO | FE_OVERFLOW | FE_UNDERFLOW | FE_INEXACT;
+
+pub const FE_TONEAREST: c_int = 0x00_0000;
+pub const FE_UPWARD: c_int = 0x40_0000;
+pub const FE_DOWNWARD: c_int = 0x80_0000;
+pub const FE_TOWARDZERO: c_int = 0xc0_0000;
+
+pub type fexcept_t = u64;
+
+/// FPCR in the high half and FPSR in the low one
+pub type fenv_t = u64;
+
+const ROUNDING: u64 = 0xc0_0000;
+const TRAP_SHIFT: u32 = 8;
+
+unsafe fn fpcr() -> u64 {
+    let fpcr: u64;
+    asm!("mrs {}, fpcr", out(reg) fpcr, options(nomem, nostack));
+    fpcr
+}
+
+unsafe fn set_fpcr(fpcr: u64) {
+    asm!("msr fpcr, {}", in(reg) fpcr, options(nomem, nostack));
+}
+
+unsafe fn fpsr() -> u64 {
+    let fpsr: u64;
+    asm!("mrs {}, fpsr", out(reg) fpsr, options(nomem, nostack));
+    fpsr
+}
+
+unsafe fn set_fpsr(fpsr: u64) {
+    asm!("msr fpsr, {}", in(reg) fpsr, options(nomem, nostack));
+}
+
+pub unsafe fn get_env() -> fenv_t {
+    fpcr() << 32 | fpsr()
+}
+
+pub unsafe fn set_env(env: &fenv_t) {
+    set_fpcr(*env >> 32);
+    set_fpsr(*env & 0xffff_ffff);
+}
+
+pub unsafe fn get_flags() -> c_int {
+    fpsr() as c_int & FE_ALL_EXCEPT
+}
+
+/// Sets the flags of `excepts` to those in `flags`.
+pub unsafe fn set_flags(excepts: c_int, flags: c_int) {
+    set_fpsr(fpsr() & !(excepts as u64) | flags as u64);
+}
+
+/// Makes `excepts` the only exceptions that trap.
+pub unsafe fn set_traps(excepts: c_int) {
+    let all = (FE_ALL_EXCEPT as u64) << TRAP_SHIFT;
+    set_fpcr(fpcr() & !all | (excepts as u64) << TRAP_SHIFT);
+}
+
+pub unsafe fn raise(excepts: c_int) {
+    set_flags(excepts, excepts);
+}
+
+pub unsafe fn get_round() -> c_int {
+    (fpcr() & ROUNDING) as c_int
+}
+
+pub unsafe fn set_round(round: c_int) {
+    set_fpcr(fpcr() & !ROUNDING | round as u64);
+}
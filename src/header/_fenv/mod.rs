@@ -1,69 +1,95 @@
 //! fenv.h implementation for Redox, following
 //! http://pubs.opengroup.org/onlinepubs/9699919799/basedefs/fenv.h.html
+//!
+//! The exported functions come from openlibm, whose fenv.h programs include. These are the same
+//! functions with the same values, for relibc itself, like FLT_ROUNDS and printf.
 
 use crate::platform::types::*;
 
-pub const FE_ALL_EXCEPT: c_int = 0;
-pub const FE_TONEAREST: c_int = 0;
+#[cfg(target_arch = "aarch64")]
+#[path = "aarch64.rs"]
+mod arch;
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[path = "x86.rs"]
+mod arch;
 
-pub type fexcept_t = u64;
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+pub use self::arch::FE_DENORMAL;
+pub use self::arch::{
+    fenv_t, fexcept_t, FE_ALL_EXCEPT, FE_DIVBYZERO, FE_DOWNWARD, FE_INEXACT, FE_INVALID,
+    FE_OVERFLOW, FE_TONEAREST, FE_TOWARDZERO, FE_UNDERFLOW, FE_UPWARD,
+};
 
-#[repr(C)]
-pub struct fenv_t {
-    pub cw: u64,
-}
+const ROUNDING: c_int = FE_TONEAREST | FE_DOWNWARD | FE_UPWARD | FE_TOWARDZERO;
 
 // #[no_mangle]
 pub unsafe extern "C" fn feclearexcept(excepts: c_int) -> c_int {
-    unimplemented!();
+    arch::set_flags(excepts & FE_ALL_EXCEPT, 0);
+    0
 }
 
 // #[no_mangle]
-pub unsafe extern "C" fn fegenenv(envp: *mut fenv_t) -> c_int {
-    unimplemented!();
+pub unsafe extern "C" fn fegetenv(envp: *mut fenv_t) -> c_int {
+    *envp = arch::get_env();
+    0
 }
 
 // #[no_mangle]
 pub unsafe extern "C" fn fegetexceptflag(flagp: *mut fexcept_t, excepts: c_int) -> c_int {
-    unimplemented!();
+    *flagp = (arch::get_flags() & excepts & FE_ALL_EXCEPT) as fexcept_t;
+    0
 }
 
 // #[no_mangle]
 pub unsafe extern "C" fn fegetround() -> c_int {
-    FE_TONEAREST
+    arch::get_round()
 }
 
 // #[no_mangle]
 pub unsafe extern "C" fn feholdexcept(envp: *mut fenv_t) -> c_int {
-    unimplemented!();
+    *envp = arch::get_env();
+    arch::set_flags(FE_ALL_EXCEPT, 0);
+    arch::set_traps(0);
+    0
 }
 
 // #[no_mangle]
-pub unsafe extern "C" fn feraiseexcept(except: c_int) -> c_int {
-    unimplemented!();
+pub unsafe extern "C" fn feraiseexcept(excepts: c_int) -> c_int {
+    arch::raise(excepts & FE_ALL_EXCEPT);
+    0
 }
 
 // #[no_mangle]
 pub unsafe extern "C" fn fesetenv(envp: *const fenv_t) -> c_int {
-    unimplemented!();
+    arch::set_env(&*envp);
+    0
 }
 
 // #[no_mangle]
 pub unsafe extern "C" fn fesetexceptflag(flagp: *const fexcept_t, excepts: c_int) -> c_int {
-    unimplemented!();
+    let excepts = excepts & FE_ALL_EXCEPT;
+    arch::set_flags(excepts, *flagp as c_int & excepts);
+    0
 }
 
 // #[no_mangle]
 pub unsafe extern "C" fn fesetround(round: c_int) -> c_int {
-    unimplemented!();
+    if round & !ROUNDING != 0 {
+        return -1;
+    }
+    arch::set_round(round);
+    0
 }
 
 // #[no_mangle]
 pub unsafe extern "C" fn fetestexcept(excepts: c_int) -> c_int {
-    unimplemented!();
+    arch::get_flags() & excepts & FE_ALL_EXCEPT
 }
 
 // #[no_mangle]
 pub unsafe extern "C" fn feupdateenv(envp: *const fenv_t) -> c_int {
-    unimplemented!();
+    let raised = arch::get_flags();
+    arch::set_env(&*envp);
+    arch::raise(raised);
+    0
 }
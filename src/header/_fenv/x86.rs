@@ -0,0 +1,97 @@
+//! The x87 and SSE units both have their own flags, traps and rounding mode, which are kept
+//! the same. The exceptions are bits of the x87 status word and of MXCSR, and their traps the
+//! same bits of the x87 control word and, shifted by 7, of MXCSR. The rounding mode is bits
+//! 10 and 11 of the control word and 13 and 14 of MXCSR.
+
+use core::arch::asm;
+
+use crate::platform::types::*;
+
+pub const FE_INVALID: c_int = 0x01;
+pub const FE_DENORMAL: c_int = 0x02;
+pub const FE_DIVBYZERO: c_int = 0x04;
+pub const FE_OVERFLOW: c_int = 0x08;
+pub const FE_UNDERFLOW: c_int = 0x10;
+pub const FE_INEXACT: c_int = 0x20;
+pub const FE_ALL_EXCEPT: c_int =
+    FE_INVALID | FE_DENORMAL | FE_DIVBYZERO | FE_OVERFLOW | FE_UNDERFLOW | FE_INEXACT;
+
+pub const FE_TONEAREST: c_int = 0x000;
+pub const FE_DOWNWARD: c_int = 0x400;
+pub const FE_UPWARD: c_int = 0x800;
+pub const FE_TOWARDZERO: c_int = 0xc00;
+
+pub type fexcept_t = u16;
+
+/// The environment that fnstenv stores, then MXCSR
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct fenv_t {
+    pub control: u32,
+    pub status: u32,
+    pub tag: u32,
+    pub other: [u8; 16],
+    pub mxcsr: u32,
+}
+
+const ROUNDING: u32 = 0xc00;
+
+pub unsafe fn get_env() -> fenv_t {
+    let mut env = fenv_t {
+        control: 0,
+        status: 0,
+        tag: 0,
+        other: [0; 16],
+        mxcsr: 0,
+    };
+    // fnstenv masks every exception, so the control word has to be put back
+    asm!("fnstenv [{0}]", "fldcw [{0}]", in(reg) &mut env, options(nostack));
+    asm!("stmxcsr [{}]", in(reg) &mut env.mxcsr, options(nostack));
+    env
+}
+
+pub unsafe fn set_env(env: &fenv_t) {
+    asm!("fldenv [{}]", in(reg) env, options(nostack));
+    asm!("ldmxcsr [{}]", in(reg) &env.mxcsr, options(nostack));
+}
+
+pub unsafe fn get_flags() -> c_int {
+    let env = get_env();
+    (env.status | env.mxcsr) as c_int & FE_ALL_EXCEPT
+}
+
+/// Sets the flags of `excepts` to those in `flags`.
+pub unsafe fn set_flags(excepts: c_int, flags: c_int) {
+    let mut env = get_env();
+    env.status = env.status & !(excepts as u32) | flags as u32;
+    env.mxcsr = env.mxcsr & !(excepts as u32) | flags as u32;
+    set_env(&env);
+}
+
+/// Makes `excepts` the only exceptions that trap.
+pub unsafe fn set_traps(excepts: c_int) {
+    let mut env = get_env();
+    let masked = (FE_ALL_EXCEPT & !excepts) as u32;
+    env.control = env.control & !(FE_ALL_EXCEPT as u32) | masked;
+    env.mxcsr = env.mxcsr & !((FE_ALL_EXCEPT as u32) << 7) | masked << 7;
+    set_env(&env);
+}
+
+pub unsafe fn raise(excepts: c_int) {
+    set_flags(excepts, excepts);
+    // The x87 unit traps at its next instruction that waits
+    asm!("fwait", options(nomem, nostack));
+}
+
+pub unsafe fn get_round() -> c_int {
+    let mut control: u16 = 0;
+    asm!("fnstcw [{}]", in(reg) &mut control, options(nostack));
+    (u32::from(control) & ROUNDING) as c_int
+}
+
+pub unsafe fn set_round(round: c_int) {
+    let mut env = get_env();
+    env.control = env.control & !ROUNDING | round as u32;
+    env.mxcsr = env.mxcsr & !(ROUNDING << 3) | (round as u32) << 3;
+    set_env(&env);
+}
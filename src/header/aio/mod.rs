@@ -0,0 +1,588 @@
+//! aio.h implementation, following https://pubs.opengroup.org/onlinepubs/9699919799/basedefs/aio.h.html
+//!
+//! Neither Linux nor Redox have asynchronous I/O that fits these functions, so requests are
+//! queued and carried out with blocking calls by a pool of worker threads. Requests on the same
+//! descriptor are carried out one after another, in the order they were queued, which also makes
+//! aio_fsync cover the requests queued before it.
+
+use alloc::{boxed::Box, sync::Arc, vec::Vec};
+use core::{
+    ptr, slice,
+    sync::atomic::{AtomicI32, AtomicUsize, Ordering},
+};
+
+use crate::{
+    header::{
+        errno::{EAGAIN, EBADF, ECANCELED, EINPROGRESS, EINTR, EINVAL, EIO, ESPIPE},
+        fcntl::{F_GETFL, O_ACCMODE, O_DSYNC, O_RDONLY, O_SYNC, O_WRONLY},
+        signal::{sigevent, sigval, SIGEV_NONE, SIGEV_SIGNAL, SIGEV_THREAD, SI_ASYNCIO},
+        time::{timespec, CLOCK_MONOTONIC},
+        unistd::{pread, pwrite},
+    },
+    platform::{self, types::*, Pal, PalSignal, Sys},
+    sync::{Mutex, Semaphore},
+};
+
+pub const AIO_CANCELED: c_int = 0;
+pub const AIO_NOTCANCELED: c_int = 1;
+pub const AIO_ALLDONE: c_int = 2;
+
+pub const LIO_READ: c_int = 0;
+pub const LIO_WRITE: c_int = 1;
+pub const LIO_NOP: c_int = 2;
+
+pub const LIO_WAIT: c_int = 0;
+pub const LIO_NOWAIT: c_int = 1;
+
+#[repr(C)]
+pub struct aiocb {
+    pub aio_fildes: c_int,
+    pub aio_offset: off_t,
+    pub aio_buf: *mut c_void,
+    pub aio_nbytes: size_t,
+    pub aio_reqprio: c_int,
+    pub aio_sigevent: sigevent,
+    pub aio_lio_opcode: c_int,
+    /// EINPROGRESS until the request is completed, then its error status
+    __error: c_int,
+    __return: ssize_t,
+}
+
+impl aiocb {
+    fn error(this: *const aiocb) -> &'static AtomicI32 {
+        unsafe { &*(ptr::addr_of!((*this).__error) as *const AtomicI32) }
+    }
+}
+
+const FUTEX_WAIT: c_int = 0;
+const FUTEX_WAKE: c_int = 1;
+
+/// The most worker threads carrying out requests at the same time
+const MAX_WORKERS: usize = 8;
+/// How long an idle worker waits for a request before exiting, in seconds
+const IDLE_TIMEOUT: time_t = 10;
+
+#[repr(C)]
+#[allow(non_camel_case_types)]
+struct pthread_t {
+    p: *mut c_void,
+    x: c_uint,
+}
+
+extern "C" {
+    // From pthreads-emb, pthread_attr_t is a pointer there
+    fn pthread_create(
+        thread: *mut pthread_t,
+        attr: *const *mut c_void,
+        start_routine: extern "C" fn(*mut c_void) -> *mut c_void,
+        arg: *mut c_void,
+    ) -> c_int;
+    fn pthread_detach(thread: pthread_t) -> c_int;
+}
+
+/// Starts a detached thread running `start_routine(arg)`.
+pub(crate) unsafe fn spawn(
+    attr: *const *mut c_void,
+    start_routine: extern "C" fn(*mut c_void) -> *mut c_void,
+    arg: *mut c_void,
+) -> Result<(), c_int> {
+    let mut thread = pthread_t {
+        p: ptr::null_mut(),
+        x: 0,
+    };
+    match pthread_create(&mut thread, attr, start_routine, arg) {
+        0 => {
+            pthread_detach(thread);
+            Ok(())
+        }
+        err => Err(err),
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Operation {
+    Read,
+    Write,
+    Sync,
+}
+
+/// The lio_listio call a request was queued by, when it asked to be notified.
+struct Group {
+    remaining: AtomicUsize,
+    sigevent: sigevent,
+}
+
+struct Request {
+    aiocbp: *mut aiocb,
+    operation: Operation,
+    group: Option<Arc<Group>>,
+}
+
+pub(crate) struct Pool {
+    queue: Vec<Request>,
+    /// The descriptors with a request being carried out
+    busy: Vec<c_int>,
+    workers: usize,
+    idle: usize,
+    /// The process the workers were started in, as they don't survive fork
+    pid: pid_t,
+}
+
+// The control blocks are only accessed through the pool while the request is queued
+unsafe impl Send for Request {}
+unsafe impl Send for Group {}
+unsafe impl Sync for Group {}
+
+pub(crate) static POOL: Mutex<Pool> = Mutex::new(Pool {
+    queue: Vec::new(),
+    busy: Vec::new(),
+    workers: 0,
+    idle: 0,
+    pid: 0,
+});
+/// Posted whenever there may be a request for an idle worker to take
+static PENDING: Semaphore = Semaphore::new(0);
+/// Counts completed requests, for aio_suspend to wait on
+static COMPLETIONS: AtomicI32 = AtomicI32::new(0);
+
+fn completions_futex() -> *mut c_int {
+    &COMPLETIONS as *const AtomicI32 as *mut c_int
+}
+
+fn monotonic_nanos() -> i128 {
+    let mut now = timespec::default();
+    Sys::clock_gettime(CLOCK_MONOTONIC, &mut now);
+    now.tv_sec as i128 * 1_000_000_000 + now.tv_nsec as i128
+}
+
+extern "C" fn notify_thread(arg: *mut c_void) -> *mut c_void {
+    let (function, value) = *unsafe { Box::from_raw(arg as *mut (extern "C" fn(sigval), sigval)) };
+    function(value);
+    ptr::null_mut()
+}
+
+unsafe fn notify(sigevent: &sigevent) {
+    match sigevent.sigev_notify {
+        SIGEV_SIGNAL => {
+            Sys::sigqueue(
+                Sys::getpid(),
+                sigevent.sigev_signo,
+                SI_ASYNCIO,
+                sigevent.sigev_value,
+            );
+        }
+        SIGEV_THREAD => {
+            if let Some(function) = sigevent.sigev_notify_function {
+                let arg = Box::into_raw(Box::new((function, sigevent.sigev_value)));
+                let attr = sigevent.sigev_notify_attributes as *const *mut c_void;
+                if spawn(attr, notify_thread, arg as *mut c_void).is_err() {
+                    drop(Box::from_raw(arg));
+                }
+            }
+        }
+        _ => (),
+    }
+}
+
+/// Stores the outcome of a request and sends the notifications it asked for.
+unsafe fn complete(request: Request, error: c_int, value: ssize_t) {
+    let aiocbp = request.aiocbp;
+    let sigevent = (*aiocbp).aio_sigevent;
+    ptr::addr_of_mut!((*aiocbp).__return).write_volatile(value);
+    aiocb::error(aiocbp).store(error, Ordering::Release);
+
+    COMPLETIONS.fetch_add(1, Ordering::SeqCst);
+    Sys::futex(completions_futex(), FUTEX_WAKE, c_int::max_value(), 0);
+
+    // The control block may be reused as soon as it's complete, so it's not read from anymore
+    notify(&sigevent);
+    if let Some(group) = request.group {
+        if group.remaining.fetch_sub(1, Ordering::AcqRel) == 1 {
+            notify(&group.sigevent);
+        }
+    }
+}
+
+unsafe fn perform(request: &Request) -> (c_int, ssize_t) {
+    let aiocb = &*request.aiocbp;
+    let (fd, buf, len) = (aiocb.aio_fildes, aiocb.aio_buf, aiocb.aio_nbytes);
+    let res = match request.operation {
+        Operation::Read => match pread(fd, buf, len, aiocb.aio_offset) {
+            // The offset is ignored on descriptors that can't seek, like pipes
+            -1 if platform::errno == ESPIPE => {
+                Sys::read(fd, slice::from_raw_parts_mut(buf as *mut u8, len))
+            }
+            res => res,
+        },
+        Operation::Write => match pwrite(fd, buf, len, aiocb.aio_offset) {
+            -1 if platform::errno == ESPIPE => {
+                Sys::write(fd, slice::from_raw_parts(buf as *const u8, len))
+            }
+            res => res,
+        },
+        // The metadata is always synchronized too, as there is no fdatasync
+        Operation::Sync => Sys::fsync(aiocb.aio_fildes) as ssize_t,
+    };
+    if res < 0 {
+        (platform::errno, -1)
+    } else {
+        (0, res)
+    }
+}
+
+/// Takes the first queued request on a descriptor that no other worker is busy with.
+fn take(pool: &mut Pool) -> Option<Request> {
+    let busy = &pool.busy;
+    let i = pool
+        .queue
+        .iter()
+        .position(|request| !busy.contains(&unsafe { (*request.aiocbp).aio_fildes }))?;
+    let request = pool.queue.remove(i);
+    pool.busy.push(unsafe { (*request.aiocbp).aio_fildes });
+    Some(request)
+}
+
+extern "C" fn worker(_arg: *mut c_void) -> *mut c_void {
+    let mut pool = POOL.lock();
+    loop {
+        if let Some(request) = take(&mut pool) {
+            drop(pool);
+            unsafe {
+                let fd = (*request.aiocbp).aio_fildes;
+                let (error, value) = perform(&request);
+                complete(request, error, value);
+
+                pool = POOL.lock();
+                pool.busy.retain(|&busy| busy != fd);
+                // Requests on this descriptor may have been passed over meanwhile
+                if !pool.queue.is_empty() {
+                    PENDING.post(1);
+                }
+            }
+            continue;
+        }
+
+        pool.idle += 1;
+        drop(pool);
+        let mut deadline = timespec::default();
+        Sys::clock_gettime(CLOCK_MONOTONIC, &mut deadline);
+        deadline.tv_sec += IDLE_TIMEOUT;
+        let woken = PENDING.wait(Some(&deadline)).is_ok();
+        pool = POOL.lock();
+        pool.idle -= 1;
+
+        if !woken && pool.queue.is_empty() {
+            pool.workers -= 1;
+            return ptr::null_mut();
+        }
+    }
+}
+
+/// Queues requests, starting a worker for them if none is idle.
+unsafe fn submit(requests: Vec<Request>) -> Result<(), c_int> {
+    let mut pool = POOL.lock();
+    let pid = Sys::getpid();
+    if pool.pid != pid {
+        // Forked, the requests of the parent aren't carried out here
+        pool.queue.clear();
+        pool.busy.clear();
+        pool.workers = 0;
+        pool.idle = 0;
+        pool.pid = pid;
+    }
+
+    for request in &requests {
+        aiocb::error(request.aiocbp).store(EINPROGRESS, Ordering::Release);
+    }
+    let count = requests.len();
+    pool.queue.extend(requests);
+
+    if pool.idle < count && pool.workers < MAX_WORKERS {
+        match spawn(ptr::null(), worker, ptr::null_mut()) {
+            Ok(()) => pool.workers += 1,
+            Err(err) if pool.workers == 0 => {
+                pool.queue.clear();
+                return Err(err);
+            }
+            // The workers already running carry out the requests
+            Err(_) => (),
+        }
+    }
+    drop(pool);
+
+    PENDING.post(count as c_int);
+    Ok(())
+}
+
+/// Checks what can be checked before queueing a request.
+unsafe fn validate(aiocbp: *const aiocb, operation: Operation) -> Result<(), c_int> {
+    let aiocb = &*aiocbp;
+    let flags = Sys::fcntl(aiocb.aio_fildes, F_GETFL, 0);
+    if flags < 0 {
+        return Err(EBADF);
+    }
+    let readable = flags & O_ACCMODE != O_WRONLY;
+    let writable = flags & O_ACCMODE != O_RDONLY;
+    match operation {
+        Operation::Read if !readable => return Err(EBADF),
+        Operation::Write if !writable => return Err(EBADF),
+        Operation::Sync => (),
+        _ => {
+            if aiocb.aio_offset < 0 {
+                return Err(EINVAL);
+            }
+        }
+    }
+    match aiocb.aio_sigevent.sigev_notify {
+        SIGEV_NONE | SIGEV_SIGNAL | SIGEV_THREAD => Ok(()),
+        _ => Err(EINVAL),
+    }
+}
+
+unsafe fn enqueue(aiocbp: *mut aiocb, operation: Operation) -> c_int {
+    let result = validate(aiocbp, operation).and_then(|()| {
+        submit(vec![Request {
+            aiocbp,
+            operation,
+            group: None,
+        }])
+    });
+    match result {
+        Ok(()) => 0,
+        Err(err) => {
+            platform::errno = err;
+            -1
+        }
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn aio_read(aiocbp: *mut aiocb) -> c_int {
+    enqueue(aiocbp, Operation::Read)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn aio_write(aiocbp: *mut aiocb) -> c_int {
+    enqueue(aiocbp, Operation::Write)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn aio_fsync(operation: c_int, aiocbp: *mut aiocb) -> c_int {
+    if operation != O_SYNC && operation != O_DSYNC {
+        platform::errno = EINVAL;
+        return -1;
+    }
+    enqueue(aiocbp, Operation::Sync)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn lio_listio(
+    mode: c_int,
+    list: *const *mut aiocb,
+    nent: c_int,
+    sig: *mut sigevent,
+) -> c_int {
+    if (mode != LIO_WAIT && mode != LIO_NOWAIT) || nent < 0 {
+        platform::errno = EINVAL;
+        return -1;
+    }
+
+    let mut entries = Vec::new();
+    for i in 0..nent as usize {
+        let aiocbp = *list.add(i);
+        if aiocbp.is_null() {
+            continue;
+        }
+        let operation = match (*aiocbp).aio_lio_opcode {
+            LIO_READ => Operation::Read,
+            LIO_WRITE => Operation::Write,
+            LIO_NOP => continue,
+            _ => {
+                platform::errno = EINVAL;
+                return -1;
+            }
+        };
+        if let Err(err) = validate(aiocbp, operation) {
+            platform::errno = err;
+            return -1;
+        }
+        entries.push((aiocbp, operation));
+    }
+
+    let group = match sig.as_ref() {
+        Some(sig) if mode == LIO_NOWAIT && sig.sigev_notify != SIGEV_NONE => {
+            if entries.is_empty() {
+                notify(sig);
+                None
+            } else {
+                Some(Arc::new(Group {
+                    remaining: AtomicUsize::new(entries.len()),
+                    sigevent: *sig,
+                }))
+            }
+        }
+        _ => None,
+    };
+    let requests = entries
+        .iter()
+        .map(|&(aiocbp, operation)| Request {
+            aiocbp,
+            operation,
+            group: group.clone(),
+        })
+        .collect();
+    if let Err(err) = submit(requests) {
+        platform::errno = err;
+        return -1;
+    }
+    if mode == LIO_NOWAIT {
+        return 0;
+    }
+
+    loop {
+        let seen = COMPLETIONS.load(Ordering::SeqCst);
+        let pending = entries
+            .iter()
+            .any(|&(aiocbp, _)| aiocb::error(aiocbp).load(Ordering::Acquire) == EINPROGRESS);
+        if !pending {
+            break;
+        }
+        if Sys::futex(completions_futex(), FUTEX_WAIT, seen, 0) == -EINTR {
+            platform::errno = EINTR;
+            return -1;
+        }
+    }
+    if entries
+        .iter()
+        .any(|&(aiocbp, _)| aiocb::error(aiocbp).load(Ordering::Acquire) != 0)
+    {
+        platform::errno = EIO;
+        return -1;
+    }
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn aio_error(aiocbp: *const aiocb) -> c_int {
+    aiocb::error(aiocbp).load(Ordering::Acquire)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn aio_return(aiocbp: *mut aiocb) -> ssize_t {
+    if aiocb::error(aiocbp).load(Ordering::Acquire) == EINPROGRESS {
+        platform::errno = EINVAL;
+        return -1;
+    }
+    ptr::addr_of!((*aiocbp).__return).read_volatile()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn aio_cancel(fildes: c_int, aiocbp: *mut aiocb) -> c_int {
+    if Sys::fcntl(fildes, F_GETFL, 0) < 0 {
+        platform::errno = EBADF;
+        return -1;
+    }
+    if let Some(aiocb) = aiocbp.as_ref() {
+        if aiocb.aio_fildes != fildes {
+            platform::errno = EINVAL;
+            return -1;
+        }
+    }
+
+    let mut pool = POOL.lock();
+    let mut canceled = Vec::new();
+    let mut i = 0;
+    while i < pool.queue.len() {
+        let request = &pool.queue[i];
+        let matches = if aiocbp.is_null() {
+            (*request.aiocbp).aio_fildes == fildes
+        } else {
+            request.aiocbp == aiocbp
+        };
+        if matches {
+            canceled.push(pool.queue.remove(i));
+        } else {
+            i += 1;
+        }
+    }
+    // Requests that were already taken by a worker complete normally
+    let in_progress = if aiocbp.is_null() {
+        pool.busy.contains(&fildes)
+    } else {
+        aiocb::error(aiocbp).load(Ordering::Acquire) == EINPROGRESS && canceled.is_empty()
+    };
+    drop(pool);
+
+    let any_canceled = !canceled.is_empty();
+    for request in canceled {
+        complete(request, ECANCELED, -1);
+    }
+    if in_progress {
+        AIO_NOTCANCELED
+    } else if any_canceled {
+        AIO_CANCELED
+    } else {
+        AIO_ALLDONE
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn aio_suspend(
+    list: *const *const aiocb,
+    nent: c_int,
+    timeout: *const timespec,
+) -> c_int {
+    let deadline = match timeout.as_ref() {
+        Some(timeout) => {
+            if timeout.tv_sec < 0 || timeout.tv_nsec < 0 || timeout.tv_nsec >= 1_000_000_000 {
+                platform::errno = EINVAL;
+                return -1;
+            }
+            Some(
+                monotonic_nanos()
+                    + timeout.tv_sec as i128 * 1_000_000_000
+                    + timeout.tv_nsec as i128,
+            )
+        }
+        None => None,
+    };
+    let list = slice::from_raw_parts(list, nent.max(0) as usize);
+
+    loop {
+        // Read before checking, so that a completion in between makes the wait return at once
+        let seen = COMPLETIONS.load(Ordering::SeqCst);
+        let done = list.iter().any(|&aiocbp| {
+            !aiocbp.is_null() && aiocb::error(aiocbp).load(Ordering::Acquire) != EINPROGRESS
+        });
+        if done {
+            return 0;
+        }
+
+        let relative = match deadline {
+            Some(deadline) => {
+                let remaining = deadline - monotonic_nanos();
+                if remaining <= 0 {
+                    platform::errno = EAGAIN;
+                    return -1;
+                }
+                Some(timespec {
+                    tv_sec: (remaining / 1_000_000_000) as time_t,
+                    tv_nsec: (remaining % 1_000_000_000) as c_long,
+                })
+            }
+            None => None,
+        };
+        let res = Sys::futex(
+            completions_futex(),
+            FUTEX_WAIT,
+            seen,
+            relative
+                .as_ref()
+                .map_or(0, |relative| relative as *const timespec as usize),
+        );
+        if res == -EINTR {
+            platform::errno = EINTR;
+            return -1;
+        }
+    }
+}
@@ -1,6 +1,9 @@
 //! arpa/inet implementation for Redox, following http://pubs.opengroup.org/onlinepubs/7908799/xns/arpainet.h.html
 
+use alloc::{string::String, vec::Vec};
 use core::{
+    cmp,
+    fmt::Write,
     ptr, slice,
     str::{self, FromStr},
 };
@@ -53,29 +56,150 @@ pub unsafe extern "C" fn inet_ntoa(addr: in_addr) -> *const c_char {
     )
 }
 
+/// Parses strict dotted-decimal notation: exactly four decimal octets, without leading zeros.
+fn parse_ipv4(s: &[u8]) -> Option<[u8; 4]> {
+    let mut octets = [0; 4];
+    let mut parts = s.split(|&b| b == b'.');
+    for octet in octets.iter_mut() {
+        let part = parts.next()?;
+        if part.is_empty()
+            || part.len() > 3
+            || (part.len() > 1 && part[0] == b'0')
+            || !part.iter().all(u8::is_ascii_digit)
+        {
+            return None;
+        }
+        *octet = u8::from_str(str::from_utf8(part).ok()?).ok()?;
+    }
+    if parts.next().is_some() {
+        return None;
+    }
+    Some(octets)
+}
+
+/// Parses a run of colon-separated hex groups, the last of which may be an IPv4 address in
+/// dotted-decimal form when `allow_ipv4` is set.
+fn parse_ipv6_groups(s: &[u8], allow_ipv4: bool) -> Option<Vec<u16>> {
+    let mut groups = Vec::new();
+    if s.is_empty() {
+        return Some(groups);
+    }
+
+    let mut fields = s.split(|&b| b == b':').peekable();
+    while let Some(field) = fields.next() {
+        if allow_ipv4 && fields.peek().is_none() && field.contains(&b'.') {
+            let octets = parse_ipv4(field)?;
+            groups.push(u16::from_be_bytes([octets[0], octets[1]]));
+            groups.push(u16::from_be_bytes([octets[2], octets[3]]));
+        } else {
+            if field.is_empty() || field.len() > 4 || !field.iter().all(u8::is_ascii_hexdigit) {
+                return None;
+            }
+            groups.push(u16::from_str_radix(str::from_utf8(field).ok()?, 16).ok()?);
+        }
+    }
+    Some(groups)
+}
+
+/// Parses any of the textual forms of RFC 4291 section 2.2, including "::" compression and
+/// a trailing dotted-decimal IPv4 part.
+fn parse_ipv6(s: &[u8]) -> Option<[u8; 16]> {
+    let compressed = s.windows(2).position(|w| w == b"::");
+    let groups = match compressed {
+        Some(i) => {
+            let head = parse_ipv6_groups(&s[..i], false)?;
+            let tail = &s[i + 2..];
+            if tail.windows(2).any(|w| w == b"::") {
+                return None;
+            }
+            let tail = parse_ipv6_groups(tail, true)?;
+            // "::" stands for at least one group of zeros
+            if head.len() + tail.len() > 7 {
+                return None;
+            }
+            let mut groups = head;
+            groups.resize(8 - tail.len(), 0);
+            groups.extend_from_slice(&tail);
+            groups
+        }
+        None => parse_ipv6_groups(s, true).filter(|groups| groups.len() == 8)?,
+    };
+
+    let mut addr = [0; 16];
+    for (bytes, group) in addr.chunks_mut(2).zip(&groups) {
+        bytes.copy_from_slice(&group.to_be_bytes());
+    }
+    Some(addr)
+}
+
+/// Formats an address as recommended by RFC 5952: lowercase hex without leading zeros, with the
+/// first of the longest runs of two or more zero groups compressed to "::". IPv4-mapped
+/// addresses end in dotted-decimal notation.
+fn format_ipv6(addr: &[u8; 16]) -> String {
+    let mut out = String::new();
+    if addr[..12] == [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0xff, 0xff] {
+        let _ = write!(
+            out,
+            "::ffff:{}.{}.{}.{}",
+            addr[12], addr[13], addr[14], addr[15]
+        );
+        return out;
+    }
+
+    let mut groups = [0_u16; 8];
+    for (group, bytes) in groups.iter_mut().zip(addr.chunks(2)) {
+        *group = u16::from_be_bytes([bytes[0], bytes[1]]);
+    }
+
+    // Longest run of zero groups, the first one winning ties
+    let (mut run_start, mut run_len) = (0, 0);
+    let mut i = 0;
+    while i < groups.len() {
+        let len = groups[i..].iter().take_while(|&&group| group == 0).count();
+        if len > run_len {
+            run_start = i;
+            run_len = len;
+        }
+        i += cmp::max(len, 1);
+    }
+
+    let mut i = 0;
+    while i < groups.len() {
+        if run_len >= 2 && i == run_start {
+            out.push_str("::");
+            i += run_len;
+            continue;
+        }
+        if i > 0 && !out.ends_with(':') {
+            out.push(':');
+        }
+        let _ = write!(out, "{:x}", groups[i]);
+        i += 1;
+    }
+    out
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn inet_pton(domain: c_int, src: *const c_char, dest: *mut c_void) -> c_int {
-    if domain != AF_INET {
-        platform::errno = EAFNOSUPPORT;
-        -1
-    } else {
-        let s_addr = slice::from_raw_parts_mut(
-            &mut (*(dest as *mut in_addr)).s_addr as *mut _ as *mut u8,
-            4,
-        );
-        let src_cstr = CStr::from_ptr(src);
-        let mut octets = str::from_utf8_unchecked(src_cstr.to_bytes()).split('.');
-        for i in 0..4 {
-            if let Some(n) = octets.next().and_then(|x| u8::from_str(x).ok()) {
-                s_addr[i] = n;
-            } else {
-                return 0;
+    let src = CStr::from_ptr(src).to_bytes();
+    match domain {
+        AF_INET => match parse_ipv4(src) {
+            Some(octets) => {
+                ptr::copy_nonoverlapping(octets.as_ptr(), dest as *mut u8, octets.len());
+                1
             }
-        }
-        if octets.next() == None {
-            1 // Success
-        } else {
-            0
+            None => 0,
+        },
+        AF_INET6 => match parse_ipv6(src) {
+            Some(addr) => {
+                ptr::copy_nonoverlapping(addr.as_ptr(), dest as *mut u8, addr.len());
+                1
+            }
+            None => 0,
+        },
+        _ => {
+            platform::errno = EAFNOSUPPORT;
+            -1
         }
     }
 }
@@ -87,21 +211,25 @@ pub unsafe extern "C" fn inet_ntop(
     dest: *mut c_char,
     size: socklen_t,
 ) -> *const c_char {
-    if domain != AF_INET {
-        platform::errno = EAFNOSUPPORT;
-        ptr::null()
-    } else if size < 16 {
+    let addr = match domain {
+        AF_INET => {
+            let s_addr = slice::from_raw_parts(src as *const u8, 4);
+            format!("{}.{}.{}.{}", s_addr[0], s_addr[1], s_addr[2], s_addr[3])
+        }
+        AF_INET6 => format_ipv6(&*(src as *const [u8; 16])),
+        _ => {
+            platform::errno = EAFNOSUPPORT;
+            return ptr::null();
+        }
+    };
+
+    if (size as usize) < addr.len() + 1 {
         platform::errno = ENOSPC;
-        ptr::null()
-    } else {
-        let s_addr = slice::from_raw_parts(
-            &(*(src as *const in_addr)).s_addr as *const _ as *const u8,
-            4,
-        );
-        let addr = format!("{}.{}.{}.{}\0", s_addr[0], s_addr[1], s_addr[2], s_addr[3]);
-        ptr::copy(addr.as_ptr() as *const c_char, dest, addr.len());
-        dest
+        return ptr::null();
     }
+    ptr::copy_nonoverlapping(addr.as_ptr() as *const c_char, dest, addr.len());
+    *dest.add(addr.len()) = 0;
+    dest
 }
 
 #[no_mangle]
@@ -0,0 +1,170 @@
+//! The "$1$" method from FreeBSD, which hashes the key with MD5 a thousand times
+
+use alloc::vec::Vec;
+
+use crate::platform::types::*;
+
+use super::{push_base64, push_salt};
+
+const SHIFTS: [u32; 16] = [7, 12, 17, 22, 5, 9, 14, 20, 4, 11, 16, 23, 6, 10, 15, 21];
+
+const K: [u32; 64] = [
+    0xd76aa478, 0xe8c7b756, 0x242070db, 0xc1bdceee, 0xf57c0faf, 0x4787c62a, 0xa8304613, 0xfd469501,
+    0x698098d8, 0x8b44f7af, 0xffff5bb1, 0x895cd7be, 0x6b901122, 0xfd987193, 0xa679438e, 0x49b40821,
+    0xf61e2562, 0xc040b340, 0x265e5a51, 0xe9b6c7aa, 0xd62f105d, 0x02441453, 0xd8a1e681, 0xe7d3fbc8,
+    0x21e1cde6, 0xc33707d6, 0xf4d50d87, 0x455a14ed, 0xa9e3e905, 0xfcefa3f8, 0x676f02d9, 0x8d2a4c8a,
+    0xfffa3942, 0x8771f681, 0x6d9d6122, 0xfde5380c, 0xa4beea44, 0x4bdecfa9, 0xf6bb4b60, 0xbebfbc70,
+    0x289b7ec6, 0xeaa127fa, 0xd4ef3085, 0x04881d05, 0xd9d4d039, 0xe6db99e5, 0x1fa27cf8, 0xc4ac5665,
+    0xf4292244, 0x432aff97, 0xab9423a7, 0xfc93a039, 0x655b59c3, 0x8f0ccc92, 0xffeff47d, 0x85845dd1,
+    0x6fa87e4f, 0xfe2ce6e0, 0xa3014314, 0x4e0811a1, 0xf7537e82, 0xbd3af235, 0x2ad7d2bb, 0xeb86d391,
+];
+
+const MAX_SALT: usize = 8;
+
+struct Md5 {
+    state: [u32; 4],
+    block: [u8; 64],
+    len: u64,
+}
+
+impl Md5 {
+    fn new() -> Self {
+        Md5 {
+            state: [0x67452301, 0xefcdab89, 0x98badcfe, 0x10325476],
+            block: [0; 64],
+            len: 0,
+        }
+    }
+
+    fn compress(&mut self) {
+        let mut m = [0; 16];
+        for (i, word) in m.iter_mut().enumerate() {
+            let mut bytes = [0; 4];
+            bytes.copy_from_slice(&self.block[4 * i..4 * i + 4]);
+            *word = u32::from_le_bytes(bytes);
+        }
+
+        let [mut a, mut b, mut c, mut d] = self.state;
+        for i in 0..64 {
+            let (f, g) = match i / 16 {
+                0 => ((b & c) | (!b & d), i),
+                1 => ((d & b) | (!d & c), (5 * i + 1) % 16),
+                2 => (b ^ c ^ d, (3 * i + 5) % 16),
+                _ => (c ^ (b | !d), (7 * i) % 16),
+            };
+            let rotated = a
+                .wrapping_add(f)
+                .wrapping_add(K[i])
+                .wrapping_add(m[g])
+                .rotate_left(SHIFTS[i / 16 * 4 + i % 4]);
+            a = d;
+            d = c;
+            c = b;
+            b = b.wrapping_add(rotated);
+        }
+
+        for (state, value) in self.state.iter_mut().zip([a, b, c, d].iter()) {
+            *state = state.wrapping_add(*value);
+        }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        for b in data {
+            self.block[(self.len % 64) as usize] = *b;
+            self.len += 1;
+            if self.len % 64 == 0 {
+                self.compress();
+            }
+        }
+    }
+
+    fn finish(mut self) -> [u8; 16] {
+        let bits = self.len.wrapping_mul(8);
+        self.update(&[0x80]);
+        while self.len % 64 != 56 {
+            self.update(&[0]);
+        }
+        self.update(&bits.to_le_bytes());
+
+        let mut digest = [0; 16];
+        for (chunk, word) in digest.chunks_mut(4).zip(self.state.iter()) {
+            chunk.copy_from_slice(&word.to_le_bytes());
+        }
+        digest
+    }
+}
+
+pub fn crypt(key: &[u8], setting: &[u8]) -> Option<Vec<u8>> {
+    let rest = &setting[3..];
+    let salt_len = rest
+        .iter()
+        .take(MAX_SALT)
+        .position(|&b| b == b'$')
+        .unwrap_or_else(|| rest.len().min(MAX_SALT));
+    let salt = &rest[..salt_len];
+
+    let mut alternate = Md5::new();
+    alternate.update(key);
+    alternate.update(salt);
+    alternate.update(key);
+    let alternate = alternate.finish();
+
+    let mut ctx = Md5::new();
+    ctx.update(key);
+    ctx.update(b"$1$");
+    ctx.update(salt);
+    for chunk in key.chunks(16) {
+        ctx.update(&alternate[..chunk.len()]);
+    }
+    let mut i = key.len();
+    while i > 0 {
+        if i & 1 == 1 {
+            ctx.update(&[0]);
+        } else {
+            ctx.update(&key[..1]);
+        }
+        i >>= 1;
+    }
+    let mut digest = ctx.finish();
+
+    for i in 0..1000 {
+        let mut ctx = Md5::new();
+        if i & 1 == 1 {
+            ctx.update(key);
+        } else {
+            ctx.update(&digest);
+        }
+        if i % 3 != 0 {
+            ctx.update(salt);
+        }
+        if i % 7 != 0 {
+            ctx.update(key);
+        }
+        if i & 1 == 1 {
+            ctx.update(&digest);
+        } else {
+            ctx.update(key);
+        }
+        digest = ctx.finish();
+    }
+
+    let mut out = Vec::with_capacity(3 + salt.len() + 1 + 22);
+    out.extend_from_slice(b"$1$");
+    out.extend_from_slice(salt);
+    out.push(b'$');
+    for &(a, b, c) in &[(0, 6, 12), (1, 7, 13), (2, 8, 14), (3, 9, 15), (4, 10, 5)] {
+        let value = u32::from(digest[a]) << 16 | u32::from(digest[b]) << 8 | u32::from(digest[c]);
+        push_base64(&mut out, value, 4);
+    }
+    push_base64(&mut out, u32::from(digest[11]), 2);
+    Some(out)
+}
+
+pub fn gensalt(count: c_ulong, rbytes: &[u8]) -> Option<Vec<u8>> {
+    if count != 0 || rbytes.len() < 6 {
+        return None;
+    }
+    let mut out = b"$1$".to_vec();
+    push_salt(&mut out, &rbytes[..6]);
+    Some(out)
+}
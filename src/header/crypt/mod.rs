@@ -0,0 +1,187 @@
+//! crypt.h implementation, following the interface of libxcrypt's
+//!
+//! The method is chosen by the prefix of the setting: "$1$" for MD5, "$5$" and "$6$" for
+//! SHA-256 and SHA-512, "$2b$" (or "$2a$" and "$2y$") for bcrypt and two characters of salt
+//! for the traditional DES one. Invalid settings make crypt return NULL and set errno.
+
+use alloc::vec::Vec;
+use core::{ptr, slice};
+
+use crate::{
+    c_str::CStr,
+    header::errno::{EINVAL, ERANGE},
+    platform::{self, types::*, Pal, Sys},
+};
+
+mod blowfish;
+mod des;
+mod md5;
+mod sha;
+
+pub const CRYPT_OUTPUT_SIZE: usize = 384;
+pub const CRYPT_MAX_PASSPHRASE_SIZE: usize = 512;
+pub const CRYPT_GENSALT_OUTPUT_SIZE: usize = 192;
+
+#[repr(C)]
+pub struct crypt_data {
+    pub output: [c_char; CRYPT_OUTPUT_SIZE],
+    pub initialized: c_char,
+}
+
+static mut CRYPT_DATA: crypt_data = crypt_data {
+    output: [0; CRYPT_OUTPUT_SIZE],
+    initialized: 0,
+};
+static mut GENSALT_OUTPUT: [c_char; CRYPT_GENSALT_OUTPUT_SIZE] = [0; CRYPT_GENSALT_OUTPUT_SIZE];
+
+/// The alphabet hashes and salts are written in, except by bcrypt
+const ALPHABET: &[u8; 64] = b"./0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz";
+
+/// The most random bytes any method's salt is made from
+const MAX_RBYTES: usize = 16;
+
+/// Writes the low `count` groups of 6 bits of `value`, lowest first.
+fn push_base64(out: &mut Vec<u8>, mut value: u32, count: usize) {
+    for _ in 0..count {
+        out.push(ALPHABET[(value & 0x3f) as usize]);
+        value >>= 6;
+    }
+}
+
+/// Writes `bytes` three at a time, as the salts from crypt_gensalt are.
+fn push_salt(out: &mut Vec<u8>, bytes: &[u8]) {
+    for chunk in bytes.chunks(3) {
+        let mut value = 0;
+        for (i, b) in chunk.iter().enumerate() {
+            value |= u32::from(*b) << (8 * i);
+        }
+        push_base64(out, value, chunk.len() + 1);
+    }
+}
+
+fn hash(key: &[u8], setting: &[u8]) -> Option<Vec<u8>> {
+    if setting.starts_with(b"$1$") {
+        md5::crypt(key, setting)
+    } else if setting.starts_with(b"$5$") {
+        sha::crypt::<sha::Sha256>(key, setting)
+    } else if setting.starts_with(b"$6$") {
+        sha::crypt::<sha::Sha512>(key, setting)
+    } else if setting.starts_with(b"$2") {
+        blowfish::crypt(key, setting)
+    } else {
+        des::crypt(key, setting)
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn crypt(key: *const c_char, setting: *const c_char) -> *mut c_char {
+    crypt_r(key, setting, &mut CRYPT_DATA)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn crypt_r(
+    key: *const c_char,
+    setting: *const c_char,
+    data: *mut crypt_data,
+) -> *mut c_char {
+    let key = CStr::from_ptr(key).to_bytes();
+    let setting = CStr::from_ptr(setting).to_bytes();
+    if key.len() >= CRYPT_MAX_PASSPHRASE_SIZE {
+        platform::errno = ERANGE;
+        return ptr::null_mut();
+    }
+
+    match hash(key, setting) {
+        Some(hashed) => {
+            let output = &mut (*data).output;
+            for (dest, b) in output.iter_mut().zip(hashed.iter()) {
+                *dest = *b as c_char;
+            }
+            output[hashed.len()] = 0;
+            output.as_mut_ptr()
+        }
+        None => {
+            platform::errno = EINVAL;
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Makes a setting for crypt that uses the method `prefix` starts with, or SHA-512 if it's
+/// NULL, with `count` setting how costly it is or 0 for the default. The salt is made from
+/// `rbytes`, or random bytes if it's NULL.
+#[no_mangle]
+pub unsafe extern "C" fn crypt_gensalt(
+    prefix: *const c_char,
+    count: c_ulong,
+    rbytes: *const c_char,
+    nrbytes: c_int,
+) -> *mut c_char {
+    crypt_gensalt_rn(
+        prefix,
+        count,
+        rbytes,
+        nrbytes,
+        GENSALT_OUTPUT.as_mut_ptr(),
+        CRYPT_GENSALT_OUTPUT_SIZE as c_int,
+    )
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn crypt_gensalt_rn(
+    prefix: *const c_char,
+    count: c_ulong,
+    rbytes: *const c_char,
+    nrbytes: c_int,
+    output: *mut c_char,
+    output_size: c_int,
+) -> *mut c_char {
+    let prefix = if prefix.is_null() {
+        &b"$6$"[..]
+    } else {
+        CStr::from_ptr(prefix).to_bytes()
+    };
+
+    let mut random = [0; MAX_RBYTES];
+    let rbytes = if rbytes.is_null() {
+        if Sys::getrandom(&mut random, 0) != MAX_RBYTES as ssize_t {
+            return ptr::null_mut();
+        }
+        &random[..]
+    } else if nrbytes < 0 {
+        platform::errno = EINVAL;
+        return ptr::null_mut();
+    } else {
+        slice::from_raw_parts(rbytes as *const u8, nrbytes as usize)
+    };
+
+    let setting = if prefix == b"$1$" {
+        md5::gensalt(count, rbytes)
+    } else if prefix == b"$5$" || prefix == b"$6$" {
+        sha::gensalt(prefix, count, rbytes)
+    } else if prefix.starts_with(b"$2") {
+        blowfish::gensalt(prefix, count, rbytes)
+    } else if prefix.is_empty() {
+        des::gensalt(count, rbytes)
+    } else {
+        None
+    };
+
+    match setting {
+        Some(setting) if (setting.len() as c_int) < output_size => {
+            for (i, b) in setting.iter().enumerate() {
+                *output.add(i) = *b as c_char;
+            }
+            *output.add(setting.len()) = 0;
+            output
+        }
+        Some(_) => {
+            platform::errno = ERANGE;
+            ptr::null_mut()
+        }
+        None => {
+            platform::errno = EINVAL;
+            ptr::null_mut()
+        }
+    }
+}
@@ -0,0 +1,396 @@
+//! The "$5$" and "$6$" methods from glibc, which hash the key with SHA-256 or SHA-512 a number
+//! of times the setting can give
+//!
+//! Both hashes work the same way on words of different sizes, so they're made by one macro.
+
+use alloc::vec::Vec;
+
+use crate::platform::types::*;
+
+use super::{push_base64, push_salt};
+
+const MAX_SALT: usize = 16;
+const DEFAULT_ROUNDS: c_ulong = 5000;
+const MIN_ROUNDS: c_ulong = 1000;
+const MAX_ROUNDS: c_ulong = 999_999_999;
+
+const K256: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+const H256: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+const K512: [u64; 80] = [
+    0x428a2f98d728ae22,
+    0x7137449123ef65cd,
+    0xb5c0fbcfec4d3b2f,
+    0xe9b5dba58189dbbc,
+    0x3956c25bf348b538,
+    0x59f111f1b605d019,
+    0x923f82a4af194f9b,
+    0xab1c5ed5da6d8118,
+    0xd807aa98a3030242,
+    0x12835b0145706fbe,
+    0x243185be4ee4b28c,
+    0x550c7dc3d5ffb4e2,
+    0x72be5d74f27b896f,
+    0x80deb1fe3b1696b1,
+    0x9bdc06a725c71235,
+    0xc19bf174cf692694,
+    0xe49b69c19ef14ad2,
+    0xefbe4786384f25e3,
+    0x0fc19dc68b8cd5b5,
+    0x240ca1cc77ac9c65,
+    0x2de92c6f592b0275,
+    0x4a7484aa6ea6e483,
+    0x5cb0a9dcbd41fbd4,
+    0x76f988da831153b5,
+    0x983e5152ee66dfab,
+    0xa831c66d2db43210,
+    0xb00327c898fb213f,
+    0xbf597fc7beef0ee4,
+    0xc6e00bf33da88fc2,
+    0xd5a79147930aa725,
+    0x06ca6351e003826f,
+    0x142929670a0e6e70,
+    0x27b70a8546d22ffc,
+    0x2e1b21385c26c926,
+    0x4d2c6dfc5ac42aed,
+    0x53380d139d95b3df,
+    0x650a73548baf63de,
+    0x766a0abb3c77b2a8,
+    0x81c2c92e47edaee6,
+    0x92722c851482353b,
+    0xa2bfe8a14cf10364,
+    0xa81a664bbc423001,
+    0xc24b8b70d0f89791,
+    0xc76c51a30654be30,
+    0xd192e819d6ef5218,
+    0xd69906245565a910,
+    0xf40e35855771202a,
+    0x106aa07032bbd1b8,
+    0x19a4c116b8d2d0c8,
+    0x1e376c085141ab53,
+    0x2748774cdf8eeb99,
+    0x34b0bcb5e19b48a8,
+    0x391c0cb3c5c95a63,
+    0x4ed8aa4ae3418acb,
+    0x5b9cca4f7763e373,
+    0x682e6ff3d6b2b8a3,
+    0x748f82ee5defb2fc,
+    0x78a5636f43172f60,
+    0x84c87814a1f0ab72,
+    0x8cc702081a6439ec,
+    0x90befffa23631e28,
+    0xa4506cebde82bde9,
+    0xbef9a3f7b2c67915,
+    0xc67178f2e372532b,
+    0xca273eceea26619c,
+    0xd186b8c721c0c207,
+    0xeada7dd6cde0eb1e,
+    0xf57d4f7fee6ed178,
+    0x06f067aa72176fba,
+    0x0a637dc5a2c898a6,
+    0x113f9804bef90dae,
+    0x1b710b35131c471b,
+    0x28db77f523047d84,
+    0x32caab7b40c72493,
+    0x3c9ebe0a15c9bebc,
+    0x431d67c49c100d4c,
+    0x4cc5d4becb3e42b6,
+    0x597f299cfc657e2a,
+    0x5fcb6fab3ad6faec,
+    0x6c44198c4a475817,
+];
+
+const H512: [u64; 8] = [
+    0x6a09e667f3bcc908,
+    0xbb67ae8584caa73b,
+    0x3c6ef372fe94f82b,
+    0xa54ff53a5f1d36f1,
+    0x510e527fade682d1,
+    0x9b05688c2b3e6c1f,
+    0x1f83d9abfb41bd6b,
+    0x5be0cd19137e2179,
+];
+
+pub trait Digest {
+    const PREFIX: &'static [u8];
+    /// The bytes of the digest in the order they're written, three at a time and then however
+    /// many are left, with the first of each group in the highest bits
+    const ORDER: &'static [usize];
+
+    fn new() -> Self;
+    fn update(&mut self, data: &[u8]);
+    fn finish(self) -> Vec<u8>;
+
+    fn digest(parts: &[&[u8]]) -> Vec<u8>
+    where
+        Self: Sized,
+    {
+        let mut ctx = Self::new();
+        for part in parts {
+            ctx.update(part);
+        }
+        ctx.finish()
+    }
+}
+
+macro_rules! sha2 {
+    (
+        $name:ident, $word:ty, $k:ident, $h:ident, $prefix:expr, $order:expr,
+        $sigma0:expr, $sigma1:expr, $big_sigma0:expr, $big_sigma1:expr
+    ) => {
+        pub struct $name {
+            state: [$word; 8],
+            block: [u8; 16 * core::mem::size_of::<$word>()],
+            len: u64,
+        }
+
+        impl $name {
+            const BLOCK: usize = 16 * core::mem::size_of::<$word>();
+
+            fn compress(&mut self) {
+                const SIZE: usize = core::mem::size_of::<$word>();
+                let mut w = [0; $k.len()];
+                for i in 0..16 {
+                    let mut bytes = [0; SIZE];
+                    bytes.copy_from_slice(&self.block[SIZE * i..SIZE * (i + 1)]);
+                    w[i] = <$word>::from_be_bytes(bytes);
+                }
+                for i in 16..w.len() {
+                    let (r1, r2, s) = $sigma0;
+                    let s0 =
+                        w[i - 15].rotate_right(r1) ^ w[i - 15].rotate_right(r2) ^ w[i - 15] >> s;
+                    let (r1, r2, s) = $sigma1;
+                    let s1 = w[i - 2].rotate_right(r1) ^ w[i - 2].rotate_right(r2) ^ w[i - 2] >> s;
+                    w[i] = w[i - 16]
+                        .wrapping_add(s0)
+                        .wrapping_add(w[i - 7])
+                        .wrapping_add(s1);
+                }
+
+                let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = self.state;
+                for i in 0..w.len() {
+                    let (r1, r2, r3) = $big_sigma1;
+                    let s1 = e.rotate_right(r1) ^ e.rotate_right(r2) ^ e.rotate_right(r3);
+                    let choice = (e & f) ^ (!e & g);
+                    let temp1 = h
+                        .wrapping_add(s1)
+                        .wrapping_add(choice)
+                        .wrapping_add($k[i])
+                        .wrapping_add(w[i]);
+                    let (r1, r2, r3) = $big_sigma0;
+                    let s0 = a.rotate_right(r1) ^ a.rotate_right(r2) ^ a.rotate_right(r3);
+                    let majority = (a & b) ^ (a & c) ^ (b & c);
+                    let temp2 = s0.wrapping_add(majority);
+
+                    h = g;
+                    g = f;
+                    f = e;
+                    e = d.wrapping_add(temp1);
+                    d = c;
+                    c = b;
+                    b = a;
+                    a = temp1.wrapping_add(temp2);
+                }
+
+                for (state, value) in self.state.iter_mut().zip([a, b, c, d, e, f, g, h].iter()) {
+                    *state = state.wrapping_add(*value);
+                }
+            }
+        }
+
+        impl Digest for $name {
+            const PREFIX: &'static [u8] = $prefix;
+            const ORDER: &'static [usize] = &$order;
+
+            fn new() -> Self {
+                $name {
+                    state: $h,
+                    block: [0; Self::BLOCK],
+                    len: 0,
+                }
+            }
+
+            fn update(&mut self, data: &[u8]) {
+                for b in data {
+                    self.block[self.len as usize % Self::BLOCK] = *b;
+                    self.len += 1;
+                    if self.len as usize % Self::BLOCK == 0 {
+                        self.compress();
+                    }
+                }
+            }
+
+            fn finish(mut self) -> Vec<u8> {
+                // the length in bits fills the last 8 or 16 bytes of the block
+                let bits = u128::from(self.len) * 8;
+                let length_size = 2 * core::mem::size_of::<$word>();
+                self.update(&[0x80]);
+                while self.len as usize % Self::BLOCK != Self::BLOCK - length_size {
+                    self.update(&[0]);
+                }
+                self.update(&bits.to_be_bytes()[16 - length_size..]);
+
+                let mut digest = Vec::with_capacity(8 * core::mem::size_of::<$word>());
+                for word in &self.state {
+                    digest.extend_from_slice(&word.to_be_bytes());
+                }
+                digest
+            }
+        }
+    };
+}
+
+sha2!(
+    Sha256,
+    u32,
+    K256,
+    H256,
+    b"$5$",
+    [
+        0, 10, 20, 21, 1, 11, 12, 22, 2, 3, 13, 23, 24, 4, 14, 15, 25, 5, 6, 16, 26, 27, 7, 17, 18,
+        28, 8, 9, 19, 29, 31, 30,
+    ],
+    (7, 18, 3),
+    (17, 19, 10),
+    (2, 13, 22),
+    (6, 11, 25)
+);
+
+sha2!(
+    Sha512,
+    u64,
+    K512,
+    H512,
+    b"$6$",
+    [
+        0, 21, 42, 22, 43, 1, 44, 2, 23, 3, 24, 45, 25, 46, 4, 47, 5, 26, 6, 27, 48, 28, 49, 7, 50,
+        8, 29, 9, 30, 51, 31, 52, 10, 53, 11, 32, 12, 33, 54, 34, 55, 13, 56, 14, 35, 15, 36, 57,
+        37, 58, 16, 59, 17, 38, 18, 39, 60, 40, 61, 19, 62, 20, 41, 63,
+    ],
+    (1, 8, 7),
+    (19, 61, 6),
+    (28, 34, 39),
+    (14, 18, 41)
+);
+
+/// Repeats `digest` to fill `len` bytes.
+fn repeat(digest: &[u8], len: usize) -> Vec<u8> {
+    digest.iter().copied().cycle().take(len).collect()
+}
+
+/// Parses the number of rounds the setting starts with after "rounds=", if there is one.
+fn parse_rounds(setting: &[u8]) -> Option<(c_ulong, &[u8])> {
+    let rest = setting.strip_prefix(&b"rounds="[..])?;
+    let end = rest.iter().position(|&b| b == b'$')?;
+    let digits = &rest[..end];
+    if digits.is_empty() || !digits.iter().all(u8::is_ascii_digit) {
+        return None;
+    }
+    let rounds = digits.iter().fold(0, |rounds: c_ulong, &digit| {
+        rounds
+            .saturating_mul(10)
+            .saturating_add(c_ulong::from(digit - b'0'))
+    });
+    Some((rounds.max(MIN_ROUNDS).min(MAX_ROUNDS), &rest[end + 1..]))
+}
+
+pub fn crypt<D: Digest>(key: &[u8], setting: &[u8]) -> Option<Vec<u8>> {
+    let setting = &setting[D::PREFIX.len()..];
+    let (rounds, custom, setting) = match parse_rounds(setting) {
+        Some((rounds, rest)) => (rounds, true, rest),
+        None => (DEFAULT_ROUNDS, false, setting),
+    };
+    let salt_len = setting
+        .iter()
+        .take(MAX_SALT)
+        .position(|&b| b == b'$')
+        .unwrap_or_else(|| setting.len().min(MAX_SALT));
+    let salt = &setting[..salt_len];
+
+    let alternate = D::digest(&[key, salt, key]);
+    let mut ctx = D::new();
+    ctx.update(key);
+    ctx.update(salt);
+    ctx.update(&repeat(&alternate, key.len()));
+    let mut i = key.len();
+    while i > 0 {
+        if i & 1 == 1 {
+            ctx.update(&alternate);
+        } else {
+            ctx.update(key);
+        }
+        i >>= 1;
+    }
+    let mut digest = ctx.finish();
+
+    let mut ctx = D::new();
+    for _ in 0..key.len() {
+        ctx.update(key);
+    }
+    let p = repeat(&ctx.finish(), key.len());
+    let mut ctx = D::new();
+    for _ in 0..16 + usize::from(digest[0]) {
+        ctx.update(salt);
+    }
+    let s = repeat(&ctx.finish(), salt.len());
+
+    for i in 0..rounds {
+        let mut ctx = D::new();
+        if i & 1 == 1 {
+            ctx.update(&p);
+        } else {
+            ctx.update(&digest);
+        }
+        if i % 3 != 0 {
+            ctx.update(&s);
+        }
+        if i % 7 != 0 {
+            ctx.update(&p);
+        }
+        if i & 1 == 1 {
+            ctx.update(&digest);
+        } else {
+            ctx.update(&p);
+        }
+        digest = ctx.finish();
+    }
+
+    let mut out = D::PREFIX.to_vec();
+    if custom {
+        out.extend_from_slice(format!("rounds={}$", rounds).as_bytes());
+    }
+    out.extend_from_slice(salt);
+    out.push(b'$');
+    for group in D::ORDER.chunks(3) {
+        let value = group
+            .iter()
+            .fold(0, |value, &i| value << 8 | u32::from(digest[i]));
+        push_base64(&mut out, value, group.len() + 1);
+    }
+    Some(out)
+}
+
+pub fn gensalt(prefix: &[u8], count: c_ulong, rbytes: &[u8]) -> Option<Vec<u8>> {
+    if rbytes.len() < 12 {
+        return None;
+    }
+    let mut out = prefix.to_vec();
+    if count != 0 {
+        let rounds = count.max(MIN_ROUNDS).min(MAX_ROUNDS);
+        out.extend_from_slice(format!("rounds={}$", rounds).as_bytes());
+    }
+    push_salt(&mut out, &rbytes[..12]);
+    Some(out)
+}
@@ -39,10 +39,7 @@ pub struct dirent {
 #[no_mangle]
 pub unsafe extern "C" fn opendir(path: *const c_char) -> *mut DIR {
     let path = CStr::from_ptr(path);
-    let file = match File::open(
-        path,
-        fcntl::O_RDONLY | fcntl::O_DIRECTORY | fcntl::O_CLOEXEC,
-    ) {
+    let file = match File::open(path, fcntl::O_RDONLY | fcntl::O_DIRECTORY) {
         Ok(file) => file,
         Err(_) => return ptr::null_mut(),
     };
@@ -58,14 +55,11 @@ pub unsafe extern "C" fn opendir(path: *const c_char) -> *mut DIR {
 
 #[no_mangle]
 pub unsafe extern "C" fn closedir(dir: *mut DIR) -> c_int {
-    let mut dir = Box::from_raw(dir);
-
-    let ret = Sys::close(*dir.file);
-
-    // Reference files aren't closed when dropped
-    dir.file.reference = true;
-
-    ret
+    let dir = Box::from_raw(dir);
+    match dir.file.close() {
+        Ok(()) => 0,
+        Err(_) => -1,
+    }
 }
 
 #[no_mangle]
@@ -160,7 +154,7 @@ pub unsafe extern "C" fn scandir(
             }
         }
 
-        let copy = platform::alloc(mem::size_of::<dirent>()) as *mut dirent;
+        let copy = stdlib::malloc(mem::size_of::<dirent>()) as *mut dirent;
         if copy.is_null() {
             break;
         }
@@ -179,7 +173,7 @@ pub unsafe extern "C" fn scandir(
 
     if platform::errno != 0 {
         for ptr in &mut vec {
-            platform::free(*ptr as *mut c_void);
+            stdlib::free(*ptr as *mut c_void);
         }
         -1
     } else {
@@ -196,3 +190,37 @@ pub unsafe extern "C" fn scandir(
         len as c_int
     }
 }
+
+// Large file support's names, which glibc's binaries use. d_ino and d_off are 64 bits already.
+
+#[no_mangle]
+pub unsafe extern "C" fn alphasort64(
+    first: *mut *const dirent,
+    second: *mut *const dirent,
+) -> c_int {
+    alphasort(first, second)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn readdir64(dir: *mut DIR) -> *mut dirent {
+    readdir(dir)
+}
+
+#[no_mangle]
+pub extern "C" fn readdir64_r(
+    dir: *mut DIR,
+    entry: *mut dirent,
+    result: *mut *mut dirent,
+) -> *mut dirent {
+    readdir_r(dir, entry, result)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn scandir64(
+    dirp: *const c_char,
+    namelist: *mut *mut *mut dirent,
+    filter: Option<extern "C" fn(_: *const dirent) -> c_int>,
+    compare: Option<extern "C" fn(_: *mut *const dirent, _: *mut *const dirent) -> c_int>,
+) -> c_int {
+    scandir(dirp, namelist, filter, compare)
+}
@@ -19,7 +19,11 @@ pub unsafe extern "C" fn __tls_get_addr(ti: *mut dl_tls_index) -> *mut c_void {
     if let Some(tcb) = Tcb::current() {
         if let Some(masters) = tcb.masters() {
             if let Some(master) = masters.get((*ti).ti_module as usize) {
+                // Only the TLS of the program is supported on aarch64, which starts TLS
+                #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
                 let addr = tcb.tls_end.sub(master.offset).add((*ti).ti_offset as usize);
+                #[cfg(target_arch = "aarch64")]
+                let addr = tcb.tls_end.sub(tcb.tls_len).add((*ti).ti_offset as usize);
                 trace!(
                     "__tls_get_addr({:p}: {:#x}, {:#x}) = {:p}",
                     ti,
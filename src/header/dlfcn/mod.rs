@@ -19,20 +19,41 @@ static ERROR: AtomicUsize = AtomicUsize::new(0);
 
 #[repr(C)]
 pub struct Dl_info {
-    dli_fname: *const c_char,
-    dli_fbase: *mut c_void,
-    dli_sname: *const c_char,
-    dli_saddr: *mut c_void,
+    pub dli_fname: *const c_char,
+    pub dli_fbase: *mut c_void,
+    pub dli_sname: *const c_char,
+    pub dli_saddr: *mut c_void,
 }
 
 #[no_mangle]
 pub unsafe extern "C" fn dladdr(addr: *mut c_void, info: *mut Dl_info) -> c_int {
-    //TODO
     (*info).dli_fname = ptr::null();
     (*info).dli_fbase = ptr::null_mut();
     (*info).dli_sname = ptr::null();
     (*info).dli_saddr = ptr::null_mut();
-    0
+
+    let tcb = match Tcb::current() {
+        Some(tcb) => tcb,
+        None => return 0,
+    };
+    if tcb.linker_ptr.is_null() {
+        return 0;
+    }
+    let linker = (&*tcb.linker_ptr).lock();
+    let cbs_c = linker.cbs.clone();
+    let cbs = cbs_c.borrow();
+    let found = match (cbs.addr_info)(&linker, addr as usize) {
+        Some(found) => found,
+        None => return 0,
+    };
+
+    (*info).dli_fname = found.path;
+    (*info).dli_fbase = found.base as *mut c_void;
+    if let Some(symbol) = found.symbol {
+        (*info).dli_sname = symbol.name;
+        (*info).dli_saddr = symbol.as_ptr();
+    }
+    1
 }
 
 #[no_mangle]
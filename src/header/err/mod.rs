@@ -0,0 +1,65 @@
+//! err.h implementation for Redox, following the BSD functions of the same name.
+//!
+//! Each prints the program's name, the message and, for all but the x ones, the description of
+//! an error, which is `errno` unless it's given as `code`. The err ones then exit with `eval`.
+
+use core::ffi::VaList as va_list;
+
+use crate::{
+    header::{
+        stdio::{fputc, fputs, stderr, vfprintf},
+        stdlib::exit,
+        string::strerror,
+    },
+    platform::{self, types::*},
+};
+
+unsafe fn report(code: Option<c_int>, fmt: *const c_char, ap: va_list) {
+    if !platform::program_invocation_short_name.is_null() {
+        fputs(platform::program_invocation_short_name, stderr);
+        fputs(": \0".as_ptr() as *const c_char, stderr);
+    }
+    if !fmt.is_null() {
+        vfprintf(stderr, fmt, ap);
+    }
+    if let Some(code) = code {
+        if !fmt.is_null() {
+            fputs(": \0".as_ptr() as *const c_char, stderr);
+        }
+        fputs(strerror(code), stderr);
+    }
+    fputc(b'\n' as c_int, stderr);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn vwarn(fmt: *const c_char, ap: va_list) {
+    report(Some(platform::errno), fmt, ap);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn vwarnc(code: c_int, fmt: *const c_char, ap: va_list) {
+    report(Some(code), fmt, ap);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn vwarnx(fmt: *const c_char, ap: va_list) {
+    report(None, fmt, ap);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn verr(eval: c_int, fmt: *const c_char, ap: va_list) {
+    vwarn(fmt, ap);
+    exit(eval);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn verrc(eval: c_int, code: c_int, fmt: *const c_char, ap: va_list) {
+    vwarnc(code, fmt, ap);
+    exit(eval);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn verrx(eval: c_int, fmt: *const c_char, ap: va_list) {
+    vwarnx(fmt, ap);
+    exit(eval);
+}
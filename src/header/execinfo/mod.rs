@@ -0,0 +1,122 @@
+//! execinfo.h implementation, following the GNU functions of the same name.
+//!
+//! The stack is walked by following the chain of frame pointers, so only the frames of code
+//! built with them can be found. Addresses are named with the dynamic linker's symbol tables,
+//! which only have the symbols each object exports.
+
+use alloc::{string::String, vec::Vec};
+use core::{fmt, mem, ptr, str};
+
+use crate::{
+    c_str::CStr,
+    header::{
+        dlfcn::{dladdr, Dl_info},
+        stdlib::malloc,
+    },
+    platform::{types::*, FileWriter},
+};
+
+/// The most the stack is expected to grow by in one frame, so a pointer that isn't really a
+/// frame's ends the walk instead of being followed
+const MAX_FRAME: usize = 16 * 1024 * 1024;
+
+/// Fills `buffer` with the return addresses of the frames above `frame`, which is called by
+/// backtrace with its own frame.
+///
+/// Each frame starts with the frame pointer of the one above it, followed by the address it
+/// returns to.
+#[no_mangle]
+pub unsafe extern "C" fn __backtrace_from(
+    mut frame: *const *mut c_void,
+    buffer: *mut *mut c_void,
+    size: c_int,
+) -> c_int {
+    let mut count = 0;
+    while count < size && !frame.is_null() {
+        let ret = *frame.add(1);
+        if ret.is_null() {
+            break;
+        }
+        *buffer.add(count as usize) = ret;
+        count += 1;
+
+        let next = *frame as *const *mut c_void;
+        if next <= frame
+            || next as usize - frame as usize > MAX_FRAME
+            || next as usize % mem::align_of::<usize>() != 0
+        {
+            break;
+        }
+        frame = next;
+    }
+    count
+}
+
+/// Writes `addr` the way glibc does: the object it's in, the symbol and offset in brackets,
+/// and the address, with the parts dladdr couldn't find left out.
+unsafe fn describe<W: fmt::Write>(w: &mut W, addr: *mut c_void) -> fmt::Result {
+    let mut info: Dl_info = mem::zeroed();
+    if dladdr(addr, &mut info) == 0 || info.dli_fname.is_null() {
+        return write!(w, "[{:p}]", addr);
+    }
+
+    let fname = str::from_utf8_unchecked(CStr::from_ptr(info.dli_fname).to_bytes());
+    let (sname, base) = if info.dli_sname.is_null() {
+        ("", info.dli_fbase)
+    } else {
+        (
+            str::from_utf8_unchecked(CStr::from_ptr(info.dli_sname).to_bytes()),
+            info.dli_saddr,
+        )
+    };
+    let (sign, offset) = if addr >= base {
+        ('+', addr as usize - base as usize)
+    } else {
+        ('-', base as usize - addr as usize)
+    };
+    write!(w, "{}({}{}{:#x}) [{:p}]", fname, sname, sign, offset, addr)
+}
+
+/// Describes each address in `buffer`, returning an array of the strings, which can be freed
+/// all at once by freeing the array.
+#[no_mangle]
+pub unsafe extern "C" fn backtrace_symbols(
+    buffer: *const *mut c_void,
+    size: c_int,
+) -> *mut *mut c_char {
+    let mut lines = Vec::with_capacity(size.max(0) as usize);
+    for i in 0..size.max(0) as usize {
+        let mut line = String::new();
+        let _ = describe(&mut line, *buffer.add(i));
+        lines.push(line);
+    }
+
+    let array_size = lines.len() * mem::size_of::<*mut c_char>();
+    let total = lines
+        .iter()
+        .fold(array_size, |total, line| total + line.len() + 1);
+    let array = malloc(total) as *mut *mut c_char;
+    if array.is_null() {
+        return ptr::null_mut();
+    }
+
+    let mut string = (array as *mut u8).add(array_size);
+    for (i, line) in lines.iter().enumerate() {
+        ptr::copy_nonoverlapping(line.as_ptr(), string, line.len());
+        *string.add(line.len()) = 0;
+        *array.add(i) = string as *mut c_char;
+        string = string.add(line.len() + 1);
+    }
+    array
+}
+
+/// Like backtrace_symbols, but writes the lines to `fd` without allocating, so it can be used
+/// when the heap can't be trusted, like after a crash.
+#[no_mangle]
+pub unsafe extern "C" fn backtrace_symbols_fd(buffer: *const *mut c_void, size: c_int, fd: c_int) {
+    let mut w = FileWriter(fd);
+    for i in 0..size.max(0) as usize {
+        let _ = describe(&mut w, *buffer.add(i));
+        w.write(b"\n");
+    }
+}
@@ -2,7 +2,7 @@
 
 use crate::{
     c_str::CStr,
-    platform::{types::*, Pal, Sys},
+    platform::{self, types::*, Pal, Sys},
 };
 
 pub use self::sys::*;
@@ -23,6 +23,15 @@ pub const F_SETFL: c_int = 4;
 pub const F_GETLK: c_int = 5;
 pub const F_SETLK: c_int = 6;
 pub const F_SETLKW: c_int = 7;
+pub const F_DUPFD_CLOEXEC: c_int = 1030;
+pub const F_ADD_SEALS: c_int = 1033;
+pub const F_GET_SEALS: c_int = 1034;
+
+pub const F_SEAL_SEAL: c_int = 0x0001;
+pub const F_SEAL_SHRINK: c_int = 0x0002;
+pub const F_SEAL_GROW: c_int = 0x0004;
+pub const F_SEAL_WRITE: c_int = 0x0008;
+pub const F_SEAL_FUTURE_WRITE: c_int = 0x0010;
 
 pub const F_RDLCK: c_int = 0;
 pub const F_WRLCK: c_int = 1;
@@ -32,6 +41,13 @@ pub const F_UNLCK: c_int = 2;
 pub unsafe extern "C" fn creat(path: *const c_char, mode: mode_t) -> c_int {
     sys_open(path, O_WRONLY | O_CREAT | O_TRUNC, mode)
 }
+
+/// creat's large file support name, for glibc's binaries. Files are always opened with 64-bit
+/// offsets, so there's no flag to add.
+#[no_mangle]
+pub unsafe extern "C" fn creat64(path: *const c_char, mode: mode_t) -> c_int {
+    creat(path, mode)
+}
 #[repr(C)]
 pub struct flock {
     pub l_type: c_short,
@@ -42,13 +58,16 @@ pub struct flock {
 }
 #[no_mangle]
 pub extern "C" fn sys_fcntl(fildes: c_int, cmd: c_int, arg: c_int) -> c_int {
+    if cmd == F_SETLKW {
+        return platform::cancellation_point(|| Sys::fcntl(fildes, cmd, arg));
+    }
     Sys::fcntl(fildes, cmd, arg)
 }
 
 #[no_mangle]
 pub unsafe extern "C" fn sys_open(path: *const c_char, oflag: c_int, mode: mode_t) -> c_int {
     let path = CStr::from_ptr(path);
-    Sys::open(path, oflag, mode)
+    platform::cancellation_point(|| Sys::open(path, oflag, mode))
 }
 
 #[no_mangle]
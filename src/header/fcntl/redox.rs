@@ -10,6 +10,9 @@ pub const O_SHLOCK: c_int = 0x0010_0000;
 pub const O_EXLOCK: c_int = 0x0020_0000;
 pub const O_ASYNC: c_int = 0x0040_0000;
 pub const O_FSYNC: c_int = 0x0080_0000;
+// Written data is always synchronized with its metadata
+pub const O_SYNC: c_int = O_FSYNC;
+pub const O_DSYNC: c_int = O_FSYNC;
 pub const O_CLOEXEC: c_int = 0x0100_0000;
 pub const O_CREAT: c_int = 0x0200_0000;
 pub const O_TRUNC: c_int = 0x0400_0000;
@@ -19,5 +22,7 @@ pub const O_PATH: c_int = 0x2000_0000;
 pub const O_SYMLINK: c_int = 0x4000_0000;
 // Negative to allow it to be used as int
 pub const O_NOFOLLOW: c_int = -0x8000_0000;
+// Redox has no controlling terminals, so there is nothing for it to do
+pub const O_NOCTTY: c_int = 0;
 
 pub const FD_CLOEXEC: c_int = 0x0100_0000;
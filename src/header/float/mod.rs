@@ -2,7 +2,7 @@
 //! http://pubs.opengroup.org/onlinepubs/7908799/xsh/float.h.html
 
 use crate::{
-    header::_fenv::{fegetround, FE_TONEAREST},
+    header::_fenv::{fegetround, FE_DOWNWARD, FE_TONEAREST, FE_TOWARDZERO, FE_UPWARD},
     platform::types::*,
 };
 
@@ -11,7 +11,10 @@ pub const FLT_RADIX: c_int = 2;
 #[no_mangle]
 pub unsafe extern "C" fn flt_rounds() -> c_int {
     match fegetround() {
+        FE_TOWARDZERO => 0,
         FE_TONEAREST => 1,
+        FE_UPWARD => 2,
+        FE_DOWNWARD => 3,
         _ => -1,
     }
 }
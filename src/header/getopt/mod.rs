@@ -1,15 +1,22 @@
 //! getopt implementation for relibc
+//!
+//! Like GNU's, getopt_long and getopt_long_only move the options before the other arguments as
+//! they go, unless optstring starts with '+' or POSIXLY_CORRECT is set, which stop at the first
+//! argument that isn't an option, or '-', which returns each of them as the argument of an
+//! option 1.
 
 use crate::{
+    c_str::CStr,
     header::{
-        stdio, string,
+        stdio, stdlib, string,
         unistd::{optarg, opterr, optind, optopt},
     },
     platform::types::*,
 };
 use core::ptr;
 
-static mut CURRENT_OPT: *mut c_char = ptr::null_mut();
+/// Where the next option is in the current argument, or 0 at the start of an argument
+static mut CURRENT_POS: usize = 0;
 
 pub const no_argument: c_int = 0;
 pub const required_argument: c_int = 1;
@@ -32,183 +39,347 @@ pub unsafe extern "C" fn getopt_long(
     longopts: *const option,
     longindex: *mut c_int,
 ) -> c_int {
-    // if optarg is not set, we still don't want the previous value leaking
-    optarg = ptr::null_mut();
+    getopt_permute(argc, argv, optstring, longopts, longindex, false)
+}
+
+/// Like getopt_long, but long options can also start with a single '-', in which case they're
+/// only parsed as short options if they don't match any long one.
+#[no_mangle]
+#[linkage = "weak"] // often redefined in GNU programs
+pub unsafe extern "C" fn getopt_long_only(
+    argc: c_int,
+    argv: *const *mut c_char,
+    optstring: *const c_char,
+    longopts: *const option,
+    longindex: *mut c_int,
+) -> c_int {
+    getopt_permute(argc, argv, optstring, longopts, longindex, true)
+}
 
+unsafe fn reinitialize() {
     // handle reinitialization request
     if optind == 0 {
         optind = 1;
-        CURRENT_OPT = ptr::null_mut();
+        CURRENT_POS = 0;
+    }
+}
+
+unsafe fn is_option(arg: *const c_char) -> bool {
+    *arg == b'-' as c_char && *arg.offset(1) != 0
+}
+
+/// Moves `argv[src]` to `argv[dest]`, shifting the arguments in between up by one.
+unsafe fn permute(argv: *mut *mut c_char, dest: c_int, src: c_int) {
+    let moved = *argv.offset(src as isize);
+    for i in (dest..src).rev() {
+        *argv.offset(i as isize + 1) = *argv.offset(i as isize);
     }
+    *argv.offset(dest as isize) = moved;
+}
 
-    if CURRENT_OPT.is_null() || *CURRENT_OPT == 0 {
-        if optind >= argc {
-            -1
-        } else {
-            let current_arg = *argv.offset(optind as isize);
-            if current_arg.is_null()
-                || *current_arg != b'-' as c_char
-                || *current_arg.offset(1) == 0
-            {
-                -1
-            } else if string::strcmp(current_arg, c_str!("--").as_ptr()) == 0 {
-                optind += 1;
-                -1
-            } else {
-                // remove the '-'
-                let current_arg = current_arg.offset(1);
-
-                if *current_arg == b'-' as c_char && !longopts.is_null() {
-                    let current_arg = current_arg.offset(1);
-                    // is a long option
-                    for i in 0.. {
-                        let opt = &*longopts.offset(i);
-                        if opt.name.is_null() {
-                            break;
-                        }
-
-                        let mut end = 0;
-                        while {
-                            let c = *current_arg.offset(end);
-                            c != 0 && c != b'=' as c_char
-                        } {
-                            end += 1;
-                        }
-
-                        if string::strncmp(current_arg, opt.name, end as size_t) == 0 {
-                            optind += 1;
-                            *longindex = i as c_int;
-
-                            if opt.has_arg == optional_argument {
-                                if *current_arg.offset(end) == b'=' as c_char {
-                                    optarg = current_arg.offset(end + 1);
-                                }
-                            } else if opt.has_arg == required_argument {
-                                if *current_arg.offset(end) == b'=' as c_char {
-                                    optarg = current_arg.offset(end + 1);
-                                } else if optind < argc {
-                                    optarg = *argv.offset(optind as isize);
-                                    optind += 1;
-                                } else if *optstring == b':' as c_char {
-                                    return b':' as c_int;
-                                } else {
-                                    stdio::fputs(*argv as _, &mut *stdio::stderr);
-                                    stdio::fputs(
-                                        ": option '--\0".as_ptr() as _,
-                                        &mut *stdio::stderr,
-                                    );
-                                    stdio::fputs(current_arg, &mut *stdio::stderr);
-                                    stdio::fputs(
-                                        "' requires an argument\n\0".as_ptr() as _,
-                                        &mut *stdio::stderr,
-                                    );
-                                    return b'?' as c_int;
-                                }
-                            }
-
-                            if opt.flag.is_null() {
-                                return opt.val;
-                            } else {
-                                *opt.flag = opt.val;
-                                return 0;
-                            }
-                        }
-                    }
-                }
+unsafe fn getopt_permute(
+    argc: c_int,
+    argv: *const *mut c_char,
+    optstring: *const c_char,
+    longopts: *const option,
+    longindex: *mut c_int,
+    long_only: bool,
+) -> c_int {
+    reinitialize();
+    optarg = ptr::null_mut();
+
+    if optind >= argc || (*argv.offset(optind as isize)).is_null() {
+        return -1;
+    }
 
-                parse_arg(argc, argv, current_arg, optstring)
+    // skip the arguments that aren't options, to move them after the ones that are
+    let skipped = optind;
+    if *optstring != b'+' as c_char
+        && *optstring != b'-' as c_char
+        && stdlib::getenv(c_str!("POSIXLY_CORRECT").as_ptr()).is_null()
+    {
+        let mut i = optind;
+        loop {
+            if i >= argc || (*argv.offset(i as isize)).is_null() {
+                return -1;
             }
+            if is_option(*argv.offset(i as isize)) {
+                break;
+            }
+            i += 1;
         }
-    } else {
-        parse_arg(argc, argv, CURRENT_OPT, optstring)
+        optind = i;
+    }
+
+    let resumed = optind;
+    let ret = getopt_long_core(argc, argv, optstring, longopts, longindex, long_only);
+
+    if resumed > skipped {
+        // the option and its argument, if it's separate, go where the skipped arguments started
+        let count = optind - resumed;
+        for _ in 0..count {
+            permute(argv as *mut *mut c_char, skipped, optind - 1);
+        }
+        optind = skipped + count;
     }
+    ret
 }
 
-unsafe fn parse_arg(
+unsafe fn getopt_long_core(
     argc: c_int,
     argv: *const *mut c_char,
-    current_arg: *mut c_char,
     optstring: *const c_char,
+    longopts: *const option,
+    longindex: *mut c_int,
+    long_only: bool,
 ) -> c_int {
-    let update_current_opt = || {
-        CURRENT_OPT = current_arg.offset(1);
-        if *CURRENT_OPT == 0 {
-            optind += 1;
+    let current_arg = *argv.offset(optind as isize);
+    if longopts.is_null() || CURRENT_POS != 0 || *current_arg != b'-' as c_char {
+        return getopt_short(argc, argv, optstring);
+    }
+
+    let dashes = if *current_arg.offset(1) == b'-' as c_char {
+        if *current_arg.offset(2) == 0 {
+            // "--" ends the options
+            return getopt_short(argc, argv, optstring);
         }
+        2
+    } else if long_only
+        && *current_arg.offset(1) != 0
+        && (*current_arg.offset(2) != 0
+            || find_option(*current_arg.offset(1), skip_mode(optstring)).is_none())
+    {
+        // a single letter that's a short option is just that
+        1
+    } else {
+        return getopt_short(argc, argv, optstring);
     };
+    let prefix = &b"--"[..dashes];
+    let colon = *skip_mode(optstring) == b':' as c_char;
 
-    let print_error = |desc: &[u8]| {
-        // NOTE: we don't use fprintf to get around the usage of va_list
-        stdio::fputs(*argv as _, &mut *stdio::stderr);
-        stdio::fputs(desc.as_ptr() as _, &mut *stdio::stderr);
-        stdio::fputc(*current_arg as _, &mut *stdio::stderr);
-        stdio::fputc(b'\n' as _, &mut *stdio::stderr);
-    };
+    let name = current_arg.offset(dashes as isize);
+    let mut end = 0;
+    while {
+        let c = *name.offset(end);
+        c != 0 && c != b'=' as c_char
+    } {
+        end += 1;
+    }
 
-    match find_option(*current_arg, optstring) {
-        Some(GetoptOption::Flag) => {
-            update_current_opt();
+    // an exact match or, failing that, the only option it's the start of
+    let mut matches = 0;
+    let mut index = 0;
+    for i in 0.. {
+        let opt = &*longopts.offset(i);
+        if opt.name.is_null() {
+            break;
+        }
+        if string::strncmp(name, opt.name, end as size_t) == 0 {
+            if *opt.name.offset(end) == 0 {
+                matches = 1;
+                index = i;
+                break;
+            }
+            if matches == 0 {
+                index = i;
+            }
+            matches += 1;
+        }
+    }
 
-            *current_arg as c_int
+    if matches != 1 {
+        if matches == 0 && dashes == 1 && find_option(*name, skip_mode(optstring)).is_some() {
+            return getopt_short(argc, argv, optstring);
         }
-        Some(GetoptOption::OptArg) => {
-            CURRENT_OPT = b"\0".as_ptr() as _;
-            if *current_arg.offset(1) == 0 {
-                optind += 2;
-                if optind > argc {
-                    CURRENT_OPT = ptr::null_mut();
-
-                    optopt = *current_arg as c_int;
-                    let errch = if *optstring == b':' as c_char {
-                        b':'
-                    } else {
-                        if opterr != 0 {
-                            print_error(b": option requries an argument -- \0");
-                        }
-
-                        b'?'
-                    };
-                    errch as c_int
-                } else {
-                    optarg = *argv.offset(optind as isize - 1);
-
-                    *current_arg as c_int
-                }
+        optopt = 0;
+        optind += 1;
+        if !colon && opterr != 0 {
+            let arg = CStr::from_ptr(name).to_bytes();
+            if matches == 0 {
+                print_error(argv, &[b"unrecognized option '", prefix, arg, b"'\n"]);
             } else {
-                optarg = current_arg.offset(1);
-                optind += 1;
+                print_error(argv, &[b"option '", prefix, arg, b"' is ambiguous\n"]);
+            }
+        }
+        return b'?' as c_int;
+    }
 
-                *current_arg as c_int
+    let opt = &*longopts.offset(index);
+    let opt_name = CStr::from_ptr(opt.name).to_bytes();
+    optind += 1;
+
+    if *name.offset(end) == b'=' as c_char {
+        if opt.has_arg == no_argument {
+            optopt = opt.val;
+            if !colon && opterr != 0 {
+                print_error(
+                    argv,
+                    &[
+                        b"option '",
+                        prefix,
+                        opt_name,
+                        b"' doesn't allow an argument\n",
+                    ],
+                );
             }
+            return b'?' as c_int;
         }
-        None => {
-            // couldn't find the given option in optstring
+        optarg = name.offset(end + 1);
+    } else if opt.has_arg == required_argument {
+        if optind >= argc {
+            optopt = opt.val;
+            if colon {
+                return b':' as c_int;
+            }
             if opterr != 0 {
-                print_error(b": illegal option -- \0");
+                print_error(
+                    argv,
+                    &[b"option '", prefix, opt_name, b"' requires an argument\n"],
+                );
+            }
+            return b'?' as c_int;
+        }
+        optarg = *argv.offset(optind as isize);
+        optind += 1;
+    }
+
+    if !longindex.is_null() {
+        *longindex = index as c_int;
+    }
+    if opt.flag.is_null() {
+        opt.val
+    } else {
+        *opt.flag = opt.val;
+        0
+    }
+}
+
+/// Parses the next short option, as getopt does.
+pub(crate) unsafe fn getopt_short(
+    argc: c_int,
+    argv: *const *mut c_char,
+    optstring: *const c_char,
+) -> c_int {
+    reinitialize();
+    // if optarg is not set, we still don't want the previous value leaking
+    optarg = ptr::null_mut();
+
+    if optind >= argc {
+        return -1;
+    }
+    let current_arg = *argv.offset(optind as isize);
+    if current_arg.is_null() {
+        return -1;
+    }
+
+    if CURRENT_POS == 0 {
+        if !is_option(current_arg) {
+            if *optstring == b'-' as c_char {
+                optarg = current_arg;
+                optind += 1;
+                return 1;
             }
+            return -1;
+        }
+        if string::strcmp(current_arg, c_str!("--").as_ptr()) == 0 {
+            optind += 1;
+            return -1;
+        }
+        // skip the '-'
+        CURRENT_POS = 1;
+    }
+
+    let c = *current_arg.offset(CURRENT_POS as isize);
+    CURRENT_POS += 1;
+    let rest = current_arg.offset(CURRENT_POS as isize);
+    if *rest == 0 {
+        optind += 1;
+        CURRENT_POS = 0;
+    }
 
-            update_current_opt();
+    let optstring = skip_mode(optstring);
+    let colon = *optstring == b':' as c_char;
+    let kind = match find_option(c, optstring) {
+        Some(kind) => kind,
+        None => {
+            // couldn't find the given option in optstring
+            optopt = c as c_int;
+            if !colon && opterr != 0 {
+                print_error(argv, &[b"invalid option -- '", &[c as u8], b"'\n"]);
+            }
+            return b'?' as c_int;
+        }
+    };
 
-            optopt = *current_arg as c_int;
-            b'?' as c_int
+    match kind {
+        GetoptOption::Flag => (),
+        _ if CURRENT_POS != 0 => {
+            // the rest of the argument is the option's
+            optarg = rest;
+            optind += 1;
+            CURRENT_POS = 0;
+        }
+        GetoptOption::OptArg => {
+            if optind >= argc {
+                optopt = c as c_int;
+                if colon {
+                    return b':' as c_int;
+                }
+                if opterr != 0 {
+                    print_error(
+                        argv,
+                        &[b"option requires an argument -- '", &[c as u8], b"'\n"],
+                    );
+                }
+                return b'?' as c_int;
+            }
+            optarg = *argv.offset(optind as isize);
+            optind += 1;
         }
+        GetoptOption::OptionalArg => (),
+    }
+    c as c_int
+}
+
+/// Prints the program's name and then `parts`, without using fprintf to get around the usage
+/// of va_list.
+unsafe fn print_error(argv: *const *mut c_char, parts: &[&[u8]]) {
+    stdio::fputs(*argv as _, &mut *stdio::stderr);
+    stdio::fputs(": \0".as_ptr() as _, &mut *stdio::stderr);
+    for part in parts {
+        stdio::fwrite(part.as_ptr() as _, 1, part.len(), &mut *stdio::stderr);
+    }
+}
+
+/// Skips the '+' or '-' at the start of optstring that sets how the arguments are permuted.
+unsafe fn skip_mode(optstring: *const c_char) -> *const c_char {
+    if *optstring == b'+' as c_char || *optstring == b'-' as c_char {
+        optstring.offset(1)
+    } else {
+        optstring
     }
 }
 
 enum GetoptOption {
     Flag,
     OptArg,
+    OptionalArg,
 }
 
 unsafe fn find_option(ch: c_char, optstring: *const c_char) -> Option<GetoptOption> {
-    let mut i = 0;
+    if ch == b':' as c_char {
+        return None;
+    }
 
+    let mut i = 0;
     while *optstring.offset(i) != 0 {
         if *optstring.offset(i) == ch {
-            let result = if *optstring.offset(i + 1) == b':' as c_char {
-                GetoptOption::OptArg
-            } else {
+            let result = if *optstring.offset(i + 1) != b':' as c_char {
                 GetoptOption::Flag
+            } else if *optstring.offset(i + 2) == b':' as c_char {
+                GetoptOption::OptionalArg
+            } else {
+                GetoptOption::OptArg
             };
             return Some(result);
         }
@@ -1,6 +1,21 @@
 //! grp implementation for Redox, following http://pubs.opengroup.org/onlinepubs/7908799/xsh/grp.h.html
 
-use crate::platform::types::*;
+use alloc::vec::Vec;
+use core::{mem, ptr};
+
+use crate::{
+    c_str::CStr,
+    fs::File,
+    header::{
+        errno::{ENOENT, ERANGE},
+        fcntl::O_RDONLY,
+        netdb::db,
+        stdio::FILE,
+    },
+    io::{BufReader, Seek, SeekFrom},
+    nss::{self, files, GroupKey},
+    platform::{self, types::*},
+};
 
 #[repr(C)]
 pub struct group {
@@ -10,56 +25,196 @@ pub struct group {
     pub gr_mem: *mut *mut c_char,
 }
 
-// #[no_mangle]
-pub extern "C" fn getgrgid(gid: gid_t) -> *mut group {
-    unimplemented!();
+/// Holds the strings and member array GROUP points to
+static mut GROUP_BUF: Vec<u8> = Vec::new();
+static mut GROUP: group = group {
+    gr_name: ptr::null_mut(),
+    gr_passwd: ptr::null_mut(),
+    gr_gid: 0,
+    gr_mem: ptr::null_mut(),
+};
+
+static mut READER: Option<BufReader<File>> = None;
+
+/// Lays out `entry` in `buf` and fills `out` to point into it, returning the number of bytes
+/// needed if `buf` is too small.
+unsafe fn pack(
+    entry: &nss::Group,
+    out: *mut group,
+    buf: *mut c_char,
+    buflen: size_t,
+) -> Result<(), usize> {
+    let packed = db::pack(
+        buf,
+        buflen,
+        &entry.name,
+        &entry.members,
+        Some(&entry.passwd),
+    )?;
+    *out = group {
+        gr_name: packed.name,
+        gr_passwd: packed.extra,
+        gr_gid: entry.gid,
+        gr_mem: packed.aliases,
+    };
+    Ok(())
 }
 
-// #[no_mangle]
-pub extern "C" fn getgrnam(name: *const c_char) -> *mut group {
-    unimplemented!();
+/// Stores `entry` in GROUP, for the functions returning static storage.
+unsafe fn static_group(entry: &nss::Group) -> *mut group {
+    loop {
+        let buf = GROUP_BUF.as_mut_ptr() as *mut c_char;
+        match pack(entry, &mut GROUP, buf, GROUP_BUF.len()) {
+            Ok(()) => return &mut GROUP,
+            // Leave room for realigning the member array after the reallocation
+            Err(needed) => GROUP_BUF.resize(needed + mem::align_of::<*mut c_char>(), 0),
+        }
+    }
 }
 
-// #[no_mangle]
-pub extern "C" fn getgrgid_r(
-    gid: gid_t,
+/// Returns a lookup's entry in static storage, setting errno if the lookup failed rather than
+/// finding nothing.
+unsafe fn static_lookup(res: Result<nss::Group, nss::Status>) -> *mut group {
+    match res {
+        Ok(entry) => static_group(&entry),
+        Err(status) => {
+            if status.errno() != 0 {
+                platform::errno = status.errno();
+            }
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Packs a lookup's entry into the caller's buffer for the _r functions, which return 0 with a
+/// NULL result when there's no entry.
+unsafe fn lookup_r(
+    res: Result<nss::Group, nss::Status>,
     grp: *mut group,
     buffer: *mut c_char,
-    bufsize: usize,
+    bufsize: size_t,
     result: *mut *mut group,
 ) -> c_int {
-    unimplemented!();
+    *result = ptr::null_mut();
+    match res {
+        Ok(entry) => match pack(&entry, grp, buffer, bufsize) {
+            Ok(()) => {
+                *result = grp;
+                0
+            }
+            Err(_) => {
+                // like glibc, for callers that check errno instead
+                platform::errno = ERANGE;
+                ERANGE
+            }
+        },
+        Err(status) => status.errno(),
+    }
 }
 
-// #[no_mangle]
-pub extern "C" fn getgrnam_r(
-    name: *const c_char,
+/// Opens the file getgrent goes through, the first time it's needed.
+unsafe fn reader() -> Option<&'static mut BufReader<File>> {
+    if READER.is_none() {
+        let file = File::open(c_str!("/etc/group"), O_RDONLY).ok()?;
+        READER = Some(BufReader::new(file));
+    }
+    READER.as_mut()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn endgrent() {
+    READER = None;
+}
+
+/// Reads the next entry from `stream`, which has the format of /etc/group.
+#[no_mangle]
+pub unsafe extern "C" fn fgetgrent(stream: *mut FILE) -> *mut group {
+    let mut stream = (*stream).lock();
+    match files::next(&mut *stream, files::parse_group) {
+        Some(entry) => static_group(&entry),
+        None => ptr::null_mut(),
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn getgrent() -> *mut group {
+    match reader().and_then(|reader| files::next(reader, files::parse_group)) {
+        Some(entry) => static_group(&entry),
+        None => ptr::null_mut(),
+    }
+}
+
+/// The reentrant getgrent, which returns ENOENT after the last entry. If `buffer` is too small,
+/// it returns ERANGE and the same entry is read again by the next call.
+#[no_mangle]
+pub unsafe extern "C" fn getgrent_r(
     grp: *mut group,
     buffer: *mut c_char,
-    bufsize: usize,
+    bufsize: size_t,
     result: *mut *mut group,
 ) -> c_int {
-    unimplemented!();
+    *result = ptr::null_mut();
+    let reader = match reader() {
+        Some(reader) => reader,
+        None => return platform::errno,
+    };
+    let pos = match reader.seek(SeekFrom::Current(0)) {
+        Ok(pos) => pos,
+        Err(_) => return platform::errno,
+    };
+    let entry = match files::next(reader, files::parse_group) {
+        Some(entry) => entry,
+        None => return ENOENT,
+    };
+    match pack(&entry, grp, buffer, bufsize) {
+        Ok(()) => {
+            *result = grp;
+            0
+        }
+        Err(_) => {
+            let _ = reader.seek(SeekFrom::Start(pos));
+            platform::errno = ERANGE;
+            ERANGE
+        }
+    }
 }
 
-// #[no_mangle]
-pub extern "C" fn getgrent() -> *mut group {
-    unimplemented!();
+#[no_mangle]
+pub unsafe extern "C" fn getgrgid(gid: gid_t) -> *mut group {
+    static_lookup(nss::group(GroupKey::Gid(gid)))
 }
 
-// #[no_mangle]
-pub extern "C" fn endgrent() {
-    unimplemented!();
+#[no_mangle]
+pub unsafe extern "C" fn getgrgid_r(
+    gid: gid_t,
+    grp: *mut group,
+    buffer: *mut c_char,
+    bufsize: size_t,
+    result: *mut *mut group,
+) -> c_int {
+    lookup_r(nss::group(GroupKey::Gid(gid)), grp, buffer, bufsize, result)
 }
 
-// #[no_mangle]
-pub extern "C" fn setgrent() {
-    unimplemented!();
+#[no_mangle]
+pub unsafe extern "C" fn getgrnam(name: *const c_char) -> *mut group {
+    static_lookup(nss::group(GroupKey::Name(CStr::from_ptr(name).to_bytes())))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn getgrnam_r(
+    name: *const c_char,
+    grp: *mut group,
+    buffer: *mut c_char,
+    bufsize: size_t,
+    result: *mut *mut group,
+) -> c_int {
+    let res = nss::group(GroupKey::Name(CStr::from_ptr(name).to_bytes()));
+    lookup_r(res, grp, buffer, bufsize, result)
 }
 
-/*
 #[no_mangle]
-pub extern "C" fn func(args) -> c_int {
-    unimplemented!();
+pub unsafe extern "C" fn setgrent() {
+    if let Some(reader) = &mut READER {
+        let _ = reader.seek(SeekFrom::Start(0));
+    }
 }
-*/
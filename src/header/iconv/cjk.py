@@ -0,0 +1,86 @@
+#!/usr/bin/env python3
+# Writes cjk.rs from the codecs of Python's standard library: python3 cjk.py > cjk.rs
+#
+# The tables in the tree were made with Python 3.11.7.
+
+
+def table(name, doc, leads, trails, codec):
+    values = []
+    for lead in leads:
+        for trail in trails:
+            try:
+                c = bytes([lead, trail]).decode(codec)
+            except UnicodeDecodeError:
+                values.append(0)
+                continue
+            assert len(c) == 1 and ord(c) < 0x10000
+            values.append(ord(c))
+    print(f"/// {doc}")
+    print(f"pub const {name}: [u16; {len(values)}] = [")
+    for i in range(0, len(values), 12):
+        print("    " + " ".join(f"0x{v:04x}," for v in values[i : i + 12]))
+    print("];")
+    print()
+
+
+# The four-byte sequences of the Basic Multilingual Plane, as ranges of consecutive characters
+# with consecutive sequences, and the index after the last sequence
+def gb18030_ranges():
+    ranges = []
+    last = None
+    for cp in range(0x80, 0x10000):
+        if 0xd800 <= cp < 0xe000:
+            continue
+        e = chr(cp).encode("gb18030")
+        if len(e) != 4:
+            continue
+        index = (((e[0] - 0x81) * 10 + (e[1] - 0x30)) * 126 + (e[2] - 0x81)) * 10 + (e[3] - 0x30)
+        if last is None or last != (index - 1, cp - 1):
+            ranges.append((index, cp))
+        last = (index, cp)
+    return ranges, last[0] + 1
+
+
+def main():
+    print("//! The double-byte tables of the East Asian character sets, indexed by the position of the")
+    print("//! lead byte among the lead bytes times the number of trail bytes, plus the position of the")
+    print("//! trail byte among them. Zero stands for a pair of bytes that doesn't map to anything.")
+    print()
+    print("// Generated by cjk.py from the codecs of Python's standard library, which follow the mapping")
+    print("// tables of the standards and the vendors")
+    print()
+    table(
+        "GB18030",
+        "GB18030, with lead bytes 0x81 to 0xFE and trail bytes 0x40 to 0x7E and 0x80 to 0xFE",
+        range(0x81, 0xFF),
+        list(range(0x40, 0x7F)) + list(range(0x80, 0xFF)),
+        "gb18030",
+    )
+    table(
+        "SHIFT_JIS",
+        "Shift_JIS, with lead bytes 0x81 to 0x9F and 0xE0 to 0xEF and trail bytes 0x40 to 0x7E and 0x80 to 0xFC",
+        list(range(0x81, 0xA0)) + list(range(0xE0, 0xF0)),
+        list(range(0x40, 0x7F)) + list(range(0x80, 0xFD)),
+        "shift_jis",
+    )
+    table(
+        "EUC_KR",
+        "EUC-KR, with lead and trail bytes 0xA1 to 0xFE",
+        range(0xA1, 0xFF),
+        range(0xA1, 0xFF),
+        "euc_kr",
+    )
+
+    ranges, end = gb18030_ranges()
+    print("/// The four-byte sequences of GB18030 for the rest of the Basic Multilingual Plane, as the index")
+    print("/// of the first sequence of each range of consecutive characters and the first character")
+    print(f"pub const GB18030_RANGES: [(u32, u16); {len(ranges)}] = [")
+    for index, cp in ranges:
+        print(f"    ({index}, 0x{cp:04x}),")
+    print("];")
+    print()
+    print("/// The index after the last four-byte sequence of the Basic Multilingual Plane")
+    print(f"pub const GB18030_RANGES_END: u32 = {end};")
+
+
+main()
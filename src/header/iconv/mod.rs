@@ -0,0 +1,821 @@
+//! iconv implementation for Redox, following https://pubs.opengroup.org/onlinepubs/9699919799/basedefs/iconv.h.html
+
+use alloc::{boxed::Box, vec::Vec};
+use core::{char, slice};
+
+use crate::{
+    c_str::CStr,
+    header::{
+        errno::{E2BIG, EILSEQ, EINVAL},
+        locale::{self, LC_CTYPE},
+    },
+    platform::{self, types::*},
+    sync::Once,
+};
+
+mod cjk;
+mod tables;
+
+pub type iconv_t = *mut c_void;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Charset {
+    Utf8,
+    /// UTF-16 and UTF-32 with a byte order mark, which is written in the native order and read
+    /// in either
+    Utf16,
+    Utf16Be,
+    Utf16Le,
+    Utf32,
+    Utf32Be,
+    Utf32Le,
+    Ucs2,
+    Ucs2Be,
+    Ucs2Le,
+    Ucs4Be,
+    Ucs4Le,
+    Ascii,
+    Latin1,
+    /// An 8-bit character set with ASCII as its lower half
+    Table(&'static [u16; 128]),
+    Gb18030,
+    ShiftJis,
+    EucKr,
+}
+
+const NATIVE_BIG_ENDIAN: bool = cfg!(target_endian = "big");
+/// What wchar_t holds, UTF-32 in the native byte order
+const WCHAR_T: Charset = if NATIVE_BIG_ENDIAN {
+    Charset::Ucs4Be
+} else {
+    Charset::Ucs4Le
+};
+
+/// The character sets by name, with only the letters and digits of the names, in lowercase
+const NAMES: &[(&str, Charset)] = &[
+    ("utf8", Charset::Utf8),
+    ("utf16", Charset::Utf16),
+    ("utf16be", Charset::Utf16Be),
+    ("utf16le", Charset::Utf16Le),
+    ("utf32", Charset::Utf32),
+    ("utf32be", Charset::Utf32Be),
+    ("utf32le", Charset::Utf32Le),
+    ("ucs2", Charset::Ucs2),
+    ("ucs2be", Charset::Ucs2Be),
+    ("ucs2le", Charset::Ucs2Le),
+    ("ucs4", Charset::Ucs4Be),
+    ("ucs4be", Charset::Ucs4Be),
+    ("ucs4le", Charset::Ucs4Le),
+    ("wchart", WCHAR_T),
+    ("ascii", Charset::Ascii),
+    ("usascii", Charset::Ascii),
+    ("ansix341968", Charset::Ascii),
+    ("iso646us", Charset::Ascii),
+    ("iso88591", Charset::Latin1),
+    ("latin1", Charset::Latin1),
+    ("l1", Charset::Latin1),
+    ("iso88592", Charset::Table(&tables::ISO_8859_2)),
+    ("latin2", Charset::Table(&tables::ISO_8859_2)),
+    ("iso88593", Charset::Table(&tables::ISO_8859_3)),
+    ("latin3", Charset::Table(&tables::ISO_8859_3)),
+    ("iso88594", Charset::Table(&tables::ISO_8859_4)),
+    ("latin4", Charset::Table(&tables::ISO_8859_4)),
+    ("iso88595", Charset::Table(&tables::ISO_8859_5)),
+    ("cyrillic", Charset::Table(&tables::ISO_8859_5)),
+    ("iso88596", Charset::Table(&tables::ISO_8859_6)),
+    ("arabic", Charset::Table(&tables::ISO_8859_6)),
+    ("iso88597", Charset::Table(&tables::ISO_8859_7)),
+    ("greek", Charset::Table(&tables::ISO_8859_7)),
+    ("iso88598", Charset::Table(&tables::ISO_8859_8)),
+    ("hebrew", Charset::Table(&tables::ISO_8859_8)),
+    ("iso88599", Charset::Table(&tables::ISO_8859_9)),
+    ("latin5", Charset::Table(&tables::ISO_8859_9)),
+    ("iso885910", Charset::Table(&tables::ISO_8859_10)),
+    ("latin6", Charset::Table(&tables::ISO_8859_10)),
+    ("iso885913", Charset::Table(&tables::ISO_8859_13)),
+    ("latin7", Charset::Table(&tables::ISO_8859_13)),
+    ("iso885914", Charset::Table(&tables::ISO_8859_14)),
+    ("latin8", Charset::Table(&tables::ISO_8859_14)),
+    ("iso885915", Charset::Table(&tables::ISO_8859_15)),
+    ("latin9", Charset::Table(&tables::ISO_8859_15)),
+    ("iso885916", Charset::Table(&tables::ISO_8859_16)),
+    ("latin10", Charset::Table(&tables::ISO_8859_16)),
+    ("koi8r", Charset::Table(&tables::KOI8_R)),
+    ("koi8u", Charset::Table(&tables::KOI8_U)),
+    ("cp437", Charset::Table(&tables::CP437)),
+    ("ibm437", Charset::Table(&tables::CP437)),
+    ("cp850", Charset::Table(&tables::CP850)),
+    ("ibm850", Charset::Table(&tables::CP850)),
+    ("cp866", Charset::Table(&tables::CP866)),
+    ("ibm866", Charset::Table(&tables::CP866)),
+    ("cp1250", Charset::Table(&tables::CP1250)),
+    ("windows1250", Charset::Table(&tables::CP1250)),
+    ("cp1251", Charset::Table(&tables::CP1251)),
+    ("windows1251", Charset::Table(&tables::CP1251)),
+    ("cp1252", Charset::Table(&tables::CP1252)),
+    ("windows1252", Charset::Table(&tables::CP1252)),
+    ("cp1253", Charset::Table(&tables::CP1253)),
+    ("windows1253", Charset::Table(&tables::CP1253)),
+    ("cp1254", Charset::Table(&tables::CP1254)),
+    ("windows1254", Charset::Table(&tables::CP1254)),
+    ("cp1255", Charset::Table(&tables::CP1255)),
+    ("windows1255", Charset::Table(&tables::CP1255)),
+    ("cp1256", Charset::Table(&tables::CP1256)),
+    ("windows1256", Charset::Table(&tables::CP1256)),
+    ("cp1257", Charset::Table(&tables::CP1257)),
+    ("windows1257", Charset::Table(&tables::CP1257)),
+    ("cp1258", Charset::Table(&tables::CP1258)),
+    ("windows1258", Charset::Table(&tables::CP1258)),
+    ("macintosh", Charset::Table(&tables::MACINTOSH)),
+    ("macroman", Charset::Table(&tables::MACINTOSH)),
+    ("mac", Charset::Table(&tables::MACINTOSH)),
+    ("gb18030", Charset::Gb18030),
+    ("shiftjis", Charset::ShiftJis),
+    ("sjis", Charset::ShiftJis),
+    ("mskanji", Charset::ShiftJis),
+    ("euckr", Charset::EucKr),
+];
+
+/// A double-byte character set, as its table and the ranges of its lead and trail bytes
+struct DoubleByte {
+    table: &'static [u16],
+    leads: &'static [(u8, u8)],
+    trails: &'static [(u8, u8)],
+    /// The characters of the table sorted, with their index, once something is encoded
+    encoding: Once<Vec<(u16, u16)>>,
+}
+
+static GB18030: DoubleByte = DoubleByte {
+    table: &cjk::GB18030,
+    leads: &[(0x81, 0xFE)],
+    trails: &[(0x40, 0x7E), (0x80, 0xFE)],
+    encoding: Once::new(),
+};
+
+static SHIFT_JIS: DoubleByte = DoubleByte {
+    table: &cjk::SHIFT_JIS,
+    leads: &[(0x81, 0x9F), (0xE0, 0xEF)],
+    trails: &[(0x40, 0x7E), (0x80, 0xFC)],
+    encoding: Once::new(),
+};
+
+static EUC_KR: DoubleByte = DoubleByte {
+    table: &cjk::EUC_KR,
+    leads: &[(0xA1, 0xFE)],
+    trails: &[(0xA1, 0xFE)],
+    encoding: Once::new(),
+};
+
+/// The position of `byte` among the bytes of `ranges`.
+fn position(byte: u8, ranges: &[(u8, u8)]) -> Option<usize> {
+    let mut base = 0;
+    for &(first, last) in ranges {
+        if (first..=last).contains(&byte) {
+            return Some(base + usize::from(byte - first));
+        }
+        base += usize::from(last - first) + 1;
+    }
+    None
+}
+
+/// The byte at `position` among the bytes of `ranges`.
+fn nth(mut position: usize, ranges: &[(u8, u8)]) -> u8 {
+    for &(first, last) in ranges {
+        let len = usize::from(last - first) + 1;
+        if position < len {
+            return first + position as u8;
+        }
+        position -= len;
+    }
+    unreachable!()
+}
+
+impl DoubleByte {
+    fn decode(&self, input: &[u8]) -> Result<(u32, usize), DecodeError> {
+        let lead = position(input[0], self.leads).ok_or(DecodeError::Invalid)?;
+        let trail = *input.get(1).ok_or(DecodeError::Incomplete)?;
+        let trail = position(trail, self.trails).ok_or(DecodeError::Invalid)?;
+        match self.table[lead * self.trail_count() + trail] {
+            0 => Err(DecodeError::Invalid),
+            c => Ok((u32::from(c), 2)),
+        }
+    }
+
+    fn encode(&self, c: u32) -> Option<[u8; 2]> {
+        if c > 0xFFFF {
+            return None;
+        }
+        let encoding = self.encoding.call_once(|| {
+            let mut encoding: Vec<(u16, u16)> = (0..self.table.len())
+                .filter(|&i| self.table[i] != 0)
+                .map(|i| (self.table[i], i as u16))
+                .collect();
+            encoding.sort_unstable();
+            encoding
+        });
+        // The first of the sequences for a character, if it has several
+        let i = encoding.partition_point(|&(mapped, _)| u32::from(mapped) < c);
+        let &(mapped, index) = encoding.get(i)?;
+        if u32::from(mapped) != c {
+            return None;
+        }
+        let index = usize::from(index);
+        Some([
+            nth(index / self.trail_count(), self.leads),
+            nth(index % self.trail_count(), self.trails),
+        ])
+    }
+
+    fn trail_count(&self) -> usize {
+        self.trails
+            .iter()
+            .map(|&(first, last)| usize::from(last - first) + 1)
+            .sum()
+    }
+}
+
+fn decode_gb18030(input: &[u8]) -> Result<(u32, usize), DecodeError> {
+    match input {
+        [byte @ 0x00..=0x7F, ..] => return Ok((u32::from(*byte), 1)),
+        // The four-byte sequences have a digit as their second byte
+        [0x81..=0xFE, 0x30..=0x39, ..] => (),
+        _ => return GB18030.decode(input),
+    }
+    match input {
+        [_, _] | [_, _, 0x81..=0xFE] => return Err(DecodeError::Incomplete),
+        [_, _, 0x81..=0xFE, 0x30..=0x39, ..] => (),
+        _ => return Err(DecodeError::Invalid),
+    }
+    let index = ((u32::from(input[0] - 0x81) * 10 + u32::from(input[1] - 0x30)) * 126
+        + u32::from(input[2] - 0x81))
+        * 10
+        + u32::from(input[3] - 0x30);
+    let c = if index < cjk::GB18030_RANGES_END {
+        let range = match cjk::GB18030_RANGES.binary_search_by_key(&index, |&(first, _)| first) {
+            Ok(range) => range,
+            Err(range) => range - 1,
+        };
+        let (first, c) = cjk::GB18030_RANGES[range];
+        u32::from(c) + (index - first)
+    } else if index >= GB18030_SUPPLEMENTARY && index - GB18030_SUPPLEMENTARY < 0x100000 {
+        0x10000 + (index - GB18030_SUPPLEMENTARY)
+    } else {
+        return Err(DecodeError::Invalid);
+    };
+    Ok((c, 4))
+}
+
+/// The index of the four-byte sequence of GB18030 for U+10000, 0x90 0x30 0x81 0x30
+const GB18030_SUPPLEMENTARY: u32 = 189000;
+
+fn encode_gb18030(c: u32, out: &mut [u8]) -> Result<usize, EncodeError> {
+    if c < 0x80 {
+        return encode_byte(Some(c as u8), out);
+    }
+    if let Some(bytes) = GB18030.encode(c) {
+        return encode_bytes(&bytes, out);
+    }
+    let index = match c {
+        0xD800..=0xDFFF => return Err(EncodeError::Unrepresentable),
+        0x80..=0xFFFF => {
+            let range = cjk::GB18030_RANGES.partition_point(|&(_, first)| u32::from(first) <= c);
+            let (index, first) = cjk::GB18030_RANGES[range - 1];
+            let end = cjk::GB18030_RANGES
+                .get(range)
+                .map_or(cjk::GB18030_RANGES_END, |&(end, _)| end);
+            let index = index + (c - u32::from(first));
+            if index >= end {
+                return Err(EncodeError::Unrepresentable);
+            }
+            index
+        }
+        0x10000..=0x10FFFF => GB18030_SUPPLEMENTARY + (c - 0x10000),
+        _ => return Err(EncodeError::Unrepresentable),
+    };
+    encode_bytes(
+        &[
+            (index / 12600) as u8 + 0x81,
+            (index / 1260 % 10) as u8 + 0x30,
+            (index / 10 % 126) as u8 + 0x81,
+            (index % 10) as u8 + 0x30,
+        ],
+        out,
+    )
+}
+
+pub(crate) enum DecodeError {
+    /// The input ends in the middle of a character
+    Incomplete,
+    Invalid,
+}
+
+pub(crate) enum EncodeError {
+    TooBig,
+    Unrepresentable,
+}
+
+struct Converter {
+    from: Charset,
+    to: Charset,
+    translit: bool,
+    ignore: bool,
+    /// Whether the input of UTF-16 or UTF-32 is big-endian, once its byte order mark is read
+    from_big_endian: Option<bool>,
+    /// Whether the byte order mark of UTF-16 or UTF-32 was written
+    to_started: bool,
+}
+
+fn u16_from(bytes: &[u8], big_endian: bool) -> u32 {
+    let bytes = [bytes[0], bytes[1]];
+    u32::from(if big_endian {
+        u16::from_be_bytes(bytes)
+    } else {
+        u16::from_le_bytes(bytes)
+    })
+}
+
+fn u32_from(bytes: &[u8], big_endian: bool) -> u32 {
+    let bytes = [bytes[0], bytes[1], bytes[2], bytes[3]];
+    if big_endian {
+        u32::from_be_bytes(bytes)
+    } else {
+        u32::from_le_bytes(bytes)
+    }
+}
+
+fn decode_utf16(input: &[u8], big_endian: bool) -> Result<(u32, usize), DecodeError> {
+    if input.len() < 2 {
+        return Err(DecodeError::Incomplete);
+    }
+    let high = u16_from(input, big_endian);
+    match high {
+        0xD800..=0xDBFF => {
+            if input.len() < 4 {
+                return Err(DecodeError::Incomplete);
+            }
+            let low = u16_from(&input[2..], big_endian);
+            if !(0xDC00..=0xDFFF).contains(&low) {
+                return Err(DecodeError::Invalid);
+            }
+            Ok((0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00), 4))
+        }
+        0xDC00..=0xDFFF => Err(DecodeError::Invalid),
+        _ => Ok((high, 2)),
+    }
+}
+
+fn decode_utf32(input: &[u8], big_endian: bool) -> Result<(u32, usize), DecodeError> {
+    if input.len() < 4 {
+        return Err(DecodeError::Incomplete);
+    }
+    let c = u32_from(input, big_endian);
+    if char::from_u32(c).is_none() {
+        return Err(DecodeError::Invalid);
+    }
+    Ok((c, 4))
+}
+
+fn decode_utf8(input: &[u8]) -> Result<(u32, usize), DecodeError> {
+    let len = match input[0] {
+        0x00..=0x7F => return Ok((u32::from(input[0]), 1)),
+        0xC2..=0xDF => 2,
+        0xE0..=0xEF => 3,
+        0xF0..=0xF4 => 4,
+        _ => return Err(DecodeError::Invalid),
+    };
+    let available = input.len().min(len);
+    // An invalid sequence is reported as such even when it's cut short
+    match core::str::from_utf8(&input[..available]) {
+        Ok(s) => Ok((s.chars().next().unwrap() as u32, len)),
+        Err(err) if err.error_len().is_none() => Err(DecodeError::Incomplete),
+        Err(_) => Err(DecodeError::Invalid),
+    }
+}
+
+fn encode_utf16(c: u32, out: &mut [u8], big_endian: bool) -> Result<usize, EncodeError> {
+    let mut units = [0; 2];
+    let units = char::from_u32(c)
+        .ok_or(EncodeError::Unrepresentable)?
+        .encode_utf16(&mut units);
+    if out.len() < units.len() * 2 {
+        return Err(EncodeError::TooBig);
+    }
+    for (i, unit) in units.iter().enumerate() {
+        let bytes = if big_endian {
+            unit.to_be_bytes()
+        } else {
+            unit.to_le_bytes()
+        };
+        out[i * 2..i * 2 + 2].copy_from_slice(&bytes);
+    }
+    Ok(units.len() * 2)
+}
+
+fn encode_utf32(c: u32, out: &mut [u8], big_endian: bool) -> Result<usize, EncodeError> {
+    if out.len() < 4 {
+        return Err(EncodeError::TooBig);
+    }
+    let bytes = if big_endian {
+        c.to_be_bytes()
+    } else {
+        c.to_le_bytes()
+    };
+    out[..4].copy_from_slice(&bytes);
+    Ok(4)
+}
+
+fn encode_bytes(bytes: &[u8], out: &mut [u8]) -> Result<usize, EncodeError> {
+    if out.len() < bytes.len() {
+        return Err(EncodeError::TooBig);
+    }
+    out[..bytes.len()].copy_from_slice(bytes);
+    Ok(bytes.len())
+}
+
+fn encode_byte(byte: Option<u8>, out: &mut [u8]) -> Result<usize, EncodeError> {
+    let byte = byte.ok_or(EncodeError::Unrepresentable)?;
+    match out.first_mut() {
+        Some(out) => {
+            *out = byte;
+            Ok(1)
+        }
+        None => Err(EncodeError::TooBig),
+    }
+}
+
+impl Converter {
+    fn new(from: Charset, to: Charset) -> Self {
+        Self {
+            from,
+            to,
+            translit: false,
+            ignore: false,
+            from_big_endian: None,
+            to_started: false,
+        }
+    }
+
+    /// The size of the units of the input, which is skipped a unit at a time when it's invalid.
+    fn unit(&self) -> usize {
+        match self.from {
+            Charset::Utf16 | Charset::Utf16Be | Charset::Utf16Le => 2,
+            Charset::Ucs2 | Charset::Ucs2Be | Charset::Ucs2Le => 2,
+            Charset::Utf32 | Charset::Utf32Be | Charset::Utf32Le => 4,
+            Charset::Ucs4Be | Charset::Ucs4Le => 4,
+            _ => 1,
+        }
+    }
+
+    fn decode(&mut self, input: &[u8]) -> Result<(u32, usize), DecodeError> {
+        match self.from {
+            Charset::Utf8 => decode_utf8(input),
+            Charset::Utf16 | Charset::Utf32 => {
+                let unit = if self.from == Charset::Utf16 { 2 } else { 4 };
+                if input.len() < unit {
+                    return Err(DecodeError::Incomplete);
+                }
+                // A byte order mark only counts at the start
+                let mut bom = 0;
+                if self.from_big_endian.is_none() {
+                    let (be, le) = if unit == 2 {
+                        (u16_from(input, true), u16_from(input, false))
+                    } else {
+                        (u32_from(input, true), u32_from(input, false))
+                    };
+                    self.from_big_endian = Some(match (be, le) {
+                        (0xFEFF, _) => {
+                            bom = unit;
+                            true
+                        }
+                        (_, 0xFEFF) => {
+                            bom = unit;
+                            false
+                        }
+                        _ => NATIVE_BIG_ENDIAN,
+                    });
+                }
+                let big_endian = self.from_big_endian.unwrap();
+                let rest = &input[bom..];
+                let decoded = if unit == 2 {
+                    decode_utf16(rest, big_endian)
+                } else {
+                    decode_utf32(rest, big_endian)
+                };
+                match decoded {
+                    Ok((c, len)) => Ok((c, bom + len)),
+                    Err(err) => {
+                        if bom != 0 {
+                            self.from_big_endian = None;
+                        }
+                        Err(err)
+                    }
+                }
+            }
+            Charset::Utf16Be => decode_utf16(input, true),
+            Charset::Utf16Le => decode_utf16(input, false),
+            Charset::Utf32Be | Charset::Ucs4Be => decode_utf32(input, true),
+            Charset::Utf32Le | Charset::Ucs4Le => decode_utf32(input, false),
+            Charset::Ucs2 | Charset::Ucs2Be | Charset::Ucs2Le => {
+                if input.len() < 2 {
+                    return Err(DecodeError::Incomplete);
+                }
+                let big_endian = match self.from {
+                    Charset::Ucs2 => NATIVE_BIG_ENDIAN,
+                    charset => charset == Charset::Ucs2Be,
+                };
+                match u16_from(input, big_endian) {
+                    0xD800..=0xDFFF => Err(DecodeError::Invalid),
+                    c => Ok((c, 2)),
+                }
+            }
+            Charset::Ascii if input[0] >= 0x80 => Err(DecodeError::Invalid),
+            Charset::Ascii | Charset::Latin1 => Ok((u32::from(input[0]), 1)),
+            Charset::Table(table) => match input[0] {
+                byte @ 0x00..=0x7F => Ok((u32::from(byte), 1)),
+                byte => match table[byte as usize - 0x80] {
+                    0 => Err(DecodeError::Invalid),
+                    c => Ok((u32::from(c), 1)),
+                },
+            },
+            Charset::Gb18030 => decode_gb18030(input),
+            Charset::ShiftJis => match input[0] {
+                byte @ 0x00..=0x7F => Ok((u32::from(byte), 1)),
+                // Katakana, in their halfwidth forms
+                byte @ 0xA1..=0xDF => Ok((0xFF61 + u32::from(byte - 0xA1), 1)),
+                _ => SHIFT_JIS.decode(input),
+            },
+            Charset::EucKr => match input[0] {
+                byte @ 0x00..=0x7F => Ok((u32::from(byte), 1)),
+                _ => EUC_KR.decode(input),
+            },
+        }
+    }
+
+    fn encode(&mut self, c: u32, out: &mut [u8]) -> Result<usize, EncodeError> {
+        match self.to {
+            Charset::Utf8 => {
+                let mut buf = [0; 4];
+                let encoded = char::from_u32(c)
+                    .ok_or(EncodeError::Unrepresentable)?
+                    .encode_utf8(&mut buf);
+                if out.len() < encoded.len() {
+                    return Err(EncodeError::TooBig);
+                }
+                out[..encoded.len()].copy_from_slice(encoded.as_bytes());
+                Ok(encoded.len())
+            }
+            Charset::Utf16 | Charset::Utf32 => {
+                let utf16 = self.to == Charset::Utf16;
+                let mut bom = 0;
+                if !self.to_started {
+                    bom = if utf16 {
+                        encode_utf16(0xFEFF, out, NATIVE_BIG_ENDIAN)?
+                    } else {
+                        encode_utf32(0xFEFF, out, NATIVE_BIG_ENDIAN)?
+                    };
+                }
+                let len = if utf16 {
+                    encode_utf16(c, &mut out[bom..], NATIVE_BIG_ENDIAN)?
+                } else {
+                    encode_utf32(c, &mut out[bom..], NATIVE_BIG_ENDIAN)?
+                };
+                self.to_started = true;
+                Ok(bom + len)
+            }
+            Charset::Utf16Be => encode_utf16(c, out, true),
+            Charset::Utf16Le => encode_utf16(c, out, false),
+            Charset::Utf32Be | Charset::Ucs4Be => encode_utf32(c, out, true),
+            Charset::Utf32Le | Charset::Ucs4Le => encode_utf32(c, out, false),
+            Charset::Ucs2 | Charset::Ucs2Be | Charset::Ucs2Le => {
+                if c > 0xFFFF {
+                    return Err(EncodeError::Unrepresentable);
+                }
+                let big_endian = match self.to {
+                    Charset::Ucs2 => NATIVE_BIG_ENDIAN,
+                    charset => charset == Charset::Ucs2Be,
+                };
+                encode_utf16(c, out, big_endian)
+            }
+            Charset::Ascii => encode_byte(Some(c as u8).filter(|_| c < 0x80), out),
+            Charset::Latin1 => encode_byte(Some(c as u8).filter(|_| c < 0x100), out),
+            Charset::Table(table) => {
+                let byte = if c < 0x80 {
+                    Some(c as u8)
+                } else {
+                    table
+                        .iter()
+                        .position(|&mapped| mapped != 0 && u32::from(mapped) == c)
+                        .map(|i| (i + 0x80) as u8)
+                };
+                encode_byte(byte, out)
+            }
+            Charset::Gb18030 => encode_gb18030(c, out),
+            Charset::ShiftJis => match c {
+                0x00..=0x7F => encode_byte(Some(c as u8), out),
+                0xFF61..=0xFF9F => encode_byte(Some((c - 0xFF61) as u8 + 0xA1), out),
+                _ => encode_bytes(
+                    &SHIFT_JIS.encode(c).ok_or(EncodeError::Unrepresentable)?,
+                    out,
+                ),
+            },
+            Charset::EucKr => match c {
+                0x00..=0x7F => encode_byte(Some(c as u8), out),
+                _ => encode_bytes(&EUC_KR.encode(c).ok_or(EncodeError::Unrepresentable)?, out),
+            },
+        }
+    }
+
+    /// Writes an approximation of `c`, or a question mark if there is none.
+    fn transliterate(&mut self, c: u32, out: &mut [u8]) -> Result<usize, EncodeError> {
+        let approximation = tables::TRANSLIT
+            .binary_search_by_key(&c, |&(c, _)| c)
+            .map_or("?", |i| tables::TRANSLIT[i].1);
+        let mut len = 0;
+        for c in approximation.chars() {
+            len += self.encode(c as u32, &mut out[len..])?;
+        }
+        Ok(len)
+    }
+}
+
+/// `name` with only its letters and digits, in lowercase.
+fn normalize(name: &[u8]) -> Vec<u8> {
+    name.iter()
+        .filter(|b| b.is_ascii_alphanumeric())
+        .map(|b| b.to_ascii_lowercase())
+        .collect()
+}
+
+fn charset(name: &[u8]) -> Option<Charset> {
+    let normalized = normalize(name);
+    NAMES
+        .iter()
+        .find(|(name, _)| name.as_bytes() == &normalized[..])
+        .map(|&(_, charset)| charset)
+}
+
+/// A character set multibyte strings can be in, with ASCII as its single bytes and without a
+/// state, for the functions of wchar.h and stdlib.h.
+#[derive(Clone, Copy)]
+pub(crate) struct Multibyte(Charset);
+
+impl Multibyte {
+    /// The character set `name`, if multibyte strings can be in it.
+    pub(crate) fn new(name: &[u8]) -> Option<Self> {
+        match charset(name)? {
+            found @ Charset::Utf8
+            | found @ Charset::Ascii
+            | found @ Charset::Latin1
+            | found @ Charset::Table(_)
+            | found @ Charset::Gb18030
+            | found @ Charset::ShiftJis
+            | found @ Charset::EucKr => Some(Self(found)),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn is_utf8(self) -> bool {
+        self.0 == Charset::Utf8
+    }
+
+    /// The first character of `input`, which isn't empty, and its length.
+    pub(crate) fn decode(self, input: &[u8]) -> Result<(u32, usize), DecodeError> {
+        Converter::new(self.0, WCHAR_T).decode(input)
+    }
+
+    pub(crate) fn encode(self, c: u32, out: &mut [u8]) -> Result<usize, EncodeError> {
+        Converter::new(WCHAR_T, self.0).encode(c, out)
+    }
+}
+
+/// The character set of `name`, ignoring case and punctuation, and the options after it.
+unsafe fn parse(name: &[u8]) -> Option<(Charset, bool, bool)> {
+    let end = name
+        .windows(2)
+        .position(|w| w == b"//")
+        .unwrap_or(name.len());
+    let charset = match normalize(&name[..end]).as_slice() {
+        // The character set of the locale
+        b"" | b"char" => charset(locale::item(LC_CTYPE, "charmap", 0).to_bytes())?,
+        _ => charset(&name[..end])?,
+    };
+
+    let (mut translit, mut ignore) = (false, false);
+    for option in name[end..].split(|&b| b == b'/' || b == b',') {
+        if option.eq_ignore_ascii_case(b"TRANSLIT") {
+            translit = true;
+        } else if option.eq_ignore_ascii_case(b"IGNORE") {
+            ignore = true;
+        }
+    }
+    Some((charset, translit, ignore))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn iconv_open(tocode: *const c_char, fromcode: *const c_char) -> iconv_t {
+    let to = parse(CStr::from_ptr(tocode).to_bytes());
+    let from = parse(CStr::from_ptr(fromcode).to_bytes());
+    match (to, from) {
+        (Some((to, translit, ignore)), Some((from, _, _))) => Box::into_raw(Box::new(Converter {
+            translit,
+            ignore,
+            ..Converter::new(from, to)
+        })) as iconv_t,
+        _ => {
+            platform::errno = EINVAL;
+            -1isize as iconv_t
+        }
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn iconv(
+    cd: iconv_t,
+    inbuf: *mut *mut c_char,
+    inbytesleft: *mut size_t,
+    outbuf: *mut *mut c_char,
+    outbytesleft: *mut size_t,
+) -> size_t {
+    let converter = &mut *(cd as *mut Converter);
+
+    // Without input, the conversion starts over
+    if inbuf.is_null() || (*inbuf).is_null() {
+        converter.from_big_endian = None;
+        converter.to_started = false;
+        return 0;
+    }
+
+    let mut irreversible = 0;
+    while *inbytesleft > 0 {
+        let input = slice::from_raw_parts(*inbuf as *const u8, *inbytesleft);
+        let out: &mut [u8] = if outbuf.is_null() || (*outbuf).is_null() {
+            &mut []
+        } else {
+            slice::from_raw_parts_mut(*outbuf as *mut u8, *outbytesleft)
+        };
+
+        let (c, read) = match converter.decode(input) {
+            Ok(decoded) => decoded,
+            Err(DecodeError::Incomplete) => {
+                platform::errno = EINVAL;
+                return !0;
+            }
+            Err(DecodeError::Invalid) if converter.ignore => {
+                let skipped = converter.unit().min(*inbytesleft);
+                *inbuf = (*inbuf).add(skipped);
+                *inbytesleft -= skipped;
+                irreversible += 1;
+                continue;
+            }
+            Err(DecodeError::Invalid) => {
+                platform::errno = EILSEQ;
+                return !0;
+            }
+        };
+        let written = match converter.encode(c, out) {
+            Ok(written) => written,
+            Err(EncodeError::Unrepresentable) if converter.translit => {
+                match converter.transliterate(c, out) {
+                    Ok(written) => {
+                        irreversible += 1;
+                        written
+                    }
+                    Err(EncodeError::TooBig) => {
+                        platform::errno = E2BIG;
+                        return !0;
+                    }
+                    Err(EncodeError::Unrepresentable) => {
+                        platform::errno = EILSEQ;
+                        return !0;
+                    }
+                }
+            }
+            Err(EncodeError::Unrepresentable) if converter.ignore => {
+                irreversible += 1;
+                0
+            }
+            Err(EncodeError::Unrepresentable) => {
+                platform::errno = EILSEQ;
+                return !0;
+            }
+            Err(EncodeError::TooBig) => {
+                platform::errno = E2BIG;
+                return !0;
+            }
+        };
+
+        *inbuf = (*inbuf).add(read);
+        *inbytesleft -= read;
+        if written > 0 {
+            *outbuf = (*outbuf).add(written);
+            *outbytesleft -= written;
+        }
+    }
+    irreversible
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn iconv_close(cd: iconv_t) -> c_int {
+    drop(Box::from_raw(cd as *mut Converter));
+    0
+}
@@ -0,0 +1,114 @@
+#!/usr/bin/env python3
+# Writes tables.rs from the codecs of Python's standard library, and unicodedata for the
+# approximations of //TRANSLIT: python3 tables.py > tables.rs
+#
+# The tables in the tree were made with Python 3.11.7, whose unicodedata is Unicode 14.0.0.
+
+import unicodedata
+
+CHARSETS = [
+    ("ISO_8859_2", "iso8859_2"),
+    ("ISO_8859_3", "iso8859_3"),
+    ("ISO_8859_4", "iso8859_4"),
+    ("ISO_8859_5", "iso8859_5"),
+    ("ISO_8859_6", "iso8859_6"),
+    ("ISO_8859_7", "iso8859_7"),
+    ("ISO_8859_8", "iso8859_8"),
+    ("ISO_8859_9", "iso8859_9"),
+    ("ISO_8859_10", "iso8859_10"),
+    ("ISO_8859_13", "iso8859_13"),
+    ("ISO_8859_14", "iso8859_14"),
+    ("ISO_8859_15", "iso8859_15"),
+    ("ISO_8859_16", "iso8859_16"),
+    ("KOI8_R", "koi8_r"),
+    ("KOI8_U", "koi8_u"),
+    ("CP437", "cp437"),
+    ("CP850", "cp850"),
+    ("CP866", "cp866"),
+    ("CP1250", "cp1250"),
+    ("CP1251", "cp1251"),
+    ("CP1252", "cp1252"),
+    ("CP1253", "cp1253"),
+    ("CP1254", "cp1254"),
+    ("CP1255", "cp1255"),
+    ("CP1256", "cp1256"),
+    ("CP1257", "cp1257"),
+    ("CP1258", "cp1258"),
+    ("MACINTOSH", "mac_roman"),
+]
+
+# Approximations that the compatibility decompositions don't give
+SPECIAL = {
+    0xa1: "!", 0xa2: "c", 0xa3: "GBP", 0xa5: "JPY", 0xa6: "|", 0xa7: "SS", 0xa9: "(C)",
+    0xab: "<<", 0xac: "!", 0xae: "(R)", 0xb0: "^0", 0xb1: "+/-", 0xb6: "P", 0xb7: ".",
+    0xbb: ">>", 0xbf: "?", 0xc6: "AE", 0xd0: "D", 0xd7: "x", 0xd8: "O", 0xde: "TH", 0xdf: "ss",
+    0xe6: "ae", 0xf0: "d", 0xf7: ":", 0xf8: "o", 0xfe: "th", 0x110: "D", 0x111: "d", 0x126: "H",
+    0x127: "h", 0x131: "i", 0x141: "L", 0x142: "l", 0x152: "OE", 0x153: "oe", 0x166: "T",
+    0x167: "t", 0x2010: "-", 0x2011: "-", 0x2012: "-", 0x2013: "-", 0x2014: "-", 0x2015: "-",
+    0x2018: "'", 0x2019: "'", 0x201a: "'", 0x201b: "'", 0x201c: '"', 0x201d: '"', 0x201e: '"',
+    0x201f: '"', 0x2022: "o", 0x2032: "'", 0x2033: '"', 0x2039: "<", 0x203a: ">", 0x20ac: "EUR",
+    0x2190: "<-", 0x2192: "->", 0x2194: "<->", 0x21d2: "=>", 0x2212: "-", 0x2260: "!=",
+    0x2264: "<=", 0x2265: ">=",
+}
+
+# Where approximations are looked for
+TRANSLIT_RANGES = [
+    range(0xa0, 0x250),
+    range(0x1e00, 0x1f00),
+    range(0x2000, 0x2200),
+    range(0x2460, 0x24ea),
+    range(0xfb00, 0xfb07),
+    range(0xff01, 0xff5f),
+]
+
+
+def upper_half(codec):
+    for byte in range(128):
+        assert bytes([byte]).decode(codec) == chr(byte)
+    values = []
+    for byte in range(128, 256):
+        try:
+            c = bytes([byte]).decode(codec)
+        except UnicodeDecodeError:
+            values.append(0)
+            continue
+        assert len(c) == 1 and 0 < ord(c) < 0x10000
+        values.append(ord(c))
+    return values
+
+
+def translit():
+    approximations = {}
+    for r in TRANSLIT_RANGES:
+        for cp in r:
+            decomposed = unicodedata.normalize("NFKD", chr(cp))
+            base = "".join(c for c in decomposed if not unicodedata.combining(c))
+            if base and base != chr(cp) and all(0x20 <= ord(c) < 0x7f for c in base):
+                approximations[cp] = base
+    approximations.update(SPECIAL)
+    return approximations
+
+
+def main():
+    print("//! The upper halves of the 8-bit character sets, whose lower halves are ASCII. Zero stands for a")
+    print("//! byte that doesn't map to anything.")
+    print()
+    print("// Generated by tables.py from the codecs of Python's standard library, which follow the mapping")
+    print("// tables of the Unicode Consortium and the vendors")
+    print()
+    for name, codec in CHARSETS:
+        values = upper_half(codec)
+        print(f"pub const {name}: [u16; 128] = [")
+        for i in range(0, len(values), 12):
+            print("    " + " ".join(f"0x{v:04x}," for v in values[i : i + 12]))
+        print("];")
+        print()
+    print("/// Approximations in ASCII, sorted by character, for //TRANSLIT")
+    print("pub const TRANSLIT: &[(u32, &str)] = &[")
+    for cp, text in sorted(translit().items()):
+        text = text.replace("\\", "\\\\").replace('"', '\\"')
+        print(f'    (0x{cp:04x}, "{text}"),')
+    print("];")
+
+
+main()
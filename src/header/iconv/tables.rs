@@ -0,0 +1,1356 @@
+//! The upper halves of the 8-bit character sets, whose lower halves are ASCII. Zero stands for a
+//! byte that doesn't map to anything.
+
+// Generated by tables.py from the codecs of Python's standard library, which follow the mapping
+// tables of the Unicode Consortium and the vendors
+
+pub const ISO_8859_2: [u16; 128] = [
+    0x0080, 0x0081, 0x0082, 0x0083, 0x0084, 0x0085, 0x0086, 0x0087, 0x0088, 0x0089, 0x008a, 0x008b,
+    0x008c, 0x008d, 0x008e, 0x008f, 0x0090, 0x0091, 0x0092, 0x0093, 0x0094, 0x0095, 0x0096, 0x0097,
+    0x0098, 0x0099, 0x009a, 0x009b, 0x009c, 0x009d, 0x009e, 0x009f, 0x00a0, 0x0104, 0x02d8, 0x0141,
+    0x00a4, 0x013d, 0x015a, 0x00a7, 0x00a8, 0x0160, 0x015e, 0x0164, 0x0179, 0x00ad, 0x017d, 0x017b,
+    0x00b0, 0x0105, 0x02db, 0x0142, 0x00b4, 0x013e, 0x015b, 0x02c7, 0x00b8, 0x0161, 0x015f, 0x0165,
+    0x017a, 0x02dd, 0x017e, 0x017c, 0x0154, 0x00c1, 0x00c2, 0x0102, 0x00c4, 0x0139, 0x0106, 0x00c7,
+    0x010c, 0x00c9, 0x0118, 0x00cb, 0x011a, 0x00cd, 0x00ce, 0x010e, 0x0110, 0x0143, 0x0147, 0x00d3,
+    0x00d4, 0x0150, 0x00d6, 0x00d7, 0x0158, 0x016e, 0x00da, 0x0170, 0x00dc, 0x00dd, 0x0162, 0x00df,
+    0x0155, 0x00e1, 0x00e2, 0x0103, 0x00e4, 0x013a, 0x0107, 0x00e7, 0x010d, 0x00e9, 0x0119, 0x00eb,
+    0x011b, 0x00ed, 0x00ee, 0x010f, 0x0111, 0x0144, 0x0148, 0x00f3, 0x00f4, 0x0151, 0x00f6, 0x00f7,
+    0x0159, 0x016f, 0x00fa, 0x0171, 0x00fc, 0x00fd, 0x0163, 0x02d9,
+];
+
+pub const ISO_8859_3: [u16; 128] = [
+    0x0080, 0x0081, 0x0082, 0x0083, 0x0084, 0x0085, 0x0086, 0x0087, 0x0088, 0x0089, 0x008a, 0x008b,
+    0x008c, 0x008d, 0x008e, 0x008f, 0x0090, 0x0091, 0x0092, 0x0093, 0x0094, 0x0095, 0x0096, 0x0097,
+    0x0098, 0x0099, 0x009a, 0x009b, 0x009c, 0x009d, 0x009e, 0x009f, 0x00a0, 0x0126, 0x02d8, 0x00a3,
+    0x00a4, 0x0000, 0x0124, 0x00a7, 0x00a8, 0x0130, 0x015e, 0x011e, 0x0134, 0x00ad, 0x0000, 0x017b,
+    0x00b0, 0x0127, 0x00b2, 0x00b3, 0x00b4, 0x00b5, 0x0125, 0x00b7, 0x00b8, 0x0131, 0x015f, 0x011f,
+    0x0135, 0x00bd, 0x0000, 0x017c, 0x00c0, 0x00c1, 0x00c2, 0x0000, 0x00c4, 0x010a, 0x0108, 0x00c7,
+    0x00c8, 0x00c9, 0x00ca, 0x00cb, 0x00cc, 0x00cd, 0x00ce, 0x00cf, 0x0000, 0x00d1, 0x00d2, 0x00d3,
+    0x00d4, 0x0120, 0x00d6, 0x00d7, 0x011c, 0x00d9, 0x00da, 0x00db, 0x00dc, 0x016c, 0x015c, 0x00df,
+    0x00e0, 0x00e1, 0x00e2, 0x0000, 0x00e4, 0x010b, 0x0109, 0x00e7, 0x00e8, 0x00e9, 0x00ea, 0x00eb,
+    0x00ec, 0x00ed, 0x00ee, 0x00ef, 0x0000, 0x00f1, 0x00f2, 0x00f3, 0x00f4, 0x0121, 0x00f6, 0x00f7,
+    0x011d, 0x00f9, 0x00fa, 0x00fb, 0x00fc, 0x016d, 0x015d, 0x02d9,
+];
+
+pub const ISO_8859_4: [u16; 128] = [
+    0x0080, 0x0081, 0x0082, 0x0083, 0x0084, 0x0085, 0x0086, 0x0087, 0x0088, 0x0089, 0x008a, 0x008b,
+    0x008c, 0x008d, 0x008e, 0x008f, 0x0090, 0x0091, 0x0092, 0x0093, 0x0094, 0x0095, 0x0096, 0x0097,
+    0x0098, 0x0099, 0x009a, 0x009b, 0x009c, 0x009d, 0x009e, 0x009f, 0x00a0, 0x0104, 0x0138, 0x0156,
+    0x00a4, 0x0128, 0x013b, 0x00a7, 0x00a8, 0x0160, 0x0112, 0x0122, 0x0166, 0x00ad, 0x017d, 0x00af,
+    0x00b0, 0x0105, 0x02db, 0x0157, 0x00b4, 0x0129, 0x013c, 0x02c7, 0x00b8, 0x0161, 0x0113, 0x0123,
+    0x0167, 0x014a, 0x017e, 0x014b, 0x0100, 0x00c1, 0x00c2, 0x00c3, 0x00c4, 0x00c5, 0x00c6, 0x012e,
+    0x010c, 0x00c9, 0x0118, 0x00cb, 0x0116, 0x00cd, 0x00ce, 0x012a, 0x0110, 0x0145, 0x014c, 0x0136,
+    0x00d4, 0x00d5, 0x00d6, 0x00d7, 0x00d8, 0x0172, 0x00da, 0x00db, 0x00dc, 0x0168, 0x016a, 0x00df,
+    0x0101, 0x00e1, 0x00e2, 0x00e3, 0x00e4, 0x00e5, 0x00e6, 0x012f, 0x010d, 0x00e9, 0x0119, 0x00eb,
+    0x0117, 0x00ed, 0x00ee, 0x012b, 0x0111, 0x0146, 0x014d, 0x0137, 0x00f4, 0x00f5, 0x00f6, 0x00f7,
+    0x00f8, 0x0173, 0x00fa, 0x00fb, 0x00fc, 0x0169, 0x016b, 0x02d9,
+];
+
+pub const ISO_8859_5: [u16; 128] = [
+    0x0080, 0x0081, 0x0082, 0x0083, 0x0084, 0x0085, 0x0086, 0x0087, 0x0088, 0x0089, 0x008a, 0x008b,
+    0x008c, 0x008d, 0x008e, 0x008f, 0x0090, 0x0091, 0x0092, 0x0093, 0x0094, 0x0095, 0x0096, 0x0097,
+    0x0098, 0x0099, 0x009a, 0x009b, 0x009c, 0x009d, 0x009e, 0x009f, 0x00a0, 0x0401, 0x0402, 0x0403,
+    0x0404, 0x0405, 0x0406, 0x0407, 0x0408, 0x0409, 0x040a, 0x040b, 0x040c, 0x00ad, 0x040e, 0x040f,
+    0x0410, 0x0411, 0x0412, 0x0413, 0x0414, 0x0415, 0x0416, 0x0417, 0x0418, 0x0419, 0x041a, 0x041b,
+    0x041c, 0x041d, 0x041e, 0x041f, 0x0420, 0x0421, 0x0422, 0x0423, 0x0424, 0x0425, 0x0426, 0x0427,
+    0x0428, 0x0429, 0x042a, 0x042b, 0x042c, 0x042d, 0x042e, 0x042f, 0x0430, 0x0431, 0x0432, 0x0433,
+    0x0434, 0x0435, 0x0436, 0x0437, 0x0438, 0x0439, 0x043a, 0x043b, 0x043c, 0x043d, 0x043e, 0x043f,
+    0x0440, 0x0441, 0x0442, 0x0443, 0x0444, 0x0445, 0x0446, 0x0447, 0x0448, 0x0449, 0x044a, 0x044b,
+    0x044c, 0x044d, 0x044e, 0x044f, 0x2116, 0x0451, 0x0452, 0x0453, 0x0454, 0x0455, 0x0456, 0x0457,
+    0x0458, 0x0459, 0x045a, 0x045b, 0x045c, 0x00a7, 0x045e, 0x045f,
+];
+
+pub const ISO_8859_6: [u16; 128] = [
+    0x0080, 0x0081, 0x0082, 0x0083, 0x0084, 0x0085, 0x0086, 0x0087, 0x0088, 0x0089, 0x008a, 0x008b,
+    0x008c, 0x008d, 0x008e, 0x008f, 0x0090, 0x0091, 0x0092, 0x0093, 0x0094, 0x0095, 0x0096, 0x0097,
+    0x0098, 0x0099, 0x009a, 0x009b, 0x009c, 0x009d, 0x009e, 0x009f, 0x00a0, 0x0000, 0x0000, 0x0000,
+    0x00a4, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x060c, 0x00ad, 0x0000, 0x0000,
+    0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x061b,
+    0x0000, 0x0000, 0x0000, 0x061f, 0x0000, 0x0621, 0x0622, 0x0623, 0x0624, 0x0625, 0x0626, 0x0627,
+    0x0628, 0x0629, 0x062a, 0x062b, 0x062c, 0x062d, 0x062e, 0x062f, 0x0630, 0x0631, 0x0632, 0x0633,
+    0x0634, 0x0635, 0x0636, 0x0637, 0x0638, 0x0639, 0x063a, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000,
+    0x0640, 0x0641, 0x0642, 0x0643, 0x0644, 0x0645, 0x0646, 0x0647, 0x0648, 0x0649, 0x064a, 0x064b,
+    0x064c, 0x064d, 0x064e, 0x064f, 0x0650, 0x0651, 0x0652, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000,
+    0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000,
+];
+
+pub const ISO_8859_7: [u16; 128] = [
+    0x0080, 0x0081, 0x0082, 0x0083, 0x0084, 0x0085, 0x0086, 0x0087, 0x0088, 0x0089, 0x008a, 0x008b,
+    0x008c, 0x008d, 0x008e, 0x008f, 0x0090, 0x0091, 0x0092, 0x0093, 0x0094, 0x0095, 0x0096, 0x0097,
+    0x0098, 0x0099, 0x009a, 0x009b, 0x009c, 0x009d, 0x009e, 0x009f, 0x00a0, 0x2018, 0x2019, 0x00a3,
+    0x20ac, 0x20af, 0x00a6, 0x00a7, 0x00a8, 0x00a9, 0x037a, 0x00ab, 0x00ac, 0x00ad, 0x0000, 0x2015,
+    0x00b0, 0x00b1, 0x00b2, 0x00b3, 0x0384, 0x0385, 0x0386, 0x00b7, 0x0388, 0x0389, 0x038a, 0x00bb,
+    0x038c, 0x00bd, 0x038e, 0x038f, 0x0390, 0x0391, 0x0392, 0x0393, 0x0394, 0x0395, 0x0396, 0x0397,
+    0x0398, 0x0399, 0x039a, 0x039b, 0x039c, 0x039d, 0x039e, 0x039f, 0x03a0, 0x03a1, 0x0000, 0x03a3,
+    0x03a4, 0x03a5, 0x03a6, 0x03a7, 0x03a8, 0x03a9, 0x03aa, 0x03ab, 0x03ac, 0x03ad, 0x03ae, 0x03af,
+    0x03b0, 0x03b1, 0x03b2, 0x03b3, 0x03b4, 0x03b5, 0x03b6, 0x03b7, 0x03b8, 0x03b9, 0x03ba, 0x03bb,
+    0x03bc, 0x03bd, 0x03be, 0x03bf, 0x03c0, 0x03c1, 0x03c2, 0x03c3, 0x03c4, 0x03c5, 0x03c6, 0x03c7,
+    0x03c8, 0x03c9, 0x03ca, 0x03cb, 0x03cc, 0x03cd, 0x03ce, 0x0000,
+];
+
+pub const ISO_8859_8: [u16; 128] = [
+    0x0080, 0x0081, 0x0082, 0x0083, 0x0084, 0x0085, 0x0086, 0x0087, 0x0088, 0x0089, 0x008a, 0x008b,
+    0x008c, 0x008d, 0x008e, 0x008f, 0x0090, 0x0091, 0x0092, 0x0093, 0x0094, 0x0095, 0x0096, 0x0097,
+    0x0098, 0x0099, 0x009a, 0x009b, 0x009c, 0x009d, 0x009e, 0x009f, 0x00a0, 0x0000, 0x00a2, 0x00a3,
+    0x00a4, 0x00a5, 0x00a6, 0x00a7, 0x00a8, 0x00a9, 0x00d7, 0x00ab, 0x00ac, 0x00ad, 0x00ae, 0x00af,
+    0x00b0, 0x00b1, 0x00b2, 0x00b3, 0x00b4, 0x00b5, 0x00b6, 0x00b7, 0x00b8, 0x00b9, 0x00f7, 0x00bb,
+    0x00bc, 0x00bd, 0x00be, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000,
+    0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000,
+    0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x2017,
+    0x05d0, 0x05d1, 0x05d2, 0x05d3, 0x05d4, 0x05d5, 0x05d6, 0x05d7, 0x05d8, 0x05d9, 0x05da, 0x05db,
+    0x05dc, 0x05dd, 0x05de, 0x05df, 0x05e0, 0x05e1, 0x05e2, 0x05e3, 0x05e4, 0x05e5, 0x05e6, 0x05e7,
+    0x05e8, 0x05e9, 0x05ea, 0x0000, 0x0000, 0x200e, 0x200f, 0x0000,
+];
+
+pub const ISO_8859_9: [u16; 128] = [
+    0x0080, 0x0081, 0x0082, 0x0083, 0x0084, 0x0085, 0x0086, 0x0087, 0x0088, 0x0089, 0x008a, 0x008b,
+    0x008c, 0x008d, 0x008e, 0x008f, 0x0090, 0x0091, 0x0092, 0x0093, 0x0094, 0x0095, 0x0096, 0x0097,
+    0x0098, 0x0099, 0x009a, 0x009b, 0x009c, 0x009d, 0x009e, 0x009f, 0x00a0, 0x00a1, 0x00a2, 0x00a3,
+    0x00a4, 0x00a5, 0x00a6, 0x00a7, 0x00a8, 0x00a9, 0x00aa, 0x00ab, 0x00ac, 0x00ad, 0x00ae, 0x00af,
+    0x00b0, 0x00b1, 0x00b2, 0x00b3, 0x00b4, 0x00b5, 0x00b6, 0x00b7, 0x00b8, 0x00b9, 0x00ba, 0x00bb,
+    0x00bc, 0x00bd, 0x00be, 0x00bf, 0x00c0, 0x00c1, 0x00c2, 0x00c3, 0x00c4, 0x00c5, 0x00c6, 0x00c7,
+    0x00c8, 0x00c9, 0x00ca, 0x00cb, 0x00cc, 0x00cd, 0x00ce, 0x00cf, 0x011e, 0x00d1, 0x00d2, 0x00d3,
+    0x00d4, 0x00d5, 0x00d6, 0x00d7, 0x00d8, 0x00d9, 0x00da, 0x00db, 0x00dc, 0x0130, 0x015e, 0x00df,
+    0x00e0, 0x00e1, 0x00e2, 0x00e3, 0x00e4, 0x00e5, 0x00e6, 0x00e7, 0x00e8, 0x00e9, 0x00ea, 0x00eb,
+    0x00ec, 0x00ed, 0x00ee, 0x00ef, 0x011f, 0x00f1, 0x00f2, 0x00f3, 0x00f4, 0x00f5, 0x00f6, 0x00f7,
+    0x00f8, 0x00f9, 0x00fa, 0x00fb, 0x00fc, 0x0131, 0x015f, 0x00ff,
+];
+
+pub const ISO_8859_10: [u16; 128] = [
+    0x0080, 0x0081, 0x0082, 0x0083, 0x0084, 0x0085, 0x0086, 0x0087, 0x0088, 0x0089, 0x008a, 0x008b,
+    0x008c, 0x008d, 0x008e, 0x008f, 0x0090, 0x0091, 0x0092, 0x0093, 0x0094, 0x0095, 0x0096, 0x0097,
+    0x0098, 0x0099, 0x009a, 0x009b, 0x009c, 0x009d, 0x009e, 0x009f, 0x00a0, 0x0104, 0x0112, 0x0122,
+    0x012a, 0x0128, 0x0136, 0x00a7, 0x013b, 0x0110, 0x0160, 0x0166, 0x017d, 0x00ad, 0x016a, 0x014a,
+    0x00b0, 0x0105, 0x0113, 0x0123, 0x012b, 0x0129, 0x0137, 0x00b7, 0x013c, 0x0111, 0x0161, 0x0167,
+    0x017e, 0x2015, 0x016b, 0x014b, 0x0100, 0x00c1, 0x00c2, 0x00c3, 0x00c4, 0x00c5, 0x00c6, 0x012e,
+    0x010c, 0x00c9, 0x0118, 0x00cb, 0x0116, 0x00cd, 0x00ce, 0x00cf, 0x00d0, 0x0145, 0x014c, 0x00d3,
+    0x00d4, 0x00d5, 0x00d6, 0x0168, 0x00d8, 0x0172, 0x00da, 0x00db, 0x00dc, 0x00dd, 0x00de, 0x00df,
+    0x0101, 0x00e1, 0x00e2, 0x00e3, 0x00e4, 0x00e5, 0x00e6, 0x012f, 0x010d, 0x00e9, 0x0119, 0x00eb,
+    0x0117, 0x00ed, 0x00ee, 0x00ef, 0x00f0, 0x0146, 0x014d, 0x00f3, 0x00f4, 0x00f5, 0x00f6, 0x0169,
+    0x00f8, 0x0173, 0x00fa, 0x00fb, 0x00fc, 0x00fd, 0x00fe, 0x0138,
+];
+
+pub const ISO_8859_13: [u16; 128] = [
+    0x0080, 0x0081, 0x0082, 0x0083, 0x0084, 0x0085, 0x0086, 0x0087, 0x0088, 0x0089, 0x008a, 0x008b,
+    0x008c, 0x008d, 0x008e, 0x008f, 0x0090, 0x0091, 0x0092, 0x0093, 0x0094, 0x0095, 0x0096, 0x0097,
+    0x0098, 0x0099, 0x009a, 0x009b, 0x009c, 0x009d, 0x009e, 0x009f, 0x00a0, 0x201d, 0x00a2, 0x00a3,
+    0x00a4, 0x201e, 0x00a6, 0x00a7, 0x00d8, 0x00a9, 0x0156, 0x00ab, 0x00ac, 0x00ad, 0x00ae, 0x00c6,
+    0x00b0, 0x00b1, 0x00b2, 0x00b3, 0x201c, 0x00b5, 0x00b6, 0x00b7, 0x00f8, 0x00b9, 0x0157, 0x00bb,
+    0x00bc, 0x00bd, 0x00be, 0x00e6, 0x0104, 0x012e, 0x0100, 0x0106, 0x00c4, 0x00c5, 0x0118, 0x0112,
+    0x010c, 0x00c9, 0x0179, 0x0116, 0x0122, 0x0136, 0x012a, 0x013b, 0x0160, 0x0143, 0x0145, 0x00d3,
+    0x014c, 0x00d5, 0x00d6, 0x00d7, 0x0172, 0x0141, 0x015a, 0x016a, 0x00dc, 0x017b, 0x017d, 0x00df,
+    0x0105, 0x012f, 0x0101, 0x0107, 0x00e4, 0x00e5, 0x0119, 0x0113, 0x010d, 0x00e9, 0x017a, 0x0117,
+    0x0123, 0x0137, 0x012b, 0x013c, 0x0161, 0x0144, 0x0146, 0x00f3, 0x014d, 0x00f5, 0x00f6, 0x00f7,
+    0x0173, 0x0142, 0x015b, 0x016b, 0x00fc, 0x017c, 0x017e, 0x2019,
+];
+
+pub const ISO_8859_14: [u16; 128] = [
+    0x0080, 0x0081, 0x0082, 0x0083, 0x0084, 0x0085, 0x0086, 0x0087, 0x0088, 0x0089, 0x008a, 0x008b,
+    0x008c, 0x008d, 0x008e, 0x008f, 0x0090, 0x0091, 0x0092, 0x0093, 0x0094, 0x0095, 0x0096, 0x0097,
+    0x0098, 0x0099, 0x009a, 0x009b, 0x009c, 0x009d, 0x009e, 0x009f, 0x00a0, 0x1e02, 0x1e03, 0x00a3,
+    0x010a, 0x010b, 0x1e0a, 0x00a7, 0x1e80, 0x00a9, 0x1e82, 0x1e0b, 0x1ef2, 0x00ad, 0x00ae, 0x0178,
+    0x1e1e, 0x1e1f, 0x0120, 0x0121, 0x1e40, 0x1e41, 0x00b6, 0x1e56, 0x1e81, 0x1e57, 0x1e83, 0x1e60,
+    0x1ef3, 0x1e84, 0x1e85, 0x1e61, 0x00c0, 0x00c1, 0x00c2, 0x00c3, 0x00c4, 0x00c5, 0x00c6, 0x00c7,
+    0x00c8, 0x00c9, 0x00ca, 0x00cb, 0x00cc, 0x00cd, 0x00ce, 0x00cf, 0x0174, 0x00d1, 0x00d2, 0x00d3,
+    0x00d4, 0x00d5, 0x00d6, 0x1e6a, 0x00d8, 0x00d9, 0x00da, 0x00db, 0x00dc, 0x00dd, 0x0176, 0x00df,
+    0x00e0, 0x00e1, 0x00e2, 0x00e3, 0x00e4, 0x00e5, 0x00e6, 0x00e7, 0x00e8, 0x00e9, 0x00ea, 0x00eb,
+    0x00ec, 0x00ed, 0x00ee, 0x00ef, 0x0175, 0x00f1, 0x00f2, 0x00f3, 0x00f4, 0x00f5, 0x00f6, 0x1e6b,
+    0x00f8, 0x00f9, 0x00fa, 0x00fb, 0x00fc, 0x00fd, 0x0177, 0x00ff,
+];
+
+pub const ISO_8859_15: [u16; 128] = [
+    0x0080, 0x0081, 0x0082, 0x0083, 0x0084, 0x0085, 0x0086, 0x0087, 0x0088, 0x0089, 0x008a, 0x008b,
+    0x008c, 0x008d, 0x008e, 0x008f, 0x0090, 0x0091, 0x0092, 0x0093, 0x0094, 0x0095, 0x0096, 0x0097,
+    0x0098, 0x0099, 0x009a, 0x009b, 0x009c, 0x009d, 0x009e, 0x009f, 0x00a0, 0x00a1, 0x00a2, 0x00a3,
+    0x20ac, 0x00a5, 0x0160, 0x00a7, 0x0161, 0x00a9, 0x00aa, 0x00ab, 0x00ac, 0x00ad, 0x00ae, 0x00af,
+    0x00b0, 0x00b1, 0x00b2, 0x00b3, 0x017d, 0x00b5, 0x00b6, 0x00b7, 0x017e, 0x00b9, 0x00ba, 0x00bb,
+    0x0152, 0x0153, 0x0178, 0x00bf, 0x00c0, 0x00c1, 0x00c2, 0x00c3, 0x00c4, 0x00c5, 0x00c6, 0x00c7,
+    0x00c8, 0x00c9, 0x00ca, 0x00cb, 0x00cc, 0x00cd, 0x00ce, 0x00cf, 0x00d0, 0x00d1, 0x00d2, 0x00d3,
+    0x00d4, 0x00d5, 0x00d6, 0x00d7, 0x00d8, 0x00d9, 0x00da, 0x00db, 0x00dc, 0x00dd, 0x00de, 0x00df,
+    0x00e0, 0x00e1, 0x00e2, 0x00e3, 0x00e4, 0x00e5, 0x00e6, 0x00e7, 0x00e8, 0x00e9, 0x00ea, 0x00eb,
+    0x00ec, 0x00ed, 0x00ee, 0x00ef, 0x00f0, 0x00f1, 0x00f2, 0x00f3, 0x00f4, 0x00f5, 0x00f6, 0x00f7,
+    0x00f8, 0x00f9, 0x00fa, 0x00fb, 0x00fc, 0x00fd, 0x00fe, 0x00ff,
+];
+
+pub const ISO_8859_16: [u16; 128] = [
+    0x0080, 0x0081, 0x0082, 0x0083, 0x0084, 0x0085, 0x0086, 0x0087, 0x0088, 0x0089, 0x008a, 0x008b,
+    0x008c, 0x008d, 0x008e, 0x008f, 0x0090, 0x0091, 0x0092, 0x0093, 0x0094, 0x0095, 0x0096, 0x0097,
+    0x0098, 0x0099, 0x009a, 0x009b, 0x009c, 0x009d, 0x009e, 0x009f, 0x00a0, 0x0104, 0x0105, 0x0141,
+    0x20ac, 0x201e, 0x0160, 0x00a7, 0x0161, 0x00a9, 0x0218, 0x00ab, 0x0179, 0x00ad, 0x017a, 0x017b,
+    0x00b0, 0x00b1, 0x010c, 0x0142, 0x017d, 0x201d, 0x00b6, 0x00b7, 0x017e, 0x010d, 0x0219, 0x00bb,
+    0x0152, 0x0153, 0x0178, 0x017c, 0x00c0, 0x00c1, 0x00c2, 0x0102, 0x00c4, 0x0106, 0x00c6, 0x00c7,
+    0x00c8, 0x00c9, 0x00ca, 0x00cb, 0x00cc, 0x00cd, 0x00ce, 0x00cf, 0x0110, 0x0143, 0x00d2, 0x00d3,
+    0x00d4, 0x0150, 0x00d6, 0x015a, 0x0170, 0x00d9, 0x00da, 0x00db, 0x00dc, 0x0118, 0x021a, 0x00df,
+    0x00e0, 0x00e1, 0x00e2, 0x0103, 0x00e4, 0x0107, 0x00e6, 0x00e7, 0x00e8, 0x00e9, 0x00ea, 0x00eb,
+    0x00ec, 0x00ed, 0x00ee, 0x00ef, 0x0111, 0x0144, 0x00f2, 0x00f3, 0x00f4, 0x0151, 0x00f6, 0x015b,
+    0x0171, 0x00f9, 0x00fa, 0x00fb, 0x00fc, 0x0119, 0x021b, 0x00ff,
+];
+
+pub const KOI8_R: [u16; 128] = [
+    0x2500, 0x2502, 0x250c, 0x2510, 0x2514, 0x2518, 0x251c, 0x2524, 0x252c, 0x2534, 0x253c, 0x2580,
+    0x2584, 0x2588, 0x258c, 0x2590, 0x2591, 0x2592, 0x2593, 0x2320, 0x25a0, 0x2219, 0x221a, 0x2248,
+    0x2264, 0x2265, 0x00a0, 0x2321, 0x00b0, 0x00b2, 0x00b7, 0x00f7, 0x2550, 0x2551, 0x2552, 0x0451,
+    0x2553, 0x2554, 0x2555, 0x2556, 0x2557, 0x2558, 0x2559, 0x255a, 0x255b, 0x255c, 0x255d, 0x255e,
+    0x255f, 0x2560, 0x2561, 0x0401, 0x2562, 0x2563, 0x2564, 0x2565, 0x2566, 0x2567, 0x2568, 0x2569,
+    0x256a, 0x256b, 0x256c, 0x00a9, 0x044e, 0x0430, 0x0431, 0x0446, 0x0434, 0x0435, 0x0444, 0x0433,
+    0x0445, 0x0438, 0x0439, 0x043a, 0x043b, 0x043c, 0x043d, 0x043e, 0x043f, 0x044f, 0x0440, 0x0441,
+    0x0442, 0x0443, 0x0436, 0x0432, 0x044c, 0x044b, 0x0437, 0x0448, 0x044d, 0x0449, 0x0447, 0x044a,
+    0x042e, 0x0410, 0x0411, 0x0426, 0x0414, 0x0415, 0x0424, 0x0413, 0x0425, 0x0418, 0x0419, 0x041a,
+    0x041b, 0x041c, 0x041d, 0x041e, 0x041f, 0x042f, 0x0420, 0x0421, 0x0422, 0x0423, 0x0416, 0x0412,
+    0x042c, 0x042b, 0x0417, 0x0428, 0x042d, 0x0429, 0x0427, 0x042a,
+];
+
+pub const KOI8_U: [u16; 128] = [
+    0x2500, 0x2502, 0x250c, 0x2510, 0x2514, 0x2518, 0x251c, 0x2524, 0x252c, 0x2534, 0x253c, 0x2580,
+    0x2584, 0x2588, 0x258c, 0x2590, 0x2591, 0x2592, 0x2593, 0x2320, 0x25a0, 0x2219, 0x221a, 0x2248,
+    0x2264, 0x2265, 0x00a0, 0x2321, 0x00b0, 0x00b2, 0x00b7, 0x00f7, 0x2550, 0x2551, 0x2552, 0x0451,
+    0x0454, 0x2554, 0x0456, 0x0457, 0x2557, 0x2558, 0x2559, 0x255a, 0x255b, 0x0491, 0x255d, 0x255e,
+    0x255f, 0x2560, 0x2561, 0x0401, 0x0404, 0x2563, 0x0406, 0x0407, 0x2566, 0x2567, 0x2568, 0x2569,
+    0x256a, 0x0490, 0x256c, 0x00a9, 0x044e, 0x0430, 0x0431, 0x0446, 0x0434, 0x0435, 0x0444, 0x0433,
+    0x0445, 0x0438, 0x0439, 0x043a, 0x043b, 0x043c, 0x043d, 0x043e, 0x043f, 0x044f, 0x0440, 0x0441,
+    0x0442, 0x0443, 0x0436, 0x0432, 0x044c, 0x044b, 0x0437, 0x0448, 0x044d, 0x0449, 0x0447, 0x044a,
+    0x042e, 0x0410, 0x0411, 0x0426, 0x0414, 0x0415, 0x0424, 0x0413, 0x0425, 0x0418, 0x0419, 0x041a,
+    0x041b, 0x041c, 0x041d, 0x041e, 0x041f, 0x042f, 0x0420, 0x0421, 0x0422, 0x0423, 0x0416, 0x0412,
+    0x042c, 0x042b, 0x0417, 0x0428, 0x042d, 0x0429, 0x0427, 0x042a,
+];
+
+pub const CP437: [u16; 128] = [
+    0x00c7, 0x00fc, 0x00e9, 0x00e2, 0x00e4, 0x00e0, 0x00e5, 0x00e7, 0x00ea, 0x00eb, 0x00e8, 0x00ef,
+    0x00ee, 0x00ec, 0x00c4, 0x00c5, 0x00c9, 0x00e6, 0x00c6, 0x00f4, 0x00f6, 0x00f2, 0x00fb, 0x00f9,
+    0x00ff, 0x00d6, 0x00dc, 0x00a2, 0x00a3, 0x00a5, 0x20a7, 0x0192, 0x00e1, 0x00ed, 0x00f3, 0x00fa,
+    0x00f1, 0x00d1, 0x00aa, 0x00ba, 0x00bf, 0x2310, 0x00ac, 0x00bd, 0x00bc, 0x00a1, 0x00ab, 0x00bb,
+    0x2591, 0x2592, 0x2593, 0x2502, 0x2524, 0x2561, 0x2562, 0x2556, 0x2555, 0x2563, 0x2551, 0x2557,
+    0x255d, 0x255c, 0x255b, 0x2510, 0x2514, 0x2534, 0x252c, 0x251c, 0x2500, 0x253c, 0x255e, 0x255f,
+    0x255a, 0x2554, 0x2569, 0x2566, 0x2560, 0x2550, 0x256c, 0x2567, 0x2568, 0x2564, 0x2565, 0x2559,
+    0x2558, 0x2552, 0x2553, 0x256b, 0x256a, 0x2518, 0x250c, 0x2588, 0x2584, 0x258c, 0x2590, 0x2580,
+    0x03b1, 0x00df, 0x0393, 0x03c0, 0x03a3, 0x03c3, 0x00b5, 0x03c4, 0x03a6, 0x0398, 0x03a9, 0x03b4,
+    0x221e, 0x03c6, 0x03b5, 0x2229, 0x2261, 0x00b1, 0x2265, 0x2264, 0x2320, 0x2321, 0x00f7, 0x2248,
+    0x00b0, 0x2219, 0x00b7, 0x221a, 0x207f, 0x00b2, 0x25a0, 0x00a0,
+];
+
+pub const CP850: [u16; 128] = [
+    0x00c7, 0x00fc, 0x00e9, 0x00e2, 0x00e4, 0x00e0, 0x00e5, 0x00e7, 0x00ea, 0x00eb, 0x00e8, 0x00ef,
+    0x00ee, 0x00ec, 0x00c4, 0x00c5, 0x00c9, 0x00e6, 0x00c6, 0x00f4, 0x00f6, 0x00f2, 0x00fb, 0x00f9,
+    0x00ff, 0x00d6, 0x00dc, 0x00f8, 0x00a3, 0x00d8, 0x00d7, 0x0192, 0x00e1, 0x00ed, 0x00f3, 0x00fa,
+    0x00f1, 0x00d1, 0x00aa, 0x00ba, 0x00bf, 0x00ae, 0x00ac, 0x00bd, 0x00bc, 0x00a1, 0x00ab, 0x00bb,
+    0x2591, 0x2592, 0x2593, 0x2502, 0x2524, 0x00c1, 0x00c2, 0x00c0, 0x00a9, 0x2563, 0x2551, 0x2557,
+    0x255d, 0x00a2, 0x00a5, 0x2510, 0x2514, 0x2534, 0x252c, 0x251c, 0x2500, 0x253c, 0x00e3, 0x00c3,
+    0x255a, 0x2554, 0x2569, 0x2566, 0x2560, 0x2550, 0x256c, 0x00a4, 0x00f0, 0x00d0, 0x00ca, 0x00cb,
+    0x00c8, 0x0131, 0x00cd, 0x00ce, 0x00cf, 0x2518, 0x250c, 0x2588, 0x2584, 0x00a6, 0x00cc, 0x2580,
+    0x00d3, 0x00df, 0x00d4, 0x00d2, 0x00f5, 0x00d5, 0x00b5, 0x00fe, 0x00de, 0x00da, 0x00db, 0x00d9,
+    0x00fd, 0x00dd, 0x00af, 0x00b4, 0x00ad, 0x00b1, 0x2017, 0x00be, 0x00b6, 0x00a7, 0x00f7, 0x00b8,
+    0x00b0, 0x00a8, 0x00b7, 0x00b9, 0x00b3, 0x00b2, 0x25a0, 0x00a0,
+];
+
+pub const CP866: [u16; 128] = [
+    0x0410, 0x0411, 0x0412, 0x0413, 0x0414, 0x0415, 0x0416, 0x0417, 0x0418, 0x0419, 0x041a, 0x041b,
+    0x041c, 0x041d, 0x041e, 0x041f, 0x0420, 0x0421, 0x0422, 0x0423, 0x0424, 0x0425, 0x0426, 0x0427,
+    0x0428, 0x0429, 0x042a, 0x042b, 0x042c, 0x042d, 0x042e, 0x042f, 0x0430, 0x0431, 0x0432, 0x0433,
+    0x0434, 0x0435, 0x0436, 0x0437, 0x0438, 0x0439, 0x043a, 0x043b, 0x043c, 0x043d, 0x043e, 0x043f,
+    0x2591, 0x2592, 0x2593, 0x2502, 0x2524, 0x2561, 0x2562, 0x2556, 0x2555, 0x2563, 0x2551, 0x2557,
+    0x255d, 0x255c, 0x255b, 0x2510, 0x2514, 0x2534, 0x252c, 0x251c, 0x2500, 0x253c, 0x255e, 0x255f,
+    0x255a, 0x2554, 0x2569, 0x2566, 0x2560, 0x2550, 0x256c, 0x2567, 0x2568, 0x2564, 0x2565, 0x2559,
+    0x2558, 0x2552, 0x2553, 0x256b, 0x256a, 0x2518, 0x250c, 0x2588, 0x2584, 0x258c, 0x2590, 0x2580,
+    0x0440, 0x0441, 0x0442, 0x0443, 0x0444, 0x0445, 0x0446, 0x0447, 0x0448, 0x0449, 0x044a, 0x044b,
+    0x044c, 0x044d, 0x044e, 0x044f, 0x0401, 0x0451, 0x0404, 0x0454, 0x0407, 0x0457, 0x040e, 0x045e,
+    0x00b0, 0x2219, 0x00b7, 0x221a, 0x2116, 0x00a4, 0x25a0, 0x00a0,
+];
+
+pub const CP1250: [u16; 128] = [
+    0x20ac, 0x0000, 0x201a, 0x0000, 0x201e, 0x2026, 0x2020, 0x2021, 0x0000, 0x2030, 0x0160, 0x2039,
+    0x015a, 0x0164, 0x017d, 0x0179, 0x0000, 0x2018, 0x2019, 0x201c, 0x201d, 0x2022, 0x2013, 0x2014,
+    0x0000, 0x2122, 0x0161, 0x203a, 0x015b, 0x0165, 0x017e, 0x017a, 0x00a0, 0x02c7, 0x02d8, 0x0141,
+    0x00a4, 0x0104, 0x00a6, 0x00a7, 0x00a8, 0x00a9, 0x015e, 0x00ab, 0x00ac, 0x00ad, 0x00ae, 0x017b,
+    0x00b0, 0x00b1, 0x02db, 0x0142, 0x00b4, 0x00b5, 0x00b6, 0x00b7, 0x00b8, 0x0105, 0x015f, 0x00bb,
+    0x013d, 0x02dd, 0x013e, 0x017c, 0x0154, 0x00c1, 0x00c2, 0x0102, 0x00c4, 0x0139, 0x0106, 0x00c7,
+    0x010c, 0x00c9, 0x0118, 0x00cb, 0x011a, 0x00cd, 0x00ce, 0x010e, 0x0110, 0x0143, 0x0147, 0x00d3,
+    0x00d4, 0x0150, 0x00d6, 0x00d7, 0x0158, 0x016e, 0x00da, 0x0170, 0x00dc, 0x00dd, 0x0162, 0x00df,
+    0x0155, 0x00e1, 0x00e2, 0x0103, 0x00e4, 0x013a, 0x0107, 0x00e7, 0x010d, 0x00e9, 0x0119, 0x00eb,
+    0x011b, 0x00ed, 0x00ee, 0x010f, 0x0111, 0x0144, 0x0148, 0x00f3, 0x00f4, 0x0151, 0x00f6, 0x00f7,
+    0x0159, 0x016f, 0x00fa, 0x0171, 0x00fc, 0x00fd, 0x0163, 0x02d9,
+];
+
+pub const CP1251: [u16; 128] = [
+    0x0402, 0x0403, 0x201a, 0x0453, 0x201e, 0x2026, 0x2020, 0x2021, 0x20ac, 0x2030, 0x0409, 0x2039,
+    0x040a, 0x040c, 0x040b, 0x040f, 0x0452, 0x2018, 0x2019, 0x201c, 0x201d, 0x2022, 0x2013, 0x2014,
+    0x0000, 0x2122, 0x0459, 0x203a, 0x045a, 0x045c, 0x045b, 0x045f, 0x00a0, 0x040e, 0x045e, 0x0408,
+    0x00a4, 0x0490, 0x00a6, 0x00a7, 0x0401, 0x00a9, 0x0404, 0x00ab, 0x00ac, 0x00ad, 0x00ae, 0x0407,
+    0x00b0, 0x00b1, 0x0406, 0x0456, 0x0491, 0x00b5, 0x00b6, 0x00b7, 0x0451, 0x2116, 0x0454, 0x00bb,
+    0x0458, 0x0405, 0x0455, 0x0457, 0x0410, 0x0411, 0x0412, 0x0413, 0x0414, 0x0415, 0x0416, 0x0417,
+    0x0418, 0x0419, 0x041a, 0x041b, 0x041c, 0x041d, 0x041e, 0x041f, 0x0420, 0x0421, 0x0422, 0x0423,
+    0x0424, 0x0425, 0x0426, 0x0427, 0x0428, 0x0429, 0x042a, 0x042b, 0x042c, 0x042d, 0x042e, 0x042f,
+    0x0430, 0x0431, 0x0432, 0x0433, 0x0434, 0x0435, 0x0436, 0x0437, 0x0438, 0x0439, 0x043a, 0x043b,
+    0x043c, 0x043d, 0x043e, 0x043f, 0x0440, 0x0441, 0x0442, 0x0443, 0x0444, 0x0445, 0x0446, 0x0447,
+    0x0448, 0x0449, 0x044a, 0x044b, 0x044c, 0x044d, 0x044e, 0x044f,
+];
+
+pub const CP1252: [u16; 128] = [
+    0x20ac, 0x0000, 0x201a, 0x0192, 0x201e, 0x2026, 0x2020, 0x2021, 0x02c6, 0x2030, 0x0160, 0x2039,
+    0x0152, 0x0000, 0x017d, 0x0000, 0x0000, 0x2018, 0x2019, 0x201c, 0x201d, 0x2022, 0x2013, 0x2014,
+    0x02dc, 0x2122, 0x0161, 0x203a, 0x0153, 0x0000, 0x017e, 0x0178, 0x00a0, 0x00a1, 0x00a2, 0x00a3,
+    0x00a4, 0x00a5, 0x00a6, 0x00a7, 0x00a8, 0x00a9, 0x00aa, 0x00ab, 0x00ac, 0x00ad, 0x00ae, 0x00af,
+    0x00b0, 0x00b1, 0x00b2, 0x00b3, 0x00b4, 0x00b5, 0x00b6, 0x00b7, 0x00b8, 0x00b9, 0x00ba, 0x00bb,
+    0x00bc, 0x00bd, 0x00be, 0x00bf, 0x00c0, 0x00c1, 0x00c2, 0x00c3, 0x00c4, 0x00c5, 0x00c6, 0x00c7,
+    0x00c8, 0x00c9, 0x00ca, 0x00cb, 0x00cc, 0x00cd, 0x00ce, 0x00cf, 0x00d0, 0x00d1, 0x00d2, 0x00d3,
+    0x00d4, 0x00d5, 0x00d6, 0x00d7, 0x00d8, 0x00d9, 0x00da, 0x00db, 0x00dc, 0x00dd, 0x00de, 0x00df,
+    0x00e0, 0x00e1, 0x00e2, 0x00e3, 0x00e4, 0x00e5, 0x00e6, 0x00e7, 0x00e8, 0x00e9, 0x00ea, 0x00eb,
+    0x00ec, 0x00ed, 0x00ee, 0x00ef, 0x00f0, 0x00f1, 0x00f2, 0x00f3, 0x00f4, 0x00f5, 0x00f6, 0x00f7,
+    0x00f8, 0x00f9, 0x00fa, 0x00fb, 0x00fc, 0x00fd, 0x00fe, 0x00ff,
+];
+
+pub const CP1253: [u16; 128] = [
+    0x20ac, 0x0000, 0x201a, 0x0192, 0x201e, 0x2026, 0x2020, 0x2021, 0x0000, 0x2030, 0x0000, 0x2039,
+    0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x2018, 0x2019, 0x201c, 0x201d, 0x2022, 0x2013, 0x2014,
+    0x0000, 0x2122, 0x0000, 0x203a, 0x0000, 0x0000, 0x0000, 0x0000, 0x00a0, 0x0385, 0x0386, 0x00a3,
+    0x00a4, 0x00a5, 0x00a6, 0x00a7, 0x00a8, 0x00a9, 0x0000, 0x00ab, 0x00ac, 0x00ad, 0x00ae, 0x2015,
+    0x00b0, 0x00b1, 0x00b2, 0x00b3, 0x0384, 0x00b5, 0x00b6, 0x00b7, 0x0388, 0x0389, 0x038a, 0x00bb,
+    0x038c, 0x00bd, 0x038e, 0x038f, 0x0390, 0x0391, 0x0392, 0x0393, 0x0394, 0x0395, 0x0396, 0x0397,
+    0x0398, 0x0399, 0x039a, 0x039b, 0x039c, 0x039d, 0x039e, 0x039f, 0x03a0, 0x03a1, 0x0000, 0x03a3,
+    0x03a4, 0x03a5, 0x03a6, 0x03a7, 0x03a8, 0x03a9, 0x03aa, 0x03ab, 0x03ac, 0x03ad, 0x03ae, 0x03af,
+    0x03b0, 0x03b1, 0x03b2, 0x03b3, 0x03b4, 0x03b5, 0x03b6, 0x03b7, 0x03b8, 0x03b9, 0x03ba, 0x03bb,
+    0x03bc, 0x03bd, 0x03be, 0x03bf, 0x03c0, 0x03c1, 0x03c2, 0x03c3, 0x03c4, 0x03c5, 0x03c6, 0x03c7,
+    0x03c8, 0x03c9, 0x03ca, 0x03cb, 0x03cc, 0x03cd, 0x03ce, 0x0000,
+];
+
+pub const CP1254: [u16; 128] = [
+    0x20ac, 0x0000, 0x201a, 0x0192, 0x201e, 0x2026, 0x2020, 0x2021, 0x02c6, 0x2030, 0x0160, 0x2039,
+    0x0152, 0x0000, 0x0000, 0x0000, 0x0000, 0x2018, 0x2019, 0x201c, 0x201d, 0x2022, 0x2013, 0x2014,
+    0x02dc, 0x2122, 0x0161, 0x203a, 0x0153, 0x0000, 0x0000, 0x0178, 0x00a0, 0x00a1, 0x00a2, 0x00a3,
+    0x00a4, 0x00a5, 0x00a6, 0x00a7, 0x00a8, 0x00a9, 0x00aa, 0x00ab, 0x00ac, 0x00ad, 0x00ae, 0x00af,
+    0x00b0, 0x00b1, 0x00b2, 0x00b3, 0x00b4, 0x00b5, 0x00b6, 0x00b7, 0x00b8, 0x00b9, 0x00ba, 0x00bb,
+    0x00bc, 0x00bd, 0x00be, 0x00bf, 0x00c0, 0x00c1, 0x00c2, 0x00c3, 0x00c4, 0x00c5, 0x00c6, 0x00c7,
+    0x00c8, 0x00c9, 0x00ca, 0x00cb, 0x00cc, 0x00cd, 0x00ce, 0x00cf, 0x011e, 0x00d1, 0x00d2, 0x00d3,
+    0x00d4, 0x00d5, 0x00d6, 0x00d7, 0x00d8, 0x00d9, 0x00da, 0x00db, 0x00dc, 0x0130, 0x015e, 0x00df,
+    0x00e0, 0x00e1, 0x00e2, 0x00e3, 0x00e4, 0x00e5, 0x00e6, 0x00e7, 0x00e8, 0x00e9, 0x00ea, 0x00eb,
+    0x00ec, 0x00ed, 0x00ee, 0x00ef, 0x011f, 0x00f1, 0x00f2, 0x00f3, 0x00f4, 0x00f5, 0x00f6, 0x00f7,
+    0x00f8, 0x00f9, 0x00fa, 0x00fb, 0x00fc, 0x0131, 0x015f, 0x00ff,
+];
+
+pub const CP1255: [u16; 128] = [
+    0x20ac, 0x0000, 0x201a, 0x0192, 0x201e, 0x2026, 0x2020, 0x2021, 0x02c6, 0x2030, 0x0000, 0x2039,
+    0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x2018, 0x2019, 0x201c, 0x201d, 0x2022, 0x2013, 0x2014,
+    0x02dc, 0x2122, 0x0000, 0x203a, 0x0000, 0x0000, 0x0000, 0x0000, 0x00a0, 0x00a1, 0x00a2, 0x00a3,
+    0x20aa, 0x00a5, 0x00a6, 0x00a7, 0x00a8, 0x00a9, 0x00d7, 0x00ab, 0x00ac, 0x00ad, 0x00ae, 0x00af,
+    0x00b0, 0x00b1, 0x00b2, 0x00b3, 0x00b4, 0x00b5, 0x00b6, 0x00b7, 0x00b8, 0x00b9, 0x00f7, 0x00bb,
+    0x00bc, 0x00bd, 0x00be, 0x00bf, 0x05b0, 0x05b1, 0x05b2, 0x05b3, 0x05b4, 0x05b5, 0x05b6, 0x05b7,
+    0x05b8, 0x05b9, 0x0000, 0x05bb, 0x05bc, 0x05bd, 0x05be, 0x05bf, 0x05c0, 0x05c1, 0x05c2, 0x05c3,
+    0x05f0, 0x05f1, 0x05f2, 0x05f3, 0x05f4, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000, 0x0000,
+    0x05d0, 0x05d1, 0x05d2, 0x05d3, 0x05d4, 0x05d5, 0x05d6, 0x05d7, 0x05d8, 0x05d9, 0x05da, 0x05db,
+    0x05dc, 0x05dd, 0x05de, 0x05df, 0x05e0, 0x05e1, 0x05e2, 0x05e3, 0x05e4, 0x05e5, 0x05e6, 0x05e7,
+    0x05e8, 0x05e9, 0x05ea, 0x0000, 0x0000, 0x200e, 0x200f, 0x0000,
+];
+
+pub const CP1256: [u16; 128] = [
+    0x20ac, 0x067e, 0x201a, 0x0192, 0x201e, 0x2026, 0x2020, 0x2021, 0x02c6, 0x2030, 0x0679, 0x2039,
+    0x0152, 0x0686, 0x0698, 0x0688, 0x06af, 0x2018, 0x2019, 0x201c, 0x201d, 0x2022, 0x2013, 0x2014,
+    0x06a9, 0x2122, 0x0691, 0x203a, 0x0153, 0x200c, 0x200d, 0x06ba, 0x00a0, 0x060c, 0x00a2, 0x00a3,
+    0x00a4, 0x00a5, 0x00a6, 0x00a7, 0x00a8, 0x00a9, 0x06be, 0x00ab, 0x00ac, 0x00ad, 0x00ae, 0x00af,
+    0x00b0, 0x00b1, 0x00b2, 0x00b3, 0x00b4, 0x00b5, 0x00b6, 0x00b7, 0x00b8, 0x00b9, 0x061b, 0x00bb,
+    0x00bc, 0x00bd, 0x00be, 0x061f, 0x06c1, 0x0621, 0x0622, 0x0623, 0x0624, 0x0625, 0x0626, 0x0627,
+    0x0628, 0x0629, 0x062a, 0x062b, 0x062c, 0x062d, 0x062e, 0x062f, 0x0630, 0x0631, 0x0632, 0x0633,
+    0x0634, 0x0635, 0x0636, 0x00d7, 0x0637, 0x0638, 0x0639, 0x063a, 0x0640, 0x0641, 0x0642, 0x0643,
+    0x00e0, 0x0644, 0x00e2, 0x0645, 0x0646, 0x0647, 0x0648, 0x00e7, 0x00e8, 0x00e9, 0x00ea, 0x00eb,
+    0x0649, 0x064a, 0x00ee, 0x00ef, 0x064b, 0x064c, 0x064d, 0x064e, 0x00f4, 0x064f, 0x0650, 0x00f7,
+    0x0651, 0x00f9, 0x0652, 0x00fb, 0x00fc, 0x200e, 0x200f, 0x06d2,
+];
+
+pub const CP1257: [u16; 128] = [
+    0x20ac, 0x0000, 0x201a, 0x0000, 0x201e, 0x2026, 0x2020, 0x2021, 0x0000, 0x2030, 0x0000, 0x2039,
+    0x0000, 0x00a8, 0x02c7, 0x00b8, 0x0000, 0x2018, 0x2019, 0x201c, 0x201d, 0x2022, 0x2013, 0x2014,
+    0x0000, 0x2122, 0x0000, 0x203a, 0x0000, 0x00af, 0x02db, 0x0000, 0x00a0, 0x0000, 0x00a2, 0x00a3,
+    0x00a4, 0x0000, 0x00a6, 0x00a7, 0x00d8, 0x00a9, 0x0156, 0x00ab, 0x00ac, 0x00ad, 0x00ae, 0x00c6,
+    0x00b0, 0x00b1, 0x00b2, 0x00b3, 0x00b4, 0x00b5, 0x00b6, 0x00b7, 0x00f8, 0x00b9, 0x0157, 0x00bb,
+    0x00bc, 0x00bd, 0x00be, 0x00e6, 0x0104, 0x012e, 0x0100, 0x0106, 0x00c4, 0x00c5, 0x0118, 0x0112,
+    0x010c, 0x00c9, 0x0179, 0x0116, 0x0122, 0x0136, 0x012a, 0x013b, 0x0160, 0x0143, 0x0145, 0x00d3,
+    0x014c, 0x00d5, 0x00d6, 0x00d7, 0x0172, 0x0141, 0x015a, 0x016a, 0x00dc, 0x017b, 0x017d, 0x00df,
+    0x0105, 0x012f, 0x0101, 0x0107, 0x00e4, 0x00e5, 0x0119, 0x0113, 0x010d, 0x00e9, 0x017a, 0x0117,
+    0x0123, 0x0137, 0x012b, 0x013c, 0x0161, 0x0144, 0x0146, 0x00f3, 0x014d, 0x00f5, 0x00f6, 0x00f7,
+    0x0173, 0x0142, 0x015b, 0x016b, 0x00fc, 0x017c, 0x017e, 0x02d9,
+];
+
+pub const CP1258: [u16; 128] = [
+    0x20ac, 0x0000, 0x201a, 0x0192, 0x201e, 0x2026, 0x2020, 0x2021, 0x02c6, 0x2030, 0x0000, 0x2039,
+    0x0152, 0x0000, 0x0000, 0x0000, 0x0000, 0x2018, 0x2019, 0x201c, 0x201d, 0x2022, 0x2013, 0x2014,
+    0x02dc, 0x2122, 0x0000, 0x203a, 0x0153, 0x0000, 0x0000, 0x0178, 0x00a0, 0x00a1, 0x00a2, 0x00a3,
+    0x00a4, 0x00a5, 0x00a6, 0x00a7, 0x00a8, 0x00a9, 0x00aa, 0x00ab, 0x00ac, 0x00ad, 0x00ae, 0x00af,
+    0x00b0, 0x00b1, 0x00b2, 0x00b3, 0x00b4, 0x00b5, 0x00b6, 0x00b7, 0x00b8, 0x00b9, 0x00ba, 0x00bb,
+    0x00bc, 0x00bd, 0x00be, 0x00bf, 0x00c0, 0x00c1, 0x00c2, 0x0102, 0x00c4, 0x00c5, 0x00c6, 0x00c7,
+    0x00c8, 0x00c9, 0x00ca, 0x00cb, 0x0300, 0x00cd, 0x00ce, 0x00cf, 0x0110, 0x00d1, 0x0309, 0x00d3,
+    0x00d4, 0x01a0, 0x00d6, 0x00d7, 0x00d8, 0x00d9, 0x00da, 0x00db, 0x00dc, 0x01af, 0x0303, 0x00df,
+    0x00e0, 0x00e1, 0x00e2, 0x0103, 0x00e4, 0x00e5, 0x00e6, 0x00e7, 0x00e8, 0x00e9, 0x00ea, 0x00eb,
+    0x0301, 0x00ed, 0x00ee, 0x00ef, 0x0111, 0x00f1, 0x0323, 0x00f3, 0x00f4, 0x01a1, 0x00f6, 0x00f7,
+    0x00f8, 0x00f9, 0x00fa, 0x00fb, 0x00fc, 0x01b0, 0x20ab, 0x00ff,
+];
+
+pub const MACINTOSH: [u16; 128] = [
+    0x00c4, 0x00c5, 0x00c7, 0x00c9, 0x00d1, 0x00d6, 0x00dc, 0x00e1, 0x00e0, 0x00e2, 0x00e4, 0x00e3,
+    0x00e5, 0x00e7, 0x00e9, 0x00e8, 0x00ea, 0x00eb, 0x00ed, 0x00ec, 0x00ee, 0x00ef, 0x00f1, 0x00f3,
+    0x00f2, 0x00f4, 0x00f6, 0x00f5, 0x00fa, 0x00f9, 0x00fb, 0x00fc, 0x2020, 0x00b0, 0x00a2, 0x00a3,
+    0x00a7, 0x2022, 0x00b6, 0x00df, 0x00ae, 0x00a9, 0x2122, 0x00b4, 0x00a8, 0x2260, 0x00c6, 0x00d8,
+    0x221e, 0x00b1, 0x2264, 0x2265, 0x00a5, 0x00b5, 0x2202, 0x2211, 0x220f, 0x03c0, 0x222b, 0x00aa,
+    0x00ba, 0x03a9, 0x00e6, 0x00f8, 0x00bf, 0x00a1, 0x00ac, 0x221a, 0x0192, 0x2248, 0x2206, 0x00ab,
+    0x00bb, 0x2026, 0x00a0, 0x00c0, 0x00c3, 0x00d5, 0x0152, 0x0153, 0x2013, 0x2014, 0x201c, 0x201d,
+    0x2018, 0x2019, 0x00f7, 0x25ca, 0x00ff, 0x0178, 0x2044, 0x20ac, 0x2039, 0x203a, 0xfb01, 0xfb02,
+    0x2021, 0x00b7, 0x201a, 0x201e, 0x2030, 0x00c2, 0x00ca, 0x00c1, 0x00cb, 0x00c8, 0x00cd, 0x00ce,
+    0x00cf, 0x00cc, 0x00d3, 0x00d4, 0xf8ff, 0x00d2, 0x00da, 0x00db, 0x00d9, 0x0131, 0x02c6, 0x02dc,
+    0x00af, 0x02d8, 0x02d9, 0x02da, 0x00b8, 0x02dd, 0x02db, 0x02c7,
+];
+
+/// Approximations in ASCII, sorted by character, for //TRANSLIT
+pub const TRANSLIT: &[(u32, &str)] = &[
+    (0x00a0, " "),
+    (0x00a1, "!"),
+    (0x00a2, "c"),
+    (0x00a3, "GBP"),
+    (0x00a5, "JPY"),
+    (0x00a6, "|"),
+    (0x00a7, "SS"),
+    (0x00a8, " "),
+    (0x00a9, "(C)"),
+    (0x00aa, "a"),
+    (0x00ab, "<<"),
+    (0x00ac, "!"),
+    (0x00ae, "(R)"),
+    (0x00af, " "),
+    (0x00b0, "^0"),
+    (0x00b1, "+/-"),
+    (0x00b2, "2"),
+    (0x00b3, "3"),
+    (0x00b4, " "),
+    (0x00b6, "P"),
+    (0x00b7, "."),
+    (0x00b8, " "),
+    (0x00b9, "1"),
+    (0x00ba, "o"),
+    (0x00bb, ">>"),
+    (0x00bf, "?"),
+    (0x00c0, "A"),
+    (0x00c1, "A"),
+    (0x00c2, "A"),
+    (0x00c3, "A"),
+    (0x00c4, "A"),
+    (0x00c5, "A"),
+    (0x00c6, "AE"),
+    (0x00c7, "C"),
+    (0x00c8, "E"),
+    (0x00c9, "E"),
+    (0x00ca, "E"),
+    (0x00cb, "E"),
+    (0x00cc, "I"),
+    (0x00cd, "I"),
+    (0x00ce, "I"),
+    (0x00cf, "I"),
+    (0x00d0, "D"),
+    (0x00d1, "N"),
+    (0x00d2, "O"),
+    (0x00d3, "O"),
+    (0x00d4, "O"),
+    (0x00d5, "O"),
+    (0x00d6, "O"),
+    (0x00d7, "x"),
+    (0x00d8, "O"),
+    (0x00d9, "U"),
+    (0x00da, "U"),
+    (0x00db, "U"),
+    (0x00dc, "U"),
+    (0x00dd, "Y"),
+    (0x00de, "TH"),
+    (0x00df, "ss"),
+    (0x00e0, "a"),
+    (0x00e1, "a"),
+    (0x00e2, "a"),
+    (0x00e3, "a"),
+    (0x00e4, "a"),
+    (0x00e5, "a"),
+    (0x00e6, "ae"),
+    (0x00e7, "c"),
+    (0x00e8, "e"),
+    (0x00e9, "e"),
+    (0x00ea, "e"),
+    (0x00eb, "e"),
+    (0x00ec, "i"),
+    (0x00ed, "i"),
+    (0x00ee, "i"),
+    (0x00ef, "i"),
+    (0x00f0, "d"),
+    (0x00f1, "n"),
+    (0x00f2, "o"),
+    (0x00f3, "o"),
+    (0x00f4, "o"),
+    (0x00f5, "o"),
+    (0x00f6, "o"),
+    (0x00f7, ":"),
+    (0x00f8, "o"),
+    (0x00f9, "u"),
+    (0x00fa, "u"),
+    (0x00fb, "u"),
+    (0x00fc, "u"),
+    (0x00fd, "y"),
+    (0x00fe, "th"),
+    (0x00ff, "y"),
+    (0x0100, "A"),
+    (0x0101, "a"),
+    (0x0102, "A"),
+    (0x0103, "a"),
+    (0x0104, "A"),
+    (0x0105, "a"),
+    (0x0106, "C"),
+    (0x0107, "c"),
+    (0x0108, "C"),
+    (0x0109, "c"),
+    (0x010a, "C"),
+    (0x010b, "c"),
+    (0x010c, "C"),
+    (0x010d, "c"),
+    (0x010e, "D"),
+    (0x010f, "d"),
+    (0x0110, "D"),
+    (0x0111, "d"),
+    (0x0112, "E"),
+    (0x0113, "e"),
+    (0x0114, "E"),
+    (0x0115, "e"),
+    (0x0116, "E"),
+    (0x0117, "e"),
+    (0x0118, "E"),
+    (0x0119, "e"),
+    (0x011a, "E"),
+    (0x011b, "e"),
+    (0x011c, "G"),
+    (0x011d, "g"),
+    (0x011e, "G"),
+    (0x011f, "g"),
+    (0x0120, "G"),
+    (0x0121, "g"),
+    (0x0122, "G"),
+    (0x0123, "g"),
+    (0x0124, "H"),
+    (0x0125, "h"),
+    (0x0126, "H"),
+    (0x0127, "h"),
+    (0x0128, "I"),
+    (0x0129, "i"),
+    (0x012a, "I"),
+    (0x012b, "i"),
+    (0x012c, "I"),
+    (0x012d, "i"),
+    (0x012e, "I"),
+    (0x012f, "i"),
+    (0x0130, "I"),
+    (0x0131, "i"),
+    (0x0132, "IJ"),
+    (0x0133, "ij"),
+    (0x0134, "J"),
+    (0x0135, "j"),
+    (0x0136, "K"),
+    (0x0137, "k"),
+    (0x0139, "L"),
+    (0x013a, "l"),
+    (0x013b, "L"),
+    (0x013c, "l"),
+    (0x013d, "L"),
+    (0x013e, "l"),
+    (0x0141, "L"),
+    (0x0142, "l"),
+    (0x0143, "N"),
+    (0x0144, "n"),
+    (0x0145, "N"),
+    (0x0146, "n"),
+    (0x0147, "N"),
+    (0x0148, "n"),
+    (0x014c, "O"),
+    (0x014d, "o"),
+    (0x014e, "O"),
+    (0x014f, "o"),
+    (0x0150, "O"),
+    (0x0151, "o"),
+    (0x0152, "OE"),
+    (0x0153, "oe"),
+    (0x0154, "R"),
+    (0x0155, "r"),
+    (0x0156, "R"),
+    (0x0157, "r"),
+    (0x0158, "R"),
+    (0x0159, "r"),
+    (0x015a, "S"),
+    (0x015b, "s"),
+    (0x015c, "S"),
+    (0x015d, "s"),
+    (0x015e, "S"),
+    (0x015f, "s"),
+    (0x0160, "S"),
+    (0x0161, "s"),
+    (0x0162, "T"),
+    (0x0163, "t"),
+    (0x0164, "T"),
+    (0x0165, "t"),
+    (0x0166, "T"),
+    (0x0167, "t"),
+    (0x0168, "U"),
+    (0x0169, "u"),
+    (0x016a, "U"),
+    (0x016b, "u"),
+    (0x016c, "U"),
+    (0x016d, "u"),
+    (0x016e, "U"),
+    (0x016f, "u"),
+    (0x0170, "U"),
+    (0x0171, "u"),
+    (0x0172, "U"),
+    (0x0173, "u"),
+    (0x0174, "W"),
+    (0x0175, "w"),
+    (0x0176, "Y"),
+    (0x0177, "y"),
+    (0x0178, "Y"),
+    (0x0179, "Z"),
+    (0x017a, "z"),
+    (0x017b, "Z"),
+    (0x017c, "z"),
+    (0x017d, "Z"),
+    (0x017e, "z"),
+    (0x017f, "s"),
+    (0x01a0, "O"),
+    (0x01a1, "o"),
+    (0x01af, "U"),
+    (0x01b0, "u"),
+    (0x01c4, "DZ"),
+    (0x01c5, "Dz"),
+    (0x01c6, "dz"),
+    (0x01c7, "LJ"),
+    (0x01c8, "Lj"),
+    (0x01c9, "lj"),
+    (0x01ca, "NJ"),
+    (0x01cb, "Nj"),
+    (0x01cc, "nj"),
+    (0x01cd, "A"),
+    (0x01ce, "a"),
+    (0x01cf, "I"),
+    (0x01d0, "i"),
+    (0x01d1, "O"),
+    (0x01d2, "o"),
+    (0x01d3, "U"),
+    (0x01d4, "u"),
+    (0x01d5, "U"),
+    (0x01d6, "u"),
+    (0x01d7, "U"),
+    (0x01d8, "u"),
+    (0x01d9, "U"),
+    (0x01da, "u"),
+    (0x01db, "U"),
+    (0x01dc, "u"),
+    (0x01de, "A"),
+    (0x01df, "a"),
+    (0x01e0, "A"),
+    (0x01e1, "a"),
+    (0x01e6, "G"),
+    (0x01e7, "g"),
+    (0x01e8, "K"),
+    (0x01e9, "k"),
+    (0x01ea, "O"),
+    (0x01eb, "o"),
+    (0x01ec, "O"),
+    (0x01ed, "o"),
+    (0x01f0, "j"),
+    (0x01f1, "DZ"),
+    (0x01f2, "Dz"),
+    (0x01f3, "dz"),
+    (0x01f4, "G"),
+    (0x01f5, "g"),
+    (0x01f8, "N"),
+    (0x01f9, "n"),
+    (0x01fa, "A"),
+    (0x01fb, "a"),
+    (0x0200, "A"),
+    (0x0201, "a"),
+    (0x0202, "A"),
+    (0x0203, "a"),
+    (0x0204, "E"),
+    (0x0205, "e"),
+    (0x0206, "E"),
+    (0x0207, "e"),
+    (0x0208, "I"),
+    (0x0209, "i"),
+    (0x020a, "I"),
+    (0x020b, "i"),
+    (0x020c, "O"),
+    (0x020d, "o"),
+    (0x020e, "O"),
+    (0x020f, "o"),
+    (0x0210, "R"),
+    (0x0211, "r"),
+    (0x0212, "R"),
+    (0x0213, "r"),
+    (0x0214, "U"),
+    (0x0215, "u"),
+    (0x0216, "U"),
+    (0x0217, "u"),
+    (0x0218, "S"),
+    (0x0219, "s"),
+    (0x021a, "T"),
+    (0x021b, "t"),
+    (0x021e, "H"),
+    (0x021f, "h"),
+    (0x0226, "A"),
+    (0x0227, "a"),
+    (0x0228, "E"),
+    (0x0229, "e"),
+    (0x022a, "O"),
+    (0x022b, "o"),
+    (0x022c, "O"),
+    (0x022d, "o"),
+    (0x022e, "O"),
+    (0x022f, "o"),
+    (0x0230, "O"),
+    (0x0231, "o"),
+    (0x0232, "Y"),
+    (0x0233, "y"),
+    (0x1e00, "A"),
+    (0x1e01, "a"),
+    (0x1e02, "B"),
+    (0x1e03, "b"),
+    (0x1e04, "B"),
+    (0x1e05, "b"),
+    (0x1e06, "B"),
+    (0x1e07, "b"),
+    (0x1e08, "C"),
+    (0x1e09, "c"),
+    (0x1e0a, "D"),
+    (0x1e0b, "d"),
+    (0x1e0c, "D"),
+    (0x1e0d, "d"),
+    (0x1e0e, "D"),
+    (0x1e0f, "d"),
+    (0x1e10, "D"),
+    (0x1e11, "d"),
+    (0x1e12, "D"),
+    (0x1e13, "d"),
+    (0x1e14, "E"),
+    (0x1e15, "e"),
+    (0x1e16, "E"),
+    (0x1e17, "e"),
+    (0x1e18, "E"),
+    (0x1e19, "e"),
+    (0x1e1a, "E"),
+    (0x1e1b, "e"),
+    (0x1e1c, "E"),
+    (0x1e1d, "e"),
+    (0x1e1e, "F"),
+    (0x1e1f, "f"),
+    (0x1e20, "G"),
+    (0x1e21, "g"),
+    (0x1e22, "H"),
+    (0x1e23, "h"),
+    (0x1e24, "H"),
+    (0x1e25, "h"),
+    (0x1e26, "H"),
+    (0x1e27, "h"),
+    (0x1e28, "H"),
+    (0x1e29, "h"),
+    (0x1e2a, "H"),
+    (0x1e2b, "h"),
+    (0x1e2c, "I"),
+    (0x1e2d, "i"),
+    (0x1e2e, "I"),
+    (0x1e2f, "i"),
+    (0x1e30, "K"),
+    (0x1e31, "k"),
+    (0x1e32, "K"),
+    (0x1e33, "k"),
+    (0x1e34, "K"),
+    (0x1e35, "k"),
+    (0x1e36, "L"),
+    (0x1e37, "l"),
+    (0x1e38, "L"),
+    (0x1e39, "l"),
+    (0x1e3a, "L"),
+    (0x1e3b, "l"),
+    (0x1e3c, "L"),
+    (0x1e3d, "l"),
+    (0x1e3e, "M"),
+    (0x1e3f, "m"),
+    (0x1e40, "M"),
+    (0x1e41, "m"),
+    (0x1e42, "M"),
+    (0x1e43, "m"),
+    (0x1e44, "N"),
+    (0x1e45, "n"),
+    (0x1e46, "N"),
+    (0x1e47, "n"),
+    (0x1e48, "N"),
+    (0x1e49, "n"),
+    (0x1e4a, "N"),
+    (0x1e4b, "n"),
+    (0x1e4c, "O"),
+    (0x1e4d, "o"),
+    (0x1e4e, "O"),
+    (0x1e4f, "o"),
+    (0x1e50, "O"),
+    (0x1e51, "o"),
+    (0x1e52, "O"),
+    (0x1e53, "o"),
+    (0x1e54, "P"),
+    (0x1e55, "p"),
+    (0x1e56, "P"),
+    (0x1e57, "p"),
+    (0x1e58, "R"),
+    (0x1e59, "r"),
+    (0x1e5a, "R"),
+    (0x1e5b, "r"),
+    (0x1e5c, "R"),
+    (0x1e5d, "r"),
+    (0x1e5e, "R"),
+    (0x1e5f, "r"),
+    (0x1e60, "S"),
+    (0x1e61, "s"),
+    (0x1e62, "S"),
+    (0x1e63, "s"),
+    (0x1e64, "S"),
+    (0x1e65, "s"),
+    (0x1e66, "S"),
+    (0x1e67, "s"),
+    (0x1e68, "S"),
+    (0x1e69, "s"),
+    (0x1e6a, "T"),
+    (0x1e6b, "t"),
+    (0x1e6c, "T"),
+    (0x1e6d, "t"),
+    (0x1e6e, "T"),
+    (0x1e6f, "t"),
+    (0x1e70, "T"),
+    (0x1e71, "t"),
+    (0x1e72, "U"),
+    (0x1e73, "u"),
+    (0x1e74, "U"),
+    (0x1e75, "u"),
+    (0x1e76, "U"),
+    (0x1e77, "u"),
+    (0x1e78, "U"),
+    (0x1e79, "u"),
+    (0x1e7a, "U"),
+    (0x1e7b, "u"),
+    (0x1e7c, "V"),
+    (0x1e7d, "v"),
+    (0x1e7e, "V"),
+    (0x1e7f, "v"),
+    (0x1e80, "W"),
+    (0x1e81, "w"),
+    (0x1e82, "W"),
+    (0x1e83, "w"),
+    (0x1e84, "W"),
+    (0x1e85, "w"),
+    (0x1e86, "W"),
+    (0x1e87, "w"),
+    (0x1e88, "W"),
+    (0x1e89, "w"),
+    (0x1e8a, "X"),
+    (0x1e8b, "x"),
+    (0x1e8c, "X"),
+    (0x1e8d, "x"),
+    (0x1e8e, "Y"),
+    (0x1e8f, "y"),
+    (0x1e90, "Z"),
+    (0x1e91, "z"),
+    (0x1e92, "Z"),
+    (0x1e93, "z"),
+    (0x1e94, "Z"),
+    (0x1e95, "z"),
+    (0x1e96, "h"),
+    (0x1e97, "t"),
+    (0x1e98, "w"),
+    (0x1e99, "y"),
+    (0x1e9b, "s"),
+    (0x1ea0, "A"),
+    (0x1ea1, "a"),
+    (0x1ea2, "A"),
+    (0x1ea3, "a"),
+    (0x1ea4, "A"),
+    (0x1ea5, "a"),
+    (0x1ea6, "A"),
+    (0x1ea7, "a"),
+    (0x1ea8, "A"),
+    (0x1ea9, "a"),
+    (0x1eaa, "A"),
+    (0x1eab, "a"),
+    (0x1eac, "A"),
+    (0x1ead, "a"),
+    (0x1eae, "A"),
+    (0x1eaf, "a"),
+    (0x1eb0, "A"),
+    (0x1eb1, "a"),
+    (0x1eb2, "A"),
+    (0x1eb3, "a"),
+    (0x1eb4, "A"),
+    (0x1eb5, "a"),
+    (0x1eb6, "A"),
+    (0x1eb7, "a"),
+    (0x1eb8, "E"),
+    (0x1eb9, "e"),
+    (0x1eba, "E"),
+    (0x1ebb, "e"),
+    (0x1ebc, "E"),
+    (0x1ebd, "e"),
+    (0x1ebe, "E"),
+    (0x1ebf, "e"),
+    (0x1ec0, "E"),
+    (0x1ec1, "e"),
+    (0x1ec2, "E"),
+    (0x1ec3, "e"),
+    (0x1ec4, "E"),
+    (0x1ec5, "e"),
+    (0x1ec6, "E"),
+    (0x1ec7, "e"),
+    (0x1ec8, "I"),
+    (0x1ec9, "i"),
+    (0x1eca, "I"),
+    (0x1ecb, "i"),
+    (0x1ecc, "O"),
+    (0x1ecd, "o"),
+    (0x1ece, "O"),
+    (0x1ecf, "o"),
+    (0x1ed0, "O"),
+    (0x1ed1, "o"),
+    (0x1ed2, "O"),
+    (0x1ed3, "o"),
+    (0x1ed4, "O"),
+    (0x1ed5, "o"),
+    (0x1ed6, "O"),
+    (0x1ed7, "o"),
+    (0x1ed8, "O"),
+    (0x1ed9, "o"),
+    (0x1eda, "O"),
+    (0x1edb, "o"),
+    (0x1edc, "O"),
+    (0x1edd, "o"),
+    (0x1ede, "O"),
+    (0x1edf, "o"),
+    (0x1ee0, "O"),
+    (0x1ee1, "o"),
+    (0x1ee2, "O"),
+    (0x1ee3, "o"),
+    (0x1ee4, "U"),
+    (0x1ee5, "u"),
+    (0x1ee6, "U"),
+    (0x1ee7, "u"),
+    (0x1ee8, "U"),
+    (0x1ee9, "u"),
+    (0x1eea, "U"),
+    (0x1eeb, "u"),
+    (0x1eec, "U"),
+    (0x1eed, "u"),
+    (0x1eee, "U"),
+    (0x1eef, "u"),
+    (0x1ef0, "U"),
+    (0x1ef1, "u"),
+    (0x1ef2, "Y"),
+    (0x1ef3, "y"),
+    (0x1ef4, "Y"),
+    (0x1ef5, "y"),
+    (0x1ef6, "Y"),
+    (0x1ef7, "y"),
+    (0x1ef8, "Y"),
+    (0x1ef9, "y"),
+    (0x2000, " "),
+    (0x2001, " "),
+    (0x2002, " "),
+    (0x2003, " "),
+    (0x2004, " "),
+    (0x2005, " "),
+    (0x2006, " "),
+    (0x2007, " "),
+    (0x2008, " "),
+    (0x2009, " "),
+    (0x200a, " "),
+    (0x2010, "-"),
+    (0x2011, "-"),
+    (0x2012, "-"),
+    (0x2013, "-"),
+    (0x2014, "-"),
+    (0x2015, "-"),
+    (0x2017, " "),
+    (0x2018, "'"),
+    (0x2019, "'"),
+    (0x201a, "'"),
+    (0x201b, "'"),
+    (0x201c, "\""),
+    (0x201d, "\""),
+    (0x201e, "\""),
+    (0x201f, "\""),
+    (0x2022, "o"),
+    (0x2024, "."),
+    (0x2025, ".."),
+    (0x2026, "..."),
+    (0x202f, " "),
+    (0x2032, "'"),
+    (0x2033, "\""),
+    (0x2039, "<"),
+    (0x203a, ">"),
+    (0x203c, "!!"),
+    (0x203e, " "),
+    (0x2047, "??"),
+    (0x2048, "?!"),
+    (0x2049, "!?"),
+    (0x205f, " "),
+    (0x2070, "0"),
+    (0x2071, "i"),
+    (0x2074, "4"),
+    (0x2075, "5"),
+    (0x2076, "6"),
+    (0x2077, "7"),
+    (0x2078, "8"),
+    (0x2079, "9"),
+    (0x207a, "+"),
+    (0x207c, "="),
+    (0x207d, "("),
+    (0x207e, ")"),
+    (0x207f, "n"),
+    (0x2080, "0"),
+    (0x2081, "1"),
+    (0x2082, "2"),
+    (0x2083, "3"),
+    (0x2084, "4"),
+    (0x2085, "5"),
+    (0x2086, "6"),
+    (0x2087, "7"),
+    (0x2088, "8"),
+    (0x2089, "9"),
+    (0x208a, "+"),
+    (0x208c, "="),
+    (0x208d, "("),
+    (0x208e, ")"),
+    (0x2090, "a"),
+    (0x2091, "e"),
+    (0x2092, "o"),
+    (0x2093, "x"),
+    (0x2095, "h"),
+    (0x2096, "k"),
+    (0x2097, "l"),
+    (0x2098, "m"),
+    (0x2099, "n"),
+    (0x209a, "p"),
+    (0x209b, "s"),
+    (0x209c, "t"),
+    (0x20a8, "Rs"),
+    (0x20ac, "EUR"),
+    (0x2100, "a/c"),
+    (0x2101, "a/s"),
+    (0x2102, "C"),
+    (0x2105, "c/o"),
+    (0x2106, "c/u"),
+    (0x210a, "g"),
+    (0x210b, "H"),
+    (0x210c, "H"),
+    (0x210d, "H"),
+    (0x210e, "h"),
+    (0x2110, "I"),
+    (0x2111, "I"),
+    (0x2112, "L"),
+    (0x2113, "l"),
+    (0x2115, "N"),
+    (0x2116, "No"),
+    (0x2119, "P"),
+    (0x211a, "Q"),
+    (0x211b, "R"),
+    (0x211c, "R"),
+    (0x211d, "R"),
+    (0x2120, "SM"),
+    (0x2121, "TEL"),
+    (0x2122, "TM"),
+    (0x2124, "Z"),
+    (0x2128, "Z"),
+    (0x212a, "K"),
+    (0x212b, "A"),
+    (0x212c, "B"),
+    (0x212d, "C"),
+    (0x212f, "e"),
+    (0x2130, "E"),
+    (0x2131, "F"),
+    (0x2133, "M"),
+    (0x2134, "o"),
+    (0x2139, "i"),
+    (0x213b, "FAX"),
+    (0x2145, "D"),
+    (0x2146, "d"),
+    (0x2147, "e"),
+    (0x2148, "i"),
+    (0x2149, "j"),
+    (0x2160, "I"),
+    (0x2161, "II"),
+    (0x2162, "III"),
+    (0x2163, "IV"),
+    (0x2164, "V"),
+    (0x2165, "VI"),
+    (0x2166, "VII"),
+    (0x2167, "VIII"),
+    (0x2168, "IX"),
+    (0x2169, "X"),
+    (0x216a, "XI"),
+    (0x216b, "XII"),
+    (0x216c, "L"),
+    (0x216d, "C"),
+    (0x216e, "D"),
+    (0x216f, "M"),
+    (0x2170, "i"),
+    (0x2171, "ii"),
+    (0x2172, "iii"),
+    (0x2173, "iv"),
+    (0x2174, "v"),
+    (0x2175, "vi"),
+    (0x2176, "vii"),
+    (0x2177, "viii"),
+    (0x2178, "ix"),
+    (0x2179, "x"),
+    (0x217a, "xi"),
+    (0x217b, "xii"),
+    (0x217c, "l"),
+    (0x217d, "c"),
+    (0x217e, "d"),
+    (0x217f, "m"),
+    (0x2190, "<-"),
+    (0x2192, "->"),
+    (0x2194, "<->"),
+    (0x21d2, "=>"),
+    (0x2212, "-"),
+    (0x2260, "!="),
+    (0x2264, "<="),
+    (0x2265, ">="),
+    (0x2460, "1"),
+    (0x2461, "2"),
+    (0x2462, "3"),
+    (0x2463, "4"),
+    (0x2464, "5"),
+    (0x2465, "6"),
+    (0x2466, "7"),
+    (0x2467, "8"),
+    (0x2468, "9"),
+    (0x2469, "10"),
+    (0x246a, "11"),
+    (0x246b, "12"),
+    (0x246c, "13"),
+    (0x246d, "14"),
+    (0x246e, "15"),
+    (0x246f, "16"),
+    (0x2470, "17"),
+    (0x2471, "18"),
+    (0x2472, "19"),
+    (0x2473, "20"),
+    (0x2474, "(1)"),
+    (0x2475, "(2)"),
+    (0x2476, "(3)"),
+    (0x2477, "(4)"),
+    (0x2478, "(5)"),
+    (0x2479, "(6)"),
+    (0x247a, "(7)"),
+    (0x247b, "(8)"),
+    (0x247c, "(9)"),
+    (0x247d, "(10)"),
+    (0x247e, "(11)"),
+    (0x247f, "(12)"),
+    (0x2480, "(13)"),
+    (0x2481, "(14)"),
+    (0x2482, "(15)"),
+    (0x2483, "(16)"),
+    (0x2484, "(17)"),
+    (0x2485, "(18)"),
+    (0x2486, "(19)"),
+    (0x2487, "(20)"),
+    (0x2488, "1."),
+    (0x2489, "2."),
+    (0x248a, "3."),
+    (0x248b, "4."),
+    (0x248c, "5."),
+    (0x248d, "6."),
+    (0x248e, "7."),
+    (0x248f, "8."),
+    (0x2490, "9."),
+    (0x2491, "10."),
+    (0x2492, "11."),
+    (0x2493, "12."),
+    (0x2494, "13."),
+    (0x2495, "14."),
+    (0x2496, "15."),
+    (0x2497, "16."),
+    (0x2498, "17."),
+    (0x2499, "18."),
+    (0x249a, "19."),
+    (0x249b, "20."),
+    (0x249c, "(a)"),
+    (0x249d, "(b)"),
+    (0x249e, "(c)"),
+    (0x249f, "(d)"),
+    (0x24a0, "(e)"),
+    (0x24a1, "(f)"),
+    (0x24a2, "(g)"),
+    (0x24a3, "(h)"),
+    (0x24a4, "(i)"),
+    (0x24a5, "(j)"),
+    (0x24a6, "(k)"),
+    (0x24a7, "(l)"),
+    (0x24a8, "(m)"),
+    (0x24a9, "(n)"),
+    (0x24aa, "(o)"),
+    (0x24ab, "(p)"),
+    (0x24ac, "(q)"),
+    (0x24ad, "(r)"),
+    (0x24ae, "(s)"),
+    (0x24af, "(t)"),
+    (0x24b0, "(u)"),
+    (0x24b1, "(v)"),
+    (0x24b2, "(w)"),
+    (0x24b3, "(x)"),
+    (0x24b4, "(y)"),
+    (0x24b5, "(z)"),
+    (0x24b6, "A"),
+    (0x24b7, "B"),
+    (0x24b8, "C"),
+    (0x24b9, "D"),
+    (0x24ba, "E"),
+    (0x24bb, "F"),
+    (0x24bc, "G"),
+    (0x24bd, "H"),
+    (0x24be, "I"),
+    (0x24bf, "J"),
+    (0x24c0, "K"),
+    (0x24c1, "L"),
+    (0x24c2, "M"),
+    (0x24c3, "N"),
+    (0x24c4, "O"),
+    (0x24c5, "P"),
+    (0x24c6, "Q"),
+    (0x24c7, "R"),
+    (0x24c8, "S"),
+    (0x24c9, "T"),
+    (0x24ca, "U"),
+    (0x24cb, "V"),
+    (0x24cc, "W"),
+    (0x24cd, "X"),
+    (0x24ce, "Y"),
+    (0x24cf, "Z"),
+    (0x24d0, "a"),
+    (0x24d1, "b"),
+    (0x24d2, "c"),
+    (0x24d3, "d"),
+    (0x24d4, "e"),
+    (0x24d5, "f"),
+    (0x24d6, "g"),
+    (0x24d7, "h"),
+    (0x24d8, "i"),
+    (0x24d9, "j"),
+    (0x24da, "k"),
+    (0x24db, "l"),
+    (0x24dc, "m"),
+    (0x24dd, "n"),
+    (0x24de, "o"),
+    (0x24df, "p"),
+    (0x24e0, "q"),
+    (0x24e1, "r"),
+    (0x24e2, "s"),
+    (0x24e3, "t"),
+    (0x24e4, "u"),
+    (0x24e5, "v"),
+    (0x24e6, "w"),
+    (0x24e7, "x"),
+    (0x24e8, "y"),
+    (0x24e9, "z"),
+    (0xfb00, "ff"),
+    (0xfb01, "fi"),
+    (0xfb02, "fl"),
+    (0xfb03, "ffi"),
+    (0xfb04, "ffl"),
+    (0xfb05, "st"),
+    (0xfb06, "st"),
+    (0xff01, "!"),
+    (0xff02, "\""),
+    (0xff03, "#"),
+    (0xff04, "$"),
+    (0xff05, "%"),
+    (0xff06, "&"),
+    (0xff07, "'"),
+    (0xff08, "("),
+    (0xff09, ")"),
+    (0xff0a, "*"),
+    (0xff0b, "+"),
+    (0xff0c, ","),
+    (0xff0d, "-"),
+    (0xff0e, "."),
+    (0xff0f, "/"),
+    (0xff10, "0"),
+    (0xff11, "1"),
+    (0xff12, "2"),
+    (0xff13, "3"),
+    (0xff14, "4"),
+    (0xff15, "5"),
+    (0xff16, "6"),
+    (0xff17, "7"),
+    (0xff18, "8"),
+    (0xff19, "9"),
+    (0xff1a, ":"),
+    (0xff1b, ";"),
+    (0xff1c, "<"),
+    (0xff1d, "="),
+    (0xff1e, ">"),
+    (0xff1f, "?"),
+    (0xff20, "@"),
+    (0xff21, "A"),
+    (0xff22, "B"),
+    (0xff23, "C"),
+    (0xff24, "D"),
+    (0xff25, "E"),
+    (0xff26, "F"),
+    (0xff27, "G"),
+    (0xff28, "H"),
+    (0xff29, "I"),
+    (0xff2a, "J"),
+    (0xff2b, "K"),
+    (0xff2c, "L"),
+    (0xff2d, "M"),
+    (0xff2e, "N"),
+    (0xff2f, "O"),
+    (0xff30, "P"),
+    (0xff31, "Q"),
+    (0xff32, "R"),
+    (0xff33, "S"),
+    (0xff34, "T"),
+    (0xff35, "U"),
+    (0xff36, "V"),
+    (0xff37, "W"),
+    (0xff38, "X"),
+    (0xff39, "Y"),
+    (0xff3a, "Z"),
+    (0xff3b, "["),
+    (0xff3c, "\\"),
+    (0xff3d, "]"),
+    (0xff3e, "^"),
+    (0xff3f, "_"),
+    (0xff40, "`"),
+    (0xff41, "a"),
+    (0xff42, "b"),
+    (0xff43, "c"),
+    (0xff44, "d"),
+    (0xff45, "e"),
+    (0xff46, "f"),
+    (0xff47, "g"),
+    (0xff48, "h"),
+    (0xff49, "i"),
+    (0xff4a, "j"),
+    (0xff4b, "k"),
+    (0xff4c, "l"),
+    (0xff4d, "m"),
+    (0xff4e, "n"),
+    (0xff4f, "o"),
+    (0xff50, "p"),
+    (0xff51, "q"),
+    (0xff52, "r"),
+    (0xff53, "s"),
+    (0xff54, "t"),
+    (0xff55, "u"),
+    (0xff56, "v"),
+    (0xff57, "w"),
+    (0xff58, "x"),
+    (0xff59, "y"),
+    (0xff5a, "z"),
+    (0xff5b, "{"),
+    (0xff5c, "|"),
+    (0xff5d, "}"),
+    (0xff5e, "~"),
+];
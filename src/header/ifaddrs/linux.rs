@@ -0,0 +1,252 @@
+use alloc::vec::Vec;
+use core::{mem, ptr};
+
+use crate::{
+    header::{
+        errno::EIO,
+        sys_socket::{
+            self,
+            constants::{AF_INET, AF_INET6, AF_UNSPEC, SOCK_CLOEXEC},
+        },
+    },
+    platform::{self, types::*, Pal, Sys},
+};
+
+use super::{IfAddr, IfAddress, Interface};
+
+const AF_NETLINK: c_int = 16;
+const SOCK_RAW: c_int = 3;
+const NETLINK_ROUTE: c_int = 0;
+
+const NLMSG_ERROR: u16 = 0x2;
+const NLMSG_DONE: u16 = 0x3;
+const NLM_F_REQUEST: u16 = 0x1;
+const NLM_F_DUMP: u16 = 0x300;
+
+const RTM_NEWLINK: u16 = 16;
+const RTM_GETLINK: u16 = 18;
+const RTM_NEWADDR: u16 = 20;
+const RTM_GETADDR: u16 = 22;
+
+const IFLA_IFNAME: u16 = 3;
+
+const IFA_ADDRESS: u16 = 1;
+const IFA_LOCAL: u16 = 2;
+const IFA_BROADCAST: u16 = 4;
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct nlmsghdr {
+    nlmsg_len: u32,
+    nlmsg_type: u16,
+    nlmsg_flags: u16,
+    nlmsg_seq: u32,
+    nlmsg_pid: u32,
+}
+
+#[repr(C)]
+struct DumpRequest {
+    header: nlmsghdr,
+    // struct rtgenmsg, padded to the netlink alignment
+    family: u8,
+    pad: [u8; 3],
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct ifinfomsg {
+    ifi_family: u8,
+    ifi_pad: u8,
+    ifi_type: u16,
+    ifi_index: i32,
+    ifi_flags: u32,
+    ifi_change: u32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct ifaddrmsg {
+    ifa_family: u8,
+    ifa_prefixlen: u8,
+    ifa_flags: u8,
+    ifa_scope: u8,
+    ifa_index: u32,
+}
+
+fn align(len: usize) -> usize {
+    (len + 3) & !3
+}
+
+unsafe fn read<T: Copy>(buf: &[u8]) -> Option<T> {
+    if buf.len() < mem::size_of::<T>() {
+        return None;
+    }
+    Some(ptr::read_unaligned(buf.as_ptr() as *const T))
+}
+
+/// Iterates over the rtattr list following a fixed-size message header.
+fn attributes(mut buf: &[u8]) -> impl Iterator<Item = (u16, &[u8])> {
+    core::iter::from_fn(move || {
+        let len = u16::from_ne_bytes([*buf.first()?, *buf.get(1)?]) as usize;
+        let kind = u16::from_ne_bytes([*buf.get(2)?, *buf.get(3)?]);
+        if len < 4 || len > buf.len() {
+            return None;
+        }
+        let data = &buf[4..len];
+        buf = &buf[align(len).min(buf.len())..];
+        Some((kind, data))
+    })
+}
+
+/// Sends a dump request for `kind` and feeds every reply message to `f`.
+fn dump<F: FnMut(u16, &[u8])>(fd: c_int, kind: u16, seq: u32, mut f: F) -> Result<(), c_int> {
+    let request = DumpRequest {
+        header: nlmsghdr {
+            nlmsg_len: mem::size_of::<DumpRequest>() as u32,
+            nlmsg_type: kind,
+            nlmsg_flags: NLM_F_REQUEST | NLM_F_DUMP,
+            nlmsg_seq: seq,
+            nlmsg_pid: 0,
+        },
+        family: AF_UNSPEC as u8,
+        pad: [0; 3],
+    };
+    let sent = unsafe {
+        sys_socket::send(
+            fd,
+            &request as *const DumpRequest as *const c_void,
+            mem::size_of::<DumpRequest>(),
+            0,
+        )
+    };
+    if sent < 0 {
+        return Err(unsafe { platform::errno });
+    }
+
+    let mut buf = vec![0_u8; 8192];
+    loop {
+        let count = unsafe { sys_socket::recv(fd, buf.as_mut_ptr() as *mut c_void, buf.len(), 0) };
+        if count < 0 {
+            return Err(unsafe { platform::errno });
+        }
+        if count == 0 {
+            return Err(EIO);
+        }
+
+        let mut msgs = &buf[..count as usize];
+        while let Some(header) = unsafe { read::<nlmsghdr>(msgs) } {
+            let len = header.nlmsg_len as usize;
+            if len < mem::size_of::<nlmsghdr>() || len > msgs.len() {
+                break;
+            }
+            let payload = &msgs[mem::size_of::<nlmsghdr>()..len];
+            msgs = &msgs[align(len).min(msgs.len())..];
+
+            if header.nlmsg_seq != seq {
+                continue;
+            }
+            match header.nlmsg_type {
+                NLMSG_DONE => return Ok(()),
+                NLMSG_ERROR => {
+                    let error = unsafe { read::<c_int>(payload) }.unwrap_or(-EIO);
+                    return Err(if error < 0 { -error } else { EIO });
+                }
+                kind => f(kind, payload),
+            }
+        }
+    }
+}
+
+fn parse_link(interfaces: &mut Vec<Interface>, payload: &[u8]) {
+    let info = match unsafe { read::<ifinfomsg>(payload) } {
+        Some(info) => info,
+        None => return,
+    };
+    let mut name = Vec::new();
+    for (kind, data) in attributes(&payload[mem::size_of::<ifinfomsg>()..]) {
+        if kind == IFLA_IFNAME {
+            name = data.split(|&b| b == 0).next().unwrap_or(&[]).to_vec();
+        }
+    }
+    interfaces.push(Interface {
+        name,
+        index: info.ifi_index as c_uint,
+        flags: info.ifi_flags,
+        addrs: Vec::new(),
+    });
+}
+
+fn parse_addr(interfaces: &mut Vec<Interface>, payload: &[u8]) {
+    let info = match unsafe { read::<ifaddrmsg>(payload) } {
+        Some(info) => info,
+        None => return,
+    };
+    let iface = match interfaces
+        .iter_mut()
+        .find(|iface| iface.index == info.ifa_index)
+    {
+        Some(iface) => iface,
+        None => return,
+    };
+
+    let (mut address, mut local, mut broadcast) = (None, None, None);
+    for (kind, data) in attributes(&payload[mem::size_of::<ifaddrmsg>()..]) {
+        let addr = match (info.ifa_family as c_int, data.len()) {
+            (AF_INET, 4) => {
+                let mut octets = [0; 4];
+                octets.copy_from_slice(data);
+                IfAddr::V4(octets)
+            }
+            (AF_INET6, 16) => {
+                let mut octets = [0; 16];
+                octets.copy_from_slice(data);
+                IfAddr::V6(octets)
+            }
+            _ => continue,
+        };
+        match kind {
+            IFA_ADDRESS => address = Some(addr),
+            IFA_LOCAL => local = Some(addr),
+            IFA_BROADCAST => {
+                if let IfAddr::V4(octets) = addr {
+                    broadcast = Some(octets);
+                }
+            }
+            _ => (),
+        }
+    }
+
+    // For IPv4, IFA_ADDRESS is the peer address on point-to-point links, IFA_LOCAL is ours
+    if let Some(addr) = local.or(address) {
+        iface.addrs.push(IfAddress {
+            addr,
+            prefix_len: info.ifa_prefixlen,
+            broadcast,
+        });
+    }
+}
+
+/// Enumerates interfaces and their addresses using a NETLINK_ROUTE dump.
+pub fn interfaces() -> Result<Vec<Interface>, c_int> {
+    let fd = unsafe { sys_socket::socket(AF_NETLINK, SOCK_RAW | SOCK_CLOEXEC, NETLINK_ROUTE) };
+    if fd < 0 {
+        return Err(unsafe { platform::errno });
+    }
+
+    let mut interfaces = Vec::new();
+    let mut result = dump(fd, RTM_GETLINK, 1, |kind, payload| {
+        if kind == RTM_NEWLINK {
+            parse_link(&mut interfaces, payload);
+        }
+    });
+    if result.is_ok() {
+        result = dump(fd, RTM_GETADDR, 2, |kind, payload| {
+            if kind == RTM_NEWADDR {
+                parse_addr(&mut interfaces, payload);
+            }
+        });
+    }
+    Sys::close(fd);
+
+    result.map(|()| interfaces)
+}
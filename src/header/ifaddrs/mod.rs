@@ -0,0 +1,176 @@
+//! ifaddrs.h implementation for Redox, following the BSD/glibc getifaddrs(3) interface
+
+use alloc::{boxed::Box, vec::Vec};
+use core::{mem, ptr};
+
+use crate::{
+    header::{
+        net_if::{IFF_BROADCAST, IF_NAMESIZE},
+        netinet_in::{in6_addr, in_addr, sockaddr_in, sockaddr_in6},
+        sys_socket::{
+            constants::{AF_INET, AF_INET6},
+            sa_family_t, sockaddr,
+        },
+    },
+    platform::{self, types::*},
+};
+
+#[cfg(target_os = "linux")]
+#[path = "linux.rs"]
+pub(crate) mod sys;
+
+#[cfg(target_os = "redox")]
+#[path = "redox.rs"]
+pub(crate) mod sys;
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub union ifaddrs_ifa_ifu {
+    pub ifu_broadaddr: *mut sockaddr,
+    pub ifu_dstaddr: *mut sockaddr,
+}
+
+#[repr(C)]
+pub struct ifaddrs {
+    pub ifa_next: *mut ifaddrs,
+    pub ifa_name: *mut c_char,
+    pub ifa_flags: c_uint,
+    pub ifa_addr: *mut sockaddr,
+    pub ifa_netmask: *mut sockaddr,
+    pub ifa_ifu: ifaddrs_ifa_ifu,
+    pub ifa_data: *mut c_void,
+}
+
+#[derive(Clone, Copy)]
+pub enum IfAddr {
+    V4([u8; 4]),
+    V6([u8; 16]),
+}
+
+pub struct IfAddress {
+    pub addr: IfAddr,
+    pub prefix_len: u8,
+    pub broadcast: Option<[u8; 4]>,
+}
+
+/// A network interface as reported by the platform, with all of its addresses.
+pub struct Interface {
+    pub name: Vec<u8>,
+    pub index: c_uint,
+    pub flags: c_uint,
+    pub addrs: Vec<IfAddress>,
+}
+
+/// A single list element as handed out by getifaddrs. All pointers in `ifa` point into the node
+/// itself, so that freeifaddrs only has to free the node.
+#[repr(C)]
+struct IfaddrsNode {
+    ifa: ifaddrs,
+    name: [c_char; IF_NAMESIZE],
+    addr: sockaddr_in6,
+    netmask: sockaddr_in6,
+    broadaddr: sockaddr_in6,
+}
+
+fn prefix_to_mask<const N: usize>(prefix_len: u8) -> [u8; N] {
+    let mut mask = [0; N];
+    let mut bits = prefix_len as usize;
+    for byte in mask.iter_mut() {
+        let take = bits.min(8);
+        *byte = !(0xFF_u8.checked_shr(take as u32).unwrap_or(0));
+        bits -= take;
+    }
+    mask
+}
+
+unsafe fn write_sockaddr(dst: *mut sockaddr_in6, addr: IfAddr, scope_id: u32) -> *mut sockaddr {
+    match addr {
+        IfAddr::V4(octets) => {
+            let sin = dst as *mut sockaddr_in;
+            (*sin).sin_family = AF_INET as sa_family_t;
+            (*sin).sin_addr = in_addr {
+                s_addr: u32::from_ne_bytes(octets),
+            };
+        }
+        IfAddr::V6(octets) => {
+            (*dst).sin6_family = AF_INET6 as sa_family_t;
+            (*dst).sin6_addr = in6_addr { s6_addr: octets };
+            (*dst).sin6_scope_id = scope_id;
+        }
+    }
+    dst as *mut sockaddr
+}
+
+fn new_node(iface: &Interface) -> Box<IfaddrsNode> {
+    let mut node: Box<IfaddrsNode> = Box::new(unsafe { mem::zeroed() });
+    let len = iface.name.len().min(IF_NAMESIZE - 1);
+    for (dst, src) in node.name.iter_mut().zip(&iface.name[..len]) {
+        *dst = *src as c_char;
+    }
+    node.ifa.ifa_name = node.name.as_mut_ptr();
+    node.ifa.ifa_flags = iface.flags;
+    node
+}
+
+unsafe fn build_node(iface: &Interface, address: &IfAddress) -> Box<IfaddrsNode> {
+    let mut node = new_node(iface);
+    let is_link_local = match address.addr {
+        IfAddr::V6(octets) => octets[0] == 0xFE && octets[1] & 0xC0 == 0x80,
+        IfAddr::V4(_) => false,
+    };
+    let scope_id = if is_link_local { iface.index } else { 0 };
+
+    node.ifa.ifa_addr = write_sockaddr(&mut node.addr, address.addr, scope_id);
+    let netmask = match address.addr {
+        IfAddr::V4(_) => IfAddr::V4(prefix_to_mask(address.prefix_len)),
+        IfAddr::V6(_) => IfAddr::V6(prefix_to_mask(address.prefix_len)),
+    };
+    node.ifa.ifa_netmask = write_sockaddr(&mut node.netmask, netmask, 0);
+    if let Some(broadcast) = address
+        .broadcast
+        .filter(|_| iface.flags & IFF_BROADCAST != 0)
+    {
+        node.ifa.ifa_ifu.ifu_broadaddr =
+            write_sockaddr(&mut node.broadaddr, IfAddr::V4(broadcast), 0);
+    }
+    node
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn getifaddrs(ifap: *mut *mut ifaddrs) -> c_int {
+    let interfaces = match sys::interfaces() {
+        Ok(interfaces) => interfaces,
+        Err(err) => {
+            platform::errno = err;
+            return -1;
+        }
+    };
+
+    let mut nodes = Vec::new();
+    for iface in &interfaces {
+        if iface.addrs.is_empty() {
+            // Interfaces without addresses are still listed, with a null ifa_addr
+            nodes.push(new_node(iface));
+        }
+        for address in &iface.addrs {
+            nodes.push(build_node(iface, address));
+        }
+    }
+
+    let mut head: *mut ifaddrs = ptr::null_mut();
+    for mut node in nodes.into_iter().rev() {
+        node.ifa.ifa_next = head;
+        head = Box::into_raw(node) as *mut ifaddrs;
+    }
+    *ifap = head;
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn freeifaddrs(ifa: *mut ifaddrs) {
+    let mut ifa = ifa;
+    while !ifa.is_null() {
+        let node = Box::from_raw(ifa as *mut IfaddrsNode);
+        ifa = node.ifa.ifa_next;
+    }
+}
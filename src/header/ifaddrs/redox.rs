@@ -0,0 +1,82 @@
+use alloc::{string::String, vec::Vec};
+
+use crate::{
+    c_str::CString,
+    fs::File,
+    header::{
+        fcntl,
+        net_if::{IFF_BROADCAST, IFF_LOOPBACK, IFF_MULTICAST, IFF_RUNNING, IFF_UP},
+    },
+    io::Read,
+    platform::types::*,
+};
+
+use super::{IfAddr, IfAddress, Interface};
+
+fn read_netcfg(path: &str) -> Option<String> {
+    let path = CString::new(format!("netcfg:{}", path)).ok()?;
+    let mut file = File::open(&path, fcntl::O_RDONLY).ok()?;
+    let mut string = String::new();
+    file.read_to_string(&mut string).ok()?;
+    Some(string)
+}
+
+/// Parses an `a.b.c.d/prefix` entry from an interface's `addr/list`.
+fn parse_cidr(entry: &str) -> Option<([u8; 4], u8)> {
+    let mut parts = entry.trim().splitn(2, '/');
+    let mut octets = [0_u8; 4];
+    let mut addr = parts.next()?.split('.');
+    for octet in octets.iter_mut() {
+        *octet = addr.next()?.parse().ok()?;
+    }
+    if addr.next().is_some() {
+        return None;
+    }
+    let prefix_len = match parts.next() {
+        Some(prefix) => prefix.parse().ok().filter(|len| *len <= 32)?,
+        None => 32,
+    };
+    Some((octets, prefix_len))
+}
+
+/// Enumerates interfaces through the netstack's `netcfg:` scheme.
+///
+/// The netstack answers 127.0.0.0/8 itself without exposing a device for it, so a loopback
+/// interface is always reported first, with index 1.
+pub fn interfaces() -> Result<Vec<Interface>, c_int> {
+    let mut interfaces = vec![Interface {
+        name: b"lo".to_vec(),
+        index: 1,
+        flags: IFF_UP | IFF_RUNNING | IFF_LOOPBACK,
+        addrs: vec![IfAddress {
+            addr: IfAddr::V4([127, 0, 0, 1]),
+            prefix_len: 8,
+            broadcast: None,
+        }],
+    }];
+
+    // Without a running netstack there is nothing besides loopback
+    let names = read_netcfg("ifaces").unwrap_or_default();
+    for name in names.lines().map(str::trim).filter(|name| !name.is_empty()) {
+        let mut addrs = Vec::new();
+        let list = read_netcfg(&format!("ifaces/{}/addr/list", name)).unwrap_or_default();
+        for (octets, prefix_len) in list.lines().filter_map(parse_cidr) {
+            let mask = u32::MAX.checked_shl(32 - prefix_len as u32).unwrap_or(0);
+            let broadcast = u32::from_be_bytes(octets) | !mask;
+            addrs.push(IfAddress {
+                addr: IfAddr::V4(octets),
+                prefix_len,
+                broadcast: Some(broadcast.to_be_bytes()),
+            });
+        }
+
+        interfaces.push(Interface {
+            name: name.as_bytes().to_vec(),
+            index: interfaces.len() as c_uint + 1,
+            flags: IFF_UP | IFF_RUNNING | IFF_BROADCAST | IFF_MULTICAST,
+            addrs,
+        });
+    }
+
+    Ok(interfaces)
+}
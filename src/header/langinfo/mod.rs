@@ -0,0 +1,167 @@
+//! langinfo implementation for Redox, following https://pubs.opengroup.org/onlinepubs/9699919799/basedefs/langinfo.h.html
+
+use crate::{
+    header::{
+        locale::{self, locale_t, LC_CTYPE, LC_MESSAGES, LC_MONETARY, LC_NUMERIC, LC_TIME},
+        nl_types::nl_item,
+    },
+    platform::types::*,
+};
+
+pub const CODESET: nl_item = 0;
+pub const D_T_FMT: nl_item = 1;
+pub const D_FMT: nl_item = 2;
+pub const T_FMT: nl_item = 3;
+pub const T_FMT_AMPM: nl_item = 4;
+pub const AM_STR: nl_item = 5;
+pub const PM_STR: nl_item = 6;
+pub const DAY_1: nl_item = 7;
+pub const DAY_2: nl_item = 8;
+pub const DAY_3: nl_item = 9;
+pub const DAY_4: nl_item = 10;
+pub const DAY_5: nl_item = 11;
+pub const DAY_6: nl_item = 12;
+pub const DAY_7: nl_item = 13;
+pub const ABDAY_1: nl_item = 14;
+pub const ABDAY_2: nl_item = 15;
+pub const ABDAY_3: nl_item = 16;
+pub const ABDAY_4: nl_item = 17;
+pub const ABDAY_5: nl_item = 18;
+pub const ABDAY_6: nl_item = 19;
+pub const ABDAY_7: nl_item = 20;
+pub const MON_1: nl_item = 21;
+pub const MON_2: nl_item = 22;
+pub const MON_3: nl_item = 23;
+pub const MON_4: nl_item = 24;
+pub const MON_5: nl_item = 25;
+pub const MON_6: nl_item = 26;
+pub const MON_7: nl_item = 27;
+pub const MON_8: nl_item = 28;
+pub const MON_9: nl_item = 29;
+pub const MON_10: nl_item = 30;
+pub const MON_11: nl_item = 31;
+pub const MON_12: nl_item = 32;
+pub const ABMON_1: nl_item = 33;
+pub const ABMON_2: nl_item = 34;
+pub const ABMON_3: nl_item = 35;
+pub const ABMON_4: nl_item = 36;
+pub const ABMON_5: nl_item = 37;
+pub const ABMON_6: nl_item = 38;
+pub const ABMON_7: nl_item = 39;
+pub const ABMON_8: nl_item = 40;
+pub const ABMON_9: nl_item = 41;
+pub const ABMON_10: nl_item = 42;
+pub const ABMON_11: nl_item = 43;
+pub const ABMON_12: nl_item = 44;
+pub const ERA: nl_item = 45;
+pub const ERA_D_FMT: nl_item = 46;
+pub const ALT_DIGITS: nl_item = 47;
+pub const ERA_D_T_FMT: nl_item = 48;
+pub const ERA_T_FMT: nl_item = 49;
+pub const RADIXCHAR: nl_item = 50;
+pub const THOUSEP: nl_item = 51;
+pub const YESEXPR: nl_item = 52;
+pub const NOEXPR: nl_item = 53;
+pub const YESSTR: nl_item = 54;
+pub const NOSTR: nl_item = 55;
+/// Not in the table, as it is made from several items
+pub const CRNCYSTR: nl_item = 56;
+
+/// The category, keyword and index in the locale data of each item, by number
+const ITEMS: [(c_int, &str, usize); 56] = [
+    (LC_CTYPE, "charmap", 0),
+    (LC_TIME, "d_t_fmt", 0),
+    (LC_TIME, "d_fmt", 0),
+    (LC_TIME, "t_fmt", 0),
+    (LC_TIME, "t_fmt_ampm", 0),
+    (LC_TIME, "am_pm", 0),
+    (LC_TIME, "am_pm", 1),
+    (LC_TIME, "day", 0),
+    (LC_TIME, "day", 1),
+    (LC_TIME, "day", 2),
+    (LC_TIME, "day", 3),
+    (LC_TIME, "day", 4),
+    (LC_TIME, "day", 5),
+    (LC_TIME, "day", 6),
+    (LC_TIME, "abday", 0),
+    (LC_TIME, "abday", 1),
+    (LC_TIME, "abday", 2),
+    (LC_TIME, "abday", 3),
+    (LC_TIME, "abday", 4),
+    (LC_TIME, "abday", 5),
+    (LC_TIME, "abday", 6),
+    (LC_TIME, "mon", 0),
+    (LC_TIME, "mon", 1),
+    (LC_TIME, "mon", 2),
+    (LC_TIME, "mon", 3),
+    (LC_TIME, "mon", 4),
+    (LC_TIME, "mon", 5),
+    (LC_TIME, "mon", 6),
+    (LC_TIME, "mon", 7),
+    (LC_TIME, "mon", 8),
+    (LC_TIME, "mon", 9),
+    (LC_TIME, "mon", 10),
+    (LC_TIME, "mon", 11),
+    (LC_TIME, "abmon", 0),
+    (LC_TIME, "abmon", 1),
+    (LC_TIME, "abmon", 2),
+    (LC_TIME, "abmon", 3),
+    (LC_TIME, "abmon", 4),
+    (LC_TIME, "abmon", 5),
+    (LC_TIME, "abmon", 6),
+    (LC_TIME, "abmon", 7),
+    (LC_TIME, "abmon", 8),
+    (LC_TIME, "abmon", 9),
+    (LC_TIME, "abmon", 10),
+    (LC_TIME, "abmon", 11),
+    (LC_TIME, "era", 0),
+    (LC_TIME, "era_d_fmt", 0),
+    (LC_TIME, "alt_digits", 0),
+    (LC_TIME, "era_d_t_fmt", 0),
+    (LC_TIME, "era_t_fmt", 0),
+    (LC_NUMERIC, "decimal_point", 0),
+    (LC_NUMERIC, "thousands_sep", 0),
+    (LC_MESSAGES, "yesexpr", 0),
+    (LC_MESSAGES, "noexpr", 0),
+    (LC_MESSAGES, "yesstr", 0),
+    (LC_MESSAGES, "nostr", 0),
+];
+
+/// The currency symbol and where it goes, for CRNCYSTR
+static mut CURRENCY: [u8; 64] = [0; 64];
+
+unsafe fn currency() -> *mut c_char {
+    let symbol = locale::item(LC_MONETARY, "currency_symbol", 0).to_bytes();
+    let precedes = locale::item(LC_MONETARY, "p_cs_precedes", 0).to_bytes();
+    // '-' if it goes before the value, '+' after, and '.' if it replaces the radix character
+    let position = if symbol == locale::item(LC_NUMERIC, "decimal_point", 0).to_bytes() {
+        b'.'
+    } else if precedes == b"0" {
+        b'+'
+    } else {
+        b'-'
+    };
+    let len = symbol.len().min(CURRENCY.len() - 2);
+    CURRENCY[0] = position;
+    CURRENCY[1..len + 1].copy_from_slice(&symbol[..len]);
+    CURRENCY[len + 1] = 0;
+    CURRENCY.as_mut_ptr() as *mut c_char
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn nl_langinfo(item: nl_item) -> *mut c_char {
+    if item == CRNCYSTR {
+        return currency();
+    }
+    match ITEMS.get(item as usize) {
+        Some(&(category, keyword, index)) => {
+            locale::item(category, keyword, index).as_ptr() as *mut c_char
+        }
+        None => c_str!("").as_ptr() as *mut c_char,
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn nl_langinfo_l(item: nl_item, locale: locale_t) -> *mut c_char {
+    locale::with(locale, || nl_langinfo(item))
+}
@@ -0,0 +1,427 @@
+//! libintl implementation for Redox, following the GNU gettext API
+//!
+//! Translations are read from the `.mo` catalogs of GNU gettext, looked up as
+//! `<directory>/<locale>/<category>/<domain>.mo`. They are handed out as the catalog has them,
+//! which for the UTF-8 locales supported is what they should be in.
+
+use alloc::{boxed::Box, vec::Vec};
+use core::{cmp::Ordering, ptr};
+
+use crate::{
+    c_str::{CStr, CString},
+    fs::File,
+    header::{
+        errno::EINVAL,
+        fcntl::O_RDONLY,
+        locale::{self, LC_MESSAGES},
+        stdlib::getenv,
+    },
+    io::Read,
+    platform::{self, types::*},
+    sync::Mutex,
+};
+
+mod plural;
+
+/// Where catalogs are looked for when the domain isn't bound to a directory
+const DEFAULT_DIRECTORY: &CStr = c_str!("/usr/share/locale");
+
+const MAGIC: u32 = 0x950412de;
+
+fn trim(bytes: &[u8]) -> &[u8] {
+    let start = bytes
+        .iter()
+        .position(|b| !b.is_ascii_whitespace())
+        .unwrap_or(bytes.len());
+    let end = bytes
+        .iter()
+        .rposition(|b| !b.is_ascii_whitespace())
+        .map_or(start, |end| end + 1);
+    &bytes[start..end]
+}
+
+struct Catalog {
+    data: Vec<u8>,
+    big_endian: bool,
+    count: usize,
+    originals: usize,
+    translations: usize,
+    /// The number of plural forms and the expression that picks one, from the header entry
+    plurals: c_ulong,
+    plural: Vec<u8>,
+}
+
+impl Catalog {
+    fn open(path: &[u8]) -> Option<Self> {
+        let path = CString::new(path).ok()?;
+        let mut file = File::open(&path, O_RDONLY).ok()?;
+        let mut data = Vec::new();
+        file.read_to_end(&mut data).ok()?;
+
+        let mut catalog = Self {
+            data,
+            big_endian: false,
+            count: 0,
+            originals: 0,
+            translations: 0,
+            plurals: 2,
+            plural: b"n != 1".to_vec(),
+        };
+        match catalog.word(0)? {
+            MAGIC => (),
+            magic if magic.swap_bytes() == MAGIC => catalog.big_endian = true,
+            _ => return None,
+        }
+        catalog.count = catalog.word(8)? as usize;
+        catalog.originals = catalog.word(12)? as usize;
+        catalog.translations = catalog.word(16)? as usize;
+
+        let header = catalog
+            .find(b"")
+            .and_then(|index| catalog.string(catalog.translations, index))
+            .map(|header| header.to_vec());
+        if let Some(header) = header {
+            catalog.plural_forms(&header);
+        }
+        Some(catalog)
+    }
+
+    fn word(&self, offset: usize) -> Option<u32> {
+        let bytes = self.data.get(offset..offset + 4)?;
+        let bytes = [bytes[0], bytes[1], bytes[2], bytes[3]];
+        Some(if self.big_endian {
+            u32::from_be_bytes(bytes)
+        } else {
+            u32::from_le_bytes(bytes)
+        })
+    }
+
+    /// String `index` of the table at `table`, without its terminating NUL but with those
+    /// separating the forms of plurals.
+    fn string(&self, table: usize, index: usize) -> Option<&[u8]> {
+        let entry = table + index * 8;
+        let length = self.word(entry)? as usize;
+        let offset = self.word(entry + 4)? as usize;
+        if *self.data.get(offset + length)? != 0 {
+            return None;
+        }
+        Some(&self.data[offset..offset + length])
+    }
+
+    /// The index of `msgid`, the originals being sorted.
+    fn find(&self, msgid: &[u8]) -> Option<usize> {
+        let (mut low, mut high) = (0, self.count);
+        while low < high {
+            let middle = (low + high) / 2;
+            let original = self.string(self.originals, middle)?;
+            // The plural form of a message comes after a NUL, and isn't part of its key
+            let end = original
+                .iter()
+                .position(|&b| b == 0)
+                .unwrap_or(original.len());
+            match original[..end].cmp(msgid) {
+                Ordering::Less => low = middle + 1,
+                Ordering::Greater => high = middle,
+                Ordering::Equal => return Some(middle),
+            }
+        }
+        None
+    }
+
+    /// Reads the Plural-Forms line of header entry `header`, as in
+    /// `Plural-Forms: nplurals=2; plural=(n != 1);`
+    fn plural_forms(&mut self, header: &[u8]) {
+        let line = match header
+            .split(|&b| b == b'\n')
+            .find(|line| line.starts_with(b"Plural-Forms:"))
+        {
+            Some(line) => &line[b"Plural-Forms:".len()..],
+            None => return,
+        };
+        for field in line.split(|&b| b == b';') {
+            let field = trim(field);
+            if let Some(value) = field.strip_prefix(b"nplurals=") {
+                let value = core::str::from_utf8(trim(value)).unwrap_or("");
+                if let Ok(plurals) = value.parse::<c_ulong>() {
+                    self.plurals = plurals.max(1);
+                }
+            } else if let Some(value) = field.strip_prefix(b"plural=") {
+                self.plural = value.to_vec();
+            }
+        }
+    }
+
+    /// The translation of `msgid`, in the form for `n` if it is given.
+    fn translate(&self, msgid: &[u8], n: Option<c_ulong>) -> Option<&CStr> {
+        let index = self.find(msgid)?;
+        let entry = self.translations + index * 8;
+        let offset = self.word(entry + 4)? as usize;
+        let translation = self.string(self.translations, index)?;
+
+        let form = n.map_or(0, |n| {
+            plural::evaluate(&self.plural, n)
+                .filter(|&form| form < self.plurals)
+                .unwrap_or(0)
+        });
+        let mut start = 0;
+        for _ in 0..form {
+            match translation[start..].iter().position(|&b| b == 0) {
+                Some(end) => start += end + 1,
+                // A catalog with fewer forms than it says has the first one stand for the rest
+                None => {
+                    start = 0;
+                    break;
+                }
+            }
+        }
+        let end = translation[start..]
+            .iter()
+            .position(|&b| b == 0)
+            .map_or(translation.len(), |end| start + end);
+        Some(unsafe {
+            CStr::from_bytes_with_nul_unchecked(&self.data[offset + start..=offset + end])
+        })
+    }
+}
+
+struct Binding {
+    domain: CString,
+    directory: Option<CString>,
+    codeset: Option<CString>,
+}
+
+/// The current domain, or None for "messages"
+static DOMAIN: Mutex<Option<CString>> = Mutex::new(None);
+static BINDINGS: Mutex<Vec<Binding>> = Mutex::new(Vec::new());
+/// The catalogs looked up so far, with None for those that couldn't be read, which are kept so
+/// that the translations handed out from them stay valid.
+static CATALOGS: Mutex<Vec<(Vec<u8>, Option<&'static Catalog>)>> = Mutex::new(Vec::new());
+
+fn catalog(path: Vec<u8>) -> Option<&'static Catalog> {
+    let mut catalogs = CATALOGS.lock();
+    if let Some(&(_, catalog)) = catalogs.iter().find(|(p, _)| *p == path) {
+        return catalog;
+    }
+    let catalog = Catalog::open(&path).map(|catalog| &*Box::leak(Box::new(catalog)));
+    catalogs.push((path, catalog));
+    catalog
+}
+
+/// The directory `domain` is bound to.
+fn directory(domain: &[u8]) -> Vec<u8> {
+    BINDINGS
+        .lock()
+        .iter()
+        .find(|binding| binding.domain.to_bytes() == domain)
+        .and_then(|binding| binding.directory.as_ref())
+        .map_or(DEFAULT_DIRECTORY.to_bytes().to_vec(), |directory| {
+            directory.to_bytes().to_vec()
+        })
+}
+
+/// The locales whose catalogs are looked in, from `category` of the current locale or else from
+/// LANGUAGE, with each the less specific names it may be found under.
+unsafe fn languages(category: c_int) -> Vec<Vec<u8>> {
+    let name = locale::category(category).name.to_bytes();
+    if let b"C" | b"POSIX" | b"C.UTF-8" | b"C.utf8" = name {
+        return Vec::new();
+    }
+    let language = getenv(c_str!("LANGUAGE").as_ptr());
+    let names = if language.is_null() || *language == 0 {
+        name
+    } else {
+        CStr::from_ptr(language).to_bytes()
+    };
+
+    let mut languages = Vec::new();
+    // Names that could leave the directory aren't looked up
+    let names = names
+        .split(|&b| b == b':')
+        .filter(|name| !name.is_empty() && name[0] != b'.' && !name.contains(&b'/'));
+    for name in names {
+        let candidates = locale::candidates(name);
+        // Then without the territory, as in "fr" for "fr_FR"
+        let without_territory = candidates.iter().filter_map(|candidate| {
+            let underscore = candidate.iter().position(|&b| b == b'_')?;
+            let end = candidate[underscore..]
+                .iter()
+                .position(|&b| b == b'.' || b == b'@')
+                .map_or(candidate.len(), |end| underscore + end);
+            Some([&candidate[..underscore], &candidate[end..]].concat())
+        });
+        for candidate in candidates.iter().cloned().chain(without_territory) {
+            if !languages.contains(&candidate) {
+                languages.push(candidate);
+            }
+        }
+    }
+    languages
+}
+
+unsafe fn translate(
+    domain: *const c_char,
+    msgid: *const c_char,
+    msgid_plural: *const c_char,
+    n: Option<c_ulong>,
+    category: c_int,
+) -> *mut c_char {
+    let fallback = match n {
+        Some(n) if n != 1 && !msgid_plural.is_null() => msgid_plural,
+        _ => msgid,
+    } as *mut c_char;
+    let category_name = match locale::category_name(category) {
+        Some(name) if !msgid.is_null() => name,
+        _ => return fallback,
+    };
+    let domain = if domain.is_null() {
+        match &*DOMAIN.lock() {
+            Some(domain) => domain.to_bytes().to_vec(),
+            None => b"messages".to_vec(),
+        }
+    } else {
+        CStr::from_ptr(domain).to_bytes().to_vec()
+    };
+    let msgid = CStr::from_ptr(msgid).to_bytes();
+
+    // Looking for catalogs doesn't change errno
+    let errno = platform::errno;
+    let directory = directory(&domain);
+    let translation = languages(category).into_iter().find_map(|language| {
+        let path = [
+            &directory[..],
+            b"/",
+            &language,
+            b"/",
+            category_name.as_bytes(),
+            b"/",
+            &domain,
+            b".mo",
+        ]
+        .concat();
+        catalog(path)?.translate(msgid, n)
+    });
+    platform::errno = errno;
+    translation.map_or(fallback, |translation| translation.as_ptr() as *mut c_char)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn gettext(msgid: *const c_char) -> *mut c_char {
+    translate(ptr::null(), msgid, ptr::null(), None, LC_MESSAGES)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn dgettext(domainname: *const c_char, msgid: *const c_char) -> *mut c_char {
+    translate(domainname, msgid, ptr::null(), None, LC_MESSAGES)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn dcgettext(
+    domainname: *const c_char,
+    msgid: *const c_char,
+    category: c_int,
+) -> *mut c_char {
+    translate(domainname, msgid, ptr::null(), None, category)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn ngettext(
+    msgid1: *const c_char,
+    msgid2: *const c_char,
+    n: c_ulong,
+) -> *mut c_char {
+    translate(ptr::null(), msgid1, msgid2, Some(n), LC_MESSAGES)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn dngettext(
+    domainname: *const c_char,
+    msgid1: *const c_char,
+    msgid2: *const c_char,
+    n: c_ulong,
+) -> *mut c_char {
+    translate(domainname, msgid1, msgid2, Some(n), LC_MESSAGES)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn dcngettext(
+    domainname: *const c_char,
+    msgid1: *const c_char,
+    msgid2: *const c_char,
+    n: c_ulong,
+    category: c_int,
+) -> *mut c_char {
+    translate(domainname, msgid1, msgid2, Some(n), category)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn textdomain(domainname: *const c_char) -> *mut c_char {
+    let mut domain = DOMAIN.lock();
+    if !domainname.is_null() {
+        *domain = match CStr::from_ptr(domainname).to_bytes() {
+            b"" | b"messages" => None,
+            name => Some(CString::new(name).unwrap()),
+        };
+    }
+    match &*domain {
+        Some(domain) => domain.as_ptr() as *mut c_char,
+        None => c_str!("messages").as_ptr() as *mut c_char,
+    }
+}
+
+/// Sets what `f` picks of the binding of `domainname` to `value` unless it is null, and returns
+/// it.
+unsafe fn bind(
+    domainname: *const c_char,
+    value: *const c_char,
+    f: impl Fn(&mut Binding) -> &mut Option<CString>,
+) -> Option<*mut c_char> {
+    if domainname.is_null() || *domainname == 0 {
+        platform::errno = EINVAL;
+        return None;
+    }
+    let domain = CStr::from_ptr(domainname);
+    let mut bindings = BINDINGS.lock();
+    let index = match bindings
+        .iter()
+        .position(|binding| binding.domain.as_c_str() == domain)
+    {
+        Some(index) => index,
+        None => {
+            bindings.push(Binding {
+                domain: domain.into(),
+                directory: None,
+                codeset: None,
+            });
+            bindings.len() - 1
+        }
+    };
+    let field = f(&mut bindings[index]);
+    if !value.is_null() {
+        *field = Some(CStr::from_ptr(value).into());
+    }
+    Some(
+        field
+            .as_ref()
+            .map_or(ptr::null_mut(), |value| value.as_ptr() as *mut c_char),
+    )
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn bindtextdomain(
+    domainname: *const c_char,
+    dirname: *const c_char,
+) -> *mut c_char {
+    match bind(domainname, dirname, |binding| &mut binding.directory) {
+        Some(directory) if directory.is_null() => DEFAULT_DIRECTORY.as_ptr() as *mut c_char,
+        Some(directory) => directory,
+        None => ptr::null_mut(),
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn bind_textdomain_codeset(
+    domainname: *const c_char,
+    codeset: *const c_char,
+) -> *mut c_char {
+    bind(domainname, codeset, |binding| &mut binding.codeset).unwrap_or(ptr::null_mut())
+}
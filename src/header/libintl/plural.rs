@@ -0,0 +1,117 @@
+//! The plural forms expressions of catalogs, which are C expressions of the count `n`.
+
+use crate::platform::types::*;
+
+/// The binary operators, from the lowest to the highest precedence, with at each level those a
+/// shorter one is the start of coming first
+const OPERATORS: &[&[&str]] = &[
+    &["||"],
+    &["&&"],
+    &["==", "!="],
+    &["<=", ">=", "<", ">"],
+    &["+", "-"],
+    &["*", "/", "%"],
+];
+
+struct Parser<'a> {
+    expression: &'a [u8],
+    n: c_ulong,
+}
+
+impl<'a> Parser<'a> {
+    fn skip_spaces(&mut self) {
+        while let Some((b, rest)) = self.expression.split_first() {
+            if !b.is_ascii_whitespace() {
+                break;
+            }
+            self.expression = rest;
+        }
+    }
+
+    fn eat(&mut self, token: &str) -> bool {
+        self.skip_spaces();
+        match self.expression.strip_prefix(token.as_bytes()) {
+            Some(rest) => {
+                self.expression = rest;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn conditional(&mut self) -> Option<c_ulong> {
+        let condition = self.binary(0)?;
+        if !self.eat("?") {
+            return Some(condition);
+        }
+        let then = self.conditional()?;
+        if !self.eat(":") {
+            return None;
+        }
+        let otherwise = self.conditional()?;
+        Some(if condition != 0 { then } else { otherwise })
+    }
+
+    fn binary(&mut self, level: usize) -> Option<c_ulong> {
+        if level == OPERATORS.len() {
+            return self.unary();
+        }
+        let mut value = self.binary(level + 1)?;
+        while let Some(&operator) = OPERATORS[level].iter().find(|&&op| self.eat(op)) {
+            let right = self.binary(level + 1)?;
+            value = match operator {
+                "||" => (value != 0 || right != 0) as c_ulong,
+                "&&" => (value != 0 && right != 0) as c_ulong,
+                "==" => (value == right) as c_ulong,
+                "!=" => (value != right) as c_ulong,
+                "<=" => (value <= right) as c_ulong,
+                ">=" => (value >= right) as c_ulong,
+                "<" => (value < right) as c_ulong,
+                ">" => (value > right) as c_ulong,
+                "+" => value.wrapping_add(right),
+                "-" => value.wrapping_sub(right),
+                "*" => value.wrapping_mul(right),
+                "/" => value.checked_div(right)?,
+                _ => value.checked_rem(right)?,
+            };
+        }
+        Some(value)
+    }
+
+    fn unary(&mut self) -> Option<c_ulong> {
+        if self.eat("!") {
+            return Some((self.unary()? == 0) as c_ulong);
+        }
+        if self.eat("(") {
+            let value = self.conditional()?;
+            return if self.eat(")") { Some(value) } else { None };
+        }
+        if self.eat("n") {
+            return Some(self.n);
+        }
+        let digits = self
+            .expression
+            .iter()
+            .take_while(|b| b.is_ascii_digit())
+            .count();
+        if digits == 0 {
+            return None;
+        }
+        let (number, rest) = self.expression.split_at(digits);
+        self.expression = rest;
+        core::str::from_utf8(number).ok()?.parse().ok()
+    }
+}
+
+/// The value of `expression` for count `n`, or None if it isn't a valid expression or divides
+/// by zero.
+pub fn evaluate(expression: &[u8], n: c_ulong) -> Option<c_ulong> {
+    let mut parser = Parser { expression, n };
+    let value = parser.conditional()?;
+    parser.skip_spaces();
+    if parser.expression.is_empty() {
+        Some(value)
+    } else {
+        None
+    }
+}
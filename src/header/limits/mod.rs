@@ -1,3 +1,6 @@
 //! limits.h implementation for relibc
 
+pub const HOST_NAME_MAX: usize = 64;
+pub const NAME_MAX: usize = 255;
+pub const MQ_PRIO_MAX: usize = 32768;
 pub const PATH_MAX: usize = 4096;
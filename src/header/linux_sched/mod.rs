@@ -0,0 +1,203 @@
+//! linux/sched.h implementation, following https://man7.org/linux/man-pages/man2/clone.2.html
+//!
+//! sched.h is pthreads-emb's, so clone, clone3, unshare and setns are declared here, with the
+//! flags and struct clone_args the kernel's header of this name has. Only Linux has them, they
+//! fail with ENOSYS on Redox.
+
+use core::{mem, ptr, slice};
+
+use crate::{
+    header::errno::{E2BIG, EINVAL},
+    platform::{self, types::*, Pal, Sys},
+    sync,
+};
+
+pub const CSIGNAL: c_int = 0x0000_00ff;
+pub const CLONE_NEWTIME: c_int = 0x0000_0080;
+pub const CLONE_VM: c_int = 0x0000_0100;
+pub const CLONE_FS: c_int = 0x0000_0200;
+pub const CLONE_FILES: c_int = 0x0000_0400;
+pub const CLONE_SIGHAND: c_int = 0x0000_0800;
+pub const CLONE_PIDFD: c_int = 0x0000_1000;
+pub const CLONE_PTRACE: c_int = 0x0000_2000;
+pub const CLONE_VFORK: c_int = 0x0000_4000;
+pub const CLONE_PARENT: c_int = 0x0000_8000;
+pub const CLONE_THREAD: c_int = 0x0001_0000;
+pub const CLONE_NEWNS: c_int = 0x0002_0000;
+pub const CLONE_SYSVSEM: c_int = 0x0004_0000;
+pub const CLONE_SETTLS: c_int = 0x0008_0000;
+pub const CLONE_PARENT_SETTID: c_int = 0x0010_0000;
+pub const CLONE_CHILD_CLEARTID: c_int = 0x0020_0000;
+pub const CLONE_DETACHED: c_int = 0x0040_0000;
+pub const CLONE_UNTRACED: c_int = 0x0080_0000;
+pub const CLONE_CHILD_SETTID: c_int = 0x0100_0000;
+pub const CLONE_NEWCGROUP: c_int = 0x0200_0000;
+pub const CLONE_NEWUTS: c_int = 0x0400_0000;
+pub const CLONE_NEWIPC: c_int = 0x0800_0000;
+pub const CLONE_NEWUSER: c_int = 0x1000_0000;
+pub const CLONE_NEWPID: c_int = 0x2000_0000;
+pub const CLONE_NEWNET: c_int = 0x4000_0000;
+pub const CLONE_IO: c_int = 0x8000_0000_u32 as c_int;
+
+// Only clone3 takes these
+pub const CLONE_CLEAR_SIGHAND: u64 = 0x1_0000_0000;
+pub const CLONE_INTO_CGROUP: u64 = 0x2_0000_0000;
+
+pub const CLONE_ARGS_SIZE_VER0: size_t = 64;
+pub const CLONE_ARGS_SIZE_VER1: size_t = 80;
+pub const CLONE_ARGS_SIZE_VER2: size_t = 88;
+
+/// What clone3 takes. Pointers are held in 64 bits, on every architecture.
+#[repr(C)]
+#[derive(Clone, Copy, Default)]
+pub struct clone_args {
+    pub flags: u64,
+    /// Where the pidfd goes, with CLONE_PIDFD
+    pub pidfd: u64,
+    pub child_tid: u64,
+    pub parent_tid: u64,
+    pub exit_signal: u64,
+    /// The lowest address of the stack, not its top as clone takes
+    pub stack: u64,
+    pub stack_size: u64,
+    pub tls: u64,
+    /// The thread IDs the child is given in each PID namespace, the innermost first
+    pub set_tid: u64,
+    pub set_tid_size: u64,
+    /// The cgroup the child starts in, with CLONE_INTO_CGROUP
+    pub cgroup: u64,
+}
+
+/// What the child calls
+pub type clone_fn = Option<unsafe extern "C" fn(arg: *mut c_void) -> c_int>;
+
+struct Start {
+    func: unsafe extern "C" fn(arg: *mut c_void) -> c_int,
+    arg: *mut c_void,
+}
+
+/// Makes what fork holds usable in a child that has a copy of the memory, before calling its
+/// function.
+unsafe extern "C" fn start_forked(start: *mut c_void) -> c_int {
+    let start = &*(start as *const Start);
+    sync::fork::child();
+    (start.func)(start.arg)
+}
+
+/// Creates the child. One with a copy of the memory gets it with every lock fork holds in a
+/// consistent state, one that shares it shares the locks too.
+unsafe fn create(args: &clone_args, func: clone_fn, arg: *mut c_void) -> pid_t {
+    // Without a function, the child would return on the stack it was given or the one it shares
+    if func.is_none() && (args.stack != 0 || args.flags & CLONE_VM as u64 != 0) {
+        platform::errno = EINVAL;
+        return -1;
+    }
+    if args.flags & CLONE_VM as u64 != 0 {
+        return Sys::clone3(args, func, arg);
+    }
+
+    // Read by the child, in its copy of this stack
+    let mut start = func.map(|func| Start { func, arg });
+    sync::fork::prepare();
+    let pid = match start {
+        Some(ref mut start) => {
+            Sys::clone3(args, Some(start_forked), start as *mut Start as *mut c_void)
+        }
+        None => Sys::clone3(args, None, ptr::null_mut()),
+    };
+    if pid == 0 {
+        sync::fork::child();
+    } else {
+        sync::fork::parent();
+    }
+    pid
+}
+
+/// Called by clone, which only has `parent_tid`, `tls` and `child_tid` with the flags that use
+/// them.
+#[no_mangle]
+pub unsafe extern "C" fn sys_clone(
+    func: clone_fn,
+    stack: *mut c_void,
+    flags: c_int,
+    arg: *mut c_void,
+    parent_tid: *mut pid_t,
+    tls: *mut c_void,
+    child_tid: *mut pid_t,
+) -> c_int {
+    if func.is_none() || stack.is_null() {
+        platform::errno = EINVAL;
+        return -1;
+    }
+    // The old system call puts the pidfd where the parent's thread ID would go
+    let args = clone_args {
+        flags: (flags & !CSIGNAL) as c_uint as u64,
+        pidfd: parent_tid as usize as u64,
+        child_tid: child_tid as usize as u64,
+        parent_tid: parent_tid as usize as u64,
+        exit_signal: (flags & CSIGNAL) as u64,
+        // The top of the stack, which only the old system call takes
+        stack: stack as usize as u64,
+        stack_size: 0,
+        tls: tls as usize as u64,
+        ..Default::default()
+    };
+    trace_expr!(
+        create(&args, func, arg),
+        "clone({:p}, {:#x}, {:p})",
+        stack,
+        flags,
+        arg
+    )
+}
+
+/// Creates a child as the clone3 system call does. The child calls `func` with `arg` and exits
+/// with what it returns, or returns 0 if `func` is null, which it can only do with no stack and
+/// a copy of the memory, as with fork.
+#[no_mangle]
+pub unsafe extern "C" fn clone3(
+    cl_args: *const clone_args,
+    size: size_t,
+    func: clone_fn,
+    arg: *mut c_void,
+) -> pid_t {
+    // Newer versions are longer, and can be used while what they add isn't
+    if cl_args.is_null() || size < CLONE_ARGS_SIZE_VER0 {
+        platform::errno = EINVAL;
+        return -1;
+    }
+    let ours = mem::size_of::<clone_args>();
+    if size > ours {
+        let rest = slice::from_raw_parts((cl_args as *const u8).add(ours), size - ours);
+        if rest.iter().any(|&byte| byte != 0) {
+            platform::errno = E2BIG;
+            return -1;
+        }
+    }
+    let mut args = clone_args::default();
+    ptr::copy_nonoverlapping(
+        cl_args as *const u8,
+        &mut args as *mut clone_args as *mut u8,
+        size.min(ours),
+    );
+    trace_expr!(
+        create(&args, func, arg),
+        "clone3({:p}, {}, {:p})",
+        cl_args,
+        size,
+        arg
+    )
+}
+
+/// Moves the calling thread into new namespaces, or stops it sharing what it shared.
+#[no_mangle]
+pub extern "C" fn unshare(flags: c_int) -> c_int {
+    trace_expr!(Sys::unshare(flags), "unshare({:#x})", flags)
+}
+
+/// Moves the calling thread into the namespace `fd` refers to, which must be of type `nstype`
+/// unless it's 0.
+#[no_mangle]
+pub extern "C" fn setns(fd: c_int, nstype: c_int) -> c_int {
+    trace_expr!(Sys::setns(fd, nstype), "setns({}, {:#x})", fd, nstype)
+}
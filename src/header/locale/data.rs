@@ -0,0 +1,314 @@
+//! Locale data, loaded from a locale directory.
+//!
+//! A locale is a directory named after it, in LOCPATH or else LOCALE_PATH, with a file for each
+//! category: LC_COLLATE, LC_CTYPE, LC_MESSAGES, LC_MONETARY, LC_NUMERIC and LC_TIME. Each line of
+//! a file is a keyword and its value, separated by blanks, with `;` between the values of the
+//! items that have several, such as the names of the days. In values, `\;`, `\\` and `\xHH` stand
+//! for a semicolon, a backslash and any byte. Lines starting with `#` are comments. Items a file
+//! leaves out have their value in the C locale.
+
+use alloc::{boxed::Box, collections::BTreeMap, vec::Vec};
+
+use super::{LC_COLLATE, LC_CTYPE, LC_MESSAGES, LC_MONETARY, LC_NUMERIC, LC_TIME};
+use crate::{
+    c_str::{CStr, CString},
+    fs::File,
+    header::{fcntl::O_RDONLY, iconv::Multibyte, stdlib::getenv},
+    io::Read,
+    platform::types::*,
+    sync::Mutex,
+};
+
+/// Where locales are looked for when LOCPATH isn't set
+pub const LOCALE_PATH: &str = "/usr/share/relibc/locale";
+
+/// The category names, which are also the names of their files
+pub const NAMES: [&str; 7] = [
+    "LC_ALL",
+    "LC_COLLATE",
+    "LC_CTYPE",
+    "LC_MESSAGES",
+    "LC_MONETARY",
+    "LC_NUMERIC",
+    "LC_TIME",
+];
+
+pub struct Category {
+    /// The name the category was set by
+    pub name: CString,
+    items: BTreeMap<Vec<u8>, Vec<CString>>,
+}
+
+impl Category {
+    /// Value `index` of `keyword`, if the locale has it.
+    pub fn get(&self, keyword: &str, index: usize) -> Option<&CStr> {
+        self.items.get(keyword.as_bytes()).map(|values| {
+            values
+                .get(index)
+                .map_or(c_str!(""), |value| value.as_c_str())
+        })
+    }
+}
+
+/// The items of the C locale, with the values of those that have several separated by `;`
+const C_ITEMS: &[(c_int, &str, &str)] = &[
+    (LC_CTYPE, "charmap", "UTF-8"),
+    (LC_NUMERIC, "decimal_point", "."),
+    (LC_NUMERIC, "thousands_sep", ""),
+    (LC_NUMERIC, "grouping", ""),
+    (LC_MONETARY, "int_curr_symbol", ""),
+    (LC_MONETARY, "currency_symbol", ""),
+    (LC_MONETARY, "mon_decimal_point", ""),
+    (LC_MONETARY, "mon_thousands_sep", ""),
+    (LC_MONETARY, "mon_grouping", ""),
+    (LC_MONETARY, "positive_sign", ""),
+    (LC_MONETARY, "negative_sign", ""),
+    // -1 stands for CHAR_MAX, the value not being available
+    (LC_MONETARY, "int_frac_digits", "-1"),
+    (LC_MONETARY, "frac_digits", "-1"),
+    (LC_MONETARY, "p_cs_precedes", "-1"),
+    (LC_MONETARY, "p_sep_by_space", "-1"),
+    (LC_MONETARY, "n_cs_precedes", "-1"),
+    (LC_MONETARY, "n_sep_by_space", "-1"),
+    (LC_MONETARY, "p_sign_posn", "-1"),
+    (LC_MONETARY, "n_sign_posn", "-1"),
+    (LC_TIME, "abday", "Sun;Mon;Tue;Wed;Thu;Fri;Sat"),
+    (
+        LC_TIME,
+        "day",
+        "Sunday;Monday;Tuesday;Wednesday;Thursday;Friday;Saturday",
+    ),
+    (
+        LC_TIME,
+        "abmon",
+        "Jan;Feb;Mar;Apr;May;Jun;Jul;Aug;Sep;Oct;Nov;Dec",
+    ),
+    (
+        LC_TIME,
+        "mon",
+        "January;February;March;April;May;June;July;August;September;October;November;December",
+    ),
+    (LC_TIME, "am_pm", "AM;PM"),
+    (LC_TIME, "d_t_fmt", "%a %b %e %H:%M:%S %Y"),
+    (LC_TIME, "d_fmt", "%m/%d/%y"),
+    (LC_TIME, "t_fmt", "%H:%M:%S"),
+    (LC_TIME, "t_fmt_ampm", "%I:%M:%S %p"),
+    (LC_MESSAGES, "yesexpr", "^[yY]"),
+    (LC_MESSAGES, "noexpr", "^[nN]"),
+    (LC_MESSAGES, "yesstr", "yes"),
+    (LC_MESSAGES, "nostr", "no"),
+];
+
+/// The C locale, which stands for every item of `category`.
+fn c_locale(category: c_int) -> BTreeMap<Vec<u8>, Vec<CString>> {
+    C_ITEMS
+        .iter()
+        .filter(|item| item.0 == category)
+        .map(|&(_, keyword, values)| {
+            let values = values
+                .split(';')
+                .map(|value| CString::new(value).unwrap())
+                .collect();
+            (keyword.as_bytes().to_vec(), values)
+        })
+        .collect()
+}
+
+/// Reads one value, up to an unescaped `;` or the end of `value`.
+fn unescape(value: &mut &[u8]) -> Vec<u8> {
+    let mut out = Vec::new();
+    while let Some((&byte, rest)) = value.split_first() {
+        *value = rest;
+        match byte {
+            b';' => break,
+            b'\\' => match value.split_first() {
+                Some((&b'x', rest)) if rest.len() >= 2 => {
+                    let hex = core::str::from_utf8(&rest[..2]).ok();
+                    match hex.and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+                        Some(byte) => {
+                            out.push(byte);
+                            *value = &rest[2..];
+                        }
+                        None => out.push(b'\\'),
+                    }
+                }
+                Some((&escaped, rest)) => {
+                    out.push(escaped);
+                    *value = rest;
+                }
+                None => out.push(b'\\'),
+            },
+            _ => out.push(byte),
+        }
+    }
+    out
+}
+
+fn parse(data: &[u8], items: &mut BTreeMap<Vec<u8>, Vec<CString>>) {
+    for line in data.split(|&b| b == b'\n') {
+        if line.first() == Some(&b'#') {
+            continue;
+        }
+        let end = line
+            .iter()
+            .position(|b| b.is_ascii_whitespace())
+            .unwrap_or(line.len());
+        let (keyword, rest) = line.split_at(end);
+        if keyword.is_empty() {
+            continue;
+        }
+        let start = rest
+            .iter()
+            .position(|b| !b.is_ascii_whitespace())
+            .unwrap_or(rest.len());
+        let mut value = &rest[start..];
+
+        let mut values = Vec::new();
+        loop {
+            // Values can't hold a NUL, they stop at one
+            let mut bytes = unescape(&mut value);
+            if let Some(nul) = bytes.iter().position(|&b| b == 0) {
+                bytes.truncate(nul);
+            }
+            values.push(CString::new(bytes).unwrap());
+            if value.is_empty() {
+                break;
+            }
+        }
+        items.insert(keyword.to_vec(), values);
+    }
+}
+
+/// The codeset of a locale name normalized like glibc does, "UTF-8" into "utf8" and "8859-1"
+/// into "iso88591".
+pub fn normalize_codeset(codeset: &[u8]) -> Vec<u8> {
+    let mut normalized: Vec<u8> = codeset
+        .iter()
+        .filter(|b| b.is_ascii_alphanumeric())
+        .map(|b| b.to_ascii_lowercase())
+        .collect();
+    if normalized.iter().all(|b| b.is_ascii_digit()) {
+        normalized.splice(0..0, b"iso".iter().cloned());
+    }
+    normalized
+}
+
+/// The directories a locale may be in, from the most to the least specific: the name as given,
+/// then with its codeset normalized, then without its codeset, then without its modifier.
+pub fn candidates(name: &[u8]) -> Vec<Vec<u8>> {
+    let at = name.iter().position(|&b| b == b'@').unwrap_or(name.len());
+    let (base, modifier) = name.split_at(at);
+    let dot = base.iter().position(|&b| b == b'.').unwrap_or(base.len());
+    let (language, codeset) = base.split_at(dot);
+
+    let mut candidates = vec![name.to_vec()];
+    if !codeset.is_empty() {
+        let mut normalized = language.to_vec();
+        normalized.push(b'.');
+        normalized.extend(normalize_codeset(&codeset[1..]));
+        normalized.extend_from_slice(modifier);
+        candidates.push(normalized);
+        candidates.push([language, modifier].concat());
+    }
+    if !modifier.is_empty() {
+        candidates.push(language.to_vec());
+    }
+    candidates.dedup();
+    candidates
+}
+
+fn read(path: &[u8]) -> Option<Vec<u8>> {
+    let path = CString::new(path).ok()?;
+    let mut file = File::open(&path, O_RDONLY).ok()?;
+    let mut data = Vec::new();
+    file.read_to_end(&mut data).ok()?;
+    Some(data)
+}
+
+unsafe fn locale_path() -> Vec<u8> {
+    let path = getenv(c_str!("LOCPATH").as_ptr());
+    if path.is_null() || *path == 0 {
+        LOCALE_PATH.as_bytes().to_vec()
+    } else {
+        CStr::from_ptr(path).to_bytes().to_vec()
+    }
+}
+
+/// Whether the multibyte functions can handle `charmap`.
+fn supported_charmap(charmap: &CStr) -> bool {
+    Multibyte::new(charmap.to_bytes()).is_some()
+}
+
+/// Reads `category` of locale `name`.
+unsafe fn load_category(category: c_int, name: &[u8]) -> Option<Category> {
+    let mut items = c_locale(category);
+    match name {
+        b"C" | b"POSIX" | b"C.UTF-8" | b"C.utf8" => (),
+        // Names that could leave the locale directory aren't looked up
+        _ if name.is_empty() || name.len() > 255 || name[0] == b'.' || name.contains(&b'/') => {
+            return None;
+        }
+        _ => {
+            let path = locale_path();
+            let data = candidates(name).into_iter().find_map(|candidate| {
+                let file = [
+                    &path[..],
+                    b"/",
+                    &candidate,
+                    b"/",
+                    NAMES[category as usize].as_bytes(),
+                ]
+                .concat();
+                read(&file)
+            })?;
+            parse(&data, &mut items);
+        }
+    }
+
+    let category = Category {
+        name: CString::new(name).ok()?,
+        items,
+    };
+    if let Some(charmap) = category.get("charmap", 0) {
+        if !supported_charmap(charmap) {
+            return None;
+        }
+    }
+    Some(category)
+}
+
+/// The categories loaded so far, which are kept so that what was handed out from them stays
+/// valid.
+static LOADED: Mutex<Vec<(c_int, &'static Category)>> = Mutex::new(Vec::new());
+
+/// Gets `category` of locale `name`, loading it the first time.
+pub unsafe fn load(category: c_int, name: &[u8]) -> Option<&'static Category> {
+    let mut loaded = LOADED.lock();
+    if let Some(&(_, found)) = loaded
+        .iter()
+        .find(|(c, found)| *c == category && found.name.to_bytes() == name)
+    {
+        return Some(found);
+    }
+    let found: &'static Category = Box::leak(Box::new(load_category(category, name)?));
+    loaded.push((category, found));
+    Some(found)
+}
+
+/// The C locale of `category`.
+pub unsafe fn c(category: c_int) -> &'static Category {
+    load(category, b"C").unwrap()
+}
+
+/// Whether `category` is one a locale is made of.
+pub fn valid(category: c_int) -> bool {
+    [
+        LC_COLLATE,
+        LC_CTYPE,
+        LC_MESSAGES,
+        LC_MONETARY,
+        LC_NUMERIC,
+        LC_TIME,
+    ]
+    .contains(&category)
+}
@@ -1,12 +1,71 @@
 //! locale implementation for Redox, following http://pubs.opengroup.org/onlinepubs/7908799/xsh/locale.h.html
 
-use core::ptr;
+use alloc::{boxed::Box, vec::Vec};
+use core::{convert::TryFrom, ptr};
 
-use crate::platform::types::*;
+use crate::{
+    c_str::{CStr, CString},
+    header::{
+        errno::{EINVAL, ENOENT},
+        stdlib::getenv,
+    },
+    platform::{self, types::*},
+};
+
+mod data;
+
+pub const LC_ALL: c_int = 0;
+pub const LC_COLLATE: c_int = 1;
+pub const LC_CTYPE: c_int = 2;
+pub const LC_MESSAGES: c_int = 3;
+pub const LC_MONETARY: c_int = 4;
+pub const LC_NUMERIC: c_int = 5;
+pub const LC_TIME: c_int = 6;
+
+pub const LC_COLLATE_MASK: c_int = 1 << LC_COLLATE;
+pub const LC_CTYPE_MASK: c_int = 1 << LC_CTYPE;
+pub const LC_MESSAGES_MASK: c_int = 1 << LC_MESSAGES;
+pub const LC_MONETARY_MASK: c_int = 1 << LC_MONETARY;
+pub const LC_NUMERIC_MASK: c_int = 1 << LC_NUMERIC;
+pub const LC_TIME_MASK: c_int = 1 << LC_TIME;
+pub const LC_ALL_MASK: c_int = LC_COLLATE_MASK
+    | LC_CTYPE_MASK
+    | LC_MESSAGES_MASK
+    | LC_MONETARY_MASK
+    | LC_NUMERIC_MASK
+    | LC_TIME_MASK;
+
+/// What uselocale takes to go back to the global locale, and LC_GLOBAL_LOCALE in C
+const GLOBAL_LOCALE: locale_t = -1isize as locale_t;
 
 const EMPTY_PTR: *const c_char = "\0" as *const _ as *const c_char;
-// Can't use &str because of the mutability
-static mut C_LOCALE: [c_char; 2] = [b'C' as c_char, 0];
+
+/// The categories of a locale, by number, those that are None being in the C locale
+type Categories = [Option<&'static data::Category>; 7];
+
+// No repr(C) needed, C won't see the content
+#[derive(Clone)]
+pub struct __locale_struct {
+    categories: Categories,
+}
+
+pub type locale_t = *mut __locale_struct;
+
+static mut GLOBAL: Categories = [None; 7];
+/// The locale of the thread, or null if it uses the global one
+#[thread_local]
+static mut THREAD_LOCALE: locale_t = ptr::null_mut();
+/// The name setlocale returns for LC_ALL when the categories aren't all in the same locale
+static mut COMPOSITE: Option<CString> = None;
+/// The order categories are in in a composite name, as in glibc
+const COMPOSITE_ORDER: [c_int; 6] = [
+    LC_CTYPE,
+    LC_NUMERIC,
+    LC_TIME,
+    LC_COLLATE,
+    LC_MONETARY,
+    LC_MESSAGES,
+];
 
 #[repr(C)]
 #[no_mangle]
@@ -17,6 +76,12 @@ pub struct lconv {
     grouping: *const c_char,
     int_curr_symbol: *const c_char,
     int_frac_digits: c_char,
+    int_n_cs_precedes: c_char,
+    int_n_sep_by_space: c_char,
+    int_n_sign_posn: c_char,
+    int_p_cs_precedes: c_char,
+    int_p_sep_by_space: c_char,
+    int_p_sign_posn: c_char,
     mon_decimal_point: *const c_char,
     mon_grouping: *const c_char,
     mon_thousands_sep: *const c_char,
@@ -37,34 +102,342 @@ unsafe impl Sync for lconv {}
 static mut CURRENT_LOCALE: lconv = lconv {
     currency_symbol: EMPTY_PTR,
     decimal_point: ".\0" as *const _ as *const c_char,
-    frac_digits: c_char::max_value(),
+    frac_digits: c_char::MAX,
     grouping: EMPTY_PTR,
     int_curr_symbol: EMPTY_PTR,
-    int_frac_digits: c_char::max_value(),
+    int_frac_digits: c_char::MAX,
+    int_n_cs_precedes: c_char::MAX,
+    int_n_sep_by_space: c_char::MAX,
+    int_n_sign_posn: c_char::MAX,
+    int_p_cs_precedes: c_char::MAX,
+    int_p_sep_by_space: c_char::MAX,
+    int_p_sign_posn: c_char::MAX,
     mon_decimal_point: EMPTY_PTR,
     mon_grouping: EMPTY_PTR,
     mon_thousands_sep: EMPTY_PTR,
     negative_sign: EMPTY_PTR,
-    n_cs_precedes: c_char::max_value(),
-    n_sep_by_space: c_char::max_value(),
-    n_sign_posn: c_char::max_value(),
+    n_cs_precedes: c_char::MAX,
+    n_sep_by_space: c_char::MAX,
+    n_sign_posn: c_char::MAX,
     positive_sign: EMPTY_PTR,
-    p_cs_precedes: c_char::max_value(),
-    p_sep_by_space: c_char::max_value(),
-    p_sign_posn: c_char::max_value(),
+    p_cs_precedes: c_char::MAX,
+    p_sep_by_space: c_char::MAX,
+    p_sign_posn: c_char::MAX,
     thousands_sep: EMPTY_PTR,
 };
 
+unsafe fn get(categories: &Categories, category: c_int) -> &'static data::Category {
+    categories[category as usize].unwrap_or_else(|| data::c(category))
+}
+
+/// `category` of the current locale, that of the thread if it has one.
+pub(crate) unsafe fn category(category: c_int) -> &'static data::Category {
+    if THREAD_LOCALE.is_null() {
+        get(&GLOBAL, category)
+    } else {
+        get(&(*THREAD_LOCALE).categories, category)
+    }
+}
+
+/// The name of `category`, as in "LC_MESSAGES", if it is one a locale is made of.
+pub(crate) fn category_name(category: c_int) -> Option<&'static str> {
+    if data::valid(category) {
+        Some(data::NAMES[category as usize])
+    } else {
+        None
+    }
+}
+
+/// The names locale `name` may be found under, from the most to the least specific.
+pub(crate) fn candidates(name: &[u8]) -> Vec<Vec<u8>> {
+    data::candidates(name)
+}
+
+/// Runs `f` in locale `locale` rather than the current one, for the functions that take one.
+pub(crate) unsafe fn with<T>(locale: locale_t, f: impl FnOnce() -> T) -> T {
+    let old = uselocale(locale);
+    let result = f();
+    uselocale(old);
+    result
+}
+
+/// Value `index` of `keyword` in `category` of the current locale, or an empty string if there
+/// is no such item. It stays valid until the locale changes.
+pub(crate) unsafe fn item(category: c_int, keyword: &str, index: usize) -> &'static CStr {
+    self::category(category)
+        .get(keyword, index)
+        .unwrap_or(c_str!(""))
+}
+
+/// The decimal point of LC_NUMERIC, or "." if the locale's is empty or isn't UTF-8.
+pub(crate) unsafe fn decimal_point() -> &'static str {
+    match core::str::from_utf8(item(LC_NUMERIC, "decimal_point", 0).to_bytes()) {
+        Ok(decimal_point) if !decimal_point.is_empty() => decimal_point,
+        _ => ".",
+    }
+}
+
+/// The groupings of LC_NUMERIC and LC_MONETARY, in the form of lconv
+static mut GROUPING: [c_char; 16] = [0; 16];
+static mut MON_GROUPING: [c_char; 16] = [0; 16];
+
+/// Turns a grouping such as "3;2" into the sizes of the groups, from the right, with -1 for no
+/// further grouping.
+unsafe fn grouping(category: c_int, keyword: &str, out: &mut [c_char]) {
+    let mut len = 0;
+    while len < out.len() - 1 {
+        let group = item(category, keyword, len).to_bytes();
+        if group.is_empty() {
+            break;
+        }
+        out[len] = match number(group) {
+            Some(c_char::MAX) => c_char::MAX,
+            Some(size) if size > 0 => size,
+            _ => break,
+        };
+        len += 1;
+    }
+    out[len] = 0;
+}
+
+/// A number of the locale data, -1 standing for CHAR_MAX, which stands for a value that isn't
+/// available.
+fn number(value: &[u8]) -> Option<c_char> {
+    match core::str::from_utf8(value).ok()?.parse::<i16>().ok()? {
+        -1 => Some(c_char::MAX),
+        number => c_char::try_from(number).ok(),
+    }
+}
+
+/// A number of LC_MONETARY, or CHAR_MAX if there is none.
+pub(crate) unsafe fn monetary(keyword: &str) -> c_char {
+    number(item(LC_MONETARY, keyword, 0).to_bytes()).unwrap_or(c_char::MAX)
+}
+
+/// Inserts the thousands separator in `digits`, as LC_NUMERIC groups them, or LC_MONETARY if
+/// `monetary` is set.
+pub(crate) unsafe fn group_digits(digits: &[u8], monetary: bool) -> Vec<u8> {
+    let (separator, keyword) = if monetary {
+        (item(LC_MONETARY, "mon_thousands_sep", 0), "mon_grouping")
+    } else {
+        (item(LC_NUMERIC, "thousands_sep", 0), "grouping")
+    };
+    let category = if monetary { LC_MONETARY } else { LC_NUMERIC };
+    let mut groups = [0; 16];
+    grouping(category, keyword, &mut groups);
+    if separator.to_bytes().is_empty() || groups[0] == 0 {
+        return digits.to_vec();
+    }
+
+    // Groups are counted from the right, the last size repeating
+    let mut grouped = Vec::new();
+    let (mut end, mut group) = (digits.len(), 0);
+    loop {
+        let size = groups[group];
+        if size == c_char::MAX || end <= size as usize {
+            grouped.splice(0..0, digits[..end].iter().cloned());
+            break;
+        }
+        grouped.splice(0..0, digits[end - size as usize..end].iter().cloned());
+        grouped.splice(0..0, separator.to_bytes().iter().cloned());
+        end -= size as usize;
+        if groups[group + 1] != 0 {
+            group += 1;
+        }
+    }
+    grouped
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn localeconv() -> *mut lconv {
-    &mut CURRENT_LOCALE as *mut _
+    let conv = &mut CURRENT_LOCALE;
+    conv.decimal_point = item(LC_NUMERIC, "decimal_point", 0).as_ptr();
+    conv.thousands_sep = item(LC_NUMERIC, "thousands_sep", 0).as_ptr();
+    grouping(LC_NUMERIC, "grouping", &mut GROUPING);
+    conv.grouping = GROUPING.as_ptr();
+
+    conv.int_curr_symbol = item(LC_MONETARY, "int_curr_symbol", 0).as_ptr();
+    conv.currency_symbol = item(LC_MONETARY, "currency_symbol", 0).as_ptr();
+    conv.mon_decimal_point = item(LC_MONETARY, "mon_decimal_point", 0).as_ptr();
+    conv.mon_thousands_sep = item(LC_MONETARY, "mon_thousands_sep", 0).as_ptr();
+    grouping(LC_MONETARY, "mon_grouping", &mut MON_GROUPING);
+    conv.mon_grouping = MON_GROUPING.as_ptr();
+    conv.positive_sign = item(LC_MONETARY, "positive_sign", 0).as_ptr();
+    conv.negative_sign = item(LC_MONETARY, "negative_sign", 0).as_ptr();
+    conv.int_frac_digits = monetary("int_frac_digits");
+    conv.frac_digits = monetary("frac_digits");
+    conv.p_cs_precedes = monetary("p_cs_precedes");
+    conv.p_sep_by_space = monetary("p_sep_by_space");
+    conv.n_cs_precedes = monetary("n_cs_precedes");
+    conv.n_sep_by_space = monetary("n_sep_by_space");
+    conv.p_sign_posn = monetary("p_sign_posn");
+    conv.n_sign_posn = monetary("n_sign_posn");
+
+    // The international format is the local one unless the locale says otherwise
+    let international = |keyword: &str, local: c_char| match monetary(keyword) {
+        c_char::MAX => local,
+        value => value,
+    };
+    conv.int_p_cs_precedes = international("int_p_cs_precedes", conv.p_cs_precedes);
+    conv.int_p_sep_by_space = international("int_p_sep_by_space", conv.p_sep_by_space);
+    conv.int_n_cs_precedes = international("int_n_cs_precedes", conv.n_cs_precedes);
+    conv.int_n_sep_by_space = international("int_n_sep_by_space", conv.n_sep_by_space);
+    conv.int_p_sign_posn = international("int_p_sign_posn", conv.p_sign_posn);
+    conv.int_n_sign_posn = international("int_n_sign_posn", conv.n_sign_posn);
+    conv
+}
+
+/// The locale in the environment for `category`, from LC_ALL, the variable named after the
+/// category, or LANG, the first one set.
+unsafe fn from_env(category: c_int) -> Vec<u8> {
+    for var in [
+        data::NAMES[LC_ALL as usize],
+        data::NAMES[category as usize],
+        "LANG",
+    ]
+    .iter()
+    {
+        let var = CString::new(*var).unwrap();
+        let value = getenv(var.as_ptr());
+        if !value.is_null() && *value != 0 {
+            return CStr::from_ptr(value).to_bytes().to_vec();
+        }
+    }
+    b"C".to_vec()
+}
+
+/// The name of the locale of `category` in a composite name, such as setlocale returns for
+/// LC_ALL.
+fn from_composite(name: &[u8], category: c_int) -> Option<&[u8]> {
+    name.split(|&b| b == b';').find_map(|part| {
+        let eq = part.iter().position(|&b| b == b'=')?;
+        if &part[..eq] == data::NAMES[category as usize].as_bytes() {
+            Some(&part[eq + 1..])
+        } else {
+            None
+        }
+    })
+}
+
+unsafe fn name(categories: &Categories, category: c_int) -> *mut c_char {
+    if category != LC_ALL {
+        return get(categories, category).name.as_ptr() as *mut c_char;
+    }
+    let first = get(categories, COMPOSITE_ORDER[0]).name.to_bytes();
+    if COMPOSITE_ORDER
+        .iter()
+        .all(|&c| get(categories, c).name.to_bytes() == first)
+    {
+        return get(categories, COMPOSITE_ORDER[0]).name.as_ptr() as *mut c_char;
+    }
+
+    let mut composite = Vec::new();
+    for &c in COMPOSITE_ORDER.iter() {
+        if !composite.is_empty() {
+            composite.push(b';');
+        }
+        composite.extend_from_slice(data::NAMES[c as usize].as_bytes());
+        composite.push(b'=');
+        composite.extend_from_slice(get(categories, c).name.to_bytes());
+    }
+    COMPOSITE = CString::new(composite).ok();
+    COMPOSITE
+        .as_ref()
+        .map_or(ptr::null_mut(), |name| name.as_ptr() as *mut c_char)
+}
+
+/// Sets the categories in `mask` of `categories` to locale `locale`, leaving them all alone
+/// unless every one of them can be loaded.
+unsafe fn load(categories: &mut Categories, mask: c_int, locale: &[u8]) -> bool {
+    let mut loaded = *categories;
+    for &c in COMPOSITE_ORDER.iter().filter(|&&c| mask & (1 << c) != 0) {
+        let name = if locale.is_empty() {
+            from_env(c)
+        } else if locale.contains(&b'=') {
+            from_composite(locale, c).unwrap_or(b"C").to_vec()
+        } else {
+            locale.to_vec()
+        };
+        match data::load(c, &name) {
+            Some(found) => loaded[c as usize] = Some(found),
+            None => return false,
+        }
+    }
+    *categories = loaded;
+    true
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn setlocale(category: c_int, locale: *const c_char) -> *mut c_char {
+    if category != LC_ALL && !data::valid(category) {
+        return ptr::null_mut();
+    }
+    if locale.is_null() {
+        return name(&GLOBAL, category);
+    }
+
+    let mask = if category == LC_ALL {
+        LC_ALL_MASK
+    } else {
+        1 << category
+    };
+    if !load(&mut GLOBAL, mask, CStr::from_ptr(locale).to_bytes()) {
+        return ptr::null_mut();
+    }
+    name(&GLOBAL, category)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn newlocale(mask: c_int, locale: *const c_char, base: locale_t) -> locale_t {
+    if mask & !LC_ALL_MASK != 0 || locale.is_null() {
+        platform::errno = EINVAL;
+        return ptr::null_mut();
+    }
+
+    let mut categories = if base.is_null() {
+        [None; 7]
+    } else {
+        (*base).categories
+    };
+    if !load(&mut categories, mask, CStr::from_ptr(locale).to_bytes()) {
+        platform::errno = ENOENT;
+        return ptr::null_mut();
+    }
+    // The base is reused, as it may be freed anyway
+    if base.is_null() {
+        Box::into_raw(Box::new(__locale_struct { categories }))
+    } else {
+        (*base).categories = categories;
+        base
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn duplocale(locale: locale_t) -> locale_t {
+    let locale = if locale == GLOBAL_LOCALE {
+        __locale_struct { categories: GLOBAL }
+    } else {
+        (*locale).clone()
+    };
+    Box::into_raw(Box::new(locale))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn freelocale(locale: locale_t) {
+    drop(Box::from_raw(locale));
 }
 
 #[no_mangle]
-pub unsafe extern "C" fn setlocale(_option: c_int, val: *const c_char) -> *mut c_char {
-    if val.is_null() {
-        return C_LOCALE.as_mut_ptr() as *mut c_char;
+pub unsafe extern "C" fn uselocale(locale: locale_t) -> locale_t {
+    let old = if THREAD_LOCALE.is_null() {
+        GLOBAL_LOCALE
+    } else {
+        THREAD_LOCALE
+    };
+    if locale == GLOBAL_LOCALE {
+        THREAD_LOCALE = ptr::null_mut();
+    } else if !locale.is_null() {
+        THREAD_LOCALE = locale;
     }
-    // TODO actually implement
-    ptr::null_mut()
+    old
 }
@@ -0,0 +1,222 @@
+//! malloc.h implementation, following glibc
+
+use core::mem;
+
+use crate::{
+    header::{errno::EINVAL, stdio::FILE},
+    io::Write,
+    platform::{self, types::*},
+};
+
+pub const M_MXFAST: c_int = 1;
+pub const M_TRIM_THRESHOLD: c_int = -1;
+pub const M_TOP_PAD: c_int = -2;
+pub const M_MMAP_THRESHOLD: c_int = -3;
+pub const M_MMAP_MAX: c_int = -4;
+pub const M_CHECK_ACTION: c_int = -5;
+pub const M_PERTURB: c_int = -6;
+pub const M_ARENA_TEST: c_int = -7;
+pub const M_ARENA_MAX: c_int = -8;
+/// The size of free runs in the middle of the heap, past which their pages go back to the system,
+/// -1 to keep them all. A relibc extension.
+pub const M_DECOMMIT_THRESHOLD: c_int = -9;
+
+// The hooks of old glibc, called in place of the allocation functions when set. A hook that
+// needs the real function restores the previous hook around calling it. The caller's address
+// isn't known, so the last argument is always null.
+#[no_mangle]
+pub static mut __malloc_hook: Option<unsafe extern "C" fn(size_t, *const c_void) -> *mut c_void> =
+    None;
+#[no_mangle]
+pub static mut __realloc_hook: Option<
+    unsafe extern "C" fn(*mut c_void, size_t, *const c_void) -> *mut c_void,
+> = None;
+#[no_mangle]
+pub static mut __memalign_hook: Option<
+    unsafe extern "C" fn(size_t, size_t, *const c_void) -> *mut c_void,
+> = None;
+#[no_mangle]
+pub static mut __free_hook: Option<unsafe extern "C" fn(*mut c_void, *const c_void)> = None;
+
+#[repr(C)]
+#[derive(Clone, Copy, Default)]
+pub struct mallinfo {
+    pub arena: c_int,
+    pub ordblks: c_int,
+    pub smblks: c_int,
+    pub hblks: c_int,
+    pub hblkhd: c_int,
+    pub usmblks: c_int,
+    pub fsmblks: c_int,
+    pub uordblks: c_int,
+    pub fordblks: c_int,
+    pub keepcost: c_int,
+}
+
+/// The layout the allocator reports statistics in
+#[repr(C)]
+#[derive(Clone, Copy, Default)]
+pub struct mallinfo2 {
+    /// Bytes obtained from the system, other than for mapped chunks
+    pub arena: size_t,
+    /// Free chunks
+    pub ordblks: size_t,
+    pub smblks: size_t,
+    pub hblks: size_t,
+    /// Bytes in mapped chunks
+    pub hblkhd: size_t,
+    /// The most bytes ever obtained from the system
+    pub usmblks: size_t,
+    pub fsmblks: size_t,
+    /// Bytes in use, mapped chunks included
+    pub uordblks: size_t,
+    /// Free bytes
+    pub fordblks: size_t,
+    /// Bytes malloc_trim could release
+    pub keepcost: size_t,
+}
+
+const CLASSES: usize = mem::size_of::<size_t>() * 8 + 1;
+
+/// Free space, counted by the power of two its chunks round up to.
+struct Classes {
+    count: [size_t; CLASSES],
+    total: [size_t; CLASSES],
+}
+
+extern "C" fn count_free(start: *mut c_void, end: *mut c_void, used: size_t, arg: *mut c_void) {
+    if used != 0 {
+        return;
+    }
+    // Called with the allocator locked, so this must not allocate
+    let classes = unsafe { &mut *(arg as *mut Classes) };
+    let size = end as usize - start as usize;
+    let class = CLASSES - 1 - (size - 1).leading_zeros() as usize;
+    classes.count[class] += 1;
+    classes.total[class] += size;
+}
+
+#[no_mangle]
+pub extern "C" fn mallinfo() -> mallinfo {
+    // Truncated like glibc does, which is why mallinfo2 exists
+    let info = platform::info();
+    mallinfo {
+        arena: info.arena as c_int,
+        ordblks: info.ordblks as c_int,
+        smblks: info.smblks as c_int,
+        hblks: info.hblks as c_int,
+        hblkhd: info.hblkhd as c_int,
+        usmblks: info.usmblks as c_int,
+        fsmblks: info.fsmblks as c_int,
+        uordblks: info.uordblks as c_int,
+        fordblks: info.fordblks as c_int,
+        keepcost: info.keepcost as c_int,
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn mallinfo2() -> mallinfo2 {
+    platform::info()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn malloc_info(options: c_int, stream: *mut FILE) -> c_int {
+    // The error is returned rather than set, as in glibc
+    if options != 0 {
+        return EINVAL;
+    }
+
+    let info = platform::info();
+    let mut classes = Classes {
+        count: [0; CLASSES],
+        total: [0; CLASSES],
+    };
+    platform::inspect(count_free, &mut classes as *mut Classes as *mut c_void);
+
+    let mut stream = (*stream).lock();
+    let mut write = || -> crate::io::Result<()> {
+        stream.write_all(b"<malloc version=\"1\">\n<heap nr=\"0\">\n<sizes>\n")?;
+        for class in 0..classes.count.len() {
+            if classes.count[class] == 0 {
+                continue;
+            }
+            let from = if class == 0 {
+                1
+            } else {
+                (1 << (class - 1)) + 1
+            };
+            write!(
+                stream,
+                "<size from=\"{}\" to=\"{}\" total=\"{}\" count=\"{}\"/>\n",
+                from,
+                1usize << class,
+                classes.total[class],
+                classes.count[class]
+            )?;
+        }
+        stream.write_all(b"</sizes>\n")?;
+        let totals = format!(
+            "<total type=\"rest\" count=\"{}\" size=\"{}\"/>\n\
+             <system type=\"current\" size=\"{}\"/>\n\
+             <system type=\"max\" size=\"{}\"/>\n",
+            info.ordblks,
+            info.fordblks,
+            info.arena + info.hblkhd,
+            info.usmblks
+        );
+        // There is a single heap, so the totals are its own, plus the mapped chunks
+        stream.write_all(totals.as_bytes())?;
+        stream.write_all(b"</heap>\n")?;
+        stream.write_all(totals.as_bytes())?;
+        write!(
+            stream,
+            "<total type=\"mmap\" count=\"{}\" size=\"{}\"/>\n</malloc>\n",
+            info.hblks, info.hblkhd
+        )?;
+        Ok(())
+    };
+    match write() {
+        Ok(()) => 0,
+        Err(_) => -1,
+    }
+}
+
+#[no_mangle]
+pub extern "C" fn mallopt(param: c_int, value: c_int) -> c_int {
+    match param {
+        M_TRIM_THRESHOLD | M_MMAP_THRESHOLD | M_DECOMMIT_THRESHOLD => {
+            platform::set_param(param, value)
+        }
+        // There is a single arena shared by all threads, which satisfies any limit
+        M_ARENA_MAX | M_ARENA_TEST => (value > 0) as c_int,
+        _ => 0,
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn malloc_stats() {
+    let info = platform::info();
+    let mut stream = (*crate::header::stdio::stderr).lock();
+    let _ = write!(
+        stream,
+        "max system bytes = {:>10}\nsystem bytes     = {:>10}\nin use bytes     = {:>10}\n",
+        info.usmblks,
+        info.arena + info.hblkhd,
+        info.uordblks
+    );
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn malloc_trim(pad: size_t) -> c_int {
+    platform::trim(pad)
+}
+
+#[no_mangle]
+#[linkage = "weak"] // replaced along with malloc
+pub unsafe extern "C" fn malloc_usable_size(ptr: *mut c_void) -> size_t {
+    if ptr.is_null() {
+        0
+    } else {
+        platform::usable_size(ptr)
+    }
+}
@@ -1,51 +1,76 @@
-pub mod _aio;
 pub mod _fenv;
+pub mod aio;
 pub mod arpa_inet;
 pub mod assert;
+pub mod crypt;
 pub mod ctype;
 pub mod dirent;
 #[path = "dl-tls/mod.rs"]
 pub mod dl_tls;
 pub mod dlfcn;
 pub mod elf;
+pub mod err;
 pub mod errno;
+pub mod execinfo;
 pub mod fcntl;
 pub mod float;
 pub mod fnmatch;
 pub mod getopt;
 pub mod grp;
+pub mod iconv;
+pub mod ifaddrs;
 pub mod inttypes;
+pub mod langinfo;
 pub mod libgen;
+pub mod libintl;
 pub mod limits;
+pub mod linux_sched;
 pub mod locale;
+pub mod malloc;
+pub mod monetary;
+pub mod mqueue;
+pub mod net_if;
 pub mod netdb;
 pub mod netinet_in;
 pub mod netinet_ip;
 pub mod netinet_tcp;
+pub mod nl_types;
 pub mod poll;
+pub mod pty;
 pub mod pwd;
 pub mod regex;
+pub mod search;
 pub mod semaphore;
 pub mod setjmp;
 pub mod sgtty;
+pub mod shadow;
 pub mod signal;
+pub mod spawn;
 pub mod stdio;
 pub mod stdlib;
 pub mod string;
 pub mod strings;
 pub mod sys_auxv;
 pub mod sys_epoll;
+pub mod sys_eventfd;
 pub mod sys_file;
+pub mod sys_inotify;
 pub mod sys_ioctl;
+pub mod sys_ipc;
 pub mod sys_mman;
+pub mod sys_msg;
 pub mod sys_ptrace;
 pub mod sys_resource;
 pub mod sys_select;
+pub mod sys_sem;
+pub mod sys_shm;
 pub mod sys_socket;
 pub mod sys_stat;
 pub mod sys_statvfs;
+pub mod sys_sysinfo;
 pub mod sys_time;
 pub mod sys_timeb;
+pub mod sys_timerfd;
 //pub mod sys_times;
 pub mod arch_aarch64_user;
 pub mod arch_x64_user;
@@ -57,9 +82,12 @@ pub mod sys_uio;
 pub mod sys_un;
 pub mod sys_utsname;
 pub mod sys_wait;
+pub mod syslog;
 pub mod termios;
 pub mod time;
 pub mod unistd;
 pub mod utime;
+pub mod utmp;
+pub mod utmpx;
 pub mod wchar;
 pub mod wctype;
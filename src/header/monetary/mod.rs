@@ -0,0 +1,265 @@
+//! monetary implementation for Redox, following https://pubs.opengroup.org/onlinepubs/9699919799/basedefs/monetary.h.html
+
+use alloc::{string::String, vec::Vec};
+use core::{ffi::VaList as va_list, ptr};
+
+use crate::{
+    header::{
+        errno::{E2BIG, EINVAL},
+        locale::{self, locale_t, LC_MONETARY},
+    },
+    platform::{self, types::*},
+};
+
+/// A conversion specification, with its flags, width and precisions
+struct Spec {
+    fill: u8,
+    group: bool,
+    parentheses: bool,
+    currency: bool,
+    left: bool,
+    width: usize,
+    left_precision: Option<usize>,
+    right_precision: Option<usize>,
+    international: bool,
+}
+
+unsafe fn pop_number(format: &mut *const u8) -> Option<usize> {
+    let mut number = None;
+    while (**format).is_ascii_digit() {
+        number = Some(number.unwrap_or(0) * 10 + (**format - b'0') as usize);
+        *format = format.add(1);
+    }
+    number
+}
+
+unsafe fn parse(format: &mut *const u8) -> Option<Spec> {
+    let mut spec = Spec {
+        fill: b' ',
+        group: true,
+        parentheses: false,
+        currency: true,
+        left: false,
+        width: 0,
+        left_precision: None,
+        right_precision: None,
+        international: false,
+    };
+    loop {
+        match **format {
+            b'=' => {
+                *format = format.add(1);
+                if **format == 0 {
+                    return None;
+                }
+                spec.fill = **format;
+            }
+            b'^' => spec.group = false,
+            b'+' => spec.parentheses = false,
+            b'(' => spec.parentheses = true,
+            b'!' => spec.currency = false,
+            b'-' => spec.left = true,
+            _ => break,
+        }
+        *format = format.add(1);
+    }
+    spec.width = pop_number(format).unwrap_or(0);
+    if **format == b'#' {
+        *format = format.add(1);
+        spec.left_precision = Some(pop_number(format)?);
+    }
+    if **format == b'.' {
+        *format = format.add(1);
+        spec.right_precision = Some(pop_number(format)?);
+    }
+    spec.international = match **format {
+        b'i' => true,
+        b'n' => false,
+        _ => return None,
+    };
+    *format = format.add(1);
+    Some(spec)
+}
+
+/// A number of LC_MONETARY, the international one if `spec` asks for it and the locale has it.
+unsafe fn number(spec: &Spec, keyword: &str) -> c_char {
+    if spec.international {
+        let mut international = String::from("int_");
+        international.push_str(keyword);
+        let value = locale::monetary(&international);
+        if value != c_char::MAX {
+            return value;
+        }
+    }
+    locale::monetary(keyword)
+}
+
+unsafe fn format_value(spec: &Spec, value: c_double) -> Vec<u8> {
+    let negative = value < 0.0;
+    let frac_digits = number(spec, "frac_digits");
+    let right = spec
+        .right_precision
+        .unwrap_or(if frac_digits == c_char::MAX {
+            2
+        } else {
+            frac_digits as usize
+        });
+
+    // The quantity: its integer part grouped and padded to the left precision, then the rest
+    let digits = format!("{:.*}", right, if negative { -value } else { value });
+    let (integer, fraction) = digits.split_at(digits.find('.').unwrap_or(digits.len()));
+    let mut quantity = if spec.group {
+        locale::group_digits(integer.as_bytes(), true)
+    } else {
+        integer.as_bytes().to_vec()
+    };
+    if let Some(left) = spec.left_precision {
+        let zeros = vec![b'0'; left];
+        let width = if spec.group {
+            locale::group_digits(&zeros, true).len()
+        } else {
+            left
+        };
+        while quantity.len() < width {
+            quantity.insert(0, spec.fill);
+        }
+    }
+    if right > 0 {
+        let radix = locale::item(LC_MONETARY, "mon_decimal_point", 0).to_bytes();
+        quantity.extend_from_slice(if radix.is_empty() { b"." } else { radix });
+        quantity.extend_from_slice(fraction[1..].as_bytes());
+    }
+
+    let currency: &[u8] = if !spec.currency {
+        b""
+    } else if spec.international {
+        // The fourth character of int_curr_symbol is the separator that sep_by_space stands for
+        let symbol = locale::item(LC_MONETARY, "int_curr_symbol", 0).to_bytes();
+        &symbol[..symbol.len().min(3)]
+    } else {
+        locale::item(LC_MONETARY, "currency_symbol", 0).to_bytes()
+    };
+    let positive_sign = locale::item(LC_MONETARY, "positive_sign", 0).to_bytes();
+    let negative_sign: &[u8] = match locale::item(LC_MONETARY, "negative_sign", 0).to_bytes() {
+        b"" => b"-",
+        sign => sign,
+    };
+    let (prefix, sign) = if negative {
+        ("n_", negative_sign)
+    } else {
+        ("p_", positive_sign)
+    };
+    let field = |name: &str| {
+        let mut keyword = String::from(prefix);
+        keyword.push_str(name);
+        number(spec, &keyword)
+    };
+    let precedes = field("cs_precedes") != 0;
+    let separation = field("sep_by_space");
+    let mut position = field("sign_posn");
+    if spec.parentheses && negative {
+        position = 0;
+    }
+
+    // The spaces between the currency symbol and the quantity, and next to the sign string
+    let around_currency: &[u8] = if separation == 1 && !currency.is_empty() {
+        b" "
+    } else {
+        b""
+    };
+    let around_sign: &[u8] = if separation == 2 && !sign.is_empty() {
+        b" "
+    } else {
+        b""
+    };
+    let with_currency = |quantity: &[u8]| -> Vec<u8> {
+        if precedes {
+            [currency, around_currency, quantity].concat()
+        } else {
+            [quantity, around_currency, currency].concat()
+        }
+    };
+    let quantity = &quantity[..];
+    let mut formatted = match position {
+        0 => [&b"("[..], &with_currency(quantity), &b")"[..]].concat(),
+        2 => [&with_currency(quantity), around_sign, sign].concat(),
+        3 if precedes => [sign, around_sign, currency, around_currency, quantity].concat(),
+        3 => [quantity, around_currency, sign, around_sign, currency].concat(),
+        4 if precedes => [currency, around_sign, sign, around_currency, quantity].concat(),
+        4 => [quantity, around_currency, currency, around_sign, sign].concat(),
+        // 1 and CHAR_MAX, the sign string coming first
+        _ => [sign, around_sign, &with_currency(quantity)].concat(),
+    };
+
+    // With a left precision, positive and negative values line up
+    if spec.left_precision.is_some() && !negative {
+        if spec.parentheses || number(spec, "n_sign_posn") == 0 {
+            formatted.insert(0, b' ');
+            formatted.push(b' ');
+        } else if sign.len() < negative_sign.len() && position != 2 && position != 4 {
+            for _ in sign.len()..negative_sign.len() {
+                formatted.insert(0, b' ');
+            }
+        }
+    }
+
+    while formatted.len() < spec.width {
+        if spec.left {
+            formatted.push(b' ');
+        } else {
+            formatted.insert(0, b' ');
+        }
+    }
+    formatted
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn vstrfmon(
+    s: *mut c_char,
+    maxsize: size_t,
+    format: *const c_char,
+    mut ap: va_list,
+) -> ssize_t {
+    let mut format = format as *const u8;
+    let mut out = Vec::new();
+    while *format != 0 {
+        if *format != b'%' {
+            out.push(*format);
+            format = format.add(1);
+            continue;
+        }
+        format = format.add(1);
+        if *format == b'%' {
+            out.push(b'%');
+            format = format.add(1);
+            continue;
+        }
+        let spec = match parse(&mut format) {
+            Some(spec) => spec,
+            None => {
+                platform::errno = EINVAL;
+                return -1;
+            }
+        };
+        out.extend_from_slice(&format_value(&spec, ap.arg::<c_double>()));
+    }
+
+    if out.len() >= maxsize {
+        platform::errno = E2BIG;
+        return -1;
+    }
+    ptr::copy_nonoverlapping(out.as_ptr(), s as *mut u8, out.len());
+    *s.add(out.len()) = 0;
+    out.len() as ssize_t
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn vstrfmon_l(
+    s: *mut c_char,
+    maxsize: size_t,
+    locale: locale_t,
+    format: *const c_char,
+    ap: va_list,
+) -> ssize_t {
+    locale::with(locale, move || vstrfmon(s, maxsize, format, ap))
+}
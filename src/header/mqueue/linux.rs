@@ -0,0 +1,56 @@
+use core::ptr;
+
+use crate::{
+    header::{
+        signal::{sigevent, sigval, SIGEV_THREAD},
+        sys_socket::constants::{MSG_NOSIGNAL, MSG_WAITALL, SOCK_CLOEXEC},
+    },
+    platform::{types::*, Pal, PalIpc, PalSocket, Sys},
+};
+
+const AF_NETLINK: c_int = 16;
+const SOCK_RAW: c_int = 3;
+
+/// The size of the message the kernel sends, which ends with why it was sent
+const NOTIFY_COOKIE_LEN: usize = 32;
+const NOTIFY_WOKENUP: u8 = 1;
+
+/// What the kernel starts its message with
+static COOKIE: [u8; NOTIFY_COOKIE_LEN] = [0; NOTIFY_COOKIE_LEN];
+
+/// Asks the kernel to send a message to a netlink socket when the notification happens, which
+/// is how it does SIGEV_THREAD, returning the socket.
+pub unsafe fn register(mqdes: c_int) -> c_int {
+    let fd = Sys::socket(AF_NETLINK, SOCK_RAW | SOCK_CLOEXEC, 0);
+    if fd < 0 {
+        return -1;
+    }
+    let notification = sigevent {
+        sigev_value: sigval {
+            sival_ptr: COOKIE.as_ptr() as *mut c_void,
+        },
+        sigev_signo: fd,
+        sigev_notify: SIGEV_THREAD,
+        sigev_notify_function: None,
+        sigev_notify_attributes: ptr::null_mut(),
+    };
+    if Sys::mq_notify(mqdes, &notification) < 0 {
+        Sys::close(fd);
+        return -1;
+    }
+    fd
+}
+
+/// Waits for the message on `fd`, returning whether it says the notification happened.
+pub unsafe fn wait(fd: c_int) -> bool {
+    let mut buf = [0; NOTIFY_COOKIE_LEN];
+    let len = Sys::recvfrom(
+        fd,
+        buf.as_mut_ptr() as *mut c_void,
+        buf.len(),
+        MSG_NOSIGNAL | MSG_WAITALL,
+        ptr::null_mut(),
+        ptr::null_mut(),
+    );
+    len == buf.len() as ssize_t && buf[NOTIFY_COOKIE_LEN - 1] == NOTIFY_WOKENUP
+}
@@ -0,0 +1,187 @@
+//! mqueue.h implementation, following https://pubs.opengroup.org/onlinepubs/9699919799/basedefs/mqueue.h.html
+//!
+//! Queues are the kernel's on Linux, and those of the mqueue: scheme on Redox. Names start with
+//! a slash, which is left out of the name the system is given.
+
+use alloc::boxed::Box;
+use core::ptr;
+
+use crate::{
+    c_str::CStr,
+    header::{
+        aio::spawn,
+        errno::{EAGAIN, EINVAL},
+        signal::{sigevent, sigval, SIGEV_THREAD},
+        time::timespec,
+    },
+    platform::{self, types::*, Pal, PalIpc, Sys},
+};
+
+#[cfg(target_os = "linux")]
+#[path = "linux.rs"]
+mod sys;
+
+#[cfg(target_os = "redox")]
+#[path = "redox.rs"]
+mod sys;
+
+pub type mqd_t = c_int;
+
+#[repr(C)]
+#[derive(Clone, Copy, Default)]
+pub struct mq_attr {
+    pub mq_flags: c_long,
+    pub mq_maxmsg: c_long,
+    pub mq_msgsize: c_long,
+    pub mq_curmsgs: c_long,
+    pub __reserved: [c_long; 4],
+}
+
+/// Strips the leading slash that every name has.
+unsafe fn name(name: *const c_char) -> Option<&'static CStr> {
+    if *name != b'/' as c_char {
+        platform::errno = EINVAL;
+        return None;
+    }
+    Some(CStr::from_ptr(name.add(1)))
+}
+
+#[no_mangle]
+pub extern "C" fn mq_close(mqdes: mqd_t) -> c_int {
+    Sys::close(mqdes)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn mq_getattr(mqdes: mqd_t, mqstat: *mut mq_attr) -> c_int {
+    Sys::mq_getsetattr(mqdes, ptr::null(), mqstat)
+}
+
+struct Notification {
+    fd: c_int,
+    function: extern "C" fn(sigval),
+    value: sigval,
+}
+
+extern "C" fn notify_thread(arg: *mut c_void) -> *mut c_void {
+    let notification = unsafe { Box::from_raw(arg as *mut Notification) };
+    if unsafe { sys::wait(notification.fd) } {
+        (notification.function)(notification.value);
+    }
+    Sys::close(notification.fd);
+    ptr::null_mut()
+}
+
+/// Asks for `notification` to be sent when a message arrives on the empty queue, for this
+/// process only, or cancels the request if it's null.
+///
+/// SIGEV_THREAD is done by a thread that's started right away and waits to be told that the
+/// message arrived, or that the request was cancelled.
+#[no_mangle]
+pub unsafe extern "C" fn mq_notify(mqdes: mqd_t, notification: *const sigevent) -> c_int {
+    let notification = match notification.as_ref() {
+        Some(notification) if notification.sigev_notify == SIGEV_THREAD => notification,
+        _ => return Sys::mq_notify(mqdes, notification),
+    };
+    let function = match notification.sigev_notify_function {
+        Some(function) => function,
+        None => {
+            platform::errno = EINVAL;
+            return -1;
+        }
+    };
+
+    let fd = sys::register(mqdes);
+    if fd < 0 {
+        return -1;
+    }
+    let arg = Box::into_raw(Box::new(Notification {
+        fd,
+        function,
+        value: notification.sigev_value,
+    }));
+    let attr = notification.sigev_notify_attributes as *const *mut c_void;
+    if spawn(attr, notify_thread, arg as *mut c_void).is_err() {
+        drop(Box::from_raw(arg));
+        Sys::mq_notify(mqdes, ptr::null());
+        Sys::close(fd);
+        platform::errno = EAGAIN;
+        return -1;
+    }
+    0
+}
+
+/// Opens the queue `name`, creating it with `attr` as its limits if O_CREAT is given, or with the
+/// system's defaults if `attr` is null. Called by mq_open, which only has `mode` and `attr` with
+/// O_CREAT.
+#[no_mangle]
+pub unsafe extern "C" fn sys_mq_open(
+    name: *const c_char,
+    oflag: c_int,
+    mode: mode_t,
+    attr: *const mq_attr,
+) -> mqd_t {
+    match self::name(name) {
+        Some(name) => Sys::mq_open(name, oflag, mode, attr),
+        None => -1,
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn mq_receive(
+    mqdes: mqd_t,
+    msg_ptr: *mut c_char,
+    msg_len: size_t,
+    msg_prio: *mut c_uint,
+) -> ssize_t {
+    Sys::mq_timedreceive(mqdes, msg_ptr, msg_len, msg_prio, ptr::null())
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn mq_send(
+    mqdes: mqd_t,
+    msg_ptr: *const c_char,
+    msg_len: size_t,
+    msg_prio: c_uint,
+) -> c_int {
+    Sys::mq_timedsend(mqdes, msg_ptr, msg_len, msg_prio, ptr::null())
+}
+
+/// Sets whether the queue is O_NONBLOCK from `mqstat`, the rest of which is ignored.
+#[no_mangle]
+pub unsafe extern "C" fn mq_setattr(
+    mqdes: mqd_t,
+    mqstat: *const mq_attr,
+    omqstat: *mut mq_attr,
+) -> c_int {
+    Sys::mq_getsetattr(mqdes, mqstat, omqstat)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn mq_timedreceive(
+    mqdes: mqd_t,
+    msg_ptr: *mut c_char,
+    msg_len: size_t,
+    msg_prio: *mut c_uint,
+    abs_timeout: *const timespec,
+) -> ssize_t {
+    Sys::mq_timedreceive(mqdes, msg_ptr, msg_len, msg_prio, abs_timeout)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn mq_timedsend(
+    mqdes: mqd_t,
+    msg_ptr: *const c_char,
+    msg_len: size_t,
+    msg_prio: c_uint,
+    abs_timeout: *const timespec,
+) -> c_int {
+    Sys::mq_timedsend(mqdes, msg_ptr, msg_len, msg_prio, abs_timeout)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn mq_unlink(name: *const c_char) -> c_int {
+    match self::name(name) {
+        Some(name) => Sys::mq_unlink(name),
+        None => -1,
+    }
+}
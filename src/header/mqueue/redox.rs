@@ -0,0 +1,21 @@
+use crate::platform::{e, types::*};
+
+/// Registers for a notification without a signal, returning the descriptor that becomes
+/// readable when it happens.
+pub unsafe fn register(mqdes: c_int) -> c_int {
+    let fd = match syscall::dup(mqdes as usize, b"notify") {
+        Ok(fd) => fd,
+        Err(err) => return e(Err(err)) as c_int,
+    };
+    if let Err(err) = syscall::write(fd, &0i32.to_ne_bytes()) {
+        let _ = syscall::close(fd);
+        return e(Err(err)) as c_int;
+    }
+    fd as c_int
+}
+
+/// Waits on `fd`, returning whether the notification happened rather than being cancelled.
+pub unsafe fn wait(fd: c_int) -> bool {
+    let mut byte = [0];
+    matches!(syscall::read(fd as usize, &mut byte), Ok(len) if len > 0)
+}
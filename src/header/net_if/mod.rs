@@ -0,0 +1,115 @@
+//! net/if.h implementation for Redox, following https://pubs.opengroup.org/onlinepubs/9699919799/basedefs/net_if.h.html
+
+use alloc::vec::Vec;
+use core::{mem, ptr};
+
+use crate::{
+    c_str::CStr,
+    header::{
+        errno::{ENOBUFS, ENXIO},
+        ifaddrs::{sys, Interface},
+        stdlib,
+    },
+    platform::{self, types::*},
+};
+
+pub const IF_NAMESIZE: usize = 16;
+
+// Interface flags, as reported in ifa_flags
+pub const IFF_UP: c_uint = 0x1;
+pub const IFF_BROADCAST: c_uint = 0x2;
+pub const IFF_DEBUG: c_uint = 0x4;
+pub const IFF_LOOPBACK: c_uint = 0x8;
+pub const IFF_POINTOPOINT: c_uint = 0x10;
+pub const IFF_NOTRAILERS: c_uint = 0x20;
+pub const IFF_RUNNING: c_uint = 0x40;
+pub const IFF_NOARP: c_uint = 0x80;
+pub const IFF_PROMISC: c_uint = 0x100;
+pub const IFF_ALLMULTI: c_uint = 0x200;
+pub const IFF_MASTER: c_uint = 0x400;
+pub const IFF_SLAVE: c_uint = 0x800;
+pub const IFF_MULTICAST: c_uint = 0x1000;
+pub const IFF_PORTSEL: c_uint = 0x2000;
+pub const IFF_AUTOMEDIA: c_uint = 0x4000;
+pub const IFF_DYNAMIC: c_uint = 0x8000;
+
+#[repr(C)]
+pub struct if_nameindex {
+    pub if_index: c_uint,
+    pub if_name: *mut c_char,
+}
+
+fn interfaces() -> Option<Vec<Interface>> {
+    match sys::interfaces() {
+        Ok(interfaces) => Some(interfaces),
+        Err(err) => {
+            unsafe { platform::errno = err };
+            None
+        }
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn if_nametoindex(ifname: *const c_char) -> c_uint {
+    let name = CStr::from_ptr(ifname).to_bytes();
+    interfaces()
+        .and_then(|interfaces| interfaces.into_iter().find(|iface| iface.name == name))
+        .map_or(0, |iface| iface.index)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn if_indextoname(ifindex: c_uint, ifname: *mut c_char) -> *mut c_char {
+    let iface = match interfaces()
+        .and_then(|interfaces| interfaces.into_iter().find(|iface| iface.index == ifindex))
+    {
+        Some(iface) => iface,
+        None => {
+            platform::errno = ENXIO;
+            return ptr::null_mut();
+        }
+    };
+
+    let len = iface.name.len().min(IF_NAMESIZE - 1);
+    ptr::copy_nonoverlapping(iface.name.as_ptr() as *const c_char, ifname, len);
+    *ifname.add(len) = 0;
+    ifname
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn if_nameindex() -> *mut if_nameindex {
+    let interfaces = match interfaces() {
+        Some(interfaces) => interfaces,
+        None => return ptr::null_mut(),
+    };
+
+    // The array, its terminating entry and all names share one allocation, so that
+    // if_freenameindex only has to free a single pointer.
+    let array_size = (interfaces.len() + 1) * mem::size_of::<if_nameindex>();
+    let names_size: usize = interfaces.iter().map(|iface| iface.name.len() + 1).sum();
+    let array = stdlib::malloc(array_size + names_size) as *mut if_nameindex;
+    if array.is_null() {
+        platform::errno = ENOBUFS;
+        return ptr::null_mut();
+    }
+
+    let mut name = (array as *mut c_char).add(array_size);
+    for (i, iface) in interfaces.iter().enumerate() {
+        ptr::copy_nonoverlapping(iface.name.as_ptr() as *const c_char, name, iface.name.len());
+        *name.add(iface.name.len()) = 0;
+        array.add(i).write(if_nameindex {
+            if_index: iface.index,
+            if_name: name,
+        });
+        name = name.add(iface.name.len() + 1);
+    }
+    array.add(interfaces.len()).write(if_nameindex {
+        if_index: 0,
+        if_name: ptr::null_mut(),
+    });
+    array
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn if_freenameindex(ptr: *mut if_nameindex) {
+    stdlib::free(ptr as *mut c_void);
+}
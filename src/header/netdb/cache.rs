@@ -0,0 +1,217 @@
+//! In-process cache of DNS responses, keyed by query name and type. Only class IN answers of
+//! the queried type are kept.
+//!
+//! Positive entries live for the smallest TTL among their answer records, negative entries
+//! (NXDOMAIN or no matching records) for `NEGATIVE_TTL` seconds. The cache can be turned off by
+//! setting `RELIBC_DNS_CACHE=0` in the environment, or with `options no-cache` in resolv.conf.
+
+use alloc::{collections::BTreeMap, string::String, vec::Vec};
+
+use crate::{
+    c_str::CStr,
+    header::{stdlib::getenv, time},
+    platform::{types::*, Pal, Sys},
+    sync::{Mutex, Once},
+};
+
+use super::{dns::DnsAnswer, lookup::DNS_CLASS_IN, sys::dns_cache_disabled};
+
+/// Maximum number of cached (name, type) pairs.
+const MAX_ENTRIES: usize = 256;
+/// Upper bound on how long any record is kept, regardless of its TTL.
+const MAX_TTL: u32 = 24 * 60 * 60;
+/// Lifetime of negative entries. The authority section is not parsed, so the SOA minimum is
+/// unavailable.
+const NEGATIVE_TTL: u32 = 30;
+
+pub(crate) struct Entry {
+    answers: Vec<DnsAnswer>,
+    expires: time_t,
+}
+
+pub(crate) static CACHE: Mutex<BTreeMap<(String, u16), Entry>> = Mutex::new(BTreeMap::new());
+static ENABLED: Once<bool> = Once::new();
+
+fn enabled() -> bool {
+    *ENABLED.call_once(|| {
+        let var = unsafe { getenv(c_str!("RELIBC_DNS_CACHE").as_ptr()) };
+        if !var.is_null() && unsafe { CStr::from_ptr(var) }.to_bytes() == b"0" {
+            return false;
+        }
+        !dns_cache_disabled()
+    })
+}
+
+fn now() -> time_t {
+    let mut ts = time::timespec::default();
+    Sys::clock_gettime(time::constants::CLOCK_MONOTONIC, &mut ts);
+    ts.tv_sec
+}
+
+fn key(name: &str, q_type: u16) -> (String, u16) {
+    (name.trim_end_matches('.').to_ascii_lowercase(), q_type)
+}
+
+fn answer_ttl(answer: &DnsAnswer) -> u32 {
+    (u32::from(answer.ttl_a) << 16) | u32::from(answer.ttl_b)
+}
+
+/// Returns the cached answers for `name`, if a live entry exists. An empty vector is a cached
+/// negative result.
+pub fn get(name: &str, q_type: u16) -> Option<Vec<DnsAnswer>> {
+    if !enabled() {
+        return None;
+    }
+    get_at(name, q_type, now())
+}
+
+fn get_at(name: &str, q_type: u16, now: time_t) -> Option<Vec<DnsAnswer>> {
+    let key = key(name, q_type);
+    let mut cache = CACHE.lock();
+    let expired = match cache.get(&key) {
+        Some(entry) if entry.expires > now => return Some(entry.answers.clone()),
+        Some(_) => true,
+        None => false,
+    };
+    if expired {
+        cache.remove(&key);
+    }
+    None
+}
+
+/// Stores the class IN answers of type `q_type` from a response. Passing no answers records a
+/// negative result.
+pub fn insert(name: &str, q_type: u16, answers: &[DnsAnswer]) {
+    if !enabled() {
+        return;
+    }
+    insert_at(name, q_type, answers, now());
+}
+
+fn insert_at(name: &str, q_type: u16, answers: &[DnsAnswer], now: time_t) {
+    let answers: Vec<DnsAnswer> = answers
+        .iter()
+        .filter(|answer| answer.a_type == q_type && answer.a_class == DNS_CLASS_IN)
+        .cloned()
+        .collect();
+    let ttl = if answers.is_empty() {
+        NEGATIVE_TTL
+    } else {
+        answers
+            .iter()
+            .map(answer_ttl)
+            .min()
+            .unwrap_or(0)
+            .min(MAX_TTL)
+    };
+    if ttl == 0 {
+        // A zero TTL forbids caching
+        return;
+    }
+
+    let mut cache = CACHE.lock();
+    if cache.len() >= MAX_ENTRIES {
+        cache.retain(|_, entry| entry.expires > now);
+    }
+    if cache.len() >= MAX_ENTRIES {
+        // Still full of live entries, evict the one closest to expiring
+        let victim = cache
+            .iter()
+            .min_by_key(|(_, entry)| entry.expires)
+            .map(|(key, _)| key.clone());
+        if let Some(victim) = victim {
+            cache.remove(&victim);
+        }
+    }
+    cache.insert(
+        key(name, q_type),
+        Entry {
+            answers,
+            expires: now + ttl as time_t,
+        },
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{string::ToString, vec, vec::Vec};
+
+    use super::{get_at, insert_at, time_t, DnsAnswer, DNS_CLASS_IN, NEGATIVE_TTL};
+
+    const TYPE_A: u16 = 1;
+    const CLASS_CH: u16 = 3;
+
+    fn answer(a_class: u16, ttl: u32, data: &[u8]) -> DnsAnswer {
+        DnsAnswer {
+            name: "ignored".to_string(),
+            a_type: TYPE_A,
+            a_class,
+            ttl_a: (ttl >> 16) as u16,
+            ttl_b: ttl as u16,
+            data: data.to_vec(),
+        }
+    }
+
+    fn data(answers: Option<Vec<DnsAnswer>>) -> Option<Vec<Vec<u8>>> {
+        answers.map(|answers| answers.into_iter().map(|answer| answer.data).collect())
+    }
+
+    // Each test uses its own names, the cache being shared by the tests running at once
+
+    #[test]
+    fn hit_until_smallest_ttl() {
+        let answers = [
+            answer(DNS_CLASS_IN, 60, &[1]),
+            answer(DNS_CLASS_IN, 10, &[2]),
+        ];
+        insert_at("hit.test.", TYPE_A, &answers, 1000);
+        assert_eq!(
+            data(get_at("HIT.test", TYPE_A, 1000)),
+            Some(vec![vec![1], vec![2]])
+        );
+        assert_eq!(
+            data(get_at("hit.test", TYPE_A, 1009)),
+            Some(vec![vec![1], vec![2]])
+        );
+        assert_eq!(data(get_at("hit.test", TYPE_A, 1010)), None);
+        // The expired entry is gone, not only hidden
+        assert_eq!(data(get_at("hit.test", TYPE_A, 1000)), None);
+    }
+
+    #[test]
+    fn other_type_misses() {
+        insert_at("type.test", TYPE_A, &[answer(DNS_CLASS_IN, 60, &[1])], 1000);
+        assert_eq!(data(get_at("type.test", 28, 1000)), None);
+    }
+
+    #[test]
+    fn class_in_only() {
+        let answers = [answer(CLASS_CH, 60, &[1]), answer(DNS_CLASS_IN, 60, &[2])];
+        insert_at("class.test", TYPE_A, &answers, 1000);
+        assert_eq!(
+            data(get_at("class.test", TYPE_A, 1000)),
+            Some(vec![vec![2]])
+        );
+
+        // With no class IN answer, the response is a negative one
+        insert_at("chaos.test", TYPE_A, &[answer(CLASS_CH, 60, &[1])], 1000);
+        assert_eq!(data(get_at("chaos.test", TYPE_A, 1000)), Some(vec![]));
+    }
+
+    #[test]
+    fn negative_expires() {
+        insert_at("negative.test", TYPE_A, &[], 1000);
+        let expires = 1000 + NEGATIVE_TTL as time_t;
+        assert_eq!(
+            data(get_at("negative.test", TYPE_A, expires - 1)),
+            Some(vec![])
+        );
+        assert_eq!(data(get_at("negative.test", TYPE_A, expires)), None);
+    }
+
+    #[test]
+    fn zero_ttl_not_cached() {
+        insert_at("zero.test", TYPE_A, &[answer(DNS_CLASS_IN, 0, &[1])], 1000);
+        assert_eq!(data(get_at("zero.test", TYPE_A, 1000)), None);
+    }
+}
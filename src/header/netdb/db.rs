@@ -0,0 +1,169 @@
+//! Parsing of the /etc/services and /etc/protocols databases, shared by the functions returning
+//! static storage and their reentrant _r variants.
+
+use alloc::vec::Vec;
+use core::{mem, ptr, str};
+
+use crate::{c_str::CStr, fs::File, header::fcntl::O_RDONLY, io::Read, platform::types::*};
+
+pub fn services_path() -> &'static CStr {
+    c_str!("/etc/services")
+}
+
+pub fn protocols_path() -> &'static CStr {
+    c_str!("/etc/protocols")
+}
+
+pub struct Service {
+    pub name: Vec<u8>,
+    pub aliases: Vec<Vec<u8>>,
+    /// In host byte order
+    pub port: u16,
+    pub proto: Vec<u8>,
+}
+
+pub struct Protocol {
+    pub name: Vec<u8>,
+    pub aliases: Vec<Vec<u8>>,
+    pub number: c_int,
+}
+
+fn names_match(name: &[u8], aliases: &[Vec<u8>], wanted: &[u8]) -> bool {
+    name.eq_ignore_ascii_case(wanted) || aliases.iter().any(|a| a.eq_ignore_ascii_case(wanted))
+}
+
+impl Service {
+    pub fn is_named(&self, wanted: &[u8]) -> bool {
+        names_match(&self.name, &self.aliases, wanted)
+    }
+
+    /// Whether the entry is for `proto`, where no protocol matches any
+    pub fn has_proto(&self, proto: Option<&[u8]>) -> bool {
+        proto.map_or(true, |proto| self.proto.eq_ignore_ascii_case(proto))
+    }
+}
+
+impl Protocol {
+    pub fn is_named(&self, wanted: &[u8]) -> bool {
+        names_match(&self.name, &self.aliases, wanted)
+    }
+}
+
+/// Splits a line into whitespace separated fields, dropping any comment.
+fn fields(line: &[u8]) -> impl Iterator<Item = &[u8]> {
+    let line = line.split(|&b| b == b'#').next().unwrap_or(&[]);
+    line.split(u8::is_ascii_whitespace)
+        .filter(|field| !field.is_empty())
+}
+
+/// Parses a `name port/protocol [aliases...]` line.
+pub fn parse_service(line: &[u8]) -> Option<Service> {
+    let mut fields = fields(line);
+    let name = fields.next()?.to_vec();
+    let port_proto = fields.next()?;
+    let slash = port_proto.iter().position(|&b| b == b'/')?;
+    let port = str::from_utf8(&port_proto[..slash]).ok()?.parse().ok()?;
+    let proto = port_proto[slash + 1..].to_vec();
+    if proto.is_empty() {
+        return None;
+    }
+    Some(Service {
+        name,
+        aliases: fields.map(<[u8]>::to_vec).collect(),
+        port,
+        proto,
+    })
+}
+
+/// Parses a `name number [aliases...]` line.
+pub fn parse_protocol(line: &[u8]) -> Option<Protocol> {
+    let mut fields = fields(line);
+    let name = fields.next()?.to_vec();
+    let number = str::from_utf8(fields.next()?).ok()?.parse().ok()?;
+    Some(Protocol {
+        name,
+        aliases: fields.map(<[u8]>::to_vec).collect(),
+        number,
+    })
+}
+
+/// Reads a whole database, for lookups that must not disturb the position of the *ent functions.
+fn read_db(path: &CStr) -> Vec<u8> {
+    let mut data = Vec::new();
+    if let Ok(mut file) = File::open(path, O_RDONLY) {
+        // A partially read database still yields the entries that were read
+        let _ = file.read_to_end(&mut data);
+    }
+    data
+}
+
+pub fn find_service<F: Fn(&Service) -> bool>(f: F) -> Option<Service> {
+    read_db(services_path())
+        .split(|&b| b == b'\n')
+        .filter_map(parse_service)
+        .find(|service| f(service))
+}
+
+pub fn find_protocol<F: Fn(&Protocol) -> bool>(f: F) -> Option<Protocol> {
+    read_db(protocols_path())
+        .split(|&b| b == b'\n')
+        .filter_map(parse_protocol)
+        .find(|protocol| f(protocol))
+}
+
+/// Pointers into a buffer filled by `pack`.
+pub struct Packed {
+    pub name: *mut c_char,
+    pub aliases: *mut *mut c_char,
+    pub extra: *mut c_char,
+}
+
+/// Lays out the strings and the NULL-terminated alias array of an entry in `buf`, followed by
+/// `extra` (the protocol of a service), as the _r functions require. grp uses it for the member
+/// array and password of a group too. `Packed::extra` is null
+/// without one.
+///
+/// Returns the number of bytes needed if `buf` is too small.
+pub unsafe fn pack(
+    buf: *mut c_char,
+    buflen: size_t,
+    name: &[u8],
+    aliases: &[Vec<u8>],
+    extra: Option<&[u8]>,
+) -> Result<Packed, usize> {
+    // The alias array comes first, so that it's aligned
+    let align = (mem::align_of::<*mut c_char>() - buf as usize % mem::align_of::<*mut c_char>())
+        % mem::align_of::<*mut c_char>();
+    let array_len = (aliases.len() + 1) * mem::size_of::<*mut c_char>();
+    let strings_len = name.len()
+        + 1
+        + aliases.iter().map(|alias| alias.len() + 1).sum::<usize>()
+        + extra.map_or(0, |extra| extra.len() + 1);
+    let needed = align + array_len + strings_len;
+    if buf.is_null() || buflen < needed {
+        return Err(needed);
+    }
+
+    let array = buf.add(align) as *mut *mut c_char;
+    let mut cursor = (array as *mut c_char).add(array_len);
+    let mut push = |bytes: &[u8]| {
+        let start = cursor;
+        ptr::copy_nonoverlapping(bytes.as_ptr() as *const c_char, cursor, bytes.len());
+        *cursor.add(bytes.len()) = 0;
+        cursor = cursor.add(bytes.len() + 1);
+        start
+    };
+
+    let name = push(name);
+    for (i, alias) in aliases.iter().enumerate() {
+        *array.add(i) = push(alias);
+    }
+    *array.add(aliases.len()) = ptr::null_mut();
+    let extra = extra.map_or(ptr::null_mut(), push);
+
+    Ok(Packed {
+        name,
+        aliases: array,
+        extra,
+    })
+}
@@ -27,3 +27,28 @@ pub fn get_dns_server() -> String {
     // TODO: better error handling
     String::new()
 }
+
+/// Checks resolv.conf for `options no-cache`, which disables the in-process DNS cache.
+pub fn dns_cache_disabled() -> bool {
+    let file = match File::open(&CString::new("/etc/resolv.conf").unwrap(), fcntl::O_RDONLY) {
+        Ok(file) => file,
+        Err(_) => return false,
+    };
+    let file = BufReader::new(file);
+
+    for line in file.split(b'\n') {
+        let line = match line {
+            Ok(line) => line,
+            Err(_) => return false,
+        };
+        if line.starts_with(b"options")
+            && line
+                .split(|c| c.is_ascii_whitespace())
+                .any(|option| option == b"no-cache")
+        {
+            return true;
+        }
+    }
+
+    false
+}
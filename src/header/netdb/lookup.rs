@@ -1,5 +1,4 @@
 use alloc::{
-    boxed::Box,
     string::{String, ToString},
     vec::{IntoIter, Vec},
 };
@@ -20,7 +19,8 @@ use crate::header::{
 };
 
 use super::{
-    dns::{Dns, DnsQuery},
+    cache,
+    dns::{Dns, DnsAnswer, DnsQuery},
     sys::get_dns_server,
 };
 
@@ -33,7 +33,12 @@ impl Iterator for LookupHost {
     }
 }
 
-pub fn lookup_host(host: &str) -> Result<LookupHost, c_int> {
+const DNS_TYPE_A: u16 = 0x0001;
+const DNS_TYPE_PTR: u16 = 0x000C;
+pub(super) const DNS_CLASS_IN: u16 = 0x0001;
+const DNS_RCODE_NXDOMAIN: u16 = 3;
+
+fn dns_server() -> Result<u32, c_int> {
     let dns_string = get_dns_server();
 
     let dns_vec: Vec<u8> = dns_string
@@ -47,105 +52,111 @@ pub fn lookup_host(host: &str) -> Result<LookupHost, c_int> {
         for (i, octet) in dns_vec.iter().enumerate() {
             dns_arr[i] = *octet;
         }
-        let dns_addr = unsafe { mem::transmute::<[u8; 4], u32>(dns_arr) };
-
-        let mut timespec = timespec::default();
-        Sys::clock_gettime(time::constants::CLOCK_REALTIME, &mut timespec);
-        let tid = (timespec.tv_nsec >> 16) as u16;
-
-        let packet = Dns {
-            transaction_id: tid,
-            flags: 0x0100,
-            queries: vec![DnsQuery {
-                name: host.to_string(),
-                q_type: 0x0001,
-                q_class: 0x0001,
-            }],
-            answers: vec![],
-        };
-
-        let packet_data = packet.compile();
-        let packet_data_len = packet_data.len();
-
-        let packet_data_box = packet_data.into_boxed_slice();
-        let packet_data_ptr = Box::into_raw(packet_data_box) as *mut _ as *mut c_void;
-
-        let dest = sockaddr_in {
-            sin_family: AF_INET as u16,
-            sin_port: htons(53),
-            sin_addr: in_addr { s_addr: dns_addr },
-            ..Default::default()
-        };
-        let dest_ptr = &dest as *const _ as *const sockaddr;
-
-        let sock = unsafe {
-            let sock = sys_socket::socket(AF_INET, SOCK_DGRAM, IPPROTO_UDP as i32);
-            if sys_socket::connect(sock, dest_ptr, mem::size_of_val(&dest) as socklen_t) < 0 {
-                return Err(EIO);
-            }
-            if sys_socket::send(sock, packet_data_ptr, packet_data_len, 0) < 0 {
-                Box::from_raw(packet_data_ptr);
-                return Err(EIO);
-            }
-            sock
-        };
-
-        unsafe {
-            Box::from_raw(packet_data_ptr);
-        }
+        Ok(unsafe { mem::transmute::<[u8; 4], u32>(dns_arr) })
+    } else {
+        Err(EINVAL)
+    }
+}
 
-        let i = 0 as socklen_t;
-        let mut buf = vec![0u8; 65536];
-        let buf_ptr = buf.as_mut_ptr() as *mut c_void;
+/// Sends a single query to the configured nameserver and returns the parsed response.
+fn query(name: &str, q_type: u16) -> Result<Dns, c_int> {
+    let dns_addr = dns_server()?;
+
+    let mut timespec = timespec::default();
+    Sys::clock_gettime(time::constants::CLOCK_REALTIME, &mut timespec);
+    let tid = (timespec.tv_nsec >> 16) as u16;
+
+    let packet = Dns {
+        transaction_id: tid,
+        flags: 0x0100,
+        queries: vec![DnsQuery {
+            name: name.to_string(),
+            q_type,
+            q_class: DNS_CLASS_IN,
+        }],
+        answers: vec![],
+    };
+
+    let packet_data = packet.compile();
+
+    let dest = sockaddr_in {
+        sin_family: AF_INET as u16,
+        sin_port: htons(53),
+        sin_addr: in_addr { s_addr: dns_addr },
+        ..Default::default()
+    };
+    let dest_ptr = &dest as *const _ as *const sockaddr;
+
+    let sock = unsafe { sys_socket::socket(AF_INET, SOCK_DGRAM, IPPROTO_UDP as i32) };
+    if sock < 0 {
+        return Err(EIO);
+    }
 
-        let count = unsafe { sys_socket::recv(sock, buf_ptr, 65536, 0) };
-        if count < 0 {
+    let mut buf = vec![0u8; 65536];
+    let count = unsafe {
+        if sys_socket::connect(sock, dest_ptr, mem::size_of_val(&dest) as socklen_t) < 0
+            || sys_socket::send(
+                sock,
+                packet_data.as_ptr() as *const c_void,
+                packet_data.len(),
+                0,
+            ) < 0
+        {
+            Sys::close(sock);
             return Err(EIO);
         }
-
-        match Dns::parse(&buf[..count as usize]) {
-            Ok(response) => {
-                let mut addrs = vec![];
-                for answer in response.answers.iter() {
-                    if answer.a_type == 0x0001 && answer.a_class == 0x0001 && answer.data.len() == 4
-                    {
-                        let addr = in_addr {
-                            s_addr: unsafe {
-                                mem::transmute::<[u8; 4], u32>([
-                                    answer.data[0],
-                                    answer.data[1],
-                                    answer.data[2],
-                                    answer.data[3],
-                                ])
-                            },
-                        };
-                        addrs.push(addr);
-                    }
-                }
-                Ok(LookupHost(addrs.into_iter()))
-            }
-            Err(_err) => Err(EINVAL),
-        }
-    } else {
-        Err(EINVAL)
+        sys_socket::recv(sock, buf.as_mut_ptr() as *mut c_void, buf.len(), 0)
+    };
+    Sys::close(sock);
+    if count < 0 {
+        return Err(EIO);
     }
+
+    Dns::parse(&buf[..count as usize]).map_err(|_err| EINVAL)
 }
 
-pub fn lookup_addr(addr: in_addr) -> Result<Vec<Vec<u8>>, c_int> {
-    let dns_string = get_dns_server();
+/// Resolves `name`, consulting the cache first. Only answers of type `q_type` are returned.
+fn resolve(name: &str, q_type: u16) -> Result<Vec<DnsAnswer>, c_int> {
+    if let Some(answers) = cache::get(name, q_type) {
+        return Ok(answers);
+    }
 
-    let dns_vec: Vec<u8> = dns_string
-        .trim()
-        .split('.')
-        .map(|octet| octet.parse::<u8>().unwrap_or(0))
-        .collect();
+    let response = query(name, q_type)?;
+    let rcode = response.flags & 0x000F;
+    if rcode == 0 {
+        cache::insert(name, q_type, &response.answers);
+    } else if rcode == DNS_RCODE_NXDOMAIN {
+        cache::insert(name, q_type, &[]);
+    }
 
-    let mut dns_arr = [0u8; 4];
+    Ok(response
+        .answers
+        .into_iter()
+        .filter(|answer| answer.a_type == q_type && answer.a_class == DNS_CLASS_IN)
+        .collect())
+}
 
-    for (i, octet) in dns_vec.iter().enumerate() {
-        dns_arr[i] = *octet;
+pub fn lookup_host(host: &str) -> Result<LookupHost, c_int> {
+    let mut addrs = vec![];
+    for answer in resolve(host, DNS_TYPE_A)? {
+        if answer.data.len() == 4 {
+            let addr = in_addr {
+                s_addr: unsafe {
+                    mem::transmute::<[u8; 4], u32>([
+                        answer.data[0],
+                        answer.data[1],
+                        answer.data[2],
+                        answer.data[3],
+                    ])
+                },
+            };
+            addrs.push(addr);
+        }
     }
+    Ok(LookupHost(addrs.into_iter()))
+}
 
+pub fn lookup_addr(addr: in_addr) -> Result<Vec<Vec<u8>>, c_int> {
     let mut addr_vec: Vec<u8> = unsafe { mem::transmute::<u32, [u8; 4]>(addr.s_addr).to_vec() };
     addr_vec.reverse();
     let mut name: Vec<u8> = vec![];
@@ -159,87 +170,19 @@ pub fn lookup_addr(addr: in_addr) -> Result<Vec<Vec<u8>>, c_int> {
     for ch in b".IN-ADDR.ARPA" {
         name.push(*ch);
     }
-
-    if dns_vec.len() == 4 {
-        let mut timespec = timespec::default();
-        Sys::clock_gettime(time::constants::CLOCK_REALTIME, &mut timespec);
-        let tid = (timespec.tv_nsec >> 16) as u16;
-
-        let packet = Dns {
-            transaction_id: tid,
-            flags: 0x0100,
-            queries: vec![DnsQuery {
-                name: String::from_utf8(name).unwrap(),
-                q_type: 0x000C,
-                q_class: 0x0001,
-            }],
-            answers: vec![],
-        };
-
-        let packet_data = packet.compile();
-        let packet_data_len = packet_data.len();
-        let packet_data_box = packet_data.into_boxed_slice();
-        let packet_data_ptr = Box::into_raw(packet_data_box) as *mut _ as *mut c_void;
-
-        let dest = sockaddr_in {
-            sin_family: AF_INET as u16,
-            sin_port: htons(53),
-            sin_addr: in_addr {
-                s_addr: unsafe { mem::transmute::<[u8; 4], u32>(dns_arr) },
-            },
-            ..Default::default()
-        };
-
-        let dest_ptr = &dest as *const _ as *const sockaddr;
-
-        let sock = unsafe {
-            let sock = sys_socket::socket(AF_INET, SOCK_DGRAM, IPPROTO_UDP as i32);
-            if sys_socket::connect(sock, dest_ptr, mem::size_of_val(&dest) as socklen_t) < 0 {
-                return Err(EIO);
-            }
-            sock
-        };
-
-        unsafe {
-            if sys_socket::send(sock, packet_data_ptr, packet_data_len, 0) < 0 {
-                return Err(EIO);
-            }
-        }
-
-        unsafe {
-            Box::from_raw(packet_data_ptr);
-        }
-
-        let i = mem::size_of::<sockaddr_in>() as socklen_t;
-        let mut buf = [0u8; 65536];
-        let buf_ptr = buf.as_mut_ptr() as *mut c_void;
-
-        let count = unsafe { sys_socket::recv(sock, buf_ptr, 65536, 0) };
-        if count < 0 {
-            return Err(EIO);
-        }
-
-        match Dns::parse(&buf[..count as usize]) {
-            Ok(response) => {
-                let mut names = vec![];
-                for answer in response.answers.iter() {
-                    if answer.a_type == 0x000C && answer.a_class == 0x0001 {
-                        // answer.data is encoded kinda weird.
-                        // Basically length-prefixed strings for each
-                        // subsection of the domain.
-                        // We need to parse this to insert periods where
-                        // they belong (ie at the end of each string)
-                        let data = parse_revdns_answer(&answer.data);
-                        names.push(data);
-                    }
-                }
-                Ok(names)
-            }
-            Err(_err) => Err(EINVAL),
-        }
-    } else {
-        Err(EINVAL)
+    let name = String::from_utf8(name).unwrap();
+
+    let mut names = vec![];
+    for answer in resolve(&name, DNS_TYPE_PTR)? {
+        // answer.data is encoded kinda weird.
+        // Basically length-prefixed strings for each
+        // subsection of the domain.
+        // We need to parse this to insert periods where
+        // they belong (ie at the end of each string)
+        let data = parse_revdns_answer(&answer.data);
+        names.push(data);
     }
+    Ok(names)
 }
 
 fn parse_revdns_answer(data: &[u8]) -> Vec<u8> {
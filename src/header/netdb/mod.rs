@@ -1,5 +1,7 @@
 //! netdb implementation for Redox, following http://pubs.opengroup.org/onlinepubs/7908799/xns/netdb.h.html
 
+pub(crate) mod cache;
+pub(crate) mod db;
 mod dns;
 
 use core::{
@@ -16,11 +18,14 @@ use crate::{
         errno::*,
         fcntl::O_RDONLY,
         netinet_in::{in_addr, sockaddr_in, sockaddr_in6},
-        stdlib::atoi,
         strings::strcasecmp,
-        sys_socket::{constants::AF_INET, sa_family_t, sockaddr, socklen_t},
+        sys_socket::{
+            constants::{AF_INET, SOCK_DGRAM, SOCK_STREAM},
+            sa_family_t, sockaddr, socklen_t,
+        },
         unistd::SEEK_SET,
     },
+    nss,
     platform::{
         self,
         rlb::{Line, RawLineBuffer},
@@ -131,6 +136,8 @@ pub static mut NET_ADDR: Option<u32> = None;
 static mut N_POS: usize = 0;
 static mut NET_STAYOPEN: c_int = 0;
 
+/// Why the last hosts lookup of this thread failed, read in C through __h_errno_location
+#[thread_local]
 #[allow(non_upper_case_globals)]
 #[no_mangle]
 pub static mut h_errno: c_int = 0;
@@ -139,29 +146,26 @@ pub const NO_DATA: c_int = 2;
 pub const NO_RECOVERY: c_int = 3;
 pub const TRY_AGAIN: c_int = 4;
 
-static mut PROTODB: c_int = 0;
+static mut PROTODB: c_int = -1;
 static mut PROTO_ENTRY: protoent = protoent {
     p_name: ptr::null_mut(),
     p_aliases: ptr::null_mut(),
     p_proto: 0 as c_int,
 };
-static mut PROTO_NAME: Option<Vec<u8>> = None;
-static mut PROTO_ALIASES: Option<Vec<Vec<u8>>> = None;
-static mut PROTO_NUM: Option<c_int> = None;
+/// Holds the strings and alias array PROTO_ENTRY points to
+static mut PROTO_BUF: Vec<u8> = Vec::new();
 static mut P_POS: usize = 0;
 static mut PROTO_STAYOPEN: c_int = 0;
 
-static mut SERVDB: c_int = 0;
+static mut SERVDB: c_int = -1;
 static mut SERV_ENTRY: servent = servent {
     s_name: ptr::null_mut(),
     s_aliases: ptr::null_mut(),
     s_port: 0 as c_int,
     s_proto: ptr::null_mut(),
 };
-static mut SERV_NAME: Option<Vec<u8>> = None;
-static mut SERV_ALIASES: Option<Vec<Vec<u8>>> = None;
-static mut SERV_PORT: Option<c_int> = None;
-static mut SERV_PROTO: Option<Vec<u8>> = None;
+/// Holds the strings and alias array SERV_ENTRY points to
+static mut SERV_BUF: Vec<u8> = Vec::new();
 static mut S_POS: usize = 0;
 static mut SERV_STAYOPEN: c_int = 0;
 
@@ -177,14 +181,73 @@ pub unsafe extern "C" fn endnetent() {
 
 #[no_mangle]
 pub unsafe extern "C" fn endprotoent() {
-    Sys::close(PROTODB);
-    PROTODB = 0;
+    if PROTODB >= 0 {
+        Sys::close(PROTODB);
+    }
+    PROTODB = -1;
 }
 
 #[no_mangle]
 pub unsafe extern "C" fn endservent() {
-    Sys::close(SERVDB);
-    SERVDB = 0;
+    if SERVDB >= 0 {
+        Sys::close(SERVDB);
+    }
+    SERVDB = -1;
+}
+
+/// Holds the strings and alias array HOST_ENTRY points to after a lookup
+static mut HOST_BUF: Vec<u8> = Vec::new();
+/// Holds the addresses of HOST_ENTRY after a lookup, and the NULL-terminated array of them
+static mut HOST_ADDRS: Vec<in_addr> = Vec::new();
+static mut HOST_ADDR_PTRS: Vec<*mut c_char> = Vec::new();
+
+/// Stores the result of a hosts lookup in HOST_ENTRY, or sets h_errno and errno to why there
+/// isn't one.
+unsafe fn static_hostent(res: Result<nss::Host, nss::Status>) -> *mut hostent {
+    let host = match res {
+        Ok(host) => host,
+        Err(status) => {
+            h_errno = match status {
+                nss::Status::NotFound => HOST_NOT_FOUND,
+                nss::Status::TryAgain(_) => TRY_AGAIN,
+                nss::Status::Unavailable(_) => NO_RECOVERY,
+            };
+            platform::errno = match status.errno() {
+                0 => ENOENT,
+                errno => errno,
+            };
+            return ptr::null_mut();
+        }
+    };
+
+    let packed = loop {
+        let buf = HOST_BUF.as_mut_ptr() as *mut c_char;
+        match db::pack(buf, HOST_BUF.len(), &host.name, &host.aliases, None) {
+            Ok(packed) => break packed,
+            // Leave room for realigning the alias array after the reallocation
+            Err(needed) => HOST_BUF.resize(needed + mem::align_of::<*mut c_char>(), 0),
+        }
+    };
+    HOST_ADDRS = host.addrs;
+    HOST_ADDR_PTRS = HOST_ADDRS
+        .iter_mut()
+        .map(|addr| addr as *mut in_addr as *mut c_char)
+        .collect();
+    HOST_ADDR_PTRS.push(ptr::null_mut());
+
+    HOST_ENTRY = hostent {
+        h_name: packed.name,
+        h_aliases: packed.aliases,
+        h_addrtype: AF_INET,
+        h_length: mem::size_of::<in_addr>() as c_int,
+        h_addr_list: HOST_ADDR_PTRS.as_mut_ptr(),
+    };
+    &mut HOST_ENTRY
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn __h_errno_location() -> *mut c_int {
+    &mut h_errno
 }
 
 #[no_mangle]
@@ -193,61 +256,13 @@ pub unsafe extern "C" fn gethostbyaddr(
     length: socklen_t,
     format: c_int,
 ) -> *mut hostent {
-    let addr: in_addr = *(v as *mut in_addr);
-
-    // check the hosts file first
-    let mut p: *mut hostent;
-    sethostent(HOST_STAYOPEN);
-    while {
-        p = gethostent();
-        !p.is_null()
-    } {
-        let mut cp = (*p).h_addr_list;
-        loop {
-            if cp.is_null() {
-                break;
-            }
-            if (*cp).is_null() {
-                break;
-            }
-            let mut cp_slice: [i8; 4] = [0i8; 4];
-            (*cp).copy_to(cp_slice.as_mut_ptr(), 4);
-            let cp_s_addr = mem::transmute::<[i8; 4], u32>(cp_slice);
-            if cp_s_addr == addr.s_addr {
-                sethostent(HOST_STAYOPEN);
-                return p;
-            }
-            cp = cp.offset(1);
-        }
-    }
-
-    //TODO actually get aliases
-    let mut _host_aliases: Vec<Vec<u8>> = Vec::new();
-    _host_aliases.push(vec![b'\0']);
-    let mut host_aliases: Vec<*mut i8> = Vec::new();
-    host_aliases.push(ptr::null_mut());
-    HOST_ALIASES = Some(_host_aliases);
-
-    match lookup_addr(addr) {
-        Ok(s) => {
-            _HOST_ADDR_LIST = mem::transmute::<u32, [u8; 4]>(addr.s_addr);
-            HOST_ADDR_LIST = [_HOST_ADDR_LIST.as_mut_ptr() as *mut c_char, ptr::null_mut()];
-            let host_name = s[0].to_vec();
-            HOST_NAME = Some(host_name);
-            HOST_ENTRY = hostent {
-                h_name: HOST_NAME.as_mut().unwrap().as_mut_ptr() as *mut c_char,
-                h_aliases: host_aliases.as_mut_slice().as_mut_ptr() as *mut *mut i8,
-                h_addrtype: format,
-                h_length: length as i32,
-                h_addr_list: HOST_ADDR_LIST.as_mut_ptr(),
-            };
-            &mut HOST_ENTRY
-        }
-        Err(e) => {
-            platform::errno = e;
-            ptr::null_mut()
-        }
+    if format != AF_INET || (length as usize) < mem::size_of::<in_addr>() {
+        h_errno = NO_RECOVERY;
+        platform::errno = EAFNOSUPPORT;
+        return ptr::null_mut();
     }
+    let addr: in_addr = *(v as *const in_addr);
+    static_hostent(nss::hosts(nss::HostKey::Addr(addr)))
 }
 
 #[no_mangle]
@@ -275,73 +290,7 @@ pub unsafe extern "C" fn gethostbyname(name: *const c_char) -> *mut hostent {
         return gethostbyaddr(&addr as *const _ as *const c_void, 4, AF_INET);
     }
 
-    // check the hosts file first
-    let mut p: *mut hostent;
-    sethostent(HOST_STAYOPEN);
-    while {
-        p = gethostent();
-        !p.is_null()
-    } {
-        if strcasecmp((*p).h_name, name) == 0 {
-            sethostent(HOST_STAYOPEN);
-            return p;
-        }
-        let mut cp = (*p).h_aliases;
-        loop {
-            if cp.is_null() {
-                break;
-            }
-            if (*cp).is_null() {
-                break;
-            }
-            if strcasecmp(*cp, name) == 0 {
-                sethostent(HOST_STAYOPEN);
-                return p;
-            }
-            cp = cp.offset(1);
-        }
-    }
-
-    let name_cstr = CStr::from_ptr(name);
-
-    let mut host = match lookup_host(str::from_utf8_unchecked(name_cstr.to_bytes())) {
-        Ok(lookuphost) => lookuphost,
-        Err(e) => {
-            platform::errno = e;
-            return ptr::null_mut();
-        }
-    };
-    let host_addr = match host.next() {
-        Some(result) => result,
-        None => {
-            platform::errno = ENOENT;
-            return ptr::null_mut();
-        }
-    };
-
-    let host_name: Vec<u8> = name_cstr.to_bytes().to_vec();
-    HOST_NAME = Some(host_name);
-    _HOST_ADDR_LIST = mem::transmute::<u32, [u8; 4]>(host_addr.s_addr);
-    HOST_ADDR_LIST = [_HOST_ADDR_LIST.as_mut_ptr() as *mut c_char, ptr::null_mut()];
-    HOST_ADDR = Some(host_addr);
-
-    //TODO actually get aliases
-    let mut _host_aliases: Vec<Vec<u8>> = Vec::new();
-    _host_aliases.push(vec![b'\0']);
-    let mut host_aliases: Vec<*mut i8> = Vec::new();
-    host_aliases.push(ptr::null_mut());
-    host_aliases.push(ptr::null_mut());
-    HOST_ALIASES = Some(_host_aliases);
-
-    HOST_ENTRY = hostent {
-        h_name: HOST_NAME.as_mut().unwrap().as_mut_ptr() as *mut c_char,
-        h_aliases: host_aliases.as_mut_slice().as_mut_ptr() as *mut *mut i8,
-        h_addrtype: AF_INET,
-        h_length: 4,
-        h_addr_list: HOST_ADDR_LIST.as_mut_ptr(),
-    };
-    sethostent(HOST_STAYOPEN);
-    &mut HOST_ENTRY as *mut hostent
+    static_hostent(nss::hosts(nss::HostKey::Name(name_cstr.to_bytes())))
 }
 
 pub unsafe extern "C" fn getnetbyaddr(net: u32, net_type: c_int) -> *mut netent {
@@ -427,263 +376,332 @@ pub unsafe extern "C" fn getnetent() -> *mut netent {
     &mut NET_ENTRY as *mut netent
 }
 
-#[no_mangle]
-pub unsafe extern "C" fn getprotobyname(name: *const c_char) -> *mut protoent {
-    let mut p: *mut protoent;
-    setprotoent(PROTO_STAYOPEN);
-    while {
-        p = getprotoent();
-        !p.is_null()
-    } {
-        if strcasecmp((*p).p_name, name) == 0 {
-            setprotoent(PROTO_STAYOPEN);
-            return p;
+/// Reads the database entry following `pos` that `parse` accepts, opening the database if needed.
+unsafe fn next_entry<T>(
+    fd: &mut c_int,
+    path: &CStr,
+    pos: &mut usize,
+    parse: fn(&[u8]) -> Option<T>,
+) -> Option<T> {
+    if *fd < 0 {
+        *fd = Sys::open(path, O_RDONLY, 0);
+    }
+    let mut rlb = RawLineBuffer::new(*fd);
+    rlb.seek(*pos);
+
+    loop {
+        let entry = match rlb.next() {
+            Line::Some(line) => parse(line),
+            _ => return None,
+        };
+        if let Some(entry) = entry {
+            rlb.next();
+            *pos = rlb.line_pos();
+            return Some(entry);
         }
+    }
+}
 
-        let mut cp = (*p).p_aliases;
-        loop {
-            if cp.is_null() {
-                setprotoent(PROTO_STAYOPEN);
-                break;
-            }
-            if (*cp).is_null() {
-                setprotoent(PROTO_STAYOPEN);
-                break;
-            }
-            if strcasecmp(*cp, name) == 0 {
-                setprotoent(PROTO_STAYOPEN);
-                return p;
-            }
-            cp = cp.offset(1);
+fn c_str_arg<'a>(s: *const c_char) -> Option<&'a [u8]> {
+    if s.is_null() {
+        None
+    } else {
+        Some(unsafe { CStr::from_ptr(s) }.to_bytes())
+    }
+}
+
+unsafe fn fill_protoent(
+    protocol: &db::Protocol,
+    result_buf: *mut protoent,
+    buf: *mut c_char,
+    buflen: size_t,
+) -> Result<(), usize> {
+    let packed = db::pack(buf, buflen, &protocol.name, &protocol.aliases, None)?;
+    *result_buf = protoent {
+        p_name: packed.name,
+        p_aliases: packed.aliases,
+        p_proto: protocol.number,
+    };
+    Ok(())
+}
+
+unsafe fn fill_servent(
+    service: &db::Service,
+    result_buf: *mut servent,
+    buf: *mut c_char,
+    buflen: size_t,
+) -> Result<(), usize> {
+    let packed = db::pack(
+        buf,
+        buflen,
+        &service.name,
+        &service.aliases,
+        Some(&service.proto),
+    )?;
+    *result_buf = servent {
+        s_name: packed.name,
+        s_aliases: packed.aliases,
+        s_port: htons(service.port) as c_int,
+        s_proto: packed.extra,
+    };
+    Ok(())
+}
+
+/// Stores `protocol` in PROTO_ENTRY, for the functions returning static storage.
+unsafe fn static_protoent(protocol: Option<db::Protocol>) -> *mut protoent {
+    let protocol = match protocol {
+        Some(protocol) => protocol,
+        None => return ptr::null_mut(),
+    };
+    loop {
+        let buf = PROTO_BUF.as_mut_ptr() as *mut c_char;
+        match fill_protoent(&protocol, &mut PROTO_ENTRY, buf, PROTO_BUF.len()) {
+            Ok(()) => return &mut PROTO_ENTRY,
+            // Leave room for realigning the alias array after the reallocation
+            Err(needed) => PROTO_BUF.resize(needed + mem::align_of::<*mut c_char>(), 0),
         }
     }
-    setprotoent(PROTO_STAYOPEN);
+}
 
-    platform::errno = ENOENT;
-    ptr::null_mut() as *mut protoent
+/// Stores `service` in SERV_ENTRY, for the functions returning static storage.
+unsafe fn static_servent(service: Option<db::Service>) -> *mut servent {
+    let service = match service {
+        Some(service) => service,
+        None => return ptr::null_mut(),
+    };
+    loop {
+        let buf = SERV_BUF.as_mut_ptr() as *mut c_char;
+        match fill_servent(&service, &mut SERV_ENTRY, buf, SERV_BUF.len()) {
+            Ok(()) => return &mut SERV_ENTRY,
+            Err(needed) => SERV_BUF.resize(needed + mem::align_of::<*mut c_char>(), 0),
+        }
+    }
 }
 
-#[no_mangle]
-pub unsafe extern "C" fn getprotobynumber(number: c_int) -> *mut protoent {
-    setprotoent(PROTO_STAYOPEN);
-    let mut p: *mut protoent;
-    while {
-        p = getprotoent();
-        !p.is_null()
-    } {
-        if (*p).p_proto == number {
-            setprotoent(PROTO_STAYOPEN);
-            return p;
+/// Completes a reentrant lookup. `missing` is returned when there is no entry, which is ENOENT
+/// at the end of the *ent_r iteration but not an error for the other functions.
+unsafe fn protoent_r(
+    protocol: Option<db::Protocol>,
+    missing: c_int,
+    result_buf: *mut protoent,
+    buf: *mut c_char,
+    buflen: size_t,
+    result: *mut *mut protoent,
+) -> c_int {
+    *result = ptr::null_mut();
+    let protocol = match protocol {
+        Some(protocol) => protocol,
+        None => return missing,
+    };
+    match fill_protoent(&protocol, result_buf, buf, buflen) {
+        Ok(()) => {
+            *result = result_buf;
+            0
         }
+        Err(_) => ERANGE,
     }
-    setprotoent(PROTO_STAYOPEN);
-    platform::errno = ENOENT;
-    ptr::null_mut() as *mut protoent
 }
 
-#[no_mangle]
-pub unsafe extern "C" fn getprotoent() -> *mut protoent {
-    if PROTODB == 0 {
-        PROTODB = Sys::open(&CString::new("/etc/protocols").unwrap(), O_RDONLY, 0);
+/// See `protoent_r`.
+unsafe fn servent_r(
+    service: Option<db::Service>,
+    missing: c_int,
+    result_buf: *mut servent,
+    buf: *mut c_char,
+    buflen: size_t,
+    result: *mut *mut servent,
+) -> c_int {
+    *result = ptr::null_mut();
+    let service = match service {
+        Some(service) => service,
+        None => return missing,
+    };
+    match fill_servent(&service, result_buf, buf, buflen) {
+        Ok(()) => {
+            *result = result_buf;
+            0
+        }
+        Err(_) => ERANGE,
     }
+}
 
-    let mut rlb = RawLineBuffer::new(PROTODB);
-    rlb.seek(P_POS);
+fn protocol_by_name(name: &[u8]) -> Option<db::Protocol> {
+    db::find_protocol(|protocol| protocol.is_named(name))
+}
 
-    let mut r: Box<str> = Box::default();
-    while r.is_empty() || r.split_whitespace().next() == None || r.starts_with('#') {
-        r = match rlb.next() {
-            Line::Some(s) => bytes_to_box_str(s),
-            _ => {
-                if PROTO_STAYOPEN == 0 {
-                    endprotoent();
-                }
-                return ptr::null_mut();
-            }
-        };
+fn protocol_by_number(number: c_int) -> Option<db::Protocol> {
+    db::find_protocol(|protocol| protocol.number == number)
+}
+
+unsafe fn next_protocol() -> Option<db::Protocol> {
+    let protocol = next_entry(
+        &mut PROTODB,
+        db::protocols_path(),
+        &mut P_POS,
+        db::parse_protocol,
+    );
+    if PROTO_STAYOPEN == 0 {
+        endprotoent();
     }
-    rlb.next();
-    P_POS = rlb.line_pos();
+    protocol
+}
 
-    let mut iter: SplitWhitespace = r.split_whitespace();
+fn service_by_name(name: &[u8], proto: Option<&[u8]>) -> Option<db::Service> {
+    db::find_service(|service| service.is_named(name) && service.has_proto(proto))
+}
 
-    let mut proto_name: Vec<u8> = iter.next().unwrap().as_bytes().to_vec();
-    proto_name.push(b'\0');
+/// `port` is in network byte order, like `servent::s_port`.
+fn service_by_port(port: c_int, proto: Option<&[u8]>) -> Option<db::Service> {
+    db::find_service(|service| htons(service.port) as c_int == port && service.has_proto(proto))
+}
 
-    let mut num = iter.next().unwrap().as_bytes().to_vec();
-    num.push(b'\0');
-    PROTO_NUM = Some(atoi(num.as_mut_slice().as_mut_ptr() as *mut i8));
+unsafe fn next_service() -> Option<db::Service> {
+    let service = next_entry(
+        &mut SERVDB,
+        db::services_path(),
+        &mut S_POS,
+        db::parse_service,
+    );
+    if SERV_STAYOPEN == 0 {
+        endservent();
+    }
+    service
+}
 
-    let mut _proto_aliases: Vec<Vec<u8>> = Vec::new();
-    for s in iter {
-        let mut alias = s.as_bytes().to_vec();
-        alias.push(b'\0');
-        _proto_aliases.push(alias);
+#[no_mangle]
+pub unsafe extern "C" fn getprotobyname(name: *const c_char) -> *mut protoent {
+    let p = static_protoent(protocol_by_name(CStr::from_ptr(name).to_bytes()));
+    if p.is_null() {
+        platform::errno = ENOENT;
     }
-    let mut proto_aliases: Vec<*mut i8> = _proto_aliases
-        .iter_mut()
-        .map(|x| x.as_mut_ptr() as *mut i8)
-        .collect();
-    proto_aliases.push(ptr::null_mut());
+    p
+}
 
-    PROTO_ALIASES = Some(_proto_aliases);
-    PROTO_NAME = Some(proto_name);
+#[no_mangle]
+pub unsafe extern "C" fn getprotobyname_r(
+    name: *const c_char,
+    result_buf: *mut protoent,
+    buf: *mut c_char,
+    buflen: size_t,
+    result: *mut *mut protoent,
+) -> c_int {
+    let protocol = protocol_by_name(CStr::from_ptr(name).to_bytes());
+    protoent_r(protocol, 0, result_buf, buf, buflen, result)
+}
 
-    PROTO_ENTRY = protoent {
-        p_name: PROTO_NAME.as_mut().unwrap().as_mut_slice().as_mut_ptr() as *mut c_char,
-        p_aliases: proto_aliases.as_mut_slice().as_mut_ptr() as *mut *mut i8,
-        p_proto: PROTO_NUM.unwrap(),
-    };
-    if PROTO_STAYOPEN == 0 {
-        endprotoent();
+#[no_mangle]
+pub unsafe extern "C" fn getprotobynumber(number: c_int) -> *mut protoent {
+    let p = static_protoent(protocol_by_number(number));
+    if p.is_null() {
+        platform::errno = ENOENT;
     }
-    &mut PROTO_ENTRY as *mut protoent
+    p
 }
 
 #[no_mangle]
-pub unsafe extern "C" fn getservbyname(name: *const c_char, proto: *const c_char) -> *mut servent {
-    setservent(SERV_STAYOPEN);
-    let mut p: *mut servent;
-    if proto.is_null() {
-        while {
-            p = getservent();
-            !p.is_null()
-        } {
-            if strcasecmp((*p).s_name, name) == 0 {
-                setservent(SERV_STAYOPEN);
-                return p;
-            }
-        }
-    } else {
-        while {
-            p = getservent();
-            !p.is_null()
-        } {
-            if strcasecmp((*p).s_name, name) == 0 && strcasecmp((*p).s_proto, proto) == 0 {
-                setservent(SERV_STAYOPEN);
-                return p;
-            }
-        }
-    }
-    setservent(SERV_STAYOPEN);
-    platform::errno = ENOENT;
-    ptr::null_mut() as *mut servent
+pub unsafe extern "C" fn getprotobynumber_r(
+    number: c_int,
+    result_buf: *mut protoent,
+    buf: *mut c_char,
+    buflen: size_t,
+    result: *mut *mut protoent,
+) -> c_int {
+    protoent_r(
+        protocol_by_number(number),
+        0,
+        result_buf,
+        buf,
+        buflen,
+        result,
+    )
 }
 
 #[no_mangle]
-pub unsafe extern "C" fn getservbyport(port: c_int, proto: *const c_char) -> *mut servent {
-    setservent(SERV_STAYOPEN);
-    let mut p: *mut servent;
-    if proto.is_null() {
-        while {
-            p = getservent();
-            !p.is_null()
-        } {
-            if (*p).s_port == port {
-                setservent(SERV_STAYOPEN);
-                return p;
-            }
-        }
-    } else {
-        while {
-            p = getservent();
-            !p.is_null()
-        } {
-            if (*p).s_port == port && strcasecmp((*p).s_proto, proto) == 0 {
-                setservent(SERV_STAYOPEN);
-                return p;
-            }
-        }
+pub unsafe extern "C" fn getprotoent() -> *mut protoent {
+    static_protoent(next_protocol())
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn getprotoent_r(
+    result_buf: *mut protoent,
+    buf: *mut c_char,
+    buflen: size_t,
+    result: *mut *mut protoent,
+) -> c_int {
+    let old_pos = P_POS;
+    let ret = protoent_r(next_protocol(), ENOENT, result_buf, buf, buflen, result);
+    if ret == ERANGE {
+        // Let the caller retry the same entry with a larger buffer
+        P_POS = old_pos;
     }
-    setservent(SERV_STAYOPEN);
-    platform::errno = ENOENT;
-    ptr::null_mut()
+    ret
 }
 
 #[no_mangle]
-pub unsafe extern "C" fn getservent() -> *mut servent {
-    if SERVDB == 0 {
-        SERVDB = Sys::open(&CString::new("/etc/services").unwrap(), O_RDONLY, 0);
+pub unsafe extern "C" fn getservbyname(name: *const c_char, proto: *const c_char) -> *mut servent {
+    let p = static_servent(service_by_name(
+        CStr::from_ptr(name).to_bytes(),
+        c_str_arg(proto),
+    ));
+    if p.is_null() {
+        platform::errno = ENOENT;
     }
-    let mut rlb = RawLineBuffer::new(SERVDB);
-    rlb.seek(S_POS);
+    p
+}
 
-    let r: Box<str> = Box::default();
+#[no_mangle]
+pub unsafe extern "C" fn getservbyname_r(
+    name: *const c_char,
+    proto: *const c_char,
+    result_buf: *mut servent,
+    buf: *mut c_char,
+    buflen: size_t,
+    result: *mut *mut servent,
+) -> c_int {
+    let service = service_by_name(CStr::from_ptr(name).to_bytes(), c_str_arg(proto));
+    servent_r(service, 0, result_buf, buf, buflen, result)
+}
 
-    loop {
-        let r = match rlb.next() {
-            Line::Some(s) => bytes_to_box_str(s),
-            _ => {
-                if SERV_STAYOPEN == 0 {
-                    endservent();
-                }
-                return ptr::null_mut();
-            }
-        };
+#[no_mangle]
+pub unsafe extern "C" fn getservbyport(port: c_int, proto: *const c_char) -> *mut servent {
+    let p = static_servent(service_by_port(port, c_str_arg(proto)));
+    if p.is_null() {
+        platform::errno = ENOENT;
+    }
+    p
+}
 
-        let mut iter = r.split_whitespace();
-        let mut serv_name = match iter.next() {
-            Some(serv_name) => serv_name.as_bytes().to_vec(),
-            None => continue,
-        };
-        serv_name.push(b'\0');
-        let port_proto = match iter.next() {
-            Some(port_proto) => port_proto,
-            None => continue,
-        };
-        let mut split = port_proto.split('/');
-        let mut port = match split.next() {
-            Some(port) => port.as_bytes().to_vec(),
-            None => continue,
-        };
-        port.push(b'\0');
-        SERV_PORT =
-            Some(htons(atoi(port.as_mut_slice().as_mut_ptr() as *mut i8) as u16) as u32 as i32);
-        let mut proto = match split.next() {
-            Some(proto) => proto.as_bytes().to_vec(),
-            None => continue,
-        };
-        proto.push(b'\0');
-
-        rlb.next();
-        S_POS = rlb.line_pos();
-
-        /*
-         *let mut _serv_aliases: Vec<Vec<u8>> = Vec::new();
-         *loop {
-         *    let mut alias = match iter.next() {
-         *        Some(s) => s.as_bytes().to_vec(),
-         *        _ => break
-         *    };
-         *    alias.push(b'\0');
-         *    _serv_aliases.push(alias);
-         *}
-         *let mut serv_aliases: Vec<*mut i8> = _serv_aliases.iter_mut().map(|x| x.as_mut_ptr() as *mut i8).collect();
-         *serv_aliases.push(ptr::null_mut());
-         *
-         */
-        let mut _serv_aliases: Vec<Vec<u8>> = Vec::new();
-        _serv_aliases.push(vec![b'\0']);
-        let mut serv_aliases: Vec<*mut i8> = Vec::new();
-        serv_aliases.push(ptr::null_mut());
-        serv_aliases.push(ptr::null_mut());
-
-        SERV_ALIASES = Some(_serv_aliases);
-        SERV_NAME = Some(serv_name);
-        SERV_PROTO = Some(proto);
-
-        SERV_ENTRY = servent {
-            s_name: SERV_NAME.as_mut().unwrap().as_mut_slice().as_mut_ptr() as *mut c_char,
-            s_aliases: serv_aliases.as_mut_slice().as_mut_ptr() as *mut *mut i8,
-            s_port: SERV_PORT.unwrap(),
-            s_proto: SERV_PROTO.as_mut().unwrap().as_mut_slice().as_mut_ptr() as *mut c_char,
-        };
+#[no_mangle]
+pub unsafe extern "C" fn getservbyport_r(
+    port: c_int,
+    proto: *const c_char,
+    result_buf: *mut servent,
+    buf: *mut c_char,
+    buflen: size_t,
+    result: *mut *mut servent,
+) -> c_int {
+    let service = service_by_port(port, c_str_arg(proto));
+    servent_r(service, 0, result_buf, buf, buflen, result)
+}
 
-        if SERV_STAYOPEN == 0 {
-            endservent();
-        }
-        break &mut SERV_ENTRY as *mut servent;
+#[no_mangle]
+pub unsafe extern "C" fn getservent() -> *mut servent {
+    static_servent(next_service())
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn getservent_r(
+    result_buf: *mut servent,
+    buf: *mut c_char,
+    buflen: size_t,
+    result: *mut *mut servent,
+) -> c_int {
+    let old_pos = S_POS;
+    let ret = servent_r(next_service(), ENOENT, result_buf, buf, buflen, result);
+    if ret == ERANGE {
+        S_POS = old_pos;
     }
+    ret
 }
 
 #[no_mangle]
@@ -700,23 +718,23 @@ pub unsafe extern "C" fn setnetent(stayopen: c_int) {
 #[no_mangle]
 pub unsafe extern "C" fn setprotoent(stayopen: c_int) {
     PROTO_STAYOPEN = stayopen;
-    if PROTODB == 0 {
-        PROTODB = Sys::open(&CString::new("/etc/protocols").unwrap(), O_RDONLY, 0)
+    if PROTODB < 0 {
+        PROTODB = Sys::open(db::protocols_path(), O_RDONLY, 0)
     } else {
         Sys::lseek(PROTODB, 0, SEEK_SET);
-        P_POS = 0;
     }
+    P_POS = 0;
 }
 
 #[no_mangle]
 pub unsafe extern "C" fn setservent(stayopen: c_int) {
     SERV_STAYOPEN = stayopen;
-    if SERVDB == 0 {
-        SERVDB = Sys::open(&CString::new("/etc/services").unwrap(), O_RDONLY, 0)
+    if SERVDB < 0 {
+        SERVDB = Sys::open(db::services_path(), O_RDONLY, 0)
     } else {
         Sys::lseek(SERVDB, 0, SEEK_SET);
-        S_POS = 0;
     }
+    S_POS = 0;
 }
 
 #[no_mangle]
@@ -758,25 +776,40 @@ pub unsafe extern "C" fn getaddrinfo(
 
     let mut port = 0;
     if let Some(service) = service_opt {
-        //TODO: Support other service definitions as well as AI_NUMERICSERV
-        match str::from_utf8_unchecked(service.to_bytes()).parse::<u16>() {
-            Ok(ok) => port = ok,
-            Err(_err) => (),
-        }
+        let service = service.to_bytes();
+        port = match str::from_utf8(service)
+            .ok()
+            .and_then(|s| s.parse::<u16>().ok())
+        {
+            Some(port) => port,
+            None if ai_flags & AI_NUMERICSERV != 0 => return EAI_NONAME,
+            None => {
+                let proto: Option<&[u8]> = match ai_socktype {
+                    SOCK_STREAM => Some(b"tcp"),
+                    SOCK_DGRAM => Some(b"udp"),
+                    _ => None,
+                };
+                match service_by_name(service, proto) {
+                    Some(entry) => entry.port,
+                    None => return EAI_SERVICE,
+                }
+            }
+        };
     }
 
-    //TODO: Check hosts file
     if let Some(node) = node_opt {
         //TODO: Support AI_NUMERICHOST
-        let lookuphost = match lookup_host(str::from_utf8_unchecked(node.to_bytes())) {
-            Ok(lookuphost) => lookuphost,
-            Err(e) => {
+        let host = match nss::hosts(nss::HostKey::Name(node.to_bytes())) {
+            Ok(host) => host,
+            Err(nss::Status::NotFound) => return EAI_NONAME,
+            Err(nss::Status::TryAgain(_)) => return EAI_AGAIN,
+            Err(nss::Status::Unavailable(e)) => {
                 platform::errno = e;
                 return EAI_SYSTEM;
             }
         };
 
-        for in_addr in lookuphost {
+        for in_addr in host.addrs {
             ai_family = AF_INET;
             ai_protocol = 0;
 
@@ -7,3 +7,8 @@ pub fn get_dns_server() -> String {
     file.read_to_string(&mut string).unwrap(); // TODO: error handling
     string
 }
+
+pub fn dns_cache_disabled() -> bool {
+    // Redox only stores the nameserver address, there are no resolver options
+    false
+}
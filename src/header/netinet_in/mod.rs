@@ -34,6 +34,20 @@ pub struct sockaddr_in6 {
     pub sin6_scope_id: u32,
 }
 
+#[repr(C)]
+pub struct ip_mreq {
+    pub imr_multiaddr: in_addr,
+    pub imr_interface: in_addr,
+}
+
+/// The Linux form of ip_mreq, which can name the interface by its index as well
+#[repr(C)]
+pub struct ip_mreqn {
+    pub imr_multiaddr: in_addr,
+    pub imr_address: in_addr,
+    pub imr_ifindex: c_int,
+}
+
 #[repr(C)]
 pub struct ipv6_mreq {
     pub ipv6mr_multiaddr: in6_addr,
@@ -53,6 +67,24 @@ pub const IPPROTO_IPV6: u8 = 0x29;
 pub const IPPROTO_RAW: u8 = 0xff;
 pub const IPPROTO_MAX: u8 = 0xff;
 
+// IPPROTO_IP level socket options
+pub const IP_TOS: c_int = 1;
+pub const IP_TTL: c_int = 2;
+pub const IP_MULTICAST_IF: c_int = 32;
+pub const IP_MULTICAST_TTL: c_int = 33;
+pub const IP_MULTICAST_LOOP: c_int = 34;
+pub const IP_ADD_MEMBERSHIP: c_int = 35;
+pub const IP_DROP_MEMBERSHIP: c_int = 36;
+
+// IPPROTO_IPV6 level socket options
+pub const IPV6_MULTICAST_IF: c_int = 17;
+pub const IPV6_MULTICAST_HOPS: c_int = 18;
+pub const IPV6_MULTICAST_LOOP: c_int = 19;
+pub const IPV6_JOIN_GROUP: c_int = 20;
+pub const IPV6_LEAVE_GROUP: c_int = 21;
+pub const IPV6_ADD_MEMBERSHIP: c_int = IPV6_JOIN_GROUP;
+pub const IPV6_DROP_MEMBERSHIP: c_int = IPV6_LEAVE_GROUP;
+
 pub const INADDR_ANY: u32 = 0; // Can't use in_addr_t alias because cbindgen :(
 pub const INADDR_BROADCAST: u32 = 0xFFFF_FFFF; // Can't use core::u32::MAX because cbindgen :(
 pub const INADDR_NONE: u32 = 0xFFFF_FFFF;
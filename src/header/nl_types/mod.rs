@@ -0,0 +1,203 @@
+//! nl_types implementation for Redox, following https://pubs.opengroup.org/onlinepubs/9699919799/basedefs/nl_types.h.html
+//!
+//! Catalogs are in the format of the BSDs, with every number a big endian 32-bit one: a header
+//! of a magic number, the number of sets, the size of what follows and the offsets of the
+//! messages and of the strings from its end, then the sets. A set or a message is its number,
+//! followed for a set by how many messages it has and the index of the first, and for a message
+//! by its length and the offset of its string. Sets and the messages of a set are in ascending
+//! order.
+
+use alloc::{boxed::Box, vec::Vec};
+use core::cmp::Ordering;
+
+use crate::{
+    c_str::{CStr, CString},
+    fs::File,
+    header::{
+        errno::{EBADF, EINVAL, ENOENT, ENOMSG},
+        fcntl::O_RDONLY,
+        locale::{self, LC_MESSAGES},
+        stdlib::getenv,
+    },
+    io::Read,
+    platform::{self, types::*},
+};
+
+pub type nl_catd = *mut c_void;
+pub type nl_item = c_int;
+
+pub const NL_SETD: c_int = 1;
+pub const NL_CAT_LOCALE: c_int = 1;
+
+/// Where catalogs are looked for when NLSPATH isn't set
+const DEFAULT_NLSPATH: &str =
+    "/usr/share/locale/%L/LC_MESSAGES/%N.cat:/usr/share/locale/%l/LC_MESSAGES/%N.cat";
+
+const MAGIC: u32 = 0xff88ff89;
+const HEADER: usize = 20;
+const ENTRY: usize = 12;
+
+// No repr(C) needed, C won't see the content
+struct Catalog {
+    data: Vec<u8>,
+}
+
+fn word(data: &[u8], offset: usize) -> Option<u32> {
+    let bytes = data.get(offset..offset + 4)?;
+    Some(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+/// Where entry `number` is among the `count` entries at `offset`.
+fn find(data: &[u8], offset: usize, count: usize, number: u32) -> Option<usize> {
+    let (mut low, mut high) = (0, count);
+    while low < high {
+        let middle = (low + high) / 2;
+        let entry = offset + middle * ENTRY;
+        match word(data, entry)?.cmp(&number) {
+            Ordering::Less => low = middle + 1,
+            Ordering::Greater => high = middle,
+            Ordering::Equal => return Some(entry),
+        }
+    }
+    None
+}
+
+impl Catalog {
+    fn open(path: &[u8]) -> Option<Self> {
+        let path = CString::new(path).ok()?;
+        let mut file = File::open(&path, O_RDONLY).ok()?;
+        let mut data = Vec::new();
+        file.read_to_end(&mut data).ok()?;
+        if word(&data, 0) != Some(MAGIC) {
+            unsafe { platform::errno = EINVAL };
+            return None;
+        }
+        Some(Self { data })
+    }
+
+    /// Message `message` of set `set`, if the catalog has it.
+    fn message(&self, set: c_int, message: c_int) -> Option<&CStr> {
+        if set < 1 || message < 1 {
+            return None;
+        }
+        let data = &self.data[..];
+        let sets = word(data, 4)? as usize;
+        let messages = HEADER + word(data, 12)? as usize;
+        let strings = HEADER + word(data, 16)? as usize;
+
+        let set = find(data, HEADER, sets, set as u32)?;
+        let count = word(data, set + 4)? as usize;
+        let first = messages + word(data, set + 8)? as usize * ENTRY;
+        let message = find(data, first, count, message as u32)?;
+        let string = data.get(strings + word(data, message + 8)? as usize..)?;
+        // The string has to end within the catalog
+        let end = string.iter().position(|&b| b == 0)?;
+        Some(unsafe { CStr::from_bytes_with_nul_unchecked(&string[..=end]) })
+    }
+}
+
+/// The part of `locale`, as in `language_territory.codeset@modifier`, after `start` and up to
+/// any of `ends`.
+fn part<'a>(locale: &'a [u8], start: Option<u8>, ends: &[u8]) -> &'a [u8] {
+    let rest = match start {
+        Some(start) => match locale.iter().position(|&b| b == start) {
+            Some(i) => &locale[i + 1..],
+            None => return b"",
+        },
+        None => locale,
+    };
+    &rest[..rest
+        .iter()
+        .position(|b| ends.contains(b))
+        .unwrap_or(rest.len())]
+}
+
+/// `template` of NLSPATH, with the name of the catalog and the locale or parts of it in place of
+/// its `%` escapes.
+fn expand(template: &[u8], name: &[u8], locale: &[u8]) -> Vec<u8> {
+    let mut path = Vec::new();
+    let mut bytes = template.iter();
+    while let Some(&b) = bytes.next() {
+        if b != b'%' {
+            path.push(b);
+            continue;
+        }
+        match bytes.next() {
+            Some(b'N') => path.extend_from_slice(name),
+            Some(b'L') => path.extend_from_slice(locale),
+            Some(b'l') => path.extend_from_slice(part(locale, None, b"_.@")),
+            Some(b't') => path.extend_from_slice(part(locale, Some(b'_'), b".@")),
+            Some(b'c') => path.extend_from_slice(part(locale, Some(b'.'), b"@")),
+            Some(&other) => path.push(other),
+            None => path.push(b'%'),
+        }
+    }
+    path
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn catopen(name: *const c_char, oflag: c_int) -> nl_catd {
+    let name = CStr::from_ptr(name).to_bytes();
+    platform::errno = ENOENT;
+    let catalog = if name.is_empty() {
+        None
+    } else if name.contains(&b'/') {
+        Catalog::open(name)
+    } else {
+        let locale: &[u8] = if oflag == NL_CAT_LOCALE {
+            locale::category(LC_MESSAGES).name.to_bytes()
+        } else {
+            let lang = getenv(c_str!("LANG").as_ptr());
+            if lang.is_null() || *lang == 0 {
+                &b"C"[..]
+            } else {
+                CStr::from_ptr(lang).to_bytes()
+            }
+        };
+        let nlspath = getenv(c_str!("NLSPATH").as_ptr());
+        let nlspath = if nlspath.is_null() {
+            DEFAULT_NLSPATH.as_bytes()
+        } else {
+            CStr::from_ptr(nlspath).to_bytes()
+        };
+        nlspath
+            .split(|&b| b == b':')
+            .filter(|template| !template.is_empty())
+            .find_map(|template| Catalog::open(&expand(template, name, locale)))
+    };
+
+    match catalog {
+        Some(catalog) => Box::into_raw(Box::new(catalog)) as nl_catd,
+        None => -1isize as nl_catd,
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn catgets(
+    catd: nl_catd,
+    set_id: c_int,
+    msg_id: c_int,
+    s: *const c_char,
+) -> *mut c_char {
+    if catd.is_null() || catd == -1isize as nl_catd {
+        platform::errno = EBADF;
+        return s as *mut c_char;
+    }
+    match (*(catd as *const Catalog)).message(set_id, msg_id) {
+        Some(message) => message.as_ptr() as *mut c_char,
+        None => {
+            platform::errno = ENOMSG;
+            s as *mut c_char
+        }
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn catclose(catd: nl_catd) -> c_int {
+    if catd.is_null() || catd == -1isize as nl_catd {
+        platform::errno = EBADF;
+        return -1;
+    }
+    drop(Box::from_raw(catd as *mut Catalog));
+    0
+}
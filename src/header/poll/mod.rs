@@ -1,14 +1,23 @@
 //! poll implementation for Redox, following http://pubs.opengroup.org/onlinepubs/7908799/xsh/poll.h.html
 
-use core::{mem, slice};
+use alloc::{collections::BTreeMap, vec::Vec};
+use core::{ptr, slice};
 
+#[cfg(target_os = "redox")]
+use crate::sync::Mutex;
 use crate::{
     fs::File,
-    header::sys_epoll::{
-        epoll_create1, epoll_ctl, epoll_data, epoll_event, epoll_wait, EPOLLERR, EPOLLHUP, EPOLLIN,
-        EPOLLNVAL, EPOLLOUT, EPOLLPRI, EPOLL_CLOEXEC, EPOLL_CTL_ADD,
+    header::{
+        errno::{EBADF, EINVAL, EPERM},
+        signal::sigset_t,
+        sys_epoll::{
+            epoll_create1, epoll_ctl, epoll_data, epoll_event, EPOLLERR, EPOLLHUP, EPOLLIN,
+            EPOLLNVAL, EPOLLOUT, EPOLLPRI, EPOLL_CLOEXEC, EPOLL_CTL_ADD, EPOLL_CTL_DEL,
+            EPOLL_CTL_MOD,
+        },
+        time::timespec,
     },
-    platform::types::*,
+    platform::{self, types::*, PalEpoll, Sys},
 };
 
 pub const POLLIN: c_short = 0x001;
@@ -27,80 +36,338 @@ pub struct pollfd {
     pub revents: c_short,
 }
 
-pub fn poll_epoll(fds: &mut [pollfd], timeout: c_int) -> c_int {
-    let event_map = [
-        (POLLIN, EPOLLIN),
-        (POLLPRI, EPOLLPRI),
-        (POLLOUT, EPOLLOUT),
-        (POLLERR, EPOLLERR),
-        (POLLHUP, EPOLLHUP),
-        (POLLNVAL, EPOLLNVAL),
-    ];
-
-    let ep = {
+/// Converts the timeout of ppoll and pselect to milliseconds, rounding up so that the wait is
+/// never shorter than requested. A null timeout waits indefinitely.
+pub(crate) unsafe fn timespec_to_ms(timeout: *const timespec) -> Option<c_int> {
+    if timeout.is_null() {
+        return Some(-1);
+    }
+    let timeout = &*timeout;
+    if timeout.tv_sec < 0 || timeout.tv_nsec < 0 || timeout.tv_nsec >= 1_000_000_000 {
+        return None;
+    }
+    let ms = (timeout.tv_sec as i64)
+        .saturating_mul(1000)
+        .saturating_add((timeout.tv_nsec as i64 + 999_999) / 1_000_000);
+    Some(ms.min(c_int::MAX as i64) as c_int)
+}
+
+const EVENT_MAP: [(c_short, c_uint); 6] = [
+    (POLLIN, EPOLLIN),
+    (POLLPRI, EPOLLPRI),
+    (POLLOUT, EPOLLOUT),
+    (POLLERR, EPOLLERR),
+    (POLLHUP, EPOLLHUP),
+    (POLLNVAL, EPOLLNVAL),
+];
+
+fn poll_to_epoll(events: c_short) -> c_uint {
+    EVENT_MAP
+        .iter()
+        .filter(|(p, _)| events & p != 0)
+        .fold(0, |acc, (_, ep)| acc | ep)
+}
+
+fn epoll_to_poll(events: c_uint) -> c_short {
+    EVENT_MAP
+        .iter()
+        .filter(|(_, ep)| events & ep != 0)
+        .fold(0, |acc, (p, _)| acc | p)
+}
+
+/// An epoll instance registered for the descriptors of a pollfd array.
+///
+/// On Redox, it's kept between calls for the same descriptors, so that a call only registers what
+/// changed since the previous one instead of every descriptor. Elsewhere a closed descriptor may
+/// stay registered, so a new instance is used for every call.
+pub(crate) struct Poller {
+    ep: File,
+    /// The events each descriptor is registered for
+    registered: BTreeMap<c_int, c_uint>,
+    events: Vec<epoll_event>,
+    /// Whether it was kept from a previous call, since when the queue may have reported
+    /// descriptors that were drained before this one
+    kept: bool,
+}
+
+/// The kept instances, keyed by the descriptors of their calls, in order and without the
+/// negative ones, rather than by the array, which select allocates for every call.
+#[cfg(target_os = "redox")]
+pub(crate) static POLLERS: Mutex<BTreeMap<Vec<c_int>, Poller>> = Mutex::new(BTreeMap::new());
+#[cfg(target_os = "redox")]
+const MAX_POLLERS: usize = 16;
+
+impl Poller {
+    fn new() -> Option<Self> {
         let epfd = epoll_create1(EPOLL_CLOEXEC);
         if epfd < 0 {
-            return -1;
+            return None;
         }
-        File::new(epfd)
-    };
+        Some(Self {
+            ep: File::new(epfd),
+            registered: BTreeMap::new(),
+            events: Vec::new(),
+            kept: false,
+        })
+    }
 
-    for i in 0..fds.len() {
-        let mut pfd = &mut fds[i];
+    /// Takes the instance kept for `key`, for the duration of a call. If another thread is
+    /// polling the same descriptors, this gets a new one.
+    fn take(key: &[c_int]) -> Option<Self> {
+        #[cfg(target_os = "redox")]
+        {
+            if let Some(mut poller) = POLLERS.lock().remove(key) {
+                poller.kept = true;
+                return Some(poller);
+            }
+        }
+        let _ = key;
+        Self::new()
+    }
 
-        let mut event = epoll_event {
-            events: 0,
-            data: epoll_data { u64: i as u64 },
-            ..Default::default()
-        };
+    fn put(self, key: Vec<c_int>) {
+        #[cfg(target_os = "redox")]
+        {
+            let mut pollers = POLLERS.lock();
+            let evicted = if pollers.contains_key(&key) {
+                Some(self)
+            } else {
+                let evicted = if pollers.len() >= MAX_POLLERS {
+                    let first = pollers.keys().next().unwrap().clone();
+                    pollers.remove(&first)
+                } else {
+                    None
+                };
+                pollers.insert(key, self);
+                evicted
+            };
+            // Closing goes through forget, which needs the lock
+            drop(pollers);
+            drop(evicted);
+        }
+        #[cfg(not(target_os = "redox"))]
+        {
+            let _ = key;
+        }
+    }
 
-        for (p, ep) in event_map.iter() {
-            if pfd.events & p > 0 {
-                event.events |= ep;
+    /// Brings the registrations in line with `fds`. Returns what is known without waiting: the
+    /// invalid descriptors, and those epoll doesn't support, which are always ready.
+    fn update(&mut self, fds: &[pollfd]) -> Result<BTreeMap<c_int, c_short>, ()> {
+        let mut wanted: BTreeMap<c_int, c_uint> = BTreeMap::new();
+        for pfd in fds {
+            // Negative descriptors are ignored
+            if pfd.fd >= 0 {
+                *wanted.entry(pfd.fd).or_default() |= poll_to_epoll(pfd.events);
             }
         }
 
-        pfd.revents = 0;
+        let stale: Vec<c_int> = self
+            .registered
+            .keys()
+            .filter(|fd| !wanted.contains_key(fd))
+            .copied()
+            .collect();
+        for fd in stale {
+            self.registered.remove(&fd);
+            // The descriptor may have been closed since, which unregistered it
+            epoll_ctl(*self.ep, EPOLL_CTL_DEL, fd, ptr::null_mut());
+        }
 
-        if epoll_ctl(*ep, EPOLL_CTL_ADD, pfd.fd, &mut event) < 0 {
-            return -1;
+        let mut immediate = BTreeMap::new();
+        for (&fd, &events) in &wanted {
+            let op = match self.registered.get(&fd) {
+                Some(&registered) if registered == events => continue,
+                Some(_) => EPOLL_CTL_MOD,
+                None => EPOLL_CTL_ADD,
+            };
+            let mut event = epoll_event {
+                events,
+                data: epoll_data { u64: fd as u64 },
+                ..Default::default()
+            };
+            if epoll_ctl(*self.ep, op, fd, &mut event) == 0 {
+                self.registered.insert(fd, events);
+                continue;
+            }
+
+            self.registered.remove(&fd);
+            match unsafe { platform::errno } {
+                EBADF => {
+                    immediate.insert(fd, POLLNVAL);
+                }
+                // Regular files never block
+                EPERM => {
+                    immediate.insert(fd, epoll_to_poll(events & (EPOLLIN | EPOLLOUT)));
+                }
+                _ => return Err(()),
+            }
         }
+        Ok(immediate)
     }
 
-    let mut events: [epoll_event; 32] = unsafe { mem::zeroed() };
-    let res = epoll_wait(*ep, events.as_mut_ptr(), events.len() as c_int, timeout);
-    if res < 0 {
-        return -1;
+    /// Reads what the queue reported between the previous call and this one, about descriptors
+    /// that may have been drained meanwhile. Being level-triggered, the ones read are registered
+    /// again at the next wait, which only reports them if they are still ready.
+    fn discard_stale(&mut self) -> Result<(), ()> {
+        if !self.kept || self.registered.is_empty() {
+            return Ok(());
+        }
+        // If reading and writing are reported separately, and with the timer of the wait
+        self.events
+            .resize(2 * self.registered.len() + 1, epoll_event::default());
+        let res = Sys::epoll_pwait(
+            *self.ep,
+            self.events.as_mut_ptr(),
+            self.events.len() as c_int,
+            0,
+            ptr::null(),
+        );
+        if res < 0 {
+            return Err(());
+        }
+        Ok(())
     }
 
-    for event in events.iter().take(res as usize) {
-        let pi = unsafe { event.data.u64 as usize };
-        // TODO: Error status when fd does not match?
-        if let Some(pfd) = fds.get_mut(pi) {
-            for (p, ep) in event_map.iter() {
-                if event.events & ep > 0 {
-                    pfd.revents |= p;
+    fn poll(&mut self, fds: &mut [pollfd], timeout: c_int, sigmask: *const sigset_t) -> c_int {
+        for pfd in fds.iter_mut() {
+            pfd.revents = 0;
+        }
+        if self.discard_stale().is_err() {
+            return -1;
+        }
+        let mut ready = match self.update(fds) {
+            Ok(immediate) => immediate,
+            Err(()) => return -1,
+        };
+        // Sockets with data MSG_PEEK read are readable, whatever their scheme says
+        #[cfg(target_os = "redox")]
+        for pfd in fds.iter() {
+            if pfd.events & POLLIN != 0 && platform::sys::socket::has_peeked(pfd.fd) {
+                *ready.entry(pfd.fd).or_default() |= POLLIN;
+            }
+        }
+
+        // Only check the others if some are known to be ready
+        let timeout = if ready.is_empty() { timeout } else { 0 };
+        self.events
+            .resize(self.registered.len().max(1), epoll_event::default());
+        // Not the C function, whose cancellation would lose the instance taken for the call
+        let res = Sys::epoll_pwait(
+            *self.ep,
+            self.events.as_mut_ptr(),
+            self.events.len() as c_int,
+            timeout,
+            sigmask,
+        );
+        if res < 0 {
+            return -1;
+        }
+
+        for event in self.events.iter().take(res as usize) {
+            let fd = unsafe { event.data.u64 } as c_int;
+            *ready.entry(fd).or_default() |= epoll_to_poll(event.events);
+        }
+
+        let mut count = 0;
+        for pfd in fds.iter_mut() {
+            if let Some(&revents) = ready.get(&pfd.fd) {
+                // Errors and hangups are reported even if not asked for
+                pfd.revents = revents & (pfd.events | POLLERR | POLLHUP | POLLNVAL);
+                if pfd.revents != 0 {
+                    count += 1;
                 }
             }
         }
+        count
     }
+}
 
-    let mut count = 0;
-    for pfd in fds.iter() {
-        if pfd.revents > 0 {
-            count += 1;
+/// Called when `fd` is closed or replaced, so that a kept instance registers the descriptor
+/// again next time, or is dropped if `fd` was its own.
+#[cfg(target_os = "redox")]
+pub(crate) fn forget(fd: c_int) {
+    let mut pollers = POLLERS.lock();
+    if pollers.is_empty() {
+        return;
+    }
+    let mut own = None;
+    for (key, poller) in pollers.iter_mut() {
+        if *poller.ep == fd {
+            own = Some(key.clone());
+        } else {
+            poller.registered.remove(&fd);
+        }
+    }
+    if let Some(key) = own {
+        if let Some(mut poller) = pollers.remove(&key) {
+            // Already closed, and the number may be in use again
+            poller.ep.reference = true;
         }
     }
-    count
+}
+
+/// Called in the child after fork, which would otherwise share the kept instances with the
+/// parent.
+#[cfg(target_os = "redox")]
+pub(crate) fn forget_all() {
+    let pollers = core::mem::take(&mut *POLLERS.lock());
+    drop(pollers);
+}
+
+/// Waits through epoll, which installs `sigmask` (if not null) for the duration of the wait.
+pub fn poll_epoll(fds: &mut [pollfd], timeout: c_int, sigmask: *const sigset_t) -> c_int {
+    let mut key: Vec<c_int> = fds.iter().map(|pfd| pfd.fd).filter(|&fd| fd >= 0).collect();
+    key.sort_unstable();
+    key.dedup();
+    let mut poller = match Poller::take(&key) {
+        Some(poller) => poller,
+        None => return -1,
+    };
+    let res = poller.poll(fds, timeout, sigmask);
+    poller.put(key);
+    res
 }
 
 #[no_mangle]
 pub unsafe extern "C" fn poll(fds: *mut pollfd, nfds: nfds_t, timeout: c_int) -> c_int {
     trace_expr!(
-        poll_epoll(slice::from_raw_parts_mut(fds, nfds as usize), timeout),
+        platform::cancellation_point(|| poll_epoll(
+            slice::from_raw_parts_mut(fds, nfds as usize),
+            timeout,
+            ptr::null()
+        )),
         "poll({:p}, {}, {})",
         fds,
         nfds,
         timeout
     )
 }
+
+#[no_mangle]
+pub unsafe extern "C" fn ppoll(
+    fds: *mut pollfd,
+    nfds: nfds_t,
+    tmo_p: *const timespec,
+    sigmask: *const sigset_t,
+) -> c_int {
+    let timeout = match timespec_to_ms(tmo_p) {
+        Some(timeout) => timeout,
+        None => {
+            platform::errno = EINVAL;
+            return -1;
+        }
+    };
+    trace_expr!(
+        platform::cancellation_point(|| poll_epoll(
+            slice::from_raw_parts_mut(fds, nfds as usize),
+            timeout,
+            sigmask
+        )),
+        "ppoll({:p}, {}, {:p}, {:p})",
+        fds,
+        nfds,
+        tmo_p,
+        sigmask
+    )
+}
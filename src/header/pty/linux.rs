@@ -0,0 +1,40 @@
+use alloc::format;
+
+use crate::{
+    header::{errno, sys_ioctl},
+    platform::{self, types::*, Pal, Sys},
+};
+
+pub unsafe fn posix_openpt(flags: c_int) -> c_int {
+    Sys::open(c_str!("/dev/ptmx"), flags, 0)
+}
+
+pub unsafe fn unlockpt(fildes: c_int) -> c_int {
+    let mut lock: c_int = 0;
+    sys_ioctl::ioctl(
+        fildes,
+        sys_ioctl::TIOCSPTLCK,
+        &mut lock as *mut c_int as *mut c_void,
+    )
+}
+
+/// Writes the path of the slave of `fildes` to `buf`, returning the error number if it can't.
+pub unsafe fn ptsname(fildes: c_int, buf: &mut [u8]) -> c_int {
+    let mut number: c_uint = 0;
+    if sys_ioctl::ioctl(
+        fildes,
+        sys_ioctl::TIOCGPTN,
+        &mut number as *mut c_uint as *mut c_void,
+    ) < 0
+    {
+        return platform::errno;
+    }
+
+    let path = format!("/dev/pts/{}", number);
+    if path.len() >= buf.len() {
+        return errno::ERANGE;
+    }
+    buf[..path.len()].copy_from_slice(path.as_bytes());
+    buf[path.len()] = 0;
+    0
+}
@@ -0,0 +1,106 @@
+//! pty.h implementation, following the BSD functions of the same name.
+//!
+//! On Linux, masters are opened from /dev/ptmx and their slaves are in /dev/pts. On Redox, they
+//! are opened from the pty scheme, and a master's path is the one its slave is opened with.
+
+use crate::{
+    header::{
+        fcntl, limits,
+        stdlib::{grantpt, posix_openpt, ptsname_r, unlockpt},
+        string::strcpy,
+        sys_ioctl::{self, winsize},
+        termios::{self, termios},
+        unistd::{self, close},
+        utmp::login_tty,
+    },
+    platform::types::*,
+};
+
+#[cfg(target_os = "linux")]
+#[path = "linux.rs"]
+pub(crate) mod sys;
+
+#[cfg(target_os = "redox")]
+#[path = "redox.rs"]
+pub(crate) mod sys;
+
+/// Opens a master and its slave, setting the slave's attributes to `termp` and its window size
+/// to `winp` unless they're NULL. The slave's path is written to `name` unless it's NULL, which
+/// must be big enough for it.
+#[no_mangle]
+pub unsafe extern "C" fn openpty(
+    amaster: *mut c_int,
+    aslave: *mut c_int,
+    name: *mut c_char,
+    termp: *const termios,
+    winp: *const winsize,
+) -> c_int {
+    let master = posix_openpt(fcntl::O_RDWR | fcntl::O_NOCTTY);
+    if master < 0 {
+        return -1;
+    }
+
+    let mut path = [0; limits::PATH_MAX];
+    if grantpt(master) < 0
+        || unlockpt(master) < 0
+        || ptsname_r(master, path.as_mut_ptr(), path.len()) != 0
+    {
+        close(master);
+        return -1;
+    }
+
+    let slave = fcntl::sys_open(path.as_ptr(), fcntl::O_RDWR | fcntl::O_NOCTTY, 0);
+    if slave < 0 {
+        close(master);
+        return -1;
+    }
+    if (!termp.is_null()
+        && termios::tcsetattr(slave, termios::TCSANOW as c_int, termp as *mut termios) < 0)
+        || (!winp.is_null()
+            && sys_ioctl::ioctl(slave, sys_ioctl::TIOCSWINSZ, winp as *mut c_void) < 0)
+    {
+        close(slave);
+        close(master);
+        return -1;
+    }
+
+    if !name.is_null() {
+        strcpy(name, path.as_ptr());
+    }
+    *amaster = master;
+    *aslave = slave;
+    0
+}
+
+/// Forks with the child's standard streams and controlling terminal being the slave of a new
+/// master, which the parent gets in `amaster`. The rest is as with openpty.
+#[no_mangle]
+pub unsafe extern "C" fn forkpty(
+    amaster: *mut c_int,
+    name: *mut c_char,
+    termp: *const termios,
+    winp: *const winsize,
+) -> pid_t {
+    let mut master = 0;
+    let mut slave = 0;
+    if openpty(&mut master, &mut slave, name, termp, winp) < 0 {
+        return -1;
+    }
+
+    let pid = unistd::fork();
+    if pid == 0 {
+        close(master);
+        if login_tty(slave) < 0 {
+            unistd::_exit(1);
+        }
+        return 0;
+    }
+
+    close(slave);
+    if pid < 0 {
+        close(master);
+    } else {
+        *amaster = master;
+    }
+    pid
+}
@@ -0,0 +1,43 @@
+use crate::{
+    header::{errno, fcntl},
+    platform::{self, types::*, Pal, Sys},
+};
+
+pub unsafe fn posix_openpt(flags: c_int) -> c_int {
+    Sys::open(c_str!("pty:"), flags | fcntl::O_CREAT, 0)
+}
+
+pub unsafe fn unlockpt(fildes: c_int) -> c_int {
+    // The pty scheme doesn't lock slaves, so this only checks it's a master
+    let mut buf = [0; 64];
+    match ptsname(fildes, &mut buf) {
+        0 => 0,
+        err => {
+            platform::errno = err;
+            -1
+        }
+    }
+}
+
+/// Writes the path of the slave of `fildes` to `buf`, returning the error number if it can't.
+///
+/// The path of a master is the one its slave is opened with.
+pub unsafe fn ptsname(fildes: c_int, buf: &mut [u8]) -> c_int {
+    if buf.is_empty() {
+        return errno::ERANGE;
+    }
+    let len = buf.len();
+    let count = Sys::fpath(fildes, &mut buf[..len - 1]);
+    if count < 0 {
+        return platform::errno;
+    }
+    let count = count as usize;
+    if count == len - 1 {
+        return errno::ERANGE;
+    }
+    if !buf.starts_with(b"pty:") {
+        return errno::ENOTTY;
+    }
+    buf[count] = 0;
+    0
+}
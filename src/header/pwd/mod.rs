@@ -1,29 +1,17 @@
 //! pwd implementation for relibc
 
-use alloc::{boxed::Box, vec::Vec};
-use core::{
-    ops::{Deref, DerefMut},
-    pin::Pin,
-    ptr,
-};
+use alloc::vec::Vec;
+use core::ptr;
 
 use crate::{
+    c_str::CStr,
     fs::File,
-    header::{errno, fcntl, string::strcmp},
+    header::{errno, fcntl, stdio::FILE},
     io::{prelude::*, BufReader, SeekFrom},
+    nss::{self, files, PasswdKey},
     platform::{self, types::*},
 };
 
-#[cfg(target_os = "linux")]
-mod linux;
-#[cfg(target_os = "redox")]
-mod redox;
-
-#[cfg(target_os = "linux")]
-use self::linux as sys;
-#[cfg(target_os = "redox")]
-use self::redox as sys;
-
 #[repr(C)]
 #[derive(Debug)]
 pub struct passwd {
@@ -36,7 +24,8 @@ pub struct passwd {
     pw_shell: *mut c_char,
 }
 
-static mut PASSWD_BUF: Option<MaybeAllocated> = None;
+/// Holds the strings PASSWD points to
+static mut PASSWD_BUF: Vec<u8> = Vec::new();
 static mut PASSWD: passwd = passwd {
     pw_name: ptr::null_mut(),
     pw_passwd: ptr::null_mut(),
@@ -47,165 +36,99 @@ static mut PASSWD: passwd = passwd {
     pw_shell: ptr::null_mut(),
 };
 
-#[derive(Clone, Copy, Debug)]
-struct DestBuffer {
-    ptr: *mut u8,
-    len: usize,
-}
-
-#[derive(Debug)]
-enum MaybeAllocated {
-    Owned(Pin<Box<[u8]>>),
-    Borrowed(DestBuffer),
-}
-impl Deref for MaybeAllocated {
-    type Target = [u8];
-
-    fn deref(&self) -> &Self::Target {
-        match self {
-            MaybeAllocated::Owned(boxed) => boxed,
-            MaybeAllocated::Borrowed(dst) => unsafe {
-                core::slice::from_raw_parts(dst.ptr, dst.len)
-            },
-        }
-    }
-}
-impl DerefMut for MaybeAllocated {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        match self {
-            MaybeAllocated::Owned(boxed) => boxed,
-            MaybeAllocated::Borrowed(dst) => unsafe {
-                core::slice::from_raw_parts_mut(dst.ptr, dst.len)
-            },
-        }
-    }
-}
-
-#[derive(Debug)]
-struct OwnedPwd {
-    buffer: MaybeAllocated,
-    reference: passwd,
-}
-
-impl OwnedPwd {
-    fn into_global(self) -> *mut passwd {
-        unsafe {
-            PASSWD_BUF = Some(self.buffer);
-            PASSWD = self.reference;
-            &mut PASSWD
-        }
-    }
-}
-
-#[derive(Clone, Copy, Debug)]
-enum Cause {
-    Eof,
-    Other,
-}
-
 static mut READER: Option<BufReader<File>> = None;
 
-fn parsed<I, O>(buf: Option<I>) -> Option<O>
-where
-    I: core::borrow::Borrow<[u8]>,
-    O: core::str::FromStr,
-{
-    let buf = buf?;
-    let string = core::str::from_utf8(buf.borrow()).ok()?;
-    string.parse().ok()
-}
-
-fn getpwent_r(
-    reader: &mut BufReader<File>,
-    destination: Option<DestBuffer>,
-) -> Result<OwnedPwd, Cause> {
-    let mut buf = Vec::new();
-    if reader
-        .read_until(b'\n', &mut buf)
-        .map_err(|_| Cause::Other)?
-        == 0
-    {
-        return Err(Cause::Eof);
-    }
-
-    // Replace all occurences of ':' with terminating NUL byte
-    let mut start = 0;
-    while let Some(i) = memchr::memchr(b':', &buf[start..]) {
-        buf[start + i] = 0;
-        start += i + 1;
+/// Lays out the strings of `entry` in `buf` and fills `out` to point at them, as the _r
+/// functions require.
+///
+/// Returns the number of bytes needed if `buf` is too small.
+unsafe fn pack(
+    entry: &nss::Passwd,
+    out: *mut passwd,
+    buf: *mut c_char,
+    buflen: size_t,
+) -> Result<(), usize> {
+    let fields = [
+        &entry.name,
+        &entry.passwd,
+        &entry.gecos,
+        &entry.dir,
+        &entry.shell,
+    ];
+    let needed = fields.iter().map(|field| field.len() + 1).sum();
+    if buf.is_null() || buflen < needed {
+        return Err(needed);
     }
 
-    // Place terminating NUL byte at the end, replace newline
-    let last = buf.last_mut();
-    if last == Some(&mut b'\n') {
-        *last.unwrap() = 0;
-    } else {
-        buf.push(0);
+    let mut strings = [ptr::null_mut(); 5];
+    let mut cursor = buf;
+    for (field, string) in fields.iter().zip(strings.iter_mut()) {
+        ptr::copy_nonoverlapping(field.as_ptr() as *const c_char, cursor, field.len());
+        *cursor.add(field.len()) = 0;
+        *string = cursor;
+        cursor = cursor.add(field.len() + 1);
     }
 
-    let mut buf = match destination {
-        None => MaybeAllocated::Owned(Box::into_pin(buf.into_boxed_slice())),
-        Some(dst) => {
-            let mut new = MaybeAllocated::Borrowed(dst);
-            if new.len() < buf.len() {
-                unsafe {
-                    platform::errno = errno::ERANGE;
-                }
-                return Err(Cause::Other);
-            }
-            new[..buf.len()].copy_from_slice(&buf);
-            new
-        }
+    *out = passwd {
+        pw_name: strings[0],
+        pw_passwd: strings[1],
+        pw_uid: entry.uid,
+        pw_gid: entry.gid,
+        pw_gecos: strings[2],
+        pw_dir: strings[3],
+        pw_shell: strings[4],
     };
-
-    // Chop up the result into a valid structure
-    let passwd = sys::split(&mut buf).ok_or(Cause::Other)?;
-
-    Ok(OwnedPwd {
-        buffer: buf,
-        reference: passwd,
-    })
+    Ok(())
 }
 
-fn pwd_lookup<F>(mut matches: F, destination: Option<DestBuffer>) -> Result<OwnedPwd, Cause>
-where
-    F: FnMut(&passwd) -> bool,
-{
-    let file = match File::open(c_str!("/etc/passwd"), fcntl::O_RDONLY) {
-        Ok(file) => file,
-        Err(_) => return Err(Cause::Other),
-    };
-
-    let mut reader = BufReader::new(file);
-
+/// Stores `entry` in PASSWD, for the functions returning static storage.
+unsafe fn static_passwd(entry: &nss::Passwd) -> *mut passwd {
     loop {
-        let entry = getpwent_r(&mut reader, destination)?;
+        let buf = PASSWD_BUF.as_mut_ptr() as *mut c_char;
+        match pack(entry, &mut PASSWD, buf, PASSWD_BUF.len()) {
+            Ok(()) => return &mut PASSWD,
+            Err(needed) => PASSWD_BUF.resize(needed, 0),
+        }
+    }
+}
 
-        if matches(&entry.reference) {
-            return Ok(entry);
+/// Returns a lookup's entry in static storage, setting errno if the lookup failed rather than
+/// finding nothing.
+unsafe fn static_lookup(res: Result<nss::Passwd, nss::Status>) -> *mut passwd {
+    match res {
+        Ok(entry) => static_passwd(&entry),
+        Err(status) => {
+            if status.errno() != 0 {
+                platform::errno = status.errno();
+            }
+            ptr::null_mut()
         }
     }
 }
 
-unsafe fn mux(
-    status: Result<OwnedPwd, Cause>,
+/// Packs a lookup's entry into the caller's buffer for the _r functions, which return 0 with a
+/// NULL result when there's no entry.
+unsafe fn lookup_r(
+    res: Result<nss::Passwd, nss::Status>,
     out: *mut passwd,
+    buf: *mut c_char,
+    size: size_t,
     result: *mut *mut passwd,
 ) -> c_int {
-    match status {
-        Ok(owned) => {
-            *out = owned.reference;
-            *result = out;
-            0
-        }
-        Err(Cause::Eof) => {
-            *result = ptr::null_mut();
-            0
-        }
-        Err(Cause::Other) => {
-            *result = ptr::null_mut();
-            -1
-        }
+    *result = ptr::null_mut();
+    match res {
+        Ok(entry) => match pack(&entry, out, buf, size) {
+            Ok(()) => {
+                *result = out;
+                0
+            }
+            Err(_) => {
+                // like glibc, for callers that check errno instead
+                platform::errno = errno::ERANGE;
+                errno::ERANGE
+            }
+        },
+        Err(status) => status.errno(),
     }
 }
 
@@ -217,17 +140,8 @@ pub unsafe extern "C" fn getpwnam_r(
     size: size_t,
     result: *mut *mut passwd,
 ) -> c_int {
-    mux(
-        pwd_lookup(
-            |parts| strcmp(parts.pw_name, name) == 0,
-            Some(DestBuffer {
-                ptr: buf as *mut u8,
-                len: size,
-            }),
-        ),
-        out,
-        result,
-    )
+    let res = nss::passwd(PasswdKey::Name(CStr::from_ptr(name).to_bytes()));
+    lookup_r(res, out, buf, size, result)
 }
 
 #[no_mangle]
@@ -238,65 +152,91 @@ pub unsafe extern "C" fn getpwuid_r(
     size: size_t,
     result: *mut *mut passwd,
 ) -> c_int {
-    let slice = core::slice::from_raw_parts_mut(buf as *mut u8, size);
-    mux(
-        pwd_lookup(
-            |part| part.pw_uid == uid,
-            Some(DestBuffer {
-                ptr: buf as *mut u8,
-                len: size,
-            }),
-        ),
-        out,
-        result,
-    )
+    lookup_r(nss::passwd(PasswdKey::Uid(uid)), out, buf, size, result)
 }
 
 #[no_mangle]
-pub extern "C" fn getpwnam(name: *const c_char) -> *mut passwd {
-    pwd_lookup(|parts| unsafe { strcmp(parts.pw_name, name) } == 0, None)
-        .map(|res| res.into_global())
-        .unwrap_or(ptr::null_mut())
+pub unsafe extern "C" fn getpwnam(name: *const c_char) -> *mut passwd {
+    static_lookup(nss::passwd(PasswdKey::Name(
+        CStr::from_ptr(name).to_bytes(),
+    )))
 }
 
 #[no_mangle]
-pub extern "C" fn getpwuid(uid: uid_t) -> *mut passwd {
-    pwd_lookup(|parts| parts.pw_uid == uid, None)
-        .map(|res| res.into_global())
-        .unwrap_or(ptr::null_mut())
+pub unsafe extern "C" fn getpwuid(uid: uid_t) -> *mut passwd {
+    static_lookup(nss::passwd(PasswdKey::Uid(uid)))
+}
+
+/// Opens the file getpwent goes through, the first time it's needed.
+unsafe fn reader() -> Option<&'static mut BufReader<File>> {
+    if READER.is_none() {
+        let file = File::open(c_str!("/etc/passwd"), fcntl::O_RDONLY).ok()?;
+        READER = Some(BufReader::new(file));
+    }
+    READER.as_mut()
 }
 
 #[no_mangle]
-pub extern "C" fn getpwent() -> *mut passwd {
-    let reader = match unsafe { &mut READER } {
-        Some(reader) => reader,
-        None => {
-            let file = match File::open(c_str!("/etc/passwd"), fcntl::O_RDONLY) {
-                Ok(file) => file,
-                Err(_) => return ptr::null_mut(),
-            };
-            let reader = BufReader::new(file);
-            unsafe {
-                READER = Some(reader);
-                READER.as_mut().unwrap()
-            }
-        }
-    };
-    getpwent_r(reader, None)
-        .map(|res| res.into_global())
-        .unwrap_or(ptr::null_mut())
+pub unsafe extern "C" fn endpwent() {
+    READER = None;
 }
 
+/// Reads the next entry from `stream`, which has the format of /etc/passwd.
 #[no_mangle]
-pub extern "C" fn setpwent() {
-    if let Some(reader) = unsafe { &mut READER } {
-        let _ = reader.seek(SeekFrom::Start(0));
+pub unsafe extern "C" fn fgetpwent(stream: *mut FILE) -> *mut passwd {
+    let mut stream = (*stream).lock();
+    match files::next(&mut *stream, files::parse_passwd) {
+        Some(entry) => static_passwd(&entry),
+        None => ptr::null_mut(),
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn getpwent() -> *mut passwd {
+    match reader().and_then(|reader| files::next(reader, files::parse_passwd)) {
+        Some(entry) => static_passwd(&entry),
+        None => ptr::null_mut(),
+    }
+}
+
+/// The reentrant getpwent, which returns ENOENT after the last entry. If `buf` is too small, it
+/// returns ERANGE and the same entry is read again by the next call.
+#[no_mangle]
+pub unsafe extern "C" fn getpwent_r(
+    out: *mut passwd,
+    buf: *mut c_char,
+    size: size_t,
+    result: *mut *mut passwd,
+) -> c_int {
+    *result = ptr::null_mut();
+    let reader = match reader() {
+        Some(reader) => reader,
+        None => return platform::errno,
+    };
+    let pos = match reader.seek(SeekFrom::Current(0)) {
+        Ok(pos) => pos,
+        Err(_) => return platform::errno,
+    };
+    let entry = match files::next(reader, files::parse_passwd) {
+        Some(entry) => entry,
+        None => return errno::ENOENT,
+    };
+    match pack(&entry, out, buf, size) {
+        Ok(()) => {
+            *result = out;
+            0
+        }
+        Err(_) => {
+            let _ = reader.seek(SeekFrom::Start(pos));
+            platform::errno = errno::ERANGE;
+            errno::ERANGE
+        }
     }
 }
 
 #[no_mangle]
-pub extern "C" fn endpwent() {
-    unsafe {
-        READER = None;
+pub unsafe extern "C" fn setpwent() {
+    if let Some(reader) = &mut READER {
+        let _ = reader.seek(SeekFrom::Start(0));
     }
 }
@@ -0,0 +1,652 @@
+//! Parses basic and extended regular expressions and compiles them to a program for exec.
+
+use alloc::{boxed::Box, vec::Vec};
+
+use super::*;
+
+/// The most repetitions an interval can ask for, as in limits.h
+pub const RE_DUP_MAX: u32 = 255;
+
+/// The most instructions a program can have before regcomp gives up with REG_ESPACE
+const MAX_PROGRAM: usize = 1 << 20;
+
+/// A set of bytes
+#[derive(Clone)]
+pub struct Set([u32; 8]);
+
+impl Set {
+    fn new() -> Self {
+        Set([0; 8])
+    }
+
+    fn insert(&mut self, b: u8) {
+        self.0[(b / 32) as usize] |= 1 << (b % 32);
+    }
+
+    pub fn contains(&self, b: u8) -> bool {
+        self.0[(b / 32) as usize] & 1 << (b % 32) != 0
+    }
+
+    fn invert(&mut self) {
+        for word in self.0.iter_mut() {
+            *word = !*word;
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Assertion {
+    /// `^`, at the start of the string or, with REG_NEWLINE, of a line
+    LineStart,
+    /// `$`, at the end of the string or, with REG_NEWLINE, of a line
+    LineEnd,
+    /// `\``, at the start of the string
+    StringStart,
+    /// `\'`, at the end of the string
+    StringEnd,
+    /// `\<`
+    WordStart,
+    /// `\>`
+    WordEnd,
+    /// `\b`
+    WordBoundary,
+    /// `\B`
+    NotWordBoundary,
+}
+
+#[derive(Clone)]
+enum Node {
+    Empty,
+    Byte(u8),
+    Set(Box<Set>),
+    Assert(Assertion),
+    Group(Box<Node>, usize),
+    Backref(usize),
+    Concat(Vec<Node>),
+    Alternation(Vec<Node>),
+    Repeat(Box<Node>, u32, Option<u32>),
+}
+
+pub enum Inst {
+    Byte(u8),
+    Set(Box<Set>),
+    Assert(Assertion),
+    /// Records the position in a capture slot: 2n for the start of group n, 2n + 1 for its end
+    Save(usize),
+    /// Goes to both, preferring the first
+    Split(usize, usize),
+    Jump(usize),
+    Backref(usize),
+    /// Records the position at the start of an iteration of a loop
+    Mark(usize),
+    /// Leaves the loop for the instruction given if an iteration hasn't moved since its mark,
+    /// which would otherwise repeat forever
+    Progress(usize, usize),
+    Match,
+}
+
+pub struct Program {
+    pub insts: Vec<Inst>,
+    pub nsub: usize,
+    pub marks: usize,
+    pub backrefs: bool,
+    pub icase: bool,
+    pub newline: bool,
+}
+
+type Result<T> = core::result::Result<T, c_int>;
+
+struct Parser<'a> {
+    pattern: &'a [u8],
+    pos: usize,
+    extended: bool,
+    icase: bool,
+    newline: bool,
+    nsub: usize,
+    /// The groups that have been closed, which are the only ones backreferences can refer to
+    closed: Vec<bool>,
+    backrefs: bool,
+}
+
+const CLASSES: &[(&[u8], fn(u8) -> bool)] = &[
+    (b"alnum", |b| b.is_ascii_alphanumeric()),
+    (b"alpha", |b| b.is_ascii_alphabetic()),
+    (b"blank", |b| b == b' ' || b == b'\t'),
+    (b"cntrl", |b| b.is_ascii_control()),
+    (b"digit", |b| b.is_ascii_digit()),
+    (b"graph", |b| b.is_ascii_graphic()),
+    (b"lower", |b| b.is_ascii_lowercase()),
+    (b"print", |b| b.is_ascii_graphic() || b == b' '),
+    (b"punct", |b| b.is_ascii_punctuation()),
+    (b"space", |b| b.is_ascii_whitespace() || b == 0x0b),
+    (b"upper", |b| b.is_ascii_uppercase()),
+    (b"xdigit", |b| b.is_ascii_hexdigit()),
+];
+
+pub fn is_word(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'_'
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<u8> {
+        self.pattern.get(self.pos).copied()
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<u8> {
+        self.pattern.get(self.pos + offset).copied()
+    }
+
+    fn at_end(&self) -> bool {
+        self.pos >= self.pattern.len()
+    }
+
+    /// Whether the pattern continues with `s`, spelled with a backslash in basic expressions if
+    /// `escaped_in_bre`
+    fn looking_at(&self, c: u8, escaped_in_bre: bool) -> bool {
+        if self.extended || !escaped_in_bre {
+            self.peek() == Some(c)
+        } else {
+            self.peek() == Some(b'\\') && self.peek_at(1) == Some(c)
+        }
+    }
+
+    fn skip(&mut self, c: u8, escaped_in_bre: bool) -> bool {
+        if self.looking_at(c, escaped_in_bre) {
+            self.pos += if self.extended || !escaped_in_bre {
+                1
+            } else {
+                2
+            };
+            true
+        } else {
+            false
+        }
+    }
+
+    fn set_of(&self, b: u8) -> Node {
+        if self.icase && b.is_ascii_alphabetic() {
+            let mut set = Set::new();
+            set.insert(b.to_ascii_lowercase());
+            set.insert(b.to_ascii_uppercase());
+            Node::Set(Box::new(set))
+        } else {
+            Node::Byte(b)
+        }
+    }
+
+    fn alternation(&mut self, depth: usize) -> Result<Node> {
+        let mut branches = vec![self.branch(depth)?];
+        while self.skip(b'|', true) {
+            branches.push(self.branch(depth)?);
+        }
+        Ok(if branches.len() == 1 {
+            branches.pop().unwrap()
+        } else {
+            Node::Alternation(branches)
+        })
+    }
+
+    /// Whether the branch ends here, before a '|' or a ')' that closes a group
+    fn branch_ends(&self, depth: usize) -> bool {
+        self.at_end() || self.looking_at(b'|', true) || (depth > 0 && self.looking_at(b')', true))
+    }
+
+    fn branch(&mut self, depth: usize) -> Result<Node> {
+        let mut nodes = Vec::new();
+        let start = self.pos;
+        while !self.branch_ends(depth) {
+            // in basic expressions, '^' is only an anchor at the start and '*' is just a
+            // character there or after it
+            let first = self.pos == start
+                || (!self.extended && self.pos == start + 1 && self.pattern[start] == b'^');
+            if !self.extended && self.peek() == Some(b'^') && self.pos == start {
+                self.pos += 1;
+                nodes.push(Node::Assert(Assertion::LineStart));
+                continue;
+            }
+            if !self.extended && self.peek() == Some(b'$') {
+                self.pos += 1;
+                if self.branch_ends(depth) {
+                    nodes.push(Node::Assert(Assertion::LineEnd));
+                } else {
+                    nodes.push(self.set_of(b'$'));
+                    self.repetitions(&mut nodes)?;
+                }
+                continue;
+            }
+            if self.is_repetition() {
+                if self.extended || self.looking_at(b'{', true) {
+                    return Err(REG_BADRPT);
+                }
+                if first {
+                    // a literal '*', or '+' or '?'
+                    if self.peek() == Some(b'\\') {
+                        self.pos += 1;
+                    }
+                    let c = self.peek().unwrap();
+                    self.pos += 1;
+                    nodes.push(Node::Byte(c));
+                    self.repetitions(&mut nodes)?;
+                    continue;
+                }
+            }
+            let atom = self.atom(depth)?;
+            nodes.push(atom);
+            self.repetitions(&mut nodes)?;
+        }
+        Ok(match nodes.len() {
+            0 => Node::Empty,
+            1 => nodes.pop().unwrap(),
+            _ => Node::Concat(nodes),
+        })
+    }
+
+    fn is_repetition(&self) -> bool {
+        if self.extended {
+            matches!(
+                self.peek(),
+                Some(b'*') | Some(b'+') | Some(b'?') | Some(b'{')
+            )
+        } else {
+            self.peek() == Some(b'*')
+                || self.looking_at(b'{', true)
+                || self.looking_at(b'+', true)
+                || self.looking_at(b'?', true)
+        }
+    }
+
+    /// Applies the repetitions that follow to the last node, which basic expressions only allow
+    /// one of.
+    fn repetitions(&mut self, nodes: &mut Vec<Node>) -> Result<()> {
+        let mut repeated = false;
+        while self.is_repetition() {
+            if repeated && !self.extended {
+                return Err(REG_BADRPT);
+            }
+            repeated = true;
+            let (min, max) = if self.skip(b'*', false) {
+                (0, None)
+            } else if self.skip(b'+', true) {
+                (1, None)
+            } else if self.skip(b'?', true) {
+                (0, Some(1))
+            } else {
+                self.skip(b'{', true);
+                self.interval()?
+            };
+            let node = nodes.pop().unwrap();
+            // anchors don't repeat
+            if let Node::Assert(_) = node {
+                if self.extended {
+                    return Err(REG_BADRPT);
+                }
+                nodes.push(node);
+                continue;
+            }
+            nodes.push(Node::Repeat(Box::new(node), min, max));
+        }
+        Ok(())
+    }
+
+    fn number(&mut self) -> Result<Option<u32>> {
+        let mut number: Option<u32> = None;
+        while let Some(c) = self.peek().filter(u8::is_ascii_digit) {
+            self.pos += 1;
+            let n = number.unwrap_or(0) * 10 + u32::from(c - b'0');
+            if n > RE_DUP_MAX {
+                return Err(REG_BADBR);
+            }
+            number = Some(n);
+        }
+        Ok(number)
+    }
+
+    fn interval(&mut self) -> Result<(u32, Option<u32>)> {
+        let closed = (self.pos..self.pattern.len())
+            .any(|i| self.pattern[i] == b'}' && (self.extended || self.pattern[i - 1] == b'\\'));
+        if !closed {
+            return Err(REG_EBRACE);
+        }
+        let min = self.number()?;
+        let max = if self.peek() == Some(b',') {
+            self.pos += 1;
+            self.number()?
+        } else {
+            Some(min.ok_or(REG_BADBR)?)
+        };
+        if !self.skip(b'}', true) {
+            return Err(REG_BADBR);
+        }
+        let min = min.unwrap_or(0);
+        if max.map_or(false, |max| max < min) {
+            return Err(REG_BADBR);
+        }
+        Ok((min, max))
+    }
+
+    fn atom(&mut self, depth: usize) -> Result<Node> {
+        if self.skip(b'(', true) {
+            self.nsub += 1;
+            let index = self.nsub;
+            self.closed.push(false);
+            let inner = self.alternation(depth + 1)?;
+            if !self.skip(b')', true) {
+                return Err(REG_EPAREN);
+            }
+            self.closed[index - 1] = true;
+            return Ok(Node::Group(Box::new(inner), index));
+        }
+        if !self.extended && self.looking_at(b')', true) {
+            return Err(REG_EPAREN);
+        }
+
+        let c = self.peek().unwrap();
+        self.pos += 1;
+        match c {
+            b'.' => {
+                let mut set = Set::new();
+                set.invert();
+                if self.newline {
+                    set.0[(b'\n' / 32) as usize] &= !(1 << (b'\n' % 32));
+                }
+                Ok(Node::Set(Box::new(set)))
+            }
+            b'[' => self.bracket(),
+            b'^' if self.extended => Ok(Node::Assert(Assertion::LineStart)),
+            b'$' if self.extended => Ok(Node::Assert(Assertion::LineEnd)),
+            b'\\' => self.escape(),
+            c => Ok(self.set_of(c)),
+        }
+    }
+
+    fn escape(&mut self) -> Result<Node> {
+        let c = self.peek().ok_or(REG_EESCAPE)?;
+        self.pos += 1;
+        let class = |test: fn(u8) -> bool, negate: bool| {
+            let mut set = Set::new();
+            for b in 1..=255 {
+                if test(b) != negate {
+                    set.insert(b);
+                }
+            }
+            Node::Set(Box::new(set))
+        };
+        Ok(match c {
+            b'1'..=b'9' => {
+                let n = (c - b'0') as usize;
+                if !self.closed.get(n - 1).copied().unwrap_or(false) {
+                    return Err(REG_ESUBREG);
+                }
+                self.backrefs = true;
+                Node::Backref(n)
+            }
+            b'w' => class(is_word, false),
+            b'W' => class(is_word, true),
+            b's' => class(|b| b.is_ascii_whitespace() || b == 0x0b, false),
+            b'S' => class(|b| b.is_ascii_whitespace() || b == 0x0b, true),
+            b'<' => Node::Assert(Assertion::WordStart),
+            b'>' => Node::Assert(Assertion::WordEnd),
+            b'b' => Node::Assert(Assertion::WordBoundary),
+            b'B' => Node::Assert(Assertion::NotWordBoundary),
+            b'`' => Node::Assert(Assertion::StringStart),
+            b'\'' => Node::Assert(Assertion::StringEnd),
+            c => self.set_of(c),
+        })
+    }
+
+    /// Parses the name of a class, equivalence class or collating symbol up to its `end` and
+    /// then ']'.
+    fn bracket_name(&mut self, end: u8) -> Result<&'a [u8]> {
+        let start = self.pos;
+        loop {
+            match self.peek() {
+                None => return Err(REG_EBRACK),
+                Some(c) if c == end && self.peek_at(1) == Some(b']') => break,
+                Some(_) => self.pos += 1,
+            }
+        }
+        let name = &self.pattern[start..self.pos];
+        self.pos += 2;
+        Ok(name)
+    }
+
+    /// Parses a character that can end a range, which may be a collating symbol.
+    fn bracket_char(&mut self) -> Result<Option<u8>> {
+        if self.peek() == Some(b'[') && self.peek_at(1) == Some(b'.') {
+            self.pos += 2;
+            match self.bracket_name(b'.')? {
+                [c] => return Ok(Some(*c)),
+                _ => return Err(REG_ECOLLATE),
+            }
+        }
+        if self.peek() == Some(b'[') && self.peek_at(1) == Some(b'=') {
+            return Ok(None);
+        }
+        if self.peek() == Some(b'[') && self.peek_at(1) == Some(b':') {
+            return Ok(None);
+        }
+        let c = self.peek().ok_or(REG_EBRACK)?;
+        self.pos += 1;
+        Ok(Some(c))
+    }
+
+    /// Whether a '-' follows that isn't the last character of the bracket
+    fn range_follows(&self) -> bool {
+        self.peek() == Some(b'-') && self.peek_at(1).map_or(false, |c| c != b']')
+    }
+
+    fn bracket(&mut self) -> Result<Node> {
+        let mut set = Set::new();
+        let negate = self.peek() == Some(b'^');
+        if negate {
+            self.pos += 1;
+        }
+
+        let mut first = true;
+        loop {
+            match self.peek() {
+                None => return Err(REG_EBRACK),
+                Some(b']') if !first => {
+                    self.pos += 1;
+                    break;
+                }
+                _ => (),
+            }
+            first = false;
+
+            if self.peek() == Some(b'[') && self.peek_at(1) == Some(b':') {
+                self.pos += 2;
+                let name = self.bracket_name(b':')?;
+                let test = CLASSES
+                    .iter()
+                    .find(|(class, _)| *class == name)
+                    .ok_or(REG_ECTYPE)?
+                    .1;
+                for b in 1..=255 {
+                    if test(b) {
+                        set.insert(b);
+                    }
+                }
+                if self.range_follows() {
+                    return Err(REG_ERANGE);
+                }
+                continue;
+            }
+            if self.peek() == Some(b'[') && self.peek_at(1) == Some(b'=') {
+                self.pos += 2;
+                match self.bracket_name(b'=')? {
+                    [c] => set.insert(*c),
+                    _ => return Err(REG_ECOLLATE),
+                }
+                if self.range_follows() {
+                    return Err(REG_ERANGE);
+                }
+                continue;
+            }
+
+            let low = self.bracket_char()?.ok_or(REG_ERANGE)?;
+            if self.range_follows() {
+                self.pos += 1;
+                let high = self.bracket_char()?.ok_or(REG_ERANGE)?;
+                if high < low {
+                    return Err(REG_ERANGE);
+                }
+                for b in low..=high {
+                    set.insert(b);
+                }
+            } else {
+                set.insert(low);
+            }
+        }
+
+        if self.icase {
+            for b in b'A'..=b'Z' {
+                if set.contains(b) || set.contains(b.to_ascii_lowercase()) {
+                    set.insert(b);
+                    set.insert(b.to_ascii_lowercase());
+                }
+            }
+        }
+        if negate {
+            set.invert();
+            if self.newline {
+                set.0[(b'\n' / 32) as usize] &= !(1 << (b'\n' % 32));
+            }
+        }
+        set.0[0] &= !1;
+        Ok(Node::Set(Box::new(set)))
+    }
+}
+
+struct Compiler {
+    insts: Vec<Inst>,
+    marks: usize,
+}
+
+impl Compiler {
+    fn push(&mut self, inst: Inst) -> Result<usize> {
+        if self.insts.len() >= MAX_PROGRAM {
+            return Err(REG_ESPACE);
+        }
+        self.insts.push(inst);
+        Ok(self.insts.len() - 1)
+    }
+
+    fn patch(&mut self, at: usize, target: usize) {
+        match &mut self.insts[at] {
+            Inst::Split(_, second) => *second = target,
+            Inst::Jump(to) | Inst::Progress(_, to) => *to = target,
+            _ => unreachable!(),
+        }
+    }
+
+    fn compile(&mut self, node: &Node) -> Result<()> {
+        match node {
+            Node::Empty => (),
+            Node::Byte(b) => {
+                self.push(Inst::Byte(*b))?;
+            }
+            Node::Set(set) => {
+                self.push(Inst::Set(set.clone()))?;
+            }
+            Node::Assert(assertion) => {
+                self.push(Inst::Assert(*assertion))?;
+            }
+            Node::Group(inner, index) => {
+                self.push(Inst::Save(2 * index))?;
+                self.compile(inner)?;
+                self.push(Inst::Save(2 * index + 1))?;
+            }
+            Node::Backref(index) => {
+                self.push(Inst::Backref(*index))?;
+            }
+            Node::Concat(nodes) => {
+                for node in nodes {
+                    self.compile(node)?;
+                }
+            }
+            Node::Alternation(branches) => {
+                let mut jumps = Vec::new();
+                for (i, branch) in branches.iter().enumerate() {
+                    if i + 1 < branches.len() {
+                        let split = self.push(Inst::Split(self.insts.len() + 1, 0))?;
+                        self.compile(branch)?;
+                        jumps.push(self.push(Inst::Jump(0))?);
+                        let next = self.insts.len();
+                        self.patch(split, next);
+                    } else {
+                        self.compile(branch)?;
+                    }
+                }
+                let end = self.insts.len();
+                for jump in jumps {
+                    self.patch(jump, end);
+                }
+            }
+            Node::Repeat(inner, min, max) => {
+                for _ in 0..*min {
+                    self.compile(inner)?;
+                }
+                match max {
+                    None => {
+                        let mark = self.marks;
+                        self.marks += 1;
+                        let split = self.push(Inst::Split(self.insts.len() + 1, 0))?;
+                        self.push(Inst::Mark(mark))?;
+                        self.compile(inner)?;
+                        let progress = self.push(Inst::Progress(mark, 0))?;
+                        self.push(Inst::Jump(split))?;
+                        let end = self.insts.len();
+                        self.patch(split, end);
+                        self.patch(progress, end);
+                    }
+                    Some(max) => {
+                        // each optional copy skips all the others
+                        let mut splits = Vec::new();
+                        for _ in *min..*max {
+                            splits.push(self.push(Inst::Split(self.insts.len() + 1, 0))?);
+                            self.compile(inner)?;
+                        }
+                        let end = self.insts.len();
+                        for split in splits {
+                            self.patch(split, end);
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+pub fn compile(pattern: &[u8], cflags: c_int) -> Result<Program> {
+    let mut parser = Parser {
+        pattern,
+        pos: 0,
+        extended: cflags & REG_EXTENDED == REG_EXTENDED,
+        icase: cflags & REG_ICASE == REG_ICASE,
+        newline: cflags & REG_NEWLINE == REG_NEWLINE,
+        nsub: 0,
+        closed: Vec::new(),
+        backrefs: false,
+    };
+    let node = parser.alternation(0)?;
+
+    let mut compiler = Compiler {
+        insts: Vec::new(),
+        marks: 0,
+    };
+    compiler.push(Inst::Save(0))?;
+    compiler.compile(&node)?;
+    compiler.push(Inst::Save(1))?;
+    compiler.push(Inst::Match)?;
+
+    Ok(Program {
+        insts: compiler.insts,
+        nsub: parser.nsub,
+        marks: compiler.marks,
+        backrefs: parser.backrefs,
+        icase: parser.icase,
+        newline: parser.newline,
+    })
+}
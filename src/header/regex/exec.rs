@@ -0,0 +1,309 @@
+//! Runs compiled programs, finding the leftmost match and the longest of those as POSIX asks.
+//!
+//! Programs without backreferences run on every thread of the automaton at once, which takes
+//! time linear in the input. Backreferences need the positions of each attempt, so programs
+//! with them try the alternatives one after another.
+
+use alloc::{boxed::Box, collections::BTreeSet, vec::Vec};
+
+use super::compile::{is_word, Assertion, Inst, Program};
+
+/// The position of a group that didn't match
+pub const UNSET: usize = usize::MAX;
+
+struct Input<'a> {
+    text: &'a [u8],
+    not_bol: bool,
+    not_eol: bool,
+    newline: bool,
+}
+
+impl<'a> Input<'a> {
+    fn assert(&self, assertion: Assertion, pos: usize) -> bool {
+        let before = if pos > 0 {
+            Some(self.text[pos - 1])
+        } else {
+            None
+        };
+        let after = self.text.get(pos).copied();
+        let word_before = before.map_or(false, is_word);
+        let word_after = after.map_or(false, is_word);
+        match assertion {
+            Assertion::LineStart => {
+                (before.is_none() && !self.not_bol) || (self.newline && before == Some(b'\n'))
+            }
+            Assertion::LineEnd => {
+                (after.is_none() && !self.not_eol) || (self.newline && after == Some(b'\n'))
+            }
+            Assertion::StringStart => before.is_none(),
+            Assertion::StringEnd => after.is_none(),
+            Assertion::WordStart => !word_before && word_after,
+            Assertion::WordEnd => word_before && !word_after,
+            Assertion::WordBoundary => word_before != word_after,
+            Assertion::NotWordBoundary => word_before == word_after,
+        }
+    }
+
+    /// Whether the byte at `pos` is the one or in the set `inst` wants.
+    fn consumes(&self, inst: &Inst, pos: usize) -> bool {
+        match (inst, self.text.get(pos)) {
+            (Inst::Byte(b), Some(c)) => b == c,
+            (Inst::Set(set), Some(c)) => set.contains(*c),
+            _ => false,
+        }
+    }
+}
+
+/// Whether a match with `caps` is preferred to `best`, being further left or as far left and
+/// longer
+fn better(caps: &[usize], best: &Option<Box<[usize]>>) -> bool {
+    match best {
+        None => true,
+        Some(best) => caps[0] < best[0] || (caps[0] == best[0] && caps[1] > best[1]),
+    }
+}
+
+/// The instruction a thread is at, and its capture slots followed by the position each loop's
+/// iteration started at
+type Thread = (usize, Box<[usize]>);
+
+/// Adds the thread at `pc` to `list`, following the instructions that don't consume anything,
+/// in the order of their priority.
+fn add_thread(
+    program: &Program,
+    input: &Input,
+    list: &mut Vec<Thread>,
+    seen: &mut [usize],
+    generation: usize,
+    thread: Thread,
+    pos: usize,
+) {
+    let slots = 2 * (program.nsub + 1);
+    let mut stack = vec![thread];
+    while let Some((mut pc, mut caps)) = stack.pop() {
+        loop {
+            if seen[pc] == generation {
+                break;
+            }
+            seen[pc] = generation;
+            match &program.insts[pc] {
+                Inst::Jump(to) => pc = *to,
+                Inst::Split(first, second) => {
+                    stack.push((*second, caps.clone()));
+                    pc = *first;
+                }
+                Inst::Save(slot) => {
+                    caps[*slot] = pos;
+                    pc += 1;
+                }
+                Inst::Assert(assertion) => {
+                    if !input.assert(*assertion, pos) {
+                        break;
+                    }
+                    pc += 1;
+                }
+                Inst::Mark(mark) => {
+                    caps[slots + mark] = pos;
+                    pc += 1;
+                }
+                Inst::Progress(mark, exit) => {
+                    pc = if caps[slots + mark] == pos {
+                        *exit
+                    } else {
+                        pc + 1
+                    };
+                }
+                Inst::Byte(_) | Inst::Set(_) | Inst::Match | Inst::Backref(_) => {
+                    list.push((pc, caps));
+                    break;
+                }
+            }
+        }
+    }
+}
+
+fn run_threads(program: &Program, input: &Input) -> Option<Box<[usize]>> {
+    let slots = 2 * (program.nsub + 1);
+    let mut current = Vec::new();
+    let mut next = Vec::new();
+    let mut seen = vec![UNSET; program.insts.len()];
+    let mut best: Option<Box<[usize]>> = None;
+
+    for pos in 0..=input.text.len() {
+        // a match further right can't be preferred to one that's found
+        if best.is_none() {
+            let caps = vec![UNSET; slots + program.marks].into_boxed_slice();
+            add_thread(program, input, &mut current, &mut seen, pos, (0, caps), pos);
+        } else if current.is_empty() {
+            break;
+        }
+
+        for (pc, caps) in current.drain(..) {
+            if best.as_ref().map_or(false, |best| caps[0] > best[0]) {
+                continue;
+            }
+            let inst = &program.insts[pc];
+            if let Inst::Match = inst {
+                if better(&caps, &best) {
+                    best = Some(caps);
+                }
+            } else if input.consumes(inst, pos) {
+                add_thread(
+                    program,
+                    input,
+                    &mut next,
+                    &mut seen,
+                    pos + 1,
+                    (pc + 1, caps),
+                    pos + 1,
+                );
+            }
+        }
+        core::mem::swap(&mut current, &mut next);
+    }
+    best.map(|caps| caps[..slots].into())
+}
+
+struct State {
+    pc: usize,
+    pos: usize,
+    caps: Box<[usize]>,
+    marks: Box<[usize]>,
+}
+
+fn backref_matches(
+    program: &Program,
+    input: &Input,
+    caps: &[usize],
+    n: usize,
+    pos: usize,
+) -> Option<usize> {
+    let (start, end) = (caps[2 * n], caps[2 * n + 1]);
+    if start == UNSET || end == UNSET {
+        return None;
+    }
+    let group = &input.text[start..end];
+    let rest = input.text.get(pos..pos + group.len())?;
+    let equal = if program.icase {
+        group.eq_ignore_ascii_case(rest)
+    } else {
+        group == rest
+    };
+    if equal {
+        Some(pos + group.len())
+    } else {
+        None
+    }
+}
+
+/// Tries every way to match at `start`, returning the longest or, of those, the first.
+///
+/// How a branch carries on depends only on where it is in the program and the input, the groups
+/// that are referred back to and where the loops' iterations started, so a branch that's been
+/// tried before with those is skipped, as it could only match the same way.
+fn backtrack_at(
+    program: &Program,
+    input: &Input,
+    referenced: &[usize],
+    start: usize,
+) -> Option<Box<[usize]>> {
+    let mut best: Option<Box<[usize]>> = None;
+    let mut tried = BTreeSet::new();
+    let mut stack = vec![State {
+        pc: 0,
+        pos: start,
+        caps: vec![UNSET; 2 * (program.nsub + 1)].into_boxed_slice(),
+        marks: vec![UNSET; program.marks].into_boxed_slice(),
+    }];
+
+    while let Some(mut state) = stack.pop() {
+        loop {
+            let inst = &program.insts[state.pc];
+            match inst {
+                Inst::Byte(_) | Inst::Set(_) => {
+                    if !input.consumes(inst, state.pos) {
+                        break;
+                    }
+                    state.pos += 1;
+                }
+                Inst::Assert(assertion) => {
+                    if !input.assert(*assertion, state.pos) {
+                        break;
+                    }
+                }
+                Inst::Save(slot) => state.caps[*slot] = state.pos,
+                Inst::Split(first, second) => {
+                    let mut key = vec![state.pc, state.pos];
+                    for n in referenced {
+                        key.extend_from_slice(&state.caps[2 * n..2 * n + 2]);
+                    }
+                    key.extend_from_slice(&state.marks);
+                    if !tried.insert(key) {
+                        break;
+                    }
+                    stack.push(State {
+                        pc: *second,
+                        pos: state.pos,
+                        caps: state.caps.clone(),
+                        marks: state.marks.clone(),
+                    });
+                    state.pc = *first;
+                    continue;
+                }
+                Inst::Jump(to) => {
+                    state.pc = *to;
+                    continue;
+                }
+                Inst::Backref(n) => {
+                    match backref_matches(program, input, &state.caps, *n, state.pos) {
+                        Some(pos) => state.pos = pos,
+                        None => break,
+                    }
+                }
+                Inst::Mark(mark) => state.marks[*mark] = state.pos,
+                Inst::Progress(mark, exit) => {
+                    if state.marks[*mark] == state.pos {
+                        state.pc = *exit;
+                        continue;
+                    }
+                }
+                Inst::Match => {
+                    if better(&state.caps, &best) {
+                        // nothing can be longer than the rest of the input
+                        let whole = state.pos == input.text.len();
+                        best = Some(state.caps);
+                        if whole {
+                            return best;
+                        }
+                    }
+                    break;
+                }
+            }
+            state.pc += 1;
+        }
+    }
+    best
+}
+
+/// Finds the match, returning the start and end of each group, or UNSET for those that didn't
+/// match.
+pub fn exec(program: &Program, text: &[u8], not_bol: bool, not_eol: bool) -> Option<Box<[usize]>> {
+    let input = Input {
+        text,
+        not_bol,
+        not_eol,
+        newline: program.newline,
+    };
+    if !program.backrefs {
+        return run_threads(program, &input);
+    }
+    let referenced: Vec<usize> = program
+        .insts
+        .iter()
+        .filter_map(|inst| match inst {
+            Inst::Backref(n) => Some(*n),
+            _ => None,
+        })
+        .collect();
+    (0..=text.len()).find_map(|start| backtrack_at(program, &input, &referenced, start))
+}
@@ -1,22 +1,19 @@
 //! regex.h implementation, following http://pubs.opengroup.org/onlinepubs/7908799/xsh/regex.h.html
 
 use crate::{header::string::strlen, platform::types::*};
-use alloc::{borrow::Cow, vec::Vec};
-use core::{mem, ptr, slice};
-use posix_regex::{
-    compile::{Error as CompileError, Range, Token},
-    PosixRegex, PosixRegexBuilder,
-};
+use alloc::boxed::Box;
+use core::{ptr, slice};
 
-pub type regoff_t = size_t;
+mod compile;
+mod exec;
+
+pub type regoff_t = ssize_t;
 
 #[repr(C)]
 pub struct regex_t {
-    // Can't be a normal Vec<T> because then the struct size won't be known
+    // Can't be a normal Box<T> because then the struct size won't be known
     // from C.
     ptr: *mut c_void,
-    length: size_t,
-    capacity: size_t,
 
     cflags: c_int,
     re_nsub: size_t,
@@ -52,48 +49,25 @@ pub const REG_BADRPT: c_int = 14;
 #[no_mangle]
 #[linkage = "weak"] // redefined in GIT
 pub unsafe extern "C" fn regcomp(out: *mut regex_t, pat: *const c_char, cflags: c_int) -> c_int {
-    if cflags & REG_EXTENDED == REG_EXTENDED {
-        return REG_ENOSYS;
-    }
-
     let pat = slice::from_raw_parts(pat as *const u8, strlen(pat));
-    let res = PosixRegexBuilder::new(pat)
-        .with_default_classes()
-        .compile_tokens();
-
-    match res {
-        Ok(mut branches) => {
-            let re_nsub = PosixRegex::new(Cow::Borrowed(&branches)).count_groups();
+    match compile::compile(pat, cflags) {
+        Ok(program) => {
             *out = regex_t {
-                ptr: branches.as_mut_ptr() as *mut c_void,
-                length: branches.len(),
-                capacity: branches.capacity(),
+                re_nsub: program.nsub,
+                ptr: Box::into_raw(Box::new(program)) as *mut c_void,
 
                 cflags,
-                re_nsub,
             };
-            mem::forget(branches);
             0
         }
-        Err(CompileError::EmptyRepetition)
-        | Err(CompileError::IntegerOverflow)
-        | Err(CompileError::IllegalRange) => REG_BADBR,
-        Err(CompileError::UnclosedRepetition) => REG_EBRACE,
-        Err(CompileError::LeadingRepetition) => REG_BADRPT,
-        Err(CompileError::UnknownCollation) => REG_ECOLLATE,
-        Err(CompileError::UnknownClass(_)) => REG_ECTYPE,
-        Err(_) => REG_BADPAT,
+        Err(code) => code,
     }
 }
 
 #[no_mangle]
 #[linkage = "weak"] // redefined in GIT
 pub unsafe extern "C" fn regfree(regex: *mut regex_t) {
-    Vec::from_raw_parts(
-        (*regex).ptr as *mut Vec<(Token, Range)>,
-        (*regex).length,
-        (*regex).capacity,
-    );
+    drop(Box::from_raw((*regex).ptr as *mut compile::Program));
 }
 
 #[no_mangle]
@@ -105,43 +79,35 @@ pub unsafe extern "C" fn regexec(
     pmatch: *mut regmatch_t,
     eflags: c_int,
 ) -> c_int {
-    if eflags & REG_EXTENDED == REG_EXTENDED {
-        return REG_ENOSYS;
-    }
-
     let regex = &*regex;
-
-    // Allow specifying a compiler argument to the executor and vise versa
-    // because why not?
-    let flags = regex.cflags | eflags;
+    let program = &*(regex.ptr as *const compile::Program);
 
     let input = slice::from_raw_parts(input as *const u8, strlen(input));
-    let branches = slice::from_raw_parts(regex.ptr as *const Vec<(Token, Range)>, regex.length);
-
-    let matches = PosixRegex::new(Cow::Borrowed(&branches))
-        .case_insensitive(flags & REG_ICASE == REG_ICASE)
-        .newline(flags & REG_NEWLINE == REG_NEWLINE)
-        .no_start(flags & REG_NOTBOL == REG_NOTBOL)
-        .no_end(flags & REG_NOTEOL == REG_NOTEOL)
-        .matches(input, Some(1));
-
-    if !matches.is_empty() && eflags & REG_NOSUB != REG_NOSUB && !pmatch.is_null() && nmatch > 0 {
-        let first = &matches[0];
+    let caps = match exec::exec(
+        program,
+        input,
+        eflags & REG_NOTBOL == REG_NOTBOL,
+        eflags & REG_NOTEOL == REG_NOTEOL,
+    ) {
+        Some(caps) => caps,
+        None => return REG_NOMATCH,
+    };
 
+    if regex.cflags & REG_NOSUB != REG_NOSUB && !pmatch.is_null() {
         for i in 0..nmatch {
-            let (start, end) = first.get(i).and_then(|&range| range).unwrap_or((!0, !0));
+            let (start, end) = match (caps.get(2 * i), caps.get(2 * i + 1)) {
+                (Some(&start), Some(&end)) if start != exec::UNSET && end != exec::UNSET => {
+                    (start as regoff_t, end as regoff_t)
+                }
+                _ => (-1, -1),
+            };
             *pmatch.add(i) = regmatch_t {
                 rm_so: start,
                 rm_eo: end,
             };
         }
     }
-
-    if matches.is_empty() {
-        REG_NOMATCH
-    } else {
-        0
-    }
+    0
 }
 
 #[no_mangle]
@@ -153,30 +119,31 @@ pub extern "C" fn regerror(
     max: size_t,
 ) -> size_t {
     let string = match code {
-        0 => "No error\0",
+        0 => "Success\0",
         REG_NOMATCH => "No match\0",
-        REG_BADPAT => "Invalid regexp\0",
-        REG_ECOLLATE => "Unknown collating element\0",
-        REG_ECTYPE => "Unknown character class name\0",
+        REG_BADPAT => "Invalid regular expression\0",
+        REG_ECOLLATE => "Invalid collation character\0",
+        REG_ECTYPE => "Invalid character class name\0",
         REG_EESCAPE => "Trailing backslash\0",
         REG_ESUBREG => "Invalid back reference\0",
-        REG_EBRACK => "Missing ']'\0",
+        REG_EBRACK => "Unmatched [, [^, [:, [., or [=\0",
         REG_ENOSYS => "Unsupported operation\0",
-        REG_EPAREN => "Missing ')'\0",
-        REG_EBRACE => "Missing '}'\0",
-        REG_BADBR => "Invalid contents of {}\0",
-        REG_ERANGE => "Invalid character range\0",
-        REG_ESPACE => "Out of memory\0",
-        REG_BADRPT => "Repetition not preceded by valid expression\0",
+        REG_EPAREN => "Unmatched ( or \\(\0",
+        REG_EBRACE => "Unmatched \\{\0",
+        REG_BADBR => "Invalid content of \\{\\}\0",
+        REG_ERANGE => "Invalid range end\0",
+        REG_ESPACE => "Memory exhausted\0",
+        REG_BADRPT => "Invalid preceding regular expression\0",
         _ => "Unknown error\0",
     };
 
-    unsafe {
-        ptr::copy_nonoverlapping(
-            string.as_ptr(),
-            out as *mut u8,
-            string.len().min(max as usize),
-        );
+    if max > 0 {
+        // truncated messages are still terminated
+        let len = string.len().min(max as usize);
+        unsafe {
+            ptr::copy_nonoverlapping(string.as_ptr(), out as *mut u8, len - 1);
+            *out.add(len - 1) = 0;
+        }
     }
 
     string.len()
@@ -0,0 +1,526 @@
+//! search.h implementation for Redox, following
+//! https://pubs.opengroup.org/onlinepubs/9699919799/basedefs/search.h.html
+
+use core::{cmp, mem, ptr};
+
+use crate::{
+    header::{
+        errno::{EINVAL, ENOMEM, ESRCH},
+        stdlib::{calloc, free, malloc},
+        string::{memcpy, strcmp},
+    },
+    platform::{self, types::*},
+};
+
+// ACTION and VISIT are enums with unprefixed constants, so they're defined in bits/search.h
+pub type ACTION = c_int;
+pub type VISIT = c_int;
+
+const FIND: ACTION = 0;
+
+const preorder: VISIT = 0;
+const postorder: VISIT = 1;
+const endorder: VISIT = 2;
+const leaf: VISIT = 3;
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct entry {
+    pub key: *mut c_char,
+    pub data: *mut c_void,
+}
+
+pub type ENTRY = entry;
+
+/// A table for hsearch_r, which has to be zeroed before hcreate_r. Laid out like glibc's.
+#[repr(C)]
+pub struct hsearch_data {
+    table: *mut ENTRY,
+    size: c_uint,
+    used: c_uint,
+}
+
+const MIN_SIZE: c_uint = 8;
+
+static mut TABLE: hsearch_data = hsearch_data {
+    table: ptr::null_mut(),
+    size: 0,
+    used: 0,
+};
+
+unsafe fn hash(mut key: *const c_char) -> size_t {
+    let mut h: size_t = 0;
+    while *key != 0 {
+        h = h.wrapping_mul(31).wrapping_add(*key as u8 as size_t);
+        key = key.add(1);
+    }
+    h
+}
+
+/// Finds the slot either holding `key` or where it goes, which always exists as the table
+/// is never full.
+unsafe fn lookup(htab: &hsearch_data, key: *const c_char) -> *mut ENTRY {
+    let mask = htab.size as size_t - 1;
+    let mut i = hash(key);
+    loop {
+        let e = htab.table.add(i & mask);
+        if (*e).key.is_null() || strcmp((*e).key, key) == 0 {
+            return e;
+        }
+        i = i.wrapping_add(1);
+    }
+}
+
+/// Moves the entries to a table of at least `nel` slots, keeping the old one on failure.
+unsafe fn resize(htab: &mut hsearch_data, nel: c_uint) -> bool {
+    let mut size = MIN_SIZE;
+    while size < nel {
+        size = match size.checked_mul(2) {
+            Some(size) => size,
+            None => return false,
+        };
+    }
+    let table = calloc(size as size_t, mem::size_of::<ENTRY>()) as *mut ENTRY;
+    if table.is_null() {
+        return false;
+    }
+
+    let old = mem::replace(
+        htab,
+        hsearch_data {
+            table,
+            size,
+            used: htab.used,
+        },
+    );
+    for i in 0..old.size {
+        let e = *old.table.add(i as size_t);
+        if !e.key.is_null() {
+            *lookup(htab, e.key) = e;
+        }
+    }
+    free(old.table as *mut c_void);
+    true
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn hcreate(nel: size_t) -> c_int {
+    hcreate_r(nel, &mut TABLE)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn hdestroy() {
+    hdestroy_r(&mut TABLE);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn hsearch(item: ENTRY, action: ACTION) -> *mut ENTRY {
+    let mut retval = ptr::null_mut();
+    hsearch_r(item, action, &mut retval, &mut TABLE);
+    retval
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn hcreate_r(nel: size_t, htab: *mut hsearch_data) -> c_int {
+    if htab.is_null() {
+        platform::errno = EINVAL;
+        return 0;
+    }
+    let htab = &mut *htab;
+    if !htab.table.is_null() {
+        return 0;
+    }
+    htab.used = 0;
+    // Room for nel entries at most three quarters full
+    let nel = cmp::min(nel, c_uint::max_value() as size_t / 2) as c_uint;
+    if !resize(htab, nel + nel / 3 + 1) {
+        platform::errno = ENOMEM;
+        return 0;
+    }
+    1
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn hdestroy_r(htab: *mut hsearch_data) {
+    if htab.is_null() {
+        platform::errno = EINVAL;
+        return;
+    }
+    let htab = &mut *htab;
+    free(htab.table as *mut c_void);
+    htab.table = ptr::null_mut();
+    htab.size = 0;
+    htab.used = 0;
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn hsearch_r(
+    item: ENTRY,
+    action: ACTION,
+    retval: *mut *mut ENTRY,
+    htab: *mut hsearch_data,
+) -> c_int {
+    if htab.is_null() {
+        platform::errno = EINVAL;
+        return 0;
+    }
+    let htab = &mut *htab;
+    *retval = ptr::null_mut();
+
+    if htab.table.is_null() {
+        if action == FIND {
+            platform::errno = ESRCH;
+            return 0;
+        }
+        if !resize(htab, MIN_SIZE) {
+            platform::errno = ENOMEM;
+            return 0;
+        }
+    }
+
+    let mut e = lookup(htab, item.key);
+    if (*e).key.is_null() {
+        if action == FIND {
+            platform::errno = ESRCH;
+            return 0;
+        }
+        *e = item;
+        htab.used += 1;
+        // Grows past three quarters full, so that probes stay short
+        if htab.used > htab.size - htab.size / 4 {
+            if !resize(htab, htab.size.saturating_mul(2)) {
+                htab.used -= 1;
+                (*e).key = ptr::null_mut();
+                platform::errno = ENOMEM;
+                return 0;
+            }
+            e = lookup(htab, item.key);
+        }
+    }
+    *retval = e;
+    1
+}
+
+/// An AVL tree node, whose first member is the key as callers of tsearch rely on
+#[repr(C)]
+struct Node {
+    key: *const c_void,
+    left: *mut Node,
+    right: *mut Node,
+    height: c_int,
+}
+
+type Compar = unsafe extern "C" fn(*const c_void, *const c_void) -> c_int;
+
+unsafe fn height(node: *mut Node) -> c_int {
+    if node.is_null() {
+        0
+    } else {
+        (*node).height
+    }
+}
+
+unsafe fn update(node: *mut Node) {
+    (*node).height = cmp::max(height((*node).left), height((*node).right)) + 1;
+}
+
+unsafe fn rotate_left(slot: *mut *mut Node) {
+    let node = *slot;
+    let right = (*node).right;
+    (*node).right = (*right).left;
+    (*right).left = node;
+    update(node);
+    update(right);
+    *slot = right;
+}
+
+unsafe fn rotate_right(slot: *mut *mut Node) {
+    let node = *slot;
+    let left = (*node).left;
+    (*node).left = (*left).right;
+    (*left).right = node;
+    update(node);
+    update(left);
+    *slot = left;
+}
+
+/// Restores the height of the subtree in `slot` and, if its sides differ by two, its balance.
+unsafe fn balance(slot: *mut *mut Node) {
+    let node = *slot;
+    update(node);
+    let diff = height((*node).left) - height((*node).right);
+    if diff > 1 {
+        let left = (*node).left;
+        if height((*left).left) < height((*left).right) {
+            rotate_left(&mut (*node).left);
+        }
+        rotate_right(slot);
+    } else if diff < -1 {
+        let right = (*node).right;
+        if height((*right).right) < height((*right).left) {
+            rotate_right(&mut (*node).right);
+        }
+        rotate_left(slot);
+    }
+}
+
+unsafe fn insert(slot: *mut *mut Node, key: *const c_void, compar: Compar) -> *mut Node {
+    let node = *slot;
+    if node.is_null() {
+        let node = malloc(mem::size_of::<Node>()) as *mut Node;
+        if !node.is_null() {
+            *node = Node {
+                key,
+                left: ptr::null_mut(),
+                right: ptr::null_mut(),
+                height: 1,
+            };
+            *slot = node;
+        }
+        return node;
+    }
+
+    let diff = compar(key, (*node).key);
+    if diff == 0 {
+        return node;
+    }
+    let found = if diff < 0 {
+        insert(&mut (*node).left, key, compar)
+    } else {
+        insert(&mut (*node).right, key, compar)
+    };
+    balance(slot);
+    found
+}
+
+/// Unlinks and frees the greatest node below `slot`, returning its key.
+unsafe fn remove_max(slot: *mut *mut Node) -> *const c_void {
+    let node = *slot;
+    if (*node).right.is_null() {
+        let key = (*node).key;
+        *slot = (*node).left;
+        free(node as *mut c_void);
+        return key;
+    }
+    let key = remove_max(&mut (*node).right);
+    balance(slot);
+    key
+}
+
+unsafe fn remove(
+    slot: *mut *mut Node,
+    key: *const c_void,
+    compar: Compar,
+    parent: *mut c_void,
+) -> *mut c_void {
+    let node = *slot;
+    if node.is_null() {
+        return ptr::null_mut();
+    }
+
+    let diff = compar(key, (*node).key);
+    if diff != 0 {
+        let child = if diff < 0 {
+            &mut (*node).left
+        } else {
+            &mut (*node).right
+        };
+        let parent = remove(child, key, compar, node as *mut c_void);
+        if !parent.is_null() {
+            balance(slot);
+        }
+        return parent;
+    }
+
+    if (*node).left.is_null() {
+        *slot = (*node).right;
+        free(node as *mut c_void);
+    } else if (*node).right.is_null() {
+        *slot = (*node).left;
+        free(node as *mut c_void);
+    } else {
+        // The node keeps its place and takes the key of the one before it
+        (*node).key = remove_max(&mut (*node).left);
+        balance(slot);
+    }
+    parent
+}
+
+unsafe fn walk(
+    node: *const Node,
+    action: unsafe extern "C" fn(*const c_void, VISIT, c_int),
+    depth: c_int,
+) {
+    if (*node).left.is_null() && (*node).right.is_null() {
+        action(node as *const c_void, leaf, depth);
+        return;
+    }
+    action(node as *const c_void, preorder, depth);
+    if !(*node).left.is_null() {
+        walk((*node).left, action, depth + 1);
+    }
+    action(node as *const c_void, postorder, depth);
+    if !(*node).right.is_null() {
+        walk((*node).right, action, depth + 1);
+    }
+    action(node as *const c_void, endorder, depth);
+}
+
+unsafe fn destroy(node: *mut Node, free_node: unsafe extern "C" fn(*mut c_void)) {
+    if node.is_null() {
+        return;
+    }
+    destroy((*node).left, free_node);
+    destroy((*node).right, free_node);
+    free_node((*node).key as *mut c_void);
+    free(node as *mut c_void);
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn tsearch(
+    key: *const c_void,
+    rootp: *mut *mut c_void,
+    compar: Option<Compar>,
+) -> *mut c_void {
+    match compar {
+        Some(compar) if !rootp.is_null() => insert(rootp as *mut *mut Node, key, compar) as _,
+        _ => ptr::null_mut(),
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn tfind(
+    key: *const c_void,
+    rootp: *const *mut c_void,
+    compar: Option<Compar>,
+) -> *mut c_void {
+    let compar = match compar {
+        Some(compar) if !rootp.is_null() => compar,
+        _ => return ptr::null_mut(),
+    };
+    let mut node = *rootp as *mut Node;
+    while !node.is_null() {
+        let diff = compar(key, (*node).key);
+        if diff == 0 {
+            break;
+        }
+        node = if diff < 0 {
+            (*node).left
+        } else {
+            (*node).right
+        };
+    }
+    node as *mut c_void
+}
+
+/// Returns the parent of the node that held `key`, or `rootp` if that was the root.
+#[no_mangle]
+pub unsafe extern "C" fn tdelete(
+    key: *const c_void,
+    rootp: *mut *mut c_void,
+    compar: Option<Compar>,
+) -> *mut c_void {
+    match compar {
+        Some(compar) if !rootp.is_null() => {
+            remove(rootp as *mut *mut Node, key, compar, rootp as *mut c_void)
+        }
+        _ => ptr::null_mut(),
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn twalk(
+    root: *const c_void,
+    action: Option<unsafe extern "C" fn(*const c_void, VISIT, c_int)>,
+) {
+    if let Some(action) = action {
+        if !root.is_null() {
+            walk(root as *const Node, action, 0);
+        }
+    }
+}
+
+/// Frees every node of the tree, calling `free_node` on its key. A GNU extension.
+#[no_mangle]
+pub unsafe extern "C" fn tdestroy(
+    root: *mut c_void,
+    free_node: Option<unsafe extern "C" fn(*mut c_void)>,
+) {
+    if let Some(free_node) = free_node {
+        destroy(root as *mut Node, free_node);
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn lfind(
+    key: *const c_void,
+    base: *const c_void,
+    nelp: *const size_t,
+    width: size_t,
+    compar: Option<Compar>,
+) -> *mut c_void {
+    let compar = match compar {
+        Some(compar) => compar,
+        None => return ptr::null_mut(),
+    };
+    for i in 0..*nelp {
+        let elem = (base as *const u8).add(i * width) as *mut c_void;
+        if compar(key, elem) == 0 {
+            return elem;
+        }
+    }
+    ptr::null_mut()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn lsearch(
+    key: *const c_void,
+    base: *mut c_void,
+    nelp: *mut size_t,
+    width: size_t,
+    compar: Option<Compar>,
+) -> *mut c_void {
+    let found = lfind(key, base, nelp, width, compar);
+    if !found.is_null() || compar.is_none() {
+        return found;
+    }
+    let end = (base as *mut u8).add(*nelp * width) as *mut c_void;
+    memcpy(end, key, width);
+    *nelp += 1;
+    end
+}
+
+/// The links that insque and remque expect at the start of each element
+#[repr(C)]
+struct Link {
+    next: *mut Link,
+    prev: *mut Link,
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn insque(element: *mut c_void, pred: *mut c_void) {
+    let element = element as *mut Link;
+    let pred = pred as *mut Link;
+    if pred.is_null() {
+        (*element).next = ptr::null_mut();
+        (*element).prev = ptr::null_mut();
+        return;
+    }
+    (*element).next = (*pred).next;
+    (*element).prev = pred;
+    if !(*pred).next.is_null() {
+        (*(*pred).next).prev = element;
+    }
+    (*pred).next = element;
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn remque(element: *mut c_void) {
+    let element = element as *mut Link;
+    if !(*element).next.is_null() {
+        (*(*element).next).prev = (*element).prev;
+    }
+    if !(*element).prev.is_null() {
+        (*(*element).prev).next = (*element).next;
+    }
+}
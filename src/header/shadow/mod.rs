@@ -0,0 +1,194 @@
+//! shadow.h implementation, following the GNU functions for reading the shadow password
+//! database.
+//!
+//! The database is /etc/shadow, which only privileged processes may read, so failing to open it
+//! is reported with the error that open gave, such as EACCES, rather than as a missing entry. On
+//! Linux its lines have all the fields, separated by colons, with empty ones meaning -1. On
+//! Redox they only have the name and the password, separated by a semicolon, so the other fields
+//! are always -1.
+
+use alloc::vec::Vec;
+use core::{ptr, slice};
+
+use crate::{
+    c_str::CStr,
+    fs::File,
+    header::{errno, fcntl},
+    io::{prelude::*, BufReader},
+    platform::{self, types::*},
+};
+
+#[cfg(target_os = "linux")]
+const SEPARATOR: u8 = b':';
+#[cfg(target_os = "redox")]
+const SEPARATOR: u8 = b';';
+
+/// The number of fields on a line
+#[cfg(target_os = "linux")]
+const FIELDS: usize = 9;
+#[cfg(target_os = "redox")]
+const FIELDS: usize = 2;
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct spwd {
+    pub sp_namp: *mut c_char,
+    pub sp_pwdp: *mut c_char,
+    pub sp_lstchg: c_long,
+    pub sp_min: c_long,
+    pub sp_max: c_long,
+    pub sp_warn: c_long,
+    pub sp_inact: c_long,
+    pub sp_expire: c_long,
+    pub sp_flag: c_ulong,
+}
+
+static mut SPWD: spwd = spwd {
+    sp_namp: ptr::null_mut(),
+    sp_pwdp: ptr::null_mut(),
+    sp_lstchg: -1,
+    sp_min: -1,
+    sp_max: -1,
+    sp_warn: -1,
+    sp_inact: -1,
+    sp_expire: -1,
+    sp_flag: !0,
+};
+/// Where the strings of SPWD are
+static mut BUFFER: Vec<u8> = Vec::new();
+
+/// Parses an empty field as `empty`, which is what it means in the database.
+fn number<T: core::str::FromStr>(field: Option<&[u8]>, empty: T) -> Result<T, c_int> {
+    match field {
+        None | Some(b"") => Ok(empty),
+        Some(field) => core::str::from_utf8(field)
+            .ok()
+            .and_then(|field| field.parse().ok())
+            .ok_or(errno::EINVAL),
+    }
+}
+
+/// Copies `line`, which is without its newline, to `buf` and parses it into `out`, with the
+/// strings pointing into `buf`.
+fn parse(line: &[u8], out: &mut spwd, buf: &mut [u8]) -> Result<(), c_int> {
+    if line.iter().filter(|&&c| c == SEPARATOR).count() != FIELDS - 1 {
+        return Err(errno::EINVAL);
+    }
+    let mut fields = line.split(|&c| c == SEPARATOR);
+    let name = fields.next().ok_or(errno::EINVAL)?;
+    let password = fields.next().ok_or(errno::EINVAL)?;
+    let entry = spwd {
+        sp_namp: ptr::null_mut(),
+        sp_pwdp: ptr::null_mut(),
+        sp_lstchg: number(fields.next(), -1)?,
+        sp_min: number(fields.next(), -1)?,
+        sp_max: number(fields.next(), -1)?,
+        sp_warn: number(fields.next(), -1)?,
+        sp_inact: number(fields.next(), -1)?,
+        sp_expire: number(fields.next(), -1)?,
+        sp_flag: number(fields.next(), !0)?,
+    };
+
+    if name.len() + password.len() + 2 > buf.len() {
+        return Err(errno::ERANGE);
+    }
+    let (name_buf, rest) = buf.split_at_mut(name.len() + 1);
+    name_buf[..name.len()].copy_from_slice(name);
+    name_buf[name.len()] = 0;
+    rest[..password.len()].copy_from_slice(password);
+    rest[password.len()] = 0;
+
+    *out = spwd {
+        sp_namp: name_buf.as_mut_ptr() as *mut c_char,
+        sp_pwdp: rest.as_mut_ptr() as *mut c_char,
+        ..entry
+    };
+    Ok(())
+}
+
+/// Finds the line of the user `name`, returning the error number if the database can't be
+/// read.
+fn find(name: &CStr) -> Result<Option<Vec<u8>>, c_int> {
+    let file = File::open(c_str!("/etc/shadow"), fcntl::O_RDONLY).map_err(|err| err.0)?;
+    let mut reader = BufReader::new(file);
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        match reader.read_until(b'\n', &mut line) {
+            Ok(0) => return Ok(None),
+            Ok(_) => (),
+            Err(_) => return Err(errno::EIO),
+        }
+        if line.last() == Some(&b'\n') {
+            line.pop();
+        }
+        let mut fields = line.splitn(2, |&c| c == SEPARATOR);
+        if fields.next() == Some(name.to_bytes()) && fields.next().is_some() {
+            return Ok(Some(line));
+        }
+    }
+}
+
+/// Parses `line` into the entry that getspnam and sgetspent return.
+unsafe fn parse_global(line: &[u8]) -> *mut spwd {
+    BUFFER.clear();
+    BUFFER.resize(line.len() + 2, 0);
+    match parse(line, &mut SPWD, &mut BUFFER) {
+        Ok(()) => &mut SPWD,
+        Err(err) => {
+            platform::errno = err;
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Returns the entry of the user `name`, or null if there's none or the database can't be read,
+/// which sets errno.
+#[no_mangle]
+pub unsafe extern "C" fn getspnam(name: *const c_char) -> *mut spwd {
+    match find(CStr::from_ptr(name)) {
+        Ok(Some(line)) => parse_global(&line),
+        Ok(None) => ptr::null_mut(),
+        Err(err) => {
+            platform::errno = err;
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Like getspnam, but stores the entry in `spbuf` and its strings in `buf`, returning the error
+/// number instead of setting errno. Not finding the user isn't an error, and only sets
+/// `*spbufp` to null, as it is whenever this fails.
+#[no_mangle]
+pub unsafe extern "C" fn getspnam_r(
+    name: *const c_char,
+    spbuf: *mut spwd,
+    buf: *mut c_char,
+    buflen: size_t,
+    spbufp: *mut *mut spwd,
+) -> c_int {
+    *spbufp = ptr::null_mut();
+    let line = match find(CStr::from_ptr(name)) {
+        Ok(Some(line)) => line,
+        Ok(None) => return 0,
+        Err(err) => return err,
+    };
+    let buf = slice::from_raw_parts_mut(buf as *mut u8, buflen);
+    match parse(&line, &mut *spbuf, buf) {
+        Ok(()) => {
+            *spbufp = spbuf;
+            0
+        }
+        Err(err) => err,
+    }
+}
+
+/// Parses a line of the database, which may end with a newline.
+#[no_mangle]
+pub unsafe extern "C" fn sgetspent(s: *const c_char) -> *mut spwd {
+    let mut line = CStr::from_ptr(s).to_bytes();
+    if line.last() == Some(&b'\n') {
+        line = &line[..line.len() - 1];
+    }
+    parse_global(line)
+}
@@ -1,6 +1,6 @@
 //! signal implementation for Redox, following http://pubs.opengroup.org/onlinepubs/7908799/xsh/signal.h.html
 
-use core::mem;
+use core::{fmt, mem};
 
 use cbitset::BitSet;
 
@@ -29,15 +29,6 @@ pub const SIG_BLOCK: c_int = 0;
 pub const SIG_UNBLOCK: c_int = 1;
 pub const SIG_SETMASK: c_int = 2;
 
-#[repr(C)]
-#[derive(Clone, Debug)]
-pub struct sigaction {
-    pub sa_handler: Option<extern "C" fn(c_int)>,
-    pub sa_flags: c_ulong,
-    pub sa_restorer: Option<unsafe extern "C" fn()>,
-    pub sa_mask: sigset_t,
-}
-
 #[repr(C)]
 #[derive(Clone)]
 pub struct sigaltstack {
@@ -50,6 +41,90 @@ pub type sigset_t = c_ulong;
 
 pub type stack_t = sigaltstack;
 
+pub const SIGEV_SIGNAL: c_int = 0;
+pub const SIGEV_NONE: c_int = 1;
+pub const SIGEV_THREAD: c_int = 2;
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub union sigval {
+    pub sival_int: c_int,
+    pub sival_ptr: *mut c_void,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct sigevent {
+    pub sigev_value: sigval,
+    pub sigev_signo: c_int,
+    pub sigev_notify: c_int,
+    pub sigev_notify_function: Option<extern "C" fn(sigval)>,
+    /// A `pthread_attr_t *` for the thread started by SIGEV_THREAD, or null
+    pub sigev_notify_attributes: *mut c_void,
+}
+
+pub const SI_USER: c_int = 0;
+pub const SI_QUEUE: c_int = -1;
+pub const SI_TIMER: c_int = -2;
+pub const SI_MESGQ: c_int = -3;
+pub const SI_ASYNCIO: c_int = -4;
+
+/// What a handler installed with SA_SIGINFO is told of the signal, laid out as on Linux. C reaches
+/// the fields of __si_fields through the macros of bits/signal.h.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct siginfo_t {
+    pub si_signo: c_int,
+    pub si_errno: c_int,
+    pub si_code: c_int,
+    pub __si_fields: __siginfo_fields,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub union __siginfo_fields {
+    /// Those of SI_USER, SI_QUEUE, SI_TIMER, SI_MESGQ and SI_ASYNCIO
+    pub __rt: __siginfo_rt,
+    pub __pad: [c_int; 28],
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct __siginfo_rt {
+    pub si_pid: pid_t,
+    pub si_uid: uid_t,
+    pub si_value: sigval,
+}
+
+/// The handler of a sigaction, which C reaches as sa_handler and sa_sigaction through the macros
+/// of bits/signal.h
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub union __sigaction_handler {
+    pub sa_handler: Option<extern "C" fn(c_int)>,
+    /// The one called with SA_SIGINFO
+    pub sa_sigaction: Option<extern "C" fn(c_int, *mut siginfo_t, *mut c_void)>,
+}
+
+impl fmt::Debug for __sigaction_handler {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{:#x}",
+            unsafe { self.sa_handler }.map_or(SIG_DFL, |h| h as usize)
+        )
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Debug)]
+pub struct sigaction {
+    pub __sa_handler: __sigaction_handler,
+    pub sa_flags: c_ulong,
+    pub sa_restorer: Option<unsafe extern "C" fn()>,
+    pub sa_mask: sigset_t,
+}
+
 #[no_mangle]
 pub extern "C" fn kill(pid: pid_t, sig: c_int) -> c_int {
     Sys::kill(pid, sig)
@@ -198,7 +273,7 @@ pub extern "C" fn signal(
     func: Option<extern "C" fn(c_int)>,
 ) -> Option<extern "C" fn(c_int)> {
     let sa = sigaction {
-        sa_handler: func,
+        __sa_handler: __sigaction_handler { sa_handler: func },
         sa_flags: SA_RESTART as c_ulong,
         sa_restorer: Some(__restore_rt),
         sa_mask: sigset_t::default(),
@@ -208,7 +283,7 @@ pub extern "C" fn signal(
         mem::forget(old_sa);
         return unsafe { mem::transmute(SIG_ERR) };
     }
-    unsafe { old_sa.assume_init() }.sa_handler
+    unsafe { old_sa.assume_init().__sa_handler.sa_handler }
 }
 
 // #[no_mangle]
@@ -226,6 +301,11 @@ pub extern "C" fn sigprocmask(how: c_int, set: *const sigset_t, oset: *mut sigse
     Sys::sigprocmask(how, set, oset)
 }
 
+#[no_mangle]
+pub extern "C" fn sigqueue(pid: pid_t, sig: c_int, value: sigval) -> c_int {
+    Sys::sigqueue(pid, sig, SI_QUEUE, value)
+}
+
 // #[no_mangle]
 pub extern "C" fn sigrelse(sig: c_int) -> c_int {
     unimplemented!();
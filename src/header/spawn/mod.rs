@@ -0,0 +1,473 @@
+//! spawn.h implementation, following https://pubs.opengroup.org/onlinepubs/9699919799/basedefs/spawn.h.html
+//!
+//! The child is made with fork and exec, after which it applies the attributes and then the file
+//! actions in the order they were added. Redox creates the child without duplicating the address
+//! space instead, when the program lets it.
+
+use alloc::{boxed::Box, vec::Vec};
+use core::{mem, ptr};
+
+use crate::{
+    c_str::{CStr, CString},
+    header::{
+        errno::{EBADF, EINVAL, ENOENT},
+        fcntl::{F_DUPFD, F_GETFD, F_SETFD, O_CLOEXEC},
+        signal::{self, sigset_t, NSIG, SIG_SETMASK},
+        stdlib::getenv,
+    },
+    platform::{self, types::*, Pal, PalSignal, Sys},
+    sync,
+};
+
+pub const POSIX_SPAWN_RESETIDS: c_short = 0x01;
+pub const POSIX_SPAWN_SETPGROUP: c_short = 0x02;
+pub const POSIX_SPAWN_SETSIGDEF: c_short = 0x04;
+pub const POSIX_SPAWN_SETSIGMASK: c_short = 0x08;
+pub const POSIX_SPAWN_SETSID: c_short = 0x80;
+
+const FLAGS: c_short = POSIX_SPAWN_RESETIDS
+    | POSIX_SPAWN_SETPGROUP
+    | POSIX_SPAWN_SETSIGDEF
+    | POSIX_SPAWN_SETSIGMASK
+    | POSIX_SPAWN_SETSID;
+
+pub(crate) enum FileAction {
+    Close(c_int),
+    Dup2(c_int, c_int),
+    Open {
+        fd: c_int,
+        path: CString,
+        oflag: c_int,
+        mode: mode_t,
+    },
+}
+
+#[repr(C)]
+pub struct posix_spawn_file_actions_t {
+    /// A `Box<Vec<FileAction>>`
+    __actions: *mut c_void,
+    __pad: [c_int; 16],
+}
+
+impl posix_spawn_file_actions_t {
+    pub(crate) unsafe fn actions(&self) -> &[FileAction] {
+        match (self.__actions as *const Vec<FileAction>).as_ref() {
+            Some(actions) => actions,
+            None => &[],
+        }
+    }
+
+    unsafe fn push(&mut self, action: FileAction) -> c_int {
+        (*(self.__actions as *mut Vec<FileAction>)).push(action);
+        0
+    }
+}
+
+#[repr(C)]
+pub struct posix_spawnattr_t {
+    pub(crate) __flags: c_short,
+    pub(crate) __pgroup: pid_t,
+    pub(crate) __sigdefault: sigset_t,
+    pub(crate) __sigmask: sigset_t,
+    __pad: [c_int; 16],
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn posix_spawn_file_actions_addclose(
+    file_actions: *mut posix_spawn_file_actions_t,
+    fildes: c_int,
+) -> c_int {
+    if fildes < 0 {
+        return EBADF;
+    }
+    (*file_actions).push(FileAction::Close(fildes))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn posix_spawn_file_actions_adddup2(
+    file_actions: *mut posix_spawn_file_actions_t,
+    fildes: c_int,
+    newfildes: c_int,
+) -> c_int {
+    if fildes < 0 || newfildes < 0 {
+        return EBADF;
+    }
+    (*file_actions).push(FileAction::Dup2(fildes, newfildes))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn posix_spawn_file_actions_addopen(
+    file_actions: *mut posix_spawn_file_actions_t,
+    fildes: c_int,
+    path: *const c_char,
+    oflag: c_int,
+    mode: mode_t,
+) -> c_int {
+    if fildes < 0 {
+        return EBADF;
+    }
+    (*file_actions).push(FileAction::Open {
+        fd: fildes,
+        path: CStr::from_ptr(path).into(),
+        oflag,
+        mode,
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn posix_spawn_file_actions_destroy(
+    file_actions: *mut posix_spawn_file_actions_t,
+) -> c_int {
+    let actions = mem::replace(&mut (*file_actions).__actions, ptr::null_mut());
+    if !actions.is_null() {
+        drop(Box::from_raw(actions as *mut Vec<FileAction>));
+    }
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn posix_spawn_file_actions_init(
+    file_actions: *mut posix_spawn_file_actions_t,
+) -> c_int {
+    let actions: Box<Vec<FileAction>> = Box::new(Vec::new());
+    file_actions.write(posix_spawn_file_actions_t {
+        __actions: Box::into_raw(actions) as *mut c_void,
+        __pad: [0; 16],
+    });
+    0
+}
+
+#[no_mangle]
+pub extern "C" fn posix_spawnattr_destroy(_attr: *mut posix_spawnattr_t) -> c_int {
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn posix_spawnattr_getflags(
+    attr: *const posix_spawnattr_t,
+    flags: *mut c_short,
+) -> c_int {
+    *flags = (*attr).__flags;
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn posix_spawnattr_getpgroup(
+    attr: *const posix_spawnattr_t,
+    pgroup: *mut pid_t,
+) -> c_int {
+    *pgroup = (*attr).__pgroup;
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn posix_spawnattr_getsigdefault(
+    attr: *const posix_spawnattr_t,
+    sigdefault: *mut sigset_t,
+) -> c_int {
+    *sigdefault = (*attr).__sigdefault;
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn posix_spawnattr_getsigmask(
+    attr: *const posix_spawnattr_t,
+    sigmask: *mut sigset_t,
+) -> c_int {
+    *sigmask = (*attr).__sigmask;
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn posix_spawnattr_init(attr: *mut posix_spawnattr_t) -> c_int {
+    attr.write(posix_spawnattr_t {
+        __flags: 0,
+        __pgroup: 0,
+        __sigdefault: 0,
+        __sigmask: 0,
+        __pad: [0; 16],
+    });
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn posix_spawnattr_setflags(
+    attr: *mut posix_spawnattr_t,
+    flags: c_short,
+) -> c_int {
+    if flags & !FLAGS != 0 {
+        return EINVAL;
+    }
+    (*attr).__flags = flags;
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn posix_spawnattr_setpgroup(
+    attr: *mut posix_spawnattr_t,
+    pgroup: pid_t,
+) -> c_int {
+    (*attr).__pgroup = pgroup;
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn posix_spawnattr_setsigdefault(
+    attr: *mut posix_spawnattr_t,
+    sigdefault: *const sigset_t,
+) -> c_int {
+    (*attr).__sigdefault = *sigdefault;
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn posix_spawnattr_setsigmask(
+    attr: *mut posix_spawnattr_t,
+    sigmask: *const sigset_t,
+) -> c_int {
+    (*attr).__sigmask = *sigmask;
+    0
+}
+
+/// Fails with the errno of the call that just failed.
+unsafe fn check(ret: c_int) -> Result<c_int, c_int> {
+    if ret < 0 {
+        Err(platform::errno)
+    } else {
+        Ok(ret)
+    }
+}
+
+/// Does what `attr` and `actions` ask for to the current process, which is about to become the
+/// child.
+unsafe fn apply(actions: &[FileAction], attr: Option<&posix_spawnattr_t>) -> Result<(), c_int> {
+    apply_attr(attr)?;
+    if let Some(attr) = attr.filter(|attr| attr.__flags & POSIX_SPAWN_SETSIGDEF != 0) {
+        let default = signal::sigaction {
+            __sa_handler: signal::__sigaction_handler { sa_handler: None },
+            sa_flags: 0,
+            sa_restorer: None,
+            sa_mask: 0,
+        };
+        for sig in 1..=NSIG as c_int {
+            if attr.__sigdefault & (1 << (sig - 1)) != 0 {
+                // SIGKILL and SIGSTOP can't be changed, and always have their default
+                let _ = Sys::sigaction(sig, Some(&default), None);
+            }
+        }
+    }
+    apply_actions(actions)
+}
+
+/// Does what `attr` asks for to the current process, except for POSIX_SPAWN_SETSIGDEF.
+pub(crate) unsafe fn apply_attr(attr: Option<&posix_spawnattr_t>) -> Result<(), c_int> {
+    if let Some(attr) = attr {
+        let flags = attr.__flags;
+        if flags & POSIX_SPAWN_SETSIGMASK != 0 {
+            check(Sys::sigprocmask(
+                SIG_SETMASK,
+                &attr.__sigmask,
+                ptr::null_mut(),
+            ))?;
+        }
+        if flags & POSIX_SPAWN_SETSID != 0 {
+            check(Sys::setsid())?;
+        }
+        if flags & POSIX_SPAWN_SETPGROUP != 0 {
+            check(Sys::setpgid(0, attr.__pgroup))?;
+        }
+        if flags & POSIX_SPAWN_RESETIDS != 0 {
+            check(Sys::setregid(-1, Sys::getgid()))?;
+            check(Sys::setreuid(-1, Sys::getuid()))?;
+        }
+    }
+    Ok(())
+}
+
+fn apply_actions(actions: &[FileAction]) -> Result<(), c_int> {
+    for action in actions {
+        match *action {
+            FileAction::Close(fd) => {
+                // like glibc, closing something that isn't open isn't an error
+                let _ = Sys::close(fd);
+            }
+            FileAction::Dup2(fd, newfd) if fd == newfd => {
+                // the descriptor is inherited even if it's close-on-exec
+                check(Sys::fcntl(fd, F_SETFD, 0))?;
+            }
+            FileAction::Dup2(fd, newfd) => {
+                check(Sys::dup2(fd, newfd))?;
+            }
+            FileAction::Open {
+                fd,
+                ref path,
+                oflag,
+                mode,
+            } => {
+                let opened = check(Sys::open(path, oflag, mode))?;
+                if opened != fd {
+                    let res = check(Sys::dup2(opened, fd));
+                    Sys::close(opened);
+                    res?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The highest descriptor that `actions` change
+fn max_fd(actions: &[FileAction]) -> c_int {
+    actions
+        .iter()
+        .map(|action| match *action {
+            FileAction::Close(fd) | FileAction::Open { fd, .. } => fd,
+            FileAction::Dup2(fd, newfd) => fd.max(newfd),
+        })
+        .max()
+        .unwrap_or(-1)
+}
+
+/// Spawns with fork and exec. The child reports why it failed through a close-on-exec pipe, so
+/// reading nothing from it means the program was executed.
+unsafe fn fork_exec(
+    path: &CStr,
+    actions: &[FileAction],
+    attr: Option<&posix_spawnattr_t>,
+    argv: *const *mut c_char,
+    envp: *const *mut c_char,
+) -> Result<pid_t, c_int> {
+    let mut fds = [0; 2];
+    check(Sys::pipe2(&mut fds, O_CLOEXEC))?;
+
+    let pid = sync::fork::fork();
+    if pid < 0 {
+        let err = platform::errno;
+        Sys::close(fds[0]);
+        Sys::close(fds[1]);
+        return Err(err);
+    }
+
+    if pid == 0 {
+        Sys::close(fds[0]);
+
+        // The actions could change the pipe's descriptor, so it's moved out of their way
+        let mut report = fds[1];
+        let max = max_fd(actions);
+        if report <= max {
+            report = Sys::fcntl(fds[1], F_DUPFD, max + 1);
+            Sys::fcntl(report, F_SETFD, Sys::fcntl(fds[1], F_GETFD, 0));
+            Sys::close(fds[1]);
+        }
+
+        let err = match apply(actions, attr) {
+            Ok(()) => {
+                Sys::execve(path, argv, envp);
+                platform::errno
+            }
+            Err(err) => err,
+        };
+        Sys::write(report, &err.to_ne_bytes());
+        Sys::exit(127);
+    }
+
+    Sys::close(fds[1]);
+    let mut buf = [0; mem::size_of::<c_int>()];
+    let read = Sys::read(fds[0], &mut buf);
+    Sys::close(fds[0]);
+
+    if read == buf.len() as ssize_t {
+        // the child couldn't execute the program, and is reaped so the caller never sees it
+        Sys::waitpid(pid, ptr::null_mut(), 0);
+        return Err(c_int::from_ne_bytes(buf));
+    }
+    Ok(pid)
+}
+
+unsafe fn spawn(
+    pid: *mut pid_t,
+    path: &CStr,
+    file_actions: *const posix_spawn_file_actions_t,
+    attrp: *const posix_spawnattr_t,
+    argv: *const *mut c_char,
+    envp: *const *mut c_char,
+) -> c_int {
+    let actions = match file_actions.as_ref() {
+        Some(file_actions) => file_actions.actions(),
+        None => &[],
+    };
+    let attr = attrp.as_ref();
+
+    #[cfg(target_os = "redox")]
+    let native = platform::sys::spawn::spawn(path, actions, attr, argv, envp);
+    #[cfg(not(target_os = "redox"))]
+    let native = None;
+
+    match native.unwrap_or_else(|| fork_exec(path, actions, attr, argv, envp)) {
+        Ok(child) => {
+            if !pid.is_null() {
+                *pid = child;
+            }
+            0
+        }
+        Err(err) => err,
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn posix_spawn(
+    pid: *mut pid_t,
+    path: *const c_char,
+    file_actions: *const posix_spawn_file_actions_t,
+    attrp: *const posix_spawnattr_t,
+    argv: *const *mut c_char,
+    envp: *const *mut c_char,
+) -> c_int {
+    spawn(pid, CStr::from_ptr(path), file_actions, attrp, argv, envp)
+}
+
+#[cfg(target_os = "linux")]
+const PATH_SEPARATOR: u8 = b':';
+
+#[cfg(target_os = "redox")]
+const PATH_SEPARATOR: u8 = b';';
+
+/// posix_spawn, looking `file` up in PATH like execvp does.
+#[no_mangle]
+pub unsafe extern "C" fn posix_spawnp(
+    pid: *mut pid_t,
+    file: *const c_char,
+    file_actions: *const posix_spawn_file_actions_t,
+    attrp: *const posix_spawnattr_t,
+    argv: *const *mut c_char,
+    envp: *const *mut c_char,
+) -> c_int {
+    let file = CStr::from_ptr(file);
+
+    if file.to_bytes().contains(&b'/')
+        || (cfg!(target_os = "redox") && file.to_bytes().contains(&b':'))
+    {
+        return spawn(pid, file, file_actions, attrp, argv, envp);
+    }
+
+    let mut error = ENOENT;
+
+    let path_env = getenv(c_str!("PATH\0").as_ptr());
+    if !path_env.is_null() {
+        let path_env = CStr::from_ptr(path_env);
+        for path in path_env.to_bytes().split(|&b| b == PATH_SEPARATOR) {
+            let mut program = path.to_vec();
+            program.push(b'/');
+            program.extend_from_slice(file.to_bytes());
+            program.push(b'\0');
+
+            let program_c = CStr::from_bytes_with_nul(&program).unwrap();
+            match spawn(pid, program_c, file_actions, attrp, argv, envp) {
+                0 => return 0,
+                ENOENT => (),
+                other => error = other,
+            }
+        }
+    }
+    error
+}
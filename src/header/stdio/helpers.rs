@@ -64,7 +64,7 @@ pub unsafe fn _fdopen(fd: c_int, mode: *const c_char) -> Option<*mut FILE> {
     let file = File::new(fd);
     let writer = Box::new(BufWriter::new(file.get_ref()));
 
-    Some(Box::into_raw(Box::new(FILE {
+    let stream = Box::into_raw(Box::new(FILE {
         lock: Mutex::new(()),
 
         file,
@@ -78,5 +78,7 @@ pub unsafe fn _fdopen(fd: c_int, mode: *const c_char) -> Option<*mut FILE> {
         pid: None,
 
         orientation: 0,
-    })))
+    }));
+    super::OPEN.lock().push(stream as usize);
+    Some(stream)
 }
@@ -22,11 +22,11 @@ use crate::{
         errno::{self, STR_ERROR},
         fcntl, stdlib,
         string::{self, strlen},
-        unistd,
+        sys_socket, unistd,
     },
     io::{self, BufRead, BufWriter, LineWriter, Read, Write},
     platform::{self, errno, types::*, Pal, Sys, WriteByte},
-    sync::Mutex,
+    sync::{fork::ForkLock, Mutex},
 };
 
 pub use self::constants::*;
@@ -41,7 +41,7 @@ mod getdelim;
 mod ext;
 mod helpers;
 mod lookaheadreader;
-mod printf;
+pub(crate) mod printf;
 mod scanf;
 use lookaheadreader::LookAheadReader;
 static mut TMPNAM_BUF: [c_char; L_tmpnam as usize + 1] = [0; L_tmpnam as usize + 1];
@@ -263,18 +263,21 @@ pub extern "C" fn cuserid(_s: *mut c_char) -> *mut c_char {
 #[no_mangle]
 pub unsafe extern "C" fn fclose(stream: *mut FILE) -> c_int {
     let stream = &mut *stream;
+    if stream.flags & constants::F_PERM == 0 {
+        // Before locking it, as fork takes this lock first
+        let address = stream as *mut FILE as usize;
+        OPEN.lock().retain(|&open| open != address);
+    }
     flockfile(stream);
 
     let mut r = stream.flush().is_err();
-    let close = Sys::close(*stream.file) < 0;
-    r = r || close;
 
     if stream.flags & constants::F_PERM == 0 {
         // Not one of stdin, stdout or stderr
-        let mut stream = Box::from_raw(stream);
-        // Reference files aren't closed on drop, so pretend to be a reference
-        stream.file.reference = true;
+        let stream = Box::from_raw(stream);
+        r = stream.file.close().is_err() || r;
     } else {
+        r = Sys::close(*stream.file) < 0 || r;
         funlockfile(stream);
     }
 
@@ -786,11 +789,14 @@ pub unsafe extern "C" fn popen(command: *const c_char, mode: *const c_char) -> *
 
     let mut cloexec = false;
     let mut write_opt = None;
+    let mut read_write = false;
     for b in mode.to_bytes().iter() {
         match b {
             b'e' => cloexec = true,
             b'r' if write_opt.is_none() => write_opt = Some(false),
             b'w' if write_opt.is_none() => write_opt = Some(true),
+            // "r+" talks to the command in both directions, over a socket pair
+            b'+' if write_opt == Some(false) => read_write = true,
             _ => {
                 errno = errno::EINVAL;
                 return ptr::null_mut();
@@ -807,7 +813,17 @@ pub unsafe extern "C" fn popen(command: *const c_char, mode: *const c_char) -> *
     };
 
     let mut pipes = [-1, -1];
-    if unistd::pipe(pipes.as_mut_ptr()) != 0 {
+    let status = if read_write {
+        sys_socket::socketpair(
+            sys_socket::constants::AF_UNIX,
+            sys_socket::constants::SOCK_STREAM,
+            0,
+            pipes.as_mut_ptr(),
+        )
+    } else {
+        unistd::pipe(pipes.as_mut_ptr())
+    };
+    if status != 0 {
         return ptr::null_mut();
     }
 
@@ -831,10 +847,13 @@ pub unsafe extern "C" fn popen(command: *const c_char, mode: *const c_char) -> *
         // Setup up stdin or stdout
         //TODO: dup errors are ignored, should they be?
         {
-            if write {
-                unistd::dup2(0, pipes[0]);
+            if read_write {
+                unistd::dup2(pipes[1], 0);
+                unistd::dup2(pipes[1], 1);
+            } else if write {
+                unistd::dup2(pipes[0], 0);
             } else {
-                unistd::dup2(1, pipes[1]);
+                unistd::dup2(pipes[1], 1);
             }
 
             unistd::close(pipes[0]);
@@ -847,7 +866,10 @@ pub unsafe extern "C" fn popen(command: *const c_char, mode: *const c_char) -> *
 
         unreachable!();
     } else if child_pid > 0 {
-        let (fd, fd_mode) = if write {
+        let (fd, fd_mode) = if read_write {
+            unistd::close(pipes[1]);
+            (pipes[0], if cloexec { c_str!("r+e") } else { c_str!("r+") })
+        } else if write {
             unistd::close(pipes[0]);
             (pipes[1], if cloexec { c_str!("we") } else { c_str!("w") })
         } else {
@@ -1008,7 +1030,7 @@ pub unsafe extern "C" fn tempnam(dir: *const c_char, pfx: *const c_char) -> *mut
 
     // allocate enough for dirname "/" prefix "XXXXXX\0"
     let mut out_buf =
-        platform::alloc(dirname_len + 1 + prefix_len + L_tmpnam as usize + 1) as *mut c_char;
+        stdlib::malloc(dirname_len + 1 + prefix_len + L_tmpnam as usize + 1) as *mut c_char;
 
     if !out_buf.is_null() {
         // copy the directory name and prefix into the allocated buffer
@@ -1021,7 +1043,7 @@ pub unsafe extern "C" fn tempnam(dir: *const c_char, pfx: *const c_char) -> *mut
         // use the same mechanism as tmpnam to get the file name
         if tmpnam_inner(out_buf, dirname_len + 1 + prefix_len).is_null() {
             // failed to find a valid file name, so we need to free the buffer
-            platform::free(out_buf as _);
+            stdlib::free(out_buf as _);
             out_buf = ptr::null_mut();
         }
     }
@@ -1052,6 +1074,48 @@ pub unsafe extern "C" fn tmpfile() -> *mut FILE {
     fp
 }
 
+// Large file support's names, which glibc's binaries link to. Offsets are 64 bits already, so
+// bits/stdio.h defines them to the usual names for sources.
+
+#[no_mangle]
+pub unsafe extern "C" fn fgetpos64(stream: *mut FILE, pos: *mut fpos_t) -> c_int {
+    fgetpos(stream, pos)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn fopen64(filename: *const c_char, mode: *const c_char) -> *mut FILE {
+    fopen(filename, mode)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn freopen64(
+    filename: *const c_char,
+    mode: *const c_char,
+    stream: &mut FILE,
+) -> *mut FILE {
+    freopen(filename, mode, stream)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn fseeko64(stream: *mut FILE, off: off_t, whence: c_int) -> c_int {
+    fseeko(stream, off, whence)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn fsetpos64(stream: *mut FILE, pos: *const fpos_t) -> c_int {
+    fsetpos(stream, pos)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn ftello64(stream: *mut FILE) -> off_t {
+    ftello(stream)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn tmpfile64() -> *mut FILE {
+    tmpfile()
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn tmpnam(s: *mut c_char) -> *mut c_char {
     let buf = if s.is_null() {
@@ -1175,3 +1239,36 @@ pub unsafe fn flush_io_streams() {
     flush(stdout);
     flush(stderr);
 }
+
+/// The streams fopen, fdopen and popen made, and haven't been closed yet, for fork to lock
+static OPEN: Mutex<Vec<usize>> = Mutex::new(Vec::new());
+
+/// The locks of the streams, which fork holds so that their buffers aren't copied in the middle
+/// of a write
+pub(crate) struct Streams;
+
+impl Streams {
+    unsafe fn each(f: impl Fn(&Mutex<()>)) {
+        for &stream in &[stdin, stdout, stderr] {
+            f(&(*stream).lock);
+        }
+        for &stream in OPEN.manual_content().iter() {
+            f(&(*(stream as *mut FILE)).lock);
+        }
+    }
+}
+
+impl ForkLock for Streams {
+    unsafe fn prepare(&self) {
+        OPEN.manual_lock();
+        Self::each(|lock| lock.prepare());
+    }
+    unsafe fn parent(&self) {
+        Self::each(|lock| lock.parent());
+        OPEN.manual_unlock();
+    }
+    unsafe fn child(&self) {
+        Self::each(|lock| lock.child());
+        OPEN.manual_reinit();
+    }
+}
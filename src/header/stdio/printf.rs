@@ -4,10 +4,11 @@ use alloc::{
     string::{String, ToString},
     vec::Vec,
 };
-use core::{char, cmp, f64, ffi::VaList, fmt, num::FpCategory, ops::Range, slice};
+use core::{ffi::VaList, fmt, num::FpCategory, ops::Range, ptr, slice};
 
 use crate::{
-    header::errno::EILSEQ,
+    float::{self, Float, LongDouble},
+    header::{locale, stdlib::MB_CUR_MAX, wchar::wcrtomb},
     platform::{self, types::*},
 };
 
@@ -70,6 +71,7 @@ impl Number {
             VaArg::c_longlong(i) => i as usize,
             VaArg::c_short(i) => i as usize,
             VaArg::intmax_t(i) => i as usize,
+            VaArg::long_double(i) => Float::from_long_double(i).to_f64() as usize,
             VaArg::pointer(i) => i as usize,
             VaArg::ptrdiff_t(i) => i as usize,
             VaArg::ssize_t(i) => i as usize,
@@ -86,6 +88,7 @@ enum VaArg {
     c_longlong(c_longlong),
     c_short(c_short),
     intmax_t(intmax_t),
+    long_double(LongDouble),
     pointer(*const c_void),
     ptrdiff_t(ptrdiff_t),
     ssize_t(ssize_t),
@@ -132,6 +135,12 @@ impl VaArg {
                 VaArg::ssize_t(ap.arg::<ssize_t>())
             }
 
+            // Both L and ll make it a long double
+            (FmtKind::AnyNotation, IntKind::LongLong)
+            | (FmtKind::Decimal, IntKind::LongLong)
+            | (FmtKind::Scientific, IntKind::LongLong) => {
+                VaArg::long_double(float::va_arg_long_double(ap))
+            }
             (FmtKind::AnyNotation, _) | (FmtKind::Decimal, _) | (FmtKind::Scientific, _) => {
                 VaArg::c_double(ap.arg::<c_double>())
             }
@@ -158,6 +167,7 @@ impl VaArg {
             c_longlong: c_longlong,
             c_short: c_short,
             intmax_t: intmax_t,
+            long_double: LongDouble,
             pointer: *const c_void,
             ptrdiff_t: ptrdiff_t,
             ssize_t: ssize_t,
@@ -171,6 +181,7 @@ impl VaArg {
             VaArg::c_longlong(i) => Untyped { c_longlong: i },
             VaArg::c_short(i) => Untyped { c_short: i },
             VaArg::intmax_t(i) => Untyped { intmax_t: i },
+            VaArg::long_double(i) => Untyped { long_double: i },
             VaArg::pointer(i) => Untyped { pointer: i },
             VaArg::ptrdiff_t(i) => Untyped { ptrdiff_t: i },
             VaArg::ssize_t(i) => Untyped { ssize_t: i },
@@ -208,6 +219,9 @@ impl VaArg {
                 VaArg::ssize_t(untyped.ssize_t)
             }
 
+            (FmtKind::AnyNotation, IntKind::LongLong)
+            | (FmtKind::Decimal, IntKind::LongLong)
+            | (FmtKind::Scientific, IntKind::LongLong) => VaArg::long_double(untyped.long_double),
             (FmtKind::AnyNotation, _) | (FmtKind::Decimal, _) | (FmtKind::Scientific, _) => {
                 VaArg::c_double(untyped.c_double)
             }
@@ -341,63 +355,63 @@ fn pad<W: Write>(
     Ok(())
 }
 
-fn abs(float: c_double) -> c_double {
-    // Don't ask me whe float.abs() seems absent...
-    if float.is_sign_negative() {
-        -float
-    } else {
-        float
-    }
-}
-
-fn float_string(float: c_double, precision: usize, trim: bool) -> String {
-    let mut string = format!("{:.p$}", float, p = precision);
-    if trim && string.contains('.') {
+/// The digits of `float`, with its sign, the locale's decimal point and without the zeros
+/// at the end of the fraction if `trim`
+fn float_string(float: &Float, mut digits: String, trim: bool) -> String {
+    if trim && digits.contains('.') {
         let truncate = {
-            let slice = string.trim_end_matches('0');
+            let slice = digits.trim_end_matches('0');
             let mut truncate = slice.len();
             if slice.ends_with('.') {
                 truncate -= 1;
             }
             truncate
         };
-        string.truncate(truncate);
+        digits.truncate(truncate);
     }
-    string
-}
-
-fn float_exp(mut float: c_double) -> (c_double, isize) {
-    let mut exp: isize = 0;
-    while abs(float) >= 10.0 {
-        float /= 10.0;
-        exp += 1;
+    if let Some(dot) = digits.find('.') {
+        let decimal_point = unsafe { locale::decimal_point() };
+        if decimal_point != "." {
+            digits.replace_range(dot..dot + 1, decimal_point);
+        }
     }
-    while f64::EPSILON < abs(float) && abs(float) < 1.0 {
-        float *= 10.0;
-        exp -= 1;
+    if float.negative {
+        digits.insert(0, '-');
     }
-    (float, exp)
+    digits
+}
+
+/// Puts the thousands separator of the locale between the groups of digits of the integer part
+/// of `string`, for the ' flag.
+fn grouped(string: &str) -> String {
+    let start = if string.starts_with('-') { 1 } else { 0 };
+    let end = string[start..]
+        .find(|c: char| !c.is_ascii_digit())
+        .map_or(string.len(), |end| start + end);
+    let digits = unsafe { locale::group_digits(string[start..end].as_bytes(), false) };
+
+    let mut grouped = String::from(&string[..start]);
+    grouped.push_str(&String::from_utf8_lossy(&digits));
+    grouped.push_str(&string[end..]);
+    grouped
 }
 
 fn fmt_float_exp<W: Write>(
     w: &mut W,
     exp_fmt: u8,
-    trim: bool,
-    precision: usize,
-    float: c_double,
+    string: String,
     exp: isize,
     left: bool,
     pad_space: usize,
     pad_zero: usize,
 ) -> io::Result<()> {
-    let mut exp2 = exp;
+    let mut exp2 = exp.unsigned_abs();
     let mut exp_len = 1;
     while exp2 >= 10 {
         exp2 /= 10;
         exp_len += 1;
     }
 
-    let string = float_string(float, precision, trim);
     let len = string.len() + 2 + 2.max(exp_len);
 
     pad(w, !left, b' ', len..pad_space)?;
@@ -417,14 +431,15 @@ fn fmt_float_exp<W: Write>(
 
 fn fmt_float_normal<W: Write>(
     w: &mut W,
-    trim: bool,
-    precision: usize,
-    float: c_double,
+    group: bool,
+    mut string: String,
     left: bool,
     pad_space: usize,
     pad_zero: usize,
 ) -> io::Result<usize> {
-    let string = float_string(float, precision, trim);
+    if group {
+        string = grouped(&string);
+    }
 
     pad(w, !left, b' ', string.len()..pad_space)?;
     let bytes = if string.starts_with('-') {
@@ -441,12 +456,12 @@ fn fmt_float_normal<W: Write>(
 }
 
 /// Write ±infinity or ±NaN representation for any floating-point style
-fn fmt_float_nonfinite<W: Write>(w: &mut W, float: c_double, case: FmtCase) -> io::Result<()> {
-    if float.is_sign_negative() {
+fn fmt_float_nonfinite<W: Write>(w: &mut W, float: &Float, case: FmtCase) -> io::Result<()> {
+    if float.negative {
         w.write_all(&[b'-'])?;
     }
 
-    let nonfinite_str = match float.classify() {
+    let nonfinite_str = match float.class {
         FpCategory::Infinite => match case {
             FmtCase::Lower => INF_STR_LOWER,
             FmtCase::Upper => INF_STR_UPPER,
@@ -466,6 +481,15 @@ fn fmt_float_nonfinite<W: Write>(w: &mut W, float: c_double, case: FmtCase) -> i
     Ok(())
 }
 
+/// The floating point argument of %e, %f or %g
+fn float_arg(arg: VaArg) -> Float {
+    match arg {
+        VaArg::c_double(i) => Float::from_f64(i),
+        VaArg::long_double(i) => Float::from_long_double(i),
+        _ => panic!("this should not be possible"),
+    }
+}
+
 #[derive(Clone, Copy)]
 struct PrintfIter {
     format: *const u8,
@@ -476,6 +500,7 @@ struct PrintfArg {
     alternate: bool,
     zero: bool,
     left: bool,
+    grouping: bool,
     sign_reserve: bool,
     sign_always: bool,
     min_width: Number,
@@ -521,6 +546,7 @@ impl Iterator for PrintfIter {
             let mut alternate = false;
             let mut zero = false;
             let mut left = false;
+            let mut grouping = false;
             let mut sign_reserve = false;
             let mut sign_always = false;
 
@@ -529,6 +555,7 @@ impl Iterator for PrintfIter {
                     b'#' => alternate = true,
                     b'0' => zero = true,
                     b'-' => left = true,
+                    b'\'' => grouping = true,
                     b' ' => sign_reserve = true,
                     b'+' => sign_always = true,
                     _ => break,
@@ -596,6 +623,7 @@ impl Iterator for PrintfIter {
                 alternate,
                 zero,
                 left,
+                grouping,
                 sign_reserve,
                 sign_always,
                 min_width,
@@ -666,6 +694,7 @@ unsafe fn inner_printf<W: Write>(w: W, format: *const c_char, mut ap: VaList) ->
         let alternate = arg.alternate;
         let zero = arg.zero;
         let mut left = arg.left;
+        let grouping = arg.grouping;
         let sign_reserve = arg.sign_reserve;
         let sign_always = arg.sign_always;
         let min_width = arg.min_width.resolve(&mut varargs, &mut ap);
@@ -706,6 +735,7 @@ unsafe fn inner_printf<W: Write>(w: W, format: *const c_char, mut ap: VaList) ->
                 let string = match varargs.get(index, &mut ap, Some((arg.fmtkind, arg.intkind))) {
                     VaArg::c_char(i) => i.to_string(),
                     VaArg::c_double(i) => panic!("this should not be possible"),
+                    VaArg::long_double(i) => panic!("this should not be possible"),
                     VaArg::c_int(i) => i.to_string(),
                     VaArg::c_long(i) => i.to_string(),
                     VaArg::c_longlong(i) => i.to_string(),
@@ -716,6 +746,7 @@ unsafe fn inner_printf<W: Write>(w: W, format: *const c_char, mut ap: VaList) ->
                     VaArg::ssize_t(i) => i.to_string(),
                     VaArg::wint_t(_) => unreachable!("this should not be possible"),
                 };
+                let string = if grouping { grouped(&string) } else { string };
                 let positive = !string.starts_with('-');
                 let zero = precision == Some(0) && string == "0";
 
@@ -754,6 +785,7 @@ unsafe fn inner_printf<W: Write>(w: W, format: *const c_char, mut ap: VaList) ->
                 let string = match varargs.get(index, &mut ap, Some((arg.fmtkind, arg.intkind))) {
                     VaArg::c_char(i) => fmt_int(fmt, i as c_uchar),
                     VaArg::c_double(i) => panic!("this should not be possible"),
+                    VaArg::long_double(i) => panic!("this should not be possible"),
                     VaArg::c_int(i) => fmt_int(fmt, i as c_uint),
                     VaArg::c_long(i) => fmt_int(fmt, i as c_ulong),
                     VaArg::c_longlong(i) => fmt_int(fmt, i as c_ulonglong),
@@ -764,6 +796,11 @@ unsafe fn inner_printf<W: Write>(w: W, format: *const c_char, mut ap: VaList) ->
                     VaArg::ssize_t(i) => fmt_int(fmt, i as size_t),
                     VaArg::wint_t(_) => unreachable!("this should not be possible"),
                 };
+                let string = if grouping && fmt == b'u' {
+                    grouped(&string)
+                } else {
+                    string
+                };
                 let zero = precision == Some(0) && string == "0";
 
                 // If this int is padded out to be larger than it is, don't
@@ -807,63 +844,47 @@ unsafe fn inner_printf<W: Write>(w: W, format: *const c_char, mut ap: VaList) ->
                 pad(w, left, b' ', final_len..pad_space)?;
             }
             FmtKind::Scientific => {
-                let float = match varargs.get(index, &mut ap, Some((arg.fmtkind, arg.intkind))) {
-                    VaArg::c_double(i) => i,
-                    _ => panic!("this should not be possible"),
-                };
+                let float =
+                    float_arg(varargs.get(index, &mut ap, Some((arg.fmtkind, arg.intkind))));
                 if float.is_finite() {
-                    let (float, exp) = float_exp(float);
-                    let precision = precision.unwrap_or(6);
+                    let (digits, exp) = float.scientific(precision.unwrap_or(6));
+                    let string = float_string(&float, digits, false);
 
-                    fmt_float_exp(
-                        w, fmt, false, precision, float, exp, left, pad_space, pad_zero,
-                    )?;
+                    fmt_float_exp(w, fmt, string, exp, left, pad_space, pad_zero)?;
                 } else {
-                    fmt_float_nonfinite(w, float, fmtcase.unwrap())?;
+                    fmt_float_nonfinite(w, &float, fmtcase.unwrap())?;
                 }
             }
             FmtKind::Decimal => {
-                let float = match varargs.get(index, &mut ap, Some((arg.fmtkind, arg.intkind))) {
-                    VaArg::c_double(i) => i,
-                    _ => panic!("this should not be possible"),
-                };
+                let float =
+                    float_arg(varargs.get(index, &mut ap, Some((arg.fmtkind, arg.intkind))));
                 if float.is_finite() {
-                    let precision = precision.unwrap_or(6);
+                    let string = float_string(&float, float.fixed(precision.unwrap_or(6)), false);
 
-                    fmt_float_normal(w, false, precision, float, left, pad_space, pad_zero)?;
+                    fmt_float_normal(w, grouping, string, left, pad_space, pad_zero)?;
                 } else {
-                    fmt_float_nonfinite(w, float, fmtcase.unwrap())?;
+                    fmt_float_nonfinite(w, &float, fmtcase.unwrap())?;
                 }
             }
             FmtKind::AnyNotation => {
-                let float = match varargs.get(index, &mut ap, Some((arg.fmtkind, arg.intkind))) {
-                    VaArg::c_double(i) => i,
-                    _ => panic!("this should not be possible"),
-                };
+                let float =
+                    float_arg(varargs.get(index, &mut ap, Some((arg.fmtkind, arg.intkind))));
                 if float.is_finite() {
-                    let (log, exp) = float_exp(float);
                     let exp_fmt = b'E' | (fmt & 32);
-                    let precision = precision.unwrap_or(6);
-                    let use_exp_format = exp < -4 || exp >= precision as isize;
-
-                    if use_exp_format {
-                        // Length of integral part will always be 1 here,
-                        // because that's how x/floor(log10(x)) works
-                        let precision = precision.saturating_sub(1);
-                        fmt_float_exp(
-                            w, exp_fmt, true, precision, log, exp, left, pad_space, pad_zero,
-                        )?;
+                    let precision = precision.unwrap_or(6).max(1);
+                    // The exponent %e would write, after rounding to the precision
+                    let (digits, exp) = float.scientific(precision - 1);
+
+                    if exp < -4 || exp >= precision as isize {
+                        let string = float_string(&float, digits, !alternate);
+                        fmt_float_exp(w, exp_fmt, string, exp, left, pad_space, pad_zero)?;
                     } else {
-                        // Length of integral part will be the exponent of
-                        // the unused logarithm, unless the exponent is
-                        // negative which in case the integral part must
-                        // of course be 0, 1 in length
-                        let len = 1 + cmp::max(0, exp) as usize;
-                        let precision = precision.saturating_sub(len);
-                        fmt_float_normal(w, true, precision, float, left, pad_space, pad_zero)?;
+                        let digits = float.fixed((precision as isize - 1 - exp) as usize);
+                        let string = float_string(&float, digits, !alternate);
+                        fmt_float_normal(w, grouping, string, left, pad_space, pad_zero)?;
                     }
                 } else {
-                    fmt_float_nonfinite(w, float, fmtcase.unwrap())?;
+                    fmt_float_nonfinite(w, &float, fmtcase.unwrap())?;
                 }
             }
             FmtKind::String => {
@@ -878,27 +899,25 @@ unsafe fn inner_printf<W: Write>(w: W, format: *const c_char, mut ap: VaList) ->
                     let max = precision.unwrap_or(::core::usize::MAX);
 
                     if intkind == IntKind::Long || intkind == IntKind::LongLong {
-                        // Handle wchar_t
+                        // Handle wchar_t, in the multibyte character set of the locale
                         let mut ptr = ptr as *const wchar_t;
-                        let mut string = String::new();
+                        let mut string = Vec::new();
 
                         while *ptr != 0 {
-                            let c = match char::from_u32(*ptr as _) {
-                                Some(c) => c,
-                                None => {
-                                    platform::errno = EILSEQ;
-                                    return Err(io::last_os_error());
-                                }
-                            };
-                            if string.len() + c.len_utf8() >= max {
+                            let mut bytes = [0; MB_CUR_MAX as usize];
+                            let len = wcrtomb(bytes.as_mut_ptr(), *ptr, ptr::null_mut());
+                            if len == -1isize as usize {
+                                return Err(io::last_os_error());
+                            }
+                            if string.len() + len > max {
                                 break;
                             }
-                            string.push(c);
+                            string.extend(bytes[..len].iter().map(|&b| b as u8));
                             ptr = ptr.add(1);
                         }
 
                         pad(w, !left, b' ', string.len()..pad_space)?;
-                        w.write_all(string.as_bytes())?;
+                        w.write_all(&string)?;
                         pad(w, left, b' ', string.len()..pad_space)?;
                     } else {
                         let mut len = 0;
@@ -919,17 +938,15 @@ unsafe fn inner_printf<W: Write>(w: W, format: *const c_char, mut ap: VaList) ->
                     pad(w, left, b' ', 1..pad_space)?;
                 }
                 VaArg::wint_t(c) => {
-                    let c = match char::from_u32(c as _) {
-                        Some(c) => c,
-                        None => {
-                            platform::errno = EILSEQ;
-                            return Err(io::last_os_error());
-                        }
-                    };
-                    let mut buf = [0; 4];
+                    let mut bytes = [0; MB_CUR_MAX as usize];
+                    let len = wcrtomb(bytes.as_mut_ptr(), c as wchar_t, ptr::null_mut());
+                    if len == -1isize as usize {
+                        return Err(io::last_os_error());
+                    }
+                    let bytes: Vec<u8> = bytes[..len].iter().map(|&b| b as u8).collect();
 
                     pad(w, !left, b' ', 1..pad_space)?;
-                    w.write_all(c.encode_utf8(&mut buf).as_bytes())?;
+                    w.write_all(&bytes)?;
                     pad(w, left, b' ', 1..pad_space)?;
                 }
                 _ => unreachable!("this should not be possible"),
@@ -1,5 +1,8 @@
 use super::lookaheadreader::LookAheadReader;
-use crate::platform::types::*;
+use crate::{
+    float::{Float, LongDouble, LONG_DOUBLE},
+    platform::types::*,
+};
 use alloc::{string::String, vec::Vec};
 use core::ffi::VaList as va_list;
 
@@ -261,7 +264,18 @@ unsafe fn inner_scanf(
                     }
 
                     if float {
-                        if kind == IntKind::Long || kind == IntKind::LongLong {
+                        if kind == IntKind::LongLong {
+                            // Both L and ll make it a long double
+                            let n = match Float::parse(n.as_bytes(), b".", &LONG_DOUBLE) {
+                                Some((float, _, _)) => float,
+                                None if n.is_empty() => Float::from_f64(0.0),
+                                None => return Err(0),
+                            };
+                            if !ignore {
+                                *ap.arg::<*mut LongDouble>() = n.to_long_double();
+                                matched += 1;
+                            }
+                        } else if kind == IntKind::Long {
                             parse_type!(c_double);
                         } else {
                             parse_type!(c_float);
@@ -16,6 +16,8 @@ use crate::{
         errno::{self, *},
         fcntl::*,
         limits,
+        locale::{self, locale_t},
+        malloc, pty,
         stdio::flush_io_streams,
         string::*,
         time::constants::CLOCK_MONOTONIC,
@@ -24,6 +26,7 @@ use crate::{
     },
     ld_so,
     platform::{self, types::*, Pal, Sys},
+    sync::Mutex,
 };
 
 mod rand48;
@@ -43,6 +46,9 @@ static mut ATEXIT_FUNCS: [Option<extern "C" fn()>; 32] = [None; 32];
 static mut L64A_BUFFER: [c_char; 7] = [0; 7]; // up to 6 digits plus null terminator
 static mut RNG: Option<XorShiftRng> = None;
 
+/// Held while the environment is changed, so that fork doesn't copy it half-changed
+pub(crate) static ENVIRON_LOCK: Mutex<()> = Mutex::new(());
+
 lazy_static! {
     static ref RNG_SAMPLER: Uniform<c_int> = Uniform::new_inclusive(0, RAND_MAX);
 }
@@ -101,6 +107,7 @@ pub extern "C" fn abs(i: c_int) -> c_int {
 }
 
 #[no_mangle]
+#[linkage = "weak"] // may be replaced by another allocator
 pub unsafe extern "C" fn aligned_alloc(alignment: size_t, size: size_t) -> *mut c_void {
     if size % alignment == 0 {
         /* The size-is-multiple-of-alignment requirement is the only
@@ -212,6 +219,7 @@ pub unsafe extern "C" fn bsearch(
 }
 
 #[no_mangle]
+#[linkage = "weak"] // may be replaced by another allocator
 pub unsafe extern "C" fn calloc(nelem: size_t, elsize: size_t) -> *mut c_void {
     //Handle possible integer overflow in size calculation
     match nelem.checked_mul(elsize) {
@@ -314,7 +322,11 @@ pub extern "C" fn fcvt(
 }
 
 #[no_mangle]
+#[linkage = "weak"] // may be replaced by another allocator
 pub unsafe extern "C" fn free(ptr: *mut c_void) {
+    if let Some(hook) = malloc::__free_hook {
+        return hook(ptr, ptr::null());
+    }
     platform::free(ptr);
 }
 
@@ -364,9 +376,17 @@ pub extern "C" fn getsubopt(
     unimplemented!();
 }
 
-// #[no_mangle]
-pub extern "C" fn grantpt(fildes: c_int) -> c_int {
-    unimplemented!();
+/// Slaves already belong to whoever opened their master, so this only checks `fildes` is one.
+#[no_mangle]
+pub unsafe extern "C" fn grantpt(fildes: c_int) -> c_int {
+    let mut buf = [0; limits::PATH_MAX];
+    match ptsname_r(fildes, buf.as_mut_ptr(), buf.len()) {
+        0 => 0,
+        err => {
+            platform::errno = err;
+            -1
+        }
+    }
 }
 
 #[no_mangle]
@@ -500,7 +520,11 @@ pub unsafe extern "C" fn lrand48() -> c_long {
 }
 
 #[no_mangle]
+#[linkage = "weak"] // may be replaced by another allocator
 pub unsafe extern "C" fn malloc(size: size_t) -> *mut c_void {
+    if let Some(hook) = malloc::__malloc_hook {
+        return hook(size, ptr::null());
+    }
     let ptr = platform::alloc(size);
     if ptr.is_null() {
         platform::errno = ENOMEM;
@@ -509,7 +533,11 @@ pub unsafe extern "C" fn malloc(size: size_t) -> *mut c_void {
 }
 
 #[no_mangle]
+#[linkage = "weak"] // may be replaced by another allocator
 pub unsafe extern "C" fn memalign(alignment: size_t, size: size_t) -> *mut c_void {
+    if let Some(hook) = malloc::__memalign_hook {
+        return hook(alignment, size, ptr::null());
+    }
     if alignment.is_power_of_two() {
         let ptr = platform::alloc_align(size, alignment);
         if ptr.is_null() {
@@ -647,6 +675,24 @@ pub extern "C" fn mkstemps(name: *mut c_char, suffix_len: c_int) -> c_int {
     mkostemps(name, suffix_len, 0)
 }
 
+// Large file support's names, for glibc's binaries. Files are always opened with 64-bit offsets.
+#[no_mangle]
+pub extern "C" fn mkostemp64(name: *mut c_char, flags: c_int) -> c_int {
+    mkostemp(name, flags)
+}
+#[no_mangle]
+pub extern "C" fn mkostemps64(name: *mut c_char, suffix_len: c_int, flags: c_int) -> c_int {
+    mkostemps(name, suffix_len, flags)
+}
+#[no_mangle]
+pub extern "C" fn mkstemp64(name: *mut c_char) -> c_int {
+    mkstemp(name)
+}
+#[no_mangle]
+pub extern "C" fn mkstemps64(name: *mut c_char, suffix_len: c_int) -> c_int {
+    mkstemps(name, suffix_len)
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn mrand48() -> c_long {
     let new_xsubi_value = rand48::generator_step(&mut rand48::DEFAULT_XSUBI);
@@ -660,6 +706,7 @@ pub unsafe extern "C" fn nrand48(xsubi: *mut c_ushort) -> c_long {
 }
 
 #[no_mangle]
+#[linkage = "weak"] // may be replaced by another allocator
 pub unsafe extern "C" fn posix_memalign(
     memptr: *mut *mut c_void,
     alignment: size_t,
@@ -668,7 +715,10 @@ pub unsafe extern "C" fn posix_memalign(
     const VOID_PTR_SIZE: usize = mem::size_of::<*mut c_void>();
 
     if alignment % VOID_PTR_SIZE == 0 && alignment.is_power_of_two() {
-        let ptr = platform::alloc_align(size, alignment);
+        let ptr = match malloc::__memalign_hook {
+            Some(hook) => hook(alignment, size, ptr::null()),
+            None => platform::alloc_align(size, alignment),
+        };
         *memptr = ptr;
         if ptr.is_null() {
             ENOMEM
@@ -681,9 +731,29 @@ pub unsafe extern "C" fn posix_memalign(
     }
 }
 
-// #[no_mangle]
-pub extern "C" fn ptsname(fildes: c_int) -> *mut c_char {
-    unimplemented!();
+#[no_mangle]
+pub unsafe extern "C" fn posix_openpt(flags: c_int) -> c_int {
+    pty::sys::posix_openpt(flags)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn ptsname(fildes: c_int) -> *mut c_char {
+    static mut PTSNAME: [c_char; limits::PATH_MAX] = [0; limits::PATH_MAX];
+    match ptsname_r(fildes, PTSNAME.as_mut_ptr(), PTSNAME.len()) {
+        0 => PTSNAME.as_mut_ptr(),
+        err => {
+            platform::errno = err;
+            ptr::null_mut()
+        }
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn ptsname_r(fildes: c_int, buf: *mut c_char, buflen: size_t) -> c_int {
+    if buf.is_null() {
+        return EINVAL;
+    }
+    pty::sys::ptsname(fildes, slice::from_raw_parts_mut(buf as *mut u8, buflen))
 }
 
 unsafe fn put_new_env(insert: *mut c_char) {
@@ -706,6 +776,7 @@ unsafe fn put_new_env(insert: *mut c_char) {
 #[no_mangle]
 pub unsafe extern "C" fn putenv(insert: *mut c_char) -> c_int {
     assert_ne!(insert, ptr::null_mut(), "putenv(NULL)");
+    let _guard = ENVIRON_LOCK.lock();
     if let Some((i, _)) = find_env(insert) {
         // XXX: The POSIX manual states that environment variables can be *set* via the `environ`
         // global variable. While we can check if a pointer belongs to our allocator, or check
@@ -804,7 +875,11 @@ pub unsafe extern "C" fn random() -> c_long {
 }
 
 #[no_mangle]
+#[linkage = "weak"] // may be replaced by another allocator
 pub unsafe extern "C" fn realloc(ptr: *mut c_void, size: size_t) -> *mut c_void {
+    if let Some(hook) = malloc::__realloc_hook {
+        return hook(ptr, size, ptr::null());
+    }
     let new_ptr = platform::realloc(ptr, size);
     if new_ptr.is_null() {
         platform::errno = ENOMEM;
@@ -812,6 +887,72 @@ pub unsafe extern "C" fn realloc(ptr: *mut c_void, size: size_t) -> *mut c_void
     new_ptr
 }
 
+#[no_mangle]
+pub unsafe extern "C" fn reallocarray(
+    ptr: *mut c_void,
+    nmemb: size_t,
+    size: size_t,
+) -> *mut c_void {
+    match nmemb.checked_mul(size) {
+        Some(total) => realloc(ptr, total),
+        None => {
+            platform::errno = ENOMEM;
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Like reallocarray, but with the memory added zeroed and, when the memory moves, the old memory
+/// cleared before being freed, as in OpenBSD.
+#[no_mangle]
+pub unsafe extern "C" fn recallocarray(
+    ptr: *mut c_void,
+    oldnmemb: size_t,
+    nmemb: size_t,
+    size: size_t,
+) -> *mut c_void {
+    if ptr.is_null() {
+        return calloc(nmemb, size);
+    }
+    let new_size = match nmemb.checked_mul(size) {
+        Some(new_size) => new_size,
+        None => {
+            platform::errno = ENOMEM;
+            return ptr::null_mut();
+        }
+    };
+    let old_size = match oldnmemb.checked_mul(size) {
+        Some(old_size) => old_size,
+        None => {
+            platform::errno = EINVAL;
+            return ptr::null_mut();
+        }
+    };
+
+    // Shrinking by a little is done in place
+    if new_size <= old_size {
+        let cut = old_size - new_size;
+        if cut < old_size / 2 && cut < unistd::getpagesize() as size_t {
+            ptr::write_bytes((ptr as *mut u8).add(new_size), 0, cut);
+            return ptr;
+        }
+    }
+
+    let new_ptr = malloc(new_size);
+    if new_ptr.is_null() {
+        return new_ptr;
+    }
+    if new_size > old_size {
+        ptr::copy_nonoverlapping(ptr as *const u8, new_ptr as *mut u8, old_size);
+        ptr::write_bytes((new_ptr as *mut u8).add(old_size), 0, new_size - old_size);
+    } else {
+        ptr::copy_nonoverlapping(ptr as *const u8, new_ptr as *mut u8, new_size);
+    }
+    intrinsics::volatile_set_memory(ptr as *mut u8, 0, old_size);
+    free(ptr);
+    new_ptr
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn realpath(pathname: *const c_char, resolved: *mut c_char) -> *mut c_char {
     let ptr = if resolved.is_null() {
@@ -822,7 +963,7 @@ pub unsafe extern "C" fn realpath(pathname: *const c_char, resolved: *mut c_char
 
     let out = slice::from_raw_parts_mut(ptr as *mut u8, limits::PATH_MAX);
     {
-        let file = match File::open(&CStr::from_ptr(pathname), O_PATH | O_CLOEXEC) {
+        let file = match File::open(&CStr::from_ptr(pathname), O_PATH) {
             Ok(file) => file,
             Err(_) => return ptr::null_mut(),
         };
@@ -875,6 +1016,7 @@ pub unsafe extern "C" fn setenv(
     let key_len = strlen(key);
     let value_len = strlen(value);
 
+    let _guard = ENVIRON_LOCK.lock();
     if let Some((i, existing)) = find_env(key) {
         if overwrite == 0 {
             return 0;
@@ -889,13 +1031,13 @@ pub unsafe extern "C" fn setenv(
             core::ptr::write(existing.add(value_len), 0);
         } else {
             // Reuse platform::environ slot, but allocate a new pointer.
-            let ptr = platform::alloc(key_len as usize + 1 + value_len as usize + 1) as *mut c_char;
+            let ptr = malloc(key_len as usize + 1 + value_len as usize + 1) as *mut c_char;
             copy_kv(ptr, key, value, key_len, value_len);
             platform::environ.add(i).write(ptr);
         }
     } else {
         // Expand platform::environ and allocate a new pointer.
-        let ptr = platform::alloc(key_len as usize + 1 + value_len as usize + 1) as *mut c_char;
+        let ptr = malloc(key_len as usize + 1 + value_len as usize + 1) as *mut c_char;
         copy_kv(ptr, key, value, key_len, value_len);
         put_new_env(ptr);
     }
@@ -956,6 +1098,23 @@ pub unsafe extern "C" fn strtof(s: *const c_char, endptr: *mut *mut c_char) -> c
     strto_float_impl!(c_float, s, endptr)
 }
 
+#[no_mangle]
+pub unsafe extern "C" fn strtod_l(
+    s: *const c_char,
+    endptr: *mut *mut c_char,
+    locale: locale_t,
+) -> c_double {
+    locale::with(locale, || strtod(s, endptr))
+}
+#[no_mangle]
+pub unsafe extern "C" fn strtof_l(
+    s: *const c_char,
+    endptr: *mut *mut c_char,
+    locale: locale_t,
+) -> c_float {
+    locale::with(locale, || strtof(s, endptr))
+}
+
 pub fn is_positive(ch: c_char) -> Option<(bool, isize)> {
     match ch {
         0 => None,
@@ -1080,6 +1239,16 @@ pub unsafe extern "C" fn strtoul(
     )
 }
 
+#[no_mangle]
+pub unsafe extern "C" fn strtoul_l(
+    s: *const c_char,
+    endptr: *mut *mut c_char,
+    base: c_int,
+    _locale: locale_t,
+) -> c_ulong {
+    strtoul(s, endptr, base)
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn strtol(s: *const c_char, endptr: *mut *mut c_char, base: c_int) -> c_long {
     strto_impl!(
@@ -1093,6 +1262,16 @@ pub unsafe extern "C" fn strtol(s: *const c_char, endptr: *mut *mut c_char, base
     )
 }
 
+#[no_mangle]
+pub unsafe extern "C" fn strtol_l(
+    s: *const c_char,
+    endptr: *mut *mut c_char,
+    base: c_int,
+    _locale: locale_t,
+) -> c_long {
+    strtol(s, endptr, base)
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn strtoull(
     s: *const c_char,
@@ -1110,6 +1289,16 @@ pub unsafe extern "C" fn strtoull(
     )
 }
 
+#[no_mangle]
+pub unsafe extern "C" fn strtoull_l(
+    s: *const c_char,
+    endptr: *mut *mut c_char,
+    base: c_int,
+    _locale: locale_t,
+) -> c_ulonglong {
+    strtoull(s, endptr, base)
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn strtoll(
     s: *const c_char,
@@ -1127,6 +1316,16 @@ pub unsafe extern "C" fn strtoll(
     )
 }
 
+#[no_mangle]
+pub unsafe extern "C" fn strtoll_l(
+    s: *const c_char,
+    endptr: *mut *mut c_char,
+    base: c_int,
+    _locale: locale_t,
+) -> c_longlong {
+    strtoll(s, endptr, base)
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn system(command: *const c_char) -> c_int {
     //TODO: share code with popen
@@ -1170,13 +1369,14 @@ pub extern "C" fn ttyslot() -> c_int {
     unimplemented!();
 }
 
-// #[no_mangle]
-pub extern "C" fn unlockpt(fildes: c_int) -> c_int {
-    unimplemented!();
+#[no_mangle]
+pub unsafe extern "C" fn unlockpt(fildes: c_int) -> c_int {
+    pty::sys::unlockpt(fildes)
 }
 
 #[no_mangle]
 pub unsafe extern "C" fn unsetenv(key: *const c_char) -> c_int {
+    let _guard = ENVIRON_LOCK.lock();
     if let Some((i, _)) = find_env(key) {
         if platform::environ == platform::OUR_ENVIRON.as_mut_ptr() {
             // No need to worry about updating the pointer, this does not
@@ -1201,6 +1401,7 @@ pub unsafe extern "C" fn unsetenv(key: *const c_char) -> c_int {
 }
 
 #[no_mangle]
+#[linkage = "weak"] // may be replaced by another allocator
 pub unsafe extern "C" fn valloc(size: size_t) -> *mut c_void {
     /* sysconf(_SC_PAGESIZE) is a c_long and may in principle not
      * convert correctly to a size_t. */
@@ -1208,7 +1409,10 @@ pub unsafe extern "C" fn valloc(size: size_t) -> *mut c_void {
         Ok(page_size) => {
             /* valloc() is not supposed to be able to set errno to
              * EINVAL, hence no call to memalign(). */
-            let ptr = platform::alloc_align(size, page_size);
+            let ptr = match malloc::__memalign_hook {
+                Some(hook) => hook(page_size, size, ptr::null()),
+                None => platform::alloc_align(size, page_size),
+            };
             if ptr.is_null() {
                 platform::errno = ENOMEM;
             }
@@ -1222,9 +1426,9 @@ pub unsafe extern "C" fn valloc(size: size_t) -> *mut c_void {
 }
 
 #[no_mangle]
-pub extern "C" fn wcstombs(s: *mut c_char, pwcs: *mut *const wchar_t, n: size_t) -> size_t {
+pub unsafe extern "C" fn wcstombs(s: *mut c_char, mut pwcs: *const wchar_t, n: size_t) -> size_t {
     let mut state: mbstate_t = mbstate_t {};
-    wcsrtombs(s, pwcs, n, &mut state)
+    wcsrtombs(s, &mut pwcs, n, &mut state)
 }
 
 #[no_mangle]
@@ -5,7 +5,7 @@ use core::{mem, ptr, slice, usize};
 use cbitset::BitSet256;
 
 use crate::{
-    header::{errno::*, signal},
+    header::{errno::*, locale::locale_t, signal, stdlib},
     platform::{self, types::*},
 };
 
@@ -152,6 +152,15 @@ pub unsafe extern "C" fn strcoll(s1: *const c_char, s2: *const c_char) -> c_int
     strcmp(s1, s2)
 }
 
+#[no_mangle]
+pub unsafe extern "C" fn strcoll_l(
+    s1: *const c_char,
+    s2: *const c_char,
+    _locale: locale_t,
+) -> c_int {
+    strcoll(s1, s2)
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn strcpy(dst: *mut c_char, src: *const c_char) -> *mut c_char {
     let mut i = 0;
@@ -212,7 +221,7 @@ pub unsafe extern "C" fn strndup(s1: *const c_char, size: size_t) -> *mut c_char
     let len = strnlen(s1, size);
 
     // the "+ 1" is to account for the NUL byte
-    let buffer = platform::alloc(len + 1) as *mut c_char;
+    let buffer = stdlib::malloc(len + 1) as *mut c_char;
     if buffer.is_null() {
         platform::errno = ENOMEM as c_int;
     } else {
@@ -243,6 +252,12 @@ pub unsafe extern "C" fn strerror(errnum: c_int) -> *mut c_char {
     strerror_buf.as_mut_ptr() as *mut c_char
 }
 
+#[no_mangle]
+pub unsafe extern "C" fn strerror_l(errnum: c_int, _locale: locale_t) -> *mut c_char {
+    // There are no translations of the messages
+    strerror(errnum)
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn strerror_r(errnum: c_int, buf: *mut c_char, buflen: size_t) -> c_int {
     let msg = strerror(errnum);
@@ -461,3 +476,13 @@ pub unsafe extern "C" fn strxfrm(s1: *mut c_char, s2: *const c_char, n: size_t)
     }
     len
 }
+
+#[no_mangle]
+pub unsafe extern "C" fn strxfrm_l(
+    s1: *mut c_char,
+    s2: *const c_char,
+    n: size_t,
+    _locale: locale_t,
+) -> size_t {
+    strxfrm(s1, s2, n)
+}
@@ -3,7 +3,7 @@
 use core::ptr;
 
 use crate::{
-    header::{ctype, string},
+    header::{ctype, locale::locale_t, string},
     platform::types::*,
 };
 
@@ -45,6 +45,15 @@ pub unsafe extern "C" fn strcasecmp(first: *const c_char, second: *const c_char)
     strncasecmp(first, second, size_t::max_value())
 }
 
+#[no_mangle]
+pub unsafe extern "C" fn strcasecmp_l(
+    first: *const c_char,
+    second: *const c_char,
+    _locale: locale_t,
+) -> c_int {
+    strcasecmp(first, second)
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn strncasecmp(
     mut first: *const c_char,
@@ -63,3 +72,13 @@ pub unsafe extern "C" fn strncasecmp(
     }
     0
 }
+
+#[no_mangle]
+pub unsafe extern "C" fn strncasecmp_l(
+    first: *const c_char,
+    second: *const c_char,
+    n: size_t,
+    _locale: locale_t,
+) -> c_int {
+    strncasecmp(first, second, n)
+}
@@ -1,10 +1,21 @@
-//! sys/auxv.h implementation
+//! sys/auxv.h implementation, following https://man7.org/linux/man-pages/man3/getauxval.3.html
 
-use crate::platform::types::*;
+use crate::{
+    header::errno::ENOENT,
+    platform::{self, types::*},
+};
 
 pub use crate::platform::auxv_defs::*;
 
+/// The entry of type `t` of the auxiliary vector the program was started with, or 0 with errno
+/// set to ENOENT if there was none.
 #[no_mangle]
-pub extern "C" fn getauxval(_t: c_ulong) -> c_ulong {
-    0
+pub extern "C" fn getauxval(t: c_ulong) -> c_ulong {
+    match platform::auxv(t as usize) {
+        Some(value) => value as c_ulong,
+        None => {
+            unsafe { platform::errno = ENOENT };
+            0
+        }
+    }
 }
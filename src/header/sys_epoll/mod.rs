@@ -4,7 +4,7 @@ use core::ptr;
 
 use crate::{
     header::signal::sigset_t,
-    platform::{types::*, PalEpoll, Sys},
+    platform::{self, types::*, PalEpoll, Sys},
 };
 
 pub use self::sys::*;
@@ -90,7 +90,9 @@ pub extern "C" fn epoll_pwait(
     sigmask: *const sigset_t,
 ) -> c_int {
     trace_expr!(
-        Sys::epoll_pwait(epfd, events, maxevents, timeout, sigmask),
+        platform::cancellation_point(|| Sys::epoll_pwait(
+            epfd, events, maxevents, timeout, sigmask
+        )),
         "epoll_pwait({}, {:p}, {}, {}, {:p})",
         epfd,
         events,
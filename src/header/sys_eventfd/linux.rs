@@ -0,0 +1,5 @@
+use crate::platform::types::*;
+
+pub const EFD_SEMAPHORE: c_int = 1;
+pub const EFD_CLOEXEC: c_int = 0x8_0000;
+pub const EFD_NONBLOCK: c_int = 0x800;
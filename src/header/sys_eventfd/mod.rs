@@ -0,0 +1,46 @@
+//! sys/eventfd.h implementation, following https://man7.org/linux/man-pages/man2/eventfd.2.html
+
+use core::mem;
+
+use crate::platform::{types::*, Pal, Sys};
+
+pub use self::sys::*;
+
+#[cfg(target_os = "linux")]
+#[path = "linux.rs"]
+pub mod sys;
+
+#[cfg(target_os = "redox")]
+#[path = "redox.rs"]
+pub mod sys;
+
+pub type eventfd_t = u64;
+
+#[no_mangle]
+pub extern "C" fn eventfd(initval: c_uint, flags: c_int) -> c_int {
+    trace_expr!(
+        Sys::eventfd(initval, flags),
+        "eventfd({}, {:#x})",
+        initval,
+        flags
+    )
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn eventfd_read(fd: c_int, value: *mut eventfd_t) -> c_int {
+    let mut buf = [0; mem::size_of::<eventfd_t>()];
+    if Sys::read(fd, &mut buf) != buf.len() as ssize_t {
+        return -1;
+    }
+    *value = eventfd_t::from_ne_bytes(buf);
+    0
+}
+
+#[no_mangle]
+pub extern "C" fn eventfd_write(fd: c_int, value: eventfd_t) -> c_int {
+    let buf = value.to_ne_bytes();
+    if Sys::write(fd, &buf) != buf.len() as ssize_t {
+        return -1;
+    }
+    0
+}
@@ -0,0 +1,6 @@
+use crate::platform::types::*;
+
+pub const EFD_SEMAPHORE: c_int = 1;
+// Equal to O_CLOEXEC and O_NONBLOCK
+pub const EFD_CLOEXEC: c_int = 0x0100_0000;
+pub const EFD_NONBLOCK: c_int = 0x0004_0000;
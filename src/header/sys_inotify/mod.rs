@@ -0,0 +1,87 @@
+//! sys/inotify.h implementation, following https://man7.org/linux/man-pages/man7/inotify.7.html
+
+use crate::{
+    c_str::CStr,
+    platform::{types::*, Pal, Sys},
+};
+
+pub use self::sys::*;
+
+#[cfg(target_os = "linux")]
+#[path = "linux.rs"]
+pub mod sys;
+
+#[cfg(target_os = "redox")]
+#[path = "redox.rs"]
+pub mod sys;
+
+pub const IN_ACCESS: u32 = 0x0000_0001;
+pub const IN_MODIFY: u32 = 0x0000_0002;
+pub const IN_ATTRIB: u32 = 0x0000_0004;
+pub const IN_CLOSE_WRITE: u32 = 0x0000_0008;
+pub const IN_CLOSE_NOWRITE: u32 = 0x0000_0010;
+pub const IN_CLOSE: u32 = IN_CLOSE_WRITE | IN_CLOSE_NOWRITE;
+pub const IN_OPEN: u32 = 0x0000_0020;
+pub const IN_MOVED_FROM: u32 = 0x0000_0040;
+pub const IN_MOVED_TO: u32 = 0x0000_0080;
+pub const IN_MOVE: u32 = IN_MOVED_FROM | IN_MOVED_TO;
+pub const IN_CREATE: u32 = 0x0000_0100;
+pub const IN_DELETE: u32 = 0x0000_0200;
+pub const IN_DELETE_SELF: u32 = 0x0000_0400;
+pub const IN_MOVE_SELF: u32 = 0x0000_0800;
+pub const IN_ALL_EVENTS: u32 = 0x0000_0fff;
+
+pub const IN_UNMOUNT: u32 = 0x0000_2000;
+pub const IN_Q_OVERFLOW: u32 = 0x0000_4000;
+pub const IN_IGNORED: u32 = 0x0000_8000;
+
+pub const IN_ONLYDIR: u32 = 0x0100_0000;
+pub const IN_DONT_FOLLOW: u32 = 0x0200_0000;
+pub const IN_EXCL_UNLINK: u32 = 0x0400_0000;
+pub const IN_MASK_CREATE: u32 = 0x1000_0000;
+pub const IN_MASK_ADD: u32 = 0x2000_0000;
+pub const IN_ISDIR: u32 = 0x4000_0000;
+pub const IN_ONESHOT: u32 = 0x8000_0000;
+
+/// Followed by `len` bytes holding the NUL-padded name, for events on the entries of a watched
+/// directory.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct inotify_event {
+    pub wd: c_int,
+    pub mask: u32,
+    pub cookie: u32,
+    pub len: u32,
+}
+
+#[no_mangle]
+pub extern "C" fn inotify_init() -> c_int {
+    inotify_init1(0)
+}
+
+#[no_mangle]
+pub extern "C" fn inotify_init1(flags: c_int) -> c_int {
+    trace_expr!(Sys::inotify_init1(flags), "inotify_init1({:#x})", flags)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn inotify_add_watch(fd: c_int, pathname: *const c_char, mask: u32) -> c_int {
+    let path = CStr::from_ptr(pathname);
+    trace_expr!(
+        Sys::inotify_add_watch(fd, path, mask),
+        "inotify_add_watch({}, {:?}, {:#x})",
+        fd,
+        path,
+        mask
+    )
+}
+
+#[no_mangle]
+pub extern "C" fn inotify_rm_watch(fd: c_int, wd: c_int) -> c_int {
+    trace_expr!(
+        Sys::inotify_rm_watch(fd, wd),
+        "inotify_rm_watch({}, {})",
+        fd,
+        wd
+    )
+}
@@ -0,0 +1,5 @@
+use crate::platform::types::*;
+
+// Equal to O_CLOEXEC and O_NONBLOCK
+pub const IN_CLOEXEC: c_int = 0x0100_0000;
+pub const IN_NONBLOCK: c_int = 0x0004_0000;
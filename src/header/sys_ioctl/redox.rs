@@ -3,7 +3,7 @@ use syscall;
 
 use crate::{
     header::{errno, fcntl, termios},
-    platform::{self, e, types::*},
+    platform::{self, e, types::*, Pal, Sys},
 };
 
 use super::winsize;
@@ -21,6 +21,7 @@ pub const TCXONC: c_ulong = 0x540A;
 
 pub const TCFLSH: c_ulong = 0x540B;
 
+pub const TIOCSCTTY: c_ulong = 0x540E;
 pub const TIOCGPGRP: c_ulong = 0x540F;
 pub const TIOCSPGRP: c_ulong = 0x5410;
 
@@ -84,8 +85,16 @@ pub unsafe extern "C" fn ioctl(fd: c_int, request: c_ulong, out: *mut c_void) ->
                 0
             }
         }
-        // TODO: give these different behaviors
         TCSETS | TCSETSW | TCSETSF => {
+            // TCSETSW waits for the output to be written first, and TCSETSF also throws away
+            // the input that hasn't been read
+            if request != TCSETS && e(syscall::fsync(fd as usize)) == !0 {
+                return -1;
+            }
+            if request == TCSETSF && e(dup_write(fd, "flush", &(termios::TCIFLUSH as c_int))) == !0
+            {
+                return -1;
+            }
             let termios = &*(out as *const termios::termios);
             if e(dup_write(fd, "termios", termios)) == !0 {
                 -1
@@ -101,6 +110,16 @@ pub unsafe extern "C" fn ioctl(fd: c_int, request: c_ulong, out: *mut c_void) ->
                 0
             }
         }
+        TIOCSCTTY => {
+            // There are no controlling terminals, so the nearest thing is for the caller's
+            // process group to be the one the terminal sends signals to
+            let pgrp = Sys::getpgid(0);
+            if pgrp < 0 || e(dup_write(fd, "pgrp", &pgrp)) == !0 {
+                -1
+            } else {
+                0
+            }
+        }
         TIOCGPGRP => {
             let pgrp = &mut *(out as *mut pid_t);
             if e(dup_read(fd, "pgrp", pgrp)) == !0 {
@@ -134,13 +153,35 @@ pub unsafe extern "C" fn ioctl(fd: c_int, request: c_ulong, out: *mut c_void) ->
             }
         }
         TCSBRK => {
-            // TODO
-            0
-        }
-        TCXONC => {
-            // TODO
-            0
+            // A non-zero argument is tcdrain, a zero one a break, which there is no line to
+            // send on
+            if out as c_int != 0 && e(syscall::fsync(fd as usize)) == !0 {
+                -1
+            } else {
+                0
+            }
         }
+        TCXONC => match out as usize {
+            termios::TCIOFF | termios::TCION => {
+                // Sending the STOP or START character asks the other end to stop or start
+                let mut attrs = termios::termios::default();
+                if e(dup_read(fd, "termios", &mut attrs)) == !0 {
+                    return -1;
+                }
+                let c = if out as usize == termios::TCIOFF {
+                    attrs.c_cc[termios::VSTOP]
+                } else {
+                    attrs.c_cc[termios::VSTART]
+                };
+                if e(syscall::write(fd as usize, &[c])) == !0 {
+                    -1
+                } else {
+                    0
+                }
+            }
+            // The pty scheme has no way to suspend output, like a terminal without flow control
+            _ => 0,
+        },
         _ => {
             platform::errno = errno::EINVAL;
             -1
@@ -0,0 +1,44 @@
+//! sys/ipc.h implementation, following https://pubs.opengroup.org/onlinepubs/9699919799/basedefs/sys_ipc.h.html
+
+use crate::{
+    header::sys_stat::{self, stat},
+    platform::types::*,
+};
+
+pub const IPC_PRIVATE: key_t = 0;
+
+pub const IPC_CREAT: c_int = 0o1000;
+pub const IPC_EXCL: c_int = 0o2000;
+pub const IPC_NOWAIT: c_int = 0o4000;
+
+pub const IPC_RMID: c_int = 0;
+pub const IPC_SET: c_int = 1;
+pub const IPC_STAT: c_int = 2;
+pub const IPC_INFO: c_int = 3;
+
+#[repr(C)]
+#[derive(Clone, Copy, Default)]
+pub struct ipc_perm {
+    pub __key: key_t,
+    pub uid: uid_t,
+    pub gid: gid_t,
+    pub cuid: uid_t,
+    pub cgid: gid_t,
+    pub mode: mode_t,
+    pub __seq: c_int,
+    pub __unused1: c_long,
+    pub __unused2: c_long,
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn ftok(path: *const c_char, id: c_int) -> key_t {
+    let mut buf = stat::default();
+    if sys_stat::stat(path, &mut buf) < 0 {
+        return -1;
+    }
+    // The same layout as other implementations, so that keys can be shared with them
+    (buf.st_ino & 0xffff) as key_t | ((buf.st_dev & 0xff) << 16) as key_t | (id & 0xff) << 24
+}
+
+#[no_mangle]
+pub extern "C" fn cbindgen_stupid_struct_user_for_ipc_perm(a: ipc_perm) {}
@@ -1,7 +1,11 @@
 use crate::{
     c_str::{CStr, CString},
-    header::{fcntl, unistd},
-    platform::{types::*, Pal, Sys},
+    header::{
+        errno::{EINVAL, ENAMETOOLONG},
+        fcntl,
+        limits::NAME_MAX,
+    },
+    platform::{self, types::*, Pal, Sys},
 };
 
 pub use self::sys::*;
@@ -19,17 +23,58 @@ pub const MADV_RANDOM: c_int = 1;
 pub const MADV_SEQUENTIAL: c_int = 2;
 pub const MADV_WILLNEED: c_int = 3;
 pub const MADV_DONTNEED: c_int = 4;
+pub const MADV_FREE: c_int = 8;
+pub const MADV_HUGEPAGE: c_int = 14;
+pub const MADV_NOHUGEPAGE: c_int = 15;
 
 pub const MAP_SHARED: c_int = 0x0001;
 pub const MAP_PRIVATE: c_int = 0x0002;
 pub const MAP_TYPE: c_int = 0x000F;
 pub const MAP_ANON: c_int = 0x0020;
 pub const MAP_ANONYMOUS: c_int = MAP_ANON;
+#[cfg(target_arch = "x86_64")]
+pub const MAP_32BIT: c_int = 0x0040;
+pub const MAP_GROWSDOWN: c_int = 0x0100;
+pub const MAP_DENYWRITE: c_int = 0x0800;
+pub const MAP_EXECUTABLE: c_int = 0x1000;
+pub const MAP_LOCKED: c_int = 0x2000;
+pub const MAP_NORESERVE: c_int = 0x4000;
+pub const MAP_POPULATE: c_int = 0x8000;
+pub const MAP_NONBLOCK: c_int = 0x1_0000;
+pub const MAP_STACK: c_int = 0x2_0000;
+pub const MAP_HUGETLB: c_int = 0x4_0000;
+
+/// The huge page size to use with MAP_HUGETLB or MFD_HUGETLB, as its log2 shifted left this much
+pub const MAP_HUGE_SHIFT: c_int = 26;
+pub const MAP_HUGE_MASK: c_int = 0x3F;
+pub const MAP_HUGE_2MB: c_int = 21 << MAP_HUGE_SHIFT;
+pub const MAP_HUGE_1GB: c_int = 30 << MAP_HUGE_SHIFT;
+
+pub const MCL_CURRENT: c_int = 0x0001;
+pub const MCL_FUTURE: c_int = 0x0002;
+pub const MCL_ONFAULT: c_int = 0x0004;
+
+pub const MFD_CLOEXEC: c_uint = 0x0001;
+pub const MFD_ALLOW_SEALING: c_uint = 0x0002;
+pub const MFD_HUGETLB: c_uint = 0x0004;
+
+pub const MREMAP_MAYMOVE: c_int = 0x0001;
+pub const MREMAP_FIXED: c_int = 0x0002;
 
 pub const MS_ASYNC: c_int = 0x0001;
 pub const MS_INVALIDATE: c_int = 0x0002;
 pub const MS_SYNC: c_int = 0x0004;
 
+#[no_mangle]
+pub unsafe extern "C" fn madvise(addr: *mut c_void, len: size_t, advice: c_int) -> c_int {
+    Sys::madvise(addr, len, advice)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn memfd_create(name: *const c_char, flags: c_uint) -> c_int {
+    Sys::memfd_create(CStr::from_ptr(name), flags)
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn mlock(addr: *const c_void, len: usize) -> c_int {
     Sys::mlock(addr, len)
@@ -52,11 +97,36 @@ pub unsafe extern "C" fn mmap(
     Sys::mmap(addr, len, prot, flags, fildes, off)
 }
 
+/// mmap's large file support name, for glibc's binaries.
+#[no_mangle]
+pub unsafe extern "C" fn mmap64(
+    addr: *mut c_void,
+    len: size_t,
+    prot: c_int,
+    flags: c_int,
+    fildes: c_int,
+    off: off_t,
+) -> *mut c_void {
+    mmap(addr, len, prot, flags, fildes, off)
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn mprotect(addr: *mut c_void, len: size_t, prot: c_int) -> c_int {
     Sys::mprotect(addr, len, prot)
 }
 
+/// Called by mremap, which is variadic and so defined in C.
+#[no_mangle]
+pub unsafe extern "C" fn sys_mremap(
+    old_address: *mut c_void,
+    old_size: size_t,
+    new_size: size_t,
+    flags: c_int,
+    new_address: *mut c_void,
+) -> *mut c_void {
+    Sys::mremap(old_address, old_size, new_size, flags, new_address)
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn msync(addr: *mut c_void, len: size_t, flags: c_int) -> c_int {
     Sys::msync(addr, len, flags)
@@ -79,38 +149,51 @@ pub unsafe extern "C" fn munmap(addr: *mut c_void, len: size_t) -> c_int {
 
 #[cfg(target_os = "linux")]
 static SHM_PATH: &'static [u8] = b"/dev/shm/";
+// The objects are files like any other in /dev/shm, so links there must not be followed
+#[cfg(target_os = "linux")]
+const SHM_FLAGS: c_int = fcntl::O_CLOEXEC | fcntl::O_NOFOLLOW;
 
 #[cfg(target_os = "redox")]
 static SHM_PATH: &'static [u8] = b"shm:";
-
-unsafe fn shm_path(name: *const c_char) -> CString {
-    let name_c = CStr::from_ptr(name);
-
-    let mut path = SHM_PATH.to_vec();
-
-    let mut skip_slash = true;
-    for &b in name_c.to_bytes() {
-        if skip_slash {
-            if b == b'/' {
-                continue;
-            } else {
-                skip_slash = false;
-            }
-        }
-        path.push(b);
+#[cfg(target_os = "redox")]
+const SHM_FLAGS: c_int = fcntl::O_CLOEXEC;
+
+/// The path of the object called `name`, which may start with slashes but contain no other.
+unsafe fn shm_path(name: *const c_char) -> Option<CString> {
+    let name = CStr::from_ptr(name).to_bytes();
+    let name = &name[name.iter().take_while(|&&b| b == b'/').count()..];
+    if name.is_empty() || name.contains(&b'/') || name == b"." || name == b".." {
+        platform::errno = EINVAL;
+        return None;
+    }
+    if name.len() > NAME_MAX {
+        platform::errno = ENAMETOOLONG;
+        return None;
     }
 
-    CString::from_vec_unchecked(path)
+    let mut path = SHM_PATH.to_vec();
+    path.extend_from_slice(name);
+    Some(CString::from_vec_unchecked(path))
 }
 
 #[no_mangle]
 pub unsafe extern "C" fn shm_open(name: *const c_char, oflag: c_int, mode: mode_t) -> c_int {
-    let path = shm_path(name);
-    fcntl::sys_open(path.as_ptr(), oflag, mode)
+    // Only reading with or without writing makes sense for shared memory
+    let accmode = oflag & fcntl::O_ACCMODE;
+    if accmode != fcntl::O_RDONLY && accmode != fcntl::O_RDWR {
+        platform::errno = EINVAL;
+        return -1;
+    }
+    match shm_path(name) {
+        Some(path) => Sys::open(&path, oflag | SHM_FLAGS, mode),
+        None => -1,
+    }
 }
 
 #[no_mangle]
 pub unsafe extern "C" fn shm_unlink(name: *const c_char) -> c_int {
-    let path = shm_path(name);
-    unistd::unlink(path.as_ptr())
+    match shm_path(name) {
+        Some(path) => Sys::unlink(&path),
+        None => -1,
+    }
 }
@@ -6,6 +6,7 @@ use core::ffi::VaList;
 pub const PTRACE_TRACEME: c_int = 0;
 pub const PTRACE_PEEKTEXT: c_int = 1;
 pub const PTRACE_PEEKDATA: c_int = 2;
+pub const PTRACE_PEEKUSER: c_int = 3;
 pub const PTRACE_POKETEXT: c_int = 4;
 pub const PTRACE_POKEDATA: c_int = 5;
 pub const PTRACE_CONT: c_int = 7;
@@ -23,7 +24,7 @@ pub const PTRACE_SYSEMU_SINGLESTEP: c_int = 32;
 
 // Can't use "params: ..." syntax, because... guess what? Cbingen again :(
 #[no_mangle]
-pub unsafe extern "C" fn sys_ptrace(request: c_int, mut params: VaList) -> c_int {
+pub unsafe extern "C" fn sys_ptrace(request: c_int, mut params: VaList) -> c_long {
     // Musl also just grabs the arguments from the varargs...
     Sys::ptrace(request, params.arg(), params.arg(), params.arg())
 }
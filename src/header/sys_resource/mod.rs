@@ -81,8 +81,20 @@ pub unsafe extern "C" fn getrlimit(resource: c_int, rlp: *mut rlimit) -> c_int {
 // pub unsafe extern "C" fn setpriority(which: c_int, who: id_t, nice: c_int) -> c_int {
 //     unimplemented!();
 // }
-//
-// #[no_mangle]
-// pub unsafe extern "C" fn setrlimit(resource: c_int, rlp: *const rlimit) -> c_int {
-//     unimplemented!();
-// }
+
+#[no_mangle]
+pub unsafe extern "C" fn setrlimit(resource: c_int, rlp: *const rlimit) -> c_int {
+    Sys::setrlimit(resource, rlp)
+}
+
+// Large file support's names, for glibc's binaries. rlim_t is 64 bits already.
+
+#[no_mangle]
+pub unsafe extern "C" fn getrlimit64(resource: c_int, rlp: *mut rlimit) -> c_int {
+    getrlimit(resource, rlp)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn setrlimit64(resource: c_int, rlp: *const rlimit) -> c_int {
+    setrlimit(resource, rlp)
+}
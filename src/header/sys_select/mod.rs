@@ -1,20 +1,22 @@
 //! sys/select.h implementation
 
-use core::mem;
+use alloc::vec::Vec;
+use core::{mem, ptr};
 
 use cbitset::BitSet;
 
 use crate::{
-    fs::File,
     header::{
         errno,
-        sys_epoll::{
-            epoll_create1, epoll_ctl, epoll_data, epoll_event, epoll_wait, EPOLLERR, EPOLLIN,
-            EPOLLOUT, EPOLL_CLOEXEC, EPOLL_CTL_ADD,
+        poll::{
+            poll_epoll, pollfd, timespec_to_ms, POLLERR, POLLHUP, POLLIN, POLLNVAL, POLLOUT,
+            POLLPRI,
         },
+        signal::sigset_t,
         sys_time::timeval,
+        time::{timespec, CLOCK_MONOTONIC},
     },
-    platform::{self, types::*},
+    platform::{self, types::*, Pal, Sys},
 };
 
 // fd_set is also defined in C because cbindgen is incompatible with mem::size_of booo
@@ -27,130 +29,92 @@ pub struct fd_set {
     pub fds_bits: bitset,
 }
 
-pub fn select_epoll(
-    nfds: c_int,
-    readfds: Option<&mut fd_set>,
-    writefds: Option<&mut fd_set>,
-    exceptfds: Option<&mut fd_set>,
-    timeout: Option<&mut timeval>,
-) -> c_int {
-    if nfds < 0 || nfds > FD_SETSIZE as i32 {
-        unsafe { platform::errno = errno::EINVAL };
-        return -1;
-    };
+/// The bits of a set passed to select. Descriptors from FD_SETSIZE up are in the same layout past
+/// the end of `fd_set`, where the caller has to allocate room for `nfds` of them.
+#[derive(Clone, Copy)]
+struct FdBits(*mut c_ulong);
 
-    let ep = {
-        let epfd = epoll_create1(EPOLL_CLOEXEC);
-        if epfd < 0 {
-            return -1;
-        }
-        File::new(epfd)
-    };
+impl FdBits {
+    const BITS: usize = 8 * mem::size_of::<c_ulong>();
 
-    let mut read_bitset: Option<&mut bitset> = readfds.map(|fd_set| &mut fd_set.fds_bits);
-    let mut write_bitset: Option<&mut bitset> = writefds.map(|fd_set| &mut fd_set.fds_bits);
-    let mut except_bitset: Option<&mut bitset> = exceptfds.map(|fd_set| &mut fd_set.fds_bits);
+    fn new(set: *mut fd_set) -> Option<Self> {
+        if set.is_null() {
+            None
+        } else {
+            Some(Self(set as *mut c_ulong))
+        }
+    }
 
-    // Keep track of the number of file descriptors that do not support epoll
-    let mut not_epoll = 0;
-    for fd in 0..nfds {
-        let mut events = 0;
+    unsafe fn contains(self, fd: usize) -> bool {
+        *self.0.add(fd / Self::BITS) & (1 << (fd % Self::BITS)) != 0
+    }
 
-        if let Some(ref fd_set) = read_bitset {
-            if fd_set.contains(fd as usize) {
-                events |= EPOLLIN;
-            }
+    unsafe fn set(self, fd: usize, value: bool) {
+        let word = self.0.add(fd / Self::BITS);
+        if value {
+            *word |= 1 << (fd % Self::BITS);
+        } else {
+            *word &= !(1 << (fd % Self::BITS));
         }
+    }
+}
 
-        if let Some(ref fd_set) = write_bitset {
-            if fd_set.contains(fd as usize) {
-                events |= EPOLLOUT;
-            }
-        }
+/// Waits through poll, on the descriptors below `nfds` in the sets that aren't null. The sets are
+/// then left with the ready descriptors only.
+pub unsafe fn select_epoll(
+    nfds: c_int,
+    readfds: *mut fd_set,
+    writefds: *mut fd_set,
+    exceptfds: *mut fd_set,
+    timeout: c_int,
+    sigmask: *const sigset_t,
+) -> c_int {
+    if nfds < 0 {
+        platform::errno = errno::EINVAL;
+        return -1;
+    }
+    let sets = [
+        (FdBits::new(readfds), POLLIN),
+        (FdBits::new(writefds), POLLOUT),
+        (FdBits::new(exceptfds), POLLPRI),
+    ];
 
-        if let Some(ref fd_set) = except_bitset {
-            if fd_set.contains(fd as usize) {
-                events |= EPOLLERR;
+    let mut fds = Vec::new();
+    for fd in 0..nfds {
+        let mut events = 0;
+        for &(set, event) in &sets {
+            if set.map_or(false, |set| set.contains(fd as usize)) {
+                events |= event;
             }
         }
-
-        if events > 0 {
-            let mut event = epoll_event {
+        if events != 0 {
+            fds.push(pollfd {
+                fd,
                 events,
-                data: epoll_data { fd },
-                ..Default::default()
-            };
-            if epoll_ctl(*ep, EPOLL_CTL_ADD, fd, &mut event) < 0 {
-                if unsafe { platform::errno == errno::EPERM } {
-                    not_epoll += 1;
-                } else {
-                    return -1;
-                }
-            } else {
-                if let Some(ref mut fd_set) = read_bitset {
-                    if fd_set.contains(fd as usize) {
-                        fd_set.remove(fd as usize);
-                    }
-                }
-
-                if let Some(ref mut fd_set) = write_bitset {
-                    if fd_set.contains(fd as usize) {
-                        fd_set.remove(fd as usize);
-                    }
-                }
-
-                if let Some(ref mut fd_set) = except_bitset {
-                    if fd_set.contains(fd as usize) {
-                        fd_set.remove(fd as usize);
-                    }
-                }
-            }
+                revents: 0,
+            });
         }
     }
 
-    let mut events: [epoll_event; 32] = unsafe { mem::zeroed() };
-    let epoll_timeout = if not_epoll > 0 {
-        // Do not wait if any non-epoll file descriptors were found
-        0
-    } else {
-        match timeout {
-            Some(timeout) => {
-                //TODO: Check for overflow
-                ((timeout.tv_sec as c_int) * 1000) + ((timeout.tv_usec as c_int) / 1000)
-            }
-            None => -1,
-        }
-    };
-    let res = epoll_wait(
-        *ep,
-        events.as_mut_ptr(),
-        events.len() as c_int,
-        epoll_timeout,
-    );
-    if res < 0 {
+    if poll_epoll(&mut fds, timeout, sigmask) < 0 {
+        return -1;
+    }
+    if fds.iter().any(|pfd| pfd.revents & POLLNVAL != 0) {
+        // Unlike poll, select fails on a descriptor that isn't open
+        platform::errno = errno::EBADF;
         return -1;
     }
 
-    let mut count = not_epoll;
-    for event in events.iter().take(res as usize) {
-        let fd = unsafe { event.data.fd };
-        // TODO: Error status when fd does not match?
-        if fd >= 0 && fd < FD_SETSIZE as c_int {
-            if event.events & EPOLLIN > 0 {
-                if let Some(ref mut fd_set) = read_bitset {
-                    fd_set.insert(fd as usize);
-                    count += 1;
-                }
-            }
-            if event.events & EPOLLOUT > 0 {
-                if let Some(ref mut fd_set) = write_bitset {
-                    fd_set.insert(fd as usize);
-                    count += 1;
-                }
-            }
-            if event.events & EPOLLERR > 0 {
-                if let Some(ref mut fd_set) = except_bitset {
-                    fd_set.insert(fd as usize);
+    // Errors and hangups make a descriptor readable and writable, so that the call finding out
+    // about them doesn't block, as on Linux
+    let ready_when = [POLLIN | POLLHUP | POLLERR, POLLOUT | POLLERR, POLLPRI];
+    let mut count = 0;
+    for pfd in &fds {
+        for (&(set, event), &ready) in sets.iter().zip(&ready_when) {
+            if let Some(set) = set {
+                let is_ready = pfd.events & event != 0 && pfd.revents & ready != 0;
+                set.set(pfd.fd as usize, is_ready);
+                if is_ready {
                     count += 1;
                 }
             }
@@ -159,6 +123,13 @@ pub fn select_epoll(
     count
 }
 
+/// The current time in nanoseconds, for updating the timeout of select.
+fn monotonic_nanos() -> i128 {
+    let mut now = timespec::default();
+    Sys::clock_gettime(CLOCK_MONOTONIC, &mut now);
+    now.tv_sec as i128 * 1_000_000_000 + now.tv_nsec as i128
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn select(
     nfds: c_int,
@@ -167,35 +138,83 @@ pub unsafe extern "C" fn select(
     exceptfds: *mut fd_set,
     timeout: *mut timeval,
 ) -> c_int {
-    trace_expr!(
-        select_epoll(
-            nfds,
-            if readfds.is_null() {
-                None
-            } else {
-                Some(&mut *readfds)
-            },
-            if writefds.is_null() {
-                None
-            } else {
-                Some(&mut *writefds)
-            },
-            if exceptfds.is_null() {
-                None
-            } else {
-                Some(&mut *exceptfds)
-            },
-            if timeout.is_null() {
-                None
-            } else {
-                Some(&mut *timeout)
+    let (epoll_timeout, start) = if timeout.is_null() {
+        (-1, 0)
+    } else {
+        // Whole seconds in tv_usec are carried over, as on Linux
+        let timeout = &*timeout;
+        let as_timespec = timespec {
+            tv_sec: timeout.tv_sec + (timeout.tv_usec / 1_000_000) as time_t,
+            tv_nsec: (timeout.tv_usec % 1_000_000) as c_long * 1000,
+        };
+        match timespec_to_ms(&as_timespec) {
+            Some(epoll_timeout) => (epoll_timeout, monotonic_nanos()),
+            None => {
+                platform::errno = errno::EINVAL;
+                return -1;
             }
-        ),
+        }
+    };
+
+    let res = trace_expr!(
+        platform::cancellation_point(|| select_epoll(
+            nfds,
+            readfds,
+            writefds,
+            exceptfds,
+            epoll_timeout,
+            ptr::null()
+        )),
         "select({}, {:p}, {:p}, {:p}, {:p})",
         nfds,
         readfds,
         writefds,
         exceptfds,
         timeout
+    );
+
+    if !timeout.is_null() {
+        // Like Linux, leave the time that was not slept in the timeout
+        let timeout = &mut *timeout;
+        let total = timeout.tv_sec as i128 * 1_000_000_000 + timeout.tv_usec as i128 * 1000;
+        let remaining = (total - (monotonic_nanos() - start)).max(0);
+        timeout.tv_sec = (remaining / 1_000_000_000) as time_t;
+        timeout.tv_usec = (remaining % 1_000_000_000 / 1000) as suseconds_t;
+    }
+    res
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pselect(
+    nfds: c_int,
+    readfds: *mut fd_set,
+    writefds: *mut fd_set,
+    exceptfds: *mut fd_set,
+    timeout: *const timespec,
+    sigmask: *const sigset_t,
+) -> c_int {
+    let epoll_timeout = match timespec_to_ms(timeout) {
+        Some(epoll_timeout) => epoll_timeout,
+        None => {
+            platform::errno = errno::EINVAL;
+            return -1;
+        }
+    };
+    trace_expr!(
+        platform::cancellation_point(|| select_epoll(
+            nfds,
+            readfds,
+            writefds,
+            exceptfds,
+            epoll_timeout,
+            sigmask
+        )),
+        "pselect({}, {:p}, {:p}, {:p}, {:p}, {:p})",
+        nfds,
+        readfds,
+        writefds,
+        exceptfds,
+        timeout,
+        sigmask
     )
 }
@@ -0,0 +1,84 @@
+//! sys/sem.h implementation, following https://pubs.opengroup.org/onlinepubs/9699919799/basedefs/sys_sem.h.html
+
+use core::ptr;
+
+use crate::{
+    header::{sys_ipc::ipc_perm, time::timespec},
+    platform::{types::*, PalIpc, Sys},
+};
+
+pub const SEM_UNDO: c_int = 0x1000;
+
+pub const GETPID: c_int = 11;
+pub const GETVAL: c_int = 12;
+pub const GETALL: c_int = 13;
+pub const GETNCNT: c_int = 14;
+pub const GETZCNT: c_int = 15;
+pub const SETVAL: c_int = 16;
+pub const SETALL: c_int = 17;
+pub const SEM_STAT: c_int = 18;
+pub const SEM_INFO: c_int = 19;
+
+#[repr(C)]
+#[derive(Clone, Copy, Default)]
+pub struct sembuf {
+    pub sem_num: c_ushort,
+    pub sem_op: c_short,
+    pub sem_flg: c_short,
+}
+
+// x86_64 keeps padding after the times, left over from 32-bit times
+#[cfg(target_arch = "x86_64")]
+#[repr(C)]
+#[derive(Clone, Copy, Default)]
+pub struct semid_ds {
+    pub sem_perm: ipc_perm,
+    pub sem_otime: time_t,
+    pub __unused1: c_ulong,
+    pub sem_ctime: time_t,
+    pub __unused2: c_ulong,
+    pub sem_nsems: c_ulong,
+    pub __unused3: c_ulong,
+    pub __unused4: c_ulong,
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+#[repr(C)]
+#[derive(Clone, Copy, Default)]
+pub struct semid_ds {
+    pub sem_perm: ipc_perm,
+    pub sem_otime: time_t,
+    pub sem_ctime: time_t,
+    pub sem_nsems: c_ulong,
+    pub __unused3: c_ulong,
+    pub __unused4: c_ulong,
+}
+
+/// Called by the variadic semctl in C, with the `union semun` argument, if any.
+#[no_mangle]
+pub extern "C" fn sys_semctl(semid: c_int, semnum: c_int, cmd: c_int, arg: c_ulong) -> c_int {
+    Sys::semctl(semid, semnum, cmd, arg)
+}
+
+#[no_mangle]
+pub extern "C" fn semget(key: key_t, nsems: c_int, semflg: c_int) -> c_int {
+    Sys::semget(key, nsems, semflg)
+}
+
+#[no_mangle]
+pub extern "C" fn semop(semid: c_int, sops: *mut sembuf, nsops: size_t) -> c_int {
+    Sys::semtimedop(semid, sops, nsops, ptr::null())
+}
+
+#[no_mangle]
+pub extern "C" fn semtimedop(
+    semid: c_int,
+    sops: *mut sembuf,
+    nsops: size_t,
+    timeout: *const timespec,
+) -> c_int {
+    Sys::semtimedop(semid, sops, nsops, timeout)
+}
+
+#[no_mangle]
+pub extern "C" fn cbindgen_stupid_struct_user_for_semid_ds(a: semid_ds) {}
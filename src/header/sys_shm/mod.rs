@@ -0,0 +1,56 @@
+//! sys/shm.h implementation, following https://pubs.opengroup.org/onlinepubs/9699919799/basedefs/sys_shm.h.html
+
+use crate::{
+    header::sys_ipc::ipc_perm,
+    platform::{types::*, PalIpc, Sys},
+};
+
+pub const SHM_RDONLY: c_int = 0o10000;
+pub const SHM_RND: c_int = 0o20000;
+pub const SHM_REMAP: c_int = 0o40000;
+pub const SHM_EXEC: c_int = 0o100000;
+
+pub const SHM_LOCK: c_int = 11;
+pub const SHM_UNLOCK: c_int = 12;
+pub const SHM_STAT: c_int = 13;
+pub const SHM_INFO: c_int = 14;
+
+/// Segments are attached at multiples of this with SHM_RND
+pub const SHMLBA: c_int = 4096;
+
+pub type shmatt_t = c_ulong;
+
+#[repr(C)]
+#[derive(Clone, Copy, Default)]
+pub struct shmid_ds {
+    pub shm_perm: ipc_perm,
+    pub shm_segsz: size_t,
+    pub shm_atime: time_t,
+    pub shm_dtime: time_t,
+    pub shm_ctime: time_t,
+    pub shm_cpid: pid_t,
+    pub shm_lpid: pid_t,
+    pub shm_nattch: shmatt_t,
+    pub __unused4: c_ulong,
+    pub __unused5: c_ulong,
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn shmat(shmid: c_int, shmaddr: *const c_void, shmflg: c_int) -> *mut c_void {
+    Sys::shmat(shmid, shmaddr, shmflg)
+}
+
+#[no_mangle]
+pub extern "C" fn shmctl(shmid: c_int, cmd: c_int, buf: *mut shmid_ds) -> c_int {
+    Sys::shmctl(shmid, cmd, buf)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn shmdt(shmaddr: *const c_void) -> c_int {
+    Sys::shmdt(shmaddr)
+}
+
+#[no_mangle]
+pub extern "C" fn shmget(key: key_t, size: size_t, shmflg: c_int) -> c_int {
+    Sys::shmget(key, size, shmflg)
+}
@@ -2,7 +2,10 @@
 
 use core::ptr;
 
-use crate::platform::{types::*, PalSocket, Sys};
+use crate::{
+    header::sys_uio::iovec,
+    platform::{types::*, PalSocket, Sys},
+};
 
 pub mod constants;
 
@@ -16,6 +19,40 @@ pub struct sockaddr {
     pub sa_data: [c_char; 14],
 }
 
+#[repr(C)]
+pub struct msghdr {
+    pub msg_name: *mut c_void,
+    pub msg_namelen: socklen_t,
+    pub msg_iov: *mut iovec,
+    pub msg_iovlen: size_t,
+    pub msg_control: *mut c_void,
+    pub msg_controllen: size_t,
+    pub msg_flags: c_int,
+}
+
+#[repr(C)]
+pub struct cmsghdr {
+    pub cmsg_len: size_t,
+    pub cmsg_level: c_int,
+    pub cmsg_type: c_int,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Default)]
+pub struct linger {
+    pub l_onoff: c_int,
+    pub l_linger: c_int,
+}
+
+/// Credentials passed with SCM_CREDENTIALS
+#[repr(C)]
+#[derive(Clone, Copy, Default)]
+pub struct ucred {
+    pub pid: pid_t,
+    pub uid: uid_t,
+    pub gid: gid_t,
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn accept(
     socket: c_int,
@@ -31,6 +68,23 @@ pub unsafe extern "C" fn accept(
     )
 }
 
+#[no_mangle]
+pub unsafe extern "C" fn accept4(
+    socket: c_int,
+    address: *mut sockaddr,
+    address_len: *mut socklen_t,
+    flags: c_int,
+) -> c_int {
+    trace_expr!(
+        Sys::accept4(socket, address, address_len, flags),
+        "accept4({}, {:p}, {:p}, {:#x})",
+        socket,
+        address,
+        address_len,
+        flags
+    )
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn bind(
     socket: c_int,
@@ -153,6 +207,17 @@ pub unsafe extern "C" fn recvfrom(
     )
 }
 
+#[no_mangle]
+pub unsafe extern "C" fn recvmsg(socket: c_int, msg: *mut msghdr, flags: c_int) -> ssize_t {
+    trace_expr!(
+        Sys::recvmsg(socket, msg, flags),
+        "recvmsg({}, {:p}, {:#x})",
+        socket,
+        msg,
+        flags
+    )
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn send(
     socket: c_int,
@@ -163,6 +228,17 @@ pub unsafe extern "C" fn send(
     sendto(socket, message, length, flags, ptr::null(), 0)
 }
 
+#[no_mangle]
+pub unsafe extern "C" fn sendmsg(socket: c_int, msg: *const msghdr, flags: c_int) -> ssize_t {
+    trace_expr!(
+        Sys::sendmsg(socket, msg, flags),
+        "sendmsg({}, {:p}, {:#x})",
+        socket,
+        msg,
+        flags
+    )
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn sendto(
     socket: c_int,
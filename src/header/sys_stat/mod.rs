@@ -2,6 +2,7 @@
 
 use crate::{
     c_str::CStr,
+    fs::File,
     header::{
         fcntl::{O_NOFOLLOW, O_PATH},
         time::timespec,
@@ -82,6 +83,11 @@ pub extern "C" fn __fxstat(_ver: c_int, fildes: c_int, buf: *mut stat) -> c_int
     fstat(fildes, buf)
 }
 
+#[no_mangle]
+pub extern "C" fn __fxstat64(ver: c_int, fildes: c_int, buf: *mut stat) -> c_int {
+    __fxstat(ver, fildes, buf)
+}
+
 #[no_mangle]
 pub extern "C" fn futimens(fd: c_int, times: *const timespec) -> c_int {
     Sys::futimens(fd, times)
@@ -90,16 +96,20 @@ pub extern "C" fn futimens(fd: c_int, times: *const timespec) -> c_int {
 #[no_mangle]
 pub unsafe extern "C" fn lstat(path: *const c_char, buf: *mut stat) -> c_int {
     let path = CStr::from_ptr(path);
-    let fd = Sys::open(path, O_PATH | O_NOFOLLOW, 0);
-    if fd < 0 {
-        return -1;
+    match File::open(path, O_PATH | O_NOFOLLOW) {
+        Ok(file) => Sys::fstat(*file, buf),
+        Err(_) => -1,
     }
+}
 
-    let res = Sys::fstat(fd, buf);
-
-    Sys::close(fd);
+#[no_mangle]
+pub unsafe extern "C" fn __lxstat(_ver: c_int, path: *const c_char, buf: *mut stat) -> c_int {
+    lstat(path, buf)
+}
 
-    res
+#[no_mangle]
+pub unsafe extern "C" fn __lxstat64(ver: c_int, path: *const c_char, buf: *mut stat) -> c_int {
+    __lxstat(ver, path, buf)
 }
 
 #[no_mangle]
@@ -122,19 +132,41 @@ pub extern "C" fn mknod(path: *const c_char, mode: mode_t, dev: dev_t) -> c_int
 #[no_mangle]
 pub unsafe extern "C" fn stat(file: *const c_char, buf: *mut stat) -> c_int {
     let file = CStr::from_ptr(file);
-    let fd = Sys::open(file, O_PATH, 0);
-    if fd < 0 {
-        return -1;
+    match File::open(file, O_PATH) {
+        Ok(file) => Sys::fstat(*file, buf),
+        Err(_) => -1,
     }
+}
 
-    let res = Sys::fstat(fd, buf);
-
-    Sys::close(fd);
+#[no_mangle]
+pub unsafe extern "C" fn __xstat(_ver: c_int, file: *const c_char, buf: *mut stat) -> c_int {
+    stat(file, buf)
+}
 
-    res
+#[no_mangle]
+pub unsafe extern "C" fn __xstat64(ver: c_int, file: *const c_char, buf: *mut stat) -> c_int {
+    __xstat(ver, file, buf)
 }
 
 #[no_mangle]
 pub extern "C" fn umask(mask: mode_t) -> mode_t {
     Sys::umask(mask)
 }
+
+// The names large file support gives these, for glibc's binaries. off_t and the rest are already
+// 64 bits, so they are the same functions, which bits/sys/stat.h defines the names to in sources.
+
+#[no_mangle]
+pub extern "C" fn fstat64(fildes: c_int, buf: *mut stat) -> c_int {
+    fstat(fildes, buf)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn lstat64(path: *const c_char, buf: *mut stat) -> c_int {
+    lstat(path, buf)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn stat64(file: *const c_char, buf: *mut stat) -> c_int {
+    stat(file, buf)
+}
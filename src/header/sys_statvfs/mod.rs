@@ -2,6 +2,7 @@
 
 use crate::{
     c_str::CStr,
+    fs::File,
     header::fcntl::O_PATH,
     platform::{types::*, Pal, Sys},
 };
@@ -33,14 +34,21 @@ pub extern "C" fn fstatvfs(fildes: c_int, buf: *mut statvfs) -> c_int {
 #[no_mangle]
 pub unsafe extern "C" fn statvfs(file: *const c_char, buf: *mut statvfs) -> c_int {
     let file = CStr::from_ptr(file);
-    let fd = Sys::open(file, O_PATH, 0);
-    if fd < 0 {
-        return -1;
+    match File::open(file, O_PATH) {
+        Ok(file) => Sys::fstatvfs(*file, buf),
+        Err(_) => -1,
     }
+}
 
-    let res = Sys::fstatvfs(fd, buf);
+// Large file support's names, as glibc's binaries use them. fsblkcnt_t and fsfilcnt_t are 64 bits
+// already.
 
-    Sys::close(fd);
+#[no_mangle]
+pub extern "C" fn fstatvfs64(fildes: c_int, buf: *mut statvfs) -> c_int {
+    fstatvfs(fildes, buf)
+}
 
-    res
+#[no_mangle]
+pub unsafe extern "C" fn statvfs64(file: *const c_char, buf: *mut statvfs) -> c_int {
+    statvfs(file, buf)
 }
@@ -0,0 +1,67 @@
+//! sys/sysinfo.h implementation, following the GNU functions for the memory, load and processors
+//! of the system.
+
+use crate::platform::{types::*, Pal, Sys};
+
+/// The load averages are fixed point numbers with this many bits after the point
+pub const SI_LOAD_SHIFT: c_int = 16;
+
+/// The layout of Linux's struct, where the memory and swap sizes are in units of `mem_unit` bytes
+#[repr(C)]
+pub struct sysinfo {
+    pub uptime: c_long,
+    pub loads: [c_ulong; 3],
+    pub totalram: c_ulong,
+    pub freeram: c_ulong,
+    pub sharedram: c_ulong,
+    pub bufferram: c_ulong,
+    pub totalswap: c_ulong,
+    pub freeswap: c_ulong,
+    pub procs: c_ushort,
+    pub pad: c_ushort,
+    pub totalhigh: c_ulong,
+    pub freehigh: c_ulong,
+    pub mem_unit: c_uint,
+    pub __reserved: [c_char; 256],
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn sysinfo(info: *mut sysinfo) -> c_int {
+    Sys::sysinfo(info)
+}
+
+/// The number of processors this process may run on.
+#[no_mangle]
+pub extern "C" fn get_nprocs() -> c_int {
+    Sys::get_nprocs()
+}
+
+/// The number of processors the system has, including the ones that are offline.
+#[no_mangle]
+pub extern "C" fn get_nprocs_conf() -> c_int {
+    Sys::get_nprocs_conf()
+}
+
+/// Converts an amount of memory from sysinfo into pages.
+fn pages(amount: c_ulong, mem_unit: c_uint) -> c_long {
+    let bytes = amount as u128 * mem_unit.max(1) as u128;
+    (bytes / Sys::getpagesize() as u128) as c_long
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn get_avphys_pages() -> c_long {
+    let mut info: sysinfo = core::mem::zeroed();
+    if Sys::sysinfo(&mut info) < 0 {
+        return -1;
+    }
+    pages(info.freeram, info.mem_unit)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn get_phys_pages() -> c_long {
+    let mut info: sysinfo = core::mem::zeroed();
+    if Sys::sysinfo(&mut info) < 0 {
+        return -1;
+    }
+    pages(info.totalram, info.mem_unit)
+}
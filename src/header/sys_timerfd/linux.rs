@@ -0,0 +1,4 @@
+use crate::platform::types::*;
+
+pub const TFD_CLOEXEC: c_int = 0x8_0000;
+pub const TFD_NONBLOCK: c_int = 0x800;
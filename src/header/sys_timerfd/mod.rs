@@ -0,0 +1,56 @@
+//! sys/timerfd.h implementation, following https://man7.org/linux/man-pages/man2/timerfd_create.2.html
+
+use crate::{
+    header::time::itimerspec,
+    platform::{types::*, Pal, Sys},
+};
+
+pub use self::sys::*;
+
+#[cfg(target_os = "linux")]
+#[path = "linux.rs"]
+pub mod sys;
+
+#[cfg(target_os = "redox")]
+#[path = "redox.rs"]
+pub mod sys;
+
+pub const TFD_TIMER_ABSTIME: c_int = 1;
+pub const TFD_TIMER_CANCEL_ON_SET: c_int = 2;
+
+#[no_mangle]
+pub extern "C" fn timerfd_create(clockid: c_int, flags: c_int) -> c_int {
+    trace_expr!(
+        Sys::timerfd_create(clockid, flags),
+        "timerfd_create({}, {:#x})",
+        clockid,
+        flags
+    )
+}
+
+#[no_mangle]
+pub extern "C" fn timerfd_settime(
+    fd: c_int,
+    flags: c_int,
+    new_value: *const itimerspec,
+    old_value: *mut itimerspec,
+) -> c_int {
+    trace_expr!(
+        Sys::timerfd_settime(fd, flags, new_value, old_value),
+        "timerfd_settime({}, {:#x}, {:p}, {:p})",
+        fd,
+        flags,
+        new_value,
+        old_value
+    )
+}
+
+#[no_mangle]
+pub extern "C" fn timerfd_gettime(fd: c_int, curr_value: *mut itimerspec) -> c_int {
+    trace_expr!(
+        Sys::timerfd_gettime(fd, curr_value),
+        "timerfd_gettime({}, {:p})",
+        fd,
+        curr_value
+    )
+}
@@ -12,8 +12,8 @@ pub const IOV_MAX: c_int = 1024;
 
 #[repr(C)]
 pub struct iovec {
-    iov_base: *mut c_void,
-    iov_len: size_t,
+    pub iov_base: *mut c_void,
+    pub iov_len: size_t,
 }
 
 impl iovec {
@@ -22,7 +22,7 @@ impl iovec {
     }
 }
 
-unsafe fn gather(iovs: &[iovec]) -> Vec<u8> {
+pub(crate) unsafe fn gather(iovs: &[iovec]) -> Vec<u8> {
     let mut vec = Vec::new();
     for iov in iovs.iter() {
         vec.extend_from_slice(iov.to_slice());
@@ -30,12 +30,13 @@ unsafe fn gather(iovs: &[iovec]) -> Vec<u8> {
     vec
 }
 
-unsafe fn scatter(iovs: &[iovec], vec: Vec<u8>) {
-    let mut i = 0;
+/// Copies `data` into the buffers in order, stopping once it runs out
+pub(crate) unsafe fn scatter(iovs: &[iovec], mut data: &[u8]) {
     for iov in iovs.iter() {
         let slice = iov.to_slice();
-        slice.copy_from_slice(&vec[i..]);
-        i += slice.len();
+        let len = slice.len().min(data.len());
+        slice[..len].copy_from_slice(&data[..len]);
+        data = &data[len..];
     }
 }
 
@@ -51,7 +52,7 @@ pub unsafe extern "C" fn readv(fd: c_int, iov: *const iovec, iovcnt: c_int) -> s
 
     let ret = unistd::read(fd, vec.as_mut_ptr() as *mut c_void, vec.len());
 
-    scatter(iovs, vec);
+    scatter(iovs, &vec);
 
     ret
 }
@@ -2,7 +2,7 @@
 //! http://pubs.opengroup.org/onlinepubs/7908799/xsh/syswait.h.html
 
 //use header::sys_resource::rusage;
-use crate::platform::{types::*, Pal, Sys};
+use crate::platform::{self, types::*, Pal, Sys};
 
 pub const WNOHANG: c_int = 1;
 pub const WUNTRACED: c_int = 2;
@@ -47,5 +47,5 @@ pub unsafe extern "C" fn wait(stat_loc: *mut c_int) -> pid_t {
 
 #[no_mangle]
 pub unsafe extern "C" fn waitpid(pid: pid_t, stat_loc: *mut c_int, options: c_int) -> pid_t {
-    Sys::waitpid(pid, stat_loc, options)
+    platform::cancellation_point(|| Sys::waitpid(pid, stat_loc, options))
 }
@@ -0,0 +1,37 @@
+use core::{mem, ptr};
+
+use crate::{
+    header::{
+        sys_socket::{constants::*, sa_family_t, sockaddr, socklen_t},
+        sys_un::sockaddr_un,
+    },
+    platform::{types::*, Pal, PalSocket, Sys},
+};
+
+const LOG_PATH: &[u8] = b"/dev/log";
+
+/// Connects to the socket syslogd reads messages from, returning -1 if it can't.
+pub unsafe fn connect() -> c_int {
+    let fd = Sys::socket(AF_UNIX, SOCK_DGRAM | SOCK_CLOEXEC, 0);
+    if fd < 0 {
+        return -1;
+    }
+
+    let mut addr: sockaddr_un = mem::zeroed();
+    addr.sun_family = AF_UNIX as sa_family_t;
+    ptr::copy_nonoverlapping(
+        LOG_PATH.as_ptr() as *const c_char,
+        addr.sun_path.as_mut_ptr(),
+        LOG_PATH.len(),
+    );
+    if Sys::connect(
+        fd,
+        &addr as *const sockaddr_un as *const sockaddr,
+        mem::size_of::<sockaddr_un>() as socklen_t,
+    ) < 0
+    {
+        Sys::close(fd);
+        return -1;
+    }
+    fd
+}
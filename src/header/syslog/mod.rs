@@ -0,0 +1,233 @@
+//! syslog.h implementation, following http://pubs.opengroup.org/onlinepubs/7908799/xsh/syslog.h.html
+//!
+//! Messages are sent to syslogd through /dev/log on Linux and written to the log scheme on Redox,
+//! in the format of RFC 3164, and they're written to stderr instead if there's no logger to take
+//! them.
+
+use core::{ffi::VaList as va_list, ptr};
+
+use crate::{
+    c_str::CStr,
+    c_vec::CVec,
+    header::{
+        stdio::printf,
+        string::strerror,
+        time::{localtime_r, strftime, time, tm},
+        unistd::close,
+    },
+    io::Write,
+    platform::{self, types::*, Pal, Sys},
+    sync::Mutex,
+};
+
+#[cfg(target_os = "linux")]
+#[path = "linux.rs"]
+mod sys;
+
+#[cfg(target_os = "redox")]
+#[path = "redox.rs"]
+mod sys;
+
+pub const LOG_PID: c_int = 0x01;
+pub const LOG_CONS: c_int = 0x02;
+pub const LOG_ODELAY: c_int = 0x04;
+pub const LOG_NDELAY: c_int = 0x08;
+pub const LOG_NOWAIT: c_int = 0x10;
+pub const LOG_PERROR: c_int = 0x20;
+
+pub const LOG_KERN: c_int = 0 << 3;
+pub const LOG_USER: c_int = 1 << 3;
+pub const LOG_MAIL: c_int = 2 << 3;
+pub const LOG_DAEMON: c_int = 3 << 3;
+pub const LOG_AUTH: c_int = 4 << 3;
+pub const LOG_SYSLOG: c_int = 5 << 3;
+pub const LOG_LPR: c_int = 6 << 3;
+pub const LOG_NEWS: c_int = 7 << 3;
+pub const LOG_UUCP: c_int = 8 << 3;
+pub const LOG_CRON: c_int = 9 << 3;
+pub const LOG_AUTHPRIV: c_int = 10 << 3;
+pub const LOG_FTP: c_int = 11 << 3;
+pub const LOG_LOCAL0: c_int = 16 << 3;
+pub const LOG_LOCAL1: c_int = 17 << 3;
+pub const LOG_LOCAL2: c_int = 18 << 3;
+pub const LOG_LOCAL3: c_int = 19 << 3;
+pub const LOG_LOCAL4: c_int = 20 << 3;
+pub const LOG_LOCAL5: c_int = 21 << 3;
+pub const LOG_LOCAL6: c_int = 22 << 3;
+pub const LOG_LOCAL7: c_int = 23 << 3;
+pub const LOG_NFACILITIES: c_int = 24;
+pub const LOG_FACMASK: c_int = 0x03f8;
+
+pub const LOG_EMERG: c_int = 0;
+pub const LOG_ALERT: c_int = 1;
+pub const LOG_CRIT: c_int = 2;
+pub const LOG_ERR: c_int = 3;
+pub const LOG_WARNING: c_int = 4;
+pub const LOG_NOTICE: c_int = 5;
+pub const LOG_INFO: c_int = 6;
+pub const LOG_DEBUG: c_int = 7;
+pub const LOG_PRIMASK: c_int = 0x07;
+
+struct Log {
+    ident: *const c_char,
+    options: c_int,
+    facility: c_int,
+    mask: c_int,
+    fd: c_int,
+}
+
+unsafe impl Send for Log {}
+
+impl Log {
+    unsafe fn connect(&mut self) {
+        if self.fd < 0 {
+            self.fd = sys::connect();
+        }
+    }
+
+    unsafe fn disconnect(&mut self) {
+        if self.fd >= 0 {
+            close(self.fd);
+            self.fd = -1;
+        }
+    }
+
+    /// Sends `msg`, connecting again once if the logger was restarted.
+    unsafe fn send(&mut self, msg: &[u8]) -> bool {
+        for _ in 0..2 {
+            self.connect();
+            if self.fd >= 0 && Sys::write(self.fd, msg) >= 0 {
+                return true;
+            }
+            self.disconnect();
+        }
+        false
+    }
+}
+
+static LOG: Mutex<Log> = Mutex::new(Log {
+    ident: ptr::null(),
+    options: 0,
+    facility: LOG_USER,
+    mask: 0xff,
+    fd: -1,
+});
+
+/// Copies `fmt`, replacing %m with the description of `errnum`.
+unsafe fn expand_errno(fmt: *const c_char, errnum: c_int) -> CVec<u8> {
+    let mut expanded = CVec::new();
+    let mut bytes = CStr::from_ptr(fmt).to_bytes().iter();
+    while let Some(&b) = bytes.next() {
+        if b != b'%' {
+            let _ = expanded.push(b);
+            continue;
+        }
+        match bytes.next() {
+            Some(b'm') => {
+                for &c in CStr::from_ptr(strerror(errnum)).to_bytes() {
+                    // the description is printed as it is, even if it has a %
+                    if c == b'%' {
+                        let _ = expanded.push(b'%');
+                    }
+                    let _ = expanded.push(c);
+                }
+            }
+            Some(&c) => {
+                let _ = expanded.extend_from_slice(&[b'%', c]);
+            }
+            None => {
+                let _ = expanded.push(b'%');
+            }
+        }
+    }
+    let _ = expanded.push(0);
+    expanded
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn closelog() {
+    let mut log = LOG.lock();
+    log.disconnect();
+    log.ident = ptr::null();
+}
+
+/// Sets the name messages are logged with, which is the program's if `ident` is NULL, the
+/// options and the facility of messages that don't give one.
+#[no_mangle]
+pub unsafe extern "C" fn openlog(ident: *const c_char, option: c_int, facility: c_int) {
+    let mut log = LOG.lock();
+    log.ident = ident;
+    log.options = option;
+    if facility != 0 && facility & !LOG_FACMASK == 0 {
+        log.facility = facility;
+    }
+    if option & LOG_NDELAY != 0 {
+        log.connect();
+    }
+}
+
+/// Sets which priorities are logged, returning the mask before, or leaves it if `mask` is 0.
+#[no_mangle]
+pub unsafe extern "C" fn setlogmask(mask: c_int) -> c_int {
+    let mut log = LOG.lock();
+    let old = log.mask;
+    if mask != 0 {
+        log.mask = mask;
+    }
+    old
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn vsyslog(priority: c_int, fmt: *const c_char, ap: va_list) {
+    let errnum = platform::errno;
+    // bits that aren't a priority or a facility are ignored
+    let mut priority = priority & (LOG_PRIMASK | LOG_FACMASK);
+
+    let mut log = LOG.lock();
+    if log.mask & (1 << (priority & LOG_PRIMASK)) == 0 {
+        return;
+    }
+    if priority & LOG_FACMASK == 0 {
+        priority |= log.facility;
+    }
+
+    let fmt = expand_errno(fmt, errnum);
+    let mut body = CVec::new();
+    printf::printf(&mut body, fmt.as_ptr() as *const c_char, ap);
+
+    let mut timestamp = [0; 32];
+    let now = time(ptr::null_mut());
+    let mut local: tm = core::mem::zeroed();
+    let len = strftime(
+        timestamp.as_mut_ptr(),
+        timestamp.len(),
+        c_str!("%b %e %T ").as_ptr(),
+        localtime_r(&now, &mut local),
+    );
+
+    // everything from the name on is what's written to stderr
+    let mut msg = CVec::new();
+    let _ = write!(msg, "<{}>", priority);
+    let _ = msg.write_all(&*(&timestamp[..len] as *const [c_char] as *const [u8]));
+    let start = msg.len();
+    let ident = if log.ident.is_null() {
+        platform::program_invocation_short_name as *const c_char
+    } else {
+        log.ident
+    };
+    if !ident.is_null() {
+        let _ = msg.write_all(CStr::from_ptr(ident).to_bytes());
+    }
+    if log.options & LOG_PID != 0 {
+        let _ = write!(msg, "[{}]", Sys::getpid());
+    }
+    let _ = msg.write_all(b": ");
+    let _ = msg.write_all(&body);
+
+    let sent = log.send(&msg);
+    if !sent || log.options & LOG_PERROR != 0 {
+        let _ = msg.push(b'\n');
+        Sys::write(2, &msg[start..]);
+    }
+    platform::errno = errnum;
+}
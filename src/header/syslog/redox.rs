@@ -0,0 +1,9 @@
+use crate::{
+    header::fcntl,
+    platform::{types::*, Pal, Sys},
+};
+
+/// Opens the log scheme, which is where messages are logged on Redox, returning -1 if it can't.
+pub unsafe fn connect() -> c_int {
+    Sys::open(c_str!("log:"), fcntl::O_WRONLY | fcntl::O_CLOEXEC, 0)
+}
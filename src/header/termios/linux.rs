@@ -86,6 +86,9 @@ pub const B3000000: usize = 0o010_015;
 pub const B3500000: usize = 0o010_016;
 pub const B4000000: usize = 0o010_017;
 
+pub const CBAUD: usize = 0o010_017;
+pub const CBAUDEX: usize = 0o010_000;
+
 pub const CSIZE: usize = 0o000_060;
 pub const CS5: usize = 0o000_000;
 pub const CS6: usize = 0o000_020;
@@ -98,6 +101,7 @@ pub const PARENB: usize = 0o000_400;
 pub const PARODD: usize = 0o001_000;
 pub const HUPCL: usize = 0o002_000;
 pub const CLOCAL: usize = 0o004_000;
+pub const CRTSCTS: usize = 0o20_000_000_000;
 /* } c_clfag */
 
 /* c_lflag { */
@@ -109,5 +113,12 @@ pub const ECHOK: usize = 0o000_040;
 pub const ECHONL: usize = 0o000_100;
 pub const NOFLSH: usize = 0o000_200;
 pub const TOSTOP: usize = 0o000_400;
+pub const XCASE: usize = 0o000_004;
+pub const ECHOCTL: usize = 0o001_000;
+pub const ECHOPRT: usize = 0o002_000;
+pub const ECHOKE: usize = 0o004_000;
+pub const FLUSHO: usize = 0o010_000;
+pub const PENDIN: usize = 0o040_000;
 pub const IEXTEN: usize = 0o100_000;
+pub const EXTPROC: usize = 0o200_000;
 /* } c_lflag */
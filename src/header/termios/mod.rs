@@ -36,12 +36,12 @@ pub const TCSAFLUSH: usize = 2;
 #[repr(C)]
 #[derive(Default)]
 pub struct termios {
-    c_iflag: tcflag_t,
-    c_oflag: tcflag_t,
-    c_cflag: tcflag_t,
-    c_lflag: tcflag_t,
-    c_line: cc_t,
-    c_cc: [cc_t; NCCS],
+    pub(crate) c_iflag: tcflag_t,
+    pub(crate) c_oflag: tcflag_t,
+    pub(crate) c_cflag: tcflag_t,
+    pub(crate) c_lflag: tcflag_t,
+    pub(crate) c_line: cc_t,
+    pub(crate) c_cc: [cc_t; NCCS],
     __c_ispeed: speed_t,
     __c_ospeed: speed_t,
 }
@@ -51,11 +51,11 @@ pub struct termios {
 #[repr(C)]
 #[derive(Default)]
 pub struct termios {
-    c_iflag: tcflag_t,
-    c_oflag: tcflag_t,
-    c_cflag: tcflag_t,
-    c_lflag: tcflag_t,
-    c_cc: [cc_t; NCCS],
+    pub(crate) c_iflag: tcflag_t,
+    pub(crate) c_oflag: tcflag_t,
+    pub(crate) c_cflag: tcflag_t,
+    pub(crate) c_lflag: tcflag_t,
+    pub(crate) c_cc: [cc_t; NCCS],
 }
 
 #[no_mangle]
@@ -73,38 +73,36 @@ pub unsafe extern "C" fn tcsetattr(fd: c_int, act: c_int, value: *mut termios) -
     sys_ioctl::ioctl(fd, sys_ioctl::TCSETS + act as c_ulong, value as *mut c_void)
 }
 
-#[cfg(target_os = "linux")]
-#[no_mangle]
-pub unsafe extern "C" fn cfgetispeed(termios_p: *const termios) -> speed_t {
-    (*termios_p).__c_ispeed
-}
+// The speed is kept in the CBAUD bits of c_cflag, which is the only one the kernel and the pty
+// scheme look at, so the input and output speeds are always the same
 
-#[cfg(target_os = "redox")]
 #[no_mangle]
 pub unsafe extern "C" fn cfgetispeed(termios_p: *const termios) -> speed_t {
-    //TODO
-    0
+    cfgetospeed(termios_p)
 }
 
-#[cfg(target_os = "linux")]
 #[no_mangle]
 pub unsafe extern "C" fn cfgetospeed(termios_p: *const termios) -> speed_t {
-    (*termios_p).__c_ospeed
+    (*termios_p).c_cflag & CBAUD as speed_t
 }
 
-#[cfg(target_os = "redox")]
+/// A speed of 0 leaves the input speed the same as the output speed.
 #[no_mangle]
-pub unsafe extern "C" fn cfgetospeed(termios_p: *const termios) -> speed_t {
-    //TODO
-    0
+pub unsafe extern "C" fn cfsetispeed(termios_p: *mut termios, speed: speed_t) -> c_int {
+    if speed == 0 {
+        0
+    } else {
+        cfsetospeed(termios_p, speed)
+    }
 }
 
-#[cfg(target_os = "linux")]
 #[no_mangle]
-pub unsafe extern "C" fn cfsetispeed(termios_p: *mut termios, speed: speed_t) -> c_int {
+pub unsafe extern "C" fn cfsetospeed(termios_p: *mut termios, speed: speed_t) -> c_int {
     match speed as usize {
         B0..=B38400 | B57600..=B4000000 => {
-            (*termios_p).__c_ispeed = speed;
+            (*termios_p).c_cflag &= !(CBAUD as tcflag_t);
+            (*termios_p).c_cflag |= speed;
+            set_speed_fields(&mut *termios_p, speed);
             0
         }
         _ => {
@@ -114,21 +112,41 @@ pub unsafe extern "C" fn cfsetispeed(termios_p: *mut termios, speed: speed_t) ->
     }
 }
 
+#[cfg(target_os = "linux")]
+fn set_speed_fields(termios: &mut termios, speed: speed_t) {
+    termios.__c_ispeed = speed;
+    termios.__c_ospeed = speed;
+}
+
 #[cfg(target_os = "redox")]
+fn set_speed_fields(_termios: &mut termios, _speed: speed_t) {}
+
 #[no_mangle]
-pub unsafe extern "C" fn cfsetispeed(termios_p: *mut termios, speed: speed_t) -> c_int {
-    //TODO
-    platform::errno = errno::EINVAL;
-    -1
+pub unsafe extern "C" fn cfsetspeed(termios_p: *mut termios, speed: speed_t) -> c_int {
+    cfsetospeed(termios_p, speed)
 }
 
-#[cfg(target_os = "linux")]
+/// Sets the attributes for raw mode, in which input is given to the program a byte at a time
+/// as it arrives, without being echoed, edited or turned into signals, and output is written
+/// as it is.
 #[no_mangle]
-pub unsafe extern "C" fn cfsetospeed(termios_p: *mut termios, speed: speed_t) -> c_int {
-    match speed as usize {
-        B0..=B38400 | B57600..=B4000000 => {
-            (*termios_p).__c_ospeed = speed;
-            0
+pub unsafe extern "C" fn cfmakeraw(termios_p: *mut termios) {
+    let termios = &mut *termios_p;
+    termios.c_iflag &=
+        !(IGNBRK | BRKINT | PARMRK | ISTRIP | INLCR | IGNCR | ICRNL | IXON) as tcflag_t;
+    termios.c_oflag &= !OPOST as tcflag_t;
+    termios.c_lflag &= !(ECHO | ECHONL | ICANON | ISIG | IEXTEN) as tcflag_t;
+    termios.c_cflag &= !(CSIZE | PARENB) as tcflag_t;
+    termios.c_cflag |= CS8 as tcflag_t;
+    termios.c_cc[VMIN] = 1;
+    termios.c_cc[VTIME] = 0;
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn tcflush(fd: c_int, queue: c_int) -> c_int {
+    match queue as usize {
+        TCIFLUSH | TCOFLUSH | TCIOFLUSH => {
+            sys_ioctl::ioctl(fd, sys_ioctl::TCFLSH, queue as *mut c_void)
         }
         _ => {
             platform::errno = errno::EINVAL;
@@ -137,19 +155,6 @@ pub unsafe extern "C" fn cfsetospeed(termios_p: *mut termios, speed: speed_t) ->
     }
 }
 
-#[cfg(target_os = "redox")]
-#[no_mangle]
-pub unsafe extern "C" fn cfsetospeed(termios_p: *mut termios, speed: speed_t) -> c_int {
-    //TODO
-    platform::errno = errno::EINVAL;
-    -1
-}
-
-#[no_mangle]
-pub unsafe extern "C" fn tcflush(fd: c_int, queue: c_int) -> c_int {
-    sys_ioctl::ioctl(fd, sys_ioctl::TCFLSH, queue as *mut c_void)
-}
-
 #[no_mangle]
 pub unsafe extern "C" fn tcdrain(fd: c_int) -> c_int {
     sys_ioctl::ioctl(fd, sys_ioctl::TCSBRK, 1 as *mut _)
@@ -164,7 +169,13 @@ pub unsafe extern "C" fn tcsendbreak(fd: c_int, _dur: c_int) -> c_int {
 
 #[no_mangle]
 pub unsafe extern "C" fn tcflow(fd: c_int, action: c_int) -> c_int {
-    // non-zero duration is ignored by musl due to it being
-    // implementation-defined. we do the same.
-    sys_ioctl::ioctl(fd, sys_ioctl::TCXONC, action as *mut _)
+    match action as usize {
+        TCOOFF | TCOON | TCIOFF | TCION => {
+            sys_ioctl::ioctl(fd, sys_ioctl::TCXONC, action as *mut _)
+        }
+        _ => {
+            platform::errno = errno::EINVAL;
+            -1
+        }
+    }
 }
@@ -31,6 +31,9 @@ pub const IGNCR: usize = 0o000_200;
 pub const ICRNL: usize = 0o000_400;
 pub const IXON: usize = 0o001_000;
 pub const IXOFF: usize = 0o002_000;
+pub const IXANY: usize = 0o004_000;
+pub const IMAXBEL: usize = 0o020_000;
+pub const IUTF8: usize = 0o040_000;
 /* } c_iflag */
 
 /* c_oflag { */
@@ -78,6 +81,8 @@ pub const B3000000: usize = 0o0_034;
 pub const B3500000: usize = 0o0_035;
 pub const B4000000: usize = 0o0_036;
 
+pub const CBAUD: usize = 0o000_037;
+
 pub const CSIZE: usize = 0o001_400;
 pub const CS5: usize = 0o000_000;
 pub const CS6: usize = 0o000_400;
@@ -91,6 +96,7 @@ pub const PARODD: usize = 0o020_000;
 pub const HUPCL: usize = 0o040_000;
 
 pub const CLOCAL: usize = 0o0100000;
+pub const CRTSCTS: usize = 0x0003_0000;
 /* } c_clfag */
 
 /* c_lflag { */
@@ -103,4 +109,10 @@ pub const ECHONL: usize = 0x0000_0010;
 pub const NOFLSH: usize = 0x8000_0000;
 pub const TOSTOP: usize = 0x0040_0000;
 pub const IEXTEN: usize = 0x0000_0400;
+pub const ECHOKE: usize = 0x0000_0001;
+pub const ECHOPRT: usize = 0x0000_0020;
+pub const ECHOCTL: usize = 0x0000_0040;
+pub const EXTPROC: usize = 0x0000_0800;
+pub const FLUSHO: usize = 0x0080_0000;
+pub const PENDIN: usize = 0x2000_0000;
 /* } c_lflag */
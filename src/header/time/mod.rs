@@ -3,7 +3,11 @@
 use core::convert::{TryFrom, TryInto};
 
 use crate::{
-    header::errno::EOVERFLOW,
+    header::{
+        errno::EOVERFLOW,
+        locale::{self, locale_t},
+        signal::sigevent,
+    },
     platform::{self, types::*, Pal, Sys},
 };
 
@@ -70,8 +74,6 @@ pub struct itimerspec {
     pub it_value: timespec,
 }
 
-pub struct sigevent;
-
 #[no_mangle]
 pub unsafe extern "C" fn asctime(timeptr: *const tm) -> *mut c_char {
     asctime_r(timeptr, ASCTIME.as_mut_ptr().cast())
@@ -391,7 +393,7 @@ pub unsafe extern "C" fn mktime(t: *mut tm) -> time_t {
 
 #[no_mangle]
 pub extern "C" fn nanosleep(rqtp: *const timespec, rmtp: *mut timespec) -> c_int {
-    Sys::nanosleep(rqtp, rmtp)
+    platform::cancellation_point(|| Sys::nanosleep(rqtp, rmtp))
 }
 
 #[no_mangle]
@@ -413,6 +415,17 @@ pub unsafe extern "C" fn strftime(
     }
 }
 
+#[no_mangle]
+pub unsafe extern "C" fn strftime_l(
+    s: *mut c_char,
+    maxsize: size_t,
+    format: *const c_char,
+    timeptr: *const tm,
+    locale: locale_t,
+) -> size_t {
+    locale::with(locale, || strftime(s, maxsize, format, timeptr))
+}
+
 // #[no_mangle]
 pub extern "C" fn strptime(buf: *const c_char, format: *const c_char, tm: *mut tm) -> *mut c_char {
     unimplemented!();
@@ -1,6 +1,9 @@
 use alloc::string::String;
 
-use crate::platform::{self, types::*, WriteByte};
+use crate::{
+    header::locale::{self, LC_TIME},
+    platform::{self, types::*, WriteByte},
+};
 
 use super::tm;
 
@@ -21,6 +24,16 @@ pub unsafe fn strftime<W: WriteByte>(w: &mut W, format: *const c_char, t: *const
                     return false;
                 }
             }};
+            (bytes $bytes:expr) => {{
+                for &b in $bytes.to_bytes() {
+                    w!(byte b);
+                }
+            }};
+            (recurse item $keyword:expr) => {{
+                if !inner_strftime(w, item!($keyword).as_ptr(), t) {
+                    return false;
+                }
+            }};
             (recurse $fmt:expr) => {{
                 let mut fmt = String::with_capacity($fmt.len() + 1);
                 fmt.push_str($fmt);
@@ -42,30 +55,15 @@ pub unsafe fn strftime<W: WriteByte>(w: &mut W, format: *const c_char, t: *const
                 }
             }};
         }
-        const WDAYS: [&str; 7] = [
-            "Sunday",
-            "Monday",
-            "Tuesday",
-            "Wednesday",
-            "Thursday",
-            "Friday",
-            "Saturday",
-        ];
-        const MONTHS: [&str; 12] = [
-            "January",
-            "Febuary",
-            "March",
-            "April",
-            "May",
-            "June",
-            "July",
-            "August",
-            "September",
-            "October",
-            "November",
-            "December",
-        ];
-
+        // The names and formats of the locale, which needn't be UTF-8
+        macro_rules! item {
+            ($keyword:expr) => {
+                item!($keyword, 0)
+            };
+            ($keyword:expr, $index:expr) => {
+                locale::item(LC_TIME, $keyword, $index as usize)
+            };
+        }
         while *format != 0 {
             if *format as u8 != b'%' {
                 w!(byte * format as u8);
@@ -84,10 +82,11 @@ pub unsafe fn strftime<W: WriteByte>(w: &mut W, format: *const c_char, t: *const
                 b'%' => w!(byte b'%'),
                 b'n' => w!(byte b'\n'),
                 b't' => w!(byte b'\t'),
-                b'a' => w!(&WDAYS[(*t).tm_wday as usize][..3]),
-                b'A' => w!(WDAYS[(*t).tm_wday as usize]),
-                b'b' | b'h' => w!(&MONTHS[(*t).tm_mon as usize][..3]),
-                b'B' => w!(MONTHS[(*t).tm_mon as usize]),
+                b'a' => w!(bytes item!("abday", (*t).tm_wday)),
+                b'A' => w!(bytes item!("day", (*t).tm_wday)),
+                b'b' | b'h' => w!(bytes item!("abmon", (*t).tm_mon)),
+                b'B' => w!(bytes item!("mon", (*t).tm_mon)),
+                b'c' => w!(recurse item "d_t_fmt"),
                 b'C' => {
                     let mut year = (*t).tm_year / 100;
                     // Round up
@@ -107,9 +106,9 @@ pub unsafe fn strftime<W: WriteByte>(w: &mut W, format: *const c_char, t: *const
                 b'l' => w!("{:2}", ((*t).tm_hour + 12 - 1) % 12 + 1),
                 b'm' => w!("{:02}", (*t).tm_mon + 1),
                 b'M' => w!("{:02}", (*t).tm_min),
-                b'p' => w!(if (*t).tm_hour < 12 { "AM" } else { "PM" }),
+                b'p' => w!(bytes item!("am_pm", (*t).tm_hour >= 12)),
                 b'P' => w!(if (*t).tm_hour < 12 { "am" } else { "pm" }),
-                b'r' => w!(recurse "%I:%M:%S %p"),
+                b'r' => w!(recurse item "t_fmt_ampm"),
                 b'R' => w!(recurse "%H:%M"),
                 // Nothing is modified in mktime, but the C standard of course requires a mutable pointer ._.
                 b's' => w!("{}", super::mktime(t as *mut tm)),
@@ -119,6 +118,8 @@ pub unsafe fn strftime<W: WriteByte>(w: &mut W, format: *const c_char, t: *const
                 b'U' => w!("{}", ((*t).tm_yday + 7 - (*t).tm_wday) / 7),
                 b'w' => w!("{}", (*t).tm_wday),
                 b'W' => w!("{}", ((*t).tm_yday + 7 - ((*t).tm_wday + 6) % 7) / 7),
+                b'x' => w!(recurse item "d_fmt"),
+                b'X' => w!(recurse item "t_fmt"),
                 b'y' => w!("{:02}", (*t).tm_year % 100),
                 b'Y' => w!("{}", (*t).tm_year + 1900),
                 b'z' => w!("+0000"), // TODO
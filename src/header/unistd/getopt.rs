@@ -31,5 +31,5 @@ pub unsafe extern "C" fn getopt(
     argv: *const *mut c_char,
     optstring: *const c_char,
 ) -> c_int {
-    getopt::getopt_long(argc, argv, optstring, ptr::null(), ptr::null_mut())
+    getopt::getopt_short(argc, argv, optstring)
 }
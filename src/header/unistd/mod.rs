@@ -4,11 +4,15 @@ use core::{convert::TryFrom, mem, ptr, slice};
 
 use crate::{
     c_str::CStr,
+    fs::File,
     header::{
-        errno, fcntl, limits, stdlib::getenv, sys_ioctl, sys_time, sys_utsname, termios,
+        errno, fcntl, limits,
+        stdlib::{self, getenv},
+        sys_ioctl, sys_time, sys_utsname, termios,
         time::timespec,
     },
     platform::{self, types::*, Pal, Sys},
+    sync,
 };
 use alloc::collections::LinkedList;
 
@@ -114,11 +118,6 @@ pub extern "C" fn confstr(name: c_int, buf: *mut c_char, len: size_t) -> size_t
     unimplemented!();
 }
 
-// #[no_mangle]
-pub extern "C" fn crypt(key: *const c_char, salt: *const c_char) -> *mut c_char {
-    unimplemented!();
-}
-
 #[no_mangle]
 pub extern "C" fn dup(fildes: c_int) -> c_int {
     Sys::dup(fildes)
@@ -230,7 +229,8 @@ pub extern "C" fn fork() -> pid_t {
     for prepare in &fork_hooks[0] {
         prepare();
     }
-    let pid = Sys::fork();
+    // After the prepare handlers, which may use what the internal locks protect
+    let pid = unsafe { sync::fork::fork() };
     if pid == 0 {
         for child in &fork_hooks[2] {
             child();
@@ -273,7 +273,7 @@ pub extern "C" fn getcwd(mut buf: *mut c_char, mut size: size_t) -> *mut c_char
             .position(|b| *b == 0)
             .expect("no nul-byte in getcwd string")
             + 1;
-        let heap_buf = unsafe { platform::alloc(len) as *mut c_char };
+        let heap_buf = unsafe { stdlib::malloc(len) as *mut c_char };
         for i in 0..len {
             unsafe {
                 *heap_buf.add(i) = stack_buf[i];
@@ -315,30 +315,40 @@ pub extern "C" fn gethostid() -> c_long {
     unimplemented!();
 }
 
+/// Copies a NUL-terminated field of utsname to `name`, or as much of it as fits if `truncate`,
+/// and otherwise fails with ENAMETOOLONG if it doesn't.
+unsafe fn copy_uts_field(
+    field: &[c_char],
+    name: *mut c_char,
+    len: size_t,
+    truncate: bool,
+) -> c_int {
+    let field = CStr::from_ptr(field.as_ptr()).to_bytes_with_nul();
+    if field.len() > len && !truncate {
+        platform::errno = errno::ENAMETOOLONG;
+        return -1;
+    }
+    ptr::copy_nonoverlapping(field.as_ptr() as *const c_char, name, field.len().min(len));
+    0
+}
+
+/// Gets the NIS domain name of the system, which is cut short if `len` is too small for it.
 #[no_mangle]
-pub unsafe extern "C" fn gethostname(mut name: *mut c_char, mut len: size_t) -> c_int {
+pub unsafe extern "C" fn getdomainname(name: *mut c_char, len: size_t) -> c_int {
     let mut uts = mem::MaybeUninit::<sys_utsname::utsname>::uninit();
-    let err = Sys::uname(uts.as_mut_ptr());
-    if err < 0 {
-        mem::forget(uts);
-        return err;
-    }
-    for c in uts.assume_init().nodename.iter() {
-        if len == 0 {
-            break;
-        }
-        len -= 1;
-
-        *name = *c;
-
-        if *name == 0 {
-            // We do want to copy the zero also, so we check this after the copying.
-            break;
-        }
+    if Sys::uname(uts.as_mut_ptr()) < 0 {
+        return -1;
+    }
+    copy_uts_field(&uts.assume_init_ref().domainname, name, len, true)
+}
 
-        name = name.offset(1);
+#[no_mangle]
+pub unsafe extern "C" fn gethostname(name: *mut c_char, len: size_t) -> c_int {
+    let mut uts = mem::MaybeUninit::<sys_utsname::utsname>::uninit();
+    if Sys::uname(uts.as_mut_ptr()) < 0 {
+        return -1;
     }
-    0
+    copy_uts_field(&uts.assume_init_ref().nodename, name, len, false)
 }
 
 #[no_mangle]
@@ -540,7 +550,7 @@ pub extern "C" fn pwrite(
 pub extern "C" fn read(fildes: c_int, buf: *const c_void, nbyte: size_t) -> ssize_t {
     let buf = unsafe { slice::from_raw_parts_mut(buf as *mut u8, nbyte as usize) };
     trace_expr!(
-        Sys::read(fildes, buf),
+        platform::cancellation_point(|| Sys::read(fildes, buf)),
         "read({}, {:p}, {})",
         fildes,
         buf,
@@ -570,6 +580,15 @@ pub extern "C" fn setgid(gid: gid_t) -> c_int {
     Sys::setregid(gid, gid)
 }
 
+#[no_mangle]
+pub unsafe extern "C" fn sethostname(name: *const c_char, len: size_t) -> c_int {
+    if len > limits::HOST_NAME_MAX {
+        platform::errno = errno::EINVAL;
+        return -1;
+    }
+    Sys::sethostname(slice::from_raw_parts(name as *const u8, len))
+}
+
 #[no_mangle]
 pub extern "C" fn setpgid(pid: pid_t, pgid: pid_t) -> c_int {
     Sys::setpgid(pid, pgid)
@@ -590,9 +609,9 @@ pub extern "C" fn setreuid(ruid: uid_t, euid: uid_t) -> c_int {
     Sys::setreuid(ruid, euid)
 }
 
-// #[no_mangle]
+#[no_mangle]
 pub extern "C" fn setsid() -> pid_t {
-    unimplemented!();
+    Sys::setsid()
 }
 
 #[no_mangle]
@@ -606,8 +625,11 @@ pub extern "C" fn sleep(seconds: c_uint) -> c_uint {
         tv_sec: seconds as time_t,
         tv_nsec: 0,
     };
-    let rmtp = ptr::null_mut();
-    Sys::nanosleep(&rqtp, rmtp);
+    let mut rmtp = timespec::default();
+    if platform::cancellation_point(|| Sys::nanosleep(&rqtp, &mut rmtp)) == -1 {
+        // Interrupted, with what's left
+        return rmtp.tv_sec as c_uint;
+    }
     0
 }
 
@@ -659,16 +681,10 @@ pub extern "C" fn tcsetpgrp(fd: c_int, pgrp: pid_t) -> c_int {
 #[no_mangle]
 pub extern "C" fn truncate(path: *const c_char, length: off_t) -> c_int {
     let file = unsafe { CStr::from_ptr(path) };
-    let fd = Sys::open(file, fcntl::O_WRONLY, 0);
-    if fd < 0 {
-        return -1;
+    match File::open(file, fcntl::O_WRONLY) {
+        Ok(file) => ftruncate(*file, length),
+        Err(_) => -1,
     }
-
-    let res = ftruncate(fd, length);
-
-    Sys::close(fd);
-
-    res
 }
 
 #[no_mangle]
@@ -735,7 +751,7 @@ pub extern "C" fn usleep(useconds: useconds_t) -> c_int {
         tv_nsec: ((useconds % 1_000_000) * 1000) as c_long,
     };
     let rmtp = ptr::null_mut();
-    Sys::nanosleep(&rqtp, rmtp)
+    platform::cancellation_point(|| Sys::nanosleep(&rqtp, rmtp))
 }
 
 // #[no_mangle]
@@ -746,5 +762,48 @@ pub extern "C" fn vfork() -> pid_t {
 #[no_mangle]
 pub extern "C" fn write(fildes: c_int, buf: *const c_void, nbyte: size_t) -> ssize_t {
     let buf = unsafe { slice::from_raw_parts(buf as *const u8, nbyte as usize) };
-    Sys::write(fildes, buf)
+    platform::cancellation_point(|| Sys::write(fildes, buf))
+}
+
+// The large file support names, for glibc's binaries. off_t is always 64 bits, so they're the
+// same functions, and sources get defines from bits/unistd.h instead.
+
+#[no_mangle]
+pub extern "C" fn ftruncate64(fildes: c_int, length: off_t) -> c_int {
+    ftruncate(fildes, length)
+}
+
+#[no_mangle]
+pub extern "C" fn lockf64(fildes: c_int, function: c_int, size: off_t) -> c_int {
+    lockf(fildes, function, size)
+}
+
+#[no_mangle]
+pub extern "C" fn lseek64(fildes: c_int, offset: off_t, whence: c_int) -> off_t {
+    lseek(fildes, offset, whence)
+}
+
+#[no_mangle]
+pub extern "C" fn pread64(
+    fildes: c_int,
+    buf: *mut c_void,
+    nbyte: size_t,
+    offset: off_t,
+) -> ssize_t {
+    pread(fildes, buf, nbyte, offset)
+}
+
+#[no_mangle]
+pub extern "C" fn pwrite64(
+    fildes: c_int,
+    buf: *const c_void,
+    nbyte: size_t,
+    offset: off_t,
+) -> ssize_t {
+    pwrite(fildes, buf, nbyte, offset)
+}
+
+#[no_mangle]
+pub extern "C" fn truncate64(path: *const c_char, length: off_t) -> c_int {
+    truncate(path, length)
 }
@@ -1,7 +1,7 @@
 use core::convert::TryInto;
 
 use crate::{
-    header::errno,
+    header::{errno, limits, sys_sysinfo},
     platform::{self, types::*, Pal, Sys},
 };
 
@@ -14,6 +14,8 @@ pub const _SC_OPEN_MAX: c_int = 4;
 pub const _SC_STREAM_MAX: c_int = 5;
 pub const _SC_TZNAME_MAX: c_int = 6;
 // ...
+pub const _SC_MQ_OPEN_MAX: c_int = 27;
+pub const _SC_MQ_PRIO_MAX: c_int = 28;
 pub const _SC_VERSION: c_int = 29;
 pub const _SC_PAGESIZE: c_int = 30;
 pub const _SC_PAGE_SIZE: c_int = 30;
@@ -24,6 +26,11 @@ pub const _SC_GETPW_R_SIZE_MAX: c_int = 70;
 pub const _SC_LOGIN_NAME_MAX: c_int = 71;
 pub const _SC_TTY_NAME_MAX: c_int = 72;
 // ...
+pub const _SC_NPROCESSORS_CONF: c_int = 83;
+pub const _SC_NPROCESSORS_ONLN: c_int = 84;
+pub const _SC_PHYS_PAGES: c_int = 85;
+pub const _SC_AVPHYS_PAGES: c_int = 86;
+// ...
 pub const _SC_SYMLOOP_MAX: c_int = 173;
 // ...
 pub const _SC_HOST_NAME_MAX: c_int = 180;
@@ -40,6 +47,8 @@ pub extern "C" fn sysconf(name: c_int) -> c_long {
         _SC_OPEN_MAX => 1024,
         _SC_STREAM_MAX => 16,
         _SC_TZNAME_MAX => -1,
+        _SC_MQ_OPEN_MAX => -1,
+        _SC_MQ_PRIO_MAX => limits::MQ_PRIO_MAX as c_long,
         _SC_VERSION => 200809,
         _SC_PAGESIZE => Sys::getpagesize()
             .try_into()
@@ -48,8 +57,12 @@ pub extern "C" fn sysconf(name: c_int) -> c_long {
         _SC_GETPW_R_SIZE_MAX => -1,
         _SC_LOGIN_NAME_MAX => 256,
         _SC_TTY_NAME_MAX => 32,
+        _SC_NPROCESSORS_CONF => sys_sysinfo::get_nprocs_conf() as c_long,
+        _SC_NPROCESSORS_ONLN => sys_sysinfo::get_nprocs() as c_long,
+        _SC_PHYS_PAGES => unsafe { sys_sysinfo::get_phys_pages() },
+        _SC_AVPHYS_PAGES => unsafe { sys_sysinfo::get_avphys_pages() },
         _SC_SYMLOOP_MAX => -1,
-        _SC_HOST_NAME_MAX => 64,
+        _SC_HOST_NAME_MAX => limits::HOST_NAME_MAX as c_long,
         _ => {
             unsafe {
                 platform::errno = errno::EINVAL;
@@ -0,0 +1,30 @@
+//! utmp.h implementation, following the BSD functions of the same name.
+
+use core::ptr;
+
+use crate::{
+    header::{
+        sys_ioctl,
+        unistd::{close, dup2, setsid},
+    },
+    platform::types::*,
+};
+
+/// Makes `fd` the controlling terminal and standard streams of the calling process, in a new
+/// session, closing `fd` itself.
+#[no_mangle]
+pub unsafe extern "C" fn login_tty(fd: c_int) -> c_int {
+    setsid();
+    if sys_ioctl::ioctl(fd, sys_ioctl::TIOCSCTTY, ptr::null_mut()) < 0 {
+        return -1;
+    }
+    for stream in 0..3 {
+        if dup2(fd, stream) < 0 {
+            return -1;
+        }
+    }
+    if fd > 2 {
+        close(fd);
+    }
+    0
+}
@@ -0,0 +1,23 @@
+use crate::{
+    c_str::CStr,
+    header::sys_file::{LOCK_EX, LOCK_SH, LOCK_UN},
+    platform::{types::*, Pal, Sys},
+};
+
+/// Holds a lock on the file, which is given up when it's dropped
+pub struct Lock(c_int);
+
+pub fn lock(fd: c_int, _path: &CStr, exclusive: bool) -> Option<Lock> {
+    let operation = if exclusive { LOCK_EX } else { LOCK_SH };
+    if Sys::flock(fd, operation as c_int) < 0 {
+        None
+    } else {
+        Some(Lock(fd))
+    }
+}
+
+impl Drop for Lock {
+    fn drop(&mut self) {
+        Sys::flock(self.0, LOCK_UN as c_int);
+    }
+}
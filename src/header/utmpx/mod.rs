@@ -0,0 +1,255 @@
+//! utmpx.h implementation, following http://pubs.opengroup.org/onlinepubs/7908799/xsh/utmpx.h.html
+//! and the GNU functions for choosing the file and appending to wtmp.
+//!
+//! The files are arrays of utmpx records, each stored as the bytes of the struct, which has the
+//! same layout as glibc's. Records are written while holding an exclusive lock on the file and
+//! read while holding a shared one. As with glibc, the files aren't created, so accounting is
+//! turned on by creating them.
+
+use core::{mem, ptr, slice};
+
+use crate::{
+    c_str::{CStr, CString},
+    fs::File,
+    header::fcntl,
+    io::{Read, Seek, SeekFrom, Write},
+    platform::types::*,
+};
+
+#[cfg(target_os = "linux")]
+#[path = "linux.rs"]
+mod sys;
+
+#[cfg(target_os = "redox")]
+#[path = "redox.rs"]
+mod sys;
+
+pub const EMPTY: c_short = 0;
+pub const RUN_LVL: c_short = 1;
+pub const BOOT_TIME: c_short = 2;
+pub const NEW_TIME: c_short = 3;
+pub const OLD_TIME: c_short = 4;
+pub const INIT_PROCESS: c_short = 5;
+pub const LOGIN_PROCESS: c_short = 6;
+pub const USER_PROCESS: c_short = 7;
+pub const DEAD_PROCESS: c_short = 8;
+pub const ACCOUNTING: c_short = 9;
+
+const UTMPX_PATH: &[u8] = b"/var/run/utmp\0";
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct exit_status {
+    pub e_termination: c_short,
+    pub e_exit: c_short,
+}
+
+/// The time of a record, which is 32 bits so the records are the same size everywhere
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct __utmpx_tv {
+    pub tv_sec: i32,
+    pub tv_usec: i32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct utmpx {
+    pub ut_type: c_short,
+    pub ut_pid: pid_t,
+    pub ut_line: [c_char; 32],
+    pub ut_id: [c_char; 4],
+    pub ut_user: [c_char; 32],
+    pub ut_host: [c_char; 256],
+    pub ut_exit: exit_status,
+    pub ut_session: i32,
+    pub ut_tv: __utmpx_tv,
+    pub ut_addr_v6: [i32; 4],
+    pub __reserved: [c_char; 20],
+}
+
+const EMPTY_ENTRY: utmpx = utmpx {
+    ut_type: EMPTY,
+    ut_pid: 0,
+    ut_line: [0; 32],
+    ut_id: [0; 4],
+    ut_user: [0; 32],
+    ut_host: [0; 256],
+    ut_exit: exit_status {
+        e_termination: 0,
+        e_exit: 0,
+    },
+    ut_session: 0,
+    ut_tv: __utmpx_tv {
+        tv_sec: 0,
+        tv_usec: 0,
+    },
+    ut_addr_v6: [0; 4],
+    __reserved: [0; 20],
+};
+
+static mut PATH: Option<CString> = None;
+static mut FILE: Option<File> = None;
+static mut ENTRY: utmpx = EMPTY_ENTRY;
+
+unsafe fn path() -> &'static CStr {
+    match &PATH {
+        Some(path) => path,
+        None => CStr::from_bytes_with_nul_unchecked(UTMPX_PATH),
+    }
+}
+
+/// Opens the file if it isn't already, for writing if we're allowed to.
+unsafe fn file() -> Option<&'static File> {
+    if FILE.is_none() {
+        let path = path();
+        let file = File::open(path, fcntl::O_RDWR)
+            .or_else(|_| File::open(path, fcntl::O_RDONLY))
+            .ok()?;
+        FILE = Some(file);
+    }
+    FILE.as_ref()
+}
+
+fn as_bytes(entry: &utmpx) -> &[u8] {
+    unsafe { slice::from_raw_parts(entry as *const utmpx as *const u8, mem::size_of::<utmpx>()) }
+}
+
+fn read_entry(mut file: &File, entry: &mut utmpx) -> bool {
+    let buf = unsafe {
+        slice::from_raw_parts_mut(entry as *mut utmpx as *mut u8, mem::size_of::<utmpx>())
+    };
+    file.read_exact(buf).is_ok()
+}
+
+/// Appends `entry` to `file`, after the last whole record in case the one before it was cut
+/// short.
+fn append(mut file: &File, entry: &utmpx) -> bool {
+    let len = match file.seek(SeekFrom::End(0)) {
+        Ok(len) => len,
+        Err(_) => return false,
+    };
+    let size = mem::size_of::<utmpx>() as u64;
+    file.seek(SeekFrom::Start(len - len % size)).is_ok() && file.write_all(as_bytes(entry)).is_ok()
+}
+
+/// Whether `entry` is what getutxid would find for `id`
+fn same_id(entry: &utmpx, id: &utmpx) -> bool {
+    match id.ut_type {
+        RUN_LVL | BOOT_TIME | NEW_TIME | OLD_TIME => entry.ut_type == id.ut_type,
+        INIT_PROCESS | LOGIN_PROCESS | USER_PROCESS | DEAD_PROCESS => {
+            matches!(
+                entry.ut_type,
+                INIT_PROCESS | LOGIN_PROCESS | USER_PROCESS | DEAD_PROCESS
+            ) && entry.ut_id == id.ut_id
+        }
+        _ => false,
+    }
+}
+
+/// Reads records from where the last one was read until one matches.
+unsafe fn find<F: Fn(&utmpx) -> bool>(matches: F) -> *mut utmpx {
+    let file = match file() {
+        Some(file) => file,
+        None => return ptr::null_mut(),
+    };
+    let _lock = match sys::lock(**file, path(), false) {
+        Some(lock) => lock,
+        None => return ptr::null_mut(),
+    };
+
+    let mut entry = EMPTY_ENTRY;
+    while read_entry(file, &mut entry) {
+        if matches(&entry) {
+            ENTRY = entry;
+            return &mut ENTRY;
+        }
+    }
+    ptr::null_mut()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn endutxent() {
+    FILE = None;
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn getutxent() -> *mut utmpx {
+    find(|_| true)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn getutxid(id: *const utmpx) -> *mut utmpx {
+    let id = *id;
+    find(|entry| same_id(entry, &id))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn getutxline(line: *const utmpx) -> *mut utmpx {
+    let line = (*line).ut_line;
+    find(|entry| matches!(entry.ut_type, LOGIN_PROCESS | USER_PROCESS) && entry.ut_line == line)
+}
+
+/// Replaces the record getutxid would find for `ut` with it, or adds it if there isn't one.
+#[no_mangle]
+pub unsafe extern "C" fn pututxline(ut: *const utmpx) -> *mut utmpx {
+    let new = *ut;
+    let mut file = match file() {
+        Some(file) => file,
+        None => return ptr::null_mut(),
+    };
+    let _lock = match sys::lock(**file, path(), true) {
+        Some(lock) => lock,
+        None => return ptr::null_mut(),
+    };
+
+    if file.seek(SeekFrom::Start(0)).is_err() {
+        return ptr::null_mut();
+    }
+    let size = mem::size_of::<utmpx>() as i64;
+    let mut entry = EMPTY_ENTRY;
+    let written = loop {
+        if !read_entry(file, &mut entry) {
+            break append(file, &new);
+        }
+        if same_id(&entry, &new) {
+            break file.seek(SeekFrom::Current(-size)).is_ok()
+                && file.write_all(as_bytes(&new)).is_ok();
+        }
+    };
+
+    if written {
+        ENTRY = new;
+        &mut ENTRY
+    } else {
+        ptr::null_mut()
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn setutxent() {
+    if let Some(mut file) = FILE.as_ref() {
+        let _ = file.seek(SeekFrom::Start(0));
+    }
+}
+
+/// Appends `ut` to `file`, which is usually the wtmp file of all the sessions there have been.
+#[no_mangle]
+pub unsafe extern "C" fn updwtmpx(file: *const c_char, ut: *const utmpx) {
+    let path = CStr::from_ptr(file);
+    let file = match File::open(path, fcntl::O_WRONLY) {
+        Ok(file) => file,
+        Err(_) => return,
+    };
+    if let Some(_lock) = sys::lock(*file, path, true) {
+        append(&file, &*ut);
+    }
+}
+
+/// Chooses the file the other functions use instead of the utmp file.
+#[no_mangle]
+pub unsafe extern "C" fn utmpxname(file: *const c_char) -> c_int {
+    FILE = None;
+    PATH = Some(CStr::from_ptr(file).into());
+    0
+}
@@ -0,0 +1,92 @@
+//! Redox has no file locks, so a file is locked by creating the file with ".lock" added to its
+//! path, which the pid of the process holding it is written to. Writers take turns creating it,
+//! and readers wait for it to be gone, so they don't need to be able to write to the directory.
+//! One left behind by a process that's gone is removed.
+
+use alloc::vec::Vec;
+use core::{fmt::Write, ptr, str};
+
+use crate::{
+    c_str::{CStr, CString},
+    fs::File,
+    header::{errno, fcntl, time::timespec},
+    io::Read,
+    platform::{self, types::*, FileWriter, Pal, PalSignal, Sys},
+};
+
+/// How many times to wait for the lock to be given up, a millisecond each
+const TRIES: usize = 1000;
+
+/// Holds a lock on the file, which is given up when it's dropped
+pub struct Lock(Option<CString>);
+
+pub fn lock(_fd: c_int, path: &CStr, exclusive: bool) -> Option<Lock> {
+    let mut lock_path: Vec<u8> = path.to_bytes().to_vec();
+    lock_path.extend_from_slice(b".lock");
+    let lock_path = CString::new(lock_path).ok()?;
+
+    for _ in 0..TRIES {
+        if exclusive {
+            let fd = Sys::open(
+                &lock_path,
+                fcntl::O_WRONLY | fcntl::O_CREAT | fcntl::O_EXCL | fcntl::O_CLOEXEC,
+                0o644,
+            );
+            if fd >= 0 {
+                let file = File::new(fd);
+                let _ = write!(FileWriter(*file), "{}", Sys::getpid());
+                return Some(Lock(Some(lock_path)));
+            }
+            if unsafe { platform::errno } != errno::EEXIST {
+                return None;
+            }
+        } else if !exists(&lock_path) {
+            return Some(Lock(None));
+        }
+
+        if is_stale(&lock_path) {
+            Sys::unlink(&lock_path);
+        } else {
+            let wait = timespec {
+                tv_sec: 0,
+                tv_nsec: 1_000_000,
+            };
+            Sys::nanosleep(&wait, ptr::null_mut());
+        }
+    }
+    unsafe { platform::errno = errno::EAGAIN };
+    None
+}
+
+fn exists(path: &CStr) -> bool {
+    Sys::access(path, 0) == 0
+}
+
+/// Whether the lock was left by a process that's gone, which it can't be until its pid has
+/// been written.
+fn is_stale(path: &CStr) -> bool {
+    let mut file = match File::open(path, fcntl::O_RDONLY) {
+        Ok(file) => file,
+        Err(_) => return false,
+    };
+    let mut buf = [0; 16];
+    let len = match file.read(&mut buf) {
+        Ok(len) => len,
+        Err(_) => return false,
+    };
+    match str::from_utf8(&buf[..len])
+        .ok()
+        .and_then(|s| s.parse().ok())
+    {
+        Some(pid) => Sys::kill(pid, 0) < 0 && unsafe { platform::errno } == errno::ESRCH,
+        None => false,
+    }
+}
+
+impl Drop for Lock {
+    fn drop(&mut self) {
+        if let Some(path) = &self.0 {
+            Sys::unlink(path);
+        }
+    }
+}
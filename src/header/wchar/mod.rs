@@ -4,11 +4,13 @@ use core::{char, ffi::VaList as va_list, mem, ptr, slice, usize};
 
 use crate::{
     header::{
-        ctype::isspace, errno::ERANGE, stdio::*, stdlib::MB_CUR_MAX, string, time::*, wctype::*,
+        ctype::isspace, errno::ERANGE, locale::locale_t, stdio::*, stdlib::MB_CUR_MAX, string,
+        time::*, wctype::*,
     },
     platform::{self, types::*},
 };
 
+mod multibyte;
 mod utf8;
 #[repr(C)]
 #[derive(Clone, Copy)]
@@ -120,7 +122,6 @@ pub unsafe extern "C" fn mbrlen(s: *const c_char, n: size_t, ps: *mut mbstate_t)
     mbrtowc(ptr::null_mut(), s, n, &mut INTERNAL)
 }
 
-//Only works for UTF8 at the moment
 #[no_mangle]
 pub unsafe extern "C" fn mbrtowc(
     pwc: *mut wchar_t,
@@ -133,11 +134,15 @@ pub unsafe extern "C" fn mbrtowc(
     if ps.is_null() {
         let ps = &mut INTERNAL;
     }
-    if s.is_null() {
-        let xs: [c_char; 1] = [0];
-        utf8::mbrtowc(pwc, &xs[0] as *const c_char, 1, ps)
+    let xs: [c_char; 1] = [0];
+    let (s, n) = if s.is_null() {
+        (&xs[0] as *const c_char, 1)
     } else {
-        utf8::mbrtowc(pwc, s, n, ps)
+        (s, n)
+    };
+    match multibyte::charset() {
+        Some(charset) => multibyte::mbrtowc(charset, pwc, s, n),
+        None => utf8::mbrtowc(pwc, s, n, ps),
     }
 }
 
@@ -272,7 +277,10 @@ pub unsafe extern "C" fn wcrtomb(s: *mut c_char, wc: wchar_t, ps: *mut mbstate_t
         (s, wc)
     };
 
-    utf8::wcrtomb(s_cpy, wc_cpy, ps)
+    match multibyte::charset() {
+        Some(charset) => multibyte::wcrtomb(charset, s_cpy, wc_cpy),
+        None => utf8::wcrtomb(s_cpy, wc_cpy, ps),
+    }
 }
 
 #[no_mangle]
@@ -304,6 +312,15 @@ pub unsafe extern "C" fn wcscoll(ws1: *const wchar_t, ws2: *const wchar_t) -> c_
     wcscmp(ws1, ws2)
 }
 
+#[no_mangle]
+pub unsafe extern "C" fn wcscoll_l(
+    ws1: *const wchar_t,
+    ws2: *const wchar_t,
+    _locale: locale_t,
+) -> c_int {
+    wcscoll(ws1, ws2)
+}
+
 #[no_mangle]
 pub unsafe extern "C" fn wcscpy(ws1: *mut wchar_t, ws2: *const wchar_t) -> *mut wchar_t {
     let mut i = 0;
@@ -436,14 +453,68 @@ pub unsafe extern "C" fn wcsrchr(ws1: *const wchar_t, wc: wchar_t) -> *mut wchar
     last_matching_wc as *mut wchar_t
 }
 
-// #[no_mangle]
-pub extern "C" fn wcsrtombs(
+//Convert a limited amount of wide characters to a multibyte string
+//Required for in POSIX.1-2008
+#[no_mangle]
+pub unsafe extern "C" fn wcsnrtombs(
     dst: *mut c_char,
     src: *mut *const wchar_t,
+    nwc: size_t,
     len: size_t,
     ps: *mut mbstate_t,
 ) -> size_t {
-    unimplemented!();
+    let mut ws = *src;
+    let mut written = 0;
+
+    for _ in 0..nwc {
+        let mut bytes: [c_char; MB_CUR_MAX as usize] = [0; MB_CUR_MAX as usize];
+        let wc = *ws;
+        let amount = wcrtomb(bytes.as_mut_ptr(), wc, ps);
+
+        // Stop at a character that can't be converted, leaving the source pointing to it.
+        if amount == -1isize as usize {
+            if !dst.is_null() {
+                *src = ws;
+            }
+            return amount;
+        }
+
+        // Stop early when the next character doesn't fit in the destination buffer.
+        if !dst.is_null() {
+            if written + amount > len {
+                break;
+            }
+            ptr::copy_nonoverlapping(bytes.as_ptr(), dst.add(written), amount);
+        }
+
+        // Stop after converting a null character and return a NULL source pointer to the
+        // caller, not including the null character in the number of bytes stored.
+        if wc == 0 {
+            if !dst.is_null() {
+                *src = ptr::null();
+            }
+            return written;
+        }
+
+        written += amount;
+        ws = ws.add(1);
+    }
+
+    if !dst.is_null() {
+        *src = ws;
+    }
+    written
+}
+
+//Convert a wide string to a multibyte string
+#[no_mangle]
+pub unsafe extern "C" fn wcsrtombs(
+    dst: *mut c_char,
+    src: *mut *const wchar_t,
+    len: size_t,
+    ps: *mut mbstate_t,
+) -> size_t {
+    wcsnrtombs(dst, src, size_t::max_value(), len, ps)
 }
 
 #[no_mangle]
@@ -656,9 +727,13 @@ pub extern "C" fn wcsxfrm(ws1: *mut wchar_t, ws2: *const wchar_t, n: size_t) ->
 }
 
 #[no_mangle]
-pub extern "C" fn wctob(c: wint_t) -> c_int {
-    if c <= 0x7F {
-        c as c_int
+pub unsafe extern "C" fn wctob(c: wint_t) -> c_int {
+    let mut bytes: [c_char; MB_CUR_MAX as usize] = [0; MB_CUR_MAX as usize];
+    let saved_errno = platform::errno;
+    let amount = wcrtomb(bytes.as_mut_ptr(), c as wchar_t, ptr::null_mut());
+    platform::errno = saved_errno;
+    if amount == 1 {
+        bytes[0] as u8 as c_int
     } else {
         EOF
     }
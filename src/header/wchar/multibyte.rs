@@ -0,0 +1,65 @@
+//! Multibyte strings in the character sets other than UTF-8 that a locale can have as its
+//! charmap.
+
+use core::slice;
+
+use crate::{
+    header::{
+        errno,
+        iconv::{DecodeError, Multibyte},
+        locale::{self, LC_CTYPE},
+        stdlib::MB_CUR_MAX,
+    },
+    platform::{self, types::*},
+};
+
+/// The charmap the character set was last looked up for, and what it is
+#[thread_local]
+static mut CURRENT: (*const c_char, Option<Multibyte>) = (0 as *const c_char, None);
+
+/// The character set of multibyte strings in the current locale, or None for UTF-8.
+pub unsafe fn charset() -> Option<Multibyte> {
+    let charmap = locale::item(LC_CTYPE, "charmap", 0);
+    if CURRENT.0 != charmap.as_ptr() {
+        let charset = Multibyte::new(charmap.to_bytes()).filter(|charset| !charset.is_utf8());
+        CURRENT = (charmap.as_ptr(), charset);
+    }
+    CURRENT.1
+}
+
+//It's guaranteed that we don't have any nullpointers here
+pub unsafe fn mbrtowc(charset: Multibyte, pwc: *mut wchar_t, s: *const c_char, n: usize) -> usize {
+    if n == 0 {
+        return -2isize as usize;
+    }
+    let input = slice::from_raw_parts(s as *const u8, n.min(MB_CUR_MAX as usize));
+    match charset.decode(input) {
+        Ok((c, size)) => {
+            if !pwc.is_null() {
+                *pwc = c as wchar_t;
+            }
+            if c != 0 {
+                size
+            } else {
+                0
+            }
+        }
+        Err(DecodeError::Incomplete) => -2isize as usize,
+        Err(DecodeError::Invalid) => {
+            platform::errno = errno::EILSEQ;
+            -1isize as usize
+        }
+    }
+}
+
+//It's guaranteed that we don't have any nullpointers here
+pub unsafe fn wcrtomb(charset: Multibyte, s: *mut c_char, wc: wchar_t) -> usize {
+    let out = slice::from_raw_parts_mut(s as *mut u8, MB_CUR_MAX as usize);
+    match charset.encode(wc as u32, out) {
+        Ok(size) => size,
+        Err(_) => {
+            platform::errno = errno::EILSEQ;
+            -1isize as usize
+        }
+    }
+}
@@ -1,13 +1,11 @@
 //! wchar implementation for Redox, following http://pubs.opengroup.org/onlinepubs/7908799/xsh/wctype.h.html
 
-use self::casecmp::casemap;
-use crate::{c_str::CStr, header::ctype, platform::types::*};
+use crate::{c_str::CStr, header::locale::locale_t, platform::types::*};
 
-mod alpha;
-mod casecmp;
-mod punct;
+mod tables;
 
 pub type wctype_t = u32;
+pub type wctrans_t = u32;
 
 pub const WEOF: wint_t = 0xFFFF_FFFFu32;
 
@@ -24,21 +22,33 @@ pub const WCTYPE_SPACE: wctype_t = 10;
 pub const WCTYPE_UPPER: wctype_t = 11;
 pub const WCTYPE_XDIGIT: wctype_t = 12;
 
+pub const WCTRANS_TOLOWER: wctrans_t = 1;
+pub const WCTRANS_TOUPPER: wctrans_t = 2;
+
+/// The classes of `wc`, as the bits of tables::CLASSES.
+fn classes(wc: wint_t) -> u16 {
+    if wc > 0x10FFFF {
+        return 0;
+    }
+    let run = tables::CLASSES.partition_point(|&(first, _)| first <= wc);
+    tables::CLASSES[run - 1].1
+}
+
+/// `wc` moved by the distance of the run of `table` it is in, if it is in one.
+fn map(table: &[(u32, u32, u8, i32)], wc: wint_t) -> wint_t {
+    let run = table.partition_point(|&(first, _, _, _)| first <= wc);
+    match run.checked_sub(1).map(|run| table[run]) {
+        Some((first, last, step, delta)) if wc <= last && (wc - first) % u32::from(step) == 0 => {
+            (wc as i32 + delta) as wint_t
+        }
+        _ => wc,
+    }
+}
+
 #[no_mangle]
 pub extern "C" fn iswctype(wc: wint_t, desc: wctype_t) -> c_int {
     match desc {
-        WCTYPE_ALNUM => iswalnum(wc),
-        WCTYPE_ALPHA => iswalpha(wc),
-        WCTYPE_BLANK => iswblank(wc),
-        WCTYPE_CNTRL => iswcntrl(wc),
-        WCTYPE_DIGIT => iswdigit(wc),
-        WCTYPE_GRAPH => iswgraph(wc),
-        WCTYPE_LOWER => iswlower(wc),
-        WCTYPE_PRINT => iswprint(wc),
-        WCTYPE_PUNCT => iswpunct(wc),
-        WCTYPE_SPACE => iswspace(wc),
-        WCTYPE_UPPER => iswupper(wc),
-        WCTYPE_XDIGIT => iswxdigit(wc),
+        WCTYPE_ALNUM..=WCTYPE_XDIGIT => c_int::from(classes(wc) & 1 << (desc - 1) != 0),
         _ => 0,
     }
 }
@@ -65,111 +75,180 @@ pub unsafe extern "C" fn wctype(name: *const c_char) -> wctype_t {
 
 #[no_mangle]
 pub extern "C" fn iswalnum(wc: wint_t) -> c_int {
-    c_int::from(iswdigit(wc) != 0 || iswalpha(wc) != 0)
+    iswctype(wc, WCTYPE_ALNUM)
 }
 
 #[no_mangle]
 pub extern "C" fn iswalpha(wc: wint_t) -> c_int {
-    c_int::from(alpha::is(wc as usize))
+    iswctype(wc, WCTYPE_ALPHA)
 }
 
 #[no_mangle]
 pub extern "C" fn iswblank(wc: wint_t) -> c_int {
-    ctype::isblank(wc as c_int)
+    iswctype(wc, WCTYPE_BLANK)
 }
 
 #[no_mangle]
 pub extern "C" fn iswcntrl(wc: wint_t) -> c_int {
-    c_int::from(
-        wc < 32
-            || wc.wrapping_sub(0x7f) < 33
-            || wc.wrapping_sub(0x2028) < 2
-            || wc.wrapping_sub(0xfff9) < 3,
-    )
+    iswctype(wc, WCTYPE_CNTRL)
 }
 
 #[no_mangle]
 pub extern "C" fn iswdigit(wc: wint_t) -> c_int {
-    c_int::from(wc.wrapping_sub('0' as wint_t) < 10)
+    iswctype(wc, WCTYPE_DIGIT)
 }
 
 #[no_mangle]
 pub extern "C" fn iswgraph(wc: wint_t) -> c_int {
-    c_int::from(iswspace(wc) == 0 && iswprint(wc) != 0)
+    iswctype(wc, WCTYPE_GRAPH)
 }
 
 #[no_mangle]
 pub extern "C" fn iswlower(wc: wint_t) -> c_int {
-    c_int::from(towupper(wc) != wc)
+    iswctype(wc, WCTYPE_LOWER)
 }
 
 #[no_mangle]
 pub extern "C" fn iswprint(wc: wint_t) -> c_int {
-    if wc < 0xff {
-        c_int::from((wc + 1 & 0x7f) >= 0x21)
-    } else if wc < 0x2028
-        || wc.wrapping_sub(0x202a) < 0xd800 - 0x202a
-        || wc.wrapping_sub(0xe000) < 0xfff9 - 0xe000
-    {
-        1
-    } else if wc.wrapping_sub(0xfffc) > 0x10ffff - 0xfffc || (wc & 0xfffe) == 0xfffe {
-        0
-    } else {
-        1
-    }
+    iswctype(wc, WCTYPE_PRINT)
 }
 
 #[no_mangle]
 pub extern "C" fn iswpunct(wc: wint_t) -> c_int {
-    c_int::from(punct::is(wc as usize))
+    iswctype(wc, WCTYPE_PUNCT)
 }
 
 #[no_mangle]
 pub extern "C" fn iswspace(wc: wint_t) -> c_int {
-    c_int::from(
-        [
-            ' ' as wint_t,
-            '\t' as wint_t,
-            '\n' as wint_t,
-            '\r' as wint_t,
-            11,
-            12,
-            0x0085,
-            0x2000,
-            0x2001,
-            0x2002,
-            0x2003,
-            0x2004,
-            0x2005,
-            0x2006,
-            0x2008,
-            0x2009,
-            0x200a,
-            0x2028,
-            0x2029,
-            0x205f,
-            0x3000,
-        ]
-        .contains(&wc),
-    )
+    iswctype(wc, WCTYPE_SPACE)
 }
 
 #[no_mangle]
 pub extern "C" fn iswupper(wc: wint_t) -> c_int {
-    c_int::from(towlower(wc) != wc)
+    iswctype(wc, WCTYPE_UPPER)
 }
 
 #[no_mangle]
 pub extern "C" fn iswxdigit(wc: wint_t) -> c_int {
-    c_int::from(wc.wrapping_sub('0' as wint_t) < 10 || (wc | 32).wrapping_sub('a' as wint_t) < 6)
+    iswctype(wc, WCTYPE_XDIGIT)
 }
 
 #[no_mangle]
 pub extern "C" fn towlower(wc: wint_t) -> wint_t {
-    casemap(wc, 0)
+    map(&tables::TO_LOWER, wc)
 }
 
 #[no_mangle]
 pub extern "C" fn towupper(wc: wint_t) -> wint_t {
-    casemap(wc, 1)
+    map(&tables::TO_UPPER, wc)
+}
+
+#[no_mangle]
+pub extern "C" fn towctrans(wc: wint_t, desc: wctrans_t) -> wint_t {
+    match desc {
+        WCTRANS_TOLOWER => towlower(wc),
+        WCTRANS_TOUPPER => towupper(wc),
+        _ => wc,
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn wctrans(name: *const c_char) -> wctrans_t {
+    match CStr::from_ptr(name).to_bytes() {
+        b"tolower" => WCTRANS_TOLOWER,
+        b"toupper" => WCTRANS_TOUPPER,
+        _ => 0,
+    }
+}
+
+// The classes and case mappings are the same in every locale
+
+#[no_mangle]
+pub extern "C" fn iswalnum_l(wc: wint_t, _locale: locale_t) -> c_int {
+    iswalnum(wc)
+}
+
+#[no_mangle]
+pub extern "C" fn iswalpha_l(wc: wint_t, _locale: locale_t) -> c_int {
+    iswalpha(wc)
+}
+
+#[no_mangle]
+pub extern "C" fn iswblank_l(wc: wint_t, _locale: locale_t) -> c_int {
+    iswblank(wc)
+}
+
+#[no_mangle]
+pub extern "C" fn iswcntrl_l(wc: wint_t, _locale: locale_t) -> c_int {
+    iswcntrl(wc)
+}
+
+#[no_mangle]
+pub extern "C" fn iswdigit_l(wc: wint_t, _locale: locale_t) -> c_int {
+    iswdigit(wc)
+}
+
+#[no_mangle]
+pub extern "C" fn iswgraph_l(wc: wint_t, _locale: locale_t) -> c_int {
+    iswgraph(wc)
+}
+
+#[no_mangle]
+pub extern "C" fn iswlower_l(wc: wint_t, _locale: locale_t) -> c_int {
+    iswlower(wc)
+}
+
+#[no_mangle]
+pub extern "C" fn iswprint_l(wc: wint_t, _locale: locale_t) -> c_int {
+    iswprint(wc)
+}
+
+#[no_mangle]
+pub extern "C" fn iswpunct_l(wc: wint_t, _locale: locale_t) -> c_int {
+    iswpunct(wc)
+}
+
+#[no_mangle]
+pub extern "C" fn iswspace_l(wc: wint_t, _locale: locale_t) -> c_int {
+    iswspace(wc)
+}
+
+#[no_mangle]
+pub extern "C" fn iswupper_l(wc: wint_t, _locale: locale_t) -> c_int {
+    iswupper(wc)
+}
+
+#[no_mangle]
+pub extern "C" fn iswxdigit_l(wc: wint_t, _locale: locale_t) -> c_int {
+    iswxdigit(wc)
+}
+
+#[no_mangle]
+pub extern "C" fn iswctype_l(wc: wint_t, desc: wctype_t, _locale: locale_t) -> c_int {
+    iswctype(wc, desc)
+}
+
+#[no_mangle]
+pub extern "C" fn towlower_l(wc: wint_t, _locale: locale_t) -> wint_t {
+    towlower(wc)
+}
+
+#[no_mangle]
+pub extern "C" fn towupper_l(wc: wint_t, _locale: locale_t) -> wint_t {
+    towupper(wc)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn wctype_l(name: *const c_char, _locale: locale_t) -> wctype_t {
+    wctype(name)
+}
+
+#[no_mangle]
+pub extern "C" fn towctrans_l(wc: wint_t, desc: wctrans_t, _locale: locale_t) -> wint_t {
+    towctrans(wc, desc)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn wctrans_l(name: *const c_char, _locale: locale_t) -> wctrans_t {
+    wctrans(name)
 }
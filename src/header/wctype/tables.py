@@ -0,0 +1,109 @@
+#!/usr/bin/env python3
+# Writes tables.rs from the i18n_ctype locale source of glibc, which glibc generates from the
+# Unicode data files: python3 tables.py [/usr/share/i18n/locales/i18n_ctype] > tables.rs
+#
+# The tables in the tree were made from the i18n_ctype of glibc 2.36, for Unicode 14.0.0.
+
+import re
+import sys
+
+CLASSES = [
+    "alnum", "alpha", "blank", "cntrl", "digit", "graph", "lower", "print", "punct", "space",
+    "upper", "xdigit",
+]
+
+path = sys.argv[1] if len(sys.argv) > 1 else "/usr/share/i18n/locales/i18n_ctype"
+source = open(path).read()
+version = re.search(r'^title\s+"Unicode ([0-9.]+)', source, re.M).group(1)
+
+# The value of each keyword, and of each class and map by its name
+sections = {}
+for line in re.sub(r"%[^\n]*", "", source).replace("/\n", "").split("\n"):
+    m = re.match(r"^(\w+)\s+(.*)$", line.strip())
+    if m:
+        key, value = m.groups()
+        if key in ("class", "map"):
+            key, value = re.match(r'"(\w+)";\s*(.*)', value).groups()
+        sections[key] = value
+
+
+def code(symbol):
+    return int(symbol[2:-1], 16)
+
+
+def members(value):
+    out = set()
+    for item in value.split(";"):
+        item = item.strip()
+        if ".." in item:
+            first, last = item.split("..")
+            out.update(range(code(first), code(last) + 1))
+        elif item:
+            out.add(code(item))
+    return out
+
+
+def mapping(value):
+    return {code(a): code(b) for a, b in re.findall(r"\((<U\w+>),(<U\w+>)\)", value)}
+
+
+# The code points where the set of classes changes, and the set from there on
+def class_runs():
+    sets = {name: members(sections[name]) for name in CLASSES if name != "alnum"}
+    sets["alnum"] = sets["alpha"] | sets["digit"]
+    flags = [0] * 0x110000
+    for bit, name in enumerate(CLASSES):
+        for c in sets[name]:
+            flags[c] |= 1 << bit
+    runs = []
+    for c, f in enumerate(flags):
+        if not runs or runs[-1][1] != f:
+            runs.append((c, f))
+    return runs
+
+
+# The runs of code points mapped the same distance away, every code point or every other one:
+# first, last, step and distance
+def case_runs(m):
+    out = []
+    for c in sorted(m):
+        delta = m[c] - c
+        if out:
+            first, last, step, d = out[-1]
+            if d == delta and (first == last and c - last in (1, 2) or c - last == step):
+                out[-1] = (first, c, c - first if first == last else step, d)
+                continue
+        out.append((c, c, 1, delta))
+    return out
+
+
+def rows(entries, fmt):
+    return "\n".join("    " + fmt % e + "," for e in entries)
+
+
+runs = class_runs()
+upper = case_runs(mapping(sections["toupper"]))
+lower = case_runs(mapping(sections["tolower"]))
+print(
+    f"""//! The character classes and case mappings of Unicode {version}, as the i18n_ctype locale source of
+//! glibc defines them from UnicodeData.txt and DerivedCoreProperties.txt.
+
+// Generated by tables.py from that file
+
+/// The code points where the set of classes changes, and the classes from there on, with the
+/// bit 1 << (desc - 1) for the wctype_t desc
+pub const CLASSES: [(u32, u16); {len(runs)}] = [
+{rows(runs, "(0x%x, 0x%x)")}
+];
+
+/// The runs of code points with the same distance to their uppercase form: the first and last of
+/// them, the distance between them, and the one to the uppercase form
+pub const TO_UPPER: [(u32, u32, u8, i32); {len(upper)}] = [
+{rows(upper, "(0x%x, 0x%x, %d, %d)")}
+];
+
+/// The same for the lowercase forms
+pub const TO_LOWER: [(u32, u32, u8, i32); {len(lower)}] = [
+{rows(lower, "(0x%x, 0x%x, %d, %d)")}
+];"""
+)
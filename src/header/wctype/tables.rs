@@ -0,0 +1,3534 @@
+//! The character classes and case mappings of Unicode 14.0.0, as the i18n_ctype locale source of
+//! glibc defines them from UnicodeData.txt and DerivedCoreProperties.txt.
+
+// Generated by tables.py from that file
+
+/// The code points where the set of classes changes, and the classes from there on, with the
+/// bit 1 << (desc - 1) for the wctype_t desc
+pub const CLASSES: [(u32, u16); 3134] = [
+    (0x0, 0x8),
+    (0x9, 0x20c),
+    (0xa, 0x208),
+    (0xe, 0x8),
+    (0x20, 0x284),
+    (0x21, 0x1a0),
+    (0x30, 0x8b1),
+    (0x3a, 0x1a0),
+    (0x41, 0xca3),
+    (0x47, 0x4a3),
+    (0x5b, 0x1a0),
+    (0x61, 0x8e3),
+    (0x67, 0xe3),
+    (0x7b, 0x1a0),
+    (0x7f, 0x8),
+    (0xa0, 0x1a0),
+    (0xaa, 0xe3),
+    (0xab, 0x1a0),
+    (0xb5, 0xe3),
+    (0xb6, 0x1a0),
+    (0xba, 0xe3),
+    (0xbb, 0x1a0),
+    (0xc0, 0x4a3),
+    (0xd7, 0x1a0),
+    (0xd8, 0x4a3),
+    (0xdf, 0xe3),
+    (0xf7, 0x1a0),
+    (0xf8, 0xe3),
+    (0x100, 0x4a3),
+    (0x101, 0xe3),
+    (0x102, 0x4a3),
+    (0x103, 0xe3),
+    (0x104, 0x4a3),
+    (0x105, 0xe3),
+    (0x106, 0x4a3),
+    (0x107, 0xe3),
+    (0x108, 0x4a3),
+    (0x109, 0xe3),
+    (0x10a, 0x4a3),
+    (0x10b, 0xe3),
+    (0x10c, 0x4a3),
+    (0x10d, 0xe3),
+    (0x10e, 0x4a3),
+    (0x10f, 0xe3),
+    (0x110, 0x4a3),
+    (0x111, 0xe3),
+    (0x112, 0x4a3),
+    (0x113, 0xe3),
+    (0x114, 0x4a3),
+    (0x115, 0xe3),
+    (0x116, 0x4a3),
+    (0x117, 0xe3),
+    (0x118, 0x4a3),
+    (0x119, 0xe3),
+    (0x11a, 0x4a3),
+    (0x11b, 0xe3),
+    (0x11c, 0x4a3),
+    (0x11d, 0xe3),
+    (0x11e, 0x4a3),
+    (0x11f, 0xe3),
+    (0x120, 0x4a3),
+    (0x121, 0xe3),
+    (0x122, 0x4a3),
+    (0x123, 0xe3),
+    (0x124, 0x4a3),
+    (0x125, 0xe3),
+    (0x126, 0x4a3),
+    (0x127, 0xe3),
+    (0x128, 0x4a3),
+    (0x129, 0xe3),
+    (0x12a, 0x4a3),
+    (0x12b, 0xe3),
+    (0x12c, 0x4a3),
+    (0x12d, 0xe3),
+    (0x12e, 0x4a3),
+    (0x12f, 0xe3),
+    (0x130, 0x4a3),
+    (0x131, 0xe3),
+    (0x132, 0x4a3),
+    (0x133, 0xe3),
+    (0x134, 0x4a3),
+    (0x135, 0xe3),
+    (0x136, 0x4a3),
+    (0x137, 0xe3),
+    (0x139, 0x4a3),
+    (0x13a, 0xe3),
+    (0x13b, 0x4a3),
+    (0x13c, 0xe3),
+    (0x13d, 0x4a3),
+    (0x13e, 0xe3),
+    (0x13f, 0x4a3),
+    (0x140, 0xe3),
+    (0x141, 0x4a3),
+    (0x142, 0xe3),
+    (0x143, 0x4a3),
+    (0x144, 0xe3),
+    (0x145, 0x4a3),
+    (0x146, 0xe3),
+    (0x147, 0x4a3),
+    (0x148, 0xe3),
+    (0x14a, 0x4a3),
+    (0x14b, 0xe3),
+    (0x14c, 0x4a3),
+    (0x14d, 0xe3),
+    (0x14e, 0x4a3),
+    (0x14f, 0xe3),
+    (0x150, 0x4a3),
+    (0x151, 0xe3),
+    (0x152, 0x4a3),
+    (0x153, 0xe3),
+    (0x154, 0x4a3),
+    (0x155, 0xe3),
+    (0x156, 0x4a3),
+    (0x157, 0xe3),
+    (0x158, 0x4a3),
+    (0x159, 0xe3),
+    (0x15a, 0x4a3),
+    (0x15b, 0xe3),
+    (0x15c, 0x4a3),
+    (0x15d, 0xe3),
+    (0x15e, 0x4a3),
+    (0x15f, 0xe3),
+    (0x160, 0x4a3),
+    (0x161, 0xe3),
+    (0x162, 0x4a3),
+    (0x163, 0xe3),
+    (0x164, 0x4a3),
+    (0x165, 0xe3),
+    (0x166, 0x4a3),
+    (0x167, 0xe3),
+    (0x168, 0x4a3),
+    (0x169, 0xe3),
+    (0x16a, 0x4a3),
+    (0x16b, 0xe3),
+    (0x16c, 0x4a3),
+    (0x16d, 0xe3),
+    (0x16e, 0x4a3),
+    (0x16f, 0xe3),
+    (0x170, 0x4a3),
+    (0x171, 0xe3),
+    (0x172, 0x4a3),
+    (0x173, 0xe3),
+    (0x174, 0x4a3),
+    (0x175, 0xe3),
+    (0x176, 0x4a3),
+    (0x177, 0xe3),
+    (0x178, 0x4a3),
+    (0x17a, 0xe3),
+    (0x17b, 0x4a3),
+    (0x17c, 0xe3),
+    (0x17d, 0x4a3),
+    (0x17e, 0xe3),
+    (0x181, 0x4a3),
+    (0x183, 0xe3),
+    (0x184, 0x4a3),
+    (0x185, 0xe3),
+    (0x186, 0x4a3),
+    (0x188, 0xe3),
+    (0x189, 0x4a3),
+    (0x18c, 0xe3),
+    (0x18e, 0x4a3),
+    (0x192, 0xe3),
+    (0x193, 0x4a3),
+    (0x195, 0xe3),
+    (0x196, 0x4a3),
+    (0x199, 0xe3),
+    (0x19c, 0x4a3),
+    (0x19e, 0xe3),
+    (0x19f, 0x4a3),
+    (0x1a1, 0xe3),
+    (0x1a2, 0x4a3),
+    (0x1a3, 0xe3),
+    (0x1a4, 0x4a3),
+    (0x1a5, 0xe3),
+    (0x1a6, 0x4a3),
+    (0x1a8, 0xe3),
+    (0x1a9, 0x4a3),
+    (0x1aa, 0xe3),
+    (0x1ac, 0x4a3),
+    (0x1ad, 0xe3),
+    (0x1ae, 0x4a3),
+    (0x1b0, 0xe3),
+    (0x1b1, 0x4a3),
+    (0x1b4, 0xe3),
+    (0x1b5, 0x4a3),
+    (0x1b6, 0xe3),
+    (0x1b7, 0x4a3),
+    (0x1b9, 0xe3),
+    (0x1bb, 0xa3),
+    (0x1bc, 0x4a3),
+    (0x1bd, 0xe3),
+    (0x1c0, 0xa3),
+    (0x1c4, 0x4a3),
+    (0x1c5, 0x4e3),
+    (0x1c6, 0xe3),
+    (0x1c7, 0x4a3),
+    (0x1c8, 0x4e3),
+    (0x1c9, 0xe3),
+    (0x1ca, 0x4a3),
+    (0x1cb, 0x4e3),
+    (0x1cc, 0xe3),
+    (0x1cd, 0x4a3),
+    (0x1ce, 0xe3),
+    (0x1cf, 0x4a3),
+    (0x1d0, 0xe3),
+    (0x1d1, 0x4a3),
+    (0x1d2, 0xe3),
+    (0x1d3, 0x4a3),
+    (0x1d4, 0xe3),
+    (0x1d5, 0x4a3),
+    (0x1d6, 0xe3),
+    (0x1d7, 0x4a3),
+    (0x1d8, 0xe3),
+    (0x1d9, 0x4a3),
+    (0x1da, 0xe3),
+    (0x1db, 0x4a3),
+    (0x1dc, 0xe3),
+    (0x1de, 0x4a3),
+    (0x1df, 0xe3),
+    (0x1e0, 0x4a3),
+    (0x1e1, 0xe3),
+    (0x1e2, 0x4a3),
+    (0x1e3, 0xe3),
+    (0x1e4, 0x4a3),
+    (0x1e5, 0xe3),
+    (0x1e6, 0x4a3),
+    (0x1e7, 0xe3),
+    (0x1e8, 0x4a3),
+    (0x1e9, 0xe3),
+    (0x1ea, 0x4a3),
+    (0x1eb, 0xe3),
+    (0x1ec, 0x4a3),
+    (0x1ed, 0xe3),
+    (0x1ee, 0x4a3),
+    (0x1ef, 0xe3),
+    (0x1f1, 0x4a3),
+    (0x1f2, 0x4e3),
+    (0x1f3, 0xe3),
+    (0x1f4, 0x4a3),
+    (0x1f5, 0xe3),
+    (0x1f6, 0x4a3),
+    (0x1f9, 0xe3),
+    (0x1fa, 0x4a3),
+    (0x1fb, 0xe3),
+    (0x1fc, 0x4a3),
+    (0x1fd, 0xe3),
+    (0x1fe, 0x4a3),
+    (0x1ff, 0xe3),
+    (0x200, 0x4a3),
+    (0x201, 0xe3),
+    (0x202, 0x4a3),
+    (0x203, 0xe3),
+    (0x204, 0x4a3),
+    (0x205, 0xe3),
+    (0x206, 0x4a3),
+    (0x207, 0xe3),
+    (0x208, 0x4a3),
+    (0x209, 0xe3),
+    (0x20a, 0x4a3),
+    (0x20b, 0xe3),
+    (0x20c, 0x4a3),
+    (0x20d, 0xe3),
+    (0x20e, 0x4a3),
+    (0x20f, 0xe3),
+    (0x210, 0x4a3),
+    (0x211, 0xe3),
+    (0x212, 0x4a3),
+    (0x213, 0xe3),
+    (0x214, 0x4a3),
+    (0x215, 0xe3),
+    (0x216, 0x4a3),
+    (0x217, 0xe3),
+    (0x218, 0x4a3),
+    (0x219, 0xe3),
+    (0x21a, 0x4a3),
+    (0x21b, 0xe3),
+    (0x21c, 0x4a3),
+    (0x21d, 0xe3),
+    (0x21e, 0x4a3),
+    (0x21f, 0xe3),
+    (0x220, 0x4a3),
+    (0x221, 0xe3),
+    (0x222, 0x4a3),
+    (0x223, 0xe3),
+    (0x224, 0x4a3),
+    (0x225, 0xe3),
+    (0x226, 0x4a3),
+    (0x227, 0xe3),
+    (0x228, 0x4a3),
+    (0x229, 0xe3),
+    (0x22a, 0x4a3),
+    (0x22b, 0xe3),
+    (0x22c, 0x4a3),
+    (0x22d, 0xe3),
+    (0x22e, 0x4a3),
+    (0x22f, 0xe3),
+    (0x230, 0x4a3),
+    (0x231, 0xe3),
+    (0x232, 0x4a3),
+    (0x233, 0xe3),
+    (0x23a, 0x4a3),
+    (0x23c, 0xe3),
+    (0x23d, 0x4a3),
+    (0x23f, 0xe3),
+    (0x241, 0x4a3),
+    (0x242, 0xe3),
+    (0x243, 0x4a3),
+    (0x247, 0xe3),
+    (0x248, 0x4a3),
+    (0x249, 0xe3),
+    (0x24a, 0x4a3),
+    (0x24b, 0xe3),
+    (0x24c, 0x4a3),
+    (0x24d, 0xe3),
+    (0x24e, 0x4a3),
+    (0x24f, 0xe3),
+    (0x294, 0xa3),
+    (0x295, 0xe3),
+    (0x2b9, 0xa3),
+    (0x2c0, 0xe3),
+    (0x2c2, 0x1a0),
+    (0x2c6, 0xa3),
+    (0x2d2, 0x1a0),
+    (0x2e0, 0xe3),
+    (0x2e5, 0x1a0),
+    (0x2ec, 0xa3),
+    (0x2ed, 0x1a0),
+    (0x2ee, 0xa3),
+    (0x2ef, 0x1a0),
+    (0x345, 0xe3),
+    (0x346, 0x1a0),
+    (0x370, 0x4a3),
+    (0x371, 0xe3),
+    (0x372, 0x4a3),
+    (0x373, 0xe3),
+    (0x374, 0xa3),
+    (0x375, 0x1a0),
+    (0x376, 0x4a3),
+    (0x377, 0xe3),
+    (0x378, 0x0),
+    (0x37a, 0xe3),
+    (0x37e, 0x1a0),
+    (0x37f, 0x4a3),
+    (0x380, 0x0),
+    (0x384, 0x1a0),
+    (0x386, 0x4a3),
+    (0x387, 0x1a0),
+    (0x388, 0x4a3),
+    (0x38b, 0x0),
+    (0x38c, 0x4a3),
+    (0x38d, 0x0),
+    (0x38e, 0x4a3),
+    (0x390, 0xe3),
+    (0x391, 0x4a3),
+    (0x3a2, 0x0),
+    (0x3a3, 0x4a3),
+    (0x3ac, 0xe3),
+    (0x3cf, 0x4a3),
+    (0x3d0, 0xe3),
+    (0x3d2, 0x4a3),
+    (0x3d5, 0xe3),
+    (0x3d8, 0x4a3),
+    (0x3d9, 0xe3),
+    (0x3da, 0x4a3),
+    (0x3db, 0xe3),
+    (0x3dc, 0x4a3),
+    (0x3dd, 0xe3),
+    (0x3de, 0x4a3),
+    (0x3df, 0xe3),
+    (0x3e0, 0x4a3),
+    (0x3e1, 0xe3),
+    (0x3e2, 0x4a3),
+    (0x3e3, 0xe3),
+    (0x3e4, 0x4a3),
+    (0x3e5, 0xe3),
+    (0x3e6, 0x4a3),
+    (0x3e7, 0xe3),
+    (0x3e8, 0x4a3),
+    (0x3e9, 0xe3),
+    (0x3ea, 0x4a3),
+    (0x3eb, 0xe3),
+    (0x3ec, 0x4a3),
+    (0x3ed, 0xe3),
+    (0x3ee, 0x4a3),
+    (0x3ef, 0xe3),
+    (0x3f4, 0x4a3),
+    (0x3f5, 0xe3),
+    (0x3f6, 0x1a0),
+    (0x3f7, 0x4a3),
+    (0x3f8, 0xe3),
+    (0x3f9, 0x4a3),
+    (0x3fb, 0xe3),
+    (0x3fd, 0x4a3),
+    (0x430, 0xe3),
+    (0x460, 0x4a3),
+    (0x461, 0xe3),
+    (0x462, 0x4a3),
+    (0x463, 0xe3),
+    (0x464, 0x4a3),
+    (0x465, 0xe3),
+    (0x466, 0x4a3),
+    (0x467, 0xe3),
+    (0x468, 0x4a3),
+    (0x469, 0xe3),
+    (0x46a, 0x4a3),
+    (0x46b, 0xe3),
+    (0x46c, 0x4a3),
+    (0x46d, 0xe3),
+    (0x46e, 0x4a3),
+    (0x46f, 0xe3),
+    (0x470, 0x4a3),
+    (0x471, 0xe3),
+    (0x472, 0x4a3),
+    (0x473, 0xe3),
+    (0x474, 0x4a3),
+    (0x475, 0xe3),
+    (0x476, 0x4a3),
+    (0x477, 0xe3),
+    (0x478, 0x4a3),
+    (0x479, 0xe3),
+    (0x47a, 0x4a3),
+    (0x47b, 0xe3),
+    (0x47c, 0x4a3),
+    (0x47d, 0xe3),
+    (0x47e, 0x4a3),
+    (0x47f, 0xe3),
+    (0x480, 0x4a3),
+    (0x481, 0xe3),
+    (0x482, 0x1a0),
+    (0x48a, 0x4a3),
+    (0x48b, 0xe3),
+    (0x48c, 0x4a3),
+    (0x48d, 0xe3),
+    (0x48e, 0x4a3),
+    (0x48f, 0xe3),
+    (0x490, 0x4a3),
+    (0x491, 0xe3),
+    (0x492, 0x4a3),
+    (0x493, 0xe3),
+    (0x494, 0x4a3),
+    (0x495, 0xe3),
+    (0x496, 0x4a3),
+    (0x497, 0xe3),
+    (0x498, 0x4a3),
+    (0x499, 0xe3),
+    (0x49a, 0x4a3),
+    (0x49b, 0xe3),
+    (0x49c, 0x4a3),
+    (0x49d, 0xe3),
+    (0x49e, 0x4a3),
+    (0x49f, 0xe3),
+    (0x4a0, 0x4a3),
+    (0x4a1, 0xe3),
+    (0x4a2, 0x4a3),
+    (0x4a3, 0xe3),
+    (0x4a4, 0x4a3),
+    (0x4a5, 0xe3),
+    (0x4a6, 0x4a3),
+    (0x4a7, 0xe3),
+    (0x4a8, 0x4a3),
+    (0x4a9, 0xe3),
+    (0x4aa, 0x4a3),
+    (0x4ab, 0xe3),
+    (0x4ac, 0x4a3),
+    (0x4ad, 0xe3),
+    (0x4ae, 0x4a3),
+    (0x4af, 0xe3),
+    (0x4b0, 0x4a3),
+    (0x4b1, 0xe3),
+    (0x4b2, 0x4a3),
+    (0x4b3, 0xe3),
+    (0x4b4, 0x4a3),
+    (0x4b5, 0xe3),
+    (0x4b6, 0x4a3),
+    (0x4b7, 0xe3),
+    (0x4b8, 0x4a3),
+    (0x4b9, 0xe3),
+    (0x4ba, 0x4a3),
+    (0x4bb, 0xe3),
+    (0x4bc, 0x4a3),
+    (0x4bd, 0xe3),
+    (0x4be, 0x4a3),
+    (0x4bf, 0xe3),
+    (0x4c0, 0x4a3),
+    (0x4c2, 0xe3),
+    (0x4c3, 0x4a3),
+    (0x4c4, 0xe3),
+    (0x4c5, 0x4a3),
+    (0x4c6, 0xe3),
+    (0x4c7, 0x4a3),
+    (0x4c8, 0xe3),
+    (0x4c9, 0x4a3),
+    (0x4ca, 0xe3),
+    (0x4cb, 0x4a3),
+    (0x4cc, 0xe3),
+    (0x4cd, 0x4a3),
+    (0x4ce, 0xe3),
+    (0x4d0, 0x4a3),
+    (0x4d1, 0xe3),
+    (0x4d2, 0x4a3),
+    (0x4d3, 0xe3),
+    (0x4d4, 0x4a3),
+    (0x4d5, 0xe3),
+    (0x4d6, 0x4a3),
+    (0x4d7, 0xe3),
+    (0x4d8, 0x4a3),
+    (0x4d9, 0xe3),
+    (0x4da, 0x4a3),
+    (0x4db, 0xe3),
+    (0x4dc, 0x4a3),
+    (0x4dd, 0xe3),
+    (0x4de, 0x4a3),
+    (0x4df, 0xe3),
+    (0x4e0, 0x4a3),
+    (0x4e1, 0xe3),
+    (0x4e2, 0x4a3),
+    (0x4e3, 0xe3),
+    (0x4e4, 0x4a3),
+    (0x4e5, 0xe3),
+    (0x4e6, 0x4a3),
+    (0x4e7, 0xe3),
+    (0x4e8, 0x4a3),
+    (0x4e9, 0xe3),
+    (0x4ea, 0x4a3),
+    (0x4eb, 0xe3),
+    (0x4ec, 0x4a3),
+    (0x4ed, 0xe3),
+    (0x4ee, 0x4a3),
+    (0x4ef, 0xe3),
+    (0x4f0, 0x4a3),
+    (0x4f1, 0xe3),
+    (0x4f2, 0x4a3),
+    (0x4f3, 0xe3),
+    (0x4f4, 0x4a3),
+    (0x4f5, 0xe3),
+    (0x4f6, 0x4a3),
+    (0x4f7, 0xe3),
+    (0x4f8, 0x4a3),
+    (0x4f9, 0xe3),
+    (0x4fa, 0x4a3),
+    (0x4fb, 0xe3),
+    (0x4fc, 0x4a3),
+    (0x4fd, 0xe3),
+    (0x4fe, 0x4a3),
+    (0x4ff, 0xe3),
+    (0x500, 0x4a3),
+    (0x501, 0xe3),
+    (0x502, 0x4a3),
+    (0x503, 0xe3),
+    (0x504, 0x4a3),
+    (0x505, 0xe3),
+    (0x506, 0x4a3),
+    (0x507, 0xe3),
+    (0x508, 0x4a3),
+    (0x509, 0xe3),
+    (0x50a, 0x4a3),
+    (0x50b, 0xe3),
+    (0x50c, 0x4a3),
+    (0x50d, 0xe3),
+    (0x50e, 0x4a3),
+    (0x50f, 0xe3),
+    (0x510, 0x4a3),
+    (0x511, 0xe3),
+    (0x512, 0x4a3),
+    (0x513, 0xe3),
+    (0x514, 0x4a3),
+    (0x515, 0xe3),
+    (0x516, 0x4a3),
+    (0x517, 0xe3),
+    (0x518, 0x4a3),
+    (0x519, 0xe3),
+    (0x51a, 0x4a3),
+    (0x51b, 0xe3),
+    (0x51c, 0x4a3),
+    (0x51d, 0xe3),
+    (0x51e, 0x4a3),
+    (0x51f, 0xe3),
+    (0x520, 0x4a3),
+    (0x521, 0xe3),
+    (0x522, 0x4a3),
+    (0x523, 0xe3),
+    (0x524, 0x4a3),
+    (0x525, 0xe3),
+    (0x526, 0x4a3),
+    (0x527, 0xe3),
+    (0x528, 0x4a3),
+    (0x529, 0xe3),
+    (0x52a, 0x4a3),
+    (0x52b, 0xe3),
+    (0x52c, 0x4a3),
+    (0x52d, 0xe3),
+    (0x52e, 0x4a3),
+    (0x52f, 0xe3),
+    (0x530, 0x0),
+    (0x531, 0x4a3),
+    (0x557, 0x0),
+    (0x559, 0xa3),
+    (0x55a, 0x1a0),
+    (0x560, 0xe3),
+    (0x589, 0x1a0),
+    (0x58b, 0x0),
+    (0x58d, 0x1a0),
+    (0x590, 0x0),
+    (0x591, 0x1a0),
+    (0x5b0, 0xa3),
+    (0x5be, 0x1a0),
+    (0x5bf, 0xa3),
+    (0x5c0, 0x1a0),
+    (0x5c1, 0xa3),
+    (0x5c3, 0x1a0),
+    (0x5c4, 0xa3),
+    (0x5c6, 0x1a0),
+    (0x5c7, 0xa3),
+    (0x5c8, 0x0),
+    (0x5d0, 0xa3),
+    (0x5eb, 0x0),
+    (0x5ef, 0xa3),
+    (0x5f3, 0x1a0),
+    (0x5f5, 0x0),
+    (0x600, 0x1a0),
+    (0x610, 0xa3),
+    (0x61b, 0x1a0),
+    (0x620, 0xa3),
+    (0x658, 0x1a0),
+    (0x659, 0xa3),
+    (0x66a, 0x1a0),
+    (0x66e, 0xa3),
+    (0x6d4, 0x1a0),
+    (0x6d5, 0xa3),
+    (0x6dd, 0x1a0),
+    (0x6e1, 0xa3),
+    (0x6e9, 0x1a0),
+    (0x6ed, 0xa3),
+    (0x6fd, 0x1a0),
+    (0x6ff, 0xa3),
+    (0x700, 0x1a0),
+    (0x70e, 0x0),
+    (0x70f, 0x1a0),
+    (0x710, 0xa3),
+    (0x740, 0x1a0),
+    (0x74b, 0x0),
+    (0x74d, 0xa3),
+    (0x7b2, 0x0),
+    (0x7c0, 0xa3),
+    (0x7eb, 0x1a0),
+    (0x7f4, 0xa3),
+    (0x7f6, 0x1a0),
+    (0x7fa, 0xa3),
+    (0x7fb, 0x0),
+    (0x7fd, 0x1a0),
+    (0x800, 0xa3),
+    (0x818, 0x1a0),
+    (0x81a, 0xa3),
+    (0x82d, 0x1a0),
+    (0x82e, 0x0),
+    (0x830, 0x1a0),
+    (0x83f, 0x0),
+    (0x840, 0xa3),
+    (0x859, 0x1a0),
+    (0x85c, 0x0),
+    (0x85e, 0x1a0),
+    (0x85f, 0x0),
+    (0x860, 0xa3),
+    (0x86b, 0x0),
+    (0x870, 0xa3),
+    (0x888, 0x1a0),
+    (0x889, 0xa3),
+    (0x88f, 0x0),
+    (0x890, 0x1a0),
+    (0x892, 0x0),
+    (0x898, 0x1a0),
+    (0x8a0, 0xa3),
+    (0x8ca, 0x1a0),
+    (0x8d4, 0xa3),
+    (0x8e0, 0x1a0),
+    (0x8e3, 0xa3),
+    (0x8ea, 0x1a0),
+    (0x8f0, 0xa3),
+    (0x93c, 0x1a0),
+    (0x93d, 0xa3),
+    (0x94d, 0x1a0),
+    (0x94e, 0xa3),
+    (0x951, 0x1a0),
+    (0x955, 0xa3),
+    (0x964, 0x1a0),
+    (0x966, 0xa3),
+    (0x970, 0x1a0),
+    (0x971, 0xa3),
+    (0x984, 0x0),
+    (0x985, 0xa3),
+    (0x98d, 0x0),
+    (0x98f, 0xa3),
+    (0x991, 0x0),
+    (0x993, 0xa3),
+    (0x9a9, 0x0),
+    (0x9aa, 0xa3),
+    (0x9b1, 0x0),
+    (0x9b2, 0xa3),
+    (0x9b3, 0x0),
+    (0x9b6, 0xa3),
+    (0x9ba, 0x0),
+    (0x9bc, 0x1a0),
+    (0x9bd, 0xa3),
+    (0x9c5, 0x0),
+    (0x9c7, 0xa3),
+    (0x9c9, 0x0),
+    (0x9cb, 0xa3),
+    (0x9cd, 0x1a0),
+    (0x9ce, 0xa3),
+    (0x9cf, 0x0),
+    (0x9d7, 0xa3),
+    (0x9d8, 0x0),
+    (0x9dc, 0xa3),
+    (0x9de, 0x0),
+    (0x9df, 0xa3),
+    (0x9e4, 0x0),
+    (0x9e6, 0xa3),
+    (0x9f2, 0x1a0),
+    (0x9fc, 0xa3),
+    (0x9fd, 0x1a0),
+    (0x9ff, 0x0),
+    (0xa01, 0xa3),
+    (0xa04, 0x0),
+    (0xa05, 0xa3),
+    (0xa0b, 0x0),
+    (0xa0f, 0xa3),
+    (0xa11, 0x0),
+    (0xa13, 0xa3),
+    (0xa29, 0x0),
+    (0xa2a, 0xa3),
+    (0xa31, 0x0),
+    (0xa32, 0xa3),
+    (0xa34, 0x0),
+    (0xa35, 0xa3),
+    (0xa37, 0x0),
+    (0xa38, 0xa3),
+    (0xa3a, 0x0),
+    (0xa3c, 0x1a0),
+    (0xa3d, 0x0),
+    (0xa3e, 0xa3),
+    (0xa43, 0x0),
+    (0xa47, 0xa3),
+    (0xa49, 0x0),
+    (0xa4b, 0xa3),
+    (0xa4d, 0x1a0),
+    (0xa4e, 0x0),
+    (0xa51, 0xa3),
+    (0xa52, 0x0),
+    (0xa59, 0xa3),
+    (0xa5d, 0x0),
+    (0xa5e, 0xa3),
+    (0xa5f, 0x0),
+    (0xa66, 0xa3),
+    (0xa76, 0x1a0),
+    (0xa77, 0x0),
+    (0xa81, 0xa3),
+    (0xa84, 0x0),
+    (0xa85, 0xa3),
+    (0xa8e, 0x0),
+    (0xa8f, 0xa3),
+    (0xa92, 0x0),
+    (0xa93, 0xa3),
+    (0xaa9, 0x0),
+    (0xaaa, 0xa3),
+    (0xab1, 0x0),
+    (0xab2, 0xa3),
+    (0xab4, 0x0),
+    (0xab5, 0xa3),
+    (0xaba, 0x0),
+    (0xabc, 0x1a0),
+    (0xabd, 0xa3),
+    (0xac6, 0x0),
+    (0xac7, 0xa3),
+    (0xaca, 0x0),
+    (0xacb, 0xa3),
+    (0xacd, 0x1a0),
+    (0xace, 0x0),
+    (0xad0, 0xa3),
+    (0xad1, 0x0),
+    (0xae0, 0xa3),
+    (0xae4, 0x0),
+    (0xae6, 0xa3),
+    (0xaf0, 0x1a0),
+    (0xaf2, 0x0),
+    (0xaf9, 0xa3),
+    (0xafd, 0x1a0),
+    (0xb00, 0x0),
+    (0xb01, 0xa3),
+    (0xb04, 0x0),
+    (0xb05, 0xa3),
+    (0xb0d, 0x0),
+    (0xb0f, 0xa3),
+    (0xb11, 0x0),
+    (0xb13, 0xa3),
+    (0xb29, 0x0),
+    (0xb2a, 0xa3),
+    (0xb31, 0x0),
+    (0xb32, 0xa3),
+    (0xb34, 0x0),
+    (0xb35, 0xa3),
+    (0xb3a, 0x0),
+    (0xb3c, 0x1a0),
+    (0xb3d, 0xa3),
+    (0xb45, 0x0),
+    (0xb47, 0xa3),
+    (0xb49, 0x0),
+    (0xb4b, 0xa3),
+    (0xb4d, 0x1a0),
+    (0xb4e, 0x0),
+    (0xb55, 0x1a0),
+    (0xb56, 0xa3),
+    (0xb58, 0x0),
+    (0xb5c, 0xa3),
+    (0xb5e, 0x0),
+    (0xb5f, 0xa3),
+    (0xb64, 0x0),
+    (0xb66, 0xa3),
+    (0xb70, 0x1a0),
+    (0xb71, 0xa3),
+    (0xb72, 0x1a0),
+    (0xb78, 0x0),
+    (0xb82, 0xa3),
+    (0xb84, 0x0),
+    (0xb85, 0xa3),
+    (0xb8b, 0x0),
+    (0xb8e, 0xa3),
+    (0xb91, 0x0),
+    (0xb92, 0xa3),
+    (0xb96, 0x0),
+    (0xb99, 0xa3),
+    (0xb9b, 0x0),
+    (0xb9c, 0xa3),
+    (0xb9d, 0x0),
+    (0xb9e, 0xa3),
+    (0xba0, 0x0),
+    (0xba3, 0xa3),
+    (0xba5, 0x0),
+    (0xba8, 0xa3),
+    (0xbab, 0x0),
+    (0xbae, 0xa3),
+    (0xbba, 0x0),
+    (0xbbe, 0xa3),
+    (0xbc3, 0x0),
+    (0xbc6, 0xa3),
+    (0xbc9, 0x0),
+    (0xbca, 0xa3),
+    (0xbcd, 0x1a0),
+    (0xbce, 0x0),
+    (0xbd0, 0xa3),
+    (0xbd1, 0x0),
+    (0xbd7, 0xa3),
+    (0xbd8, 0x0),
+    (0xbe6, 0xa3),
+    (0xbf0, 0x1a0),
+    (0xbfb, 0x0),
+    (0xc00, 0xa3),
+    (0xc04, 0x1a0),
+    (0xc05, 0xa3),
+    (0xc0d, 0x0),
+    (0xc0e, 0xa3),
+    (0xc11, 0x0),
+    (0xc12, 0xa3),
+    (0xc29, 0x0),
+    (0xc2a, 0xa3),
+    (0xc3a, 0x0),
+    (0xc3c, 0x1a0),
+    (0xc3d, 0xa3),
+    (0xc45, 0x0),
+    (0xc46, 0xa3),
+    (0xc49, 0x0),
+    (0xc4a, 0xa3),
+    (0xc4d, 0x1a0),
+    (0xc4e, 0x0),
+    (0xc55, 0xa3),
+    (0xc57, 0x0),
+    (0xc58, 0xa3),
+    (0xc5b, 0x0),
+    (0xc5d, 0xa3),
+    (0xc5e, 0x0),
+    (0xc60, 0xa3),
+    (0xc64, 0x0),
+    (0xc66, 0xa3),
+    (0xc70, 0x0),
+    (0xc77, 0x1a0),
+    (0xc80, 0xa3),
+    (0xc84, 0x1a0),
+    (0xc85, 0xa3),
+    (0xc8d, 0x0),
+    (0xc8e, 0xa3),
+    (0xc91, 0x0),
+    (0xc92, 0xa3),
+    (0xca9, 0x0),
+    (0xcaa, 0xa3),
+    (0xcb4, 0x0),
+    (0xcb5, 0xa3),
+    (0xcba, 0x0),
+    (0xcbc, 0x1a0),
+    (0xcbd, 0xa3),
+    (0xcc5, 0x0),
+    (0xcc6, 0xa3),
+    (0xcc9, 0x0),
+    (0xcca, 0xa3),
+    (0xccd, 0x1a0),
+    (0xcce, 0x0),
+    (0xcd5, 0xa3),
+    (0xcd7, 0x0),
+    (0xcdd, 0xa3),
+    (0xcdf, 0x0),
+    (0xce0, 0xa3),
+    (0xce4, 0x0),
+    (0xce6, 0xa3),
+    (0xcf0, 0x0),
+    (0xcf1, 0xa3),
+    (0xcf3, 0x0),
+    (0xd00, 0xa3),
+    (0xd0d, 0x0),
+    (0xd0e, 0xa3),
+    (0xd11, 0x0),
+    (0xd12, 0xa3),
+    (0xd3b, 0x1a0),
+    (0xd3d, 0xa3),
+    (0xd45, 0x0),
+    (0xd46, 0xa3),
+    (0xd49, 0x0),
+    (0xd4a, 0xa3),
+    (0xd4d, 0x1a0),
+    (0xd4e, 0xa3),
+    (0xd4f, 0x1a0),
+    (0xd50, 0x0),
+    (0xd54, 0xa3),
+    (0xd58, 0x1a0),
+    (0xd5f, 0xa3),
+    (0xd64, 0x0),
+    (0xd66, 0xa3),
+    (0xd70, 0x1a0),
+    (0xd7a, 0xa3),
+    (0xd80, 0x0),
+    (0xd81, 0xa3),
+    (0xd84, 0x0),
+    (0xd85, 0xa3),
+    (0xd97, 0x0),
+    (0xd9a, 0xa3),
+    (0xdb2, 0x0),
+    (0xdb3, 0xa3),
+    (0xdbc, 0x0),
+    (0xdbd, 0xa3),
+    (0xdbe, 0x0),
+    (0xdc0, 0xa3),
+    (0xdc7, 0x0),
+    (0xdca, 0x1a0),
+    (0xdcb, 0x0),
+    (0xdcf, 0xa3),
+    (0xdd5, 0x0),
+    (0xdd6, 0xa3),
+    (0xdd7, 0x0),
+    (0xdd8, 0xa3),
+    (0xde0, 0x0),
+    (0xde6, 0xa3),
+    (0xdf0, 0x0),
+    (0xdf2, 0xa3),
+    (0xdf4, 0x1a0),
+    (0xdf5, 0x0),
+    (0xe01, 0xa3),
+    (0xe3b, 0x0),
+    (0xe3f, 0x1a0),
+    (0xe40, 0xa3),
+    (0xe47, 0x1a0),
+    (0xe4d, 0xa3),
+    (0xe4e, 0x1a0),
+    (0xe50, 0xa3),
+    (0xe5a, 0x1a0),
+    (0xe5c, 0x0),
+    (0xe81, 0xa3),
+    (0xe83, 0x0),
+    (0xe84, 0xa3),
+    (0xe85, 0x0),
+    (0xe86, 0xa3),
+    (0xe8b, 0x0),
+    (0xe8c, 0xa3),
+    (0xea4, 0x0),
+    (0xea5, 0xa3),
+    (0xea6, 0x0),
+    (0xea7, 0xa3),
+    (0xeba, 0x1a0),
+    (0xebb, 0xa3),
+    (0xebe, 0x0),
+    (0xec0, 0xa3),
+    (0xec5, 0x0),
+    (0xec6, 0xa3),
+    (0xec7, 0x0),
+    (0xec8, 0x1a0),
+    (0xecd, 0xa3),
+    (0xece, 0x0),
+    (0xed0, 0xa3),
+    (0xeda, 0x0),
+    (0xedc, 0xa3),
+    (0xee0, 0x0),
+    (0xf00, 0xa3),
+    (0xf01, 0x1a0),
+    (0xf20, 0xa3),
+    (0xf2a, 0x1a0),
+    (0xf40, 0xa3),
+    (0xf48, 0x0),
+    (0xf49, 0xa3),
+    (0xf6d, 0x0),
+    (0xf71, 0xa3),
+    (0xf82, 0x1a0),
+    (0xf88, 0xa3),
+    (0xf98, 0x0),
+    (0xf99, 0xa3),
+    (0xfbd, 0x0),
+    (0xfbe, 0x1a0),
+    (0xfcd, 0x0),
+    (0xfce, 0x1a0),
+    (0xfdb, 0x0),
+    (0x1000, 0xa3),
+    (0x1037, 0x1a0),
+    (0x1038, 0xa3),
+    (0x1039, 0x1a0),
+    (0x103b, 0xa3),
+    (0x104a, 0x1a0),
+    (0x1050, 0xa3),
+    (0x109e, 0x1a0),
+    (0x10a0, 0x4a3),
+    (0x10c6, 0x0),
+    (0x10c7, 0x4a3),
+    (0x10c8, 0x0),
+    (0x10cd, 0x4a3),
+    (0x10ce, 0x0),
+    (0x10d0, 0xe3),
+    (0x10fb, 0x1a0),
+    (0x10fc, 0xa3),
+    (0x10fd, 0xe3),
+    (0x1100, 0xa3),
+    (0x1249, 0x0),
+    (0x124a, 0xa3),
+    (0x124e, 0x0),
+    (0x1250, 0xa3),
+    (0x1257, 0x0),
+    (0x1258, 0xa3),
+    (0x1259, 0x0),
+    (0x125a, 0xa3),
+    (0x125e, 0x0),
+    (0x1260, 0xa3),
+    (0x1289, 0x0),
+    (0x128a, 0xa3),
+    (0x128e, 0x0),
+    (0x1290, 0xa3),
+    (0x12b1, 0x0),
+    (0x12b2, 0xa3),
+    (0x12b6, 0x0),
+    (0x12b8, 0xa3),
+    (0x12bf, 0x0),
+    (0x12c0, 0xa3),
+    (0x12c1, 0x0),
+    (0x12c2, 0xa3),
+    (0x12c6, 0x0),
+    (0x12c8, 0xa3),
+    (0x12d7, 0x0),
+    (0x12d8, 0xa3),
+    (0x1311, 0x0),
+    (0x1312, 0xa3),
+    (0x1316, 0x0),
+    (0x1318, 0xa3),
+    (0x135b, 0x0),
+    (0x135d, 0x1a0),
+    (0x137d, 0x0),
+    (0x1380, 0xa3),
+    (0x1390, 0x1a0),
+    (0x139a, 0x0),
+    (0x13a0, 0x4a3),
+    (0x13f6, 0x0),
+    (0x13f8, 0xe3),
+    (0x13fe, 0x0),
+    (0x1400, 0x1a0),
+    (0x1401, 0xa3),
+    (0x166d, 0x1a0),
+    (0x166f, 0xa3),
+    (0x1680, 0x284),
+    (0x1681, 0xa3),
+    (0x169b, 0x1a0),
+    (0x169d, 0x0),
+    (0x16a0, 0xa3),
+    (0x16eb, 0x1a0),
+    (0x16ee, 0xa3),
+    (0x16f9, 0x0),
+    (0x1700, 0xa3),
+    (0x1714, 0x1a0),
+    (0x1716, 0x0),
+    (0x171f, 0xa3),
+    (0x1734, 0x1a0),
+    (0x1737, 0x0),
+    (0x1740, 0xa3),
+    (0x1754, 0x0),
+    (0x1760, 0xa3),
+    (0x176d, 0x0),
+    (0x176e, 0xa3),
+    (0x1771, 0x0),
+    (0x1772, 0xa3),
+    (0x1774, 0x0),
+    (0x1780, 0xa3),
+    (0x17b4, 0x1a0),
+    (0x17b6, 0xa3),
+    (0x17c9, 0x1a0),
+    (0x17d7, 0xa3),
+    (0x17d8, 0x1a0),
+    (0x17dc, 0xa3),
+    (0x17dd, 0x1a0),
+    (0x17de, 0x0),
+    (0x17e0, 0xa3),
+    (0x17ea, 0x0),
+    (0x17f0, 0x1a0),
+    (0x17fa, 0x0),
+    (0x1800, 0x1a0),
+    (0x1810, 0xa3),
+    (0x181a, 0x0),
+    (0x1820, 0xa3),
+    (0x1879, 0x0),
+    (0x1880, 0xa3),
+    (0x18ab, 0x0),
+    (0x18b0, 0xa3),
+    (0x18f6, 0x0),
+    (0x1900, 0xa3),
+    (0x191f, 0x0),
+    (0x1920, 0xa3),
+    (0x192c, 0x0),
+    (0x1930, 0xa3),
+    (0x1939, 0x1a0),
+    (0x193c, 0x0),
+    (0x1940, 0x1a0),
+    (0x1941, 0x0),
+    (0x1944, 0x1a0),
+    (0x1946, 0xa3),
+    (0x196e, 0x0),
+    (0x1970, 0xa3),
+    (0x1975, 0x0),
+    (0x1980, 0xa3),
+    (0x19ac, 0x0),
+    (0x19b0, 0xa3),
+    (0x19ca, 0x0),
+    (0x19d0, 0xa3),
+    (0x19da, 0x1a0),
+    (0x19db, 0x0),
+    (0x19de, 0x1a0),
+    (0x1a00, 0xa3),
+    (0x1a1c, 0x0),
+    (0x1a1e, 0x1a0),
+    (0x1a20, 0xa3),
+    (0x1a5f, 0x0),
+    (0x1a60, 0x1a0),
+    (0x1a61, 0xa3),
+    (0x1a75, 0x1a0),
+    (0x1a7d, 0x0),
+    (0x1a7f, 0x1a0),
+    (0x1a80, 0xa3),
+    (0x1a8a, 0x0),
+    (0x1a90, 0xa3),
+    (0x1a9a, 0x0),
+    (0x1aa0, 0x1a0),
+    (0x1aa7, 0xa3),
+    (0x1aa8, 0x1a0),
+    (0x1aae, 0x0),
+    (0x1ab0, 0x1a0),
+    (0x1abf, 0xa3),
+    (0x1ac1, 0x1a0),
+    (0x1acc, 0xa3),
+    (0x1acf, 0x0),
+    (0x1b00, 0xa3),
+    (0x1b34, 0x1a0),
+    (0x1b35, 0xa3),
+    (0x1b44, 0x1a0),
+    (0x1b45, 0xa3),
+    (0x1b4d, 0x0),
+    (0x1b50, 0xa3),
+    (0x1b5a, 0x1a0),
+    (0x1b7f, 0x0),
+    (0x1b80, 0xa3),
+    (0x1baa, 0x1a0),
+    (0x1bac, 0xa3),
+    (0x1be6, 0x1a0),
+    (0x1be7, 0xa3),
+    (0x1bf2, 0x1a0),
+    (0x1bf4, 0x0),
+    (0x1bfc, 0x1a0),
+    (0x1c00, 0xa3),
+    (0x1c37, 0x1a0),
+    (0x1c38, 0x0),
+    (0x1c3b, 0x1a0),
+    (0x1c40, 0xa3),
+    (0x1c4a, 0x0),
+    (0x1c4d, 0xa3),
+    (0x1c7e, 0x1a0),
+    (0x1c80, 0xe3),
+    (0x1c89, 0x0),
+    (0x1c90, 0x4a3),
+    (0x1cbb, 0x0),
+    (0x1cbd, 0x4a3),
+    (0x1cc0, 0x1a0),
+    (0x1cc8, 0x0),
+    (0x1cd0, 0x1a0),
+    (0x1ce9, 0xa3),
+    (0x1ced, 0x1a0),
+    (0x1cee, 0xa3),
+    (0x1cf4, 0x1a0),
+    (0x1cf5, 0xa3),
+    (0x1cf7, 0x1a0),
+    (0x1cfa, 0xa3),
+    (0x1cfb, 0x0),
+    (0x1d00, 0xe3),
+    (0x1dc0, 0x1a0),
+    (0x1de7, 0xa3),
+    (0x1df5, 0x1a0),
+    (0x1e00, 0x4a3),
+    (0x1e01, 0xe3),
+    (0x1e02, 0x4a3),
+    (0x1e03, 0xe3),
+    (0x1e04, 0x4a3),
+    (0x1e05, 0xe3),
+    (0x1e06, 0x4a3),
+    (0x1e07, 0xe3),
+    (0x1e08, 0x4a3),
+    (0x1e09, 0xe3),
+    (0x1e0a, 0x4a3),
+    (0x1e0b, 0xe3),
+    (0x1e0c, 0x4a3),
+    (0x1e0d, 0xe3),
+    (0x1e0e, 0x4a3),
+    (0x1e0f, 0xe3),
+    (0x1e10, 0x4a3),
+    (0x1e11, 0xe3),
+    (0x1e12, 0x4a3),
+    (0x1e13, 0xe3),
+    (0x1e14, 0x4a3),
+    (0x1e15, 0xe3),
+    (0x1e16, 0x4a3),
+    (0x1e17, 0xe3),
+    (0x1e18, 0x4a3),
+    (0x1e19, 0xe3),
+    (0x1e1a, 0x4a3),
+    (0x1e1b, 0xe3),
+    (0x1e1c, 0x4a3),
+    (0x1e1d, 0xe3),
+    (0x1e1e, 0x4a3),
+    (0x1e1f, 0xe3),
+    (0x1e20, 0x4a3),
+    (0x1e21, 0xe3),
+    (0x1e22, 0x4a3),
+    (0x1e23, 0xe3),
+    (0x1e24, 0x4a3),
+    (0x1e25, 0xe3),
+    (0x1e26, 0x4a3),
+    (0x1e27, 0xe3),
+    (0x1e28, 0x4a3),
+    (0x1e29, 0xe3),
+    (0x1e2a, 0x4a3),
+    (0x1e2b, 0xe3),
+    (0x1e2c, 0x4a3),
+    (0x1e2d, 0xe3),
+    (0x1e2e, 0x4a3),
+    (0x1e2f, 0xe3),
+    (0x1e30, 0x4a3),
+    (0x1e31, 0xe3),
+    (0x1e32, 0x4a3),
+    (0x1e33, 0xe3),
+    (0x1e34, 0x4a3),
+    (0x1e35, 0xe3),
+    (0x1e36, 0x4a3),
+    (0x1e37, 0xe3),
+    (0x1e38, 0x4a3),
+    (0x1e39, 0xe3),
+    (0x1e3a, 0x4a3),
+    (0x1e3b, 0xe3),
+    (0x1e3c, 0x4a3),
+    (0x1e3d, 0xe3),
+    (0x1e3e, 0x4a3),
+    (0x1e3f, 0xe3),
+    (0x1e40, 0x4a3),
+    (0x1e41, 0xe3),
+    (0x1e42, 0x4a3),
+    (0x1e43, 0xe3),
+    (0x1e44, 0x4a3),
+    (0x1e45, 0xe3),
+    (0x1e46, 0x4a3),
+    (0x1e47, 0xe3),
+    (0x1e48, 0x4a3),
+    (0x1e49, 0xe3),
+    (0x1e4a, 0x4a3),
+    (0x1e4b, 0xe3),
+    (0x1e4c, 0x4a3),
+    (0x1e4d, 0xe3),
+    (0x1e4e, 0x4a3),
+    (0x1e4f, 0xe3),
+    (0x1e50, 0x4a3),
+    (0x1e51, 0xe3),
+    (0x1e52, 0x4a3),
+    (0x1e53, 0xe3),
+    (0x1e54, 0x4a3),
+    (0x1e55, 0xe3),
+    (0x1e56, 0x4a3),
+    (0x1e57, 0xe3),
+    (0x1e58, 0x4a3),
+    (0x1e59, 0xe3),
+    (0x1e5a, 0x4a3),
+    (0x1e5b, 0xe3),
+    (0x1e5c, 0x4a3),
+    (0x1e5d, 0xe3),
+    (0x1e5e, 0x4a3),
+    (0x1e5f, 0xe3),
+    (0x1e60, 0x4a3),
+    (0x1e61, 0xe3),
+    (0x1e62, 0x4a3),
+    (0x1e63, 0xe3),
+    (0x1e64, 0x4a3),
+    (0x1e65, 0xe3),
+    (0x1e66, 0x4a3),
+    (0x1e67, 0xe3),
+    (0x1e68, 0x4a3),
+    (0x1e69, 0xe3),
+    (0x1e6a, 0x4a3),
+    (0x1e6b, 0xe3),
+    (0x1e6c, 0x4a3),
+    (0x1e6d, 0xe3),
+    (0x1e6e, 0x4a3),
+    (0x1e6f, 0xe3),
+    (0x1e70, 0x4a3),
+    (0x1e71, 0xe3),
+    (0x1e72, 0x4a3),
+    (0x1e73, 0xe3),
+    (0x1e74, 0x4a3),
+    (0x1e75, 0xe3),
+    (0x1e76, 0x4a3),
+    (0x1e77, 0xe3),
+    (0x1e78, 0x4a3),
+    (0x1e79, 0xe3),
+    (0x1e7a, 0x4a3),
+    (0x1e7b, 0xe3),
+    (0x1e7c, 0x4a3),
+    (0x1e7d, 0xe3),
+    (0x1e7e, 0x4a3),
+    (0x1e7f, 0xe3),
+    (0x1e80, 0x4a3),
+    (0x1e81, 0xe3),
+    (0x1e82, 0x4a3),
+    (0x1e83, 0xe3),
+    (0x1e84, 0x4a3),
+    (0x1e85, 0xe3),
+    (0x1e86, 0x4a3),
+    (0x1e87, 0xe3),
+    (0x1e88, 0x4a3),
+    (0x1e89, 0xe3),
+    (0x1e8a, 0x4a3),
+    (0x1e8b, 0xe3),
+    (0x1e8c, 0x4a3),
+    (0x1e8d, 0xe3),
+    (0x1e8e, 0x4a3),
+    (0x1e8f, 0xe3),
+    (0x1e90, 0x4a3),
+    (0x1e91, 0xe3),
+    (0x1e92, 0x4a3),
+    (0x1e93, 0xe3),
+    (0x1e94, 0x4a3),
+    (0x1e95, 0xe3),
+    (0x1e9e, 0x4a3),
+    (0x1e9f, 0xe3),
+    (0x1ea0, 0x4a3),
+    (0x1ea1, 0xe3),
+    (0x1ea2, 0x4a3),
+    (0x1ea3, 0xe3),
+    (0x1ea4, 0x4a3),
+    (0x1ea5, 0xe3),
+    (0x1ea6, 0x4a3),
+    (0x1ea7, 0xe3),
+    (0x1ea8, 0x4a3),
+    (0x1ea9, 0xe3),
+    (0x1eaa, 0x4a3),
+    (0x1eab, 0xe3),
+    (0x1eac, 0x4a3),
+    (0x1ead, 0xe3),
+    (0x1eae, 0x4a3),
+    (0x1eaf, 0xe3),
+    (0x1eb0, 0x4a3),
+    (0x1eb1, 0xe3),
+    (0x1eb2, 0x4a3),
+    (0x1eb3, 0xe3),
+    (0x1eb4, 0x4a3),
+    (0x1eb5, 0xe3),
+    (0x1eb6, 0x4a3),
+    (0x1eb7, 0xe3),
+    (0x1eb8, 0x4a3),
+    (0x1eb9, 0xe3),
+    (0x1eba, 0x4a3),
+    (0x1ebb, 0xe3),
+    (0x1ebc, 0x4a3),
+    (0x1ebd, 0xe3),
+    (0x1ebe, 0x4a3),
+    (0x1ebf, 0xe3),
+    (0x1ec0, 0x4a3),
+    (0x1ec1, 0xe3),
+    (0x1ec2, 0x4a3),
+    (0x1ec3, 0xe3),
+    (0x1ec4, 0x4a3),
+    (0x1ec5, 0xe3),
+    (0x1ec6, 0x4a3),
+    (0x1ec7, 0xe3),
+    (0x1ec8, 0x4a3),
+    (0x1ec9, 0xe3),
+    (0x1eca, 0x4a3),
+    (0x1ecb, 0xe3),
+    (0x1ecc, 0x4a3),
+    (0x1ecd, 0xe3),
+    (0x1ece, 0x4a3),
+    (0x1ecf, 0xe3),
+    (0x1ed0, 0x4a3),
+    (0x1ed1, 0xe3),
+    (0x1ed2, 0x4a3),
+    (0x1ed3, 0xe3),
+    (0x1ed4, 0x4a3),
+    (0x1ed5, 0xe3),
+    (0x1ed6, 0x4a3),
+    (0x1ed7, 0xe3),
+    (0x1ed8, 0x4a3),
+    (0x1ed9, 0xe3),
+    (0x1eda, 0x4a3),
+    (0x1edb, 0xe3),
+    (0x1edc, 0x4a3),
+    (0x1edd, 0xe3),
+    (0x1ede, 0x4a3),
+    (0x1edf, 0xe3),
+    (0x1ee0, 0x4a3),
+    (0x1ee1, 0xe3),
+    (0x1ee2, 0x4a3),
+    (0x1ee3, 0xe3),
+    (0x1ee4, 0x4a3),
+    (0x1ee5, 0xe3),
+    (0x1ee6, 0x4a3),
+    (0x1ee7, 0xe3),
+    (0x1ee8, 0x4a3),
+    (0x1ee9, 0xe3),
+    (0x1eea, 0x4a3),
+    (0x1eeb, 0xe3),
+    (0x1eec, 0x4a3),
+    (0x1eed, 0xe3),
+    (0x1eee, 0x4a3),
+    (0x1eef, 0xe3),
+    (0x1ef0, 0x4a3),
+    (0x1ef1, 0xe3),
+    (0x1ef2, 0x4a3),
+    (0x1ef3, 0xe3),
+    (0x1ef4, 0x4a3),
+    (0x1ef5, 0xe3),
+    (0x1ef6, 0x4a3),
+    (0x1ef7, 0xe3),
+    (0x1ef8, 0x4a3),
+    (0x1ef9, 0xe3),
+    (0x1efa, 0x4a3),
+    (0x1efb, 0xe3),
+    (0x1efc, 0x4a3),
+    (0x1efd, 0xe3),
+    (0x1efe, 0x4a3),
+    (0x1eff, 0xe3),
+    (0x1f08, 0x4a3),
+    (0x1f10, 0xe3),
+    (0x1f16, 0x0),
+    (0x1f18, 0x4a3),
+    (0x1f1e, 0x0),
+    (0x1f20, 0xe3),
+    (0x1f28, 0x4a3),
+    (0x1f30, 0xe3),
+    (0x1f38, 0x4a3),
+    (0x1f40, 0xe3),
+    (0x1f46, 0x0),
+    (0x1f48, 0x4a3),
+    (0x1f4e, 0x0),
+    (0x1f50, 0xe3),
+    (0x1f58, 0x0),
+    (0x1f59, 0x4a3),
+    (0x1f5a, 0x0),
+    (0x1f5b, 0x4a3),
+    (0x1f5c, 0x0),
+    (0x1f5d, 0x4a3),
+    (0x1f5e, 0x0),
+    (0x1f5f, 0x4a3),
+    (0x1f60, 0xe3),
+    (0x1f68, 0x4a3),
+    (0x1f70, 0xe3),
+    (0x1f7e, 0x0),
+    (0x1f80, 0xe3),
+    (0x1f88, 0x4a3),
+    (0x1f90, 0xe3),
+    (0x1f98, 0x4a3),
+    (0x1fa0, 0xe3),
+    (0x1fa8, 0x4a3),
+    (0x1fb0, 0xe3),
+    (0x1fb5, 0x0),
+    (0x1fb6, 0xe3),
+    (0x1fb8, 0x4a3),
+    (0x1fbd, 0x1a0),
+    (0x1fbe, 0xe3),
+    (0x1fbf, 0x1a0),
+    (0x1fc2, 0xe3),
+    (0x1fc5, 0x0),
+    (0x1fc6, 0xe3),
+    (0x1fc8, 0x4a3),
+    (0x1fcd, 0x1a0),
+    (0x1fd0, 0xe3),
+    (0x1fd4, 0x0),
+    (0x1fd6, 0xe3),
+    (0x1fd8, 0x4a3),
+    (0x1fdc, 0x0),
+    (0x1fdd, 0x1a0),
+    (0x1fe0, 0xe3),
+    (0x1fe8, 0x4a3),
+    (0x1fed, 0x1a0),
+    (0x1ff0, 0x0),
+    (0x1ff2, 0xe3),
+    (0x1ff5, 0x0),
+    (0x1ff6, 0xe3),
+    (0x1ff8, 0x4a3),
+    (0x1ffd, 0x1a0),
+    (0x1fff, 0x0),
+    (0x2000, 0x284),
+    (0x2007, 0x1a0),
+    (0x2008, 0x284),
+    (0x200b, 0x1a0),
+    (0x2028, 0x208),
+    (0x202a, 0x1a0),
+    (0x205f, 0x284),
+    (0x2060, 0x1a0),
+    (0x2065, 0x0),
+    (0x2066, 0x1a0),
+    (0x2071, 0xe3),
+    (0x2072, 0x0),
+    (0x2074, 0x1a0),
+    (0x207f, 0xe3),
+    (0x2080, 0x1a0),
+    (0x208f, 0x0),
+    (0x2090, 0xe3),
+    (0x209d, 0x0),
+    (0x20a0, 0x1a0),
+    (0x20c1, 0x0),
+    (0x20d0, 0x1a0),
+    (0x20f1, 0x0),
+    (0x2100, 0x1a0),
+    (0x2102, 0x4a3),
+    (0x2103, 0x1a0),
+    (0x2107, 0x4a3),
+    (0x2108, 0x1a0),
+    (0x210a, 0xe3),
+    (0x210b, 0x4a3),
+    (0x210e, 0xe3),
+    (0x2110, 0x4a3),
+    (0x2113, 0xe3),
+    (0x2114, 0x1a0),
+    (0x2115, 0x4a3),
+    (0x2116, 0x1a0),
+    (0x2119, 0x4a3),
+    (0x211e, 0x1a0),
+    (0x2124, 0x4a3),
+    (0x2125, 0x1a0),
+    (0x2126, 0x4a3),
+    (0x2127, 0x1a0),
+    (0x2128, 0x4a3),
+    (0x2129, 0x1a0),
+    (0x212a, 0x4a3),
+    (0x212e, 0x1a0),
+    (0x212f, 0xe3),
+    (0x2130, 0x4a3),
+    (0x2134, 0xe3),
+    (0x2135, 0xa3),
+    (0x2139, 0xe3),
+    (0x213a, 0x1a0),
+    (0x213c, 0xe3),
+    (0x213e, 0x4a3),
+    (0x2140, 0x1a0),
+    (0x2145, 0x4a3),
+    (0x2146, 0xe3),
+    (0x214a, 0x1a0),
+    (0x214e, 0xe3),
+    (0x214f, 0x1a0),
+    (0x2160, 0x4a3),
+    (0x2170, 0xe3),
+    (0x2180, 0xa3),
+    (0x2183, 0x4a3),
+    (0x2184, 0xe3),
+    (0x2185, 0xa3),
+    (0x2189, 0x1a0),
+    (0x218c, 0x0),
+    (0x2190, 0x1a0),
+    (0x2427, 0x0),
+    (0x2440, 0x1a0),
+    (0x244b, 0x0),
+    (0x2460, 0x1a0),
+    (0x24b6, 0x4a3),
+    (0x24d0, 0xe3),
+    (0x24ea, 0x1a0),
+    (0x2b74, 0x0),
+    (0x2b76, 0x1a0),
+    (0x2b96, 0x0),
+    (0x2b97, 0x1a0),
+    (0x2c00, 0x4a3),
+    (0x2c30, 0xe3),
+    (0x2c60, 0x4a3),
+    (0x2c61, 0xe3),
+    (0x2c62, 0x4a3),
+    (0x2c65, 0xe3),
+    (0x2c67, 0x4a3),
+    (0x2c68, 0xe3),
+    (0x2c69, 0x4a3),
+    (0x2c6a, 0xe3),
+    (0x2c6b, 0x4a3),
+    (0x2c6c, 0xe3),
+    (0x2c6d, 0x4a3),
+    (0x2c71, 0xe3),
+    (0x2c72, 0x4a3),
+    (0x2c73, 0xe3),
+    (0x2c75, 0x4a3),
+    (0x2c76, 0xe3),
+    (0x2c7e, 0x4a3),
+    (0x2c81, 0xe3),
+    (0x2c82, 0x4a3),
+    (0x2c83, 0xe3),
+    (0x2c84, 0x4a3),
+    (0x2c85, 0xe3),
+    (0x2c86, 0x4a3),
+    (0x2c87, 0xe3),
+    (0x2c88, 0x4a3),
+    (0x2c89, 0xe3),
+    (0x2c8a, 0x4a3),
+    (0x2c8b, 0xe3),
+    (0x2c8c, 0x4a3),
+    (0x2c8d, 0xe3),
+    (0x2c8e, 0x4a3),
+    (0x2c8f, 0xe3),
+    (0x2c90, 0x4a3),
+    (0x2c91, 0xe3),
+    (0x2c92, 0x4a3),
+    (0x2c93, 0xe3),
+    (0x2c94, 0x4a3),
+    (0x2c95, 0xe3),
+    (0x2c96, 0x4a3),
+    (0x2c97, 0xe3),
+    (0x2c98, 0x4a3),
+    (0x2c99, 0xe3),
+    (0x2c9a, 0x4a3),
+    (0x2c9b, 0xe3),
+    (0x2c9c, 0x4a3),
+    (0x2c9d, 0xe3),
+    (0x2c9e, 0x4a3),
+    (0x2c9f, 0xe3),
+    (0x2ca0, 0x4a3),
+    (0x2ca1, 0xe3),
+    (0x2ca2, 0x4a3),
+    (0x2ca3, 0xe3),
+    (0x2ca4, 0x4a3),
+    (0x2ca5, 0xe3),
+    (0x2ca6, 0x4a3),
+    (0x2ca7, 0xe3),
+    (0x2ca8, 0x4a3),
+    (0x2ca9, 0xe3),
+    (0x2caa, 0x4a3),
+    (0x2cab, 0xe3),
+    (0x2cac, 0x4a3),
+    (0x2cad, 0xe3),
+    (0x2cae, 0x4a3),
+    (0x2caf, 0xe3),
+    (0x2cb0, 0x4a3),
+    (0x2cb1, 0xe3),
+    (0x2cb2, 0x4a3),
+    (0x2cb3, 0xe3),
+    (0x2cb4, 0x4a3),
+    (0x2cb5, 0xe3),
+    (0x2cb6, 0x4a3),
+    (0x2cb7, 0xe3),
+    (0x2cb8, 0x4a3),
+    (0x2cb9, 0xe3),
+    (0x2cba, 0x4a3),
+    (0x2cbb, 0xe3),
+    (0x2cbc, 0x4a3),
+    (0x2cbd, 0xe3),
+    (0x2cbe, 0x4a3),
+    (0x2cbf, 0xe3),
+    (0x2cc0, 0x4a3),
+    (0x2cc1, 0xe3),
+    (0x2cc2, 0x4a3),
+    (0x2cc3, 0xe3),
+    (0x2cc4, 0x4a3),
+    (0x2cc5, 0xe3),
+    (0x2cc6, 0x4a3),
+    (0x2cc7, 0xe3),
+    (0x2cc8, 0x4a3),
+    (0x2cc9, 0xe3),
+    (0x2cca, 0x4a3),
+    (0x2ccb, 0xe3),
+    (0x2ccc, 0x4a3),
+    (0x2ccd, 0xe3),
+    (0x2cce, 0x4a3),
+    (0x2ccf, 0xe3),
+    (0x2cd0, 0x4a3),
+    (0x2cd1, 0xe3),
+    (0x2cd2, 0x4a3),
+    (0x2cd3, 0xe3),
+    (0x2cd4, 0x4a3),
+    (0x2cd5, 0xe3),
+    (0x2cd6, 0x4a3),
+    (0x2cd7, 0xe3),
+    (0x2cd8, 0x4a3),
+    (0x2cd9, 0xe3),
+    (0x2cda, 0x4a3),
+    (0x2cdb, 0xe3),
+    (0x2cdc, 0x4a3),
+    (0x2cdd, 0xe3),
+    (0x2cde, 0x4a3),
+    (0x2cdf, 0xe3),
+    (0x2ce0, 0x4a3),
+    (0x2ce1, 0xe3),
+    (0x2ce2, 0x4a3),
+    (0x2ce3, 0xe3),
+    (0x2ce5, 0x1a0),
+    (0x2ceb, 0x4a3),
+    (0x2cec, 0xe3),
+    (0x2ced, 0x4a3),
+    (0x2cee, 0xe3),
+    (0x2cef, 0x1a0),
+    (0x2cf2, 0x4a3),
+    (0x2cf3, 0xe3),
+    (0x2cf4, 0x0),
+    (0x2cf9, 0x1a0),
+    (0x2d00, 0xe3),
+    (0x2d26, 0x0),
+    (0x2d27, 0xe3),
+    (0x2d28, 0x0),
+    (0x2d2d, 0xe3),
+    (0x2d2e, 0x0),
+    (0x2d30, 0xa3),
+    (0x2d68, 0x0),
+    (0x2d6f, 0xa3),
+    (0x2d70, 0x1a0),
+    (0x2d71, 0x0),
+    (0x2d7f, 0x1a0),
+    (0x2d80, 0xa3),
+    (0x2d97, 0x0),
+    (0x2da0, 0xa3),
+    (0x2da7, 0x0),
+    (0x2da8, 0xa3),
+    (0x2daf, 0x0),
+    (0x2db0, 0xa3),
+    (0x2db7, 0x0),
+    (0x2db8, 0xa3),
+    (0x2dbf, 0x0),
+    (0x2dc0, 0xa3),
+    (0x2dc7, 0x0),
+    (0x2dc8, 0xa3),
+    (0x2dcf, 0x0),
+    (0x2dd0, 0xa3),
+    (0x2dd7, 0x0),
+    (0x2dd8, 0xa3),
+    (0x2ddf, 0x0),
+    (0x2de0, 0xa3),
+    (0x2e00, 0x1a0),
+    (0x2e2f, 0xa3),
+    (0x2e30, 0x1a0),
+    (0x2e5e, 0x0),
+    (0x2e80, 0x1a0),
+    (0x2e9a, 0x0),
+    (0x2e9b, 0x1a0),
+    (0x2ef4, 0x0),
+    (0x2f00, 0x1a0),
+    (0x2fd6, 0x0),
+    (0x2ff0, 0x1a0),
+    (0x2ffc, 0x0),
+    (0x3000, 0x284),
+    (0x3001, 0x1a0),
+    (0x3005, 0xa3),
+    (0x3008, 0x1a0),
+    (0x3021, 0xa3),
+    (0x302a, 0x1a0),
+    (0x3031, 0xa3),
+    (0x3036, 0x1a0),
+    (0x3038, 0xa3),
+    (0x303d, 0x1a0),
+    (0x3040, 0x0),
+    (0x3041, 0xa3),
+    (0x3097, 0x0),
+    (0x3099, 0x1a0),
+    (0x309d, 0xa3),
+    (0x30a0, 0x1a0),
+    (0x30a1, 0xa3),
+    (0x30fb, 0x1a0),
+    (0x30fc, 0xa3),
+    (0x3100, 0x0),
+    (0x3105, 0xa3),
+    (0x3130, 0x0),
+    (0x3131, 0xa3),
+    (0x318f, 0x0),
+    (0x3190, 0x1a0),
+    (0x31a0, 0xa3),
+    (0x31c0, 0x1a0),
+    (0x31e4, 0x0),
+    (0x31f0, 0xa3),
+    (0x3200, 0x1a0),
+    (0x321f, 0x0),
+    (0x3220, 0x1a0),
+    (0x3400, 0xa3),
+    (0x4dc0, 0x1a0),
+    (0x4e00, 0xa3),
+    (0xa48d, 0x0),
+    (0xa490, 0x1a0),
+    (0xa4c7, 0x0),
+    (0xa4d0, 0xa3),
+    (0xa4fe, 0x1a0),
+    (0xa500, 0xa3),
+    (0xa60d, 0x1a0),
+    (0xa610, 0xa3),
+    (0xa62c, 0x0),
+    (0xa640, 0x4a3),
+    (0xa641, 0xe3),
+    (0xa642, 0x4a3),
+    (0xa643, 0xe3),
+    (0xa644, 0x4a3),
+    (0xa645, 0xe3),
+    (0xa646, 0x4a3),
+    (0xa647, 0xe3),
+    (0xa648, 0x4a3),
+    (0xa649, 0xe3),
+    (0xa64a, 0x4a3),
+    (0xa64b, 0xe3),
+    (0xa64c, 0x4a3),
+    (0xa64d, 0xe3),
+    (0xa64e, 0x4a3),
+    (0xa64f, 0xe3),
+    (0xa650, 0x4a3),
+    (0xa651, 0xe3),
+    (0xa652, 0x4a3),
+    (0xa653, 0xe3),
+    (0xa654, 0x4a3),
+    (0xa655, 0xe3),
+    (0xa656, 0x4a3),
+    (0xa657, 0xe3),
+    (0xa658, 0x4a3),
+    (0xa659, 0xe3),
+    (0xa65a, 0x4a3),
+    (0xa65b, 0xe3),
+    (0xa65c, 0x4a3),
+    (0xa65d, 0xe3),
+    (0xa65e, 0x4a3),
+    (0xa65f, 0xe3),
+    (0xa660, 0x4a3),
+    (0xa661, 0xe3),
+    (0xa662, 0x4a3),
+    (0xa663, 0xe3),
+    (0xa664, 0x4a3),
+    (0xa665, 0xe3),
+    (0xa666, 0x4a3),
+    (0xa667, 0xe3),
+    (0xa668, 0x4a3),
+    (0xa669, 0xe3),
+    (0xa66a, 0x4a3),
+    (0xa66b, 0xe3),
+    (0xa66c, 0x4a3),
+    (0xa66d, 0xe3),
+    (0xa66e, 0xa3),
+    (0xa66f, 0x1a0),
+    (0xa674, 0xa3),
+    (0xa67c, 0x1a0),
+    (0xa67f, 0xa3),
+    (0xa680, 0x4a3),
+    (0xa681, 0xe3),
+    (0xa682, 0x4a3),
+    (0xa683, 0xe3),
+    (0xa684, 0x4a3),
+    (0xa685, 0xe3),
+    (0xa686, 0x4a3),
+    (0xa687, 0xe3),
+    (0xa688, 0x4a3),
+    (0xa689, 0xe3),
+    (0xa68a, 0x4a3),
+    (0xa68b, 0xe3),
+    (0xa68c, 0x4a3),
+    (0xa68d, 0xe3),
+    (0xa68e, 0x4a3),
+    (0xa68f, 0xe3),
+    (0xa690, 0x4a3),
+    (0xa691, 0xe3),
+    (0xa692, 0x4a3),
+    (0xa693, 0xe3),
+    (0xa694, 0x4a3),
+    (0xa695, 0xe3),
+    (0xa696, 0x4a3),
+    (0xa697, 0xe3),
+    (0xa698, 0x4a3),
+    (0xa699, 0xe3),
+    (0xa69a, 0x4a3),
+    (0xa69b, 0xe3),
+    (0xa69e, 0xa3),
+    (0xa6f0, 0x1a0),
+    (0xa6f8, 0x0),
+    (0xa700, 0x1a0),
+    (0xa717, 0xa3),
+    (0xa720, 0x1a0),
+    (0xa722, 0x4a3),
+    (0xa723, 0xe3),
+    (0xa724, 0x4a3),
+    (0xa725, 0xe3),
+    (0xa726, 0x4a3),
+    (0xa727, 0xe3),
+    (0xa728, 0x4a3),
+    (0xa729, 0xe3),
+    (0xa72a, 0x4a3),
+    (0xa72b, 0xe3),
+    (0xa72c, 0x4a3),
+    (0xa72d, 0xe3),
+    (0xa72e, 0x4a3),
+    (0xa72f, 0xe3),
+    (0xa732, 0x4a3),
+    (0xa733, 0xe3),
+    (0xa734, 0x4a3),
+    (0xa735, 0xe3),
+    (0xa736, 0x4a3),
+    (0xa737, 0xe3),
+    (0xa738, 0x4a3),
+    (0xa739, 0xe3),
+    (0xa73a, 0x4a3),
+    (0xa73b, 0xe3),
+    (0xa73c, 0x4a3),
+    (0xa73d, 0xe3),
+    (0xa73e, 0x4a3),
+    (0xa73f, 0xe3),
+    (0xa740, 0x4a3),
+    (0xa741, 0xe3),
+    (0xa742, 0x4a3),
+    (0xa743, 0xe3),
+    (0xa744, 0x4a3),
+    (0xa745, 0xe3),
+    (0xa746, 0x4a3),
+    (0xa747, 0xe3),
+    (0xa748, 0x4a3),
+    (0xa749, 0xe3),
+    (0xa74a, 0x4a3),
+    (0xa74b, 0xe3),
+    (0xa74c, 0x4a3),
+    (0xa74d, 0xe3),
+    (0xa74e, 0x4a3),
+    (0xa74f, 0xe3),
+    (0xa750, 0x4a3),
+    (0xa751, 0xe3),
+    (0xa752, 0x4a3),
+    (0xa753, 0xe3),
+    (0xa754, 0x4a3),
+    (0xa755, 0xe3),
+    (0xa756, 0x4a3),
+    (0xa757, 0xe3),
+    (0xa758, 0x4a3),
+    (0xa759, 0xe3),
+    (0xa75a, 0x4a3),
+    (0xa75b, 0xe3),
+    (0xa75c, 0x4a3),
+    (0xa75d, 0xe3),
+    (0xa75e, 0x4a3),
+    (0xa75f, 0xe3),
+    (0xa760, 0x4a3),
+    (0xa761, 0xe3),
+    (0xa762, 0x4a3),
+    (0xa763, 0xe3),
+    (0xa764, 0x4a3),
+    (0xa765, 0xe3),
+    (0xa766, 0x4a3),
+    (0xa767, 0xe3),
+    (0xa768, 0x4a3),
+    (0xa769, 0xe3),
+    (0xa76a, 0x4a3),
+    (0xa76b, 0xe3),
+    (0xa76c, 0x4a3),
+    (0xa76d, 0xe3),
+    (0xa76e, 0x4a3),
+    (0xa76f, 0xe3),
+    (0xa779, 0x4a3),
+    (0xa77a, 0xe3),
+    (0xa77b, 0x4a3),
+    (0xa77c, 0xe3),
+    (0xa77d, 0x4a3),
+    (0xa77f, 0xe3),
+    (0xa780, 0x4a3),
+    (0xa781, 0xe3),
+    (0xa782, 0x4a3),
+    (0xa783, 0xe3),
+    (0xa784, 0x4a3),
+    (0xa785, 0xe3),
+    (0xa786, 0x4a3),
+    (0xa787, 0xe3),
+    (0xa788, 0xa3),
+    (0xa789, 0x1a0),
+    (0xa78b, 0x4a3),
+    (0xa78c, 0xe3),
+    (0xa78d, 0x4a3),
+    (0xa78e, 0xe3),
+    (0xa78f, 0xa3),
+    (0xa790, 0x4a3),
+    (0xa791, 0xe3),
+    (0xa792, 0x4a3),
+    (0xa793, 0xe3),
+    (0xa796, 0x4a3),
+    (0xa797, 0xe3),
+    (0xa798, 0x4a3),
+    (0xa799, 0xe3),
+    (0xa79a, 0x4a3),
+    (0xa79b, 0xe3),
+    (0xa79c, 0x4a3),
+    (0xa79d, 0xe3),
+    (0xa79e, 0x4a3),
+    (0xa79f, 0xe3),
+    (0xa7a0, 0x4a3),
+    (0xa7a1, 0xe3),
+    (0xa7a2, 0x4a3),
+    (0xa7a3, 0xe3),
+    (0xa7a4, 0x4a3),
+    (0xa7a5, 0xe3),
+    (0xa7a6, 0x4a3),
+    (0xa7a7, 0xe3),
+    (0xa7a8, 0x4a3),
+    (0xa7a9, 0xe3),
+    (0xa7aa, 0x4a3),
+    (0xa7af, 0xe3),
+    (0xa7b0, 0x4a3),
+    (0xa7b5, 0xe3),
+    (0xa7b6, 0x4a3),
+    (0xa7b7, 0xe3),
+    (0xa7b8, 0x4a3),
+    (0xa7b9, 0xe3),
+    (0xa7ba, 0x4a3),
+    (0xa7bb, 0xe3),
+    (0xa7bc, 0x4a3),
+    (0xa7bd, 0xe3),
+    (0xa7be, 0x4a3),
+    (0xa7bf, 0xe3),
+    (0xa7c0, 0x4a3),
+    (0xa7c1, 0xe3),
+    (0xa7c2, 0x4a3),
+    (0xa7c3, 0xe3),
+    (0xa7c4, 0x4a3),
+    (0xa7c8, 0xe3),
+    (0xa7c9, 0x4a3),
+    (0xa7ca, 0xe3),
+    (0xa7cb, 0x0),
+    (0xa7d0, 0x4a3),
+    (0xa7d1, 0xe3),
+    (0xa7d2, 0x0),
+    (0xa7d3, 0xe3),
+    (0xa7d4, 0x0),
+    (0xa7d5, 0xe3),
+    (0xa7d6, 0x4a3),
+    (0xa7d7, 0xe3),
+    (0xa7d8, 0x4a3),
+    (0xa7d9, 0xe3),
+    (0xa7da, 0x0),
+    (0xa7f2, 0xa3),
+    (0xa7f5, 0x4a3),
+    (0xa7f6, 0xe3),
+    (0xa7f7, 0xa3),
+    (0xa7f8, 0xe3),
+    (0xa7fb, 0xa3),
+    (0xa806, 0x1a0),
+    (0xa807, 0xa3),
+    (0xa828, 0x1a0),
+    (0xa82d, 0x0),
+    (0xa830, 0x1a0),
+    (0xa83a, 0x0),
+    (0xa840, 0xa3),
+    (0xa874, 0x1a0),
+    (0xa878, 0x0),
+    (0xa880, 0xa3),
+    (0xa8c4, 0x1a0),
+    (0xa8c5, 0xa3),
+    (0xa8c6, 0x0),
+    (0xa8ce, 0x1a0),
+    (0xa8d0, 0xa3),
+    (0xa8da, 0x0),
+    (0xa8e0, 0x1a0),
+    (0xa8f2, 0xa3),
+    (0xa8f8, 0x1a0),
+    (0xa8fb, 0xa3),
+    (0xa8fc, 0x1a0),
+    (0xa8fd, 0xa3),
+    (0xa92b, 0x1a0),
+    (0xa930, 0xa3),
+    (0xa953, 0x1a0),
+    (0xa954, 0x0),
+    (0xa95f, 0x1a0),
+    (0xa960, 0xa3),
+    (0xa97d, 0x0),
+    (0xa980, 0xa3),
+    (0xa9b3, 0x1a0),
+    (0xa9b4, 0xa3),
+    (0xa9c0, 0x1a0),
+    (0xa9ce, 0x0),
+    (0xa9cf, 0xa3),
+    (0xa9da, 0x0),
+    (0xa9de, 0x1a0),
+    (0xa9e0, 0xa3),
+    (0xa9ff, 0x0),
+    (0xaa00, 0xa3),
+    (0xaa37, 0x0),
+    (0xaa40, 0xa3),
+    (0xaa4e, 0x0),
+    (0xaa50, 0xa3),
+    (0xaa5a, 0x0),
+    (0xaa5c, 0x1a0),
+    (0xaa60, 0xa3),
+    (0xaa77, 0x1a0),
+    (0xaa7a, 0xa3),
+    (0xaabf, 0x1a0),
+    (0xaac0, 0xa3),
+    (0xaac1, 0x1a0),
+    (0xaac2, 0xa3),
+    (0xaac3, 0x0),
+    (0xaadb, 0xa3),
+    (0xaade, 0x1a0),
+    (0xaae0, 0xa3),
+    (0xaaf0, 0x1a0),
+    (0xaaf2, 0xa3),
+    (0xaaf6, 0x1a0),
+    (0xaaf7, 0x0),
+    (0xab01, 0xa3),
+    (0xab07, 0x0),
+    (0xab09, 0xa3),
+    (0xab0f, 0x0),
+    (0xab11, 0xa3),
+    (0xab17, 0x0),
+    (0xab20, 0xa3),
+    (0xab27, 0x0),
+    (0xab28, 0xa3),
+    (0xab2f, 0x0),
+    (0xab30, 0xe3),
+    (0xab5b, 0x1a0),
+    (0xab5c, 0xe3),
+    (0xab69, 0xa3),
+    (0xab6a, 0x1a0),
+    (0xab6c, 0x0),
+    (0xab70, 0xe3),
+    (0xabc0, 0xa3),
+    (0xabeb, 0x1a0),
+    (0xabee, 0x0),
+    (0xabf0, 0xa3),
+    (0xabfa, 0x0),
+    (0xac00, 0xa3),
+    (0xd7a4, 0x0),
+    (0xd7b0, 0xa3),
+    (0xd7c7, 0x0),
+    (0xd7cb, 0xa3),
+    (0xd7fc, 0x0),
+    (0xe000, 0x1a0),
+    (0xf900, 0xa3),
+    (0xfa6e, 0x0),
+    (0xfa70, 0xa3),
+    (0xfada, 0x0),
+    (0xfb00, 0xe3),
+    (0xfb07, 0x0),
+    (0xfb13, 0xe3),
+    (0xfb18, 0x0),
+    (0xfb1d, 0xa3),
+    (0xfb29, 0x1a0),
+    (0xfb2a, 0xa3),
+    (0xfb37, 0x0),
+    (0xfb38, 0xa3),
+    (0xfb3d, 0x0),
+    (0xfb3e, 0xa3),
+    (0xfb3f, 0x0),
+    (0xfb40, 0xa3),
+    (0xfb42, 0x0),
+    (0xfb43, 0xa3),
+    (0xfb45, 0x0),
+    (0xfb46, 0xa3),
+    (0xfbb2, 0x1a0),
+    (0xfbc3, 0x0),
+    (0xfbd3, 0xa3),
+    (0xfd3e, 0x1a0),
+    (0xfd50, 0xa3),
+    (0xfd90, 0x0),
+    (0xfd92, 0xa3),
+    (0xfdc8, 0x0),
+    (0xfdcf, 0x1a0),
+    (0xfdd0, 0x0),
+    (0xfdf0, 0xa3),
+    (0xfdfc, 0x1a0),
+    (0xfe1a, 0x0),
+    (0xfe20, 0x1a0),
+    (0xfe53, 0x0),
+    (0xfe54, 0x1a0),
+    (0xfe67, 0x0),
+    (0xfe68, 0x1a0),
+    (0xfe6c, 0x0),
+    (0xfe70, 0xa3),
+    (0xfe75, 0x0),
+    (0xfe76, 0xa3),
+    (0xfefd, 0x0),
+    (0xfeff, 0x1a0),
+    (0xff00, 0x0),
+    (0xff01, 0x1a0),
+    (0xff10, 0xa3),
+    (0xff1a, 0x1a0),
+    (0xff21, 0x4a3),
+    (0xff3b, 0x1a0),
+    (0xff41, 0xe3),
+    (0xff5b, 0x1a0),
+    (0xff66, 0xa3),
+    (0xffbf, 0x0),
+    (0xffc2, 0xa3),
+    (0xffc8, 0x0),
+    (0xffca, 0xa3),
+    (0xffd0, 0x0),
+    (0xffd2, 0xa3),
+    (0xffd8, 0x0),
+    (0xffda, 0xa3),
+    (0xffdd, 0x0),
+    (0xffe0, 0x1a0),
+    (0xffe7, 0x0),
+    (0xffe8, 0x1a0),
+    (0xffef, 0x0),
+    (0xfff9, 0x1a0),
+    (0xfffe, 0x0),
+    (0x10000, 0xa3),
+    (0x1000c, 0x0),
+    (0x1000d, 0xa3),
+    (0x10027, 0x0),
+    (0x10028, 0xa3),
+    (0x1003b, 0x0),
+    (0x1003c, 0xa3),
+    (0x1003e, 0x0),
+    (0x1003f, 0xa3),
+    (0x1004e, 0x0),
+    (0x10050, 0xa3),
+    (0x1005e, 0x0),
+    (0x10080, 0xa3),
+    (0x100fb, 0x0),
+    (0x10100, 0x1a0),
+    (0x10103, 0x0),
+    (0x10107, 0x1a0),
+    (0x10134, 0x0),
+    (0x10137, 0x1a0),
+    (0x10140, 0xa3),
+    (0x10175, 0x1a0),
+    (0x1018f, 0x0),
+    (0x10190, 0x1a0),
+    (0x1019d, 0x0),
+    (0x101a0, 0x1a0),
+    (0x101a1, 0x0),
+    (0x101d0, 0x1a0),
+    (0x101fe, 0x0),
+    (0x10280, 0xa3),
+    (0x1029d, 0x0),
+    (0x102a0, 0xa3),
+    (0x102d1, 0x0),
+    (0x102e0, 0x1a0),
+    (0x102fc, 0x0),
+    (0x10300, 0xa3),
+    (0x10320, 0x1a0),
+    (0x10324, 0x0),
+    (0x1032d, 0xa3),
+    (0x1034b, 0x0),
+    (0x10350, 0xa3),
+    (0x1037b, 0x0),
+    (0x10380, 0xa3),
+    (0x1039e, 0x0),
+    (0x1039f, 0x1a0),
+    (0x103a0, 0xa3),
+    (0x103c4, 0x0),
+    (0x103c8, 0xa3),
+    (0x103d0, 0x1a0),
+    (0x103d1, 0xa3),
+    (0x103d6, 0x0),
+    (0x10400, 0x4a3),
+    (0x10428, 0xe3),
+    (0x10450, 0xa3),
+    (0x1049e, 0x0),
+    (0x104a0, 0xa3),
+    (0x104aa, 0x0),
+    (0x104b0, 0x4a3),
+    (0x104d4, 0x0),
+    (0x104d8, 0xe3),
+    (0x104fc, 0x0),
+    (0x10500, 0xa3),
+    (0x10528, 0x0),
+    (0x10530, 0xa3),
+    (0x10564, 0x0),
+    (0x1056f, 0x1a0),
+    (0x10570, 0x4a3),
+    (0x1057b, 0x0),
+    (0x1057c, 0x4a3),
+    (0x1058b, 0x0),
+    (0x1058c, 0x4a3),
+    (0x10593, 0x0),
+    (0x10594, 0x4a3),
+    (0x10596, 0x0),
+    (0x10597, 0xe3),
+    (0x105a2, 0x0),
+    (0x105a3, 0xe3),
+    (0x105b2, 0x0),
+    (0x105b3, 0xe3),
+    (0x105ba, 0x0),
+    (0x105bb, 0xe3),
+    (0x105bd, 0x0),
+    (0x10600, 0xa3),
+    (0x10737, 0x0),
+    (0x10740, 0xa3),
+    (0x10756, 0x0),
+    (0x10760, 0xa3),
+    (0x10768, 0x0),
+    (0x10780, 0xe3),
+    (0x10781, 0xa3),
+    (0x10783, 0xe3),
+    (0x10786, 0x0),
+    (0x10787, 0xe3),
+    (0x107b1, 0x0),
+    (0x107b2, 0xe3),
+    (0x107bb, 0x0),
+    (0x10800, 0xa3),
+    (0x10806, 0x0),
+    (0x10808, 0xa3),
+    (0x10809, 0x0),
+    (0x1080a, 0xa3),
+    (0x10836, 0x0),
+    (0x10837, 0xa3),
+    (0x10839, 0x0),
+    (0x1083c, 0xa3),
+    (0x1083d, 0x0),
+    (0x1083f, 0xa3),
+    (0x10856, 0x0),
+    (0x10857, 0x1a0),
+    (0x10860, 0xa3),
+    (0x10877, 0x1a0),
+    (0x10880, 0xa3),
+    (0x1089f, 0x0),
+    (0x108a7, 0x1a0),
+    (0x108b0, 0x0),
+    (0x108e0, 0xa3),
+    (0x108f3, 0x0),
+    (0x108f4, 0xa3),
+    (0x108f6, 0x0),
+    (0x108fb, 0x1a0),
+    (0x10900, 0xa3),
+    (0x10916, 0x1a0),
+    (0x1091c, 0x0),
+    (0x1091f, 0x1a0),
+    (0x10920, 0xa3),
+    (0x1093a, 0x0),
+    (0x1093f, 0x1a0),
+    (0x10940, 0x0),
+    (0x10980, 0xa3),
+    (0x109b8, 0x0),
+    (0x109bc, 0x1a0),
+    (0x109be, 0xa3),
+    (0x109c0, 0x1a0),
+    (0x109d0, 0x0),
+    (0x109d2, 0x1a0),
+    (0x10a00, 0xa3),
+    (0x10a04, 0x0),
+    (0x10a05, 0xa3),
+    (0x10a07, 0x0),
+    (0x10a0c, 0xa3),
+    (0x10a14, 0x0),
+    (0x10a15, 0xa3),
+    (0x10a18, 0x0),
+    (0x10a19, 0xa3),
+    (0x10a36, 0x0),
+    (0x10a38, 0x1a0),
+    (0x10a3b, 0x0),
+    (0x10a3f, 0x1a0),
+    (0x10a49, 0x0),
+    (0x10a50, 0x1a0),
+    (0x10a59, 0x0),
+    (0x10a60, 0xa3),
+    (0x10a7d, 0x1a0),
+    (0x10a80, 0xa3),
+    (0x10a9d, 0x1a0),
+    (0x10aa0, 0x0),
+    (0x10ac0, 0xa3),
+    (0x10ac8, 0x1a0),
+    (0x10ac9, 0xa3),
+    (0x10ae5, 0x1a0),
+    (0x10ae7, 0x0),
+    (0x10aeb, 0x1a0),
+    (0x10af7, 0x0),
+    (0x10b00, 0xa3),
+    (0x10b36, 0x0),
+    (0x10b39, 0x1a0),
+    (0x10b40, 0xa3),
+    (0x10b56, 0x0),
+    (0x10b58, 0x1a0),
+    (0x10b60, 0xa3),
+    (0x10b73, 0x0),
+    (0x10b78, 0x1a0),
+    (0x10b80, 0xa3),
+    (0x10b92, 0x0),
+    (0x10b99, 0x1a0),
+    (0x10b9d, 0x0),
+    (0x10ba9, 0x1a0),
+    (0x10bb0, 0x0),
+    (0x10c00, 0xa3),
+    (0x10c49, 0x0),
+    (0x10c80, 0x4a3),
+    (0x10cb3, 0x0),
+    (0x10cc0, 0xe3),
+    (0x10cf3, 0x0),
+    (0x10cfa, 0x1a0),
+    (0x10d00, 0xa3),
+    (0x10d28, 0x0),
+    (0x10d30, 0xa3),
+    (0x10d3a, 0x0),
+    (0x10e60, 0x1a0),
+    (0x10e7f, 0x0),
+    (0x10e80, 0xa3),
+    (0x10eaa, 0x0),
+    (0x10eab, 0xa3),
+    (0x10ead, 0x1a0),
+    (0x10eae, 0x0),
+    (0x10eb0, 0xa3),
+    (0x10eb2, 0x0),
+    (0x10f00, 0xa3),
+    (0x10f1d, 0x1a0),
+    (0x10f27, 0xa3),
+    (0x10f28, 0x0),
+    (0x10f30, 0xa3),
+    (0x10f46, 0x1a0),
+    (0x10f5a, 0x0),
+    (0x10f70, 0xa3),
+    (0x10f82, 0x1a0),
+    (0x10f8a, 0x0),
+    (0x10fb0, 0xa3),
+    (0x10fc5, 0x1a0),
+    (0x10fcc, 0x0),
+    (0x10fe0, 0xa3),
+    (0x10ff7, 0x0),
+    (0x11000, 0xa3),
+    (0x11046, 0x1a0),
+    (0x1104e, 0x0),
+    (0x11052, 0x1a0),
+    (0x11066, 0xa3),
+    (0x11070, 0x1a0),
+    (0x11071, 0xa3),
+    (0x11076, 0x0),
+    (0x1107f, 0x1a0),
+    (0x11082, 0xa3),
+    (0x110b9, 0x1a0),
+    (0x110c2, 0xa3),
+    (0x110c3, 0x0),
+    (0x110cd, 0x1a0),
+    (0x110ce, 0x0),
+    (0x110d0, 0xa3),
+    (0x110e9, 0x0),
+    (0x110f0, 0xa3),
+    (0x110fa, 0x0),
+    (0x11100, 0xa3),
+    (0x11133, 0x1a0),
+    (0x11135, 0x0),
+    (0x11136, 0xa3),
+    (0x11140, 0x1a0),
+    (0x11144, 0xa3),
+    (0x11148, 0x0),
+    (0x11150, 0xa3),
+    (0x11173, 0x1a0),
+    (0x11176, 0xa3),
+    (0x11177, 0x0),
+    (0x11180, 0xa3),
+    (0x111c0, 0x1a0),
+    (0x111c1, 0xa3),
+    (0x111c5, 0x1a0),
+    (0x111ce, 0xa3),
+    (0x111db, 0x1a0),
+    (0x111dc, 0xa3),
+    (0x111dd, 0x1a0),
+    (0x111e0, 0x0),
+    (0x111e1, 0x1a0),
+    (0x111f5, 0x0),
+    (0x11200, 0xa3),
+    (0x11212, 0x0),
+    (0x11213, 0xa3),
+    (0x11235, 0x1a0),
+    (0x11237, 0xa3),
+    (0x11238, 0x1a0),
+    (0x1123e, 0xa3),
+    (0x1123f, 0x0),
+    (0x11280, 0xa3),
+    (0x11287, 0x0),
+    (0x11288, 0xa3),
+    (0x11289, 0x0),
+    (0x1128a, 0xa3),
+    (0x1128e, 0x0),
+    (0x1128f, 0xa3),
+    (0x1129e, 0x0),
+    (0x1129f, 0xa3),
+    (0x112a9, 0x1a0),
+    (0x112aa, 0x0),
+    (0x112b0, 0xa3),
+    (0x112e9, 0x1a0),
+    (0x112eb, 0x0),
+    (0x112f0, 0xa3),
+    (0x112fa, 0x0),
+    (0x11300, 0xa3),
+    (0x11304, 0x0),
+    (0x11305, 0xa3),
+    (0x1130d, 0x0),
+    (0x1130f, 0xa3),
+    (0x11311, 0x0),
+    (0x11313, 0xa3),
+    (0x11329, 0x0),
+    (0x1132a, 0xa3),
+    (0x11331, 0x0),
+    (0x11332, 0xa3),
+    (0x11334, 0x0),
+    (0x11335, 0xa3),
+    (0x1133a, 0x0),
+    (0x1133b, 0x1a0),
+    (0x1133d, 0xa3),
+    (0x11345, 0x0),
+    (0x11347, 0xa3),
+    (0x11349, 0x0),
+    (0x1134b, 0xa3),
+    (0x1134d, 0x1a0),
+    (0x1134e, 0x0),
+    (0x11350, 0xa3),
+    (0x11351, 0x0),
+    (0x11357, 0xa3),
+    (0x11358, 0x0),
+    (0x1135d, 0xa3),
+    (0x11364, 0x0),
+    (0x11366, 0x1a0),
+    (0x1136d, 0x0),
+    (0x11370, 0x1a0),
+    (0x11375, 0x0),
+    (0x11400, 0xa3),
+    (0x11442, 0x1a0),
+    (0x11443, 0xa3),
+    (0x11446, 0x1a0),
+    (0x11447, 0xa3),
+    (0x1144b, 0x1a0),
+    (0x11450, 0xa3),
+    (0x1145a, 0x1a0),
+    (0x1145c, 0x0),
+    (0x1145d, 0x1a0),
+    (0x1145f, 0xa3),
+    (0x11462, 0x0),
+    (0x11480, 0xa3),
+    (0x114c2, 0x1a0),
+    (0x114c4, 0xa3),
+    (0x114c6, 0x1a0),
+    (0x114c7, 0xa3),
+    (0x114c8, 0x0),
+    (0x114d0, 0xa3),
+    (0x114da, 0x0),
+    (0x11580, 0xa3),
+    (0x115b6, 0x0),
+    (0x115b8, 0xa3),
+    (0x115bf, 0x1a0),
+    (0x115d8, 0xa3),
+    (0x115de, 0x0),
+    (0x11600, 0xa3),
+    (0x1163f, 0x1a0),
+    (0x11640, 0xa3),
+    (0x11641, 0x1a0),
+    (0x11644, 0xa3),
+    (0x11645, 0x0),
+    (0x11650, 0xa3),
+    (0x1165a, 0x0),
+    (0x11660, 0x1a0),
+    (0x1166d, 0x0),
+    (0x11680, 0xa3),
+    (0x116b6, 0x1a0),
+    (0x116b8, 0xa3),
+    (0x116b9, 0x1a0),
+    (0x116ba, 0x0),
+    (0x116c0, 0xa3),
+    (0x116ca, 0x0),
+    (0x11700, 0xa3),
+    (0x1171b, 0x0),
+    (0x1171d, 0xa3),
+    (0x1172b, 0x1a0),
+    (0x1172c, 0x0),
+    (0x11730, 0xa3),
+    (0x1173a, 0x1a0),
+    (0x11740, 0xa3),
+    (0x11747, 0x0),
+    (0x11800, 0xa3),
+    (0x11839, 0x1a0),
+    (0x1183c, 0x0),
+    (0x118a0, 0x4a3),
+    (0x118c0, 0xe3),
+    (0x118e0, 0xa3),
+    (0x118ea, 0x1a0),
+    (0x118f3, 0x0),
+    (0x118ff, 0xa3),
+    (0x11907, 0x0),
+    (0x11909, 0xa3),
+    (0x1190a, 0x0),
+    (0x1190c, 0xa3),
+    (0x11914, 0x0),
+    (0x11915, 0xa3),
+    (0x11917, 0x0),
+    (0x11918, 0xa3),
+    (0x11936, 0x0),
+    (0x11937, 0xa3),
+    (0x11939, 0x0),
+    (0x1193b, 0xa3),
+    (0x1193d, 0x1a0),
+    (0x1193f, 0xa3),
+    (0x11943, 0x1a0),
+    (0x11947, 0x0),
+    (0x11950, 0xa3),
+    (0x1195a, 0x0),
+    (0x119a0, 0xa3),
+    (0x119a8, 0x0),
+    (0x119aa, 0xa3),
+    (0x119d8, 0x0),
+    (0x119da, 0xa3),
+    (0x119e0, 0x1a0),
+    (0x119e1, 0xa3),
+    (0x119e2, 0x1a0),
+    (0x119e3, 0xa3),
+    (0x119e5, 0x0),
+    (0x11a00, 0xa3),
+    (0x11a33, 0x1a0),
+    (0x11a35, 0xa3),
+    (0x11a3f, 0x1a0),
+    (0x11a48, 0x0),
+    (0x11a50, 0xa3),
+    (0x11a98, 0x1a0),
+    (0x11a9d, 0xa3),
+    (0x11a9e, 0x1a0),
+    (0x11aa3, 0x0),
+    (0x11ab0, 0xa3),
+    (0x11af9, 0x0),
+    (0x11c00, 0xa3),
+    (0x11c09, 0x0),
+    (0x11c0a, 0xa3),
+    (0x11c37, 0x0),
+    (0x11c38, 0xa3),
+    (0x11c3f, 0x1a0),
+    (0x11c40, 0xa3),
+    (0x11c41, 0x1a0),
+    (0x11c46, 0x0),
+    (0x11c50, 0xa3),
+    (0x11c5a, 0x1a0),
+    (0x11c6d, 0x0),
+    (0x11c70, 0x1a0),
+    (0x11c72, 0xa3),
+    (0x11c90, 0x0),
+    (0x11c92, 0xa3),
+    (0x11ca8, 0x0),
+    (0x11ca9, 0xa3),
+    (0x11cb7, 0x0),
+    (0x11d00, 0xa3),
+    (0x11d07, 0x0),
+    (0x11d08, 0xa3),
+    (0x11d0a, 0x0),
+    (0x11d0b, 0xa3),
+    (0x11d37, 0x0),
+    (0x11d3a, 0xa3),
+    (0x11d3b, 0x0),
+    (0x11d3c, 0xa3),
+    (0x11d3e, 0x0),
+    (0x11d3f, 0xa3),
+    (0x11d42, 0x1a0),
+    (0x11d43, 0xa3),
+    (0x11d44, 0x1a0),
+    (0x11d46, 0xa3),
+    (0x11d48, 0x0),
+    (0x11d50, 0xa3),
+    (0x11d5a, 0x0),
+    (0x11d60, 0xa3),
+    (0x11d66, 0x0),
+    (0x11d67, 0xa3),
+    (0x11d69, 0x0),
+    (0x11d6a, 0xa3),
+    (0x11d8f, 0x0),
+    (0x11d90, 0xa3),
+    (0x11d92, 0x0),
+    (0x11d93, 0xa3),
+    (0x11d97, 0x1a0),
+    (0x11d98, 0xa3),
+    (0x11d99, 0x0),
+    (0x11da0, 0xa3),
+    (0x11daa, 0x0),
+    (0x11ee0, 0xa3),
+    (0x11ef7, 0x1a0),
+    (0x11ef9, 0x0),
+    (0x11fb0, 0xa3),
+    (0x11fb1, 0x0),
+    (0x11fc0, 0x1a0),
+    (0x11ff2, 0x0),
+    (0x11fff, 0x1a0),
+    (0x12000, 0xa3),
+    (0x1239a, 0x0),
+    (0x12400, 0xa3),
+    (0x1246f, 0x0),
+    (0x12470, 0x1a0),
+    (0x12475, 0x0),
+    (0x12480, 0xa3),
+    (0x12544, 0x0),
+    (0x12f90, 0xa3),
+    (0x12ff1, 0x1a0),
+    (0x12ff3, 0x0),
+    (0x13000, 0xa3),
+    (0x1342f, 0x0),
+    (0x13430, 0x1a0),
+    (0x13439, 0x0),
+    (0x14400, 0xa3),
+    (0x14647, 0x0),
+    (0x16800, 0xa3),
+    (0x16a39, 0x0),
+    (0x16a40, 0xa3),
+    (0x16a5f, 0x0),
+    (0x16a60, 0xa3),
+    (0x16a6a, 0x0),
+    (0x16a6e, 0x1a0),
+    (0x16a70, 0xa3),
+    (0x16abf, 0x0),
+    (0x16ac0, 0xa3),
+    (0x16aca, 0x0),
+    (0x16ad0, 0xa3),
+    (0x16aee, 0x0),
+    (0x16af0, 0x1a0),
+    (0x16af6, 0x0),
+    (0x16b00, 0xa3),
+    (0x16b30, 0x1a0),
+    (0x16b40, 0xa3),
+    (0x16b44, 0x1a0),
+    (0x16b46, 0x0),
+    (0x16b50, 0xa3),
+    (0x16b5a, 0x0),
+    (0x16b5b, 0x1a0),
+    (0x16b62, 0x0),
+    (0x16b63, 0xa3),
+    (0x16b78, 0x0),
+    (0x16b7d, 0xa3),
+    (0x16b90, 0x0),
+    (0x16e40, 0x4a3),
+    (0x16e60, 0xe3),
+    (0x16e80, 0x1a0),
+    (0x16e9b, 0x0),
+    (0x16f00, 0xa3),
+    (0x16f4b, 0x0),
+    (0x16f4f, 0xa3),
+    (0x16f88, 0x0),
+    (0x16f8f, 0xa3),
+    (0x16fa0, 0x0),
+    (0x16fe0, 0xa3),
+    (0x16fe2, 0x1a0),
+    (0x16fe3, 0xa3),
+    (0x16fe4, 0x1a0),
+    (0x16fe5, 0x0),
+    (0x16ff0, 0xa3),
+    (0x16ff2, 0x0),
+    (0x17000, 0xa3),
+    (0x187f8, 0x0),
+    (0x18800, 0xa3),
+    (0x18cd6, 0x0),
+    (0x18d00, 0xa3),
+    (0x18d09, 0x0),
+    (0x1aff0, 0xa3),
+    (0x1aff4, 0x0),
+    (0x1aff5, 0xa3),
+    (0x1affc, 0x0),
+    (0x1affd, 0xa3),
+    (0x1afff, 0x0),
+    (0x1b000, 0xa3),
+    (0x1b123, 0x0),
+    (0x1b150, 0xa3),
+    (0x1b153, 0x0),
+    (0x1b164, 0xa3),
+    (0x1b168, 0x0),
+    (0x1b170, 0xa3),
+    (0x1b2fc, 0x0),
+    (0x1bc00, 0xa3),
+    (0x1bc6b, 0x0),
+    (0x1bc70, 0xa3),
+    (0x1bc7d, 0x0),
+    (0x1bc80, 0xa3),
+    (0x1bc89, 0x0),
+    (0x1bc90, 0xa3),
+    (0x1bc9a, 0x0),
+    (0x1bc9c, 0x1a0),
+    (0x1bc9e, 0xa3),
+    (0x1bc9f, 0x1a0),
+    (0x1bca4, 0x0),
+    (0x1cf00, 0x1a0),
+    (0x1cf2e, 0x0),
+    (0x1cf30, 0x1a0),
+    (0x1cf47, 0x0),
+    (0x1cf50, 0x1a0),
+    (0x1cfc4, 0x0),
+    (0x1d000, 0x1a0),
+    (0x1d0f6, 0x0),
+    (0x1d100, 0x1a0),
+    (0x1d127, 0x0),
+    (0x1d129, 0x1a0),
+    (0x1d1eb, 0x0),
+    (0x1d200, 0x1a0),
+    (0x1d246, 0x0),
+    (0x1d2e0, 0x1a0),
+    (0x1d2f4, 0x0),
+    (0x1d300, 0x1a0),
+    (0x1d357, 0x0),
+    (0x1d360, 0x1a0),
+    (0x1d379, 0x0),
+    (0x1d400, 0x4a3),
+    (0x1d41a, 0xe3),
+    (0x1d434, 0x4a3),
+    (0x1d44e, 0xe3),
+    (0x1d455, 0x0),
+    (0x1d456, 0xe3),
+    (0x1d468, 0x4a3),
+    (0x1d482, 0xe3),
+    (0x1d49c, 0x4a3),
+    (0x1d49d, 0x0),
+    (0x1d49e, 0x4a3),
+    (0x1d4a0, 0x0),
+    (0x1d4a2, 0x4a3),
+    (0x1d4a3, 0x0),
+    (0x1d4a5, 0x4a3),
+    (0x1d4a7, 0x0),
+    (0x1d4a9, 0x4a3),
+    (0x1d4ad, 0x0),
+    (0x1d4ae, 0x4a3),
+    (0x1d4b6, 0xe3),
+    (0x1d4ba, 0x0),
+    (0x1d4bb, 0xe3),
+    (0x1d4bc, 0x0),
+    (0x1d4bd, 0xe3),
+    (0x1d4c4, 0x0),
+    (0x1d4c5, 0xe3),
+    (0x1d4d0, 0x4a3),
+    (0x1d4ea, 0xe3),
+    (0x1d504, 0x4a3),
+    (0x1d506, 0x0),
+    (0x1d507, 0x4a3),
+    (0x1d50b, 0x0),
+    (0x1d50d, 0x4a3),
+    (0x1d515, 0x0),
+    (0x1d516, 0x4a3),
+    (0x1d51d, 0x0),
+    (0x1d51e, 0xe3),
+    (0x1d538, 0x4a3),
+    (0x1d53a, 0x0),
+    (0x1d53b, 0x4a3),
+    (0x1d53f, 0x0),
+    (0x1d540, 0x4a3),
+    (0x1d545, 0x0),
+    (0x1d546, 0x4a3),
+    (0x1d547, 0x0),
+    (0x1d54a, 0x4a3),
+    (0x1d551, 0x0),
+    (0x1d552, 0xe3),
+    (0x1d56c, 0x4a3),
+    (0x1d586, 0xe3),
+    (0x1d5a0, 0x4a3),
+    (0x1d5ba, 0xe3),
+    (0x1d5d4, 0x4a3),
+    (0x1d5ee, 0xe3),
+    (0x1d608, 0x4a3),
+    (0x1d622, 0xe3),
+    (0x1d63c, 0x4a3),
+    (0x1d656, 0xe3),
+    (0x1d670, 0x4a3),
+    (0x1d68a, 0xe3),
+    (0x1d6a6, 0x0),
+    (0x1d6a8, 0x4a3),
+    (0x1d6c1, 0x1a0),
+    (0x1d6c2, 0xe3),
+    (0x1d6db, 0x1a0),
+    (0x1d6dc, 0xe3),
+    (0x1d6e2, 0x4a3),
+    (0x1d6fb, 0x1a0),
+    (0x1d6fc, 0xe3),
+    (0x1d715, 0x1a0),
+    (0x1d716, 0xe3),
+    (0x1d71c, 0x4a3),
+    (0x1d735, 0x1a0),
+    (0x1d736, 0xe3),
+    (0x1d74f, 0x1a0),
+    (0x1d750, 0xe3),
+    (0x1d756, 0x4a3),
+    (0x1d76f, 0x1a0),
+    (0x1d770, 0xe3),
+    (0x1d789, 0x1a0),
+    (0x1d78a, 0xe3),
+    (0x1d790, 0x4a3),
+    (0x1d7a9, 0x1a0),
+    (0x1d7aa, 0xe3),
+    (0x1d7c3, 0x1a0),
+    (0x1d7c4, 0xe3),
+    (0x1d7ca, 0x4a3),
+    (0x1d7cb, 0xe3),
+    (0x1d7cc, 0x0),
+    (0x1d7ce, 0xa3),
+    (0x1d800, 0x1a0),
+    (0x1da8c, 0x0),
+    (0x1da9b, 0x1a0),
+    (0x1daa0, 0x0),
+    (0x1daa1, 0x1a0),
+    (0x1dab0, 0x0),
+    (0x1df00, 0xe3),
+    (0x1df0a, 0xa3),
+    (0x1df0b, 0xe3),
+    (0x1df1f, 0x0),
+    (0x1e000, 0xa3),
+    (0x1e007, 0x0),
+    (0x1e008, 0xa3),
+    (0x1e019, 0x0),
+    (0x1e01b, 0xa3),
+    (0x1e022, 0x0),
+    (0x1e023, 0xa3),
+    (0x1e025, 0x0),
+    (0x1e026, 0xa3),
+    (0x1e02b, 0x0),
+    (0x1e100, 0xa3),
+    (0x1e12d, 0x0),
+    (0x1e130, 0x1a0),
+    (0x1e137, 0xa3),
+    (0x1e13e, 0x0),
+    (0x1e140, 0xa3),
+    (0x1e14a, 0x0),
+    (0x1e14e, 0xa3),
+    (0x1e14f, 0x1a0),
+    (0x1e150, 0x0),
+    (0x1e290, 0xa3),
+    (0x1e2ae, 0x1a0),
+    (0x1e2af, 0x0),
+    (0x1e2c0, 0xa3),
+    (0x1e2ec, 0x1a0),
+    (0x1e2f0, 0xa3),
+    (0x1e2fa, 0x0),
+    (0x1e2ff, 0x1a0),
+    (0x1e300, 0x0),
+    (0x1e7e0, 0xa3),
+    (0x1e7e7, 0x0),
+    (0x1e7e8, 0xa3),
+    (0x1e7ec, 0x0),
+    (0x1e7ed, 0xa3),
+    (0x1e7ef, 0x0),
+    (0x1e7f0, 0xa3),
+    (0x1e7ff, 0x0),
+    (0x1e800, 0xa3),
+    (0x1e8c5, 0x0),
+    (0x1e8c7, 0x1a0),
+    (0x1e8d7, 0x0),
+    (0x1e900, 0x4a3),
+    (0x1e922, 0xe3),
+    (0x1e944, 0x1a0),
+    (0x1e947, 0xa3),
+    (0x1e948, 0x1a0),
+    (0x1e94b, 0xa3),
+    (0x1e94c, 0x0),
+    (0x1e950, 0xa3),
+    (0x1e95a, 0x0),
+    (0x1e95e, 0x1a0),
+    (0x1e960, 0x0),
+    (0x1ec71, 0x1a0),
+    (0x1ecb5, 0x0),
+    (0x1ed01, 0x1a0),
+    (0x1ed3e, 0x0),
+    (0x1ee00, 0xa3),
+    (0x1ee04, 0x0),
+    (0x1ee05, 0xa3),
+    (0x1ee20, 0x0),
+    (0x1ee21, 0xa3),
+    (0x1ee23, 0x0),
+    (0x1ee24, 0xa3),
+    (0x1ee25, 0x0),
+    (0x1ee27, 0xa3),
+    (0x1ee28, 0x0),
+    (0x1ee29, 0xa3),
+    (0x1ee33, 0x0),
+    (0x1ee34, 0xa3),
+    (0x1ee38, 0x0),
+    (0x1ee39, 0xa3),
+    (0x1ee3a, 0x0),
+    (0x1ee3b, 0xa3),
+    (0x1ee3c, 0x0),
+    (0x1ee42, 0xa3),
+    (0x1ee43, 0x0),
+    (0x1ee47, 0xa3),
+    (0x1ee48, 0x0),
+    (0x1ee49, 0xa3),
+    (0x1ee4a, 0x0),
+    (0x1ee4b, 0xa3),
+    (0x1ee4c, 0x0),
+    (0x1ee4d, 0xa3),
+    (0x1ee50, 0x0),
+    (0x1ee51, 0xa3),
+    (0x1ee53, 0x0),
+    (0x1ee54, 0xa3),
+    (0x1ee55, 0x0),
+    (0x1ee57, 0xa3),
+    (0x1ee58, 0x0),
+    (0x1ee59, 0xa3),
+    (0x1ee5a, 0x0),
+    (0x1ee5b, 0xa3),
+    (0x1ee5c, 0x0),
+    (0x1ee5d, 0xa3),
+    (0x1ee5e, 0x0),
+    (0x1ee5f, 0xa3),
+    (0x1ee60, 0x0),
+    (0x1ee61, 0xa3),
+    (0x1ee63, 0x0),
+    (0x1ee64, 0xa3),
+    (0x1ee65, 0x0),
+    (0x1ee67, 0xa3),
+    (0x1ee6b, 0x0),
+    (0x1ee6c, 0xa3),
+    (0x1ee73, 0x0),
+    (0x1ee74, 0xa3),
+    (0x1ee78, 0x0),
+    (0x1ee79, 0xa3),
+    (0x1ee7d, 0x0),
+    (0x1ee7e, 0xa3),
+    (0x1ee7f, 0x0),
+    (0x1ee80, 0xa3),
+    (0x1ee8a, 0x0),
+    (0x1ee8b, 0xa3),
+    (0x1ee9c, 0x0),
+    (0x1eea1, 0xa3),
+    (0x1eea4, 0x0),
+    (0x1eea5, 0xa3),
+    (0x1eeaa, 0x0),
+    (0x1eeab, 0xa3),
+    (0x1eebc, 0x0),
+    (0x1eef0, 0x1a0),
+    (0x1eef2, 0x0),
+    (0x1f000, 0x1a0),
+    (0x1f02c, 0x0),
+    (0x1f030, 0x1a0),
+    (0x1f094, 0x0),
+    (0x1f0a0, 0x1a0),
+    (0x1f0af, 0x0),
+    (0x1f0b1, 0x1a0),
+    (0x1f0c0, 0x0),
+    (0x1f0c1, 0x1a0),
+    (0x1f0d0, 0x0),
+    (0x1f0d1, 0x1a0),
+    (0x1f0f6, 0x0),
+    (0x1f100, 0x1a0),
+    (0x1f130, 0x4a3),
+    (0x1f14a, 0x1a0),
+    (0x1f150, 0x4a3),
+    (0x1f16a, 0x1a0),
+    (0x1f170, 0x4a3),
+    (0x1f18a, 0x1a0),
+    (0x1f1ae, 0x0),
+    (0x1f1e6, 0x1a0),
+    (0x1f203, 0x0),
+    (0x1f210, 0x1a0),
+    (0x1f23c, 0x0),
+    (0x1f240, 0x1a0),
+    (0x1f249, 0x0),
+    (0x1f250, 0x1a0),
+    (0x1f252, 0x0),
+    (0x1f260, 0x1a0),
+    (0x1f266, 0x0),
+    (0x1f300, 0x1a0),
+    (0x1f6d8, 0x0),
+    (0x1f6dd, 0x1a0),
+    (0x1f6ed, 0x0),
+    (0x1f6f0, 0x1a0),
+    (0x1f6fd, 0x0),
+    (0x1f700, 0x1a0),
+    (0x1f774, 0x0),
+    (0x1f780, 0x1a0),
+    (0x1f7d9, 0x0),
+    (0x1f7e0, 0x1a0),
+    (0x1f7ec, 0x0),
+    (0x1f7f0, 0x1a0),
+    (0x1f7f1, 0x0),
+    (0x1f800, 0x1a0),
+    (0x1f80c, 0x0),
+    (0x1f810, 0x1a0),
+    (0x1f848, 0x0),
+    (0x1f850, 0x1a0),
+    (0x1f85a, 0x0),
+    (0x1f860, 0x1a0),
+    (0x1f888, 0x0),
+    (0x1f890, 0x1a0),
+    (0x1f8ae, 0x0),
+    (0x1f8b0, 0x1a0),
+    (0x1f8b2, 0x0),
+    (0x1f900, 0x1a0),
+    (0x1fa54, 0x0),
+    (0x1fa60, 0x1a0),
+    (0x1fa6e, 0x0),
+    (0x1fa70, 0x1a0),
+    (0x1fa75, 0x0),
+    (0x1fa78, 0x1a0),
+    (0x1fa7d, 0x0),
+    (0x1fa80, 0x1a0),
+    (0x1fa87, 0x0),
+    (0x1fa90, 0x1a0),
+    (0x1faad, 0x0),
+    (0x1fab0, 0x1a0),
+    (0x1fabb, 0x0),
+    (0x1fac0, 0x1a0),
+    (0x1fac6, 0x0),
+    (0x1fad0, 0x1a0),
+    (0x1fada, 0x0),
+    (0x1fae0, 0x1a0),
+    (0x1fae8, 0x0),
+    (0x1faf0, 0x1a0),
+    (0x1faf7, 0x0),
+    (0x1fb00, 0x1a0),
+    (0x1fb93, 0x0),
+    (0x1fb94, 0x1a0),
+    (0x1fbcb, 0x0),
+    (0x1fbf0, 0xa3),
+    (0x1fbfa, 0x0),
+    (0x20000, 0xa3),
+    (0x2a6e0, 0x0),
+    (0x2a700, 0xa3),
+    (0x2b739, 0x0),
+    (0x2b740, 0xa3),
+    (0x2b81e, 0x0),
+    (0x2b820, 0xa3),
+    (0x2cea2, 0x0),
+    (0x2ceb0, 0xa3),
+    (0x2ebe1, 0x0),
+    (0x2f800, 0xa3),
+    (0x2fa1e, 0x0),
+    (0x30000, 0xa3),
+    (0x3134b, 0x0),
+    (0xe0001, 0x1a0),
+    (0xe0002, 0x0),
+    (0xe0020, 0x1a0),
+    (0xe0080, 0x0),
+    (0xe0100, 0x1a0),
+    (0xe01f0, 0x0),
+    (0xf0000, 0x1a0),
+    (0xffffe, 0x0),
+    (0x100000, 0x1a0),
+    (0x10fffe, 0x0),
+];
+
+/// The runs of code points with the same distance to their uppercase form: the first and last of
+/// them, the distance between them, and the one to the uppercase form
+pub const TO_UPPER: [(u32, u32, u8, i32); 200] = [
+    (0x61, 0x7a, 1, -32),
+    (0xb5, 0xb5, 1, 743),
+    (0xe0, 0xf6, 1, -32),
+    (0xf8, 0xfe, 1, -32),
+    (0xff, 0xff, 1, 121),
+    (0x101, 0x12f, 2, -1),
+    (0x131, 0x131, 1, -232),
+    (0x133, 0x137, 2, -1),
+    (0x13a, 0x148, 2, -1),
+    (0x14b, 0x177, 2, -1),
+    (0x17a, 0x17e, 2, -1),
+    (0x17f, 0x17f, 1, -300),
+    (0x180, 0x180, 1, 195),
+    (0x183, 0x185, 2, -1),
+    (0x188, 0x188, 1, -1),
+    (0x18c, 0x18c, 1, -1),
+    (0x192, 0x192, 1, -1),
+    (0x195, 0x195, 1, 97),
+    (0x199, 0x199, 1, -1),
+    (0x19a, 0x19a, 1, 163),
+    (0x19e, 0x19e, 1, 130),
+    (0x1a1, 0x1a5, 2, -1),
+    (0x1a8, 0x1a8, 1, -1),
+    (0x1ad, 0x1ad, 1, -1),
+    (0x1b0, 0x1b0, 1, -1),
+    (0x1b4, 0x1b6, 2, -1),
+    (0x1b9, 0x1b9, 1, -1),
+    (0x1bd, 0x1bd, 1, -1),
+    (0x1bf, 0x1bf, 1, 56),
+    (0x1c5, 0x1c5, 1, -1),
+    (0x1c6, 0x1c6, 1, -2),
+    (0x1c8, 0x1c8, 1, -1),
+    (0x1c9, 0x1c9, 1, -2),
+    (0x1cb, 0x1cb, 1, -1),
+    (0x1cc, 0x1cc, 1, -2),
+    (0x1ce, 0x1dc, 2, -1),
+    (0x1dd, 0x1dd, 1, -79),
+    (0x1df, 0x1ef, 2, -1),
+    (0x1f2, 0x1f2, 1, -1),
+    (0x1f3, 0x1f3, 1, -2),
+    (0x1f5, 0x1f5, 1, -1),
+    (0x1f9, 0x21f, 2, -1),
+    (0x223, 0x233, 2, -1),
+    (0x23c, 0x23c, 1, -1),
+    (0x23f, 0x240, 1, 10815),
+    (0x242, 0x242, 1, -1),
+    (0x247, 0x24f, 2, -1),
+    (0x250, 0x250, 1, 10783),
+    (0x251, 0x251, 1, 10780),
+    (0x252, 0x252, 1, 10782),
+    (0x253, 0x253, 1, -210),
+    (0x254, 0x254, 1, -206),
+    (0x256, 0x257, 1, -205),
+    (0x259, 0x259, 1, -202),
+    (0x25b, 0x25b, 1, -203),
+    (0x25c, 0x25c, 1, 42319),
+    (0x260, 0x260, 1, -205),
+    (0x261, 0x261, 1, 42315),
+    (0x263, 0x263, 1, -207),
+    (0x265, 0x265, 1, 42280),
+    (0x266, 0x266, 1, 42308),
+    (0x268, 0x268, 1, -209),
+    (0x269, 0x269, 1, -211),
+    (0x26a, 0x26a, 1, 42308),
+    (0x26b, 0x26b, 1, 10743),
+    (0x26c, 0x26c, 1, 42305),
+    (0x26f, 0x26f, 1, -211),
+    (0x271, 0x271, 1, 10749),
+    (0x272, 0x272, 1, -213),
+    (0x275, 0x275, 1, -214),
+    (0x27d, 0x27d, 1, 10727),
+    (0x280, 0x280, 1, -218),
+    (0x282, 0x282, 1, 42307),
+    (0x283, 0x283, 1, -218),
+    (0x287, 0x287, 1, 42282),
+    (0x288, 0x288, 1, -218),
+    (0x289, 0x289, 1, -69),
+    (0x28a, 0x28b, 1, -217),
+    (0x28c, 0x28c, 1, -71),
+    (0x292, 0x292, 1, -219),
+    (0x29d, 0x29d, 1, 42261),
+    (0x29e, 0x29e, 1, 42258),
+    (0x345, 0x345, 1, 84),
+    (0x371, 0x373, 2, -1),
+    (0x377, 0x377, 1, -1),
+    (0x37b, 0x37d, 1, 130),
+    (0x3ac, 0x3ac, 1, -38),
+    (0x3ad, 0x3af, 1, -37),
+    (0x3b1, 0x3c1, 1, -32),
+    (0x3c2, 0x3c2, 1, -31),
+    (0x3c3, 0x3cb, 1, -32),
+    (0x3cc, 0x3cc, 1, -64),
+    (0x3cd, 0x3ce, 1, -63),
+    (0x3d0, 0x3d0, 1, -62),
+    (0x3d1, 0x3d1, 1, -57),
+    (0x3d5, 0x3d5, 1, -47),
+    (0x3d6, 0x3d6, 1, -54),
+    (0x3d7, 0x3d7, 1, -8),
+    (0x3d9, 0x3ef, 2, -1),
+    (0x3f0, 0x3f0, 1, -86),
+    (0x3f1, 0x3f1, 1, -80),
+    (0x3f2, 0x3f2, 1, 7),
+    (0x3f3, 0x3f3, 1, -116),
+    (0x3f5, 0x3f5, 1, -96),
+    (0x3f8, 0x3f8, 1, -1),
+    (0x3fb, 0x3fb, 1, -1),
+    (0x430, 0x44f, 1, -32),
+    (0x450, 0x45f, 1, -80),
+    (0x461, 0x481, 2, -1),
+    (0x48b, 0x4bf, 2, -1),
+    (0x4c2, 0x4ce, 2, -1),
+    (0x4cf, 0x4cf, 1, -15),
+    (0x4d1, 0x52f, 2, -1),
+    (0x561, 0x586, 1, -48),
+    (0x10d0, 0x10fa, 1, 3008),
+    (0x10fd, 0x10ff, 1, 3008),
+    (0x13f8, 0x13fd, 1, -8),
+    (0x1c80, 0x1c80, 1, -6254),
+    (0x1c81, 0x1c81, 1, -6253),
+    (0x1c82, 0x1c82, 1, -6244),
+    (0x1c83, 0x1c84, 1, -6242),
+    (0x1c85, 0x1c85, 1, -6243),
+    (0x1c86, 0x1c86, 1, -6236),
+    (0x1c87, 0x1c87, 1, -6181),
+    (0x1c88, 0x1c88, 1, 35266),
+    (0x1d79, 0x1d79, 1, 35332),
+    (0x1d7d, 0x1d7d, 1, 3814),
+    (0x1d8e, 0x1d8e, 1, 35384),
+    (0x1e01, 0x1e95, 2, -1),
+    (0x1e9b, 0x1e9b, 1, -59),
+    (0x1ea1, 0x1eff, 2, -1),
+    (0x1f00, 0x1f07, 1, 8),
+    (0x1f10, 0x1f15, 1, 8),
+    (0x1f20, 0x1f27, 1, 8),
+    (0x1f30, 0x1f37, 1, 8),
+    (0x1f40, 0x1f45, 1, 8),
+    (0x1f51, 0x1f57, 2, 8),
+    (0x1f60, 0x1f67, 1, 8),
+    (0x1f70, 0x1f71, 1, 74),
+    (0x1f72, 0x1f75, 1, 86),
+    (0x1f76, 0x1f77, 1, 100),
+    (0x1f78, 0x1f79, 1, 128),
+    (0x1f7a, 0x1f7b, 1, 112),
+    (0x1f7c, 0x1f7d, 1, 126),
+    (0x1f80, 0x1f87, 1, 8),
+    (0x1f90, 0x1f97, 1, 8),
+    (0x1fa0, 0x1fa7, 1, 8),
+    (0x1fb0, 0x1fb1, 1, 8),
+    (0x1fb3, 0x1fb3, 1, 9),
+    (0x1fbe, 0x1fbe, 1, -7205),
+    (0x1fc3, 0x1fc3, 1, 9),
+    (0x1fd0, 0x1fd1, 1, 8),
+    (0x1fe0, 0x1fe1, 1, 8),
+    (0x1fe5, 0x1fe5, 1, 7),
+    (0x1ff3, 0x1ff3, 1, 9),
+    (0x214e, 0x214e, 1, -28),
+    (0x2170, 0x217f, 1, -16),
+    (0x2184, 0x2184, 1, -1),
+    (0x24d0, 0x24e9, 1, -26),
+    (0x2c30, 0x2c5f, 1, -48),
+    (0x2c61, 0x2c61, 1, -1),
+    (0x2c65, 0x2c65, 1, -10795),
+    (0x2c66, 0x2c66, 1, -10792),
+    (0x2c68, 0x2c6c, 2, -1),
+    (0x2c73, 0x2c73, 1, -1),
+    (0x2c76, 0x2c76, 1, -1),
+    (0x2c81, 0x2ce3, 2, -1),
+    (0x2cec, 0x2cee, 2, -1),
+    (0x2cf3, 0x2cf3, 1, -1),
+    (0x2d00, 0x2d25, 1, -7264),
+    (0x2d27, 0x2d27, 1, -7264),
+    (0x2d2d, 0x2d2d, 1, -7264),
+    (0xa641, 0xa66d, 2, -1),
+    (0xa681, 0xa69b, 2, -1),
+    (0xa723, 0xa72f, 2, -1),
+    (0xa733, 0xa76f, 2, -1),
+    (0xa77a, 0xa77c, 2, -1),
+    (0xa77f, 0xa787, 2, -1),
+    (0xa78c, 0xa78c, 1, -1),
+    (0xa791, 0xa793, 2, -1),
+    (0xa794, 0xa794, 1, 48),
+    (0xa797, 0xa7a9, 2, -1),
+    (0xa7b5, 0xa7c3, 2, -1),
+    (0xa7c8, 0xa7ca, 2, -1),
+    (0xa7d1, 0xa7d1, 1, -1),
+    (0xa7d7, 0xa7d9, 2, -1),
+    (0xa7f6, 0xa7f6, 1, -1),
+    (0xab53, 0xab53, 1, -928),
+    (0xab70, 0xabbf, 1, -38864),
+    (0xff41, 0xff5a, 1, -32),
+    (0x10428, 0x1044f, 1, -40),
+    (0x104d8, 0x104fb, 1, -40),
+    (0x10597, 0x105a1, 1, -39),
+    (0x105a3, 0x105b1, 1, -39),
+    (0x105b3, 0x105b9, 1, -39),
+    (0x105bb, 0x105bc, 1, -39),
+    (0x10cc0, 0x10cf2, 1, -64),
+    (0x118c0, 0x118df, 1, -32),
+    (0x16e60, 0x16e7f, 1, -32),
+    (0x1e922, 0x1e943, 1, -34),
+];
+
+/// The same for the lowercase forms
+pub const TO_LOWER: [(u32, u32, u8, i32); 182] = [
+    (0x41, 0x5a, 1, 32),
+    (0xc0, 0xd6, 1, 32),
+    (0xd8, 0xde, 1, 32),
+    (0x100, 0x12e, 2, 1),
+    (0x130, 0x130, 1, -199),
+    (0x132, 0x136, 2, 1),
+    (0x139, 0x147, 2, 1),
+    (0x14a, 0x176, 2, 1),
+    (0x178, 0x178, 1, -121),
+    (0x179, 0x17d, 2, 1),
+    (0x181, 0x181, 1, 210),
+    (0x182, 0x184, 2, 1),
+    (0x186, 0x186, 1, 206),
+    (0x187, 0x187, 1, 1),
+    (0x189, 0x18a, 1, 205),
+    (0x18b, 0x18b, 1, 1),
+    (0x18e, 0x18e, 1, 79),
+    (0x18f, 0x18f, 1, 202),
+    (0x190, 0x190, 1, 203),
+    (0x191, 0x191, 1, 1),
+    (0x193, 0x193, 1, 205),
+    (0x194, 0x194, 1, 207),
+    (0x196, 0x196, 1, 211),
+    (0x197, 0x197, 1, 209),
+    (0x198, 0x198, 1, 1),
+    (0x19c, 0x19c, 1, 211),
+    (0x19d, 0x19d, 1, 213),
+    (0x19f, 0x19f, 1, 214),
+    (0x1a0, 0x1a4, 2, 1),
+    (0x1a6, 0x1a6, 1, 218),
+    (0x1a7, 0x1a7, 1, 1),
+    (0x1a9, 0x1a9, 1, 218),
+    (0x1ac, 0x1ac, 1, 1),
+    (0x1ae, 0x1ae, 1, 218),
+    (0x1af, 0x1af, 1, 1),
+    (0x1b1, 0x1b2, 1, 217),
+    (0x1b3, 0x1b5, 2, 1),
+    (0x1b7, 0x1b7, 1, 219),
+    (0x1b8, 0x1b8, 1, 1),
+    (0x1bc, 0x1bc, 1, 1),
+    (0x1c4, 0x1c4, 1, 2),
+    (0x1c5, 0x1c5, 1, 1),
+    (0x1c7, 0x1c7, 1, 2),
+    (0x1c8, 0x1c8, 1, 1),
+    (0x1ca, 0x1ca, 1, 2),
+    (0x1cb, 0x1db, 2, 1),
+    (0x1de, 0x1ee, 2, 1),
+    (0x1f1, 0x1f1, 1, 2),
+    (0x1f2, 0x1f4, 2, 1),
+    (0x1f6, 0x1f6, 1, -97),
+    (0x1f7, 0x1f7, 1, -56),
+    (0x1f8, 0x21e, 2, 1),
+    (0x220, 0x220, 1, -130),
+    (0x222, 0x232, 2, 1),
+    (0x23a, 0x23a, 1, 10795),
+    (0x23b, 0x23b, 1, 1),
+    (0x23d, 0x23d, 1, -163),
+    (0x23e, 0x23e, 1, 10792),
+    (0x241, 0x241, 1, 1),
+    (0x243, 0x243, 1, -195),
+    (0x244, 0x244, 1, 69),
+    (0x245, 0x245, 1, 71),
+    (0x246, 0x24e, 2, 1),
+    (0x370, 0x372, 2, 1),
+    (0x376, 0x376, 1, 1),
+    (0x37f, 0x37f, 1, 116),
+    (0x386, 0x386, 1, 38),
+    (0x388, 0x38a, 1, 37),
+    (0x38c, 0x38c, 1, 64),
+    (0x38e, 0x38f, 1, 63),
+    (0x391, 0x3a1, 1, 32),
+    (0x3a3, 0x3ab, 1, 32),
+    (0x3cf, 0x3cf, 1, 8),
+    (0x3d8, 0x3ee, 2, 1),
+    (0x3f4, 0x3f4, 1, -60),
+    (0x3f7, 0x3f7, 1, 1),
+    (0x3f9, 0x3f9, 1, -7),
+    (0x3fa, 0x3fa, 1, 1),
+    (0x3fd, 0x3ff, 1, -130),
+    (0x400, 0x40f, 1, 80),
+    (0x410, 0x42f, 1, 32),
+    (0x460, 0x480, 2, 1),
+    (0x48a, 0x4be, 2, 1),
+    (0x4c0, 0x4c0, 1, 15),
+    (0x4c1, 0x4cd, 2, 1),
+    (0x4d0, 0x52e, 2, 1),
+    (0x531, 0x556, 1, 48),
+    (0x10a0, 0x10c5, 1, 7264),
+    (0x10c7, 0x10c7, 1, 7264),
+    (0x10cd, 0x10cd, 1, 7264),
+    (0x13a0, 0x13ef, 1, 38864),
+    (0x13f0, 0x13f5, 1, 8),
+    (0x1c90, 0x1cba, 1, -3008),
+    (0x1cbd, 0x1cbf, 1, -3008),
+    (0x1e00, 0x1e94, 2, 1),
+    (0x1e9e, 0x1e9e, 1, -7615),
+    (0x1ea0, 0x1efe, 2, 1),
+    (0x1f08, 0x1f0f, 1, -8),
+    (0x1f18, 0x1f1d, 1, -8),
+    (0x1f28, 0x1f2f, 1, -8),
+    (0x1f38, 0x1f3f, 1, -8),
+    (0x1f48, 0x1f4d, 1, -8),
+    (0x1f59, 0x1f5f, 2, -8),
+    (0x1f68, 0x1f6f, 1, -8),
+    (0x1f88, 0x1f8f, 1, -8),
+    (0x1f98, 0x1f9f, 1, -8),
+    (0x1fa8, 0x1faf, 1, -8),
+    (0x1fb8, 0x1fb9, 1, -8),
+    (0x1fba, 0x1fbb, 1, -74),
+    (0x1fbc, 0x1fbc, 1, -9),
+    (0x1fc8, 0x1fcb, 1, -86),
+    (0x1fcc, 0x1fcc, 1, -9),
+    (0x1fd8, 0x1fd9, 1, -8),
+    (0x1fda, 0x1fdb, 1, -100),
+    (0x1fe8, 0x1fe9, 1, -8),
+    (0x1fea, 0x1feb, 1, -112),
+    (0x1fec, 0x1fec, 1, -7),
+    (0x1ff8, 0x1ff9, 1, -128),
+    (0x1ffa, 0x1ffb, 1, -126),
+    (0x1ffc, 0x1ffc, 1, -9),
+    (0x2126, 0x2126, 1, -7517),
+    (0x212a, 0x212a, 1, -8383),
+    (0x212b, 0x212b, 1, -8262),
+    (0x2132, 0x2132, 1, 28),
+    (0x2160, 0x216f, 1, 16),
+    (0x2183, 0x2183, 1, 1),
+    (0x24b6, 0x24cf, 1, 26),
+    (0x2c00, 0x2c2f, 1, 48),
+    (0x2c60, 0x2c60, 1, 1),
+    (0x2c62, 0x2c62, 1, -10743),
+    (0x2c63, 0x2c63, 1, -3814),
+    (0x2c64, 0x2c64, 1, -10727),
+    (0x2c67, 0x2c6b, 2, 1),
+    (0x2c6d, 0x2c6d, 1, -10780),
+    (0x2c6e, 0x2c6e, 1, -10749),
+    (0x2c6f, 0x2c6f, 1, -10783),
+    (0x2c70, 0x2c70, 1, -10782),
+    (0x2c72, 0x2c72, 1, 1),
+    (0x2c75, 0x2c75, 1, 1),
+    (0x2c7e, 0x2c7f, 1, -10815),
+    (0x2c80, 0x2ce2, 2, 1),
+    (0x2ceb, 0x2ced, 2, 1),
+    (0x2cf2, 0x2cf2, 1, 1),
+    (0xa640, 0xa66c, 2, 1),
+    (0xa680, 0xa69a, 2, 1),
+    (0xa722, 0xa72e, 2, 1),
+    (0xa732, 0xa76e, 2, 1),
+    (0xa779, 0xa77b, 2, 1),
+    (0xa77d, 0xa77d, 1, -35332),
+    (0xa77e, 0xa786, 2, 1),
+    (0xa78b, 0xa78b, 1, 1),
+    (0xa78d, 0xa78d, 1, -42280),
+    (0xa790, 0xa792, 2, 1),
+    (0xa796, 0xa7a8, 2, 1),
+    (0xa7aa, 0xa7aa, 1, -42308),
+    (0xa7ab, 0xa7ab, 1, -42319),
+    (0xa7ac, 0xa7ac, 1, -42315),
+    (0xa7ad, 0xa7ad, 1, -42305),
+    (0xa7ae, 0xa7ae, 1, -42308),
+    (0xa7b0, 0xa7b0, 1, -42258),
+    (0xa7b1, 0xa7b1, 1, -42282),
+    (0xa7b2, 0xa7b2, 1, -42261),
+    (0xa7b3, 0xa7b3, 1, 928),
+    (0xa7b4, 0xa7c2, 2, 1),
+    (0xa7c4, 0xa7c4, 1, -48),
+    (0xa7c5, 0xa7c5, 1, -42307),
+    (0xa7c6, 0xa7c6, 1, -35384),
+    (0xa7c7, 0xa7c9, 2, 1),
+    (0xa7d0, 0xa7d0, 1, 1),
+    (0xa7d6, 0xa7d8, 2, 1),
+    (0xa7f5, 0xa7f5, 1, 1),
+    (0xff21, 0xff3a, 1, 32),
+    (0x10400, 0x10427, 1, 40),
+    (0x104b0, 0x104d3, 1, 40),
+    (0x10570, 0x1057a, 1, 39),
+    (0x1057c, 0x1058a, 1, 39),
+    (0x1058c, 0x10592, 1, 39),
+    (0x10594, 0x10595, 1, 39),
+    (0x10c80, 0x10cb2, 1, 64),
+    (0x118a0, 0x118bf, 1, 32),
+    (0x16e40, 0x16e5f, 1, 32),
+    (0x1e900, 0x1e921, 1, 34),
+];
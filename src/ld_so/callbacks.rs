@@ -1,4 +1,4 @@
-use super::linker::Linker;
+use super::linker::{AddrInfo, Linker};
 use crate::platform::types::c_void;
 use alloc::boxed::Box;
 use goblin::error::Result;
@@ -7,6 +7,7 @@ pub struct LinkerCallbacks {
     pub unload: Box<dyn Fn(&mut Linker, usize)>,
     pub load_library: Box<dyn Fn(&mut Linker, Option<&str>) -> Result<usize>>,
     pub get_sym: Box<dyn Fn(&Linker, usize, &str) -> Option<*mut c_void>>,
+    pub addr_info: Box<dyn Fn(&Linker, usize) -> Option<AddrInfo>>,
 }
 
 impl LinkerCallbacks {
@@ -15,6 +16,7 @@ impl LinkerCallbacks {
             unload: Box::new(unload),
             load_library: Box::new(load_library),
             get_sym: Box::new(get_sym),
+            addr_info: Box::new(addr_info),
         }
     }
 }
@@ -30,3 +32,7 @@ fn load_library(linker: &mut Linker, name: Option<&str>) -> Result<usize> {
 fn get_sym(linker: &Linker, lib_id: usize, name: &str) -> Option<*mut c_void> {
     linker.get_sym(lib_id, name)
 }
+
+fn addr_info(linker: &Linker, addr: usize) -> Option<AddrInfo> {
+    linker.addr_info(addr)
+}
@@ -2,10 +2,15 @@ use super::{
     debug::{RTLDDebug, _r_debug},
     linker::Symbol,
     tcb::{round_up, Master},
+    version::SymbolVersions,
 };
 use crate::{
+    c_str::CString,
     header::{errno::STR_ERROR, sys_mman},
-    platform::{errno, types::c_void},
+    platform::{
+        errno,
+        types::{c_char, c_void},
+    },
 };
 use alloc::{
     collections::BTreeMap,
@@ -41,6 +46,8 @@ use goblin::{
 #[derive(Default)]
 pub struct DSO {
     pub name: String,
+    /// The path it was loaded from, for dladdr
+    pub path: CString,
     pub id: usize,
     pub dlopened: bool,
     pub entry_point: usize,
@@ -49,6 +56,9 @@ pub struct DSO {
     pub mmap: &'static mut [u8],
     pub global_syms: BTreeMap<String, Symbol>,
     pub weak_syms: BTreeMap<String, Symbol>,
+    /// Every definition with a version, at `name@version`, and whether it's strong. Those that
+    /// aren't hidden are also in `global_syms` or `weak_syms`.
+    pub versioned_syms: BTreeMap<String, (Symbol, bool)>,
     pub dependencies: Vec<String>,
     /// .init_array addr and len
     pub init_array: (usize, usize),
@@ -71,7 +81,7 @@ impl DSO {
     ) -> Result<(DSO, Option<Master>)> {
         let elf = Elf::parse(data)?;
         let (mmap, tcb_master) = DSO::mmap_and_copy(&path, &elf, &data, base_addr, tls_offset)?;
-        let (global_syms, weak_syms) = DSO::collect_syms(&elf, &mmap)?;
+        let (global_syms, weak_syms, versioned_syms) = DSO::collect_syms(&elf, &mmap)?;
         let (init_array, fini_array) = DSO::init_fini_arrays(&elf, mmap.as_ptr() as usize);
 
         let name = match elf.soname {
@@ -89,6 +99,7 @@ impl DSO {
         };
         let dso = DSO {
             name: name,
+            path: CString::new(path).unwrap_or_default(),
             id: id,
             use_count: 1,
             dlopened: dlopened,
@@ -97,6 +108,7 @@ impl DSO {
             mmap: mmap,
             global_syms: global_syms,
             weak_syms: weak_syms,
+            versioned_syms: versioned_syms,
             dependencies: elf.libraries.iter().map(|s| s.to_string()).collect(),
             init_array: init_array,
             fini_array: fini_array,
@@ -116,6 +128,32 @@ impl DSO {
         }
     }
 
+    /// Finds the definition of `name` a reference to `version` links to. That's the one of the
+    /// version if there is one, the default otherwise, like in an object without versions.
+    pub fn get_sym_version(&self, name: &str, version: &str) -> Option<(Symbol, bool)> {
+        match self.versioned_syms.get(&format!("{}@{}", name, version)) {
+            Some(&found) => Some(found),
+            None => self.get_sym(name),
+        }
+    }
+
+    pub fn contains(&self, addr: usize) -> bool {
+        let start = self.mmap.as_ptr() as usize;
+        addr >= start && addr < start + self.mmap.len()
+    }
+
+    /// Finds the exported symbol whose object or function `addr` is in.
+    pub fn symbol_at(&self, addr: usize) -> Option<Symbol> {
+        self.global_syms
+            .values()
+            .chain(self.weak_syms.values())
+            .find(|sym| {
+                let start = sym.as_ptr() as usize;
+                sym.sym_type != sym::STT_TLS && addr >= start && addr < start + sym.size
+            })
+            .copied()
+    }
+
     pub fn run_init(&self) {
         unsafe {
             let (addr, size) = self.init_array;
@@ -307,10 +345,11 @@ impl DSO {
                             ph.p_vaddr as *const u8
                         }
                     };
+                    // Below the TLS of the objects loaded before, aligned as the code expects
                     tcb_master = Some(Master {
                         ptr: ptr,
                         len: ph.p_filesz as usize,
-                        offset: tls_offset + vsize,
+                        offset: round_up(tls_offset + ph.p_memsz as usize, ph.p_align as usize),
                     });
                     trace!("  tcb master {:x?}", tcb_master);
                 }
@@ -351,10 +390,26 @@ impl DSO {
     fn collect_syms(
         elf: &Elf,
         mmap: &[u8],
-    ) -> Result<(BTreeMap<String, Symbol>, BTreeMap<String, Symbol>)> {
+    ) -> Result<(
+        BTreeMap<String, Symbol>,
+        BTreeMap<String, Symbol>,
+        BTreeMap<String, (Symbol, bool)>,
+    )> {
         let mut globals = BTreeMap::new();
         let mut weak_syms = BTreeMap::new();
-        for sym in elf.dynsyms.iter() {
+        let mut versioned_syms = BTreeMap::new();
+        // the string table that's loaded, which the names dladdr gives point into
+        let strtab = elf
+            .dynamic
+            .as_ref()
+            .map_or(0, |dynamic| dynamic.info.strtab);
+        let base = if is_pie_enabled(elf) {
+            mmap.as_ptr() as usize
+        } else {
+            0
+        };
+        let versions = unsafe { SymbolVersions::new(elf, base) };
+        for (index, sym) in elf.dynsyms.iter().enumerate() {
             let bind = sym.st_bind();
             if sym.st_shndx == SHN_UNDEF as usize
                 || ![sym::STB_GLOBAL, sym::STB_WEAK].contains(&bind)
@@ -365,24 +420,30 @@ impl DSO {
             let value: Symbol;
             if let Some(name_res) = elf.dynstrtab.get(sym.st_name) {
                 name = name_res?.to_string();
-                value = if is_pie_enabled(elf) {
-                    Symbol {
-                        base: mmap.as_ptr() as usize,
-                        value: sym.st_value as usize,
-                        size: sym.st_size as usize,
-                        sym_type: sym::st_type(sym.st_info),
-                    }
-                } else {
-                    Symbol {
-                        base: 0,
-                        value: sym.st_value as usize,
-                        size: sym.st_size as usize,
-                        sym_type: sym::st_type(sym.st_info),
-                    }
+                value = Symbol {
+                    base: base,
+                    value: sym.st_value as usize,
+                    size: sym.st_size as usize,
+                    sym_type: sym::st_type(sym.st_info),
+                    name: (base + strtab + sym.st_name) as *const c_char,
                 };
             } else {
                 continue;
             }
+            let version = versions.as_ref().and_then(|versions| versions.get(index));
+            if let Some((version, hidden)) = version {
+                let strong = bind == sym::STB_GLOBAL;
+                trace!(
+                    "  version {}@{}{}",
+                    &name,
+                    version,
+                    if hidden { "" } else { " (default)" }
+                );
+                versioned_syms.insert(format!("{}@{}", name, version), (value, strong));
+                if hidden {
+                    continue;
+                }
+            }
             match sym.st_bind() {
                 sym::STB_GLOBAL => {
                     trace!("  global {}: {:x?} = {:p}", &name, sym, value.as_ptr());
@@ -395,7 +456,7 @@ impl DSO {
                 _ => unreachable!(),
             }
         }
-        return Ok((globals, weak_syms));
+        return Ok((globals, weak_syms, versioned_syms));
     }
 
     fn init_fini_arrays(elf: &Elf, mmap_addr: usize) -> ((usize, usize), (usize, usize)) {
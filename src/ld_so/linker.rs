@@ -19,7 +19,7 @@ use crate::{
         unistd::F_OK,
     },
     io::Read,
-    platform::types::c_void,
+    platform::types::{c_char, c_void},
 };
 
 use super::{
@@ -27,7 +27,8 @@ use super::{
     callbacks::LinkerCallbacks,
     debug::{RTLDState, _dl_debug_state, _r_debug},
     dso::{is_pie_enabled, DSO},
-    tcb::{round_up, Master, Tcb},
+    tcb::{round_up, Master, Tcb, STATIC_TLS_SURPLUS},
+    version::SymbolVersions,
     ExpectTlsFree, PATH_SEP,
 };
 
@@ -37,6 +38,8 @@ pub struct Symbol {
     pub base: usize,
     pub size: usize,
     pub sym_type: u8,
+    /// The name in the loaded string table, for dladdr
+    pub name: *const c_char,
 }
 
 impl Symbol {
@@ -45,6 +48,13 @@ impl Symbol {
     }
 }
 
+/// What dladdr reports about an address
+pub struct AddrInfo {
+    pub path: *const c_char,
+    pub base: usize,
+    pub symbol: Option<Symbol>,
+}
+
 pub struct Linker {
     ld_library_path: Option<String>,
     next_object_id: usize,
@@ -120,6 +130,16 @@ impl Linker {
         }
     }
 
+    /// Finds the object `addr` is in and the symbol it's in, if it's exported.
+    pub fn addr_info(&self, addr: usize) -> Option<AddrInfo> {
+        let obj = self.objects.values().find(|obj| obj.contains(addr))?;
+        Some(AddrInfo {
+            path: obj.path.as_ptr(),
+            base: obj.mmap.as_ptr() as usize,
+            symbol: obj.symbol_at(addr),
+        })
+    }
+
     pub fn unload(&mut self, lib_id: usize) {
         if let Some(obj) = self.objects.get_mut(&lib_id) {
             if obj.dlopened {
@@ -156,6 +176,7 @@ impl Linker {
         let mut new_objects = Vec::new();
         let mut objects_data = Vec::new();
         let mut tcb_masters = Vec::new();
+        let (tls_size, next_tls_module_id) = (self.tls_size, self.next_tls_module_id);
         self.load_objects_recursive(
             path,
             runpath,
@@ -168,8 +189,28 @@ impl Linker {
 
         unsafe {
             let tcb = match Tcb::current() {
-                Some(some) => some,
-                None => Tcb::new(self.tls_size)?,
+                Some(some) if some.tls_len >= self.tls_size => some,
+                // Threads may have been made, with as much TLS as this thread, so dlopen can only
+                // use what's left
+                Some(_) if dlopened => {
+                    self.tls_size = tls_size;
+                    self.next_tls_module_id = next_tls_module_id;
+                    return Err(Error::Malformed(format!(
+                        "no static TLS left to load '{}'",
+                        path
+                    )));
+                }
+                // Nothing that must be kept is in TLS before the program starts
+                //TODO: Fix memory leak of the smaller TCB
+                Some(old) => {
+                    let tcb = Tcb::new(self.tls_size + STATIC_TLS_SURPLUS)?;
+                    tcb.masters_ptr = old.masters_ptr;
+                    tcb.masters_len = old.masters_len;
+                    tcb.linker_ptr = old.linker_ptr;
+                    tcb.mspace = old.mspace;
+                    tcb
+                }
+                None => Tcb::new(self.tls_size + STATIC_TLS_SURPLUS)?,
             };
             tcb.append_masters(tcb_masters);
             tcb.copy_masters()?;
@@ -230,7 +271,8 @@ impl Linker {
             if self.next_tls_module_id == 0 {
                 // Hack to allocate TCB on the first TLS module
                 unsafe {
-                    let tcb = Tcb::new(master.offset).expect_notls("failed to allocate TCB");
+                    let tcb = Tcb::new(master.offset + STATIC_TLS_SURPLUS)
+                        .expect_notls("failed to allocate TCB");
                     tcb.activate();
                 }
             }
@@ -312,6 +354,9 @@ impl Linker {
 
             let mmap = &obj.mmap;
             let b = mmap.as_ptr() as usize;
+            // The versions its references ask for, the libraries' own named in their DT_VERNEED
+            let versions =
+                unsafe { SymbolVersions::new(&elf, if is_pie_enabled(&elf) { b } else { 0 }) };
 
             // Relocate
             for rel in elf
@@ -345,9 +390,17 @@ impl Linker {
                         reloc::R_X86_64_COPY => 1,
                         _ => 0,
                     };
+                    let version = versions
+                        .as_ref()
+                        .and_then(|versions| versions.get(rel.r_sym))
+                        .map(|(version, _)| version);
                     for lookup_id in lookup_start..symbols_lookup_objects.len() {
                         let lookup_obj = &symbols_lookup_objects[lookup_id];
-                        if let Some((s, strong)) = lookup_obj.get_sym(name) {
+                        let found_sym = match version {
+                            Some(version) => lookup_obj.get_sym_version(name, version),
+                            None => lookup_obj.get_sym(name),
+                        };
+                        if let Some((s, strong)) = found_sym {
                             trace!(
                                 "symbol {} from {} found in {} ({})",
                                 name,
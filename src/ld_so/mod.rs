@@ -1,12 +1,8 @@
 use core::{mem, ptr};
 use goblin::elf::program_header::{self, program_header32, program_header64, ProgramHeader};
 
-use self::tcb::{Master, Tcb};
-use crate::{
-    header::sys_auxv::AT_NULL,
-    platform::{Pal, Sys},
-    start::Stack,
-};
+use self::tcb::{round_up, Master, Tcb, STATIC_TLS_SURPLUS};
+use crate::{header::sys_auxv::AT_NULL, start::Stack};
 
 #[cfg(target_os = "redox")]
 pub const PATH_SEP: char = ';';
@@ -21,6 +17,7 @@ mod dso;
 pub mod linker;
 pub mod start;
 pub mod tcb;
+mod version;
 
 static mut STATIC_TCB_MASTER: Master = Master {
     ptr: ptr::null_mut(),
@@ -104,25 +101,27 @@ pub fn static_init(sp: &'static Stack) {
             _ => panic_notls(format_args!("unknown AT_PHENT size {}", phent)),
         };
 
-        let page_size = Sys::getpagesize();
-        let voff = ph.p_vaddr as usize % page_size;
-        let vaddr = ph.p_vaddr as usize - voff;
-        let vsize = ((ph.p_memsz as usize + voff + page_size - 1) / page_size) * page_size;
-
         match ph.p_type {
             program_header::PT_TLS => {
-                let valign = if ph.p_align > 0 {
-                    ((ph.p_memsz + (ph.p_align - 1)) / ph.p_align) * ph.p_align
-                } else {
-                    ph.p_memsz
-                } as usize;
+                let align = (ph.p_align as usize).max(1);
+                let memsz = ph.p_memsz as usize;
+                // x86 puts TLS right below the thread pointer, and aarch64 after the 16 bytes
+                // it points to, both at the alignment the code was linked for
+                #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+                let (offset, size) = {
+                    let offset = round_up(memsz, align);
+                    (offset, offset)
+                };
+                #[cfg(target_arch = "aarch64")]
+                let (offset, size) = (round_up(16, align), memsz);
 
                 unsafe {
                     STATIC_TCB_MASTER.ptr = ph.p_vaddr as usize as *const u8;
                     STATIC_TCB_MASTER.len = ph.p_filesz as usize;
-                    STATIC_TCB_MASTER.offset = valign;
+                    STATIC_TCB_MASTER.offset = offset;
 
-                    let tcb = Tcb::new(vsize).expect_notls("failed to allocate TCB");
+                    let tcb =
+                        Tcb::new(size + STATIC_TLS_SURPLUS).expect_notls("failed to allocate TCB");
                     tcb.masters_ptr = &mut STATIC_TCB_MASTER;
                     tcb.masters_len = mem::size_of::<Master>();
                     tcb.copy_masters()
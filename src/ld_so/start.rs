@@ -11,14 +11,14 @@ use alloc::{
 use crate::{
     c_str::CStr,
     header::unistd,
-    platform::{get_auxv, get_auxvs, new_mspace, types::c_char},
+    platform::{self, auxv, new_mspace, types::c_char},
     start::Stack,
     sync::mutex::Mutex,
     ALLOCATOR,
 };
 
 use super::{access::accessible, debug::_r_debug, linker::Linker, tcb::Tcb, PATH_SEP};
-use crate::header::sys_auxv::{AT_ENTRY, AT_PHDR};
+use crate::header::sys_auxv::{AT_ENTRY, AT_PHDR, AT_SECURE};
 use goblin::elf::header::header64::SIZEOF_EHDR;
 
 unsafe fn get_argv(mut ptr: *const usize) -> (Vec<String>, *const usize) {
@@ -146,12 +146,13 @@ pub extern "C" fn relibc_ld_so_start(sp: &'static mut Stack, ld_entry: usize) ->
     // First thing we initialize the mspace
     ALLOCATOR.set_book_keeper(new_mspace());
     // next we get the arguments, the environment, and the auxilary vector
-    let (argv, envs, auxv) = unsafe {
+    let (argv, envs) = unsafe {
         let argv_start = sp.argv() as *mut usize;
         let (argv, argv_end) = get_argv(argv_start);
         let (envs, envs_end) = get_env(argv_end.add(1));
-        let auxv = get_auxvs(envs_end.add(1));
-        (argv, envs, auxv)
+        platform::init_auxv(envs_end.add(1));
+        platform::init_stack_guard();
+        (argv, envs)
     };
 
     unsafe {
@@ -174,7 +175,7 @@ pub extern "C" fn relibc_ld_so_start(sp: &'static mut Stack, ld_entry: usize) ->
         crate::platform::environ = crate::platform::OUR_ENVIRON.as_mut_ptr();
     }
 
-    let is_manual = if let Some(img_entry) = get_auxv(&auxv, AT_ENTRY) {
+    let is_manual = if let Some(img_entry) = auxv(AT_ENTRY) {
         img_entry == ld_entry
     } else {
         true
@@ -185,17 +186,21 @@ pub extern "C" fn relibc_ld_so_start(sp: &'static mut Stack, ld_entry: usize) ->
         _r_debug.r_ldbase = ld_entry;
     }
 
-    // TODO: Fix memory leak, although minimal.
-    crate::platform::init(auxv.clone());
+    crate::platform::init();
 
-    // Some variables that will be overridden by environment and auxiliary vectors
-    let ld_library_path = envs.get("LD_LIBRARY_PATH").map(|s| s.to_owned());
+    // Some variables that will be overridden by environment and auxiliary vectors. A setuid or
+    // setgid program isn't given libraries from where whoever ran it says.
+    let ld_library_path = if auxv(AT_SECURE).unwrap_or(0) == 0 {
+        envs.get("LD_LIBRARY_PATH").map(|s| s.to_owned())
+    } else {
+        None
+    };
 
     let name_or_path = if is_manual {
         // ld.so is run directly by user and not via execve() or similar systemcall
         println!("argv: {:#?}", argv);
         println!("envs: {:#?}", envs);
-        println!("auxv: {:#x?}", auxv);
+        println!("auxv: {:#x?}", platform::auxv_entries().collect::<Vec<_>>());
 
         if sp.argc < 2 {
             eprintln!("ld.so [executable] [arguments...]");
@@ -223,7 +228,7 @@ pub extern "C" fn relibc_ld_so_start(sp: &'static mut Stack, ld_entry: usize) ->
     let base_addr = {
         let mut base = None;
         if !is_manual && cfg!(not(target_os = "redox")) {
-            let phdr = get_auxv(&auxv, AT_PHDR).unwrap();
+            let phdr = auxv(AT_PHDR).unwrap();
             if phdr != 0 {
                 base = Some(phdr - SIZEOF_EHDR);
             }
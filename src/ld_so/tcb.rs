@@ -6,10 +6,14 @@ use super::ExpectTlsFree;
 use crate::{
     header::sys_mman,
     ld_so::linker::Linker,
-    platform::{Pal, Sys},
+    platform::{self, Pal, Sys},
     sync::mutex::Mutex,
 };
 
+/// Room left in static TLS for libraries dlopen loads later, whose code may expect their TLS at
+/// a fixed offset from the thread pointer
+pub const STATIC_TLS_SURPLUS: usize = 1664;
+
 #[repr(C)]
 #[derive(Debug)]
 pub struct Master {
@@ -17,7 +21,7 @@ pub struct Master {
     pub ptr: *const u8,
     /// Length of initial data in bytes
     pub len: usize,
-    /// Offset in TLS to copy initial data to
+    /// Offset of the TLS from the thread pointer, below it on x86 and above it on aarch64
     pub offset: usize,
 }
 
@@ -41,6 +45,9 @@ pub struct Tcb {
     pub tcb_len: usize,
     /// Pointer to a list of initial TLS data
     pub masters_ptr: *mut Master,
+    /// The canary the stack protector checks, which x86 compilers read from here, %fs:0x28 or
+    /// %gs:0x14. Must be the sixth member
+    pub stack_guard: usize,
     /// Size of the masters list in bytes (multiple of mem::size_of::<Master>())
     pub masters_len: usize,
     /// Index of last copied Master
@@ -67,6 +74,7 @@ impl Tcb {
                 tcb_ptr,
                 tcb_len: tcb_page.len(),
                 masters_ptr: ptr::null_mut(),
+                stack_guard: platform::__stack_chk_guard,
                 masters_len: 0,
                 num_copied_masters: 0,
                 linker_ptr: ptr::null(),
@@ -160,8 +168,19 @@ impl Tcb {
             self.masters_len = new_masters.len() * mem::size_of::<Master>();
             mem::forget(new_masters);
         } else {
+            // Threads made before still use the list they were given, which is kept
+            //TODO: Copy the initial data of the new masters to the TLS of those threads
             let len = self.masters_len / mem::size_of::<Master>();
-            let mut masters = Vec::from_raw_parts(self.masters_ptr, len, len);
+            let mut masters = Vec::with_capacity(len + new_masters.len());
+            masters.extend(
+                slice::from_raw_parts(self.masters_ptr, len)
+                    .iter()
+                    .map(|master| Master {
+                        ptr: master.ptr,
+                        len: master.len,
+                        offset: master.offset,
+                    }),
+            );
             masters.extend(new_masters.into_iter());
             self.masters_ptr = masters.as_mut_ptr();
             self.masters_len = masters.len() * mem::size_of::<Master>();
@@ -171,7 +190,15 @@ impl Tcb {
 
     /// Activate TLS
     pub unsafe fn activate(&mut self) {
-        Self::os_arch_activate(self.tls_end as usize, self.tls_len);
+        Self::os_arch_activate(self.tls_end as usize, self.tls_len, self.tp_offset());
+    }
+
+    /// How far above the thread pointer TLS starts on aarch64, 16 or the alignment of the TLS of
+    /// the program if that's larger
+    unsafe fn tp_offset(&self) -> usize {
+        self.masters()
+            .and_then(|masters| masters.first())
+            .map_or(16, |master| master.offset)
     }
 
     /// Mapping with correct flags for TCB and TLS
@@ -251,16 +278,16 @@ impl Tcb {
 
     /// OS and architecture specific code to activate TLS - Linux x86_64
     #[cfg(all(target_os = "linux", target_arch = "x86_64"))]
-    unsafe fn os_arch_activate(tls_end: usize, _tls_len: usize) {
+    unsafe fn os_arch_activate(tls_end: usize, _tls_len: usize, _tp_offset: usize) {
         const ARCH_SET_FS: usize = 0x1002;
         syscall!(ARCH_PRCTL, ARCH_SET_FS, tls_end);
     }
 
     /// OS and architecture specific code to activate TLS - Redox aarch64
     #[cfg(all(target_os = "redox", target_arch = "aarch64"))]
-    unsafe fn os_arch_activate(tls_end: usize, tls_len: usize) {
-        // Uses ABI page
-        let abi_ptr = tls_end - tls_len - 16;
+    unsafe fn os_arch_activate(tls_end: usize, tls_len: usize, tp_offset: usize) {
+        // Uses ABI page, with the thread pointer aligned as TLS is
+        let abi_ptr = tls_end - tls_len - tp_offset;
         ptr::write(abi_ptr as *mut usize, tls_end);
         asm!(
             "msr tpidr_el0, {}",
@@ -270,7 +297,7 @@ impl Tcb {
 
     /// OS and architecture specific code to activate TLS - Redox x86
     #[cfg(all(target_os = "redox", target_arch = "x86"))]
-    unsafe fn os_arch_activate(tls_end: usize, _tls_len: usize) {
+    unsafe fn os_arch_activate(tls_end: usize, _tls_len: usize, _tp_offset: usize) {
         let mut env = syscall::EnvRegisters::default();
 
         let file = syscall::open(
@@ -290,7 +317,7 @@ impl Tcb {
 
     /// OS and architecture specific code to activate TLS - Redox x86_64
     #[cfg(all(target_os = "redox", target_arch = "x86_64"))]
-    unsafe fn os_arch_activate(tls_end: usize, _tls_len: usize) {
+    unsafe fn os_arch_activate(tls_end: usize, _tls_len: usize, _tp_offset: usize) {
         let mut env = syscall::EnvRegisters::default();
 
         let file = syscall::open(
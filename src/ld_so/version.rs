@@ -0,0 +1,137 @@
+//! Symbol versions, read from the tables DT_VERSYM, DT_VERDEF and DT_VERNEED point to, which are
+//! laid out the same in 32 and 64-bit objects.
+
+use alloc::collections::BTreeMap;
+use core::{ptr, slice};
+use goblin::elf::Elf;
+
+const DT_VERSYM: u64 = 0x6fff_fff0;
+const DT_VERDEF: u64 = 0x6fff_fffc;
+const DT_VERDEFNUM: u64 = 0x6fff_fffd;
+const DT_VERNEED: u64 = 0x6fff_fffe;
+const DT_VERNEEDNUM: u64 = 0x6fff_ffff;
+
+/// Set in a DT_VERSYM entry for a definition only a reference to its version links to
+const VERSYM_HIDDEN: u16 = 0x8000;
+/// The indexes below this are for symbols without a version, local or global
+const VER_NDX_FIRST: u16 = 2;
+/// Marks the definition that only names the object
+const VER_FLG_BASE: u16 = 0x1;
+
+// Only some of the fields are used
+#[allow(dead_code)]
+#[repr(C)]
+struct Verdef {
+    vd_version: u16,
+    vd_flags: u16,
+    vd_ndx: u16,
+    vd_cnt: u16,
+    vd_hash: u32,
+    vd_aux: u32,
+    vd_next: u32,
+}
+
+#[allow(dead_code)]
+#[repr(C)]
+struct Verdaux {
+    vda_name: u32,
+    vda_next: u32,
+}
+
+#[allow(dead_code)]
+#[repr(C)]
+struct Verneed {
+    vn_version: u16,
+    vn_cnt: u16,
+    vn_file: u32,
+    vn_aux: u32,
+    vn_next: u32,
+}
+
+#[allow(dead_code)]
+#[repr(C)]
+struct Vernaux {
+    vna_hash: u32,
+    vna_flags: u16,
+    vna_other: u16,
+    vna_name: u32,
+    vna_next: u32,
+}
+
+/// The versions of an object's dynamic symbols, those it defines and those it needs from others.
+pub struct SymbolVersions<'a> {
+    versym: &'a [u16],
+    names: BTreeMap<u16, &'a str>,
+}
+
+impl<'a> SymbolVersions<'a> {
+    /// Reads the tables of `elf`, loaded at `base`, or None if its symbols have no versions.
+    pub unsafe fn new(elf: &'a Elf, base: usize) -> Option<Self> {
+        let dynamic = elf.dynamic.as_ref()?;
+        let tag = |tag: u64| {
+            dynamic
+                .dyns
+                .iter()
+                .find(|d| d.d_tag as u64 == tag)
+                .map(|d| d.d_val as usize)
+        };
+        let versym = base + tag(DT_VERSYM)?;
+        let versym = slice::from_raw_parts(versym as *const u16, elf.dynsyms.len());
+
+        let mut names = BTreeMap::new();
+        let name = |offset: u32| elf.dynstrtab.get(offset as usize).and_then(|s| s.ok());
+
+        if let (Some(verdef), Some(count)) = (tag(DT_VERDEF), tag(DT_VERDEFNUM)) {
+            let mut def = base + verdef;
+            for _ in 0..count {
+                let entry = ptr::read_unaligned(def as *const Verdef);
+                if entry.vd_flags & VER_FLG_BASE == 0 && entry.vd_cnt > 0 {
+                    let aux = ptr::read_unaligned((def + entry.vd_aux as usize) as *const Verdaux);
+                    if let Some(name) = name(aux.vda_name) {
+                        names.insert(entry.vd_ndx, name);
+                    }
+                }
+                if entry.vd_next == 0 {
+                    break;
+                }
+                def += entry.vd_next as usize;
+            }
+        }
+
+        if let (Some(verneed), Some(count)) = (tag(DT_VERNEED), tag(DT_VERNEEDNUM)) {
+            let mut need = base + verneed;
+            for _ in 0..count {
+                let entry = ptr::read_unaligned(need as *const Verneed);
+                let mut aux = need + entry.vn_aux as usize;
+                for _ in 0..entry.vn_cnt {
+                    let entry = ptr::read_unaligned(aux as *const Vernaux);
+                    if let Some(name) = name(entry.vna_name) {
+                        names.insert(entry.vna_other, name);
+                    }
+                    if entry.vna_next == 0 {
+                        break;
+                    }
+                    aux += entry.vna_next as usize;
+                }
+                if entry.vn_next == 0 {
+                    break;
+                }
+                need += entry.vn_next as usize;
+            }
+        }
+
+        Some(Self { versym, names })
+    }
+
+    /// The version of the symbol at `index`, with whether a definition of it is hidden, only
+    /// linked to by references to the version. None for a symbol without a version.
+    pub fn get(&self, index: usize) -> Option<(&'a str, bool)> {
+        let versym = *self.versym.get(index)?;
+        let hidden = versym & VERSYM_HIDDEN != 0;
+        let ndx = versym & !VERSYM_HIDDEN;
+        if ndx < VER_NDX_FIRST {
+            return None;
+        }
+        self.names.get(&ndx).map(|&name| (name, hidden))
+    }
+}
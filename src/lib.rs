@@ -49,10 +49,13 @@ pub mod c_str;
 pub mod c_vec;
 pub mod cxa;
 pub mod db;
+pub mod error;
+pub mod float;
 pub mod fs;
 pub mod header;
 pub mod io;
 pub mod ld_so;
+pub mod nss;
 pub mod platform;
 pub mod start;
 pub mod sync;
@@ -265,8 +265,13 @@ macro_rules! strto_float_impl {
             s = s.offset(1);
         }
 
-        if *s as u8 == b'.' {
-            s = s.offset(1);
+        let decimal_point = $crate::header::locale::decimal_point().as_bytes();
+        if decimal_point
+            .iter()
+            .enumerate()
+            .all(|(i, &b)| *s.add(i) as u8 == b)
+        {
+            s = s.add(decimal_point.len());
 
             let mut i = 1.0;
             while let Some(digit) = (*s as u8 as char).to_digit(radix) {
@@ -0,0 +1,164 @@
+//! Parsing of /etc/nsswitch.conf, which is read once, the first time it's needed.
+
+use alloc::{vec, vec::Vec};
+
+use super::{Database, Status};
+use crate::{fs::File, header::fcntl::O_RDONLY, io::Read, sync::Once};
+
+const SUCCESS: usize = 0;
+const NOTFOUND: usize = 1;
+const UNAVAIL: usize = 2;
+const TRYAGAIN: usize = 3;
+
+/// A source of a database, with whether to return after each status
+pub struct Entry {
+    pub name: Vec<u8>,
+    returns: [bool; 4],
+}
+
+impl Entry {
+    fn new(name: &[u8]) -> Self {
+        Entry {
+            name: name.to_vec(),
+            returns: [true, false, false, false],
+        }
+    }
+
+    pub fn returns<T>(&self, res: &Result<T, Status>) -> bool {
+        self.returns[match res {
+            Ok(_) => SUCCESS,
+            Err(Status::NotFound) => NOTFOUND,
+            Err(Status::Unavailable(_)) => UNAVAIL,
+            Err(Status::TryAgain(_)) => TRYAGAIN,
+        }]
+    }
+
+    /// Applies a `STATUS=ACTION` pair, ignoring ones that don't make sense.
+    fn set_action(&mut self, pair: &[u8]) {
+        let (negated, pair) = match pair.split_first() {
+            Some((b'!', rest)) => (true, rest),
+            _ => (false, pair),
+        };
+        let eq = match pair.iter().position(|&b| b == b'=') {
+            Some(eq) => eq,
+            None => return,
+        };
+        let status = match &pair[..eq].to_ascii_uppercase()[..] {
+            b"SUCCESS" => SUCCESS,
+            b"NOTFOUND" => NOTFOUND,
+            b"UNAVAIL" => UNAVAIL,
+            b"TRYAGAIN" => TRYAGAIN,
+            _ => return,
+        };
+        let returns = match &pair[eq + 1..].to_ascii_lowercase()[..] {
+            b"return" => true,
+            // merging isn't supported, so it's the same as going on to the next source
+            b"continue" | b"merge" => false,
+            _ => return,
+        };
+        for (i, action) in self.returns.iter_mut().enumerate() {
+            if (i == status) != negated {
+                *action = returns;
+            }
+        }
+    }
+}
+
+fn trim(bytes: &[u8]) -> &[u8] {
+    let start = bytes
+        .iter()
+        .position(|b| !b.is_ascii_whitespace())
+        .unwrap_or(bytes.len());
+    let end = bytes
+        .iter()
+        .rposition(|b| !b.is_ascii_whitespace())
+        .map_or(start, |end| end + 1);
+    &bytes[start..end]
+}
+
+/// Parses the sources after the colon of a line.
+fn parse_sources(line: &[u8]) -> Vec<Entry> {
+    let mut entries: Vec<Entry> = Vec::new();
+    let mut rest = line;
+    loop {
+        rest = trim(rest);
+        match rest.first() {
+            None => break,
+            Some(b'[') => {
+                let end = rest.iter().position(|&b| b == b']').unwrap_or(rest.len());
+                if let Some(entry) = entries.last_mut() {
+                    for pair in rest[1..end].split(u8::is_ascii_whitespace) {
+                        entry.set_action(pair);
+                    }
+                }
+                rest = &rest[(end + 1).min(rest.len())..];
+            }
+            Some(_) => {
+                let end = rest
+                    .iter()
+                    .position(|&b| b.is_ascii_whitespace() || b == b'[')
+                    .unwrap_or(rest.len());
+                entries.push(Entry::new(&rest[..end]));
+                rest = &rest[end..];
+            }
+        }
+    }
+    entries
+}
+
+struct Config {
+    passwd: Vec<Entry>,
+    group: Vec<Entry>,
+    hosts: Vec<Entry>,
+}
+
+impl Config {
+    fn read() -> Self {
+        let mut config = Config {
+            passwd: Vec::new(),
+            group: Vec::new(),
+            hosts: Vec::new(),
+        };
+
+        let mut data = Vec::new();
+        if let Ok(mut file) = File::open(c_str!("/etc/nsswitch.conf"), O_RDONLY) {
+            let _ = file.read_to_end(&mut data);
+        }
+        for line in data.split(|&b| b == b'\n') {
+            let line = line.split(|&b| b == b'#').next().unwrap_or(&[]);
+            let colon = match line.iter().position(|&b| b == b':') {
+                Some(colon) => colon,
+                None => continue,
+            };
+            let sources = match trim(&line[..colon]) {
+                b"passwd" => &mut config.passwd,
+                b"group" => &mut config.group,
+                b"hosts" => &mut config.hosts,
+                _ => continue,
+            };
+            *sources = parse_sources(&line[colon + 1..]);
+        }
+
+        if config.passwd.is_empty() {
+            config.passwd = vec![Entry::new(b"files")];
+        }
+        if config.group.is_empty() {
+            config.group = vec![Entry::new(b"files")];
+        }
+        if config.hosts.is_empty() {
+            config.hosts = vec![Entry::new(b"files"), Entry::new(b"dns")];
+        }
+        config
+    }
+}
+
+static CONFIG: Once<Config> = Once::new();
+
+pub fn sources(database: Database) -> &'static [Entry] {
+    let config = CONFIG.call_once(Config::read);
+    match database {
+        Database::Passwd => &config.passwd,
+        Database::Group => &config.group,
+        Database::Hosts => &config.hosts,
+    }
+}
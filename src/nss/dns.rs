@@ -0,0 +1,60 @@
+//! The dns source, which asks the configured nameserver for hosts.
+
+use alloc::{vec, vec::Vec};
+use core::str;
+
+use super::{Host, HostKey, Source, Status};
+use crate::{
+    header::{
+        errno::{EINVAL, EIO},
+        netdb::{lookup_addr, lookup_host},
+    },
+    platform::types::*,
+};
+
+pub struct Dns;
+
+fn status(errno: c_int) -> Status {
+    match errno {
+        // the nameserver couldn't be reached or didn't answer
+        EIO => Status::TryAgain(errno),
+        _ => Status::Unavailable(errno),
+    }
+}
+
+impl Source for Dns {
+    fn hosts(&self, key: HostKey) -> Result<Host, Status> {
+        match key {
+            HostKey::Name(name) => {
+                let name_str = str::from_utf8(name).map_err(|_| Status::Unavailable(EINVAL))?;
+                let addrs: Vec<_> = lookup_host(name_str).map_err(status)?.collect();
+                if addrs.is_empty() {
+                    return Err(Status::NotFound);
+                }
+                Ok(Host {
+                    name: name.to_vec(),
+                    aliases: Vec::new(),
+                    addrs,
+                })
+            }
+            HostKey::Addr(addr) => {
+                let mut names = lookup_addr(addr)
+                    .map_err(status)?
+                    .into_iter()
+                    .map(|mut name| {
+                        // names may come with the terminator of their last label
+                        while name.last() == Some(&0) {
+                            name.pop();
+                        }
+                        name
+                    });
+                let name = names.next().ok_or(Status::NotFound)?;
+                Ok(Host {
+                    name,
+                    aliases: names.collect(),
+                    addrs: vec![addr],
+                })
+            }
+        }
+    }
+}
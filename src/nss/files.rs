@@ -0,0 +1,148 @@
+//! The files source, which reads /etc/passwd, /etc/group and /etc/hosts.
+
+use alloc::vec::Vec;
+use core::str;
+
+use super::{Group, GroupKey, Host, HostKey, Passwd, PasswdKey, Source, Status};
+use crate::{
+    c_str::CStr,
+    fs::File,
+    header::{arpa_inet::inet_aton, fcntl::O_RDONLY, netinet_in::in_addr},
+    io::{BufRead, BufReader},
+};
+
+pub struct Files;
+
+fn parsed<O: str::FromStr>(field: Option<&[u8]>) -> Option<O> {
+    str::from_utf8(field?).ok()?.parse().ok()
+}
+
+/// Strips the newline, if there is one, from a line read with `read_until`.
+fn strip_newline(line: &[u8]) -> &[u8] {
+    line.strip_suffix(b"\n").unwrap_or(line)
+}
+
+/// Parses a `name:passwd:uid:gid:gecos:dir:shell` line. Redox keeps passwords elsewhere, so its
+/// lines don't have that field and the password is "x".
+pub fn parse_passwd(line: &[u8]) -> Option<Passwd> {
+    let mut fields = strip_newline(line).split(|&b| b == b':');
+    let name = fields.next()?.to_vec();
+    #[cfg(target_os = "redox")]
+    let passwd = b"x".to_vec();
+    #[cfg(not(target_os = "redox"))]
+    let passwd = fields.next()?.to_vec();
+    Some(Passwd {
+        name,
+        passwd,
+        uid: parsed(fields.next())?,
+        gid: parsed(fields.next())?,
+        gecos: fields.next()?.to_vec(),
+        dir: fields.next()?.to_vec(),
+        shell: fields.next()?.to_vec(),
+    })
+}
+
+/// Parses a `name:passwd:gid:member,member...` line.
+pub fn parse_group(line: &[u8]) -> Option<Group> {
+    let mut fields = strip_newline(line).split(|&b| b == b':');
+    let name = fields.next()?.to_vec();
+    let passwd = fields.next()?.to_vec();
+    let gid = parsed(fields.next())?;
+    let members = fields
+        .next()?
+        .split(|&b| b == b',')
+        .filter(|member| !member.is_empty())
+        .map(<[u8]>::to_vec)
+        .collect();
+    Some(Group {
+        name,
+        passwd,
+        gid,
+        members,
+    })
+}
+
+/// Parses an `address name [aliases...]` line, skipping the IPv6 addresses that `Host` can't hold.
+pub fn parse_host(line: &[u8]) -> Option<Host> {
+    let line = line.split(|&b| b == b'#').next().unwrap_or(&[]);
+    let mut fields = line
+        .split(u8::is_ascii_whitespace)
+        .filter(|field| !field.is_empty());
+
+    let mut addr_str = fields.next()?.to_vec();
+    addr_str.push(0);
+    let mut addr = in_addr { s_addr: 0 };
+    if unsafe { inet_aton(addr_str.as_ptr() as *const _, &mut addr) } == 0 {
+        return None;
+    }
+    Some(Host {
+        name: fields.next()?.to_vec(),
+        aliases: fields.map(<[u8]>::to_vec).collect(),
+        addrs: alloc::vec![addr],
+    })
+}
+
+/// Reads lines from `reader` until one parses, for going through the entries of a file in turn.
+/// Both the end of the file and errors give None.
+pub fn next<R, T, P>(reader: &mut R, parse: P) -> Option<T>
+where
+    R: BufRead,
+    P: Fn(&[u8]) -> Option<T>,
+{
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        match reader.read_until(b'\n', &mut line) {
+            Ok(0) | Err(_) => return None,
+            Ok(_) => (),
+        }
+        if let Some(entry) = parse(&line) {
+            return Some(entry);
+        }
+    }
+}
+
+/// Finds the first entry of the file at `path` that `matches`.
+fn find<T, P, M>(path: &CStr, parse: P, matches: M) -> Result<T, Status>
+where
+    P: Fn(&[u8]) -> Option<T>,
+    M: Fn(&T) -> bool,
+{
+    let file = File::open(path, O_RDONLY).map_err(|err| Status::Unavailable(err.0))?;
+    let mut reader = BufReader::new(file);
+    while let Some(entry) = next(&mut reader, &parse) {
+        if matches(&entry) {
+            return Ok(entry);
+        }
+    }
+    Err(Status::NotFound)
+}
+
+impl Source for Files {
+    fn passwd(&self, key: PasswdKey) -> Result<Passwd, Status> {
+        find(c_str!("/etc/passwd"), parse_passwd, |entry| match key {
+            PasswdKey::Name(name) => entry.name == name,
+            PasswdKey::Uid(uid) => entry.uid == uid,
+        })
+    }
+
+    fn group(&self, key: GroupKey) -> Result<Group, Status> {
+        find(c_str!("/etc/group"), parse_group, |entry| match key {
+            GroupKey::Name(name) => entry.name == name,
+            GroupKey::Gid(gid) => entry.gid == gid,
+        })
+    }
+
+    fn hosts(&self, key: HostKey) -> Result<Host, Status> {
+        find(c_str!("/etc/hosts"), parse_host, |entry| match key {
+            HostKey::Name(name) => {
+                entry.name.eq_ignore_ascii_case(name)
+                    || entry
+                        .aliases
+                        .iter()
+                        .any(|alias| alias.eq_ignore_ascii_case(name))
+            }
+            HostKey::Addr(addr) => entry.addrs[0].s_addr == addr.s_addr,
+        })
+    }
+}
@@ -0,0 +1,150 @@
+//! A name service switch, which looks user, group and host entries up in the sources that
+//! /etc/nsswitch.conf lists for each database, in the order it lists them.
+//!
+//! A line of the configuration is `database: source [STATUS=ACTION] source...`, where STATUS is
+//! SUCCESS, NOTFOUND, UNAVAIL or TRYAGAIN, ACTION is return or continue, and `!STATUS` stands for
+//! every other status. A lookup returns after a source that succeeds and goes on to the next one
+//! otherwise, unless the brackets after the source say differently. Databases the configuration
+//! doesn't mention use files, and hosts also use dns after them.
+//!
+//! Sources implement `Source` and are found by name in `source`, so resolvers for other services
+//! are added there.
+
+mod config;
+mod dns;
+pub mod files;
+
+use alloc::vec::Vec;
+
+use crate::{
+    header::{errno::ENOENT, netinet_in::in_addr},
+    platform::types::*,
+};
+
+/// The owned form of a `passwd` entry
+#[derive(Clone, Debug)]
+pub struct Passwd {
+    pub name: Vec<u8>,
+    pub passwd: Vec<u8>,
+    pub uid: uid_t,
+    pub gid: gid_t,
+    pub gecos: Vec<u8>,
+    pub dir: Vec<u8>,
+    pub shell: Vec<u8>,
+}
+
+/// The owned form of a `group` entry
+#[derive(Clone, Debug)]
+pub struct Group {
+    pub name: Vec<u8>,
+    pub passwd: Vec<u8>,
+    pub gid: gid_t,
+    pub members: Vec<Vec<u8>>,
+}
+
+/// The owned form of an IPv4 `hostent`
+#[derive(Clone, Debug)]
+pub struct Host {
+    pub name: Vec<u8>,
+    pub aliases: Vec<Vec<u8>>,
+    pub addrs: Vec<in_addr>,
+}
+
+#[derive(Clone, Copy)]
+pub enum PasswdKey<'a> {
+    Name(&'a [u8]),
+    Uid(uid_t),
+}
+
+#[derive(Clone, Copy)]
+pub enum GroupKey<'a> {
+    Name(&'a [u8]),
+    Gid(gid_t),
+}
+
+#[derive(Clone, Copy)]
+pub enum HostKey<'a> {
+    Name(&'a [u8]),
+    Addr(in_addr),
+}
+
+/// Why a source didn't give an entry
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Status {
+    NotFound,
+    /// The source can't answer, for the reason in the errno
+    Unavailable(c_int),
+    /// The source can't answer right now, like a nameserver that didn't reply
+    TryAgain(c_int),
+}
+
+impl Status {
+    /// The errno for callers that report failures that way, which is 0 when there's no entry.
+    pub fn errno(self) -> c_int {
+        match self {
+            Status::NotFound => 0,
+            Status::Unavailable(errno) | Status::TryAgain(errno) => errno,
+        }
+    }
+}
+
+/// A backend that can answer lookups in some of the databases
+pub trait Source: Sync {
+    fn passwd(&self, _key: PasswdKey) -> Result<Passwd, Status> {
+        Err(Status::Unavailable(ENOENT))
+    }
+
+    fn group(&self, _key: GroupKey) -> Result<Group, Status> {
+        Err(Status::Unavailable(ENOENT))
+    }
+
+    fn hosts(&self, _key: HostKey) -> Result<Host, Status> {
+        Err(Status::Unavailable(ENOENT))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Database {
+    Passwd,
+    Group,
+    Hosts,
+}
+
+fn source(name: &[u8]) -> Option<&'static dyn Source> {
+    match name {
+        b"files" => Some(&files::Files),
+        b"dns" => Some(&dns::Dns),
+        _ => None,
+    }
+}
+
+/// Asks the sources of `database` in turn with `f`, until one's outcome says to stop.
+fn lookup<T, F>(database: Database, mut f: F) -> Result<T, Status>
+where
+    F: FnMut(&dyn Source) -> Result<T, Status>,
+{
+    let mut res = Err(Status::NotFound);
+    for entry in config::sources(database) {
+        res = match source(&entry.name) {
+            Some(source) => f(source),
+            // Sources we don't have are as good as ones that can't be reached
+            None => Err(Status::Unavailable(ENOENT)),
+        };
+        if entry.returns(&res) {
+            break;
+        }
+    }
+    res
+}
+
+pub fn passwd(key: PasswdKey) -> Result<Passwd, Status> {
+    lookup(Database::Passwd, |source| source.passwd(key))
+}
+
+pub fn group(key: GroupKey) -> Result<Group, Status> {
+    lookup(Database::Group, |source| source.group(key))
+}
+
+pub fn hosts(key: HostKey) -> Result<Host, Status> {
+    lookup(Database::Hosts, |source| source.hosts(key))
+}
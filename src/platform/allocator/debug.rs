@@ -0,0 +1,283 @@
+//! The checks MALLOC_CHECK_ turns on.
+//!
+//! Every allocation is surrounded by canaries and filled with a pattern, and freed allocations
+//! are filled with another and held back for a while before being freed for real. Damaged
+//! canaries, a changed pattern in memory that was freed and freeing twice are reported with the
+//! address involved. Bit 0 of MALLOC_CHECK_ prints the report, bit 1 aborts, as in glibc.
+//!
+//! The checks are chosen before anything is allocated, and memory from before then, such as
+//! what the dynamic linker allocated, is passed through unchecked, to the slabs or dlmalloc,
+//! wherever it came from.
+
+use core::{fmt::Write, intrinsics, mem, ptr, slice};
+
+use super::{mspace_free, mspace_memalign, mspace_usable_size, slab, MALLOC_ALIGNMENT};
+use crate::{
+    platform::{types::*, FileWriter},
+    sync::Mutex,
+    ALLOCATOR,
+};
+
+/// Marks memory in use. It's kept right before the memory, where dlmalloc keeps the size of a
+/// chunk, which is never this large, which tells memory allocated without the checks apart.
+const LIVE: usize = !0xA11C;
+/// Marks memory that was freed
+const FREED: usize = !0xF4EE;
+const CANARY: usize = 0xCA4A_21E5_CA4A_21E5_u64 as usize;
+
+/// The bytes after an allocation, filled with TRAILER_BYTE
+const TRAILER: usize = 16;
+const TRAILER_BYTE: u8 = 0xA5;
+/// What memory is filled with when allocated, to show up reads of memory never written
+const ALLOC_BYTE: u8 = 0xAA;
+/// What memory is filled with when freed, to show up writes to it
+const FREE_BYTE: u8 = 0xDF;
+
+/// The number of freed allocations held back
+const QUARANTINE: usize = 64;
+
+#[repr(C)]
+struct Header {
+    /// What dlmalloc gave
+    base: *mut c_void,
+    /// The size asked for
+    size: usize,
+    /// Where dlmalloc keeps the size of the chunk before, which is never CANARY
+    canary: usize,
+    /// LIVE or FREED, right before the memory
+    magic: usize,
+}
+
+const HEADER: usize = mem::size_of::<Header>();
+
+/// The MALLOC_CHECK_ bits
+static mut CHECK: c_int = 0;
+
+/// The addresses of the freed allocations held back, and the slot of the one held longest
+static HELD: Mutex<([usize; QUARANTINE], usize)> = Mutex::new(([0; QUARANTINE], 0));
+
+const REPORT: c_int = 1;
+const ABORT: c_int = 2;
+
+/// Reads MALLOC_CHECK_ from `envp`, which must happen before anything is allocated.
+pub unsafe fn init(mut envp: *const *const c_char) {
+    const NAME: &[u8] = b"MALLOC_CHECK_=";
+    while !envp.is_null() && !(*envp).is_null() {
+        let var = *envp as *const u8;
+        if (0..NAME.len()).all(|i| *var.add(i) == NAME[i]) {
+            let value = *var.add(NAME.len());
+            if value.is_ascii_digit() {
+                CHECK = c_int::from(value - b'0') & (REPORT | ABORT);
+            }
+            return;
+        }
+        envp = envp.add(1);
+    }
+}
+
+pub fn enabled() -> bool {
+    unsafe { CHECK != 0 }
+}
+
+/// Takes the lock of the allocations held back, for fork.
+pub unsafe fn fork_prepare() {
+    HELD.manual_lock();
+}
+
+pub unsafe fn fork_parent() {
+    HELD.manual_unlock();
+}
+
+pub unsafe fn fork_child() {
+    HELD.manual_reinit();
+}
+
+unsafe fn report(what: &str, ptr: *const c_void) {
+    if CHECK & REPORT != 0 {
+        let _ = writeln!(FileWriter(2), "malloc: {} at {:p}", what, ptr);
+    }
+    if CHECK & ABORT != 0 {
+        intrinsics::abort();
+    }
+}
+
+unsafe fn header<'a>(ptr: *mut c_void) -> &'a mut Header {
+    &mut *((ptr as *mut u8).sub(HEADER) as *mut Header)
+}
+
+enum Kind {
+    Unchecked,
+    Checked,
+    Damaged,
+}
+
+/// Tells what `ptr` is, reading only the two words before it, which are in its chunk either way.
+/// The objects of the slabs are all from before the checks.
+unsafe fn kind(ptr: *mut c_void) -> Kind {
+    if slab::contains(ptr) {
+        return Kind::Unchecked;
+    }
+    let words = ptr as *const usize;
+    match *words.offset(-1) {
+        LIVE | FREED => Kind::Checked,
+        // The magic was overwritten, but not the canary before it
+        _ if *words.offset(-2) == CANARY => {
+            report("write before the start of the memory", ptr);
+            Kind::Damaged
+        }
+        _ => Kind::Unchecked,
+    }
+}
+
+unsafe fn free_unchecked(ptr: *mut c_void) {
+    if slab::contains(ptr) {
+        slab::free(ptr);
+    } else {
+        mspace_free(ALLOCATOR.get_book_keeper(), ptr);
+    }
+}
+
+unsafe fn usable_size_unchecked(ptr: *const c_void) -> usize {
+    if slab::contains(ptr) {
+        slab::usable_size(ptr)
+    } else {
+        mspace_usable_size(ptr)
+    }
+}
+
+pub unsafe fn alloc(size: usize, alignment: usize) -> *mut c_void {
+    let alignment = alignment.max(MALLOC_ALIGNMENT);
+    let offset = (HEADER + alignment - 1) / alignment * alignment;
+    let total = match offset
+        .checked_add(size)
+        .and_then(|total| total.checked_add(TRAILER))
+    {
+        Some(total) => total,
+        None => return ptr::null_mut(),
+    };
+    let base = mspace_memalign(ALLOCATOR.get_book_keeper(), alignment, total);
+    if base.is_null() {
+        return base;
+    }
+
+    let ptr = (base as *mut u8).add(offset) as *mut c_void;
+    *header(ptr) = Header {
+        base,
+        size,
+        canary: CANARY,
+        magic: LIVE,
+    };
+    ptr::write_bytes(ptr as *mut u8, ALLOC_BYTE, size);
+    ptr::write_bytes((ptr as *mut u8).add(size), TRAILER_BYTE, TRAILER);
+    ptr
+}
+
+/// Checks that `ptr`, which is marked, is in use and that its canaries are intact.
+unsafe fn check_live(ptr: *mut c_void) -> bool {
+    let header = header(ptr);
+    if header.magic == FREED {
+        report("double free", ptr);
+        return false;
+    }
+    if header.canary != CANARY {
+        report("write before the start of the memory", ptr);
+        return false;
+    }
+    let trailer = slice::from_raw_parts((ptr as *const u8).add(header.size), TRAILER);
+    if trailer.iter().any(|&byte| byte != TRAILER_BYTE) {
+        report("write past the end of the memory", ptr);
+        return false;
+    }
+    true
+}
+
+pub unsafe fn free(ptr: *mut c_void) {
+    if ptr.is_null() {
+        return;
+    }
+    // Damaged memory is left alone, rather than risk freeing what isn't a chunk
+    match kind(ptr) {
+        Kind::Unchecked => return free_unchecked(ptr),
+        Kind::Checked if check_live(ptr) => (),
+        _ => return,
+    }
+    let header = header(ptr);
+    header.magic = FREED;
+    ptr::write_bytes(ptr as *mut u8, FREE_BYTE, header.size);
+
+    let mut held = HELD.lock();
+    let next = held.1;
+    let evicted = mem::replace(&mut held.0[next], ptr as usize);
+    held.1 = (next + 1) % QUARANTINE;
+    drop(held);
+    if evicted != 0 {
+        release(evicted as *mut c_void);
+    }
+}
+
+/// Really frees `ptr`, which was held back, after checking that it wasn't written to.
+unsafe fn release(ptr: *mut c_void) {
+    let header = header(ptr);
+    let memory = slice::from_raw_parts(ptr as *const u8, header.size);
+    if header.magic != FREED || memory.iter().any(|&byte| byte != FREE_BYTE) {
+        report("write after free", ptr);
+        return;
+    }
+    mspace_free(ALLOCATOR.get_book_keeper(), header.base);
+}
+
+pub unsafe fn realloc(ptr: *mut c_void, size: usize) -> *mut c_void {
+    if ptr.is_null() {
+        return alloc(size, MALLOC_ALIGNMENT);
+    }
+    let old_size = match kind(ptr) {
+        Kind::Unchecked => usable_size_unchecked(ptr),
+        Kind::Checked if check_live(ptr) => header(ptr).size,
+        _ => return ptr::null_mut(),
+    };
+
+    let new = alloc(size, MALLOC_ALIGNMENT);
+    if !new.is_null() {
+        ptr::copy_nonoverlapping(ptr as *const u8, new as *mut u8, old_size.min(size));
+        free(ptr);
+    }
+    new
+}
+
+pub unsafe fn usable_size(ptr: *const c_void) -> usize {
+    match kind(ptr as *mut c_void) {
+        Kind::Unchecked => usable_size_unchecked(ptr),
+        Kind::Checked => header(ptr as *mut c_void).size,
+        Kind::Damaged => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::header::malloc::mallinfo2;
+
+    fn in_use() -> usize {
+        let mut info = mallinfo2::default();
+        slab::info(&mut info);
+        info.uordblks
+    }
+
+    #[test]
+    fn slab_object_from_before() {
+        unsafe {
+            // A small block, from the slabs, allocated before the checks are turned on
+            let ptr = slab::alloc(24);
+            assert!(!ptr.is_null());
+            let before = in_use();
+
+            CHECK = REPORT;
+            assert_eq!(usable_size(ptr), 32);
+            free(ptr);
+            CHECK = 0;
+
+            // It went back to its slab, rather than to dlmalloc
+            assert_eq!(in_use(), before - 32);
+        }
+    }
+}
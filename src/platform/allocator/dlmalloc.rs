@@ -1,17 +1,48 @@
-use crate::ALLOCATOR;
+use crate::{
+    header::{
+        malloc::{mallinfo2, M_DECOMMIT_THRESHOLD},
+        stdlib,
+    },
+    ALLOCATOR,
+};
 use core::{
     alloc::{GlobalAlloc, Layout},
-    sync::atomic::{AtomicUsize, Ordering},
+    mem, ptr,
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
 };
 
 use super::types::*;
 
+#[path = "debug.rs"]
+mod debug;
+#[path = "slab.rs"]
+mod slab;
+
 extern "C" {
     fn create_mspace(capacity: size_t, locked: c_int) -> usize;
     fn mspace_malloc(msp: usize, bytes: size_t) -> *mut c_void;
     fn mspace_memalign(msp: usize, alignment: size_t, bytes: size_t) -> *mut c_void;
     fn mspace_realloc(msp: usize, oldmem: *mut c_void, bytes: size_t) -> *mut c_void;
     fn mspace_free(msp: usize, mem: *mut c_void);
+    fn mspace_bulk_free(msp: usize, array: *mut *mut c_void, nelem: size_t) -> size_t;
+    fn mspace_independent_comalloc(
+        msp: usize,
+        n_elements: size_t,
+        sizes: *const size_t,
+        chunks: *mut *mut c_void,
+    ) -> *mut *mut c_void;
+    fn mspace_usable_size(mem: *const c_void) -> size_t;
+    fn mspace_mallinfo(msp: usize) -> mallinfo2;
+    fn mspace_mallopt(param_number: c_int, value: c_int) -> c_int;
+    fn mspace_trim(msp: usize, pad: size_t) -> c_int;
+    fn mspace_inspect_all(
+        msp: usize,
+        handler: extern "C" fn(*mut c_void, *mut c_void, size_t, *mut c_void),
+        arg: *mut c_void,
+    );
+    fn mspace_fork_prepare(msp: usize);
+    fn mspace_fork_parent(msp: usize);
+    fn mspace_fork_child(msp: usize);
     //fn dlmalloc(bytes: size_t) -> *mut c_void;
     //fn dlmemalign(alignment: size_t, bytes: size_t) -> *mut c_void;
     //fn dlrealloc(oldmem: *mut c_void, bytes: size_t) -> *mut c_void;
@@ -34,32 +65,304 @@ impl Allocator {
         self.mstate.load(Ordering::Relaxed)
     }
 }
+// Through the C functions, so that an allocator that replaces them gets relibc's allocations too
 unsafe impl<'a> GlobalAlloc for Allocator {
     unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
-        alloc_align(layout.size(), layout.align()) as *mut u8
+        if layout.align() <= MALLOC_ALIGNMENT {
+            stdlib::malloc(layout.size()) as *mut u8
+        } else {
+            stdlib::memalign(layout.align(), layout.size()) as *mut u8
+        }
     }
 
     unsafe fn dealloc(&self, ptr: *mut u8, _layout: Layout) {
-        free(ptr as *mut c_void)
+        stdlib::free(ptr as *mut c_void)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        if layout.align() <= MALLOC_ALIGNMENT {
+            return stdlib::realloc(ptr as *mut c_void, new_size) as *mut u8;
+        }
+        let new = self.alloc(Layout::from_size_align_unchecked(new_size, layout.align()));
+        if !new.is_null() {
+            ptr::copy_nonoverlapping(ptr, new, layout.size().min(new_size));
+            self.dealloc(ptr, layout);
+        }
+        new
+    }
+}
+
+/// The alignment of every chunk dlmalloc gives
+const MALLOC_ALIGNMENT: usize = 2 * mem::size_of::<usize>();
+
+/// The size classes of the thread caches are this far apart
+const CACHE_STEP: usize = 16;
+/// The number of size classes, which cover requests of up to 512 bytes
+const CACHE_CLASSES: usize = 32;
+/// The number of chunks a size class holds before half of them go back to the heap
+const CACHE_LEN: usize = 16;
+/// The number of chunks taken from the heap at once, for a size class that ran out
+const CACHE_REFILL: usize = CACHE_LEN / 2;
+
+/// The free chunks of a size class, linked through their first word.
+///
+/// Every chunk of size class `i` has at least `(i + 1) * CACHE_STEP` usable bytes.
+#[derive(Clone, Copy)]
+struct CacheClass {
+    head: *mut c_void,
+    len: usize,
+}
+
+/// Small chunks freed by this thread, kept from the heap so that most allocations and frees
+/// don't take its lock. A size class that fills up gives half of its chunks back to the heap at
+/// once, and one that runs out takes several at once. All of it goes back when the thread exits.
+///
+/// The heap counts these chunks as in use.
+#[thread_local]
+static mut CACHE: [CacheClass; CACHE_CLASSES] = [CacheClass {
+    head: ptr::null_mut(),
+    len: 0,
+}; CACHE_CLASSES];
+
+/// Set once thread-local storage is set up, before which the caches can't be used
+static CACHE_ENABLED: AtomicBool = AtomicBool::new(false);
+
+impl CacheClass {
+    unsafe fn push(&mut self, chunk: *mut c_void) {
+        *(chunk as *mut *mut c_void) = self.head;
+        self.head = chunk;
+        self.len += 1;
+    }
+
+    unsafe fn pop(&mut self) -> *mut c_void {
+        let chunk = self.head;
+        if !chunk.is_null() {
+            self.head = *(chunk as *mut *mut c_void);
+            self.len -= 1;
+        }
+        chunk
+    }
+
+    /// Gives `count` chunks back to the heap, taking its lock once.
+    unsafe fn flush(&mut self, count: usize) {
+        let mut chunks = [ptr::null_mut(); CACHE_LEN];
+        let mut len = 0;
+        for _ in 0..count {
+            let chunk = self.pop();
+            if chunk.is_null() {
+                break;
+            } else if slab::contains(chunk) {
+                slab::free(chunk);
+            } else {
+                chunks[len] = chunk;
+                len += 1;
+            }
+        }
+        mspace_bulk_free(ALLOCATOR.get_book_keeper(), chunks.as_mut_ptr(), len);
+    }
+
+    /// Takes chunks of class `index` from the slabs, or from the heap, taking its lock once.
+    unsafe fn refill(&mut self, index: usize) {
+        if index < slab::CLASSES {
+            let mut chunks = [ptr::null_mut(); CACHE_REFILL];
+            let count = slab::fill(index, &mut chunks);
+            for &chunk in chunks[..count].iter() {
+                self.push(chunk);
+            }
+            if count > 0 {
+                return;
+            }
+        }
+
+        let sizes = [(index + 1) * CACHE_STEP; CACHE_REFILL];
+        let mut chunks = [ptr::null_mut(); CACHE_REFILL];
+        if !mspace_independent_comalloc(
+            ALLOCATOR.get_book_keeper(),
+            CACHE_REFILL,
+            sizes.as_ptr(),
+            chunks.as_mut_ptr(),
+        )
+        .is_null()
+        {
+            for &chunk in chunks.iter() {
+                self.push(chunk);
+            }
+        }
+    }
+}
+
+/// Turns on the checks MALLOC_CHECK_ in `envp` asks for, before anything is allocated.
+pub unsafe fn init_debug(envp: *const *const c_char) {
+    debug::init(envp);
+}
+
+/// Lets allocations use the thread caches, once thread-local storage is set up.
+pub fn enable_cache() {
+    CACHE_ENABLED.store(true, Ordering::Relaxed);
+}
+
+/// Gives every chunk in the cache of this thread back to the heap.
+pub unsafe fn flush_cache() {
+    if !CACHE_ENABLED.load(Ordering::Relaxed) {
+        return;
+    }
+    for class in CACHE.iter_mut() {
+        while class.len > 0 {
+            class.flush(CACHE_LEN);
+        }
     }
 }
 
 pub unsafe fn alloc(size: usize) -> *mut c_void {
+    if debug::enabled() {
+        return debug::alloc(size, MALLOC_ALIGNMENT);
+    }
+    if size <= CACHE_CLASSES * CACHE_STEP && CACHE_ENABLED.load(Ordering::Relaxed) {
+        let index = size.saturating_sub(1) / CACHE_STEP;
+        let class = &mut CACHE[index];
+        if class.head.is_null() {
+            class.refill(index);
+        }
+        let chunk = class.pop();
+        if !chunk.is_null() {
+            return chunk;
+        }
+    }
+    if size <= slab::MAX {
+        let chunk = slab::alloc(size);
+        if !chunk.is_null() {
+            return chunk;
+        }
+    }
     mspace_malloc(ALLOCATOR.get_book_keeper(), size)
 }
 
 pub unsafe fn alloc_align(size: usize, alignment: usize) -> *mut c_void {
+    if debug::enabled() {
+        return debug::alloc(size, alignment);
+    }
+    if alignment <= MALLOC_ALIGNMENT {
+        return alloc(size);
+    }
     mspace_memalign(ALLOCATOR.get_book_keeper(), alignment, size)
 }
 
 pub unsafe fn realloc(ptr: *mut c_void, size: size_t) -> *mut c_void {
+    if debug::enabled() {
+        return debug::realloc(ptr, size);
+    }
+    if slab::contains(ptr) {
+        let old_size = slab::usable_size(ptr);
+        if size <= old_size {
+            return ptr;
+        }
+        let new = alloc(size);
+        if !new.is_null() {
+            ptr::copy_nonoverlapping(ptr as *const u8, new as *mut u8, old_size);
+            free(ptr);
+        }
+        return new;
+    }
     mspace_realloc(ALLOCATOR.get_book_keeper(), ptr, size)
 }
 
 pub unsafe fn free(ptr: *mut c_void) {
+    if debug::enabled() {
+        return debug::free(ptr);
+    }
+    let in_slab = slab::contains(ptr);
+    if !ptr.is_null() && CACHE_ENABLED.load(Ordering::Relaxed) {
+        // Zero for what isn't in use, which mspace_free reports
+        let usable = if in_slab {
+            slab::usable_size(ptr)
+        } else {
+            mspace_usable_size(ptr)
+        };
+        let classes = usable / CACHE_STEP;
+        if classes > 0 && classes <= CACHE_CLASSES {
+            let class = &mut CACHE[classes - 1];
+            if class.len == CACHE_LEN {
+                class.flush(CACHE_LEN / 2);
+            }
+            class.push(ptr);
+            return;
+        }
+    }
+    if in_slab {
+        return slab::free(ptr);
+    }
     mspace_free(ALLOCATOR.get_book_keeper(), ptr)
 }
 
+pub unsafe fn usable_size(ptr: *const c_void) -> usize {
+    if debug::enabled() {
+        return debug::usable_size(ptr);
+    }
+    if slab::contains(ptr) {
+        return slab::usable_size(ptr);
+    }
+    mspace_usable_size(ptr)
+}
+
+pub fn info() -> mallinfo2 {
+    let mut info = unsafe { mspace_mallinfo(ALLOCATOR.get_book_keeper()) };
+    slab::info(&mut info);
+    info
+}
+
+/// Sets M_TRIM_THRESHOLD or M_MMAP_THRESHOLD, which dlmalloc numbers the same as glibc, or
+/// M_DECOMMIT_THRESHOLD, which also applies to the slabs.
+pub fn set_param(param: c_int, value: c_int) -> c_int {
+    if param == M_DECOMMIT_THRESHOLD {
+        slab::set_decommit_threshold(if value == -1 {
+            usize::MAX
+        } else {
+            value as usize
+        });
+    }
+    unsafe { mspace_mallopt(param, value) }
+}
+
+/// Returns free memory at the top of the heap to the system, keeping `pad` bytes of it.
+pub fn trim(pad: size_t) -> c_int {
+    unsafe {
+        flush_cache();
+        mspace_trim(ALLOCATOR.get_book_keeper(), pad)
+    }
+}
+
+/// Calls `handler` with each region of the heap and how many of its bytes are in use, with the
+/// heap locked.
+pub unsafe fn inspect(
+    handler: extern "C" fn(*mut c_void, *mut c_void, size_t, *mut c_void),
+    arg: *mut c_void,
+) {
+    mspace_inspect_all(ALLOCATOR.get_book_keeper(), handler, arg)
+}
+
+/// Takes every lock of the heap before a fork, in the order they're taken while allocating, so
+/// that the child gets the heap in a consistent state. The thread caches need no lock.
+pub unsafe fn fork_prepare() {
+    debug::fork_prepare();
+    slab::fork_prepare();
+    mspace_fork_prepare(ALLOCATOR.get_book_keeper());
+}
+
+/// Releases the locks fork_prepare took, in the parent.
+pub unsafe fn fork_parent() {
+    mspace_fork_parent(ALLOCATOR.get_book_keeper());
+    slab::fork_parent();
+    debug::fork_parent();
+}
+
+/// Makes the locks fork_prepare took usable again, in the child.
+pub unsafe fn fork_child() {
+    mspace_fork_child(ALLOCATOR.get_book_keeper());
+    slab::fork_child();
+    debug::fork_child();
+}
+
+/// Makes the heap, with its lock, as threads share it
 pub fn new_mspace() -> usize {
-    unsafe { create_mspace(0, 0) }
+    unsafe { create_mspace(0, 1) }
 }
@@ -46,3 +46,44 @@ pub unsafe fn free(ptr: *mut c_void) {
     let _align = *(ptr as *mut u64).offset(1);
     ralloc::free(ptr, size as usize);
 }
+
+pub unsafe fn usable_size(ptr: *const c_void) -> usize {
+    let ptr = (ptr as *const u8).offset(-16);
+    let size = *(ptr as *const u64);
+    let align = *(ptr as *const u64).offset(1);
+    // The offset alloc_inner was given
+    let offset = if align > 8 { align / 2 } else { 16 };
+    (size - offset) as usize
+}
+
+// ralloc keeps no statistics, nor takes any parameters
+pub fn info() -> crate::header::malloc::mallinfo2 {
+    crate::header::malloc::mallinfo2::default()
+}
+
+pub unsafe fn inspect(
+    _handler: extern "C" fn(*mut c_void, *mut c_void, size_t, *mut c_void),
+    _arg: *mut c_void,
+) {
+}
+
+pub fn set_param(_param: c_int, _value: c_int) -> c_int {
+    0
+}
+
+pub fn trim(_pad: size_t) -> c_int {
+    0
+}
+
+pub fn enable_cache() {}
+
+pub unsafe fn flush_cache() {}
+
+pub unsafe fn init_debug(_envp: *const *const c_char) {}
+
+// ralloc's own locks aren't reachable from here
+pub unsafe fn fork_prepare() {}
+
+pub unsafe fn fork_parent() {}
+
+pub unsafe fn fork_child() {}
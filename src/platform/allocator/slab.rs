@@ -0,0 +1,358 @@
+//! Slabs for small allocations.
+//!
+//! Requests of up to 256 bytes are served from slabs of 64 KiB, each holding objects of a single
+//! size class. A slab hands out its objects in order until it reaches its end, and after that the
+//! ones freed since, which a bitmap in its header keeps track of, so that freeing an object is a
+//! matter of setting a bit. The slabs are cut from a region reserved up front, which tells their
+//! objects apart from dlmalloc's by address alone. A slab with nothing left in use goes back to
+//! the region, for any size class to take, and past the decommit threshold its pages go back to
+//! the system.
+
+use core::{
+    mem, ptr, slice,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use super::CACHE_STEP;
+use crate::{
+    header::{
+        malloc::mallinfo2,
+        sys_mman::{
+            MADV_DONTNEED, MAP_ANONYMOUS, MAP_NORESERVE, MAP_PRIVATE, PROT_NONE, PROT_READ,
+            PROT_WRITE,
+        },
+    },
+    platform::{types::*, Pal, Sys},
+    sync::{Mutex, Once},
+};
+
+const SLAB_SIZE: usize = 64 * 1024;
+/// The room reserved for slabs. It's mapped inaccessible, which neither Redox nor Linux with
+/// strict overcommit charge, and each slab is made readable and writable as it's cut from it.
+const REGION_SIZE: usize = 64 * 1024 * 1024;
+
+/// The size classes are those of the thread caches, so that one that runs out refills from the
+/// slabs of the same index.
+pub const CLASSES: usize = 16;
+pub const MAX: usize = CLASSES * CACHE_STEP;
+
+/// The words of the bitmap, with a bit for each object the smallest size class fits in a slab
+const WORDS: usize = SLAB_SIZE / CACHE_STEP / 64;
+
+#[repr(C)]
+struct Slab {
+    /// The size of the objects
+    size: usize,
+    /// The number of objects the slab holds
+    capacity: usize,
+    /// The number of objects in use
+    used: usize,
+    /// The number of objects handed out in order, after which `free` is used
+    bumped: usize,
+    /// The first word of `free` that may have a bit set
+    hint: usize,
+    /// The neighbours of the slab in the list of its size class, or in the list of empty slabs
+    prev: *mut Slab,
+    next: *mut Slab,
+    /// The objects freed after being handed out, one bit each
+    free: [u64; WORDS],
+}
+
+/// Where the objects start, which keeps them aligned like dlmalloc's chunks
+const OBJECTS: usize = (mem::size_of::<Slab>() + CACHE_STEP - 1) / CACHE_STEP * CACHE_STEP;
+
+impl Slab {
+    /// Hands out an object, of which the slab must have one that isn't in use.
+    unsafe fn take(&mut self) -> *mut c_void {
+        let index = if self.bumped < self.capacity {
+            self.bumped += 1;
+            self.bumped - 1
+        } else {
+            while self.free[self.hint] == 0 {
+                self.hint += 1;
+            }
+            let word = &mut self.free[self.hint];
+            let bit = word.trailing_zeros() as usize;
+            *word &= !(1 << bit);
+            self.hint * 64 + bit
+        };
+        self.used += 1;
+        (self as *mut Slab as *mut u8).add(OBJECTS + index * self.size) as *mut c_void
+    }
+
+    /// Marks `ptr` free, unless it isn't an object in use, which is left alone.
+    unsafe fn give(&mut self, ptr: *mut c_void) -> bool {
+        let offset = ptr as usize - self as *mut Slab as usize;
+        if offset < OBJECTS || (offset - OBJECTS) % self.size != 0 {
+            return false;
+        }
+        let index = (offset - OBJECTS) / self.size;
+        let (word, bit) = (index / 64, index % 64);
+        if index >= self.bumped || self.free[word] & (1 << bit) != 0 {
+            return false;
+        }
+        self.free[word] |= 1 << bit;
+        self.hint = self.hint.min(word);
+        self.used -= 1;
+        true
+    }
+}
+
+/// Puts `slab` at the front of the list starting at `head`.
+unsafe fn push(head: &mut usize, slab: *mut Slab) {
+    let next = *head as *mut Slab;
+    (*slab).prev = ptr::null_mut();
+    (*slab).next = next;
+    if !next.is_null() {
+        (*next).prev = slab;
+    }
+    *head = slab as usize;
+}
+
+/// Takes `slab` out of the list starting at `head`.
+unsafe fn unlink(head: &mut usize, slab: *mut Slab) {
+    let (prev, next) = ((*slab).prev, (*slab).next);
+    if prev.is_null() {
+        *head = next as usize;
+    } else {
+        (*prev).next = next;
+    }
+    if !next.is_null() {
+        (*next).prev = prev;
+    }
+}
+
+struct Spare {
+    /// The offset in the region of the first slab never cut
+    cut: usize,
+    /// The empty slabs that are still resident
+    empty: usize,
+    /// The number of them
+    empties: usize,
+    /// The empty slabs that gave their pages back
+    decommitted: usize,
+}
+
+static REGION: Once<usize> = Once::new();
+/// The start of the region, or zero until it is reserved
+static BASE: AtomicUsize = AtomicUsize::new(0);
+static SPARE: Mutex<Spare> = Mutex::new(Spare {
+    cut: 0,
+    empty: 0,
+    empties: 0,
+    decommitted: 0,
+});
+
+/// The bytes of empty slabs kept resident, past which they give their pages back, as for the
+/// free runs of dlmalloc
+static DECOMMIT_THRESHOLD: AtomicUsize = AtomicUsize::new(1024 * 1024);
+/// Where the pages a slab gives back start, after those of the header, which holds the links
+const KEPT: usize = (mem::size_of::<Slab>() + 4095) / 4096 * 4096;
+
+/// The slabs of each size class that have objects to hand out
+const NO_SLABS: Mutex<usize> = Mutex::new(0);
+static PARTIAL: [Mutex<usize>; CLASSES] = [NO_SLABS; CLASSES];
+
+/// The bytes of the objects in use
+static IN_USE: AtomicUsize = AtomicUsize::new(0);
+
+/// Reserves the region, or returns zero when it can't be.
+fn reserve() -> usize {
+    *REGION.call_once(|| unsafe {
+        // Room to spare, to align the slabs to their size
+        let start = Sys::mmap(
+            ptr::null_mut(),
+            REGION_SIZE + SLAB_SIZE,
+            PROT_NONE,
+            MAP_PRIVATE | MAP_ANONYMOUS | MAP_NORESERVE,
+            -1,
+            0,
+        );
+        if start as usize == !0
+        /* MAP_FAILED */
+        {
+            // Allocations then go to dlmalloc, and may well succeed
+            return 0;
+        }
+        let base = (start as usize + SLAB_SIZE - 1) & !(SLAB_SIZE - 1);
+        BASE.store(base, Ordering::Release);
+        base
+    })
+}
+
+/// Gets an empty slab ready for size class `class`.
+unsafe fn new_slab(base: usize, class: usize) -> *mut Slab {
+    let mut spare = SPARE.lock();
+    let slab = if spare.empty != 0 {
+        let slab = spare.empty as *mut Slab;
+        unlink(&mut spare.empty, slab);
+        spare.empties -= 1;
+        slab
+    } else if spare.decommitted != 0 {
+        let slab = spare.decommitted as *mut Slab;
+        unlink(&mut spare.decommitted, slab);
+        slab
+    } else if spare.cut < REGION_SIZE {
+        let slab = (base + spare.cut) as *mut Slab;
+        if Sys::mprotect(slab as *mut c_void, SLAB_SIZE, PROT_READ | PROT_WRITE) < 0 {
+            // Out of memory to commit, for which allocations go to dlmalloc
+            return ptr::null_mut();
+        }
+        spare.cut += SLAB_SIZE;
+        slab
+    } else {
+        return ptr::null_mut();
+    };
+    drop(spare);
+
+    let size = (class + 1) * CACHE_STEP;
+    ptr::write(
+        slab,
+        Slab {
+            size,
+            capacity: (SLAB_SIZE - OBJECTS) / size,
+            used: 0,
+            bumped: 0,
+            hint: 0,
+            prev: ptr::null_mut(),
+            next: ptr::null_mut(),
+            free: [0; WORDS],
+        },
+    );
+    slab
+}
+
+/// Whether `ptr` is an object of a slab.
+pub fn contains(ptr: *const c_void) -> bool {
+    let base = BASE.load(Ordering::Relaxed);
+    base != 0 && (ptr as usize).wrapping_sub(base) < REGION_SIZE
+}
+
+/// Fills `chunks` with objects of size class `class`, taking its lock once, and returns how many
+/// it got.
+pub unsafe fn fill(class: usize, chunks: &mut [*mut c_void]) -> usize {
+    let mut base = BASE.load(Ordering::Acquire);
+    if base == 0 {
+        base = reserve();
+        if base == 0 {
+            return 0;
+        }
+    }
+
+    let mut head = PARTIAL[class].lock();
+    let mut count = 0;
+    while count < chunks.len() {
+        let mut slab = *head as *mut Slab;
+        if slab.is_null() {
+            slab = new_slab(base, class);
+            if slab.is_null() {
+                break;
+            }
+            push(&mut *head, slab);
+        }
+        chunks[count] = (*slab).take();
+        count += 1;
+        // A full slab comes back to the list once something in it is freed
+        if (*slab).used == (*slab).capacity {
+            unlink(&mut *head, slab);
+        }
+    }
+    IN_USE.fetch_add(count * (class + 1) * CACHE_STEP, Ordering::Relaxed);
+    count
+}
+
+pub unsafe fn alloc(size: usize) -> *mut c_void {
+    let mut chunk = ptr::null_mut();
+    fill(
+        size.saturating_sub(1) / CACHE_STEP,
+        slice::from_mut(&mut chunk),
+    );
+    chunk
+}
+
+fn slab_of(ptr: *const c_void) -> *mut Slab {
+    (ptr as usize & !(SLAB_SIZE - 1)) as *mut Slab
+}
+
+/// Frees `ptr`, which must be an object of a slab.
+pub unsafe fn free(ptr: *mut c_void) {
+    let slab = slab_of(ptr);
+    // Doesn't change while an object of the slab is in use
+    let size = (*slab).size;
+    let mut head = PARTIAL[size / CACHE_STEP - 1].lock();
+    let was_full = (*slab).used == (*slab).capacity;
+    if !(*slab).give(ptr) {
+        return;
+    }
+    IN_USE.fetch_sub(size, Ordering::Relaxed);
+    if was_full {
+        push(&mut *head, slab);
+    }
+    // The last slab of a size class is kept, not to take another as soon as it's needed again
+    if (*slab).used == 0 && (*head != slab as usize || !(*slab).next.is_null()) {
+        unlink(&mut *head, slab);
+        drop(head);
+        give_back(slab);
+    }
+}
+
+/// Puts `slab`, which is empty, with the spare ones. The empty slabs past the threshold give
+/// their pages back, and are only taken again once those still resident are.
+unsafe fn give_back(slab: *mut Slab) {
+    let mut spare = SPARE.lock();
+    if spare.empties.saturating_mul(SLAB_SIZE) < DECOMMIT_THRESHOLD.load(Ordering::Relaxed) {
+        push(&mut spare.empty, slab);
+        spare.empties += 1;
+        return;
+    }
+    Sys::madvise(
+        (slab as *mut u8).add(KEPT) as *mut c_void,
+        SLAB_SIZE - KEPT,
+        MADV_DONTNEED,
+    );
+    push(&mut spare.decommitted, slab);
+}
+
+/// Takes the locks of the size classes and then of the spare slabs, which are taken in that
+/// order while allocating, for fork.
+pub unsafe fn fork_prepare() {
+    for partial in PARTIAL.iter() {
+        partial.manual_lock();
+    }
+    SPARE.manual_lock();
+}
+
+pub unsafe fn fork_parent() {
+    SPARE.manual_unlock();
+    for partial in PARTIAL.iter() {
+        partial.manual_unlock();
+    }
+}
+
+pub unsafe fn fork_child() {
+    SPARE.manual_reinit();
+    for partial in PARTIAL.iter() {
+        partial.manual_reinit();
+    }
+}
+
+/// Sets the bytes of empty slabs kept resident, `usize::MAX` to keep them all.
+pub fn set_decommit_threshold(threshold: usize) {
+    DECOMMIT_THRESHOLD.store(threshold, Ordering::Relaxed);
+}
+
+/// The size of the objects of the slab of `ptr`, which must be in use.
+pub unsafe fn usable_size(ptr: *const c_void) -> usize {
+    (*slab_of(ptr)).size
+}
+
+/// Counts the slabs in what dlmalloc reports. The empty ones past the decommit threshold have
+/// given their pages back to the system, but keep their address space, and count as free.
+pub fn info(info: &mut mallinfo2) {
+    let cut = SPARE.lock().cut;
+    let in_use = IN_USE.load(Ordering::Relaxed);
+    info.arena += cut;
+    info.usmblks += cut;
+    info.uordblks += in_use;
+    info.fordblks += cut.saturating_sub(in_use);
+}
@@ -0,0 +1,61 @@
+//! What blocking calls do when a signal or pthread_cancel interrupts them, the same way for both
+//! backends.
+//!
+//! Each of the Pal's blocking calls goes through [`call`]. The calls POSIX lets SA_RESTART resume,
+//! like reading, writing and waiting for children, are resumed if the handlers asked for it, while
+//! sleeping and waiting for events always fail with EINTR. Linux resumes them itself, before the
+//! call returns, so only Redox's are resumed here.
+//!
+//! The cancellation points are the C functions, which go through [`cancellation_point`], rather
+//! than the Pal, which relibc calls with its own locks held: a thread cancelled there would never
+//! release them. Nothing interrupts a thread that is blocked when it is cancelled, it acts on the
+//! cancel when the call returns.
+
+use crate::header::errno::EINTR;
+
+use super::{errno, pte};
+
+/// Whether a call is resumed after a signal handler returns.
+#[derive(Clone, Copy, Eq, PartialEq)]
+pub enum Restart {
+    /// Resumed if every handler that could have interrupted it was installed with SA_RESTART
+    IfAsked,
+    /// Fails with EINTR whatever the handler asked for
+    Never,
+}
+
+/// Makes the blocking call `f`, which returns -1 and sets errno when it fails.
+pub fn call<T: PartialEq + From<i8>>(restart: Restart, mut f: impl FnMut() -> T) -> T {
+    loop {
+        let res = f();
+        if res != T::from(-1) || unsafe { errno } != EINTR {
+            return res;
+        }
+        if restart == Restart::Never || !handlers_restart() {
+            return res;
+        }
+    }
+}
+
+/// Makes `f` a cancellation point: a thread with a cancel pending acts on it before `f`, and again
+/// if a signal interrupted `f`, which then returned -1 with errno set to EINTR. Only for the C
+/// functions POSIX makes cancellation points, called without any of relibc's locks held.
+pub fn cancellation_point<T: PartialEq + From<i8>>(f: impl FnOnce() -> T) -> T {
+    pte::test_cancel();
+    let res = f();
+    if res == T::from(-1) && unsafe { errno } == EINTR {
+        pte::test_cancel();
+    }
+    res
+}
+
+#[cfg(target_os = "redox")]
+fn handlers_restart() -> bool {
+    super::sys::signal::handlers_restart()
+}
+
+// The kernel already resumed the call if it was asked to
+#[cfg(not(target_os = "redox"))]
+fn handlers_restart() -> bool {
+    false
+}
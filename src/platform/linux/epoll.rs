@@ -1,5 +1,9 @@
 use super::{
-    super::{types::*, PalEpoll},
+    super::{
+        blocking::{self, Restart},
+        types::*,
+        PalEpoll,
+    },
     e, Sys,
 };
 use crate::header::{signal::sigset_t, sys_epoll::epoll_event};
@@ -20,7 +24,7 @@ impl PalEpoll for Sys {
         timeout: c_int,
         sigmask: *const sigset_t,
     ) -> c_int {
-        unsafe {
+        blocking::call(Restart::Never, || unsafe {
             e(syscall!(
                 EPOLL_PWAIT,
                 epfd,
@@ -29,6 +33,6 @@ impl PalEpoll for Sys {
                 timeout,
                 sigmask
             )) as c_int
-        }
+        })
     }
 }
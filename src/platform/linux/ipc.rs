@@ -0,0 +1,134 @@
+use core::ptr;
+
+use super::{
+    super::{types::*, PalIpc},
+    e, Sys,
+};
+use crate::{
+    c_str::CStr,
+    header::{
+        mqueue::mq_attr,
+        signal::sigevent,
+        sys_msg::msqid_ds,
+        sys_sem::{sembuf, semid_ds},
+        sys_shm::shmid_ds,
+        time::timespec,
+    },
+};
+
+impl PalIpc for Sys {
+    fn mq_getsetattr(mqdes: c_int, new: *const mq_attr, old: *mut mq_attr) -> c_int {
+        unsafe { e(syscall!(MQ_GETSETATTR, mqdes, new, old)) as c_int }
+    }
+
+    fn mq_notify(mqdes: c_int, sevp: *const sigevent) -> c_int {
+        // The kernel's struct is padded to 64 bytes, all of which it reads
+        let mut padded = [0u64; 8];
+        let sevp = if sevp.is_null() {
+            ptr::null()
+        } else {
+            unsafe {
+                ptr::copy_nonoverlapping(sevp, padded.as_mut_ptr() as *mut sigevent, 1);
+            }
+            padded.as_ptr()
+        };
+        unsafe { e(syscall!(MQ_NOTIFY, mqdes, sevp)) as c_int }
+    }
+
+    fn mq_open(name: &CStr, oflag: c_int, mode: mode_t, attr: *const mq_attr) -> c_int {
+        unsafe { e(syscall!(MQ_OPEN, name.as_ptr(), oflag, mode, attr)) as c_int }
+    }
+
+    unsafe fn mq_timedreceive(
+        mqdes: c_int,
+        msg_ptr: *mut c_char,
+        msg_len: size_t,
+        msg_prio: *mut c_uint,
+        abs_timeout: *const timespec,
+    ) -> ssize_t {
+        e(syscall!(
+            MQ_TIMEDRECEIVE,
+            mqdes,
+            msg_ptr,
+            msg_len,
+            msg_prio,
+            abs_timeout
+        )) as ssize_t
+    }
+
+    unsafe fn mq_timedsend(
+        mqdes: c_int,
+        msg_ptr: *const c_char,
+        msg_len: size_t,
+        msg_prio: c_uint,
+        abs_timeout: *const timespec,
+    ) -> c_int {
+        e(syscall!(
+            MQ_TIMEDSEND,
+            mqdes,
+            msg_ptr,
+            msg_len,
+            msg_prio,
+            abs_timeout
+        )) as c_int
+    }
+
+    fn mq_unlink(name: &CStr) -> c_int {
+        unsafe { e(syscall!(MQ_UNLINK, name.as_ptr())) as c_int }
+    }
+
+    fn msgctl(msqid: c_int, cmd: c_int, buf: *mut msqid_ds) -> c_int {
+        unsafe { e(syscall!(MSGCTL, msqid, cmd, buf)) as c_int }
+    }
+
+    fn msgget(key: key_t, msgflg: c_int) -> c_int {
+        unsafe { e(syscall!(MSGGET, key, msgflg)) as c_int }
+    }
+
+    fn msgrcv(
+        msqid: c_int,
+        msgp: *mut c_void,
+        msgsz: size_t,
+        msgtyp: c_long,
+        msgflg: c_int,
+    ) -> ssize_t {
+        unsafe { e(syscall!(MSGRCV, msqid, msgp, msgsz, msgtyp, msgflg)) as ssize_t }
+    }
+
+    fn msgsnd(msqid: c_int, msgp: *const c_void, msgsz: size_t, msgflg: c_int) -> c_int {
+        unsafe { e(syscall!(MSGSND, msqid, msgp, msgsz, msgflg)) as c_int }
+    }
+
+    fn semctl(semid: c_int, semnum: c_int, cmd: c_int, arg: c_ulong) -> c_int {
+        unsafe { e(syscall!(SEMCTL, semid, semnum, cmd, arg)) as c_int }
+    }
+
+    fn semget(key: key_t, nsems: c_int, semflg: c_int) -> c_int {
+        unsafe { e(syscall!(SEMGET, key, nsems, semflg)) as c_int }
+    }
+
+    fn semtimedop(
+        semid: c_int,
+        sops: *mut sembuf,
+        nsops: size_t,
+        timeout: *const timespec,
+    ) -> c_int {
+        unsafe { e(syscall!(SEMTIMEDOP, semid, sops, nsops, timeout)) as c_int }
+    }
+
+    unsafe fn shmat(shmid: c_int, shmaddr: *const c_void, shmflg: c_int) -> *mut c_void {
+        e(syscall!(SHMAT, shmid, shmaddr, shmflg)) as *mut c_void
+    }
+
+    fn shmctl(shmid: c_int, cmd: c_int, buf: *mut shmid_ds) -> c_int {
+        unsafe { e(syscall!(SHMCTL, shmid, cmd, buf)) as c_int }
+    }
+
+    unsafe fn shmdt(shmaddr: *const c_void) -> c_int {
+        e(syscall!(SHMDT, shmaddr)) as c_int
+    }
+
+    fn shmget(key: key_t, size: size_t, shmflg: c_int) -> c_int {
+        unsafe { e(syscall!(SHMGET, key, size, shmflg)) as c_int }
+    }
+}
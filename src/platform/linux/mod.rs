@@ -1,22 +1,42 @@
-use core::{arch::asm, ptr};
-use core_io::Write;
-
-use super::{errno, types::*, Pal};
+use alloc::vec::Vec;
+use core::{arch::asm, mem, ptr, str};
+use core_io::{Read, Write};
+
+use super::{
+    blocking::{self, Restart},
+    errno,
+    types::*,
+    Pal,
+};
 use crate::{
     c_str::CStr,
-    header::{dirent::dirent, signal::SIGCHLD, sys_stat::S_IFIFO},
+    fs::File,
+    header::{
+        dirent::dirent,
+        errno::{EEXIST, ENOSYS},
+        fcntl,
+        linux_sched::{self, clone_args, clone_fn},
+        signal::SIGCHLD,
+        sys_mman::MAP_FIXED_NOREPLACE,
+        sys_stat::S_IFIFO,
+    },
 };
 // use header::sys_resource::rusage;
 use crate::header::{
     sys_resource::rlimit,
     sys_stat::stat,
     sys_statvfs::statvfs,
+    sys_sysinfo::sysinfo,
     sys_time::{timeval, timezone},
 };
 // use header::sys_times::tms;
-use crate::header::{sys_utsname::utsname, time::timespec};
+use crate::header::{
+    sys_utsname::utsname,
+    time::{itimerspec, timespec},
+};
 
 mod epoll;
+mod ipc;
 mod ptrace;
 mod signal;
 mod socket;
@@ -26,6 +46,7 @@ const AT_EMPTY_PATH: c_int = 0x1000;
 const AT_REMOVEDIR: c_int = 0x200;
 
 const SYS_CLONE: usize = 56;
+const SYS_CLONE3: usize = 435;
 const CLONE_VM: usize = 0x0100;
 const CLONE_FS: usize = 0x0200;
 const CLONE_FILES: usize = 0x0400;
@@ -60,6 +81,49 @@ pub fn e(sys: usize) -> usize {
     }
 }
 
+/// Makes the clone or clone3 system call `nr` with `args`. A child with a `func` calls it with
+/// `arg` and exits with what it returns, one without returns 0 as the parent returns its ID.
+#[cfg(target_arch = "x86_64")]
+unsafe fn clone_raw(nr: usize, args: [usize; 5], func: usize, arg: *mut c_void) -> usize {
+    let ret;
+    asm!("
+        syscall
+
+        # Return in the parent, and in a child with nothing to call
+        test rax, rax
+        jnz 1f
+        test r12, r12
+        jz 1f
+
+        # Call the function on the child's stack
+        mov rdi, r13
+        and rsp, -16
+        call r12
+
+        # Exit with what it returned
+        mov edi, eax
+        mov eax, 60
+        syscall
+
+        # Invalid instruction on failure to exit
+        ud2
+
+        1:
+        ",
+        inout("rax") nr => ret,
+        in("rdi") args[0],
+        in("rsi") args[1],
+        in("rdx") args[2],
+        in("r10") args[3],
+        in("r8") args[4],
+        in("r12") func,
+        in("r13") arg,
+        out("rcx") _,
+        out("r11") _,
+    );
+    ret
+}
+
 pub struct Sys;
 
 impl Sys {
@@ -110,6 +174,56 @@ impl Pal for Sys {
         e(unsafe { syscall!(CLOCK_GETTIME, clk_id, tp) }) as c_int
     }
 
+    #[cfg(target_arch = "x86_64")]
+    unsafe fn clone3(args: &clone_args, func: clone_fn, arg: *mut c_void) -> pid_t {
+        let func = func.map_or(0, |func| func as usize);
+        if args.stack == 0 || args.stack_size != 0 {
+            let res = clone_raw(
+                SYS_CLONE3,
+                [
+                    args as *const clone_args as usize,
+                    mem::size_of::<clone_args>(),
+                    0,
+                    0,
+                    0,
+                ],
+                func,
+                arg,
+            );
+            if res as isize != -(ENOSYS as isize) {
+                return e(res) as pid_t;
+            }
+        }
+
+        // Linux before 5.3 only has clone, which takes the top of the stack and can't be given
+        // the flags of clone3 only, an exit signal that doesn't fit with the flags or thread IDs
+        if args.flags >> 32 != 0
+            || args.exit_signal & !(linux_sched::CSIGNAL as u64) != 0
+            || args.set_tid_size != 0
+        {
+            errno = ENOSYS;
+            return -1;
+        }
+        // It puts the pidfd where the parent's thread ID would go
+        let parent_tid = if args.flags & linux_sched::CLONE_PIDFD as u64 != 0 {
+            args.pidfd
+        } else {
+            args.parent_tid
+        };
+        e(clone_raw(
+            SYS_CLONE,
+            [
+                (args.flags | args.exit_signal) as usize,
+                (args.stack + args.stack_size) as usize,
+                parent_tid as usize,
+                args.child_tid as usize,
+                args.tls as usize,
+            ],
+            func,
+            arg,
+        )) as pid_t
+    }
+
     fn close(fildes: c_int) -> c_int {
         e(unsafe { syscall!(CLOSE, fildes) }) as c_int
     }
@@ -122,6 +236,10 @@ impl Pal for Sys {
         e(unsafe { syscall!(DUP3, fildes, fildes2, 0) }) as c_int
     }
 
+    fn eventfd(initval: c_uint, flags: c_int) -> c_int {
+        e(unsafe { syscall!(EVENTFD2, initval, flags) }) as c_int
+    }
+
     unsafe fn execve(path: &CStr, argv: *const *mut c_char, envp: *const *mut c_char) -> c_int {
         e(syscall!(EXECVE, path.as_ptr(), argv, envp)) as c_int
     }
@@ -133,6 +251,10 @@ impl Pal for Sys {
         loop {}
     }
 
+    fn exit_thread() -> ! {
+        Self::exit(0)
+    }
+
     fn fchdir(fildes: c_int) -> c_int {
         e(unsafe { syscall!(FCHDIR, fildes) }) as c_int
     }
@@ -146,7 +268,9 @@ impl Pal for Sys {
     }
 
     fn flock(fd: c_int, operation: c_int) -> c_int {
-        e(unsafe { syscall!(FLOCK, fd, operation) }) as c_int
+        blocking::call(Restart::IfAsked, || {
+            e(unsafe { syscall!(FLOCK, fd, operation) }) as c_int
+        })
     }
 
     fn fstat(fildes: c_int, buf: *mut stat) -> c_int {
@@ -219,6 +343,42 @@ impl Pal for Sys {
         e(unsafe { syscall!(UTIMENSAT, AT_FDCWD, path.as_ptr(), times, 0) }) as c_int
     }
 
+    fn get_nprocs() -> c_int {
+        // Counted in the affinity mask, like glibc, which has room for 1024 processors
+        let mut set = [0u64; 16];
+        let set_size = mem::size_of_val(&set);
+        if e(unsafe { syscall!(SCHED_GETAFFINITY, 0, set_size, set.as_mut_ptr()) }) == !0 {
+            return 1;
+        }
+        set.iter().map(|word| word.count_ones() as c_int).sum()
+    }
+
+    fn get_nprocs_conf() -> c_int {
+        let mut possible = Vec::new();
+        let read = File::open(c_str!("/sys/devices/system/cpu/possible"), fcntl::O_RDONLY)
+            .ok()
+            .and_then(|mut file| file.read_to_end(&mut possible).ok());
+        if read.is_none() {
+            return Self::get_nprocs();
+        }
+
+        // A list of ranges like "0-3,8"
+        let mut count = 0;
+        for range in str::from_utf8(&possible).unwrap_or("").trim().split(',') {
+            let mut bounds = range.splitn(2, '-').map(str::parse::<c_int>);
+            match (bounds.next(), bounds.next()) {
+                (Some(Ok(_)), None) => count += 1,
+                (Some(Ok(first)), Some(Ok(last))) if last >= first => count += last - first + 1,
+                _ => (),
+            }
+        }
+        if count > 0 {
+            count
+        } else {
+            Self::get_nprocs()
+        }
+    }
+
     fn getcwd(buf: *mut c_char, size: size_t) -> *mut c_char {
         if e(unsafe { syscall!(GETCWD, buf, size) }) == !0 {
             ptr::null_mut()
@@ -244,7 +404,7 @@ impl Pal for Sys {
     }
 
     fn getpagesize() -> usize {
-        4096
+        super::auxv(super::auxv_defs::AT_PAGESZ).unwrap_or(4096)
     }
 
     fn getpgid(pid: pid_t) -> pid_t {
@@ -283,6 +443,18 @@ impl Pal for Sys {
         e(unsafe { syscall!(GETUID) }) as uid_t
     }
 
+    fn inotify_add_watch(fd: c_int, path: &CStr, mask: u32) -> c_int {
+        e(unsafe { syscall!(INOTIFY_ADD_WATCH, fd, path.as_ptr(), mask) }) as c_int
+    }
+
+    fn inotify_init1(flags: c_int) -> c_int {
+        e(unsafe { syscall!(INOTIFY_INIT1, flags) }) as c_int
+    }
+
+    fn inotify_rm_watch(fd: c_int, wd: c_int) -> c_int {
+        e(unsafe { syscall!(INOTIFY_RM_WATCH, fd, wd) }) as c_int
+    }
+
     fn lchown(path: &CStr, owner: uid_t, group: gid_t) -> c_int {
         e(unsafe { syscall!(LCHOWN, path.as_ptr(), owner, group) }) as c_int
     }
@@ -312,6 +484,14 @@ impl Pal for Sys {
         e(unsafe { syscall!(MKNODAT, AT_FDCWD, path.as_ptr(), mode | S_IFIFO, 0) }) as c_int
     }
 
+    unsafe fn madvise(addr: *mut c_void, len: usize, advice: c_int) -> c_int {
+        e(syscall!(MADVISE, addr, len, advice)) as c_int
+    }
+
+    fn memfd_create(name: &CStr, flags: c_uint) -> c_int {
+        e(unsafe { syscall!(MEMFD_CREATE, name.as_ptr(), flags) }) as c_int
+    }
+
     unsafe fn mlock(addr: *const c_void, len: usize) -> c_int {
         e(syscall!(MLOCK, addr, len)) as c_int
     }
@@ -328,13 +508,37 @@ impl Pal for Sys {
         fildes: c_int,
         off: off_t,
     ) -> *mut c_void {
-        e(syscall!(MMAP, addr, len, prot, flags, fildes, off)) as *mut c_void
+        let res = e(syscall!(MMAP, addr, len, prot, flags, fildes, off)) as *mut c_void;
+        // Kernels before 4.17 take MAP_FIXED_NOREPLACE for a mere hint
+        if flags & MAP_FIXED_NOREPLACE != 0 && res != !0 as *mut c_void && res != addr {
+            syscall!(MUNMAP, res, len);
+            errno = EEXIST;
+            return !0 as *mut c_void;
+        }
+        res
     }
 
     unsafe fn mprotect(addr: *mut c_void, len: usize, prot: c_int) -> c_int {
         e(syscall!(MPROTECT, addr, len, prot)) as c_int
     }
 
+    unsafe fn mremap(
+        old_address: *mut c_void,
+        old_size: usize,
+        new_size: usize,
+        flags: c_int,
+        new_address: *mut c_void,
+    ) -> *mut c_void {
+        e(syscall!(
+            MREMAP,
+            old_address,
+            old_size,
+            new_size,
+            flags,
+            new_address
+        )) as *mut c_void
+    }
+
     unsafe fn msync(addr: *mut c_void, len: usize, flags: c_int) -> c_int {
         e(syscall!(MSYNC, addr, len, flags)) as c_int
     }
@@ -352,11 +556,15 @@ impl Pal for Sys {
     }
 
     fn nanosleep(rqtp: *const timespec, rmtp: *mut timespec) -> c_int {
-        e(unsafe { syscall!(NANOSLEEP, rqtp, rmtp) }) as c_int
+        blocking::call(Restart::Never, || {
+            e(unsafe { syscall!(NANOSLEEP, rqtp, rmtp) }) as c_int
+        })
     }
 
     fn open(path: &CStr, oflag: c_int, mode: mode_t) -> c_int {
-        e(unsafe { syscall!(OPENAT, AT_FDCWD, path.as_ptr(), oflag, mode) }) as c_int
+        blocking::call(Restart::IfAsked, || {
+            e(unsafe { syscall!(OPENAT, AT_FDCWD, path.as_ptr(), oflag, mode) }) as c_int
+        })
     }
 
     fn pipe2(fildes: &mut [c_int], flags: c_int) -> c_int {
@@ -417,7 +625,9 @@ impl Pal for Sys {
     }
 
     fn read(fildes: c_int, buf: &mut [u8]) -> ssize_t {
-        e(unsafe { syscall!(READ, fildes, buf.as_mut_ptr(), buf.len()) }) as ssize_t
+        blocking::call(Restart::IfAsked, || {
+            e(unsafe { syscall!(READ, fildes, buf.as_mut_ptr(), buf.len()) }) as ssize_t
+        })
     }
 
     fn readlink(pathname: &CStr, out: &mut [u8]) -> ssize_t {
@@ -444,10 +654,26 @@ impl Pal for Sys {
         e(unsafe { syscall!(SCHED_YIELD) }) as c_int
     }
 
+    fn sethostname(name: &[u8]) -> c_int {
+        e(unsafe { syscall!(SETHOSTNAME, name.as_ptr(), name.len()) }) as c_int
+    }
+
+    fn setns(fd: c_int, nstype: c_int) -> c_int {
+        e(unsafe { syscall!(SETNS, fd, nstype) }) as c_int
+    }
+
     fn setpgid(pid: pid_t, pgid: pid_t) -> c_int {
         e(unsafe { syscall!(SETPGID, pid, pgid) }) as c_int
     }
 
+    unsafe fn setrlimit(resource: c_int, rlim: *const rlimit) -> c_int {
+        e(syscall!(SETRLIMIT, resource, rlim)) as c_int
+    }
+
+    fn setsid() -> pid_t {
+        e(unsafe { syscall!(SETSID) }) as pid_t
+    }
+
     fn setregid(rgid: gid_t, egid: gid_t) -> c_int {
         e(unsafe { syscall!(SETREGID, rgid, egid) }) as c_int
     }
@@ -460,6 +686,27 @@ impl Pal for Sys {
         e(unsafe { syscall!(SYMLINKAT, path1.as_ptr(), AT_FDCWD, path2.as_ptr()) }) as c_int
     }
 
+    fn sysinfo(info: *mut sysinfo) -> c_int {
+        e(unsafe { syscall!(SYSINFO, info) }) as c_int
+    }
+
+    fn timerfd_create(clockid: c_int, flags: c_int) -> c_int {
+        e(unsafe { syscall!(TIMERFD_CREATE, clockid, flags) }) as c_int
+    }
+
+    fn timerfd_gettime(fd: c_int, curr_value: *mut itimerspec) -> c_int {
+        e(unsafe { syscall!(TIMERFD_GETTIME, fd, curr_value) }) as c_int
+    }
+
+    fn timerfd_settime(
+        fd: c_int,
+        flags: c_int,
+        new_value: *const itimerspec,
+        old_value: *mut itimerspec,
+    ) -> c_int {
+        e(unsafe { syscall!(TIMERFD_SETTIME, fd, flags, new_value, old_value) }) as c_int
+    }
+
     fn umask(mask: mode_t) -> mode_t {
         unsafe { syscall!(UMASK, mask) as mode_t }
     }
@@ -472,12 +719,20 @@ impl Pal for Sys {
         e(unsafe { syscall!(UNLINKAT, AT_FDCWD, path.as_ptr(), 0) }) as c_int
     }
 
+    fn unshare(flags: c_int) -> c_int {
+        e(unsafe { syscall!(UNSHARE, flags) }) as c_int
+    }
+
     fn waitpid(pid: pid_t, stat_loc: *mut c_int, options: c_int) -> pid_t {
-        e(unsafe { syscall!(WAIT4, pid, stat_loc, options, 0) }) as pid_t
+        blocking::call(Restart::IfAsked, || {
+            e(unsafe { syscall!(WAIT4, pid, stat_loc, options, 0) }) as pid_t
+        })
     }
 
     fn write(fildes: c_int, buf: &[u8]) -> ssize_t {
-        e(unsafe { syscall!(WRITE, fildes, buf.as_ptr(), buf.len()) }) as ssize_t
+        blocking::call(Restart::IfAsked, || {
+            e(unsafe { syscall!(WRITE, fildes, buf.as_ptr(), buf.len()) }) as ssize_t
+        })
     }
 
     fn verify() -> bool {
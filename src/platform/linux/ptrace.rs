@@ -2,9 +2,22 @@ use super::{
     super::{types::*, PalPtrace},
     e, Sys,
 };
+use crate::header::sys_ptrace::{PTRACE_PEEKDATA, PTRACE_PEEKTEXT, PTRACE_PEEKUSER};
 
 impl PalPtrace for Sys {
-    fn ptrace(request: c_int, pid: pid_t, addr: *mut c_void, data: *mut c_void) -> c_int {
-        unsafe { e(syscall!(PTRACE, request, pid, addr, data)) as c_int }
+    fn ptrace(request: c_int, pid: pid_t, addr: *mut c_void, data: *mut c_void) -> c_long {
+        match request {
+            // The kernel stores the word it peeks at in data, which libcs return instead
+            PTRACE_PEEKTEXT | PTRACE_PEEKDATA | PTRACE_PEEKUSER => {
+                let mut word: c_long = 0;
+                let res = e(unsafe { syscall!(PTRACE, request, pid, addr, &mut word) });
+                if res == !0 {
+                    -1
+                } else {
+                    word
+                }
+            }
+            _ => unsafe { e(syscall!(PTRACE, request, pid, addr, data)) as c_long },
+        }
     }
 }
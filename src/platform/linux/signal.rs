@@ -1,11 +1,11 @@
 use core::mem;
 
 use super::{
-    super::{types::*, PalSignal},
+    super::{types::*, Pal, PalSignal},
     e, Sys,
 };
 use crate::header::{
-    signal::{sigaction, sigset_t, stack_t},
+    signal::{__siginfo_rt, sigaction, siginfo_t, sigset_t, sigval, stack_t},
     sys_time::itimerval,
 };
 
@@ -54,4 +54,16 @@ impl PalSignal for Sys {
     fn sigprocmask(how: c_int, set: *const sigset_t, oset: *mut sigset_t) -> c_int {
         e(unsafe { syscall!(RT_SIGPROCMASK, how, set, oset, mem::size_of::<sigset_t>()) }) as c_int
     }
+
+    fn sigqueue(pid: pid_t, sig: c_int, code: c_int, value: sigval) -> c_int {
+        let mut info: siginfo_t = unsafe { mem::zeroed() };
+        info.si_signo = sig;
+        info.si_code = code;
+        info.__si_fields.__rt = __siginfo_rt {
+            si_pid: Sys::getpid(),
+            si_uid: Sys::getuid(),
+            si_value: value,
+        };
+        e(unsafe { syscall!(RT_SIGQUEUEINFO, pid, sig, &info as *const siginfo_t) }) as c_int
+    }
 }
@@ -2,13 +2,22 @@ use super::{
     super::{types::*, PalSocket},
     e, Sys,
 };
-use crate::header::sys_socket::{sockaddr, socklen_t};
+use crate::header::sys_socket::{msghdr, sockaddr, socklen_t};
 
 impl PalSocket for Sys {
     unsafe fn accept(socket: c_int, address: *mut sockaddr, address_len: *mut socklen_t) -> c_int {
         e(syscall!(ACCEPT, socket, address, address_len)) as c_int
     }
 
+    unsafe fn accept4(
+        socket: c_int,
+        address: *mut sockaddr,
+        address_len: *mut socklen_t,
+        flags: c_int,
+    ) -> c_int {
+        e(syscall!(ACCEPT4, socket, address, address_len, flags)) as c_int
+    }
+
     unsafe fn bind(socket: c_int, address: *const sockaddr, address_len: socklen_t) -> c_int {
         e(syscall!(BIND, socket, address, address_len)) as c_int
     }
@@ -75,6 +84,14 @@ impl PalSocket for Sys {
         )) as ssize_t
     }
 
+    unsafe fn recvmsg(socket: c_int, msg: *mut msghdr, flags: c_int) -> ssize_t {
+        e(syscall!(RECVMSG, socket, msg, flags)) as ssize_t
+    }
+
+    unsafe fn sendmsg(socket: c_int, msg: *const msghdr, flags: c_int) -> ssize_t {
+        e(syscall!(SENDMSG, socket, msg, flags)) as ssize_t
+    }
+
     unsafe fn sendto(
         socket: c_int,
         buf: *const c_void,
@@ -1,6 +1,6 @@
 use crate::io::{self, Read, Write};
-use alloc::{boxed::Box, vec::Vec};
-use core::{fmt, ptr};
+use alloc::vec::Vec;
+use core::{fmt, mem, ptr};
 
 pub use self::allocator::*;
 
@@ -12,11 +12,14 @@ mod allocator;
 #[path = "allocator/ralloc.rs"]
 mod allocator;
 
-pub use self::pal::{Pal, PalEpoll, PalPtrace, PalSignal, PalSocket};
+pub use self::pal::{Pal, PalEpoll, PalIpc, PalPtrace, PalSignal, PalSocket};
 
 mod pal;
 
-pub use self::sys::{e, Sys};
+pub use self::blocking::cancellation_point;
+mod blocking;
+
+pub use self::{sys::e, trace::Sys};
 
 #[cfg(all(not(feature = "no_std"), target_os = "linux"))]
 #[path = "linux/mod.rs"]
@@ -31,6 +34,9 @@ mod test;
 
 mod pte;
 
+pub use self::trace::init as init_trace;
+mod trace;
+
 pub use self::rlb::{Line, RawLineBuffer};
 pub mod rlb;
 
@@ -262,41 +268,72 @@ impl<T: Write> Write for CountingWriter<T> {
     }
 }
 
-// TODO: Set a global variable once get_auxvs is called, and then implement getauxval based on
-// get_auxv.
+/// One more than the largest type of entry the auxiliary vector table holds, which covers
+/// every one Linux and Redox give
+const AUXV_LEN: usize = 64;
 
-#[cold]
-pub unsafe fn get_auxvs(mut ptr: *const usize) -> Box<[[usize; 2]]> {
-    //traverse the stack and collect argument environment variables
-    let mut auxvs = Vec::new();
+/// The auxiliary vector, by type of entry
+static mut AUXV: [Option<usize>; AUXV_LEN] = [None; AUXV_LEN];
 
+/// Reads the auxiliary vector at `ptr` into the table getauxval reads. Nothing is allocated, so
+/// this can come first, before the allocator and the stack protector are set up.
+#[cold]
+pub unsafe fn init_auxv(mut ptr: *const usize) {
     while *ptr != self::auxv_defs::AT_NULL {
         let kind = ptr.read();
-        ptr = ptr.add(1);
-        let value = ptr.read();
-        ptr = ptr.add(1);
-        auxvs.push([kind, value]);
+        let value = ptr.add(1).read();
+        if let Some(entry) = AUXV.get_mut(kind) {
+            *entry = Some(value);
+        }
+        ptr = ptr.add(2);
     }
+}
+
+/// The entry of type `kind` of the auxiliary vector, if it was given
+pub fn auxv(kind: usize) -> Option<usize> {
+    unsafe { AUXV.get(kind).copied().flatten() }
+}
 
-    auxvs.sort_unstable_by_key(|[kind, _]| *kind);
-    auxvs.into_boxed_slice()
+/// Every entry of the auxiliary vector that was given, by type
+pub fn auxv_entries() -> impl Iterator<Item = (usize, usize)> {
+    (0..AUXV_LEN).filter_map(|kind| auxv(kind).map(|value| (kind, value)))
 }
-pub fn get_auxv(auxvs: &[[usize; 2]], key: usize) -> Option<usize> {
-    auxvs
-        .binary_search_by_key(&key, |[entry_key, _]| *entry_key)
-        .ok()
-        .map(|idx| auxvs[idx][1])
+
+extern "C" {
+    /// The canary the stack protector checks, where it isn't in the TCB
+    pub static mut __stack_chk_guard: usize;
+}
+
+/// Makes the canary the stack protector checks random, from the bytes the kernel gave or from
+/// getrandom without them, with the lowest byte 0 so that a string can't be read or written
+/// past it. It must be done before any function protected is called, since the canary it saved
+/// would no longer match. TCBs made after get it.
+#[cold]
+pub unsafe fn init_stack_guard() {
+    let mut guard = [0; mem::size_of::<usize>()];
+    match auxv(self::auxv_defs::AT_RANDOM) {
+        Some(random) if random != 0 => {
+            ptr::copy_nonoverlapping(random as *const u8, guard.as_mut_ptr(), guard.len())
+        }
+        _ => {
+            let flags = crate::header::sys_random::GRND_NONBLOCK;
+            // The default stays without either
+            if Sys::getrandom(&mut guard, flags) != guard.len() as ssize_t {
+                return;
+            }
+        }
+    }
+    __stack_chk_guard = usize::from_ne_bytes(guard) & !0xff;
 }
 
 #[cold]
 #[cfg(target_os = "redox")]
-pub fn init(auxvs: Box<[[usize; 2]]>) {
+pub fn init() {
     use self::auxv_defs::*;
 
-    if let (Some(cwd_ptr), Some(cwd_len)) = (
-        get_auxv(&auxvs, AT_REDOX_INITIALCWD_PTR),
-        get_auxv(&auxvs, AT_REDOX_INITIALCWD_LEN),
-    ) {
+    if let (Some(cwd_ptr), Some(cwd_len)) =
+        (auxv(AT_REDOX_INITIALCWD_PTR), auxv(AT_REDOX_INITIALCWD_LEN))
+    {
         let cwd_bytes: &'static [u8] =
             unsafe { core::slice::from_raw_parts(cwd_ptr as *const u8, cwd_len) };
         if let Ok(cwd) = core::str::from_utf8(cwd_bytes) {
@@ -305,4 +342,4 @@ pub fn init(auxvs: Box<[[usize; 2]]>) {
     }
 }
 #[cfg(not(target_os = "redox"))]
-pub fn init(auxvs: Box<[[usize; 2]]>) {}
+pub fn init() {}
@@ -0,0 +1,76 @@
+use super::super::{types::*, Pal};
+use crate::{
+    c_str::CStr,
+    header::{
+        mqueue::mq_attr,
+        signal::sigevent,
+        sys_msg::msqid_ds,
+        sys_sem::{sembuf, semid_ds},
+        sys_shm::shmid_ds,
+        time::timespec,
+    },
+};
+
+pub trait PalIpc: Pal {
+    /// `new` and `old` may each be null, as with the Linux system call.
+    fn mq_getsetattr(mqdes: c_int, new: *const mq_attr, old: *mut mq_attr) -> c_int;
+
+    /// Requests `sevp`'s notification, or cancels the request if it's null. SIGEV_THREAD means
+    /// what it does to the Linux system call, and isn't supported on Redox.
+    fn mq_notify(mqdes: c_int, sevp: *const sigevent) -> c_int;
+
+    /// `name` is without the leading slash, and `attr` is only used with O_CREAT.
+    fn mq_open(name: &CStr, oflag: c_int, mode: mode_t, attr: *const mq_attr) -> c_int;
+
+    unsafe fn mq_timedreceive(
+        mqdes: c_int,
+        msg_ptr: *mut c_char,
+        msg_len: size_t,
+        msg_prio: *mut c_uint,
+        abs_timeout: *const timespec,
+    ) -> ssize_t;
+
+    unsafe fn mq_timedsend(
+        mqdes: c_int,
+        msg_ptr: *const c_char,
+        msg_len: size_t,
+        msg_prio: c_uint,
+        abs_timeout: *const timespec,
+    ) -> c_int;
+
+    fn mq_unlink(name: &CStr) -> c_int;
+
+    fn msgctl(msqid: c_int, cmd: c_int, buf: *mut msqid_ds) -> c_int;
+
+    fn msgget(key: key_t, msgflg: c_int) -> c_int;
+
+    fn msgrcv(
+        msqid: c_int,
+        msgp: *mut c_void,
+        msgsz: size_t,
+        msgtyp: c_long,
+        msgflg: c_int,
+    ) -> ssize_t;
+
+    fn msgsnd(msqid: c_int, msgp: *const c_void, msgsz: size_t, msgflg: c_int) -> c_int;
+
+    /// `arg` is the `union semun` argument, which only some commands take.
+    fn semctl(semid: c_int, semnum: c_int, cmd: c_int, arg: c_ulong) -> c_int;
+
+    fn semget(key: key_t, nsems: c_int, semflg: c_int) -> c_int;
+
+    fn semtimedop(
+        semid: c_int,
+        sops: *mut sembuf,
+        nsops: size_t,
+        timeout: *const timespec,
+    ) -> c_int;
+
+    unsafe fn shmat(shmid: c_int, shmaddr: *const c_void, shmflg: c_int) -> *mut c_void;
+
+    fn shmctl(shmid: c_int, cmd: c_int, buf: *mut shmid_ds) -> c_int;
+
+    unsafe fn shmdt(shmaddr: *const c_void) -> c_int;
+
+    fn shmget(key: key_t, size: size_t, shmflg: c_int) -> c_int;
+}
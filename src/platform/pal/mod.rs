@@ -3,18 +3,23 @@ use crate::{
     c_str::CStr,
     header::{
         dirent::dirent,
+        linux_sched::{clone_args, clone_fn},
         sys_resource::rlimit,
         sys_stat::stat,
         sys_statvfs::statvfs,
+        sys_sysinfo::sysinfo,
         sys_time::{timeval, timezone},
         sys_utsname::utsname,
-        time::timespec,
+        time::{itimerspec, timespec},
     },
 };
 
 pub use self::epoll::PalEpoll;
 mod epoll;
 
+pub use self::ipc::PalIpc;
+mod ipc;
+
 pub use self::ptrace::PalPtrace;
 mod ptrace;
 
@@ -37,16 +42,26 @@ pub trait Pal {
 
     fn clock_gettime(clk_id: clockid_t, tp: *mut timespec) -> c_int;
 
+    /// Creates a child as clone3 does. The child calls `func` with `arg` on its stack and exits
+    /// with what it returns, or returns 0 if there's no `func`. A stack with no size is its top,
+    /// as clone takes it.
+    unsafe fn clone3(args: &clone_args, func: clone_fn, arg: *mut c_void) -> pid_t;
+
     fn close(fildes: c_int) -> c_int;
 
     fn dup(fildes: c_int) -> c_int;
 
     fn dup2(fildes: c_int, fildes2: c_int) -> c_int;
 
+    fn eventfd(initval: c_uint, flags: c_int) -> c_int;
+
     unsafe fn execve(path: &CStr, argv: *const *mut c_char, envp: *const *mut c_char) -> c_int;
 
     fn exit(status: c_int) -> !;
 
+    /// Exits the calling thread, which others outlive, without what ending the process does.
+    fn exit_thread() -> !;
+
     fn fchdir(fildes: c_int) -> c_int;
 
     fn fchmod(fildes: c_int, mode: mode_t) -> c_int;
@@ -75,6 +90,10 @@ pub trait Pal {
 
     fn utimens(path: &CStr, times: *const timespec) -> c_int;
 
+    fn get_nprocs() -> c_int;
+
+    fn get_nprocs_conf() -> c_int;
+
     fn getcwd(buf: *mut c_char, size: size_t) -> *mut c_char;
 
     fn getdents(fd: c_int, dirents: *mut dirent, bytes: usize) -> c_int;
@@ -108,6 +127,12 @@ pub trait Pal {
 
     fn getuid() -> uid_t;
 
+    fn inotify_add_watch(fd: c_int, path: &CStr, mask: u32) -> c_int;
+
+    fn inotify_init1(flags: c_int) -> c_int;
+
+    fn inotify_rm_watch(fd: c_int, wd: c_int) -> c_int;
+
     fn lchown(path: &CStr, owner: uid_t, group: gid_t) -> c_int;
 
     fn link(path1: &CStr, path2: &CStr) -> c_int;
@@ -118,6 +143,10 @@ pub trait Pal {
 
     fn mkfifo(path: &CStr, mode: mode_t) -> c_int;
 
+    unsafe fn madvise(addr: *mut c_void, len: usize, advice: c_int) -> c_int;
+
+    fn memfd_create(name: &CStr, flags: c_uint) -> c_int;
+
     unsafe fn mlock(addr: *const c_void, len: usize) -> c_int;
 
     fn mlockall(flags: c_int) -> c_int;
@@ -133,6 +162,14 @@ pub trait Pal {
 
     unsafe fn mprotect(addr: *mut c_void, len: usize, prot: c_int) -> c_int;
 
+    unsafe fn mremap(
+        old_address: *mut c_void,
+        old_size: usize,
+        new_size: usize,
+        flags: c_int,
+        new_address: *mut c_void,
+    ) -> *mut c_void;
+
     unsafe fn msync(addr: *mut c_void, len: usize, flags: c_int) -> c_int;
 
     unsafe fn munlock(addr: *const c_void, len: usize) -> c_int;
@@ -159,20 +196,43 @@ pub trait Pal {
 
     fn sched_yield() -> c_int;
 
+    fn sethostname(name: &[u8]) -> c_int;
+
+    fn setns(fd: c_int, nstype: c_int) -> c_int;
+
     fn setpgid(pid: pid_t, pgid: pid_t) -> c_int;
 
+    unsafe fn setrlimit(resource: c_int, rlim: *const rlimit) -> c_int;
+
+    fn setsid() -> pid_t;
+
     fn setregid(rgid: gid_t, egid: gid_t) -> c_int;
 
     fn setreuid(ruid: uid_t, euid: uid_t) -> c_int;
 
     fn symlink(path1: &CStr, path2: &CStr) -> c_int;
 
+    fn sysinfo(info: *mut sysinfo) -> c_int;
+
+    fn timerfd_create(clockid: c_int, flags: c_int) -> c_int;
+
+    fn timerfd_gettime(fd: c_int, curr_value: *mut itimerspec) -> c_int;
+
+    fn timerfd_settime(
+        fd: c_int,
+        flags: c_int,
+        new_value: *const itimerspec,
+        old_value: *mut itimerspec,
+    ) -> c_int;
+
     fn umask(mask: mode_t) -> mode_t;
 
     fn uname(utsname: *mut utsname) -> c_int;
 
     fn unlink(path: &CStr) -> c_int;
 
+    fn unshare(flags: c_int) -> c_int;
+
     fn waitpid(pid: pid_t, stat_loc: *mut c_int, options: c_int) -> pid_t;
 
     fn write(fildes: c_int, buf: &[u8]) -> ssize_t;
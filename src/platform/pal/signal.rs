@@ -1,6 +1,6 @@
 use super::super::{types::*, Pal};
 use crate::header::{
-    signal::{sigaction, sigset_t, stack_t},
+    signal::{sigaction, sigset_t, sigval, stack_t},
     sys_time::itimerval,
 };
 
@@ -20,4 +20,7 @@ pub trait PalSignal: Pal {
     fn sigaltstack(ss: *const stack_t, old_ss: *mut stack_t) -> c_int;
 
     fn sigprocmask(how: c_int, set: *const sigset_t, oset: *mut sigset_t) -> c_int;
+
+    /// Sends `sig` to `pid`, with `code` and `value` in the siginfo_t of its handler.
+    fn sigqueue(pid: pid_t, sig: c_int, code: c_int, value: sigval) -> c_int;
 }
@@ -1,9 +1,16 @@
 use super::super::{types::*, Pal};
-use crate::header::sys_socket::{sockaddr, socklen_t};
+use crate::header::sys_socket::{msghdr, sockaddr, socklen_t};
 
 pub trait PalSocket: Pal {
     unsafe fn accept(socket: c_int, address: *mut sockaddr, address_len: *mut socklen_t) -> c_int;
 
+    unsafe fn accept4(
+        socket: c_int,
+        address: *mut sockaddr,
+        address_len: *mut socklen_t,
+        flags: c_int,
+    ) -> c_int;
+
     unsafe fn bind(socket: c_int, address: *const sockaddr, address_len: socklen_t) -> c_int;
 
     unsafe fn connect(socket: c_int, address: *const sockaddr, address_len: socklen_t) -> c_int;
@@ -39,6 +46,10 @@ pub trait PalSocket: Pal {
         address_len: *mut socklen_t,
     ) -> ssize_t;
 
+    unsafe fn recvmsg(socket: c_int, msg: *mut msghdr, flags: c_int) -> ssize_t;
+
+    unsafe fn sendmsg(socket: c_int, msg: *const msghdr, flags: c_int) -> ssize_t;
+
     unsafe fn sendto(
         socket: c_int,
         buf: *const c_void,
@@ -2,13 +2,14 @@
 
 use alloc::{boxed::Box, collections::BTreeMap};
 use core::{
-    cell::UnsafeCell,
+    cell::{Cell, UnsafeCell},
     intrinsics, ptr,
-    sync::atomic::{AtomicU32, Ordering},
+    sync::atomic::{AtomicBool, AtomicPtr, AtomicU32, Ordering},
 };
 
 use crate::{
     header::{
+        errno::EINVAL,
         sys_mman,
         time::{clock_gettime, timespec, CLOCK_MONOTONIC},
     },
@@ -17,6 +18,7 @@ use crate::{
         tcb::{Master, Tcb},
     },
     platform::{
+        self,
         types::{c_int, c_long, c_uint, c_void, pid_t, size_t, time_t},
         Pal, Sys,
     },
@@ -49,19 +51,171 @@ static mut pid_mutexes_lock: Mutex<()> = Mutex::new(());
 static mut pid_stacks: Option<BTreeMap<pte_osThreadHandle, (*mut c_void, size_t)>> = None;
 static mut pid_stacks_lock: Mutex<()> = Mutex::new(());
 
+/// Where each thread's CANCEL is, for pte_osThreadCancel to set it
+static mut pid_cancels: Option<BTreeMap<pte_osThreadHandle, *const Cancel>> = None;
+static mut pid_cancels_lock: Mutex<()> = Mutex::new(());
+
+struct Cancel {
+    /// Set by pte_osThreadCancel, and acted on at the next cancellation point
+    pending: AtomicBool,
+    /// The semaphore pte_osSemaphoreCancellablePend is waiting for, which cancelling wakes
+    waiting: AtomicPtr<Semaphore>,
+}
+
+#[thread_local]
+static CANCEL: Cancel = Cancel {
+    pending: AtomicBool::new(false),
+    waiting: AtomicPtr::new(ptr::null_mut()),
+};
+
+/// Whether any thread was cancelled, so cancellation points don't read the TLS until then, as the
+/// dynamic linker makes its calls before there is any
+static ANY_CANCELLED: AtomicBool = AtomicBool::new(false);
+
 // TODO: VecMap/SLOB (speed) / radix tree (speed while allowing randomization for security).
 #[thread_local]
 static LOCALS: UnsafeCell<BTreeMap<c_uint, *mut c_void>> = UnsafeCell::new(BTreeMap::new());
 
 static NEXT_KEY: AtomicU32 = AtomicU32::new(0);
 
+/// pte's pthread_attr_t, a pointer to the attributes pthread_attr_init allocates
+type pthread_attr_t = *mut c_void;
+
+/// The guard sizes pthread_attr_setguardsize set, by the attributes they were set in, which pte
+/// has no field for
+static GUARD_SIZES: Mutex<BTreeMap<usize, size_t>> = Mutex::new(BTreeMap::new());
+
+/// The guard size of the thread pthread_create is creating, for pte_osThreadCreate, which it
+/// calls with the stack size of the attributes only
+#[thread_local]
+static GUARD_SIZE: Cell<Option<size_t>> = Cell::new(None);
+
+extern "C" {
+    // pte's, the Makefile renames them so that these can come first
+    fn __pte_pthread_create(
+        thread: *mut c_void,
+        attr: *const pthread_attr_t,
+        start: pte_osThreadEntryPoint,
+        arg: *mut c_void,
+    ) -> c_int;
+    fn __pte_pthread_attr_destroy(attr: *mut pthread_attr_t) -> c_int;
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_create(
+    thread: *mut c_void,
+    attr: *const pthread_attr_t,
+    start: pte_osThreadEntryPoint,
+    arg: *mut c_void,
+) -> c_int {
+    if !attr.is_null() {
+        GUARD_SIZE.set(GUARD_SIZES.lock().get(&(*attr as usize)).copied());
+    }
+    let result = __pte_pthread_create(thread, attr, start, arg);
+    GUARD_SIZE.set(None);
+    result
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_attr_destroy(attr: *mut pthread_attr_t) -> c_int {
+    if !attr.is_null() {
+        GUARD_SIZES.lock().remove(&(*attr as usize));
+    }
+    __pte_pthread_attr_destroy(attr)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_attr_setguardsize(
+    attr: *mut pthread_attr_t,
+    guardsize: size_t,
+) -> c_int {
+    if attr.is_null() || (*attr).is_null() {
+        return EINVAL;
+    }
+    GUARD_SIZES.lock().insert(*attr as usize, guardsize);
+    0
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pthread_attr_getguardsize(
+    attr: *const pthread_attr_t,
+    guardsize: *mut size_t,
+) -> c_int {
+    if attr.is_null() || (*attr).is_null() {
+        return EINVAL;
+    }
+    *guardsize = GUARD_SIZES
+        .lock()
+        .get(&(*attr as usize))
+        .copied()
+        .unwrap_or(Sys::getpagesize());
+    0
+}
+
 unsafe fn locals<'a>() -> &'a mut BTreeMap<c_uint, *mut c_void> {
     &mut *LOCALS.get()
 }
 
+/// Lets the calling thread be cancelled.
+unsafe fn register_cancel() {
+    pte_osMutexLock(&mut pid_cancels_lock);
+    if pid_cancels.is_none() {
+        pid_cancels = Some(BTreeMap::new());
+    }
+    pid_cancels.as_mut().unwrap().insert(Sys::gettid(), &CANCEL);
+    pte_osMutexUnlock(&mut pid_cancels_lock);
+}
+
+/// Whether `handle` has a cancel pending, setting it first if `cancel`.
+unsafe fn cancel_pending(handle: pte_osThreadHandle, cancel: bool) -> bool {
+    pte_osMutexLock(&mut pid_cancels_lock);
+    let mut pending = false;
+    if let Some(&state) = pid_cancels
+        .as_ref()
+        .and_then(|cancels| cancels.get(&handle))
+    {
+        if cancel {
+            ANY_CANCELLED.store(true, Ordering::SeqCst);
+            (*state).pending.store(true, Ordering::SeqCst);
+            // A wake may come between the thread's check and its sleep, so it's repeated until
+            // the thread saw the cancel. The thread then waits for the lock before the semaphore
+            // can be freed.
+            loop {
+                let semaphore = (*state).waiting.load(Ordering::SeqCst);
+                if semaphore.is_null() {
+                    break;
+                }
+                (*semaphore).wake();
+                Sys::sched_yield();
+            }
+        }
+        pending = (*state).pending.load(Ordering::SeqCst);
+    }
+    pte_osMutexUnlock(&mut pid_cancels_lock);
+    pending
+}
+
+/// Whether the calling thread has a cancel pending.
+fn cancelled() -> bool {
+    ANY_CANCELLED.load(Ordering::SeqCst) && CANCEL.pending.load(Ordering::SeqCst)
+}
+
+/// Acts on a cancel pending for the calling thread, unless it disabled cancellation. The
+/// cancellation points of the C functions call this.
+pub fn test_cancel() {
+    extern "C" {
+        fn pthread_testcancel();
+    }
+    if cancelled() {
+        unsafe { pthread_testcancel() };
+    }
+}
+
 // pte_osResult pte_osInit(void)
 #[no_mangle]
 pub unsafe extern "C" fn pte_osInit() -> pte_osResult {
+    // Called on the main thread, which other threads may cancel too
+    register_cancel();
     PTE_OS_OK
 }
 
@@ -87,6 +241,7 @@ unsafe extern "C" fn pte_osThreadShim(
         tcb.copy_masters().unwrap();
         tcb.activate();
     }
+    register_cancel();
 
     // Wait until pte_osThreadStart
     pte_osMutexLock(mutex);
@@ -105,22 +260,34 @@ pub unsafe extern "C" fn pte_osThreadCreate(
     // Create a locked mutex, unlocked by pte_osThreadStart
     let mutex: pte_osMutexHandle = Box::into_raw(Box::new(Mutex::locked(())));
 
-    let stack_size = if stackSize == 0 {
+    // The guard goes below the stack, a page of it unless the attributes asked otherwise
+    let page_size = Sys::getpagesize();
+    let stack_size = if stackSize <= 0 {
         1024 * 1024
     } else {
-        stackSize as usize
+        (stackSize as usize + page_size - 1) / page_size * page_size
+    };
+    let guard_size = match GUARD_SIZE.get() {
+        Some(size) => (size + page_size - 1) / page_size * page_size,
+        None => page_size,
     };
-    let stack_base = sys_mman::mmap(
+    let map_base = sys_mman::mmap(
         ptr::null_mut(),
-        stack_size,
+        guard_size + stack_size,
         sys_mman::PROT_READ | sys_mman::PROT_WRITE,
         sys_mman::MAP_SHARED | sys_mman::MAP_ANONYMOUS,
         -1,
         0,
     );
-    if stack_base as isize == -1 {
+    if map_base as isize == -1 {
         return PTE_OS_GENERAL_FAILURE;
     }
+    // An overflow faults there, rather than writing over whatever was mapped below
+    if guard_size > 0 && sys_mman::mprotect(map_base, guard_size, sys_mman::PROT_NONE) < 0 {
+        sys_mman::munmap(map_base, guard_size + stack_size);
+        return PTE_OS_GENERAL_FAILURE;
+    }
+    let stack_base = map_base.add(guard_size);
     let stack_end = stack_base.add(stack_size);
     let mut stack = stack_end as *mut usize;
     {
@@ -154,6 +321,7 @@ pub unsafe extern "C" fn pte_osThreadCreate(
 
     let id = Sys::pte_clone(stack);
     if id < 0 {
+        sys_mman::munmap(map_base, guard_size + stack_size);
         return PTE_OS_GENERAL_FAILURE;
     }
 
@@ -171,7 +339,7 @@ pub unsafe extern "C" fn pte_osThreadCreate(
     pid_stacks
         .as_mut()
         .unwrap()
-        .insert(id, (stack_base, stack_size));
+        .insert(id, (map_base, guard_size + stack_size));
     pte_osMutexUnlock(&mut pid_stacks_lock);
 
     *ppte_osThreadHandle = id;
@@ -195,7 +363,8 @@ pub unsafe extern "C" fn pte_osThreadStart(handle: pte_osThreadHandle) -> pte_os
 
 #[no_mangle]
 pub unsafe extern "C" fn pte_osThreadExit() {
-    Sys::exit(0);
+    platform::flush_cache();
+    Sys::exit_thread();
 }
 
 #[no_mangle]
@@ -227,6 +396,12 @@ pub unsafe extern "C" fn pte_osThreadDelete(handle: pte_osThreadHandle) -> pte_o
     }
     pte_osMutexUnlock(&mut pid_stacks_lock);
 
+    pte_osMutexLock(&mut pid_cancels_lock);
+    if let Some(ref mut cancels) = pid_cancels {
+        cancels.remove(&handle);
+    }
+    pte_osMutexUnlock(&mut pid_cancels_lock);
+
     PTE_OS_OK
 }
 
@@ -239,15 +414,18 @@ pub unsafe extern "C" fn pte_osThreadWaitForEnd(handle: pte_osThreadHandle) -> p
 
 #[no_mangle]
 pub unsafe extern "C" fn pte_osThreadCancel(handle: pte_osThreadHandle) -> pte_osResult {
-    //TODO: allow cancel of thread
-    eprintln!("pte_osThreadCancel");
+    // pte marked the cancel pending, the thread acts on it at a cancellation point
+    cancel_pending(handle, true);
     PTE_OS_OK
 }
 
 #[no_mangle]
 pub unsafe extern "C" fn pte_osThreadCheckCancel(handle: pte_osThreadHandle) -> pte_osResult {
-    //TODO: thread cancel
-    PTE_OS_OK
+    if cancel_pending(handle, false) {
+        PTE_OS_INTERRUPTED
+    } else {
+        PTE_OS_OK
+    }
 }
 
 #[no_mangle]
@@ -345,12 +523,9 @@ pub unsafe extern "C" fn pte_osSemaphorePost(
     PTE_OS_OK
 }
 
-#[no_mangle]
-pub unsafe extern "C" fn pte_osSemaphorePend(
-    handle: pte_osSemaphoreHandle,
-    pTimeout: *mut c_uint,
-) -> pte_osResult {
-    let timeout_opt = if !pTimeout.is_null() {
+/// The CLOCK_MONOTONIC time a wait of `*pTimeout` milliseconds ends at, or none without a timeout.
+unsafe fn deadline(pTimeout: *mut c_uint) -> Option<timespec> {
+    if !pTimeout.is_null() {
         // Get current time
         let mut time = timespec::default();
         clock_gettime(CLOCK_MONOTONIC, &mut time);
@@ -366,8 +541,15 @@ pub unsafe extern "C" fn pte_osSemaphorePend(
         Some(time)
     } else {
         None
-    };
-    match (*handle).wait(timeout_opt.as_ref()) {
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn pte_osSemaphorePend(
+    handle: pte_osSemaphoreHandle,
+    pTimeout: *mut c_uint,
+) -> pte_osResult {
+    match (*handle).wait(deadline(pTimeout).as_ref()) {
         Ok(()) => PTE_OS_OK,
         Err(()) => PTE_OS_TIMEOUT,
     }
@@ -378,8 +560,20 @@ pub unsafe extern "C" fn pte_osSemaphoreCancellablePend(
     handle: pte_osSemaphoreHandle,
     pTimeout: *mut c_uint,
 ) -> pte_osResult {
-    //TODO: thread cancel
-    pte_osSemaphorePend(handle, pTimeout)
+    // pte_osThreadCancel wakes the semaphore until this is cleared
+    CANCEL.waiting.store(handle, Ordering::SeqCst);
+    let res = (*handle).wait_unless(deadline(pTimeout).as_ref(), cancelled);
+    CANCEL.waiting.store(ptr::null_mut(), Ordering::SeqCst);
+    if ANY_CANCELLED.load(Ordering::SeqCst) {
+        // A pte_osThreadCancel may still be waking the semaphore, which the caller may free next
+        pte_osMutexLock(&mut pid_cancels_lock);
+        pte_osMutexUnlock(&mut pid_cancels_lock);
+    }
+    match res {
+        Ok(()) => PTE_OS_OK,
+        Err(()) if cancelled() => PTE_OS_INTERRUPTED,
+        Err(()) => PTE_OS_TIMEOUT,
+    }
 }
 
 #[no_mangle]
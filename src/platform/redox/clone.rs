@@ -1,82 +1,157 @@
-use core::{arch::global_asm, mem::size_of};
+use core::{
+    arch::global_asm,
+    mem::size_of,
+    sync::atomic::{AtomicUsize, Ordering},
+};
 
 use alloc::{boxed::Box, vec::Vec};
 
 use syscall::{
     data::Map,
-    error::{Error, Result, EINVAL, ENAMETOOLONG},
+    error::{Error, Result, EEXIST, EINVAL, ENAMETOOLONG},
     flag::{MapFlags, O_CLOEXEC},
-    SIGCONT,
+    PAGE_SIZE, SIGCONT,
 };
 
-use super::extra::{create_set_addr_space_buf, FdGuard};
+use super::extra::create_set_addr_space_buf;
+use crate::{c_str::CStr, fs::File, header::stdlib::getenv, sync::Once};
 
 pub use redox_exec::*;
 
-/// Spawns a new context sharing the same address space as the current one (i.e. a new thread).
-pub unsafe fn pte_clone_impl(stack: *mut usize) -> Result<usize> {
-    let cur_pid_fd = FdGuard::new(syscall::open("thisproc:current/open_via_dup", O_CLOEXEC)?);
-    let (new_pid_fd, new_pid) = new_context()?;
-
-    // Allocate a new signal stack.
-    {
-        let sigstack_fd = FdGuard::new(syscall::dup(*new_pid_fd, b"sigstack")?);
-
-        const SIGSTACK_SIZE: usize = 1024 * 256;
+const DEFAULT_SIGSTACK_SIZE: usize = 1024 * 256;
+const GUARD_SIZE: usize = PAGE_SIZE;
+
+static SIGSTACK_SIZE: Once<usize> = Once::new();
+
+/// The size of signal stacks, which RELIBC_SIGSTACK_SIZE can set in bytes, for handlers that need
+/// more than the default
+fn sigstack_size() -> usize {
+    *SIGSTACK_SIZE.call_once(|| {
+        let var = unsafe { getenv(c_str!("RELIBC_SIGSTACK_SIZE").as_ptr()) };
+        let size = if var.is_null() {
+            None
+        } else {
+            unsafe { CStr::from_ptr(var) }
+                .to_str()
+                .ok()
+                .and_then(|size| size.parse::<usize>().ok())
+        };
+        match size {
+            Some(size) => (size.max(PAGE_SIZE) + PAGE_SIZE - 1) / PAGE_SIZE * PAGE_SIZE,
+            None => DEFAULT_SIGSTACK_SIZE,
+        }
+    })
+}
 
-        // TODO: Put sigstack at high addresses?
-        let target_sigstack = syscall::fmap(
+/// Where signal stacks are put, going down from high in the address space, away from the
+/// mappings that are made from the bottom up
+#[cfg(target_pointer_width = "64")]
+const SIGSTACK_TOP: usize = 0x7000_0000_0000;
+#[cfg(target_pointer_width = "32")]
+const SIGSTACK_TOP: usize = 0xb000_0000;
+/// Where the next signal stack ends, below the last one
+static SIGSTACK_NEXT: AtomicUsize = AtomicUsize::new(SIGSTACK_TOP);
+
+/// Maps a signal stack with a guard page below it, returning its end. It goes high in the address
+/// space if there's room there, and anywhere otherwise.
+pub unsafe fn map_sigstack() -> Result<usize> {
+    let size = GUARD_SIZE + sigstack_size();
+    let flags = MapFlags::PROT_READ | MapFlags::PROT_WRITE | MapFlags::MAP_PRIVATE;
+
+    let mut address = SIGSTACK_NEXT.fetch_sub(size, Ordering::Relaxed) - size;
+    let mut base = None;
+    for _ in 0..64 {
+        let map = Map {
+            address,
+            flags: flags | MapFlags::MAP_FIXED_NOREPLACE,
+            offset: 0,
+            size,
+        };
+        match syscall::fmap(!0, &map) {
+            Ok(mapped) => {
+                // Past the ones found in the way
+                SIGSTACK_NEXT.fetch_min(mapped, Ordering::Relaxed);
+                base = Some(mapped);
+                break;
+            }
+            Err(err) if err.errno == EEXIST => address -= size,
+            Err(err) => return Err(err),
+        }
+    }
+    let base = match base {
+        Some(base) => base,
+        None => syscall::fmap(
             !0,
             &Map {
                 address: 0,
-                flags: MapFlags::PROT_READ | MapFlags::PROT_WRITE | MapFlags::MAP_PRIVATE,
+                flags,
                 offset: 0,
-                size: SIGSTACK_SIZE,
+                size,
             },
-        )? + SIGSTACK_SIZE;
+        )?,
+    };
+
+    // No access at all, so that overflowing faults
+    if let Err(err) = syscall::mprotect(base, GUARD_SIZE, MapFlags::empty()) {
+        let _ = syscall::funmap(base, size);
+        return Err(err);
+    }
+    Ok(base + size)
+}
+
+/// Spawns a new context sharing the same address space as the current one (i.e. a new thread).
+pub unsafe fn pte_clone_impl(stack: *mut usize) -> Result<usize> {
+    let cur_pid_fd = File::open_bare("thisproc:current/open_via_dup", O_CLOEXEC)?;
+    let (new_pid_fd, new_pid) = new_context()?;
+    let new_pid_fd = File::from(new_pid_fd);
+
+    // Allocate a new signal stack.
+    {
+        let sigstack_fd = new_pid_fd.dup_with(b"sigstack")?;
+        let target_sigstack = map_sigstack()?;
 
-        let _ = syscall::write(*sigstack_fd, &usize::to_ne_bytes(target_sigstack))?;
+        let _ = syscall::write(sigstack_fd.raw(), &usize::to_ne_bytes(target_sigstack))?;
     }
 
-    copy_str(*cur_pid_fd, *new_pid_fd, "name")?;
+    copy_str(cur_pid_fd.raw(), new_pid_fd.raw(), "name")?;
 
     // Reuse existing address space
     {
-        let cur_addr_space_fd = FdGuard::new(syscall::dup(*cur_pid_fd, b"addrspace")?);
-        let new_addr_space_sel_fd = FdGuard::new(syscall::dup(*new_pid_fd, b"current-addrspace")?);
+        let cur_addr_space_fd = cur_pid_fd.dup_with(b"addrspace")?;
+        let new_addr_space_sel_fd = new_pid_fd.dup_with(b"current-addrspace")?;
 
         let buf = create_set_addr_space_buf(
-            *cur_addr_space_fd,
+            cur_addr_space_fd.raw(),
             __relibc_internal_pte_clone_ret as usize,
             stack as usize,
         );
-        let _ = syscall::write(*new_addr_space_sel_fd, &buf)?;
+        let _ = syscall::write(new_addr_space_sel_fd.raw(), &buf)?;
     }
 
     // Reuse file table
     {
-        let cur_filetable_fd = FdGuard::new(syscall::dup(*cur_pid_fd, b"filetable")?);
-        let new_filetable_sel_fd = FdGuard::new(syscall::dup(*new_pid_fd, b"current-filetable")?);
+        let cur_filetable_fd = cur_pid_fd.dup_with(b"filetable")?;
+        let new_filetable_sel_fd = new_pid_fd.dup_with(b"current-filetable")?;
 
         let _ = syscall::write(
-            *new_filetable_sel_fd,
-            &usize::to_ne_bytes(*cur_filetable_fd),
+            new_filetable_sel_fd.raw(),
+            &usize::to_ne_bytes(cur_filetable_fd.raw()),
         )?;
     }
 
     // Reuse sigactions (on Linux, CLONE_THREAD requires CLONE_SIGHAND which implies the sigactions
     // table is reused).
     {
-        let cur_sigaction_fd = FdGuard::new(syscall::dup(*cur_pid_fd, b"sigactions")?);
-        let new_sigaction_sel_fd = FdGuard::new(syscall::dup(*new_pid_fd, b"current-sigactions")?);
+        let cur_sigaction_fd = cur_pid_fd.dup_with(b"sigactions")?;
+        let new_sigaction_sel_fd = new_pid_fd.dup_with(b"current-sigactions")?;
 
         let _ = syscall::write(
-            *new_sigaction_sel_fd,
-            &usize::to_ne_bytes(*cur_sigaction_fd),
+            new_sigaction_sel_fd.raw(),
+            &usize::to_ne_bytes(cur_sigaction_fd.raw()),
         )?;
     }
 
-    copy_env_regs(*cur_pid_fd, *new_pid_fd)?;
+    copy_env_regs(cur_pid_fd.raw(), new_pid_fd.raw())?;
 
     // Unblock context.
     syscall::kill(new_pid, SIGCONT)?;
@@ -85,6 +160,66 @@ pub unsafe fn pte_clone_impl(stack: *mut usize) -> Result<usize> {
     Ok(new_pid)
 }
 
+/// Creates a context that will run on `stack` in the current address space like a thread, but
+/// with a copy of the file table and of the signal actions, for posix_spawn to turn into the
+/// child. It's started with SIGCONT.
+///
+/// The copy of the file table was made while it had descriptors for doing so, which the new
+/// context has to close; these are returned with it. So is its table of signal actions, which
+/// the program gets in place of the empty one exec gives it, once the context has put the
+/// parent's handlers back to SIG_DFL.
+pub unsafe fn spawn_helper_impl(stack: *mut usize) -> Result<(File, usize, [usize; 2], File)> {
+    let cur_pid_fd = File::open_bare("thisproc:current/open_via_dup", O_CLOEXEC)?;
+    let (new_pid_fd, new_pid) = new_context()?;
+    let new_pid_fd = File::from(new_pid_fd);
+
+    copy_str(cur_pid_fd.raw(), new_pid_fd.raw(), "name")?;
+
+    // Reuse existing address space, which is replaced with the program once the context is ready
+    {
+        let cur_addr_space_fd = cur_pid_fd.dup_with(b"addrspace")?;
+        let new_addr_space_sel_fd = new_pid_fd.dup_with(b"current-addrspace")?;
+
+        let buf = create_set_addr_space_buf(
+            cur_addr_space_fd.raw(),
+            __relibc_internal_pte_clone_ret as usize,
+            stack as usize,
+        );
+        let _ = syscall::write(new_addr_space_sel_fd.raw(), &buf)?;
+    }
+
+    // Copy file table, so the file actions aren't seen by the parent
+    let inherited_fds;
+    {
+        let cur_filetable_fd = cur_pid_fd.dup_with(b"filetable")?;
+        let new_filetable_fd = cur_filetable_fd.dup_with(b"copy")?;
+        let new_filetable_sel_fd = new_pid_fd.dup_with(b"current-filetable")?;
+
+        let _ = syscall::write(
+            new_filetable_sel_fd.raw(),
+            &usize::to_ne_bytes(new_filetable_fd.raw()),
+        )?;
+        inherited_fds = [cur_filetable_fd.raw(), new_filetable_fd.raw()];
+    }
+
+    // Keep what's ignored, as the program must
+    let new_sigaction_fd;
+    {
+        let cur_sigaction_fd = cur_pid_fd.dup_with(b"sigactions")?;
+        new_sigaction_fd = cur_sigaction_fd.dup_with(b"copy")?;
+        let new_sigaction_sel_fd = new_pid_fd.dup_with(b"current-sigactions")?;
+
+        let _ = syscall::write(
+            new_sigaction_sel_fd.raw(),
+            &usize::to_ne_bytes(new_sigaction_fd.raw()),
+        )?;
+    }
+
+    copy_env_regs(cur_pid_fd.raw(), new_pid_fd.raw())?;
+
+    Ok((new_pid_fd, new_pid, inherited_fds, new_sigaction_fd))
+}
+
 extern "C" {
     fn __relibc_internal_pte_clone_ret();
 }
@@ -96,7 +231,7 @@ core::arch::global_asm!(
     .type __relibc_internal_pte_clone_ret, @function
     .p2align 6
 __relibc_internal_pte_clone_ret:
-    # Load registers
+    # Load registers, all eight arguments, which leaves the stack 16-byte aligned
     ldr x8, [sp], #8
     ldr x0, [sp], #8
     ldr x1, [sp], #8
@@ -104,6 +239,11 @@ __relibc_internal_pte_clone_ret:
     ldr x3, [sp], #8
     ldr x4, [sp], #8
     ldr x5, [sp], #8
+    ldr x6, [sp], #8
+    ldr x7, [sp], #8
+
+    # Default floating point control, like the MXCSR and x87 control word on x86
+    msr fpcr, xzr
 
     # Call entry point
     blr x8
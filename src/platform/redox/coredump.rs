@@ -0,0 +1,439 @@
+//! Core dumps, which Redox's kernel doesn't write, so relibc does from a handler of the signals
+//! that dump core by default, installed in place of SIG_DFL while RLIMIT_CORE isn't 0.
+//!
+//! The file is `core` in the working directory, as gdb reads Linux's: an ELF file of type ET_CORE
+//! with an NT_PRSTATUS note holding the signal and the registers, an NT_PRFPREG note, and a
+//! PT_LOAD segment for each readable mapping, cut off at RLIMIT_CORE bytes. Only the thread that
+//! received the signal is in it. The registers are those the kernel reports for it while the
+//! handler runs, which are what the signal interrupted on kernels that keep them aside, and are
+//! left 0 on architectures other than x86_64.
+//!
+//! Nothing here allocates or takes relibc's locks other than the working directory's, as the
+//! signal may have come while the heap's were held.
+
+use core::{
+    convert::TryInto,
+    mem, ptr, slice,
+    sync::atomic::{AtomicBool, AtomicU64, Ordering},
+};
+use syscall::{self, MapFlags};
+
+#[cfg(target_arch = "aarch64")]
+use crate::header::arch_aarch64_user::{
+    user_fpsimd_struct as user_fpregs_struct, user_regs_struct,
+};
+#[cfg(target_arch = "x86_64")]
+use crate::header::arch_x64_user::{user_fpregs_struct, user_regs_struct};
+use crate::{
+    fs::File,
+    header::{
+        signal::{
+            SIGABRT, SIGBUS, SIGFPE, SIGILL, SIGQUIT, SIGSEGV, SIGSYS, SIGTRAP, SIGXCPU, SIGXFSZ,
+        },
+        sys_resource::rlim_t,
+    },
+};
+
+use super::{
+    super::{types::*, Pal},
+    path, Sys, PAGE_SIZE,
+};
+
+/// The signals whose default action is to dump core.
+const SIGNALS: [usize; 10] = [
+    SIGQUIT, SIGILL, SIGTRAP, SIGABRT, SIGBUS, SIGFPE, SIGSEGV, SIGXCPU, SIGXFSZ, SIGSYS,
+];
+
+/// RLIMIT_CORE's soft limit, kept here for the handler.
+static LIMIT: AtomicU64 = AtomicU64::new(0);
+
+/// Set by the first thread to dump core, the others only die.
+static DUMPING: AtomicBool = AtomicBool::new(false);
+
+/// EM_X86_64
+#[cfg(target_arch = "x86_64")]
+const MACHINE: u16 = 62;
+/// EM_AARCH64
+#[cfg(target_arch = "aarch64")]
+const MACHINE: u16 = 183;
+
+const ET_CORE: u16 = 4;
+const PT_LOAD: u32 = 1;
+const PT_NOTE: u32 = 4;
+const PF_X: u32 = 1;
+const PF_W: u32 = 2;
+const PF_R: u32 = 4;
+const NT_PRSTATUS: u32 = 1;
+const NT_PRFPREG: u32 = 2;
+
+/// The most mappings the file has segments for.
+const MAX_GRANTS: usize = 512;
+
+#[repr(C)]
+struct Elf64Ehdr {
+    e_ident: [u8; 16],
+    e_type: u16,
+    e_machine: u16,
+    e_version: u32,
+    e_entry: u64,
+    e_phoff: u64,
+    e_shoff: u64,
+    e_flags: u32,
+    e_ehsize: u16,
+    e_phentsize: u16,
+    e_phnum: u16,
+    e_shentsize: u16,
+    e_shnum: u16,
+    e_shstrndx: u16,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Default)]
+struct Elf64Phdr {
+    p_type: u32,
+    p_flags: u32,
+    p_offset: u64,
+    p_vaddr: u64,
+    p_paddr: u64,
+    p_filesz: u64,
+    p_memsz: u64,
+    p_align: u64,
+}
+
+#[repr(C)]
+struct Elf64Nhdr {
+    n_namesz: u32,
+    n_descsz: u32,
+    n_type: u32,
+}
+
+/// What Linux's NT_PRSTATUS holds, with the times left 0.
+#[repr(C)]
+struct ElfPrstatus {
+    si_signo: c_int,
+    si_code: c_int,
+    si_errno: c_int,
+    pr_cursig: c_short,
+    pr_sigpend: c_ulong,
+    pr_sighold: c_ulong,
+    pr_pid: pid_t,
+    pr_ppid: pid_t,
+    pr_pgrp: pid_t,
+    pr_sid: pid_t,
+    pr_times: [c_long; 8],
+    pr_reg: user_regs_struct,
+    pr_fpvalid: c_int,
+}
+
+/// The readable mappings, the address, the size and the segment's flags of each.
+static mut GRANTS: [(usize, usize, u32); MAX_GRANTS] = [(0, 0, 0); MAX_GRANTS];
+
+/// Sets RLIMIT_CORE's soft limit, installing `dump` for the signals left at SIG_DFL if it's no
+/// longer 0 and putting SIG_DFL back if it is.
+pub fn set_limit(limit: rlim_t) {
+    LIMIT.store(limit as u64, Ordering::Relaxed);
+    for &sig in SIGNALS.iter() {
+        let mut old = syscall::SigAction::default();
+        if syscall::sigaction(sig, None, Some(&mut old)).is_err() {
+            continue;
+        }
+        let handler = if limit != 0 && old.sa_handler.is_none() {
+            Some(dump as extern "C" fn(usize))
+        } else if limit == 0 && is_dump(old.sa_handler) {
+            None
+        } else {
+            continue;
+        };
+        let new = syscall::SigAction {
+            sa_handler: handler,
+            ..old
+        };
+        let _ = syscall::sigaction(sig, Some(&new), None);
+    }
+}
+
+/// What the kernel is to call for `sig` when sigaction is given `handler`, which is `dump` in
+/// place of SIG_DFL while cores are written.
+pub fn handler_for(
+    sig: usize,
+    handler: Option<extern "C" fn(usize)>,
+) -> Option<extern "C" fn(usize)> {
+    if handler.is_none() && LIMIT.load(Ordering::Relaxed) != 0 && SIGNALS.contains(&sig) {
+        Some(dump)
+    } else {
+        handler
+    }
+}
+
+/// Whether the kernel calls `dump`, which sigaction reports as SIG_DFL.
+pub fn is_dump(handler: Option<extern "C" fn(usize)>) -> bool {
+    handler.map_or(false, |handler| handler as usize == dump as usize)
+}
+
+/// Writes the core file, then lets the signal kill the process as SIG_DFL would have.
+extern "C" fn dump(sig: usize) {
+    let _ = syscall::sigaction(sig, Some(&syscall::SigAction::default()), None);
+
+    if !DUMPING.swap(true, Ordering::SeqCst) {
+        unsafe {
+            let _ = write_core(sig);
+        }
+    }
+
+    // Blocked until the handler returns, when it kills the process. A fault would have happened
+    // again anyway.
+    let _ = syscall::kill(syscall::getpid().unwrap_or(0), sig);
+}
+
+/// Opens `core` next to the working directory, making the path without allocating.
+fn open_core() -> syscall::Result<File> {
+    const NAME: &[u8] = b"/core";
+    let mut buf = [0_u8; 4096];
+    let len = path::getcwd(&mut buf[..4096 - NAME.len()])
+        .ok_or(syscall::Error::new(syscall::ENAMETOOLONG))?;
+    buf[len..len + NAME.len()].copy_from_slice(NAME);
+    let path = core::str::from_utf8(&buf[..len + NAME.len()])
+        .map_err(|_| syscall::Error::new(syscall::ENOENT))?;
+    let flags = syscall::O_WRONLY | syscall::O_CREAT | syscall::O_TRUNC | syscall::O_CLOEXEC;
+    Ok(File::open_bare(path, flags | 0o600)?)
+}
+
+/// The file being written, which stops growing at RLIMIT_CORE.
+struct Core {
+    fd: File,
+    offset: u64,
+    limit: u64,
+}
+
+impl Core {
+    fn write(&mut self, bytes: &[u8]) -> syscall::Result<()> {
+        let room = self.limit.saturating_sub(self.offset) as usize;
+        let mut bytes = &bytes[..bytes.len().min(room)];
+        while !bytes.is_empty() {
+            match syscall::write(self.fd.raw(), bytes) {
+                Ok(0) => return Err(syscall::Error::new(syscall::EIO)),
+                Ok(n) => {
+                    bytes = &bytes[n..];
+                    self.offset += n as u64;
+                }
+                Err(err) if err.errno == syscall::EINTR => (),
+                Err(err) => return Err(err),
+            }
+        }
+        Ok(())
+    }
+
+    fn write_struct<T>(&mut self, value: &T) -> syscall::Result<()> {
+        self.write(unsafe {
+            slice::from_raw_parts(value as *const T as *const u8, mem::size_of::<T>())
+        })
+    }
+
+    fn write_zeros(&mut self, mut len: u64) -> syscall::Result<()> {
+        let zeros = [0_u8; 512];
+        while len > 0 {
+            let n = len.min(zeros.len() as u64);
+            self.write(&zeros[..n as usize])?;
+            len -= n;
+        }
+        Ok(())
+    }
+
+    /// Writes a note, its name and what it holds each padded to 4 bytes.
+    fn write_note<T>(&mut self, kind: u32, desc: &T) -> syscall::Result<()> {
+        self.write_struct(&Elf64Nhdr {
+            n_namesz: 5,
+            n_descsz: mem::size_of::<T>() as u32,
+            n_type: kind,
+        })?;
+        self.write(b"CORE\0\0\0\0")?;
+        self.write_struct(desc)?;
+        self.write_zeros(note_padding(mem::size_of::<T>()) as u64)
+    }
+}
+
+fn note_padding(len: usize) -> usize {
+    (4 - len % 4) % 4
+}
+
+fn note_size<T>() -> usize {
+    mem::size_of::<Elf64Nhdr>() + 8 + mem::size_of::<T>() + note_padding(mem::size_of::<T>())
+}
+
+/// Lists the readable mappings in GRANTS, as the address space describes them: four words each,
+/// the address, the size, the flags and the offset.
+unsafe fn read_grants(pid_fd: &File) -> syscall::Result<usize> {
+    let addrspace_fd = pid_fd.dup_with(b"addrspace")?;
+    const GRANT_SIZE: usize = mem::size_of::<usize>() * 4;
+    let mut buf = [0_u8; 4096];
+    let mut len = 0;
+    let mut count = 0;
+
+    loop {
+        let read = syscall::read(addrspace_fd.raw(), &mut buf[len..])?;
+        if read == 0 {
+            break;
+        }
+        len += read;
+        let whole = len / GRANT_SIZE * GRANT_SIZE;
+
+        for grant in buf[..whole].chunks_exact(GRANT_SIZE) {
+            let mut words = grant
+                .chunks_exact(mem::size_of::<usize>())
+                .map(|word| usize::from_ne_bytes(word.try_into().unwrap()));
+            let addr = words.next().unwrap();
+            let size = words.next().unwrap();
+            let flags = MapFlags::from_bits_truncate(words.next().unwrap());
+
+            if !flags.contains(MapFlags::PROT_READ) || size == 0 || count == MAX_GRANTS {
+                continue;
+            }
+            let mut segment_flags = PF_R;
+            if flags.contains(MapFlags::PROT_WRITE) {
+                segment_flags |= PF_W;
+            }
+            if flags.contains(MapFlags::PROT_EXEC) {
+                segment_flags |= PF_X;
+            }
+            GRANTS[count] = (addr, size, segment_flags);
+            count += 1;
+        }
+        buf.copy_within(whole..len, 0);
+        len -= whole;
+    }
+    Ok(count)
+}
+
+unsafe fn prstatus(sig: usize, pid_fd: &File) -> ElfPrstatus {
+    let mut status: ElfPrstatus = mem::zeroed();
+    status.si_signo = sig as c_int;
+    status.pr_cursig = sig as c_short;
+    status.pr_pid = Sys::getpid();
+    status.pr_ppid = Sys::getppid();
+    status.pr_pgrp = Sys::getpgid(0);
+
+    #[cfg(target_arch = "x86_64")]
+    {
+        if let Ok(fd) = pid_fd.dup_with(b"regs/int") {
+            let mut regs = syscall::IntRegisters::default();
+            if syscall::read(fd.raw(), &mut regs).is_ok() {
+                status.pr_reg = super::ptrace::user_regs(&regs);
+            }
+        }
+    }
+    #[cfg(not(target_arch = "x86_64"))]
+    let _ = pid_fd;
+    status
+}
+
+/// The floating point registers, laid out like the area FXSAVE stores as PTRACE_GETFPREGS has
+/// them, or None if they can't be read.
+unsafe fn fpregs(pid_fd: &File) -> Option<user_fpregs_struct> {
+    #[cfg(target_arch = "x86_64")]
+    {
+        let fd = pid_fd.dup_with(b"regs/float").ok()?;
+        let mut redox_fpregs = syscall::FloatRegisters::default();
+        syscall::read(fd.raw(), &mut redox_fpregs).ok()?;
+        let mut fpregs: user_fpregs_struct = mem::zeroed();
+        ptr::copy_nonoverlapping(
+            &redox_fpregs as *const _ as *const u8,
+            &mut fpregs as *mut _ as *mut u8,
+            mem::size_of::<syscall::FloatRegisters>().min(mem::size_of::<user_fpregs_struct>()),
+        );
+        Some(fpregs)
+    }
+    #[cfg(not(target_arch = "x86_64"))]
+    {
+        let _ = pid_fd;
+        None
+    }
+}
+
+unsafe fn write_core(sig: usize) -> syscall::Result<()> {
+    let pid_fd = File::open_bare("thisproc:current/open_via_dup", syscall::O_CLOEXEC)?;
+    let count = read_grants(&pid_fd)?;
+    let mut status = prstatus(sig, &pid_fd);
+    let fpregs = fpregs(&pid_fd);
+    status.pr_fpvalid = fpregs.is_some() as c_int;
+
+    let mut core = Core {
+        fd: open_core()?,
+        offset: 0,
+        limit: LIMIT.load(Ordering::Relaxed),
+    };
+
+    let phnum = count + 1;
+    let notes_offset = mem::size_of::<Elf64Ehdr>() + phnum * mem::size_of::<Elf64Phdr>();
+    let mut notes_size = note_size::<ElfPrstatus>();
+    if fpregs.is_some() {
+        notes_size += note_size::<user_fpregs_struct>();
+    }
+    let data_offset = (notes_offset + notes_size + PAGE_SIZE - 1) / PAGE_SIZE * PAGE_SIZE;
+
+    let mut e_ident = [0_u8; 16];
+    e_ident[..4].copy_from_slice(b"\x7fELF");
+    // 64 bits, little-endian, version 1
+    e_ident[4] = 2;
+    e_ident[5] = 1;
+    e_ident[6] = 1;
+    core.write_struct(&Elf64Ehdr {
+        e_ident,
+        e_type: ET_CORE,
+        e_machine: MACHINE,
+        e_version: 1,
+        e_entry: 0,
+        e_phoff: mem::size_of::<Elf64Ehdr>() as u64,
+        e_shoff: 0,
+        e_flags: 0,
+        e_ehsize: mem::size_of::<Elf64Ehdr>() as u16,
+        e_phentsize: mem::size_of::<Elf64Phdr>() as u16,
+        e_phnum: phnum as u16,
+        e_shentsize: 0,
+        e_shnum: 0,
+        e_shstrndx: 0,
+    })?;
+
+    core.write_struct(&Elf64Phdr {
+        p_type: PT_NOTE,
+        p_offset: notes_offset as u64,
+        p_filesz: notes_size as u64,
+        ..Elf64Phdr::default()
+    })?;
+    let mut offset = data_offset as u64;
+    for &(addr, size, flags) in GRANTS[..count].iter() {
+        core.write_struct(&Elf64Phdr {
+            p_type: PT_LOAD,
+            p_flags: flags,
+            p_offset: offset,
+            p_vaddr: addr as u64,
+            p_paddr: 0,
+            p_filesz: size as u64,
+            p_memsz: size as u64,
+            p_align: PAGE_SIZE as u64,
+        })?;
+        offset += size as u64;
+    }
+
+    core.write_note(NT_PRSTATUS, &status)?;
+    if let Some(fpregs) = fpregs {
+        core.write_note(NT_PRFPREG, &fpregs)?;
+    }
+    core.write_zeros(data_offset as u64 - core.offset)?;
+
+    // The kernel copies the memory, so pages it can't read fail the write instead of faulting
+    for &(addr, size, _) in GRANTS[..count].iter() {
+        for page in (addr..addr + size).step_by(PAGE_SIZE) {
+            let start = core.offset;
+            if core
+                .write(slice::from_raw_parts(page as *const u8, PAGE_SIZE))
+                .is_err()
+            {
+                core.write_zeros(PAGE_SIZE as u64 - (core.offset - start))?;
+            }
+            if core.offset >= core.limit {
+                return Ok(());
+            }
+        }
+    }
+    Ok(())
+}
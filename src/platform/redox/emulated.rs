@@ -0,0 +1,156 @@
+//! The descriptors of the eventfd, timerfd and inotify emulations, whose reads and writes are
+//! redirected to state kept in this process, and of epoll instances.
+//!
+//! One table maps each such descriptor to its state. Every call that closes or duplicates a
+//! descriptor, close, dup, dup2 and fcntl with F_DUPFD or F_DUPFD_CLOEXEC, updates it through
+//! [`closed`] and [`duplicated`], so that duplicates share the state, whichever call made them,
+//! and it goes away with the last of them.
+
+use alloc::{collections::BTreeMap, sync::Arc, vec::Vec};
+use core::sync::atomic::{AtomicUsize, Ordering};
+use syscall::{Error, Result, EINVAL};
+
+use super::{
+    epoll::Epoll, eventfd::EventFd, inotify::Inotify, memfd, mman, socket, timerfd::TimerFd,
+};
+use crate::{
+    header::poll,
+    platform::types::*,
+    sync::{fork::ForkLock, Mutex},
+};
+
+#[derive(Clone)]
+pub enum Emulated {
+    EventFd(Arc<EventFd>),
+    TimerFd(Arc<TimerFd>),
+    Inotify(Arc<Inotify>),
+    Epoll(Arc<Epoll>),
+}
+
+impl Emulated {
+    fn fork_lock(&self) -> &dyn ForkLock {
+        match self {
+            Emulated::EventFd(event) => event.fork_lock(),
+            Emulated::TimerFd(timer) => timer.fork_lock(),
+            Emulated::Inotify(inotify) => inotify.fork_lock(),
+            Emulated::Epoll(epoll) => epoll.fork_lock(),
+        }
+    }
+}
+
+static FDS: Mutex<BTreeMap<c_int, Emulated>> = Mutex::new(BTreeMap::new());
+/// The number of descriptors in FDS, which spares other reads and writes the lock
+static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+pub fn get(fd: c_int) -> Option<Emulated> {
+    if COUNT.load(Ordering::Relaxed) == 0 {
+        return None;
+    }
+    FDS.lock().get(&fd).cloned()
+}
+
+pub fn insert(fd: c_int, emulated: Emulated) {
+    if FDS.lock().insert(fd, emulated).is_none() {
+        COUNT.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Called when `fd` is closed.
+pub fn closed(fd: c_int) {
+    if COUNT.load(Ordering::Relaxed) != 0 && FDS.lock().remove(&fd).is_some() {
+        COUNT.fetch_sub(1, Ordering::Relaxed);
+    }
+    poll::forget(fd);
+    mman::closed(fd as usize);
+    socket::closed(fd);
+    memfd::closed(fd);
+}
+
+/// Called when `new_fd` was made a duplicate of `fd`, closing what it was before.
+pub fn duplicated(fd: c_int, new_fd: c_int) {
+    if fd == new_fd {
+        return;
+    }
+    closed(new_fd);
+    if let Some(emulated) = get(fd) {
+        insert(new_fd, emulated);
+    }
+    mman::duplicated(fd as usize, new_fd as usize);
+    socket::duplicated(fd, new_fd);
+    memfd::duplicated(fd, new_fd);
+}
+
+/// Reads the state of `fd`, if it's emulated.
+pub fn read(fd: c_int, buf: &mut [u8]) -> Option<Result<usize>> {
+    Some(match get(fd)? {
+        Emulated::EventFd(event) => event.read(fd as usize, buf),
+        Emulated::TimerFd(timer) => timer.read(fd as usize, buf),
+        Emulated::Inotify(inotify) => inotify.read(fd as usize, buf),
+        // Reading an epoll descriptor reads its event queue
+        Emulated::Epoll(_) => return None,
+    })
+}
+
+/// Writes to the state of `fd`, if it's emulated. Only an eventfd can be written to.
+pub fn write(fd: c_int, buf: &[u8]) -> Option<Result<usize>> {
+    Some(match get(fd)? {
+        Emulated::EventFd(event) => event.write(buf),
+        Emulated::TimerFd(_) | Emulated::Inotify(_) => Err(Error::new(EINVAL)),
+        Emulated::Epoll(_) => return None,
+    })
+}
+
+/// The states in `fds`, once each, as duplicates share them
+fn states(fds: &BTreeMap<c_int, Emulated>) -> Vec<Emulated> {
+    let mut states: Vec<Emulated> = Vec::new();
+    for emulated in fds.values() {
+        if !states.iter().any(|other| same(other, emulated)) {
+            states.push(emulated.clone());
+        }
+    }
+    states
+}
+
+fn same(a: &Emulated, b: &Emulated) -> bool {
+    a.fork_lock() as *const dyn ForkLock as *const u8
+        == b.fork_lock() as *const dyn ForkLock as *const u8
+}
+
+/// The lock of the table and those of the states in it, which fork holds, so the child gets them
+/// in a consistent state. The states are locked before the table, as an epoll instance looks
+/// descriptors up while its lock is held.
+pub(crate) struct Descriptors;
+
+impl ForkLock for Descriptors {
+    unsafe fn prepare(&self) {
+        loop {
+            let held = states(&FDS.lock());
+            for emulated in &held {
+                emulated.fork_lock().prepare();
+            }
+            FDS.manual_lock();
+
+            // Descriptors may have been made or closed in the meantime
+            let now = states(FDS.manual_content());
+            if now.len() == held.len() && now.iter().all(|a| held.iter().any(|b| same(a, b))) {
+                return;
+            }
+            FDS.manual_unlock();
+            for emulated in &held {
+                emulated.fork_lock().parent();
+            }
+        }
+    }
+    unsafe fn parent(&self) {
+        for emulated in states(FDS.manual_content()) {
+            emulated.fork_lock().parent();
+        }
+        FDS.manual_unlock();
+    }
+    unsafe fn child(&self) {
+        for emulated in states(FDS.manual_content()) {
+            emulated.fork_lock().child();
+        }
+        FDS.manual_reinit();
+    }
+}
@@ -1,54 +1,333 @@
 use super::{
-    super::{types::*, Pal, PalEpoll},
-    Sys,
+    super::{
+        blocking::{self, Restart},
+        types::*,
+        Pal, PalEpoll, PalSignal,
+    },
+    e,
+    emulated::{self, Emulated},
+    signal, Sys,
 };
 
 use crate::{
     fs::File,
-    header::{errno::*, fcntl::*, signal::sigset_t, sys_epoll::*},
+    header::{
+        errno::*,
+        fcntl::*,
+        signal::{sigset_t, SIG_SETMASK},
+        sys_epoll::*,
+    },
     io::prelude::*,
     platform,
+    sync::{fork::ForkLock, Mutex},
 };
-use core::{mem, slice};
+use alloc::{collections::BTreeMap, sync::Arc, vec::Vec};
+use core::{mem, ptr, slice};
 use syscall::{
     data::{Event, TimeSpec},
-    flag::EVENT_READ,
+    flag::{EventFlags, EVENT_READ, EVENT_WRITE},
 };
 
+/// What a descriptor was registered for with epoll_ctl.
+#[derive(Clone, Copy)]
+struct Registration {
+    events: c_uint,
+    data: u64,
+    /// Cleared once an EPOLLONESHOT registration was reported, until EPOLL_CTL_MOD rearms it
+    armed: bool,
+}
+
+#[derive(Default)]
+struct Instance {
+    registrations: BTreeMap<c_int, Registration>,
+    /// The level-triggered descriptors reported by the previous wait
+    reported: Vec<c_int>,
+}
+
+/// An epoll instance, shared by the duplicates of the descriptor epoll_create1 returned and
+/// dropped with the last of them.
+///
+/// The event queue only reports readiness changes, so the epoll semantics are layered on top of
+/// it: the user data is kept here, as `Event::data` is narrower than 64 bits on 32-bit targets,
+/// level-triggered descriptors are registered again by the wait after the one reporting them,
+/// which makes the queue report them again if they are still ready by then, and one-shot
+/// descriptors are unregistered.
+pub struct Epoll {
+    instance: Mutex<Instance>,
+}
+
+impl Epoll {
+    /// The lock of the instance, which fork holds.
+    pub fn fork_lock(&self) -> &dyn ForkLock {
+        &self.instance
+    }
+}
+
+/// The instance of `epfd`, or EINVAL if it isn't an epoll descriptor.
+fn lookup(epfd: c_int) -> Option<Arc<Epoll>> {
+    match emulated::get(epfd) {
+        Some(Emulated::Epoll(epoll)) => Some(epoll),
+        _ => {
+            unsafe { platform::errno = EINVAL };
+            None
+        }
+    }
+}
+
+fn event_flags(events: c_uint) -> EventFlags {
+    let mut flags = EventFlags::empty();
+    if events & (EPOLLIN | EPOLLPRI | EPOLLRDNORM | EPOLLRDBAND | EPOLLRDHUP) != 0 {
+        flags |= EVENT_READ;
+    }
+    if events & (EPOLLOUT | EPOLLWRNORM | EPOLLWRBAND) != 0 {
+        flags |= EVENT_WRITE;
+    }
+    flags
+}
+
+/// Translates reported readiness back, limited to what was asked for, and to errors and hangups,
+/// which are always reported.
+fn epoll_events(flags: EventFlags, events: c_uint) -> c_uint {
+    let mut ready = 0;
+    if flags.contains(EVENT_READ) {
+        ready |= EPOLLIN | EPOLLRDNORM;
+    }
+    if flags.contains(EVENT_WRITE) {
+        ready |= EPOLLOUT | EPOLLWRNORM;
+    }
+    ready & (events | EPOLLERR | EPOLLHUP)
+}
+
+fn register(epfd: c_int, fd: c_int, flags: EventFlags) -> c_int {
+    Sys::write(
+        epfd,
+        &Event {
+            id: fd as usize,
+            flags,
+            data: 0,
+        },
+    ) as c_int
+}
+
+/// A timer on the event queue, for the timeout of a single wait.
+struct Timeout {
+    epfd: c_int,
+    timer: File,
+}
+
+impl Timeout {
+    fn start(epfd: c_int, timeout: c_int) -> Option<Self> {
+        // time:4 is CLOCK_MONOTONIC
+        let timer = File::open(c_str!("time:4"), O_RDWR).ok()?;
+        if register(epfd, timer.fd, EVENT_READ) < 0 {
+            return None;
+        }
+        let mut timeout_guard = Self { epfd, timer };
+
+        let mut time = TimeSpec::default();
+        timeout_guard.timer.read(&mut time).ok()?;
+        time.tv_sec += (timeout / 1000) as i64;
+        time.tv_nsec += (timeout % 1000) * 1_000_000;
+        if time.tv_nsec >= 1_000_000_000 {
+            time.tv_sec += 1;
+            time.tv_nsec -= 1_000_000_000;
+        }
+        timeout_guard.timer.write(&time).ok()?;
+
+        Some(timeout_guard)
+    }
+}
+
+impl Drop for Timeout {
+    fn drop(&mut self) {
+        // Descriptors are reused, so don't leave the timer behind on the queue
+        register(self.epfd, self.timer.fd, EventFlags::empty());
+    }
+}
+
+/// Waits for the events of `epoll`, or, if `wake` is set, until that descriptor is readable, which
+/// makes the wait fail with EINTR.
+fn wait(
+    epfd: c_int,
+    epoll: &Epoll,
+    events: &mut [epoll_event],
+    timeout: c_int,
+    wake: Option<c_int>,
+) -> c_int {
+    let timeout = if timeout >= 0 {
+        match Timeout::start(epfd, timeout) {
+            Some(timeout) => Some(timeout),
+            None => return -1,
+        }
+    } else {
+        None
+    };
+    let timer_id = timeout.as_ref().map(|timeout| timeout.timer.fd as usize);
+    let wake_id = wake.map(|fd| fd as usize);
+
+    {
+        // Registering them only now, rather than when they were reported, keeps a descriptor that
+        // was drained in between from being reported as still ready
+        let mut instance = epoll.instance.lock();
+        for fd in mem::take(&mut instance.reported) {
+            match instance.registrations.get(&fd) {
+                Some(registration)
+                    if registration.armed
+                        && registration.events & (EPOLLET | EPOLLONESHOT) == 0 =>
+                {
+                    register(epfd, fd, event_flags(registration.events));
+                }
+                _ => (),
+            }
+        }
+    }
+
+    let mut queued = vec![Event::default(); events.len()];
+    loop {
+        // Not Sys::read, which would resume after a handler with SA_RESTART, as epoll doesn't
+        let bytes_read = e(syscall::read(epfd as usize, unsafe {
+            slice::from_raw_parts_mut(
+                queued.as_mut_ptr() as *mut u8,
+                queued.len() * mem::size_of::<Event>(),
+            )
+        })) as ssize_t;
+        if bytes_read == -1 {
+            return -1;
+        }
+        let read = bytes_read as usize / mem::size_of::<Event>();
+
+        let mut instance = epoll.instance.lock();
+        let instance = &mut *instance;
+        let registrations = &mut instance.registrations;
+        let mut reported: Vec<c_int> = Vec::new();
+        let mut timed_out = false;
+        let mut interrupted = false;
+        for event in &queued[..read] {
+            if Some(event.id) == timer_id {
+                timed_out = true;
+                continue;
+            }
+            if Some(event.id) == wake_id {
+                interrupted = true;
+                continue;
+            }
+
+            let fd = event.id as c_int;
+            let registration = match registrations.get_mut(&fd) {
+                Some(registration) if registration.armed => registration,
+                // Unregistered since, or a one-shot registration that already fired
+                _ => continue,
+            };
+            let ready = epoll_events(event.flags, registration.events);
+            if ready == 0 {
+                continue;
+            }
+
+            // The queue may report reading and writing separately, epoll merges them
+            match reported.iter().position(|&other| other == fd) {
+                Some(i) => events[i].events |= ready,
+                None => {
+                    events[reported.len()] = epoll_event {
+                        events: ready,
+                        data: epoll_data {
+                            u64: registration.data,
+                        },
+                        ..Default::default()
+                    };
+                    reported.push(fd);
+                }
+            }
+
+            if registration.events & EPOLLONESHOT != 0 {
+                registration.armed = false;
+                register(epfd, fd, EventFlags::empty());
+            }
+        }
+
+        if !reported.is_empty() || timed_out {
+            let count = reported.len() as c_int;
+            instance.reported = reported;
+            return count;
+        }
+        if interrupted {
+            unsafe { platform::errno = EINTR };
+            return -1;
+        }
+    }
+}
+
 impl PalEpoll for Sys {
     fn epoll_create1(flags: c_int) -> c_int {
-        Sys::open(c_str!("event:"), O_RDWR | flags, 0)
+        if flags & !EPOLL_CLOEXEC != 0 {
+            unsafe { platform::errno = EINVAL };
+            return -1;
+        }
+        let epfd = Sys::open(c_str!("event:"), O_RDWR | flags, 0);
+        if epfd >= 0 {
+            let epoll = Epoll {
+                instance: Mutex::new(Instance::default()),
+            };
+            emulated::insert(epfd, Emulated::Epoll(Arc::new(epoll)));
+        }
+        epfd
     }
 
     fn epoll_ctl(epfd: c_int, op: c_int, fd: c_int, event: *mut epoll_event) -> c_int {
+        if fd == epfd {
+            unsafe { platform::errno = EINVAL };
+            return -1;
+        }
+
+        let epoll = match lookup(epfd) {
+            Some(epoll) => epoll,
+            None => return -1,
+        };
+        let mut instance = epoll.instance.lock();
+        let registrations = &mut instance.registrations;
         match op {
             EPOLL_CTL_ADD | EPOLL_CTL_MOD => {
-                Sys::write(
-                    epfd,
-                    &Event {
-                        id: fd as usize,
-                        flags: syscall::EventFlags::from_bits(unsafe { (*event).events as usize })
-                            .expect("epoll: invalid bit pattern"),
-                        // NOTE: Danger when using something smaller than 64-bit
-                        // systems. If this is needed, use a box or something
-                        data: unsafe { (*event).data.u64 as usize },
+                if event.is_null() {
+                    unsafe { platform::errno = EFAULT };
+                    return -1;
+                }
+                if op == EPOLL_CTL_MOD && !registrations.contains_key(&fd) {
+                    unsafe { platform::errno = ENOENT };
+                    return -1;
+                }
+                // A closed descriptor fails with EBADF, rather than never being reported
+                if Sys::fcntl(fd, F_GETFD, 0) < 0 {
+                    return -1;
+                }
+                let (events, data) = unsafe { ((*event).events, (*event).data.u64) };
+
+                // Closing a descriptor doesn't remove it here, so rather than failing with
+                // EEXIST, adding one again replaces what may be a stale registration.
+                if register(epfd, fd, event_flags(events)) < 0 {
+                    return -1;
+                }
+                registrations.insert(
+                    fd,
+                    Registration {
+                        events,
+                        data,
+                        armed: true,
                     },
-                ) as c_int
+                );
+                0
             }
             EPOLL_CTL_DEL => {
-                Sys::write(
-                    epfd,
-                    &Event {
-                        id: fd as usize,
-                        flags: syscall::EventFlags::empty(),
-                        //TODO: Is data required?
-                        data: 0,
-                    },
-                ) as c_int
+                if registrations.remove(&fd).is_none() {
+                    unsafe { platform::errno = ENOENT };
+                    return -1;
+                }
+                if register(epfd, fd, EventFlags::empty()) < 0 {
+                    return -1;
+                }
+                0
             }
             _ => {
                 unsafe { platform::errno = EINVAL };
-                return -1;
+                -1
             }
         }
     }
@@ -58,74 +337,51 @@ impl PalEpoll for Sys {
         events: *mut epoll_event,
         maxevents: c_int,
         timeout: c_int,
-        _sigset: *const sigset_t,
+        sigmask: *const sigset_t,
     ) -> c_int {
-        // TODO: sigset
-        assert_eq!(mem::size_of::<epoll_event>(), mem::size_of::<Event>());
-
-        let timer_opt = if timeout != -1 {
-            match File::open(c_str!("time:4"), O_RDWR) {
-                Err(_) => return -1,
-                Ok(mut timer) => {
-                    if Sys::write(
-                        epfd,
-                        &Event {
-                            id: timer.fd as usize,
-                            flags: EVENT_READ,
-                            data: 0,
-                        },
-                    ) == -1
-                    {
-                        return -1;
-                    }
-
-                    let mut time = TimeSpec::default();
-                    if let Err(err) = timer.read(&mut time) {
-                        return -1;
-                    }
-                    time.tv_sec += (timeout as i64) / 1000;
-                    time.tv_nsec += (timeout % 1000) * 1000000;
-                    if let Err(err) = timer.write(&time) {
-                        return -1;
-                    }
-
-                    Some(timer)
-                }
-            }
-        } else {
-            None
+        if maxevents <= 0 {
+            unsafe { platform::errno = EINVAL };
+            return -1;
+        }
+        let events = unsafe { slice::from_raw_parts_mut(events, maxevents as usize) };
+        let epoll = match lookup(epfd) {
+            Some(epoll) => epoll,
+            None => return -1,
         };
+        if sigmask.is_null() {
+            return blocking::call(Restart::Never, || wait(epfd, &epoll, events, timeout, None));
+        }
 
-        let bytes_read = Sys::read(epfd, unsafe {
-            slice::from_raw_parts_mut(events as *mut u8, maxevents as usize)
-        });
-        if bytes_read == -1 {
+        // There is no kernel primitive to wait with a different mask, so it's swapped around the
+        // wait. A signal the new mask unblocks may be handled before the read blocks, which then
+        // wouldn't be interrupted. So the handlers that run meanwhile write to a pipe whose read
+        // end is waited for too.
+        let mut fds = [0; 2];
+        if e(syscall::pipe2(
+            &mut fds,
+            syscall::O_CLOEXEC | syscall::O_NONBLOCK,
+        )) == !0
+        {
             return -1;
         }
-        let read = bytes_read as usize / mem::size_of::<syscall::Event>();
-
-        let mut count = 0;
-        for i in 0..read {
-            unsafe {
-                let event_ptr = events.add(i);
-                let event = *(event_ptr as *mut Event);
-                if let Some(ref timer) = timer_opt {
-                    if event.id as c_int == timer.fd {
-                        // Do not count timer event
-                        continue;
-                    }
-                }
-                *event_ptr = epoll_event {
-                    events: event.flags.bits() as _,
-                    data: epoll_data {
-                        u64: event.data as u64,
-                    },
-                    ..Default::default()
-                };
-                count += 1;
-            }
+        let (wake, _wake_write) = (File::new(fds[0] as c_int), File::new(fds[1] as c_int));
+        if register(epfd, *wake, EVENT_READ) < 0 {
+            return -1;
         }
-
-        count as c_int
+        signal::wake_on_handler(fds[1]);
+        let mut old_mask: sigset_t = 0;
+        let count = if Sys::sigprocmask(SIG_SETMASK, sigmask, &mut old_mask) < 0 {
+            -1
+        } else {
+            let count = blocking::call(Restart::Never, || {
+                wait(epfd, &epoll, events, timeout, Some(*wake))
+            });
+            Sys::sigprocmask(SIG_SETMASK, &old_mask, ptr::null_mut());
+            count
+        };
+        signal::stop_waking();
+        // Descriptors are reused, so don't leave the pipe behind on the queue
+        register(epfd, *wake, EventFlags::empty());
+        count
     }
 }
@@ -0,0 +1,114 @@
+//! eventfd emulation, as Redox has no scheme for it.
+//!
+//! The descriptor handed out is the read end of a pipe, which holds a single byte whenever the
+//! counter is nonzero, so that poll, select and epoll see it as readable exactly then. Reads and
+//! writes on it are intercepted to operate on the counter instead, which lives in this process:
+//! unlike on Linux, a child doesn't share it after fork.
+
+use alloc::sync::Arc;
+use core::{convert::TryInto, mem};
+use syscall::{self, flag::*, Error, Result, EAGAIN, EINVAL};
+
+use super::emulated::{self, Emulated};
+use crate::{
+    header::sys_eventfd::{EFD_CLOEXEC, EFD_NONBLOCK, EFD_SEMAPHORE},
+    platform::types::*,
+    sync::{fork::ForkLock, Mutex},
+};
+
+pub struct EventFd {
+    /// The write end of the pipe
+    signal: usize,
+    semaphore: bool,
+    counter: Mutex<u64>,
+}
+
+impl Drop for EventFd {
+    fn drop(&mut self) {
+        let _ = syscall::close(self.signal);
+    }
+}
+
+impl EventFd {
+    /// The lock of the state, which fork holds.
+    pub fn fork_lock(&self) -> &dyn ForkLock {
+        &self.counter
+    }
+
+    pub fn read(&self, fd: usize, buf: &mut [u8]) -> Result<usize> {
+        if buf.len() < mem::size_of::<u64>() {
+            return Err(Error::new(EINVAL));
+        }
+
+        // Taking the byte blocks, or fails with EAGAIN, for as long as the counter is zero
+        let mut byte = [0];
+        syscall::read(fd, &mut byte)?;
+
+        let mut counter = self.counter.lock();
+        let value = if self.semaphore { 1 } else { *counter };
+        *counter -= value;
+        if *counter > 0 {
+            syscall::write(self.signal, &byte)?;
+        }
+        buf[..mem::size_of::<u64>()].copy_from_slice(&value.to_ne_bytes());
+        Ok(mem::size_of::<u64>())
+    }
+
+    pub fn write(&self, buf: &[u8]) -> Result<usize> {
+        let value = match buf.get(..mem::size_of::<u64>()) {
+            Some(bytes) => u64::from_ne_bytes(bytes.try_into().unwrap()),
+            None => return Err(Error::new(EINVAL)),
+        };
+        if value == u64::MAX {
+            return Err(Error::new(EINVAL));
+        }
+
+        let mut counter = self.counter.lock();
+        // Linux blocks a write that would overflow the counter, waiting for reads is not
+        // supported here
+        if u64::MAX - 1 - *counter < value {
+            return Err(Error::new(EAGAIN));
+        }
+        if *counter == 0 && value > 0 {
+            syscall::write(self.signal, &[0])?;
+        }
+        *counter += value;
+        Ok(mem::size_of::<u64>())
+    }
+}
+
+pub fn eventfd(initval: c_uint, flags: c_int) -> Result<usize> {
+    if flags & !(EFD_CLOEXEC | EFD_NONBLOCK | EFD_SEMAPHORE) != 0 {
+        return Err(Error::new(EINVAL));
+    }
+
+    // The write end is internal, so it's always closed on exec
+    let mut fds = [0; 2];
+    let mut pipe_flags = O_CLOEXEC;
+    if flags & EFD_NONBLOCK != 0 {
+        pipe_flags |= O_NONBLOCK;
+    }
+    syscall::pipe2(&mut fds, pipe_flags)?;
+    let event = Arc::new(EventFd {
+        signal: fds[1],
+        semaphore: flags & EFD_SEMAPHORE != 0,
+        counter: Mutex::new(initval.into()),
+    });
+
+    let setup = (|| -> Result<()> {
+        if flags & EFD_CLOEXEC == 0 {
+            syscall::fcntl(fds[0], F_SETFD, 0)?;
+        }
+        if initval > 0 {
+            syscall::write(event.signal, &[0])?;
+        }
+        Ok(())
+    })();
+    if let Err(err) = setup {
+        let _ = syscall::close(fds[0]);
+        return Err(err);
+    }
+
+    emulated::insert(fds[0] as c_int, Emulated::EventFd(event));
+    Ok(fds[0])
+}
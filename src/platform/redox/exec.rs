@@ -4,8 +4,9 @@ use crate::{
     fs::File,
     header::{fcntl, string::strlen},
     platform::{
-        sys::{S_ISGID, S_ISUID},
+        sys::{Sys, S_ISGID, S_ISUID},
         types::*,
+        Pal,
     },
 };
 
@@ -21,12 +22,9 @@ fn fexec_impl(
     extrainfo: &ExtraInfo,
     interp_override: Option<redox_exec::InterpOverride>,
 ) -> Result<usize> {
-    let fd = *file;
-    core::mem::forget(file);
-    let image_file = FdGuard::new(fd as usize);
-
-    let open_via_dup = FdGuard::new(syscall::open("thisproc:current/open_via_dup", 0)?);
-    let memory = FdGuard::new(syscall::open("memory:", 0)?);
+    let image_file = FdGuard::from(file);
+    let open_via_dup = FdGuard::from(File::open_bare("thisproc:current/open_via_dup", 0)?);
+    let memory = FdGuard::from(File::open_bare("memory:", 0)?);
 
     let addrspace_selection_fd = match redox_exec::fexec_impl(
         image_file,
@@ -72,26 +70,8 @@ fn fexec_impl(
 
     unreachable!();
 }
-pub enum ArgEnv<'a> {
-    C {
-        argv: *const *mut c_char,
-        envp: *const *mut c_char,
-    },
-    Parsed {
-        args: &'a [&'a [u8]],
-        envs: &'a [&'a [u8]],
-        total_args_envs_size: usize,
-    },
-}
-pub fn execve(
-    path: &CStr,
-    arg_env: ArgEnv,
-    interp_override: Option<redox_exec::InterpOverride>,
-) -> Result<usize> {
-    // NOTE: We must omit O_CLOEXEC and close manually, otherwise it will be closed before we
-    // have even read it!
-    let mut image_file = File::open(path, O_RDONLY as c_int).map_err(|_| Error::new(ENOENT))?;
-
+/// Checks that `image_file` may be executed, returning whether it's setuid or setgid.
+pub fn check_executable(image_file: &File) -> Result<bool> {
     // With execve now being implemented in userspace, we need to check ourselves that this
     // file is actually executable. While checking for read permission is unnecessary as the
     // scheme will not allow us to read otherwise, the execute bit is completely unenforced. We
@@ -103,7 +83,7 @@ pub fn execve(
     // when the binary needs setuid/setgid.
 
     let mut stat = Stat::default();
-    syscall::fstat(*image_file as usize, &mut stat)?;
+    syscall::fstat(**image_file as usize, &mut stat)?;
     let uid = syscall::getuid()?;
     let gid = syscall::getuid()?;
 
@@ -118,7 +98,81 @@ pub fn execve(
     if mode & 0o1 == 0o0 {
         return Err(Error::new(EPERM));
     }
-    let wants_setugid = stat.st_mode & ((S_ISUID | S_ISGID) as u16) != 0;
+    Ok(stat.st_mode & ((S_ISUID | S_ISGID) as u16) != 0)
+}
+
+/// Opens the program to execute.
+fn open_image(path: &CStr) -> Result<File> {
+    // NOTE: We must omit O_CLOEXEC, which File::open adds, and close manually, otherwise it will
+    // be closed before we have even read it!
+    match Sys::open(path, O_RDONLY as c_int, 0) {
+        -1 => Err(Error::new(ENOENT)),
+        fd => Ok(File::new(fd)),
+    }
+}
+
+/// Closes all O_CLOEXEC file descriptors of the current file table. TODO: close_range?
+///
+/// This only makes system calls, and neither allocates nor goes through Sys::close, because
+/// posix_spawn's helper runs it in its parent's address space, on its own copy of the file table.
+pub fn close_cloexec() -> Result<()> {
+    let files_fd = File::open_bare("thisproc:current/filetable", O_RDONLY)?;
+    let mut buf = [0_u8; 4096];
+    // The start of a line the last read cut off
+    let mut kept = 0;
+    loop {
+        let read = syscall::read(files_fd.raw(), &mut buf[kept..])?;
+        let end = kept + read;
+        let mut start = 0;
+        while let Some(len) = buf[start..end].iter().position(|&b| b == b'\n') {
+            close_if_cloexec(&buf[start..start + len])?;
+            start += len + 1;
+        }
+        if read == 0 {
+            return close_if_cloexec(&buf[start..end]);
+        }
+        // A line can't be anywhere near the size of the buffer
+        buf.copy_within(start..end, 0);
+        kept = end - start;
+    }
+}
+
+fn close_if_cloexec(line: &[u8]) -> Result<()> {
+    let fd = match core::str::from_utf8(line)
+        .ok()
+        .and_then(|line| line.parse::<usize>().ok())
+    {
+        Some(fd) => fd,
+        None => return Ok(()),
+    };
+
+    let flags = syscall::fcntl(fd, F_GETFD, 0)?;
+
+    if flags & O_CLOEXEC == O_CLOEXEC {
+        let _ = syscall::close(fd);
+    }
+    Ok(())
+}
+
+pub enum ArgEnv<'a> {
+    C {
+        argv: *const *mut c_char,
+        envp: *const *mut c_char,
+    },
+    Parsed {
+        args: &'a [&'a [u8]],
+        envs: &'a [&'a [u8]],
+        total_args_envs_size: usize,
+    },
+}
+pub fn execve(
+    path: &CStr,
+    arg_env: ArgEnv,
+    interp_override: Option<redox_exec::InterpOverride>,
+) -> Result<usize> {
+    let mut image_file = open_image(path)?;
+
+    let wants_setugid = check_executable(&image_file)?;
 
     let cwd: Box<[u8]> = super::path::clone_cwd().unwrap_or_default().into();
 
@@ -177,7 +231,7 @@ pub fn execve(
             interpreter.pop().unwrap();
         }
         let cstring = CString::new(interpreter).map_err(|_| Error::new(ENOEXEC))?;
-        image_file = File::open(&cstring, O_RDONLY as c_int).map_err(|_| Error::new(ENOENT))?;
+        image_file = open_image(&cstring)?;
 
         // Make sure path is kept alive long enough, and push it to the arguments
         _interpreter_path = Some(cstring);
@@ -235,37 +289,18 @@ pub fn execve(
         }
     };
 
-    // Close all O_CLOEXEC file descriptors. TODO: close_range?
-    {
-        // NOTE: This approach of implementing O_CLOEXEC will not work in multithreaded
-        // scenarios. While execve() is undefined according to POSIX if there exist sibling
-        // threads, it could still be allowed by keeping certain file descriptors and instead
-        // set the active file table.
-        let files_fd = File::new(syscall::open("thisproc:current/filetable", O_RDONLY)? as c_int);
-        for line in BufReader::new(files_fd).lines() {
-            let line = match line {
-                Ok(l) => l,
-                Err(_) => break,
-            };
-            let fd = match line.parse::<usize>() {
-                Ok(f) => f,
-                Err(_) => continue,
-            };
-
-            let flags = syscall::fcntl(fd, F_GETFD, 0)?;
-
-            if flags & O_CLOEXEC == O_CLOEXEC {
-                let _ = syscall::close(fd);
-            }
-        }
-    }
+    // NOTE: This approach of implementing O_CLOEXEC will not work in multithreaded scenarios.
+    // While execve() is undefined according to POSIX if there exist sibling threads, it could
+    // still be allowed by keeping certain file descriptors and instead set the active file table.
+    super::mman::release();
+    close_cloexec()?;
 
     if !is_interpreted && wants_setugid {
         // Make sure the last file descriptor not covered by O_CLOEXEC is not leaked.
         drop(image_file);
 
         // We are now going to invoke `escalate:` rather than loading the program ourselves.
-        let escalate_fd = FdGuard::new(syscall::open("escalate:", O_WRONLY)?);
+        let escalate_fd = File::open_bare("escalate:", O_WRONLY)?;
 
         // First, we write the path.
         //
@@ -278,21 +313,18 @@ pub fn execve(
         // TODO: Plus, at this point fexecve is not implemented (but specified in
         // POSIX.1-2008), and to avoid bad syscalls such as fpath, passing a file descriptor
         // would be better.
-        let _ = syscall::write(*escalate_fd, path.to_bytes());
+        let _ = syscall::write(escalate_fd.raw(), path.to_bytes());
 
         // Second, we write the flattened args and envs with NUL characters separating
         // individual items. This can be copied directly into the new executable's memory.
-        let _ = syscall::write(*escalate_fd, &flatten_with_nul(args))?;
-        let _ = syscall::write(*escalate_fd, &flatten_with_nul(envs))?;
-        let _ = syscall::write(*escalate_fd, &cwd)?;
+        let _ = syscall::write(escalate_fd.raw(), &flatten_with_nul(args))?;
+        let _ = syscall::write(escalate_fd.raw(), &flatten_with_nul(envs))?;
+        let _ = syscall::write(escalate_fd.raw(), &cwd)?;
 
         // Closing will notify the scheme, and from that point we will no longer have control
         // over this process (unless it fails). We do this manually since drop cannot handle
         // errors.
-        let fd = *escalate_fd as usize;
-        core::mem::forget(escalate_fd);
-
-        syscall::close(fd)?;
+        syscall::close(escalate_fd.into_raw_fd() as usize)?;
 
         unreachable!()
     } else {
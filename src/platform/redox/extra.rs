@@ -48,4 +48,4 @@ pub unsafe extern "C" fn redox_physunmap(virtual_address: *mut c_void) -> c_int
     e(syscall::physunmap(virtual_address as usize)) as c_int
 }
 
-pub use redox_exec::{create_set_addr_space_buf, FdGuard};
+pub use redox_exec::create_set_addr_space_buf;
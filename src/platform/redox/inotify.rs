@@ -0,0 +1,488 @@
+//! inotify emulation, as Redox has no change notification for filesystems.
+//!
+//! The watched files and directories are polled instead: each read compares their metadata, and
+//! the metadata of the entries of watched directories, to what it was at the previous read. The
+//! descriptor handed out is a timer of the time: scheme, armed SCAN_INTERVAL ahead at every read,
+//! so that poll, select and epoll wake up to read it again. Such a wake-up doesn't mean there is
+//! an event, a nonblocking read may still fail with EAGAIN.
+//!
+//! Only what leaves a trace in the metadata can be reported: IN_ACCESS, IN_OPEN and IN_CLOSE_*
+//! never are, and changes undone between two reads go unnoticed.
+
+use alloc::{
+    collections::{BTreeMap, VecDeque},
+    string::String,
+    sync::Arc,
+    vec::Vec,
+};
+use core::{mem, str};
+use syscall::{
+    self,
+    data::{Event, Stat, TimeSpec},
+    flag::*,
+    Error, Result, EAGAIN, EEXIST, EINVAL, ENOTDIR,
+};
+
+use super::{
+    emulated::{self, Emulated},
+    path,
+};
+use crate::{
+    c_str::CStr,
+    header::sys_inotify::*,
+    platform::types::*,
+    sync::{fork::ForkLock, Mutex},
+};
+
+/// How long poll waits at most before the next comparison, in nanoseconds
+const SCAN_INTERVAL: i64 = 100_000_000;
+/// The number of events to queue before reporting IN_Q_OVERFLOW, as on Linux by default
+const MAX_QUEUED_EVENTS: usize = 16384;
+
+/// The metadata compared between reads.
+#[derive(Clone, Copy, PartialEq)]
+struct Status {
+    dev: u64,
+    ino: u64,
+    mode: u16,
+    uid: u32,
+    gid: u32,
+    nlink: u32,
+    size: u64,
+    mtime: (u64, u32),
+}
+
+impl Status {
+    fn is_dir(&self) -> bool {
+        self.mode & MODE_TYPE == MODE_DIR
+    }
+
+    fn is_same_file(&self, other: &Self) -> bool {
+        self.dev == other.dev && self.ino == other.ino
+    }
+
+    fn modified(&self, old: &Self) -> bool {
+        self.mtime != old.mtime || self.size != old.size
+    }
+
+    fn attributes_changed(&self, old: &Self) -> bool {
+        // Directories gain and lose links with their subdirectories, which is no attribute change
+        self.mode != old.mode
+            || self.uid != old.uid
+            || self.gid != old.gid
+            || (!self.is_dir() && self.nlink != old.nlink)
+    }
+}
+
+fn status(path: &str, follow: bool) -> Result<Status> {
+    let mut flags = O_PATH | O_CLOEXEC;
+    if !follow {
+        flags |= O_NOFOLLOW;
+    }
+    let fd = path::open(path, flags)?;
+    let mut stat = Stat::default();
+    let result = syscall::fstat(fd, &mut stat);
+    let _ = syscall::close(fd);
+    result?;
+
+    Ok(Status {
+        dev: stat.st_dev,
+        ino: stat.st_ino,
+        mode: stat.st_mode,
+        uid: stat.st_uid,
+        gid: stat.st_gid,
+        nlink: stat.st_nlink,
+        size: stat.st_size,
+        mtime: (stat.st_mtime, stat.st_mtime_nsec),
+    })
+}
+
+/// Lists the entries of a directory with their metadata. Entries that vanish while listing are
+/// left out, they are reported at the next comparison.
+fn entries(dir: &str) -> BTreeMap<Vec<u8>, Status> {
+    let mut entries = BTreeMap::new();
+    let fd = match path::open(dir, O_RDONLY | O_DIRECTORY | O_CLOEXEC) {
+        Ok(fd) => fd,
+        Err(_) => return entries,
+    };
+    let mut names = Vec::new();
+    let mut buf = [0; 1024];
+    loop {
+        match syscall::read(fd, &mut buf) {
+            Ok(0) | Err(_) => break,
+            Ok(n) => names.extend_from_slice(&buf[..n]),
+        }
+    }
+    let _ = syscall::close(fd);
+
+    for name in names.split(|&b| b == b'\n') {
+        let name_str = match str::from_utf8(name) {
+            Ok(name_str) if !name_str.is_empty() && name_str != "." && name_str != ".." => name_str,
+            _ => continue,
+        };
+        let mut child = String::from(dir.trim_end_matches('/'));
+        child.push('/');
+        child.push_str(name_str);
+        if let Ok(status) = status(&child, false) {
+            entries.insert(name.to_vec(), status);
+        }
+    }
+    entries
+}
+
+struct Watch {
+    path: String,
+    mask: u32,
+    status: Status,
+    /// The entries at the previous comparison, if this is a directory
+    entries: BTreeMap<Vec<u8>, Status>,
+}
+
+struct QueuedEvent {
+    wd: c_int,
+    mask: u32,
+    cookie: u32,
+    name: Vec<u8>,
+}
+
+impl QueuedEvent {
+    /// `len` of the event, which pads the name with NULs to keep the next event aligned
+    fn name_len(&self) -> usize {
+        if self.name.is_empty() {
+            0
+        } else {
+            let align = mem::size_of::<inotify_event>();
+            (self.name.len() + 1 + align - 1) / align * align
+        }
+    }
+}
+
+#[derive(Default)]
+struct State {
+    watches: BTreeMap<c_int, Watch>,
+    last_wd: c_int,
+    last_cookie: u32,
+    queue: VecDeque<QueuedEvent>,
+}
+
+impl State {
+    fn push(&mut self, wd: c_int, mask: u32, cookie: u32, name: &[u8]) {
+        if self.queue.len() >= MAX_QUEUED_EVENTS {
+            if self
+                .queue
+                .back()
+                .map_or(true, |last| last.mask != IN_Q_OVERFLOW)
+            {
+                self.queue.push_back(QueuedEvent {
+                    wd: -1,
+                    mask: IN_Q_OVERFLOW,
+                    cookie: 0,
+                    name: Vec::new(),
+                });
+            }
+            return;
+        }
+        self.queue.push_back(QueuedEvent {
+            wd,
+            mask,
+            cookie,
+            name: name.to_vec(),
+        });
+    }
+
+    /// Queues the changes to one watch. Returns false if the watch is gone.
+    fn compare(&mut self, wd: c_int, watch: &mut Watch) -> bool {
+        let queued = self.queue.len();
+        let follow = watch.mask & IN_DONT_FOLLOW == 0;
+        let wanted = watch.mask & IN_ALL_EVENTS;
+        let old = watch.status;
+
+        let new = match status(&watch.path, follow) {
+            Ok(new) if new.is_same_file(&old) => new,
+            // Removed, or replaced by another file
+            _ => {
+                if wanted & IN_DELETE_SELF != 0 {
+                    self.push(wd, IN_DELETE_SELF, 0, &[]);
+                }
+                self.push(wd, IN_IGNORED, 0, &[]);
+                return false;
+            }
+        };
+        watch.status = new;
+
+        let isdir = |status: &Status| if status.is_dir() { IN_ISDIR } else { 0 };
+        if new.is_dir() {
+            let new_entries = entries(&watch.path);
+            let old_entries = mem::replace(&mut watch.entries, new_entries);
+
+            for (name, old_status) in &old_entries {
+                match watch.entries.get(name) {
+                    Some(new_status) if new_status.is_same_file(old_status) => {
+                        if !new_status.is_dir()
+                            && new_status.modified(old_status)
+                            && wanted & IN_MODIFY != 0
+                        {
+                            self.push(wd, IN_MODIFY, 0, name);
+                        }
+                        if new_status.attributes_changed(old_status) && wanted & IN_ATTRIB != 0 {
+                            self.push(wd, IN_ATTRIB | isdir(new_status), 0, name);
+                        }
+                    }
+                    _ => {
+                        // An entry that appeared as the same file was renamed
+                        let moved_to = watch.entries.iter().find(|(new_name, new_status)| {
+                            new_status.is_same_file(old_status)
+                                && old_entries
+                                    .get(*new_name)
+                                    .map_or(true, |other| !other.is_same_file(new_status))
+                        });
+                        match moved_to {
+                            Some((new_name, _)) => {
+                                self.last_cookie = self.last_cookie.wrapping_add(1).max(1);
+                                let cookie = self.last_cookie;
+                                if wanted & IN_MOVED_FROM != 0 {
+                                    self.push(wd, IN_MOVED_FROM | isdir(old_status), cookie, name);
+                                }
+                                if wanted & IN_MOVED_TO != 0 {
+                                    self.push(
+                                        wd,
+                                        IN_MOVED_TO | isdir(old_status),
+                                        cookie,
+                                        new_name,
+                                    );
+                                }
+                            }
+                            None if wanted & IN_DELETE != 0 => {
+                                self.push(wd, IN_DELETE | isdir(old_status), 0, name);
+                            }
+                            None => (),
+                        }
+                    }
+                }
+            }
+
+            for (name, new_status) in &watch.entries {
+                let is_new = old_entries
+                    .get(name)
+                    .map_or(true, |old_status| !old_status.is_same_file(new_status));
+                let renamed = old_entries
+                    .values()
+                    .any(|old_status| old_status.is_same_file(new_status));
+                if is_new && !renamed && wanted & IN_CREATE != 0 {
+                    self.push(wd, IN_CREATE | isdir(new_status), 0, name);
+                }
+            }
+        } else if new.modified(&old) && wanted & IN_MODIFY != 0 {
+            self.push(wd, IN_MODIFY, 0, &[]);
+        }
+        if new.attributes_changed(&old) && wanted & IN_ATTRIB != 0 {
+            self.push(wd, IN_ATTRIB | isdir(&new), 0, &[]);
+        }
+
+        if watch.mask & IN_ONESHOT != 0 && self.queue.len() > queued {
+            self.push(wd, IN_IGNORED, 0, &[]);
+            return false;
+        }
+        true
+    }
+
+    fn scan(&mut self) {
+        let mut watches = mem::take(&mut self.watches);
+        watches.retain(|&wd, watch| self.compare(wd, watch));
+        self.watches = watches;
+    }
+
+    /// Copies out as many queued events as fit in `buf`.
+    fn drain(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let mut written = 0;
+        while let Some(event) = self.queue.front() {
+            let name_len = event.name_len();
+            let size = mem::size_of::<inotify_event>() + name_len;
+            if buf.len() - written < size {
+                if written == 0 {
+                    return Err(Error::new(EINVAL));
+                }
+                break;
+            }
+
+            let header = inotify_event {
+                wd: event.wd,
+                mask: event.mask,
+                cookie: event.cookie,
+                len: name_len as u32,
+            };
+            let header_bytes = unsafe {
+                &*(&header as *const inotify_event as *const [u8; mem::size_of::<inotify_event>()])
+            };
+            let dst = &mut buf[written..written + size];
+            dst[..header_bytes.len()].copy_from_slice(header_bytes);
+            let name = &mut dst[header_bytes.len()..];
+            name[..event.name.len()].copy_from_slice(&event.name);
+            for byte in &mut name[event.name.len()..] {
+                *byte = 0;
+            }
+
+            written += size;
+            self.queue.pop_front();
+        }
+        Ok(written)
+    }
+}
+
+pub struct Inotify {
+    state: Mutex<State>,
+}
+
+/// Makes the timer expire when the next comparison is due.
+fn arm(fd: usize) -> Result<()> {
+    let mut time = TimeSpec::default();
+    syscall::read(fd, &mut time)?;
+    time.tv_sec += SCAN_INTERVAL / 1_000_000_000;
+    time.tv_nsec += (SCAN_INTERVAL % 1_000_000_000) as i32;
+    if time.tv_nsec >= 1_000_000_000 {
+        time.tv_sec += 1;
+        time.tv_nsec -= 1_000_000_000;
+    }
+    syscall::write(fd, &time)?;
+    Ok(())
+}
+
+impl Inotify {
+    /// The lock of the state, which fork holds.
+    pub fn fork_lock(&self) -> &dyn ForkLock {
+        &self.state
+    }
+
+    fn take(&self, fd: usize, buf: &mut [u8]) -> Result<usize> {
+        let mut state = self.state.lock();
+        state.scan();
+        if !state.watches.is_empty() {
+            arm(fd)?;
+        }
+        state.drain(buf)
+    }
+
+    pub fn read(&self, fd: usize, buf: &mut [u8]) -> Result<usize> {
+        let written = self.take(fd, buf)?;
+        if written > 0 {
+            return Ok(written);
+        }
+        if syscall::fcntl(fd, F_GETFL, 0)? & O_NONBLOCK != 0 {
+            return Err(Error::new(EAGAIN));
+        }
+
+        let queue = syscall::open("event:", O_RDWR | O_CLOEXEC)?;
+        let result = (|| -> Result<usize> {
+            syscall::write(
+                queue,
+                &Event {
+                    id: fd,
+                    flags: EVENT_READ,
+                    data: 0,
+                },
+            )?;
+            loop {
+                let written = self.take(fd, buf)?;
+                if written > 0 {
+                    return Ok(written);
+                }
+                let mut event = Event::default();
+                syscall::read(queue, &mut event)?;
+            }
+        })();
+        let _ = syscall::close(queue);
+        result
+    }
+
+    fn add_watch(&self, fd: usize, path: &str, mask: u32) -> Result<c_int> {
+        if mask & IN_ALL_EVENTS == 0 || (mask & IN_MASK_ADD != 0 && mask & IN_MASK_CREATE != 0) {
+            return Err(Error::new(EINVAL));
+        }
+        let path = path::canonicalize(path)?;
+        let status = status(&path, mask & IN_DONT_FOLLOW == 0)?;
+        if mask & IN_ONLYDIR != 0 && !status.is_dir() {
+            return Err(Error::new(ENOTDIR));
+        }
+        let new_mask = mask & !(IN_MASK_ADD | IN_MASK_CREATE);
+
+        let mut state = self.state.lock();
+        if let Some((&wd, watch)) = state
+            .watches
+            .iter_mut()
+            .find(|(_, watch)| watch.status.is_same_file(&status))
+        {
+            if mask & IN_MASK_CREATE != 0 {
+                return Err(Error::new(EEXIST));
+            }
+            if mask & IN_MASK_ADD != 0 {
+                watch.mask |= new_mask;
+            } else {
+                watch.mask = new_mask;
+            }
+            return Ok(wd);
+        }
+
+        state.last_wd += 1;
+        let wd = state.last_wd;
+        let entries = if status.is_dir() {
+            entries(&path)
+        } else {
+            BTreeMap::new()
+        };
+        state.watches.insert(
+            wd,
+            Watch {
+                path,
+                mask: new_mask,
+                status,
+                entries,
+            },
+        );
+        arm(fd)?;
+        Ok(wd)
+    }
+
+    fn rm_watch(&self, wd: c_int) -> Result<()> {
+        let mut state = self.state.lock();
+        if state.watches.remove(&wd).is_none() {
+            return Err(Error::new(EINVAL));
+        }
+        state.push(wd, IN_IGNORED, 0, &[]);
+        Ok(())
+    }
+}
+
+fn lookup(fd: c_int) -> Result<Arc<Inotify>> {
+    match emulated::get(fd) {
+        Some(Emulated::Inotify(inotify)) => Ok(inotify),
+        _ => Err(Error::new(EINVAL)),
+    }
+}
+
+pub fn init1(flags: c_int) -> Result<usize> {
+    if flags & !(IN_CLOEXEC | IN_NONBLOCK) != 0 {
+        return Err(Error::new(EINVAL));
+    }
+
+    // time:4 is CLOCK_MONOTONIC
+    let fd = syscall::open("time:4", O_RDWR | flags as usize)?;
+    emulated::insert(
+        fd as c_int,
+        Emulated::Inotify(Arc::new(Inotify {
+            state: Mutex::new(State::default()),
+        })),
+    );
+    Ok(fd)
+}
+
+pub fn add_watch(fd: c_int, path: &CStr, mask: u32) -> Result<usize> {
+    let inotify = lookup(fd)?;
+    let path = str::from_utf8(path.to_bytes()).map_err(|_| Error::new(EINVAL))?;
+    inotify
+        .add_watch(fd as usize, path, mask)
+        .map(|wd| wd as usize)
+}
+
+pub fn rm_watch(fd: c_int, wd: c_int) -> Result<()> {
+    lookup(fd)?.rm_watch(wd)
+}
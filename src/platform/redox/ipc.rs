@@ -0,0 +1,1272 @@
+//! System V IPC emulation on top of the shm: scheme.
+//!
+//! Every segment, semaphore set and message queue is an shm: object named after its kind and id,
+//! which starts with the state of the object, locked by a futex in that same shared memory. What
+//! follows is the segment, the semaphores or the messages. An object is found from its key
+//! through another object, named after the key, which holds the id. Removing an object unlinks
+//! both names, so that it goes away once the processes using it let go of it, which relies on the
+//! scheme keeping objects until they are unlinked, as shm_open needs it to anyway.
+//!
+//! Nothing is told when a process exits or execs, so the attachment count of a segment only goes
+//! down with shmdt. The operations made with SEM_UNDO are undone when the process calls exit, but
+//! not when a signal kills it, and those made before an exec are forgotten by it.
+
+use alloc::{collections::BTreeMap, format, string::String, vec::Vec};
+use core::{
+    marker::PhantomData,
+    mem, ptr, slice,
+    sync::atomic::{AtomicI32, AtomicUsize, Ordering},
+};
+use syscall::{
+    self,
+    data::Map,
+    flag::{MapFlags, O_CLOEXEC, O_CREAT, O_EXCL, O_RDWR},
+    Error, Result, E2BIG, EACCES, EAGAIN, EEXIST, EFAULT, EFBIG, EIDRM, EINTR, EINVAL, ENOENT,
+    ENOMSG, EPERM, ERANGE,
+};
+
+use super::{
+    super::{types::*, Pal, PalIpc},
+    e, mqueue, round_up_to_page_size, Sys, PAGE_SIZE,
+};
+use crate::{
+    c_str::CStr,
+    header::{
+        mqueue::mq_attr,
+        signal::sigevent,
+        sys_ipc::{
+            ipc_perm, IPC_CREAT, IPC_EXCL, IPC_NOWAIT, IPC_PRIVATE, IPC_RMID, IPC_SET, IPC_STAT,
+        },
+        sys_msg::{msqid_ds, MSG_EXCEPT, MSG_NOERROR},
+        sys_sem::{
+            sembuf, semid_ds, GETALL, GETNCNT, GETPID, GETVAL, GETZCNT, SEM_UNDO, SETALL, SETVAL,
+        },
+        sys_shm::{
+            shmid_ds, SHMLBA, SHM_EXEC, SHM_LOCK, SHM_RDONLY, SHM_REMAP, SHM_RND, SHM_UNLOCK,
+        },
+        time::{self, timespec, CLOCK_MONOTONIC},
+    },
+    sync::{Mutex, MutexGuard},
+};
+
+const FUTEX_WAIT: c_int = 0;
+const FUTEX_WAKE: c_int = 1;
+
+/// The permission bits needed to read or to write an object
+const READ: c_int = 0o444;
+const WRITE: c_int = 0o222;
+
+/// The largest segment
+const SHMMAX: usize = isize::max_value() as usize / 2;
+/// The most semaphores in a set, as on Linux
+const SEMMSL: usize = 32000;
+/// The most operations in one semop, as on Linux
+const SEMOPM: usize = 500;
+/// The largest value of a semaphore, as on Linux
+const SEMVMX: c_int = 32767;
+/// The largest message, as on Linux
+const MSGMAX: usize = 8192;
+/// The default limit on the bytes queued, which only privileged processes may raise
+const MSGMNB: usize = 16384;
+/// The room for the messages of a queue along with their headers
+const MSG_STORAGE: usize = 65536;
+/// The highest limit on the bytes queued, which leaves room for the headers of small messages
+const MSG_QBYTES_MAX: usize = MSG_STORAGE / 2;
+
+#[derive(Clone, Copy)]
+enum Kind {
+    Shm,
+    Sem,
+    Msg,
+}
+
+impl Kind {
+    fn name(self) -> &'static str {
+        match self {
+            Kind::Shm => "shm",
+            Kind::Sem => "sem",
+            Kind::Msg => "msg",
+        }
+    }
+
+    fn path(self, id: c_int) -> String {
+        format!("shm:sysv.{}.{}", self.name(), id)
+    }
+
+    fn key_path(self, key: key_t) -> String {
+        format!("shm:sysv.{}.key.{:x}", self.name(), key)
+    }
+}
+
+/// What starts the shared memory of an object.
+#[repr(C)]
+struct Object<T> {
+    /// The size of what is mapped to get at the object, which doesn't change
+    size: usize,
+    /// Bumped and woken whenever a waiter may be able to proceed
+    changes: AtomicI32,
+    state: Mutex<State<T>>,
+}
+
+struct State<T> {
+    removed: bool,
+    perm: ipc_perm,
+    ctime: time_t,
+    data: T,
+}
+
+impl<T> Object<T> {
+    /// Locks the state, unless the object has been removed.
+    fn lock(&self) -> Result<MutexGuard<State<T>>> {
+        let state = self.state.lock();
+        if state.removed {
+            return Err(Error::new(EIDRM));
+        }
+        Ok(state)
+    }
+
+    fn changed(&self) {
+        self.changes.fetch_add(1, Ordering::SeqCst);
+        Sys::futex(
+            &self.changes as *const AtomicI32 as *mut c_int,
+            FUTEX_WAKE,
+            c_int::max_value(),
+            0,
+        );
+    }
+
+    /// Waits for a change after `seen`, which is to be read before unlocking the state.
+    fn wait(&self, seen: c_int, deadline: Option<i128>) -> Result<()> {
+        let relative = match deadline {
+            Some(deadline) => {
+                let remaining = deadline - monotonic_nanos();
+                if remaining <= 0 {
+                    return Err(Error::new(EAGAIN));
+                }
+                Some(timespec {
+                    tv_sec: (remaining / 1_000_000_000) as time_t,
+                    tv_nsec: (remaining % 1_000_000_000) as c_long,
+                })
+            }
+            None => None,
+        };
+        let res = Sys::futex(
+            &self.changes as *const AtomicI32 as *mut c_int,
+            FUTEX_WAIT,
+            seen,
+            relative
+                .as_ref()
+                .map_or(0, |relative| relative as *const timespec as usize),
+        );
+        if res == -EINTR {
+            return Err(Error::new(EINTR));
+        }
+        Ok(())
+    }
+}
+
+fn map(fd: usize, offset: usize, size: usize, flags: MapFlags, address: usize) -> Result<usize> {
+    unsafe {
+        syscall::fmap(
+            fd,
+            &Map {
+                offset,
+                size,
+                flags,
+                address,
+            },
+        )
+    }
+}
+
+fn map_shared(fd: usize, size: usize) -> Result<usize> {
+    map(
+        fd,
+        0,
+        size,
+        MapFlags::PROT_READ | MapFlags::PROT_WRITE | MapFlags::MAP_SHARED,
+        0,
+    )
+}
+
+/// An object mapped into this process, unmapped and closed when dropped.
+struct Mapped<T> {
+    fd: usize,
+    address: usize,
+    size: usize,
+    object: PhantomData<T>,
+}
+
+impl<T> Mapped<T> {
+    fn new(fd: usize) -> Self {
+        Self {
+            fd,
+            address: 0,
+            size: 0,
+            object: PhantomData,
+        }
+    }
+
+    fn open(kind: Kind, id: c_int) -> Result<Self> {
+        if id <= 0 {
+            return Err(Error::new(EINVAL));
+        }
+        let mut mapped = match syscall::open(kind.path(id), O_RDWR | O_CLOEXEC) {
+            Ok(fd) => Self::new(fd),
+            // Never created, or removed since
+            Err(err) if err.errno == ENOENT => return Err(Error::new(EINVAL)),
+            Err(err) => return Err(err),
+        };
+        mapped.address = map_shared(mapped.fd, PAGE_SIZE)?;
+        mapped.size = PAGE_SIZE;
+
+        let size = mapped.object().size;
+        if size > PAGE_SIZE {
+            unsafe { syscall::funmap(mapped.address, PAGE_SIZE)? };
+            mapped.address = 0;
+            mapped.address = map_shared(mapped.fd, size)?;
+            mapped.size = size;
+        }
+        Ok(mapped)
+    }
+
+    /// Creates an object under a new id, of which the first `size` bytes are mapped to get at it.
+    fn create(
+        kind: Kind,
+        key: key_t,
+        flags: c_int,
+        size: usize,
+        total: usize,
+        data: T,
+    ) -> Result<(c_int, Self)> {
+        let (id, fd) = loop {
+            let id = next_id();
+            match syscall::open(kind.path(id), O_RDWR | O_CREAT | O_EXCL | O_CLOEXEC | 0o666) {
+                Ok(fd) => break (id, fd),
+                Err(err) if err.errno == EEXIST => continue,
+                Err(err) => return Err(err),
+            }
+        };
+        let mut mapped = Self::new(fd);
+        // The scheme may size objects by their mappings rather than this
+        let _ = syscall::ftruncate(fd, total);
+        match map_shared(fd, size) {
+            Ok(address) => mapped.address = address,
+            Err(err) => {
+                let _ = syscall::unlink(kind.path(id));
+                return Err(err);
+            }
+        }
+        mapped.size = size;
+
+        let uid = Sys::geteuid();
+        let gid = Sys::getegid();
+        unsafe {
+            ptr::write(
+                mapped.address as *mut Object<T>,
+                Object {
+                    size,
+                    changes: AtomicI32::new(0),
+                    state: Mutex::new(State {
+                        removed: false,
+                        perm: ipc_perm {
+                            __key: key,
+                            uid,
+                            gid,
+                            cuid: uid,
+                            cgid: gid,
+                            mode: flags & 0o777,
+                            ..Default::default()
+                        },
+                        ctime: now(),
+                        data,
+                    }),
+                },
+            );
+        }
+        Ok((id, mapped))
+    }
+
+    fn object(&self) -> &Object<T> {
+        unsafe { &*(self.address as *const Object<T>) }
+    }
+
+    /// What follows the state of the object, which the lock on it must be held to get at.
+    fn trailing(&self, _state: &mut State<T>) -> *mut u8 {
+        (self.address + PAGE_SIZE) as *mut u8
+    }
+}
+
+impl<T> Drop for Mapped<T> {
+    fn drop(&mut self) {
+        if self.address != 0 {
+            let _ = unsafe { syscall::funmap(self.address, self.size) };
+        }
+        let _ = syscall::close(self.fd);
+    }
+}
+
+/// Ids are tried until one is free, starting from one unlikely to be taken by another process.
+fn next_id() -> c_int {
+    static NEXT: AtomicUsize = AtomicUsize::new(0);
+    let id =
+        ((Sys::getpid() as u32) << 12 ^ NEXT.fetch_add(1, Ordering::Relaxed) as u32) & 0x7fff_ffff;
+    if id == 0 {
+        1
+    } else {
+        id as c_int
+    }
+}
+
+fn now() -> time_t {
+    unsafe { time::time(ptr::null_mut()) }
+}
+
+fn monotonic_nanos() -> i128 {
+    let mut now = timespec::default();
+    Sys::clock_gettime(CLOCK_MONOTONIC, &mut now);
+    now.tv_sec as i128 * 1_000_000_000 + now.tv_nsec as i128
+}
+
+/// Checks the permission bits in `mode` against `perm`, the way Linux does.
+fn check(perm: &ipc_perm, mode: c_int) -> Result<()> {
+    let euid = Sys::geteuid();
+    if euid == 0 {
+        return Ok(());
+    }
+    let egid = Sys::getegid();
+    let requested = (mode >> 6 | mode >> 3 | mode) & 0o7;
+    let granted = if euid == perm.uid || euid == perm.cuid {
+        perm.mode >> 6
+    } else if egid == perm.gid || egid == perm.cgid {
+        perm.mode >> 3
+    } else {
+        perm.mode
+    };
+    if requested & !granted != 0 {
+        return Err(Error::new(EACCES));
+    }
+    Ok(())
+}
+
+/// Checks that the caller may change or remove an object.
+fn check_owner(perm: &ipc_perm) -> Result<()> {
+    let euid = Sys::geteuid();
+    if euid != 0 && euid != perm.uid && euid != perm.cuid {
+        return Err(Error::new(EPERM));
+    }
+    Ok(())
+}
+
+fn set_perm(perm: &mut ipc_perm, new: &ipc_perm) {
+    perm.uid = new.uid;
+    perm.gid = new.gid;
+    perm.mode = (perm.mode & !0o777) | (new.mode & 0o777);
+}
+
+fn remove<T>(kind: Kind, id: c_int, object: &Object<T>, state: &mut State<T>) {
+    state.removed = true;
+    // The key first, for it not to be found while its object has gone
+    if state.perm.__key != IPC_PRIVATE {
+        let _ = syscall::unlink(kind.key_path(state.perm.__key));
+    }
+    let _ = syscall::unlink(kind.path(id));
+    // Waiters are woken to fail with EIDRM
+    object.changed();
+}
+
+/// The id stored in the object named after a key.
+fn read_key(fd: usize) -> Result<c_int> {
+    let address = map_shared(fd, PAGE_SIZE)?;
+    let stored = unsafe { &*(address as *const AtomicI32) };
+    // The creator only stores the id once the object is ready
+    let id = loop {
+        let id = stored.load(Ordering::SeqCst);
+        if id != 0 {
+            break id;
+        }
+        Sys::sched_yield();
+    };
+    let _ = unsafe { syscall::funmap(address, PAGE_SIZE) };
+    Ok(id)
+}
+
+fn write_key(fd: usize, id: c_int) -> Result<()> {
+    let address = map_shared(fd, PAGE_SIZE)?;
+    unsafe { &*(address as *const AtomicI32) }.store(id, Ordering::SeqCst);
+    let _ = unsafe { syscall::funmap(address, PAGE_SIZE) };
+    Ok(())
+}
+
+/// Finds the object of `key`, or creates it with `create` as `flags` ask. `suits` checks an
+/// object that already exists against what the caller asked for.
+fn get<T>(
+    kind: Kind,
+    key: key_t,
+    flags: c_int,
+    create: impl Fn() -> Result<(c_int, Mapped<T>)>,
+    suits: impl Fn(&T) -> Result<()>,
+) -> Result<c_int> {
+    if key == IPC_PRIVATE {
+        return create().map(|(id, _)| id);
+    }
+
+    let key_path = kind.key_path(key);
+    loop {
+        match syscall::open(&key_path, O_RDWR | O_CLOEXEC) {
+            Ok(fd) => {
+                if flags & IPC_CREAT != 0 && flags & IPC_EXCL != 0 {
+                    let _ = syscall::close(fd);
+                    return Err(Error::new(EEXIST));
+                }
+                let id = read_key(fd);
+                let _ = syscall::close(fd);
+                let id = id?;
+                let mapped = match Mapped::<T>::open(kind, id) {
+                    Ok(mapped) => mapped,
+                    // Removed in between, after its key
+                    Err(err) if err.errno == EINVAL => continue,
+                    Err(err) => return Err(err),
+                };
+                let state = match mapped.object().lock() {
+                    Ok(state) => state,
+                    Err(err) if err.errno == EIDRM => continue,
+                    Err(err) => return Err(err),
+                };
+                check(&state.perm, flags & 0o777)?;
+                suits(&state.data)?;
+                return Ok(id);
+            }
+            Err(err) if err.errno == ENOENT && flags & IPC_CREAT != 0 => {
+                let (id, _) = create()?;
+                match syscall::open(&key_path, O_RDWR | O_CREAT | O_EXCL | O_CLOEXEC | 0o666) {
+                    Ok(fd) => {
+                        let res = write_key(fd, id);
+                        let _ = syscall::close(fd);
+                        res?;
+                        return Ok(id);
+                    }
+                    Err(err) => {
+                        let _ = syscall::unlink(kind.path(id));
+                        // Another process created one first
+                        if err.errno != EEXIST {
+                            return Err(err);
+                        }
+                    }
+                }
+            }
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+struct Segment {
+    segsz: usize,
+    atime: time_t,
+    dtime: time_t,
+    cpid: pid_t,
+    lpid: pid_t,
+    nattch: c_ulong,
+}
+
+pub(crate) struct Attachment {
+    size: usize,
+    segment: Mapped<Segment>,
+}
+
+/// The segments attached in this process, by address
+pub(crate) static ATTACHED: Mutex<BTreeMap<usize, Attachment>> = Mutex::new(BTreeMap::new());
+
+fn shmget(key: key_t, size: usize, flags: c_int) -> Result<c_int> {
+    get(
+        Kind::Shm,
+        key,
+        flags,
+        || {
+            if size == 0 || size > SHMMAX {
+                return Err(Error::new(EINVAL));
+            }
+            // The segment follows the page with the state
+            Mapped::create(
+                Kind::Shm,
+                key,
+                flags,
+                PAGE_SIZE,
+                PAGE_SIZE + round_up_to_page_size(size),
+                Segment {
+                    segsz: size,
+                    atime: 0,
+                    dtime: 0,
+                    cpid: Sys::getpid(),
+                    lpid: 0,
+                    nattch: 0,
+                },
+            )
+        },
+        |segment| {
+            if size > segment.segsz {
+                return Err(Error::new(EINVAL));
+            }
+            Ok(())
+        },
+    )
+}
+
+unsafe fn shmat(id: c_int, address: usize, flags: c_int) -> Result<usize> {
+    let segment = Mapped::<Segment>::open(Kind::Shm, id)?;
+    let mut map_flags = MapFlags::PROT_READ | MapFlags::MAP_SHARED;
+    let mut mode = READ;
+    if flags & SHM_RDONLY == 0 {
+        map_flags |= MapFlags::PROT_WRITE;
+        mode |= WRITE;
+    }
+    if flags & SHM_EXEC != 0 {
+        map_flags |= MapFlags::PROT_EXEC;
+        mode |= 0o111;
+    }
+    let size = {
+        let state = segment.object().lock()?;
+        check(&state.perm, mode)?;
+        round_up_to_page_size(state.data.segsz)
+    };
+
+    let mut address = address;
+    if address != 0 {
+        let misaligned = address % SHMLBA as usize;
+        if misaligned != 0 {
+            if flags & SHM_RND == 0 {
+                return Err(Error::new(EINVAL));
+            }
+            address -= misaligned;
+        }
+        map_flags |= if flags & SHM_REMAP != 0 {
+            MapFlags::MAP_FIXED
+        } else {
+            MapFlags::MAP_FIXED_NOREPLACE
+        };
+    } else if flags & SHM_REMAP != 0 {
+        return Err(Error::new(EINVAL));
+    }
+    let address =
+        map(segment.fd, PAGE_SIZE, size, map_flags, address).map_err(|err| match err.errno {
+            EEXIST => Error::new(EINVAL),
+            _ => err,
+        })?;
+
+    {
+        let mut state = segment.object().state.lock();
+        state.data.nattch += 1;
+        state.data.atime = now();
+        state.data.lpid = Sys::getpid();
+    }
+    ATTACHED
+        .lock()
+        .insert(address, Attachment { size, segment });
+    Ok(address)
+}
+
+unsafe fn shmdt(address: usize) -> Result<()> {
+    let attachment = ATTACHED.lock().remove(&address).ok_or(Error::new(EINVAL))?;
+    syscall::funmap(address, attachment.size)?;
+
+    let mut state = attachment.segment.object().state.lock();
+    state.data.nattch = state.data.nattch.saturating_sub(1);
+    state.data.dtime = now();
+    state.data.lpid = Sys::getpid();
+    Ok(())
+}
+
+/// Counts the attachments a child process inherited from its parent, which doesn't inherit the
+/// adjustments of SEM_UNDO.
+pub fn forked() {
+    for attachment in ATTACHED.lock().values() {
+        attachment.segment.object().state.lock().data.nattch += 1;
+    }
+    UNDO.lock().clear();
+}
+
+fn shmctl(id: c_int, cmd: c_int, buf: *mut shmid_ds) -> Result<c_int> {
+    let segment = Mapped::<Segment>::open(Kind::Shm, id)?;
+    let object = segment.object();
+    let mut state = object.lock()?;
+    match cmd {
+        IPC_STAT => {
+            check(&state.perm, READ)?;
+            let buf = unsafe { buf.as_mut() }.ok_or(Error::new(EFAULT))?;
+            *buf = shmid_ds {
+                shm_perm: state.perm,
+                shm_segsz: state.data.segsz,
+                shm_atime: state.data.atime,
+                shm_dtime: state.data.dtime,
+                shm_ctime: state.ctime,
+                shm_cpid: state.data.cpid,
+                shm_lpid: state.data.lpid,
+                shm_nattch: state.data.nattch,
+                ..Default::default()
+            };
+        }
+        IPC_SET => {
+            check_owner(&state.perm)?;
+            let buf = unsafe { buf.as_ref() }.ok_or(Error::new(EFAULT))?;
+            set_perm(&mut state.perm, &buf.shm_perm);
+            state.ctime = now();
+        }
+        IPC_RMID => {
+            check_owner(&state.perm)?;
+            remove(Kind::Shm, id, object, &mut state);
+        }
+        // Redox never pages memory out
+        SHM_LOCK | SHM_UNLOCK => check_owner(&state.perm)?,
+        _ => return Err(Error::new(EINVAL)),
+    }
+    Ok(0)
+}
+
+struct SemaphoreSet {
+    nsems: usize,
+    otime: time_t,
+}
+
+#[derive(Clone, Copy)]
+struct Semaphore {
+    value: c_int,
+    pid: pid_t,
+    ncnt: c_int,
+    zcnt: c_int,
+    /// Bumped by SETVAL and SETALL, which cancel the adjustments made before
+    generation: c_uint,
+}
+
+impl Mapped<SemaphoreSet> {
+    fn semaphores<'a>(&self, state: &'a mut State<SemaphoreSet>) -> &'a mut [Semaphore] {
+        let nsems = state.data.nsems;
+        unsafe { slice::from_raw_parts_mut(self.trailing(state) as *mut Semaphore, nsems) }
+    }
+}
+
+fn semget(key: key_t, nsems: c_int, flags: c_int) -> Result<c_int> {
+    if nsems < 0 || nsems as usize > SEMMSL {
+        return Err(Error::new(EINVAL));
+    }
+    let nsems = nsems as usize;
+    get(
+        Kind::Sem,
+        key,
+        flags,
+        || {
+            if nsems == 0 {
+                return Err(Error::new(EINVAL));
+            }
+            // New objects are zeroed, and so are the semaphores
+            let size = PAGE_SIZE + round_up_to_page_size(nsems * mem::size_of::<Semaphore>());
+            Mapped::create(
+                Kind::Sem,
+                key,
+                flags,
+                size,
+                size,
+                SemaphoreSet { nsems, otime: 0 },
+            )
+        },
+        |set| {
+            if nsems > set.nsems {
+                return Err(Error::new(EINVAL));
+            }
+            Ok(())
+        },
+    )
+}
+
+/// The adjustments the operations made with SEM_UNDO ask for in a set, made when the process
+/// exits.
+pub(crate) struct Undo {
+    set: Mapped<SemaphoreSet>,
+    /// By semaphore, the adjustment and the generation of the semaphore it's for
+    adjustments: BTreeMap<usize, (c_int, c_uint)>,
+}
+
+/// The adjustments of this process, by set
+pub(crate) static UNDO: Mutex<BTreeMap<c_int, Undo>> = Mutex::new(BTreeMap::new());
+
+/// Records the adjustments that undo `sops`, made in the generations `generations` of their
+/// semaphores.
+fn record_undo(id: c_int, set: Mapped<SemaphoreSet>, sops: &[sembuf], generations: &[c_uint]) {
+    let mut records = UNDO.lock();
+    // Those of a set that was removed since are no use
+    if records
+        .get(&id)
+        .map_or(false, |undo| undo.set.object().state.lock().removed)
+    {
+        records.remove(&id);
+    }
+    let undo = records.entry(id).or_insert_with(|| Undo {
+        set,
+        adjustments: BTreeMap::new(),
+    });
+    for (op, &generation) in sops.iter().zip(generations) {
+        if op.sem_flg as c_int & SEM_UNDO == 0 || op.sem_op == 0 {
+            continue;
+        }
+        let adjustment = undo
+            .adjustments
+            .entry(op.sem_num as usize)
+            .or_insert((0, generation));
+        if adjustment.1 != generation {
+            *adjustment = (0, generation);
+        }
+        adjustment.0 -= op.sem_op as c_int;
+    }
+}
+
+/// Makes the adjustments of the process, which is exiting.
+pub fn undo() {
+    // Another thread may be in the middle of changing them
+    let mut records = match UNDO.try_lock() {
+        Some(records) => records,
+        None => return,
+    };
+    let pid = Sys::getpid();
+    for undo in records.values() {
+        let object = undo.set.object();
+        let mut state = match object.lock() {
+            Ok(state) => state,
+            Err(_) => continue,
+        };
+        let semaphores = undo.set.semaphores(&mut state);
+        for (&num, &(adjustment, generation)) in undo.adjustments.iter() {
+            let sem = &mut semaphores[num];
+            if sem.generation == generation && adjustment != 0 {
+                // As on Linux, a value that would go below 0 stops there
+                sem.value = (sem.value + adjustment).max(0).min(SEMVMX);
+                sem.pid = pid;
+            }
+        }
+        object.changed();
+    }
+    records.clear();
+}
+
+/// Performs all of `sops` at once, or returns the index of one that can't be yet.
+fn apply(semaphores: &mut [Semaphore], sops: &[sembuf]) -> Result<Option<usize>> {
+    let mut values: Vec<c_int> = semaphores.iter().map(|sem| sem.value).collect();
+    for (i, op) in sops.iter().enumerate() {
+        let value = &mut values[op.sem_num as usize];
+        let change = op.sem_op as c_int;
+        if (change == 0 && *value != 0) || *value + change < 0 {
+            return Ok(Some(i));
+        }
+        if *value + change > SEMVMX {
+            return Err(Error::new(ERANGE));
+        }
+        *value += change;
+    }
+
+    let pid = Sys::getpid();
+    for op in sops {
+        let sem = &mut semaphores[op.sem_num as usize];
+        sem.value = values[op.sem_num as usize];
+        sem.pid = pid;
+    }
+    Ok(None)
+}
+
+fn semtimedop(id: c_int, sops: &[sembuf], timeout: Option<&timespec>) -> Result<()> {
+    if sops.is_empty() {
+        return Err(Error::new(EINVAL));
+    }
+    if sops.len() > SEMOPM {
+        return Err(Error::new(E2BIG));
+    }
+    let deadline = match timeout {
+        Some(timeout) => {
+            if timeout.tv_sec < 0 || timeout.tv_nsec < 0 || timeout.tv_nsec >= 1_000_000_000 {
+                return Err(Error::new(EINVAL));
+            }
+            Some(
+                monotonic_nanos()
+                    + timeout.tv_sec as i128 * 1_000_000_000
+                    + timeout.tv_nsec as i128,
+            )
+        }
+        None => None,
+    };
+
+    let set = Mapped::<SemaphoreSet>::open(Kind::Sem, id)?;
+    let object = set.object();
+    let alter = sops.iter().any(|op| op.sem_op != 0);
+    loop {
+        let mut state = object.lock()?;
+        check(&state.perm, if alter { WRITE } else { READ })?;
+        if sops
+            .iter()
+            .any(|op| op.sem_num as usize >= state.data.nsems)
+        {
+            return Err(Error::new(EFBIG));
+        }
+
+        let blocked = match apply(set.semaphores(&mut state), sops)? {
+            Some(blocked) => &sops[blocked],
+            None => {
+                state.data.otime = now();
+                object.changed();
+                if sops.iter().any(|op| op.sem_flg as c_int & SEM_UNDO != 0) {
+                    let semaphores = set.semaphores(&mut state);
+                    let generations: Vec<c_uint> = sops
+                        .iter()
+                        .map(|op| semaphores[op.sem_num as usize].generation)
+                        .collect();
+                    drop(state);
+                    record_undo(id, set, sops, &generations);
+                }
+                return Ok(());
+            }
+        };
+        if blocked.sem_flg as c_int & IPC_NOWAIT != 0 {
+            return Err(Error::new(EAGAIN));
+        }
+
+        let waiting = |state: &mut State<SemaphoreSet>, count: c_int| {
+            let sem = &mut set.semaphores(state)[blocked.sem_num as usize];
+            if blocked.sem_op == 0 {
+                sem.zcnt += count;
+            } else {
+                sem.ncnt += count;
+            }
+        };
+        waiting(&mut state, 1);
+        let seen = object.changes.load(Ordering::SeqCst);
+        drop(state);
+        let res = object.wait(seen, deadline);
+        // Even if the set was removed meanwhile
+        waiting(&mut object.state.lock(), -1);
+        res?;
+    }
+}
+
+fn semctl(id: c_int, semnum: c_int, cmd: c_int, arg: c_ulong) -> Result<c_int> {
+    let set = Mapped::<SemaphoreSet>::open(Kind::Sem, id)?;
+    let object = set.object();
+    let mut state = object.lock()?;
+    let nsems = state.data.nsems;
+    let index = || {
+        if semnum < 0 || semnum as usize >= nsems {
+            return Err(Error::new(EINVAL));
+        }
+        Ok(semnum as usize)
+    };
+    match cmd {
+        IPC_STAT => {
+            check(&state.perm, READ)?;
+            let buf = unsafe { (arg as *mut semid_ds).as_mut() }.ok_or(Error::new(EFAULT))?;
+            *buf = semid_ds {
+                sem_perm: state.perm,
+                sem_otime: state.data.otime,
+                sem_ctime: state.ctime,
+                sem_nsems: nsems as c_ulong,
+                ..Default::default()
+            };
+        }
+        IPC_SET => {
+            check_owner(&state.perm)?;
+            let buf = unsafe { (arg as *const semid_ds).as_ref() }.ok_or(Error::new(EFAULT))?;
+            set_perm(&mut state.perm, &buf.sem_perm);
+            state.ctime = now();
+        }
+        IPC_RMID => {
+            check_owner(&state.perm)?;
+            remove(Kind::Sem, id, object, &mut state);
+        }
+        GETVAL | GETPID | GETNCNT | GETZCNT => {
+            check(&state.perm, READ)?;
+            let sem = set.semaphores(&mut state)[index()?];
+            return Ok(match cmd {
+                GETVAL => sem.value,
+                GETPID => sem.pid,
+                GETNCNT => sem.ncnt,
+                _ => sem.zcnt,
+            });
+        }
+        GETALL => {
+            check(&state.perm, READ)?;
+            let array = arg as *mut c_ushort;
+            if array.is_null() {
+                return Err(Error::new(EFAULT));
+            }
+            for (i, sem) in set.semaphores(&mut state).iter().enumerate() {
+                unsafe { *array.add(i) = sem.value as c_ushort };
+            }
+        }
+        SETVAL => {
+            check(&state.perm, WRITE)?;
+            let value = arg as c_int;
+            if value < 0 || value > SEMVMX {
+                return Err(Error::new(ERANGE));
+            }
+            let sem = &mut set.semaphores(&mut state)[index()?];
+            sem.value = value;
+            sem.pid = Sys::getpid();
+            sem.generation = sem.generation.wrapping_add(1);
+            state.ctime = now();
+            object.changed();
+        }
+        SETALL => {
+            check(&state.perm, WRITE)?;
+            let array = arg as *const c_ushort;
+            if array.is_null() {
+                return Err(Error::new(EFAULT));
+            }
+            let values = unsafe { slice::from_raw_parts(array, nsems) };
+            if values.iter().any(|&value| value as c_int > SEMVMX) {
+                return Err(Error::new(ERANGE));
+            }
+            let pid = Sys::getpid();
+            for (sem, &value) in set.semaphores(&mut state).iter_mut().zip(values) {
+                sem.value = value as c_int;
+                sem.pid = pid;
+                sem.generation = sem.generation.wrapping_add(1);
+            }
+            state.ctime = now();
+            object.changed();
+        }
+        _ => return Err(Error::new(EINVAL)),
+    }
+    Ok(0)
+}
+
+struct Queue {
+    qbytes: usize,
+    cbytes: usize,
+    qnum: usize,
+    /// The bytes of storage taken by the messages and their headers
+    used: usize,
+    lspid: pid_t,
+    lrpid: pid_t,
+    stime: time_t,
+    rtime: time_t,
+}
+
+/// What precedes each message in the storage, in the order they were sent.
+#[derive(Clone, Copy)]
+#[repr(C)]
+struct Message {
+    mtype: c_long,
+    len: usize,
+}
+
+/// The storage taken by a message of `len` bytes, which keeps the next message aligned.
+fn message_size(len: usize) -> usize {
+    let align = mem::align_of::<Message>();
+    mem::size_of::<Message>() + (len + align - 1) / align * align
+}
+
+impl Mapped<Queue> {
+    fn storage<'a>(&self, state: &'a mut State<Queue>) -> &'a mut [u8] {
+        unsafe { slice::from_raw_parts_mut(self.trailing(state), MSG_STORAGE) }
+    }
+}
+
+fn msgget(key: key_t, flags: c_int) -> Result<c_int> {
+    get(
+        Kind::Msg,
+        key,
+        flags,
+        || {
+            let size = PAGE_SIZE + MSG_STORAGE;
+            Mapped::create(
+                Kind::Msg,
+                key,
+                flags,
+                size,
+                size,
+                Queue {
+                    qbytes: MSGMNB,
+                    cbytes: 0,
+                    qnum: 0,
+                    used: 0,
+                    lspid: 0,
+                    lrpid: 0,
+                    stime: 0,
+                    rtime: 0,
+                },
+            )
+        },
+        |_| Ok(()),
+    )
+}
+
+unsafe fn msgsnd(id: c_int, msgp: *const c_void, msgsz: usize, flags: c_int) -> Result<()> {
+    if msgsz > MSGMAX {
+        return Err(Error::new(EINVAL));
+    }
+    let mtype = (msgp as *const c_long).as_ref().ok_or(Error::new(EFAULT))?;
+    if *mtype < 1 {
+        return Err(Error::new(EINVAL));
+    }
+    let text = slice::from_raw_parts((msgp as *const c_long).add(1) as *const u8, msgsz);
+
+    let queue = Mapped::<Queue>::open(Kind::Msg, id)?;
+    let object = queue.object();
+    let size = message_size(msgsz);
+    loop {
+        let mut state = object.lock()?;
+        check(&state.perm, WRITE)?;
+        let data = &state.data;
+        if data.cbytes + msgsz <= data.qbytes
+            && data.qnum < data.qbytes
+            && data.used + size <= MSG_STORAGE
+        {
+            let used = data.used;
+            let storage = &mut queue.storage(&mut state)[used..used + size];
+            ptr::write_unaligned(
+                storage.as_mut_ptr() as *mut Message,
+                Message {
+                    mtype: *mtype,
+                    len: msgsz,
+                },
+            );
+            storage[mem::size_of::<Message>()..][..msgsz].copy_from_slice(text);
+
+            state.data.used += size;
+            state.data.cbytes += msgsz;
+            state.data.qnum += 1;
+            state.data.lspid = Sys::getpid();
+            state.data.stime = now();
+            object.changed();
+            return Ok(());
+        }
+        if flags & IPC_NOWAIT != 0 {
+            return Err(Error::new(EAGAIN));
+        }
+
+        let seen = object.changes.load(Ordering::SeqCst);
+        drop(state);
+        object.wait(seen, None)?;
+    }
+}
+
+unsafe fn msgrcv(
+    id: c_int,
+    msgp: *mut c_void,
+    msgsz: usize,
+    msgtyp: c_long,
+    flags: c_int,
+) -> Result<usize> {
+    if msgsz > isize::max_value() as usize {
+        return Err(Error::new(EINVAL));
+    }
+    let queue = Mapped::<Queue>::open(Kind::Msg, id)?;
+    let object = queue.object();
+    loop {
+        let mut state = object.lock()?;
+        check(&state.perm, READ)?;
+        let used = state.data.used;
+        let storage = queue.storage(&mut state);
+
+        // The first message of the type asked for, or of the lowest type up to -msgtyp
+        let mut found: Option<(usize, Message)> = None;
+        let mut offset = 0;
+        while offset < used {
+            let message = ptr::read_unaligned(storage[offset..].as_ptr() as *const Message);
+            let matches = if msgtyp == 0 {
+                true
+            } else if msgtyp > 0 {
+                (message.mtype == msgtyp) != (flags & MSG_EXCEPT != 0)
+            } else {
+                message.mtype <= msgtyp.saturating_neg()
+                    && found.map_or(true, |(_, lowest)| message.mtype < lowest.mtype)
+            };
+            if matches {
+                found = Some((offset, message));
+                if msgtyp >= 0 {
+                    break;
+                }
+            }
+            offset += message_size(message.len);
+        }
+
+        if let Some((offset, message)) = found {
+            if message.len > msgsz && flags & MSG_NOERROR == 0 {
+                return Err(Error::new(E2BIG));
+            }
+            let len = message.len.min(msgsz);
+            let out = msgp as *mut c_long;
+            *out.as_mut().ok_or(Error::new(EFAULT))? = message.mtype;
+            ptr::copy_nonoverlapping(
+                storage[offset + mem::size_of::<Message>()..].as_ptr(),
+                out.add(1) as *mut u8,
+                len,
+            );
+            let size = message_size(message.len);
+            storage.copy_within(offset + size..used, offset);
+
+            state.data.used -= size;
+            state.data.cbytes -= message.len;
+            state.data.qnum -= 1;
+            state.data.lrpid = Sys::getpid();
+            state.data.rtime = now();
+            object.changed();
+            return Ok(len);
+        }
+        if flags & IPC_NOWAIT != 0 {
+            return Err(Error::new(ENOMSG));
+        }
+
+        let seen = object.changes.load(Ordering::SeqCst);
+        drop(state);
+        object.wait(seen, None)?;
+    }
+}
+
+fn msgctl(id: c_int, cmd: c_int, buf: *mut msqid_ds) -> Result<c_int> {
+    let queue = Mapped::<Queue>::open(Kind::Msg, id)?;
+    let object = queue.object();
+    let mut state = object.lock()?;
+    match cmd {
+        IPC_STAT => {
+            check(&state.perm, READ)?;
+            let buf = unsafe { buf.as_mut() }.ok_or(Error::new(EFAULT))?;
+            *buf = msqid_ds {
+                msg_perm: state.perm,
+                msg_stime: state.data.stime,
+                msg_rtime: state.data.rtime,
+                msg_ctime: state.ctime,
+                __msg_cbytes: state.data.cbytes as c_ulong,
+                msg_qnum: state.data.qnum as c_ulong,
+                msg_qbytes: state.data.qbytes as c_ulong,
+                msg_lspid: state.data.lspid,
+                msg_lrpid: state.data.lrpid,
+                ..Default::default()
+            };
+        }
+        IPC_SET => {
+            check_owner(&state.perm)?;
+            let buf = unsafe { buf.as_ref() }.ok_or(Error::new(EFAULT))?;
+            let qbytes = buf.msg_qbytes as usize;
+            if qbytes > MSGMNB && qbytes > state.data.qbytes && Sys::geteuid() != 0 {
+                return Err(Error::new(EPERM));
+            }
+            if qbytes > MSG_QBYTES_MAX {
+                return Err(Error::new(EINVAL));
+            }
+            set_perm(&mut state.perm, &buf.msg_perm);
+            state.data.qbytes = qbytes;
+            state.ctime = now();
+            // Senders may fit now
+            object.changed();
+        }
+        IPC_RMID => {
+            check_owner(&state.perm)?;
+            remove(Kind::Msg, id, object, &mut state);
+        }
+        _ => return Err(Error::new(EINVAL)),
+    }
+    Ok(0)
+}
+
+impl PalIpc for Sys {
+    fn mq_getsetattr(mqdes: c_int, new: *const mq_attr, old: *mut mq_attr) -> c_int {
+        e(mqueue::getsetattr(mqdes as usize, new, old).map(|()| 0)) as c_int
+    }
+
+    fn mq_notify(mqdes: c_int, sevp: *const sigevent) -> c_int {
+        e(mqueue::notify(mqdes as usize, sevp).map(|()| 0)) as c_int
+    }
+
+    fn mq_open(name: &CStr, oflag: c_int, mode: mode_t, attr: *const mq_attr) -> c_int {
+        e(mqueue::open(name, oflag, mode, attr)) as c_int
+    }
+
+    unsafe fn mq_timedreceive(
+        mqdes: c_int,
+        msg_ptr: *mut c_char,
+        msg_len: size_t,
+        msg_prio: *mut c_uint,
+        abs_timeout: *const timespec,
+    ) -> ssize_t {
+        let msg = slice::from_raw_parts_mut(msg_ptr as *mut u8, msg_len);
+        e(mqueue::timedreceive(
+            mqdes as usize,
+            msg,
+            msg_prio.as_mut(),
+            abs_timeout,
+        )) as ssize_t
+    }
+
+    unsafe fn mq_timedsend(
+        mqdes: c_int,
+        msg_ptr: *const c_char,
+        msg_len: size_t,
+        msg_prio: c_uint,
+        abs_timeout: *const timespec,
+    ) -> c_int {
+        let msg = slice::from_raw_parts(msg_ptr as *const u8, msg_len);
+        e(mqueue::timedsend(mqdes as usize, msg, msg_prio, abs_timeout).map(|()| 0)) as c_int
+    }
+
+    fn mq_unlink(name: &CStr) -> c_int {
+        e(mqueue::unlink(name).map(|()| 0)) as c_int
+    }
+
+    fn msgctl(msqid: c_int, cmd: c_int, buf: *mut msqid_ds) -> c_int {
+        e(msgctl(msqid, cmd, buf).map(|res| res as usize)) as c_int
+    }
+
+    fn msgget(key: key_t, msgflg: c_int) -> c_int {
+        e(msgget(key, msgflg).map(|id| id as usize)) as c_int
+    }
+
+    fn msgrcv(
+        msqid: c_int,
+        msgp: *mut c_void,
+        msgsz: size_t,
+        msgtyp: c_long,
+        msgflg: c_int,
+    ) -> ssize_t {
+        e(unsafe { msgrcv(msqid, msgp, msgsz, msgtyp, msgflg) }) as ssize_t
+    }
+
+    fn msgsnd(msqid: c_int, msgp: *const c_void, msgsz: size_t, msgflg: c_int) -> c_int {
+        e(unsafe { msgsnd(msqid, msgp, msgsz, msgflg) }.map(|()| 0)) as c_int
+    }
+
+    fn semctl(semid: c_int, semnum: c_int, cmd: c_int, arg: c_ulong) -> c_int {
+        e(semctl(semid, semnum, cmd, arg).map(|res| res as usize)) as c_int
+    }
+
+    fn semget(key: key_t, nsems: c_int, semflg: c_int) -> c_int {
+        e(semget(key, nsems, semflg).map(|id| id as usize)) as c_int
+    }
+
+    fn semtimedop(
+        semid: c_int,
+        sops: *mut sembuf,
+        nsops: size_t,
+        timeout: *const timespec,
+    ) -> c_int {
+        if sops.is_null() && nsops > 0 {
+            return e(Err(Error::new(EFAULT))) as c_int;
+        }
+        let sops = if sops.is_null() {
+            &[][..]
+        } else {
+            unsafe { slice::from_raw_parts(sops, nsops) }
+        };
+        e(semtimedop(semid, sops, unsafe { timeout.as_ref() }).map(|()| 0)) as c_int
+    }
+
+    unsafe fn shmat(shmid: c_int, shmaddr: *const c_void, shmflg: c_int) -> *mut c_void {
+        e(shmat(shmid, shmaddr as usize, shmflg)) as *mut c_void
+    }
+
+    fn shmctl(shmid: c_int, cmd: c_int, buf: *mut shmid_ds) -> c_int {
+        e(shmctl(shmid, cmd, buf).map(|res| res as usize)) as c_int
+    }
+
+    unsafe fn shmdt(shmaddr: *const c_void) -> c_int {
+        e(shmdt(shmaddr as usize).map(|()| 0)) as c_int
+    }
+
+    fn shmget(key: key_t, size: size_t, shmflg: c_int) -> c_int {
+        e(shmget(key, size, shmflg).map(|id| id as usize)) as c_int
+    }
+}
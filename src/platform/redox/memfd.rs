@@ -0,0 +1,344 @@
+//! memfd emulation on top of the shm: scheme.
+//!
+//! A memfd is an shm: object under a name unique to this process, unlinked as soon as it's
+//! created, so that it goes away with its last descriptor. The scheme has no seals, so they are
+//! kept in the object itself, in a page before the contents, which every process the descriptor
+//! is passed to finds there. The file offset skips that page from the start, and lseek, fstat,
+//! ftruncate and mmap shift what they're given and return by its size, so that it isn't seen.
+//!
+//! Descriptors this process didn't make a memfd, like the ones inherited through exec or received
+//! through a socket, are recognized by their path the first time they're used, and ones that
+//! aren't memfds are remembered as such, so that this is only asked once for each descriptor.
+
+use alloc::{collections::BTreeMap, format, sync::Arc};
+use core::sync::atomic::{AtomicU32, AtomicU64, AtomicUsize, Ordering};
+use syscall::{
+    self,
+    data::{Map, Stat},
+    flag::*,
+    Error, Result, EINVAL, EPERM,
+};
+
+use super::PAGE_SIZE;
+use crate::{
+    c_str::CStr,
+    header::{
+        fcntl::{
+            F_ADD_SEALS, F_GET_SEALS, F_SEAL_FUTURE_WRITE, F_SEAL_GROW, F_SEAL_SEAL, F_SEAL_SHRINK,
+            F_SEAL_WRITE,
+        },
+        sys_mman::{MAP_SHARED, MFD_ALLOW_SEALING, MFD_CLOEXEC, PROT_WRITE},
+    },
+    platform::types::*,
+    sync::Mutex,
+};
+
+/// The longest name accepted, as on Linux
+const NAME_MAX: usize = 249;
+
+/// What the names of memfds start with
+const PREFIX: &str = "shm:memfd-";
+
+/// The page at the start of a memfd's object, shared by every process with a descriptor of it
+#[repr(C)]
+struct Header {
+    seals: AtomicU32,
+}
+
+/// A memfd's header, mapped, shared by the duplicates of a descriptor
+pub(crate) struct MemFd {
+    header: *mut Header,
+}
+
+// The header is shared memory, only changed atomically
+unsafe impl Send for MemFd {}
+unsafe impl Sync for MemFd {}
+
+impl MemFd {
+    /// Maps the header of the memfd `fd`.
+    fn map(fd: usize) -> Result<Self> {
+        let header = unsafe {
+            syscall::fmap(
+                fd,
+                &Map {
+                    offset: 0,
+                    size: PAGE_SIZE,
+                    flags: MapFlags::PROT_READ | MapFlags::PROT_WRITE | MapFlags::MAP_SHARED,
+                    address: 0,
+                },
+            )?
+        };
+        Ok(Self {
+            header: header as *mut Header,
+        })
+    }
+
+    fn header(&self) -> &Header {
+        unsafe { &*self.header }
+    }
+
+    fn seals(&self) -> c_int {
+        self.header().seals.load(Ordering::SeqCst) as c_int
+    }
+}
+
+impl Drop for MemFd {
+    fn drop(&mut self) {
+        let _ = unsafe { syscall::funmap(self.header as usize, PAGE_SIZE) };
+    }
+}
+
+pub(crate) static MEMFDS: Mutex<BTreeMap<c_int, Arc<MemFd>>> = Mutex::new(BTreeMap::new());
+/// The number of descriptors in MEMFDS, which spares closing other descriptors the lock
+static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+/// The descriptors below this have a bit in PLAIN
+const PLAIN_FDS: usize = 1024;
+const NOT_CHECKED: AtomicU64 = AtomicU64::new(0);
+/// A bit for each descriptor that was found not to be a memfd, which spares it the lock
+static PLAIN: [AtomicU64; PLAIN_FDS / 64] = [NOT_CHECKED; PLAIN_FDS / 64];
+
+/// Makes the names of the memfds of this process unique
+static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+fn insert(memfds: &mut BTreeMap<c_int, Arc<MemFd>>, fd: c_int, memfd: Arc<MemFd>) {
+    if memfds.insert(fd, memfd).is_none() {
+        COUNT.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+fn plain(fd: c_int) -> bool {
+    let fd = fd as usize;
+    fd < PLAIN_FDS && PLAIN[fd / 64].load(Ordering::Relaxed) & (1 << (fd % 64)) != 0
+}
+
+fn set_plain(fd: c_int, plain: bool) {
+    let fd = fd as usize;
+    if fd < PLAIN_FDS {
+        if plain {
+            PLAIN[fd / 64].fetch_or(1 << (fd % 64), Ordering::Relaxed);
+        } else {
+            PLAIN[fd / 64].fetch_and(!(1 << (fd % 64)), Ordering::Relaxed);
+        }
+    }
+}
+
+/// The memfd of `fd`, if it's one.
+fn lookup(fd: c_int) -> Option<Arc<MemFd>> {
+    if fd < 0 || plain(fd) {
+        return None;
+    }
+    let mut memfds = MEMFDS.lock();
+    if let Some(memfd) = memfds.get(&fd) {
+        return Some(Arc::clone(memfd));
+    }
+
+    let mut path = [0; 4096];
+    let len = syscall::fpath(fd as usize, &mut path).ok()?;
+    if !path[..len].starts_with(PREFIX.as_bytes()) {
+        set_plain(fd, true);
+        return None;
+    }
+    let memfd = Arc::new(MemFd::map(fd as usize).ok()?);
+    insert(&mut memfds, fd, Arc::clone(&memfd));
+    Some(memfd)
+}
+
+/// The size of the object of `fd`, header included.
+fn size(fd: usize) -> Result<u64> {
+    let mut stat = Stat::default();
+    syscall::fstat(fd, &mut stat)?;
+    Ok(stat.st_size)
+}
+
+pub fn create(name: &CStr, flags: c_uint) -> Result<usize> {
+    if flags & !(MFD_CLOEXEC | MFD_ALLOW_SEALING) != 0 || name.to_bytes().len() > NAME_MAX {
+        return Err(Error::new(EINVAL));
+    }
+
+    let path = format!(
+        "{}{}-{}",
+        PREFIX,
+        syscall::getpid()?,
+        NEXT_ID.fetch_add(1, Ordering::Relaxed)
+    );
+    let mut oflags = O_RDWR | O_CREAT | O_EXCL;
+    if flags & MFD_CLOEXEC != 0 {
+        oflags |= O_CLOEXEC;
+    }
+    let fd = syscall::open(&path, oflags)?;
+    // The object stays around for as long as it's open
+    let _ = syscall::unlink(&path);
+
+    let memfd = match syscall::ftruncate(fd, PAGE_SIZE)
+        .and_then(|_| syscall::lseek(fd, PAGE_SIZE as isize, SEEK_SET))
+        .and_then(|_| MemFd::map(fd))
+    {
+        Ok(memfd) => memfd,
+        Err(err) => {
+            let _ = syscall::close(fd);
+            return Err(err);
+        }
+    };
+    // Without MFD_ALLOW_SEALING, no seals can be added, as on Linux
+    if flags & MFD_ALLOW_SEALING == 0 {
+        memfd
+            .header()
+            .seals
+            .store(F_SEAL_SEAL as u32, Ordering::SeqCst);
+    }
+    set_plain(fd as c_int, false);
+    insert(&mut MEMFDS.lock(), fd as c_int, Arc::new(memfd));
+    Ok(fd)
+}
+
+/// Handles F_ADD_SEALS and F_GET_SEALS, which only apply to memfds.
+pub fn fcntl(fd: c_int, cmd: c_int, arg: c_int) -> Option<Result<usize>> {
+    if cmd != F_ADD_SEALS && cmd != F_GET_SEALS {
+        return None;
+    }
+    let memfd = match lookup(fd) {
+        Some(memfd) => memfd,
+        None => return Some(Err(Error::new(EINVAL))),
+    };
+    if cmd == F_GET_SEALS {
+        return Some(Ok(memfd.seals() as usize));
+    }
+
+    let known = F_SEAL_SEAL | F_SEAL_SHRINK | F_SEAL_GROW | F_SEAL_WRITE | F_SEAL_FUTURE_WRITE;
+    if arg & !known != 0 {
+        return Some(Err(Error::new(EINVAL)));
+    }
+    // Sealing requires write access, as on Linux
+    match syscall::fcntl(fd as usize, F_GETFL, 0) {
+        Ok(flags) if flags & O_ACCMODE == O_RDONLY => return Some(Err(Error::new(EPERM))),
+        Ok(_) => (),
+        Err(err) => return Some(Err(err)),
+    }
+    // Other processes may be adding seals too
+    let res = memfd
+        .header()
+        .seals
+        .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |seals| {
+            if seals & F_SEAL_SEAL as u32 != 0 {
+                None
+            } else {
+                Some(seals | arg as u32)
+            }
+        });
+    Some(res.map(|_| 0).map_err(|_| Error::new(EPERM)))
+}
+
+/// Checks that writing `len` bytes to `fd`, at its offset, isn't prevented by a seal.
+pub fn check_write(fd: c_int, len: usize) -> Result<()> {
+    let memfd = match lookup(fd) {
+        Some(memfd) => memfd,
+        None => return Ok(()),
+    };
+    let seals = memfd.seals();
+    if seals & (F_SEAL_WRITE | F_SEAL_FUTURE_WRITE) != 0 {
+        return Err(Error::new(EPERM));
+    }
+    if seals & F_SEAL_GROW != 0 {
+        let size = size(fd as usize)?;
+        let offset = if syscall::fcntl(fd as usize, F_GETFL, 0)? & O_APPEND != 0 {
+            size
+        } else {
+            syscall::lseek(fd as usize, 0, SEEK_CUR)? as u64
+        };
+        if offset + len as u64 > size {
+            return Err(Error::new(EPERM));
+        }
+    }
+    Ok(())
+}
+
+/// Truncates `fd` to `len`, if it's a memfd, unless a seal prevents it.
+pub fn ftruncate(fd: c_int, len: u64) -> Option<Result<usize>> {
+    let memfd = lookup(fd)?;
+    Some(truncate(&memfd, fd as usize, len))
+}
+
+fn truncate(memfd: &MemFd, fd: usize, len: u64) -> Result<usize> {
+    let seals = memfd.seals();
+    if seals & (F_SEAL_SHRINK | F_SEAL_GROW) != 0 {
+        let size = size(fd)? - PAGE_SIZE as u64;
+        if (len < size && seals & F_SEAL_SHRINK != 0) || (len > size && seals & F_SEAL_GROW != 0) {
+            return Err(Error::new(EPERM));
+        }
+    }
+    syscall::ftruncate(fd, len as usize + PAGE_SIZE)
+}
+
+/// Moves the offset of `fd`, if it's a memfd, within the contents.
+pub fn lseek(fd: c_int, offset: isize, whence: usize) -> Option<Result<usize>> {
+    lookup(fd)?;
+    Some(seek(fd as usize, offset, whence))
+}
+
+fn seek(fd: usize, offset: isize, whence: usize) -> Result<usize> {
+    let base = match whence {
+        SEEK_SET => 0,
+        SEEK_CUR => syscall::lseek(fd, 0, SEEK_CUR)? - PAGE_SIZE,
+        SEEK_END => size(fd)? as usize - PAGE_SIZE,
+        _ => return Err(Error::new(EINVAL)),
+    };
+    let target = (base as isize)
+        .checked_add(offset)
+        .filter(|&target| target >= 0)
+        .ok_or(Error::new(EINVAL))?;
+    Ok(syscall::lseek(fd, target + PAGE_SIZE as isize, SEEK_SET)? - PAGE_SIZE)
+}
+
+/// Leaves the header out of the size fstat gave for `fd`.
+pub fn fstat(fd: c_int, stat: &mut Stat) {
+    if lookup(fd).is_some() {
+        stat.st_size = stat.st_size.saturating_sub(PAGE_SIZE as u64);
+    }
+}
+
+/// Checks that a mapping of `fd` isn't prevented by a seal, and moves `offset` past the header if
+/// it's a memfd.
+pub fn map(fd: c_int, prot: c_int, flags: c_int, offset: &mut usize) -> Result<()> {
+    let memfd = match lookup(fd) {
+        Some(memfd) => memfd,
+        None => return Ok(()),
+    };
+    // MAP_SHARED_VALIDATE has the bit too
+    let shared = flags & MAP_SHARED != 0;
+    if shared && prot & PROT_WRITE != 0 && memfd.seals() & (F_SEAL_WRITE | F_SEAL_FUTURE_WRITE) != 0
+    {
+        return Err(Error::new(EPERM));
+    }
+    *offset = offset.checked_add(PAGE_SIZE).ok_or(Error::new(EINVAL))?;
+    Ok(())
+}
+
+/// Called when `fd` is closed, or received from another process, which may have made it a memfd.
+pub fn closed(fd: c_int) {
+    set_plain(fd, false);
+    if COUNT.load(Ordering::Relaxed) == 0 {
+        return;
+    }
+    let memfd = MEMFDS.lock().remove(&fd);
+    if memfd.is_some() {
+        COUNT.fetch_sub(1, Ordering::Relaxed);
+    }
+    // Unmapped without the lock
+    drop(memfd);
+}
+
+/// Called when `new_fd` was made a duplicate of `fd`, after closing what it was before.
+pub fn duplicated(fd: c_int, new_fd: c_int) {
+    if plain(fd) {
+        set_plain(new_fd, true);
+        return;
+    }
+    if COUNT.load(Ordering::Relaxed) == 0 {
+        return;
+    }
+    let mut memfds = MEMFDS.lock();
+    if let Some(memfd) = memfds.get(&fd).cloned() {
+        insert(&mut memfds, new_fd, memfd);
+    }
+}
@@ -0,0 +1,1133 @@
+//! Tracking of mappings, for mremap, madvise, msync and mlock.
+//!
+//! Redox can't tell what backs a range of addresses, which mremap needs to know in order to extend
+//! a mapping with more of the same. So the anonymous mappings made by mmap are recorded here along
+//! with their flags, and kept up to date by munmap and mprotect. Those can be grown in place, or
+//! moved by copying their contents. Other mappings, such as those of files, can only be shrunk.
+//!
+//! Schemes may back a shared mapping of a file with memory of their own, which other processes
+//! mapping the file don't see and which is only written to the file when it's unmapped. So a
+//! shared mapping of a file is made of an shm: object named after the file instead, which every
+//! process mapping the file maps, and which holds a copy of the file from its start after a page
+//! of bookkeeping. The first process to map a part of the file copies it there, and the pages are
+//! written back to the file by msync and munmap, and before the process exits or executes another
+//! program. The last process to stop using the object removes it. One that's killed leaves it
+//! behind, so the object records which processes use it, for those that find one gone to forget
+//! it and take back a lock it held. Once no process uses it, the copy is only kept if the file
+//! hasn't changed since they last matched, which keeps the changes the killed process didn't
+//! sync, and it's copied again otherwise, as it is for another file that has the same inode.
+//!
+//! Reads and writes through the descriptors a file was mapped through, and their duplicates, are
+//! kept coherent with the mappings: the copy is written to the file before it's read, and what is
+//! written to the file is written to the copy too. Other descriptors of the file only see changes
+//! to the mappings once they are synced, and changes made through them aren't seen by mappings
+//! that were made before.
+//!
+//! A forked child gets a copy of the parent's memory, which for a shared mapping would no longer
+//! be shared, if the kernel copies it at all. So the child maps the shm: objects again, at the
+//! same addresses and with the same flags.
+//!
+//! The same records let madvise discard the pages of anonymous mappings, by mapping fresh ones
+//! over them.
+//!
+//! Redox never pages memory out, so locking pages only records them, for the total to be checked
+//! against RLIMIT_MEMLOCK.
+
+use alloc::{
+    collections::BTreeMap,
+    format,
+    string::String,
+    sync::{Arc, Weak},
+    vec::Vec,
+};
+use core::{
+    ptr, slice, str,
+    sync::atomic::{AtomicU32, AtomicU64, AtomicUsize, Ordering},
+};
+use syscall::{
+    data::{Map, Stat},
+    flag::{
+        MapFlags, MODE_FILE, MODE_TYPE, O_CLOEXEC, O_CREAT, O_EXCL, O_RDONLY, O_RDWR, SEEK_CUR,
+        SEEK_SET,
+    },
+    Error, Result, EEXIST, EINVAL, EIO, ENOENT, ENOMEM, EPERM, ESRCH,
+};
+
+use super::{rlimit_cur, round_up_to_page_size, Sys, PAGE_SIZE};
+use crate::{
+    fs::File,
+    header::{
+        sys_mman::{
+            MADV_DONTNEED, MADV_FREE, MADV_HUGEPAGE, MADV_NOHUGEPAGE, MADV_NORMAL, MADV_RANDOM,
+            MADV_SEQUENTIAL, MADV_WILLNEED, MCL_CURRENT, MCL_FUTURE, MCL_ONFAULT, MREMAP_FIXED,
+            MREMAP_MAYMOVE, MS_ASYNC, MS_INVALIDATE, MS_SYNC,
+        },
+        sys_resource::{RLIMIT_MEMLOCK, RLIM_INFINITY},
+    },
+    platform::{types::*, Pal},
+    sync::{fork::ForkLock, Mutex},
+};
+
+/// The number of processes that can use an object at once
+const MAX_USERS: usize = 256;
+
+/// A process using an object
+#[repr(C)]
+struct User {
+    /// Its ID, or 0 for a free slot
+    pid: AtomicU32,
+    /// The number of its MappedFile instances using the object
+    count: AtomicU32,
+}
+
+/// The page at the start of a file's shm: object, shared by the processes mapping the file
+#[repr(C)]
+struct Header {
+    /// The ID of the process holding the lock, or 0. It's held to change the object, and to read
+    /// or write the file along with it.
+    lock: AtomicU32,
+    /// Set by the last process to stop using the object as it removes it, for those that opened
+    /// it meanwhile to make a new one
+    removed: AtomicU32,
+    /// How much of the file the object has a copy of, from its start
+    copied: AtomicU64,
+    /// The file's size, modification and change times when the copy last matched it
+    stamp: [AtomicU64; 4],
+    users: [User; MAX_USERS],
+}
+
+struct HeaderGuard<'a>(&'a Header);
+
+/// Whether the process `pid` still exists, if it can't be signalled.
+fn alive(pid: u32) -> bool {
+    !matches!(syscall::kill(pid as usize, 0), Err(err) if err.errno == ESRCH)
+}
+
+/// What `stat` says of a file that changes when it's written, or replaced by another
+fn stamp(stat: &Stat) -> [u64; 4] {
+    [
+        stat.st_size,
+        stat.st_mtime,
+        stat.st_ctime,
+        (stat.st_mtime_nsec as u64) << 32 | stat.st_ctime_nsec as u64,
+    ]
+}
+
+impl Header {
+    fn lock(&self) -> HeaderGuard {
+        // A spin lock, as other processes take it too, which is taken back from one that's gone
+        let pid = Sys::getpid() as u32;
+        let mut spins = 0_u32;
+        loop {
+            let holder =
+                match self
+                    .lock
+                    .compare_exchange_weak(0, pid, Ordering::Acquire, Ordering::Relaxed)
+                {
+                    Ok(_) => break,
+                    Err(holder) => holder,
+                };
+            spins = spins.wrapping_add(1);
+            if holder != 0
+                && holder != pid
+                && spins % 64 == 0
+                && !alive(holder)
+                && self
+                    .lock
+                    .compare_exchange(holder, pid, Ordering::Acquire, Ordering::Relaxed)
+                    .is_ok()
+            {
+                break;
+            }
+            let _ = syscall::sched_yield();
+        }
+        HeaderGuard(self)
+    }
+}
+
+impl HeaderGuard<'_> {
+    /// Counts one more MappedFile instance of this process as using the object, forgetting the
+    /// processes that are gone. Returns whether no process was using it.
+    fn join(&self) -> Result<bool> {
+        let pid = Sys::getpid() as u32;
+        let mut unused = true;
+        let mut own = None;
+        let mut free = None;
+        for user in self.0.users.iter() {
+            match user.pid.load(Ordering::Relaxed) {
+                0 => (),
+                other if other == pid => own = Some(user),
+                other if alive(other) => unused = false,
+                _ => {
+                    // Killed without leaving
+                    user.pid.store(0, Ordering::Relaxed);
+                    user.count.store(0, Ordering::Relaxed);
+                }
+            }
+            if free.is_none() && user.pid.load(Ordering::Relaxed) == 0 {
+                free = Some(user);
+            }
+        }
+        let user = match own {
+            Some(user) => {
+                unused = false;
+                user
+            }
+            None => free.ok_or(Error::new(ENOMEM))?,
+        };
+        user.pid.store(pid, Ordering::Relaxed);
+        user.count.fetch_add(1, Ordering::Relaxed);
+        Ok(unused)
+    }
+
+    /// Counts one MappedFile instance of this process less, forgetting the processes that are
+    /// gone. Returns whether no process uses the object any more.
+    fn leave(&self) -> bool {
+        let pid = Sys::getpid() as u32;
+        let mut unused = true;
+        for user in self.0.users.iter() {
+            match user.pid.load(Ordering::Relaxed) {
+                0 => (),
+                other if other == pid => {
+                    if user.count.fetch_sub(1, Ordering::Relaxed) == 1 {
+                        user.pid.store(0, Ordering::Relaxed);
+                    } else {
+                        unused = false;
+                    }
+                }
+                other if alive(other) => unused = false,
+                _ => {
+                    user.pid.store(0, Ordering::Relaxed);
+                    user.count.store(0, Ordering::Relaxed);
+                }
+            }
+        }
+        unused
+    }
+}
+
+impl Drop for HeaderGuard<'_> {
+    fn drop(&mut self) {
+        self.0.lock.store(0, Ordering::Release);
+    }
+}
+
+/// A file with shared mappings, opened again to access it independently of the descriptor it was
+/// mapped with, which may be closed or moved meanwhile, and its shm: object.
+struct MappedFile {
+    fd: usize,
+    shm: usize,
+    /// The object's header, mapped
+    header: *mut Header,
+    dev: u64,
+    ino: u64,
+}
+
+// The header is shared memory, only changed with its lock held
+unsafe impl Send for MappedFile {}
+unsafe impl Sync for MappedFile {}
+
+fn shm_name(dev: u64, ino: u64) -> String {
+    format!("shm:relibc-mmap-{}-{}", dev, ino)
+}
+
+fn write_all(fd: usize, mut buf: &[u8]) -> Result<()> {
+    while !buf.is_empty() {
+        match syscall::write(fd, buf)? {
+            0 => return Err(Error::new(EIO)),
+            written => buf = &buf[written..],
+        }
+    }
+    Ok(())
+}
+
+/// Opens the shm: object `name`, creating it if there is none, and maps its header.
+fn open_shm(name: &str) -> Result<(File, *mut Header)> {
+    let shm = loop {
+        match syscall::open(name, O_RDWR | O_CREAT | O_EXCL | O_CLOEXEC) {
+            Ok(shm) => {
+                let shm = File::new_bare(shm);
+                // A new object is zeroes, which is an unlocked header with nothing copied
+                syscall::ftruncate(shm.raw(), PAGE_SIZE)?;
+                break shm;
+            }
+            Err(err) if err.errno == EEXIST => (),
+            Err(err) => return Err(err),
+        }
+        match syscall::open(name, O_RDWR | O_CLOEXEC) {
+            Ok(shm) => {
+                let shm = File::new_bare(shm);
+                // Until its creator has made room for the header
+                let mut stat = Stat::default();
+                loop {
+                    syscall::fstat(shm.raw(), &mut stat)?;
+                    if stat.st_size >= PAGE_SIZE as u64 {
+                        break;
+                    }
+                    let _ = syscall::sched_yield();
+                }
+                break shm;
+            }
+            // Removed since
+            Err(err) if err.errno == ENOENT => (),
+            Err(err) => return Err(err),
+        }
+    };
+    let header = unsafe {
+        syscall::fmap(
+            shm.raw(),
+            &Map {
+                offset: 0,
+                size: PAGE_SIZE,
+                flags: MapFlags::PROT_READ | MapFlags::PROT_WRITE | MapFlags::MAP_SHARED,
+                address: 0,
+            },
+        )?
+    };
+    Ok((shm, header as *mut Header))
+}
+
+impl MappedFile {
+    /// Opens the file of `fd` again, if it's a file of a filesystem, along with its shm: object,
+    /// and has at least its first `end` bytes copied there.
+    fn open(fd: usize, end: u64) -> Result<Option<Self>> {
+        // Other schemes, like shm: itself, share what they map already
+        let mut stat = Stat::default();
+        syscall::fstat(fd, &mut stat)?;
+        if stat.st_mode & MODE_TYPE != MODE_FILE {
+            return Ok(None);
+        }
+        let mut path = [0; 4096];
+        let len = syscall::fpath(fd, &mut path)?;
+        let path = str::from_utf8(&path[..len]).map_err(|_| Error::new(EINVAL))?;
+        if !path.starts_with("file:") {
+            return Ok(None);
+        }
+
+        // Read-only mappings may become writable with mprotect
+        let file = File::new_bare(
+            syscall::open(path, O_RDWR | O_CLOEXEC)
+                .or_else(|_| syscall::open(path, O_RDONLY | O_CLOEXEC))?,
+        );
+        let mut reopened = Stat::default();
+        syscall::fstat(file.raw(), &mut reopened)?;
+        if reopened.st_dev != stat.st_dev || reopened.st_ino != stat.st_ino {
+            // Renamed or replaced since it was opened
+            return Err(Error::new(EIO));
+        }
+
+        let name = shm_name(stat.st_dev, stat.st_ino);
+        loop {
+            let (shm, header) = open_shm(&name)?;
+            let guard = unsafe { &*header }.lock();
+            if guard.0.removed.load(Ordering::Relaxed) != 0 {
+                drop(guard);
+                let _ = unsafe { syscall::funmap(header as usize, PAGE_SIZE) };
+                continue;
+            }
+            let unused = match guard.join() {
+                Ok(unused) => unused,
+                Err(err) => {
+                    drop(guard);
+                    let _ = unsafe { syscall::funmap(header as usize, PAGE_SIZE) };
+                    return Err(err);
+                }
+            };
+            let mapped = Self {
+                fd: file.into_raw_fd() as usize,
+                shm: shm.into_raw_fd() as usize,
+                header,
+                dev: stat.st_dev,
+                ino: stat.st_ino,
+            };
+            let copied = mapped.validate(unused).and_then(|()| mapped.copy(end));
+            // Dropping it on failure takes the lock
+            drop(guard);
+            return copied.map(|()| Some(mapped));
+        }
+    }
+
+    fn header(&self) -> &Header {
+        unsafe { &*self.header }
+    }
+
+    fn size(&self) -> Result<u64> {
+        let mut stat = Stat::default();
+        syscall::fstat(self.fd, &mut stat)?;
+        Ok(stat.st_size)
+    }
+
+    fn file_stamp(&self) -> Result<[u64; 4]> {
+        let mut stat = Stat::default();
+        syscall::fstat(self.fd, &mut stat)?;
+        Ok(stamp(&stat))
+    }
+
+    /// Records that the copy matches the file, with the lock held.
+    fn stamp(&self) -> Result<()> {
+        let stamp = self.file_stamp()?;
+        for (field, value) in self.header().stamp.iter().zip(stamp) {
+            field.store(value, Ordering::Relaxed);
+        }
+        Ok(())
+    }
+
+    /// Discards the copy left by processes that are all gone if the file changed since, with the
+    /// lock held.
+    fn validate(&self, unused: bool) -> Result<()> {
+        if !unused {
+            return Ok(());
+        }
+        let stamp = self.file_stamp()?;
+        let unchanged = self
+            .header()
+            .stamp
+            .iter()
+            .zip(stamp)
+            .all(|(field, value)| field.load(Ordering::Relaxed) == value);
+        if !unchanged {
+            // Past the end of the file the copy is zeroes
+            syscall::ftruncate(self.shm, PAGE_SIZE)?;
+            self.header().copied.store(0, Ordering::Relaxed);
+        }
+        Ok(())
+    }
+
+    /// Copies the file to the object up to `end`, if it isn't there yet, with the lock held.
+    fn copy(&self, end: u64) -> Result<()> {
+        let copied = self.header().copied.load(Ordering::Relaxed);
+        if end <= copied {
+            return Ok(());
+        }
+        syscall::ftruncate(self.shm, PAGE_SIZE + end as usize)?;
+        syscall::lseek(self.fd, copied as isize, SEEK_SET)?;
+        syscall::lseek(self.shm, (PAGE_SIZE as u64 + copied) as isize, SEEK_SET)?;
+        let mut buf = vec![0; ((end - copied) as usize).min(64 * 1024)];
+        let mut offset = copied;
+        while offset < end {
+            let len = ((end - offset) as usize).min(buf.len());
+            // Past the end of the file the object is left zeroes
+            let read = match syscall::read(self.fd, &mut buf[..len])? {
+                0 => break,
+                read => read,
+            };
+            write_all(self.shm, &buf[..read])?;
+            offset += read as u64;
+        }
+        self.header().copied.store(end, Ordering::Relaxed);
+        self.stamp()
+    }
+
+    /// Copies the file to the object up to `end`, taking the lock.
+    fn copy_locked(&self, end: u64) -> Result<()> {
+        let _guard = self.header().lock();
+        self.copy(end)
+    }
+
+    /// Writes the copy of `from..to` of the file back to it, up to its end.
+    fn write_back(&self, from: u64, to: u64) -> Result<()> {
+        let _guard = self.header().lock();
+        let to = to
+            .min(self.header().copied.load(Ordering::Relaxed))
+            .min(self.size()?);
+        if from >= to {
+            return Ok(());
+        }
+        syscall::lseek(self.shm, (PAGE_SIZE as u64 + from) as isize, SEEK_SET)?;
+        syscall::lseek(self.fd, from as isize, SEEK_SET)?;
+        let mut buf = vec![0; ((to - from) as usize).min(64 * 1024)];
+        let mut offset = from;
+        while offset < to {
+            let len = ((to - offset) as usize).min(buf.len());
+            match syscall::read(self.shm, &mut buf[..len])? {
+                0 => return Err(Error::new(EIO)),
+                read => {
+                    write_all(self.fd, &buf[..read])?;
+                    offset += read as u64;
+                }
+            }
+        }
+        self.stamp()
+    }
+
+    /// Writes `data`, written at `offset` in the file, to the copy.
+    fn copy_in(&self, offset: u64, data: &[u8]) -> Result<()> {
+        let _guard = self.header().lock();
+        let copied = self.header().copied.load(Ordering::Relaxed);
+        if offset >= copied {
+            return Ok(());
+        }
+        let len = ((copied - offset) as usize).min(data.len());
+        syscall::lseek(self.shm, (PAGE_SIZE as u64 + offset) as isize, SEEK_SET)?;
+        write_all(self.shm, &data[..len])?;
+        self.stamp()
+    }
+}
+
+impl Drop for MappedFile {
+    fn drop(&mut self) {
+        {
+            let header = self.header();
+            let guard = header.lock();
+            if guard.leave() {
+                header.removed.store(1, Ordering::Relaxed);
+                let _ = syscall::unlink(&shm_name(self.dev, self.ino));
+            }
+        }
+        let _ = unsafe { syscall::funmap(self.header as usize, PAGE_SIZE) };
+        let _ = syscall::close(self.shm);
+        let _ = syscall::close(self.fd);
+    }
+}
+
+/// The descriptors files were mapped through, and their duplicates
+static FDS: Mutex<BTreeMap<usize, Weak<MappedFile>>> = Mutex::new(BTreeMap::new());
+/// The number of descriptors in FDS, which spares reads and writes of others the lock
+static FD_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+fn file_of(fd: usize) -> Option<Arc<MappedFile>> {
+    if FD_COUNT.load(Ordering::Relaxed) == 0 {
+        return None;
+    }
+    FDS.lock().get(&fd)?.upgrade()
+}
+
+/// Called when `fd` is closed.
+pub fn closed(fd: usize) {
+    if FD_COUNT.load(Ordering::Relaxed) != 0 && FDS.lock().remove(&fd).is_some() {
+        FD_COUNT.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Called when `new_fd` was made a duplicate of `fd`, after closing what it was before.
+pub fn duplicated(fd: usize, new_fd: usize) {
+    if let Some(file) = file_of(fd) {
+        if FDS.lock().insert(new_fd, Arc::downgrade(&file)).is_none() {
+            FD_COUNT.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+#[derive(Clone)]
+struct Backing {
+    file: Arc<MappedFile>,
+    /// The offset in the file that the region starts at
+    offset: u64,
+}
+
+#[derive(Clone)]
+struct Region {
+    end: usize,
+    flags: MapFlags,
+    /// The file of a shared file mapping, or None for an anonymous mapping
+    backing: Option<Backing>,
+}
+
+impl Region {
+    fn anonymous(end: usize, flags: MapFlags) -> Self {
+        Self {
+            end,
+            flags: flags - MapFlags::MAP_FIXED_NOREPLACE,
+            backing: None,
+        }
+    }
+}
+
+/// The anonymous and shared file mappings, keyed by their start address
+static REGIONS: Mutex<BTreeMap<usize, Region>> = Mutex::new(BTreeMap::new());
+/// The locked pages, as regions without flags
+static LOCKED: Mutex<BTreeMap<usize, Region>> = Mutex::new(BTreeMap::new());
+
+fn prot_mask() -> MapFlags {
+    MapFlags::PROT_READ | MapFlags::PROT_WRITE | MapFlags::PROT_EXEC
+}
+
+/// Makes sure no region crosses `at`, by splitting the one that does.
+fn split(regions: &mut BTreeMap<usize, Region>, at: usize) {
+    let tail = match regions.range_mut(..at).next_back() {
+        Some((&start, region)) if region.end > at => {
+            let mut tail = region.clone();
+            if let Some(backing) = &mut tail.backing {
+                backing.offset += (at - start) as u64;
+            }
+            region.end = at;
+            tail
+        }
+        _ => return,
+    };
+    regions.insert(at, tail);
+}
+
+fn remove(regions: &mut BTreeMap<usize, Region>, start: usize, end: usize) {
+    split(regions, start);
+    split(regions, end);
+    let starts: Vec<usize> = regions.range(start..end).map(|(&start, _)| start).collect();
+    for start in starts {
+        regions.remove(&start);
+    }
+}
+
+fn insert(regions: &mut BTreeMap<usize, Region>, start: usize, region: Region) {
+    remove(regions, start, region.end);
+    regions.insert(start, region);
+}
+
+/// The flags of the anonymous mapping covering all of `start..end`, if there is one.
+fn lookup(regions: &BTreeMap<usize, Region>, start: usize, end: usize) -> Option<MapFlags> {
+    let (_, first) = regions.range(..=start).next_back()?;
+    if first.end <= start {
+        return None;
+    }
+    if first.backing.is_some() {
+        return None;
+    }
+    let flags = first.flags;
+    let mut covered = first.end;
+    for (&region_start, region) in regions.range(start + 1..end) {
+        if region_start != covered || region.flags != flags || region.backing.is_some() {
+            return None;
+        }
+        covered = region.end;
+    }
+    if covered >= end {
+        Some(flags)
+    } else {
+        None
+    }
+}
+
+/// The recorded mappings covering all of `start..end`, cut to that range, if there are.
+fn pieces(
+    regions: &BTreeMap<usize, Region>,
+    start: usize,
+    end: usize,
+) -> Option<Vec<(usize, usize, Region)>> {
+    let first = regions
+        .range(..=start)
+        .next_back()
+        .map(|(&start, _)| start)?;
+    let mut pieces = Vec::new();
+    let mut covered = start;
+    for (&region_start, region) in regions.range(first..end) {
+        if region.end <= covered {
+            continue;
+        }
+        if region_start > covered {
+            return None;
+        }
+        let piece_end = region.end.min(end);
+        let mut piece = region.clone();
+        if let Some(backing) = &mut piece.backing {
+            backing.offset += (covered - region_start) as u64;
+        }
+        piece.end = piece_end;
+        pieces.push((covered, piece_end, piece));
+        covered = piece_end;
+    }
+    if covered >= end {
+        Some(pieces)
+    } else {
+        None
+    }
+}
+
+/// Where MAP_32BIT looks for room, the first 2 GiB above what programs are loaded at
+#[cfg(target_arch = "x86_64")]
+const LOW_START: usize = 0x4000_0000;
+#[cfg(target_arch = "x86_64")]
+const LOW_END: usize = 0x8000_0000;
+/// Where the next MAP_32BIT mapping is tried first, after the last one
+#[cfg(target_arch = "x86_64")]
+static LOW_NEXT: AtomicUsize = AtomicUsize::new(LOW_START);
+
+/// Maps within the first 2 GiB for MAP_32BIT, which Redox has no flag for, by trying a handful of
+/// addresses there with MAP_FIXED_NOREPLACE.
+#[cfg(target_arch = "x86_64")]
+unsafe fn fmap_low(fd: usize, map: &Map) -> Result<usize> {
+    // Skips over other mappings faster than a page at a time
+    let step = map.size.max(0x10_0000);
+    let mut address = LOW_NEXT.load(Ordering::Relaxed);
+    for _ in 0..64 {
+        if address < LOW_START || address + map.size > LOW_END {
+            address = LOW_START;
+        }
+        let low = Map {
+            address,
+            flags: map.flags | MapFlags::MAP_FIXED_NOREPLACE,
+            ..*map
+        };
+        match syscall::fmap(fd, &low) {
+            Ok(address) => {
+                LOW_NEXT.store(address + map.size, Ordering::Relaxed);
+                return Ok(address);
+            }
+            Err(err) if err.errno == EEXIST => address += step,
+            Err(err) => return Err(err),
+        }
+    }
+    Err(Error::new(ENOMEM))
+}
+
+/// Maps `fd`, or anonymous memory if it's None, and records the mapping. Shared mappings of
+/// files map the file's shm: object. `low` asks for an address in the first 2 GiB, for
+/// MAP_32BIT.
+pub unsafe fn mmap(fd: Option<usize>, map: &Map, low: bool) -> Result<usize> {
+    let fd = match fd {
+        Some(fd) if map.flags.contains(MapFlags::MAP_SHARED) => fd,
+        _ => {
+            let address = fmap(fd.unwrap_or(!0), map, low)?;
+            let end = address + map.size;
+            let mut regions = REGIONS.lock();
+            match fd {
+                None => insert(&mut regions, address, Region::anonymous(end, map.flags)),
+                // It may have replaced a recorded mapping
+                Some(_) => remove(&mut regions, address, end),
+            }
+            return Ok(address);
+        }
+    };
+
+    let end = map.offset as u64 + map.size as u64;
+    let file = match file_of(fd) {
+        Some(file) => {
+            file.copy_locked(end)?;
+            file
+        }
+        None => match MappedFile::open(fd, end)? {
+            Some(file) => {
+                let file = Arc::new(file);
+                if FDS.lock().insert(fd, Arc::downgrade(&file)).is_none() {
+                    FD_COUNT.fetch_add(1, Ordering::Relaxed);
+                }
+                file
+            }
+            None => {
+                let address = fmap(fd, map, low)?;
+                remove(&mut REGIONS.lock(), address, address + map.size);
+                return Ok(address);
+            }
+        },
+    };
+    let address = fmap(
+        file.shm,
+        &Map {
+            offset: PAGE_SIZE + map.offset,
+            ..*map
+        },
+        low,
+    )?;
+    let region = Region {
+        end: address + map.size,
+        flags: map.flags - MapFlags::MAP_FIXED_NOREPLACE,
+        backing: Some(Backing {
+            file,
+            offset: map.offset as u64,
+        }),
+    };
+    insert(&mut REGIONS.lock(), address, region);
+    Ok(address)
+}
+
+#[cfg(target_arch = "x86_64")]
+unsafe fn fmap(fd: usize, map: &Map, low: bool) -> Result<usize> {
+    if low && !map.flags.contains(MapFlags::MAP_FIXED) {
+        fmap_low(fd, map)
+    } else {
+        syscall::fmap(fd, map)
+    }
+}
+
+#[cfg(not(target_arch = "x86_64"))]
+unsafe fn fmap(fd: usize, map: &Map, _low: bool) -> Result<usize> {
+    syscall::fmap(fd, map)
+}
+
+/// Writes the pages of `start..end` of a shared file mapping to the file, up to its end.
+unsafe fn write_back(start: usize, end: usize, region: &Region) -> Result<()> {
+    let backing = match &region.backing {
+        Some(backing) if region.flags.contains(MapFlags::PROT_WRITE) => backing,
+        _ => return Ok(()),
+    };
+    let file = &backing.file;
+    let _guard = file.header().lock();
+    let size = file.size()?;
+    if backing.offset >= size {
+        return Ok(());
+    }
+    let len = ((size - backing.offset) as usize).min(end - start);
+    syscall::lseek(file.fd, backing.offset as isize, SEEK_SET)?;
+    write_all(file.fd, slice::from_raw_parts(start as *const u8, len))?;
+    file.stamp()
+}
+
+pub unsafe fn unmap(address: usize, size: usize) -> Result<()> {
+    let end = address + size;
+    let mut regions = REGIONS.lock();
+    split(&mut regions, address);
+    split(&mut regions, end);
+    for (&start, region) in regions.range(address..end) {
+        // Failing to write back isn't reported, as munmap can only fail for invalid arguments
+        let _ = write_back(start, region.end, region);
+    }
+    syscall::funmap(address, size)?;
+    remove(&mut regions, address, end);
+    remove(&mut LOCKED.lock(), address, end);
+    Ok(())
+}
+
+pub unsafe fn msync(address: usize, size: usize, flags: c_int) -> Result<()> {
+    if address % PAGE_SIZE != 0
+        || flags & !(MS_ASYNC | MS_SYNC | MS_INVALIDATE) != 0
+        || flags & (MS_ASYNC | MS_SYNC) == MS_ASYNC | MS_SYNC
+    {
+        return Err(Error::new(EINVAL));
+    }
+    let end = address + round_up_to_page_size(size);
+
+    // The mappings of a file share its pages, so MS_INVALIDATE has nothing to discard
+    let regions = REGIONS.lock();
+    let first = regions
+        .range(..=address)
+        .next_back()
+        .map_or(address, |(&start, _)| start);
+    let mut synced: Vec<Arc<MappedFile>> = Vec::new();
+    for (&region_start, region) in regions.range(first..end) {
+        let backing = match &region.backing {
+            Some(backing) if region.end > address => backing,
+            _ => continue,
+        };
+        if !region.flags.contains(MapFlags::PROT_WRITE) {
+            continue;
+        }
+        let start = region_start.max(address);
+        let mut piece = region.clone();
+        if let Some(backing) = &mut piece.backing {
+            backing.offset += (start - region_start) as u64;
+        }
+        piece.end = region.end.min(end);
+
+        // The pages are written at once, MS_ASYNC only spares waiting for the disk
+        write_back(start, piece.end, &piece)?;
+        if flags & MS_SYNC != 0 && !synced.iter().any(|file| Arc::ptr_eq(file, &backing.file)) {
+            syscall::fsync(backing.file.fd)?;
+            synced.push(backing.file.clone());
+        }
+    }
+    Ok(())
+}
+
+/// Called before reading `len` bytes from `fd`, to write what is mapped of them to the file first.
+pub fn before_read(fd: usize, len: usize) {
+    if let Some(file) = file_of(fd) {
+        // Descriptors that can't seek, like pipes, aren't mapped
+        if let Ok(offset) = syscall::lseek(fd, 0, SEEK_CUR) {
+            let _ = file.write_back(offset as u64, offset as u64 + len as u64);
+        }
+    }
+}
+
+/// Called after writing `data` to `fd`, to copy what is mapped of it to the mappings.
+pub fn after_write(fd: usize, data: &[u8]) {
+    if let Some(file) = file_of(fd) {
+        if let Ok(end) = syscall::lseek(fd, 0, SEEK_CUR) {
+            let _ = file.copy_in((end as u64).saturating_sub(data.len() as u64), data);
+        }
+    }
+}
+
+/// Called before the process exits or executes another program, which unmaps everything, to
+/// write the shared file mappings back and give up their shm: objects.
+pub fn release() {
+    // Another thread may be in the middle of changing them
+    let mut regions = match REGIONS.try_lock() {
+        Some(regions) => regions,
+        None => return,
+    };
+    for (&start, region) in regions.iter() {
+        let _ = unsafe { write_back(start, region.end, region) };
+    }
+    regions.clear();
+}
+
+/// The locks of the records, which fork holds, so the child gets them in a consistent state.
+/// The child maps the shm: objects of shared file mappings again, and becomes one more of their
+/// users.
+pub(crate) struct Regions;
+
+impl ForkLock for Regions {
+    unsafe fn prepare(&self) {
+        REGIONS.manual_lock();
+        LOCKED.manual_lock();
+        FDS.manual_lock();
+    }
+    unsafe fn parent(&self) {
+        FDS.manual_unlock();
+        LOCKED.manual_unlock();
+        REGIONS.manual_unlock();
+    }
+    unsafe fn child(&self) {
+        REGIONS.manual_reinit();
+        LOCKED.manual_reinit();
+        FDS.manual_reinit();
+        // Memory locks aren't inherited
+        LOCKED.lock().clear();
+        let mut files: Vec<Arc<MappedFile>> = Vec::new();
+        for (&start, region) in REGIONS.lock().iter() {
+            let backing = match &region.backing {
+                Some(backing) => backing,
+                None => continue,
+            };
+            let file = &backing.file;
+            if !files.iter().any(|other| Arc::ptr_eq(other, file)) {
+                let header = Map {
+                    offset: 0,
+                    size: PAGE_SIZE,
+                    flags: MapFlags::PROT_READ
+                        | MapFlags::PROT_WRITE
+                        | MapFlags::MAP_SHARED
+                        | MapFlags::MAP_FIXED,
+                    address: file.header as usize,
+                };
+                // The objects are the ones opened in the parent, which the child inherits
+                let _ = syscall::fmap(file.shm, &header);
+                let _ = file.header().lock().join();
+                files.push(file.clone());
+            }
+            let map = Map {
+                offset: PAGE_SIZE + backing.offset as usize,
+                size: region.end - start,
+                flags: region.flags | MapFlags::MAP_FIXED,
+                address: start,
+            };
+            let _ = syscall::fmap(file.shm, &map);
+        }
+    }
+}
+
+/// Called after a successful mprotect.
+pub fn protected(address: usize, size: usize, prot: MapFlags) {
+    let mut regions = REGIONS.lock();
+    let end = address + size;
+    split(&mut regions, address);
+    split(&mut regions, end);
+    for (_, region) in regions.range_mut(address..end) {
+        region.flags = (region.flags - prot_mask()) | prot;
+    }
+}
+
+pub unsafe fn mremap(
+    old_address: usize,
+    old_size: usize,
+    new_size: usize,
+    flags: c_int,
+    new_address: usize,
+) -> Result<usize> {
+    let fixed = flags & MREMAP_FIXED != 0;
+    let may_move = flags & MREMAP_MAYMOVE != 0;
+    if old_address % PAGE_SIZE != 0
+        || old_size == 0
+        || new_size == 0
+        || flags & !(MREMAP_FIXED | MREMAP_MAYMOVE) != 0
+        || (fixed && !may_move)
+    {
+        return Err(Error::new(EINVAL));
+    }
+    let old_size = round_up_to_page_size(old_size);
+    let new_size = round_up_to_page_size(new_size);
+    if fixed
+        && (new_address % PAGE_SIZE != 0
+            || (new_address < old_address + old_size && old_address < new_address + new_size))
+    {
+        return Err(Error::new(EINVAL));
+    }
+
+    let mut regions = REGIONS.lock();
+    if !fixed && new_size <= old_size {
+        drop(regions);
+        if new_size < old_size {
+            unmap(old_address + new_size, old_size - new_size)?;
+        }
+        return Ok(old_address);
+    }
+
+    let map_flags = match lookup(&regions, old_address, old_address + old_size) {
+        Some(map_flags) => map_flags,
+        None => return Err(Error::new(ENOMEM)),
+    };
+
+    if !fixed {
+        let tail = old_address + old_size;
+        let extension = Map {
+            offset: 0,
+            size: new_size - old_size,
+            flags: map_flags | MapFlags::MAP_FIXED_NOREPLACE,
+            address: tail,
+        };
+        if let Ok(address) = syscall::fmap(!0, &extension) {
+            if address == tail {
+                insert(
+                    &mut regions,
+                    tail,
+                    Region::anonymous(old_address + new_size, map_flags),
+                );
+                return Ok(old_address);
+            }
+            let _ = syscall::funmap(address, extension.size);
+        }
+        if !may_move {
+            return Err(Error::new(ENOMEM));
+        }
+    }
+
+    // A copy would no longer be shared with the processes it was shared with
+    if map_flags.contains(MapFlags::MAP_SHARED) {
+        return Err(Error::new(ENOMEM));
+    }
+
+    let mut target_flags = (map_flags - prot_mask()) | MapFlags::PROT_READ | MapFlags::PROT_WRITE;
+    if fixed {
+        target_flags |= MapFlags::MAP_FIXED;
+    }
+    let target = syscall::fmap(
+        !0,
+        &Map {
+            offset: 0,
+            size: new_size,
+            flags: target_flags,
+            address: if fixed { new_address } else { 0 },
+        },
+    )?;
+
+    let moved = (|| -> Result<()> {
+        if !map_flags.contains(MapFlags::PROT_READ) {
+            syscall::mprotect(
+                old_address,
+                old_size,
+                (map_flags & prot_mask()) | MapFlags::PROT_READ,
+            )?;
+        }
+        ptr::copy_nonoverlapping(
+            old_address as *const u8,
+            target as *mut u8,
+            old_size.min(new_size),
+        );
+        if map_flags & prot_mask() != MapFlags::PROT_READ | MapFlags::PROT_WRITE {
+            syscall::mprotect(target, new_size, map_flags & prot_mask())?;
+        }
+        Ok(())
+    })();
+    if let Err(err) = moved {
+        let _ = syscall::funmap(target, new_size);
+        return Err(err);
+    }
+
+    syscall::funmap(old_address, old_size)?;
+    remove(&mut regions, old_address, old_address + old_size);
+    insert(
+        &mut regions,
+        target,
+        Region::anonymous(target + new_size, map_flags),
+    );
+    Ok(target)
+}
+
+pub unsafe fn madvise(address: usize, size: usize, advice: c_int) -> Result<()> {
+    if address % PAGE_SIZE != 0 {
+        return Err(Error::new(EINVAL));
+    }
+    let end = address + round_up_to_page_size(size);
+
+    match advice {
+        // Redox pages are never swapped nor read ahead
+        MADV_NORMAL | MADV_RANDOM | MADV_SEQUENTIAL | MADV_WILLNEED => Ok(()),
+        // Nor is there any huge page to back them with
+        MADV_HUGEPAGE | MADV_NOHUGEPAGE => Ok(()),
+        // Reading freed pages may give their old contents or zeroes, zeroes are given here too
+        MADV_DONTNEED | MADV_FREE => {
+            let regions = REGIONS.lock();
+            let pieces = match pieces(&regions, address, end) {
+                Some(pieces) => pieces,
+                // The pages of files would have to be read again
+                None => return Err(Error::new(EINVAL)),
+            };
+            for (start, end, region) in pieces {
+                // The pages of shared files keep what is in the file
+                if region.backing.is_some() {
+                    continue;
+                }
+                syscall::fmap(
+                    !0,
+                    &Map {
+                        offset: 0,
+                        size: end - start,
+                        flags: region.flags | MapFlags::MAP_FIXED,
+                        address: start,
+                    },
+                )?;
+            }
+            Ok(())
+        }
+        _ => Err(Error::new(EINVAL)),
+    }
+}
+
+/// Checks that `size` more bytes may be locked, or any if `size` is None.
+fn check_lock_limit(locked: usize, size: Option<usize>) -> Result<()> {
+    if Sys::geteuid() == 0 {
+        return Ok(());
+    }
+    let limit = rlimit_cur(RLIMIT_MEMLOCK);
+    if limit == 0 {
+        return Err(Error::new(EPERM));
+    }
+    match size {
+        Some(size) if limit != RLIM_INFINITY && (locked + size) as u64 > limit => {
+            Err(Error::new(ENOMEM))
+        }
+        _ => Ok(()),
+    }
+}
+
+pub fn lock(address: usize, size: usize) -> Result<()> {
+    let start = address / PAGE_SIZE * PAGE_SIZE;
+    let end = round_up_to_page_size(address + size);
+
+    let mut locked = LOCKED.lock();
+    // Pages that are locked already don't count twice
+    let (total, overlap) =
+        locked
+            .iter()
+            .fold((0, 0), |(total, overlap), (&region_start, region)| {
+                let covered = region.end.min(end).saturating_sub(region_start.max(start));
+                (total + region.end - region_start, overlap + covered)
+            });
+    check_lock_limit(total - overlap, Some(end - start))?;
+    insert(
+        &mut locked,
+        start,
+        Region::anonymous(end, MapFlags::empty()),
+    );
+    Ok(())
+}
+
+pub fn unlock(address: usize, size: usize) {
+    let start = address / PAGE_SIZE * PAGE_SIZE;
+    remove(
+        &mut LOCKED.lock(),
+        start,
+        round_up_to_page_size(address + size),
+    );
+}
+
+pub fn lock_all(flags: c_int) -> Result<()> {
+    if flags == 0 || flags & !(MCL_CURRENT | MCL_FUTURE | MCL_ONFAULT) != 0 || flags == MCL_ONFAULT
+    {
+        return Err(Error::new(EINVAL));
+    }
+    // The size of the address space isn't known, so only a zero limit can be exceeded
+    check_lock_limit(0, None)
+}
+
+pub fn unlock_all() {
+    LOCKED.lock().clear();
+}
@@ -1,3 +1,4 @@
+use alloc::{string::String, vec::Vec};
 use core::{arch::asm, convert::TryFrom, mem, ptr, result::Result as CoreResult, slice, str};
 
 use syscall::{
@@ -11,43 +12,74 @@ use crate::{
     fs::File,
     header::{
         dirent::dirent,
-        errno::{EINVAL, EIO, ENOMEM, ENOSYS, EPERM, ERANGE},
+        errno::{EAGAIN, EINVAL, EIO, ENOMEM, ENOSYS, EPERM, ERANGE},
         fcntl,
+        linux_sched::{clone_args, clone_fn},
+        poll,
         string::strlen,
-        sys_mman::{MAP_ANONYMOUS, PROT_READ, PROT_WRITE},
+        sys_mman::{MAP_ANONYMOUS, MAP_HUGETLB, MAP_LOCKED, PROT_READ, PROT_WRITE},
         sys_random,
-        sys_resource::{rlimit, RLIM_INFINITY},
+        sys_resource::{rlim_t, rlimit, RLIMIT_CORE, RLIMIT_NLIMITS, RLIM_INFINITY},
         sys_stat::{stat, S_ISGID, S_ISUID},
         sys_statvfs::statvfs,
+        sys_sysinfo::sysinfo,
         sys_time::{timeval, timezone},
         sys_utsname::{utsname, UTSLENGTH},
         sys_wait,
-        time::timespec,
+        time::{itimerspec, timespec},
         unistd::{F_OK, R_OK, W_OK, X_OK},
     },
     io::{self, prelude::*, BufReader, SeekFrom},
+    sync::Mutex,
 };
 
-pub use redox_exec::FdGuard;
-
-use super::{errno, types::*, Pal, Read};
+use super::{
+    blocking::{self, Restart},
+    errno,
+    types::*,
+    Pal, Read,
+};
 
 static mut BRK_CUR: *mut c_void = ptr::null_mut();
 static mut BRK_END: *mut c_void = ptr::null_mut();
 
+/// The soft and hard resource limits, which are only kept by this process and not enforced by the
+/// kernel. They are inherited by fork, but not across exec. No core is dumped until RLIMIT_CORE is
+/// raised, as on most Linux systems.
+static RLIMITS: Mutex<[(rlim_t, rlim_t); RLIMIT_NLIMITS as usize]> = Mutex::new({
+    let mut limits = [(RLIM_INFINITY, RLIM_INFINITY); RLIMIT_NLIMITS as usize];
+    limits[RLIMIT_CORE as usize].0 = 0;
+    limits
+});
+
+/// The soft limit of `resource`.
+fn rlimit_cur(resource: u64) -> rlim_t {
+    RLIMITS.lock()[resource as usize].0
+}
+
 const PAGE_SIZE: usize = 4096;
 fn round_up_to_page_size(val: usize) -> usize {
     (val + PAGE_SIZE - 1) / PAGE_SIZE * PAGE_SIZE
 }
 
-mod clone;
+pub(crate) mod clone;
+mod coredump;
+pub(crate) mod emulated;
 mod epoll;
+mod eventfd;
 mod exec;
 mod extra;
+mod inotify;
+pub(crate) mod ipc;
+pub(crate) mod memfd;
+pub(crate) mod mman;
+mod mqueue;
 pub(crate) mod path;
 mod ptrace;
-mod signal;
-mod socket;
+pub(crate) mod signal;
+pub(crate) mod socket;
+pub(crate) mod spawn;
+mod timerfd;
 
 macro_rules! path_from_c_str {
     ($c_str:expr) => {{
@@ -77,11 +109,18 @@ pub fn e(sys: Result<usize>) -> usize {
     }
 }
 
+/// Updates what the child of a fork inherited, after the internal locks are usable again, since
+/// this frees memory.
+pub fn forked() {
+    poll::forget_all();
+    ipc::forked();
+}
+
 pub struct Sys;
 
 impl Pal for Sys {
     fn access(path: &CStr, mode: c_int) -> c_int {
-        let fd = match File::open(path, fcntl::O_PATH | fcntl::O_CLOEXEC) {
+        let fd = match File::open(path, fcntl::O_PATH) {
             Ok(fd) => fd,
             Err(_) => return -1,
         };
@@ -171,14 +210,14 @@ impl Pal for Sys {
     }
 
     fn chmod(path: &CStr, mode: mode_t) -> c_int {
-        match File::open(path, fcntl::O_PATH | fcntl::O_CLOEXEC) {
+        match File::open(path, fcntl::O_PATH) {
             Ok(file) => Self::fchmod(*file, mode),
             Err(_) => -1,
         }
     }
 
     fn chown(path: &CStr, owner: uid_t, group: gid_t) -> c_int {
-        match File::open(path, fcntl::O_PATH | fcntl::O_CLOEXEC) {
+        match File::open(path, fcntl::O_PATH) {
             Ok(file) => Self::fchown(*file, owner, group),
             Err(_) => -1,
         }
@@ -198,23 +237,50 @@ impl Pal for Sys {
         }
     }
 
+    unsafe fn clone3(_args: &clone_args, _func: clone_fn, _arg: *mut c_void) -> pid_t {
+        // Namespaces and the rest of what clone3 is for aren't Redox's
+        errno = ENOSYS;
+        -1
+    }
+
     fn close(fd: c_int) -> c_int {
-        e(syscall::close(fd as usize)) as c_int
+        let ret = e(syscall::close(fd as usize)) as c_int;
+        emulated::closed(fd);
+        ret
     }
 
     fn dup(fd: c_int) -> c_int {
-        e(syscall::dup(fd as usize, &[])) as c_int
+        let new_fd = e(syscall::dup(fd as usize, &[])) as c_int;
+        if new_fd >= 0 {
+            emulated::duplicated(fd, new_fd);
+        }
+        new_fd
     }
 
     fn dup2(fd1: c_int, fd2: c_int) -> c_int {
-        e(syscall::dup2(fd1 as usize, fd2 as usize, &[])) as c_int
+        let ret = e(syscall::dup2(fd1 as usize, fd2 as usize, &[])) as c_int;
+        if ret >= 0 {
+            emulated::duplicated(fd1, fd2);
+        }
+        ret
+    }
+
+    fn eventfd(initval: c_uint, flags: c_int) -> c_int {
+        e(eventfd::eventfd(initval, flags)) as c_int
     }
 
     fn exit(status: c_int) -> ! {
+        ipc::undo();
+        mman::release();
         let _ = syscall::exit(status as usize);
         loop {}
     }
 
+    fn exit_thread() -> ! {
+        let _ = syscall::exit(0);
+        loop {}
+    }
+
     unsafe fn execve(path: &CStr, argv: *const *mut c_char, envp: *const *mut c_char) -> c_int {
         e(self::exec::execve(
             path,
@@ -248,6 +314,25 @@ impl Pal for Sys {
     }
 
     fn fcntl(fd: c_int, cmd: c_int, args: c_int) -> c_int {
+        if let Some(result) = memfd::fcntl(fd, cmd, args) {
+            return e(result) as c_int;
+        }
+        if cmd == fcntl::F_DUPFD || cmd == fcntl::F_DUPFD_CLOEXEC {
+            let res = syscall::fcntl(fd as usize, fcntl::F_DUPFD as usize, args as usize);
+            let new_fd = match res {
+                Ok(new_fd) => new_fd,
+                Err(err) => return e(Err(err)) as c_int,
+            };
+            if cmd == fcntl::F_DUPFD_CLOEXEC {
+                let cloexec = fcntl::FD_CLOEXEC as usize;
+                if let Err(err) = syscall::fcntl(new_fd, syscall::F_SETFD, cloexec) {
+                    let _ = syscall::close(new_fd);
+                    return e(Err(err)) as c_int;
+                }
+            }
+            emulated::duplicated(fd, new_fd as c_int);
+            return new_fd as c_int;
+        }
         e(syscall::fcntl(fd as usize, cmd as usize, args as usize)) as c_int
     }
 
@@ -264,6 +349,7 @@ impl Pal for Sys {
         let mut redox_buf: redox_stat = redox_stat::default();
         match e(syscall::fstat(fildes as usize, &mut redox_buf)) {
             0 => {
+                memfd::fstat(fildes, &mut redox_buf);
                 if let Some(buf) = unsafe { buf.as_mut() } {
                     buf.st_dev = redox_buf.st_dev as dev_t;
                     buf.st_ino = redox_buf.st_ino as ino_t;
@@ -325,6 +411,9 @@ impl Pal for Sys {
     }
 
     fn ftruncate(fd: c_int, len: off_t) -> c_int {
+        if let Some(result) = memfd::ftruncate(fd, len as u64) {
+            return e(result) as c_int;
+        }
         e(syscall::ftruncate(fd as usize, len as usize)) as c_int
     }
 
@@ -351,12 +440,29 @@ impl Pal for Sys {
     }
 
     fn utimens(path: &CStr, times: *const timespec) -> c_int {
-        match File::open(path, fcntl::O_PATH | fcntl::O_CLOEXEC) {
+        match File::open(path, fcntl::O_PATH) {
             Ok(file) => Self::futimens(*file, times),
             Err(_) => -1,
         }
     }
 
+    fn get_nprocs() -> c_int {
+        // sys:cpu starts with a "CPUs: N" line
+        let count = File::open(c_str!("sys:cpu"), fcntl::O_RDONLY)
+            .ok()
+            .and_then(|file| {
+                let mut line = String::new();
+                BufReader::new(file).read_line(&mut line).ok()?;
+                line.trim().strip_prefix("CPUs:")?.trim().parse().ok()
+            });
+        count.unwrap_or(1)
+    }
+
+    fn get_nprocs_conf() -> c_int {
+        // Processors aren't taken offline
+        Self::get_nprocs()
+    }
+
     fn getcwd(buf: *mut c_char, size: size_t) -> *mut c_char {
         // TODO: Not using MaybeUninit seems a little unsafe
 
@@ -527,10 +633,16 @@ impl Pal for Sys {
     }
 
     unsafe fn getrlimit(resource: c_int, rlim: *mut rlimit) -> c_int {
-        //TODO
+        let (cur, max) = match RLIMITS.lock().get(resource as usize) {
+            Some(&limits) => limits,
+            None => {
+                errno = EINVAL;
+                return -1;
+            }
+        };
         if !rlim.is_null() {
-            (*rlim).rlim_cur = RLIM_INFINITY;
-            (*rlim).rlim_max = RLIM_INFINITY;
+            (*rlim).rlim_cur = cur;
+            (*rlim).rlim_max = max;
         }
         0
     }
@@ -571,12 +683,24 @@ impl Pal for Sys {
         e(syscall::getuid()) as pid_t
     }
 
+    fn inotify_add_watch(fd: c_int, path: &CStr, mask: u32) -> c_int {
+        e(inotify::add_watch(fd, path, mask)) as c_int
+    }
+
+    fn inotify_init1(flags: c_int) -> c_int {
+        e(inotify::init1(flags)) as c_int
+    }
+
+    fn inotify_rm_watch(fd: c_int, wd: c_int) -> c_int {
+        e(inotify::rm_watch(fd, wd).map(|()| 0)) as c_int
+    }
+
     fn lchown(path: &CStr, owner: uid_t, group: gid_t) -> c_int {
         // TODO: Is it correct for regular chown to use O_PATH? On Linux the meaning of that flag
         // is to forbid file operations, including fchown.
 
         // unlike chown, never follow symbolic links
-        match File::open(path, fcntl::O_CLOEXEC | fcntl::O_NOFOLLOW) {
+        match File::open(path, fcntl::O_NOFOLLOW) {
             Ok(file) => Self::fchown(*file, owner, group),
             Err(_) => -1,
         }
@@ -588,6 +712,9 @@ impl Pal for Sys {
     }
 
     fn lseek(fd: c_int, offset: off_t, whence: c_int) -> off_t {
+        if let Some(result) = memfd::lseek(fd, offset as isize, whence as usize) {
+            return e(result) as off_t;
+        }
         e(syscall::lseek(
             fd as usize,
             offset as isize,
@@ -596,11 +723,7 @@ impl Pal for Sys {
     }
 
     fn mkdir(path: &CStr, mode: mode_t) -> c_int {
-        match File::create(
-            path,
-            fcntl::O_DIRECTORY | fcntl::O_EXCL | fcntl::O_CLOEXEC,
-            0o777,
-        ) {
+        match File::create(path, fcntl::O_DIRECTORY | fcntl::O_EXCL, 0o777) {
             Ok(_fd) => 0,
             Err(_) => -1,
         }
@@ -609,7 +732,7 @@ impl Pal for Sys {
     fn mkfifo(path: &CStr, mode: mode_t) -> c_int {
         match File::create(
             path,
-            fcntl::O_CREAT | fcntl::O_CLOEXEC,
+            fcntl::O_CREAT,
             syscall::MODE_FIFO as mode_t | (mode & 0o777),
         ) {
             Ok(fd) => 0,
@@ -617,14 +740,20 @@ impl Pal for Sys {
         }
     }
 
+    unsafe fn madvise(addr: *mut c_void, len: usize, advice: c_int) -> c_int {
+        e(mman::madvise(addr as usize, len, advice).map(|()| 0)) as c_int
+    }
+
+    fn memfd_create(name: &CStr, flags: c_uint) -> c_int {
+        e(memfd::create(name, flags)) as c_int
+    }
+
     unsafe fn mlock(addr: *const c_void, len: usize) -> c_int {
-        // Redox never swaps
-        0
+        e(mman::lock(addr as usize, len).map(|()| 0)) as c_int
     }
 
     fn mlockall(flags: c_int) -> c_int {
-        // Redox never swaps
-        0
+        e(mman::lock_all(flags).map(|()| 0)) as c_int
     }
 
     unsafe fn mmap(
@@ -635,7 +764,12 @@ impl Pal for Sys {
         fildes: c_int,
         off: off_t,
     ) -> *mut c_void {
-        let map = Map {
+        // Redox has no huge pages to reserve, which fails like Linux does when none are reserved
+        if flags & MAP_HUGETLB != 0 {
+            return e(Err(Error::new(ENOMEM))) as *mut c_void;
+        }
+
+        let mut map = Map {
             offset: off as usize,
             size: round_up_to_page_size(len),
             flags: syscall::MapFlags::from_bits_truncate(
@@ -644,49 +778,83 @@ impl Pal for Sys {
             address: addr as usize,
         };
 
-        if flags & MAP_ANONYMOUS == MAP_ANONYMOUS {
-            e(syscall::fmap(!0, &map)) as *mut c_void
+        let anonymous = flags & MAP_ANONYMOUS == MAP_ANONYMOUS;
+        let fd = if anonymous {
+            None
         } else {
-            e(syscall::fmap(fildes as usize, &map)) as *mut c_void
+            if let Err(err) = memfd::map(fildes, prot, flags, &mut map.offset) {
+                return e(Err(err)) as *mut c_void;
+            }
+            Some(fildes as usize)
+        };
+        // MAP_STACK, MAP_NORESERVE, MAP_POPULATE and the like make no difference here
+        #[cfg(target_arch = "x86_64")]
+        let low = flags & crate::header::sys_mman::MAP_32BIT != 0;
+        #[cfg(not(target_arch = "x86_64"))]
+        let low = false;
+        let address = match mman::mmap(fd, &map, low) {
+            Ok(address) => address,
+            Err(err) => return e(Err(err)) as *mut c_void,
+        };
+
+        if flags & MAP_LOCKED != 0 {
+            if let Err(err) = mman::lock(address, map.size) {
+                let _ = mman::unmap(address, map.size);
+                // The error Linux gives for going over RLIMIT_MEMLOCK this way
+                let err = if err.errno == ENOMEM {
+                    Error::new(EAGAIN)
+                } else {
+                    err
+                };
+                return e(Err(err)) as *mut c_void;
+            }
         }
+        address as *mut c_void
     }
 
     unsafe fn mprotect(addr: *mut c_void, len: usize, prot: c_int) -> c_int {
-        e(syscall::mprotect(
-            addr as usize,
-            round_up_to_page_size(len),
-            syscall::MapFlags::from_bits((prot as usize) << 16)
-                .expect("mprotect: invalid bit pattern"),
-        )) as c_int
+        let len = round_up_to_page_size(len);
+        let prot = syscall::MapFlags::from_bits((prot as usize) << 16)
+            .expect("mprotect: invalid bit pattern");
+        let res = syscall::mprotect(addr as usize, len, prot);
+        if res.is_ok() {
+            mman::protected(addr as usize, len, prot);
+        }
+        e(res) as c_int
+    }
+
+    unsafe fn mremap(
+        old_address: *mut c_void,
+        old_size: usize,
+        new_size: usize,
+        flags: c_int,
+        new_address: *mut c_void,
+    ) -> *mut c_void {
+        e(mman::mremap(
+            old_address as usize,
+            old_size,
+            new_size,
+            flags,
+            new_address as usize,
+        )) as *mut c_void
     }
 
     unsafe fn msync(addr: *mut c_void, len: usize, flags: c_int) -> c_int {
-        eprintln!("msync {:p} {:x} {:x}", addr, len, flags);
-        e(Err(syscall::Error::new(syscall::ENOSYS))) as c_int
-        /* TODO
-        e(syscall::msync(
-            addr as usize,
-            round_up_to_page_size(len),
-            flags
-        )) as c_int
-        */
+        e(mman::msync(addr as usize, len, flags).map(|()| 0)) as c_int
     }
 
     unsafe fn munlock(addr: *const c_void, len: usize) -> c_int {
-        // Redox never swaps
+        mman::unlock(addr as usize, len);
         0
     }
 
     fn munlockall() -> c_int {
-        // Redox never swaps
+        mman::unlock_all();
         0
     }
 
     unsafe fn munmap(addr: *mut c_void, len: usize) -> c_int {
-        if e(syscall::funmap(addr as usize, round_up_to_page_size(len))) == !0 {
-            return !0;
-        }
-        0
+        e(mman::unmap(addr as usize, round_up_to_page_size(len)).map(|()| 0)) as c_int
     }
 
     fn nanosleep(rqtp: *const timespec, rmtp: *mut timespec) -> c_int {
@@ -697,36 +865,37 @@ impl Pal for Sys {
         } else {
             redox_rmtp = unsafe { redox_timespec::from(&*rmtp) };
         }
-        match e(syscall::nanosleep(&redox_rqtp, &mut redox_rmtp)) as c_int {
-            -1 => -1,
-            _ => {
-                unsafe {
-                    if !rmtp.is_null() {
-                        (*rmtp).tv_sec = redox_rmtp.tv_sec as time_t;
-                        (*rmtp).tv_nsec = redox_rmtp.tv_nsec as c_long;
-                    }
-                }
-                0
+        let res = blocking::call(Restart::Never, || {
+            e(syscall::nanosleep(&redox_rqtp, &mut redox_rmtp)) as c_int
+        });
+        // What's left matters most when it was interrupted
+        unsafe {
+            if !rmtp.is_null() {
+                (*rmtp).tv_sec = redox_rmtp.tv_sec as time_t;
+                (*rmtp).tv_nsec = redox_rmtp.tv_nsec as c_long;
             }
         }
+        res
     }
 
     fn open(path: &CStr, oflag: c_int, mode: mode_t) -> c_int {
         let path = path_from_c_str!(path);
 
-        match path::open(
-            path,
-            ((oflag as usize) & 0xFFFF_0000) | ((mode as usize) & 0xFFFF),
-        ) {
-            Ok(fd) => match c_int::try_from(fd) {
-                Ok(c_fd) => c_fd,
-                Err(_) => {
-                    let _ = syscall::close(fd);
-                    e(Err(Error::new(EMFILE))) as c_int
-                }
-            },
-            Err(error) => e(Err(error)) as c_int,
-        }
+        blocking::call(Restart::IfAsked, || {
+            match path::open(
+                path,
+                ((oflag as usize) & 0xFFFF_0000) | ((mode as usize) & 0xFFFF),
+            ) {
+                Ok(fd) => match c_int::try_from(fd) {
+                    Ok(c_fd) => c_fd,
+                    Err(_) => {
+                        let _ = syscall::close(fd);
+                        e(Err(Error::new(EMFILE))) as c_int
+                    }
+                },
+                Err(error) => e(Err(error)) as c_int,
+            }
+        })
     }
 
     fn pipe2(fds: &mut [c_int], flags: c_int) -> c_int {
@@ -742,7 +911,16 @@ impl Pal for Sys {
     }
 
     fn read(fd: c_int, buf: &mut [u8]) -> ssize_t {
-        e(syscall::read(fd as usize, buf)) as ssize_t
+        blocking::call(Restart::IfAsked, || {
+            if let Some(result) = emulated::read(fd, buf) {
+                return e(result) as ssize_t;
+            }
+            if let Some(count) = socket::read_peeked(fd, buf) {
+                return count as ssize_t;
+            }
+            mman::before_read(fd as usize, buf.len());
+            e(syscall::read(fd as usize, buf)) as ssize_t
+        })
     }
 
     fn fpath(fildes: c_int, out: &mut [u8]) -> ssize_t {
@@ -750,10 +928,7 @@ impl Pal for Sys {
     }
 
     fn readlink(pathname: &CStr, out: &mut [u8]) -> ssize_t {
-        match File::open(
-            pathname,
-            fcntl::O_RDONLY | fcntl::O_SYMLINK | fcntl::O_CLOEXEC,
-        ) {
+        match File::open(pathname, fcntl::O_RDONLY | fcntl::O_SYMLINK) {
             Ok(file) => Self::read(*file, out),
             Err(_) => return -1,
         }
@@ -761,7 +936,7 @@ impl Pal for Sys {
 
     fn rename(oldpath: &CStr, newpath: &CStr) -> c_int {
         let newpath = path_from_c_str!(newpath);
-        match File::open(oldpath, fcntl::O_PATH | fcntl::O_CLOEXEC) {
+        match File::open(oldpath, fcntl::O_PATH) {
             Ok(file) => e(syscall::frename(*file as usize, newpath)) as c_int,
             Err(_) => -1,
         }
@@ -776,10 +951,66 @@ impl Pal for Sys {
         e(syscall::sched_yield()) as c_int
     }
 
+    fn sethostname(name: &[u8]) -> c_int {
+        // uname reads it back from the file, which only root may write
+        let mut file = match File::create(
+            c_str!("/etc/hostname"),
+            fcntl::O_WRONLY | fcntl::O_TRUNC,
+            0o644,
+        ) {
+            Ok(ok) => ok,
+            Err(_) => return -1,
+        };
+        if file.write_all(name).is_err() || file.write_all(b"\n").is_err() {
+            return -1;
+        }
+        0
+    }
+
+    fn setns(_fd: c_int, _nstype: c_int) -> c_int {
+        unsafe { errno = ENOSYS };
+        -1
+    }
+
     fn setpgid(pid: pid_t, pgid: pid_t) -> c_int {
         e(syscall::setpgid(pid as usize, pgid as usize)) as c_int
     }
 
+    unsafe fn setrlimit(resource: c_int, rlim: *const rlimit) -> c_int {
+        let mut limits = RLIMITS.lock();
+        let limit = match limits.get_mut(resource as usize) {
+            Some(limit) => limit,
+            None => {
+                errno = EINVAL;
+                return -1;
+            }
+        };
+        let (cur, max) = ((*rlim).rlim_cur, (*rlim).rlim_max);
+        if cur > max {
+            errno = EINVAL;
+            return -1;
+        }
+        if max > limit.1 && Self::geteuid() != 0 {
+            errno = EPERM;
+            return -1;
+        }
+        *limit = (cur, max);
+        if resource as u64 == RLIMIT_CORE {
+            coredump::set_limit(cur);
+        }
+        0
+    }
+
+    fn setsid() -> pid_t {
+        // There are no sessions, so leading a new process group is as close as it gets
+        let pid = Self::getpid();
+        if e(syscall::setpgid(0, 0)) == !0 {
+            -1
+        } else {
+            pid
+        }
+    }
+
     fn setregid(rgid: gid_t, egid: gid_t) -> c_int {
         e(syscall::setregid(rgid as usize, egid as usize)) as c_int
     }
@@ -789,11 +1020,7 @@ impl Pal for Sys {
     }
 
     fn symlink(path1: &CStr, path2: &CStr) -> c_int {
-        let mut file = match File::create(
-            path2,
-            fcntl::O_WRONLY | fcntl::O_SYMLINK | fcntl::O_CLOEXEC,
-            0o777,
-        ) {
+        let mut file = match File::create(path2, fcntl::O_WRONLY | fcntl::O_SYMLINK, 0o777) {
             Ok(ok) => ok,
             Err(_) => return -1,
         };
@@ -805,46 +1032,83 @@ impl Pal for Sys {
         0
     }
 
+    fn sysinfo(info: *mut sysinfo) -> c_int {
+        fn inner(info: &mut sysinfo) -> Result<()> {
+            let mut uptime = redox_timespec::default();
+            syscall::clock_gettime(syscall::CLOCK_MONOTONIC, &mut uptime)?;
+
+            // The memory scheme counts frames as the blocks of a filesystem
+            let memory = File::open_bare("memory:", syscall::O_CLOEXEC)?;
+            let mut frames = redox_statvfs::default();
+            syscall::fstatvfs(memory.raw(), &mut frames)?;
+
+            // sys:context has a line for each context after the header
+            let mut contexts = Vec::new();
+            let mut file = File::open(c_str!("sys:context"), fcntl::O_RDONLY)?;
+            file.read_to_end(&mut contexts)
+                .map_err(|_| Error::new(EIO))?;
+            let procs = contexts
+                .split(|&b| b == b'\n')
+                .filter(|line| !line.is_empty())
+                .count()
+                .saturating_sub(1);
+
+            *info = unsafe { mem::zeroed() };
+            info.uptime = uptime.tv_sec as c_long;
+            // There are no load averages or swap
+            info.totalram = frames.f_blocks as c_ulong;
+            info.freeram = frames.f_bfree as c_ulong;
+            info.procs = procs.min(c_ushort::MAX as usize) as c_ushort;
+            info.mem_unit = frames.f_bsize as c_uint;
+            Ok(())
+        }
+
+        e(inner(unsafe { &mut *info }).map(|()| 0)) as c_int
+    }
+
+    fn timerfd_create(clockid: c_int, flags: c_int) -> c_int {
+        e(timerfd::create(clockid, flags)) as c_int
+    }
+
+    fn timerfd_gettime(fd: c_int, curr_value: *mut itimerspec) -> c_int {
+        e(timerfd::gettime(fd, curr_value).map(|()| 0)) as c_int
+    }
+
+    fn timerfd_settime(
+        fd: c_int,
+        flags: c_int,
+        new_value: *const itimerspec,
+        old_value: *mut itimerspec,
+    ) -> c_int {
+        e(timerfd::settime(fd, flags, new_value, old_value).map(|()| 0)) as c_int
+    }
+
     fn umask(mask: mode_t) -> mode_t {
         e(syscall::umask(mask as usize)) as mode_t
     }
 
     fn uname(utsname: *mut utsname) -> c_int {
-        fn gethostname(name: &mut [u8]) -> io::Result<()> {
-            if name.is_empty() {
-                return Ok(());
-            }
-
-            let mut file = File::open(
-                &CString::new("/etc/hostname").unwrap(),
-                fcntl::O_RDONLY | fcntl::O_CLOEXEC,
-            )?;
-
-            let mut read = 0;
-            let name_len = name.len();
-            loop {
-                match file.read(&mut name[read..name_len - 1])? {
-                    0 => break,
-                    n => read += n,
-                }
+        /// Copies the first line of the file at `path` into `dst`, cut short if it doesn't fit.
+        fn read_first_line(path: &CStr, dst: &mut [c_char]) -> io::Result<()> {
+            let mut line = Vec::new();
+            let file = File::open(path, fcntl::O_RDONLY)?;
+            BufReader::new(file).read_until(b'\n', &mut line)?;
+            let line = line.split(|&b| b == b'\n').next().unwrap_or(&[]);
+            let len = line.len().min(dst.len() - 1);
+            for (dst, &src) in dst.iter_mut().zip(&line[..len]) {
+                *dst = src as c_char;
             }
-            name[read] = 0;
+            dst[len] = 0;
             Ok(())
         }
 
-        fn inner(utsname: *mut utsname) -> CoreResult<(), i32> {
-            match gethostname(unsafe {
-                slice::from_raw_parts_mut(
-                    (*utsname).nodename.as_mut_ptr() as *mut u8,
-                    (*utsname).nodename.len(),
-                )
-            }) {
-                Ok(_) => (),
-                Err(_) => return Err(EIO),
+        fn inner(utsname: &mut utsname) -> CoreResult<(), i32> {
+            if read_first_line(c_str!("/etc/hostname"), &mut utsname.nodename).is_err() {
+                return Err(EIO);
             }
 
             let file_path = c_str!("sys:uname");
-            let mut file = match File::open(file_path, fcntl::O_RDONLY | fcntl::O_CLOEXEC) {
+            let mut file = match File::open(file_path, fcntl::O_RDONLY) {
                 Ok(ok) => ok,
                 Err(_) => return Err(EIO),
             };
@@ -869,23 +1133,30 @@ impl Pal for Sys {
                 }
             };
 
-            unsafe {
-                read_line(&mut (*utsname).sysname)?;
-                read_line(&mut (*utsname).release)?;
-                read_line(&mut (*utsname).machine)?;
-
-                // Version is not provided
-                ptr::write_bytes((*utsname).version.as_mut_ptr(), 0, UTSLENGTH);
+            read_line(&mut utsname.sysname)?;
+            read_line(&mut utsname.release)?;
+            read_line(&mut utsname.machine)?;
+            // Kernels that don't describe their build after the machine have their release as
+            // the version
+            if read_line(&mut utsname.version).is_err() {
+                utsname.version = utsname.release;
+            }
 
-                // Redox doesn't provide domainname in sys:uname
-                //read_line(&mut (*utsname).domainname)?;
-                ptr::write_bytes((*utsname).domainname.as_mut_ptr(), 0, UTSLENGTH);
+            // The domain isn't known to the kernel, so it's kept next to the hostname, and is
+            // "(none)" without one like on Linux
+            if read_first_line(c_str!("/etc/domainname"), &mut utsname.domainname).is_err()
+                || utsname.domainname[0] == 0
+            {
+                let none = b"(none)\0";
+                for (dst, &src) in utsname.domainname.iter_mut().zip(none) {
+                    *dst = src as c_char;
+                }
             }
 
             Ok(())
         }
 
-        match inner(utsname) {
+        match inner(unsafe { &mut *utsname }) {
             Ok(()) => 0,
             Err(err) => unsafe {
                 errno = err;
@@ -899,6 +1170,11 @@ impl Pal for Sys {
         e(canonicalize(path).and_then(|path| syscall::unlink(&path))) as c_int
     }
 
+    fn unshare(_flags: c_int) -> c_int {
+        unsafe { errno = ENOSYS };
+        -1
+    }
+
     fn waitpid(mut pid: pid_t, stat_loc: *mut c_int, options: c_int) -> pid_t {
         if pid == !0 {
             pid = 0;
@@ -943,7 +1219,9 @@ impl Pal for Sys {
         // it if (and only if) a ptrace traceme was activated during
         // the wait.
         let res = res.unwrap_or_else(|| loop {
-            let res = e(inner(&mut status, options | sys_wait::WUNTRACED));
+            let res = blocking::call(Restart::IfAsked, || {
+                e(inner(&mut status, options | sys_wait::WUNTRACED)) as pid_t
+            }) as usize;
 
             // TODO: Also handle special PIDs here
             if !syscall::wifstopped(res) || ptrace::is_traceme(pid) {
@@ -961,7 +1239,19 @@ impl Pal for Sys {
     }
 
     fn write(fd: c_int, buf: &[u8]) -> ssize_t {
-        e(syscall::write(fd as usize, buf)) as ssize_t
+        blocking::call(Restart::IfAsked, || {
+            if let Some(result) = emulated::write(fd, buf) {
+                return e(result) as ssize_t;
+            }
+            if let Err(err) = memfd::check_write(fd, buf.len()) {
+                return e(Err(err)) as ssize_t;
+            }
+            let res = syscall::write(fd as usize, buf);
+            if let Ok(written) = res {
+                mman::after_write(fd as usize, &buf[..written]);
+            }
+            e(res) as ssize_t
+        })
     }
 
     fn verify() -> bool {
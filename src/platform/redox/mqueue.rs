@@ -0,0 +1,245 @@
+//! POSIX message queues, as a client of the mqueue: scheme.
+//!
+//! A queue is opened as `mqueue:NAME`, honoring O_CREAT, O_EXCL and the mode, with its limits
+//! appended as `mqueue:NAME/MAXMSG/MSGSIZE` when it may be created. A message is written as its
+//! priority, a native-endian u32, followed by its bytes, and read back the same way, always the
+//! oldest of the highest priority. The scheme never blocks: a full queue fails writes and an
+//! empty one fails reads with EAGAIN, and it wakes the event queue when that changes. Waiting is
+//! left to this side so that the timeouts can be honored, and happens unless the descriptor is
+//! O_NONBLOCK, which is the queue's mq_flags.
+//!
+//! Reading `dup(fd, "attr")` gives the number of messages allowed, the largest message and the
+//! number of messages queued, as native-endian u64s. Writing a signal number to
+//! `dup(fd, "notify")` asks for it to be sent to this process when a message arrives on the empty
+//! queue, with 0 asking for no signal and -1 cancelling, and reading that same descriptor blocks
+//! until the notification happens, returning 0 instead if it's cancelled. Only one process may
+//! be registered at a time, so the others fail with EBUSY.
+
+use alloc::{format, vec, vec::Vec};
+use core::mem;
+use syscall::{
+    self,
+    data::{Event, TimeSpec},
+    flag::*,
+    Error, Result, EACCES, EAGAIN, EFAULT, EINVAL, ENOENT, ETIMEDOUT,
+};
+
+use crate::{
+    c_str::CStr,
+    fs::File,
+    header::{
+        fcntl,
+        limits::MQ_PRIO_MAX,
+        mqueue::mq_attr,
+        signal::{sigevent, SIGEV_NONE, SIGEV_SIGNAL},
+        time::{timespec, CLOCK_REALTIME},
+    },
+    platform::types::*,
+};
+
+const PRIORITY_SIZE: usize = mem::size_of::<u32>();
+
+fn path(name: &CStr) -> Result<&str> {
+    let name = core::str::from_utf8(name.to_bytes()).map_err(|_| Error::new(EINVAL))?;
+    if name.is_empty() {
+        return Err(Error::new(ENOENT));
+    }
+    if name.contains('/') {
+        return Err(Error::new(EACCES));
+    }
+    Ok(name)
+}
+
+pub fn open(name: &CStr, oflag: c_int, mode: mode_t, attr: *const mq_attr) -> Result<usize> {
+    let name = path(name)?;
+    let mut path = format!("mqueue:{}", name);
+    if oflag & fcntl::O_CREAT != 0 {
+        if let Some(attr) = unsafe { attr.as_ref() } {
+            if attr.mq_maxmsg <= 0 || attr.mq_msgsize <= 0 {
+                return Err(Error::new(EINVAL));
+            }
+            path = format!("{}/{}/{}", path, attr.mq_maxmsg, attr.mq_msgsize);
+        }
+    }
+
+    // Queues aren't inherited across exec, as POSIX has it
+    let flags = (oflag | fcntl::O_CLOEXEC) as usize & 0xFFFF_0000 | (mode as usize & 0xFFFF);
+    syscall::open(&path, flags)
+}
+
+pub fn unlink(name: &CStr) -> Result<()> {
+    syscall::unlink(&format!("mqueue:{}", path(name)?))?;
+    Ok(())
+}
+
+/// Waits for `fd` to get `flags` ready, or for `abs_timeout` on CLOCK_REALTIME to pass.
+struct Waiter {
+    fd: usize,
+    flags: EventFlags,
+    queue: File,
+    timer: Option<File>,
+}
+
+impl Waiter {
+    fn new(fd: usize, flags: EventFlags, abs_timeout: *const timespec) -> Result<Self> {
+        let queue = File::open_bare("event:", O_RDWR | O_CLOEXEC)?;
+        let timer = match unsafe { abs_timeout.as_ref() } {
+            Some(timeout) => {
+                if timeout.tv_nsec < 0 || timeout.tv_nsec >= 1_000_000_000 {
+                    return Err(Error::new(EINVAL));
+                }
+                let timer =
+                    File::open_bare(&format!("time:{}", CLOCK_REALTIME), O_RDWR | O_CLOEXEC)?;
+                syscall::write(
+                    timer.raw(),
+                    &TimeSpec {
+                        tv_sec: timeout.tv_sec as i64,
+                        tv_nsec: timeout.tv_nsec as i32,
+                    },
+                )?;
+                syscall::write(
+                    queue.raw(),
+                    &Event {
+                        id: timer.raw(),
+                        flags: EVENT_READ,
+                        data: 0,
+                    },
+                )?;
+                Some(timer)
+            }
+            None => None,
+        };
+        Ok(Waiter {
+            fd,
+            flags,
+            queue,
+            timer,
+        })
+    }
+
+    /// Runs `f` until it doesn't fail with EAGAIN, waiting in between.
+    fn run<T, F: FnMut() -> Result<T>>(&self, mut f: F) -> Result<T> {
+        loop {
+            // Registered before trying, so that a change in between isn't missed
+            syscall::write(
+                self.queue.raw(),
+                &Event {
+                    id: self.fd,
+                    flags: self.flags,
+                    data: 0,
+                },
+            )?;
+            match f() {
+                Err(err) if err.errno == EAGAIN => (),
+                res => return res,
+            }
+            let mut event = Event::default();
+            syscall::read(self.queue.raw(), &mut event)?;
+            if self.timer.as_ref().map(File::raw) == Some(event.id) {
+                return Err(Error::new(ETIMEDOUT));
+            }
+        }
+    }
+}
+
+fn nonblocking(fd: usize) -> Result<bool> {
+    Ok(syscall::fcntl(fd, F_GETFL, 0)? & O_NONBLOCK != 0)
+}
+
+/// Sends or receives with `f`, waiting as the descriptor and `abs_timeout` say.
+fn transfer<T, F: FnMut() -> Result<T>>(
+    fd: usize,
+    flags: EventFlags,
+    abs_timeout: *const timespec,
+    mut f: F,
+) -> Result<T> {
+    match f() {
+        Err(err) if err.errno == EAGAIN && !nonblocking(fd)? => {
+            Waiter::new(fd, flags, abs_timeout)?.run(f)
+        }
+        res => res,
+    }
+}
+
+pub fn timedsend(
+    fd: usize,
+    msg: &[u8],
+    priority: c_uint,
+    abs_timeout: *const timespec,
+) -> Result<()> {
+    if priority >= MQ_PRIO_MAX as c_uint {
+        return Err(Error::new(EINVAL));
+    }
+    let mut buf = Vec::with_capacity(PRIORITY_SIZE + msg.len());
+    buf.extend_from_slice(&(priority as u32).to_ne_bytes());
+    buf.extend_from_slice(msg);
+    transfer(fd, EVENT_WRITE, abs_timeout, || syscall::write(fd, &buf))?;
+    Ok(())
+}
+
+pub fn timedreceive(
+    fd: usize,
+    msg: &mut [u8],
+    priority: Option<&mut c_uint>,
+    abs_timeout: *const timespec,
+) -> Result<usize> {
+    let mut buf = vec![0; PRIORITY_SIZE + msg.len()];
+    let len = transfer(fd, EVENT_READ, abs_timeout, || syscall::read(fd, &mut buf))?;
+    if len < PRIORITY_SIZE {
+        return Err(Error::new(EFAULT));
+    }
+    let len = len - PRIORITY_SIZE;
+    msg[..len].copy_from_slice(&buf[PRIORITY_SIZE..PRIORITY_SIZE + len]);
+    if let Some(priority) = priority {
+        let mut bytes = [0; PRIORITY_SIZE];
+        bytes.copy_from_slice(&buf[..PRIORITY_SIZE]);
+        *priority = u32::from_ne_bytes(bytes) as c_uint;
+    }
+    Ok(len)
+}
+
+pub fn getsetattr(fd: usize, new: *const mq_attr, old: *mut mq_attr) -> Result<()> {
+    let flags = syscall::fcntl(fd, F_GETFL, 0)?;
+    if let Some(old) = unsafe { old.as_mut() } {
+        let attr = File::new_bare(syscall::dup(fd, b"attr")?);
+        let mut values = [0u64; 3];
+        let bytes = unsafe {
+            core::slice::from_raw_parts_mut(
+                values.as_mut_ptr() as *mut u8,
+                mem::size_of_val(&values),
+            )
+        };
+        syscall::read(attr.raw(), bytes)?;
+        *old = mq_attr {
+            mq_flags: (flags & O_NONBLOCK) as c_long,
+            mq_maxmsg: values[0] as c_long,
+            mq_msgsize: values[1] as c_long,
+            mq_curmsgs: values[2] as c_long,
+            __reserved: [0; 4],
+        };
+    }
+    if let Some(new) = unsafe { new.as_ref() } {
+        let flags = if new.mq_flags & fcntl::O_NONBLOCK as c_long != 0 {
+            flags | O_NONBLOCK
+        } else {
+            flags & !O_NONBLOCK
+        };
+        syscall::fcntl(fd, F_SETFL, flags)?;
+    }
+    Ok(())
+}
+
+pub fn notify(fd: usize, sevp: *const sigevent) -> Result<()> {
+    let signo = match unsafe { sevp.as_ref() } {
+        None => -1,
+        Some(sevp) => match sevp.sigev_notify {
+            SIGEV_NONE => 0,
+            // There is no way to queue a signal, so the value isn't passed along
+            SIGEV_SIGNAL if sevp.sigev_signo > 0 => sevp.sigev_signo,
+            _ => return Err(Error::new(EINVAL)),
+        },
+    };
+    let notify = File::new_bare(syscall::dup(fd, b"notify")?);
+    syscall::write(notify.raw(), &signo.to_ne_bytes())?;
+    Ok(())
+}
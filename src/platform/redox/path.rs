@@ -7,8 +7,7 @@ use alloc::{
     vec::Vec,
 };
 
-use super::FdGuard;
-use crate::sync::Mutex;
+use crate::{fs::File, sync::Mutex};
 
 // TODO: Define in syscall
 const PATH_MAX: usize = 4096;
@@ -177,9 +176,9 @@ pub fn open(path: &str, flags: usize) -> Result<usize> {
             Ok(fd) => return Ok(fd),
             Err(error) if error == Error::new(EXDEV) => {
                 let resolve_flags = O_CLOEXEC | O_SYMLINK | O_RDONLY;
-                let resolve_fd = FdGuard::new(syscall::open(&*canon, resolve_flags)?);
+                let resolve_fd = File::open_bare(&canon, resolve_flags)?;
 
-                let bytes_read = syscall::read(*resolve_fd, &mut resolve_buf)?;
+                let bytes_read = syscall::read(resolve_fd.raw(), &mut resolve_buf)?;
                 // TODO: make resolve_buf PATH_MAX + 1 bytes?
                 if bytes_read == resolve_buf.len() {
                     return Err(Error::new(ENAMETOOLONG));
@@ -8,17 +8,17 @@ use super::super::{errno, types::*, Pal, PalPtrace, PalSignal, Sys};
 #[cfg(target_arch = "aarch64")]
 use crate::header::arch_aarch64_user::user_regs_struct;
 #[cfg(target_arch = "x86_64")]
-use crate::header::arch_x64_user::user_regs_struct;
+use crate::header::arch_x64_user::{user_fpregs_struct, user_regs_struct};
 use crate::{
     c_str::CString,
     fs::File,
     header::{errno as errnoh, fcntl, signal, sys_ptrace},
-    io::{self, prelude::*},
+    io::{self, prelude::*, SeekFrom},
     sync::{Mutex, Once},
 };
 
 use alloc::collections::{btree_map::Entry, BTreeMap};
-use core::mem;
+use core::{mem, ptr};
 use syscall;
 
 pub struct Session {
@@ -62,34 +62,31 @@ pub fn is_traceme(pid: pid_t) -> bool {
     )
     .is_ok()
 }
+fn open_session(pid: pid_t, first: bool) -> io::Result<Session> {
+    const NEW_FLAGS: c_int = fcntl::O_RDWR | fcntl::O_CLOEXEC;
+
+    let open = |file: &str| {
+        File::open(
+            &CString::new(format!("proc:{}/{}", pid, file)).unwrap(),
+            NEW_FLAGS,
+        )
+    };
+    Ok(Session {
+        first,
+        tracer: open("trace")?,
+        mem: open("mem")?,
+        regs: open("regs/int")?,
+        fpregs: open("regs/float")?,
+    })
+}
 pub fn get_session(
     sessions: &mut BTreeMap<pid_t, Session>,
     pid: pid_t,
 ) -> io::Result<&mut Session> {
-    const NEW_FLAGS: c_int = fcntl::O_RDWR | fcntl::O_CLOEXEC;
-
     match sessions.entry(pid) {
         Entry::Vacant(entry) => {
             if is_traceme(pid) {
-                Ok(entry.insert(Session {
-                    first: true,
-                    tracer: File::open(
-                        &CString::new(format!("proc:{}/trace", pid)).unwrap(),
-                        NEW_FLAGS,
-                    )?,
-                    mem: File::open(
-                        &CString::new(format!("proc:{}/mem", pid)).unwrap(),
-                        NEW_FLAGS,
-                    )?,
-                    regs: File::open(
-                        &CString::new(format!("proc:{}/regs/int", pid)).unwrap(),
-                        NEW_FLAGS,
-                    )?,
-                    fpregs: File::open(
-                        &CString::new(format!("proc:{}/regs/float", pid)).unwrap(),
-                        NEW_FLAGS,
-                    )?,
-                }))
+                Ok(entry.insert(open_session(pid, true)?))
             } else {
                 unsafe {
                     errno = errnoh::ESRCH;
@@ -107,7 +104,7 @@ fn inner_ptrace(
     pid: pid_t,
     addr: *mut c_void,
     data: *mut c_void,
-) -> io::Result<c_int> {
+) -> io::Result<c_long> {
     //TODO: aarch64
     unimplemented!("inner_ptrace not implemented on aarch64");
 }
@@ -118,31 +115,97 @@ fn inner_ptrace(
     pid: pid_t,
     addr: *mut c_void,
     data: *mut c_void,
-) -> io::Result<c_int> {
+) -> io::Result<c_long> {
     //TODO: x86
     unimplemented!("inner_ptrace not implemented on x86");
 }
 
+/// The registers Redox gives, laid out as for PTRACE_GETREGS and core dumps.
+#[cfg(target_arch = "x86_64")]
+pub(crate) fn user_regs(redox_regs: &syscall::IntRegisters) -> user_regs_struct {
+    user_regs_struct {
+        r15: redox_regs.r15 as _,
+        r14: redox_regs.r14 as _,
+        r13: redox_regs.r13 as _,
+        r12: redox_regs.r12 as _,
+        rbp: redox_regs.rbp as _,
+        rbx: redox_regs.rbx as _,
+        r11: redox_regs.r11 as _,
+        r10: redox_regs.r10 as _,
+        r9: redox_regs.r9 as _,
+        r8: redox_regs.r8 as _,
+        rax: redox_regs.rax as _,
+        rcx: redox_regs.rcx as _,
+        rdx: redox_regs.rdx as _,
+        rsi: redox_regs.rsi as _,
+        rdi: redox_regs.rdi as _,
+        orig_rax: redox_regs.rax as _, // redox_regs.orig_rax as _,
+        rip: redox_regs.rip as _,
+        cs: redox_regs.cs as _,
+        eflags: redox_regs.rflags as _,
+        rsp: redox_regs.rsp as _,
+        ss: redox_regs.ss as _,
+        fs_base: 0, // fs_base: redox_regs.fs_base as _,
+        gs_base: 0, // gs_base: redox_regs.gs_base as _,
+        ds: 0,      // ds: redox_regs.ds as _,
+        es: 0,      // es: redox_regs.es as _,
+        fs: redox_regs.fs as _,
+        gs: 0, // gs: redox_regs.gs as _,
+    }
+}
+
 #[cfg(target_arch = "x86_64")]
 fn inner_ptrace(
     request: c_int,
     pid: pid_t,
     addr: *mut c_void,
     data: *mut c_void,
-) -> io::Result<c_int> {
+) -> io::Result<c_long> {
     let state = init_state();
 
     if request == sys_ptrace::PTRACE_TRACEME {
-        // Mark this child as traced, parent will check for this marker file
+        // Mark this child as traced, parent will check for this marker file. It's kept open, and
+        // across exec, for as long as the child lives.
         let pid = Sys::getpid();
-        mem::forget(File::open(
+        let marker = Sys::open(
             &CString::new(format!("chan:ptrace-relibc/{}/traceme", pid)).unwrap(),
             fcntl::O_CREAT | fcntl::O_PATH | fcntl::O_EXCL,
-        )?);
+            0,
+        );
+        if marker < 0 {
+            return Err(io::last_os_error());
+        }
         return Ok(0);
     }
 
     let mut sessions = state.sessions.lock();
+
+    match request {
+        sys_ptrace::PTRACE_ATTACH => {
+            if sessions.contains_key(&pid) {
+                unsafe {
+                    errno = errnoh::EPERM;
+                }
+                return Err(io::last_os_error());
+            }
+            // Unlike a child that asked to be traced, this one isn't stopped after a syscall
+            sessions.insert(pid, open_session(pid, false)?);
+            Sys::kill(pid, signal::SIGSTOP as _);
+            return Ok(0);
+        }
+        sys_ptrace::PTRACE_DETACH => {
+            get_session(&mut sessions, pid)?;
+            // Closing the tracer lets the process run untraced again
+            sessions.remove(&pid);
+            if !data.is_null() {
+                Sys::kill(pid, data as c_int);
+            }
+            Sys::kill(pid, signal::SIGCONT as _);
+            return Ok(0);
+        }
+        _ => (),
+    }
+
     let session = get_session(&mut sessions, pid)?;
 
     match request {
@@ -179,35 +242,7 @@ fn inner_ptrace(
             let c_regs = unsafe { &mut *(data as *mut user_regs_struct) };
             let mut redox_regs = syscall::IntRegisters::default();
             (&mut &session.regs).read(&mut redox_regs)?;
-            *c_regs = user_regs_struct {
-                r15: redox_regs.r15 as _,
-                r14: redox_regs.r14 as _,
-                r13: redox_regs.r13 as _,
-                r12: redox_regs.r12 as _,
-                rbp: redox_regs.rbp as _,
-                rbx: redox_regs.rbx as _,
-                r11: redox_regs.r11 as _,
-                r10: redox_regs.r10 as _,
-                r9: redox_regs.r9 as _,
-                r8: redox_regs.r8 as _,
-                rax: redox_regs.rax as _,
-                rcx: redox_regs.rcx as _,
-                rdx: redox_regs.rdx as _,
-                rsi: redox_regs.rsi as _,
-                rdi: redox_regs.rdi as _,
-                orig_rax: redox_regs.rax as _, // redox_regs.orig_rax as _,
-                rip: redox_regs.rip as _,
-                cs: redox_regs.cs as _,
-                eflags: redox_regs.rflags as _,
-                rsp: redox_regs.rsp as _,
-                ss: redox_regs.ss as _,
-                fs_base: 0, // fs_base: redox_regs.fs_base as _,
-                gs_base: 0, // gs_base: redox_regs.gs_base as _,
-                ds: 0,      // ds: redox_regs.ds as _,
-                es: 0,      // es: redox_regs.es as _,
-                fs: redox_regs.fs as _,
-                gs: 0, // gs: redox_regs.gs as _,
-            };
+            *c_regs = user_regs(&redox_regs);
             Ok(0)
         }
         sys_ptrace::PTRACE_SETREGS => {
@@ -244,12 +279,59 @@ fn inner_ptrace(
             (&mut &session.regs).write(&redox_regs)?;
             Ok(0)
         }
-        _ => unimplemented!(),
+        sys_ptrace::PTRACE_GETFPREGS => {
+            // Both are laid out like the area FXSAVE stores, which the C struct has the whole of
+            let mut redox_fpregs = syscall::FloatRegisters::default();
+            (&mut &session.fpregs).read(&mut redox_fpregs)?;
+            unsafe {
+                ptr::write_bytes(data as *mut user_fpregs_struct, 0, 1);
+                ptr::copy_nonoverlapping(
+                    &redox_fpregs as *const _ as *const u8,
+                    data as *mut u8,
+                    mem::size_of::<syscall::FloatRegisters>(),
+                );
+            }
+            Ok(0)
+        }
+        sys_ptrace::PTRACE_SETFPREGS => {
+            let mut redox_fpregs = syscall::FloatRegisters::default();
+            unsafe {
+                ptr::copy_nonoverlapping(
+                    data as *const u8,
+                    &mut redox_fpregs as *mut _ as *mut u8,
+                    mem::size_of::<syscall::FloatRegisters>(),
+                );
+            }
+            (&mut &session.fpregs).write(&redox_fpregs)?;
+            Ok(0)
+        }
+        sys_ptrace::PTRACE_PEEKTEXT | sys_ptrace::PTRACE_PEEKDATA => {
+            // Text and data share the address space, so they're the same
+            let mut word = [0; mem::size_of::<c_long>()];
+            session.mem.seek(SeekFrom::Start(addr as u64))?;
+            session.mem.read_exact(&mut word)?;
+            Ok(c_long::from_ne_bytes(word))
+        }
+        sys_ptrace::PTRACE_POKETEXT | sys_ptrace::PTRACE_POKEDATA => {
+            session.mem.seek(SeekFrom::Start(addr as u64))?;
+            session.mem.write_all(&(data as c_long).to_ne_bytes())?;
+            Ok(0)
+        }
+        sys_ptrace::PTRACE_KILL => {
+            Sys::kill(pid, signal::SIGKILL as _);
+            Ok(0)
+        }
+        _ => {
+            unsafe {
+                errno = errnoh::EIO;
+            }
+            Err(io::last_os_error())
+        }
     }
 }
 
 impl PalPtrace for Sys {
-    fn ptrace(request: c_int, pid: pid_t, addr: *mut c_void, data: *mut c_void) -> c_int {
+    fn ptrace(request: c_int, pid: pid_t, addr: *mut c_void, data: *mut c_void) -> c_long {
         inner_ptrace(request, pid, addr, data).unwrap_or(-1)
     }
 }
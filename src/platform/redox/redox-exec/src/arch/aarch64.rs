@@ -62,9 +62,17 @@ __relibc_internal_fork_wrapper:
     str x29, [sp, #-8]!
     str x30, [sp, #-8]!
 
+    // The low halves of v8-v15 are preserved too
+    stp d8, d9, [sp, #-16]!
+    stp d10, d11, [sp, #-16]!
+    stp d12, d13, [sp, #-16]!
+    stp d14, d15, [sp, #-16]!
+
     sub sp, sp, #32
 
-    //TODO: store floating point regs
+    // The first two words are written by fork_inner, for __relibc_internal_fork_hook
+    mrs x9, fpcr
+    str x9, [sp, #16]
 
     mov x0, sp
     bl __relibc_internal_fork_impl
@@ -80,13 +88,18 @@ __relibc_internal_fork_ret:
     ldr x1, [sp, #8]
     bl __relibc_internal_fork_hook
 
-    //TODO: load floating point regs
+    ldr x9, [sp, #16]
+    msr fpcr, x9
 
     mov x0, xzr
 
     .p2align 4
 2:
     add sp, sp, #32
+    ldp d14, d15, [sp], #16
+    ldp d12, d13, [sp], #16
+    ldp d10, d11, [sp], #16
+    ldp d8, d9, [sp], #16
     ldr x30, [sp], #8
     ldr x29, [sp], #8
     ldr x28, [sp], #8
@@ -466,37 +466,45 @@ fn fork_inner(initial_rsp: *mut usize) -> Result<usize> {
         {
             let cur_addr_space_fd = FdGuard::new(syscall::dup(*cur_pid_fd, b"addrspace")?);
 
-            // FIXME: Find mappings which use external file descriptors
+            // Mappings of other schemes' files aren't copied here. relibc maps the shared ones
+            // again in the child, as it knows which files they are.
 
             let new_addr_space_fd = FdGuard::new(syscall::dup(*cur_addr_space_fd, b"exclusive")?);
 
-            let mut buf = vec! [0_u8; 4096];
-            let mut bytes_read = 0;
+            // Nothing here may allocate, as relibc holds the locks of its heap while forking. So
+            // the grants are read a buffer at a time, keeping a grant that's cut off for the next
+            // read.
+            const GRANT_SIZE: usize = size_of::<usize>() * 4;
+            let mut buf = [0_u8; 4096];
+            let mut len = 0;
 
             loop {
-                let new_bytes_read = syscall::read(*cur_addr_space_fd, &mut buf[bytes_read..])?;
+                let new_bytes_read = syscall::read(*cur_addr_space_fd, &mut buf[len..])?;
 
                 if new_bytes_read == 0 { break }
 
-                bytes_read += new_bytes_read;
-            }
-            let bytes = &buf[..bytes_read];
+                len += new_bytes_read;
+                let whole = len / GRANT_SIZE * GRANT_SIZE;
 
-            for struct_bytes in bytes.array_chunks::<{size_of::<usize>() * 4}>() {
-                let mut words = struct_bytes.array_chunks::<{size_of::<usize>()}>().copied().map(usize::from_ne_bytes);
+                for struct_bytes in buf[..whole].array_chunks::<GRANT_SIZE>() {
+                    let mut words = struct_bytes.array_chunks::<{size_of::<usize>()}>().copied().map(usize::from_ne_bytes);
 
-                let addr = words.next().unwrap();
-                let size = words.next().unwrap();
-                let flags = words.next().unwrap();
-                let offset = words.next().unwrap();
+                    let addr = words.next().unwrap();
+                    let size = words.next().unwrap();
+                    let flags = words.next().unwrap();
+                    let offset = words.next().unwrap();
 
-                if flags & 0x8000_0000 == 0 {
-                    continue;
-                }
-                let map_flags = MapFlags::from_bits_truncate(flags);
+                    if flags & 0x8000_0000 == 0 {
+                        continue;
+                    }
+                    let map_flags = MapFlags::from_bits_truncate(flags);
 
-                let grant_fd = FdGuard::new(syscall::dup(*cur_addr_space_fd, alloc::format!("grant-{:x}", addr).as_bytes())?);
-                mmap_remote(&new_addr_space_fd, &grant_fd, offset, addr, size, map_flags)?;
+                    let mut name = [0_u8; 32];
+                    let grant_fd = FdGuard::new(syscall::dup(*cur_addr_space_fd, grant_name(addr, &mut name))?);
+                    mmap_remote(&new_addr_space_fd, &grant_fd, offset, addr, size, map_flags)?;
+                }
+                buf.copy_within(whole..len, 0);
+                len -= whole;
             }
             let new_addr_space_sel_fd = FdGuard::new(syscall::dup(*new_pid_fd, b"current-addrspace")?);
 
@@ -528,6 +536,19 @@ fn fork_inner(initial_rsp: *mut usize) -> Result<usize> {
     Ok(new_pid)
 }
 
+/// Writes the name a grant is opened with, `grant-` and its address in hex, to `buf`.
+fn grant_name(addr: usize, buf: &mut [u8; 32]) -> &[u8] {
+    const PREFIX: &[u8] = b"grant-";
+    let digits = ((usize::BITS - addr.leading_zeros() + 3) / 4).max(1) as usize;
+
+    buf[..PREFIX.len()].copy_from_slice(PREFIX);
+    for i in 0..digits {
+        let nibble = (addr >> (4 * (digits - 1 - i))) & 0xf;
+        buf[PREFIX.len() + i] = b"0123456789abcdef"[nibble];
+    }
+    &buf[..PREFIX.len() + digits]
+}
+
 pub fn new_context() -> Result<(FdGuard, usize)> {
     // Create a new context (fields such as uid/gid will be inherited from the current context).
     let fd = FdGuard::new(syscall::open("thisproc:new/open_via_dup", syscall::O_CLOEXEC)?);
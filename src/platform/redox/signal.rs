@@ -1,19 +1,112 @@
-use core::mem;
+use core::{
+    mem, ptr,
+    sync::atomic::{AtomicI32, AtomicU64, AtomicUsize, Ordering},
+};
 use syscall;
 
 use super::{
     super::{types::*, Pal, PalSignal},
-    e, Sys,
+    coredump, e, Sys,
 };
 use crate::{
     header::{
         errno::EINVAL,
-        signal::{sigaction, sigset_t, stack_t},
+        signal::{
+            __siginfo_rt, sigaction, siginfo_t, sigset_t, sigval, stack_t, SA_RESTART, SA_SIGINFO,
+            SIG_DFL, SIG_IGN, SI_USER,
+        },
         sys_time::{itimerval, ITIMER_REAL},
     },
     platform::errno,
 };
 
+/// The signals caught by a handler that was installed without SA_RESTART, one bit each from
+/// signal 1 on. Redox doesn't resume what a signal interrupted, so the Pal does.
+static NO_RESTART: AtomicU64 = AtomicU64::new(0);
+
+/// Whether a call that a signal interrupted is to be resumed. Which signal it was isn't known, so
+/// only if every handler asked for it.
+pub fn handlers_restart() -> bool {
+    NO_RESTART.load(Ordering::Relaxed) == 0
+}
+
+const NO_HANDLER: AtomicUsize = AtomicUsize::new(SIG_DFL);
+/// The handlers given to sigaction, by signal. The kernel calls `interrupt` instead, which calls
+/// them, so that a thread waiting with a signal mask installed learns that a handler ran.
+static HANDLERS: [AtomicUsize; 65] = [NO_HANDLER; 65];
+/// The signals whose handler was installed with SA_SIGINFO, one bit each from signal 1 on
+static SIGINFO: AtomicU64 = AtomicU64::new(0);
+
+/// Redox signals carry nothing, so the code and value sigqueue sends a signal to this process
+/// with wait here for its handler, one for each signal. The bits tell the signals that have one.
+static QUEUED: AtomicU64 = AtomicU64::new(0);
+const NO_CODE: AtomicI32 = AtomicI32::new(SI_USER);
+static QUEUED_CODES: [AtomicI32; 65] = [NO_CODE; 65];
+const NO_VALUE: AtomicUsize = AtomicUsize::new(0);
+static QUEUED_VALUES: [AtomicUsize; 65] = [NO_VALUE; 65];
+
+/// The number of threads between wake_on_handler and stop_waking, which spares the others
+/// reading the TLS
+static WAITING: AtomicUsize = AtomicUsize::new(0);
+const NO_WAKE: usize = !0;
+/// The descriptor `interrupt` writes to after a handler ran on this thread
+#[thread_local]
+static WAKE: AtomicUsize = AtomicUsize::new(NO_WAKE);
+
+/// What a handler installed with SA_SIGINFO is told of `sig`.
+fn siginfo(sig: usize) -> siginfo_t {
+    let mut info: siginfo_t = unsafe { mem::zeroed() };
+    info.si_signo = sig as c_int;
+    let bit = 1 << (sig - 1);
+    if QUEUED.fetch_and(!bit, Ordering::Acquire) & bit != 0 {
+        info.si_code = QUEUED_CODES[sig].load(Ordering::Relaxed);
+        info.__si_fields.__rt = __siginfo_rt {
+            si_pid: Sys::getpid(),
+            si_uid: Sys::getuid(),
+            si_value: sigval {
+                sival_ptr: QUEUED_VALUES[sig].load(Ordering::Relaxed) as *mut c_void,
+            },
+        };
+    }
+    info
+}
+
+extern "C" fn interrupt(sig: usize) {
+    let handler = HANDLERS
+        .get(sig)
+        .map_or(SIG_DFL, |h| h.load(Ordering::Relaxed));
+    if handler != SIG_DFL && handler != SIG_IGN {
+        if SIGINFO.load(Ordering::Relaxed) & 1 << (sig - 1) != 0 {
+            let handler: extern "C" fn(c_int, *mut siginfo_t, *mut c_void) =
+                unsafe { mem::transmute(handler) };
+            let mut info = siginfo(sig);
+            handler(sig as c_int, &mut info, ptr::null_mut());
+        } else {
+            let handler: extern "C" fn(c_int) = unsafe { mem::transmute(handler) };
+            handler(sig as c_int);
+        }
+    }
+    if WAITING.load(Ordering::SeqCst) != 0 {
+        let wake = WAKE.load(Ordering::Relaxed);
+        if wake != NO_WAKE {
+            let _ = syscall::write(wake, &[0]);
+        }
+    }
+}
+
+/// Makes the handlers that run on this thread write a byte to `fd`, until stop_waking. Waiting
+/// for `fd` too, a wait can't miss a signal handled just before it blocks, as happens when the
+/// signal mask is installed by a separate call.
+pub fn wake_on_handler(fd: usize) {
+    WAITING.fetch_add(1, Ordering::SeqCst);
+    WAKE.store(fd, Ordering::SeqCst);
+}
+
+pub fn stop_waking() {
+    WAKE.store(NO_WAKE, Ordering::SeqCst);
+    WAITING.fetch_sub(1, Ordering::SeqCst);
+}
+
 impl PalSignal for Sys {
     fn getitimer(which: c_int, out: *mut itimerval) -> c_int {
         let path = match which {
@@ -106,9 +199,19 @@ impl PalSignal for Sys {
     }
 
     fn sigaction(sig: c_int, act: Option<&sigaction>, oact: Option<&mut sigaction>) -> c_int {
+        let slot = HANDLERS.get(sig as usize);
+        let previous = slot.map_or(SIG_DFL, |slot| slot.load(Ordering::Relaxed));
         let new_opt = act.map(|act| {
             let m = act.sa_mask;
-            let sa_handler = unsafe { mem::transmute(act.sa_handler) };
+            let act_handler = unsafe { act.__sa_handler.sa_handler };
+            let handler = act_handler.map_or(SIG_DFL, |handler| handler as usize);
+            let sa_handler = match slot {
+                Some(slot) if handler != SIG_DFL && handler != SIG_IGN => {
+                    slot.store(handler, Ordering::Relaxed);
+                    Some(interrupt as extern "C" fn(usize))
+                }
+                _ => coredump::handler_for(sig as usize, unsafe { mem::transmute(act_handler) }),
+            };
             syscall::SigAction {
                 sa_handler,
                 sa_mask: [m as u64, 0],
@@ -122,8 +225,35 @@ impl PalSignal for Sys {
             new_opt.as_ref(),
             old_opt.as_mut(),
         )) as c_int;
+        if let (0, Some(act)) = (ret, act) {
+            if (1..=64).contains(&sig) {
+                let handler = unsafe { act.__sa_handler.sa_handler }
+                    .map_or(SIG_DFL, |handler| handler as usize);
+                let bit = 1u64 << (sig - 1);
+                if handler != SIG_DFL
+                    && handler != SIG_IGN
+                    && act.sa_flags as usize & SA_RESTART == 0
+                {
+                    NO_RESTART.fetch_or(bit, Ordering::Relaxed);
+                } else {
+                    NO_RESTART.fetch_and(!bit, Ordering::Relaxed);
+                }
+                if act.sa_flags as usize & SA_SIGINFO != 0 {
+                    SIGINFO.fetch_or(bit, Ordering::Relaxed);
+                } else {
+                    SIGINFO.fetch_and(!bit, Ordering::Relaxed);
+                }
+            }
+        }
         if let (Some(old), Some(oact)) = (old_opt, oact) {
-            oact.sa_handler = unsafe { mem::transmute(old.sa_handler) };
+            let handler = old.sa_handler.map_or(SIG_DFL, |handler| handler as usize);
+            oact.__sa_handler.sa_handler = if coredump::is_dump(old.sa_handler) {
+                None
+            } else if handler == interrupt as usize {
+                unsafe { mem::transmute(previous) }
+            } else {
+                unsafe { mem::transmute(old.sa_handler) }
+            };
             let m = old.sa_mask;
             oact.sa_mask = m[0] as c_ulong;
             oact.sa_flags = old.sa_flags.bits() as c_ulong;
@@ -152,4 +282,15 @@ impl PalSignal for Sys {
         }
         ret
     }
+
+    fn sigqueue(pid: pid_t, sig: c_int, code: c_int, value: sigval) -> c_int {
+        // Those sent to other processes arrive without the code and value
+        if pid == Self::getpid() && (1..=64).contains(&sig) {
+            QUEUED_CODES[sig as usize].store(code, Ordering::Relaxed);
+            QUEUED_VALUES[sig as usize]
+                .store(unsafe { value.sival_ptr } as usize, Ordering::Relaxed);
+            QUEUED.fetch_or(1 << (sig - 1), Ordering::Release);
+        }
+        Self::kill(pid, sig)
+    }
 }
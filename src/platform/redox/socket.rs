@@ -1,21 +1,45 @@
-use alloc::vec::Vec;
-use core::{cmp, mem, ptr, slice, str};
+use alloc::{borrow::ToOwned, collections::BTreeMap, string::String, sync::Arc, vec::Vec};
+use core::{
+    cmp, mem, ptr, slice, str,
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+};
 use syscall::{self, flag::*, Result};
 
 use super::{
     super::{errno, types::*, Pal, PalSocket},
-    e, Sys,
+    e, memfd,
+    path::canonicalize,
+    Sys,
 };
-use crate::header::{
-    arpa_inet::inet_aton,
-    netinet_in::{in_addr, in_port_t, sockaddr_in},
-    string::strnlen,
-    sys_socket::{constants::*, sa_family_t, sockaddr, socklen_t},
-    sys_time::timeval,
-    sys_un::sockaddr_un,
+use crate::{
+    fs::File,
+    header::{
+        arpa_inet::inet_aton,
+        ifaddrs::{self, IfAddr},
+        netinet_in::{
+            self, in_addr, in_port_t, ip_mreq, ip_mreqn, ipv6_mreq, sockaddr_in, IPV6_JOIN_GROUP,
+            IPV6_LEAVE_GROUP, IPV6_MULTICAST_HOPS, IPV6_MULTICAST_IF, IPV6_MULTICAST_LOOP,
+            IP_ADD_MEMBERSHIP, IP_DROP_MEMBERSHIP, IP_MULTICAST_IF, IP_MULTICAST_LOOP,
+            IP_MULTICAST_TTL, IP_TOS, IP_TTL,
+        },
+        netinet_tcp::TCP_NODELAY,
+        sys_socket::{cmsghdr, constants::*, msghdr, sa_family_t, sockaddr, socklen_t},
+        sys_time::timeval,
+        sys_uio::{gather, iovec, scatter},
+        sys_un::sockaddr_un,
+    },
+    sync::{fork::ForkLock, Mutex},
 };
 
 macro_rules! bind_or_connect {
+    (bind unix, $path:expr) => {
+        create_socket_file($path).map(|()| Some($path.to_owned()))
+    };
+    (connect unix, $path:expr) => {
+        syscall::open($path, O_STAT | O_CLOEXEC)
+            .and_then(syscall::close)
+            .map(|_| None)
+    };
     (bind $path:expr) => {
         concat!("/", $path)
     };
@@ -38,6 +62,9 @@ macro_rules! bind_or_connect {
             return -1;
         }
 
+        // Placeholder file created by binding to a filesystem address, removed again on failure
+        let mut socket_file: Option<String> = None;
+
         let path = match (*$address).sa_family as c_int {
             AF_INET => {
                 if ($address_len as usize) != mem::size_of::<sockaddr_in>() {
@@ -63,33 +90,25 @@ macro_rules! bind_or_connect {
             },
             AF_UNIX => {
                 let data = &*($address as *const sockaddr_un);
-
-                // NOTE: It's UB to access data in given address that exceeds
-                // the given address length.
-
-                let maxlen = cmp::min(
-                    // Max path length of the full-sized struct
-                    data.sun_path.len(),
-                    // Length inferred from given addrlen
-                    $address_len as usize - data.path_offset()
-                );
-                let len = cmp::min(
-                    // The maximum length of the address
-                    maxlen,
-                    // The first NUL byte, if any
-                    strnlen(&data.sun_path as *const _, maxlen as size_t),
-                );
-
-                let addr = slice::from_raw_parts(
-                    &data.sun_path as *const _ as *const u8,
-                    len,
-                );
-                let path = format!(
-                    "{}",
-                    str::from_utf8(addr).unwrap()
-                );
+                let (path, in_filesystem) = match unix_path(data, $address_len) {
+                    Ok(ok) => ok,
+                    Err(err) => {
+                        errno = err.errno;
+                        return -1;
+                    }
+                };
                 trace!("path: {:?}", path);
 
+                if in_filesystem {
+                    socket_file = match bind_or_connect!($mode unix, &path) {
+                        Ok(socket_file) => socket_file,
+                        Err(err) => {
+                            errno = err.errno;
+                            return -1;
+                        }
+                    };
+                }
+
                 path
             },
             _ => {
@@ -101,24 +120,94 @@ macro_rules! bind_or_connect {
         // Duplicate the socket, and then duplicate the copy back to the original fd
         let fd = e(syscall::dup($socket as usize, path.as_bytes()));
         if (fd as c_int) < 0 {
+            if let Some(socket_file) = socket_file {
+                let _ = syscall::unlink(&socket_file);
+            }
             return -1;
         }
         fd
     }};
 }
 
+/// Scheme-native name under which abstract addresses (those starting with a NUL byte) are
+/// registered. They never appear in the filesystem.
+const UNIX_ABSTRACT_PREFIX: &str = "abstract:";
+
+/// Resolves a Unix socket address to the name used with the IPC scheme, and whether that name is
+/// a filesystem path.
+///
+/// Filesystem paths are canonicalized, so that relative addresses name the same socket from any
+/// working directory. Paths that already carry a scheme (`foo:bar`) and abstract addresses are
+/// passed through as scheme-native names.
+unsafe fn unix_path(data: &sockaddr_un, address_len: socklen_t) -> Result<(String, bool)> {
+    // NOTE: It's UB to access data in given address that exceeds
+    // the given address length.
+
+    let maxlen = cmp::min(
+        // Max path length of the full-sized struct
+        data.sun_path.len(),
+        // Length inferred from given addrlen
+        (address_len as usize).saturating_sub(data.path_offset()),
+    );
+    let bytes = slice::from_raw_parts(&data.sun_path as *const _ as *const u8, maxlen);
+
+    if bytes.first() == Some(&0) {
+        // Abstract names are not NUL-terminated, their length is given by addrlen alone
+        let name = str::from_utf8(&bytes[1..]).map_err(|_| syscall::Error::new(syscall::EINVAL))?;
+        return Ok((format!("{}{}", UNIX_ABSTRACT_PREFIX, name), false));
+    }
+
+    let len = memchr::memchr(0, bytes).unwrap_or(maxlen);
+    if len == 0 {
+        return Err(syscall::Error::new(syscall::EINVAL));
+    }
+    let path = str::from_utf8(&bytes[..len]).map_err(|_| syscall::Error::new(syscall::EINVAL))?;
+    if path.contains(':') {
+        return Ok((path.into(), false));
+    }
+    Ok((canonicalize(path)?, true))
+}
+
+/// Creates the placeholder file that makes a bound socket visible in the filesystem. As with
+/// other systems, binding to an existing path fails with EADDRINUSE and the file outlives the
+/// socket until it's unlinked.
+fn create_socket_file(path: &str) -> Result<()> {
+    match syscall::open(path, O_CREAT | O_EXCL | O_WRONLY | O_CLOEXEC | 0o777) {
+        Ok(fd) => syscall::close(fd).map(|_| ()),
+        Err(err) if err == syscall::Error::new(syscall::EEXIST) => {
+            Err(syscall::Error::new(syscall::EADDRINUSE))
+        }
+        Err(err) => Err(err),
+    }
+}
+
 unsafe fn inner_af_unix(buf: &[u8], address: *mut sockaddr, address_len: *mut socklen_t) {
     let data = &mut *(address as *mut sockaddr_un);
+    let offset = data.path_offset();
 
-    data.sun_family = AF_UNIX as c_ushort;
-
-    let path =
-        slice::from_raw_parts_mut(&mut data.sun_path as *mut _ as *mut u8, data.sun_path.len());
+    // Map the scheme-native name back to the address that was bound
+    let mut name = Vec::with_capacity(buf.len() + 1);
+    if let Some(abstract_name) = buf.strip_prefix(UNIX_ABSTRACT_PREFIX.as_bytes()) {
+        name.push(0);
+        name.extend_from_slice(abstract_name);
+    } else {
+        name.extend_from_slice(buf.strip_prefix(b"file:").unwrap_or(buf));
+        name.push(0);
+    }
 
-    let len = cmp::min(path.len(), buf.len());
-    path[..len].copy_from_slice(&buf[..len]);
+    // Like other systems, report the full length and truncate what doesn't fit
+    let capacity = cmp::min(
+        data.sun_path.len(),
+        (*address_len as usize).saturating_sub(offset),
+    );
+    if *address_len as usize >= mem::size_of::<sa_family_t>() {
+        data.sun_family = AF_UNIX as sa_family_t;
+    }
+    let path = slice::from_raw_parts_mut(&mut data.sun_path as *mut _ as *mut u8, capacity);
+    let len = cmp::min(path.len(), name.len());
+    path[..len].copy_from_slice(&name[..len]);
 
-    *address_len = len as socklen_t;
+    *address_len = (offset + cmp::min(name.len(), data.sun_path.len())) as socklen_t;
 }
 
 unsafe fn inner_af_inet(
@@ -173,7 +262,7 @@ unsafe fn inner_get_name(
     address: *mut sockaddr,
     address_len: *mut socklen_t,
 ) -> Result<usize> {
-    // Format: [udp|tcp:]remote/local, chan:path
+    // Format: [udp|tcp:]remote/local, chan:path, uds_dgram:path
     let mut buf = [0; 256];
     let len = syscall::fpath(socket as usize, &mut buf)?;
     let buf = &buf[..len];
@@ -182,10 +271,12 @@ unsafe fn inner_get_name(
         inner_af_inet(local, &buf[4..], address, address_len);
     } else if buf.starts_with(b"chan:") {
         inner_af_unix(&buf[5..], address, address_len);
+    } else if buf.starts_with(b"uds_dgram:") {
+        inner_af_unix(&buf[10..], address, address_len);
     } else {
         // Socket doesn't belong to any scheme
         panic!(
-            "socket {:?} doesn't match either tcp, udp or chan schemes",
+            "socket {:?} doesn't match either tcp, udp, chan or uds_dgram schemes",
             str::from_utf8(buf)
         );
     }
@@ -193,6 +284,619 @@ unsafe fn inner_get_name(
     Ok(0)
 }
 
+fn cmsg_align(len: usize) -> usize {
+    let align = mem::size_of::<size_t>();
+    (len + align - 1) & !(align - 1)
+}
+
+/// Queues a copy of `fd` on the socket for the peer to pick up with `receive_fd`.
+fn send_fd(socket: c_int, fd: c_int) -> Result<()> {
+    // sendfd moves the descriptor into the receiving scheme, so send a duplicate to keep ours
+    let dup = syscall::dup(fd as usize, &[])?;
+    match syscall::sendfd(socket as usize, dup, 0, 0) {
+        Ok(_) => Ok(()),
+        Err(err) => {
+            let _ = syscall::close(dup);
+            Err(err)
+        }
+    }
+}
+
+/// Takes the next descriptor the peer queued on the socket, if any.
+fn receive_fd(socket: c_int, cloexec: bool) -> Option<c_int> {
+    let fd = syscall::dup(socket as usize, b"recvfd").ok()?;
+    if cloexec {
+        let _ = syscall::fcntl(fd, F_SETFD, O_CLOEXEC);
+    }
+    // It may be a memfd, where the number was known not to be one
+    memfd::closed(fd as c_int);
+    Some(fd as c_int)
+}
+
+/// Levels of the options handled by the netstack, as the c_int that setsockopt takes
+const IPPROTO_IP: c_int = netinet_in::IPPROTO_IP as c_int;
+const IPPROTO_TCP: c_int = netinet_in::IPPROTO_TCP as c_int;
+const IPPROTO_IPV6: c_int = netinet_in::IPPROTO_IPV6 as c_int;
+
+/// Reads one of the netstack's per-socket settings. Each setting is a file, opened by dup'ing
+/// the socket with the setting's name.
+fn read_setting(socket: c_int, name: &[u8], buf: &mut [u8]) -> Result<usize> {
+    let fd = File::new_bare(syscall::dup(socket as usize, name)?);
+    syscall::read(fd.raw(), buf)
+}
+
+fn write_setting(socket: c_int, name: &[u8], buf: &[u8]) -> Result<()> {
+    let fd = File::new_bare(syscall::dup(socket as usize, name)?);
+    syscall::write(fd.raw(), buf).map(|_| ())
+}
+
+/// What this process keeps of a socket that its scheme doesn't: SO_REUSEADDR and SO_REUSEPORT,
+/// on schemes without those settings, and what MSG_PEEK took off the socket. Duplicates share
+/// it, as they share the socket.
+#[derive(Default)]
+pub(crate) struct Local {
+    reuse_addr: AtomicBool,
+    reuse_port: AtomicBool,
+    /// Oldest first. A stream only ever has one.
+    peeked: Mutex<Vec<Peeked>>,
+}
+
+/// Data a MSG_PEEK call read, which the next reads return before reading the socket again
+struct Peeked {
+    data: Vec<u8>,
+    /// The sender, if the call asked for it
+    address: Option<Vec<u8>>,
+    /// A datagram is taken whole by the next read, what doesn't fit being discarded
+    datagram: bool,
+}
+
+impl Local {
+    fn reuse(&self, option_name: c_int) -> &AtomicBool {
+        if option_name == SO_REUSEADDR {
+            &self.reuse_addr
+        } else {
+            &self.reuse_port
+        }
+    }
+}
+
+/// The sockets with a `Local`, by descriptor. emulated keeps it in step with close and dup.
+pub(crate) static LOCALS: Mutex<BTreeMap<c_int, Arc<Local>>> = Mutex::new(BTreeMap::new());
+/// The number of descriptors in LOCALS, which spares closing other descriptors the lock
+static LOCAL_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+fn local(socket: c_int) -> Option<Arc<Local>> {
+    if LOCAL_COUNT.load(Ordering::Relaxed) == 0 {
+        return None;
+    }
+    LOCALS.lock().get(&socket).cloned()
+}
+
+fn local_or_new(socket: c_int) -> Arc<Local> {
+    let mut locals = LOCALS.lock();
+    if let Some(local) = locals.get(&socket) {
+        return Arc::clone(local);
+    }
+    let local = Arc::new(Local::default());
+    locals.insert(socket, Arc::clone(&local));
+    LOCAL_COUNT.fetch_add(1, Ordering::Relaxed);
+    local
+}
+
+/// Called when `fd` is closed.
+pub fn closed(fd: c_int) {
+    if LOCAL_COUNT.load(Ordering::Relaxed) != 0 && LOCALS.lock().remove(&fd).is_some() {
+        LOCAL_COUNT.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Called when `new_fd` was made a duplicate of `fd`, after closing what it was before.
+pub fn duplicated(fd: c_int, new_fd: c_int) {
+    if let Some(local) = local(fd) {
+        if LOCALS.lock().insert(new_fd, local).is_none() {
+            LOCAL_COUNT.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+/// The `Local`s in the table, once each, as duplicates share them
+fn locals(table: &BTreeMap<c_int, Arc<Local>>) -> Vec<Arc<Local>> {
+    let mut locals: Vec<Arc<Local>> = Vec::new();
+    for local in table.values() {
+        if !locals.iter().any(|other| Arc::ptr_eq(other, local)) {
+            locals.push(Arc::clone(local));
+        }
+    }
+    locals
+}
+
+/// The lock of the table and those of the peeked data, which fork holds. The table's is taken
+/// first, as nothing takes it while holding one of the others.
+pub(crate) struct Sockets;
+
+impl ForkLock for Sockets {
+    unsafe fn prepare(&self) {
+        LOCALS.manual_lock();
+        for local in locals(LOCALS.manual_content()) {
+            local.peeked.manual_lock();
+        }
+    }
+    unsafe fn parent(&self) {
+        for local in locals(LOCALS.manual_content()) {
+            local.peeked.manual_unlock();
+        }
+        LOCALS.manual_unlock();
+    }
+    unsafe fn child(&self) {
+        for local in locals(LOCALS.manual_content()) {
+            local.peeked.manual_reinit();
+        }
+        LOCALS.manual_reinit();
+    }
+}
+
+/// Whether a MSG_PEEK call left data of `socket` to read, which makes it readable whatever its
+/// scheme says.
+pub fn has_peeked(socket: c_int) -> bool {
+    local(socket).map_or(false, |local| !local.peeked.lock().is_empty())
+}
+
+/// Reads what a MSG_PEEK call left of `socket`, if anything.
+pub fn read_peeked(socket: c_int, buf: &mut [u8]) -> Option<usize> {
+    unsafe { take_peeked(socket, buf, ptr::null_mut(), ptr::null_mut()) }
+}
+
+/// Copies the address a MSG_PEEK call kept, truncated as getpeername does, or gives it no length
+/// if the call didn't ask for it.
+unsafe fn put_address(address: Option<&[u8]>, out: *mut sockaddr, out_len: *mut socklen_t) {
+    if out.is_null() || out_len.is_null() {
+        return;
+    }
+    let address = address.unwrap_or(&[]);
+    let count = cmp::min(address.len(), *out_len as usize);
+    ptr::copy_nonoverlapping(address.as_ptr(), out as *mut u8, count);
+    *out_len = address.len() as socklen_t;
+}
+
+unsafe fn take_peeked(
+    socket: c_int,
+    buf: &mut [u8],
+    address: *mut sockaddr,
+    address_len: *mut socklen_t,
+) -> Option<usize> {
+    let local = local(socket)?;
+    let mut peeked = local.peeked.lock();
+    let kept = peeked.first_mut()?;
+    let count = cmp::min(buf.len(), kept.data.len());
+    buf[..count].copy_from_slice(&kept.data[..count]);
+    put_address(kept.address.as_deref(), address, address_len);
+    if kept.datagram || count == kept.data.len() {
+        peeked.remove(0);
+    } else {
+        kept.data.drain(..count);
+    }
+    Some(count)
+}
+
+/// Reads `socket` for MSG_PEEK, keeping what it read for the next reads. Nothing is read while
+/// data is kept, except to fill `buf` for MSG_WAITALL on a stream.
+unsafe fn peek(
+    socket: c_int,
+    buf: &mut [u8],
+    wait_all: bool,
+    address: *mut sockaddr,
+    address_len: *mut socklen_t,
+) -> Result<usize> {
+    let local = local_or_new(socket);
+    let datagram = socket_domain_kind(socket).map_or(false, |(_, kind)| kind == SOCK_DGRAM);
+    let copy = |kept: &Peeked, buf: &mut [u8]| {
+        let count = cmp::min(buf.len(), kept.data.len());
+        buf[..count].copy_from_slice(&kept.data[..count]);
+        put_address(kept.address.as_deref(), address, address_len);
+        count
+    };
+    loop {
+        if let Some(kept) = local.peeked.lock().first() {
+            if kept.datagram || !wait_all || kept.data.len() >= buf.len() {
+                return Ok(copy(kept, buf));
+            }
+        }
+
+        // Read without the lock, which a blocking read would hold for as long as it waits. The
+        // read is the scheme's own, as Sys::read would return the kept data.
+        let size = if datagram {
+            65536
+        } else {
+            cmp::max(buf.len(), 1)
+        };
+        let mut data = vec![0; size];
+        let mut sender = None;
+        let count = if address.is_null() || address_len.is_null() {
+            syscall::read(socket as usize, &mut data)?
+        } else {
+            let fd = File::new_bare(syscall::dup(socket as usize, b"listen")?);
+            let mut from = vec![0; mem::size_of::<sockaddr_un>()];
+            let mut from_len = from.len() as socklen_t;
+            let from_ptr = from.as_mut_ptr() as *mut sockaddr;
+            if Sys::getpeername(*fd, from_ptr, &mut from_len) < 0 {
+                return Err(syscall::Error::new(errno));
+            }
+            from.truncate(from_len as usize);
+            sender = Some(from);
+            syscall::read(fd.raw(), &mut data)?
+        };
+        data.truncate(count);
+
+        let mut peeked = local.peeked.lock();
+        if count == 0 && !datagram {
+            // End of stream, so there won't be more to wait for
+            return Ok(peeked.first().map_or(0, |kept| copy(kept, buf)));
+        }
+        match peeked.last_mut() {
+            // Another thread peeked meanwhile, and what it read comes first
+            Some(kept) if !datagram => kept.data.extend_from_slice(&data),
+            _ => peeked.push(Peeked {
+                data,
+                address: sender,
+                datagram,
+            }),
+        }
+    }
+}
+
+/// Returns the domain and type of a socket from the scheme it was opened on.
+fn socket_domain_kind(socket: c_int) -> Result<(c_int, c_int)> {
+    let mut buf = [0; 256];
+    let len = syscall::fpath(socket as usize, &mut buf)?;
+    let scheme = buf[..len].split(|&b| b == b':').next().unwrap_or(&[]);
+    match scheme {
+        b"tcp" => Ok((AF_INET, SOCK_STREAM)),
+        b"udp" => Ok((AF_INET, SOCK_DGRAM)),
+        b"chan" => Ok((AF_UNIX, SOCK_STREAM)),
+        b"uds_dgram" => Ok((AF_UNIX, SOCK_DGRAM)),
+        _ => Err(syscall::Error::new(syscall::ENOTSOCK)),
+    }
+}
+
+/// How the value of a single-byte setting is derived from the option's int
+#[derive(Clone, Copy, PartialEq)]
+enum ByteOption {
+    /// Any non-zero value enables the option
+    Bool,
+    /// The value is stored as is, and has to fit in a byte
+    Byte,
+    /// Hop limits, where -1 selects the default of 1
+    Hops,
+}
+
+/// The netstack setting behind byte-sized and boolean options.
+fn option_setting(level: c_int, option_name: c_int) -> Option<(&'static [u8], ByteOption)> {
+    let setting: (&'static [u8], ByteOption) = match (level, option_name) {
+        (SOL_SOCKET, SO_REUSEADDR) => (b"reuseaddr", ByteOption::Bool),
+        (SOL_SOCKET, SO_REUSEPORT) => (b"reuseport", ByteOption::Bool),
+        (SOL_SOCKET, SO_KEEPALIVE) => (b"keepalive", ByteOption::Bool),
+        (IPPROTO_TCP, TCP_NODELAY) => (b"nodelay", ByteOption::Bool),
+        (IPPROTO_IP, IP_TOS) => (b"tos", ByteOption::Byte),
+        (IPPROTO_IP, IP_TTL) => (b"ttl", ByteOption::Byte),
+        (IPPROTO_IP, IP_MULTICAST_TTL) => (b"multicast_ttl", ByteOption::Byte),
+        (IPPROTO_IP, IP_MULTICAST_LOOP) => (b"multicast_loop", ByteOption::Bool),
+        (IPPROTO_IPV6, IPV6_MULTICAST_HOPS) => (b"ipv6_multicast_hops", ByteOption::Hops),
+        (IPPROTO_IPV6, IPV6_MULTICAST_LOOP) => (b"ipv6_multicast_loop", ByteOption::Bool),
+        _ => return None,
+    };
+    Some(setting)
+}
+
+/// The netstack setting taking the raw option struct, for options that don't fit in a byte.
+/// Group memberships are write-only.
+fn struct_setting(level: c_int, option_name: c_int) -> Option<(&'static [u8], usize, bool)> {
+    let setting: (&'static [u8], usize, bool) = match (level, option_name) {
+        (IPPROTO_IP, IP_ADD_MEMBERSHIP) => (b"add_membership", mem::size_of::<ip_mreq>(), false),
+        (IPPROTO_IP, IP_DROP_MEMBERSHIP) => (b"drop_membership", mem::size_of::<ip_mreq>(), false),
+        (IPPROTO_IP, IP_MULTICAST_IF) => (b"multicast_if", mem::size_of::<in_addr>(), true),
+        (IPPROTO_IPV6, IPV6_JOIN_GROUP) => {
+            (b"ipv6_add_membership", mem::size_of::<ipv6_mreq>(), false)
+        }
+        (IPPROTO_IPV6, IPV6_LEAVE_GROUP) => {
+            (b"ipv6_drop_membership", mem::size_of::<ipv6_mreq>(), false)
+        }
+        (IPPROTO_IPV6, IPV6_MULTICAST_IF) => (b"ipv6_multicast_if", mem::size_of::<c_uint>(), true),
+        _ => return None,
+    };
+    Some(setting)
+}
+
+/// smoltcp does not hold on to addresses after a socket is closed, so rebinding always works and
+/// the reuse options can be accepted even by a netstack that doesn't know about them.
+fn is_reuse_option(level: c_int, option_name: c_int) -> bool {
+    level == SOL_SOCKET && (option_name == SO_REUSEADDR || option_name == SO_REUSEPORT)
+}
+
+fn is_unknown_setting(err: &syscall::Error) -> bool {
+    err.errno == syscall::EINVAL || err.errno == syscall::ENOENT
+}
+
+unsafe fn put_option<T>(
+    option_value: *mut c_void,
+    option_len: *mut socklen_t,
+    value: T,
+) -> Result<usize> {
+    if (*option_len as usize) < mem::size_of::<T>() {
+        return Err(syscall::Error::new(syscall::EINVAL));
+    }
+    ptr::write_unaligned(option_value as *mut T, value);
+    *option_len = mem::size_of::<T>() as socklen_t;
+    Ok(0)
+}
+
+unsafe fn get_option(
+    socket: c_int,
+    level: c_int,
+    option_name: c_int,
+    option_value: *mut c_void,
+    option_len: *mut socklen_t,
+) -> Result<usize> {
+    if option_value.is_null() || option_len.is_null() {
+        return Err(syscall::Error::new(syscall::EFAULT));
+    }
+
+    let value: c_int = match (level, option_name) {
+        (SOL_SOCKET, SO_RCVTIMEO) | (SOL_SOCKET, SO_SNDTIMEO) => {
+            let name: &[u8] = if option_name == SO_RCVTIMEO {
+                b"read_timeout"
+            } else {
+                b"write_timeout"
+            };
+            let mut timespec = syscall::TimeSpec::default();
+            read_setting(socket, name, &mut timespec)?;
+            let timeval = timeval {
+                tv_sec: timespec.tv_sec as time_t,
+                tv_usec: (timespec.tv_nsec / 1000) as suseconds_t,
+            };
+            return put_option(option_value, option_len, timeval);
+        }
+        (SOL_SOCKET, SO_LINGER) => {
+            let mut secs = [0; mem::size_of::<i32>()];
+            read_setting(socket, b"linger", &mut secs)?;
+            let secs = i32::from_ne_bytes(secs);
+            let linger = linger {
+                l_onoff: (secs >= 0) as c_int,
+                l_linger: cmp::max(secs, 0),
+            };
+            return put_option(option_value, option_len, linger);
+        }
+        (SOL_SOCKET, SO_ERROR) => {
+            // Pending error of a non-blocking connect, cleared by reading it
+            let mut error = [0; mem::size_of::<i32>()];
+            match read_setting(socket, b"error", &mut error) {
+                Ok(_) => i32::from_ne_bytes(error),
+                Err(err) if is_unknown_setting(&err) => 0,
+                Err(err) => return Err(err),
+            }
+        }
+        (SOL_SOCKET, SO_TYPE) => socket_domain_kind(socket)?.1,
+        (SOL_SOCKET, SO_DOMAIN) => socket_domain_kind(socket)?.0,
+        _ => {
+            if let Some((name, size, true)) = struct_setting(level, option_name) {
+                if (*option_len as usize) < size {
+                    return Err(syscall::Error::new(syscall::EINVAL));
+                }
+                let buf = slice::from_raw_parts_mut(option_value as *mut u8, size);
+                read_setting(socket, name, buf)?;
+                *option_len = size as socklen_t;
+                return Ok(0);
+            }
+
+            let (name, _) = option_setting(level, option_name)
+                .ok_or(syscall::Error::new(syscall::ENOPROTOOPT))?;
+            let mut byte = [0];
+            match read_setting(socket, name, &mut byte) {
+                Ok(_) => byte[0] as c_int,
+                Err(err) if is_reuse_option(level, option_name) && is_unknown_setting(&err) => {
+                    local(socket).map_or(0, |local| {
+                        local.reuse(option_name).load(Ordering::Relaxed) as c_int
+                    })
+                }
+                Err(err) => return Err(err),
+            }
+        }
+    };
+
+    put_option(option_value, option_len, value)
+}
+
+/// The address of the interface IP_MULTICAST_IF selects. Like Linux, it takes an ip_mreqn, where
+/// a non-zero interface index wins over the address, an ip_mreq, or the address alone.
+unsafe fn multicast_if_address(option_value: *const c_void, option_len: usize) -> Result<in_addr> {
+    if option_len >= mem::size_of::<ip_mreqn>() {
+        let mreqn = ptr::read_unaligned(option_value as *const ip_mreqn);
+        if mreqn.imr_ifindex == 0 {
+            Ok(mreqn.imr_address)
+        } else {
+            interface_address(mreqn.imr_ifindex as c_uint)
+        }
+    } else if option_len >= mem::size_of::<ip_mreq>() {
+        Ok(ptr::read_unaligned(option_value as *const ip_mreq).imr_interface)
+    } else if option_len >= mem::size_of::<in_addr>() {
+        Ok(ptr::read_unaligned(option_value as *const in_addr))
+    } else {
+        Err(syscall::Error::new(syscall::EINVAL))
+    }
+}
+
+/// The first IPv4 address of the interface with the index `index`, as the netstack picks
+/// interfaces by their address
+fn interface_address(index: c_uint) -> Result<in_addr> {
+    let interfaces = ifaddrs::sys::interfaces().map_err(syscall::Error::new)?;
+    let iface = interfaces
+        .iter()
+        .find(|iface| iface.index == index)
+        .ok_or(syscall::Error::new(syscall::ENODEV))?;
+    iface
+        .addrs
+        .iter()
+        .find_map(|address| match address.addr {
+            IfAddr::V4(octets) => Some(in_addr {
+                s_addr: u32::from_ne_bytes(octets),
+            }),
+            IfAddr::V6(_) => None,
+        })
+        .ok_or(syscall::Error::new(syscall::EADDRNOTAVAIL))
+}
+
+unsafe fn set_option(
+    socket: c_int,
+    level: c_int,
+    option_name: c_int,
+    option_value: *const c_void,
+    option_len: socklen_t,
+) -> Result<usize> {
+    if option_value.is_null() {
+        return Err(syscall::Error::new(syscall::EFAULT));
+    }
+    let check_len = |len: usize| {
+        if (option_len as usize) < len {
+            Err(syscall::Error::new(syscall::EINVAL))
+        } else {
+            Ok(())
+        }
+    };
+
+    match (level, option_name) {
+        (SOL_SOCKET, SO_RCVTIMEO) | (SOL_SOCKET, SO_SNDTIMEO) => {
+            check_len(mem::size_of::<timeval>())?;
+            let timeval = ptr::read_unaligned(option_value as *const timeval);
+            let timespec = syscall::TimeSpec {
+                tv_sec: timeval.tv_sec as i64,
+                tv_nsec: (timeval.tv_usec * 1000) as i32,
+            };
+            let name: &[u8] = if option_name == SO_RCVTIMEO {
+                b"read_timeout"
+            } else {
+                b"write_timeout"
+            };
+            write_setting(socket, name, &timespec)?;
+        }
+        (SOL_SOCKET, SO_LINGER) => {
+            check_len(mem::size_of::<linger>())?;
+            let linger = ptr::read_unaligned(option_value as *const linger);
+            // The netstack takes the linger time in seconds, negative to not linger
+            let secs: i32 = if linger.l_onoff != 0 {
+                cmp::max(linger.l_linger, 0)
+            } else {
+                -1
+            };
+            write_setting(socket, b"linger", &secs.to_ne_bytes())?;
+        }
+        (IPPROTO_IP, IP_MULTICAST_IF) => {
+            let address = multicast_if_address(option_value, option_len as usize)?;
+            write_setting(socket, b"multicast_if", &address.s_addr.to_ne_bytes())?;
+        }
+        _ => {
+            if let Some((name, size, _)) = struct_setting(level, option_name) {
+                check_len(size)?;
+                let buf = slice::from_raw_parts(option_value as *const u8, size);
+                return write_setting(socket, name, buf).map(|()| 0);
+            }
+
+            let (name, kind) = option_setting(level, option_name)
+                .ok_or(syscall::Error::new(syscall::ENOPROTOOPT))?;
+            let value = if level == IPPROTO_IP && (option_len as usize) < mem::size_of::<c_int>() {
+                // Like on other systems, IPPROTO_IP options may also be passed as a single byte
+                check_len(1)?;
+                *(option_value as *const u8) as c_int
+            } else {
+                check_len(mem::size_of::<c_int>())?;
+                ptr::read_unaligned(option_value as *const c_int)
+            };
+            let byte = match kind {
+                ByteOption::Bool => (value != 0) as u8,
+                ByteOption::Hops if value == -1 => 1,
+                ByteOption::Byte | ByteOption::Hops => {
+                    if value < 0 || value > 0xFF {
+                        return Err(syscall::Error::new(syscall::EINVAL));
+                    }
+                    value as u8
+                }
+            };
+            match write_setting(socket, name, &[byte]) {
+                Err(err) if is_reuse_option(level, option_name) && is_unknown_setting(&err) => {
+                    // Kept here, for getsockopt
+                    local_or_new(socket)
+                        .reuse(option_name)
+                        .store(byte != 0, Ordering::Relaxed);
+                }
+                result => result?,
+            }
+        }
+    }
+    Ok(0)
+}
+
+fn is_nonblocking(socket: c_int) -> bool {
+    syscall::fcntl(socket as usize, F_GETFL, 0).map_or(false, |flags| flags & O_NONBLOCK != 0)
+}
+
+/// Makes a socket non-blocking for a single call with MSG_DONTWAIT, and blocking again once it's
+/// dropped, if it was. The flag belongs to the open file description, so the socket's other users
+/// see it in the meantime.
+struct NonBlocking {
+    socket: usize,
+    /// The flags to restore
+    flags: Option<usize>,
+}
+
+impl NonBlocking {
+    fn new(socket: c_int, enable: bool) -> Result<Self> {
+        let socket = socket as usize;
+        if !enable {
+            return Ok(Self {
+                socket,
+                flags: None,
+            });
+        }
+        let flags = syscall::fcntl(socket, F_GETFL, 0)?;
+        if flags & O_NONBLOCK == O_NONBLOCK {
+            return Ok(Self {
+                socket,
+                flags: None,
+            });
+        }
+        syscall::fcntl(socket, F_SETFL, flags | O_NONBLOCK)?;
+        Ok(Self {
+            socket,
+            flags: Some(flags),
+        })
+    }
+}
+
+impl Drop for NonBlocking {
+    fn drop(&mut self) {
+        if let Some(flags) = self.flags {
+            let _ = syscall::fcntl(self.socket, F_SETFL, flags);
+        }
+    }
+}
+
+/// Reads until `buf` is full, for MSG_WAITALL. Stops early at end of stream, or on an error
+/// after some data was read.
+fn read_all(socket: c_int, buf: &mut [u8]) -> ssize_t {
+    let mut total = 0;
+    while total < buf.len() {
+        match Sys::read(socket, &mut buf[total..]) {
+            0 => break,
+            count if count < 0 => {
+                if total == 0 {
+                    return -1;
+                }
+                break;
+            }
+            count => total += count as usize,
+        }
+    }
+    total as ssize_t
+}
+
 fn socket_kind(mut kind: c_int) -> (c_int, usize) {
     let mut flags = O_RDWR;
     if kind & SOCK_NONBLOCK == SOCK_NONBLOCK {
@@ -206,19 +910,53 @@ fn socket_kind(mut kind: c_int) -> (c_int, usize) {
     (kind, flags)
 }
 
+/// Applies the SOCK_NONBLOCK and SOCK_CLOEXEC bits of `kind` to a descriptor obtained by `dup`,
+/// which (as accepted sockets on Linux) doesn't inherit them from the listener.
+fn set_stream_flags(stream: usize, kind: c_int) -> Result<()> {
+    let (_, flags) = socket_kind(kind);
+    let status = syscall::fcntl(stream, F_GETFL, 0)?;
+    syscall::fcntl(
+        stream,
+        F_SETFL,
+        (status & !O_NONBLOCK) | (flags & O_NONBLOCK),
+    )?;
+    syscall::fcntl(stream, F_SETFD, flags & O_CLOEXEC)?;
+    Ok(())
+}
+
 impl PalSocket for Sys {
     unsafe fn accept(socket: c_int, address: *mut sockaddr, address_len: *mut socklen_t) -> c_int {
-        let stream = e(syscall::dup(socket as usize, b"listen")) as c_int;
-        if stream < 0 {
+        Self::accept4(socket, address, address_len, 0)
+    }
+
+    unsafe fn accept4(
+        socket: c_int,
+        address: *mut sockaddr,
+        address_len: *mut socklen_t,
+        flags: c_int,
+    ) -> c_int {
+        if flags & !(SOCK_NONBLOCK | SOCK_CLOEXEC) != 0 {
+            errno = syscall::EINVAL;
+            return -1;
+        }
+
+        let stream = e(syscall::dup(socket as usize, b"listen"));
+        if stream == !0 {
+            return -1;
+        }
+        if let Err(err) = set_stream_flags(stream, flags) {
+            let _ = syscall::close(stream);
+            errno = err.errno;
             return -1;
         }
         if address != ptr::null_mut()
             && address_len != ptr::null_mut()
-            && Self::getpeername(stream, address, address_len) < 0
+            && Self::getpeername(stream as c_int, address, address_len) < 0
         {
+            let _ = syscall::close(stream);
             return -1;
         }
-        stream
+        stream as c_int
     }
 
     unsafe fn bind(socket: c_int, address: *const sockaddr, address_len: socklen_t) -> c_int {
@@ -226,7 +964,19 @@ impl PalSocket for Sys {
     }
 
     unsafe fn connect(socket: c_int, address: *const sockaddr, address_len: socklen_t) -> c_int {
-        bind_or_connect!(connect into, socket, address, address_len)
+        let ret = bind_or_connect!(connect into, socket, address, address_len);
+
+        // On a non-blocking socket the netstack completes the handshake in the background. As
+        // POSIX expects, the socket becomes writable once that's done, with the outcome
+        // available through SO_ERROR.
+        if ret == 0
+            && is_nonblocking(socket)
+            && socket_domain_kind(socket) == Ok((AF_INET, SOCK_STREAM))
+        {
+            errno = syscall::EINPROGRESS;
+            return -1;
+        }
+        ret
     }
 
     unsafe fn getpeername(
@@ -252,33 +1002,7 @@ impl PalSocket for Sys {
         option_value: *mut c_void,
         option_len: *mut socklen_t,
     ) -> c_int {
-        match level {
-            SOL_SOCKET => match option_name {
-                SO_ERROR => {
-                    if option_value.is_null() {
-                        return e(Err(syscall::Error::new(syscall::EFAULT))) as c_int;
-                    }
-
-                    if (option_len as usize) < mem::size_of::<c_int>() {
-                        return e(Err(syscall::Error::new(syscall::EINVAL))) as c_int;
-                    }
-
-                    let error = unsafe { &mut *(option_value as *mut c_int) };
-                    //TODO: Socket nonblock connection error
-                    *error = 0;
-
-                    return 0;
-                }
-                _ => (),
-            },
-            _ => (),
-        }
-
-        eprintln!(
-            "getsockopt({}, {}, {}, {:p}, {:p})",
-            socket, level, option_name, option_value, option_len
-        );
-        e(Err(syscall::Error::new(syscall::ENOSYS))) as c_int
+        e(unsafe { get_option(socket, level, option_name, option_value, option_len) }) as c_int
     }
 
     fn listen(socket: c_int, backlog: c_int) -> c_int {
@@ -294,13 +1018,31 @@ impl PalSocket for Sys {
         address: *mut sockaddr,
         address_len: *mut socklen_t,
     ) -> ssize_t {
-        if flags != 0 {
+        if flags & !(MSG_DONTWAIT | MSG_PEEK | MSG_WAITALL) != 0 {
             errno = syscall::EOPNOTSUPP;
             return -1;
         }
+        let _nonblocking = match NonBlocking::new(socket, flags & MSG_DONTWAIT != 0) {
+            Ok(nonblocking) => nonblocking,
+            Err(err) => return e(Err(err)) as ssize_t,
+        };
+        let buf = slice::from_raw_parts_mut(buf as *mut u8, len);
+
+        if flags & MSG_PEEK == MSG_PEEK {
+            let wait_all = flags & MSG_WAITALL == MSG_WAITALL;
+            return e(peek(socket, buf, wait_all, address, address_len)) as ssize_t;
+        }
         if address == ptr::null_mut() || address_len == ptr::null_mut() {
-            Self::read(socket, slice::from_raw_parts_mut(buf as *mut u8, len))
+            // Reads return what MSG_PEEK left first
+            if flags & MSG_WAITALL == MSG_WAITALL {
+                read_all(socket, buf)
+            } else {
+                Self::read(socket, buf)
+            }
         } else {
+            if let Some(count) = take_peeked(socket, buf, address, address_len) {
+                return count as ssize_t;
+            }
             let fd = e(syscall::dup(socket as usize, b"listen"));
             if fd == !0 {
                 return -1;
@@ -310,12 +1052,133 @@ impl PalSocket for Sys {
                 return -1;
             }
 
-            let ret = Self::read(fd as c_int, slice::from_raw_parts_mut(buf as *mut u8, len));
+            let ret = Self::read(fd as c_int, buf);
             let _ = syscall::close(fd);
             ret
         }
     }
 
+    unsafe fn recvmsg(socket: c_int, msg: *mut msghdr, flags: c_int) -> ssize_t {
+        let msg = &mut *msg;
+        let iovs: &[iovec] = if msg.msg_iov.is_null() {
+            &[]
+        } else {
+            slice::from_raw_parts(msg.msg_iov, msg.msg_iovlen)
+        };
+        let (address, address_len) = if msg.msg_name.is_null() {
+            (ptr::null_mut(), ptr::null_mut())
+        } else {
+            (
+                msg.msg_name as *mut sockaddr,
+                &mut msg.msg_namelen as *mut _,
+            )
+        };
+
+        let mut data = gather(iovs);
+        let count = Self::recvfrom(
+            socket,
+            data.as_mut_ptr() as *mut c_void,
+            data.len(),
+            flags & !MSG_CMSG_CLOEXEC,
+            address,
+            address_len,
+        );
+        if count < 0 {
+            return -1;
+        }
+        scatter(iovs, &data[..count as usize]);
+        msg.msg_flags = 0;
+
+        // Pick up the descriptors the peer queued ahead of the data, and pass as many as fit in a
+        // single SCM_RIGHTS message. Like Linux, close the ones that don't fit and set MSG_CTRUNC,
+        // rather than leave them for a later call that the sender didn't mean them for.
+        let cloexec = flags & MSG_CMSG_CLOEXEC == MSG_CMSG_CLOEXEC;
+        let mut controllen = 0;
+        let mut capacity = 0;
+        let header_len = cmsg_align(mem::size_of::<cmsghdr>());
+        if !msg.msg_control.is_null() {
+            capacity = msg.msg_controllen.saturating_sub(header_len) / mem::size_of::<c_int>();
+        }
+        let fds = (msg.msg_control as *mut u8).wrapping_add(header_len) as *mut c_int;
+
+        let mut received = 0;
+        while let Some(fd) = receive_fd(socket, cloexec) {
+            if received < capacity {
+                fds.add(received).write_unaligned(fd);
+                received += 1;
+            } else {
+                let _ = syscall::close(fd as usize);
+                msg.msg_flags |= MSG_CTRUNC;
+            }
+        }
+
+        if received > 0 {
+            let len = header_len + received * mem::size_of::<c_int>();
+            (msg.msg_control as *mut cmsghdr).write_unaligned(cmsghdr {
+                cmsg_len: len,
+                cmsg_level: SOL_SOCKET,
+                cmsg_type: SCM_RIGHTS,
+            });
+            controllen = cmp::min(cmsg_align(len), msg.msg_controllen);
+        }
+        msg.msg_controllen = controllen;
+
+        count
+    }
+
+    unsafe fn sendmsg(socket: c_int, msg: *const msghdr, flags: c_int) -> ssize_t {
+        let msg = &*msg;
+
+        // Descriptors are queued before the data is written, so that they are available by the
+        // time the peer receives it
+        if !msg.msg_control.is_null() {
+            let control = slice::from_raw_parts(msg.msg_control as *const u8, msg.msg_controllen);
+            let header_len = cmsg_align(mem::size_of::<cmsghdr>());
+            let mut offset = 0;
+            while offset + mem::size_of::<cmsghdr>() <= control.len() {
+                let header = ptr::read_unaligned(control[offset..].as_ptr() as *const cmsghdr);
+                if header.cmsg_len < header_len || header.cmsg_len > control.len() - offset {
+                    errno = syscall::EINVAL;
+                    return -1;
+                }
+                let payload = &control[offset + header_len..offset + header.cmsg_len];
+
+                match (header.cmsg_level, header.cmsg_type) {
+                    (SOL_SOCKET, SCM_RIGHTS) => {
+                        for fd in payload.chunks_exact(mem::size_of::<c_int>()) {
+                            let fd = ptr::read_unaligned(fd.as_ptr() as *const c_int);
+                            if let Err(err) = send_fd(socket, fd) {
+                                errno = err.errno;
+                                return -1;
+                            }
+                        }
+                    }
+                    _ => {
+                        errno = syscall::EINVAL;
+                        return -1;
+                    }
+                }
+
+                offset += cmsg_align(header.cmsg_len);
+            }
+        }
+
+        let iovs: &[iovec] = if msg.msg_iov.is_null() {
+            &[]
+        } else {
+            slice::from_raw_parts(msg.msg_iov, msg.msg_iovlen)
+        };
+        let data = gather(iovs);
+        Self::sendto(
+            socket,
+            data.as_ptr() as *const c_void,
+            data.len(),
+            flags,
+            msg.msg_name as *const sockaddr,
+            msg.msg_namelen,
+        )
+    }
+
     unsafe fn sendto(
         socket: c_int,
         buf: *const c_void,
@@ -324,10 +1187,17 @@ impl PalSocket for Sys {
         dest_addr: *const sockaddr,
         dest_len: socklen_t,
     ) -> ssize_t {
-        if flags != 0 {
+        // Writing to a closed connection never raises SIGPIPE on Redox, so MSG_NOSIGNAL is
+        // always in effect
+        if flags & !(MSG_DONTWAIT | MSG_NOSIGNAL) != 0 {
             errno = syscall::EOPNOTSUPP;
             return -1;
         }
+        let _nonblocking = match NonBlocking::new(socket, flags & MSG_DONTWAIT != 0) {
+            Ok(nonblocking) => nonblocking,
+            Err(err) => return e(Err(err)) as ssize_t,
+        };
+
         if dest_addr == ptr::null() || dest_len == 0 {
             Self::write(socket, slice::from_raw_parts(buf as *const u8, len))
         } else {
@@ -345,52 +1215,16 @@ impl PalSocket for Sys {
         option_value: *const c_void,
         option_len: socklen_t,
     ) -> c_int {
-        let set_timeout = |timeout_name: &[u8]| -> c_int {
-            if option_value.is_null() {
-                return e(Err(syscall::Error::new(syscall::EFAULT))) as c_int;
-            }
-
-            if (option_len as usize) < mem::size_of::<timeval>() {
-                return e(Err(syscall::Error::new(syscall::EINVAL))) as c_int;
-            }
-
-            let timeval = unsafe { &*(option_value as *const timeval) };
-
-            let fd = e(syscall::dup(socket as usize, timeout_name));
-            if fd == !0 {
-                return -1;
-            }
-
-            let timespec = syscall::TimeSpec {
-                tv_sec: timeval.tv_sec as i64,
-                tv_nsec: timeval.tv_usec * 1000,
-            };
-
-            let ret = Self::write(fd as c_int, &timespec);
-
-            let _ = syscall::close(fd);
-
-            if ret >= 0 {
+        match unsafe { set_option(socket, level, option_name, option_value, option_len) } {
+            Err(err) if err.errno == syscall::ENOPROTOOPT => {
+                eprintln!(
+                    "setsockopt({}, {}, {}, {:p}, {}) - unknown option",
+                    socket, level, option_name, option_value, option_len
+                );
                 0
-            } else {
-                -1
             }
-        };
-
-        match level {
-            SOL_SOCKET => match option_name {
-                SO_RCVTIMEO => return set_timeout(b"read_timeout"),
-                SO_SNDTIMEO => return set_timeout(b"write_timeout"),
-                _ => (),
-            },
-            _ => (),
+            result => e(result) as c_int,
         }
-
-        eprintln!(
-            "setsockopt({}, {}, {}, {:p}, {}) - unknown option",
-            socket, level, option_name, option_value, option_len
-        );
-        0
     }
 
     fn shutdown(socket: c_int, how: c_int) -> c_int {
@@ -416,6 +1250,7 @@ impl PalSocket for Sys {
             (AF_INET, SOCK_STREAM) => e(syscall::open("tcp:", flags)) as c_int,
             (AF_INET, SOCK_DGRAM) => e(syscall::open("udp:", flags)) as c_int,
             (AF_UNIX, SOCK_STREAM) => e(syscall::open("chan:", flags | O_CREAT)) as c_int,
+            (AF_UNIX, SOCK_DGRAM) => e(syscall::open("uds_dgram:", flags | O_CREAT)) as c_int,
             _ => {
                 errno = syscall::EPROTONOSUPPORT;
                 -1
@@ -424,47 +1259,59 @@ impl PalSocket for Sys {
     }
 
     fn socketpair(domain: c_int, kind: c_int, protocol: c_int, sv: &mut [c_int; 2]) -> c_int {
-        let (kind, flags) = socket_kind(kind);
+        let (base_kind, flags) = socket_kind(kind);
 
-        match (domain, kind) {
-            (AF_UNIX, SOCK_STREAM) => {
-                let listener = e(syscall::open("chan:", flags | O_CREAT));
-                if listener == !0 {
-                    return -1;
-                }
+        let scheme = match (domain, base_kind) {
+            (AF_UNIX, SOCK_STREAM) => "chan:",
+            (AF_UNIX, SOCK_DGRAM) => "uds_dgram:",
+            (AF_UNIX, _) => {
+                unsafe { errno = syscall::EPROTONOSUPPORT };
+                return -1;
+            }
+            _ => {
+                unsafe { errno = syscall::EAFNOSUPPORT };
+                return -1;
+            }
+        };
+        if protocol != 0 {
+            unsafe { errno = syscall::EPROTONOSUPPORT };
+            return -1;
+        }
 
-                // For now, chan: lets connects be instant, and instead blocks
-                // on any I/O performed. So we don't need to mark this as
-                // nonblocking.
+        // An unnamed listener, immediately connected to itself. Each end is a separate
+        // descriptor, so the pair behaves like two connected sockets.
+        let listener = e(syscall::open(scheme, flags | O_CREAT));
+        if listener == !0 {
+            return -1;
+        }
 
-                let fd0 = e(syscall::dup(listener, b"connect"));
-                if fd0 == !0 {
-                    let _ = syscall::close(listener);
-                    return -1;
-                }
+        // For now, chan: lets connects be instant, and instead blocks
+        // on any I/O performed. So we don't need to mark this as
+        // nonblocking.
 
-                let fd1 = e(syscall::dup(listener, b"listen"));
-                if fd1 == !0 {
-                    let _ = syscall::close(fd0);
-                    let _ = syscall::close(listener);
-                    return -1;
-                }
+        let fd0 = e(syscall::dup(listener, b"connect"));
+        if fd0 == !0 {
+            let _ = syscall::close(listener);
+            return -1;
+        }
 
-                sv[0] = fd0 as c_int;
-                sv[1] = fd1 as c_int;
-                0
-            }
-            _ => unsafe {
-                eprintln!(
-                    "socketpair({}, {}, {}, {:p})",
-                    domain,
-                    kind,
-                    protocol,
-                    sv.as_mut_ptr()
-                );
-                errno = syscall::EPROTONOSUPPORT;
-                -1
-            },
+        let fd1 = e(syscall::dup(listener, b"listen"));
+        let _ = syscall::close(listener);
+        if fd1 == !0 {
+            let _ = syscall::close(fd0);
+            return -1;
         }
+
+        let flagged = set_stream_flags(fd0, kind).and_then(|()| set_stream_flags(fd1, kind));
+        if let Err(err) = flagged {
+            let _ = syscall::close(fd0);
+            let _ = syscall::close(fd1);
+            unsafe { errno = err.errno };
+            return -1;
+        }
+
+        sv[0] = fd0 as c_int;
+        sv[1] = fd1 as c_int;
+        0
     }
 }
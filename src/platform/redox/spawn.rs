@@ -0,0 +1,323 @@
+//! posix_spawn without fork. The child is a new context that first runs in this address space,
+//! like a thread, to apply the attributes and file actions to its own copy of the file table.
+//! Once it has stopped itself, the program is loaded into a fresh address space for it and it's
+//! continued, so nothing of the parent's memory is ever copied.
+//!
+//! Scripts and setuid or setgid programs need what only execve does, so they're left to fork and
+//! exec.
+
+use alloc::{boxed::Box, vec, vec::Vec};
+use core::ptr;
+
+use redox_exec::{ExtraInfo, FdGuard, FexecResult};
+use syscall::{data::SigAction, error::*, flag::*};
+
+use super::{
+    clone,
+    exec::{check_executable, close_cloexec},
+    path,
+};
+use crate::{
+    c_str::CStr,
+    core_io::Read,
+    fs::File,
+    header::{
+        fcntl,
+        signal::{sigset_t, NSIG, SIG_BLOCK, SIG_IGN, SIG_SETMASK},
+        spawn::{self, posix_spawnattr_t, FileAction},
+    },
+    platform::{self, types::*, PalSignal, Sys},
+};
+
+/// The helper only makes system calls, so it doesn't need much of a stack
+const HELPER_STACK_WORDS: usize = 8192;
+
+/// What the parent shares with the child while it runs in the parent's address space
+struct Helper<'a> {
+    actions: &'a [FileAction],
+    attr: Option<&'a posix_spawnattr_t>,
+    sigmask: sigset_t,
+    inherited_fds: [usize; 2],
+    error: c_int,
+}
+
+unsafe fn prepare(helper: &Helper) -> core::result::Result<(), c_int> {
+    reset_handlers(helper.attr);
+    if Sys::sigprocmask(SIG_SETMASK, &helper.sigmask, ptr::null_mut()) < 0 {
+        return Err(platform::errno);
+    }
+    spawn::apply_attr(helper.attr)?;
+    apply_actions(helper.actions).map_err(|err| err.errno)?;
+    close_cloexec().map_err(|err| err.errno)
+}
+
+/// Puts the signals the parent catches, and those POSIX_SPAWN_SETSIGDEF names, back to SIG_DFL,
+/// keeping the ones it ignores. Sys::sigaction isn't used, because it would change the parent's
+/// records of them.
+fn reset_handlers(attr: Option<&posix_spawnattr_t>) {
+    let sigdefault = attr
+        .filter(|attr| attr.__flags & spawn::POSIX_SPAWN_SETSIGDEF != 0)
+        .map_or(0, |attr| attr.__sigdefault);
+    for sig in 1..=NSIG {
+        let mut old = SigAction::default();
+        if syscall::sigaction(sig, None, Some(&mut old)).is_err() {
+            continue;
+        }
+        let handler = match old.sa_handler {
+            Some(handler) => handler as usize,
+            None => continue,
+        };
+        if handler != SIG_IGN || sigdefault & (1 << (sig - 1)) != 0 {
+            // SIGKILL and SIGSTOP can't be changed, and always have their default
+            let _ = syscall::sigaction(sig, Some(&SigAction::default()), None);
+        }
+    }
+}
+
+/// The file actions, with system calls only. Sys::close and Sys::dup2 would also change the
+/// parent's tables of emulated descriptors, which the helper shares though its file table is its
+/// own.
+fn apply_actions(actions: &[FileAction]) -> Result<()> {
+    for action in actions {
+        match *action {
+            FileAction::Close(fd) => {
+                // like glibc, closing something that isn't open isn't an error
+                let _ = syscall::close(fd as usize);
+            }
+            FileAction::Dup2(fd, newfd) if fd == newfd => {
+                // the descriptor is inherited even if it's close-on-exec
+                syscall::fcntl(fd as usize, F_SETFD, 0)?;
+            }
+            FileAction::Dup2(fd, newfd) => {
+                syscall::dup2(fd as usize, newfd as usize, &[])?;
+            }
+            FileAction::Open {
+                fd,
+                ref path,
+                oflag,
+                mode,
+            } => {
+                let path = path.to_str().map_err(|_| Error::new(EINVAL))?;
+                let opened = path::open(
+                    path,
+                    ((oflag as usize) & 0xFFFF_0000) | ((mode as usize) & 0xFFFF),
+                )?;
+                if opened != fd as usize {
+                    let res = syscall::dup2(opened, fd as usize, &[]);
+                    let _ = syscall::close(opened);
+                    res?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Where the child starts. It stops itself when it's ready for the program, and exits if it
+/// can't be made ready, after leaving the errno for the parent.
+unsafe extern "C" fn helper_main(helper: *mut Helper) {
+    let helper = &mut *helper;
+    // None of the parent's handlers can run here before they're reset
+    let _ = syscall::sigprocmask(syscall::SIG_SETMASK, Some(&[!0, !0]), None);
+    for &fd in &helper.inherited_fds {
+        let _ = syscall::close(fd);
+    }
+
+    match prepare(helper) {
+        // The parent continues it as the program
+        Ok(()) => {
+            let _ = syscall::kill(syscall::getpid().unwrap_or(0), SIGSTOP);
+        }
+        Err(err) => helper.error = err,
+    }
+    let _ = syscall::exit(127);
+}
+
+/// Collects a NULL-terminated array of strings, with their total size including terminators.
+unsafe fn c_strings<'a>(mut strings: *const *mut c_char) -> (Vec<&'a [u8]>, usize) {
+    let mut collected = Vec::new();
+    let mut size = 0;
+    while !(*strings).is_null() {
+        let string = CStr::from_ptr(*strings).to_bytes();
+        size += string.len() + 1;
+        collected.push(string);
+        strings = strings.add(1);
+    }
+    (collected, size)
+}
+
+/// Waits until the helper has stopped itself, returning the errno it left if it exited instead.
+fn wait_ready(pid: usize, helper: *const Helper) -> Result<()> {
+    let mut status = 0;
+    loop {
+        match syscall::waitpid(pid, &mut status, WUNTRACED | WCONTINUED) {
+            // It still runs on the parent's stack, so this can't return early
+            Err(err) if err.errno == EINTR => continue,
+            res => res?,
+        };
+        if syscall::wifstopped(status) {
+            return Ok(());
+        }
+        if syscall::wifexited(status) || syscall::wifsignaled(status) {
+            let error = unsafe { (*helper).error };
+            return Err(Error::new(if error == 0 { ECHILD } else { error }));
+        }
+    }
+}
+
+/// Loads the program into the stopped context `pid_fd`, following PT_INTERP like execve.
+fn load(
+    image_file: File,
+    pid_fd: File,
+    path: &CStr,
+    args: &[&[u8]],
+    envs: &[&[u8]],
+    total_args_envs_size: usize,
+) -> Result<()> {
+    let memory = FdGuard::from(File::open_bare("memory:", O_CLOEXEC)?);
+    let cwd: Box<[u8]> = super::path::clone_cwd().unwrap_or_default().into();
+    let extrainfo = ExtraInfo { cwd: Some(&cwd) };
+
+    let mut image_file = FdGuard::from(image_file);
+    let mut open_via_dup = FdGuard::from(pid_fd);
+    let mut name: Box<[u8]> = path.to_bytes().into();
+    let mut interp_override = None;
+
+    loop {
+        match redox_exec::fexec_impl(
+            image_file,
+            open_via_dup,
+            &memory,
+            &name,
+            args.iter().rev(),
+            envs.iter().rev(),
+            total_args_envs_size,
+            &extrainfo,
+            interp_override,
+        )? {
+            // The context gets the new address space when this is closed
+            FexecResult::Normal { addrspace_handle } => {
+                drop(addrspace_handle);
+                return Ok(());
+            }
+            FexecResult::Interp {
+                path,
+                image_file: old_image_file,
+                open_via_dup: old_open_via_dup,
+                interp_override: new_interp_override,
+            } => {
+                drop(old_image_file);
+
+                let path_cstr =
+                    CStr::from_bytes_with_nul(&path).map_err(|_| Error::new(ENOEXEC))?;
+                let interp =
+                    File::open(path_cstr, fcntl::O_RDONLY).map_err(|_| Error::new(ENOENT))?;
+
+                image_file = FdGuard::from(interp);
+                open_via_dup = old_open_via_dup;
+                interp_override = Some(new_interp_override);
+                name = path;
+            }
+        }
+    }
+}
+
+unsafe fn spawn_native(
+    image_file: File,
+    path: &CStr,
+    actions: &[FileAction],
+    attr: Option<&posix_spawnattr_t>,
+    argv: *const *mut c_char,
+    envp: *const *mut c_char,
+) -> Result<pid_t> {
+    let (args, args_size) = c_strings(argv);
+    let (envs, envs_size) = c_strings(envp);
+
+    let mut sigmask = 0;
+    Sys::sigprocmask(SIG_BLOCK, ptr::null(), &mut sigmask);
+    let mut helper = Helper {
+        actions,
+        attr,
+        sigmask,
+        inherited_fds: [0; 2],
+        error: 0,
+    };
+    let helper_ptr = &mut helper as *mut Helper;
+
+    // Laid out for __relibc_internal_pte_clone_ret, which pops the entry point and the arguments
+    // passed in registers, six on x86_64 and eight on aarch64
+    let mut stack = vec![0_usize; HELPER_STACK_WORDS];
+    let mut sp = (stack.as_mut_ptr().add(stack.len()) as usize & !15) as *mut usize;
+    {
+        let mut push = |value: usize| {
+            sp = sp.sub(1);
+            *sp = value;
+        };
+
+        //WARNING: Stack must be 128-bit aligned for SSE
+        for _ in 0..7 {
+            push(0);
+        }
+        push(helper_ptr as usize);
+        push(helper_main as usize);
+    }
+
+    let (pid_fd, pid, inherited_fds, sigaction_fd) = clone::spawn_helper_impl(sp)?;
+    (*helper_ptr).inherited_fds = inherited_fds;
+    syscall::kill(pid, SIGCONT)?;
+    wait_ready(pid, helper_ptr)?;
+
+    // Loading the program empties its signal actions, so the helper's go back afterwards
+    let sigaction_sel_fd = pid_fd.dup_with(b"current-sigactions")?;
+
+    if let Err(err) = load(
+        image_file,
+        pid_fd,
+        path,
+        &args,
+        &envs,
+        args_size + envs_size,
+    ) {
+        let _ = syscall::kill(pid, SIGKILL);
+        let _ = syscall::waitpid(pid, &mut 0, WaitFlags::empty());
+        return Err(err);
+    }
+    let _ = syscall::write(
+        sigaction_sel_fd.raw(),
+        &usize::to_ne_bytes(sigaction_fd.raw()),
+    );
+
+    syscall::kill(pid, SIGCONT)?;
+    // Clear the waitpid queue like fork does
+    let _ = syscall::waitpid(pid, &mut 0, WUNTRACED | WCONTINUED);
+
+    // It's running the program now, and no longer needs anything here
+    drop(stack);
+    Ok(pid as pid_t)
+}
+
+/// Spawns `path` natively, or returns None if it has to be done with fork and exec.
+pub unsafe fn spawn(
+    path: &CStr,
+    actions: &[FileAction],
+    attr: Option<&posix_spawnattr_t>,
+    argv: *const *mut c_char,
+    envp: *const *mut c_char,
+) -> Option<core::result::Result<pid_t, c_int>> {
+    let mut image_file = match File::open(path, fcntl::O_RDONLY) {
+        Ok(image_file) => image_file,
+        Err(_) => return Some(Err(ENOENT)),
+    };
+    match check_executable(&image_file) {
+        Ok(false) => (),
+        Ok(true) => return None,
+        Err(err) => return Some(Err(err.errno)),
+    }
+
+    let mut shebang = [0; 2];
+    if image_file.read_exact(&mut shebang).is_ok() && shebang == *b"#!" {
+        return None;
+    }
+
+    Some(spawn_native(image_file, path, actions, attr, argv, envp).map_err(|err| err.errno))
+}
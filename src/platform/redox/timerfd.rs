@@ -0,0 +1,240 @@
+//! timerfd emulation on top of the time: scheme.
+//!
+//! The descriptor handed out is a timer of the time: scheme, which becomes readable when a
+//! deadline written to it passes, so that poll, select and epoll wait for it. The scheme has no
+//! notion of an interval or of an expiration count, so those are kept in this process, and reads
+//! and writes on the descriptor are intercepted to report them instead.
+//!
+//! The scheme can't cancel a deadline once written, so after the timer is disarmed or rearmed, a
+//! deadline that no longer applies may still make it readable once. Reading it then fails with
+//! EAGAIN, or blocks until the current deadline, as on a timer that hasn't expired.
+
+use alloc::sync::Arc;
+use core::mem;
+use syscall::{
+    self,
+    data::{Event, TimeSpec},
+    flag::*,
+    Error, Result, EAGAIN, EFAULT, EINVAL,
+};
+
+use super::emulated::{self, Emulated};
+use crate::{
+    header::{
+        sys_timerfd::{TFD_CLOEXEC, TFD_NONBLOCK, TFD_TIMER_ABSTIME, TFD_TIMER_CANCEL_ON_SET},
+        time::{itimerspec, timespec, CLOCK_MONOTONIC, CLOCK_REALTIME},
+    },
+    platform::types::*,
+    sync::{fork::ForkLock, Mutex},
+};
+
+const NANOS_PER_SEC: i128 = 1_000_000_000;
+
+#[derive(Default)]
+struct State {
+    /// The next expiration, in nanoseconds on the clock of the timer
+    deadline: Option<i128>,
+    /// The interval between expirations, in nanoseconds, or zero for a one-shot timer
+    interval: i128,
+}
+
+pub struct TimerFd {
+    state: Mutex<State>,
+}
+
+fn to_nanos(time: &timespec) -> Result<i128> {
+    if time.tv_sec < 0 || time.tv_nsec < 0 || time.tv_nsec as i128 >= NANOS_PER_SEC {
+        return Err(Error::new(EINVAL));
+    }
+    Ok(time.tv_sec as i128 * NANOS_PER_SEC + time.tv_nsec as i128)
+}
+
+fn from_nanos(nanos: i128) -> timespec {
+    timespec {
+        tv_sec: (nanos / NANOS_PER_SEC) as time_t,
+        tv_nsec: (nanos % NANOS_PER_SEC) as c_long,
+    }
+}
+
+/// Reading a timer of the time: scheme returns the current time on its clock.
+fn now(fd: usize) -> Result<i128> {
+    let mut time = TimeSpec::default();
+    syscall::read(fd, &mut time)?;
+    Ok(time.tv_sec as i128 * NANOS_PER_SEC + time.tv_nsec as i128)
+}
+
+fn arm(fd: usize, deadline: i128) -> Result<()> {
+    let time = TimeSpec {
+        tv_sec: (deadline / NANOS_PER_SEC) as i64,
+        tv_nsec: (deadline % NANOS_PER_SEC) as i32,
+    };
+    syscall::write(fd, &time)?;
+    Ok(())
+}
+
+impl TimerFd {
+    /// The lock of the state, which fork holds.
+    pub fn fork_lock(&self) -> &dyn ForkLock {
+        &self.state
+    }
+
+    /// Takes the expirations that are due, if any.
+    fn expirations(&self, fd: usize) -> Result<Option<u64>> {
+        let mut state = self.state.lock();
+        let deadline = match state.deadline {
+            Some(deadline) => deadline,
+            None => return Ok(None),
+        };
+        let now = now(fd)?;
+        if now < deadline {
+            return Ok(None);
+        }
+
+        if state.interval > 0 {
+            let count = 1 + (now - deadline) / state.interval;
+            let next = deadline + count * state.interval;
+            state.deadline = Some(next);
+            arm(fd, next)?;
+            Ok(Some(count as u64))
+        } else {
+            state.deadline = None;
+            Ok(Some(1))
+        }
+    }
+
+    pub fn read(&self, fd: usize, buf: &mut [u8]) -> Result<usize> {
+        if buf.len() < mem::size_of::<u64>() {
+            return Err(Error::new(EINVAL));
+        }
+
+        let output = |buf: &mut [u8], count: u64| -> Result<usize> {
+            buf[..mem::size_of::<u64>()].copy_from_slice(&count.to_ne_bytes());
+            Ok(mem::size_of::<u64>())
+        };
+        if let Some(count) = self.expirations(fd)? {
+            return output(buf, count);
+        }
+        if syscall::fcntl(fd, F_GETFL, 0)? & O_NONBLOCK != 0 {
+            return Err(Error::new(EAGAIN));
+        }
+
+        // Registered before checking again, so that an expiration in between isn't missed
+        let queue = syscall::open("event:", O_RDWR | O_CLOEXEC)?;
+        let result = (|| -> Result<usize> {
+            loop {
+                syscall::write(
+                    queue,
+                    &Event {
+                        id: fd,
+                        flags: EVENT_READ,
+                        data: 0,
+                    },
+                )?;
+                if let Some(count) = self.expirations(fd)? {
+                    return output(buf, count);
+                }
+                let mut event = Event::default();
+                syscall::read(queue, &mut event)?;
+            }
+        })();
+        let _ = syscall::close(queue);
+        result
+    }
+
+    fn settime(
+        &self,
+        fd: usize,
+        flags: c_int,
+        new_value: &itimerspec,
+        old_value: Option<&mut itimerspec>,
+    ) -> Result<()> {
+        if flags & !(TFD_TIMER_ABSTIME | TFD_TIMER_CANCEL_ON_SET) != 0 {
+            return Err(Error::new(EINVAL));
+        }
+        let value = to_nanos(&new_value.it_value)?;
+        let interval = to_nanos(&new_value.it_interval)?;
+
+        let mut state = self.state.lock();
+        let now = now(fd)?;
+        if let Some(old_value) = old_value {
+            *old_value = Self::current(&state, now);
+        }
+
+        state.interval = interval;
+        state.deadline = if value == 0 {
+            None
+        } else if flags & TFD_TIMER_ABSTIME != 0 {
+            Some(value)
+        } else {
+            Some(now + value)
+        };
+        if let Some(deadline) = state.deadline {
+            arm(fd, deadline)?;
+        }
+        Ok(())
+    }
+
+    fn current(state: &State, now: i128) -> itimerspec {
+        itimerspec {
+            it_interval: from_nanos(state.interval),
+            // An expired timer that wasn't read yet reports the smallest remaining time
+            it_value: state.deadline.map_or(from_nanos(0), |deadline| {
+                from_nanos((deadline - now).max(1))
+            }),
+        }
+    }
+
+    fn gettime(&self, fd: usize) -> Result<itimerspec> {
+        let state = self.state.lock();
+        Ok(Self::current(&state, now(fd)?))
+    }
+}
+
+fn lookup(fd: c_int) -> Result<Arc<TimerFd>> {
+    match emulated::get(fd) {
+        Some(Emulated::TimerFd(timer)) => Ok(timer),
+        _ => Err(Error::new(EINVAL)),
+    }
+}
+
+pub fn create(clockid: c_int, flags: c_int) -> Result<usize> {
+    if clockid != CLOCK_REALTIME && clockid != CLOCK_MONOTONIC {
+        return Err(Error::new(EINVAL));
+    }
+    if flags & !(TFD_CLOEXEC | TFD_NONBLOCK) != 0 {
+        return Err(Error::new(EINVAL));
+    }
+
+    let fd = syscall::open(&alloc::format!("time:{}", clockid), O_RDWR | flags as usize)?;
+    emulated::insert(
+        fd as c_int,
+        Emulated::TimerFd(Arc::new(TimerFd {
+            state: Mutex::new(State::default()),
+        })),
+    );
+    Ok(fd)
+}
+
+pub fn settime(
+    fd: c_int,
+    flags: c_int,
+    new_value: *const itimerspec,
+    old_value: *mut itimerspec,
+) -> Result<()> {
+    let timer = lookup(fd)?;
+    if new_value.is_null() {
+        return Err(Error::new(EFAULT));
+    }
+    let (new_value, old_value) = unsafe { (&*new_value, old_value.as_mut()) };
+    timer.settime(fd as usize, flags, new_value, old_value)
+}
+
+pub fn gettime(fd: c_int, curr_value: *mut itimerspec) -> Result<()> {
+    let timer = lookup(fd)?;
+    if curr_value.is_null() {
+        return Err(Error::new(EFAULT));
+    }
+    let value = timer.gettime(fd as usize)?;
+    unsafe { *curr_value = value };
+    Ok(())
+}
@@ -0,0 +1,746 @@
+//! RELIBC_TRACE, which logs the calls made to the Pal with their arguments, what they returned
+//! and the errno they set, for where there is no strace.
+//!
+//! RELIBC_TRACE lists the categories to log, separated by commas, or `all`, with a `-` before
+//! those to leave out of it, as in `all,-mem`. The lines go to the descriptor in RELIBC_TRACE_FD,
+//! or to stderr, each written at once and starting with the ID of the thread:
+//!
+//! ```text
+//! [1234] open("/etc/hosts", 524288, 0) = 3
+//! [1234] read(3, 0x7ffc0c2e5a10, 4096) = -1 (11, Resource temporarily unavailable)
+//! ```
+//!
+//! The calls the backends make among themselves aren't logged, unless they go through the rest of
+//! relibc, and neither are those the dynamic linker makes before starting the program.
+
+use core::{
+    fmt::{self, Write},
+    sync::atomic::{AtomicI32, AtomicU32, Ordering},
+};
+
+use super::{errno, sys, types::*, Pal, PalEpoll, PalIpc, PalPtrace, PalSignal, PalSocket};
+use crate::{
+    c_str::CStr,
+    header::{
+        dirent::dirent,
+        errno::STR_ERROR,
+        linux_sched::{clone_args, clone_fn},
+        mqueue::mq_attr,
+        signal::{sigaction, sigevent, sigset_t, sigval, stack_t, SIG_IGN},
+        sys_epoll::epoll_event,
+        sys_msg::msqid_ds,
+        sys_resource::rlimit,
+        sys_sem::sembuf,
+        sys_shm::shmid_ds,
+        sys_socket::{msghdr, sockaddr, socklen_t},
+        sys_stat::stat,
+        sys_statvfs::statvfs,
+        sys_sysinfo::sysinfo,
+        sys_time::{itimerval, timeval, timezone},
+        sys_utsname::utsname,
+        time::{itimerspec, timespec},
+    },
+};
+
+pub const FILE: u32 = 1 << 0;
+pub const IO: u32 = 1 << 1;
+pub const MEM: u32 = 1 << 2;
+pub const PROC: u32 = 1 << 3;
+pub const ID: u32 = 1 << 4;
+pub const SYS: u32 = 1 << 5;
+pub const TIME: u32 = 1 << 6;
+pub const SYNC: u32 = 1 << 7;
+pub const SIGNAL: u32 = 1 << 8;
+pub const NET: u32 = 1 << 9;
+pub const IPC: u32 = 1 << 10;
+pub const ALL: u32 = (1 << 11) - 1;
+
+/// The names RELIBC_TRACE takes for each category
+const CATEGORIES: [(&[u8], u32); 12] = [
+    (b"all", ALL),
+    (b"file", FILE),
+    (b"io", IO),
+    (b"mem", MEM),
+    (b"proc", PROC),
+    (b"id", ID),
+    (b"sys", SYS),
+    (b"time", TIME),
+    (b"sync", SYNC),
+    (b"signal", SIGNAL),
+    (b"net", NET),
+    (b"ipc", IPC),
+];
+
+static ENABLED: AtomicU32 = AtomicU32::new(0);
+static FD: AtomicI32 = AtomicI32::new(2);
+
+/// Finds the value of `name` in `envp`.
+unsafe fn var<'a>(mut envp: *const *const c_char, name: &[u8]) -> Option<&'a [u8]> {
+    while !envp.is_null() && !(*envp).is_null() {
+        let var = CStr::from_ptr(*envp).to_bytes();
+        if var.len() > name.len() && var.starts_with(name) && var[name.len()] == b'=' {
+            return Some(&var[name.len() + 1..]);
+        }
+        envp = envp.add(1);
+    }
+    None
+}
+
+/// Starts logging what RELIBC_TRACE in `envp` asks for. The thread-local storage has to be set
+/// up, as the calls have their errno saved.
+pub unsafe fn init(envp: *const *const c_char) {
+    let categories = match var(envp, b"RELIBC_TRACE") {
+        Some(categories) => categories,
+        None => return,
+    };
+    if let Some(fd) = var(envp, b"RELIBC_TRACE_FD")
+        .and_then(|fd| core::str::from_utf8(fd).ok())
+        .and_then(|fd| fd.parse::<c_int>().ok())
+    {
+        FD.store(fd, Ordering::Relaxed);
+    }
+
+    let mut enabled = 0;
+    for name in categories.split(|&b| b == b',') {
+        let (name, remove) = match name.strip_prefix(b"-") {
+            Some(name) => (name, true),
+            None => (name, false),
+        };
+        // Unknown ones are left out
+        if let Some(&(_, bits)) = CATEGORIES.iter().find(|&&(known, _)| known == name) {
+            if remove {
+                enabled &= !bits;
+            } else {
+                enabled |= bits;
+            }
+        }
+    }
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+fn enabled(category: u32) -> bool {
+    ENABLED.load(Ordering::Relaxed) & category != 0
+}
+
+/// One line of the log, cut short if it doesn't fit.
+struct Line {
+    buf: [u8; 512],
+    len: usize,
+    args: usize,
+    /// The errno of the caller, which the call may not change unless it fails
+    errno: c_int,
+}
+
+impl Line {
+    fn new(name: &str) -> Self {
+        let mut line = Self {
+            buf: [0; 512],
+            len: 0,
+            args: 0,
+            errno: unsafe { errno },
+        };
+        let _ = write!(line, "[{}] {}(", sys::Sys::gettid(), name);
+        line
+    }
+
+    fn arg<A: Arg + ?Sized>(&mut self, arg: &A) {
+        if self.args > 0 {
+            let _ = self.write_str(", ");
+        }
+        self.args += 1;
+        arg.trace(self);
+    }
+
+    /// Logs the call before it's made, for those that don't return when they succeed.
+    fn started(&mut self) {
+        let len = self.len;
+        let _ = self.write_str(") ...");
+        self.flush();
+        self.len = len;
+    }
+
+    /// Clears errno, so what the call sets is known.
+    fn call(&mut self) {
+        unsafe { errno = 0 };
+    }
+
+    /// Logs the call with what it returned, and leaves errno as the caller expects it.
+    fn returned<R: Arg>(mut self, ret: &R) {
+        let set = unsafe { errno };
+        let _ = self.write_str(") = ");
+        ret.trace(&mut self);
+        if set != 0 {
+            let strerror = STR_ERROR
+                .get(set as usize)
+                .copied()
+                .unwrap_or("Unknown error");
+            let _ = write!(self, " ({}, {})", set, strerror);
+        }
+        self.flush();
+        unsafe { errno = if set != 0 { set } else { self.errno } };
+    }
+
+    fn flush(&mut self) {
+        let _ = self.write_str("\n");
+        if self.len == self.buf.len() {
+            // Cut short, with the line still ending
+            self.buf[self.len - 4..].copy_from_slice(b"...\n");
+        }
+        sys::Sys::write(FD.load(Ordering::Relaxed), &self.buf[..self.len]);
+    }
+}
+
+impl fmt::Write for Line {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let len = s.len().min(self.buf.len() - self.len);
+        self.buf[self.len..self.len + len].copy_from_slice(&s.as_bytes()[..len]);
+        self.len += len;
+        Ok(())
+    }
+}
+
+/// How an argument or return value is shown.
+trait Arg {
+    fn trace(&self, line: &mut Line);
+}
+
+macro_rules! arg_display {
+    ($($ty:ty),*) => {
+        $(
+            impl Arg for $ty {
+                fn trace(&self, line: &mut Line) {
+                    let _ = write!(line, "{}", self);
+                }
+            }
+        )*
+    };
+}
+
+arg_display!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize, bool);
+
+impl<T> Arg for *const T {
+    fn trace(&self, line: &mut Line) {
+        if self.is_null() {
+            let _ = line.write_str("NULL");
+        } else {
+            let _ = write!(line, "{:p}", *self);
+        }
+    }
+}
+
+impl<T> Arg for *mut T {
+    fn trace(&self, line: &mut Line) {
+        (*self as *const T).trace(line);
+    }
+}
+
+/// Shows `bytes` quoted, with at most `max` of them.
+fn quoted(line: &mut Line, bytes: &[u8], max: usize) {
+    let _ = line.write_str("\"");
+    for &b in bytes.iter().take(max) {
+        let _ = match b {
+            b'"' => line.write_str("\\\""),
+            b'\\' => line.write_str("\\\\"),
+            b'\n' => line.write_str("\\n"),
+            b'\t' => line.write_str("\\t"),
+            0x20..=0x7e => write!(line, "{}", b as char),
+            _ => write!(line, "\\x{:02x}", b),
+        };
+    }
+    let _ = line.write_str("\"");
+    if bytes.len() > max {
+        let _ = line.write_str("...");
+    }
+}
+
+impl Arg for CStr {
+    fn trace(&self, line: &mut Line) {
+        quoted(line, self.to_bytes(), 128);
+    }
+}
+
+impl Arg for [u8] {
+    fn trace(&self, line: &mut Line) {
+        quoted(line, self, 32);
+        let _ = write!(line, ", {}", self.len());
+    }
+}
+
+impl Arg for [c_int] {
+    fn trace(&self, line: &mut Line) {
+        self.as_ptr().trace(line);
+    }
+}
+
+impl Arg for [c_int; 2] {
+    fn trace(&self, line: &mut Line) {
+        self.as_ptr().trace(line);
+    }
+}
+
+impl<'a, T: Arg + ?Sized> Arg for &'a T {
+    fn trace(&self, line: &mut Line) {
+        (**self).trace(line);
+    }
+}
+
+// Buffers to be filled, of which only the size matters
+impl<'a> Arg for &'a mut [u8] {
+    fn trace(&self, line: &mut Line) {
+        let _ = write!(line, "{:p}, {}", self.as_ptr(), self.len());
+    }
+}
+
+impl<'a> Arg for &'a mut [c_int] {
+    fn trace(&self, line: &mut Line) {
+        self.as_ptr().trace(line);
+    }
+}
+
+impl<'a> Arg for &'a mut [c_int; 2] {
+    fn trace(&self, line: &mut Line) {
+        self.as_ptr().trace(line);
+    }
+}
+
+impl Arg for clone_args {
+    fn trace(&self, line: &mut Line) {
+        let _ = write!(
+            line,
+            "{{flags: {:#x}, exit_signal: {}, stack: {:#x}, stack_size: {}}}",
+            self.flags, self.exit_signal, self.stack, self.stack_size
+        );
+    }
+}
+
+impl Arg for clone_fn {
+    fn trace(&self, line: &mut Line) {
+        match self {
+            Some(func) => (*func as *const c_void).trace(line),
+            None => {
+                let _ = line.write_str("NULL");
+            }
+        }
+    }
+}
+
+impl Arg for sigaction {
+    fn trace(&self, line: &mut Line) {
+        let _ = match unsafe { self.__sa_handler.sa_handler }.map(|handler| handler as usize) {
+            None => line.write_str("{sa_handler: SIG_DFL"),
+            Some(SIG_IGN) => line.write_str("{sa_handler: SIG_IGN"),
+            Some(handler) => write!(line, "{{sa_handler: {:#x}", handler),
+        };
+        let _ = write!(
+            line,
+            ", sa_flags: {:#x}, sa_mask: {:#x}}}",
+            self.sa_flags, self.sa_mask
+        );
+    }
+}
+
+impl Arg for sigval {
+    fn trace(&self, line: &mut Line) {
+        unsafe { self.sival_ptr }.trace(line);
+    }
+}
+
+impl<'a> Arg for Option<&'a sigaction> {
+    fn trace(&self, line: &mut Line) {
+        match self {
+            Some(act) => act.trace(line),
+            None => {
+                let _ = line.write_str("NULL");
+            }
+        }
+    }
+}
+
+// Filled in by the call
+impl<'a> Arg for Option<&'a mut sigaction> {
+    fn trace(&self, line: &mut Line) {
+        match self {
+            Some(act) => (&**act as *const sigaction).trace(line),
+            None => {
+                let _ = line.write_str("NULL");
+            }
+        }
+    }
+}
+
+/// Implements `$trait` for the Sys the rest of relibc calls, with each method logging the call
+/// when its category is enabled and making it on the backend's.
+macro_rules! traced {
+    (
+        $trait:ident {
+            $(
+                $category:ident $mode:ident [$($qual:tt)*]
+                fn $name:ident($($arg:ident: $ty:ty),* $(,)?) -> $ret:ty;
+            )*
+        }
+    ) => {
+        impl $trait for Sys {
+            $(
+                #[allow(unreachable_code, unused_unsafe)]
+                $($qual)* fn $name($($arg: $ty),*) -> $ret {
+                    if !enabled($category) {
+                        return sys::Sys::$name($($arg),*);
+                    }
+                    let mut line = Line::new(stringify!($name));
+                    $(line.arg(&$arg);)*
+                    traced!(@$mode line, sys::Sys::$name($($arg),*))
+                }
+            )*
+        }
+    };
+    (@returns $line:ident, $call:expr) => {{
+        $line.call();
+        let ret = $call;
+        $line.returned(&ret);
+        ret
+    }};
+    (@execs $line:ident, $call:expr) => {{
+        $line.started();
+        traced!(@returns $line, $call)
+    }};
+    (@exits $line:ident, $call:expr) => {{
+        $line.started();
+        $call
+    }};
+}
+
+/// The Pal relibc calls, which is the backend's, with the calls logged if RELIBC_TRACE asks.
+pub struct Sys;
+
+#[cfg(target_os = "linux")]
+impl Sys {
+    pub unsafe fn ioctl(fd: c_int, request: c_ulong, out: *mut c_void) -> c_int {
+        if !enabled(IO) {
+            return sys::Sys::ioctl(fd, request, out);
+        }
+        let mut line = Line::new("ioctl");
+        line.arg(&fd);
+        line.arg(&request);
+        line.arg(&out);
+        traced!(@returns line, sys::Sys::ioctl(fd, request, out))
+    }
+}
+
+traced! {
+    Pal {
+        FILE returns [] fn access(path: &CStr, mode: c_int) -> c_int;
+        MEM returns [] fn brk(addr: *mut c_void) -> *mut c_void;
+        FILE returns [] fn chdir(path: &CStr) -> c_int;
+        FILE returns [] fn chmod(path: &CStr, mode: mode_t) -> c_int;
+        FILE returns [] fn chown(path: &CStr, owner: uid_t, group: gid_t) -> c_int;
+        TIME returns [] fn clock_gettime(clk_id: clockid_t, tp: *mut timespec) -> c_int;
+        PROC returns [unsafe] fn clone3(
+            args: &clone_args,
+            func: clone_fn,
+            arg: *mut c_void,
+        ) -> pid_t;
+        IO returns [] fn close(fildes: c_int) -> c_int;
+        IO returns [] fn dup(fildes: c_int) -> c_int;
+        IO returns [] fn dup2(fildes: c_int, fildes2: c_int) -> c_int;
+        IO returns [] fn eventfd(initval: c_uint, flags: c_int) -> c_int;
+        PROC execs [unsafe] fn execve(
+            path: &CStr,
+            argv: *const *mut c_char,
+            envp: *const *mut c_char,
+        ) -> c_int;
+        PROC exits [] fn exit(status: c_int) -> !;
+        PROC exits [] fn exit_thread() -> !;
+        FILE returns [] fn fchdir(fildes: c_int) -> c_int;
+        FILE returns [] fn fchmod(fildes: c_int, mode: mode_t) -> c_int;
+        FILE returns [] fn fchown(fildes: c_int, owner: uid_t, group: gid_t) -> c_int;
+        FILE returns [] fn flock(fd: c_int, operation: c_int) -> c_int;
+        FILE returns [] fn fstat(fildes: c_int, buf: *mut stat) -> c_int;
+        FILE returns [] fn fstatvfs(fildes: c_int, buf: *mut statvfs) -> c_int;
+        IO returns [] fn fcntl(fildes: c_int, cmd: c_int, arg: c_int) -> c_int;
+        PROC returns [] fn fork() -> pid_t;
+        FILE returns [] fn fpath(fildes: c_int, out: &mut [u8]) -> ssize_t;
+        IO returns [] fn fsync(fildes: c_int) -> c_int;
+        FILE returns [] fn ftruncate(fildes: c_int, length: off_t) -> c_int;
+        SYNC returns [] fn futex(addr: *mut c_int, op: c_int, val: c_int, val2: usize) -> c_int;
+        FILE returns [] fn futimens(fd: c_int, times: *const timespec) -> c_int;
+        FILE returns [] fn utimens(path: &CStr, times: *const timespec) -> c_int;
+        SYS returns [] fn get_nprocs() -> c_int;
+        SYS returns [] fn get_nprocs_conf() -> c_int;
+        FILE returns [] fn getcwd(buf: *mut c_char, size: size_t) -> *mut c_char;
+        FILE returns [] fn getdents(fd: c_int, dirents: *mut dirent, bytes: usize) -> c_int;
+        ID returns [] fn getegid() -> gid_t;
+        ID returns [] fn geteuid() -> uid_t;
+        ID returns [] fn getgid() -> gid_t;
+        SYS returns [] fn getpagesize() -> usize;
+        PROC returns [] fn getpgid(pid: pid_t) -> pid_t;
+        PROC returns [] fn getpid() -> pid_t;
+        PROC returns [] fn getppid() -> pid_t;
+        SYS returns [] fn getrandom(buf: &mut [u8], flags: c_uint) -> ssize_t;
+        SYS returns [unsafe] fn getrlimit(resource: c_int, rlim: *mut rlimit) -> c_int;
+        PROC returns [] fn getsid(pid: pid_t) -> pid_t;
+        PROC returns [] fn gettid() -> pid_t;
+        TIME returns [] fn gettimeofday(tp: *mut timeval, tzp: *mut timezone) -> c_int;
+        ID returns [] fn getuid() -> uid_t;
+        FILE returns [] fn inotify_add_watch(fd: c_int, path: &CStr, mask: u32) -> c_int;
+        FILE returns [] fn inotify_init1(flags: c_int) -> c_int;
+        FILE returns [] fn inotify_rm_watch(fd: c_int, wd: c_int) -> c_int;
+        FILE returns [] fn lchown(path: &CStr, owner: uid_t, group: gid_t) -> c_int;
+        FILE returns [] fn link(path1: &CStr, path2: &CStr) -> c_int;
+        IO returns [] fn lseek(fildes: c_int, offset: off_t, whence: c_int) -> off_t;
+        FILE returns [] fn mkdir(path: &CStr, mode: mode_t) -> c_int;
+        FILE returns [] fn mkfifo(path: &CStr, mode: mode_t) -> c_int;
+        MEM returns [unsafe] fn madvise(addr: *mut c_void, len: usize, advice: c_int) -> c_int;
+        FILE returns [] fn memfd_create(name: &CStr, flags: c_uint) -> c_int;
+        MEM returns [unsafe] fn mlock(addr: *const c_void, len: usize) -> c_int;
+        MEM returns [] fn mlockall(flags: c_int) -> c_int;
+        MEM returns [unsafe] fn mmap(
+            addr: *mut c_void,
+            len: usize,
+            prot: c_int,
+            flags: c_int,
+            fildes: c_int,
+            off: off_t,
+        ) -> *mut c_void;
+        MEM returns [unsafe] fn mprotect(addr: *mut c_void, len: usize, prot: c_int) -> c_int;
+        MEM returns [unsafe] fn mremap(
+            old_address: *mut c_void,
+            old_size: usize,
+            new_size: usize,
+            flags: c_int,
+            new_address: *mut c_void,
+        ) -> *mut c_void;
+        MEM returns [unsafe] fn msync(addr: *mut c_void, len: usize, flags: c_int) -> c_int;
+        MEM returns [unsafe] fn munlock(addr: *const c_void, len: usize) -> c_int;
+        MEM returns [] fn munlockall() -> c_int;
+        MEM returns [unsafe] fn munmap(addr: *mut c_void, len: usize) -> c_int;
+        TIME returns [] fn nanosleep(rqtp: *const timespec, rmtp: *mut timespec) -> c_int;
+        FILE returns [] fn open(path: &CStr, oflag: c_int, mode: mode_t) -> c_int;
+        IO returns [] fn pipe2(fildes: &mut [c_int], flags: c_int) -> c_int;
+        PROC returns [unsafe] fn pte_clone(stack: *mut usize) -> pid_t;
+        IO returns [] fn read(fildes: c_int, buf: &mut [u8]) -> ssize_t;
+        FILE returns [] fn readlink(pathname: &CStr, out: &mut [u8]) -> ssize_t;
+        FILE returns [] fn rename(old: &CStr, new: &CStr) -> c_int;
+        FILE returns [] fn rmdir(path: &CStr) -> c_int;
+        SYS returns [] fn sched_yield() -> c_int;
+        SYS returns [] fn sethostname(name: &[u8]) -> c_int;
+        PROC returns [] fn setns(fd: c_int, nstype: c_int) -> c_int;
+        PROC returns [] fn setpgid(pid: pid_t, pgid: pid_t) -> c_int;
+        SYS returns [unsafe] fn setrlimit(resource: c_int, rlim: *const rlimit) -> c_int;
+        PROC returns [] fn setsid() -> pid_t;
+        ID returns [] fn setregid(rgid: gid_t, egid: gid_t) -> c_int;
+        ID returns [] fn setreuid(ruid: uid_t, euid: uid_t) -> c_int;
+        FILE returns [] fn symlink(path1: &CStr, path2: &CStr) -> c_int;
+        SYS returns [] fn sysinfo(info: *mut sysinfo) -> c_int;
+        IO returns [] fn timerfd_create(clockid: c_int, flags: c_int) -> c_int;
+        IO returns [] fn timerfd_gettime(fd: c_int, curr_value: *mut itimerspec) -> c_int;
+        IO returns [] fn timerfd_settime(
+            fd: c_int,
+            flags: c_int,
+            new_value: *const itimerspec,
+            old_value: *mut itimerspec,
+        ) -> c_int;
+        FILE returns [] fn umask(mask: mode_t) -> mode_t;
+        SYS returns [] fn uname(utsname: *mut utsname) -> c_int;
+        FILE returns [] fn unlink(path: &CStr) -> c_int;
+        PROC returns [] fn unshare(flags: c_int) -> c_int;
+        PROC returns [] fn waitpid(pid: pid_t, stat_loc: *mut c_int, options: c_int) -> pid_t;
+        IO returns [] fn write(fildes: c_int, buf: &[u8]) -> ssize_t;
+        SYS returns [] fn verify() -> bool;
+    }
+}
+
+traced! {
+    PalEpoll {
+        IO returns [] fn epoll_create1(flags: c_int) -> c_int;
+        IO returns [] fn epoll_ctl(
+            epfd: c_int,
+            op: c_int,
+            fd: c_int,
+            event: *mut epoll_event,
+        ) -> c_int;
+        IO returns [] fn epoll_pwait(
+            epfd: c_int,
+            events: *mut epoll_event,
+            maxevents: c_int,
+            timeout: c_int,
+            sigmask: *const sigset_t,
+        ) -> c_int;
+    }
+}
+
+traced! {
+    PalIpc {
+        IPC returns [] fn mq_getsetattr(
+            mqdes: c_int,
+            new: *const mq_attr,
+            old: *mut mq_attr,
+        ) -> c_int;
+        IPC returns [] fn mq_notify(mqdes: c_int, sevp: *const sigevent) -> c_int;
+        IPC returns [] fn mq_open(
+            name: &CStr,
+            oflag: c_int,
+            mode: mode_t,
+            attr: *const mq_attr,
+        ) -> c_int;
+        IPC returns [unsafe] fn mq_timedreceive(
+            mqdes: c_int,
+            msg_ptr: *mut c_char,
+            msg_len: size_t,
+            msg_prio: *mut c_uint,
+            abs_timeout: *const timespec,
+        ) -> ssize_t;
+        IPC returns [unsafe] fn mq_timedsend(
+            mqdes: c_int,
+            msg_ptr: *const c_char,
+            msg_len: size_t,
+            msg_prio: c_uint,
+            abs_timeout: *const timespec,
+        ) -> c_int;
+        IPC returns [] fn mq_unlink(name: &CStr) -> c_int;
+        IPC returns [] fn msgctl(msqid: c_int, cmd: c_int, buf: *mut msqid_ds) -> c_int;
+        IPC returns [] fn msgget(key: key_t, msgflg: c_int) -> c_int;
+        IPC returns [] fn msgrcv(
+            msqid: c_int,
+            msgp: *mut c_void,
+            msgsz: size_t,
+            msgtyp: c_long,
+            msgflg: c_int,
+        ) -> ssize_t;
+        IPC returns [] fn msgsnd(
+            msqid: c_int,
+            msgp: *const c_void,
+            msgsz: size_t,
+            msgflg: c_int,
+        ) -> c_int;
+        IPC returns [] fn semctl(semid: c_int, semnum: c_int, cmd: c_int, arg: c_ulong) -> c_int;
+        IPC returns [] fn semget(key: key_t, nsems: c_int, semflg: c_int) -> c_int;
+        IPC returns [] fn semtimedop(
+            semid: c_int,
+            sops: *mut sembuf,
+            nsops: size_t,
+            timeout: *const timespec,
+        ) -> c_int;
+        IPC returns [unsafe] fn shmat(
+            shmid: c_int,
+            shmaddr: *const c_void,
+            shmflg: c_int,
+        ) -> *mut c_void;
+        IPC returns [] fn shmctl(shmid: c_int, cmd: c_int, buf: *mut shmid_ds) -> c_int;
+        IPC returns [unsafe] fn shmdt(shmaddr: *const c_void) -> c_int;
+        IPC returns [] fn shmget(key: key_t, size: size_t, shmflg: c_int) -> c_int;
+    }
+}
+
+traced! {
+    PalPtrace {
+        PROC returns [] fn ptrace(
+            request: c_int,
+            pid: pid_t,
+            addr: *mut c_void,
+            data: *mut c_void,
+        ) -> c_long;
+    }
+}
+
+traced! {
+    PalSignal {
+        TIME returns [] fn getitimer(which: c_int, out: *mut itimerval) -> c_int;
+        SIGNAL returns [] fn kill(pid: pid_t, sig: c_int) -> c_int;
+        SIGNAL returns [] fn killpg(pgrp: pid_t, sig: c_int) -> c_int;
+        SIGNAL returns [] fn raise(sig: c_int) -> c_int;
+        TIME returns [] fn setitimer(
+            which: c_int,
+            new: *const itimerval,
+            old: *mut itimerval,
+        ) -> c_int;
+        SIGNAL returns [] fn sigaction(
+            sig: c_int,
+            act: Option<&sigaction>,
+            oact: Option<&mut sigaction>,
+        ) -> c_int;
+        SIGNAL returns [] fn sigaltstack(ss: *const stack_t, old_ss: *mut stack_t) -> c_int;
+        SIGNAL returns [] fn sigprocmask(
+            how: c_int,
+            set: *const sigset_t,
+            oset: *mut sigset_t,
+        ) -> c_int;
+        SIGNAL returns [] fn sigqueue(pid: pid_t, sig: c_int, code: c_int, value: sigval) -> c_int;
+    }
+}
+
+traced! {
+    PalSocket {
+        NET returns [unsafe] fn accept(
+            socket: c_int,
+            address: *mut sockaddr,
+            address_len: *mut socklen_t,
+        ) -> c_int;
+        NET returns [unsafe] fn accept4(
+            socket: c_int,
+            address: *mut sockaddr,
+            address_len: *mut socklen_t,
+            flags: c_int,
+        ) -> c_int;
+        NET returns [unsafe] fn bind(
+            socket: c_int,
+            address: *const sockaddr,
+            address_len: socklen_t,
+        ) -> c_int;
+        NET returns [unsafe] fn connect(
+            socket: c_int,
+            address: *const sockaddr,
+            address_len: socklen_t,
+        ) -> c_int;
+        NET returns [unsafe] fn getpeername(
+            socket: c_int,
+            address: *mut sockaddr,
+            address_len: *mut socklen_t,
+        ) -> c_int;
+        NET returns [unsafe] fn getsockname(
+            socket: c_int,
+            address: *mut sockaddr,
+            address_len: *mut socklen_t,
+        ) -> c_int;
+        NET returns [] fn getsockopt(
+            socket: c_int,
+            level: c_int,
+            option_name: c_int,
+            option_value: *mut c_void,
+            option_len: *mut socklen_t,
+        ) -> c_int;
+        NET returns [] fn listen(socket: c_int, backlog: c_int) -> c_int;
+        NET returns [unsafe] fn recvfrom(
+            socket: c_int,
+            buf: *mut c_void,
+            len: size_t,
+            flags: c_int,
+            address: *mut sockaddr,
+            address_len: *mut socklen_t,
+        ) -> ssize_t;
+        NET returns [unsafe] fn recvmsg(socket: c_int, msg: *mut msghdr, flags: c_int) -> ssize_t;
+        NET returns [unsafe] fn sendmsg(socket: c_int, msg: *const msghdr, flags: c_int) -> ssize_t;
+        NET returns [unsafe] fn sendto(
+            socket: c_int,
+            buf: *const c_void,
+            len: size_t,
+            flags: c_int,
+            dest_addr: *const sockaddr,
+            dest_len: socklen_t,
+        ) -> ssize_t;
+        NET returns [] fn setsockopt(
+            socket: c_int,
+            level: c_int,
+            option_name: c_int,
+            option_value: *const c_void,
+            option_len: socklen_t,
+        ) -> c_int;
+        NET returns [] fn shutdown(socket: c_int, how: c_int) -> c_int;
+        NET returns [unsafe] fn socket(domain: c_int, kind: c_int, protocol: c_int) -> c_int;
+        NET returns [] fn socketpair(
+            domain: c_int,
+            kind: c_int,
+            protocol: c_int,
+            sv: &mut [c_int; 2],
+        ) -> c_int;
+    }
+}
@@ -59,6 +59,7 @@ pub type mode_t = c_int;
 pub type time_t = c_longlong;
 pub type pid_t = c_int;
 pub type id_t = c_uint;
+pub type key_t = c_int;
 pub type gid_t = c_int;
 pub type uid_t = c_int;
 pub type dev_t = c_long;
@@ -4,7 +4,7 @@ use core::{intrinsics, ptr};
 use crate::{
     header::{libgen, stdio, stdlib},
     ld_so::{self, linker::Linker},
-    platform::{self, get_auxvs, new_mspace, types::*, Pal, Sys},
+    platform::{self, new_mspace, types::*, Pal, Sys},
     sync::mutex::Mutex,
     ALLOCATOR,
 };
@@ -44,7 +44,7 @@ unsafe fn copy_string_array(array: *const *const c_char, len: usize) -> Vec<*mut
             len += 1;
         }
 
-        let buf = platform::alloc(len + 1) as *mut c_char;
+        let buf = stdlib::malloc(len + 1) as *mut c_char;
         for i in 0..=len {
             *buf.add(i) = *item.add(i);
         }
@@ -89,6 +89,8 @@ fn alloc_init() {
             ALLOCATOR.set_book_keeper(new_mspace());
         }
     }
+    // Thread-local storage is set up by now
+    platform::enable_cache();
 }
 
 extern "C" fn init_array() {
@@ -131,21 +133,8 @@ fn io_init() {
 
 #[cfg(target_os = "redox")]
 fn setup_sigstack() {
-    use syscall::{Map, MapFlags};
-    const SIGSTACK_SIZE: usize = 1024 * 256;
-    let sigstack = unsafe {
-        syscall::fmap(
-            !0,
-            &Map {
-                address: 0,
-                offset: 0,
-                flags: MapFlags::MAP_PRIVATE | MapFlags::PROT_READ | MapFlags::PROT_WRITE,
-                size: SIGSTACK_SIZE,
-            },
-        )
-    }
-    .expect("failed to allocate sigstack")
-        + SIGSTACK_SIZE;
+    let sigstack =
+        unsafe { platform::sys::clone::map_sigstack() }.expect("failed to allocate sigstack");
 
     let fd = syscall::open(
         "thisproc:current/sigstack",
@@ -173,9 +162,21 @@ pub unsafe extern "C" fn relibc_start(sp: &'static Stack) -> ! {
     // Ensure correct host system before executing more system calls
     relibc_verify_host();
 
+    // Everything after needs the auxiliary vector
+    platform::init_auxv(sp.auxv().cast());
+
     // Initialize TLS, if necessary
     ld_so::init(sp);
 
+    // Errors are reported in TLS
+    platform::init_stack_guard();
+
+    // Allocations are checked from the first one on, if asked to
+    platform::init_debug(sp.envp());
+
+    // Calls are logged from here on, if asked to
+    platform::init_trace(sp.envp());
+
     // Set up the right allocator...
     // if any memory rust based memory allocation happen before this step .. we are doomed.
     alloc_init();
@@ -184,6 +185,9 @@ pub unsafe extern "C" fn relibc_start(sp: &'static Stack) -> ! {
         // Update TCB mspace
         tcb.mspace = ALLOCATOR.get_book_keeper();
 
+        // The dynamic linker made the TCB, maybe with a different canary
+        tcb.stack_guard = platform::__stack_chk_guard;
+
         // Set linker pointer if necessary
         if tcb.linker_ptr.is_null() {
             //TODO: get ld path
@@ -216,8 +220,7 @@ pub unsafe extern "C" fn relibc_start(sp: &'static Stack) -> ! {
         platform::OUR_ENVIRON = copy_string_array(envp, len);
         platform::environ = platform::OUR_ENVIRON.as_mut_ptr();
     }
-    let auxvs = get_auxvs(sp.auxv().cast());
-    crate::platform::init(auxvs);
+    crate::platform::init();
 
     // Setup signal stack, otherwise we cannot handle any signals besides SIG_IGN/SIG_DFL behavior.
     #[cfg(target_os = "redox")]
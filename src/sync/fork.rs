@@ -0,0 +1,146 @@
+//! The internal locks that fork holds. Only the forking thread exists in the child, so a lock
+//! another thread held at the time would be held there forever, and what it protects would be
+//! copied half-changed. Each lock here is taken before the fork, released after it in the parent
+//! and made usable again in the child.
+//!
+//! A lock that's needed while another is held has to come after it, so the heap's come last.
+
+use super::Mutex;
+use crate::{
+    header::{aio, netdb, poll, stdio, stdlib},
+    ld_so::{linker::Linker, tcb::Tcb},
+    platform::{self, types::*, Pal, Sys},
+};
+
+/// A lock, or a group of locks, that fork holds
+pub trait ForkLock: Sync {
+    /// Takes the lock, before the fork
+    unsafe fn prepare(&self);
+    /// Releases it in the parent
+    unsafe fn parent(&self);
+    /// Makes it usable again in the child
+    unsafe fn child(&self);
+}
+
+impl<T: Send> ForkLock for Mutex<T> {
+    unsafe fn prepare(&self) {
+        self.manual_lock();
+    }
+    unsafe fn parent(&self) {
+        self.manual_unlock();
+    }
+    unsafe fn child(&self) {
+        self.manual_reinit();
+    }
+}
+
+/// The lock of the dynamic linker, which dlopen and dlclose take to change the loaded objects
+/// and the TLS images threads are created with
+struct TlsRegistry;
+
+impl TlsRegistry {
+    unsafe fn lock() -> Option<&'static Mutex<Linker>> {
+        let tcb = Tcb::current()?;
+        if tcb.linker_ptr.is_null() {
+            None
+        } else {
+            Some(&*tcb.linker_ptr)
+        }
+    }
+}
+
+impl ForkLock for TlsRegistry {
+    unsafe fn prepare(&self) {
+        if let Some(lock) = Self::lock() {
+            lock.manual_lock();
+        }
+    }
+    unsafe fn parent(&self) {
+        if let Some(lock) = Self::lock() {
+            lock.manual_unlock();
+        }
+    }
+    unsafe fn child(&self) {
+        if let Some(lock) = Self::lock() {
+            lock.manual_reinit();
+        }
+    }
+}
+
+/// The locks of the heap
+struct Heap;
+
+impl ForkLock for Heap {
+    unsafe fn prepare(&self) {
+        platform::fork_prepare();
+    }
+    unsafe fn parent(&self) {
+        platform::fork_parent();
+    }
+    unsafe fn child(&self) {
+        platform::fork_child();
+    }
+}
+
+/// Every lock fork holds, in the order they're taken
+static LOCKS: &[&dyn ForkLock] = &[
+    &stdlib::ENVIRON_LOCK,
+    // Workers are started while it's held
+    &aio::POOL,
+    &stdio::Streams,
+    &TlsRegistry,
+    &netdb::cache::CACHE,
+    #[cfg(target_os = "redox")]
+    &poll::POLLERS,
+    // Epoll instances write to descriptors and look them up while they're locked
+    #[cfg(target_os = "redox")]
+    &platform::sys::emulated::Descriptors,
+    #[cfg(target_os = "redox")]
+    &platform::sys::socket::Sockets,
+    #[cfg(target_os = "redox")]
+    &platform::sys::memfd::MEMFDS,
+    #[cfg(target_os = "redox")]
+    &platform::sys::ipc::ATTACHED,
+    #[cfg(target_os = "redox")]
+    &platform::sys::ipc::UNDO,
+    // Memory is allocated while the records of mappings are changed
+    #[cfg(target_os = "redox")]
+    &platform::sys::mman::Regions,
+    &Heap,
+];
+
+/// Takes every lock, before a fork or a clone that copies the memory
+pub unsafe fn prepare() {
+    for lock in LOCKS.iter() {
+        lock.prepare();
+    }
+}
+
+/// Releases every lock in the parent
+pub unsafe fn parent() {
+    for lock in LOCKS.iter().rev() {
+        lock.parent();
+    }
+}
+
+/// Makes every lock usable again in the child, last ones first, so that what a lock does there
+/// can use those after it, like the heap
+pub unsafe fn child() {
+    for lock in LOCKS.iter().rev() {
+        lock.child();
+    }
+    #[cfg(target_os = "redox")]
+    platform::sys::forked();
+}
+
+/// Forks with every lock held, so the child gets what they protect in a consistent state.
+pub unsafe fn fork() -> pid_t {
+    prepare();
+    let pid = Sys::fork();
+    if pid == 0 {
+        child();
+    } else {
+        parent();
+    }
+    pid
+}
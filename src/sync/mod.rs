@@ -1,3 +1,4 @@
+pub mod fork;
 pub mod mutex;
 pub mod once;
 pub mod semaphore;
@@ -77,6 +77,15 @@ impl<T> Mutex<T> {
             self.lock.notify_one();
         }
     }
+    /// The content of the mutex, for whoever locked it with manual_lock.
+    pub unsafe fn manual_content(&self) -> &mut T {
+        &mut *self.content.get()
+    }
+    /// Unlock the mutex without waking anyone, in the child of a fork, where
+    /// whoever held or waited for it doesn't exist.
+    pub unsafe fn manual_reinit(&self) {
+        self.lock.store(UNLOCKED, SeqCst);
+    }
 
     /// Tries to lock the mutex and returns a guard that automatically unlocks
     /// the mutex when it falls out of scope.
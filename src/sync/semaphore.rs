@@ -13,6 +13,9 @@ pub struct Semaphore {
     lock: AtomicLock,
 }
 
+unsafe impl Send for Semaphore {}
+unsafe impl Sync for Semaphore {}
+
 impl Semaphore {
     pub const fn new(value: c_int) -> Self {
         Self {
@@ -25,7 +28,22 @@ impl Semaphore {
         self.lock.notify_all();
     }
 
+    /// Wakes the threads waiting without posting, for them to check what stops their wait.
+    pub fn wake(&self) {
+        self.lock.notify_all();
+    }
+
     pub fn wait(&self, timeout_opt: Option<&timespec>) -> Result<(), ()> {
+        self.wait_unless(timeout_opt, || false)
+    }
+
+    /// Waits like `wait`, but fails as soon as `stop` returns true, which it is checked before
+    /// every sleep, and after [`wake`](Self::wake).
+    pub fn wait_unless(
+        &self,
+        timeout_opt: Option<&timespec>,
+        stop: impl Fn() -> bool,
+    ) -> Result<(), ()> {
         loop {
             let value = self.lock.load(Ordering::SeqCst);
             if value > 0 {
@@ -44,6 +62,9 @@ impl Semaphore {
                 // Try again (as long as value > 0)
                 continue;
             }
+            if stop() {
+                return Err(());
+            }
             if let Some(timeout) = timeout_opt {
                 let mut time = timespec::default();
                 clock_gettime(CLOCK_MONOTONIC, &mut time);
@@ -0,0 +1,124 @@
+#include <aio.h>
+#include <errno.h>
+#include <fcntl.h>
+#include <signal.h>
+#include <stdatomic.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+static atomic_int notified;
+
+static void notify(union sigval value) {
+    atomic_store(&notified, value.sival_int);
+}
+
+static void wait_for(struct aiocb *cb) {
+    const struct aiocb *list[] = { cb };
+    while (aio_error(cb) == EINPROGRESS) {
+        int status = aio_suspend(list, 1, NULL);
+        ERROR_IF(aio_suspend, status, == -1);
+    }
+}
+
+int main(void) {
+    char path[] = "/tmp/aio-XXXXXX";
+    int fd = mkstemp(path);
+    ERROR_IF(mkstemp, fd, == -1);
+    int status = unlink(path);
+    ERROR_IF(unlink, status, == -1);
+
+    // Write at an offset, and get notified in a thread when done
+    char message[] = "hello, aio";
+    struct aiocb write_cb = { 0 };
+    write_cb.aio_fildes = fd;
+    write_cb.aio_offset = 4;
+    write_cb.aio_buf = message;
+    write_cb.aio_nbytes = strlen(message);
+    write_cb.aio_sigevent.sigev_notify = SIGEV_THREAD;
+    write_cb.aio_sigevent.sigev_notify_function = notify;
+    write_cb.aio_sigevent.sigev_value.sival_int = 42;
+    status = aio_write(&write_cb);
+    ERROR_IF(aio_write, status, == -1);
+    wait_for(&write_cb);
+    printf("write: error %d, returned %zd\n", aio_error(&write_cb), aio_return(&write_cb));
+    while (atomic_load(&notified) == 0) {
+        usleep(1000);
+    }
+    printf("notified: %d\n", atomic_load(&notified));
+
+    struct aiocb sync_cb = { 0 };
+    sync_cb.aio_fildes = fd;
+    status = aio_fsync(-1, &sync_cb);
+    printf("bad fsync operation: %d, EINVAL: %d\n", status, errno == EINVAL);
+    status = aio_fsync(O_SYNC, &sync_cb);
+    ERROR_IF(aio_fsync, status, == -1);
+    wait_for(&sync_cb);
+    printf("fsync: error %d, returned %zd\n", aio_error(&sync_cb), aio_return(&sync_cb));
+
+    // Read it back with a timeout
+    char buf[32] = { 0 };
+    struct aiocb read_cb = { 0 };
+    read_cb.aio_fildes = fd;
+    read_cb.aio_offset = 4;
+    read_cb.aio_buf = buf;
+    read_cb.aio_nbytes = sizeof(buf) - 1;
+    status = aio_read(&read_cb);
+    ERROR_IF(aio_read, status, == -1);
+    const struct aiocb *list[] = { NULL, &read_cb };
+    struct timespec timeout = { .tv_sec = 5 };
+    while (aio_error(&read_cb) == EINPROGRESS) {
+        status = aio_suspend(list, 2, &timeout);
+        ERROR_IF(aio_suspend, status, == -1);
+    }
+    printf("read: error %d, returned %zd, '%s'\n", aio_error(&read_cb), aio_return(&read_cb), buf);
+
+    // Several requests at once, waiting for all of them
+    char first[4] = { 0 }, second[4] = { 0 };
+    struct aiocb cbs[3] = { 0 };
+    cbs[0].aio_fildes = fd;
+    cbs[0].aio_offset = 4;
+    cbs[0].aio_buf = first;
+    cbs[0].aio_nbytes = 3;
+    cbs[0].aio_lio_opcode = LIO_READ;
+    cbs[1].aio_lio_opcode = LIO_NOP;
+    cbs[2].aio_fildes = fd;
+    cbs[2].aio_offset = 11;
+    cbs[2].aio_buf = second;
+    cbs[2].aio_nbytes = 3;
+    cbs[2].aio_lio_opcode = LIO_READ;
+    struct aiocb *lio[] = { &cbs[0], &cbs[1], &cbs[2] };
+    status = lio_listio(LIO_WAIT, lio, 3, NULL);
+    ERROR_IF(lio_listio, status, == -1);
+    printf("lio_listio: '%s' %zd, '%s' %zd\n", first, aio_return(&cbs[0]), second,
+           aio_return(&cbs[2]));
+
+    // Reading past the end
+    read_cb.aio_offset = 100;
+    status = aio_read(&read_cb);
+    ERROR_IF(aio_read, status, == -1);
+    wait_for(&read_cb);
+    printf("read past the end: error %d, returned %zd\n", aio_error(&read_cb),
+           aio_return(&read_cb));
+
+    // Nothing is left to cancel
+    status = aio_cancel(fd, NULL);
+    ERROR_IF(aio_cancel, status, == -1);
+    printf("cancel: %d\n", status == AIO_ALLDONE);
+
+    // Requests on a descriptor that is not open may fail either when queued or later
+    read_cb.aio_fildes = 1000;
+    status = aio_read(&read_cb);
+    int error = errno;
+    if (status == 0) {
+        wait_for(&read_cb);
+        error = aio_error(&read_cb);
+    }
+    printf("bad descriptor: EBADF %d\n", error == EBADF);
+
+    status = close(fd);
+    ERROR_IF(close, status, == -1);
+}
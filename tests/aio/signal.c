@@ -0,0 +1,63 @@
+// SIGEV_SIGNAL sends the signal with SI_ASYNCIO and the sigev_value of the request
+#include <aio.h>
+#include <errno.h>
+#include <signal.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+static volatile sig_atomic_t signo, code, value, pid;
+
+static void handler(int sig, siginfo_t *info, void *context) {
+    (void) context;
+    code = info->si_code;
+    value = info->si_value.sival_int;
+    pid = info->si_pid;
+    signo = sig;
+}
+
+int main(void) {
+    struct sigaction sa = { 0 };
+    sa.sa_sigaction = handler;
+    sa.sa_flags = SA_SIGINFO;
+    sigemptyset(&sa.sa_mask);
+    int status = sigaction(SIGUSR1, &sa, NULL);
+    ERROR_IF(sigaction, status, == -1);
+
+    char path[] = "/tmp/aio_signal-XXXXXX";
+    int fd = mkstemp(path);
+    ERROR_IF(mkstemp, fd, == -1);
+    status = unlink(path);
+    ERROR_IF(unlink, status, == -1);
+
+    char message[] = "signalled";
+    struct aiocb cb = { 0 };
+    cb.aio_fildes = fd;
+    cb.aio_buf = message;
+    cb.aio_nbytes = strlen(message);
+    cb.aio_sigevent.sigev_notify = SIGEV_SIGNAL;
+    cb.aio_sigevent.sigev_signo = SIGUSR1;
+    cb.aio_sigevent.sigev_value.sival_int = 1234;
+    status = aio_write(&cb);
+    ERROR_IF(aio_write, status, == -1);
+
+    const struct aiocb *list[] = { &cb };
+    while (aio_error(&cb) == EINPROGRESS) {
+        // The signal may interrupt it
+        if (aio_suspend(list, 1, NULL) == -1) {
+            ERROR_IF(aio_suspend, errno, != EINTR);
+        }
+    }
+    printf("write: error %d, returned %zd\n", aio_error(&cb), aio_return(&cb));
+    while (signo == 0) {
+        usleep(1000);
+    }
+    printf("signal: SIGUSR1 %d, SI_ASYNCIO %d, value %d, from this process %d\n", signo == SIGUSR1,
+           code == SI_ASYNCIO, (int) value, pid == getpid());
+
+    status = close(fd);
+    ERROR_IF(close, status, == -1);
+}
@@ -1,5 +1,7 @@
 #include <arpa/inet.h>
 #include <assert.h>
+#include <errno.h>
+#include <netinet/in.h>
 #include <string.h>
 #include <stdlib.h>
 
@@ -22,4 +24,48 @@ int main(void) {
     struct in_addr* addr = malloc(sizeof addr);
     inet_aton(addr_str, addr);
     assert(strcmp(inet_ntoa(*addr), addr_str) == 0);
+
+    struct in_addr addr4;
+    assert(inet_pton(AF_INET, "01.2.3.4", &addr4) == 0);
+    assert(inet_pton(AF_INET, "256.2.3.4", &addr4) == 0);
+    assert(inet_pton(AF_INET, "1.2.3", &addr4) == 0);
+
+    // Round trips through the canonical RFC 5952 form
+    const char *ipv6[][2] = {
+        { "::", "::" },
+        { "::1", "::1" },
+        { "2001:0DB8:0000:0000:0000:ff00:0042:8329", "2001:db8::ff00:42:8329" },
+        { "1:0:0:2:0:0:0:3", "1:0:0:2::3" },
+        { "1:2:3:4:5:6:7::", "1:2:3:4:5:6:7:0" },
+        { "::ffff:192.0.2.128", "::ffff:192.0.2.128" },
+        { "fe80::1.2.3.4", "fe80::102:304" },
+    };
+    for (size_t i = 0; i < sizeof(ipv6) / sizeof(ipv6[0]); i++) {
+        struct in6_addr addr6;
+        char buf[INET6_ADDRSTRLEN];
+        assert(inet_pton(AF_INET6, ipv6[i][0], &addr6) == 1);
+        assert(inet_ntop(AF_INET6, &addr6, buf, sizeof(buf)) == buf);
+        assert(strcmp(buf, ipv6[i][1]) == 0);
+    }
+
+    const char *invalid[] = { "1::2::3", ":1::", "1:2:3:4:5:6:7", "12345::", "1:2:3:4:5:6:7:8:9" };
+    for (size_t i = 0; i < sizeof(invalid) / sizeof(invalid[0]); i++) {
+        struct in6_addr addr6;
+        assert(inet_pton(AF_INET6, invalid[i], &addr6) == 0);
+    }
+
+    struct in6_addr loopback;
+    assert(inet_pton(AF_INET6, "::1", &loopback) == 1);
+    assert(IN6_IS_ADDR_LOOPBACK(&loopback));
+    assert(IN6_ARE_ADDR_EQUAL(&loopback, &in6addr_loopback));
+    assert(IN6_IS_ADDR_UNSPECIFIED(&in6addr_any));
+
+    // The terminating NUL has to fit as well
+    char small[3];
+    errno = 0;
+    assert(inet_ntop(AF_INET6, &loopback, small, sizeof(small)) == NULL);
+    assert(errno == ENOSPC);
+
+    assert(inet_pton(12345, "::1", &loopback) == -1);
+    assert(errno == EAFNOSUPPORT);
 }
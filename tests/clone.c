@@ -0,0 +1,65 @@
+#define _GNU_SOURCE
+#include <errno.h>
+#include <linux/sched.h>
+#include <sched.h>
+#include <signal.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <sys/wait.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+#define STACK_SIZE (64 * 1024)
+
+static char stack[STACK_SIZE] __attribute__((aligned(16)));
+static volatile int value = 1;
+
+static int set_value(void *arg) {
+    value = *(int *)arg;
+    return 3;
+}
+
+static int wait_for(int pid) {
+    int status;
+    int result = waitpid(pid, &status, 0);
+    ERROR_IF(waitpid, result, == -1);
+    if (!WIFEXITED(status)) {
+        puts("child didn't exit");
+        exit(EXIT_FAILURE);
+    }
+    return WEXITSTATUS(status);
+}
+
+int main(void) {
+    int arg = 42;
+
+    // Sharing the memory, the parent sees what the child wrote
+    int pid = clone(set_value, stack + STACK_SIZE, CLONE_VM | SIGCHLD, &arg);
+    ERROR_IF(clone, pid, == -1);
+    int status = wait_for(pid);
+    printf("shared: exit status %d, value %d\n", status, value);
+
+    // With a copy, it doesn't
+    arg = 7;
+    pid = clone(set_value, stack + STACK_SIZE, SIGCHLD, &arg);
+    ERROR_IF(clone, pid, == -1);
+    status = wait_for(pid);
+    printf("copied: exit status %d, value %d\n", status, value);
+
+    int pidfd = -1;
+    pid = clone(set_value, stack + STACK_SIZE, CLONE_PIDFD | SIGCHLD, &arg, &pidfd);
+    ERROR_IF(clone, pid, == -1);
+    printf("pidfd: %s\n", pidfd >= 0 ? "set" : "not set");
+    status = wait_for(pid);
+    printf("pidfd: exit status %d\n", status);
+    close(pidfd);
+
+    int result = unshare(0);
+    ERROR_IF(unshare, result, == -1);
+    puts("unshare: ok");
+
+    errno = 0;
+    result = setns(-1, 0);
+    printf("setns: %d, %s\n", result, errno == EBADF ? "EBADF" : "other error");
+}
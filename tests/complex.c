@@ -0,0 +1,53 @@
+#include <complex.h>
+#include <stdio.h>
+
+#include "test_helpers.h"
+
+#define SHOW(expr, z) printf("%-8s = %9.6f %+9.6fi\n", #expr, creal(z), cimag(z))
+#define SHOWL(expr, z) printf("%-8s = %9.6Lf %+9.6Lfi\n", #expr, creall(z), cimagl(z))
+
+int main(void) {
+    double complex z = 1.5 - 2.0 * I;
+    printf("cabs(z) = %f, carg(z) = %f\n", cabs(z), carg(z));
+    SHOW(conj, conj(z));
+    SHOW(cproj, cproj(z));
+    SHOW(cexp, cexp(z));
+    SHOW(clog, clog(z));
+    SHOW(csqrt, csqrt(z));
+    SHOW(cpow, cpow(z, 0.5 + I));
+    SHOW(csin, csin(z));
+    SHOW(ccos, ccos(z));
+    SHOW(ctan, ctan(z));
+    SHOW(csinh, csinh(z));
+    SHOW(ccosh, ccosh(z));
+    SHOW(ctanh, ctanh(z));
+    SHOW(casin, casin(z));
+    SHOW(cacos, cacos(z));
+    SHOW(catan, catan(z));
+    SHOW(casinh, casinh(z));
+    SHOW(cacosh, cacosh(z));
+    SHOW(catanh, catanh(z));
+
+    float complex f = 0.25f + 3.0f * I;
+    printf("cabsf(f) = %f, cargf(f) = %f\n", cabsf(f), cargf(f));
+    SHOW(cexpf, cexpf(f));
+    SHOW(clogf, clogf(f));
+    SHOW(csqrtf, csqrtf(f));
+    SHOW(cpowf, cpowf(f, 0.5f + 0.5f * I));
+    SHOW(csinf, csinf(f));
+    SHOW(ctanhf, ctanhf(f));
+
+    long double complex l = -0.75L + 0.5L * I;
+    printf("cabsl(l) = %Lf, cargl(l) = %Lf\n", cabsl(l), cargl(l));
+    SHOWL(cexpl, cexpl(l));
+    SHOWL(clogl, clogl(l));
+    SHOWL(csqrtl, csqrtl(l));
+    SHOWL(cpowl, cpowl(l, 1.5L));
+    SHOWL(csinl, csinl(l));
+    SHOWL(ccosl, ccosl(l));
+    SHOWL(ctanl, ctanl(l));
+    SHOWL(csinhl, csinhl(l));
+    SHOWL(ccoshl, ccoshl(l));
+    SHOWL(ctanhl, ctanhl(l));
+    SHOWL(ctanhl, ctanhl(100.0L + l));
+}
@@ -0,0 +1,64 @@
+#include <crypt.h>
+#include <errno.h>
+#include <stdio.h>
+#include <string.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+int main(void) {
+    const char *settings[] = {
+        "ab",
+        "$1$saltsalt",
+        "$5$saltstring",
+        "$5$rounds=10000$saltstringsaltstring",
+        "$6$saltstring",
+        "$6$rounds=1400$anotherlongsaltstring",
+        "$2b$04$abcdefghijklmnopqrstuu",
+        "$2a$05$CCCCCCCCCCCCCCCCCCCCC.",
+    };
+    for (size_t i = 0; i < sizeof(settings) / sizeof(*settings); i++) {
+        char *hash = crypt("correct horse", settings[i]);
+        ERROR_IF(crypt, hash, == NULL);
+        printf("%s\n", hash);
+    }
+
+    // checking a key hashes it again with the hash as the setting
+    struct crypt_data data = {0};
+    char *hash = crypt_r("password", "$6$rounds=5000$usesomesillystri", &data);
+    ERROR_IF(crypt_r, hash, == NULL);
+    printf("%s\n", hash);
+    char stored[CRYPT_OUTPUT_SIZE];
+    strcpy(stored, hash);
+    printf("right key: %d\n", strcmp(crypt_r("password", stored, &data), stored) == 0);
+    printf("wrong key: %d\n", strcmp(crypt_r("Password", stored, &data), stored) == 0);
+
+    // only the first 8 characters of the key matter to DES
+    strcpy(stored, crypt("12345678X", "ab"));
+    printf("DES: %d\n", strcmp(crypt("12345678Y", "ab"), stored) == 0);
+
+    const char *rbytes = "0123456789abcdef";
+    const char *prefixes[] = {"$1$", "$5$", "$6$", "$2b$"};
+    for (size_t i = 0; i < sizeof(prefixes) / sizeof(*prefixes); i++) {
+        char *setting = crypt_gensalt(prefixes[i], 0, rbytes, 16);
+        ERROR_IF(crypt_gensalt, setting, == NULL);
+        printf("%s\n", setting);
+    }
+    printf("%s\n", crypt_gensalt("$6$", 20000, rbytes, 16));
+    printf("%s\n", crypt_gensalt("$2b$", 12, rbytes, 16));
+
+    // random salts differ
+    char first[CRYPT_GENSALT_OUTPUT_SIZE];
+    strcpy(first, crypt_gensalt(NULL, 0, NULL, 0));
+    printf("random: %d\n", strcmp(first, crypt_gensalt(NULL, 0, NULL, 0)) != 0);
+
+    const char *invalid[] = {"a", "!!", "$2b$03$abcdefghijklmnopqrstuu", "$2b$04$abc"};
+    for (size_t i = 0; i < sizeof(invalid) / sizeof(*invalid); i++) {
+        errno = 0;
+        hash = crypt("key", invalid[i]);
+        printf("%s: %s %d\n", invalid[i], hash ? hash : "NULL", errno == EINVAL);
+    }
+    errno = 0;
+    char *setting = crypt_gensalt("$2b$", 3, rbytes, 16);
+    printf("cost 3: %d %d\n", setting == NULL, errno == EINVAL);
+}
@@ -0,0 +1,82 @@
+#include <errno.h>
+#include <signal.h>
+#include <stdio.h>
+#include <string.h>
+#include <sys/time.h>
+#include <time.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+static volatile sig_atomic_t caught = 0;
+
+static void handler(int sig) {
+    (void)sig;
+    caught++;
+}
+
+static void catch_alarm(int flags) {
+    struct sigaction sa;
+    memset(&sa, 0, sizeof(sa));
+    sa.sa_handler = handler;
+    sa.sa_flags = flags;
+    sigemptyset(&sa.sa_mask);
+    int status = sigaction(SIGALRM, &sa, NULL);
+    ERROR_IF(sigaction, status, == -1);
+}
+
+// SIGALRM in 50 milliseconds
+static void arm(void) {
+    struct itimerval timer = {
+        .it_interval = { 0, 0 },
+        .it_value = { 0, 50000 },
+    };
+    int status = setitimer(ITIMER_REAL, &timer, NULL);
+    ERROR_IF(setitimer, status, == -1);
+}
+
+int main(void) {
+    int fds[2];
+    int status = pipe(fds);
+    ERROR_IF(pipe, status, == -1);
+
+    // Without SA_RESTART, the read fails
+    catch_alarm(0);
+    arm();
+    char c;
+    errno = 0;
+    ssize_t res = read(fds[0], &c, 1);
+    printf("read without SA_RESTART: %zd, %s\n", res, errno == EINTR ? "EINTR" : "other error");
+
+    // With it, the read is resumed, and returns what a child writes after the signal
+    catch_alarm(SA_RESTART);
+    int pid = fork();
+    ERROR_IF(fork, pid, == -1);
+    if (pid == 0) {
+        struct timespec wait = { 0, 200000000 };
+        nanosleep(&wait, NULL);
+        status = write(fds[1], "x", 1);
+        ERROR_IF(write, status, == -1);
+        _exit(0);
+    }
+    arm();
+    res = read(fds[0], &c, 1);
+    ERROR_IF(read, res, == -1);
+    printf("read with SA_RESTART: %zd, '%c'\n", res, c);
+
+    // Sleeping isn't resumed whatever the handler asked for
+    arm();
+    struct timespec request = { 5, 0 };
+    struct timespec remaining = { 0, 0 };
+    errno = 0;
+    status = nanosleep(&request, &remaining);
+    printf("nanosleep with SA_RESTART: %d, %s\n", status, errno == EINTR ? "EINTR" : "other error");
+    printf("nanosleep left time: %s\n", remaining.tv_sec >= 4 ? "yes" : "no");
+
+    // sleep returns what's left
+    arm();
+    unsigned left = sleep(5);
+    printf("sleep left time: %s\n", left >= 4 && left <= 5 ? "yes" : "no");
+
+    printf("caught: %d\n", caught);
+}
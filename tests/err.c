@@ -0,0 +1,45 @@
+#include <err.h>
+#include <errno.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <sys/wait.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+static void run(int which) {
+    fflush(stdout);
+    fflush(stderr);
+
+    pid_t pid = fork();
+    ERROR_IF(fork, pid, == -1);
+    if (pid == 0) {
+        errno = ENOENT;
+        switch (which) {
+            case 0: err(3, "err %d", 1);
+            case 1: errx(4, "errx %s", "two");
+            default: err(5, NULL);
+        }
+    }
+
+    int status;
+    pid_t waited = waitpid(pid, &status, 0);
+    ERROR_IF(waitpid, waited, == -1);
+    printf("exit status: %d\n", WIFEXITED(status) ? WEXITSTATUS(status) : -1);
+}
+
+int main(void) {
+    errno = EACCES;
+    warn("warn %d", 1);
+    warnx("warnx %s", "two");
+    warn(NULL);
+    warnx(NULL);
+
+    // The message doesn't change errno before it's described
+    errno = EBADF;
+    warn("%s", "");
+
+    for (int which = 0; which < 3; which++) {
+        run(which);
+    }
+}
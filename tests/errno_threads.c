@@ -0,0 +1,38 @@
+#include <errno.h>
+#include <netdb.h>
+#include <pthread.h>
+#include <stdio.h>
+
+#include "test_helpers.h"
+
+static void *in_thread(void *arg) {
+    int *seen = arg;
+
+    // A thread starts with its own, cleared
+    seen[0] = errno;
+    seen[1] = h_errno;
+
+    errno = ERANGE;
+    h_errno = TRY_AGAIN;
+    seen[2] = errno == ERANGE && h_errno == TRY_AGAIN;
+    return &errno;
+}
+
+int main(void) {
+    errno = EINVAL;
+    h_errno = HOST_NOT_FOUND;
+
+    int seen[3];
+    pthread_t thread;
+    int status = pthread_create(&thread, NULL, in_thread, seen);
+    ERROR_IF(pthread_create, status, != 0);
+    void *location;
+    status = pthread_join(thread, &location);
+    ERROR_IF(pthread_join, status, != 0);
+
+    printf("thread started with errno %d, h_errno %d\n", seen[0], seen[1]);
+    printf("thread kept its own: %s\n", seen[2] ? "yes" : "no");
+    printf("main kept errno: %s\n", errno == EINVAL ? "yes" : "no");
+    printf("main kept h_errno: %s\n", h_errno == HOST_NOT_FOUND ? "yes" : "no");
+    printf("separate locations: %s\n", location != &errno ? "yes" : "no");
+}
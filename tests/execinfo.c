@@ -0,0 +1,71 @@
+#include <execinfo.h>
+#include <stdint.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+// the functions are in the order they're defined, so each return address can be checked to be
+// in the one it should be
+#define IN(addr, func, next) \
+    ((uintptr_t) (addr) > (uintptr_t) (func) && (uintptr_t) (addr) < (uintptr_t) (next))
+
+static void *frames[16];
+static int count;
+
+__attribute__((noinline)) static void level3(void) {
+    count = backtrace(frames, 16);
+}
+
+__attribute__((noinline)) static void level2(void) {
+    level3();
+}
+
+__attribute__((noinline)) static void level1(void) {
+    level2();
+}
+
+__attribute__((noinline)) static void after(void) {}
+
+int main(void) {
+    level1();
+    printf("enough frames: %d\n", count >= 3);
+    printf("level3: %d\n", IN(frames[0], level3, level2));
+    printf("level2: %d\n", IN(frames[1], level2, level1));
+    printf("level1: %d\n", IN(frames[2], level1, after));
+
+    // it stops when the buffer is full
+    int full = backtrace(frames, 2);
+    printf("full: %d\n", full);
+
+    count = backtrace(frames, 16);
+    char **symbols = backtrace_symbols(frames, count);
+    ERROR_IF(backtrace_symbols, symbols, == NULL);
+    int formatted = 1;
+    for (int i = 0; i < count; i++) {
+        char *addr = strstr(symbols[i], "[0x");
+        if (addr == NULL || addr[strlen(addr) - 1] != ']') {
+            formatted = 0;
+        }
+    }
+    printf("formatted: %d\n", formatted);
+    free(symbols);
+
+    int fds[2];
+    int status = pipe(fds);
+    ERROR_IF(pipe, status, == -1);
+    backtrace_symbols_fd(frames, count, fds[1]);
+    close(fds[1]);
+    char buf[4096];
+    ssize_t len;
+    int lines = 0;
+    while ((len = read(fds[0], buf, sizeof(buf))) > 0) {
+        for (ssize_t i = 0; i < len; i++) {
+            lines += buf[i] == '\n';
+        }
+    }
+    close(fds[0]);
+    printf("lines: %d\n", lines == count);
+}
@@ -21,6 +21,13 @@ int main(void) {
 
     printf("duped fd is %d greater than the original fd\n", newfd2 - newfd);
 
+    // F_GETFD reports the flag F_SETFD set
+    int set = fcntl(newfd2, F_SETFD, FD_CLOEXEC);
+    ERROR_IF(fcntl, set, == -1);
+    int flags = fcntl(newfd2, F_GETFD);
+    ERROR_IF(fcntl, flags, == -1);
+    printf("close-on-exec: %d\n", (flags & FD_CLOEXEC) != 0);
+
     int c1 = close(newfd);
     ERROR_IF(close, c1, == -1);
     UNEXP_IF(close, c1, != 0);
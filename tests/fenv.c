@@ -0,0 +1,57 @@
+#include <fenv.h>
+#include <float.h>
+#include <stdio.h>
+
+#include "test_helpers.h"
+
+int main(void) {
+    // printf and FLT_ROUNDS follow the rounding mode
+    int modes[] = {FE_TONEAREST, FE_UPWARD, FE_DOWNWARD, FE_TOWARDZERO};
+    const char *names[] = {"to nearest", "upward", "downward", "toward zero"};
+    for (int i = 0; i < 4; i++) {
+        int status = fesetround(modes[i]);
+        ERROR_IF(fesetround, status, != 0);
+        volatile double third = 1.0 / 3.0;
+        printf("%-11s: FLT_ROUNDS=%d %.3f %.3f %.2e %.0f %.0f %.17g\n", names[i], FLT_ROUNDS,
+               2.0 / 3.0, -2.0 / 3.0, 12345.0, 0.5, -1.5, third);
+    }
+    fesetround(FE_TONEAREST);
+
+    // The flags of the exceptions
+    volatile double zero = 0.0, one = 1.0;
+    feclearexcept(FE_ALL_EXCEPT);
+    volatile double inf = one / zero;
+    printf("1/0: divbyzero=%d inexact=%d\n", fetestexcept(FE_DIVBYZERO) != 0,
+           fetestexcept(FE_INEXACT) != 0);
+    volatile double third = one / 3;
+    printf("1/3: inexact=%d\n", fetestexcept(FE_INEXACT) != 0);
+    feclearexcept(FE_DIVBYZERO);
+    printf("cleared: divbyzero=%d inexact=%d\n", fetestexcept(FE_DIVBYZERO) != 0,
+           fetestexcept(FE_INEXACT) != 0);
+    feraiseexcept(FE_OVERFLOW);
+    printf("raised: overflow=%d\n", fetestexcept(FE_OVERFLOW) != 0);
+
+    // feholdexcept clears the flags, and feupdateenv merges them back
+    fenv_t env;
+    feholdexcept(&env);
+    printf("held: any=%d\n", fetestexcept(FE_ALL_EXCEPT) != 0);
+    inf = zero / zero;
+    printf("0/0: invalid=%d\n", fetestexcept(FE_INVALID) != 0);
+    feupdateenv(&env);
+    printf("updated: invalid=%d overflow=%d\n", fetestexcept(FE_INVALID) != 0,
+           fetestexcept(FE_OVERFLOW) != 0);
+
+    fexcept_t flags;
+    fegetexceptflag(&flags, FE_OVERFLOW);
+    feclearexcept(FE_ALL_EXCEPT);
+    fesetexceptflag(&flags, FE_OVERFLOW);
+    printf("restored: overflow=%d invalid=%d\n", fetestexcept(FE_OVERFLOW) != 0,
+           fetestexcept(FE_INVALID) != 0);
+
+    fegetenv(&env);
+    fesetround(FE_UPWARD);
+    fesetenv(&env);
+    printf("fesetenv: to nearest=%d\n", fegetround() == FE_TONEAREST);
+    (void) inf;
+    (void) third;
+}
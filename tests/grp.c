@@ -0,0 +1,88 @@
+#include <errno.h>
+#include <grp.h>
+#include <stdio.h>
+#include <stdlib.h>
+
+#include "test_helpers.h"
+
+void print(struct group *grp) {
+    printf("gr_name: %s\n", grp->gr_name);
+    printf("gr_passwd: %s\n", grp->gr_passwd);
+    printf("gr_gid: %u\n", grp->gr_gid);
+    for (char **member = grp->gr_mem; *member != NULL; member++) {
+        printf("gr_mem: %s\n", *member);
+    }
+}
+
+int main(void) {
+    puts("--- Checking getgrgid ---");
+    errno = 0;
+    struct group *grp = getgrgid(0);
+    if (errno != 0) {
+        perror("getgrgid");
+        exit(EXIT_FAILURE);
+    }
+    if (grp != NULL) {
+        print(grp);
+    }
+
+    puts("--- Checking getgrnam ---");
+    errno = 0;
+    grp = getgrnam("root");
+    if (errno != 0) {
+        perror("getgrnam");
+        exit(EXIT_FAILURE);
+    }
+    if (grp != NULL) {
+        print(grp);
+    }
+
+    puts("--- Checking getgrnam with a missing group ---");
+    errno = 0;
+    grp = getgrnam("no such group");
+    if (grp != NULL || errno != 0) {
+        puts("found a group that doesn't exist");
+        exit(EXIT_FAILURE);
+    }
+
+    puts("--- Checking getgrgid_r ---");
+    struct group grp2;
+    struct group *result;
+    char buf[1024];
+    int err = getgrgid_r(0, &grp2, buf, sizeof(buf), &result);
+    if (err != 0) {
+        errno = err;
+        perror("getgrgid_r");
+        exit(EXIT_FAILURE);
+    }
+    if (result != NULL) {
+        if (result != &grp2) {
+            exit(EXIT_FAILURE);
+        }
+        print(&grp2);
+    }
+
+    puts("--- Checking getgrnam_r error handling ---");
+    char buf2[1];
+    err = getgrnam_r("root", &grp2, buf2, sizeof(buf2), &result);
+    if (err != ERANGE || result != NULL) {
+        puts("This shouldn't have succeeded, but it did!");
+        exit(EXIT_FAILURE);
+    }
+    puts("Returned ERANGE because the buffer was too small");
+
+    struct group *entry;
+    for (int i = 1; (entry = getgrent()) != NULL; ++i) {
+        printf("--- getgrent #%d ---\n", i);
+        print(entry);
+    }
+    puts("--- getgrent #1 (rewind) ---");
+    setgrent();
+    entry = getgrent();
+    if (entry == NULL) {
+        puts("getgrent found nothing after setgrent");
+        exit(EXIT_FAILURE);
+    }
+    print(entry);
+    endgrent();
+}
@@ -0,0 +1,47 @@
+#define _GNU_SOURCE
+#include <errno.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <sys/mman.h>
+
+#include "test_helpers.h"
+
+#define HUGE_PAGE_SIZE (2 * 1024 * 1024)
+
+int main(void) {
+    // Whether there are huge pages to map depends on the system, failing is fine too
+    errno = 0;
+    char *huge = mmap(NULL, HUGE_PAGE_SIZE, PROT_READ | PROT_WRITE,
+                      MAP_PRIVATE | MAP_ANONYMOUS | MAP_HUGETLB | MAP_HUGE_2MB, -1, 0);
+    if (huge != MAP_FAILED) {
+        huge[HUGE_PAGE_SIZE - 1] = 'h';
+        printf("hugetlb: %d\n", huge[HUGE_PAGE_SIZE - 1] == 'h');
+        int status = munmap(huge, HUGE_PAGE_SIZE);
+        ERROR_IF(munmap, status, == -1);
+    } else {
+        printf("hugetlb: %d\n", errno == ENOMEM || errno == EINVAL);
+    }
+
+    // The same goes for transparent huge pages, but contents are kept either way
+    char *map = mmap(NULL, HUGE_PAGE_SIZE * 2, PROT_READ | PROT_WRITE,
+                     MAP_PRIVATE | MAP_ANONYMOUS, -1, 0);
+    ERROR_IF(mmap, map, == MAP_FAILED);
+    memset(map, 'a', HUGE_PAGE_SIZE * 2);
+    errno = 0;
+    int status = madvise(map, HUGE_PAGE_SIZE * 2, MADV_HUGEPAGE);
+    printf("hugepage: %d\n", status == 0 || errno == EINVAL);
+    errno = 0;
+    status = madvise(map, HUGE_PAGE_SIZE * 2, MADV_NOHUGEPAGE);
+    printf("nohugepage: %d\n", status == 0 || errno == EINVAL);
+    printf("kept: %d\n", map[0] == 'a' && map[HUGE_PAGE_SIZE * 2 - 1] == 'a');
+    status = munmap(map, HUGE_PAGE_SIZE * 2);
+    ERROR_IF(munmap, status, == -1);
+
+    // Big allocations may be backed by huge pages
+    char *big = malloc(HUGE_PAGE_SIZE * 4);
+    ERROR_IF(malloc, big, == NULL);
+    memset(big, 'b', HUGE_PAGE_SIZE * 4);
+    printf("big allocation: %d\n", big[0] == 'b' && big[HUGE_PAGE_SIZE * 4 - 1] == 'b');
+    free(big);
+}
@@ -0,0 +1,71 @@
+#include <errno.h>
+#include <iconv.h>
+#include <stdio.h>
+#include <string.h>
+
+#include "test_helpers.h"
+
+// Converts `len` bytes of `in`, with room for `room` bytes of output, and shows what came out
+static void convert(const char *to, const char *from, const char *in, size_t len, size_t room) {
+    iconv_t cd = iconv_open(to, from);
+    ERROR_IF(iconv_open, cd, == (iconv_t) -1);
+
+    char out[64];
+    char *inbuf = (char *) in;
+    char *outbuf = out;
+    size_t inleft = len;
+    size_t outleft = room;
+    errno = 0;
+    size_t status = iconv(cd, &inbuf, &inleft, &outbuf, &outleft);
+
+    printf("%s to %s:", from, to);
+    for (char *c = out; c < outbuf; c++) {
+        printf(" %02x", (unsigned char) *c);
+    }
+    if (status == (size_t) -1) {
+        const char *error = errno == EILSEQ ? "EILSEQ"
+                            : errno == EINVAL ? "EINVAL"
+                            : errno == E2BIG  ? "E2BIG"
+                                              : "?";
+        printf(" (%s, %zu bytes left)\n", error, inleft);
+    } else {
+        printf(" (%zu irreversible)\n", status);
+    }
+
+    int closed = iconv_close(cd);
+    ERROR_IF(iconv_close, closed, != 0);
+}
+
+int main(void) {
+    convert("ISO-8859-1", "UTF-8", "caf\xc3\xa9", 5, 64);
+    convert("utf8", "CP1252", "\x80 \x93hi\x94", 6, 64);
+    convert("UTF-8", "KOI8-R", "\xf0\xd2\xc9\xd7\xc5\xd4", 6, 64);
+    convert("KOI8-R", "UTF-8", "\xd0\x9f\xd1\x80\xd0\xb8", 6, 64);
+    convert("ISO-8859-15", "UTF-8", "\xe2\x82\xac", 3, 64);
+
+    // The byte order mark of UTF-16 goes out in the native order, and in either, defaulting to
+    // the native one
+    convert("UTF-16", "UTF-8", "A\xf0\x9f\x98\x80", 5, 64);
+    convert("UTF-8", "UTF-16", "\xfe\xff\x00\x41\xd8\x3d\xde\x00", 8, 64);
+    convert("UTF-8", "UTF-16", "\xff\xfe\x41\x00", 4, 64);
+    convert("UTF-8", "UTF-16LE", "\x41\x00", 2, 64);
+    convert("UTF-32BE", "UTF-8", "\xc3\xa9", 2, 64);
+    convert("UCS-2BE", "UTF-8", "\xf0\x9f\x98\x80", 4, 64);
+
+    // Errors leave the input at the character that caused them
+    convert("UTF-8", "UTF-8", "ab\xff", 3, 64);
+    convert("UTF-8", "UTF-8", "ab\xe2\x82", 4, 64);
+    convert("ASCII", "UTF-8", "ab\xc3\xa9", 4, 64);
+    convert("UTF-8", "ISO-8859-1", "abcd", 4, 2);
+    convert("UTF-8", "ASCII", "a\x80", 2, 64);
+
+    // Characters that can't be written are approximated, or left out
+    convert("ASCII//TRANSLIT", "UTF-8", "\xe2\x82\xac \xe2\x80\x9cx\xe2\x80\x9d \xc3\xa9", 14, 64);
+    convert("ISO-8859-1//TRANSLIT", "UTF-8", "\xc3\xa9\xe2\x98\x83", 5, 64);
+    convert("ASCII//IGNORE", "UTF-8", "a\xc3\xa9" "b", 4, 64);
+    convert("UTF-8//IGNORE", "UTF-8", "a\xff" "b", 3, 64);
+
+    errno = 0;
+    iconv_t cd = iconv_open("UTF-8", "NO-SUCH-CHARSET");
+    printf("unknown: %d %d\n", cd == (iconv_t) -1, errno == EINVAL);
+}
@@ -0,0 +1,34 @@
+#include <ifaddrs.h>
+#include <net/if.h>
+#include <netinet/in.h>
+#include <arpa/inet.h>
+#include <stdio.h>
+
+#include "test_helpers.h"
+
+int main(void) {
+    struct ifaddrs *ifaddr;
+    int status = getifaddrs(&ifaddr);
+    ERROR_IF(getifaddrs, status, == -1);
+
+    for (struct ifaddrs *ifa = ifaddr; ifa != NULL; ifa = ifa->ifa_next) {
+        UNEXP_IF(getifaddrs, ifa->ifa_name, == NULL);
+        printf("%s: flags %#x", ifa->ifa_name, ifa->ifa_flags);
+
+        if (ifa->ifa_addr != NULL && ifa->ifa_addr->sa_family == AF_INET) {
+            struct sockaddr_in *addr = (struct sockaddr_in *) ifa->ifa_addr;
+            struct sockaddr_in *mask = (struct sockaddr_in *) ifa->ifa_netmask;
+            printf(" inet %s", inet_ntoa(addr->sin_addr));
+            printf(" netmask %s", inet_ntoa(mask->sin_addr));
+            if ((ifa->ifa_flags & IFF_BROADCAST) && ifa->ifa_broadaddr != NULL) {
+                struct sockaddr_in *brd = (struct sockaddr_in *) ifa->ifa_broadaddr;
+                printf(" broadcast %s", inet_ntoa(brd->sin_addr));
+            }
+        } else if (ifa->ifa_addr != NULL && ifa->ifa_addr->sa_family == AF_INET6) {
+            printf(" inet6");
+        }
+        printf("\n");
+    }
+
+    freeifaddrs(ifaddr);
+}
@@ -0,0 +1,50 @@
+#define _POSIX_C_SOURCE 200809L
+#include <langinfo.h>
+#include <locale.h>
+#include <stdio.h>
+#include <stdlib.h>
+
+#include "test_helpers.h"
+
+static void show(const char *name, const char *value) {
+    printf("%s: \"%s\"\n", name, value);
+}
+
+static void show_all(void) {
+    show("CODESET", nl_langinfo(CODESET));
+    show("D_T_FMT", nl_langinfo(D_T_FMT));
+    show("D_FMT", nl_langinfo(D_FMT));
+    show("T_FMT", nl_langinfo(T_FMT));
+    show("T_FMT_AMPM", nl_langinfo(T_FMT_AMPM));
+    show("AM_STR", nl_langinfo(AM_STR));
+    show("PM_STR", nl_langinfo(PM_STR));
+    show("DAY_1", nl_langinfo(DAY_1));
+    show("ABDAY_7", nl_langinfo(ABDAY_7));
+    show("MON_2", nl_langinfo(MON_2));
+    show("ABMON_12", nl_langinfo(ABMON_12));
+    show("RADIXCHAR", nl_langinfo(RADIXCHAR));
+    show("THOUSEP", nl_langinfo(THOUSEP));
+    show("YESEXPR", nl_langinfo(YESEXPR));
+    show("NOEXPR", nl_langinfo(NOEXPR));
+    show("CRNCYSTR", nl_langinfo(CRNCYSTR));
+}
+
+int main(void) {
+    int status = setenv("LOCPATH", "locales", 1);
+    ERROR_IF(setenv, status, == -1);
+
+    show_all();
+
+    char *locale = setlocale(LC_ALL, "fr_FR.UTF-8");
+    ERROR_IF(setlocale, locale, == NULL);
+    puts("");
+    show_all();
+
+    // In another locale than the current one
+    locale_t c = newlocale(LC_ALL_MASK, "C", NULL);
+    ERROR_IF(newlocale, c, == NULL);
+    puts("");
+    show("DAY_1", nl_langinfo_l(DAY_1, c));
+    show("RADIXCHAR", nl_langinfo_l(RADIXCHAR, c));
+    freelocale(c);
+}
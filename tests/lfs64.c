@@ -0,0 +1,80 @@
+#define _LARGEFILE64_SOURCE
+#include <dirent.h>
+#include <fcntl.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <sys/mman.h>
+#include <sys/resource.h>
+#include <sys/stat.h>
+#include <sys/statvfs.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+int main(void) {
+    char path[] = "/tmp/lfs64-XXXXXX";
+    int fd = mkstemp64(path);
+    ERROR_IF(mkstemp64, fd, == -1);
+    close(fd);
+
+    fd = open64(path, O_RDWR | O_TRUNC);
+    ERROR_IF(open64, fd, == -1);
+    ssize_t written = pwrite64(fd, "large file", 10, 0);
+    ERROR_IF(pwrite64, written, == -1);
+
+    off64_t end = lseek64(fd, 0, SEEK_END);
+    ERROR_IF(lseek64, end, == -1);
+    printf("lseek64: %lld\n", (long long)end);
+
+    int status = ftruncate64(fd, 5);
+    ERROR_IF(ftruncate64, status, == -1);
+
+    struct stat64 st;
+    status = fstat64(fd, &st);
+    ERROR_IF(fstat64, status, == -1);
+    printf("fstat64: %lld bytes\n", (long long)st.st_size);
+    status = stat64(path, &st);
+    ERROR_IF(stat64, status, == -1);
+    printf("stat64: %lld bytes\n", (long long)st.st_size);
+    status = lstat64(path, &st);
+    ERROR_IF(lstat64, status, == -1);
+    printf("lstat64: %s\n", S_ISREG(st.st_mode) ? "regular file" : "other");
+
+    char *map = mmap64(NULL, 5, PROT_READ, MAP_SHARED, fd, 0);
+    ERROR_IF(mmap64, map, == MAP_FAILED);
+    printf("mmap64: %.5s\n", map);
+    munmap(map, 5);
+    close(fd);
+
+    FILE *f = fopen64(path, "r");
+    ERROR_IF(fopen64, f, == NULL);
+    status = fseeko64(f, 2, SEEK_SET);
+    ERROR_IF(fseeko64, status, == -1);
+    printf("ftello64: %lld\n", (long long)ftello64(f));
+    fclose(f);
+
+    struct statvfs64 vfs;
+    status = statvfs64(path, &vfs);
+    ERROR_IF(statvfs64, status, == -1);
+    puts("statvfs64: ok");
+
+    struct rlimit64 limit;
+    status = getrlimit64(RLIMIT_NOFILE, &limit);
+    ERROR_IF(getrlimit64, status, == -1);
+    puts("getrlimit64: ok");
+
+    DIR *dir = opendir("/tmp");
+    ERROR_IF(opendir, dir, == NULL);
+    struct dirent64 *entry;
+    int found = 0;
+    while ((entry = readdir64(dir)) != NULL) {
+        if (strcmp(entry->d_name, path + strlen("/tmp/")) == 0) {
+            found = 1;
+        }
+    }
+    closedir(dir);
+    printf("readdir64: %s\n", found ? "found" : "not found");
+
+    unlink(path);
+}
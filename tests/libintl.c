@@ -0,0 +1,178 @@
+#define _POSIX_C_SOURCE 200809L
+#include <errno.h>
+#include <libintl.h>
+#include <locale.h>
+#include <stdint.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <sys/stat.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+struct entry {
+    const char *original;
+    size_t original_len;
+    const char *translation;
+    size_t translation_len;
+};
+
+#define ENTRY(original, translation) \
+    { original, sizeof(original) - 1, translation, sizeof(translation) - 1 }
+
+static void put(FILE *file, uint32_t value) {
+    fwrite(&value, sizeof(value), 1, file);
+}
+
+// Writes a catalog of `count` entries sorted by their original
+static void write_catalog(const char *path, const struct entry *entries, size_t count) {
+    FILE *file = fopen(path, "w");
+    ERROR_IF(fopen, file, == NULL);
+    uint32_t offset = 28 + 16 * count;
+    put(file, 0x950412de);
+    put(file, 0);
+    put(file, count);
+    put(file, 28);
+    put(file, 28 + 8 * count);
+    put(file, 0);
+    put(file, 0);
+    for (size_t i = 0; i < count; i++) {
+        put(file, entries[i].original_len);
+        put(file, offset);
+        offset += entries[i].original_len + 1;
+    }
+    for (size_t i = 0; i < count; i++) {
+        put(file, entries[i].translation_len);
+        put(file, offset);
+        offset += entries[i].translation_len + 1;
+    }
+    for (size_t i = 0; i < count; i++) {
+        fwrite(entries[i].original, 1, entries[i].original_len + 1, file);
+    }
+    for (size_t i = 0; i < count; i++) {
+        fwrite(entries[i].translation, 1, entries[i].translation_len + 1, file);
+    }
+    fclose(file);
+}
+
+static const struct entry french[] = {
+    ENTRY("", "Content-Type: text/plain; charset=UTF-8\n"
+              "Plural-Forms: nplurals=2; plural=(n > 1);\n"),
+    ENTRY("%d file\0%d files", "%d fichier\0%d fichiers"),
+    ENTRY("Hello", "Bonjour"),
+};
+
+static const struct entry french_other[] = {
+    ENTRY("Goodbye", "Au revoir"),
+};
+
+static const struct entry polish[] = {
+    ENTRY("", "Content-Type: text/plain; charset=UTF-8\n"
+              "Plural-Forms: nplurals=3; "
+              "plural=n==1 ? 0 : n%10>=2 && n%10<=4 && (n%100<10 || n%100>=20) ? 1 : 2;\n"),
+    ENTRY("%d file\0%d files", "%d plik\0%d pliki\0%d plik\xc3\xb3w"),
+};
+
+static char dir[] = "/tmp/gettext-XXXXXX";
+
+static void make_directory(const char *language) {
+    char path[64];
+    snprintf(path, sizeof(path), "%s/%s", dir, language);
+    int status = mkdir(path, 0700);
+    ERROR_IF(mkdir, status, == -1);
+    snprintf(path, sizeof(path), "%s/%s/LC_MESSAGES", dir, language);
+    status = mkdir(path, 0700);
+    ERROR_IF(mkdir, status, == -1);
+}
+
+static void remove_directory(const char *language) {
+    char path[64];
+    snprintf(path, sizeof(path), "%s/%s/LC_MESSAGES", dir, language);
+    int status = rmdir(path);
+    ERROR_IF(rmdir, status, == -1);
+    snprintf(path, sizeof(path), "%s/%s", dir, language);
+    status = rmdir(path);
+    ERROR_IF(rmdir, status, == -1);
+}
+
+static void show_files(void) {
+    unsigned long counts[] = {0, 1, 2, 5, 22};
+    for (size_t i = 0; i < sizeof(counts) / sizeof(counts[0]); i++) {
+        printf(ngettext("%d file", "%d files", counts[i]), (int) counts[i]);
+        printf(i + 1 < sizeof(counts) / sizeof(counts[0]) ? ", " : "\n");
+    }
+}
+
+int main(void) {
+    int status = setenv("LOCPATH", "locales", 1);
+    ERROR_IF(setenv, status, == -1);
+
+    char *made = mkdtemp(dir);
+    ERROR_IF(mkdtemp, made, == NULL);
+    char path[64];
+    make_directory("fr");
+    make_directory("pl");
+    snprintf(path, sizeof(path), "%s/fr/LC_MESSAGES/test.mo", dir);
+    write_catalog(path, french, sizeof(french) / sizeof(french[0]));
+    snprintf(path, sizeof(path), "%s/fr/LC_MESSAGES/other.mo", dir);
+    write_catalog(path, french_other, sizeof(french_other) / sizeof(french_other[0]));
+    snprintf(path, sizeof(path), "%s/pl/LC_MESSAGES/test.mo", dir);
+    write_catalog(path, polish, sizeof(polish) / sizeof(polish[0]));
+
+    printf("textdomain: %s\n", textdomain(NULL));
+    printf("bindtextdomain: %d\n", strcmp(bindtextdomain("test", dir), dir));
+    printf("textdomain: %s\n", textdomain("test"));
+
+    // Nothing is translated in the C locale
+    printf("C: %s\n", gettext("Hello"));
+    show_files();
+
+    // The catalog of the language is used for a locale with a territory
+    char *locale = setlocale(LC_ALL, "fr_FR.UTF-8");
+    ERROR_IF(setlocale, locale, == NULL);
+    printf("fr: %s %s\n", gettext("Hello"), gettext("Unknown"));
+    show_files();
+    printf("other: %s %s\n", dgettext("other", "Goodbye"), dgettext("other", "Hello"));
+    printf("LC_TIME: %s\n", dcgettext("test", "Hello", LC_TIME));
+
+    // Domains are looked for in the directory they are bound to
+    bindtextdomain("other", dir);
+    printf("other: %s\n", dgettext("other", "Goodbye"));
+
+    // LANGUAGE lists the languages to look in, in order
+    status = setenv("LANGUAGE", "pl:fr", 1);
+    ERROR_IF(setenv, status, == -1);
+    printf("pl: %s\n", gettext("Hello"));
+    show_files();
+
+    // It doesn't matter in the C locale
+    locale = setlocale(LC_MESSAGES, "C");
+    ERROR_IF(setlocale, locale, == NULL);
+    printf("C: %s\n", gettext("Hello"));
+
+    errno = EDOM;
+    gettext("Hello");
+    printf("errno: %d\n", errno == EDOM);
+
+    printf("bindtextdomain: %d\n", strcmp(bindtextdomain("test", NULL), dir));
+    printf("bindtextdomain: %s\n", bindtextdomain("unbound", NULL));
+    printf("bind_textdomain_codeset: %s\n",
+           bind_textdomain_codeset("test", NULL) == NULL ? "(null)" : "set");
+    printf("bind_textdomain_codeset: %s\n", bind_textdomain_codeset("test", "UTF-8"));
+    printf("textdomain: %s\n", textdomain(""));
+
+    snprintf(path, sizeof(path), "%s/fr/LC_MESSAGES/test.mo", dir);
+    status = unlink(path);
+    ERROR_IF(unlink, status, == -1);
+    snprintf(path, sizeof(path), "%s/fr/LC_MESSAGES/other.mo", dir);
+    status = unlink(path);
+    ERROR_IF(unlink, status, == -1);
+    snprintf(path, sizeof(path), "%s/pl/LC_MESSAGES/test.mo", dir);
+    status = unlink(path);
+    ERROR_IF(unlink, status, == -1);
+    remove_directory("fr");
+    remove_directory("pl");
+    status = rmdir(dir);
+    ERROR_IF(rmdir, status, == -1);
+}
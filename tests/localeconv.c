@@ -0,0 +1,82 @@
+// The numeric and monetary conventions of the C locale and of those in tests/locales
+#define _POSIX_C_SOURCE 200809L
+#include <limits.h>
+#include <locale.h>
+#include <stdio.h>
+#include <stdlib.h>
+
+#include "test_helpers.h"
+
+static void show_number(const char *name, char value) {
+    if (value == CHAR_MAX) {
+        printf(" %s=CHAR_MAX", name);
+    } else {
+        printf(" %s=%d", name, value);
+    }
+}
+
+static void show_grouping(const char *name, const char *grouping) {
+    printf(" %s=[", name);
+    for (const char *group = grouping; *group; group++) {
+        printf(group == grouping ? "%d" : " %d", *group);
+    }
+    printf("]");
+}
+
+static void show(void) {
+    struct lconv *conv = localeconv();
+    printf("decimal_point=\"%s\" thousands_sep=\"%s\"", conv->decimal_point, conv->thousands_sep);
+    show_grouping("grouping", conv->grouping);
+    printf("\n");
+    printf("int_curr_symbol=\"%s\" currency_symbol=\"%s\" mon_decimal_point=\"%s\" "
+           "mon_thousands_sep=\"%s\"",
+           conv->int_curr_symbol, conv->currency_symbol, conv->mon_decimal_point,
+           conv->mon_thousands_sep);
+    show_grouping("mon_grouping", conv->mon_grouping);
+    printf(" positive_sign=\"%s\" negative_sign=\"%s\"\n", conv->positive_sign,
+           conv->negative_sign);
+    show_number("int_frac_digits", conv->int_frac_digits);
+    show_number("frac_digits", conv->frac_digits);
+    show_number("p_cs_precedes", conv->p_cs_precedes);
+    show_number("p_sep_by_space", conv->p_sep_by_space);
+    show_number("n_cs_precedes", conv->n_cs_precedes);
+    show_number("n_sep_by_space", conv->n_sep_by_space);
+    show_number("p_sign_posn", conv->p_sign_posn);
+    show_number("n_sign_posn", conv->n_sign_posn);
+    printf("\n");
+    show_number("int_p_cs_precedes", conv->int_p_cs_precedes);
+    show_number("int_p_sep_by_space", conv->int_p_sep_by_space);
+    show_number("int_n_cs_precedes", conv->int_n_cs_precedes);
+    show_number("int_n_sep_by_space", conv->int_n_sep_by_space);
+    show_number("int_p_sign_posn", conv->int_p_sign_posn);
+    show_number("int_n_sign_posn", conv->int_n_sign_posn);
+    printf("\n");
+}
+
+int main(void) {
+    int status = setenv("LOCPATH", "locales", 1);
+    ERROR_IF(setenv, status, == -1);
+
+    show();
+
+    char *locale = setlocale(LC_ALL, "en_US.UTF-8");
+    ERROR_IF(setlocale, locale, == NULL);
+    show();
+
+    // The ' flag follows the grouping, for integers and the integer part of decimals
+    // Not literals, as ISO C doesn't have the flag
+    const char *numbers = "%'d %'d %'d %'u %'.2f %'g %'lld\n";
+    const char *padded = "%'10d|%-'10d|\n";
+    printf(numbers, 12, 1234, -1234567, 1000000u, 1234567.891, 12345.5, 1234567890123ll);
+    printf(padded, 12345, 12345);
+
+    // Only the conventions of the categories that are set change
+    locale = setlocale(LC_NUMERIC, "C");
+    ERROR_IF(setlocale, locale, == NULL);
+    show();
+    printf(numbers, 12, 1234, -1234567, 1000000u, 1234567.891, 12345.5, 1234567890123ll);
+
+    locale = setlocale(LC_ALL, "fr_FR.UTF-8");
+    ERROR_IF(setlocale, locale, == NULL);
+    show();
+}
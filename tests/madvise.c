@@ -0,0 +1,42 @@
+#define _DEFAULT_SOURCE
+#include <errno.h>
+#include <stdio.h>
+#include <string.h>
+#include <sys/mman.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+int main(void) {
+    size_t page = (size_t) getpagesize();
+    char *map = mmap(NULL, page * 2, PROT_READ | PROT_WRITE, MAP_PRIVATE | MAP_ANONYMOUS, -1, 0);
+    ERROR_IF(mmap, map, == MAP_FAILED);
+    memset(map, 'a', page * 2);
+
+    int status = madvise(map, page * 2, MADV_WILLNEED);
+    ERROR_IF(madvise, status, == -1);
+    printf("willneed kept: %d\n", map[0] == 'a' && map[page * 2 - 1] == 'a');
+
+    // Only the second page is discarded, and reads back as zeroes
+    status = madvise(map + page, page, MADV_DONTNEED);
+    ERROR_IF(madvise, status, == -1);
+    printf("dontneed: %d %d\n", map[page - 1], map[page]);
+    map[page] = 'b';
+    printf("reusable: %c\n", map[page]);
+
+    // Freed pages keep their contents until the memory is needed, then are zeroed
+    status = madvise(map, page * 2, MADV_FREE);
+    ERROR_IF(madvise, status, == -1);
+    map[0] = 'c';
+    printf("after free: %c\n", map[0]);
+
+    errno = 0;
+    status = madvise(map + 1, page, MADV_DONTNEED);
+    printf("unaligned: %d, EINVAL: %d\n", status, errno == EINVAL);
+    errno = 0;
+    status = madvise(map, page, -1);
+    printf("bad advice: %d, EINVAL: %d\n", status, errno == EINVAL);
+
+    status = munmap(map, page * 2);
+    ERROR_IF(munmap, status, == -1);
+}
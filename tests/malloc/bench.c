@@ -0,0 +1,85 @@
+// Times allocations made by producer threads and freed by consumer threads
+#define _POSIX_C_SOURCE 200809L
+#include <pthread.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <time.h>
+
+#include "test_helpers.h"
+
+#define PAIRS 4
+#define BATCHES 2000
+#define BATCH 256
+
+struct queue {
+    pthread_mutex_t lock;
+    pthread_cond_t changed;
+    void **batch;
+};
+
+static void *produce(void *arg) {
+    struct queue *queue = arg;
+    for (size_t i = 0; i < BATCHES; i++) {
+        void **batch = malloc(BATCH * sizeof(void *));
+        ERROR_IF(malloc, batch, == NULL);
+        for (size_t j = 0; j < BATCH; j++) {
+            batch[j] = malloc(16 + (i + j) % 256);
+            ERROR_IF(malloc, batch[j], == NULL);
+        }
+
+        pthread_mutex_lock(&queue->lock);
+        while (queue->batch != NULL) {
+            pthread_cond_wait(&queue->changed, &queue->lock);
+        }
+        queue->batch = batch;
+        pthread_cond_signal(&queue->changed);
+        pthread_mutex_unlock(&queue->lock);
+    }
+    return NULL;
+}
+
+static void *consume(void *arg) {
+    struct queue *queue = arg;
+    for (size_t i = 0; i < BATCHES; i++) {
+        pthread_mutex_lock(&queue->lock);
+        while (queue->batch == NULL) {
+            pthread_cond_wait(&queue->changed, &queue->lock);
+        }
+        void **batch = queue->batch;
+        queue->batch = NULL;
+        pthread_cond_signal(&queue->changed);
+        pthread_mutex_unlock(&queue->lock);
+
+        for (size_t j = 0; j < BATCH; j++) {
+            free(batch[j]);
+        }
+        free(batch);
+    }
+    return NULL;
+}
+
+int main(void) {
+    static struct queue queues[PAIRS];
+    pthread_t threads[PAIRS * 2];
+
+    struct timespec start, end;
+    clock_gettime(CLOCK_MONOTONIC, &start);
+    for (int i = 0; i < PAIRS; i++) {
+        pthread_mutex_init(&queues[i].lock, NULL);
+        pthread_cond_init(&queues[i].changed, NULL);
+        int status = pthread_create(&threads[i * 2], NULL, produce, &queues[i]);
+        ERROR_IF(pthread_create, status, != 0);
+        status = pthread_create(&threads[i * 2 + 1], NULL, consume, &queues[i]);
+        ERROR_IF(pthread_create, status, != 0);
+    }
+    for (int i = 0; i < PAIRS * 2; i++) {
+        int status = pthread_join(threads[i], NULL);
+        ERROR_IF(pthread_join, status, != 0);
+    }
+    clock_gettime(CLOCK_MONOTONIC, &end);
+
+    double seconds = (end.tv_sec - start.tv_sec) + (end.tv_nsec - start.tv_nsec) / 1e9;
+    double count = (double) PAIRS * BATCHES * BATCH;
+    printf("%d threads: %.0f allocations and frees in %.3f s, %.0f per second\n", PAIRS * 2,
+           count, seconds, count / seconds);
+}
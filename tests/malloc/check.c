@@ -0,0 +1,104 @@
+#define _DEFAULT_SOURCE
+#include <signal.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <sys/wait.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+// Runs `bug` in a child, and tells whether it was stopped and what it reported
+static void expect_report(const char *name, void (*bug)(void), const char *report) {
+    int pipefd[2];
+    int status = pipe(pipefd);
+    ERROR_IF(pipe, status, == -1);
+
+    pid_t pid = fork();
+    ERROR_IF(fork, pid, == -1);
+    if (pid == 0) {
+        dup2(pipefd[1], STDERR_FILENO);
+        close(pipefd[0]);
+        bug();
+        _exit(0);
+    }
+    close(pipefd[1]);
+
+    char buf[256] = { 0 };
+    size_t len = 0;
+    ssize_t count;
+    while ((count = read(pipefd[0], buf + len, sizeof(buf) - 1 - len)) > 0) {
+        len += count;
+    }
+    close(pipefd[0]);
+    int wstatus;
+    status = waitpid(pid, &wstatus, 0);
+    ERROR_IF(waitpid, status, == -1);
+
+    printf("%s: stopped %d, reported %d\n", name, WIFSIGNALED(wstatus),
+           strstr(buf, report) != NULL);
+}
+
+static void double_free(void) {
+    // Kept from the compiler, which would warn about the bug
+    char *volatile ptr = malloc(32);
+    free(ptr);
+    free(ptr);
+}
+
+static void overrun(void) {
+    char *ptr = malloc(32);
+    ptr[32] = 'x';
+    free(ptr);
+}
+
+static void underrun(void) {
+    char *ptr = malloc(32);
+    ptr[-1] = 'x';
+    free(ptr);
+}
+
+static void use_after_free(void) {
+    // Kept from the compiler, which would warn about the bug
+    char *volatile ptr = malloc(32);
+    free(ptr);
+    ptr[0] = 'x';
+    // Freed memory is held back for a while, then checked
+    for (int i = 0; i < 1000; i++) {
+        free(malloc(32));
+    }
+}
+
+static void realloc_freed(void) {
+    // Kept from the compiler, which would warn about the bug
+    char *volatile ptr = malloc(32);
+    free(ptr);
+    ptr = realloc(ptr, 64);
+}
+
+int main(int argc, char **argv) {
+    if (getenv("MALLOC_CHECK_") == NULL) {
+        // The checks are chosen at startup
+        int status = setenv("MALLOC_CHECK_", "3", 1);
+        ERROR_IF(setenv, status, == -1);
+        execv(argv[0], argv);
+        perror("execv");
+        exit(EXIT_FAILURE);
+    }
+
+    // Correct use goes unnoticed
+    char *ptr = malloc(100);
+    ERROR_IF(malloc, ptr, == NULL);
+    memset(ptr, 'a', 100);
+    ptr = realloc(ptr, 200);
+    ERROR_IF(realloc, ptr, == NULL);
+    printf("realloc kept: %d\n", ptr[99] == 'a');
+    free(ptr);
+    free(NULL);
+
+    expect_report("double free", double_free, "double free");
+    expect_report("overrun", overrun, "write past the end");
+    expect_report("underrun", underrun, "write before the start");
+    expect_report("use after free", use_after_free, "write after free");
+    expect_report("realloc of freed memory", realloc_freed, "double free");
+}
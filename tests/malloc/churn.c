@@ -0,0 +1,35 @@
+// Times small allocations replacing each other at random in a working set
+#define _POSIX_C_SOURCE 200809L
+#include <stdio.h>
+#include <stdlib.h>
+#include <time.h>
+
+#include "test_helpers.h"
+
+#define SLOTS 4096
+#define ROUNDS 4000000
+
+int main(void) {
+    static void *slots[SLOTS];
+    unsigned int seed = 1;
+
+    struct timespec start, end;
+    clock_gettime(CLOCK_MONOTONIC, &start);
+    for (size_t i = 0; i < ROUNDS; i++) {
+        // A linear congruential generator, the same everywhere
+        seed = seed * 1103515245 + 12345;
+        size_t slot = (seed >> 8) % SLOTS;
+        free(slots[slot]);
+        slots[slot] = malloc(8 + (seed >> 20) % 248);
+        ERROR_IF(malloc, slots[slot], == NULL);
+        *(char *) slots[slot] = 1;
+    }
+    clock_gettime(CLOCK_MONOTONIC, &end);
+    for (size_t i = 0; i < SLOTS; i++) {
+        free(slots[i]);
+    }
+
+    double seconds = (end.tv_sec - start.tv_sec) + (end.tv_nsec - start.tv_nsec) / 1e9;
+    printf("%d allocations and frees of up to 256 bytes in %.3f s, %.0f per second\n", ROUNDS,
+           seconds, ROUNDS / seconds);
+}
@@ -0,0 +1,52 @@
+#include <malloc.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+#define SIZE (4 * 1024 * 1024)
+
+// The number of resident pages
+static long resident(void) {
+    FILE *statm = fopen("/proc/self/statm", "r");
+    ERROR_IF(fopen, statm, == NULL);
+    long size, pages;
+    int status = fscanf(statm, "%ld %ld", &size, &pages);
+    ERROR_IF(fscanf, status, != 2);
+    fclose(statm);
+    return pages;
+}
+
+int main(void) {
+    long half = SIZE / sysconf(_SC_PAGESIZE) / 2;
+
+    // Serve the allocation below from the heap, with an allocation after it so that freeing it
+    // leaves a free run in the middle of the heap rather than at its top
+    mallopt(M_MMAP_THRESHOLD, SIZE * 2);
+    mallopt(M_TRIM_THRESHOLD, -1);
+    char *ptr = malloc(SIZE);
+    ERROR_IF(malloc, ptr, == NULL);
+    char *after = malloc(1000);
+    ERROR_IF(malloc, after, == NULL);
+    memset(ptr, 'a', SIZE);
+
+    long before = resident();
+    free(ptr);
+    printf("given back: %d\n", before - resident() >= half);
+
+    // The memory can be used again
+    ptr = malloc(SIZE);
+    ERROR_IF(malloc, ptr, == NULL);
+    memset(ptr, 'b', SIZE);
+    printf("reused: %d\n", ptr[0] == 'b' && ptr[SIZE - 1] == 'b');
+
+    // Unless the threshold is turned off
+    printf("off: %d\n", mallopt(M_DECOMMIT_THRESHOLD, -1));
+    before = resident();
+    free(ptr);
+    printf("kept: %d\n", before - resident() < half);
+
+    free(after);
+}
@@ -0,0 +1,73 @@
+#define _GNU_SOURCE
+#include <errno.h>
+#include <malloc.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+// Reads back what was written to a temporary file
+static void read_back(FILE *file, char *buf, size_t size) {
+    rewind(file);
+    size_t len = fread(buf, 1, size - 1, file);
+    buf[len] = 0;
+}
+
+int main(void) {
+    struct mallinfo2 before = mallinfo2();
+    char *small = malloc(1000);
+    ERROR_IF(malloc, small, == NULL);
+    char *big = malloc(1 << 20);
+    ERROR_IF(malloc, big, == NULL);
+    memset(small, 'a', 1000);
+    memset(big, 'b', 1 << 20);
+
+    struct mallinfo2 during = mallinfo2();
+    // Whether mapped chunks count as in use differs, but they are in one or the other
+    printf("in use grew: %d\n", during.uordblks + during.hblkhd >=
+                                        before.uordblks + before.hblkhd + 1000 + (1 << 20));
+    printf("in use fits: %d\n", during.uordblks <= during.arena + during.hblkhd);
+    printf("peak: %d\n", during.usmblks == 0 || during.usmblks >= during.uordblks);
+
+    // Freeing frees up room
+    free(small);
+    free(big);
+    struct mallinfo2 after = mallinfo2();
+    printf("in use shrank: %d\n",
+           after.uordblks + after.hblkhd < during.uordblks + during.hblkhd);
+
+    struct mallinfo old = mallinfo();
+    printf("mallinfo: %d\n", (size_t) old.uordblks == after.uordblks);
+
+    static char buf[65536];
+    FILE *file = tmpfile();
+    ERROR_IF(tmpfile, file, == NULL);
+    int status = malloc_info(0, file);
+    ERROR_IF(malloc_info, status, == -1);
+    read_back(file, buf, sizeof(buf));
+    printf("malloc_info: %d %d\n", strncmp(buf, "<malloc version=\"1\">\n", 21) == 0,
+           strstr(buf, "</malloc>\n") != NULL);
+    fclose(file);
+
+    printf("bad options: %d\n", malloc_info(1, stdout) == EINVAL);
+
+    // malloc_stats writes to stderr, which is kept aside for checking
+    file = tmpfile();
+    ERROR_IF(tmpfile, file, == NULL);
+    fflush(stderr);
+    int saved = dup(STDERR_FILENO);
+    ERROR_IF(dup, saved, == -1);
+    status = dup2(fileno(file), STDERR_FILENO);
+    ERROR_IF(dup2, status, == -1);
+    malloc_stats();
+    fflush(stderr);
+    status = dup2(saved, STDERR_FILENO);
+    ERROR_IF(dup2, status, == -1);
+    close(saved);
+    read_back(file, buf, sizeof(buf));
+    printf("malloc_stats: %d %d\n", strstr(buf, "system bytes") != NULL,
+           strstr(buf, "in use bytes") != NULL);
+    fclose(file);
+}
@@ -0,0 +1,132 @@
+#define _POSIX_C_SOURCE 200809L
+#include <dirent.h>
+#include <errno.h>
+#include <stdint.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+// A bump allocator replacing the one of the C library, which must then be
+// used by everything the library allocates.
+
+#define ALIGN 16
+
+static _Alignas(ALIGN) unsigned char arena[1 << 22];
+static size_t used;
+static size_t allocs;
+static size_t frees;
+
+static int ours(void *ptr) {
+    return (unsigned char *) ptr >= arena && (unsigned char *) ptr < arena + sizeof(arena);
+}
+
+void *memalign(size_t alignment, size_t size) {
+    if (alignment < ALIGN) {
+        alignment = ALIGN;
+    }
+    // The size is kept right before the memory
+    size_t start = (used + sizeof(size_t) + alignment - 1) / alignment * alignment;
+    if (start + size > sizeof(arena)) {
+        errno = ENOMEM;
+        return NULL;
+    }
+    used = start + size;
+    allocs++;
+    memcpy(arena + start - sizeof(size_t), &size, sizeof(size_t));
+    return arena + start;
+}
+
+void *malloc(size_t size) {
+    return memalign(ALIGN, size);
+}
+
+void *aligned_alloc(size_t alignment, size_t size) {
+    return memalign(alignment, size);
+}
+
+int posix_memalign(void **memptr, size_t alignment, size_t size) {
+    void *ptr = memalign(alignment, size);
+    if (ptr == NULL) {
+        return ENOMEM;
+    }
+    *memptr = ptr;
+    return 0;
+}
+
+void *calloc(size_t nelem, size_t elsize) {
+    void *ptr = malloc(nelem * elsize);
+    if (ptr != NULL) {
+        memset(ptr, 0, nelem * elsize);
+    }
+    return ptr;
+}
+
+void free(void *ptr) {
+    if (ptr == NULL) {
+        return;
+    }
+    if (!ours(ptr)) {
+        // Memory from another allocator would be a bug
+        fputs("free of foreign memory\n", stderr);
+        abort();
+    }
+    frees++;
+}
+
+void *realloc(void *ptr, size_t size) {
+    if (ptr == NULL) {
+        return malloc(size);
+    }
+    if (!ours(ptr)) {
+        fputs("realloc of foreign memory\n", stderr);
+        abort();
+    }
+    size_t old_size;
+    memcpy(&old_size, (unsigned char *) ptr - sizeof(size_t), sizeof(size_t));
+    void *new = malloc(size);
+    if (new != NULL) {
+        memcpy(new, ptr, old_size < size ? old_size : size);
+        free(ptr);
+    }
+    return new;
+}
+
+int main(void) {
+    char *copy = strdup("interposed");
+    printf("strdup: %d\n", ours(copy));
+    free(copy);
+
+    size_t before = allocs;
+    FILE *file = fopen("malloc/interpose.c", "r");
+    if (file == NULL) {
+        perror("fopen");
+        return EXIT_FAILURE;
+    }
+    char line[64];
+    if (fgets(line, sizeof(line), file) == NULL) {
+        perror("fgets");
+        return EXIT_FAILURE;
+    }
+    printf("fopen: %d\n", allocs > before);
+    before = frees;
+    fclose(file);
+    printf("fclose: %d\n", frees > before);
+
+    before = allocs;
+    DIR *dir = opendir("malloc");
+    if (dir == NULL) {
+        perror("opendir");
+        return EXIT_FAILURE;
+    }
+    printf("opendir: %d\n", allocs > before);
+    before = frees;
+    closedir(dir);
+    printf("closedir: %d\n", frees > before);
+
+    before = allocs;
+    if (setenv("RELIBC_INTERPOSE", "1", 1) != 0) {
+        perror("setenv");
+        return EXIT_FAILURE;
+    }
+    printf("setenv: %d\n", allocs > before);
+}
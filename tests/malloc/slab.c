@@ -0,0 +1,80 @@
+// Small allocations of every size, freed and reused in a mixed order
+#define _GNU_SOURCE
+#include <malloc.h>
+#include <stdint.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#include "test_helpers.h"
+
+#define COUNT 4096
+
+static unsigned char *objects[COUNT];
+static size_t sizes[COUNT];
+
+static int check(size_t i) {
+    for (size_t j = 0; j < sizes[i]; j++) {
+        if (objects[i][j] != (unsigned char) i) {
+            return 0;
+        }
+    }
+    return 1;
+}
+
+static int allocate(size_t i, size_t size) {
+    objects[i] = malloc(size);
+    ERROR_IF(malloc, objects[i], == NULL);
+    sizes[i] = size;
+    memset(objects[i], (unsigned char) i, size);
+    return (uintptr_t) objects[i] % 16 == 0 && malloc_usable_size(objects[i]) >= size;
+}
+
+int main(void) {
+    int fits = 1;
+    for (size_t i = 0; i < COUNT; i++) {
+        fits &= allocate(i, i * 37 % 256 + 1);
+    }
+
+    // Every other object gets freed and replaced by one of another size
+    for (size_t i = 0; i < COUNT; i += 2) {
+        free(objects[i]);
+    }
+    for (size_t i = 0; i < COUNT; i += 2) {
+        fits &= allocate(i, i * 53 % 256 + 1);
+    }
+    int intact = 1;
+    for (size_t i = 0; i < COUNT; i++) {
+        intact &= check(i);
+    }
+    printf("aligned and big enough: %d\n", fits);
+    printf("intact: %d\n", intact);
+
+    // Growing out of the small sizes and back keeps the contents
+    unsigned char *grown = realloc(objects[1], 1000);
+    ERROR_IF(realloc, grown, == NULL);
+    objects[1] = grown;
+    int kept = check(1);
+    unsigned char *shrunk = realloc(objects[1], 8);
+    ERROR_IF(realloc, shrunk, == NULL);
+    objects[1] = shrunk;
+    sizes[1] = 8;
+    kept &= check(1);
+    printf("realloc kept: %d\n", kept);
+
+    for (size_t i = 0; i < COUNT; i++) {
+        free(objects[i]);
+    }
+
+    // Small allocations count as in use
+    struct mallinfo2 before = mallinfo2();
+    for (size_t i = 0; i < 1000; i++) {
+        objects[i] = malloc(64);
+        ERROR_IF(malloc, objects[i], == NULL);
+    }
+    struct mallinfo2 during = mallinfo2();
+    printf("counted: %d\n", during.uordblks >= before.uordblks + 64 * 1000);
+    for (size_t i = 0; i < 1000; i++) {
+        free(objects[i]);
+    }
+}
@@ -0,0 +1,97 @@
+#include <pthread.h>
+#include <stdint.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#include "test_helpers.h"
+
+#define PAIRS 4
+#define ITEMS 20000
+#define QUEUE 64
+
+// Blocks allocated by one thread and freed by another
+struct queue {
+    pthread_mutex_t lock;
+    pthread_cond_t changed;
+    unsigned char *items[QUEUE];
+    size_t head;
+    size_t len;
+    int corrupted;
+};
+
+static size_t item_size(size_t i) {
+    return 1 + (i * 37) % 700;
+}
+
+static void *produce(void *arg) {
+    struct queue *queue = arg;
+    for (size_t i = 0; i < ITEMS; i++) {
+        size_t size = item_size(i);
+        unsigned char *item = malloc(size);
+        ERROR_IF(malloc, item, == NULL);
+        memset(item, (unsigned char) i, size);
+
+        // Some allocations never leave this thread
+        void *local = malloc(size);
+        ERROR_IF(malloc, local, == NULL);
+        free(local);
+
+        pthread_mutex_lock(&queue->lock);
+        while (queue->len == QUEUE) {
+            pthread_cond_wait(&queue->changed, &queue->lock);
+        }
+        queue->items[(queue->head + queue->len) % QUEUE] = item;
+        queue->len++;
+        pthread_cond_broadcast(&queue->changed);
+        pthread_mutex_unlock(&queue->lock);
+    }
+    return NULL;
+}
+
+static void *consume(void *arg) {
+    struct queue *queue = arg;
+    for (size_t i = 0; i < ITEMS; i++) {
+        pthread_mutex_lock(&queue->lock);
+        while (queue->len == 0) {
+            pthread_cond_wait(&queue->changed, &queue->lock);
+        }
+        unsigned char *item = queue->items[queue->head];
+        queue->head = (queue->head + 1) % QUEUE;
+        queue->len--;
+        pthread_cond_broadcast(&queue->changed);
+        pthread_mutex_unlock(&queue->lock);
+
+        for (size_t j = 0; j < item_size(i); j++) {
+            if (item[j] != (unsigned char) i) {
+                queue->corrupted = 1;
+            }
+        }
+        free(item);
+    }
+    return NULL;
+}
+
+int main(void) {
+    static struct queue queues[PAIRS];
+    pthread_t threads[PAIRS * 2];
+    for (int i = 0; i < PAIRS; i++) {
+        pthread_mutex_init(&queues[i].lock, NULL);
+        pthread_cond_init(&queues[i].changed, NULL);
+        int status = pthread_create(&threads[i * 2], NULL, produce, &queues[i]);
+        ERROR_IF(pthread_create, status, != 0);
+        status = pthread_create(&threads[i * 2 + 1], NULL, consume, &queues[i]);
+        ERROR_IF(pthread_create, status, != 0);
+    }
+
+    int corrupted = 0;
+    for (int i = 0; i < PAIRS * 2; i++) {
+        int status = pthread_join(threads[i], NULL);
+        ERROR_IF(pthread_join, status, != 0);
+    }
+    for (int i = 0; i < PAIRS; i++) {
+        corrupted |= queues[i].corrupted;
+    }
+    printf("threads: %d\n", PAIRS * 2);
+    printf("intact: %d\n", !corrupted);
+}
@@ -0,0 +1,29 @@
+#include <malloc.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#include "test_helpers.h"
+
+#define SIZE (4 * 1024 * 1024)
+
+int main(void) {
+    // Keep free memory until asked to trim it, and serve the allocation below from the heap
+    printf("trim threshold: %d\n", mallopt(M_TRIM_THRESHOLD, -1));
+    printf("mmap threshold: %d\n", mallopt(M_MMAP_THRESHOLD, SIZE * 2));
+    printf("arena max: %d\n", mallopt(M_ARENA_MAX, 1));
+
+    char *ptr = malloc(SIZE);
+    ERROR_IF(malloc, ptr, == NULL);
+    memset(ptr, 'a', SIZE);
+    free(ptr);
+    printf("trimmed: %d\n", malloc_trim(0));
+    printf("nothing left: %d\n", malloc_trim(0));
+
+    // The heap still works after being trimmed
+    ptr = malloc(SIZE);
+    ERROR_IF(malloc, ptr, == NULL);
+    memset(ptr, 'b', SIZE);
+    printf("reused: %d\n", ptr[SIZE - 1] == 'b');
+    free(ptr);
+}
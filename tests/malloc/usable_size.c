@@ -0,0 +1,33 @@
+#include <malloc.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#include "test_helpers.h"
+
+int main(void) {
+    printf("NULL: %zu\n", malloc_usable_size(NULL));
+
+    size_t sizes[] = { 1, 24, 100, 4096, 1 << 20 };
+    for (size_t i = 0; i < sizeof(sizes) / sizeof(sizes[0]); i++) {
+        char *ptr = malloc(sizes[i]);
+        ERROR_IF(malloc, ptr, == NULL);
+        size_t usable = malloc_usable_size(ptr);
+        // All of the usable size may be written to
+        memset(ptr, 'a', usable);
+        printf("malloc(%zu): %d\n", sizes[i], usable >= sizes[i]);
+        free(ptr);
+    }
+
+    char *aligned = memalign(256, 1000);
+    ERROR_IF(memalign, aligned, == NULL);
+    size_t usable = malloc_usable_size(aligned);
+    memset(aligned, 'b', usable);
+    printf("memalign: %d\n", usable >= 1000);
+
+    // Growing up to the usable size keeps the contents
+    char *grown = realloc(aligned, usable);
+    ERROR_IF(realloc, grown, == NULL);
+    printf("realloc: %d\n", malloc_usable_size(grown) >= usable && grown[usable - 1] == 'b');
+    free(grown);
+}
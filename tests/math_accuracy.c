@@ -0,0 +1,321 @@
+// expf, logf, sinf and cosf are correctly rounded for floats spread over every exponent (every
+// float with -DEXHAUSTIVE, which the exhaustive target of the Makefile builds), and powf for
+// samples and the exact powers. The reference is the long double function wherever that's far
+// enough from half way between two floats to tell, and otherwise a table of the results worked
+// out with more precision. The double functions are within one ulp of the long double ones.
+#include <fenv.h>
+#include <float.h>
+#include <math.h>
+#include <stdint.h>
+#include <stdio.h>
+#include <string.h>
+
+#include "test_helpers.h"
+
+#ifdef EXHAUSTIVE
+#define STRIDE 1
+#define TRIED "every float"
+#else
+// Odd, so that every bit of the mantissa varies
+#define STRIDE 4097
+#define TRIED "the floats tried"
+#endif
+
+// The float `exact` rounds to, unless it's too close to half way between two for the error of
+// the long double functions
+static int rounded(long double exact, float *want) {
+    float f = (float) exact;
+    *want = f;
+    if (isnan(exact) || isinf(f)) {
+        return 1;
+    }
+    float other = nextafterf(f, exact > f ? INFINITY : -INFINITY);
+    long double half = ((long double) f + other) / 2;
+    return fabsl(exact - half) > fabsl(exact) * 0x1p-58L;
+}
+
+// The floats for which the long double function is too close to half way, found by going
+// through every float, with the results rounded from hundreds of bits
+struct hard {
+    float x, want;
+};
+
+static const struct hard hard_exp[] = {
+    {-0x1.d2259ap+3f, 0x1.fa6636p-22f},
+};
+
+static const struct hard hard_log[] = {
+    {0x1.917748p-100f, -0x1.137588p+6f},
+    {0x1.22d57p-65f, -0x1.676a7cp+5f},
+    {0x1.98496ep-39f, -0x1.a90e2p+4f},
+    {0x1.b97c7p-14f, -0x1.251748p+3f},
+    {0x1.f0ddep-8f, -0x1.38733cp+2f},
+    {0x1.827a74p-7f, -0x1.1c2b1ep+2f},
+    {0x1.2f1fd6p+3f, 0x1.1fcbcep+1f},
+    {0x1.cb534cp+13f, 0x1.330e4ap+3f},
+    {0x1.bacb4ap+25f, 0x1.1e0696p+4f},
+    {0x1.c09d7cp+27f, 0x1.346a58p+4f},
+    {0x1.d1309cp+62f, 0x1.5c9442p+5f},
+    {0x1.b121a6p+76f, 0x1.a9a3f2p+5f},
+    {0x1.5190cp+78f, 0x1.b2bc8cp+5f},
+    {0x1.6351d8p+95f, 0x1.08b512p+6f},
+    {0x1.cfd86ep+116f, 0x1.43ff6ep+6f},
+};
+
+static const struct hard hard_sin[] = {
+    {0x1.e35bc6p+7f, 0x1.c5b4acp-3f},
+    {0x1.33333p+13f, -0x1.63f4bap-2f},
+    {0x1.95f654p+44f, -0x1.fcf42ep-1f},
+    {0x1.411b04p+63f, 0x1.58ed1p-1f},
+    {0x1.8d9d8p+73f, -0x1.d32b7ap-1f},
+    {0x1.524856p+80f, -0x1.ff5702p-1f},
+    {0x1.487e0cp+103f, 0x1.287508p-2f},
+    {0x1.a3eda6p+116f, -0x1.f511dcp-1f},
+    {0x1.b559b6p+117f, 0x1.b50776p-2f},
+    {-0x1.e35bc6p+7f, -0x1.c5b4acp-3f},
+    {-0x1.33333p+13f, 0x1.63f4bap-2f},
+    {-0x1.95f654p+44f, 0x1.fcf42ep-1f},
+    {-0x1.411b04p+63f, -0x1.58ed1p-1f},
+    {-0x1.8d9d8p+73f, 0x1.d32b7ap-1f},
+    {-0x1.524856p+80f, 0x1.ff5702p-1f},
+    {-0x1.487e0cp+103f, -0x1.287508p-2f},
+    {-0x1.a3eda6p+116f, 0x1.f511dcp-1f},
+    {-0x1.b559b6p+117f, -0x1.b50776p-2f},
+};
+
+static const struct hard hard_cos[] = {
+    {0x1p-12f, 0x1p+0f},
+    {0x1.20ffccp-7f, 0x1.fffae8p-1f},
+    {0x1.4555p+51f, 0x1.115d7ep-1f},
+    {0x1.887814p+51f, 0x1.84bec4p-1f},
+    {0x1.3170fp+63f, 0x1.fe2976p-1f},
+    {0x1.2b9622p+67f, 0x1.f0285ep-1f},
+    {0x1.e072ecp+67f, 0x1.b598acp-2f},
+    {0x1.119ae6p+115f, 0x1.f3176ap-1f},
+    {0x1.96344ep+117f, 0x1.f8a9b4p-1f},
+    {-0x1p-12f, 0x1p+0f},
+    {-0x1.20ffccp-7f, 0x1.fffae8p-1f},
+    {-0x1.4555p+51f, 0x1.115d7ep-1f},
+    {-0x1.887814p+51f, 0x1.84bec4p-1f},
+    {-0x1.3170fp+63f, 0x1.fe2976p-1f},
+    {-0x1.2b9622p+67f, 0x1.f0285ep-1f},
+    {-0x1.e072ecp+67f, 0x1.b598acp-2f},
+    {-0x1.119ae6p+115f, 0x1.f3176ap-1f},
+    {-0x1.96344ep+117f, 0x1.f8a9b4p-1f},
+};
+
+#define HARD(table) table, sizeof(table) / sizeof(table[0])
+
+static int lookup(const struct hard *table, size_t len, float x, float *want) {
+    for (size_t i = 0; i < len; i++) {
+        if (memcmp(&table[i].x, &x, sizeof x) == 0) {
+            *want = table[i].want;
+            return 1;
+        }
+    }
+    return 0;
+}
+
+static int wrong;
+
+static void compare(const char *name, float x, float y, float got, float want) {
+    if (isnan(want) ? isnan(got) : memcmp(&got, &want, sizeof got) == 0) {
+        return;
+    }
+    if (wrong++ < 5) {
+        printf("%s(%a, %a) = %a, not %a\n", name, x, y, got, want);
+    }
+}
+
+static void report(const char *name, const char *what) {
+    printf("%-4s %s\n", name, wrong ? "FAILED" : what);
+    wrong = 0;
+}
+
+// Where the result can't be told from the long double function, nor is in a table
+static void unresolved(const char *name, float x, float y) {
+    if (wrong++ < 5) {
+        printf("%s(%a, %a) is too close to half way to check\n", name, x, y);
+    }
+}
+
+// The floats tried, but for where the result is known without the long double function, and
+// those of the table
+static void every(const char *name, float (*f)(float), long double (*exact)(long double),
+                  int (*known)(float, float *), const struct hard *table, size_t len) {
+    for (uint64_t bits = 0; bits <= UINT32_MAX; bits += STRIDE) {
+        uint32_t b = bits;
+        float x, want;
+        memcpy(&x, &b, sizeof x);
+        if (known(x, &want) || rounded(exact(x), &want) || lookup(table, len, x, &want)) {
+            compare(name, x, 0, f(x), want);
+        } else {
+            unresolved(name, x, 0);
+        }
+    }
+    for (size_t i = 0; i < len; i++) {
+        compare(name, table[i].x, 0, f(table[i].x), table[i].want);
+    }
+    report(name, "correctly rounded for " TRIED);
+}
+
+// e^x that are 1, or out of range
+static int known_exp(float x, float *want) {
+    if (fabsf(x) < 0x1p-26f) {
+        *want = 1;
+    } else if (x > 89) {
+        *want = INFINITY;
+    } else if (x < -104) {
+        *want = 0;
+    } else {
+        return 0;
+    }
+    return 1;
+}
+
+static int known_log(float x, float *want) {
+    (void) x;
+    (void) want;
+    return 0;
+}
+
+// Below 2^-12, x^2/6 and x^2/2 are less than half an ulp
+static int known_sin(float x, float *want) {
+    *want = x;
+    return fabsf(x) < 0x1p-12f;
+}
+
+static int known_cos(float x, float *want) {
+    *want = 1;
+    return fabsf(x) < 0x1p-12f;
+}
+
+static uint64_t state = 0x853c49e6748fea9b;
+static uint32_t bits32(void) {
+    state = state * 6364136223846793005ULL + 1442695040888963407ULL;
+    return state >> 32;
+}
+
+// A sequence of doubles with random bits, between -scale and scale
+static double next(double scale) {
+    state = state * 6364136223846793005ULL + 1442695040888963407ULL;
+    return ((double) (state >> 11) / 9007199254740992.0 * 2 - 1) * scale;
+}
+
+static void pow_check(float x, float y) {
+    float want;
+    if (!rounded(powl(x, y), &want)) {
+        unresolved("powf", x, y);
+        return;
+    }
+    compare("powf", x, y, powf(x, y), want);
+}
+
+// a^n, if it's exact in long double, which then rounds to float like powf should
+static int power(long double a, int n, float *want) {
+    long double p = 1;
+    feclearexcept(FE_INEXACT);
+    for (int i = 0; i < n; i++) {
+        p *= a;
+    }
+    *want = (float) p;
+    return !fetestexcept(FE_INEXACT);
+}
+
+static void power_check(float x, float y, int a, int n) {
+    float want;
+    if (power(a, n, &want)) {
+        compare("powf", x, y, powf(x, y), want);
+    } else {
+        pow_check(x, y);
+    }
+}
+
+// Measures the error of `got` in ulps of a format with `mantissa` bits
+static double max_error;
+static void check(long double got, long double want, int mantissa, int min_exponent,
+                  long double max) {
+    if (isnan(want) || isnan(got)) {
+        if (!isnan(want) || !isnan(got)) {
+            max_error = INFINITY;
+        }
+        return;
+    }
+    // Past the biggest number of the format it can only overflow
+    if (fabsl(want) > max || isinf(got)) {
+        if (!isinf(got) || fabsl(want) <= max || signbit(got) != signbit(want)) {
+            max_error = INFINITY;
+        }
+        return;
+    }
+    int exponent;
+    frexpl(want, &exponent);
+    if (exponent < min_exponent) {
+        exponent = min_exponent;
+    }
+    double error = (double) (fabsl(got - want) / ldexpl(1, exponent - mantissa));
+    if (error > max_error) {
+        max_error = error;
+    }
+}
+
+static void ulp_report(const char *name) {
+    printf("%-4s %s\n", name, max_error < 1 ? "within 1 ulp" : "FAILED");
+    max_error = 0;
+}
+
+static void sample(const char *name, double (*f)(double), long double (*exact)(long double),
+                   double scale) {
+    for (int i = 0; i < 200000; i++) {
+        double x = next(scale);
+        check(f(x), exact(x), DBL_MANT_DIG, DBL_MIN_EXP, DBL_MAX);
+    }
+    ulp_report(name);
+}
+
+int main(void) {
+    every("expf", expf, expl, known_exp, HARD(hard_exp));
+    every("logf", logf, logl, known_log, HARD(hard_log));
+    every("sinf", sinf, sinl, known_sin, HARD(hard_sin));
+    every("cosf", cosf, cosl, known_cos, HARD(hard_cos));
+
+    for (int i = 0; i < 1000000; i++) {
+        uint32_t bx = bits32(), by = bits32();
+        float x, y;
+        memcpy(&x, &bx, sizeof x);
+        memcpy(&y, &by, sizeof y);
+        pow_check(x, y);
+        pow_check(1 + (int32_t) bits32() * 0x1p-40f, (int32_t) bits32() * 0x1p-16f);
+        pow_check(bits32() * 0x1p-24f, (int32_t) bits32() * 0x1p-26f);
+    }
+    // Exact powers, some of them right between two floats, like 4097^2
+    for (int a = 1; a < 5000; a += 2) {
+        for (int n = 1; n <= 15; n++) {
+            power_check(a, n, a, n);
+            power_check(-a, n, -a, n);
+        }
+    }
+    for (int c = 3; c < 4096; c += 2) {
+        for (int n = 1; n <= 19; n += 2) {
+            power_check((float) c * c, n * 0.5f, c, n);
+        }
+    }
+    report("powf", "correctly rounded for the pairs tried");
+
+    sample("exp", exp, expl, 700);
+    for (int i = 0; i < 200000; i++) {
+        double x = ldexp(fabs(next(1)), (int) next(1000));
+        check(log(x), logl(x), DBL_MANT_DIG, DBL_MIN_EXP, DBL_MAX);
+    }
+    ulp_report("log");
+    // Both near zero and far enough out that the argument reduction matters
+    sample("sin", sin, sinl, 10);
+    sample("sin", sin, sinl, 1e6);
+    sample("cos", cos, cosl, 10);
+    sample("cos", cos, cosl, 1e6);
+    for (int i = 0; i < 200000; i++) {
+        double x = fabs(next(100)), y = next(100);
+        check(pow(x, y), powl(x, y), DBL_MANT_DIG, DBL_MIN_EXP, DBL_MAX);
+    }
+    ulp_report("pow");
+}
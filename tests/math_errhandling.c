@@ -0,0 +1,53 @@
+// Math functions report their errors the way math_errhandling says, with errno and with the
+// floating point exceptions: EDOM and FE_INVALID for domain errors, ERANGE and FE_DIVBYZERO for
+// poles, and ERANGE and FE_OVERFLOW or FE_UNDERFLOW for results out of range
+#include <errno.h>
+#include <fenv.h>
+#include <float.h>
+#include <math.h>
+#include <stdio.h>
+
+#include "test_helpers.h"
+
+static void show(const char *name, double result) {
+    // The sign of a NaN isn't specified
+    if (isnan(result)) {
+        result = fabs(result);
+    }
+    const char *error = errno == EDOM ? "EDOM" : errno == ERANGE ? "ERANGE" : "0";
+    printf("%-13s = %-7g errno=%-6s invalid=%d divbyzero=%d overflow=%d underflow=%d\n", name,
+           result, error, fetestexcept(FE_INVALID) != 0, fetestexcept(FE_DIVBYZERO) != 0,
+           fetestexcept(FE_OVERFLOW) != 0, fetestexcept(FE_UNDERFLOW) != 0);
+    feclearexcept(FE_ALL_EXCEPT);
+    errno = 0;
+}
+
+int main(void) {
+    printf("math_errhandling & MATH_ERRNO: %d\n", (math_errhandling & MATH_ERRNO) != 0);
+    printf("math_errhandling & MATH_ERREXCEPT: %d\n", (math_errhandling & MATH_ERREXCEPT) != 0);
+
+    volatile double minus_one = -1, zero = 0, big = 1000, huge = DBL_MAX;
+    volatile float minus_onef = -1, zerof = 0, bigf = 1000, edgef = 88.75f;
+    feclearexcept(FE_ALL_EXCEPT);
+    errno = 0;
+    show("sqrt(-1)", sqrt(minus_one));
+    show("log(-1)", log(minus_one));
+    show("acos(1000)", acos(big));
+    show("pow(-1, 0.5)", pow(minus_one, 0.5));
+    show("log(0)", log(zero));
+    show("pow(0, -1)", pow(zero, -1));
+    show("atanh(-1)", atanh(minus_one));
+    show("exp(1000)", exp(big));
+    show("cosh(1000)", cosh(big));
+    show("ldexp(max, 1)", ldexp(huge, 1));
+    show("exp(-1000)", exp(-big));
+    show("log(1000)", log(big));
+
+    show("logf(-1)", logf(minus_onef));
+    show("powf(0, -1)", powf(zerof, -1));
+    show("expf(1000)", expf(bigf));
+    show("expf(-1000)", expf(-bigf));
+    // Past FLT_MAX only once rounded to float
+    show("expf(88.75)", expf(edgef));
+    show("sinf(1000)", sinf(bigf));
+}
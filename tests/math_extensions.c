@@ -0,0 +1,33 @@
+#define _GNU_SOURCE
+#include <math.h>
+#include <stdio.h>
+
+#include "test_helpers.h"
+
+int main(void) {
+    double s, c;
+    sincos(0.5, &s, &c);
+    printf("sincos(0.5) = %.15g, %.15g\n", s, c);
+    float sf, cf;
+    sincosf(2.0f, &sf, &cf);
+    printf("sincosf(2) = %.6g, %.6g\n", sf, cf);
+    long double sl, cl;
+    sincosl(-1.0L, &sl, &cl);
+    printf("sincosl(-1) = %.15Lg, %.15Lg\n", sl, cl);
+
+    printf("exp10: %g %g %.15g %g %g\n", exp10(3), exp10(-2), exp10(0.5), exp10f(5.0f),
+           (double) exp10l(-1.0L));
+
+    printf("significand: %g %g %g %g %g\n", significand(12.0), significand(-0.375),
+           significand(1.0), (double) significandf(1000.0f), (double) significandl(3.0L));
+
+    double zero = 0.0;
+    double values[] = {1.0, -zero, 1 / zero, -1 / zero, zero / zero};
+    for (int i = 0; i < 5; i++) {
+        double x = values[i];
+        printf("%g: __finite=%d finite=%d __isinf=%d __isnan=%d __finitef=%d __isinff=%d "
+               "__isnanl=%d\n",
+               x, __finite(x), finite(x), __isinf(x) != 0, __isnan(x) != 0,
+               __finitef((float) x), __isinff((float) x) != 0, __isnanl((long double) x) != 0);
+    }
+}
@@ -0,0 +1,37 @@
+// The special functions, to ten significant digits
+#define _DEFAULT_SOURCE
+#include <math.h>
+#include <stdio.h>
+
+#include "test_helpers.h"
+
+int main(void) {
+    double xs[] = {0.25, 1, 2.5, 10, 30};
+    for (int i = 0; i < 5; i++) {
+        double x = xs[i];
+        printf("x=%g: j0=%.10g j1=%.10g j5=%.10g y0=%.10g y1=%.10g y5=%.10g\n", x, j0(x), j1(x),
+               jn(5, x), y0(x), y1(x), yn(5, x));
+        printf("  j0f=%.6g y1f=%.6g jnf(2)=%.6g\n", j0f(x), y1f(x), jnf(2, x));
+    }
+    printf("y0(0)=%g y1(-1) is %s\n", y0(0), isnan(y1(-1)) ? "nan" : "a number");
+
+    double gs[] = {0.5, 1, 4.5, -1.5, -2.5, 100, 171.5};
+    for (int i = 0; i < 7; i++) {
+        double x = gs[i];
+        int sign;
+        double lg = lgamma_r(x, &sign);
+        float lgf = lgammaf_r((float) x, &sign);
+        printf("x=%g: tgamma=%.10g lgamma_r=%.10g sign=%d lgammaf_r=%.6g tgammaf=%.6g\n", x,
+               tgamma(x), lg, sign, lgf, tgammaf((float) x));
+    }
+    printf("tgamma(0)=%g tgamma(-1) is %s tgamma(172)=%g\n", tgamma(0),
+           isnan(tgamma(-1)) ? "nan" : "a number", tgamma(172));
+
+    double es[] = {-3, -0.5, 0, 1e-10, 0.5, 1, 2, 5};
+    for (int i = 0; i < 8; i++) {
+        double x = es[i];
+        printf("x=%g: erf=%.10g erfc=%.10g erff=%.6g erfcf=%.6g\n", x, erf(x), erfc(x),
+               erff((float) x), erfcf((float) x));
+    }
+    printf("erfc(10)=%.10g erfc(30) is %s\n", erfc(10), erfc(30) == 0 ? "zero" : "not zero");
+}
@@ -0,0 +1,312 @@
+// The special functions stay within the bounds include/math.h documents, at points spread over
+// the ranges it gives them and next to the zeros of the Bessel functions and of lgamma. The
+// reference values were computed with 200-bit precision.
+#define _DEFAULT_SOURCE
+#include <math.h>
+#include <stdio.h>
+
+struct point {
+    double x, value;
+};
+
+static const struct point j0_points[] = {
+    { 0x1.0000000000000p-2, 0x1.f807fc72aa864p-1 },
+    { 0x1.8000000000000p+0, 0x1.060e46ce9651bp-1 },
+    { 0x1.33d152e971b40p+1, -0x1.19b7921f03c8ep-54 },
+    { 0x1.6148f5b2c2e45p+2, -0x1.fbb40985f6e34p-56 },
+    { 0x1.4000000000000p+3, -0x1.f7ad2b89e1e54p-3 },
+    { 0x1.0a66666666666p+5, 0x1.036f375b906c2p-4 },
+    { 0x1.2d66666666666p+7, 0x1.5225a0865668dp-5 },
+    { 0x1.8f00000000000p+7, -0x1.4483d19a8320bp-5 },
+};
+
+static const struct point j0f_points[] = {
+    { 0x1.0000000000000p-2, 0x1.f807fc72aa864p-1 },
+    { 0x1.8000000000000p+0, 0x1.060e46ce9651bp-1 },
+    { 0x1.33d1520000000p+1, 0x1.e4c48e9f15adcp-25 },
+    { 0x1.6148f60000000p+2, 0x1.a4817bfc2921cp-26 },
+    { 0x1.4000000000000p+3, -0x1.f7ad2b89e1e54p-3 },
+    { 0x1.0a66660000000p+5, 0x1.036f50b981b7fp-4 },
+    { 0x1.2d66660000000p+7, 0x1.52254e7e1e82ep-5 },
+    { 0x1.8f00000000000p+7, -0x1.4483d19a8320bp-5 },
+};
+
+static const struct point j1_points[] = {
+    { 0x1.0000000000000p-3, 0x1.ff002aa71c9f5p-5 },
+    { 0x1.8000000000000p+0, 0x1.1da9da9d6fc81p-1 },
+    { 0x1.ea75575af6f08p+1, 0x1.0e9ee762f2b47p-53 },
+    { 0x1.c0ff5f3b47250p+2, 0x1.049770ce74c2ep-55 },
+    { 0x1.9000000000000p+3, -0x1.52e92c46b451fp-3 },
+    { 0x1.cf33333333333p+5, 0x1.d14f0e65a10b0p-5 },
+    { 0x1.8f00000000000p+7, -0x1.4ab8c7491efcap-5 },
+};
+
+static const struct point j1f_points[] = {
+    { 0x1.0000000000000p-3, 0x1.ff002aa71c9f5p-5 },
+    { 0x1.8000000000000p+0, 0x1.1da9da9d6fc81p-1 },
+    { 0x1.ea75580000000p+1, -0x1.09e0d2cce25afp-25 },
+    { 0x1.c0ff600000000p+2, 0x1.d850afd0b29c9p-25 },
+    { 0x1.9000000000000p+3, -0x1.52e92c46b451fp-3 },
+    { 0x1.cf33340000000p+5, 0x1.d14f5632ae50cp-5 },
+    { 0x1.8f00000000000p+7, -0x1.4ab8c7491efcap-5 },
+};
+
+static const struct point jn5_points[] = {
+    { 0x1.0000000000000p-1, 0x1.0e3c219865119p-17 },
+    { 0x1.8000000000000p+1, 0x1.607d2b130a1e5p-5 },
+    { 0x1.18affed3de1f0p+3, 0x1.3678771d28989p-54 },
+    { 0x1.8ad5d8784d155p+3, 0x1.0f57093ea8c32p-54 },
+    { 0x1.4000000000000p+4, 0x1.35987ecd06bf3p-3 },
+    { 0x1.bc66666666666p+6, -0x1.1920598cc3121p-5 },
+};
+
+static const struct point jn5f_points[] = {
+    { 0x1.0000000000000p-1, 0x1.0e3c219865119p-17 },
+    { 0x1.8000000000000p+1, 0x1.607d2b130a1e5p-5 },
+    { 0x1.18affe0000000p+3, 0x1.9fff207b2ace3p-24 },
+    { 0x1.8ad5d80000000p+3, -0x1.a2823951c7a58p-25 },
+    { 0x1.4000000000000p+4, 0x1.35987ecd06bf3p-3 },
+    { 0x1.bc66660000000p+6, -0x1.1920226c8d687p-5 },
+};
+
+static const struct point y0_points[] = {
+    { 0x1.0624dd2f1a9fcp-10, -0x1.1e2bb09429a59p+2 },
+    { 0x1.0000000000000p-1, -0x1.c72feb3b7b8a2p-2 },
+    { 0x1.c982eb8d417eap-1, -0x1.af74bfa0f1304p-56 },
+    { 0x1.fa9534d98569cp+1, -0x1.8fa8956b4b481p-55 },
+    { 0x1.2000000000000p+3, 0x1.ffdecfc9a29cap-3 },
+    { 0x1.36ccccccccccdp+6, 0x1.722be9e450a9ep-4 },
+    { 0x1.8f00000000000p+7, -0x1.49e84df0c351fp-5 },
+};
+
+static const struct point y0f_points[] = {
+    { 0x1.0624de0000000p-10, -0x1.1e2bb073b1e87p+2 },
+    { 0x1.0000000000000p-1, -0x1.c72feb3b7b8a2p-2 },
+    { 0x1.c982ec0000000p-1, 0x1.93a2340fe5581p-27 },
+    { 0x1.fa95340000000p+1, 0x1.5e3f0559d7e0ep-25 },
+    { 0x1.2000000000000p+3, 0x1.ffdecfc9a29cap-3 },
+    { 0x1.36cccc0000000p+6, 0x1.722be6373e8e4p-4 },
+    { 0x1.8f00000000000p+7, -0x1.49e84df0c351fp-5 },
+};
+
+static const struct point y1_points[] = {
+    { 0x1.47ae147ae147bp-7, -0x1.fd6dc3e3351a0p+5 },
+    { 0x1.0000000000000p+0, -0x1.8ffb207d66b94p-1 },
+    { 0x1.193bed4dff243p+1, 0x1.cf9f8d5e1a475p-56 },
+    { 0x1.5b7fe4e87b02fp+2, -0x1.72549fbad6c97p-53 },
+    { 0x1.e000000000000p+3, 0x1.594533ce7dfffp-6 },
+    { 0x1.ed9999999999ap+6, 0x1.24dcb375e3415p-4 },
+};
+
+static const struct point y1f_points[] = {
+    { 0x1.47ae140000000p-7, -0x1.fd6dc4a2272edp+5 },
+    { 0x1.0000000000000p+0, -0x1.8ffb207d66b94p-1 },
+    { 0x1.193bee0000000p+1, 0x1.72ce9162217cep-25 },
+    { 0x1.5b7fe40000000p+2, 0x1.3c782187a62cep-24 },
+    { 0x1.e000000000000p+3, 0x1.594533ce7dfffp-6 },
+    { 0x1.ed999a0000000p+6, 0x1.24dcb08924105p-4 },
+};
+
+static const struct point yn5_points[] = {
+    { 0x1.8000000000000p-1, -0x1.0acfcd21a57f5p+10 },
+    { 0x1.4000000000000p+1, -0x1.ea43350a8d845p+1 },
+    { 0x1.afd1dc1afaf57p+2, 0x1.ad38220c8c480p-60 },
+    { 0x1.5000000000000p+3, 0x1.706d4a6712716p-6 },
+    { 0x1.6333333333333p+5, -0x1.ea144583dc3a0p-4 },
+};
+
+static const struct point yn5f_points[] = {
+    { 0x1.8000000000000p-1, -0x1.0acfcd21a57f5p+10 },
+    { 0x1.4000000000000p+1, -0x1.ea43350a8d845p+1 },
+    { 0x1.afd1dc0000000p+2, -0x1.bd6b578ab6bdap-28 },
+    { 0x1.5000000000000p+3, 0x1.706d4a6712716p-6 },
+    { 0x1.6333340000000p+5, -0x1.ea14494a3e96cp-4 },
+};
+
+static const struct point lgamma_points[] = {
+    { 0x1.0624dd2f1a9fcp-10, 0x1.ba0f3807161acp+2 },
+    { 0x1.0000000000000p-1, 0x1.250d048e7a1bdp-1 },
+    { 0x1.0000000000000p+0, 0x0.0p+0 },
+    { 0x1.8000000000000p+0, -0x1.eeb95b094c191p-4 },
+    { 0x1.0000000000000p+1, 0x0.0p+0 },
+    { 0x1.4000000000000p+1, 0x1.2383e809a67e8p-2 },
+    { 0x1.4000000000000p+3, 0x1.99a8921a7f7cfp+3 },
+    { 0x1.f440000000000p+9, 0x1.714ac96c9d5d3p+12 },
+    { 0x1.86a0000000000p+16, 0x1.00a97b57f4c2ep+20 },
+    { -0x1.0000000000000p-1, 0x1.43f89a3f0edd6p+0 },
+    { -0x1.3a7fc90dfb6b4p+1, 0x1.f1981b06590bbp-25 },
+    { -0x1.de350c51471a7p+1, -0x1.56d01baf5917fp+0 },
+    { -0x1.499999999999ap+3, -0x1.cea3f76b8a357p+3 },
+};
+
+static const struct point lgammaf_points[] = {
+    { 0x1.0624de0000000p-10, 0x1.ba0f37d40e972p+2 },
+    { 0x1.0000000000000p-1, 0x1.250d048e7a1bdp-1 },
+    { 0x1.0000000000000p+0, 0x0.0p+0 },
+    { 0x1.8000000000000p+0, -0x1.eeb95b094c191p-4 },
+    { 0x1.0000000000000p+1, 0x0.0p+0 },
+    { 0x1.4000000000000p+1, 0x1.2383e809a67e8p-2 },
+    { 0x1.4000000000000p+3, 0x1.99a8921a7f7cfp+3 },
+    { 0x1.f440000000000p+9, 0x1.714ac96c9d5d3p+12 },
+    { 0x1.86a0000000000p+16, 0x1.00a97b57f4c2ep+20 },
+    { -0x1.0000000000000p-1, 0x1.43f89a3f0edd6p+0 },
+    { -0x1.3a7fca0000000p+1, -0x1.e4cf2421a71b2p-24 },
+    { -0x1.de350c0000000p+1, -0x1.56d01c97ea440p+0 },
+    { -0x1.49999a0000000p+3, -0x1.cea3f948f847fp+3 },
+};
+
+static const struct point tgamma_points[] = {
+    { 0x1.0624dd2f1a9fcp-10, 0x1.f3b63e2d41242p+9 },
+    { 0x1.0000000000000p-1, 0x1.c5bf891b4ef6bp+0 },
+    { 0x1.8000000000000p+0, 0x1.c5bf891b4ef6bp-1 },
+    { 0x1.2000000000000p+2, 0x1.74371e7866c65p+3 },
+    { 0x1.4400000000000p+4, 0x1.c6d19285a4ccap+57 },
+    { 0x1.fb33333333333p+6, 0x1.b69223ccdb3aap+700 },
+    { 0x1.5500000000000p+7, 0x1.9589f849167a8p+1015 },
+    { -0x1.0000000000000p-1, -0x1.c5bf891b4ef6bp+1 },
+    { -0x1.6000000000000p+1, -0x1.0126c7a2df451p+0 },
+    { -0x1.08c49ba5e353fp+3, -0x1.dd54433791181p-15 },
+    { -0x1.419999999999ap+4, -0x1.c7cae18653b55p-59 },
+};
+
+static const struct point tgammaf_points[] = {
+    { 0x1.0624de0000000p-10, 0x1.f3b63c9ed13f2p+9 },
+    { 0x1.0000000000000p-1, 0x1.c5bf891b4ef6bp+0 },
+    { 0x1.8000000000000p+0, 0x1.c5bf891b4ef6bp-1 },
+    { 0x1.2000000000000p+2, 0x1.74371e7866c65p+3 },
+    { 0x1.4400000000000p+4, 0x1.c6d19285a4ccap+57 },
+    { -0x1.0000000000000p-1, -0x1.c5bf891b4ef6bp+1 },
+    { -0x1.6000000000000p+1, -0x1.0126c7a2df451p+0 },
+    { -0x1.08c49c0000000p+3, -0x1.dd5429a1fe722p-15 },
+    { -0x1.41999a0000000p+4, -0x1.c7ca50e08742cp-59 },
+};
+
+static const struct point erf_points[] = {
+    { 0x1.b7cdfd9d7bdbbp-34, 0x1.f044332d68161p-34 },
+    { 0x1.0624dd2f1a9fcp-10, 0x1.27cc3804d374cp-10 },
+    { 0x1.999999999999ap-4, 0x1.cca5ea24fb334p-4 },
+    { 0x1.0000000000000p-1, 0x1.0a7ef5c18edd2p-1 },
+    { 0x1.b000000000000p-1, 0x1.88d1cd474a2e0p-1 },
+    { 0x1.4000000000000p+0, 0x1.d8865d98abe01p-1 },
+    { 0x1.4000000000000p+1, 0x1.ffcaa8f4c9beap-1 },
+    { 0x1.0000000000000p+2, 0x1.ffffff7b91176p-1 },
+    { -0x1.6666666666666p-1, -0x1.5b08c21171646p-1 },
+};
+
+static const struct point erff_points[] = {
+    { 0x1.b7cdfe0000000p-34, 0x1.f044339c91f81p-34 },
+    { 0x1.0624de0000000p-10, 0x1.27cc38f08a271p-10 },
+    { 0x1.99999a0000000p-4, 0x1.cca5ea9760a8cp-4 },
+    { 0x1.0000000000000p-1, 0x1.0a7ef5c18edd2p-1 },
+    { 0x1.b000000000000p-1, 0x1.88d1cd474a2e0p-1 },
+    { 0x1.4000000000000p+0, 0x1.d8865d98abe01p-1 },
+    { 0x1.4000000000000p+1, 0x1.ffcaa8f4c9beap-1 },
+    { 0x1.0000000000000p+2, 0x1.ffffff7b91176p-1 },
+    { -0x1.6666660000000p-1, -0x1.5b08c1caa8095p-1 },
+};
+
+static const struct point erfc_points[] = {
+    { -0x1.8000000000000p+1, 0x1.fffe8d6209afdp+0 },
+    { -0x1.0000000000000p-1, 0x1.853f7ae0c76e9p+0 },
+    { 0x1.0624dd2f1a9fcp-10, 0x1.ff6c19e3fd964p-1 },
+    { 0x1.0000000000000p-1, 0x1.eb02147ce245cp-2 },
+    { 0x1.5c28f5c28f5c3p+0, 0x1.bdf5f13384066p-5 },
+    { 0x1.4000000000000p+1, 0x1.aab859b20ac9ep-12 },
+    { 0x1.8000000000000p+2, 0x1.8cf81557d20b6p-56 },
+    { 0x1.8000000000000p+3, 0x1.c90f21d2d475fp-213 },
+    { 0x1.a000000000000p+4, 0x1.284bfe1cdea24p-981 },
+};
+
+static const struct point erfcf_points[] = {
+    { -0x1.8000000000000p+1, 0x1.fffe8d6209afdp+0 },
+    { -0x1.0000000000000p-1, 0x1.853f7ae0c76e9p+0 },
+    { 0x1.0624de0000000p-10, 0x1.ff6c19e387bafp-1 },
+    { 0x1.0000000000000p-1, 0x1.eb02147ce245cp-2 },
+    { 0x1.5c28f60000000p+0, 0x1.bdf5efd68c4efp-5 },
+    { 0x1.4000000000000p+1, 0x1.aab859b20ac9ep-12 },
+    { 0x1.8000000000000p+2, 0x1.8cf81557d20b6p-56 },
+};
+
+static double j5(double x) { return jn(5, x); }
+static double y5(double x) { return yn(5, x); }
+static float j5f(float x) { return jnf(5, x); }
+static float y5f(float x) { return ynf(5, x); }
+static double lgamma_sign(double x) {
+    int sign;
+    return lgamma_r(x, &sign);
+}
+static float lgammaf_sign(float x) {
+    int sign;
+    return lgammaf_r(x, &sign);
+}
+
+// The error of `got`, in ulp of the value, or of the larger of the value and 1 when `relative`
+// is 0, for `digits` bits of mantissa
+static double ulps(double got, double value, int relative, int digits) {
+    double scale = relative ? fabs(value) : fmax(fabs(value), 1);
+    return fabs(got - value) / ldexp(1, ilogb(scale) - digits + 1);
+}
+
+// The reference values are rounded to double, which takes up to half an ulp more
+static void report(const char *name, double worst, double at, double bound) {
+    if (worst <= bound + 0.5) {
+        printf("%s: within %g ulp\n", name, bound);
+    } else {
+        printf("%s: %.2f ulp at %a, over %g\n", name, worst, at, bound);
+    }
+}
+
+static void check(const char *name, double (*f)(double), const struct point *points, size_t n,
+                  int relative, double bound) {
+    double worst = 0, at = 0;
+    for (size_t i = 0; i < n; i++) {
+        double error = ulps(f(points[i].x), points[i].value, relative, 53);
+        if (error > worst) {
+            worst = error;
+            at = points[i].x;
+        }
+    }
+    report(name, worst, at, bound);
+}
+
+static void checkf(const char *name, float (*f)(float), const struct point *points, size_t n,
+                   int relative, double bound) {
+    double worst = 0, at = 0;
+    for (size_t i = 0; i < n; i++) {
+        double error = ulps(f((float) points[i].x), points[i].value, relative, 24);
+        if (error > worst) {
+            worst = error;
+            at = points[i].x;
+        }
+    }
+    report(name, worst, at, bound);
+}
+
+#define CHECK(name, f, points, relative, bound) \
+    check(name, f, points, sizeof(points) / sizeof(points[0]), relative, bound)
+#define CHECKF(name, f, points, relative, bound) \
+    checkf(name, f, points, sizeof(points) / sizeof(points[0]), relative, bound)
+
+int main(void) {
+    CHECK("j0", j0, j0_points, 0, 2);
+    CHECK("j1", j1, j1_points, 0, 2);
+    CHECK("jn", j5, jn5_points, 0, 8);
+    CHECK("y0", y0, y0_points, 0, 2);
+    CHECK("y1", y1, y1_points, 0, 2);
+    CHECK("yn", y5, yn5_points, 0, 8);
+    CHECK("lgamma_r", lgamma_sign, lgamma_points, 0, 4);
+    CHECK("tgamma", tgamma, tgamma_points, 1, 8);
+    CHECK("erf", erf, erf_points, 1, 1);
+    CHECK("erfc", erfc, erfc_points, 1, 4);
+
+    CHECKF("j0f", j0f, j0f_points, 0, 4);
+    CHECKF("j1f", j1f, j1f_points, 0, 4);
+    CHECKF("jnf", j5f, jn5f_points, 0, 8);
+    CHECKF("y0f", y0f, y0f_points, 0, 4);
+    CHECKF("y1f", y1f, y1f_points, 0, 4);
+    CHECKF("ynf", y5f, yn5f_points, 0, 8);
+    CHECKF("lgammaf_r", lgammaf_sign, lgammaf_points, 0, 4);
+    CHECKF("tgammaf", tgammaf, tgammaf_points, 1, 8);
+    CHECKF("erff", erff, erff_points, 1, 1);
+    CHECKF("erfcf", erfcf, erfcf_points, 1, 4);
+}
@@ -0,0 +1,165 @@
+#define _GNU_SOURCE
+#include <errno.h>
+#include <fcntl.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <sys/mman.h>
+#include <sys/socket.h>
+#include <sys/stat.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+// Passes fd through a socket, for the other end to get a descriptor of its own
+static int pass(int fd) {
+    int sockets[2];
+    int status = socketpair(AF_UNIX, SOCK_STREAM, 0, sockets);
+    ERROR_IF(socketpair, status, == -1);
+
+    char byte = 0;
+    struct iovec iov = { .iov_base = &byte, .iov_len = 1 };
+    union {
+        struct cmsghdr header;
+        char buf[CMSG_SPACE(sizeof(int))];
+    } control;
+    struct msghdr msg = {
+        .msg_iov = &iov,
+        .msg_iovlen = 1,
+        .msg_control = control.buf,
+        .msg_controllen = sizeof(control.buf),
+    };
+    struct cmsghdr *cmsg = CMSG_FIRSTHDR(&msg);
+    cmsg->cmsg_level = SOL_SOCKET;
+    cmsg->cmsg_type = SCM_RIGHTS;
+    cmsg->cmsg_len = CMSG_LEN(sizeof(int));
+    memcpy(CMSG_DATA(cmsg), &fd, sizeof(int));
+    ssize_t count = sendmsg(sockets[0], &msg, 0);
+    ERROR_IF(sendmsg, count, == -1);
+
+    msg.msg_controllen = sizeof(control.buf);
+    count = recvmsg(sockets[1], &msg, 0);
+    ERROR_IF(recvmsg, count, == -1);
+    cmsg = CMSG_FIRSTHDR(&msg);
+    if (cmsg == NULL || cmsg->cmsg_type != SCM_RIGHTS) {
+        puts("no descriptor received");
+        exit(EXIT_FAILURE);
+    }
+    int received;
+    memcpy(&received, CMSG_DATA(cmsg), sizeof(int));
+
+    close(sockets[0]);
+    close(sockets[1]);
+    return received;
+}
+
+int main(void) {
+    int fd = memfd_create("memfd test", MFD_CLOEXEC | MFD_ALLOW_SEALING);
+    ERROR_IF(memfd_create, fd, == -1);
+
+    int flags = fcntl(fd, F_GETFD);
+    ERROR_IF(fcntl, flags, == -1);
+    printf("cloexec: %d\n", (flags & FD_CLOEXEC) != 0);
+
+    int status = ftruncate(fd, 4096);
+    ERROR_IF(ftruncate, status, == -1);
+    ssize_t written = write(fd, "hello", 5);
+    ERROR_IF(write, written, == -1);
+
+    // A shared mapping sees what was written
+    char *map = mmap(NULL, 4096, PROT_READ, MAP_SHARED, fd, 0);
+    ERROR_IF(mmap, map, == MAP_FAILED);
+    printf("mapped: %.5s\n", map);
+    status = munmap(map, 4096);
+    ERROR_IF(munmap, status, == -1);
+
+    int seals = fcntl(fd, F_GET_SEALS);
+    ERROR_IF(fcntl, seals, == -1);
+    printf("seals: %d\n", seals);
+
+    status = fcntl(fd, F_ADD_SEALS, F_SEAL_SHRINK | F_SEAL_GROW);
+    ERROR_IF(fcntl, status, == -1);
+    errno = 0;
+    status = ftruncate(fd, 8192);
+    printf("grow: %d\n", status == -1 && errno == EPERM);
+    errno = 0;
+    status = ftruncate(fd, 1024);
+    printf("shrink: %d\n", status == -1 && errno == EPERM);
+    status = ftruncate(fd, 4096);
+    printf("same size: %d\n", status == 0);
+
+    // Writing within the file is still allowed, but not past its end
+    written = pwrite(fd, "world", 5, 5);
+    printf("write within: %d\n", written == 5);
+    errno = 0;
+    written = pwrite(fd, "world", 5, 4096);
+    printf("write past the end: %d\n", written == -1 && errno == EPERM);
+
+    // Duplicates share the seals
+    int dup_fd = dup(fd);
+    ERROR_IF(dup, dup_fd, == -1);
+    status = fcntl(dup_fd, F_ADD_SEALS, F_SEAL_WRITE | F_SEAL_SEAL);
+    ERROR_IF(fcntl, status, == -1);
+    seals = fcntl(fd, F_GET_SEALS);
+    ERROR_IF(fcntl, seals, == -1);
+    printf("seals: %d\n", seals);
+    status = close(dup_fd);
+    ERROR_IF(close, status, == -1);
+
+    errno = 0;
+    written = write(fd, "!", 1);
+    printf("write sealed: %d\n", written == -1 && errno == EPERM);
+    errno = 0;
+    map = mmap(NULL, 4096, PROT_READ | PROT_WRITE, MAP_SHARED, fd, 0);
+    printf("writable mapping: %d\n", map == MAP_FAILED && errno == EPERM);
+    // Also over an existing mapping
+    char *fixed = mmap(NULL, 4096, PROT_READ, MAP_PRIVATE | MAP_ANONYMOUS, -1, 0);
+    ERROR_IF(mmap, fixed, == MAP_FAILED);
+    errno = 0;
+    map = mmap(fixed, 4096, PROT_READ | PROT_WRITE, MAP_SHARED | MAP_FIXED, fd, 0);
+    printf("writable fixed mapping: %d\n", map == MAP_FAILED && errno == EPERM);
+    status = munmap(fixed, 4096);
+    ERROR_IF(munmap, status, == -1);
+    map = mmap(NULL, 4096, PROT_READ, MAP_SHARED, fd, 0);
+    ERROR_IF(mmap, map, == MAP_FAILED);
+    printf("mapped: %.10s\n", map);
+    status = munmap(map, 4096);
+    ERROR_IF(munmap, status, == -1);
+
+    // The seals go with the descriptor, to whoever it's passed to
+    int passed = pass(fd);
+    seals = fcntl(passed, F_GET_SEALS);
+    ERROR_IF(fcntl, seals, == -1);
+    printf("passed seals: %d\n", seals);
+    errno = 0;
+    written = write(passed, "!", 1);
+    printf("passed write sealed: %d\n", written == -1 && errno == EPERM);
+    struct stat st;
+    status = fstat(passed, &st);
+    ERROR_IF(fstat, status, == -1);
+    printf("passed size: %lld\n", (long long)st.st_size);
+    status = close(passed);
+    ERROR_IF(close, status, == -1);
+
+    errno = 0;
+    status = fcntl(fd, F_ADD_SEALS, F_SEAL_SHRINK);
+    printf("sealed seals: %d\n", status == -1 && errno == EPERM);
+    status = close(fd);
+    ERROR_IF(close, status, == -1);
+
+    // Without MFD_ALLOW_SEALING, no seals can be added
+    fd = memfd_create("unsealable", 0);
+    ERROR_IF(memfd_create, fd, == -1);
+    seals = fcntl(fd, F_GET_SEALS);
+    printf("unsealable: %d\n", seals == F_SEAL_SEAL);
+    errno = 0;
+    status = fcntl(fd, F_ADD_SEALS, F_SEAL_WRITE);
+    printf("add seals: %d\n", status == -1 && errno == EPERM);
+    status = close(fd);
+    ERROR_IF(close, status, == -1);
+
+    // Other files can't be sealed
+    errno = 0;
+    status = fcntl(STDIN_FILENO, F_GET_SEALS);
+    printf("not a memfd: %d\n", status == -1 && errno == EINVAL);
+}
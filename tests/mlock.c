@@ -0,0 +1,63 @@
+#include <errno.h>
+#include <stdio.h>
+#include <sys/mman.h>
+#include <sys/resource.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+int main(void) {
+    size_t page = (size_t) getpagesize();
+    char *map = mmap(NULL, page * 4, PROT_READ | PROT_WRITE, MAP_PRIVATE | MAP_ANONYMOUS, -1, 0);
+    ERROR_IF(mmap, map, == MAP_FAILED);
+
+    // Unaligned ranges lock the pages they touch
+    int status = mlock(map + 1, page);
+    ERROR_IF(mlock, status, == -1);
+    map[page] = 1;
+    status = munlock(map, page * 2);
+    ERROR_IF(munlock, status, == -1);
+
+    // Only one page may be locked now, unless privileged
+    struct rlimit limit;
+    status = getrlimit(RLIMIT_MEMLOCK, &limit);
+    ERROR_IF(getrlimit, status, == -1);
+    limit.rlim_cur = page;
+    status = setrlimit(RLIMIT_MEMLOCK, &limit);
+    ERROR_IF(setrlimit, status, == -1);
+    struct rlimit current;
+    status = getrlimit(RLIMIT_MEMLOCK, &current);
+    ERROR_IF(getrlimit, status, == -1);
+    printf("limit: %d\n", current.rlim_cur == page && current.rlim_max == limit.rlim_max);
+
+    int privileged = geteuid() == 0;
+    errno = 0;
+    status = mlock(map, page * 4);
+    printf("over the limit: %d\n",
+           privileged ? status == 0 : status == -1 && errno == ENOMEM);
+    status = munlock(map, page * 4);
+    ERROR_IF(munlock, status, == -1);
+    status = mlock(map, page);
+    ERROR_IF(mlock, status, == -1);
+    // Locking it again doesn't count twice
+    status = mlock(map, page);
+    ERROR_IF(mlock, status, == -1);
+    status = munlockall();
+    ERROR_IF(munlockall, status, == -1);
+
+    errno = 0;
+    status = mlockall(0);
+    printf("mlockall without flags: %d, EINVAL: %d\n", status, errno == EINVAL);
+
+    limit.rlim_cur = limit.rlim_max + 1;
+    if (limit.rlim_max != RLIM_INFINITY) {
+        errno = 0;
+        status = setrlimit(RLIMIT_MEMLOCK, &limit);
+        printf("soft over hard: %d, EINVAL: %d\n", status, errno == EINVAL);
+    } else {
+        puts("soft over hard: -1, EINVAL: 1");
+    }
+
+    status = munmap(map, page * 4);
+    ERROR_IF(munmap, status, == -1);
+}
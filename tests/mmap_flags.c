@@ -0,0 +1,57 @@
+#define _GNU_SOURCE
+#include <errno.h>
+#include <stdint.h>
+#include <stdio.h>
+#include <sys/mman.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+int main(void) {
+    size_t page = (size_t) getpagesize();
+    char *map = mmap(NULL, page * 2, PROT_READ | PROT_WRITE, MAP_PRIVATE | MAP_ANONYMOUS, -1, 0);
+    ERROR_IF(mmap, map, == MAP_FAILED);
+    map[page] = 42;
+
+    // Anything already mapped is left alone
+    errno = 0;
+    char *over = mmap(map + page, page, PROT_READ | PROT_WRITE,
+                      MAP_PRIVATE | MAP_ANONYMOUS | MAP_FIXED_NOREPLACE, -1, 0);
+    printf("no replace: %d\n", over == MAP_FAILED && errno == EEXIST);
+    printf("left alone: %d\n", map[page]);
+
+    // But free room is used as asked
+    int status = munmap(map + page, page);
+    ERROR_IF(munmap, status, == -1);
+    over = mmap(map + page, page, PROT_READ | PROT_WRITE,
+                MAP_PRIVATE | MAP_ANONYMOUS | MAP_FIXED_NOREPLACE, -1, 0);
+    printf("free room: %d\n", over == map + page);
+    status = munmap(map, page * 2);
+    ERROR_IF(munmap, status, == -1);
+
+    char *stack = mmap(NULL, page * 4, PROT_READ | PROT_WRITE,
+                       MAP_PRIVATE | MAP_ANONYMOUS | MAP_STACK | MAP_NORESERVE | MAP_POPULATE,
+                       -1, 0);
+    ERROR_IF(mmap, stack, == MAP_FAILED);
+    stack[page * 4 - 1] = 1;
+    printf("stack: %d\n", stack[page * 4 - 1]);
+    status = munmap(stack, page * 4);
+    ERROR_IF(munmap, status, == -1);
+
+    char *locked = mmap(NULL, page, PROT_READ | PROT_WRITE,
+                        MAP_PRIVATE | MAP_ANONYMOUS | MAP_LOCKED, -1, 0);
+    ERROR_IF(mmap, locked, == MAP_FAILED);
+    status = munmap(locked, page);
+    ERROR_IF(munmap, status, == -1);
+
+#ifdef MAP_32BIT
+    char *low = mmap(NULL, page, PROT_READ | PROT_WRITE, MAP_PRIVATE | MAP_ANONYMOUS | MAP_32BIT,
+                     -1, 0);
+    ERROR_IF(mmap, low, == MAP_FAILED);
+    printf("low: %d\n", (uintptr_t) low + page <= 0x80000000u);
+    status = munmap(low, page);
+    ERROR_IF(munmap, status, == -1);
+#else
+    puts("low: 1");
+#endif
+}
@@ -0,0 +1,118 @@
+// Processes that map the same file shared see each other's changes without syncing, as a
+// database's shared memory file used by several processes needs, even those of one that was
+// killed
+#include <fcntl.h>
+#include <signal.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <sys/mman.h>
+#include <sys/wait.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+static char *map_file(const char *path, int *fd) {
+    *fd = open(path, O_RDWR);
+    ERROR_IF(open, *fd, == -1);
+    char *map = mmap(NULL, 4096, PROT_READ | PROT_WRITE, MAP_SHARED, *fd, 0);
+    ERROR_IF(mmap, map, == MAP_FAILED);
+    return map;
+}
+
+// Waits for a byte from the other process
+static void wait_for(int fd) {
+    char c;
+    ssize_t len = read(fd, &c, 1);
+    ERROR_IF(read, (int) len, != 1);
+}
+
+static void wake(int fd) {
+    ssize_t len = write(fd, "x", 1);
+    ERROR_IF(write, (int) len, != 1);
+}
+
+int main(void) {
+    char path[] = "/tmp/mmap_shared-XXXXXX";
+    int fd = mkstemp(path);
+    ERROR_IF(mkstemp, fd, == -1);
+    ssize_t len = write(fd, "0123456789", 10);
+    ERROR_IF(write, (int) len, != 10);
+    int status = close(fd);
+    ERROR_IF(close, status, == -1);
+
+    int to_child[2], to_parent[2];
+    status = pipe(to_child);
+    ERROR_IF(pipe, status, == -1);
+    status = pipe(to_parent);
+    ERROR_IF(pipe, status, == -1);
+    fflush(stdout);
+
+    pid_t pid = fork();
+    ERROR_IF(fork, pid, == -1);
+    if (pid == 0) {
+        // The child maps the file on its own, not through a mapping inherited from the parent
+        char *map = map_file(path, &fd);
+        wake(to_parent[1]);
+
+        wait_for(to_child[0]);
+        memcpy(map, "child", 5);
+        wake(to_parent[1]);
+
+        wait_for(to_child[0]);
+        printf("child sees the parent's write: %.10s\n", map);
+        fflush(stdout);
+        _exit(0);
+    }
+
+    char *map = map_file(path, &fd);
+    wait_for(to_parent[0]);
+    wake(to_child[1]);
+    wait_for(to_parent[0]);
+    printf("parent sees the child's change: %.10s\n", map);
+
+    char buf[11] = { 0 };
+    len = pread(fd, buf, 10, 0);
+    ERROR_IF(pread, (int) len, != 10);
+    printf("parent reads it from the file: %s\n", buf);
+
+    len = pwrite(fd, "XY", 2, 8);
+    ERROR_IF(pwrite, (int) len, != 2);
+    fflush(stdout);
+    wake(to_child[1]);
+
+    int wstatus;
+    status = waitpid(pid, &wstatus, 0);
+    ERROR_IF(waitpid, status, == -1);
+
+    status = munmap(map, 4096);
+    ERROR_IF(munmap, status, == -1);
+    status = close(fd);
+    ERROR_IF(close, status, == -1);
+
+    // Killed while it maps the file, without syncing
+    pid = fork();
+    ERROR_IF(fork, pid, == -1);
+    if (pid == 0) {
+        map = map_file(path, &fd);
+        memcpy(map, "killed", 6);
+        raise(SIGKILL);
+        _exit(1);
+    }
+    status = waitpid(pid, &wstatus, 0);
+    ERROR_IF(waitpid, status, == -1);
+    printf("child killed: %d\n", WIFSIGNALED(wstatus) && WTERMSIG(wstatus) == SIGKILL);
+
+    map = map_file(path, &fd);
+    printf("parent sees the killed child's change: %.10s\n", map);
+    status = munmap(map, 4096);
+    ERROR_IF(munmap, status, == -1);
+    len = pread(fd, buf, 10, 0);
+    ERROR_IF(pread, (int) len, != 10);
+    printf("parent reads it from the file: %s\n", buf);
+    status = close(fd);
+    ERROR_IF(close, status, == -1);
+
+    status = unlink(path);
+    ERROR_IF(unlink, status, == -1);
+}
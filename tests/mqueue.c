@@ -0,0 +1,135 @@
+#define _DEFAULT_SOURCE
+#include <errno.h>
+#include <fcntl.h>
+#include <mqueue.h>
+#include <signal.h>
+#include <stdio.h>
+#include <string.h>
+#include <time.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+#define NAME "/relibc-test-mqueue"
+
+static volatile sig_atomic_t signalled = 0;
+static int pipe_fds[2];
+
+static void handler(int sig) {
+    (void) sig;
+    signalled = 1;
+}
+
+static void notified(union sigval value) {
+    char byte = (char) value.sival_int;
+    write(pipe_fds[1], &byte, 1);
+}
+
+int main(void) {
+    mq_unlink(NAME);
+
+    struct mq_attr attr = { .mq_maxmsg = 4, .mq_msgsize = 32 };
+    mqd_t mq = mq_open(NAME, O_RDWR | O_CREAT | O_EXCL, 0600, &attr);
+    ERROR_IF(mq_open, mq, == (mqd_t) -1);
+
+    mqd_t again = mq_open(NAME, O_RDWR | O_CREAT | O_EXCL, 0600, &attr);
+    printf("exclusive: %d %d\n", again == (mqd_t) -1, errno == EEXIST);
+    again = mq_open("no-slash", O_RDWR);
+    printf("no slash: %d %d\n", again == (mqd_t) -1, errno == EINVAL);
+
+    // The highest priority comes first, then the oldest
+    int status = mq_send(mq, "one", 4, 1);
+    ERROR_IF(mq_send, status, == -1);
+    status = mq_send(mq, "five", 5, 5);
+    ERROR_IF(mq_send, status, == -1);
+    status = mq_send(mq, "three", 6, 3);
+    ERROR_IF(mq_send, status, == -1);
+    status = mq_send(mq, "other one", 10, 1);
+    ERROR_IF(mq_send, status, == -1);
+
+    struct mq_attr got;
+    status = mq_getattr(mq, &got);
+    ERROR_IF(mq_getattr, status, == -1);
+    printf("attr: %ld %ld %ld %d\n", got.mq_maxmsg, got.mq_msgsize, got.mq_curmsgs,
+           (got.mq_flags & O_NONBLOCK) != 0);
+
+    // Full, so a send times out
+    struct timespec past = { 0 };
+    status = mq_timedsend(mq, "full", 5, 0, &past);
+    printf("full: %d %d\n", status, errno == ETIMEDOUT);
+
+    char buf[32];
+    ssize_t len = mq_receive(mq, buf, sizeof(buf) - 1, NULL);
+    printf("small buffer: %zd %d\n", len, errno == EMSGSIZE);
+    for (int i = 0; i < 4; i++) {
+        unsigned prio;
+        len = mq_receive(mq, buf, sizeof(buf), &prio);
+        ERROR_IF(mq_receive, len, == -1);
+        printf("received: %s (%zd, priority %u)\n", buf, len, prio);
+    }
+
+    struct timespec soon;
+    clock_gettime(CLOCK_REALTIME, &soon);
+    soon.tv_nsec += 10000000;
+    if (soon.tv_nsec >= 1000000000) {
+        soon.tv_sec++;
+        soon.tv_nsec -= 1000000000;
+    }
+    len = mq_timedreceive(mq, buf, sizeof(buf), NULL, &soon);
+    printf("empty: %zd %d\n", len, errno == ETIMEDOUT);
+
+    struct mq_attr nonblock = { .mq_flags = O_NONBLOCK };
+    struct mq_attr old;
+    status = mq_setattr(mq, &nonblock, &old);
+    ERROR_IF(mq_setattr, status, == -1);
+    printf("was nonblocking: %d\n", (old.mq_flags & O_NONBLOCK) != 0);
+    len = mq_receive(mq, buf, sizeof(buf), NULL);
+    printf("nonblocking: %zd %d\n", len, errno == EAGAIN);
+
+    // A signal for the first message on the empty queue
+    signal(SIGUSR1, handler);
+    struct sigevent event = { .sigev_notify = SIGEV_SIGNAL, .sigev_signo = SIGUSR1 };
+    status = mq_notify(mq, &event);
+    ERROR_IF(mq_notify, status, == -1);
+    status = mq_notify(mq, &event);
+    printf("busy: %d %d\n", status, errno == EBUSY);
+    status = mq_send(mq, "signal", 7, 0);
+    ERROR_IF(mq_send, status, == -1);
+    while (!signalled) {
+        usleep(1000);
+    }
+    printf("signalled: %d\n", signalled);
+    len = mq_receive(mq, buf, sizeof(buf), NULL);
+    ERROR_IF(mq_receive, len, == -1);
+
+    // And a thread
+    status = pipe(pipe_fds);
+    ERROR_IF(pipe, status, == -1);
+    struct sigevent thread = {
+        .sigev_notify = SIGEV_THREAD,
+        .sigev_notify_function = notified,
+        .sigev_value.sival_int = 'x',
+    };
+    status = mq_notify(mq, &thread);
+    ERROR_IF(mq_notify, status, == -1);
+    status = mq_send(mq, "thread", 7, 0);
+    ERROR_IF(mq_send, status, == -1);
+    char byte;
+    len = read(pipe_fds[0], &byte, 1);
+    ERROR_IF(read, len, == -1);
+    printf("thread: %c\n", byte);
+
+    // The request was used up, so another one can be made and cancelled
+    status = mq_notify(mq, &event);
+    ERROR_IF(mq_notify, status, == -1);
+    status = mq_notify(mq, NULL);
+    ERROR_IF(mq_notify, status, == -1);
+
+    status = mq_close(mq);
+    ERROR_IF(mq_close, status, == -1);
+    status = mq_unlink(NAME);
+    ERROR_IF(mq_unlink, status, == -1);
+    status = mq_unlink(NAME);
+    printf("unlinked: %d %d\n", status, errno == ENOENT);
+    return 0;
+}
@@ -0,0 +1,69 @@
+#define _GNU_SOURCE
+#include <errno.h>
+#include <stdio.h>
+#include <string.h>
+#include <sys/mman.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+static int filled(const char *map, size_t len, char c) {
+    for (size_t i = 0; i < len; i++) {
+        if (map[i] != c) {
+            return 0;
+        }
+    }
+    return 1;
+}
+
+int main(void) {
+    size_t page = (size_t) getpagesize();
+
+    // Leave two free pages after the first one
+    char *map = mmap(NULL, page * 3, PROT_READ | PROT_WRITE, MAP_PRIVATE | MAP_ANONYMOUS, -1, 0);
+    ERROR_IF(mmap, map, == MAP_FAILED);
+    int status = munmap(map + page, page * 2);
+    ERROR_IF(munmap, status, == -1);
+    memset(map, 'a', page);
+
+    char *grown = mremap(map, page, page * 3, 0);
+    ERROR_IF(mremap, grown, == MAP_FAILED);
+    printf("grown in place: %d, kept: %d\n", grown == map, filled(grown, page, 'a'));
+    memset(grown + page, 'b', page * 2);
+
+    char *shrunk = mremap(grown, page * 3, page * 2, 0);
+    ERROR_IF(mremap, shrunk, == MAP_FAILED);
+    printf("shrunk in place: %d, kept: %d\n", shrunk == map,
+           filled(shrunk, page, 'a') && filled(shrunk + page, page, 'b'));
+
+    // Block the page after it, so that it can only grow by moving
+    char *guard = mmap(map + page * 2, page, PROT_READ,
+                       MAP_PRIVATE | MAP_ANONYMOUS | MAP_FIXED_NOREPLACE, -1, 0);
+    ERROR_IF(mmap, guard, == MAP_FAILED);
+    errno = 0;
+    char *failed = mremap(map, page * 2, page * 4, 0);
+    printf("without moving: %d, ENOMEM: %d\n", failed == MAP_FAILED, errno == ENOMEM);
+
+    char *moved = mremap(map, page * 2, page * 4, MREMAP_MAYMOVE);
+    ERROR_IF(mremap, moved, == MAP_FAILED);
+    printf("moved: %d, kept: %d\n", moved != map,
+           filled(moved, page, 'a') && filled(moved + page, page, 'b'));
+    memset(moved + page * 2, 'c', page * 2);
+
+    // Move it onto a given address, replacing what was there
+    char *target = mmap(NULL, page * 4, PROT_READ, MAP_PRIVATE | MAP_ANONYMOUS, -1, 0);
+    ERROR_IF(mmap, target, == MAP_FAILED);
+    char *fixed = mremap(moved, page * 4, page * 4, MREMAP_MAYMOVE | MREMAP_FIXED, target);
+    ERROR_IF(mremap, fixed, == MAP_FAILED);
+    printf("fixed: %d, kept: %d\n", fixed == target,
+           filled(fixed, page, 'a') && filled(fixed + page * 2, page * 2, 'c'));
+
+    errno = 0;
+    failed = mremap(fixed, page * 4, page, MREMAP_FIXED, target);
+    printf("fixed without moving: %d, EINVAL: %d\n", failed == MAP_FAILED, errno == EINVAL);
+
+    status = munmap(fixed, page * 4);
+    ERROR_IF(munmap, status, == -1);
+    status = munmap(guard, page);
+    ERROR_IF(munmap, status, == -1);
+}
@@ -0,0 +1,72 @@
+#include <errno.h>
+#include <fcntl.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <sys/mman.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+int main(void) {
+    char path[] = "/tmp/msync-XXXXXX";
+    int fd = mkstemp(path);
+    ERROR_IF(mkstemp, fd, == -1);
+    const char contents[] = "0123456789";
+    ssize_t len = write(fd, contents, 10);
+    ERROR_IF(write, (int) len, == -1);
+
+    char *map = mmap(NULL, 10, PROT_READ | PROT_WRITE, MAP_SHARED, fd, 0);
+    ERROR_IF(mmap, map, == MAP_FAILED);
+    char *view = mmap(NULL, 10, PROT_READ, MAP_SHARED, fd, 0);
+    ERROR_IF(mmap, view, == MAP_FAILED);
+    printf("mapped: %.10s\n", map);
+
+    // Changes to the mapping reach the file
+    memcpy(map, "abc", 3);
+    int status = msync(map, 10, MS_SYNC);
+    ERROR_IF(msync, status, == -1);
+    char buf[11] = { 0 };
+    len = pread(fd, buf, 10, 0);
+    ERROR_IF(pread, (int) len, == -1);
+    printf("file after msync: %s\n", buf);
+
+    // Writes to the file reach the mappings
+    len = pwrite(fd, "XY", 2, 8);
+    ERROR_IF(pwrite, (int) len, == -1);
+    status = msync(view, 10, MS_INVALIDATE);
+    ERROR_IF(msync, status, == -1);
+    printf("mappings after write: %.10s %.10s\n", map, view);
+
+    memcpy(map + 5, "!", 1);
+    status = msync(map, 10, MS_ASYNC);
+    ERROR_IF(msync, status, == -1);
+
+    errno = 0;
+    status = msync(map, 10, MS_SYNC | MS_ASYNC);
+    printf("both MS_SYNC and MS_ASYNC: %d, EINVAL: %d\n", status, errno == EINVAL);
+    errno = 0;
+    status = msync(map + 1, 9, MS_SYNC);
+    printf("unaligned: %d, EINVAL: %d\n", status, errno == EINVAL);
+
+    // Unmapping writes back what wasn't synced
+    memcpy(map + 6, "?", 1);
+    status = munmap(view, 10);
+    ERROR_IF(munmap, status, == -1);
+    status = munmap(map, 10);
+    ERROR_IF(munmap, status, == -1);
+    status = close(fd);
+    ERROR_IF(close, status, == -1);
+
+    fd = open(path, O_RDONLY);
+    ERROR_IF(open, fd, == -1);
+    memset(buf, 0, sizeof(buf));
+    len = read(fd, buf, 10);
+    ERROR_IF(read, (int) len, == -1);
+    printf("file after munmap: %s\n", buf);
+
+    status = close(fd);
+    ERROR_IF(close, status, == -1);
+    status = unlink(path);
+    ERROR_IF(unlink, status, == -1);
+}
@@ -0,0 +1,35 @@
+#include <errno.h>
+#include <net/if.h>
+#include <stdio.h>
+#include <string.h>
+
+#include "test_helpers.h"
+
+int main(void) {
+    struct if_nameindex *ifs = if_nameindex();
+    ERROR_IF(if_nameindex, ifs, == NULL);
+
+    for (struct if_nameindex *i = ifs; i->if_index != 0 || i->if_name != NULL; i++) {
+        UNEXP_IF(if_nameindex, i->if_name, == NULL);
+        printf("%u: %s\n", i->if_index, i->if_name);
+
+        unsigned int index = if_nametoindex(i->if_name);
+        UNEXP_IF(if_nametoindex, index, != i->if_index);
+
+        char name[IF_NAMESIZE];
+        char *ret = if_indextoname(i->if_index, name);
+        ERROR_IF(if_indextoname, ret, == NULL);
+        UNEXP_IF(if_indextoname, strcmp(name, i->if_name), != 0);
+    }
+
+    if_freenameindex(ifs);
+
+    unsigned int index = if_nametoindex("relibc-no-such-if");
+    UNEXP_IF(if_nametoindex, index, != 0);
+
+    char name[IF_NAMESIZE];
+    errno = 0;
+    char *ret = if_indextoname(0, name);
+    UNEXP_IF(if_indextoname, ret, != NULL);
+    UNEXP_IF(if_indextoname, errno, != ENXIO);
+}
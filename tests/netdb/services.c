@@ -0,0 +1,99 @@
+#include <arpa/inet.h>
+#include <errno.h>
+#include <netdb.h>
+#include <stdio.h>
+#include <string.h>
+#include <sys/socket.h>
+
+#include "test_helpers.h"
+
+int main(void) {
+    struct servent *serv = getservbyname("http", "tcp");
+    ERROR_IF(getservbyname, serv, == NULL);
+    UNEXP_IF(getservbyname, ntohs(serv->s_port), != 80);
+    UNEXP_IF(getservbyname, strcmp(serv->s_proto, "tcp"), != 0);
+
+    serv = getservbyport(htons(80), "tcp");
+    ERROR_IF(getservbyport, serv, == NULL);
+    UNEXP_IF(getservbyport, strcmp(serv->s_name, "http"), != 0);
+
+    serv = getservbyname("no-such-service", NULL);
+    UNEXP_IF(getservbyname, (void *)serv, != NULL);
+
+    struct servent serv_buf, *serv_result;
+    char small[4], buf[1024];
+    int ret = getservbyname_r("http", "tcp", &serv_buf, small, sizeof(small), &serv_result);
+    UNEXP_IF(getservbyname_r, ret, != ERANGE);
+    ret = getservbyname_r("http", "tcp", &serv_buf, buf, sizeof(buf), &serv_result);
+    UNEXP_IF(getservbyname_r, ret, != 0);
+    UNEXP_IF(getservbyname_r, (void *)serv_result, != (void *)&serv_buf);
+    UNEXP_IF(getservbyname_r, ntohs(serv_buf.s_port), != 80);
+
+    ret = getservbyport_r(htons(53), "udp", &serv_buf, buf, sizeof(buf), &serv_result);
+    UNEXP_IF(getservbyport_r, ret, != 0);
+    UNEXP_IF(getservbyport_r, (void *)serv_result, == NULL);
+    UNEXP_IF(getservbyport_r, strcmp(serv_buf.s_name, "domain"), != 0);
+
+    // Lookups must not disturb the position of getservent
+    setservent(1);
+    serv = getservent();
+    ERROR_IF(getservent, serv, == NULL);
+    char first[64];
+    snprintf(first, sizeof(first), "%s", serv->s_name);
+    serv = getservent();
+    ERROR_IF(getservent, serv, == NULL);
+    char second[64];
+    snprintf(second, sizeof(second), "%s", serv->s_name);
+
+    setservent(1);
+    getservent();
+    getservbyname("http", "tcp");
+    serv = getservent();
+    ERROR_IF(getservent, serv, == NULL);
+    UNEXP_IF(getservent, strcmp(serv->s_name, second), != 0);
+
+    int count = 2;
+    while (getservent_r(&serv_buf, buf, sizeof(buf), &serv_result) == 0) {
+        count++;
+    }
+    UNEXP_IF(getservent_r, (void *)serv_result, != NULL);
+    endservent();
+    printf("services: first %s, %d entries\n", first, count);
+
+    struct protoent *proto = getprotobyname("tcp");
+    ERROR_IF(getprotobyname, proto, == NULL);
+    UNEXP_IF(getprotobyname, proto->p_proto, != 6);
+
+    proto = getprotobynumber(17);
+    ERROR_IF(getprotobynumber, proto, == NULL);
+    UNEXP_IF(getprotobynumber, strcmp(proto->p_name, "udp"), != 0);
+
+    struct protoent proto_buf, *proto_result;
+    ret = getprotobyname_r("UDP", &proto_buf, buf, sizeof(buf), &proto_result);
+    UNEXP_IF(getprotobyname_r, ret, != 0);
+    UNEXP_IF(getprotobyname_r, (void *)proto_result, == NULL);
+    UNEXP_IF(getprotobyname_r, proto_buf.p_proto, != 17);
+
+    ret = getprotobynumber_r(6, &proto_buf, small, sizeof(small), &proto_result);
+    UNEXP_IF(getprotobynumber_r, ret, != ERANGE);
+
+    setprotoent(0);
+    count = 0;
+    while (getprotoent_r(&proto_buf, buf, sizeof(buf), &proto_result) == 0) {
+        count++;
+    }
+    endprotoent();
+    printf("protocols: %d entries\n", count);
+
+    struct addrinfo hints, *res;
+    memset(&hints, 0, sizeof(hints));
+    hints.ai_family = AF_INET;
+    hints.ai_socktype = SOCK_STREAM;
+    hints.ai_flags = AI_NUMERICSERV;
+    ret = getaddrinfo(NULL, "http", &hints, &res);
+    UNEXP_IF(getaddrinfo, ret, != EAI_NONAME);
+
+    hints.ai_flags = 0;
+    ret = getaddrinfo(NULL, "no-such-service", &hints, &res);
+    UNEXP_IF(getaddrinfo, ret, != EAI_SERVICE);
+}
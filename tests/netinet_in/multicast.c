@@ -0,0 +1,72 @@
+#include <arpa/inet.h>
+#include <net/if.h>
+#include <netinet/in.h>
+#include <stdio.h>
+#include <sys/socket.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+static void check_int(int fd, int level, int name, const char *desc, int value) {
+    int status = setsockopt(fd, level, name, &value, sizeof(value));
+    ERROR_IF(setsockopt, status, == -1);
+
+    int result = -1;
+    socklen_t len = sizeof(result);
+    status = getsockopt(fd, level, name, &result, &len);
+    ERROR_IF(getsockopt, status, == -1);
+    printf("%s: %d\n", desc, result);
+}
+
+// IP_MULTICAST_IF takes the address of the interface, alone or in an ip_mreq or ip_mreqn
+static void check_if(int fd, const char *desc, const void *value, socklen_t size) {
+    int status = setsockopt(fd, IPPROTO_IP, IP_MULTICAST_IF, value, size);
+    ERROR_IF(setsockopt, status, == -1);
+
+    struct in_addr result = { 0 };
+    socklen_t len = sizeof(result);
+    status = getsockopt(fd, IPPROTO_IP, IP_MULTICAST_IF, &result, &len);
+    ERROR_IF(getsockopt, status, == -1);
+    printf("IP_MULTICAST_IF with %s: %s\n", desc, inet_ntoa(result));
+
+    struct in_addr any = { .s_addr = htonl(INADDR_ANY) };
+    status = setsockopt(fd, IPPROTO_IP, IP_MULTICAST_IF, &any, sizeof(any));
+    ERROR_IF(setsockopt, status, == -1);
+}
+
+int main(void) {
+    int fd = socket(AF_INET, SOCK_DGRAM, 0);
+    ERROR_IF(socket, fd, == -1);
+
+    check_int(fd, IPPROTO_IP, IP_MULTICAST_TTL, "IP_MULTICAST_TTL", 4);
+    check_int(fd, IPPROTO_IP, IP_MULTICAST_LOOP, "IP_MULTICAST_LOOP", 0);
+
+    // Single byte values are accepted as well
+    unsigned char ttl = 8;
+    int status = setsockopt(fd, IPPROTO_IP, IP_MULTICAST_TTL, &ttl, sizeof(ttl));
+    ERROR_IF(setsockopt, status, == -1);
+
+    struct ip_mreq mreq;
+    mreq.imr_multiaddr.s_addr = inet_addr("224.0.0.251");
+    mreq.imr_interface.s_addr = htonl(INADDR_LOOPBACK);
+    status = setsockopt(fd, IPPROTO_IP, IP_ADD_MEMBERSHIP, &mreq, sizeof(mreq));
+    ERROR_IF(setsockopt, status, == -1);
+    status = setsockopt(fd, IPPROTO_IP, IP_DROP_MEMBERSHIP, &mreq, sizeof(mreq));
+    ERROR_IF(setsockopt, status, == -1);
+
+    check_if(fd, "in_addr", &mreq.imr_interface, sizeof(mreq.imr_interface));
+    check_if(fd, "ip_mreq", &mreq, sizeof(mreq));
+    struct ip_mreqn mreqn = { 0 };
+    mreqn.imr_multiaddr = mreq.imr_multiaddr;
+    mreqn.imr_address = mreq.imr_interface;
+    check_if(fd, "ip_mreqn", &mreqn, sizeof(mreqn));
+
+    // Or the index of the interface
+    mreqn.imr_address.s_addr = htonl(INADDR_ANY);
+    mreqn.imr_ifindex = if_nametoindex("lo");
+    UNEXP_IF(if_nametoindex, mreqn.imr_ifindex, == 0);
+    status = setsockopt(fd, IPPROTO_IP, IP_MULTICAST_IF, &mreqn, sizeof(mreqn));
+    ERROR_IF(setsockopt, status, == -1);
+
+    close(fd);
+}
@@ -0,0 +1,84 @@
+// Locale objects, used by a thread or passed to the *_l functions
+#define _GNU_SOURCE
+#include <ctype.h>
+#include <errno.h>
+#include <locale.h>
+#include <pthread.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <time.h>
+
+#include "test_helpers.h"
+
+static void *other_thread(void *arg) {
+    (void) arg;
+    // The thread started out in the global locale
+    printf("other thread: %s %.1f\n", uselocale(NULL) == LC_GLOBAL_LOCALE ? "global" : "own", 1.5);
+    return NULL;
+}
+
+int main(void) {
+    int status = setenv("LOCPATH", "locales", 1);
+    ERROR_IF(setenv, status, == -1);
+
+    errno = 0;
+    locale_t missing = newlocale(LC_ALL_MASK, "xx_XX", NULL);
+    printf("missing: %d %d\n", missing == NULL, errno == ENOENT);
+    errno = 0;
+    locale_t invalid = newlocale(1 << 30, "C", NULL);
+    printf("invalid mask: %d %d\n", invalid == NULL, errno == EINVAL);
+
+    locale_t french = newlocale(LC_ALL_MASK, "fr_FR.UTF-8", NULL);
+    ERROR_IF(newlocale, french, == NULL);
+
+    // Given to the *_l functions, the locale leaves the rest alone
+    time_t timestamp = 1531808742;
+    char out[100];
+    strftime_l(out, sizeof(out), "%A %e %B", gmtime(&timestamp), french);
+    printf("strftime_l: %s\n", out);
+    strftime(out, sizeof(out), "%A %e %B", gmtime(&timestamp));
+    printf("strftime: %s\n", out);
+    printf("strtod_l: %g, strtod: %g\n", strtod_l("2,5", NULL, french), strtod("2,5", NULL));
+    printf("isalpha_l: %d, toupper_l: %c\n", isalpha_l('a', french) != 0, toupper_l('a', french));
+
+    // A thread that uses it has it for everything, while the others stay in the global locale
+    locale_t old = uselocale(french);
+    printf("previous: %s\n", old == LC_GLOBAL_LOCALE ? "global" : "own");
+    printf("current: %d\n", uselocale(NULL) == french);
+    printf("this thread: %.1f %s\n", 1.5, setlocale(LC_ALL, NULL));
+    pthread_t thread;
+    status = pthread_create(&thread, NULL, other_thread, NULL);
+    ERROR_IF(pthread_create, status, != 0);
+    status = pthread_join(thread, NULL);
+    ERROR_IF(pthread_join, status, != 0);
+
+    // A copy can have some of its categories changed, keeping the others
+    locale_t mixed = duplocale(french);
+    ERROR_IF(duplocale, mixed, == NULL);
+    mixed = newlocale(LC_NUMERIC_MASK, "C", mixed);
+    ERROR_IF(newlocale, mixed, == NULL);
+    uselocale(mixed);
+    strftime(out, sizeof(out), "%a", gmtime(&timestamp));
+    printf("mixed: %.1f %s\n", 1.5, out);
+
+    // A failed change leaves the locale as it was
+    printf("failed change: %d\n", newlocale(LC_TIME_MASK, "xx_XX", mixed) == NULL);
+    strftime(out, sizeof(out), "%a", gmtime(&timestamp));
+    printf("kept: %s\n", out);
+
+    uselocale(LC_GLOBAL_LOCALE);
+    printf("back: %.1f\n", 1.5);
+    freelocale(mixed);
+    freelocale(french);
+
+    // A copy of the global locale doesn't follow it
+    locale_t global = duplocale(LC_GLOBAL_LOCALE);
+    ERROR_IF(duplocale, global, == NULL);
+    setlocale(LC_ALL, "fr_FR.UTF-8");
+    uselocale(global);
+    printf("copy of the global locale: %.1f\n", 1.5);
+    uselocale(LC_GLOBAL_LOCALE);
+    printf("global locale: %.1f\n", 1.5);
+    freelocale(global);
+}
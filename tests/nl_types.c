@@ -0,0 +1,112 @@
+#define _POSIX_C_SOURCE 200809L
+#include <errno.h>
+#include <locale.h>
+#include <nl_types.h>
+#include <stdint.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <sys/stat.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+static void put(FILE *file, uint32_t value) {
+    unsigned char bytes[4] = {value >> 24, value >> 16, value >> 8, value};
+    fwrite(bytes, 1, 4, file);
+}
+
+// A catalog with messages 1 and 2 in set 1 and message 5 in set 3
+static void write_catalog(const char *path) {
+    FILE *file = fopen(path, "w");
+    ERROR_IF(fopen, file, == NULL);
+    static const char strings[] = "Bonjour\0Au revoir\0Trois";
+    uint32_t header[] = {0xff88ff89, 2, 84, 24, 60};
+    uint32_t entries[] = {1, 2, 0, 3, 1, 2, 1, 8, 0, 2, 10, 8, 5, 6, 18};
+    for (size_t i = 0; i < sizeof(header) / sizeof(header[0]); i++) {
+        put(file, header[i]);
+    }
+    for (size_t i = 0; i < sizeof(entries) / sizeof(entries[0]); i++) {
+        put(file, entries[i]);
+    }
+    fwrite(strings, 1, sizeof(strings), file);
+    fclose(file);
+}
+
+static void show(nl_catd catd, int set_id, int msg_id) {
+    errno = 0;
+    char *message = catgets(catd, set_id, msg_id, "(default)");
+    printf("%d.%d: %s%s\n", set_id, msg_id, message, errno == ENOMSG ? " (ENOMSG)" : "");
+}
+
+int main(void) {
+    int status = setenv("LOCPATH", "locales", 1);
+    ERROR_IF(setenv, status, == -1);
+
+    char dir[] = "/tmp/catopen-XXXXXX";
+    char *made = mkdtemp(dir);
+    ERROR_IF(mkdtemp, made, == NULL);
+    char path[64], nlspath[128];
+    snprintf(path, sizeof(path), "%s/fr", dir);
+    status = mkdir(path, 0700);
+    ERROR_IF(mkdir, status, == -1);
+    snprintf(path, sizeof(path), "%s/fr/hello.cat", dir);
+    write_catalog(path);
+
+    // A name with a slash is the path of the catalog
+    nl_catd catd = catopen(path, 0);
+    ERROR_IF(catopen, catd, == (nl_catd) -1);
+    show(catd, 1, 1);
+    show(catd, 1, 2);
+    show(catd, 3, 5);
+    show(catd, 1, 3);
+    show(catd, 2, 1);
+    status = catclose(catd);
+    ERROR_IF(catclose, status, != 0);
+
+    // Otherwise it is looked up in NLSPATH, for the language in LANG or LC_MESSAGES
+    snprintf(nlspath, sizeof(nlspath), "%s/%%L/%%N.cat:%s/%%l/%%N.cat", dir, dir);
+    status = setenv("NLSPATH", nlspath, 1);
+    ERROR_IF(setenv, status, == -1);
+    status = setenv("LANG", "fr_FR.UTF-8", 1);
+    ERROR_IF(setenv, status, == -1);
+    catd = catopen("hello", 0);
+    ERROR_IF(catopen, catd, == (nl_catd) -1);
+    show(catd, 1, 2);
+    catclose(catd);
+
+    errno = 0;
+    catd = catopen("hello", NL_CAT_LOCALE);
+    printf("in the C locale: %d %d\n", catd == (nl_catd) -1, errno == ENOENT);
+
+    char *locale = setlocale(LC_ALL, "fr_FR.UTF-8");
+    ERROR_IF(setlocale, locale, == NULL);
+    catd = catopen("hello", NL_CAT_LOCALE);
+    ERROR_IF(catopen, catd, == (nl_catd) -1);
+    show(catd, 3, 5);
+    catclose(catd);
+
+    // Files that aren't catalogs
+    snprintf(path, sizeof(path), "%s/fr/bad.cat", dir);
+    FILE *file = fopen(path, "w");
+    ERROR_IF(fopen, file, == NULL);
+    fputs("not a catalog", file);
+    fclose(file);
+    errno = 0;
+    catd = catopen("bad", NL_CAT_LOCALE);
+    printf("not a catalog: %d %d\n", catd == (nl_catd) -1, errno == EINVAL);
+    status = unlink(path);
+    ERROR_IF(unlink, status, == -1);
+
+    errno = 0;
+    printf("closed: %s %d\n", catgets((nl_catd) -1, 1, 1, "(default)"), errno == EBADF);
+
+    snprintf(path, sizeof(path), "%s/fr/hello.cat", dir);
+    status = unlink(path);
+    ERROR_IF(unlink, status, == -1);
+    snprintf(path, sizeof(path), "%s/fr", dir);
+    status = rmdir(path);
+    ERROR_IF(rmdir, status, == -1);
+    status = rmdir(dir);
+    ERROR_IF(rmdir, status, == -1);
+}
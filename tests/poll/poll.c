@@ -0,0 +1,85 @@
+#include <fcntl.h>
+#include <poll.h>
+#include <stdio.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+static void print_revents(const char *when, struct pollfd *fds, int nfds, int count) {
+    printf("%s: %d", when, count);
+    for (int i = 0; i < nfds; i++) {
+        printf(" %#x", fds[i].revents);
+    }
+    printf("\n");
+}
+
+int main(void) {
+    int a[2], b[2];
+    int status = pipe(a);
+    ERROR_IF(pipe, status, == -1);
+    status = pipe(b);
+    ERROR_IF(pipe, status, == -1);
+
+    // The same array polled repeatedly, as an event loop would
+    struct pollfd fds[4] = {
+        { .fd = a[0], .events = POLLIN },
+        { .fd = b[0], .events = POLLIN },
+        { .fd = -1, .events = POLLIN },
+        { .fd = a[0], .events = POLLIN },
+    };
+    int count = poll(fds, 4, 0);
+    ERROR_IF(poll, count, == -1);
+    print_revents("idle", fds, 4, count);
+
+    ssize_t len = write(a[1], "x", 1);
+    ERROR_IF(write, (int) len, == -1);
+    count = poll(fds, 4, 1000);
+    ERROR_IF(poll, count, == -1);
+    print_revents("a written", fds, 4, count);
+    count = poll(fds, 4, 0);
+    ERROR_IF(poll, count, == -1);
+    print_revents("still unread", fds, 4, count);
+
+    char c;
+    len = read(a[0], &c, 1);
+    ERROR_IF(read, (int) len, == -1);
+    count = poll(fds, 4, 0);
+    ERROR_IF(poll, count, == -1);
+    print_revents("a read", fds, 4, count);
+
+    // Written and read again between calls, it's not ready anymore
+    len = write(a[1], "z", 1);
+    ERROR_IF(write, (int) len, == -1);
+    len = read(a[0], &c, 1);
+    ERROR_IF(read, (int) len, == -1);
+    count = poll(fds, 4, 0);
+    ERROR_IF(poll, count, == -1);
+    print_revents("drained between calls", fds, 4, count);
+
+    // Changing entries between calls
+    fds[1].events = POLLOUT;
+    fds[1].fd = b[1];
+    fds[3].fd = -1;
+    count = poll(fds, 4, 0);
+    ERROR_IF(poll, count, == -1);
+    print_revents("b writable", fds, 4, count);
+
+    // A descriptor replaced by another with the same number
+    close(b[1]);
+    close(b[0]);
+    status = pipe(b);
+    ERROR_IF(pipe, status, == -1);
+    fds[1].fd = b[0];
+    fds[1].events = POLLIN;
+    len = write(b[1], "y", 1);
+    ERROR_IF(write, (int) len, == -1);
+    count = poll(fds, 4, 1000);
+    ERROR_IF(poll, count, == -1);
+    print_revents("new b written", fds, 4, count);
+
+    // A closed descriptor is invalid
+    close(a[0]);
+    count = poll(fds, 4, 1000);
+    ERROR_IF(poll, count, == -1);
+    print_revents("a closed", fds, 4, count);
+}
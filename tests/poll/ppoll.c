@@ -0,0 +1,60 @@
+#define _GNU_SOURCE
+#include <errno.h>
+#include <poll.h>
+#include <signal.h>
+#include <stdio.h>
+#include <time.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+static volatile sig_atomic_t handled = 0;
+
+static void handler(int sig) {
+    (void) sig;
+    handled++;
+}
+
+int main(void) {
+    int fds[2];
+    int status = pipe(fds);
+    ERROR_IF(pipe, status, == -1);
+    status = write(fds[1], "x", 1);
+    ERROR_IF(write, status, == -1);
+
+    struct pollfd pfd = { .fd = fds[0], .events = POLLIN };
+    struct timespec timeout = { .tv_sec = 5, .tv_nsec = 0 };
+    status = ppoll(&pfd, 1, &timeout, NULL);
+    ERROR_IF(ppoll, status, == -1);
+    printf("ready: %d, revents %#x\n", status, pfd.revents);
+
+    struct sigaction act = { 0 };
+    act.sa_handler = handler;
+    status = sigaction(SIGUSR1, &act, NULL);
+    ERROR_IF(sigaction, status, == -1);
+
+    // The signal stays pending until ppoll unblocks it, which then has to interrupt the wait
+    sigset_t block, empty;
+    sigemptyset(&block);
+    sigaddset(&block, SIGUSR1);
+    sigemptyset(&empty);
+    status = sigprocmask(SIG_BLOCK, &block, NULL);
+    ERROR_IF(sigprocmask, status, == -1);
+    status = raise(SIGUSR1);
+    ERROR_IF(raise, status, == -1);
+
+    status = ppoll(NULL, 0, &timeout, &empty);
+    UNEXP_IF(ppoll, status, != -1);
+    UNEXP_IF(ppoll, errno, != EINTR);
+    printf("interrupted, handled %d\n", handled);
+
+    sigset_t current;
+    status = sigprocmask(SIG_BLOCK, NULL, &current);
+    ERROR_IF(sigprocmask, status, == -1);
+    printf("mask restored: %d\n", sigismember(&current, SIGUSR1));
+
+    struct timespec invalid = { .tv_sec = 0, .tv_nsec = 1000000000 };
+    status = ppoll(&pfd, 1, &invalid, NULL);
+    UNEXP_IF(ppoll, status, != -1);
+    UNEXP_IF(ppoll, errno, != EINVAL);
+}
@@ -0,0 +1,59 @@
+#include <pthread.h>
+#include <stdio.h>
+#include <time.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+static int fds[2];
+
+// Blocks in read, one of the cancellation points, a little at a time
+static void *reading(void *arg) {
+    (void)arg;
+    for (;;) {
+        char c;
+        read(fds[0], &c, 1);
+    }
+    return NULL;
+}
+
+// Sleeps, with cancellation disabled until it's done
+static void *disabled(void *arg) {
+    int *finished = arg;
+    int old;
+    pthread_setcancelstate(PTHREAD_CANCEL_DISABLE, &old);
+    struct timespec wait = { 0, 100000000 };
+    nanosleep(&wait, NULL);
+    *finished = 1;
+    pthread_setcancelstate(old, NULL);
+    pthread_testcancel();
+    *finished = 2;
+    return NULL;
+}
+
+int main(void) {
+    int status = pipe(fds);
+    ERROR_IF(pipe, status, == -1);
+
+    pthread_t thread;
+    status = pthread_create(&thread, NULL, reading, NULL);
+    ERROR_IF(pthread_create, status, != 0);
+    status = pthread_cancel(thread);
+    ERROR_IF(pthread_cancel, status, != 0);
+    // Nothing wakes the read, so something has to arrive
+    status = write(fds[1], "x", 1);
+    ERROR_IF(write, status, == -1);
+    void *res;
+    status = pthread_join(thread, &res);
+    ERROR_IF(pthread_join, status, != 0);
+    printf("reading: %s\n", res == PTHREAD_CANCELED ? "cancelled" : "not cancelled");
+
+    int finished = 0;
+    status = pthread_create(&thread, NULL, disabled, &finished);
+    ERROR_IF(pthread_create, status, != 0);
+    status = pthread_cancel(thread);
+    ERROR_IF(pthread_cancel, status, != 0);
+    status = pthread_join(thread, &res);
+    ERROR_IF(pthread_join, status, != 0);
+    printf("disabled: %s, finished %d\n", res == PTHREAD_CANCELED ? "cancelled" : "not cancelled", finished);
+}
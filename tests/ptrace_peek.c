@@ -0,0 +1,57 @@
+#include <errno.h>
+#include <signal.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <sys/ptrace.h>
+#include <sys/wait.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+// The child has this at the same address as the parent, since it's forked
+volatile long value = 1234;
+
+int main(void) {
+    int pid = fork();
+    ERROR_IF(fork, pid, == -1);
+
+    if (pid == 0) {
+        long result = ptrace(PTRACE_TRACEME, 0, NULL, NULL);
+        ERROR_IF(ptrace, result, == -1);
+
+        int status = raise(SIGSTOP);
+        ERROR_IF(raise, status, == -1);
+
+        printf("child sees %ld\n", value);
+        fflush(stdout);
+        _exit(EXIT_SUCCESS);
+    }
+
+    int status;
+    int result = waitpid(pid, &status, 0);
+    ERROR_IF(waitpid, result, == -1);
+    if (!WIFSTOPPED(status)) {
+        puts("child didn't stop");
+        exit(EXIT_FAILURE);
+    }
+
+    errno = 0;
+    long word = ptrace(PTRACE_PEEKDATA, pid, (void *)&value, NULL);
+    if (word == -1 && errno != 0) {
+        perror("ptrace(PTRACE_PEEKDATA)");
+        exit(EXIT_FAILURE);
+    }
+    printf("peeked %ld\n", word);
+    fflush(stdout);
+
+    long ret = ptrace(PTRACE_POKEDATA, pid, (void *)&value, (void *)5678L);
+    ERROR_IF(ptrace, ret, == -1);
+
+    ret = ptrace(PTRACE_CONT, pid, NULL, NULL);
+    ERROR_IF(ptrace, ret, == -1);
+
+    result = waitpid(pid, &status, 0);
+    ERROR_IF(waitpid, result, == -1);
+    printf("child exited with status %d\n", WEXITSTATUS(status));
+    printf("parent still sees %ld\n", value);
+}
@@ -0,0 +1,92 @@
+#define _GNU_SOURCE
+#include <fcntl.h>
+#include <pty.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <sys/ioctl.h>
+#include <sys/wait.h>
+#include <termios.h>
+#include <unistd.h>
+#include <utmp.h>
+
+#include "test_helpers.h"
+
+static void transfer(int from, int to, const char *msg) {
+    ssize_t written = write(from, msg, strlen(msg));
+    ERROR_IF(write, written, == -1);
+    char buf[64] = {0};
+    ssize_t count = read(to, buf, sizeof(buf) - 1);
+    ERROR_IF(read, count, == -1);
+    printf("read %zd: %s", count, buf);
+}
+
+int main(void) {
+    int master = posix_openpt(O_RDWR | O_NOCTTY);
+    ERROR_IF(posix_openpt, master, == -1);
+    int status = grantpt(master);
+    ERROR_IF(grantpt, status, == -1);
+    status = unlockpt(master);
+    ERROR_IF(unlockpt, status, == -1);
+    char *name = ptsname(master);
+    ERROR_IF(ptsname, name, == NULL);
+    char name_r[256];
+    status = ptsname_r(master, name_r, sizeof(name_r));
+    printf("ptsname_r: %d %d\n", status, strcmp(name, name_r) == 0);
+    printf("too small: %d\n", ptsname_r(master, name_r, 2) == ERANGE);
+    int slave = open(name, O_RDWR | O_NOCTTY);
+    ERROR_IF(open, slave, == -1);
+    printf("isatty: %d\n", isatty(slave));
+    int fds[2];
+    status = pipe(fds);
+    ERROR_IF(pipe, status, == -1);
+    printf("not a master: %d\n", ptsname(fds[0]) == NULL);
+    close(fds[0]);
+    close(fds[1]);
+    close(slave);
+    close(master);
+
+    // a slave without line editing gets exactly what's written to the master
+    struct termios raw;
+    int amaster, aslave;
+    char path[256];
+    status = openpty(&amaster, &aslave, path, NULL, NULL);
+    ERROR_IF(openpty, status, == -1);
+    status = tcgetattr(aslave, &raw);
+    ERROR_IF(tcgetattr, status, == -1);
+    raw.c_lflag &= ~(ICANON | ECHO);
+    raw.c_oflag &= ~OPOST;
+    status = tcsetattr(aslave, TCSANOW, &raw);
+    ERROR_IF(tcsetattr, status, == -1);
+    transfer(amaster, aslave, "to slave\n");
+    transfer(aslave, amaster, "to master\n");
+    close(aslave);
+    close(amaster);
+
+    struct winsize size = {.ws_row = 24, .ws_col = 80};
+    fflush(stdout);
+    pid_t pid = forkpty(&amaster, NULL, &raw, &size);
+    ERROR_IF(forkpty, pid, == -1);
+    if (pid == 0) {
+        struct winsize got;
+        ioctl(STDOUT_FILENO, TIOCGWINSZ, &got);
+        printf("child: %d %d %d\n", isatty(STDIN_FILENO), got.ws_row, got.ws_col);
+        fflush(stdout);
+        _exit(0);
+    }
+    char buf[64] = {0};
+    size_t len = 0;
+    ssize_t count;
+    while (len < sizeof(buf) - 1 && (count = read(amaster, buf + len, sizeof(buf) - 1 - len)) > 0) {
+        len += count;
+        if (memchr(buf, '\n', len)) {
+            break;
+        }
+    }
+    printf("from child: %s", buf);
+    int wstatus;
+    status = waitpid(pid, &wstatus, 0);
+    ERROR_IF(waitpid, status, == -1);
+    printf("exited: %d\n", WIFEXITED(wstatus) && WEXITSTATUS(wstatus) == 0);
+    close(amaster);
+}
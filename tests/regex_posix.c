@@ -0,0 +1,134 @@
+#include <regex.h>
+#include <stdio.h>
+#include <string.h>
+
+#include "test_helpers.h"
+
+static const char *error_name(int error) {
+    switch (error) {
+        case 0: return "OK";
+        case REG_NOMATCH: return "REG_NOMATCH";
+        case REG_BADPAT: return "REG_BADPAT";
+        case REG_ECOLLATE: return "REG_ECOLLATE";
+        case REG_ECTYPE: return "REG_ECTYPE";
+        case REG_EESCAPE: return "REG_EESCAPE";
+        case REG_ESUBREG: return "REG_ESUBREG";
+        case REG_EBRACK: return "REG_EBRACK";
+        case REG_EPAREN: return "REG_EPAREN";
+        case REG_EBRACE: return "REG_EBRACE";
+        case REG_BADBR: return "REG_BADBR";
+        case REG_ERANGE: return "REG_ERANGE";
+        case REG_ESPACE: return "REG_ESPACE";
+        case REG_BADRPT: return "REG_BADRPT";
+        default: return "unknown";
+    }
+}
+
+static void test(const char *pattern, int cflags, const char *string, int eflags) {
+    regex_t regex;
+    regmatch_t matches[4];
+
+    printf("%s%s%s \"%s\" \"",
+        cflags & REG_EXTENDED ? "E" : "B",
+        cflags & REG_ICASE ? "I" : "",
+        cflags & REG_NEWLINE ? "N" : "",
+        pattern);
+    for (const char *c = string; *c; c++) {
+        if (*c == '\n') {
+            printf("\\n");
+        } else {
+            putchar(*c);
+        }
+    }
+    printf("\":");
+
+    int error = regcomp(&regex, pattern, cflags);
+    if (error) {
+        printf(" regcomp %s\n", error_name(error));
+        return;
+    }
+
+    error = regexec(&regex, string, 4, matches, eflags);
+    if (error) {
+        printf(" %s\n", error_name(error));
+    } else {
+        for (size_t i = 0; i < 4 && i <= regex.re_nsub; i++) {
+            printf(" (%ld,%ld)", (long) matches[i].rm_so, (long) matches[i].rm_eo);
+        }
+        printf("\n");
+    }
+    regfree(&regex);
+}
+
+int main(void) {
+    // basic expressions
+    test("a.c", 0, "xxabcxx", 0);
+    test("ab*c", 0, "abbbc", 0);
+    test("\\(a*\\)b\\1", 0, "aabaa", 0);
+    test("\\(a*\\)b\\1", 0, "aaba", 0);
+    test("^*a", 0, "*a", 0);
+    test("a^b$c", 0, "a^b$c", 0);
+    test("a\\{2,3\\}", 0, "aaaaa", 0);
+    test("a\\{,2\\}", 0, "aaaaa", 0);
+    test("a\\|b", 0, "b", 0);
+    test("a\\+b\\?", 0, "aaab", 0);
+    test("\\<bar\\>", 0, "foobar bar", 0);
+
+    // extended expressions
+    test("(a|ab)(c|bcd)(d*)", REG_EXTENDED, "abcd", 0);
+    test("(wee|week)(knights|night)", REG_EXTENDED, "weeknights", 0);
+    test("x(a|b|c)+y", REG_EXTENDED, "xabcy", 0);
+    test("(a*)*", REG_EXTENDED, "b", 0);
+    test("(a)|b", REG_EXTENDED, "b", 0);
+    test("(ab){2}", REG_EXTENDED, "ababab", 0);
+    test("([a-c]+)\\1", REG_EXTENDED, "abcabc", 0);
+    test("(\\w+)@(\\w+)\\.com", REG_EXTENDED, "mail bob@ex.com now", 0);
+
+    // brackets
+    test("[[:digit:][:upper:]]+", REG_EXTENDED, "abC1D2e", 0);
+    test("[]a]*", 0, "]a]b", 0);
+    test("[^]a]", 0, "]ab", 0);
+    test("[a-]+", REG_EXTENDED, "a-a", 0);
+    test("[[.a.]-c]+", REG_EXTENDED, "xabc", 0);
+
+    // flags
+    test("[a-c]*\\(b\\)\\1", REG_ICASE, "ABCbB", 0);
+    test("a.c", 0, "a\nc", 0);
+    test("a.c", REG_NEWLINE, "a\nc", 0);
+    test("[^x]", REG_EXTENDED | REG_NEWLINE, "\nb", 0);
+    test("^b", REG_EXTENDED, "a\nb", 0);
+    test("^b", REG_EXTENDED | REG_NEWLINE, "a\nb", 0);
+    test("a$", REG_EXTENDED | REG_NEWLINE, "a\nb", 0);
+    test("^a", REG_EXTENDED, "a", REG_NOTBOL);
+    test("a$", REG_EXTENDED, "a", REG_NOTEOL);
+
+    // errors
+    test("a\\{1", 0, "", 0);
+    test("a\\{1,x\\}", 0, "", 0);
+    test("a\\{3,2\\}", 0, "", 0);
+    test("a**", 0, "", 0);
+    test("\\(a", 0, "", 0);
+    test("a\\)", 0, "", 0);
+    test("\\(a\\)\\2", 0, "", 0);
+    test("[a", 0, "", 0);
+    test("[c-a]", 0, "", 0);
+    test("[[:foo:]]", 0, "", 0);
+    test("a\\", 0, "", 0);
+    test("*a", REG_EXTENDED, "", 0);
+    test("^*", REG_EXTENDED, "", 0);
+    test("a{", REG_EXTENDED, "", 0);
+    test("(a", REG_EXTENDED, "", 0);
+
+    // REG_NOSUB only reports whether it matches
+    regex_t regex;
+    int error = regcomp(&regex, "a(b)c", REG_EXTENDED | REG_NOSUB);
+    ERROR_IF(regcomp, error, != 0);
+    printf("REG_NOSUB: %s\n", error_name(regexec(&regex, "xabcx", 0, NULL, 0)));
+    regfree(&regex);
+
+    // regerror returns the size the whole message needs and truncates it
+    char buf[8];
+    size_t size = regerror(REG_NOMATCH, NULL, NULL, 0);
+    size_t truncated = regerror(REG_NOMATCH, NULL, buf, sizeof(buf));
+    printf("regerror: %d %zu\n", size == truncated && size > sizeof(buf), strlen(buf));
+}
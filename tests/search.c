@@ -0,0 +1,213 @@
+#define _GNU_SOURCE
+#include <errno.h>
+#include <search.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#include "test_helpers.h"
+
+static int compare(const void *a, const void *b) {
+    return *(const int *)a - *(const int *)b;
+}
+
+static int visits = 0;
+
+static void print_node(const void *node, VISIT which, int depth) {
+    (void)depth;
+    visits++;
+    // Every node is visited once in order as either a leaf or between its subtrees
+    if (which == postorder || which == leaf) {
+        printf(" %d", **(const int *const *)node);
+    }
+}
+
+static int freed = 0;
+
+static void free_key(void *key) {
+    (void)key;
+    freed++;
+}
+
+static void test_hsearch(void) {
+    static char *words[] = { "alpha", "beta", "gamma", "delta", "epsilon", "zeta", "eta", "theta" };
+    size_t count = sizeof(words) / sizeof(words[0]);
+
+    int status = hcreate(16);
+    ERROR_IF(hcreate, status, == 0);
+
+    for (size_t i = 0; i < count; i++) {
+        ENTRY item = { words[i], (void *)i };
+        ENTRY *found = hsearch(item, ENTER);
+        ERROR_IF(hsearch, found, == NULL);
+    }
+
+    // A key that's already there keeps its data
+    ENTRY again = { "gamma", (void *)100 };
+    ENTRY *found = hsearch(again, ENTER);
+    printf("gamma: %zu\n", (size_t)found->data);
+
+    for (size_t i = 0; i < count; i++) {
+        char key[16];
+        strcpy(key, words[i]);
+        ENTRY item = { key, NULL };
+        found = hsearch(item, FIND);
+        printf("%s: %zu\n", found->key, (size_t)found->data);
+    }
+
+    ENTRY missing = { "iota", NULL };
+    errno = 0;
+    found = hsearch(missing, FIND);
+    printf("iota: %s, ESRCH: %d\n", found ? "found" : "missing", errno == ESRCH);
+    hdestroy();
+}
+
+static void test_hsearch_r(void) {
+    struct hsearch_data first, second;
+    memset(&first, 0, sizeof(first));
+    memset(&second, 0, sizeof(second));
+
+    int status = hcreate_r(16, &first);
+    ERROR_IF(hcreate_r, status, == 0);
+    status = hcreate_r(16, &second);
+    ERROR_IF(hcreate_r, status, == 0);
+
+    ENTRY *found;
+    ENTRY item = { "key", "first" };
+    status = hsearch_r(item, ENTER, &found, &first);
+    ERROR_IF(hsearch_r, status, == 0);
+    item.data = "second";
+    status = hsearch_r(item, ENTER, &found, &second);
+    ERROR_IF(hsearch_r, status, == 0);
+
+    item.data = NULL;
+    hsearch_r(item, FIND, &found, &first);
+    printf("first: %s\n", (char *)found->data);
+    hsearch_r(item, FIND, &found, &second);
+    printf("second: %s\n", (char *)found->data);
+
+    item.key = "other";
+    status = hsearch_r(item, FIND, &found, &first);
+    printf("other: %d %s\n", status, found ? "found" : "missing");
+
+    hdestroy_r(&first);
+    hdestroy_r(&second);
+}
+
+static void test_tsearch(void) {
+    static int keys[] = { 50, 20, 80, 10, 30, 70, 90, 60, 40, 5, 1, 2, 3, 4, 95, 99 };
+    size_t count = sizeof(keys) / sizeof(keys[0]);
+    void *root = NULL;
+
+    for (size_t i = 0; i < count; i++) {
+        void *node = tsearch(&keys[i], &root, compare);
+        ERROR_IF(tsearch, node, == NULL);
+        if (*(int **)node != &keys[i]) {
+            puts("tsearch returned the wrong node");
+            exit(EXIT_FAILURE);
+        }
+    }
+
+    // Adding an equal key finds the first one
+    int duplicate = 30;
+    void *node = tsearch(&duplicate, &root, compare);
+    printf("duplicate: %d\n", *(int **)node == &keys[4]);
+
+    printf("tree:");
+    visits = 0;
+    twalk(root, print_node);
+    printf("\nvisits: %d\n", visits);
+
+    int key = 60;
+    node = tfind(&key, &root, compare);
+    printf("find 60: %d\n", node ? **(int **)node : -1);
+    key = 65;
+    node = tfind(&key, &root, compare);
+    printf("find 65: %s\n", node ? "found" : "missing");
+
+    static int removed[] = { 50, 1, 90, 30, 65 };
+    for (size_t i = 0; i < sizeof(removed) / sizeof(removed[0]); i++) {
+        node = tdelete(&removed[i], &root, compare);
+        printf("delete %d: %s\n", removed[i], node ? "deleted" : "missing");
+    }
+
+    printf("tree:");
+    twalk(root, print_node);
+    printf("\n");
+
+    for (size_t i = 0; i < count; i++) {
+        node = tfind(&keys[i], &root, compare);
+        if ((node != NULL) != (keys[i] != 50 && keys[i] != 1 && keys[i] != 90 && keys[i] != 30)) {
+            printf("tfind %d is wrong\n", keys[i]);
+            exit(EXIT_FAILURE);
+        }
+    }
+
+    tdestroy(root, free_key);
+    printf("freed: %d\n", freed);
+
+    // Deleting the only node leaves an empty tree
+    root = NULL;
+    tsearch(&keys[0], &root, compare);
+    node = tdelete(&keys[0], &root, compare);
+    printf("last: %s, root: %s\n", node ? "deleted" : "missing", root ? "set" : "null");
+}
+
+static void test_lsearch(void) {
+    int array[8] = { 3, 1, 4 };
+    size_t nel = 3;
+
+    int key = 4;
+    int *found = lfind(&key, array, &nel, sizeof(int), compare);
+    printf("lfind 4: %td\n", found ? found - array : -1);
+    key = 5;
+    found = lfind(&key, array, &nel, sizeof(int), compare);
+    printf("lfind 5: %s\n", found ? "found" : "missing");
+
+    found = lsearch(&key, array, &nel, sizeof(int), compare);
+    printf("lsearch 5: %td, nel: %zu\n", found - array, nel);
+    key = 1;
+    found = lsearch(&key, array, &nel, sizeof(int), compare);
+    printf("lsearch 1: %td, nel: %zu\n", found - array, nel);
+
+    printf("array:");
+    for (size_t i = 0; i < nel; i++) {
+        printf(" %d", array[i]);
+    }
+    printf("\n");
+}
+
+struct element {
+    struct element *next;
+    struct element *prev;
+    int value;
+};
+
+static void test_insque(void) {
+    struct element a = { .value = 1 }, b = { .value = 2 }, c = { .value = 3 };
+
+    insque(&a, NULL);
+    insque(&c, &a);
+    insque(&b, &a);
+
+    printf("list:");
+    for (struct element *e = &a; e; e = e->next) {
+        printf(" %d", e->value);
+    }
+    printf("\n");
+
+    remque(&b);
+    printf("list:");
+    for (struct element *e = &a; e; e = e->next) {
+        printf(" %d", e->value);
+    }
+    printf(", back: %d\n", c.prev->value);
+}
+
+int main(void) {
+    test_hsearch();
+    test_hsearch_r();
+    test_tsearch();
+    test_lsearch();
+    test_insque();
+}
@@ -0,0 +1,65 @@
+// Locales loaded from tests/locales, which LOCPATH points to
+#define _POSIX_C_SOURCE 200809L
+#include <locale.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <time.h>
+
+#include "test_helpers.h"
+
+static void show(const char *name, const char *locale) {
+    printf("%s: %s\n", name, locale ? locale : "(null)");
+}
+
+static void show_time(const char *format) {
+    time_t timestamp = 1531808742;
+    char out[100];
+    size_t n = strftime(out, sizeof(out), format, gmtime(&timestamp));
+    printf("%zu: %s\n", n, out);
+}
+
+int main(void) {
+    int status = setenv("LOCPATH", "locales", 1);
+    ERROR_IF(setenv, status, == -1);
+
+    show("default", setlocale(LC_ALL, NULL));
+    show("missing", setlocale(LC_ALL, "xx_XX"));
+    show("outside", setlocale(LC_ALL, "../locales/fr_FR.utf8"));
+    show("invalid category", setlocale(-1, "C"));
+    show("unchanged", setlocale(LC_ALL, NULL));
+
+    // The codeset is normalized to find the directory, and the name is kept as given
+    show("set", setlocale(LC_ALL, "fr_FR.UTF-8"));
+    printf("%.2f %g %g\n", 3.25, strtod("2,5", NULL), strtod("2.5", NULL));
+    struct lconv *conv = localeconv();
+    printf("decimal point: %s, thousands separator: %zu bytes, grouping: %d %d %d\n",
+           conv->decimal_point, strlen(conv->thousands_sep), conv->grouping[0],
+           conv->grouping[1], conv->grouping[2]);
+    show_time("%A %e %B %Y");
+    show_time("%c");
+    show_time("%x %X");
+
+    // Categories can be in different locales
+    show("numeric", setlocale(LC_NUMERIC, "C"));
+    printf("%.2f\n", 3.25);
+    show_time("%a %b");
+    char *all = strdup(setlocale(LC_ALL, NULL));
+    ERROR_IF(strdup, all, == NULL);
+    show("all", all);
+    show("reset", setlocale(LC_ALL, "POSIX"));
+    show("composite", setlocale(LC_ALL, all));
+    free(all);
+
+    // An empty name takes the locale from the environment
+    status = setenv("LANG", "fr_FR.UTF-8", 1);
+    ERROR_IF(setenv, status, == -1);
+    status = setenv("LC_TIME", "C", 1);
+    ERROR_IF(setenv, status, == -1);
+    setlocale(LC_ALL, "");
+    show("ctype", setlocale(LC_CTYPE, NULL));
+    show("time", setlocale(LC_TIME, NULL));
+    status = setenv("LC_ALL", "C.UTF-8", 1);
+    ERROR_IF(setenv, status, == -1);
+    show("overridden", setlocale(LC_ALL, ""));
+}
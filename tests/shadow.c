@@ -0,0 +1,30 @@
+#define _DEFAULT_SOURCE
+#include <errno.h>
+#include <shadow.h>
+#include <stdio.h>
+
+static void print(const char *line) {
+    errno = 0;
+    struct spwd *sp = sgetspent(line);
+    if (sp == NULL) {
+        puts("invalid");
+        return;
+    }
+    printf("%s %s %ld %ld %ld %ld %ld %ld %ld\n", sp->sp_namp, sp->sp_pwdp, sp->sp_lstchg,
+           sp->sp_min, sp->sp_max, sp->sp_warn, sp->sp_inact, sp->sp_expire, (long) sp->sp_flag);
+}
+
+int main(void) {
+    print("root:$6$salt$hash:19000:0:99999:7:::\n");
+    print("user:!:19001:1:90:14:30:20000:0");
+    print("old:*");
+    print("blank::::::::");
+    print("bad:x:soon::::::");
+    print("nopassword");
+
+    // Either the database can't be read or the user isn't in it
+    errno = 0;
+    struct spwd *sp = getspnam("relibc-no-such-user");
+    printf("missing: %d\n", sp == NULL);
+    return 0;
+}
@@ -0,0 +1,74 @@
+#include <errno.h>
+#include <fcntl.h>
+#include <stdio.h>
+#include <string.h>
+#include <sys/mman.h>
+#include <sys/wait.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+int main(void) {
+    const char *name = "/relibc-shm_open-test";
+    shm_unlink(name);
+
+    int fd = shm_open(name, O_RDWR | O_CREAT | O_EXCL, 0600);
+    ERROR_IF(shm_open, fd, == -1);
+    int flags = fcntl(fd, F_GETFD);
+    ERROR_IF(fcntl, flags, == -1);
+    printf("cloexec: %d\n", (flags & FD_CLOEXEC) != 0);
+    int status = ftruncate(fd, 4096);
+    ERROR_IF(ftruncate, status, == -1);
+
+    errno = 0;
+    int other = shm_open(name, O_RDWR | O_CREAT | O_EXCL, 0600);
+    printf("exclusive: %d\n", other == -1 && errno == EEXIST);
+
+    // Another process opening the object by name shares its pages
+    pid_t pid = fork();
+    ERROR_IF(fork, pid, == -1);
+    if (pid == 0) {
+        int child_fd = shm_open(name, O_RDWR, 0);
+        if (child_fd == -1) {
+            _exit(1);
+        }
+        char *map = mmap(NULL, 4096, PROT_READ | PROT_WRITE, MAP_SHARED, child_fd, 0);
+        if (map == MAP_FAILED) {
+            _exit(1);
+        }
+        strcpy(map, "written by the child");
+        munmap(map, 4096);
+        _exit(0);
+    }
+    int wstatus;
+    status = waitpid(pid, &wstatus, 0);
+    ERROR_IF(waitpid, status, == -1);
+    printf("child: %d\n", WIFEXITED(wstatus) && WEXITSTATUS(wstatus) == 0);
+
+    char *map = mmap(NULL, 4096, PROT_READ, MAP_SHARED, fd, 0);
+    ERROR_IF(mmap, map, == MAP_FAILED);
+    printf("mapped: %s\n", map);
+    status = munmap(map, 4096);
+    ERROR_IF(munmap, status, == -1);
+    status = close(fd);
+    ERROR_IF(close, status, == -1);
+
+    // Leading slashes are optional
+    fd = shm_open(name + 1, O_RDONLY, 0);
+    ERROR_IF(shm_open, fd, == -1);
+    status = close(fd);
+    ERROR_IF(close, status, == -1);
+
+    status = shm_unlink(name);
+    ERROR_IF(shm_unlink, status, == -1);
+    errno = 0;
+    fd = shm_open(name, O_RDWR, 0);
+    printf("unlinked: %d\n", fd == -1 && errno == ENOENT);
+    errno = 0;
+    status = shm_unlink(name);
+    printf("unlinked twice: %d\n", status == -1 && errno == ENOENT);
+
+    errno = 0;
+    fd = shm_open("/relibc/shm_open", O_RDWR | O_CREAT, 0600);
+    printf("slash: %d\n", fd == -1 && errno == EINVAL);
+}
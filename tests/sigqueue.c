@@ -0,0 +1,47 @@
+// sigqueue gives the handler installed with SA_SIGINFO the value, with SI_QUEUE
+#include <signal.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+static volatile sig_atomic_t signo, code, uid, pid;
+static void *volatile value;
+
+static void handler(int sig, siginfo_t *info, void *context) {
+    (void) context;
+    code = info->si_code;
+    value = info->si_value.sival_ptr;
+    pid = info->si_pid;
+    uid = info->si_uid;
+    signo = sig;
+}
+
+int main(void) {
+    struct sigaction sa = { 0 };
+    sa.sa_sigaction = handler;
+    sa.sa_flags = SA_SIGINFO;
+    sigemptyset(&sa.sa_mask);
+    int status = sigaction(SIGUSR2, &sa, NULL);
+    ERROR_IF(sigaction, status, == -1);
+
+    struct sigaction old = { 0 };
+    status = sigaction(SIGUSR2, NULL, &old);
+    ERROR_IF(sigaction, status, == -1);
+    printf("SA_SIGINFO kept: %d, same handler: %d\n", (old.sa_flags & SA_SIGINFO) != 0,
+           old.sa_sigaction == handler);
+
+    static int marker;
+    union sigval sv = { .sival_ptr = &marker };
+    status = sigqueue(getpid(), SIGUSR2, sv);
+    ERROR_IF(sigqueue, status, == -1);
+    while (signo == 0) {
+        usleep(1000);
+    }
+    printf("signal: SIGUSR2 %d, SI_QUEUE %d, value %d, pid %d, uid %d\n", signo == SIGUSR2,
+           code == SI_QUEUE, value == &marker, pid == getpid(), uid == (sig_atomic_t) getuid());
+
+    status = sigqueue(getpid(), 1000, sv);
+    printf("bad signal: %d, EINVAL: %d\n", status, errno == EINVAL);
+}
@@ -0,0 +1,107 @@
+#define _DEFAULT_SOURCE
+#include <errno.h>
+#include <fcntl.h>
+#include <signal.h>
+#include <spawn.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <sys/wait.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+extern char **environ;
+
+static void wait_for(pid_t pid) {
+    int status;
+    pid_t waited = waitpid(pid, &status, 0);
+    ERROR_IF(waitpid, waited, == -1);
+    printf("exited with %d\n", WEXITSTATUS(status));
+}
+
+int main(void) {
+    int ret;
+    pid_t pid;
+
+    // Plain spawn
+    char *echo[] = {"sh", "-c", "echo spawned", NULL};
+    fflush(stdout);
+    ret = posix_spawn(&pid, "/bin/sh", NULL, NULL, echo, environ);
+    UNEXP_IF(posix_spawn, ret, != 0);
+    wait_for(pid);
+
+    // The child's stdout is a pipe, and its stdin is closed
+    int fds[2];
+    ret = pipe(fds);
+    ERROR_IF(pipe, ret, == -1);
+
+    posix_spawn_file_actions_t actions;
+    ret = posix_spawn_file_actions_init(&actions);
+    UNEXP_IF(posix_spawn_file_actions_init, ret, != 0);
+    ret = posix_spawn_file_actions_adddup2(&actions, fds[1], STDOUT_FILENO);
+    UNEXP_IF(posix_spawn_file_actions_adddup2, ret, != 0);
+    ret = posix_spawn_file_actions_addclose(&actions, fds[0]);
+    UNEXP_IF(posix_spawn_file_actions_addclose, ret, != 0);
+    ret = posix_spawn_file_actions_addclose(&actions, fds[1]);
+    UNEXP_IF(posix_spawn_file_actions_addclose, ret, != 0);
+    ret = posix_spawn_file_actions_addopen(&actions, STDIN_FILENO, "/dev/null", O_RDONLY, 0);
+    UNEXP_IF(posix_spawn_file_actions_addopen, ret, != 0);
+    ret = posix_spawn_file_actions_addclose(&actions, -1);
+    printf("addclose(-1): %s\n", ret == EBADF ? "EBADF" : "unexpected");
+
+    char *piped[] = {"sh", "-c", "echo through the pipe; cat", NULL};
+    ret = posix_spawnp(&pid, "sh", &actions, NULL, piped, environ);
+    UNEXP_IF(posix_spawnp, ret, != 0);
+    close(fds[1]);
+
+    char buf[64] = {0};
+    ssize_t total = 0, n;
+    while ((n = read(fds[0], buf + total, sizeof(buf) - 1 - total)) > 0) {
+        total += n;
+    }
+    ERROR_IF(read, n, == -1);
+    close(fds[0]);
+    printf("read: %s", buf);
+    wait_for(pid);
+
+    ret = posix_spawn_file_actions_destroy(&actions);
+    UNEXP_IF(posix_spawn_file_actions_destroy, ret, != 0);
+
+    // Attributes
+    posix_spawnattr_t attr;
+    ret = posix_spawnattr_init(&attr);
+    UNEXP_IF(posix_spawnattr_init, ret, != 0);
+    ret = posix_spawnattr_setflags(&attr, POSIX_SPAWN_SETPGROUP | POSIX_SPAWN_SETSIGMASK);
+    UNEXP_IF(posix_spawnattr_setflags, ret, != 0);
+    short flags;
+    ret = posix_spawnattr_getflags(&attr, &flags);
+    UNEXP_IF(posix_spawnattr_getflags, ret, != 0);
+    printf("flags: %d\n", flags == (POSIX_SPAWN_SETPGROUP | POSIX_SPAWN_SETSIGMASK));
+    ret = posix_spawnattr_setpgroup(&attr, 0);
+    UNEXP_IF(posix_spawnattr_setpgroup, ret, != 0);
+    sigset_t mask;
+    sigemptyset(&mask);
+    sigaddset(&mask, SIGUSR1);
+    ret = posix_spawnattr_setsigmask(&attr, &mask);
+    UNEXP_IF(posix_spawnattr_setsigmask, ret, != 0);
+
+    // The child leads its own group, and SIGUSR1 stays pending until it exits normally
+    char *grouped[] = {"sh", "-c", "kill -USR1 $$; echo in a new group", NULL};
+    fflush(stdout);
+    ret = posix_spawn(&pid, "/bin/sh", NULL, &attr, grouped, environ);
+    UNEXP_IF(posix_spawn, ret, != 0);
+    pid_t pgid = getpgid(pid);
+    wait_for(pid);
+    printf("own group: %d\n", pgid == pid || pgid == -1);
+
+    ret = posix_spawnattr_destroy(&attr);
+    UNEXP_IF(posix_spawnattr_destroy, ret, != 0);
+
+    // Failures come back as the return value, not errno
+    char *missing[] = {"missing", NULL};
+    ret = posix_spawn(&pid, "/nonexistent/program", NULL, NULL, missing, environ);
+    printf("missing: %s\n", ret == ENOENT ? "ENOENT" : strerror(ret));
+    ret = posix_spawnp(&pid, "nonexistent-program", NULL, NULL, missing, environ);
+    printf("missing in PATH: %s\n", ret == ENOENT ? "ENOENT" : strerror(ret));
+}
@@ -0,0 +1,72 @@
+#include <pthread.h>
+#include <stdint.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <sys/wait.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+void __stack_chk_fail(void);
+
+// Where the compiler reads the canary from
+static uintptr_t canary(void) {
+    uintptr_t value;
+#if defined(__x86_64__)
+    __asm__ volatile("mov %%fs:0x28, %0" : "=r"(value));
+#elif defined(__i386__)
+    __asm__ volatile("mov %%gs:0x14, %0" : "=r"(value));
+#else
+    extern uintptr_t __stack_chk_guard;
+    value = __stack_chk_guard;
+#endif
+    return value;
+}
+
+static void *thread_canary(void *arg) {
+    *(uintptr_t *)arg = canary();
+    return NULL;
+}
+
+int main(void) {
+    uintptr_t value = canary();
+    printf("canary set: %s\n", value != 0 && value != (uintptr_t)0xd048c37519fcadfeULL ? "yes" : "no");
+    printf("lowest byte zero: %s\n", (value & 0xff) == 0 ? "yes" : "no");
+
+    uintptr_t in_thread = 0;
+    pthread_t thread;
+    int status = pthread_create(&thread, NULL, thread_canary, &in_thread);
+    ERROR_IF(pthread_create, status, != 0);
+    status = pthread_join(thread, NULL);
+    ERROR_IF(pthread_join, status, != 0);
+    printf("same in a thread: %s\n", in_thread == value ? "yes" : "no");
+
+    int fds[2];
+    status = pipe(fds);
+    ERROR_IF(pipe, status, == -1);
+    pid_t pid = fork();
+    ERROR_IF(fork, pid, == -1);
+    if (pid == 0) {
+        dup2(fds[1], STDERR_FILENO);
+        __stack_chk_fail();
+    }
+    close(fds[1]);
+
+    // Only the first line, abort may report itself after
+    char buf[128];
+    size_t len = 0;
+    while (len < sizeof(buf) - 1) {
+        ssize_t count = read(fds[0], &buf[len], 1);
+        ERROR_IF(read, count, == -1);
+        if (count == 0 || buf[len++] == '\n') {
+            break;
+        }
+    }
+    buf[len] = 0;
+    printf("reported: %s", buf);
+
+    int wstatus;
+    status = waitpid(pid, &wstatus, 0);
+    ERROR_IF(waitpid, status, == -1);
+    printf("killed by a signal: %s\n", WIFSIGNALED(wstatus) ? "yes" : "no");
+}
@@ -0,0 +1,63 @@
+#include <errno.h>
+#include <stdint.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+int main(void) {
+    int *array = reallocarray(NULL, 4, sizeof(int));
+    for (int i = 0; i < 4; i++) {
+        array[i] = i + 1;
+    }
+    array = reallocarray(array, 8, sizeof(int));
+    printf("reallocarray kept: %d\n", array[0] == 1 && array[3] == 4);
+
+    // Volatile, so that the compiler doesn't see the overflow coming
+    volatile size_t huge = SIZE_MAX / 2;
+    errno = 0;
+    int *overflow = reallocarray(array, huge, sizeof(int));
+    printf("overflow: %p, ENOMEM %d\n", (void *) overflow, errno == ENOMEM);
+    if (overflow == NULL) {
+        printf("kept: %d\n", array[3] == 4);
+        free(array);
+    }
+
+    // Growing zeroes what is added
+    unsigned char *bytes = recallocarray(NULL, 0, 16, 1);
+    int zero = 1;
+    for (int i = 0; i < 16; i++) {
+        zero &= bytes[i] == 0;
+    }
+    printf("recallocarray NULL zeroed: %d\n", zero);
+    memset(bytes, 0x55, 16);
+
+    bytes = recallocarray(bytes, 16, 4096, 1);
+    int kept = 1;
+    for (int i = 0; i < 16; i++) {
+        kept &= bytes[i] == 0x55;
+    }
+    zero = 1;
+    for (int i = 16; i < 4096; i++) {
+        zero &= bytes[i] == 0;
+    }
+    printf("recallocarray grow kept: %d, zeroed: %d\n", kept, zero);
+
+    // Shrinking clears what is cut off, whether or not the memory moves
+    memset(bytes, 0x55, 4096);
+    bytes = recallocarray(bytes, 4096, 4000, 1);
+    kept = 1;
+    for (int i = 0; i < 4000; i++) {
+        kept &= bytes[i] == 0x55;
+    }
+    printf("recallocarray shrink kept: %d\n", kept);
+    bytes = recallocarray(bytes, 4000, 8, 1);
+    printf("recallocarray shrink more kept: %d\n", bytes[0] == 0x55 && bytes[7] == 0x55);
+
+    errno = 0;
+    unsigned char *failed = recallocarray(bytes, 8, SIZE_MAX, 2);
+    printf("overflow: %p, ENOMEM %d\n", (void *) failed, errno == ENOMEM);
+    errno = 0;
+    failed = recallocarray(bytes, SIZE_MAX, 1, 2);
+    printf("old overflow: %p, EINVAL %d\n", (void *) failed, errno == EINVAL);
+    free(bytes);
+}
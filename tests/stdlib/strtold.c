@@ -0,0 +1,47 @@
+// long double keeps the 64-bit mantissa of x87 through strtold, printf and scanf
+#include <errno.h>
+#include <float.h>
+#include <stdio.h>
+#include <stdlib.h>
+
+#include "test_helpers.h"
+
+static void show(const char *s) {
+    char *end;
+    errno = 0;
+    long double value = strtold(s, &end);
+    printf("strtold(\"%s\") = %.21Lg, took %d%s\n", s, value, (int) (end - s),
+           errno == ERANGE ? ", ERANGE" : "");
+}
+
+int main(void) {
+    show("0.1");
+    show("  -3.14159265358979323846264338327950288");
+    show("18446744073709551615");
+    show("18446744073709551617");
+    show("0x1.fffffffffffffffep+16383");
+    show("1.2e4932");
+    show("3.6e-4951");
+    show("1e-5000");
+    show("0x.8p-2xyz");
+    show("-infinity");
+    show("nan(123)");
+    show("1e");
+    show("e1");
+
+    // Precision that a double doesn't have, in every notation
+    long double third = 1.0L / 3;
+    printf("%.25Lf\n", third);
+    printf("%.25Le\n", third);
+    printf("%.25Lg %Lg %#Lg %LG\n", third, third, 2.0L, 1e-10L);
+    printf("%Lf %Le\n", LDBL_MAX / 1e4900L, LDBL_MIN);
+    printf("%.0Lf %.0Lf %.0Lf %.1Le\n", 0.5L, 1.5L, 2.5L, 9.95L);
+    printf("[%12.3Lf] [%-12.3Le] [%012.3Lf]\n", -third, third, third);
+    printf("%Lf %Le %LF\n", -strtold("inf", NULL), strtold("nan", NULL), strtold("inf", NULL));
+    printf("%.20f %.20e\n", 0.1, 1e-300);
+
+    long double scanned;
+    int matched = sscanf("2.00000000000000000022", "%Lf", &scanned);
+    ERROR_IF(sscanf, matched, != 1);
+    printf("sscanf %%Lf: %.21Lg\n", scanned);
+}
@@ -0,0 +1,68 @@
+#define _POSIX_C_SOURCE 200809L
+#include <errno.h>
+#include <locale.h>
+#include <monetary.h>
+#include <stdio.h>
+#include <stdlib.h>
+
+#include "test_helpers.h"
+
+static void show(const char *format, double value) {
+    char buf[64];
+    ssize_t len = strfmon(buf, sizeof(buf), format, value);
+    ERROR_IF(strfmon, len, == -1);
+    printf("%s: [%s]\n", format, buf);
+}
+
+static void show_all(void) {
+    // The values of the examples in POSIX
+    double values[] = {123.45, -123.45, 3456.781};
+    const char *formats[] = {"%n", "%11n", "%=*#5n", "%(#5n", "%!(#5n", "%^#5n",
+                             "%#5.0n", "%-14#5.4n", "%i", "%!i"};
+    for (size_t i = 0; i < sizeof(formats) / sizeof(formats[0]); i++) {
+        for (size_t j = 0; j < sizeof(values) / sizeof(values[0]); j++) {
+            show(formats[i], values[j]);
+        }
+    }
+}
+
+int main(void) {
+    int status = setenv("LOCPATH", "locales", 1);
+    ERROR_IF(setenv, status, == -1);
+
+    show("%n", 123.45);
+    show("%n", -1234567.891);
+
+    char *locale = setlocale(LC_ALL, "en_US.UTF-8");
+    ERROR_IF(setlocale, locale, == NULL);
+    show_all();
+
+    char buf[64];
+    ssize_t len = strfmon(buf, sizeof(buf), "%n and %i, 100%%", 1.5, 2.5);
+    printf("%zd [%s]\n", len, buf);
+
+    errno = 0;
+    len = strfmon(buf, 4, "%n", 123.45);
+    printf("too small: %zd %d\n", len, errno == E2BIG);
+    // Not a literal, as compilers check the conversions
+    const char *invalid = "%q";
+    errno = 0;
+    len = strfmon(buf, sizeof(buf), invalid, 123.45);
+    printf("invalid: %zd %d\n", len, errno == EINVAL);
+
+    // In another locale than the current one
+    locale_t fr = newlocale(LC_ALL_MASK, "fr_FR.UTF-8", NULL);
+    ERROR_IF(newlocale, fr, == NULL);
+    len = strfmon_l(buf, sizeof(buf), fr, "%n %i", 1234567.891, -0.5);
+    ERROR_IF(strfmon_l, len, == -1);
+    printf("[%s]\n", buf);
+    freelocale(fr);
+
+    locale = setlocale(LC_ALL, "fr_FR.UTF-8");
+    ERROR_IF(setlocale, locale, == NULL);
+    show("%n", 1234567.891);
+    show("%n", -123.45);
+    show("%!n", 123.45);
+    show("%i", 123.45);
+    show("%^n", 1234567.891);
+}
@@ -0,0 +1,27 @@
+#include <errno.h>
+#include <stdio.h>
+#include <string.h>
+#include <sys/auxv.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+int main(void) {
+    unsigned long pagesz = getauxval(AT_PAGESZ);
+    printf("AT_PAGESZ matches sysconf: %s\n", pagesz == (unsigned long)sysconf(_SC_PAGESIZE) ? "yes" : "no");
+
+    unsigned long random = getauxval(AT_RANDOM);
+    printf("AT_RANDOM: %s\n", random != 0 ? "set" : "not set");
+
+    errno = 0;
+    unsigned long secure = getauxval(AT_SECURE);
+    printf("AT_SECURE: %lu, errno %d\n", secure, errno);
+
+    const char *execfn = (const char *)getauxval(AT_EXECFN);
+    printf("AT_EXECFN ends with getauxval: %s\n",
+        execfn != NULL && strlen(execfn) >= 9 && strcmp(execfn + strlen(execfn) - 9, "getauxval") == 0 ? "yes" : "no");
+
+    errno = 0;
+    unsigned long missing = getauxval(12345);
+    printf("missing: %lu, %s\n", missing, errno == ENOENT ? "ENOENT" : "other error");
+}
@@ -0,0 +1,103 @@
+#include <errno.h>
+#include <stdint.h>
+#include <stdio.h>
+#include <sys/epoll.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+static int wait_one(int epfd, uint64_t *data) {
+    struct epoll_event event;
+    int count = epoll_wait(epfd, &event, 1, 0);
+    ERROR_IF(epoll_wait, count, == -1);
+    if (count == 1 && data) {
+        *data = event.data.u64;
+    }
+    return count;
+}
+
+static void add(int epfd, int fd, uint32_t events, uint64_t data) {
+    struct epoll_event event = { .events = events, .data.u64 = data };
+    int status = epoll_ctl(epfd, EPOLL_CTL_ADD, fd, &event);
+    ERROR_IF(epoll_ctl, status, == -1);
+}
+
+int main(void) {
+    int status;
+    int epfd = epoll_create1(EPOLL_CLOEXEC);
+    ERROR_IF(epoll_create1, epfd, == -1);
+
+    int level[2], edge[2], oneshot[2];
+    status = pipe(level);
+    ERROR_IF(pipe, status, == -1);
+    status = pipe(edge);
+    ERROR_IF(pipe, status, == -1);
+    status = pipe(oneshot);
+    ERROR_IF(pipe, status, == -1);
+    char c;
+
+    // Level-triggered: reported for as long as there is data left
+    uint64_t data = 0;
+    add(epfd, level[0], EPOLLIN, 0x0123456789abcdefULL);
+    status = write(level[1], "ab", 2);
+    ERROR_IF(write, status, == -1);
+    printf("level: %d", wait_one(epfd, &data));
+    printf(" (data %s)", data == 0x0123456789abcdefULL ? "kept" : "lost");
+    status = read(level[0], &c, 1);
+    ERROR_IF(read, status, == -1);
+    printf(", %d", wait_one(epfd, NULL));
+    status = read(level[0], &c, 1);
+    ERROR_IF(read, status, == -1);
+    printf(", %d\n", wait_one(epfd, NULL));
+    status = epoll_ctl(epfd, EPOLL_CTL_DEL, level[0], NULL);
+    ERROR_IF(epoll_ctl, status, == -1);
+
+    // Edge-triggered: reported once per new arrival
+    add(epfd, edge[0], EPOLLIN | EPOLLET, 2);
+    status = write(edge[1], "ab", 2);
+    ERROR_IF(write, status, == -1);
+    printf("edge: %d", wait_one(epfd, NULL));
+    printf(", %d", wait_one(epfd, NULL));
+    status = write(edge[1], "c", 1);
+    ERROR_IF(write, status, == -1);
+    printf(", %d\n", wait_one(epfd, NULL));
+    status = epoll_ctl(epfd, EPOLL_CTL_DEL, edge[0], NULL);
+    ERROR_IF(epoll_ctl, status, == -1);
+
+    // One-shot: disabled after the first report, until rearmed
+    add(epfd, oneshot[0], EPOLLIN | EPOLLONESHOT, 3);
+    status = write(oneshot[1], "a", 1);
+    ERROR_IF(write, status, == -1);
+    printf("oneshot: %d", wait_one(epfd, NULL));
+    status = write(oneshot[1], "b", 1);
+    ERROR_IF(write, status, == -1);
+    printf(", %d", wait_one(epfd, NULL));
+    struct epoll_event rearm = { .events = EPOLLIN | EPOLLONESHOT, .data.u64 = 3 };
+    status = epoll_ctl(epfd, EPOLL_CTL_MOD, oneshot[0], &rearm);
+    ERROR_IF(epoll_ctl, status, == -1);
+    printf(", %d\n", wait_one(epfd, NULL));
+
+    status = epoll_ctl(epfd, EPOLL_CTL_MOD, level[0], &rearm);
+    UNEXP_IF(epoll_ctl, status, != -1);
+    UNEXP_IF(epoll_ctl, errno, != ENOENT);
+    status = epoll_ctl(epfd, EPOLL_CTL_DEL, level[0], NULL);
+    UNEXP_IF(epoll_ctl, status, != -1);
+    UNEXP_IF(epoll_ctl, errno, != ENOENT);
+    status = epoll_create1(1);
+    UNEXP_IF(epoll_create1, status, != -1);
+    UNEXP_IF(epoll_create1, errno, != EINVAL);
+
+    // A duplicate is the same instance, which outlives it
+    int dup_epfd = dup(epfd);
+    ERROR_IF(dup, dup_epfd, == -1);
+    add(dup_epfd, level[0], EPOLLIN, 4);
+    status = write(level[1], "a", 1);
+    ERROR_IF(write, status, == -1);
+    printf("dup: %d", wait_one(epfd, &data));
+    printf(" (data %llu)", (unsigned long long) data);
+    status = close(dup_epfd);
+    ERROR_IF(close, status, == -1);
+    printf(", %d\n", wait_one(epfd, NULL));
+
+    close(epfd);
+}
@@ -0,0 +1,50 @@
+#include <errno.h>
+#include <signal.h>
+#include <stdio.h>
+#include <string.h>
+#include <sys/epoll.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+static volatile sig_atomic_t handled = 0;
+
+static void handler(int sig) {
+    handled = sig;
+}
+
+int main(void) {
+    int epfd = epoll_create1(EPOLL_CLOEXEC);
+    ERROR_IF(epoll_create1, epfd, == -1);
+
+    struct sigaction act;
+    memset(&act, 0, sizeof(act));
+    act.sa_handler = handler;
+    int status = sigaction(SIGUSR1, &act, NULL);
+    ERROR_IF(sigaction, status, == -1);
+
+    // Pending when the wait begins, the signal the mask unblocks interrupts it, rather than being
+    // handled before it blocks, which would then wait forever
+    sigset_t blocked, unblocked;
+    sigemptyset(&blocked);
+    sigaddset(&blocked, SIGUSR1);
+    status = sigprocmask(SIG_BLOCK, &blocked, &unblocked);
+    ERROR_IF(sigprocmask, status, == -1);
+    status = raise(SIGUSR1);
+    ERROR_IF(raise, status, == -1);
+    printf("handled before: %d\n", handled == SIGUSR1);
+
+    struct epoll_event event;
+    errno = 0;
+    int count = epoll_pwait(epfd, &event, 1, -1, &unblocked);
+    printf("epoll_pwait: %d, %s\n", count, errno == EINTR ? "EINTR" : "other error");
+    printf("handled after: %d\n", handled == SIGUSR1);
+
+    // The mask is restored after the wait
+    sigset_t current;
+    status = sigprocmask(SIG_SETMASK, NULL, &current);
+    ERROR_IF(sigprocmask, status, == -1);
+    printf("blocked after: %d\n", sigismember(&current, SIGUSR1));
+
+    close(epfd);
+}
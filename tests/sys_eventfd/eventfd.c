@@ -0,0 +1,81 @@
+#include <errno.h>
+#include <fcntl.h>
+#include <poll.h>
+#include <stdint.h>
+#include <stdio.h>
+#include <sys/eventfd.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+static int readable(int fd) {
+    struct pollfd pfd = { .fd = fd, .events = POLLIN };
+    int status = poll(&pfd, 1, 0);
+    ERROR_IF(poll, status, == -1);
+    return status;
+}
+
+int main(void) {
+    int fd = eventfd(3, EFD_NONBLOCK | EFD_CLOEXEC);
+    ERROR_IF(eventfd, fd, == -1);
+    int flags = fcntl(fd, F_GETFD);
+    ERROR_IF(fcntl, flags, == -1);
+    printf("cloexec: %d, readable: %d\n", (flags & FD_CLOEXEC) != 0, readable(fd));
+
+    int status = eventfd_write(fd, 4);
+    ERROR_IF(eventfd_write, status, == -1);
+    eventfd_t value;
+    status = eventfd_read(fd, &value);
+    ERROR_IF(eventfd_read, status, == -1);
+    printf("counter: %llu, readable: %d\n", (unsigned long long) value, readable(fd));
+
+    status = eventfd_read(fd, &value);
+    UNEXP_IF(eventfd_read, status, != -1);
+    UNEXP_IF(eventfd_read, errno, != EAGAIN);
+
+    uint64_t max = UINT64_MAX;
+    ssize_t count = write(fd, &max, sizeof(max));
+    UNEXP_IF(write, (int) count, != -1);
+    UNEXP_IF(write, errno, != EINVAL);
+    char small[4];
+    count = read(fd, small, sizeof(small));
+    UNEXP_IF(read, (int) count, != -1);
+    UNEXP_IF(read, errno, != EINVAL);
+    close(fd);
+
+    fd = eventfd(2, EFD_SEMAPHORE);
+    ERROR_IF(eventfd, fd, == -1);
+    for (int i = 0; i < 2; i++) {
+        status = eventfd_read(fd, &value);
+        ERROR_IF(eventfd_read, status, == -1);
+        printf("semaphore: %llu, readable: %d\n", (unsigned long long) value, readable(fd));
+    }
+
+    // A duplicate shares the counter
+    int dup_fd = dup(fd);
+    ERROR_IF(dup, dup_fd, == -1);
+    status = eventfd_write(dup_fd, 1);
+    ERROR_IF(eventfd_write, status, == -1);
+    close(dup_fd);
+    status = eventfd_read(fd, &value);
+    ERROR_IF(eventfd_read, status, == -1);
+    printf("after dup: %llu\n", (unsigned long long) value);
+
+    // So does one from fcntl
+    dup_fd = fcntl(fd, F_DUPFD_CLOEXEC, 10);
+    ERROR_IF(fcntl, dup_fd, == -1);
+    flags = fcntl(dup_fd, F_GETFD);
+    ERROR_IF(fcntl, flags, == -1);
+    status = eventfd_write(dup_fd, 2);
+    ERROR_IF(eventfd_write, status, == -1);
+    close(dup_fd);
+    status = eventfd_read(fd, &value);
+    ERROR_IF(eventfd_read, status, == -1);
+    printf("after F_DUPFD_CLOEXEC: %llu, cloexec: %d\n", (unsigned long long) value,
+        (flags & FD_CLOEXEC) != 0);
+    close(fd);
+
+    fd = eventfd(0, 0x40000000);
+    UNEXP_IF(eventfd, fd, != -1);
+    UNEXP_IF(eventfd, errno, != EINVAL);
+}
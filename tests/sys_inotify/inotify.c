@@ -0,0 +1,128 @@
+#include <errno.h>
+#include <fcntl.h>
+#include <limits.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <sys/inotify.h>
+#include <sys/stat.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+static const char *event_name(uint32_t mask) {
+    switch (mask & ~IN_ISDIR) {
+    case IN_CREATE: return "IN_CREATE";
+    case IN_DELETE: return "IN_DELETE";
+    case IN_MODIFY: return "IN_MODIFY";
+    case IN_ATTRIB: return "IN_ATTRIB";
+    case IN_MOVED_FROM: return "IN_MOVED_FROM";
+    case IN_MOVED_TO: return "IN_MOVED_TO";
+    case IN_DELETE_SELF: return "IN_DELETE_SELF";
+    case IN_IGNORED: return "IN_IGNORED";
+    default: return "?";
+    }
+}
+
+// Waits for the next events and prints them
+static void print_events(int fd, int dir_wd) {
+    _Alignas(struct inotify_event) char buf[4096];
+    ssize_t len = read(fd, buf, sizeof(buf));
+    ERROR_IF(read, (int) len, == -1);
+
+    uint32_t cookie = 0;
+    for (char *p = buf; p < buf + len;) {
+        struct inotify_event *event = (struct inotify_event *) p;
+        printf("%s %s%s%s", event->wd == dir_wd ? "dir" : "file", event_name(event->mask),
+               event->len > 0 ? " " : "", event->len > 0 ? event->name : "");
+        if (event->mask & IN_ISDIR) {
+            printf(" (dir)");
+        }
+        if (event->mask & IN_MOVED_FROM) {
+            cookie = event->cookie;
+        }
+        if (event->mask & IN_MOVED_TO) {
+            printf(" (same cookie: %d)", cookie != 0 && event->cookie == cookie);
+        }
+        printf("\n");
+        p += sizeof(struct inotify_event) + event->len;
+    }
+}
+
+int main(void) {
+    char dir[] = "/tmp/inotify-XXXXXX";
+    char *mktemp_status = mktemp(dir);
+    ERROR_IF(mktemp, mktemp_status, == NULL);
+    int status = mkdir(dir, S_IRWXU);
+    ERROR_IF(mkdir, status, == -1);
+    char a[PATH_MAX], b[PATH_MAX], sub[PATH_MAX];
+    snprintf(a, sizeof(a), "%s/a", dir);
+    snprintf(b, sizeof(b), "%s/b", dir);
+    snprintf(sub, sizeof(sub), "%s/sub", dir);
+
+    int fd = inotify_init1(IN_NONBLOCK | IN_CLOEXEC);
+    ERROR_IF(inotify_init1, fd, == -1);
+    int dir_wd = inotify_add_watch(fd, dir, IN_CREATE | IN_DELETE | IN_MODIFY | IN_MOVE
+                                   | IN_DELETE_SELF);
+    ERROR_IF(inotify_add_watch, dir_wd, == -1);
+
+    char buf[4096];
+    ssize_t len = read(fd, buf, sizeof(buf));
+    UNEXP_IF(read, (int) len, != -1);
+    UNEXP_IF(read, errno, != EAGAIN);
+
+    // Reads block from here on
+    int flags = fcntl(fd, F_GETFL);
+    ERROR_IF(fcntl, flags, == -1);
+    status = fcntl(fd, F_SETFL, flags & ~O_NONBLOCK);
+    ERROR_IF(fcntl, status, == -1);
+
+    int file = open(a, O_CREAT | O_WRONLY, 0644);
+    ERROR_IF(open, file, == -1);
+    print_events(fd, dir_wd);
+
+    len = write(file, "hello", 5);
+    ERROR_IF(write, (int) len, == -1);
+    close(file);
+    print_events(fd, dir_wd);
+
+    status = rename(a, b);
+    ERROR_IF(rename, status, == -1);
+    // Too small for the pending events
+    len = read(fd, buf, sizeof(struct inotify_event));
+    UNEXP_IF(read, (int) len, != -1);
+    UNEXP_IF(read, errno, != EINVAL);
+    print_events(fd, dir_wd);
+
+    int file_wd = inotify_add_watch(fd, b, IN_ATTRIB);
+    ERROR_IF(inotify_add_watch, file_wd, == -1);
+    status = chmod(b, 0600);
+    ERROR_IF(chmod, status, == -1);
+    print_events(fd, dir_wd);
+    status = inotify_rm_watch(fd, file_wd);
+    ERROR_IF(inotify_rm_watch, status, == -1);
+    print_events(fd, dir_wd);
+    status = inotify_rm_watch(fd, file_wd);
+    UNEXP_IF(inotify_rm_watch, status, != -1);
+    UNEXP_IF(inotify_rm_watch, errno, != EINVAL);
+
+    status = mkdir(sub, S_IRWXU);
+    ERROR_IF(mkdir, status, == -1);
+    print_events(fd, dir_wd);
+    status = rmdir(sub);
+    ERROR_IF(rmdir, status, == -1);
+    print_events(fd, dir_wd);
+
+    status = unlink(b);
+    ERROR_IF(unlink, status, == -1);
+    print_events(fd, dir_wd);
+
+    status = rmdir(dir);
+    ERROR_IF(rmdir, status, == -1);
+    print_events(fd, dir_wd);
+
+    status = inotify_add_watch(fd, dir, IN_ALL_EVENTS);
+    UNEXP_IF(inotify_add_watch, status, != -1);
+    UNEXP_IF(inotify_add_watch, errno, != ENOENT);
+    close(fd);
+}
@@ -0,0 +1,87 @@
+#include <errno.h>
+#include <stdio.h>
+#include <string.h>
+#include <sys/ipc.h>
+#include <sys/msg.h>
+#include <sys/wait.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+struct message {
+    long mtype;
+    char mtext[32];
+};
+
+static int send_message(int id, long mtype, const char *text) {
+    struct message message = { .mtype = mtype };
+    strcpy(message.mtext, text);
+    return msgsnd(id, &message, strlen(text) + 1, 0);
+}
+
+int main(void) {
+    int id = msgget(IPC_PRIVATE, IPC_CREAT | 0600);
+    ERROR_IF(msgget, id, == -1);
+
+    int status = send_message(id, 2, "two");
+    ERROR_IF(msgsnd, status, == -1);
+    status = send_message(id, 1, "one");
+    ERROR_IF(msgsnd, status, == -1);
+    status = send_message(id, 3, "three");
+    ERROR_IF(msgsnd, status, == -1);
+
+    struct msqid_ds ds;
+    status = msgctl(id, IPC_STAT, &ds);
+    ERROR_IF(msgctl, status, == -1);
+    printf("queued: %lu\n", (unsigned long) ds.msg_qnum);
+
+    // By type, then the lowest type up to a limit, then the first
+    struct message message;
+    ssize_t len = msgrcv(id, &message, sizeof(message.mtext), 3, 0);
+    ERROR_IF(msgrcv, len, == -1);
+    printf("type 3: %ld %s %zd\n", message.mtype, message.mtext, len);
+    len = msgrcv(id, &message, sizeof(message.mtext), -2, 0);
+    ERROR_IF(msgrcv, len, == -1);
+    printf("up to 2: %ld %s\n", message.mtype, message.mtext);
+    len = msgrcv(id, &message, sizeof(message.mtext), 0, 0);
+    ERROR_IF(msgrcv, len, == -1);
+    printf("first: %ld %s\n", message.mtype, message.mtext);
+
+    errno = 0;
+    len = msgrcv(id, &message, sizeof(message.mtext), 0, IPC_NOWAIT);
+    printf("empty: %d\n", len == -1 && errno == ENOMSG);
+
+    // Too long for the buffer, unless truncating is fine
+    status = send_message(id, 4, "a longer message");
+    ERROR_IF(msgsnd, status, == -1);
+    errno = 0;
+    len = msgrcv(id, &message, 4, 0, 0);
+    printf("too long: %d\n", len == -1 && errno == E2BIG);
+    len = msgrcv(id, &message, 8, 0, MSG_NOERROR);
+    printf("truncated: %zd %.8s\n", len, message.mtext);
+
+    // Waiting for a child's message
+    pid_t pid = fork();
+    ERROR_IF(fork, pid, == -1);
+    if (pid == 0) {
+        usleep(100000);
+        _exit(send_message(id, 5, "from the child") == 0 ? 0 : 1);
+    }
+    len = msgrcv(id, &message, sizeof(message.mtext), 5, 0);
+    ERROR_IF(msgrcv, len, == -1);
+    printf("received: %s\n", message.mtext);
+    int wstatus;
+    status = waitpid(pid, &wstatus, 0);
+    ERROR_IF(waitpid, status, == -1);
+    printf("child: %d\n", WIFEXITED(wstatus) && WEXITSTATUS(wstatus) == 0);
+
+    errno = 0;
+    status = send_message(id, 0, "no type");
+    printf("invalid type: %d\n", status == -1 && errno == EINVAL);
+
+    status = msgctl(id, IPC_RMID, NULL);
+    ERROR_IF(msgctl, status, == -1);
+    errno = 0;
+    status = send_message(id, 1, "removed");
+    printf("removed: %d\n", status == -1 && (errno == EINVAL || errno == EIDRM));
+}
@@ -0,0 +1,56 @@
+#include <errno.h>
+#include <signal.h>
+#include <stdio.h>
+#include <sys/select.h>
+#include <time.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+static volatile sig_atomic_t handled = 0;
+
+static void handler(int sig) {
+    (void) sig;
+    handled++;
+}
+
+int main(void) {
+    int fds[2];
+    int status = pipe(fds);
+    ERROR_IF(pipe, status, == -1);
+    status = write(fds[1], "x", 1);
+    ERROR_IF(write, status, == -1);
+
+    fd_set read_fds;
+    FD_ZERO(&read_fds);
+    FD_SET(fds[0], &read_fds);
+    struct timespec timeout = { .tv_sec = 5, .tv_nsec = 0 };
+    status = pselect(fds[0] + 1, &read_fds, NULL, NULL, &timeout, NULL);
+    ERROR_IF(pselect, status, == -1);
+    printf("ready: %d, set %d\n", status, FD_ISSET(fds[0], &read_fds) != 0);
+
+    struct sigaction act = { 0 };
+    act.sa_handler = handler;
+    status = sigaction(SIGUSR1, &act, NULL);
+    ERROR_IF(sigaction, status, == -1);
+
+    // The signal stays pending until pselect unblocks it, which then has to interrupt the wait
+    sigset_t block, empty;
+    sigemptyset(&block);
+    sigaddset(&block, SIGUSR1);
+    sigemptyset(&empty);
+    status = sigprocmask(SIG_BLOCK, &block, NULL);
+    ERROR_IF(sigprocmask, status, == -1);
+    status = raise(SIGUSR1);
+    ERROR_IF(raise, status, == -1);
+
+    status = pselect(0, NULL, NULL, NULL, &timeout, &empty);
+    UNEXP_IF(pselect, status, != -1);
+    UNEXP_IF(pselect, errno, != EINTR);
+    printf("interrupted, handled %d\n", handled);
+
+    sigset_t current;
+    status = sigprocmask(SIG_BLOCK, NULL, &current);
+    ERROR_IF(sigprocmask, status, == -1);
+    printf("mask restored: %d\n", sigismember(&current, SIGUSR1));
+}
@@ -0,0 +1,73 @@
+#include <errno.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <sys/resource.h>
+#include <sys/select.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+#define LARGE_FD 1500
+
+int main(void) {
+    int fds[2];
+    int status = pipe(fds);
+    ERROR_IF(pipe, status, == -1);
+
+    // Descriptors past FD_SETSIZE, in a set allocated large enough for them
+    struct rlimit limit;
+    status = getrlimit(RLIMIT_NOFILE, &limit);
+    if (status == 0 && limit.rlim_cur <= LARGE_FD && limit.rlim_max > LARGE_FD) {
+        limit.rlim_cur = limit.rlim_max;
+        setrlimit(RLIMIT_NOFILE, &limit);
+    }
+    int large = dup2(fds[0], LARGE_FD);
+    ERROR_IF(dup2, large, == -1);
+    fd_set *set = calloc(2, sizeof(fd_set));
+    ERROR_IF(calloc, set, == NULL);
+    FD_SET(large, set);
+    status = write(fds[1], "x", 1);
+    ERROR_IF(write, status, == -1);
+    status = select(large + 1, set, NULL, NULL, NULL);
+    ERROR_IF(select, status, == -1);
+    printf("large: %d, set %d\n", status, FD_ISSET(large, set) != 0);
+    free(set);
+    close(large);
+
+    // The timeout is left with the time that was not slept
+    fd_set read_fds;
+    FD_ZERO(&read_fds);
+    FD_SET(fds[0], &read_fds);
+    struct timeval timeout = { .tv_sec = 5, .tv_usec = 0 };
+    status = select(fds[0] + 1, &read_fds, NULL, NULL, &timeout);
+    ERROR_IF(select, status, == -1);
+    printf("ready: %d, time left: %d\n", status, timeout.tv_sec >= 4);
+
+    char c;
+    status = read(fds[0], &c, 1);
+    ERROR_IF(read, status, == -1);
+    FD_ZERO(&read_fds);
+    FD_SET(fds[0], &read_fds);
+    timeout.tv_sec = 0;
+    timeout.tv_usec = 50000;
+    status = select(fds[0] + 1, &read_fds, NULL, NULL, &timeout);
+    ERROR_IF(select, status, == -1);
+    printf("timed out: %d, set %d, time left: %ld %ld\n", status,
+           FD_ISSET(fds[0], &read_fds) != 0, (long) timeout.tv_sec, (long) timeout.tv_usec);
+
+    timeout.tv_sec = -1;
+    status = select(0, NULL, NULL, NULL, &timeout);
+    UNEXP_IF(select, status, != -1);
+    UNEXP_IF(select, errno, != EINVAL);
+
+    // A closed descriptor is an error rather than never ready
+    close(fds[0]);
+    FD_ZERO(&read_fds);
+    FD_SET(fds[0], &read_fds);
+    timeout.tv_sec = 5;
+    timeout.tv_usec = 0;
+    status = select(fds[0] + 1, &read_fds, NULL, NULL, &timeout);
+    UNEXP_IF(select, status, != -1);
+    UNEXP_IF(select, errno, != EBADF);
+    close(fds[1]);
+}
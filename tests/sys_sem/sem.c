@@ -0,0 +1,120 @@
+#define _GNU_SOURCE
+#include <errno.h>
+#include <stdio.h>
+#include <sys/ipc.h>
+#include <sys/sem.h>
+#include <sys/wait.h>
+#include <time.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+union semun {
+    int val;
+    struct semid_ds *buf;
+    unsigned short *array;
+};
+
+int main(void) {
+    int id = semget(IPC_PRIVATE, 2, IPC_CREAT | 0600);
+    ERROR_IF(semget, id, == -1);
+
+    union semun arg;
+    unsigned short values[2] = {1, 0};
+    arg.array = values;
+    int status = semctl(id, 0, SETALL, arg);
+    ERROR_IF(semctl, status, == -1);
+    printf("values: %d %d\n", semctl(id, 0, GETVAL), semctl(id, 1, GETVAL));
+
+    // Operations are done all at once, or not at all
+    struct sembuf ops[2] = {
+        { .sem_num = 0, .sem_op = -1, .sem_flg = 0 },
+        { .sem_num = 1, .sem_op = -1, .sem_flg = IPC_NOWAIT },
+    };
+    errno = 0;
+    status = semop(id, ops, 2);
+    printf("would block: %d\n", status == -1 && errno == EAGAIN);
+    printf("unchanged: %d %d\n", semctl(id, 0, GETVAL), semctl(id, 1, GETVAL));
+
+    // A child posting the second semaphore lets the operations through
+    pid_t pid = fork();
+    ERROR_IF(fork, pid, == -1);
+    if (pid == 0) {
+        struct sembuf post = { .sem_num = 1, .sem_op = 1, .sem_flg = 0 };
+        usleep(100000);
+        _exit(semop(id, &post, 1) == 0 ? 0 : 1);
+    }
+    ops[1].sem_flg = 0;
+    status = semop(id, ops, 2);
+    ERROR_IF(semop, status, == -1);
+    printf("values: %d %d\n", semctl(id, 0, GETVAL), semctl(id, 1, GETVAL));
+    printf("last pid: %d\n", semctl(id, 1, GETPID) == getpid());
+    int wstatus;
+    status = waitpid(pid, &wstatus, 0);
+    ERROR_IF(waitpid, status, == -1);
+    printf("child: %d\n", WIFEXITED(wstatus) && WEXITSTATUS(wstatus) == 0);
+
+    // Waiting for zero
+    arg.val = 1;
+    status = semctl(id, 0, SETVAL, arg);
+    ERROR_IF(semctl, status, == -1);
+    struct sembuf zero = { .sem_num = 0, .sem_op = 0, .sem_flg = 0 };
+    struct timespec timeout = { .tv_sec = 0, .tv_nsec = 10000000 };
+    errno = 0;
+    status = semtimedop(id, &zero, 1, &timeout);
+    printf("timed out: %d\n", status == -1 && errno == EAGAIN);
+
+    errno = 0;
+    arg.val = 40000;
+    status = semctl(id, 0, SETVAL, arg);
+    printf("out of range: %d\n", status == -1 && errno == ERANGE);
+    struct sembuf outside = { .sem_num = 2, .sem_op = 1, .sem_flg = 0 };
+    errno = 0;
+    status = semop(id, &outside, 1);
+    printf("outside the set: %d\n", status == -1 && errno == EFBIG);
+
+    // What a child does with SEM_UNDO is undone when it exits, unless SETVAL came after
+    arg.val = 3;
+    status = semctl(id, 0, SETVAL, arg);
+    ERROR_IF(semctl, status, == -1);
+    for (int setval = 0; setval < 2; setval++) {
+        fflush(stdout);
+        pid = fork();
+        ERROR_IF(fork, pid, == -1);
+        if (pid == 0) {
+            struct sembuf take[2] = {
+                { .sem_num = 0, .sem_op = -2, .sem_flg = SEM_UNDO },
+                { .sem_num = 1, .sem_op = 1, .sem_flg = 0 },
+            };
+            if (semop(id, take, 2) == -1) {
+                _exit(1);
+            }
+            if (setval) {
+                arg.val = 2;
+                if (semctl(id, 0, SETVAL, arg) == -1) {
+                    _exit(1);
+                }
+            }
+            printf("child%s: %d %d\n", setval ? " after SETVAL" : "", semctl(id, 0, GETVAL),
+                   semctl(id, 1, GETVAL));
+            fflush(stdout);
+            _exit(0);
+        }
+        status = waitpid(pid, &wstatus, 0);
+        ERROR_IF(waitpid, status, == -1);
+        printf("after the child: %d %d, exited: %d\n", semctl(id, 0, GETVAL), semctl(id, 1, GETVAL),
+               WIFEXITED(wstatus) && WEXITSTATUS(wstatus) == 0);
+    }
+
+    struct semid_ds ds;
+    arg.buf = &ds;
+    status = semctl(id, 0, IPC_STAT, arg);
+    ERROR_IF(semctl, status, == -1);
+    printf("semaphores: %lu, mode: %o\n", (unsigned long) ds.sem_nsems, ds.sem_perm.mode & 0777);
+
+    status = semctl(id, 0, IPC_RMID);
+    ERROR_IF(semctl, status, == -1);
+    errno = 0;
+    status = semctl(id, 0, GETVAL);
+    printf("removed: %d\n", status == -1 && (errno == EINVAL || errno == EIDRM));
+}
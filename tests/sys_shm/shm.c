@@ -0,0 +1,96 @@
+#include <errno.h>
+#include <stdio.h>
+#include <string.h>
+#include <sys/ipc.h>
+#include <sys/shm.h>
+#include <sys/wait.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+int main(void) {
+    key_t key = ftok("sys_shm/shm.c", 's');
+    ERROR_IF(ftok, key, == -1);
+    printf("same key: %d\n", ftok("sys_shm/shm.c", 's') == key);
+
+    // Left behind by an earlier run
+    int old = shmget(key, 0, 0);
+    if (old != -1) {
+        shmctl(old, IPC_RMID, NULL);
+    }
+
+    int id = shmget(key, 100, IPC_CREAT | IPC_EXCL | 0600);
+    ERROR_IF(shmget, id, == -1);
+    errno = 0;
+    int status = shmget(key, 100, IPC_CREAT | IPC_EXCL | 0600);
+    printf("exclusive: %d\n", status == -1 && errno == EEXIST);
+    printf("found: %d\n", shmget(key, 50, 0) == id);
+    errno = 0;
+    status = shmget(key, 8192, 0);
+    printf("too large: %d\n", status == -1 && errno == EINVAL);
+
+    char *map = shmat(id, NULL, 0);
+    ERROR_IF(shmat, map, == (void *) -1);
+    printf("zeroed: %d\n", map[0] == 0 && map[99] == 0);
+
+    // A child attaching the segment shares its memory
+    pid_t pid = fork();
+    ERROR_IF(fork, pid, == -1);
+    if (pid == 0) {
+        char *child_map = shmat(id, NULL, 0);
+        if (child_map == (void *) -1) {
+            _exit(1);
+        }
+        strcpy(child_map, "written by the child");
+        _exit(shmdt(child_map) == 0 ? 0 : 1);
+    }
+    int wstatus;
+    status = waitpid(pid, &wstatus, 0);
+    ERROR_IF(waitpid, status, == -1);
+    printf("child: %d\n", WIFEXITED(wstatus) && WEXITSTATUS(wstatus) == 0);
+    printf("shared: %s\n", map);
+
+    struct shmid_ds ds;
+    status = shmctl(id, IPC_STAT, &ds);
+    ERROR_IF(shmctl, status, == -1);
+    printf("size: %zu, attached: %lu, mode: %o\n",
+           (size_t) ds.shm_segsz, (unsigned long) ds.shm_nattch, ds.shm_perm.mode & 0777);
+
+    ds.shm_perm.mode = 0640;
+    status = shmctl(id, IPC_SET, &ds);
+    ERROR_IF(shmctl, status, == -1);
+    status = shmctl(id, IPC_STAT, &ds);
+    ERROR_IF(shmctl, status, == -1);
+    printf("new mode: %o\n", ds.shm_perm.mode & 0777);
+
+    // A read-only attachment sees the same memory
+    char *view = shmat(id, NULL, SHM_RDONLY);
+    ERROR_IF(shmat, view, == (void *) -1);
+    printf("view: %s\n", view);
+    status = shmdt(view);
+    ERROR_IF(shmdt, status, == -1);
+    errno = 0;
+    status = shmdt(view);
+    printf("detached twice: %d\n", status == -1 && errno == EINVAL);
+
+    // The removed segment stays attached, but can't be found by its key
+    status = shmctl(id, IPC_RMID, NULL);
+    ERROR_IF(shmctl, status, == -1);
+    errno = 0;
+    status = shmget(key, 100, 0);
+    printf("removed: %d\n", status == -1 && errno == ENOENT);
+    printf("still attached: %s\n", map);
+    status = shmdt(map);
+    ERROR_IF(shmdt, status, == -1);
+
+    // Private segments are always new
+    int first = shmget(IPC_PRIVATE, 4096, 0600);
+    ERROR_IF(shmget, first, == -1);
+    int second = shmget(IPC_PRIVATE, 4096, 0600);
+    ERROR_IF(shmget, second, == -1);
+    printf("private: %d\n", first != second);
+    status = shmctl(first, IPC_RMID, NULL);
+    ERROR_IF(shmctl, status, == -1);
+    status = shmctl(second, IPC_RMID, NULL);
+    ERROR_IF(shmctl, status, == -1);
+}
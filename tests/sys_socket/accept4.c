@@ -0,0 +1,79 @@
+#define _GNU_SOURCE
+#include <arpa/inet.h>
+#include <errno.h>
+#include <fcntl.h>
+#include <netinet/in.h>
+#include <stdio.h>
+#include <sys/socket.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+static void print_flags(const char *name, int fd) {
+    int fd_flags = fcntl(fd, F_GETFD);
+    ERROR_IF(fcntl, fd_flags, == -1);
+    int status_flags = fcntl(fd, F_GETFL);
+    ERROR_IF(fcntl, status_flags, == -1);
+    printf("%s: cloexec %d, nonblock %d\n", name, (fd_flags & FD_CLOEXEC) != 0,
+           (status_flags & O_NONBLOCK) != 0);
+}
+
+int main(void) {
+    int server = socket(AF_INET, SOCK_STREAM | SOCK_CLOEXEC | SOCK_NONBLOCK, 0);
+    ERROR_IF(socket, server, == -1);
+    print_flags("socket", server);
+
+    struct sockaddr_in addr = { 0 };
+    addr.sin_family = AF_INET;
+    addr.sin_addr.s_addr = htonl(INADDR_LOOPBACK);
+    addr.sin_port = 0;
+    int status = bind(server, (struct sockaddr *) &addr, sizeof(addr));
+    ERROR_IF(bind, status, == -1);
+    socklen_t addr_len = sizeof(addr);
+    status = getsockname(server, (struct sockaddr *) &addr, &addr_len);
+    ERROR_IF(getsockname, status, == -1);
+    status = listen(server, 2);
+    ERROR_IF(listen, status, == -1);
+
+    // Make the listener blocking again, so that accepting waits for the connections
+    status = fcntl(server, F_SETFL, fcntl(server, F_GETFL) & ~O_NONBLOCK);
+    ERROR_IF(fcntl, status, == -1);
+
+    int first = socket(AF_INET, SOCK_STREAM, 0);
+    ERROR_IF(socket, first, == -1);
+    status = connect(first, (struct sockaddr *) &addr, sizeof(addr));
+    ERROR_IF(connect, status, == -1);
+    int second = socket(AF_INET, SOCK_STREAM, 0);
+    ERROR_IF(socket, second, == -1);
+    status = connect(second, (struct sockaddr *) &addr, sizeof(addr));
+    ERROR_IF(connect, status, == -1);
+
+    int plain = accept4(server, NULL, NULL, 0);
+    ERROR_IF(accept4, plain, == -1);
+    print_flags("accept4(0)", plain);
+
+    struct sockaddr_in peer;
+    socklen_t peer_len = sizeof(peer);
+    int flagged = accept4(server, (struct sockaddr *) &peer, &peer_len, SOCK_CLOEXEC | SOCK_NONBLOCK);
+    ERROR_IF(accept4, flagged, == -1);
+    print_flags("accept4(SOCK_CLOEXEC | SOCK_NONBLOCK)", flagged);
+    UNEXP_IF(accept4, peer.sin_addr.s_addr, != htonl(INADDR_LOOPBACK));
+
+    status = accept4(server, NULL, NULL, 1);
+    UNEXP_IF(accept4, status, != -1);
+    UNEXP_IF(accept4, errno, != EINVAL);
+
+    int pair[2];
+    status = socketpair(AF_UNIX, SOCK_STREAM | SOCK_CLOEXEC, 0, pair);
+    ERROR_IF(socketpair, status, == -1);
+    print_flags("socketpair", pair[0]);
+    print_flags("socketpair", pair[1]);
+
+    close(pair[0]);
+    close(pair[1]);
+    close(flagged);
+    close(plain);
+    close(second);
+    close(first);
+    close(server);
+}
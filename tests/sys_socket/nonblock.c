@@ -0,0 +1,90 @@
+#include <arpa/inet.h>
+#include <errno.h>
+#include <fcntl.h>
+#include <netinet/in.h>
+#include <poll.h>
+#include <stdio.h>
+#include <string.h>
+#include <sys/socket.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+int main(void) {
+    int server = socket(AF_INET, SOCK_STREAM, 0);
+    ERROR_IF(socket, server, == -1);
+    int reuse = 1;
+    int status = setsockopt(server, SOL_SOCKET, SO_REUSEADDR, &reuse, sizeof(reuse));
+    ERROR_IF(setsockopt, status, == -1);
+
+    struct sockaddr_in addr = { 0 };
+    addr.sin_family = AF_INET;
+    addr.sin_addr.s_addr = htonl(INADDR_LOOPBACK);
+    addr.sin_port = 0;
+    status = bind(server, (struct sockaddr *) &addr, sizeof(addr));
+    ERROR_IF(bind, status, == -1);
+    socklen_t addr_len = sizeof(addr);
+    status = getsockname(server, (struct sockaddr *) &addr, &addr_len);
+    ERROR_IF(getsockname, status, == -1);
+    status = listen(server, 1);
+    ERROR_IF(listen, status, == -1);
+
+    int client = socket(AF_INET, SOCK_STREAM, 0);
+    ERROR_IF(socket, client, == -1);
+    int flags = fcntl(client, F_GETFL);
+    ERROR_IF(fcntl, flags, == -1);
+    status = fcntl(client, F_SETFL, flags | O_NONBLOCK);
+    ERROR_IF(fcntl, status, == -1);
+
+    // Either connected right away, or in progress until the socket is writable
+    status = connect(client, (struct sockaddr *) &addr, sizeof(addr));
+    if (status == -1) {
+        UNEXP_IF(connect, errno, != EINPROGRESS);
+
+        struct pollfd pfd = { .fd = client, .events = POLLOUT };
+        status = poll(&pfd, 1, 5000);
+        ERROR_IF(poll, status, == -1);
+        UNEXP_IF(poll, status, != 1);
+    }
+
+    int error = -1;
+    socklen_t len = sizeof(error);
+    status = getsockopt(client, SOL_SOCKET, SO_ERROR, &error, &len);
+    ERROR_IF(getsockopt, status, == -1);
+    printf("SO_ERROR: %d\n", error);
+
+    int conn = accept(server, NULL, NULL);
+    ERROR_IF(accept, conn, == -1);
+
+    // Nothing to read yet
+    char buf[16] = { 0 };
+    ssize_t count = recv(conn, buf, sizeof(buf), MSG_DONTWAIT);
+    UNEXP_IF(recv, (int) count, != -1);
+    int would_block = errno == EAGAIN || errno == EWOULDBLOCK;
+    UNEXP_IF(recv, would_block, == 0);
+    // Only for the call
+    flags = fcntl(conn, F_GETFL);
+    ERROR_IF(fcntl, flags, == -1);
+    printf("blocking after MSG_DONTWAIT: %d\n", (flags & O_NONBLOCK) == 0);
+
+    count = send(client, "abcdef", 6, MSG_NOSIGNAL);
+    ERROR_IF(send, count, == -1);
+
+    // Peeking leaves the data in place
+    count = recv(conn, buf, 3, MSG_PEEK | MSG_WAITALL);
+    ERROR_IF(recv, count, == -1);
+    printf("peek: %.*s\n", (int) count, buf);
+    struct pollfd readable = { .fd = conn, .events = POLLIN };
+    status = poll(&readable, 1, 0);
+    ERROR_IF(poll, status, == -1);
+    printf("readable after peek: %d\n", status);
+
+    memset(buf, 0, sizeof(buf));
+    count = recv(conn, buf, 6, MSG_WAITALL);
+    ERROR_IF(recv, count, == -1);
+    printf("recv: %.*s\n", (int) count, buf);
+
+    close(conn);
+    close(client);
+    close(server);
+}
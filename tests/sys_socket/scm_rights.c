@@ -0,0 +1,135 @@
+#include <stdio.h>
+#include <string.h>
+#include <sys/socket.h>
+#include <sys/uio.h>
+#include <sys/wait.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+// Sends `data` with the descriptors `fds` in one SCM_RIGHTS message
+static void send_fds(int socket, char *data, int *fds, int n) {
+    struct iovec iov = { .iov_base = data, .iov_len = strlen(data) };
+    union {
+        char buf[CMSG_SPACE(2 * sizeof(int))];
+        struct cmsghdr align;
+    } control;
+    memset(&control, 0, sizeof(control));
+
+    struct msghdr msg = { 0 };
+    msg.msg_iov = &iov;
+    msg.msg_iovlen = 1;
+    if (n > 0) {
+        msg.msg_control = control.buf;
+        msg.msg_controllen = CMSG_SPACE(n * sizeof(int));
+
+        struct cmsghdr *cmsg = CMSG_FIRSTHDR(&msg);
+        cmsg->cmsg_level = SOL_SOCKET;
+        cmsg->cmsg_type = SCM_RIGHTS;
+        cmsg->cmsg_len = CMSG_LEN(n * sizeof(int));
+        memcpy(CMSG_DATA(cmsg), fds, n * sizeof(int));
+    }
+
+    ssize_t count = sendmsg(socket, &msg, 0);
+    ERROR_IF(sendmsg, count, == -1);
+    UNEXP_IF(sendmsg, (int) count, != (int) strlen(data));
+}
+
+// Receives `len` bytes with room for one descriptor, and returns how many came and whether
+// MSG_CTRUNC was set
+static void receive_one(int socket, size_t len, int *fd, int *received, int *truncated) {
+    char data[16] = { 0 };
+    struct iovec iov = { .iov_base = data, .iov_len = len };
+    union {
+        char buf[CMSG_SPACE(sizeof(int))];
+        struct cmsghdr align;
+    } control;
+
+    struct msghdr msg = { 0 };
+    msg.msg_iov = &iov;
+    msg.msg_iovlen = 1;
+    msg.msg_control = control.buf;
+    msg.msg_controllen = CMSG_LEN(sizeof(int));
+
+    ssize_t count = recvmsg(socket, &msg, 0);
+    ERROR_IF(recvmsg, count, == -1);
+    printf("data: %s\n", data);
+
+    *received = 0;
+    struct cmsghdr *cmsg = CMSG_FIRSTHDR(&msg);
+    if (cmsg) {
+        UNEXP_IF(recvmsg, cmsg->cmsg_level, != SOL_SOCKET);
+        UNEXP_IF(recvmsg, cmsg->cmsg_type, != SCM_RIGHTS);
+        UNEXP_IF(recvmsg, (void *) CMSG_NXTHDR(&msg, cmsg), != NULL);
+        *received = (cmsg->cmsg_len - CMSG_LEN(0)) / sizeof(int);
+        memcpy(fd, CMSG_DATA(cmsg), sizeof(int));
+    }
+    *truncated = (msg.msg_flags & MSG_CTRUNC) != 0;
+}
+
+int main(void) {
+    int sv[2];
+    int status = socketpair(AF_UNIX, SOCK_STREAM, 0, sv);
+    ERROR_IF(socketpair, status, == -1);
+
+    int pipes[2];
+    status = pipe(pipes);
+    ERROR_IF(pipe, status, == -1);
+
+    pid_t pid = fork();
+    ERROR_IF(fork, pid, == -1);
+
+    if (pid == 0) {
+        // Child: hand the write end of the pipe to the parent, then two descriptors where the
+        // parent has room for one, then none. The parent answers each before the next.
+        close(sv[1]);
+        char ack;
+
+        send_fds(sv[0], "fd", &pipes[1], 1);
+        ssize_t count = read(sv[0], &ack, 1);
+        ERROR_IF(read, (int) count, != 1);
+
+        int two[2] = { pipes[0], pipes[0] };
+        send_fds(sv[0], "two", two, 2);
+        count = read(sv[0], &ack, 1);
+        ERROR_IF(read, (int) count, != 1);
+
+        send_fds(sv[0], "none", NULL, 0);
+        _exit(0);
+    }
+
+    close(sv[0]);
+    close(pipes[1]);
+
+    int fd, extra, received, truncated;
+    receive_one(sv[1], 2, &fd, &received, &truncated);
+    printf("descriptors: %d, truncated: %d\n", received, truncated);
+    ssize_t count = write(sv[1], "a", 1);
+    ERROR_IF(write, (int) count, != 1);
+
+    // The descriptor that doesn't fit is dropped, not passed with the next message
+    receive_one(sv[1], 3, &extra, &received, &truncated);
+    printf("descriptors: %d, truncated: %d\n", received, truncated);
+    if (received > 0) {
+        close(extra);
+    }
+    count = write(sv[1], "a", 1);
+    ERROR_IF(write, (int) count, != 1);
+
+    receive_one(sv[1], 4, &extra, &received, &truncated);
+    printf("descriptors: %d, truncated: %d\n", received, truncated);
+
+    int wstatus;
+    status = waitpid(pid, &wstatus, 0);
+    ERROR_IF(waitpid, status, == -1);
+
+    // The received descriptor still refers to the pipe after the sender exited
+    count = write(fd, "through pipe", 12);
+    ERROR_IF(write, count, == -1);
+    close(fd);
+
+    char buf[16] = { 0 };
+    count = read(pipes[0], buf, sizeof(buf) - 1);
+    ERROR_IF(read, count, == -1);
+    printf("pipe: %s\n", buf);
+}
@@ -0,0 +1,34 @@
+#include <stdio.h>
+#include <string.h>
+#include <sys/socket.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+static void test_pair(int kind, const char *name) {
+    int sv[2];
+    int status = socketpair(AF_UNIX, kind, 0, sv);
+    ERROR_IF(socketpair, status, == -1);
+    UNEXP_IF(socketpair, status, != 0);
+
+    // Both ends can be written to and read from
+    for (int i = 0; i < 2; i++) {
+        char msg[16];
+        snprintf(msg, sizeof(msg), "hello %d", i);
+        ssize_t count = write(sv[i], msg, strlen(msg));
+        ERROR_IF(write, count, == -1);
+
+        char buf[16] = { 0 };
+        count = read(sv[1 - i], buf, sizeof(buf) - 1);
+        ERROR_IF(read, count, == -1);
+        printf("%s: %d -> %d: %s\n", name, i, 1 - i, buf);
+    }
+
+    close(sv[0]);
+    close(sv[1]);
+}
+
+int main(void) {
+    test_pair(SOCK_STREAM, "stream");
+    test_pair(SOCK_DGRAM, "dgram");
+}
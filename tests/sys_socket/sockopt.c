@@ -0,0 +1,63 @@
+#include <netinet/in.h>
+#include <netinet/tcp.h>
+#include <stdio.h>
+#include <sys/socket.h>
+#include <sys/time.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+static void check_int(int fd, int level, int name, const char *desc, int value) {
+    int status = setsockopt(fd, level, name, &value, sizeof(value));
+    ERROR_IF(setsockopt, status, == -1);
+
+    int result = -1;
+    socklen_t len = sizeof(result);
+    status = getsockopt(fd, level, name, &result, &len);
+    ERROR_IF(getsockopt, status, == -1);
+    UNEXP_IF(getsockopt, (int) len, != sizeof(int));
+    printf("%s: %d\n", desc, result);
+}
+
+int main(void) {
+    int fd = socket(AF_INET, SOCK_STREAM, 0);
+    ERROR_IF(socket, fd, == -1);
+
+    check_int(fd, SOL_SOCKET, SO_REUSEADDR, "SO_REUSEADDR", 1);
+    check_int(fd, SOL_SOCKET, SO_KEEPALIVE, "SO_KEEPALIVE", 1);
+    check_int(fd, IPPROTO_TCP, TCP_NODELAY, "TCP_NODELAY", 1);
+    check_int(fd, IPPROTO_IP, IP_TOS, "IP_TOS", 0x10);
+    check_int(fd, IPPROTO_IP, IP_TTL, "IP_TTL", 32);
+
+    struct timeval tv = { .tv_sec = 2, .tv_usec = 500000 };
+    int status = setsockopt(fd, SOL_SOCKET, SO_RCVTIMEO, &tv, sizeof(tv));
+    ERROR_IF(setsockopt, status, == -1);
+    struct timeval tv_result = { 0 };
+    socklen_t len = sizeof(tv_result);
+    status = getsockopt(fd, SOL_SOCKET, SO_RCVTIMEO, &tv_result, &len);
+    ERROR_IF(getsockopt, status, == -1);
+    printf("SO_RCVTIMEO: %ld.%06ld\n", (long) tv_result.tv_sec, (long) tv_result.tv_usec);
+
+    struct linger linger = { .l_onoff = 1, .l_linger = 5 };
+    status = setsockopt(fd, SOL_SOCKET, SO_LINGER, &linger, sizeof(linger));
+    ERROR_IF(setsockopt, status, == -1);
+    struct linger linger_result = { 0 };
+    len = sizeof(linger_result);
+    status = getsockopt(fd, SOL_SOCKET, SO_LINGER, &linger_result, &len);
+    ERROR_IF(getsockopt, status, == -1);
+    printf("SO_LINGER: %d %d\n", linger_result.l_onoff, linger_result.l_linger);
+
+    int value = -1;
+    len = sizeof(value);
+    status = getsockopt(fd, SOL_SOCKET, SO_TYPE, &value, &len);
+    ERROR_IF(getsockopt, status, == -1);
+    UNEXP_IF(getsockopt, value, != SOCK_STREAM);
+
+    value = -1;
+    len = sizeof(value);
+    status = getsockopt(fd, SOL_SOCKET, SO_ERROR, &value, &len);
+    ERROR_IF(getsockopt, status, == -1);
+    printf("SO_ERROR: %d\n", value);
+
+    close(fd);
+}
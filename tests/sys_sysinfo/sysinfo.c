@@ -0,0 +1,48 @@
+#include <stdio.h>
+#include <stdlib.h>
+#include <sys/sysinfo.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+int main(void) {
+    struct sysinfo info;
+    int status = sysinfo(&info);
+    ERROR_IF(sysinfo, status, == -1);
+    UNEXP_IF(sysinfo, status, != 0);
+
+    if (info.totalram == 0 || info.freeram > info.totalram || info.mem_unit == 0) {
+        printf("bad memory totals: %lu free of %lu in units of %u\n",
+            info.freeram, info.totalram, info.mem_unit);
+        exit(EXIT_FAILURE);
+    }
+    if (info.procs == 0) {
+        puts("no processes");
+        exit(EXIT_FAILURE);
+    }
+    puts("sysinfo: ok");
+
+    int nprocs = get_nprocs();
+    int nprocs_conf = get_nprocs_conf();
+    if (nprocs < 1 || nprocs_conf < nprocs) {
+        printf("bad processor counts: %d of %d\n", nprocs, nprocs_conf);
+        exit(EXIT_FAILURE);
+    }
+    if (sysconf(_SC_NPROCESSORS_ONLN) != nprocs || sysconf(_SC_NPROCESSORS_CONF) != nprocs_conf) {
+        puts("sysconf disagrees with get_nprocs");
+        exit(EXIT_FAILURE);
+    }
+    puts("get_nprocs: ok");
+
+    long phys = get_phys_pages();
+    long avphys = get_avphys_pages();
+    if (phys <= 0 || avphys < 0 || avphys > phys) {
+        printf("bad page counts: %ld of %ld\n", avphys, phys);
+        exit(EXIT_FAILURE);
+    }
+    if (sysconf(_SC_PHYS_PAGES) != phys) {
+        puts("sysconf disagrees with get_phys_pages");
+        exit(EXIT_FAILURE);
+    }
+    puts("get_phys_pages: ok");
+}
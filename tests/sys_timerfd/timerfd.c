@@ -0,0 +1,107 @@
+#include <errno.h>
+#include <fcntl.h>
+#include <poll.h>
+#include <stdint.h>
+#include <stdio.h>
+#include <sys/timerfd.h>
+#include <time.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+#define MS 1000000
+
+static int readable(int fd, int timeout) {
+    struct pollfd pfd = { .fd = fd, .events = POLLIN };
+    int status = poll(&pfd, 1, timeout);
+    ERROR_IF(poll, status, == -1);
+    return status;
+}
+
+static uint64_t expirations(int fd) {
+    uint64_t count;
+    ssize_t status = read(fd, &count, sizeof(count));
+    ERROR_IF(read, (int) status, == -1);
+    return count;
+}
+
+int main(void) {
+    int fd = timerfd_create(CLOCK_MONOTONIC, TFD_NONBLOCK | TFD_CLOEXEC);
+    ERROR_IF(timerfd_create, fd, == -1);
+    int flags = fcntl(fd, F_GETFD);
+    ERROR_IF(fcntl, flags, == -1);
+    printf("cloexec: %d\n", (flags & FD_CLOEXEC) != 0);
+
+    // Not armed yet
+    struct itimerspec curr;
+    int status = timerfd_gettime(fd, &curr);
+    ERROR_IF(timerfd_gettime, status, == -1);
+    printf("disarmed: %ld %ld, readable: %d\n", (long) curr.it_value.tv_sec,
+           curr.it_value.tv_nsec, readable(fd, 0));
+    uint64_t count;
+    ssize_t bytes = read(fd, &count, sizeof(count));
+    UNEXP_IF(read, (int) bytes, != -1);
+    UNEXP_IF(read, errno, != EAGAIN);
+
+    // One-shot, relative to now
+    struct itimerspec value = { .it_value = { .tv_nsec = 20 * MS } };
+    status = timerfd_settime(fd, 0, &value, NULL);
+    ERROR_IF(timerfd_settime, status, == -1);
+    status = timerfd_gettime(fd, &curr);
+    ERROR_IF(timerfd_gettime, status, == -1);
+    printf("pending: %d\n", curr.it_value.tv_sec == 0 && curr.it_value.tv_nsec > 0
+                            && curr.it_value.tv_nsec <= 20 * MS);
+    printf("fired: %d\n", readable(fd, 1000));
+    count = expirations(fd);
+    printf("one-shot: %llu, readable: %d\n", (unsigned long long) count, readable(fd, 0));
+    char small[4];
+    bytes = read(fd, small, sizeof(small));
+    UNEXP_IF(read, (int) bytes, != -1);
+    UNEXP_IF(read, errno, != EINVAL);
+
+    // Periodic, expirations accumulate until read
+    value.it_value.tv_nsec = 10 * MS;
+    value.it_interval.tv_nsec = 10 * MS;
+    status = timerfd_settime(fd, 0, &value, NULL);
+    ERROR_IF(timerfd_settime, status, == -1);
+    usleep(55000);
+    printf("periodic: %d\n", expirations(fd) >= 4);
+
+    // Disarming reports the old setting
+    struct itimerspec disarm = { 0 };
+    struct itimerspec old;
+    status = timerfd_settime(fd, 0, &disarm, &old);
+    ERROR_IF(timerfd_settime, status, == -1);
+    printf("old interval: %ld %ld\n", (long) old.it_interval.tv_sec, old.it_interval.tv_nsec);
+    status = timerfd_gettime(fd, &curr);
+    ERROR_IF(timerfd_gettime, status, == -1);
+    printf("disarmed: %ld %ld\n", (long) curr.it_value.tv_sec, curr.it_value.tv_nsec);
+    close(fd);
+
+    // Blocking read of an absolute deadline
+    fd = timerfd_create(CLOCK_REALTIME, 0);
+    ERROR_IF(timerfd_create, fd, == -1);
+    struct timespec now;
+    status = clock_gettime(CLOCK_REALTIME, &now);
+    ERROR_IF(clock_gettime, status, == -1);
+    value.it_interval.tv_nsec = 0;
+    value.it_value = now;
+    value.it_value.tv_nsec += 10 * MS;
+    if (value.it_value.tv_nsec >= 1000 * MS) {
+        value.it_value.tv_sec += 1;
+        value.it_value.tv_nsec -= 1000 * MS;
+    }
+    status = timerfd_settime(fd, TFD_TIMER_ABSTIME, &value, NULL);
+    ERROR_IF(timerfd_settime, status, == -1);
+    printf("absolute: %llu\n", (unsigned long long) expirations(fd));
+
+    value.it_value.tv_nsec = 1000 * MS;
+    status = timerfd_settime(fd, 0, &value, NULL);
+    UNEXP_IF(timerfd_settime, status, != -1);
+    UNEXP_IF(timerfd_settime, errno, != EINVAL);
+    close(fd);
+
+    fd = timerfd_create(CLOCK_MONOTONIC, 0x40000000);
+    UNEXP_IF(timerfd_create, fd, != -1);
+    UNEXP_IF(timerfd_create, errno, != EINVAL);
+}
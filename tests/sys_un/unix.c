@@ -0,0 +1,108 @@
+#include <errno.h>
+#include <stdio.h>
+#include <string.h>
+#include <sys/socket.h>
+#include <sys/un.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+#define STREAM_PATH "sys_un-stream.sock"
+#define DGRAM_PATH "sys_un-dgram.sock"
+
+static socklen_t make_addr(struct sockaddr_un *addr, const char *path) {
+    memset(addr, 0, sizeof(*addr));
+    addr->sun_family = AF_UNIX;
+    strncpy(addr->sun_path, path, sizeof(addr->sun_path) - 1);
+    return sizeof(*addr);
+}
+
+static void test_stream(void) {
+    struct sockaddr_un addr;
+    socklen_t addr_len = make_addr(&addr, STREAM_PATH);
+    unlink(STREAM_PATH);
+
+    int server = socket(AF_UNIX, SOCK_STREAM, 0);
+    ERROR_IF(socket, server, == -1);
+
+    int status = bind(server, (struct sockaddr *) &addr, addr_len);
+    ERROR_IF(bind, status, == -1);
+
+    // The bound address exists in the filesystem and can't be bound twice
+    status = access(STREAM_PATH, F_OK);
+    ERROR_IF(access, status, == -1);
+    int other = socket(AF_UNIX, SOCK_STREAM, 0);
+    ERROR_IF(socket, other, == -1);
+    status = bind(other, (struct sockaddr *) &addr, addr_len);
+    UNEXP_IF(bind, status, != -1);
+    UNEXP_IF(bind, errno, != EADDRINUSE);
+    close(other);
+
+    status = listen(server, 1);
+    ERROR_IF(listen, status, == -1);
+
+    int client = socket(AF_UNIX, SOCK_STREAM, 0);
+    ERROR_IF(socket, client, == -1);
+    status = connect(client, (struct sockaddr *) &addr, addr_len);
+    ERROR_IF(connect, status, == -1);
+
+    int conn = accept(server, NULL, NULL);
+    ERROR_IF(accept, conn, == -1);
+
+    ssize_t count = write(client, "ping", 4);
+    ERROR_IF(write, count, == -1);
+    char buf[16] = { 0 };
+    count = read(conn, buf, sizeof(buf) - 1);
+    ERROR_IF(read, count, == -1);
+    printf("stream: %s\n", buf);
+
+    close(conn);
+    close(client);
+    close(server);
+
+    status = unlink(STREAM_PATH);
+    ERROR_IF(unlink, status, == -1);
+
+    // Nothing is listening anymore
+    client = socket(AF_UNIX, SOCK_STREAM, 0);
+    ERROR_IF(socket, client, == -1);
+    status = connect(client, (struct sockaddr *) &addr, addr_len);
+    UNEXP_IF(connect, status, != -1);
+    UNEXP_IF(connect, errno, != ENOENT);
+    close(client);
+}
+
+static void test_dgram(void) {
+    struct sockaddr_un addr;
+    socklen_t addr_len = make_addr(&addr, DGRAM_PATH);
+    unlink(DGRAM_PATH);
+
+    int server = socket(AF_UNIX, SOCK_DGRAM, 0);
+    ERROR_IF(socket, server, == -1);
+    int status = bind(server, (struct sockaddr *) &addr, addr_len);
+    ERROR_IF(bind, status, == -1);
+
+    int client = socket(AF_UNIX, SOCK_DGRAM, 0);
+    ERROR_IF(socket, client, == -1);
+    ssize_t count = sendto(client, "one", 3, 0, (struct sockaddr *) &addr, addr_len);
+    ERROR_IF(sendto, count, == -1);
+    count = sendto(client, "two", 3, 0, (struct sockaddr *) &addr, addr_len);
+    ERROR_IF(sendto, count, == -1);
+
+    // Message boundaries are preserved
+    for (int i = 0; i < 2; i++) {
+        char buf[16] = { 0 };
+        count = recv(server, buf, sizeof(buf) - 1, 0);
+        ERROR_IF(recv, count, == -1);
+        printf("dgram: %s\n", buf);
+    }
+
+    close(client);
+    close(server);
+    unlink(DGRAM_PATH);
+}
+
+int main(void) {
+    test_stream();
+    test_dgram();
+}
@@ -0,0 +1,28 @@
+#include <errno.h>
+#include <stdio.h>
+#include <syslog.h>
+
+int main(void) {
+    openlog("test", LOG_PERROR, LOG_USER);
+
+    syslog(LOG_INFO, "info %d", 1);
+    errno = ENOENT;
+    syslog(LOG_ERR, "open: %m");
+    printf("errno kept: %d\n", errno == ENOENT);
+    syslog(LOG_NOTICE, "literal %%m and %s", "%m");
+
+    int old = setlogmask(LOG_UPTO(LOG_WARNING));
+    printf("old mask: %#x\n", old);
+    syslog(LOG_INFO, "filtered");
+    syslog(LOG_WARNING | LOG_DAEMON, "warning %s", "kept");
+    printf("unchanged: %#x\n", setlogmask(0));
+    printf("mask: %#x\n", setlogmask(LOG_MASK(LOG_DEBUG)));
+    syslog(LOG_ERR, "filtered");
+    syslog(LOG_DEBUG, "debug");
+
+    printf("LOG_PRI: %d\n", LOG_PRI(LOG_MAKEPRI(LOG_LOCAL3, LOG_CRIT)));
+    printf("LOG_FAC: %d\n", LOG_FAC(LOG_MAKEPRI(LOG_LOCAL3, LOG_CRIT)));
+
+    closelog();
+    return 0;
+}
@@ -0,0 +1,96 @@
+#define _DEFAULT_SOURCE
+#include <errno.h>
+#include <fcntl.h>
+#include <pty.h>
+#include <stdio.h>
+#include <string.h>
+#include <termios.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+static void show(int master, int slave, const char *input) {
+    ssize_t written = write(master, input, strlen(input));
+    ERROR_IF(write, written, == -1);
+    char buf[64] = {0};
+    ssize_t count = read(slave, buf, sizeof(buf) - 1);
+    ERROR_IF(read, count, == -1);
+    printf("read %zd:", count);
+    for (ssize_t i = 0; i < count; i++) {
+        printf(" %02x", (unsigned char) buf[i]);
+    }
+    printf("\n");
+}
+
+int main(void) {
+    int master, slave;
+    int status = openpty(&master, &slave, NULL, NULL, NULL);
+    ERROR_IF(openpty, status, == -1);
+
+    struct termios attrs;
+    status = tcgetattr(slave, &attrs);
+    ERROR_IF(tcgetattr, status, == -1);
+
+    // canonical mode gives a line at a time, after editing it
+    struct termios cooked = attrs;
+    cooked.c_lflag |= ICANON;
+    cooked.c_lflag &= ~ECHO;
+    status = tcsetattr(slave, TCSANOW, &cooked);
+    ERROR_IF(tcsetattr, status, == -1);
+    char line[4] = {'a', 'b', cooked.c_cc[VERASE], '\n'};
+    status = write(master, line, sizeof(line));
+    ERROR_IF(write, status, == -1);
+    char buf[64] = {0};
+    ssize_t count = read(slave, buf, sizeof(buf) - 1);
+    ERROR_IF(read, count, == -1);
+    printf("canonical: %zd %s", count, buf);
+
+    struct termios raw = attrs;
+    cfmakeraw(&raw);
+    printf("raw: %d %d %d %d %d %d\n",
+        (raw.c_lflag & (ICANON | ECHO | ISIG)) == 0,
+        (raw.c_iflag & (ICRNL | IXON)) == 0,
+        (raw.c_oflag & OPOST) == 0,
+        (raw.c_cflag & CSIZE) == CS8,
+        raw.c_cc[VMIN],
+        raw.c_cc[VTIME]);
+    status = tcsetattr(slave, TCSAFLUSH, &raw);
+    ERROR_IF(tcsetattr, status, == -1);
+    struct termios got;
+    status = tcgetattr(slave, &got);
+    ERROR_IF(tcgetattr, status, == -1);
+    printf("set: %d\n", (got.c_lflag & (ICANON | ECHO | ISIG)) == 0);
+
+    // raw mode gives bytes as they come, without turning the interrupt character into a signal
+    char input[] = {'x', (char) raw.c_cc[VINTR], '\r', 0};
+    show(master, slave, input);
+
+    status = cfsetospeed(&raw, B9600);
+    ERROR_IF(cfsetospeed, status, == -1);
+    status = cfsetispeed(&raw, B9600);
+    ERROR_IF(cfsetispeed, status, == -1);
+    printf("speed: %d %d\n", cfgetospeed(&raw) == B9600, cfgetispeed(&raw) == B9600);
+    status = cfsetspeed(&raw, B115200);
+    ERROR_IF(cfsetspeed, status, == -1);
+    printf("cfsetspeed: %d %d\n", cfgetospeed(&raw) == B115200, cfgetispeed(&raw) == B115200);
+    errno = 0;
+    status = cfsetospeed(&raw, 12345);
+    printf("bad speed: %d %d\n", status, errno == EINVAL);
+
+    status = tcdrain(slave);
+    ERROR_IF(tcdrain, status, == -1);
+    status = tcflush(slave, TCIOFLUSH);
+    ERROR_IF(tcflush, status, == -1);
+    errno = 0;
+    status = tcflush(slave, 42);
+    printf("bad queue: %d %d\n", status, errno == EINVAL);
+    errno = 0;
+    status = tcflow(slave, 42);
+    printf("bad action: %d %d\n", status, errno == EINVAL);
+    errno = 0;
+    status = tcsetattr(slave, 42, &raw);
+    printf("bad when: %d %d\n", status, errno == EINVAL);
+
+    close(slave);
+    close(master);
+}
@@ -0,0 +1,38 @@
+#include <pthread.h>
+#include <stdint.h>
+#include <stdio.h>
+
+#include "test_helpers.h"
+
+// Aligned more than the TCB, so the thread pointer has to be placed for it
+_Thread_local _Alignas(64) char aligned[3] = "ok";
+_Thread_local _Alignas(256) int tdata = 7;
+_Thread_local long tbss;
+
+static void report(const char *who) {
+    printf("%s: aligned %s, %s\n", who,
+        (uintptr_t)aligned % 64 == 0 && (uintptr_t)&tdata % 256 == 0 ? "yes" : "no", aligned);
+    printf("%s: tdata %d, tbss %ld\n", who, tdata, tbss);
+}
+
+static void *thread(void *arg) {
+    (void)arg;
+    // A copy of the initial data, not what the main thread changed
+    report("thread");
+    tdata = 9;
+    return NULL;
+}
+
+int main(void) {
+    tdata = 8;
+    tbss = 5;
+    report("main");
+
+    pthread_t handle;
+    int status = pthread_create(&handle, NULL, thread, NULL);
+    ERROR_IF(pthread_create, status, != 0);
+    status = pthread_join(handle, NULL);
+    ERROR_IF(pthread_join, status, != 0);
+
+    report("main");
+}
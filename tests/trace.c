@@ -0,0 +1,127 @@
+// RELIBC_TRACE logs the calls of a program it's set for in the format src/platform/trace.rs
+// documents, with the errno of those that fail, and only the categories asked for
+#include <errno.h>
+#include <fcntl.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <sys/wait.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+#define MISSING "/nonexistent/relibc-trace"
+
+// What the traced program does
+static void traced(void) {
+    errno = 0;
+    int fd = open(MISSING, O_RDONLY);
+    printf("  open returned %d with errno %d\n", fd, errno);
+
+    // A call that succeeds leaves errno as it was
+    errno = EINTR;
+    int status = access("/", F_OK);
+    printf("  access returned %d with errno %s\n", status, errno == EINTR ? "kept" : "changed");
+    fflush(stdout);
+}
+
+// Runs this program again with RELIBC_TRACE set to `categories`, and reads its log. Returns the
+// pid it ran with, that of the child fork makes.
+static pid_t run_traced(char *argv0, const char *categories, char *log, size_t size) {
+    int fds[2];
+    int status = pipe(fds);
+    ERROR_IF(pipe, status, == -1);
+    fflush(stdout);
+
+    pid_t pid = fork();
+    ERROR_IF(fork, pid, == -1);
+    if (pid == 0) {
+        status = dup2(fds[1], STDERR_FILENO);
+        ERROR_IF(dup2, status, == -1);
+        close(fds[0]);
+        close(fds[1]);
+
+        char var[64];
+        snprintf(var, sizeof(var), "RELIBC_TRACE=%s", categories);
+        char *args[] = { argv0, "traced", NULL };
+        char *envp[] = { var, NULL };
+        execve(argv0, args, envp);
+        perror("execve");
+        _exit(EXIT_FAILURE);
+    }
+    close(fds[1]);
+
+    size_t len = 0;
+    for (;;) {
+        ssize_t n = read(fds[0], log + len, size - 1 - len);
+        ERROR_IF(read, n, == -1);
+        if (n == 0) {
+            break;
+        }
+        len += n;
+    }
+    log[len] = 0;
+    close(fds[0]);
+
+    int wstatus;
+    status = waitpid(pid, &wstatus, 0);
+    ERROR_IF(waitpid, status, == -1);
+    int exited = WIFEXITED(wstatus) && WEXITSTATUS(wstatus) == 0;
+    UNEXP_IF(waitpid, exited, == 0);
+    return pid;
+}
+
+// Checks the lines of the log for the calls of traced, and returns how many others there are
+static int check(char *log, pid_t pid) {
+    int opened = 0, accessed = 0, others = 0, malformed = 0;
+    for (char *line = strtok(log, "\n"); line; line = strtok(NULL, "\n")) {
+        int tid, end = 0;
+        if (sscanf(line, "[%d] %n", &tid, &end) != 1 || end == 0 || !strchr(line, '(')) {
+            malformed++;
+            continue;
+        }
+        char *call = line + end;
+        int ret, err;
+        char text[64];
+        if (strncmp(call, "open(\"" MISSING "\", ", 6 + strlen(MISSING) + 3) == 0) {
+            char *result = strstr(call, ") = ");
+            if (result && sscanf(result, ") = %d (%d, %63[^)])", &ret, &err, text) == 3) {
+                printf("  open logged as returning %d with errno %d (%s), from a thread of %s\n",
+                       ret, err, text, tid == pid ? "the program" : "another process");
+            } else {
+                printf("  open logged without its errno: %s\n", call);
+            }
+            opened++;
+        } else if (strncmp(call, "access(\"/\", ", 12) == 0) {
+            char *result = strstr(call, ") = ");
+            printf("  access logged as %s\n",
+                   result && strcmp(result, ") = 0") == 0 ? "returning 0 with no errno" : result);
+            accessed++;
+        } else {
+            others++;
+        }
+    }
+    printf("  open logged %d times, access %d times, malformed lines %d\n", opened, accessed,
+           malformed);
+    return others;
+}
+
+int main(int argc, char **argv) {
+    if (argc > 1 && strcmp(argv[1], "traced") == 0) {
+        traced();
+        return 0;
+    }
+
+    static char log[65536];
+    const char *runs[] = { "file", "all,-file", "bogus" };
+    for (int i = 0; i < 3; i++) {
+        printf("RELIBC_TRACE=%s:\n", runs[i]);
+        pid_t pid = run_traced(argv[0], runs[i], log, sizeof(log));
+        int others = check(log, pid);
+        // Which other calls there are depends on the platform, but with all there are some, like
+        // the write of the output
+        if (strncmp(runs[i], "all", 3) == 0) {
+            printf("  other calls logged: %s\n", others > 0 ? "yes" : "no");
+        }
+    }
+}
@@ -0,0 +1,80 @@
+#define _DEFAULT_SOURCE
+#include <pthread.h>
+#include <stdatomic.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <sys/wait.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+#define THREADS 4
+#define FORKS 50
+
+static atomic_int done;
+
+// Keeps the heap and the environment busy, so forks happen while their locks are held
+static void *churn(void *arg) {
+    size_t n = (size_t) arg;
+    while (!atomic_load(&done)) {
+        void *blocks[16];
+        for (size_t i = 0; i < 16; i++) {
+            blocks[i] = malloc(1 + (n * 131 + i * 97) % 3000);
+            ERROR_IF(malloc, blocks[i], == NULL);
+        }
+        for (size_t i = 0; i < 16; i++) {
+            free(blocks[i]);
+        }
+        int status = setenv("FORK_THREADS", n % 2 ? "odd" : "even", 1);
+        ERROR_IF(setenv, status, == -1);
+        n++;
+    }
+    return NULL;
+}
+
+int main(void) {
+    int set = setenv("FORK_THREADS", "main", 1);
+    ERROR_IF(setenv, set, == -1);
+
+    pthread_t threads[THREADS];
+    for (size_t i = 0; i < THREADS; i++) {
+        int status = pthread_create(&threads[i], NULL, churn, (void *) i);
+        ERROR_IF(pthread_create, status, != 0);
+    }
+
+    int ok = 0;
+    for (int i = 0; i < FORKS; i++) {
+        pid_t pid = fork();
+        ERROR_IF(fork, pid, == -1);
+        if (pid == 0) {
+            // Each of these takes a lock another thread might have held
+            char *block = malloc(4096);
+            if (block == NULL) {
+                _exit(1);
+            }
+            memset(block, 'x', 4096);
+            free(block);
+            if (getenv("FORK_THREADS") == NULL) {
+                _exit(1);
+            }
+            _exit(0);
+        }
+
+        int status;
+        pid_t waited = waitpid(pid, &status, 0);
+        ERROR_IF(waitpid, waited, == -1);
+        if (WIFEXITED(status) && WEXITSTATUS(status) == 0) {
+            ok++;
+        }
+    }
+
+    atomic_store(&done, 1);
+    for (size_t i = 0; i < THREADS; i++) {
+        int status = pthread_join(threads[i], NULL);
+        ERROR_IF(pthread_join, status, != 0);
+    }
+
+    printf("%d of %d children could allocate\n", ok, FORKS);
+    return 0;
+}
@@ -0,0 +1,55 @@
+#include <getopt.h>
+#include <stdio.h>
+
+#include "test_helpers.h"
+
+#define RUN(...) \
+    do { \
+        optind = 0; \
+        opterr = 0; \
+        char *args_arr[] = { __VA_ARGS__ }; \
+        runner(sizeof(args_arr) / sizeof(char *), args_arr); \
+    } while (0)
+
+static int flag = 0;
+
+static struct option long_options[] = {
+    {"all", no_argument, NULL, 'A'},
+    {"name", required_argument, NULL, 'N'},
+    {"quiet", no_argument, &flag, 1},
+    {NULL, 0, NULL, 0},
+};
+
+void runner(int argc, char *argv[]) {
+    printf("--- Running:");
+    for (int i = 0; i < argc; i += 1) {
+        printf(" %s", argv[i]);
+    }
+    puts("");
+
+    int c;
+    int index = -1;
+    while ((c = getopt_long_only(argc, argv, "an:x", long_options, &index)) != -1) {
+        if (c == '?') {
+            printf("error, optopt %d\n", optopt);
+        } else if (c == 0) {
+            printf("flag set to %d, index %d\n", flag, index);
+            flag = 0;
+        } else {
+            printf("option %c = %s, index %d\n", c, optarg ? optarg : "(none)", index);
+        }
+        index = -1;
+    }
+    printf("optind %d\n", optind);
+}
+
+int main(void) {
+    // A single dash starts long options too
+    RUN("test", "-all", "-name", "value", "-name=other", "--quiet", "-q");
+
+    // Letters that are short options stay short, the others can be abbreviations
+    RUN("test", "-a", "-n", "value", "-x", "-nvalue");
+
+    // What isn't a long option is parsed as short options, if it starts with one
+    RUN("test", "-ax", "-xa", "-z");
+}
@@ -0,0 +1,74 @@
+#include <getopt.h>
+#include <stdio.h>
+#include <string.h>
+
+#include "test_helpers.h"
+
+#define RUN(optstring, ...) \
+    do { \
+        optind = 0; \
+        opterr = 0; \
+        char *args_arr[] = { __VA_ARGS__ }; \
+        runner(optstring, sizeof(args_arr) / sizeof(char *), args_arr); \
+    } while (0)
+
+static struct option long_options[] = {
+    {"verbose", no_argument, NULL, 'v'},
+    {"output", required_argument, NULL, 'o'},
+    {"color", optional_argument, NULL, 'c'},
+    {"column", required_argument, NULL, 'C'},
+    {NULL, 0, NULL, 0},
+};
+
+void runner(const char *optstring, int argc, char *argv[]) {
+    printf("--- Running (%s):", optstring);
+    for (int i = 0; i < argc; i += 1) {
+        printf(" %s", argv[i]);
+    }
+    puts("");
+
+    int c;
+    while ((c = getopt_long(argc, argv, optstring, long_options, NULL)) != -1) {
+        if (c == 1) {
+            printf("argument %s\n", optarg);
+        } else if (c == '?' || c == ':') {
+            printf("error %c, optopt %d\n", c, optopt);
+        } else {
+            printf("option %c = %s\n", c, optarg ? optarg : "(none)");
+        }
+    }
+
+    printf("optind %d:", optind);
+    for (int i = 0; i < argc; i += 1) {
+        printf(" %s", argv[i]);
+    }
+    puts("");
+}
+
+int main(void) {
+    // The options are moved before the other arguments
+    RUN("vo:", "test", "file1", "-v", "file2", "-o", "out", "file3");
+    RUN("vo:", "test", "file1", "--output", "out", "file2", "--verbose");
+    RUN("vo:", "test", "-v", "file1", "--", "-o", "out");
+    RUN("vo:", "test", "file1", "file2");
+
+    // '+' stops at the first argument that isn't an option
+    RUN("+vo:", "test", "-v", "file1", "-o", "out");
+
+    // '-' returns the other arguments in order
+    RUN("-vo:", "test", "file1", "-v", "file2", "--", "-o");
+
+    // Optional arguments only come in the same argument
+    RUN("vc::", "test", "-c", "arg", "-carg", "--color", "arg", "--color=arg");
+
+    // Abbreviations, and their ambiguities
+    RUN("vo:", "test", "--verb", "--out=x", "--col", "--colu", "y");
+
+    // Errors
+    RUN("vo:", "test", "--verbose=yes", "--unknown", "-x", "--output");
+    RUN(":vo:", "test", "-o");
+    RUN(":vo:", "test", "--output");
+
+    // Clusters of options
+    RUN("vo:", "test", "-vv");
+}
@@ -0,0 +1,37 @@
+#define _DEFAULT_SOURCE
+#include <errno.h>
+#include <limits.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <sys/utsname.h>
+#include <unistd.h>
+
+#include "test_helpers.h"
+
+int main(void) {
+    struct utsname uts;
+    int status = uname(&uts);
+    ERROR_IF(uname, status, == -1);
+
+    char name[256];
+    status = gethostname(name, sizeof(name));
+    ERROR_IF(gethostname, status, == -1);
+    UNEXP_IF(gethostname, status, != 0);
+    printf("gethostname matches uname: %d\n", strcmp(name, uts.nodename) == 0);
+
+    errno = 0;
+    status = gethostname(name, strlen(uts.nodename));
+    printf("gethostname into a short buffer: %d, %s\n", status, strerror(errno));
+
+    status = getdomainname(name, sizeof(name));
+    ERROR_IF(getdomainname, status, == -1);
+    UNEXP_IF(getdomainname, status, != 0);
+    printf("getdomainname has a name: %d\n", name[0] != 0);
+
+    errno = 0;
+    char long_name[HOST_NAME_MAX + 2];
+    memset(long_name, 'a', sizeof(long_name));
+    status = sethostname(long_name, sizeof(long_name));
+    printf("sethostname with a long name: %d, %s\n", status, strerror(errno));
+}
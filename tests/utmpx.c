@@ -0,0 +1,98 @@
+#define _GNU_SOURCE
+#include <fcntl.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <unistd.h>
+#include <utmpx.h>
+
+#include "test_helpers.h"
+
+static void record(short type, pid_t pid, const char *id, const char *line, const char *user) {
+    struct utmpx ut;
+    memset(&ut, 0, sizeof(ut));
+    ut.ut_type = type;
+    ut.ut_pid = pid;
+    strncpy(ut.ut_id, id, sizeof(ut.ut_id));
+    strncpy(ut.ut_line, line, sizeof(ut.ut_line));
+    strncpy(ut.ut_user, user, sizeof(ut.ut_user));
+    ut.ut_tv.tv_sec = 1000 + pid;
+    struct utmpx *put = pututxline(&ut);
+    ERROR_IF(pututxline, put, == NULL);
+}
+
+static void list(void) {
+    setutxent();
+    struct utmpx *ut;
+    while ((ut = getutxent()) != NULL) {
+        printf("  type %d pid %d id %.4s line %s user %s time %d\n",
+            ut->ut_type, ut->ut_pid, ut->ut_id, ut->ut_line, ut->ut_user, (int) ut->ut_tv.tv_sec);
+    }
+}
+
+int main(void) {
+    printf("size: %zu\n", sizeof(struct utmpx));
+
+    char utmp[] = "/tmp/utmpx-XXXXXX";
+    int fd = mkstemp(utmp);
+    ERROR_IF(mkstemp, fd, == -1);
+    close(fd);
+    char wtmp[] = "/tmp/wtmpx-XXXXXX";
+    fd = mkstemp(wtmp);
+    ERROR_IF(mkstemp, fd, == -1);
+    close(fd);
+
+    int status = utmpxname(utmp);
+    ERROR_IF(utmpxname, status, == -1);
+    printf("empty: %d\n", getutxent() == NULL);
+
+    record(BOOT_TIME, 0, "~~", "~", "reboot");
+    record(LOGIN_PROCESS, 10, "tty1", "tty1", "LOGIN");
+    record(LOGIN_PROCESS, 11, "tty2", "tty2", "LOGIN");
+    // a user logging in replaces the login process with the same id
+    record(USER_PROCESS, 10, "tty1", "tty1", "alice");
+    printf("after login:\n");
+    list();
+
+    struct utmpx key;
+    memset(&key, 0, sizeof(key));
+    strcpy(key.ut_line, "tty2");
+    setutxent();
+    struct utmpx *found = getutxline(&key);
+    printf("line tty2: %s\n", found ? found->ut_user : "NULL");
+
+    key.ut_type = DEAD_PROCESS;
+    strncpy(key.ut_id, "tty1", sizeof(key.ut_id));
+    setutxent();
+    found = getutxid(&key);
+    printf("id tty1: %s\n", found ? found->ut_user : "NULL");
+
+    key.ut_type = BOOT_TIME;
+    setutxent();
+    found = getutxid(&key);
+    printf("boot: %s\n", found ? found->ut_user : "NULL");
+
+    record(DEAD_PROCESS, 10, "tty1", "tty1", "");
+    printf("after logout:\n");
+    list();
+    endutxent();
+
+    struct utmpx session;
+    memset(&session, 0, sizeof(session));
+    session.ut_type = USER_PROCESS;
+    strcpy(session.ut_user, "bob");
+    updwtmpx(wtmp, &session);
+    session.ut_type = DEAD_PROCESS;
+    updwtmpx(wtmp, &session);
+    utmpxname(wtmp);
+    printf("wtmp:\n");
+    list();
+    endutxent();
+
+    // nothing is written to files that don't exist
+    unlink(wtmp);
+    updwtmpx(wtmp, &session);
+    printf("not created: %d\n", access(wtmp, F_OK) == -1);
+
+    unlink(utmp);
+}
@@ -0,0 +1,108 @@
+// The multibyte functions in the character sets other than UTF-8 that a locale can have
+#define _POSIX_C_SOURCE 200809L
+#include <errno.h>
+#include <langinfo.h>
+#include <limits.h>
+#include <locale.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <wchar.h>
+
+#include "test_helpers.h"
+
+// Converts `len` bytes of `in` to wide characters and back, one character at a time
+static void round_trip(const char *in, size_t len) {
+    mbstate_t state;
+    memset(&state, 0, sizeof state);
+    printf("  ");
+    while (len > 0) {
+        wchar_t wc;
+        size_t size = mbrtowc(&wc, in, len, &state);
+        ERROR_IF(mbrtowc, size, > len);
+
+        char out[MB_LEN_MAX];
+        size_t back = wcrtomb(out, wc, &state);
+        printf(" U+%04X:", (unsigned) wc);
+        for (size_t i = 0; i < back; i++) {
+            printf("%02x", (unsigned char) out[i]);
+        }
+        printf(back == size && memcmp(in, out, size) == 0 ? "" : "(mismatch)");
+        in += size;
+        len -= size;
+    }
+    printf("\n");
+}
+
+static void show_mbrtowc(const char *in, size_t len) {
+    mbstate_t state;
+    memset(&state, 0, sizeof state);
+    errno = 0;
+    size_t size = mbrtowc(NULL, in, len, &state);
+    printf("  mbrtowc of %zu bytes: %ld%s\n", len, (long) size, errno == EILSEQ ? " EILSEQ" : "");
+}
+
+static void show_wcrtomb(wchar_t wc) {
+    mbstate_t state;
+    memset(&state, 0, sizeof state);
+    char out[MB_LEN_MAX];
+    errno = 0;
+    size_t size = wcrtomb(out, wc, &state);
+    printf("  wcrtomb of U+%04X: %ld%s\n", (unsigned) wc, (long) size,
+           errno == EILSEQ ? " EILSEQ" : "");
+}
+
+static void use(const char *name) {
+    char *locale = setlocale(LC_CTYPE, name);
+    ERROR_IF(setlocale, locale, == NULL);
+    printf("%s (%s):\n", name, nl_langinfo(CODESET));
+}
+
+int main(void) {
+    int status = setenv("LOCPATH", "locales", 1);
+    ERROR_IF(setenv, status, == -1);
+
+    use("zh_CN.GB18030");
+    round_trip("a\xd6\xd0\xa2\xe3\xa8\xa6\x94\x39\xfc\x36\x81\x30\x81\x30", 15);
+    show_mbrtowc("\xd6", 1);
+    show_mbrtowc("\x81\x30\x81", 3);
+    show_mbrtowc("\x81\x20", 2);
+    show_mbrtowc("\xff", 1);
+
+    use("ja_JP.SJIS");
+    round_trip("A\x93\xfa\x96\x7b\xb1", 6);
+    show_mbrtowc("\x93", 1);
+    show_mbrtowc("\x93\x20", 2);
+    show_wcrtomb(0x20ac);
+
+    use("ko_KR.EUC-KR");
+    round_trip("\xc7\xd1\xb1\xb9!", 5);
+    show_wcrtomb(0x00e9);
+
+    // Every byte is a character in an 8-bit character set
+    use("de_DE.ISO-8859-15");
+    round_trip("\xa4\xe9\xff", 3);
+    printf("  wctob(U+20AC)=%#x wctob(U+00A4)=%d btowc(0xa4)=U+%04X\n", wctob(0x20ac),
+           wctob(0xa4), (unsigned) btowc(0xa4));
+
+    // The string functions and printf go through the same conversions
+    use("zh_CN.GB18030");
+    wchar_t wide[8];
+    size_t count = mbstowcs(wide, "x\xd6\xd0\x94\x39\xfc\x36", 8);
+    ERROR_IF(mbstowcs, count, == (size_t) -1);
+    printf("  mbstowcs: %zu characters, U+%04X U+%04X U+%04X\n", count, (unsigned) wide[0],
+           (unsigned) wide[1], (unsigned) wide[2]);
+    char narrow[16];
+    size_t bytes = wcstombs(narrow, wide, sizeof narrow);
+    ERROR_IF(wcstombs, bytes, == (size_t) -1);
+    printf("  wcstombs: %zu bytes, round trip %s\n", bytes,
+           strcmp(narrow, "x\xd6\xd0\x94\x39\xfc\x36") == 0 ? "ok" : "failed");
+    errno = 0;
+    bytes = wcstombs(NULL, L"\xd800", 0);
+    printf("  wcstombs of a surrogate: %ld%s\n", (long) bytes, errno == EILSEQ ? " EILSEQ" : "");
+
+    char printed[16];
+    int length = snprintf(printed, sizeof printed, "%ls|%lc", L"\x4e2d\x20ac", (wint_t) 0xe9);
+    printf("  printf: %d bytes, %s\n", length,
+           strcmp(printed, "\xd6\xd0\xa2\xe3|\xa8\xa6") == 0 ? "ok" : "failed");
+}
@@ -0,0 +1,59 @@
+// The classes of characters from several scripts, by name and through each function
+#include <locale.h>
+#include <stdio.h>
+#include <wctype.h>
+
+#include "test_helpers.h"
+
+static const char *names[] = {"alnum", "alpha", "blank", "cntrl", "digit", "graph",
+                              "lower", "print", "punct", "space", "upper", "xdigit"};
+static int (*functions[])(wint_t) = {iswalnum, iswalpha, iswblank, iswcntrl,
+                                     iswdigit, iswgraph, iswlower, iswprint,
+                                     iswpunct, iswspace, iswupper, iswxdigit};
+
+int main(void) {
+    // The classes are the same in every locale here, but not in the C locale elsewhere
+    char *locale = setlocale(LC_CTYPE, "C.UTF-8");
+    ERROR_IF(setlocale, locale, == NULL);
+
+    wint_t characters[] = {
+        'a', 'Z', '7', ' ', '\t', '~', 0x7f,
+        0x00a0,  // no-break space
+        0x00aa,  // feminine ordinal indicator
+        0x00df,  // sharp s
+        0x00f7,  // division sign
+        0x01c5,  // capital D with small z with caron
+        0x0345,  // combining ypogegrammeni
+        0x0378,  // unassigned
+        0x03a9,  // capital omega
+        0x0660,  // Arabic-Indic digit zero
+        0x0e01,  // Thai ko kai
+        0x1680,  // Ogham space mark
+        0x2028,  // line separator
+        0x2160,  // Roman numeral one
+        0x2200,  // for all
+        0x24b6,  // circled capital A
+        0x3000,  // ideographic space
+        0x4e2d,  // CJK ideograph
+        0xac00,  // Hangul syllable
+        0xe000,  // private use
+        0xff21,  // fullwidth capital A
+        0x1f600, // emoji
+        0x10fffd, WEOF,
+    };
+    for (size_t i = 0; i < sizeof characters / sizeof *characters; i++) {
+        wint_t wc = characters[i];
+        printf("U+%04X:", (unsigned) wc);
+        for (size_t j = 0; j < sizeof names / sizeof *names; j++) {
+            int is = iswctype(wc, wctype(names[j])) != 0;
+            if (is != (functions[j](wc) != 0)) {
+                printf(" (%s differs)", names[j]);
+            }
+            if (is) {
+                printf(" %s", names[j]);
+            }
+        }
+        printf("\n");
+    }
+    printf("unknown class: %d\n", wctype("no-such-class") == 0);
+}
@@ -0,0 +1,53 @@
+// The case mappings of characters from several scripts, by name and through each function
+#include <locale.h>
+#include <stdio.h>
+#include <wctype.h>
+
+#include "test_helpers.h"
+
+int main(void) {
+    char *locale = setlocale(LC_CTYPE, "C.UTF-8");
+    ERROR_IF(setlocale, locale, == NULL);
+
+    wctrans_t tolower = wctrans("tolower");
+    wctrans_t toupper = wctrans("toupper");
+    ERROR_IF(wctrans, tolower, == 0);
+    ERROR_IF(wctrans, toupper, == 0);
+
+    wint_t characters[] = {
+        'a', 'Z', '1',
+        0x00b5,  // micro sign
+        0x00df,  // sharp s, which has no single uppercase character
+        0x00ff,  // small y with diaeresis
+        0x0130,  // capital I with dot above
+        0x0131,  // small dotless i
+        0x017f,  // small long s
+        0x01c4,  // capital DZ with caron
+        0x01c5,  // its titlecase form
+        0x01c6,  // its lowercase form
+        0x03a3,  // capital sigma
+        0x03c2,  // small final sigma
+        0x0430,  // Cyrillic small a
+        0x10d0,  // Georgian letter an
+        0x1e9e,  // capital sharp s
+        0x1f80,  // small alpha with psili and ypogegrammeni
+        0x2126,  // ohm sign
+        0x2160,  // Roman numeral one
+        0x24d0,  // circled small a
+        0xa7c0,  // Latin capital old polish o
+        0xff41,  // fullwidth small a
+        0x10428, // Deseret small long i
+        0x1e922, // Adlam small alif
+        0x4e2d, WEOF,
+    };
+    for (size_t i = 0; i < sizeof characters / sizeof *characters; i++) {
+        wint_t wc = characters[i];
+        wint_t lower = towctrans(wc, tolower);
+        wint_t upper = towctrans(wc, toupper);
+        printf("U+%04X: lower U+%04X, upper U+%04X%s\n", (unsigned) wc, (unsigned) lower,
+               (unsigned) upper,
+               lower == towlower(wc) && upper == towupper(wc) ? "" : " (functions differ)");
+    }
+    printf("unknown mapping: %d, leaves U+%04X\n", wctrans("totitlecase") == 0,
+           (unsigned) towctrans(0x61, 0));
+}
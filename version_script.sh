@@ -0,0 +1,53 @@
+#!/bin/sh
+# Writes the version script libc.so is linked with. Every function and variable relibc exports is
+# given the version RELIBC_1.0, which binaries linked against it then ask ld.so for.
+#
+# They're taken from the symbols the libraries define:
+# - of the objects rustc made for relibc itself, the ones that aren't mangled, which are the
+#   #[no_mangle] items. What's only exported for relibc itself, like the symbols crt0 and ld.so
+#   look up, stays unversioned.
+# - of the C objects, from src/c, openlibm and pthreads-emb, every global one.
+# The objects of relibc's dependencies, like compiler_builtins, are left out.
+#
+# When a symbol's struct or behaviour changes, the old one keeps its name at RELIBC_1.0 with
+# .symver, and the new one is the default at a new version, which gets a node after this one.
+
+output=$1
+shift
+
+if [ -z "$output" ] || [ "$#" -eq 0 ]; then
+    echo "Usage:\n\t./version_script.sh OUTPUT LIBRARIES..."
+    exit 1
+fi
+
+# The Rust symbols that are only for relibc, crt0, ld.so and debuggers
+internal='^(__)?relibc_|^rust_|^_Unwind_|^_dl_debug_state$|^_r_debug$'
+
+# nm starts the symbols of each member of an archive with a "library[member]:" line. rustc names
+# the objects it makes "<crate>-<hash>.<codegen unit>.rcgu.o".
+symbols=`
+    "${NM:-nm}" --format=posix -g --defined-only "$@" 2>/dev/null |
+    awk -v internal="$internal" '
+        /\]:$/ {
+            member = $0
+            sub(/^.*\[/, "", member)
+            sub(/\]:$/, "", member)
+            rust = member ~ /\.rcgu\.o$/
+            own = member ~ /^relibc-/
+            next
+        }
+        NF < 2 || $2 !~ /^[A-Z]$/ { next }
+        rust && (!own || $1 ~ /^_(ZN|R)/ || $1 ~ internal) { next }
+        { print $1 }
+    ' |
+    sort -u
+`
+
+{
+    echo "RELIBC_1.0 {"
+    echo "	global:"
+    for symbol in $symbols; do
+        echo "		$symbol;"
+    done
+    echo "};"
+} > "$output"